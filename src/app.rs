@@ -15,21 +15,36 @@ pub enum InputMode {
     EditingOAuthClientId,
     EditingParamKey,
     EditingParamValue,
+    EditingPathParam,
+    EditingHeaderKey,
+    EditingHeaderValue,
     EditingChainKey,
     EditingChainPath,
     EditingFormKey,
     EditingFormValue,
+    EditingUrlEncodedKey,
+    EditingUrlEncodedValue,
     Search,
+    FindInResponse,
     EditingWsUrl,
     EditingWsMessage,
     EditingGrpcService,
     EditingGrpcProto,
     FilteringSidebar,
     CommandPalette,
+    CodegenPicker,
+    SnippetPicker,
+    Finder,
+    HistoryView,
+    SaveHistory,
     Command,
     EditingStressVUs,
     EditingStressDuration,
+    EditingStressStages,
+    EditingStressThresholds,
+    EditingStressTarget,
     EditingSentinelInterval,
+    EditingTimeout,
     ImportCurl,
 }
 
@@ -43,10 +58,59 @@ pub enum AppMode {
 pub enum BodyType {
     Raw,
     FormData,
+    UrlEncoded,
     GraphQL,
     Grpc,
 }
 
+/// How the response pane reads a response, whatever its Content-Type says
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResponseType {
+    Json,
+    Xml,
+    Html,
+    Text,
+    Binary,
+}
+
+impl ResponseType {
+    /// In the order `V` goes through them
+    pub const ALL: [ResponseType; 5] = [
+        ResponseType::Json,
+        ResponseType::Xml,
+        ResponseType::Html,
+        ResponseType::Text,
+        ResponseType::Binary,
+    ];
+
+    /// As written in `treat_as = "..."` and `:treat`
+    pub fn name(self) -> &'static str {
+        match self {
+            ResponseType::Json => "json",
+            ResponseType::Xml => "xml",
+            ResponseType::Html => "html",
+            ResponseType::Text => "text",
+            ResponseType::Binary => "binary",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<ResponseType> {
+        ResponseType::ALL
+            .into_iter()
+            .find(|t| t.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// What the highlighter goes by
+    pub fn extension(self) -> &'static str {
+        match self {
+            ResponseType::Json => "json",
+            ResponseType::Xml => "xml",
+            ResponseType::Html => "html",
+            ResponseType::Text | ResponseType::Binary => "txt",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AuthType {
     Bearer,
@@ -55,117 +119,719 @@ pub enum AuthType {
     None,
 }
 
+/// Something a workspace profile can hold back until confirmed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GuardedAction {
+    Send,
+    StressTest,
+    Fuzz,
+    SendTabs,
+    Paginate,
+}
+
+/// "Really send this to production?" prompt
+#[derive(Clone, Debug)]
+pub struct GuardPrompt {
+    pub message: String,
+    pub action: GuardedAction,
+}
+
+/// What the passphrase prompt is asking for
+#[derive(Clone, PartialEq)]
+pub enum PassphrasePurpose {
+    Unlock,
+    /// Turning encryption on, asked twice
+    Enable,
+    Confirm(String),
+    /// Sealing secrets into a bundle written to the path, asked twice
+    ExportBundle(String),
+    ConfirmExport(String, String),
+    /// Opening the sealed secrets of the bundle at the path
+    ImportBundle(String),
+}
+
+#[derive(Clone)]
+pub struct PassphrasePrompt {
+    pub purpose: PassphrasePurpose,
+    pub input: String,
+}
+
+/// A response kept around so a re-send can be compared against it
+#[derive(Clone, Debug)]
+pub struct ResponseSnapshot {
+    pub status: Option<u16>,
+    pub latency: Option<u128>,
+    pub body: String,
+}
+
+/// A workspace or environment default header, as the Headers tab lists it
+#[derive(Clone, Debug, PartialEq)]
+pub struct DefaultHeader {
+    pub name: String,
+    pub value: String,
+    /// "workspace" or the environment's name
+    pub source: String,
+    pub state: DefaultHeaderState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DefaultHeaderState {
+    Sent,
+    /// The tab has a header of the same name, that one goes out
+    Overridden,
+    /// Turned off for this tab (`:header off <Name>`)
+    Disabled,
+}
+
+/// A header row as typed in the Headers tab, written back on Enter
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeaderDraft {
+    /// The row's name before editing, None for a new header
+    pub original: Option<String>,
+    pub name: String,
+    pub value: String,
+    /// Whether the row goes out, kept through a rename
+    pub enabled: bool,
+}
+
+/// How many sends each tab remembers in its timeline
+pub const TIMELINE_LIMIT: usize = 100;
+
+/// The editable side of a tab, captured at every send so the tab can be put back
+/// exactly as it was (the request twin of the response history)
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestSnapshot {
+    pub method: String,
+    pub url: String,
+    pub params: Vec<(String, String)>,
+    pub path_params: Vec<(String, String)>,
+    pub request_headers: std::collections::HashMap<String, String>,
+    pub disabled_headers: std::collections::HashMap<String, String>,
+    pub disabled_defaults: Vec<String>,
+    pub body_type: BodyType,
+    pub request_body: String,
+    pub form_data: Vec<(String, String, bool)>,
+    pub urlencoded: Vec<(String, String)>,
+    pub graphql_query: String,
+    pub graphql_variables: String,
+    pub grpc_service: String,
+    pub grpc_method: String,
+    pub grpc_proto_path: String,
+    pub auth_type: AuthType,
+    pub auth_token: String,
+    pub basic_auth_user: String,
+    pub basic_auth_pass: String,
+    pub oauth_auth_url: String,
+    pub oauth_token_url: String,
+    pub oauth_client_id: String,
+    pub extract_rules: Vec<(String, String)>,
+    pub pre_request_script: String,
+    pub post_request_script: String,
+    pub timeout_ms: u64,
+    pub connect_timeout_ms: Option<u64>,
+    pub read_timeout_ms: Option<u64>,
+}
+
+impl RequestSnapshot {
+    pub fn capture(tab: &RequestTab) -> Self {
+        RequestSnapshot {
+            method: tab.method.clone(),
+            url: tab.url.clone(),
+            params: tab.params.clone(),
+            path_params: tab.path_params.clone(),
+            request_headers: tab.request_headers.clone(),
+            disabled_headers: tab.disabled_headers.clone(),
+            disabled_defaults: tab.disabled_defaults.clone(),
+            body_type: tab.body_type,
+            request_body: tab.request_body.clone(),
+            form_data: tab.form_data.clone(),
+            urlencoded: tab.urlencoded.clone(),
+            graphql_query: tab.graphql_query.clone(),
+            graphql_variables: tab.graphql_variables.clone(),
+            grpc_service: tab.grpc_service.clone(),
+            grpc_method: tab.grpc_method.clone(),
+            grpc_proto_path: tab.grpc_proto_path.clone(),
+            auth_type: tab.auth_type,
+            auth_token: tab.auth_token.clone(),
+            basic_auth_user: tab.basic_auth_user.clone(),
+            basic_auth_pass: tab.basic_auth_pass.clone(),
+            oauth_auth_url: tab.oauth_auth_url.clone(),
+            oauth_token_url: tab.oauth_token_url.clone(),
+            oauth_client_id: tab.oauth_client_id.clone(),
+            extract_rules: tab.extract_rules.clone(),
+            pre_request_script: tab.pre_request_script.clone(),
+            post_request_script: tab.post_request_script.clone(),
+            timeout_ms: tab.timeout_ms,
+            connect_timeout_ms: tab.connect_timeout_ms,
+            read_timeout_ms: tab.read_timeout_ms,
+        }
+    }
+
+    pub fn restore(&self, tab: &mut RequestTab) {
+        let s = self.clone();
+        tab.method = s.method;
+        tab.url = s.url;
+        tab.url_cursor_index = tab.url.len();
+        tab.params = s.params;
+        tab.path_params = s.path_params;
+        tab.request_headers = s.request_headers;
+        tab.disabled_headers = s.disabled_headers;
+        tab.header_draft = None;
+        tab.disabled_defaults = s.disabled_defaults;
+        tab.body_type = s.body_type;
+        tab.request_body = s.request_body;
+        tab.form_data = s.form_data;
+        tab.urlencoded = s.urlencoded;
+        tab.graphql_query = s.graphql_query;
+        tab.graphql_variables = s.graphql_variables;
+        tab.grpc_service = s.grpc_service;
+        tab.grpc_method = s.grpc_method;
+        tab.grpc_proto_path = s.grpc_proto_path;
+        tab.auth_type = s.auth_type;
+        tab.auth_token = s.auth_token;
+        tab.basic_auth_user = s.basic_auth_user;
+        tab.basic_auth_pass = s.basic_auth_pass;
+        tab.oauth_auth_url = s.oauth_auth_url;
+        tab.oauth_token_url = s.oauth_token_url;
+        tab.oauth_client_id = s.oauth_client_id;
+        tab.extract_rules = s.extract_rules;
+        tab.pre_request_script = s.pre_request_script;
+        tab.post_request_script = s.post_request_script;
+        tab.timeout_ms = s.timeout_ms;
+        tab.connect_timeout_ms = s.connect_timeout_ms;
+        tab.read_timeout_ms = s.read_timeout_ms;
+
+        // Selections pointing past the restored lists would edit nothing
+        for (state, len) in [
+            (&mut tab.params_list_state, tab.params.len()),
+            (&mut tab.path_params_list_state, tab.path_params.len()),
+            (&mut tab.form_list_state, tab.form_data.len()),
+            (&mut tab.urlencoded_list_state, tab.urlencoded.len()),
+            (&mut tab.extract_list_state, tab.extract_rules.len()),
+        ] {
+            if state.selected().is_some_and(|i| i >= len) {
+                state.select(len.checked_sub(1));
+            }
+        }
+    }
+
+    /// What differs from an earlier snapshot, for the timeline ("URL, headers")
+    pub fn changes_from(&self, older: &RequestSnapshot) -> Vec<&'static str> {
+        let mut changes = Vec::new();
+        if self.method != older.method {
+            changes.push("method");
+        }
+        if self.url != older.url
+            || self.params != older.params
+            || self.path_params != older.path_params
+        {
+            changes.push("URL");
+        }
+        if self.request_headers != older.request_headers
+            || self.disabled_headers != older.disabled_headers
+            || self.disabled_defaults != older.disabled_defaults
+        {
+            changes.push("headers");
+        }
+        if self.body_type != older.body_type
+            || self.request_body != older.request_body
+            || self.form_data != older.form_data
+            || self.urlencoded != older.urlencoded
+            || self.graphql_query != older.graphql_query
+            || self.graphql_variables != older.graphql_variables
+            || self.grpc_service != older.grpc_service
+            || self.grpc_method != older.grpc_method
+            || self.grpc_proto_path != older.grpc_proto_path
+        {
+            changes.push("body");
+        }
+        if self.auth_type != older.auth_type
+            || self.auth_token != older.auth_token
+            || self.basic_auth_user != older.basic_auth_user
+            || self.basic_auth_pass != older.basic_auth_pass
+            || self.oauth_auth_url != older.oauth_auth_url
+            || self.oauth_token_url != older.oauth_token_url
+            || self.oauth_client_id != older.oauth_client_id
+        {
+            changes.push("auth");
+        }
+        if self.pre_request_script != older.pre_request_script
+            || self.post_request_script != older.post_request_script
+        {
+            changes.push("scripts");
+        }
+        if self.extract_rules != older.extract_rules
+            || self.timeout_ms != older.timeout_ms
+            || self.connect_timeout_ms != older.connect_timeout_ms
+            || self.read_timeout_ms != older.read_timeout_ms
+        {
+            changes.push("settings");
+        }
+        changes
+    }
+}
+
+/// The active tab's HTTP request with everything filled in: variables, auth,
+/// cookies and what the pre-request script changed
+#[derive(Clone, Debug)]
+pub struct OutgoingRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: std::collections::HashMap<String, String>,
+    pub body: Option<String>,
+    pub form_data: Option<Vec<(String, String, bool)>>,
+    pub auth: Option<crate::net::http::AuthPayload>,
+}
+
+/// The raw request in a modal (`W`): what the next send will put on the wire, or
+/// what the last one did
+#[derive(Clone, Debug)]
+pub struct WireView {
+    /// Showing the last send rather than the preview
+    pub sent: bool,
+    /// Method and full URL of the preview; the request line only has the path
+    pub url: String,
+    pub text: String,
+    /// Anything to keep in mind about the bytes, like a script that ran
+    pub note: Option<String>,
+    pub scroll: u16,
+}
+
+/// One send in a tab's timeline
+#[derive(Clone, Debug)]
+pub struct TimelineEntry {
+    pub sent_at: std::time::Instant,
+    pub request: RequestSnapshot,
+    /// None until the response arrives
+    pub status: Option<u16>,
+    pub latency: Option<u128>,
+    /// Set instead when the request failed (timeout, DNS, ...)
+    pub error: Option<String>,
+}
+
+impl TimelineEntry {
+    pub fn is_pending(&self) -> bool {
+        self.status.is_none() && self.error.is_none()
+    }
+
+    /// Short relative age for the timeline, e.g. `42s ago`
+    pub fn age(&self) -> String {
+        let secs = self.sent_at.elapsed().as_secs();
+        if secs < 60 {
+            format!("{}s ago", secs)
+        } else if secs < 3600 {
+            format!("{}m ago", secs / 60)
+        } else {
+            format!("{}h ago", secs / 3600)
+        }
+    }
+}
+
+/// Result of "re-send and compare": the previous response vs the fresh one
+#[derive(Clone, Debug)]
+pub struct ResponseComparison {
+    pub old_status: Option<u16>,
+    pub new_status: Option<u16>,
+    pub old_latency: Option<u128>,
+    pub new_latency: Option<u128>,
+    pub body: crate::features::json_diff::BodyDiff,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MockEditorField {
+    Method,
+    Path,
+    Status,
+    Delay,
+    ErrorRate,
+    DropRate,
+    Headers,
+    Body,
+}
+
+impl MockEditorField {
+    const ALL: [MockEditorField; 8] = [
+        MockEditorField::Method,
+        MockEditorField::Path,
+        MockEditorField::Status,
+        MockEditorField::Delay,
+        MockEditorField::ErrorRate,
+        MockEditorField::DropRate,
+        MockEditorField::Headers,
+        MockEditorField::Body,
+    ];
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    /// Headers and body take newlines, the rest are single-line
+    pub fn is_multiline(self) -> bool {
+        matches!(self, MockEditorField::Headers | MockEditorField::Body)
+    }
+}
+
+/// Form state of the mock route editor. Headers are edited as `Key: Value` lines.
+#[derive(Clone, Debug)]
+pub struct MockRouteEditor {
+    /// Route being edited, `None` for a new one
+    pub index: Option<usize>,
+    pub field: MockEditorField,
+    pub method: String,
+    pub path: String,
+    pub status: String,
+    /// `150` or a random range like `100-400`, empty for none
+    pub delay: String,
+    /// Percentages, empty for none
+    pub error_rate: String,
+    pub drop_rate: String,
+    pub headers: String,
+    pub body: String,
+}
+
+fn parse_percent(text: &str, what: &str) -> Result<Option<f64>, String> {
+    let text = text.trim().trim_end_matches('%').trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    match text.parse::<f64>() {
+        Ok(p) if (0.0..=100.0).contains(&p) => Ok(Some(p).filter(|p| *p > 0.0)),
+        _ => Err(format!("{} must be a percentage (0-100): {}", what, text)),
+    }
+}
+
+impl MockRouteEditor {
+    pub const METHODS: [&'static str; 7] =
+        ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+    pub fn new_route() -> Self {
+        Self {
+            index: None,
+            field: MockEditorField::Path,
+            method: "GET".to_string(),
+            path: "/api/new".to_string(),
+            status: "200".to_string(),
+            delay: String::new(),
+            error_rate: String::new(),
+            drop_rate: String::new(),
+            headers: "Content-Type: application/json".to_string(),
+            body: "{\"message\": \"Hello Mock!\"}".to_string(),
+        }
+    }
+
+    pub fn from_route(index: usize, route: &crate::net::mock_server::MockRoute) -> Self {
+        let mut headers: Vec<String> = route
+            .headers
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k, v))
+            .collect();
+        headers.sort();
+        Self {
+            index: Some(index),
+            field: MockEditorField::Path,
+            method: route.method.clone(),
+            path: route.path.clone(),
+            status: route.status.to_string(),
+            delay: match (route.delay_ms, route.delay_max_ms) {
+                (Some(min), Some(max)) => format!("{}-{}", min, max),
+                (Some(min), None) => min.to_string(),
+                _ => String::new(),
+            },
+            error_rate: route.error_rate.map(|r| r.to_string()).unwrap_or_default(),
+            drop_rate: route.drop_rate.map(|r| r.to_string()).unwrap_or_default(),
+            headers: headers.join("\n"),
+            body: route.body.clone(),
+        }
+    }
+
+    pub fn cycle_method(&mut self, forward: bool) {
+        let len = Self::METHODS.len();
+        let i = Self::METHODS
+            .iter()
+            .position(|m| m.eq_ignore_ascii_case(&self.method))
+            .unwrap_or(0);
+        let next = if forward {
+            (i + 1) % len
+        } else {
+            (i + len - 1) % len
+        };
+        self.method = Self::METHODS[next].to_string();
+    }
+
+    pub fn field_mut(&mut self) -> Option<&mut String> {
+        match self.field {
+            MockEditorField::Method => None,
+            MockEditorField::Path => Some(&mut self.path),
+            MockEditorField::Status => Some(&mut self.status),
+            MockEditorField::Delay => Some(&mut self.delay),
+            MockEditorField::ErrorRate => Some(&mut self.error_rate),
+            MockEditorField::DropRate => Some(&mut self.drop_rate),
+            MockEditorField::Headers => Some(&mut self.headers),
+            MockEditorField::Body => Some(&mut self.body),
+        }
+    }
+
+    pub fn to_route(&self) -> Result<crate::net::mock_server::MockRoute, String> {
+        let path = self.path.trim();
+        if !path.starts_with('/') {
+            return Err("Path must start with /".to_string());
+        }
+        let status: u16 = self
+            .status
+            .trim()
+            .parse()
+            .ok()
+            .filter(|s| (100..=599).contains(s))
+            .ok_or_else(|| format!("Invalid status: {}", self.status))?;
+
+        let mut headers = std::collections::HashMap::new();
+        for line in self.headers.lines().filter(|l| !l.trim().is_empty()) {
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("Header needs 'Key: Value': {}", line))?;
+            headers.insert(key.trim().to_string(), value.trim().to_string());
+        }
+
+        let parse_ms = |text: &str| {
+            text.trim()
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid delay: {}", self.delay))
+        };
+        let (delay_ms, delay_max_ms) = match self.delay.trim() {
+            "" => (None, None),
+            delay => match delay.split_once('-') {
+                Some((min, max)) => (Some(parse_ms(min)?), Some(parse_ms(max)?)),
+                None => (Some(parse_ms(delay)?), None),
+            },
+        };
+
+        Ok(crate::net::mock_server::MockRoute {
+            path: path.to_string(),
+            method: self.method.clone(),
+            status,
+            body: self.body.clone(),
+            headers,
+            delay_ms,
+            delay_max_ms,
+            error_rate: parse_percent(&self.error_rate, "Error rate")?,
+            drop_rate: parse_percent(&self.drop_rate, "Drop rate")?,
+        })
+    }
+}
+
+/// Nodes opened up front when a tree is built or a node expanded, breadth first. A
+/// small response shows fully expanded; in a big one the deeper levels wait for `l`.
+pub const JSON_AUTO_EXPAND: usize = 10_000;
+
+/// A node of the response explorer. Every node shares the one parsed response and
+/// finds its value by JSON pointer; children are only built once a node is expanded.
 #[derive(Clone, Debug)]
 pub struct JsonEntry {
     pub key: String,
-    pub value: Value,
+    root: std::sync::Arc<Value>,
+    /// Where the value is in `root`, `/data/items/3`
+    pointer: String,
     pub level: usize,
     pub is_expanded: bool,
+    /// Empty until the node is first expanded
     pub children: Vec<JsonEntry>,
 }
 
 impl JsonEntry {
     pub fn from_value(key: String, value: &Value, level: usize) -> Self {
-        let mut children = Vec::new();
+        Self::from_parsed(key, value.clone(), level)
+    }
 
-        if let Value::Object(map) = value {
-            for (k, v) in map {
-                children.push(JsonEntry::from_value(k.clone(), v, level + 1));
-            }
-        } else if let Value::Array(list) = value {
-            for (i, v) in list.iter().enumerate() {
-                children.push(JsonEntry::from_value(format!("[{}]", i), v, level + 1));
-            }
+    /// The root of a tree over `value`, expanded as far as `JSON_AUTO_EXPAND` allows
+    pub fn from_parsed(key: String, value: Value, level: usize) -> Self {
+        let mut root = JsonEntry {
+            key,
+            root: std::sync::Arc::new(value),
+            pointer: String::new(),
+            level,
+            is_expanded: false,
+            children: Vec::new(),
+        };
+        root.set_expanded(true);
+        root
+    }
+
+    pub fn value(&self) -> &Value {
+        self.root.pointer(&self.pointer).unwrap_or(&Value::Null)
+    }
+
+    /// A non-empty object or array, whether its children are built yet or not
+    pub fn has_children(&self) -> bool {
+        match self.value() {
+            Value::Object(map) => !map.is_empty(),
+            Value::Array(list) => !list.is_empty(),
+            _ => false,
         }
+    }
 
+    fn child(&self, key: String, segment: &str) -> JsonEntry {
         JsonEntry {
             key,
-            value: value.clone(),
-            level,
-            is_expanded: true,
-            children,
+            root: self.root.clone(),
+            pointer: format!(
+                "{}/{}",
+                self.pointer,
+                segment.replace('~', "~0").replace('/', "~1")
+            ),
+            level: self.level + 1,
+            is_expanded: false,
+            children: Vec::new(),
+        }
+    }
+
+    /// The node without its children, cheap however big the subtree is
+    fn shallow(&self) -> JsonEntry {
+        JsonEntry {
+            key: self.key.clone(),
+            root: self.root.clone(),
+            pointer: self.pointer.clone(),
+            level: self.level,
+            is_expanded: false,
+            children: Vec::new(),
         }
     }
-}
 
-use ratatui::style::Color;
+    fn build_children(&mut self) {
+        if !self.children.is_empty() {
+            return;
+        }
+        self.children = match self.value() {
+            Value::Object(map) => map.keys().map(|k| self.child(k.clone(), k)).collect(),
+            Value::Array(list) => (0..list.len())
+                .map(|i| self.child(format!("[{}]", i), &i.to_string()))
+                .collect(),
+            _ => Vec::new(),
+        };
+    }
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Theme {
-    pub name: String,
-    pub background: Color,
-    pub border: Color,
-    pub border_focus: Color,
-    pub text_primary: Color,
-    pub text_secondary: Color,
-    pub highlight: Color,
-    pub success: Color,
-    pub error: Color,
-    pub accent: Color,
-}
+    /// Expanding builds the children and opens them breadth first, up to
+    /// `JSON_AUTO_EXPAND` nodes; collapsing keeps them for next time
+    pub fn set_expanded(&mut self, expanded: bool) {
+        self.is_expanded = expanded;
+        if !expanded {
+            return;
+        }
+        self.build_children();
+        let mut budget = JSON_AUTO_EXPAND.saturating_sub(self.children.len());
+        let mut level: Vec<&mut JsonEntry> = self.children.iter_mut().collect();
+        while !level.is_empty() {
+            let mut next = Vec::new();
+            for node in level {
+                let count = match node.value() {
+                    Value::Object(map) => map.len(),
+                    Value::Array(list) => list.len(),
+                    _ => 0,
+                };
+                if count == 0 || count > budget {
+                    continue;
+                }
+                budget -= count;
+                node.build_children();
+                node.is_expanded = true;
+                next.extend(node.children.iter_mut());
+            }
+            level = next;
+        }
+    }
 
-impl Theme {
-    pub fn default_theme() -> Self {
-        Theme {
-            name: "Default".to_string(),
-            background: Color::Reset,
-            border: Color::DarkGray,
-            border_focus: Color::Cyan,
-            text_primary: Color::White,
-            text_secondary: Color::Gray,
-            highlight: Color::Yellow,
-            success: Color::Green,
-            error: Color::Red,
-            accent: Color::Cyan,
-        }
-    }
-
-    pub fn matrix() -> Self {
-        Theme {
-            name: "Matrix".to_string(),
-            background: Color::Black,
-            border: Color::DarkGray,
-            border_focus: Color::Green,
-            text_primary: Color::Green,
-            text_secondary: Color::DarkGray,
-            highlight: Color::LightGreen,
-            success: Color::Green,
-            error: Color::Red,
-            accent: Color::Green,
-        }
-    }
-
-    pub fn cyberpunk() -> Self {
-        Theme {
-            name: "Cyberpunk".to_string(),
-            background: Color::Black,
-            border: Color::Magenta,
-            border_focus: Color::Yellow,
-            text_primary: Color::Cyan,
-            text_secondary: Color::Magenta,
-            highlight: Color::Yellow,
-            success: Color::LightGreen,
-            error: Color::Red,
-            accent: Color::LightCyan,
-        }
-    }
-
-    pub fn dracula() -> Self {
-        Theme {
-            name: "Dracula".to_string(),
-            background: Color::Rgb(40, 42, 54),
-            border: Color::Rgb(98, 114, 164),
-            border_focus: Color::Rgb(189, 147, 249),
-            text_primary: Color::Rgb(248, 248, 242),
-            text_secondary: Color::Rgb(98, 114, 164),
-            highlight: Color::Rgb(255, 121, 198),
-            success: Color::Rgb(80, 250, 123),
-            error: Color::Rgb(255, 85, 85),
-            accent: Color::Rgb(139, 233, 253),
+    /// The value as you'd paste it: strings without quotes, containers as compact JSON
+    pub fn text_of(value: &Value) -> String {
+        match value {
+            Value::String(s) => s.clone(),
+            v => v.to_string(),
+        }
+    }
+
+    /// The node's value as you'd paste it
+    pub fn value_text(&self) -> String {
+        Self::text_of(self.value())
+    }
+
+    /// The whole subtree below (and including) this node as pretty-printed JSON
+    pub fn subtree_json(&self) -> String {
+        serde_json::to_string_pretty(self.value()).unwrap_or_else(|_| self.value().to_string())
+    }
+
+    /// Every leaf with its full path from the root (`data.items[3].price`), whatever is
+    /// collapsed. Rows are kept if the path or the value contains `filter`.
+    pub fn flatten(entries: &[JsonEntry], filter: &str) -> Vec<(String, JsonEntry)> {
+        fn child_path(parent: &str, key: &str) -> String {
+            let plain = !key.is_empty()
+                && !key.starts_with(|c: char| c.is_ascii_digit())
+                && key
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+            if key.starts_with('[') {
+                format!("{}{}", parent, key)
+            } else if !plain {
+                format!("{}[{}]", parent, Value::String(key.to_string()))
+            } else if parent.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", parent, key)
+            }
+        }
+        // Through the values, not the built children: collapsed nodes may have none yet
+        fn walk(entry: JsonEntry, path: String, rows: &mut Vec<(String, JsonEntry)>) {
+            let children: Vec<JsonEntry> = match entry.value() {
+                Value::Object(map) => map.keys().map(|k| entry.child(k.clone(), k)).collect(),
+                Value::Array(list) => (0..list.len())
+                    .map(|i| entry.child(format!("[{}]", i), &i.to_string()))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            if children.is_empty() {
+                rows.push((path, entry));
+                return;
+            }
+            for child in children {
+                let path = child_path(&path, &child.key);
+                walk(child, path, rows);
+            }
+        }
+        let mut rows = Vec::new();
+        // A single root node ("root", or "matches" for a JSONPath result) isn't part of paths
+        match entries {
+            [root] => walk(root.shallow(), String::new(), &mut rows),
+            _ => {
+                for entry in entries {
+                    walk(entry.shallow(), child_path("", &entry.key), &mut rows);
+                }
+            }
+        }
+        let filter = filter.to_lowercase();
+        if !filter.is_empty() {
+            rows.retain(|(path, entry)| {
+                path.to_lowercase().contains(&filter)
+                    || entry.value_text().to_lowercase().contains(&filter)
+            });
+        }
+        rows
+    }
+
+    /// A flattened path as a JSONPath expression, `data.id` -> `$.data.id`
+    pub fn flat_json_path(path: &str) -> String {
+        if path.is_empty() || path.starts_with('[') {
+            format!("${}", path)
+        } else {
+            format!("$.{}", path)
         }
     }
 }
 
+pub use crate::domain::theme::Theme;
+
 #[derive(PartialEq, Copy, Clone, Debug)]
 pub enum EditorMode {
     None,
@@ -175,6 +841,9 @@ pub enum EditorMode {
     GraphQLVariables,
     PreRequestScript,
     PostRequestScript,
+    Description,
+    /// The description of the collection at this index
+    CollectionDescription(usize),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -188,6 +857,22 @@ pub struct RequestLog {
     #[serde(skip)]
     pub response_bytes: Option<Vec<u8>>,
     pub is_binary: bool,
+    /// Unix seconds, 0 for entries from before it was recorded
+    #[serde(default)]
+    pub timestamp: u64,
+    /// Kept regardless of the retention limit
+    #[serde(default)]
+    pub pinned: bool,
+    /// The tab as it was sent (unresolved `{{vars}}`, headers, body), for saving it
+    /// back into a collection. None for entries recorded before it was kept.
+    #[serde(default)]
+    pub request: Option<RequestConfig>,
+    /// Response body bytes, 0 for entries from before it was recorded
+    #[serde(default)]
+    pub size: usize,
+    /// The post-request script's tests, name and whether it passed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<(String, bool)>,
 }
 
 #[derive(Clone, Debug)]
@@ -203,9 +888,22 @@ pub struct RequestTab {
     pub body_type: BodyType,
     pub form_data: Vec<(String, String, bool)>,
     pub form_list_state: ListState,
+    pub urlencoded: Vec<(String, String)>,
+    pub urlencoded_list_state: ListState,
     pub params: Vec<(String, String)>,
     pub params_list_state: ListState,
+    /// `:id` / `{id}` in the URL's path and their values, in URL order
+    pub path_params: Vec<(String, String)>,
+    pub path_params_list_state: ListState,
     pub request_headers: std::collections::HashMap<String, String>,
+    /// The tab's own headers switched off in the Headers tab, kept but not sent
+    pub disabled_headers: std::collections::HashMap<String, String>,
+    /// Headers tab selection: the tab's own rows first, then the defaults
+    pub headers_list_state: ListState,
+    /// The header row being typed in the Headers tab
+    pub header_draft: Option<HeaderDraft>,
+    /// Default headers (workspace or environment) this tab leaves out, by name
+    pub disabled_defaults: Vec<String>,
     pub extract_rules: Vec<(String, String)>,
     pub extract_list_state: ListState,
 
@@ -237,30 +935,100 @@ pub struct RequestTab {
     pub should_describe_grpc_service: bool,
     pub grpc_service_to_describe: String,
     pub show_grpc_description_modal: bool,
+    /// The described service's methods, picked from in the description modal
+    pub grpc_methods: Vec<crate::net::grpc::RpcMethod>,
+    pub grpc_method_selected: usize,
+    /// Input message to describe for a payload skeleton, sent by the event loop
+    pub scaffold_grpc_message: Option<String>,
+    /// Status, error details, headers and trailers of the last gRPC call
+    pub grpc_metadata: Option<crate::net::grpc::GrpcMetadata>,
+    /// The trace context the last send went out with, when tracing is on
+    pub trace: Option<crate::features::trace::TraceContext>,
+    /// The last send's id in the log (`:logs`)
+    pub request_id: Option<String>,
 
     // Scripts
     pub pre_request_script: String,
     pub post_request_script: String,
     pub script_output: Vec<String>,
+    /// Load profile for the stress test, see `stress::parse_stages`
+    pub stress_stages: String,
     pub test_results: Vec<(String, bool)>,
+    /// Markdown notes on the request, saved with it (`:describe`)
+    pub description: String,
+    /// The collection the request was opened from, whose variables it sees
+    pub collection: Option<String>,
+    /// The saved request's name in `collection`, for its latency trend
+    pub request_name: Option<String>,
+    /// Request-level variables, over the collection's and environment's
+    pub variables: std::collections::HashMap<String, String>,
+    /// Saved with the request (`tags = [...]`)
+    pub tags: Vec<String>,
+    /// Pre-send warnings already sent past, not raised again for this tab
+    pub dismissed_warnings: Vec<String>,
+    /// The response pane shows the Docs sub-tab instead of the response (`K`)
+    pub show_docs: bool,
 
     // Response
     pub response: Option<String>,
     pub response_bytes: Option<Vec<u8>>,
     pub response_is_binary: bool,
+    /// Read the response as this instead of going by its Content-Type (`V`,
+    /// `:treat`). Kept across sends and saved with the request (`treat_as`)
+    pub treat_as: Option<ResponseType>,
+    /// How `:paginate` gets from page to page, saved with the request (`paginate`)
+    pub paginate: Option<crate::features::paginate::PaginateConfig>,
+    /// The last request exactly as it went out, for the hex pane
+    pub request_bytes: Option<Vec<u8>>,
+    /// The body as it came over the wire, when it had a Content-Encoding
+    pub response_encoding: Option<crate::net::encoding::ContentEncoding>,
+    /// Every Set-Cookie of the response; `response_headers` only keeps one
+    pub response_cookies: Vec<String>,
     pub response_image: Option<DynamicImage>,
     pub response_json: Option<Vec<JsonEntry>>,
+    /// The explorer's visible rows of `response_json`
+    pub json_rows: crate::ui::json_rows::JsonRows,
+    /// The response's lines when it's NDJSON / JSON Lines
+    pub response_ndjson: Option<Vec<crate::features::ndjson::NdjsonLine>>,
+    /// Line number of the NDJSON record open in the tree explorer
+    pub ndjson_record: Option<usize>,
+    /// The response as a table when it's CSV / TSV
+    pub response_csv: Option<crate::features::csv_view::CsvView>,
+    /// JSONPath currently narrowing the explorer (`:jsonpath`)
+    pub jsonpath_query: Option<String>,
+    /// File the next (partial) response gets appended to (`:resume`)
+    pub resume_download: Option<String>,
+    /// Previous response, set by "re-send and compare" until the new one arrives
+    pub compare_baseline: Option<ResponseSnapshot>,
+    pub resend_requested: bool,
     pub response_headers: std::collections::HashMap<String, String>,
     pub status_code: Option<u16>,
     pub latency: Option<u128>,
     pub latency_history: Vec<u64>,
+    /// The request as it was at each send, oldest first (`R` to browse and restore)
+    pub timeline: Vec<TimelineEntry>,
     pub is_loading: bool,
+    /// Set while a streamed response's body is still coming in
+    pub stream: Option<crate::net::streaming::StreamText>,
+    /// The response's syntax highlighting, kept between frames
+    pub highlight: crate::ui::highlight::HighlightCache,
     pub timeout_ms: u64,
+    pub connect_timeout_ms: Option<u64>,
+    pub read_timeout_ms: Option<u64>,
 
     // UI State
     pub selected_tab: usize,
+    /// The row picked in the Settings tab, one per `timeouts::Phase`
+    pub settings_list_state: ListState,
     pub json_list_state: ListState,
+    /// Show the JSON response as a flat list of full paths instead of a tree
+    pub json_flat: bool,
+    /// Show the response exactly as received: no JSON tree, no pretty-printing
+    pub response_raw: bool,
     pub search_query: String,
+    /// Full-text search over the raw response (`F`), with the selected match
+    pub find_query: String,
+    pub find_index: usize,
     pub fullscreen_response: bool,
     pub response_scroll: (u16, u16),
 
@@ -271,9 +1039,17 @@ pub struct RequestTab {
     pub ws_messages: Vec<crate::net::websocket::WsMessage>,
     pub ws_connected: bool,
     pub ws_scroll: usize,
+    /// A session replay under way: (messages sent, messages in all)
+    pub ws_replay: Option<(usize, usize)>,
 }
 
 impl RequestTab {
+    /// Replaces the JSON explorer's tree, its cached rows with it
+    pub fn set_response_json(&mut self, tree: Option<Vec<JsonEntry>>) {
+        self.response_json = tree;
+        self.json_rows.invalidate();
+    }
+
     pub fn new() -> Self {
         RequestTab {
             name: "New Request".to_string(),
@@ -286,9 +1062,17 @@ impl RequestTab {
             body_type: BodyType::Raw,
             form_data: Vec::new(),
             form_list_state: ListState::default(),
+            urlencoded: Vec::new(),
+            urlencoded_list_state: ListState::default(),
             params: Vec::new(),
             params_list_state: ListState::default(),
+            path_params: Vec::new(),
+            path_params_list_state: ListState::default(),
             request_headers: std::collections::HashMap::new(),
+            disabled_headers: std::collections::HashMap::new(),
+            headers_list_state: ListState::default(),
+            header_draft: None,
+            disabled_defaults: Vec::new(),
             extract_rules: Vec::new(),
             extract_list_state: ListState::default(),
 
@@ -317,27 +1101,64 @@ impl RequestTab {
             should_describe_grpc_service: false,
             grpc_service_to_describe: String::new(),
             show_grpc_description_modal: false,
+            grpc_methods: Vec::new(),
+            grpc_method_selected: 0,
+            scaffold_grpc_message: None,
+            grpc_metadata: None,
+            trace: None,
+            request_id: None,
 
             pre_request_script: String::new(),
             post_request_script: String::new(),
             script_output: Vec::new(),
+            stress_stages: String::new(),
+            description: String::new(),
+            collection: None,
+            request_name: None,
+            variables: std::collections::HashMap::new(),
+            tags: Vec::new(),
+            dismissed_warnings: Vec::new(),
+            show_docs: false,
             test_results: Vec::new(),
 
             response: None,
             response_bytes: None,
             response_is_binary: false,
+            treat_as: None,
+            paginate: None,
+            request_bytes: None,
+            response_encoding: None,
+            response_cookies: Vec::new(),
             response_image: None,
             response_json: None,
+            json_rows: Default::default(),
+            response_ndjson: None,
+            ndjson_record: None,
+            response_csv: None,
+            jsonpath_query: None,
+            resume_download: None,
+            compare_baseline: None,
+            resend_requested: false,
             response_headers: std::collections::HashMap::new(),
             status_code: None,
             latency: None,
             latency_history: Vec::new(),
+            timeline: Vec::new(),
             is_loading: false,
-            timeout_ms: 30000, // Default 30 seconds
+            stream: None,
+            highlight: Default::default(),
+            timeout_ms: crate::net::timeouts::DEFAULT_TOTAL_MS,
+            connect_timeout_ms: None,
+            read_timeout_ms: None,
 
             selected_tab: 0,
+            settings_list_state: ListState::default().with_selected(Some(0)),
             json_list_state: ListState::default(),
+            json_flat: false,
+            response_raw: false,
             search_query: String::new(),
+            find_query: String::new(),
+            find_index: 0,
             fullscreen_response: false,
             response_scroll: (0, 0),
 
@@ -347,521 +1168,4989 @@ impl RequestTab {
             ws_messages: Vec::new(),
             ws_connected: false,
             ws_scroll: 0,
+            ws_replay: None,
         }
     }
 
-    pub fn clear_response(&mut self) {
-        self.response = None;
-        self.response_bytes = None;
-        self.response_is_binary = false;
-        self.response_image = None;
-        self.response_json = None;
-        self.response_headers.clear();
-        self.status_code = None;
-        self.latency = None;
-        self.script_output.clear();
-        self.test_results.clear();
+    /// Encodes the urlencoded key/value pairs as an `application/x-www-form-urlencoded` body
+    pub fn urlencoded_body(&self) -> String {
+        encode_urlencoded(&self.urlencoded)
     }
-}
-
-pub struct App {
-    // Global State
-    pub spinner_state: usize,
-    pub popup_message: Option<String>,
-
-    pub collections: Vec<crate::domain::collection::Collection>,
-    pub collection_state: ListState,
-    pub active_sidebar: bool,
-    pub sidebar_filter: String,
-    pub show_sidebar_filter: bool,
-
-    pub environments: Vec<crate::domain::environment::Environment>,
-    pub selected_env_index: usize,
 
-    pub request_history: Vec<RequestLog>,
+    /// Builds the collection entry for this tab, as it would be saved with `s`
+    /// Parse a curl command into this tab
+    pub fn apply_curl(&mut self, curl_cmd: &str) -> Result<(), String> {
+        // Normalize the command: handle line continuations and clean up
+        let cmd = curl_cmd
+            .replace("\\\n", " ")
+            .replace("\\\r\n", " ")
+            .trim()
+            .to_string();
 
-    pub notification_time: Option<std::time::Instant>,
+        // Must start with 'curl'
+        if !cmd.to_lowercase().starts_with("curl") {
+            return Err("Command must start with 'curl'".to_string());
+        }
 
-    pub editor_mode: EditorMode,
+        // Simple tokenizer that respects quotes
+        let tokens = Self::tokenize_curl(&cmd)?;
 
-    pub zen_mode: bool,
+        let mut url = String::new();
+        let mut method = "GET".to_string();
+        let mut headers: Vec<(String, String)> = Vec::new();
+        let mut body = String::new();
+        let mut form_data: Vec<(String, String, bool)> = Vec::new();
+        let mut urlencoded: Vec<(String, String)> = Vec::new();
+        let mut auth_user = String::new();
+        let mut auth_pass = String::new();
+
+        let mut i = 1; // Skip 'curl'
+        while i < tokens.len() {
+            let token = &tokens[i];
+            match token.as_str() {
+                "-X" | "--request" => {
+                    if i + 1 < tokens.len() {
+                        method = tokens[i + 1].to_uppercase();
+                        i += 1;
+                    }
+                }
+                "-H" | "--header" => {
+                    if i + 1 < tokens.len() {
+                        let header = &tokens[i + 1];
+                        if let Some(colon_pos) = header.find(':') {
+                            let key = header[..colon_pos].trim().to_string();
+                            let value = header[colon_pos + 1..].trim().to_string();
+                            headers.push((key, value));
+                        }
+                        i += 1;
+                    }
+                }
+                "-d" | "--data" | "--data-raw" | "--data-binary" => {
+                    if i + 1 < tokens.len() {
+                        body = tokens[i + 1].clone();
+                        if method == "GET" {
+                            method = "POST".to_string();
+                        }
+                        i += 1;
+                    }
+                }
+                "--data-urlencode" => {
+                    if i + 1 < tokens.len() {
+                        let pair = &tokens[i + 1];
+                        match pair.find('=') {
+                            Some(eq_pos) => urlencoded
+                                .push((pair[..eq_pos].to_string(), pair[eq_pos + 1..].to_string())),
+                            None => urlencoded.push((pair.clone(), String::new())),
+                        }
+                        if method == "GET" {
+                            method = "POST".to_string();
+                        }
+                        i += 1;
+                    }
+                }
+                "-F" | "--form" => {
+                    if i + 1 < tokens.len() {
+                        let form_item = &tokens[i + 1];
+                        if let Some(eq_pos) = form_item.find('=') {
+                            let key = form_item[..eq_pos].trim().to_string();
+                            let value = form_item[eq_pos + 1..].trim().to_string();
+                            let is_file = value.starts_with('@');
+                            let clean_value = if is_file {
+                                value[1..].to_string()
+                            } else {
+                                value
+                            };
+                            form_data.push((key, clean_value, is_file));
+                        }
+                        if method == "GET" {
+                            method = "POST".to_string();
+                        }
+                        i += 1;
+                    }
+                }
+                "-u" | "--user" => {
+                    if i + 1 < tokens.len() {
+                        let auth = &tokens[i + 1];
+                        if let Some(colon_pos) = auth.find(':') {
+                            auth_user = auth[..colon_pos].to_string();
+                            auth_pass = auth[colon_pos + 1..].to_string();
+                        }
+                        i += 1;
+                    }
+                }
+                "-A" | "--user-agent" => {
+                    if i + 1 < tokens.len() {
+                        headers.push(("User-Agent".to_string(), tokens[i + 1].clone()));
+                        i += 1;
+                    }
+                }
+                "-b" | "--cookie" => {
+                    if i + 1 < tokens.len() {
+                        headers.push(("Cookie".to_string(), tokens[i + 1].clone()));
+                        i += 1;
+                    }
+                }
+                "-e" | "--referer" => {
+                    if i + 1 < tokens.len() {
+                        headers.push(("Referer".to_string(), tokens[i + 1].clone()));
+                        i += 1;
+                    }
+                }
+                // Skip flags we don't care about
+                "-k" | "--insecure" | "-v" | "--verbose" | "-s" | "--silent" | "-L"
+                | "--location" | "-i" | "--include" | "--compressed" => {}
+                // Skip flags with arguments we ignore
+                "-o" | "--output" | "--connect-timeout" | "-m" | "--max-time" => {
+                    i += 1; // Skip the argument too
+                }
+                _ => {
+                    // If it looks like a URL
+                    if token.starts_with("http://")
+                        || token.starts_with("https://")
+                        || token.starts_with("ws://")
+                        || token.starts_with("wss://")
+                    {
+                        url = token.clone();
+                    } else if !token.starts_with('-') && url.is_empty() {
+                        // Might be a URL without protocol
+                        url = token.clone();
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        if url.is_empty() {
+            return Err("No URL found in curl command".to_string());
+        }
+
+        // A plain -d body sent as a urlencoded form becomes editable key/value pairs
+        let is_urlencoded_content = headers.iter().any(|(k, v)| {
+            k.eq_ignore_ascii_case("content-type")
+                && v.to_lowercase()
+                    .starts_with("application/x-www-form-urlencoded")
+        });
+        if is_urlencoded_content && !body.is_empty() {
+            urlencoded.extend(parse_urlencoded(&body));
+            body.clear();
+        }
+
+        // Populate the tab
+        let tab = self;
+        tab.url = url;
+        tab.method = method;
+        tab.request_headers = headers.into_iter().collect();
+
+        if !form_data.is_empty() {
+            tab.body_type = BodyType::FormData;
+            tab.form_data = form_data;
+        } else if !urlencoded.is_empty() {
+            tab.body_type = BodyType::UrlEncoded;
+            tab.urlencoded = urlencoded;
+        } else if !body.is_empty() {
+            tab.body_type = BodyType::Raw;
+            tab.request_body = body;
+        }
+
+        if !auth_user.is_empty() {
+            tab.auth_type = AuthType::Basic;
+            tab.basic_auth_user = auth_user;
+            tab.basic_auth_pass = auth_pass;
+        }
+
+        // Sync URL to params
+        if let Ok(u) = reqwest::Url::parse(&tab.url) {
+            tab.params = u.query_pairs().into_owned().collect();
+        } else {
+            tab.params.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Tokenize a curl command respecting quoted strings
+    fn tokenize_curl(cmd: &str) -> Result<Vec<String>, String> {
+        let mut tokens = Vec::new();
+        let mut current = String::new();
+        let mut in_single_quote = false;
+        let mut in_double_quote = false;
+        let mut escape_next = false;
+
+        for ch in cmd.chars() {
+            if escape_next {
+                current.push(ch);
+                escape_next = false;
+                continue;
+            }
+
+            match ch {
+                '\\' if !in_single_quote => {
+                    escape_next = true;
+                }
+                '\'' if !in_double_quote => {
+                    in_single_quote = !in_single_quote;
+                }
+                '"' if !in_single_quote => {
+                    in_double_quote = !in_double_quote;
+                }
+                ' ' | '\t' if !in_single_quote && !in_double_quote => {
+                    if !current.is_empty() {
+                        tokens.push(current.clone());
+                        current.clear();
+                    }
+                }
+                _ => {
+                    current.push(ch);
+                }
+            }
+        }
+
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+
+        if in_single_quote || in_double_quote {
+            return Err("Unclosed quote in curl command".to_string());
+        }
+
+        Ok(tokens)
+    }
+
+    /// Notes the outcome on the send still waiting for it: (status, latency) or an error
+    pub fn finish_timeline_entry(&mut self, outcome: Result<(u16, u128), String>) {
+        if let Some(entry) = self.timeline.last_mut()
+            && entry.is_pending()
+        {
+            match outcome {
+                Ok((status, latency)) => {
+                    entry.status = Some(status);
+                    entry.latency = Some(latency);
+                }
+                Err(e) => entry.error = Some(e),
+            }
+        }
+    }
+
+    /// Still "New Request" / "Req N", i.e. never renamed
+    pub fn has_default_name(&self) -> bool {
+        self.name == "New Request"
+            || self
+                .name
+                .strip_prefix("Req ")
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    }
+
+    /// The tab's own headers as listed in the Headers tab, sorted by name, with
+    /// whether each is sent
+    pub fn header_rows(&self) -> Vec<(String, String, bool)> {
+        let mut rows: Vec<(String, String, bool)> = self
+            .request_headers
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone(), true))
+            .chain(
+                self.disabled_headers
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone(), false)),
+            )
+            .collect();
+        rows.sort_by_key(|r| r.0.to_lowercase());
+        rows
+    }
+
+    /// The connect and read timeouts, for the client that sends the request
+    pub fn phase_timeouts(&self) -> crate::net::timeouts::PhaseTimeouts {
+        crate::net::timeouts::PhaseTimeouts {
+            connect_ms: self.connect_timeout_ms,
+            read_ms: self.read_timeout_ms,
+        }
+    }
+
+    /// A timeout as the Settings tab holds it
+    pub fn timeout(&self, phase: crate::net::timeouts::Phase) -> Option<u64> {
+        use crate::net::timeouts::{DEFAULT_TOTAL_MS, Phase};
+        match phase {
+            Phase::Total => Some(self.timeout_ms).filter(|ms| *ms != DEFAULT_TOTAL_MS),
+            Phase::Connect => self.connect_timeout_ms,
+            Phase::Read => self.read_timeout_ms,
+        }
+    }
+
+    pub fn to_request_config(&self) -> RequestConfig {
+        fn non_empty(s: &str) -> Option<String> {
+            if s.trim().is_empty() {
+                None
+            } else {
+                Some(s.to_string())
+            }
+        }
+
+        let body = if self.body_type == BodyType::UrlEncoded {
+            self.urlencoded_body()
+        } else {
+            self.request_body.clone()
+        };
+        let body_type = match self.body_type {
+            BodyType::Raw => None,
+            BodyType::FormData => Some("FormData"),
+            BodyType::UrlEncoded => Some("UrlEncoded"),
+            BodyType::GraphQL => Some("GraphQL"),
+            BodyType::Grpc => Some("Grpc"),
+        };
+
+        RequestConfig {
+            url: self.url.clone(),
+            method: self.method.clone(),
+            body: non_empty(&body),
+            headers: if self.request_headers.is_empty() {
+                None
+            } else {
+                Some(self.request_headers.clone())
+            },
+            extract: if self.extract_rules.is_empty() {
+                None
+            } else {
+                Some(self.extract_rules.iter().cloned().collect())
+            },
+            body_type: body_type.map(|s| s.to_string()),
+            form_data: if self.form_data.is_empty() {
+                None
+            } else {
+                Some(self.form_data.clone())
+            },
+            graphql_query: non_empty(&self.graphql_query),
+            graphql_variables: non_empty(&self.graphql_variables),
+            expected_status: None,
+            timeout_ms: (self.timeout_ms != crate::net::timeouts::DEFAULT_TOTAL_MS)
+                .then_some(self.timeout_ms),
+            connect_timeout_ms: self.connect_timeout_ms,
+            read_timeout_ms: self.read_timeout_ms,
+            pre_request_script: non_empty(&self.pre_request_script),
+            post_request_script: non_empty(&self.post_request_script),
+            stress_stages: non_empty(&self.stress_stages),
+            disabled_default_headers: self.disabled_defaults.clone(),
+            disabled_headers: self.disabled_headers.clone(),
+            path_params: self
+                .path_params
+                .iter()
+                .filter(|(_, v)| !v.is_empty())
+                .cloned()
+                .collect(),
+            description: non_empty(&self.description),
+            variables: self.variables.clone(),
+            grpc_service: non_empty(&self.grpc_target())
+                .filter(|_| self.body_type == BodyType::Grpc),
+            grpc_proto_path: non_empty(&self.grpc_proto_path)
+                .filter(|_| self.body_type == BodyType::Grpc),
+            tags: self.tags.clone(),
+            treat_as: self.treat_as.map(|t| t.name().to_string()),
+            paginate: self.paginate.clone(),
+        }
+    }
+
+    /// What grpcurl calls: the service, with the method after a slash when it's
+    /// kept apart
+    pub fn grpc_target(&self) -> String {
+        if self.grpc_method.is_empty() {
+            self.grpc_service.clone()
+        } else {
+            format!("{}/{}", self.grpc_service, self.grpc_method)
+        }
+    }
+
+    /// Sets up the views of a new response, or of the same one read as another
+    /// type: the JSON explorer, then NDJSON lines and the CSV table when its
+    /// Content-Type is what decides
+    pub fn reinterpret_response(&mut self) {
+        // A new body can share the old one's fingerprint: highlight it afresh
+        self.highlight = Default::default();
+        let tree = match self.treat_as {
+            None | Some(ResponseType::Json) => self
+                .body_text()
+                .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+                .map(|val| vec![JsonEntry::from_parsed("root".to_string(), val, 0)]),
+            Some(_) => None,
+        };
+        self.set_response_json(tree);
+        self.detect_ndjson();
+        self.detect_csv();
+    }
+
+    /// The body as text: a binary one only when it's being read as text anyway
+    pub fn body_text(&self) -> Option<String> {
+        if !self.response_is_binary {
+            return self.response.clone();
+        }
+        match self.treat_as {
+            None | Some(ResponseType::Binary) => None,
+            Some(_) => self
+                .response_bytes
+                .as_deref()
+                .map(|b| String::from_utf8_lossy(b).into_owned()),
+        }
+    }
+
+    /// The response pane shows the binary panel rather than text
+    pub fn shown_binary(&self) -> bool {
+        match self.treat_as {
+            Some(t) => t == ResponseType::Binary,
+            None => self.response_is_binary,
+        }
+    }
+
+    /// Splits a new response into NDJSON lines when it is one
+    pub fn detect_ndjson(&mut self) {
+        use crate::features::ndjson;
+        self.ndjson_record = None;
+        let content_type = self
+            .response_headers
+            .get("content-type")
+            .map(String::as_str)
+            .unwrap_or_default();
+        self.response_ndjson = match self.response.as_deref() {
+            Some(text)
+                if self.treat_as.is_none()
+                    && !self.response_is_binary
+                    && ndjson::is_ndjson(content_type, text) =>
+            {
+                Some(ndjson::parse(text))
+            }
+            _ => None,
+        };
+    }
+
+    /// Lays a new response out as a table when its content type is CSV / TSV
+    pub fn detect_csv(&mut self) {
+        use crate::features::csv_view::{self, CsvView};
+        let delimiter = self
+            .response_headers
+            .get("content-type")
+            .and_then(|t| csv_view::delimiter(t));
+        self.response_csv = match (self.response.as_deref(), delimiter) {
+            (Some(text), Some(delimiter))
+                if self.treat_as.is_none() && !self.response_is_binary =>
+            {
+                Some(CsvView::new(csv_view::parse(text, delimiter)))
+            }
+            _ => None,
+        };
+    }
+
+    /// The CSV table, when that's what the response pane shows rather than text
+    pub fn shown_csv(&mut self) -> Option<&mut crate::features::csv_view::CsvView> {
+        if self.response_raw || self.show_docs || !self.find_query.is_empty() {
+            return None;
+        }
+        self.response_csv.as_mut()
+    }
+
+    /// Text used for full-text search: pretty-printed when the response is JSON
+    pub fn searchable_text(&self) -> Option<String> {
+        if self.shown_binary() {
+            return None;
+        }
+        let text = self.body_text()?;
+        if self.response_raw || !matches!(self.treat_as, None | Some(ResponseType::Json)) {
+            return self.display_text();
+        }
+        serde_json::from_str::<Value>(&text)
+            .ok()
+            .and_then(|v| serde_json::to_string_pretty(&v).ok())
+            .or_else(|| self.display_text())
+    }
+
+    /// The text response as the viewer shows it: XML gets indented unless the raw
+    /// view is on
+    pub fn display_text(&self) -> Option<String> {
+        let text = self.body_text().or_else(|| self.response.clone())?;
+        let content_type = self
+            .response_headers
+            .get("content-type")
+            .map(|ct| ct.to_lowercase())
+            .unwrap_or_default();
+        let xml = match self.treat_as {
+            Some(t) => t == ResponseType::Xml,
+            None => {
+                (content_type.contains("xml") && !content_type.contains("html"))
+                    || text.trim_start().starts_with("<?xml")
+            }
+        };
+        if self.response_raw || !xml {
+            return Some(text);
+        }
+        Some(crate::features::format::pretty_xml(&text).unwrap_or(text))
+    }
+
+    pub fn find_matches(&self) -> Vec<(usize, usize)> {
+        match self.searchable_text() {
+            Some(text) => find_text_matches(&text, &self.find_query),
+            None => Vec::new(),
+        }
+    }
+
+    /// Moves to the next (or previous) match, wrapping around, and scrolls it into view
+    pub fn jump_to_match(&mut self, forward: bool) {
+        let matches = self.find_matches();
+        if matches.is_empty() {
+            return;
+        }
+        let len = matches.len();
+        self.find_index = if self.find_index >= len {
+            0
+        } else if forward {
+            (self.find_index + 1) % len
+        } else {
+            (self.find_index + len - 1) % len
+        };
+        self.scroll_to_match(matches[self.find_index].0);
+    }
+
+    /// Selects the first match, e.g. after typing a new query
+    pub fn jump_to_first_match(&mut self) {
+        self.find_index = 0;
+        if let Some(&(line, _)) = self.find_matches().first() {
+            self.scroll_to_match(line);
+        }
+    }
+
+    fn scroll_to_match(&mut self, line: usize) {
+        // Leave a little context above the match
+        self.response_scroll.0 = line.saturating_sub(3).min(u16::MAX as usize) as u16;
+    }
+
+    pub fn clear_response(&mut self) {
+        self.response = None;
+        self.response_bytes = None;
+        self.response_is_binary = false;
+        self.response_encoding = None;
+        self.response_cookies.clear();
+        self.response_image = None;
+        self.set_response_json(None);
+        self.jsonpath_query = None;
+        self.find_index = 0;
+        self.response_headers.clear();
+        self.status_code = None;
+        self.latency = None;
+        self.script_output.clear();
+        self.test_results.clear();
+        self.grpc_metadata = None;
+        self.trace = None;
+        self.highlight = Default::default();
+        self.forget_request();
+    }
+
+    /// Stops waiting for the latest send: its reply no longer finds this tab, so
+    /// nothing would end the loading state otherwise
+    pub fn forget_request(&mut self) {
+        self.request_id = None;
+        self.is_loading = false;
+        self.stream = None;
+    }
+}
+
+pub struct App {
+    // Global State
+    pub spinner_state: usize,
+    /// Directory every collection, environment, history and config file is read from
+    pub workspace: crate::domain::workspace::Workspace,
+    /// `encryption` in config.json
+    pub encryption: crate::features::vault::EncryptionSettings,
+    /// The key while encryption is on and unlocked; None with encryption on means locked
+    pub vault: Option<crate::features::vault::Vault>,
+    pub passphrase_prompt: Option<PassphrasePrompt>,
+    pub notifications: NotificationCenter,
+    pub show_notification_history: bool,
+    pub notification_history_scroll: usize,
+    /// Timeline browser for the active tab, newest send first
+    pub show_timeline: bool,
+    pub timeline_state: ListState,
+    /// Hex pane over the response (or the sent request), in place of the response
+    pub hex_view: Option<crate::features::hex::HexView>,
+    pub wire_view: Option<WireView>,
+    /// `:fuzz`: requests a second for the next run, and the run in progress
+    pub fuzz_rate: u32,
+    pub should_run_fuzz: bool,
+    pub fuzz_stop: Option<tokio::sync::mpsc::Sender<()>>,
+    /// Sent, out of, flagged
+    pub fuzz_progress: Option<(usize, usize, usize)>,
+    pub fuzz_report: Option<crate::features::fuzz::FuzzReport>,
+    pub show_fuzz_report: bool,
+    pub fuzz_state: ListState,
+    /// `:send all` / `:send 1,3-4`: the tabs picked for the next batch, then one
+    /// summary row per tab
+    pub batch_tabs: Vec<usize>,
+    pub should_run_batch: bool,
+    /// `:wsreplay`: what to send, and when, over the next connection
+    pub ws_replay_request: Option<Vec<(u64, String)>>,
+    pub should_stop_ws_replay: bool,
+    pub batch_rows: Vec<crate::features::batch::BatchRow>,
+    pub show_batch: bool,
+    pub batch_state: ListState,
+    /// `:paginate`: fetch every page of the active tab's request
+    pub should_paginate: bool,
+    /// The NDJSON line list (`L`)
+    pub show_ndjson: bool,
+    pub ndjson_filter: String,
+    /// Typing into the line filter
+    pub ndjson_filtering: bool,
+    pub ndjson_state: ListState,
+    /// Results of the last doctor run, shown as a modal while Some
+    pub doctor_report: Option<Vec<crate::features::doctor::Check>>,
+    pub doctor_scroll: u16,
+    pub doctor: crate::features::doctor::DoctorSettings,
+    /// Headers every request gets unless the tab sets or disables them
+    /// (`default_headers` in config.json); the environment's `headers` go over these
+    pub default_headers: std::collections::BTreeMap<String, String>,
+    /// Variables every request sees (`variables` in config.json), under the
+    /// environment's, the collection's and the request's own
+    pub global_variables: std::collections::BTreeMap<String, String>,
+    /// Mistakes to look for before sending (`send_checks` in config.json)
+    pub send_checks: crate::features::presend::SendChecks,
+    /// Proto roots and import paths gRPC requests resolve against (`protos` in config.json)
+    pub proto_registry: crate::features::protos::ProtoRegistry,
+    pub proto_view: Option<crate::features::protos::ProtoView>,
+    /// W3C trace headers on every request (`trace` in config.json)
+    pub trace_settings: crate::features::trace::TraceSettings,
+    /// `dashboard` in config.json
+    pub dashboard_settings: crate::features::dashboard::DashboardSettings,
+    /// The health board (`:dashboard`), its checks running while it's open
+    pub dashboard: Option<crate::features::dashboard::Dashboard>,
+    pub should_start_dashboard: bool,
+    /// What the log layer recorded this session, newest last
+    pub log_buffer: crate::features::logging::LogBuffer,
+    /// `:logs`, shown as a modal while Some
+    pub log_view: Option<crate::features::logging::LogView>,
+    /// Latency of every send of a saved request, kept in latency.json
+    pub latency_trends: crate::features::latency::LatencyStore,
+    /// The `collection/request` charted by `:trend`, shown as a modal while Some
+    pub latency_chart: Option<String>,
+    /// Warnings about the request about to be sent, waiting for send-anyway or cancel
+    pub send_warnings: Option<Vec<crate::features::presend::Warning>>,
+    /// Where each `{{variable}}` of the active request comes from (`:vars`)
+    pub variables_inspector: Option<crate::domain::variables::Inspection>,
+    pub variables_inspector_scroll: u16,
+    /// Where the active environment's variables are used (`:vars env`)
+    pub env_usage: Option<crate::features::env_usage::UsageView>,
+    /// Duplicate requests across collections (`:dedupe`), shown as a modal while Some
+    pub dedupe: Option<crate::features::dedupe::DedupeView>,
+    /// Security audit of the active response (`A`), shown as a modal while Some
+    pub audit_report: Option<crate::features::audit::AuditReport>,
+    pub audit_scroll: u16,
+    /// `:coldiff` between two versions of a collection, shown as a modal while Some
+    pub collection_diff: Option<crate::features::collection_diff::Report>,
+    pub collection_diff_scroll: u16,
+    /// Key remapping from keymap.toml
+    pub keymap: crate::domain::keymap::Keymap,
+    pub show_keymap: bool,
+    pub keymap_scroll: u16,
+
+    pub collections: Vec<crate::domain::collection::Collection>,
+    pub collection_state: ListState,
+    pub active_sidebar: bool,
+    pub sidebar_filter: String,
+    pub show_sidebar_filter: bool,
+    /// `:tag filter smoke`: the sidebar and the runner only take requests with one
+    /// of these tags
+    pub tag_filter: Vec<String>,
+    /// Percent of the width the sidebar takes, dragged with the mouse
+    pub sidebar_width: u16,
+    /// The sidebar's edge is being dragged
+    pub dragging_sidebar: bool,
+    /// Where the last frame drew things, for the mouse
+    pub screen_areas: crate::ui::layout::ScreenAreas,
+
+    pub environments: Vec<crate::domain::environment::Environment>,
+    pub selected_env_index: usize,
+
+    pub request_history: Vec<RequestLog>,
+
+    pub editor_mode: EditorMode,
+
+    pub zen_mode: bool,
+    /// Keep the end of a streaming response in view
+    pub follow_stream: bool,
+    /// How an image response is drawn in the pane (`z`)
+    pub image_zoom: crate::features::image_view::ImageZoom,
     pub show_help: bool,
     pub help_scroll: u16,
+    pub help_query: String,
+    pub help_search_active: bool,
+    pub help_show_all: bool,
+
+    pub show_command_palette: bool,
+    pub command_query: String,
+    pub command_index: usize,
+    pub command_input: String,
+    /// Past `:` commands, oldest first, browsed with Up/Down
+    pub command_history: Vec<String>,
+    pub command_history_index: Option<usize>,
+    /// Tab completions for the command line and which one is shown
+    pub command_completions: Vec<String>,
+    pub command_completion_index: usize,
+    /// Set by `:run`, picked up by the main loop like Enter in the runner
+    pub should_run_collection: bool,
+    /// Cursor (a byte offset) in whichever input is being edited, apart from the URL
+    /// which keeps `url_cursor_index`. Back to `line_edit::CURSOR_END` on every mode change.
+    pub input_cursor: usize,
+
+    pub show_codegen_picker: bool,
+    pub codegen_targets: Vec<crate::features::codegen::Target>,
+    pub codegen_query: String,
+    pub codegen_index: usize,
+
+    pub show_snippet_picker: bool,
+    pub snippets: Vec<crate::features::snippets::Snippet>,
+    pub snippet_query: String,
+    pub snippet_index: usize,
+    /// The script the picker inserts into, `Tab` switches it
+    pub snippet_target: crate::features::snippets::ScriptKind,
+
+    pub show_finder: bool,
+    pub finder_query: String,
+    pub finder_index: usize,
+
+    pub show_history_view: bool,
+    pub history_query: String,
+    /// Position among the entries shown, headings not counted
+    pub history_index: usize,
+    /// Indices into `request_history` marked for bulk delete
+    pub history_marked: std::collections::BTreeSet<usize>,
+    /// `history` in config.json
+    pub history_settings: crate::features::history::HistorySettings,
+    /// `gist` in config.json
+    pub gist: crate::features::gist_sync::GistSettings,
+    /// `docs` in config.json
+    pub docs_settings: crate::features::doc_gen::DocsSettings,
+    /// `storage` in config.json
+    pub storage: crate::features::storage::StorageSettings,
+    pub sync_status: crate::features::gist_sync::SyncStatus,
+    /// Picked up by the main loop, which talks to GitHub or the storage
+    pub sync_request: Option<crate::features::gist_sync::SyncRequest>,
+    pub sync_prompt: Option<crate::features::gist_sync::SyncPrompt>,
+    /// Tabs an unclean exit left in `recovery.json`, waiting on restore / discard
+    pub recovery_prompt: Option<crate::features::recovery::Snapshot>,
+    pub recovery: crate::features::recovery::Autosave,
+    /// `:q`, picked up by the main loop so it can exit cleanly
+    pub should_quit: bool,
+    /// Bundle import waiting on overwrite / skip / keep both
+    pub bundle_prompt: Option<crate::features::bundle::ImportPrompt>,
+    /// History entry being saved into a collection, and its `collection/name`
+    pub save_history_entry: Option<usize>,
+    pub save_history_input: String,
+
+    pub show_cookie_modal: bool,
+    pub cookie_list_state: ListState,
+
+    pub cookie_jar: std::collections::HashMap<String, Vec<String>>,
+
+    // Token expiry tracking, keyed by access token
+    pub oauth_grants: std::collections::HashMap<String, OAuthGrant>,
+    pub token_alerts: std::collections::HashSet<String>,
+    pub refreshing_tokens: std::collections::HashSet<String>,
+    pub last_token_check: Option<std::time::Instant>,
+
+    // Tabs
+    pub tabs: Vec<RequestTab>,
+    pub active_tab: usize,
+    pub next_request_id: usize,
+
+    // Collection Runner (Global)
+    pub runner_mode: bool,
+    pub runner_result: Option<crate::features::runner::CollectionRunResult>,
+    pub runner_scroll: usize,
+    pub runner_hooks: crate::features::run_hooks::RunHooks,
+
+    // Workspace profiles (profiles.hcl) gating requests to production
+    pub profiles: Vec<crate::domain::profile::Profile>,
+    pub active_profile: Option<usize>,
+    pub guard_prompt: Option<GuardPrompt>,
+    /// Set by confirming `guard_prompt`, lets exactly one guarded action through
+    pub guard_confirmed: bool,
+
+    // Connection pool / DNS cache of the shared HTTP client
+    pub connection: crate::net::pool::PoolSettings,
+    pub should_flush_connections: bool,
+    /// Pacing for runs, stress tests and sentinel (`rate_limit` in config.json)
+    pub rate_limit: crate::net::rate_limit::RateLimitSettings,
+
+    // Splash screen
+    pub show_splash: bool,
+
+    // Theme
+    pub theme: Theme,
+    /// Index into `themes`
+    pub theme_index: usize,
+    /// The built-ins, then the workspace's `themes/*.toml`
+    pub themes: Vec<Theme>,
+    pub theme_picker: Option<ThemePicker>,
+
+    // Diff
+    pub diff_base_index: Option<usize>,
+    pub show_diff_view: bool,
+    pub diff_target_index: Option<usize>,
+    pub diff_list_state: ListState,
+    /// History diff compares parsed JSON and headers instead of lines
+    pub diff_json_mode: bool,
+    pub response_compare: Option<ResponseComparison>,
+    pub response_compare_scroll: usize,
+
+    // Mock Server
+    pub mock_mode: bool,
+    pub mock_server_running: bool,
+    pub mock_server_port: u16,
+    pub mock_routes: Vec<crate::net::mock_server::MockRoute>,
+    pub mock_list_state: ListState,
+    pub mock_server_handle: Option<crate::net::mock_server::MockServerHandle>,
+    pub mock_editor: Option<MockRouteEditor>,
+    /// The `cors` and `tls` blocks of mocks.hcl
+    pub mock_settings: crate::net::mock_server::MockSettings,
+    pub image_picker: Option<Picker>,
+    pub clipboard: Option<Clipboard>,
+
+    // Stress Testing State
+    pub show_stress_modal: bool,
+    pub stress_vus_input: String,
+    /// The Settings tab timeout being edited
+    pub timeout_input: String,
+    /// Collection whose requests the stress test cycles through, empty for the active tab
+    pub stress_target: String,
+    pub stress_duration_input: String,
+    /// Pass/fail conditions, see `stress::parse_thresholds`
+    pub stress_thresholds_input: String,
+    pub stress_running: bool,
+    pub stress_stats: Option<crate::features::stress::StressStats>,
+    pub stress_progress: Option<crate::features::stress::StressProgress>,
+    pub should_run_stress_test: bool,
+
+    // SSL Configuration
+    pub ssl_verify: bool,                 // Whether to verify SSL certificates
+    pub ssl_ca_cert_path: Option<String>, // Path to custom CA certificate
+    pub ssl_client_cert_path: Option<String>, // Path to client certificate (for mTLS)
+    pub ssl_client_key_path: Option<String>, // Path to client key (for mTLS)
+
+    // Proxy Configuration
+    pub proxy_url: Option<String>, // HTTP/HTTPS proxy URL (e.g., http://proxy:8080)
+    pub proxy_auth_user: Option<String>, // Proxy authentication username
+    pub proxy_auth_pass: Option<String>, // Proxy authentication password
+    pub no_proxy: Option<String>,  // Comma-separated list of hosts to bypass proxy
+
+    // cURL Import
+    pub curl_import_input: String, // Input field for curl command
+
+    // Sentinel Mode
+    pub sentinel_mode: bool,
+    pub sentinel_state: Option<crate::features::sentinel::SentinelState>,
+    pub should_start_sentinel: bool,
+    pub sentinel_interval_input: String,
+}
+
+/// The theme selector: moving through it previews each theme
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThemePicker {
+    pub selected: usize,
+    /// The theme to go back to on Esc
+    pub original: usize,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct AppConfig {
+    /// From before themes were kept by name, the built-in at this index
+    theme_index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    theme: Option<String>,
+    selected_env_index: usize,
+    zen_mode: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sidebar_width: Option<u16>,
+    #[serde(default)]
+    notifications: NotificationTimeouts,
+    #[serde(default)]
+    runner_hooks: crate::features::run_hooks::RunHooks,
+    #[serde(default)]
+    connection: crate::net::pool::PoolSettings,
+    #[serde(default)]
+    rate_limit: crate::net::rate_limit::RateLimitSettings,
+    #[serde(default)]
+    active_profile: Option<String>,
+    #[serde(default)]
+    doctor: crate::features::doctor::DoctorSettings,
+    #[serde(default)]
+    encryption: crate::features::vault::EncryptionSettings,
+    #[serde(default)]
+    history: crate::features::history::HistorySettings,
+    #[serde(default)]
+    gist: crate::features::gist_sync::GistSettings,
+    #[serde(default)]
+    storage: crate::features::storage::StorageSettings,
+    #[serde(default)]
+    default_headers: std::collections::BTreeMap<String, String>,
+    #[serde(default)]
+    docs: crate::features::doc_gen::DocsSettings,
+    #[serde(default)]
+    variables: std::collections::BTreeMap<String, String>,
+    #[serde(default)]
+    send_checks: crate::features::presend::SendChecks,
+    #[serde(default)]
+    protos: crate::features::protos::ProtoRegistry,
+    #[serde(default)]
+    dashboard: crate::features::dashboard::DashboardSettings,
+    #[serde(default)]
+    trace: crate::features::trace::TraceSettings,
+}
+
+use crate::domain::collection::{Collection, RequestConfig};
+use crate::domain::environment::Environment;
+use crate::domain::keymap::KeyContext;
+use crate::domain::notification::{NotificationCenter, NotificationLevel, NotificationTimeouts};
+use crate::features::token_refresh::{self, OAuthGrant, TokenAction, TokenResponse};
+use arboard::Clipboard;
+use ratatui::widgets::ListState;
+
+impl App {
+    /// A throwaway workspace, what tests start from: nothing they save lands in
+    /// the current directory
+    #[cfg(test)]
+    pub fn new() -> App {
+        App::with_workspace(crate::domain::workspace::Workspace::at(
+            crate::tests::common::temp_dir("app"),
+        ))
+    }
+
+    pub fn with_workspace(workspace: crate::domain::workspace::Workspace) -> App {
+        let mut app = App {
+            spinner_state: 0,
+            workspace,
+            encryption: Default::default(),
+            vault: None,
+            passphrase_prompt: None,
+            notifications: NotificationCenter::default(),
+            show_notification_history: false,
+            notification_history_scroll: 0,
+            show_timeline: false,
+            timeline_state: ListState::default(),
+            hex_view: None,
+            wire_view: None,
+            fuzz_rate: crate::features::fuzz::DEFAULT_RATE,
+            should_run_fuzz: false,
+            fuzz_stop: None,
+            fuzz_progress: None,
+            fuzz_report: None,
+            show_fuzz_report: false,
+            fuzz_state: ListState::default(),
+            batch_tabs: Vec::new(),
+            should_run_batch: false,
+            ws_replay_request: None,
+            should_stop_ws_replay: false,
+            batch_rows: Vec::new(),
+            show_batch: false,
+            batch_state: ListState::default(),
+            should_paginate: false,
+            show_ndjson: false,
+            ndjson_filter: String::new(),
+            ndjson_filtering: false,
+            ndjson_state: ListState::default(),
+            doctor_report: None,
+            doctor_scroll: 0,
+            doctor: Default::default(),
+            default_headers: Default::default(),
+            global_variables: Default::default(),
+            send_checks: Default::default(),
+            proto_registry: Default::default(),
+            proto_view: None,
+            trace_settings: Default::default(),
+            dashboard_settings: Default::default(),
+            dashboard: None,
+            should_start_dashboard: false,
+            log_buffer: Default::default(),
+            log_view: None,
+            latency_trends: Default::default(),
+            latency_chart: None,
+            send_warnings: None,
+            variables_inspector: None,
+            variables_inspector_scroll: 0,
+            env_usage: None,
+            dedupe: None,
+            audit_report: None,
+            audit_scroll: 0,
+            collection_diff: None,
+            collection_diff_scroll: 0,
+            keymap: Default::default(),
+            show_keymap: false,
+            keymap_scroll: 0,
+            collections: Vec::new(),
+            collection_state: ListState::default(),
+            active_sidebar: false,
+            sidebar_filter: String::new(),
+            show_sidebar_filter: false,
+            tag_filter: Vec::new(),
+            sidebar_width: crate::ui::layout::SIDEBAR_PERCENT,
+            dragging_sidebar: false,
+            screen_areas: Default::default(),
+            environments: Vec::new(),
+            selected_env_index: 0,
+            request_history: Vec::new(),
+            editor_mode: EditorMode::None,
+            zen_mode: false,
+            follow_stream: true,
+            image_zoom: Default::default(),
+            show_help: false,
+            help_scroll: 0,
+            help_query: String::new(),
+            help_search_active: false,
+            help_show_all: false,
+            show_command_palette: false,
+            command_query: String::new(),
+            command_index: 0,
+            command_input: String::new(),
+            command_history: Vec::new(),
+            command_history_index: None,
+            command_completions: Vec::new(),
+            command_completion_index: 0,
+            should_run_collection: false,
+            input_cursor: crate::domain::line_edit::CURSOR_END,
+            show_codegen_picker: false,
+            codegen_targets: Vec::new(),
+            codegen_query: String::new(),
+            codegen_index: 0,
+            show_snippet_picker: false,
+            snippets: Vec::new(),
+            snippet_query: String::new(),
+            snippet_index: 0,
+            snippet_target: crate::features::snippets::ScriptKind::Pre,
+            show_finder: false,
+            finder_query: String::new(),
+            finder_index: 0,
+            show_history_view: false,
+            history_query: String::new(),
+            history_index: 0,
+            history_marked: std::collections::BTreeSet::new(),
+            history_settings: Default::default(),
+            docs_settings: Default::default(),
+            gist: Default::default(),
+            storage: Default::default(),
+            sync_status: Default::default(),
+            sync_request: None,
+            sync_prompt: None,
+            recovery_prompt: None,
+            recovery: Default::default(),
+            should_quit: false,
+            bundle_prompt: None,
+            save_history_entry: None,
+            save_history_input: String::new(),
+            cookie_jar: std::collections::HashMap::new(),
+            oauth_grants: std::collections::HashMap::new(),
+            token_alerts: std::collections::HashSet::new(),
+            refreshing_tokens: std::collections::HashSet::new(),
+            last_token_check: None,
+
+            tabs: vec![RequestTab::new()],
+            active_tab: 0,
+            next_request_id: 1,
+
+            runner_mode: false,
+            runner_result: None,
+            runner_scroll: 0,
+            runner_hooks: Default::default(),
+            connection: Default::default(),
+            rate_limit: Default::default(),
+            should_flush_connections: false,
+            profiles: Vec::new(),
+            active_profile: None,
+            guard_prompt: None,
+            guard_confirmed: false,
+            show_splash: true,
+            theme: Theme::default_theme(),
+            theme_index: 0,
+            themes: crate::domain::theme::BUILTIN
+                .iter()
+                .filter_map(|id| Theme::builtin(id))
+                .collect(),
+            theme_picker: None,
+
+            diff_base_index: None,
+            show_diff_view: false,
+            diff_target_index: None,
+            diff_list_state: ListState::default(),
+            diff_json_mode: false,
+            response_compare: None,
+            response_compare_scroll: 0,
+
+            mock_mode: false,
+            mock_server_running: false,
+            mock_server_port: 3000,
+            mock_routes: Vec::new(),
+            mock_list_state: ListState::default(),
+            mock_server_handle: None,
+            mock_editor: None,
+            mock_settings: Default::default(),
+            image_picker: if std::env::var("TERM_PROGRAM")
+                .map(|v| v == "vscode")
+                .unwrap_or(false)
+            {
+                Some(Picker::halfblocks())
+            } else {
+                Picker::from_query_stdio()
+                    .ok()
+                    .or(Some(Picker::halfblocks()))
+            },
+            clipboard: Clipboard::new().ok(),
+
+            show_stress_modal: false,
+            stress_vus_input: "50".to_string(), // Default 50 VUs
+            timeout_input: String::new(),
+            stress_target: String::new(),
+            stress_duration_input: "10".to_string(), // Default 10s
+            stress_thresholds_input: String::new(),
+            stress_running: false,
+            stress_stats: None,
+            stress_progress: None,
+            should_run_stress_test: false,
+
+            show_cookie_modal: false,
+            cookie_list_state: ListState::default(),
+
+            // SSL: Load from environment variables or use defaults
+            ssl_verify: std::env::var("POSTDAD_SSL_VERIFY")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true),
+            ssl_ca_cert_path: std::env::var("POSTDAD_CA_CERT").ok(),
+            ssl_client_cert_path: std::env::var("POSTDAD_CLIENT_CERT").ok(),
+            ssl_client_key_path: std::env::var("POSTDAD_CLIENT_KEY").ok(),
+
+            // Proxy: Load from standard environment variables
+            proxy_url: std::env::var("HTTPS_PROXY")
+                .or_else(|_| std::env::var("https_proxy"))
+                .or_else(|_| std::env::var("HTTP_PROXY"))
+                .or_else(|_| std::env::var("http_proxy"))
+                .ok(),
+            proxy_auth_user: std::env::var("POSTDAD_PROXY_USER").ok(),
+            proxy_auth_pass: std::env::var("POSTDAD_PROXY_PASS").ok(),
+            no_proxy: std::env::var("NO_PROXY")
+                .or_else(|_| std::env::var("no_proxy"))
+                .ok(),
+
+            curl_import_input: String::new(),
+
+            sentinel_mode: false,
+            sentinel_state: Some(crate::features::sentinel::SentinelState::new()),
+            should_start_sentinel: false,
+            sentinel_interval_input: "2".to_string(),
+        };
+
+        app.load_workspace();
+
+        if app.doctor.on_startup {
+            let checks = crate::features::doctor::run(&app.doctor_probe(), app.doctor.check_proxy);
+            match crate::features::doctor::counts(&checks) {
+                (_, 0) => {}
+                (warnings, failures) => app.show_error(format!(
+                    "Doctor: {} problem(s), {} warning(s). Run :doctor for fixes",
+                    failures, warnings
+                )),
+            }
+        }
+
+        app
+    }
+
+    /// Reads everything persisted in the workspace: collections, environments, config,
+    /// profiles, cookies, history, mocks and the keymap
+    fn load_workspace(&mut self) {
+        self.collections =
+            Collection::load_from_dir(&self.workspace.path("collections")).unwrap_or_default();
+        self.collection_state = ListState::default();
+        self.environments = Environment::load_from_file(&self.workspace.path("environments.hcl"))
+            .unwrap_or_default();
+        self.mock_routes = crate::net::mock_server::load_routes(
+            &self.workspace.path(crate::net::mock_server::MOCKS_FILE),
+        )
+        .unwrap_or_default();
+        self.mock_settings = crate::net::mock_server::load_settings(
+            &self.workspace.path(crate::net::mock_server::MOCKS_FILE),
+        )
+        .unwrap_or_default();
+
+        // Load persisted config and state
+        let config = self.load_config();
+        self.load_themes();
+        self.theme_index = match &config.theme {
+            Some(id) => self.themes.iter().position(|t| t.id == *id).unwrap_or(0),
+            None => config.theme_index.min(self.themes.len() - 1),
+        };
+        self.zen_mode = config.zen_mode;
+        self.sidebar_width = config
+            .sidebar_width
+            .unwrap_or(crate::ui::layout::SIDEBAR_PERCENT);
+        self.notifications.timeouts = config.notifications;
+        self.runner_hooks = config.runner_hooks;
+        self.connection = config.connection;
+        self.rate_limit = config.rate_limit.sanitized();
+        match crate::domain::profile::load_profiles(
+            &self.workspace.path(crate::domain::profile::PROFILES_FILE),
+        ) {
+            Ok(profiles) => self.profiles = profiles,
+            Err(e) => {
+                self.profiles = Vec::new();
+                self.show_error(e);
+            }
+        }
+        self.active_profile = config
+            .active_profile
+            .and_then(|name| self.profiles.iter().position(|p| p.name == name));
+
+        // Bounds check env index
+        if config.selected_env_index < self.environments.len() {
+            self.selected_env_index = config.selected_env_index;
+        } else {
+            self.selected_env_index = 0;
+        }
+
+        self.history_settings = config.history;
+        self.gist = config.gist;
+        self.storage = config.storage;
+        let synced_at = self
+            .sync_state(crate::features::gist_sync::Target::Gist)
+            .synced_at
+            .max(
+                self.sync_state(crate::features::gist_sync::Target::Storage)
+                    .synced_at,
+            );
+        self.sync_status = match synced_at {
+            0 => crate::features::gist_sync::SyncStatus::Idle,
+            at => crate::features::gist_sync::SyncStatus::Synced(at),
+        };
+        self.sync_prompt = None;
+        self.encryption = config.encryption;
+        self.unlock_on_load();
+        self.decrypt_env_secrets();
+        self.cookie_jar = self.load_cookies();
+        self.request_history = self.load_history();
+        self.latency_trends = self
+            .read_store(crate::features::latency::FILE)
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        self.command_history = self.load_command_history();
+
+        match crate::domain::keymap::Keymap::load(
+            &self.workspace.path(crate::domain::keymap::KEYMAP_FILE),
+        ) {
+            Ok(keymap) => self.keymap = keymap,
+            Err(e) => self.show_error(format!("Keymap not loaded: {}", e)),
+        }
+
+        self.doctor = config.doctor;
+        self.default_headers = config.default_headers;
+        self.docs_settings = config.docs;
+        self.global_variables = config.variables;
+        self.send_checks = config.send_checks;
+        self.proto_registry = config.protos;
+        self.dashboard_settings = config.dashboard;
+        self.trace_settings = config.trace;
+
+        // Apply loaded theme
+        self.apply_theme();
+    }
+
+    pub fn active_tab(&self) -> &RequestTab {
+        &self.tabs[self.active_tab]
+    }
+
+    pub fn active_tab_mut(&mut self) -> &mut RequestTab {
+        &mut self.tabs[self.active_tab]
+    }
+
+    /// The tab whose latest send is `request_id`. None once that tab is closed, has
+    /// sent again or had something else loaded into it.
+    pub fn tab_for_request(&self, request_id: &str) -> Option<usize> {
+        self.tabs
+            .iter()
+            .position(|t| t.request_id.as_deref() == Some(request_id))
+    }
+
+    /// Puts the tab that sent `request_id` in front while its reply is handled, so
+    /// the reply lands there and not in whatever tab is showing. Returns the tab to
+    /// go back to with `leave_request_tab`.
+    pub fn enter_request_tab(&mut self, request_id: &str) -> Option<usize> {
+        let tab = self.tab_for_request(request_id)?;
+        Some(std::mem::replace(&mut self.active_tab, tab))
+    }
+
+    pub fn leave_request_tab(&mut self, previous: usize) {
+        self.active_tab = previous.min(self.tabs.len().saturating_sub(1));
+    }
+
+    /// The built-in themes and the workspace's theme files. Files that don't load
+    /// are reported and left out.
+    pub fn load_themes(&mut self) {
+        let current = self.theme.id.clone();
+        let (themes, errors) =
+            crate::domain::theme::load_all(&self.workspace.path(crate::domain::theme::THEMES_DIR));
+        self.themes = themes;
+        for error in errors {
+            self.show_error(format!("Theme not loaded: {}", error));
+        }
+        self.theme_index = self
+            .themes
+            .iter()
+            .position(|t| t.id == current)
+            .unwrap_or(0);
+    }
+
+    /// `:theme <name>`, by name rather than cycling
+    pub fn set_theme(&mut self, name: &str) {
+        match self.themes.iter().position(|t| t.is_called(name)) {
+            Some(i) => {
+                self.theme_index = i;
+                self.apply_theme();
+                self.save_config();
+            }
+            None => {
+                let ids: Vec<&str> = self.themes.iter().map(|t| t.id.as_str()).collect();
+                self.show_error(format!(
+                    "Unknown theme '{}' (one of {})",
+                    name,
+                    ids.join(", ")
+                ))
+            }
+        }
+    }
+
+    pub fn apply_theme(&mut self) {
+        self.theme = self
+            .themes
+            .get(self.theme_index)
+            .cloned()
+            .unwrap_or_else(Theme::default_theme);
+    }
+
+    pub fn next_theme(&mut self) {
+        self.theme_index = (self.theme_index + 1) % self.themes.len().max(1);
+        self.apply_theme();
+        self.save_config();
+    }
+
+    /// `:theme` with no name: every theme, previewed as the selection moves
+    pub fn open_theme_picker(&mut self) {
+        self.load_themes();
+        self.apply_theme();
+        self.theme_picker = Some(ThemePicker {
+            selected: self.theme_index,
+            original: self.theme_index,
+        });
+    }
+
+    pub fn move_theme_picker(&mut self, down: bool) {
+        let len = self.themes.len();
+        let Some(picker) = self.theme_picker.as_mut() else {
+            return;
+        };
+        picker.selected = if down {
+            (picker.selected + 1) % len
+        } else {
+            (picker.selected + len - 1) % len
+        };
+        self.theme_index = picker.selected;
+        self.apply_theme();
+    }
+
+    /// Enter keeps the previewed theme, Esc goes back to the one from before
+    pub fn close_theme_picker(&mut self, keep: bool) {
+        let Some(picker) = self.theme_picker.take() else {
+            return;
+        };
+        if keep {
+            self.theme_index = picker.selected;
+            self.apply_theme();
+            self.save_config();
+            self.show_success(format!("Theme: {}", self.theme.name));
+        } else {
+            self.theme_index = picker.original;
+            self.apply_theme();
+        }
+    }
+
+    pub fn add_tab(&mut self) {
+        let mut tab = RequestTab::new();
+        tab.name = format!("Req {}", self.next_request_id);
+        self.next_request_id += 1;
+        self.tabs.push(tab);
+        self.active_tab = self.tabs.len() - 1;
+    }
+
+    /// Opens `:rename` with the current name filled in
+    pub fn start_rename_tab(&mut self) {
+        self.show_command_palette = false;
+        self.command_input = format!("rename {}", self.active_tab().name);
+        self.input_cursor = crate::domain::line_edit::CURSOR_END;
+        self.active_tab_mut().input_mode = InputMode::Command;
+    }
+
+    pub fn rename_tab(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            self.show_error("Usage: :rename <name>".to_string());
+            return;
+        }
+        self.active_tab_mut().name = name.to_string();
+        self.show_success(format!("Tab renamed to {}", name));
+    }
+
+    pub fn close_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            self.tabs.remove(self.active_tab);
+            if self.active_tab >= self.tabs.len() {
+                self.active_tab = self.tabs.len() - 1;
+            }
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            self.active_tab = (self.active_tab + 1) % self.tabs.len();
+        }
+    }
+
+    pub fn prev_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            if self.active_tab == 0 {
+                self.active_tab = self.tabs.len() - 1;
+            } else {
+                self.active_tab -= 1;
+            }
+        }
+    }
+
+    pub fn add_cookies(&mut self, url: &str, new_cookies: Vec<String>) {
+        if new_cookies.is_empty() {
+            return;
+        }
+
+        if let Ok(parsed) = reqwest::Url::parse(url)
+            && let Some(host) = parsed.host_str()
+        {
+            let entry = self.cookie_jar.entry(host.to_string()).or_default();
+            for raw_cookie in new_cookies {
+                let name_val = raw_cookie
+                    .split(';')
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .to_string();
+                if name_val.is_empty() {
+                    continue;
+                }
+
+                let name = name_val.split('=').next().unwrap_or("").trim();
+                entry.retain(|c| !c.starts_with(&format!("{}=", name)));
+
+                entry.push(name_val);
+            }
+            self.save_cookies();
+        }
+    }
+
+    pub fn get_cookie_header(&self, url: &str) -> Option<String> {
+        if let Ok(parsed) = reqwest::Url::parse(url)
+            && let Some(host) = parsed.host_str()
+            && let Some(cookies) = self.cookie_jar.get(host)
+            && !cookies.is_empty()
+        {
+            return Some(cookies.join("; "));
+        }
+        None
+    }
+
+    pub fn get_flattened_cookies(&self) -> Vec<(String, String)> {
+        let mut flattened = Vec::new();
+        for (host, cookies) in &self.cookie_jar {
+            for cookie in cookies {
+                flattened.push((host.clone(), cookie.clone()));
+            }
+        }
+        flattened.sort_by(|a, b| a.0.cmp(&b.0));
+        flattened
+    }
+
+    pub fn delete_cookie_at_index(&mut self, index: usize) {
+        let flattened = self.get_flattened_cookies();
+        if let Some((host, cookie_val)) = flattened.get(index)
+            && let Some(cookies) = self.cookie_jar.get_mut(host)
+            && let Some(pos) = cookies.iter().position(|c| c == cookie_val)
+        {
+            cookies.remove(pos);
+            if cookies.is_empty() {
+                self.cookie_jar.remove(host);
+            }
+            self.save_cookies();
+        }
+    }
+
+    pub fn should_open_editor(&self) -> bool {
+        self.editor_mode != EditorMode::None
+    }
+
+    pub fn get_active_env(&self) -> &Environment {
+        &self.environments[self.selected_env_index]
+    }
+
+    pub fn next_env(&mut self) {
+        if self.environments.is_empty() {
+            return;
+        }
+        self.selected_env_index = (self.selected_env_index + 1) % self.environments.len();
+        self.save_config();
+    }
+
+    pub fn process_url(&self) -> String {
+        self.process_text(&self.resolved_url())
+    }
+
+    /// The active tab's URL with its path params filled in and a relative path put
+    /// after `base_url` (from whichever scope has it), variables still in
+    pub fn resolved_url(&self) -> String {
+        let tab = self.active_tab();
+        let url = crate::domain::path_params::fill(&tab.url, |name| {
+            tab.path_params
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        });
+        let variables = self.variables();
+        crate::domain::environment::resolve_url(
+            &url,
+            variables
+                .get(crate::domain::environment::BASE_URL)
+                .map(String::as_str),
+        )
+    }
+
+    /// What the URL bar shows next to a relative path: where it goes in this
+    /// environment, or that it has nowhere to go
+    pub fn relative_url_preview(&self) -> Option<Result<String, String>> {
+        if !crate::domain::environment::is_relative(&self.active_tab().url) {
+            return None;
+        }
+        Some(
+            match self.variables().get(crate::domain::environment::BASE_URL) {
+                Some(_) => Ok(self.substitute_env(&self.resolved_url())),
+                None => Err(format!(
+                    "no {} in environment '{}'",
+                    crate::domain::environment::BASE_URL,
+                    self.get_active_env().name
+                )),
+            },
+        )
+    }
+
+    /// The active environment's `resolve` table, checked, for every client that sends
+    pub fn host_overrides(&self) -> Result<crate::net::pool::HostOverrides, String> {
+        crate::net::pool::parse_overrides(&self.get_active_env().resolve)
+    }
+
+    /// How a send's client is built: TLS, proxy, pool and the environment's pinned
+    /// hosts, with `phase_timeouts` from the tab being sent
+    pub fn client_config(
+        &self,
+        phase_timeouts: crate::net::timeouts::PhaseTimeouts,
+    ) -> Result<crate::net::http::ClientConfig, String> {
+        Ok(crate::net::http::ClientConfig {
+            ssl_verify: self.ssl_verify,
+            ssl_ca_cert: self
+                .ssl_ca_cert_path
+                .as_ref()
+                .and_then(|p| std::fs::read(p).ok()),
+            proxy_url: self.proxy_url.clone(),
+            // Only when both are set
+            proxy_auth: match (&self.proxy_auth_user, &self.proxy_auth_pass) {
+                (Some(user), Some(pass)) => Some((user.clone(), pass.clone())),
+                _ => None,
+            },
+            pool: self.connection.clone(),
+            resolve: self.host_overrides()?,
+            phase_timeouts,
+        })
+    }
+
+    /// The active request's host, port and pinned address(es), when the environment's
+    /// `resolve` table has it
+    pub fn host_override(&self) -> Option<(String, u16, String)> {
+        let url = reqwest::Url::parse(&self.process_url()).ok()?;
+        let host = url.host_str()?;
+        let addrs = crate::net::pool::override_for(&self.get_active_env().resolve, host)?;
+        Some((
+            host.to_string(),
+            url.port_or_known_default().unwrap_or(80),
+            addrs.clone(),
+        ))
+    }
+
+    /// The scopes a `{{variable}}` of `collection` is looked up in, lowest first:
+    /// global, the active environment, the collection
+    pub fn collection_variable_layers(
+        &self,
+        collection: Option<&Collection>,
+    ) -> Vec<crate::domain::variables::Layer> {
+        use crate::domain::variables::{Layer, Scope};
+        let env = self.get_active_env();
+        let mut layers = vec![
+            Layer {
+                scope: Scope::Global,
+                source: "config.json".to_string(),
+                variables: self
+                    .global_variables
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            },
+            Layer {
+                scope: Scope::Environment,
+                source: env.name.clone(),
+                variables: env.variables.clone(),
+            },
+        ];
+        if let Some(collection) = collection {
+            layers.push(Layer {
+                scope: Scope::Collection,
+                source: collection.name.clone(),
+                variables: collection.variables.clone(),
+            });
+        }
+        layers
+    }
+
+    /// The active tab's scopes: its collection's, then the request's own variables
+    pub fn variable_layers(&self) -> Vec<crate::domain::variables::Layer> {
+        use crate::domain::variables::{Layer, Scope};
+        let tab = self.active_tab();
+        let collection = tab
+            .collection
+            .as_ref()
+            .and_then(|name| self.collections.iter().find(|c| &c.name == name));
+        let mut layers = self.collection_variable_layers(collection);
+        layers.push(Layer {
+            scope: Scope::Request,
+            source: tab.name.clone(),
+            variables: tab.variables.clone(),
+        });
+        layers
+    }
+
+    /// Every variable the active request sees, with the nearest scope's value
+    pub fn variables(&self) -> std::collections::HashMap<String, String> {
+        crate::domain::variables::merge(&self.variable_layers())
+    }
+
+    /// Sets a variable from a script or an extract rule where it's defined: the
+    /// request or its collection when they have it, otherwise the active
+    /// environment. Only in memory, like the environment's values.
+    pub fn set_variable(&mut self, name: String, value: String) {
+        let tab = self.active_tab();
+        if tab.variables.contains_key(&name) {
+            self.active_tab_mut().variables.insert(name, value);
+            return;
+        }
+        let collection = tab.collection.clone();
+        if let Some(collection) = self
+            .collections
+            .iter_mut()
+            .find(|c| Some(&c.name) == collection.as_ref())
+            && collection.variables.contains_key(&name)
+        {
+            collection.variables.insert(name, value);
+            return;
+        }
+        if let Some(env) = self.environments.get_mut(self.selected_env_index) {
+            env.variables.insert(name, value);
+        }
+    }
+
+    /// Opens the resolved-variables inspector for the active request
+    pub fn inspect_variables(&mut self) {
+        let tab = self.active_tab();
+        let mut texts: Vec<&str> = vec![
+            &tab.url,
+            &tab.request_body,
+            &tab.graphql_query,
+            &tab.graphql_variables,
+            &tab.auth_token,
+            &tab.basic_auth_user,
+            &tab.basic_auth_pass,
+        ];
+        texts.extend(tab.request_headers.values().map(String::as_str));
+        texts.extend(tab.path_params.iter().map(|(_, v)| v.as_str()));
+        texts.extend(tab.form_data.iter().map(|(_, v, _)| v.as_str()));
+        texts.extend(tab.variables.values().map(String::as_str));
+        let inspection = crate::domain::variables::inspect(&self.variable_layers(), texts);
+        self.variables_inspector = Some(inspection);
+        self.variables_inspector_scroll = 0;
+    }
+
+    /// `:vars [env]`: the active request's variables, or with `env` the active
+    /// environment's and which saved requests use them
+    pub fn vars_command(&mut self, args: &str) {
+        match args.trim() {
+            "" => self.inspect_variables(),
+            "env" => self.inspect_environment_usage(),
+            _ => self.show_error("Usage: :vars [env]".to_string()),
+        }
+    }
+
+    /// Opens the environment-variables view: each variable with the saved requests
+    /// that reference it, and the references no scope defines
+    pub fn inspect_environment_usage(&mut self) {
+        let env = self.get_active_env();
+        let report = crate::features::env_usage::report(
+            &env.name,
+            &env.variables,
+            &self.global_variables,
+            &self.collections,
+        );
+        self.env_usage = Some(crate::features::env_usage::UsageView {
+            report,
+            selected: 0,
+        });
+    }
+
+    /// Moves the environment-variables view's selection by `delta` rows
+    pub fn move_env_usage(&mut self, delta: isize) {
+        if let Some(view) = self.env_usage.as_mut() {
+            let rows = view.report.rows().len();
+            if rows > 0 {
+                view.selected = view.selected.saturating_add_signed(delta).min(rows - 1);
+            }
+        }
+    }
+
+    /// Opens the request the selected row points at and closes the view
+    pub fn open_env_usage_selection(&mut self) {
+        let Some(view) = self.env_usage.as_ref() else {
+            return;
+        };
+        let target = view
+            .report
+            .rows()
+            .get(view.selected)
+            .and_then(|row| view.report.target(*row))
+            .map(|r| r.path());
+        match target {
+            Some(path) => {
+                self.env_usage = None;
+                self.open_request(&path);
+            }
+            None => self.show_notification("No saved request uses this variable".to_string()),
+        }
+    }
+
+    /// Substitutes `{{var}}` placeholders from the request's scopes (see
+    /// `variable_layers`), leaving `{{$template}}` functions for later (per-request)
+    /// expansion
+    pub fn substitute_env(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        for (key, val) in &self.variables() {
+            let placeholder = format!("{{{{{}}}}}", key);
+            result = result.replace(&placeholder, val);
+        }
+        result
+    }
+
+    /// Substitutes environment variables and expands template functions like `{{$uuid}}`
+    pub fn process_text(&self, text: &str) -> String {
+        crate::features::template::expand(&self.substitute_env(text))
+    }
+
+    /// What sending the active tab puts together. The pre-request script's result comes
+    /// back too, for the variables it set and its output; nothing is written here.
+    pub fn outgoing_request(
+        &self,
+    ) -> (
+        OutgoingRequest,
+        Option<crate::features::scripting::ScriptResult>,
+    ) {
+        let mut request = self.unscripted_request();
+        let tab = self.active_tab();
+        if tab.pre_request_script.trim().is_empty() {
+            return (request, None);
+        }
+
+        let result = crate::features::scripting::run_script(
+            &tab.pre_request_script,
+            &request.method,
+            &request.url,
+            &request.headers,
+            request.body.as_deref().unwrap_or(""),
+            &self.variables(),
+            &self.script_history(),
+        );
+        request.headers = result.headers.clone();
+        if let Some(body) = &result.body_override {
+            request.body = Some(body.clone());
+        }
+        if let Some(url) = &result.url_override {
+            request.url = url.clone();
+        }
+        (request, Some(result))
+    }
+
+    /// The active tab's request with variables, auth and cookies in, before the
+    /// pre-request script has had a go at it
+    pub fn unscripted_request(&self) -> OutgoingRequest {
+        use crate::net::http::AuthPayload;
+
+        let processed_url = self.process_url();
+        let tab = self.active_tab();
+
+        let body = if tab.body_type == BodyType::Raw && !tab.request_body.trim().is_empty() {
+            Some(self.process_text(&tab.request_body))
+        } else if tab.body_type == BodyType::GraphQL {
+            let vars: serde_json::Value = if tab.graphql_variables.trim().is_empty() {
+                serde_json::json!({})
+            } else {
+                serde_json::from_str(&tab.graphql_variables).unwrap_or(serde_json::json!({}))
+            };
+            let payload = serde_json::json!({
+                "query": tab.graphql_query,
+                "variables": vars
+            });
+            Some(payload.to_string())
+        } else if tab.body_type == BodyType::UrlEncoded && !tab.urlencoded.is_empty() {
+            let pairs: Vec<(String, String)> = tab
+                .urlencoded
+                .iter()
+                .map(|(k, v)| (k.clone(), self.process_text(v)))
+                .collect();
+            Some(encode_urlencoded(&pairs))
+        } else {
+            None
+        };
+
+        let form_data = if tab.body_type == BodyType::FormData && !tab.form_data.is_empty() {
+            Some(tab.form_data.clone())
+        } else {
+            None
+        };
+
+        let auth = match tab.auth_type {
+            AuthType::Bearer | AuthType::OAuth2 if !tab.auth_token.is_empty() => {
+                Some(AuthPayload::Bearer(tab.auth_token.clone()))
+            }
+            AuthType::Basic
+                if !tab.basic_auth_user.is_empty() || !tab.basic_auth_pass.is_empty() =>
+            {
+                Some(AuthPayload::Basic(
+                    tab.basic_auth_user.clone(),
+                    tab.basic_auth_pass.clone(),
+                ))
+            }
+            _ => None,
+        };
+
+        let mut headers: std::collections::HashMap<String, String> = self
+            .sent_headers()
+            .iter()
+            .map(|(k, v)| (k.clone(), self.process_text(v)))
+            .collect();
+        if tab.body_type == BodyType::UrlEncoded
+            && !headers
+                .keys()
+                .any(|k| k.eq_ignore_ascii_case("content-type"))
+        {
+            headers.insert(
+                "Content-Type".to_string(),
+                "application/x-www-form-urlencoded".to_string(),
+            );
+        }
+        if let Some(cookie_header) = self.get_cookie_header(&processed_url) {
+            headers.insert("Cookie".to_string(), cookie_header);
+        }
+
+        OutgoingRequest {
+            method: tab.method.clone(),
+            url: processed_url,
+            headers,
+            body,
+            form_data,
+            auth,
+        }
+    }
+
+    /// Whether the active tab may be sent without a word. Warnings the user already
+    /// sent past on this tab don't come up again; new ones open the prompt, which
+    /// sends once they're dismissed.
+    pub fn check_send_warnings(&mut self) -> bool {
+        let dismissed = &self.active_tab().dismissed_warnings;
+        let warnings: Vec<crate::features::presend::Warning> =
+            crate::features::presend::check(&self.unscripted_request(), &self.send_checks)
+                .into_iter()
+                .filter(|w| !dismissed.contains(&w.message))
+                .collect();
+        if warnings.is_empty() {
+            return true;
+        }
+        self.send_warnings = Some(warnings);
+        false
+    }
+
+    /// Answers the pre-send warnings: send anyway, or go back and fix the request
+    pub fn resolve_send_warnings(&mut self, send: bool) {
+        let Some(warnings) = self.send_warnings.take() else {
+            return;
+        };
+        if !send {
+            self.show_notification("Not sent".to_string());
+            return;
+        }
+        let tab = self.active_tab_mut();
+        tab.dismissed_warnings
+            .extend(warnings.into_iter().map(|w| w.message));
+        tab.resend_requested = true;
+    }
+
+    /// The request the way reqwest would build it, so the wire view shows what the
+    /// network task will send
+    pub fn build_outgoing(
+        client: &reqwest::Client,
+        request: &OutgoingRequest,
+    ) -> Result<reqwest::Request, String> {
+        let mut builder = crate::net::http::request_builder(
+            client,
+            &request.method,
+            &request.url,
+            &request.headers,
+            request.auth.as_ref(),
+        );
+        if let Some(fields) = &request.form_data {
+            let files = fields
+                .iter()
+                .filter(|(_, _, is_file)| *is_file)
+                .filter_map(|(_, path, _)| Some((path.clone(), std::fs::read(path).ok()?)))
+                .collect();
+            builder = builder.multipart(crate::net::http::multipart_form(fields, &files));
+        } else if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+        builder.build().map_err(|e| e.to_string())
+    }
+
+    /// `W`: the next send as raw HTTP, without sending it
+    pub fn preview_request(&mut self) {
+        if self.active_tab().body_type == BodyType::Grpc {
+            self.show_notification("gRPC requests go through grpcurl, no wire preview".to_string());
+            return;
+        }
+        let (request, script) = self.outgoing_request();
+        let built = match Self::build_outgoing(&reqwest::Client::new(), &request) {
+            Ok(built) => built,
+            Err(e) => {
+                self.show_error(format!("Can't build the request: {}", e));
+                return;
+            }
+        };
+        let note = script.map(|_| {
+            "Pre-request script applied: anything it generates (timestamps, nonces) \
+             will differ on the real send"
+                .to_string()
+        });
+        self.wire_view = Some(WireView {
+            sent: false,
+            url: format!("{} {}", built.method(), built.url()),
+            text: String::from_utf8_lossy(&crate::net::http::wire_bytes(&built)).into_owned(),
+            note,
+            scroll: 0,
+        });
+    }
+
+    /// The wire view of the last send from this tab
+    pub fn show_sent_request(&mut self) {
+        let Some(bytes) = self.active_tab().request_bytes.as_ref() else {
+            self.show_notification("Nothing sent from this tab yet".to_string());
+            return;
+        };
+        self.wire_view = Some(WireView {
+            sent: true,
+            url: String::new(),
+            text: String::from_utf8_lossy(bytes).into_owned(),
+            note: None,
+            scroll: 0,
+        });
+    }
+
+    /// Tab in the wire view: between the preview and the last send
+    pub fn toggle_wire_view(&mut self) {
+        match self.wire_view.as_ref().map(|view| view.sent) {
+            Some(true) => self.preview_request(),
+            Some(false) => self.show_sent_request(),
+            None => {}
+        }
+    }
+
+    /// `:fuzz [rate]` starts a run against the active request, `:fuzz stop` ends it
+    /// early and `:fuzz report` brings the last report back
+    pub fn fuzz_command(&mut self, args: &str) {
+        match args.trim() {
+            "stop" => match self.fuzz_stop.take() {
+                Some(stop) => {
+                    let _ = stop.try_send(());
+                    self.show_notification("Stopping the fuzzer...".to_string());
+                }
+                None => self.show_notification("The fuzzer isn't running".to_string()),
+            },
+            "report" => {
+                if self.fuzz_report.is_some() {
+                    self.show_fuzz_report = true;
+                } else {
+                    self.show_notification("No fuzz report yet, run :fuzz first".to_string());
+                }
+            }
+            rate => {
+                if self.fuzz_progress.is_some() {
+                    self.show_notification("Already fuzzing, :fuzz stop to end it".to_string());
+                    return;
+                }
+                if !rate.is_empty() {
+                    match rate.parse::<u32>() {
+                        Ok(rate) if rate > 0 => self.fuzz_rate = rate,
+                        _ => {
+                            self.show_error(
+                                "Usage: :fuzz [requests per second|stop|report]".to_string(),
+                            );
+                            return;
+                        }
+                    }
+                }
+                if self.active_tab().body_type == BodyType::Grpc {
+                    self.show_notification("gRPC requests can't be fuzzed".to_string());
+                    return;
+                }
+                self.should_run_fuzz = self.check_guard(GuardedAction::Fuzz);
+            }
+        }
+    }
+
+    /// The variants of the active request, for the main loop to run
+    pub fn fuzz_config(&self) -> Result<crate::features::fuzz::FuzzConfig, String> {
+        let (base, _) = self.outgoing_request();
+        if base.form_data.is_some() {
+            return Err("multipart bodies aren't fuzzed, only JSON ones".to_string());
+        }
+        let cases = crate::features::fuzz::cases(&base);
+        if cases.is_empty() {
+            return Err("nothing to mutate: no query parameters, headers or JSON body".to_string());
+        }
+        Ok(crate::features::fuzz::FuzzConfig {
+            base,
+            cases,
+            rate: self.fuzz_rate,
+            ssl_verify: self.ssl_verify,
+            resolve: self.host_overrides()?,
+        })
+    }
+
+    /// Enter in the fuzz report: the first variant of the selected group in a new tab,
+    /// to send again and look into
+    pub fn open_fuzz_case(&mut self) {
+        use crate::features::fuzz::Target;
+        let Some(case) = self.fuzz_report.as_ref().and_then(|report| {
+            let group = report.groups.get(self.fuzz_state.selected()?)?;
+            report.cases.get(*group.cases.first()?).cloned()
+        }) else {
+            return;
+        };
+        let mut tab = self.active_tab().clone();
+        tab.clear_response();
+        tab.name = format!("Fuzz {}", self.next_request_id);
+        self.next_request_id += 1;
+        match &case.target {
+            Target::Param(_) => tab.url = case.request.url.clone(),
+            Target::Header(name) => {
+                tab.request_headers
+                    .insert(name.clone(), case.payload.clone());
+            }
+            Target::Body(_) => {
+                tab.body_type = BodyType::Raw;
+                tab.request_body = case.request.body.clone().unwrap_or_default();
+            }
+        }
+        self.tabs.push(tab);
+        self.active_tab = self.tabs.len() - 1;
+        self.sync_url_to_params();
+        self.show_fuzz_report = false;
+        self.show_success(format!("Opened {}", case.label()));
+    }
+
+    /// `:send all` or `:send 1,3-4` sends those tabs at once, numbered as in the tab bar
+    pub fn send_tabs_command(&mut self, spec: &str) {
+        if self.batch_rows.iter().any(|row| row.result.is_none()) {
+            self.show_notification("Still waiting on the last batch".to_string());
+            return;
+        }
+        match crate::features::batch::parse_tabs(spec, self.tabs.len()) {
+            Ok(tabs) if tabs.is_empty() => {
+                self.show_notification("No tabs picked, try :send all".to_string())
+            }
+            Ok(tabs) => {
+                self.batch_tabs = tabs;
+                self.should_run_batch = self.check_guard(GuardedAction::SendTabs);
+            }
+            Err(e) => self.show_error(format!("Usage: :send [all | 1,3-4] ({})", e)),
+        }
+    }
+
+    /// The picked tabs' requests, built as a send from each tab would build them, and
+    /// a fresh summary row for each. WebSocket and gRPC tabs get a row saying so.
+    pub fn batch_requests(&mut self) -> Vec<crate::features::batch::BatchRequest> {
+        use crate::features::batch::{BatchRequest, BatchResult, BatchRow};
+        let current = self.active_tab;
+        let mut rows = Vec::new();
+        let mut requests = Vec::new();
+        for tab in std::mem::take(&mut self.batch_tabs) {
+            if tab >= self.tabs.len() {
+                continue;
+            }
+            self.active_tab = tab;
+            let t = self.active_tab();
+            let mut row = BatchRow {
+                tab,
+                name: t.name.clone(),
+                method: t.method.clone(),
+                url: self.process_url(),
+                result: None,
+            };
+            if t.app_mode == AppMode::WebSocket {
+                row.result = Some(BatchResult::failed("WebSocket tabs aren't sent"));
+            } else if t.body_type == BodyType::Grpc {
+                row.result = Some(BatchResult::failed("gRPC tabs aren't sent"));
+            } else {
+                let timeout_ms = t.timeout_ms;
+                match self.client_config(t.phase_timeouts()) {
+                    Ok(client) => {
+                        let (request, _) = self.outgoing_request();
+                        requests.push(BatchRequest {
+                            row: rows.len(),
+                            request,
+                            timeout_ms,
+                            client,
+                        });
+                    }
+                    Err(e) => row.result = Some(BatchResult::failed(e)),
+                }
+            }
+            rows.push(row);
+        }
+        self.active_tab = current;
+        self.batch_rows = rows;
+        self.batch_state.select(Some(0));
+        self.show_batch = true;
+        requests
+    }
+
+    /// A batch request came back; once the last one has, the summary goes in a toast
+    pub fn batch_done(&mut self, row: usize, result: crate::features::batch::BatchResult) {
+        let Some(slot) = self.batch_rows.get_mut(row) else {
+            return;
+        };
+        slot.result = Some(result);
+        if self.batch_rows.iter().all(|row| row.result.is_some()) {
+            let summary = crate::features::batch::summary(&self.batch_rows);
+            if self
+                .batch_rows
+                .iter()
+                .all(|row| row.result.as_ref().is_some_and(|r| r.is_ok()))
+            {
+                self.show_success(summary);
+            } else {
+                self.show_error(summary);
+            }
+        }
+    }
+
+    /// `:timeout` shows the tab's timeouts, `:timeout [total|connect|read] <ms|5s|off>`
+    /// sets one
+    pub fn timeout_command(&mut self, args: &str) {
+        use crate::net::timeouts::{Phase, parse_ms};
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let (phase, value) = match parts.as_slice() {
+            [] => {
+                let msg = self.describe_timeouts();
+                self.show_notification(msg);
+                return;
+            }
+            [value] => (Phase::Total, *value),
+            [phase, value] => match Phase::parse(phase) {
+                Some(phase) => (phase, *value),
+                None => {
+                    self.show_error(format!(
+                        "Unknown timeout '{}' (total, connect, read)",
+                        phase
+                    ));
+                    return;
+                }
+            },
+            _ => {
+                self.show_error("Usage: :timeout [total|connect|read] <ms|5s|off>".to_string());
+                return;
+            }
+        };
+        match parse_ms(value) {
+            Ok(ms) => self.set_timeout(phase, ms),
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Sets one of the tab's timeouts; None puts it back to its default
+    pub fn set_timeout(&mut self, phase: crate::net::timeouts::Phase, ms: Option<u64>) {
+        use crate::net::timeouts::{DEFAULT_TOTAL_MS, Phase};
+        let tab = self.active_tab_mut();
+        match phase {
+            Phase::Total => tab.timeout_ms = ms.unwrap_or(DEFAULT_TOTAL_MS),
+            Phase::Connect => tab.connect_timeout_ms = ms,
+            Phase::Read => tab.read_timeout_ms = ms,
+        }
+        let msg = self.describe_timeouts();
+        self.show_success(msg);
+    }
+
+    /// `Timeouts: total 10s, connect 2s, read within total`
+    fn describe_timeouts(&self) -> String {
+        use crate::net::timeouts::{Phase, format_ms};
+        let parts: Vec<String> = Phase::ALL
+            .iter()
+            .map(|phase| {
+                let value = match self.active_tab().timeout(*phase) {
+                    Some(ms) => format_ms(ms),
+                    None => phase.unset().to_string(),
+                };
+                format!("{} {}", phase.label().to_lowercase(), value)
+            })
+            .collect();
+        format!("Timeouts: {}", parts.join(", "))
+    }
+
+    /// Starts editing the timeout picked in the Settings tab
+    pub fn edit_timeout(&mut self) {
+        let row = self
+            .active_tab()
+            .settings_list_state
+            .selected()
+            .unwrap_or(0);
+        let Some(phase) = crate::net::timeouts::Phase::ALL.get(row).copied() else {
+            return;
+        };
+        self.timeout_input = self
+            .active_tab()
+            .timeout(phase)
+            .map(|ms| ms.to_string())
+            .unwrap_or_default();
+        self.input_cursor = self.timeout_input.len();
+        self.active_tab_mut().input_mode = InputMode::EditingTimeout;
+    }
+
+    /// Enter on the timeout being edited: empty puts it back to its default
+    pub fn commit_timeout_input(&mut self) {
+        let row = self
+            .active_tab()
+            .settings_list_state
+            .selected()
+            .unwrap_or(0);
+        let phase = crate::net::timeouts::Phase::ALL[row.min(2)];
+        match crate::net::timeouts::parse_ms(&self.timeout_input) {
+            Ok(ms) => {
+                self.active_tab_mut().input_mode = InputMode::Normal;
+                self.set_timeout(phase, ms);
+            }
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// `:paginate` fetches every page, `:paginate next|cursor|page|items|more|max ...`
+    /// sets how, `:paginate show` says how, `:paginate off` forgets it
+    pub fn paginate_command(&mut self, args: &str) {
+        let args = args.trim();
+        match args {
+            "" | "run" | "all" => {
+                if self.active_tab().is_loading {
+                    self.show_notification("Still waiting on the last response".to_string());
+                } else if self.active_tab().paginate.is_none() {
+                    self.show_error(
+                        "Set how to find the next page first: :paginate next|cursor|page ..."
+                            .to_string(),
+                    );
+                } else {
+                    self.should_paginate = self.check_guard(GuardedAction::Paginate);
+                }
+            }
+            "show" => match &self.active_tab().paginate {
+                Some(config) => self.show_notification(format!("Pages: {}", config.describe())),
+                None => self.show_notification("No pagination set".to_string()),
+            },
+            "off" => {
+                self.active_tab_mut().paginate = None;
+                self.show_notification("Pagination off".to_string());
+            }
+            _ => {
+                let mut config = self.active_tab().paginate.clone().unwrap_or_default();
+                match crate::features::paginate::apply_command(&mut config, args) {
+                    Ok(summary) => {
+                        self.active_tab_mut().paginate = Some(config);
+                        self.show_success(format!("Pages: {}", summary));
+                    }
+                    Err(e) => self.show_error(e),
+                }
+            }
+        }
+    }
+
+    /// The active tab's request and pagination for `run_pagination`, built as a send
+    /// would build it. None when it can't be paged.
+    pub fn pagination_request(&mut self) -> Option<crate::features::paginate::PaginationJob> {
+        let tab = self.active_tab();
+        let config = tab.paginate.clone()?;
+        if tab.app_mode == AppMode::WebSocket || tab.body_type == BodyType::Grpc {
+            self.show_error("Only HTTP requests can be paged".to_string());
+            return None;
+        }
+        let timeout_ms = tab.timeout_ms;
+        let client = match self.client_config(tab.phase_timeouts()) {
+            Ok(client) => client,
+            Err(e) => {
+                self.show_error(e);
+                return None;
+            }
+        };
+        let (request, _) = self.outgoing_request();
+        // Routed back like a send, so closing tabs meanwhile can't misdeliver it
+        let request_id = crate::features::logging::new_request_id();
+        let tab = self.active_tab_mut();
+        tab.request_id = Some(request_id.clone());
+        tab.is_loading = true;
+        Some(crate::features::paginate::PaginationJob {
+            request_id,
+            request,
+            timeout_ms,
+            config,
+            rate_limit: self.rate_limit.clone(),
+            client,
+        })
+    }
+
+    /// Every page is in (or one failed): their items become the tab's response, one
+    /// JSON array
+    pub fn pagination_done(&mut self, result: crate::features::paginate::PaginationResult) {
+        let Some(tab) = self
+            .tab_for_request(&result.request_id)
+            .and_then(|i| self.tabs.get_mut(i))
+        else {
+            return;
+        };
+        tab.is_loading = false;
+        let summary = format!(
+            "{} page{}, {} item{}",
+            result.pages,
+            if result.pages == 1 { "" } else { "s" },
+            result.items.len(),
+            if result.items.len() == 1 { "" } else { "s" },
+        );
+        if result.pages > 0 {
+            let body = serde_json::to_string_pretty(&result.items).unwrap_or_default();
+            let mut headers = result.headers.clone();
+            headers.retain(|k, _| {
+                !k.eq_ignore_ascii_case("content-length")
+                    && !k.eq_ignore_ascii_case("content-type")
+                    && !k.eq_ignore_ascii_case("content-encoding")
+            });
+            headers.insert("content-type".to_string(), "application/json".to_string());
+            headers.insert("x-postdad-pages".to_string(), result.pages.to_string());
+            headers.insert(
+                "x-postdad-items".to_string(),
+                result.items.len().to_string(),
+            );
+            tab.status_code = result.status;
+            tab.latency = Some(result.latency_ms as u128);
+            tab.response_bytes = Some(body.clone().into_bytes());
+            tab.response = Some(body);
+            tab.response_headers = headers;
+            tab.response_is_binary = false;
+            tab.response_encoding = None;
+            tab.response_cookies.clear();
+            tab.trace = None;
+            tab.reinterpret_response();
+        }
+        match result.error {
+            Some(error) if result.pages > 1 => self.show_error(format!(
+                "Paging stopped after {}, showing those: {}",
+                summary, error
+            )),
+            Some(error) => self.show_error(format!("Paging failed: {}", error)),
+            None => self.show_success(format!("Fetched {} ({})", summary, result.stopped)),
+        }
+    }
+
+    /// Enter in the batch summary: over to that row's tab
+    /// The NDJSON lines that pass the filter, as indices into `response_ndjson`
+    pub fn ndjson_rows(&self) -> Vec<usize> {
+        match &self.active_tab().response_ndjson {
+            Some(lines) => crate::features::ndjson::filter(lines, &self.ndjson_filter),
+            None => Vec::new(),
+        }
+    }
+
+    /// `L`: the response's NDJSON lines, one per record
+    pub fn open_ndjson(&mut self) {
+        let Some(lines) = &self.active_tab().response_ndjson else {
+            self.show_error("Response is not NDJSON / JSON Lines".to_string());
+            return;
+        };
+        // Back on the record that was open, if any
+        let open = self.active_tab().ndjson_record;
+        let row = self
+            .ndjson_rows()
+            .iter()
+            .position(|&i| Some(lines[i].number) == open)
+            .unwrap_or(0);
+        self.ndjson_state.select(Some(row));
+        self.ndjson_filtering = false;
+        self.show_ndjson = true;
+    }
+
+    /// Enter on a line: that record in the tree explorer
+    pub fn open_ndjson_record(&mut self) {
+        let rows = self.ndjson_rows();
+        let Some(&index) = self.ndjson_state.selected().and_then(|i| rows.get(i)) else {
+            return;
+        };
+        let Some(line) = self
+            .active_tab()
+            .response_ndjson
+            .as_ref()
+            .map(|lines| lines[index].clone())
+        else {
+            return;
+        };
+        match &line.value {
+            Ok(value) => {
+                let tab = self.active_tab_mut();
+                tab.set_response_json(Some(vec![JsonEntry::from_value(
+                    format!("line {}", line.number),
+                    value,
+                    0,
+                )]));
+                tab.ndjson_record = Some(line.number);
+                tab.response_raw = false;
+                tab.jsonpath_query = None;
+                tab.json_list_state.select(Some(0));
+                self.show_ndjson = false;
+            }
+            Err(e) => self.show_error(format!("Line {} isn't JSON: {}", line.number, e)),
+        }
+    }
+
+    pub fn open_batch_tab(&mut self) {
+        let Some(row) = self
+            .batch_state
+            .selected()
+            .and_then(|i| self.batch_rows.get(i))
+        else {
+            return;
+        };
+        if row.tab < self.tabs.len() {
+            self.active_tab = row.tab;
+            self.show_batch = false;
+        }
+    }
+
+    /// The Params tab's selected row, counting the path params listed first
+    pub fn params_row(&self) -> Option<usize> {
+        let tab = self.active_tab();
+        tab.path_params_list_state.selected().or(tab
+            .params_list_state
+            .selected()
+            .map(|i| tab.path_params.len() + i))
+    }
+
+    pub fn select_params_row(&mut self, row: usize) {
+        let tab = self.active_tab_mut();
+        let paths = tab.path_params.len();
+        if row < paths {
+            tab.path_params_list_state.select(Some(row));
+            tab.params_list_state.select(None);
+        } else {
+            tab.path_params_list_state.select(None);
+            tab.params_list_state.select(Some(row - paths));
+        }
+    }
+
+    pub fn sync_url_to_params(&mut self) {
+        let tab = self.active_tab_mut();
+        // Path params follow the template, keeping the values of names still in it
+        let old = std::mem::take(&mut tab.path_params);
+        tab.path_params = crate::domain::path_params::names(&tab.url)
+            .into_iter()
+            .map(|name| {
+                let value = old
+                    .iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.clone())
+                    .unwrap_or_default();
+                (name, value)
+            })
+            .collect();
+        let len = tab.path_params.len();
+        if tab
+            .path_params_list_state
+            .selected()
+            .is_some_and(|i| i >= len)
+        {
+            tab.path_params_list_state.select(len.checked_sub(1));
+        }
+        if crate::domain::environment::is_relative(&tab.url) {
+            // Only the query is parsed, so `{{id}}` in the path stays as written
+            let query = tab.url.split_once('?').map_or("", |(_, query)| query);
+            tab.params = reqwest::Url::parse(&format!("http://base/?{}", query))
+                .map(|u| u.query_pairs().into_owned().collect())
+                .unwrap_or_default();
+        } else if let Ok(u) = reqwest::Url::parse(&tab.url) {
+            tab.params = u.query_pairs().into_owned().collect();
+        } else {
+            tab.params.clear();
+        }
+    }
+
+    pub fn sync_params_to_url(&mut self) {
+        let tab = self.active_tab_mut();
+        // Parsing would percent-encode `{id}`, so a templated path is kept as written
+        if crate::domain::environment::is_relative(&tab.url) || !tab.path_params.is_empty() {
+            let path = tab.url.split('?').next().unwrap_or_default().to_string();
+            let mut u = reqwest::Url::parse("http://base/").expect("static URL");
+            u.query_pairs_mut().extend_pairs(&tab.params);
+            tab.url = match u.query() {
+                Some(query) if !tab.params.is_empty() => format!("{}?{}", path, query),
+                _ => path,
+            };
+        } else if let Ok(mut u) = reqwest::Url::parse(&tab.url) {
+            u.query_pairs_mut().clear().extend_pairs(&tab.params);
+            tab.url = u.to_string();
+        }
+    }
+
+    pub fn add_history(
+        &mut self,
+        method: String,
+        url: String,
+        duration: u128,
+        status: u16,
+        body: Option<String>,
+        headers: std::collections::HashMap<String, String>,
+        response_bytes: Option<Vec<u8>>,
+        is_binary: bool,
+    ) {
+        let size = response_bytes
+            .as_ref()
+            .map(Vec::len)
+            .or(body.as_ref().map(String::len))
+            .unwrap_or(0);
+        let log = RequestLog {
+            method,
+            url,
+            status,
+            latency: duration,
+            body,
+            headers,
+            response_bytes,
+            is_binary,
+            timestamp: crate::features::history::now(),
+            pinned: false,
+            request: Some(self.active_tab().to_request_config()),
+            size,
+            tests: self.active_tab().test_results.clone(),
+        };
+        let viewed = self.selected_history_view_entry();
+        let picked = if self.show_finder {
+            self.finder_matches()
+                .into_iter()
+                .nth(self.finder_index)
+                .map(|m| m.item.target)
+        } else {
+            None
+        };
+        self.request_history.insert(0, log);
+        let moved =
+            crate::features::history::trim(&mut self.request_history, self.history_settings.limit);
+        self.save_history();
+
+        // Marks, the diff base and the highlights hold positions: keep them on their entries
+        let follow = |i: usize| moved.get(i + 1).copied().flatten();
+        self.history_marked = std::mem::take(&mut self.history_marked)
+            .into_iter()
+            .filter_map(follow)
+            .collect();
+        self.diff_base_index = self.diff_base_index.and_then(follow);
+        self.diff_target_index = self.diff_target_index.and_then(follow);
+        if let Some(i) = viewed.and_then(follow)
+            && let Some(pos) = self.history_view_entries().iter().position(|&e| e == i)
+        {
+            self.history_index = pos;
+        }
+        if let Some(target) = picked {
+            use crate::features::finder::Target;
+            let target = match target {
+                Target::History(i) => follow(i).map(Target::History),
+                other => Some(other),
+            };
+            if let Some(pos) = target.and_then(|target| {
+                self.finder_matches()
+                    .iter()
+                    .position(|m| m.item.target == target)
+            }) {
+                self.finder_index = pos;
+            }
+        }
+    }
+
+    /// History as scripts see it through `last_response()` and `history()`: recent,
+    /// text responses only, within the guard in features::scripting
+    pub fn script_history(&self) -> Vec<crate::features::scripting::HistoryEntry> {
+        let entries = self
+            .request_history
+            .iter()
+            .filter(|log| !log.is_binary)
+            .map(|log| crate::features::scripting::HistoryEntry {
+                method: log.method.clone(),
+                url: log.url.clone(),
+                status: log.status,
+                body: log.body.clone().unwrap_or_default(),
+                headers: log.headers.clone(),
+                timestamp: log.timestamp,
+            });
+        crate::features::scripting::guard_history(entries, crate::features::history::now())
+    }
+
+    /// A history/cookie store from the workspace, decrypted when encryption is on.
+    /// None if it's missing, or encrypted and still locked.
+    fn read_store(&mut self, name: &str) -> Option<String> {
+        let content = std::fs::read_to_string(self.workspace.path(name)).ok()?;
+        if !crate::features::vault::is_encrypted(&content) {
+            return Some(content);
+        }
+        let vault = self.vault.as_ref()?;
+        match vault.decrypt_file(&content) {
+            Ok(plain) => Some(plain),
+            Err(e) => {
+                self.show_error(format!("{} not loaded: {}", name, e));
+                None
+            }
+        }
+    }
+
+    /// Writes a store, encrypted when encryption is on. Skipped while locked so the
+    /// encrypted file isn't replaced by what little was recorded since.
+    fn write_store(&self, name: &str, json: String) {
+        if let Some(content) = self.seal_store(json) {
+            let _ = std::fs::write(self.workspace.path(name), content);
+        }
+    }
+
+    /// A store's content as it goes to disk, None while locked
+    fn seal_store(&self, json: String) -> Option<String> {
+        if !self.encryption.enabled {
+            Some(json)
+        } else {
+            self.vault.as_ref()?.encrypt_file(&json).ok()
+        }
+    }
+
+    fn load_history(&mut self) -> Vec<RequestLog> {
+        if let Some(content) = self.read_store("history.json")
+            && let Ok(history) = serde_json::from_str(&content)
+        {
+            return history;
+        }
+        Vec::new()
+    }
+
+    fn save_history(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(&self.request_history) {
+            self.write_store("history.json", json);
+        }
+    }
+
+    pub fn toggle_diff_selection(&mut self, history_index: usize) {
+        if let Some(base) = self.diff_base_index {
+            if base == history_index {
+                // Deselect if same
+                self.diff_base_index = None;
+                self.diff_target_index = None;
+                self.show_diff_view = false;
+            } else {
+                // Set target and show diff
+                self.diff_target_index = Some(history_index);
+                self.show_diff_view = true;
+                self.diff_list_state.select(Some(0));
+            }
+        } else {
+            self.diff_base_index = Some(history_index);
+        }
+    }
+
+    fn load_config(&self) -> AppConfig {
+        if let Ok(content) = std::fs::read_to_string(self.workspace.path("config.json"))
+            && let Ok(config) = serde_json::from_str(&content)
+        {
+            return config;
+        }
+        AppConfig::default()
+    }
+
+    pub fn save_config(&self) {
+        let config = AppConfig {
+            theme_index: self.theme_index,
+            theme: Some(self.theme.id.clone()),
+            selected_env_index: self.selected_env_index,
+            zen_mode: self.zen_mode,
+            sidebar_width: (self.sidebar_width != crate::ui::layout::SIDEBAR_PERCENT)
+                .then_some(self.sidebar_width),
+            notifications: self.notifications.timeouts.clone(),
+            runner_hooks: self.runner_hooks.clone(),
+            connection: self.connection.clone(),
+            rate_limit: self.rate_limit.clone(),
+            active_profile: self.current_profile().map(|p| p.name.clone()),
+            doctor: self.doctor.clone(),
+            encryption: self.encryption.clone(),
+            history: self.history_settings.clone(),
+            gist: self.gist.clone(),
+            storage: self.storage.clone(),
+            default_headers: self.default_headers.clone(),
+            docs: self.docs_settings.clone(),
+            variables: self.global_variables.clone(),
+            send_checks: self.send_checks.clone(),
+            protos: self.proto_registry.clone(),
+            dashboard: self.dashboard_settings.clone(),
+            trace: self.trace_settings.clone(),
+        };
+        if let Ok(json) = serde_json::to_string_pretty(&config) {
+            let _ = std::fs::write(self.workspace.path("config.json"), json);
+        }
+    }
+
+    fn load_cookies(&mut self) -> std::collections::HashMap<String, Vec<String>> {
+        if let Some(content) = self.read_store("cookies.json")
+            && let Ok(cookies) = serde_json::from_str(&content)
+        {
+            return cookies;
+        }
+        std::collections::HashMap::new()
+    }
+
+    fn save_cookies(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(&self.cookie_jar) {
+            self.write_store("cookies.json", json);
+        }
+    }
+
+    fn load_command_history(&mut self) -> Vec<String> {
+        if let Some(content) = self.read_store(crate::features::command::HISTORY_FILE)
+            && let Ok(history) = serde_json::from_str(&content)
+        {
+            return history;
+        }
+        Vec::new()
+    }
+
+    pub fn record_command(&mut self, command: &str) {
+        crate::features::command::push_history(&mut self.command_history, command);
+        self.command_history_index = None;
+        self.save_command_history();
+    }
+
+    fn save_command_history(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(&self.command_history) {
+            self.write_store(crate::features::command::HISTORY_FILE, json);
+        }
+    }
+
+    /// Up/Down in the command line: steps through past commands, past the newest
+    /// back to an empty line
+    pub fn browse_command_history(&mut self, older: bool) {
+        let len = self.command_history.len();
+        if len == 0 {
+            return;
+        }
+        self.command_history_index = match (self.command_history_index, older) {
+            (None, true) => Some(len - 1),
+            (None, false) => None,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i + 1 < len => Some(i + 1),
+            (Some(_), false) => None,
+        };
+        self.command_input = self
+            .command_history_index
+            .map(|i| self.command_history[i].clone())
+            .unwrap_or_default();
+        self.input_cursor = crate::domain::line_edit::CURSOR_END;
+        self.command_completions.clear();
+    }
+
+    /// Tab in the command line: fills in the only match or the shared prefix, and
+    /// cycles through the matches on the next presses
+    pub fn complete_command(&mut self) {
+        use crate::features::command;
+        let cycling = self
+            .command_completions
+            .get(self.command_completion_index)
+            .is_some_and(|shown| *shown == self.command_input);
+        if cycling {
+            self.command_completion_index =
+                (self.command_completion_index + 1) % self.command_completions.len();
+        } else {
+            self.command_completions = command::complete(&self.command_input, &self.completions());
+            self.command_completion_index = 0;
+            let prefix = command::common_prefix(&self.command_completions);
+            // Several matches: first fill in what they share, Tab again cycles
+            if self.command_completions.len() > 1 && prefix.len() > self.command_input.len() {
+                self.command_input = prefix;
+                self.input_cursor = crate::domain::line_edit::CURSOR_END;
+                return;
+            }
+        }
+        let Some(line) = self.command_completions.get(self.command_completion_index) else {
+            return;
+        };
+        self.command_input = line.clone();
+        // A finished word gets its trailing space so the next Tab completes the argument
+        if self.command_completions.len() == 1 && !line.ends_with(' ') {
+            self.command_input.push(' ');
+            self.command_completions.clear();
+        }
+        self.input_cursor = crate::domain::line_edit::CURSOR_END;
+    }
+
+    fn completions(&self) -> crate::features::command::Completions {
+        let mut requests: Vec<String> = self
+            .collections
+            .iter()
+            .flat_map(|c| c.requests.keys().map(move |r| format!("{}/{}", c.name, r)))
+            .collect();
+        requests.sort();
+        crate::features::command::Completions {
+            environments: self
+                .environments
+                .iter()
+                .filter(|e| e.name != "None")
+                .map(|e| e.name.clone())
+                .collect(),
+            requests,
+            collections: self.collections.iter().map(|c| c.name.clone()).collect(),
+            headers: self.active_tab().request_headers.keys().cloned().collect(),
+            default_headers: self.default_headers().into_iter().map(|d| d.name).collect(),
+            themes: self.themes.iter().map(|t| t.id.clone()).collect(),
+            codegen_targets: crate::features::codegen::load_targets(
+                &self.workspace.path(crate::features::codegen::CODEGEN_DIR),
+            )
+            .into_iter()
+            .map(|t| t.id)
+            .collect(),
+            snippets: crate::features::snippets::load_snippets(
+                &self.workspace.path(crate::features::snippets::SNIPPETS_DIR),
+            )
+            .into_iter()
+            .map(|s| s.id)
+            .collect(),
+            profiles: self.profiles.iter().map(|p| p.name.clone()).collect(),
+            workspaces: self.known_workspaces(),
+            tags: {
+                let mut tags: Vec<String> = self
+                    .collections
+                    .iter()
+                    .flat_map(|c| c.requests.values())
+                    .flat_map(|r| r.tags.iter().map(|t| t.to_lowercase()))
+                    .collect();
+                tags.sort();
+                tags.dedup();
+                tags
+            },
+        }
+    }
+
+    /// `:method <name>`
+    pub fn set_method(&mut self, method: &str) {
+        let method = method.trim().to_uppercase();
+        if !crate::features::command::METHODS.contains(&method.as_str()) {
+            self.show_error(format!(
+                "Unknown method '{}' (one of {})",
+                method,
+                crate::features::command::METHODS.join(", ")
+            ));
+            return;
+        }
+        self.active_tab_mut().method = method;
+    }
+
+    /// The workspace's default headers with the active environment's over them (names
+    /// compare case-insensitively), sorted by name, and what the active tab does with each
+    pub fn default_headers(&self) -> Vec<DefaultHeader> {
+        let env = self.get_active_env();
+        let mut defaults: Vec<DefaultHeader> = Vec::new();
+        let sources = [
+            ("workspace", &self.default_headers),
+            (env.name.as_str(), &env.headers),
+        ];
+        for (source, headers) in sources {
+            for (name, value) in headers {
+                defaults.retain(|d| !d.name.eq_ignore_ascii_case(name));
+                defaults.push(DefaultHeader {
+                    name: name.clone(),
+                    value: value.clone(),
+                    source: source.to_string(),
+                    state: DefaultHeaderState::Sent,
+                });
+            }
+        }
+        let tab = self.active_tab();
+        for default in &mut defaults {
+            if tab
+                .request_headers
+                .keys()
+                .any(|k| k.eq_ignore_ascii_case(&default.name))
+            {
+                default.state = DefaultHeaderState::Overridden;
+            } else if tab
+                .disabled_defaults
+                .iter()
+                .any(|k| k.eq_ignore_ascii_case(&default.name))
+            {
+                default.state = DefaultHeaderState::Disabled;
+            }
+        }
+        defaults.sort_by_key(|d| d.name.to_lowercase());
+        defaults
+    }
+
+    /// The active tab's headers plus the default headers it doesn't override or
+    /// disable, variables still in
+    pub fn sent_headers(&self) -> std::collections::HashMap<String, String> {
+        let mut headers = self.active_tab().request_headers.clone();
+        for default in self.default_headers() {
+            if default.state == DefaultHeaderState::Sent {
+                headers.insert(default.name, default.value);
+            }
+        }
+        headers
+    }
+
+    /// Rows in the Headers tab: the tab's own headers, then the defaults
+    pub fn header_row_count(&self) -> usize {
+        self.active_tab().header_rows().len() + self.default_headers().len()
+    }
+
+    /// Starts typing a header in the Headers tab: a new row, or the selected one.
+    /// Editing a default header starts a tab header of the same name over it
+    pub fn edit_header(&mut self, new: bool) {
+        let rows = self.active_tab().header_rows();
+        let selected = self.active_tab().headers_list_state.selected();
+        let draft = match selected {
+            _ if new => HeaderDraft {
+                original: None,
+                name: String::new(),
+                value: String::new(),
+                enabled: true,
+            },
+            Some(i) if i < rows.len() => HeaderDraft {
+                original: Some(rows[i].0.clone()),
+                name: rows[i].0.clone(),
+                value: rows[i].1.clone(),
+                enabled: rows[i].2,
+            },
+            Some(i) => match self.default_headers().into_iter().nth(i - rows.len()) {
+                Some(default) => HeaderDraft {
+                    original: None,
+                    name: default.name,
+                    value: default.value,
+                    enabled: true,
+                },
+                None => return,
+            },
+            None => return,
+        };
+        let tab = self.active_tab_mut();
+        if draft.original.is_none() {
+            // The new row shows up at the end of the tab's own
+            tab.headers_list_state.select(Some(rows.len()));
+        }
+        tab.header_draft = Some(draft);
+        tab.input_mode = InputMode::EditingHeaderKey;
+    }
+
+    /// Writes the typed header back, replacing the row it came from and any
+    /// other of the same name
+    pub fn commit_header_draft(&mut self) {
+        let tab = self.active_tab_mut();
+        tab.input_mode = InputMode::Normal;
+        let Some(draft) = tab.header_draft.take() else {
+            return;
+        };
+        let name = draft.name.trim().to_string();
+        if name.is_empty() {
+            self.show_error("A header needs a name".to_string());
+            return;
+        }
+        let tab = self.active_tab_mut();
+        if let Some(original) = &draft.original {
+            tab.request_headers.remove(original);
+            tab.disabled_headers.remove(original);
+        }
+        tab.request_headers
+            .retain(|k, _| !k.eq_ignore_ascii_case(&name));
+        tab.disabled_headers
+            .retain(|k, _| !k.eq_ignore_ascii_case(&name));
+        let headers = if draft.enabled {
+            &mut tab.request_headers
+        } else {
+            &mut tab.disabled_headers
+        };
+        headers.insert(name.clone(), draft.value);
+        let row = tab.header_rows().iter().position(|r| r.0 == name);
+        tab.headers_list_state.select(row);
+    }
+
+    /// Esc while typing a header: the row stays as it was
+    pub fn cancel_header_draft(&mut self) {
+        let rows = self.active_tab().header_rows().len();
+        let count = self.header_row_count();
+        let tab = self.active_tab_mut();
+        tab.input_mode = InputMode::Normal;
+        if tab
+            .header_draft
+            .take()
+            .is_some_and(|d| d.original.is_none())
+        {
+            tab.headers_list_state.select(if count == 0 {
+                None
+            } else {
+                Some(rows.min(count - 1))
+            });
+        }
+    }
+
+    /// 'd' in the Headers tab. Default headers can only be switched off
+    pub fn delete_selected_header(&mut self) {
+        let rows = self.active_tab().header_rows();
+        let Some(i) = self.active_tab().headers_list_state.selected() else {
+            return;
+        };
+        let Some((name, _, _)) = rows.get(i) else {
+            self.show_notification(
+                "Default headers can't be deleted, Space turns one off".to_string(),
+            );
+            return;
+        };
+        let tab = self.active_tab_mut();
+        tab.request_headers.remove(name);
+        tab.disabled_headers.remove(name);
+        let count = self.header_row_count();
+        self.active_tab_mut()
+            .headers_list_state
+            .select(if count == 0 {
+                None
+            } else {
+                Some(i.min(count - 1))
+            });
+    }
+
+    /// Space in the Headers tab: the selected header stops or starts going out
+    pub fn toggle_selected_header(&mut self) {
+        let rows = self.active_tab().header_rows();
+        let Some(i) = self.active_tab().headers_list_state.selected() else {
+            return;
+        };
+        if let Some((name, _, _)) = rows.get(i) {
+            let tab = self.active_tab_mut();
+            if let Some(value) = tab.request_headers.remove(name) {
+                tab.disabled_headers.insert(name.clone(), value);
+            } else if let Some(value) = tab.disabled_headers.remove(name) {
+                tab.request_headers.insert(name.clone(), value);
+            }
+        } else if let Some(default) = self.default_headers().into_iter().nth(i - rows.len()) {
+            match default.state {
+                DefaultHeaderState::Overridden => self.show_notification(format!(
+                    "{} is overridden by this tab's own header",
+                    default.name
+                )),
+                DefaultHeaderState::Sent => self.header_command(&format!("off {}", default.name)),
+                DefaultHeaderState::Disabled => {
+                    self.header_command(&format!("on {}", default.name))
+                }
+            }
+        }
+    }
+
+    /// `:header add <Name>: <value>`, `:header rm <Name>`, `:header clear`, and
+    /// `:header off|on <Name>` for a default header
+    pub fn header_command(&mut self, args: &str) {
+        let (verb, rest) = args
+            .split_once(char::is_whitespace)
+            .map(|(v, r)| (v, r.trim()))
+            .unwrap_or((args, ""));
+        match verb {
+            "add" | "set" => match crate::features::command::parse_header(rest) {
+                Some((name, value)) => {
+                    let tab = self.active_tab_mut();
+                    // Header names are case-insensitive, replace rather than duplicate
+                    tab.request_headers
+                        .retain(|k, _| !k.eq_ignore_ascii_case(&name));
+                    tab.disabled_headers
+                        .retain(|k, _| !k.eq_ignore_ascii_case(&name));
+                    tab.request_headers.insert(name.clone(), value.clone());
+                    self.show_success(format!("Header {}: {}", name, value));
+                }
+                None => self.show_error("Usage: header add <Name>: <value>".to_string()),
+            },
+            "rm" | "del" | "remove" if !rest.is_empty() => {
+                let tab = self.active_tab_mut();
+                let before = tab.request_headers.len() + tab.disabled_headers.len();
+                tab.request_headers
+                    .retain(|k, _| !k.eq_ignore_ascii_case(rest));
+                tab.disabled_headers
+                    .retain(|k, _| !k.eq_ignore_ascii_case(rest));
+                if tab.request_headers.len() + tab.disabled_headers.len() < before {
+                    self.show_success(format!("Removed header {}", rest));
+                } else {
+                    self.show_error(format!("No header named {}", rest));
+                }
+            }
+            "clear" => {
+                let tab = self.active_tab_mut();
+                tab.request_headers.clear();
+                tab.disabled_headers.clear();
+                self.show_success("Cleared all headers".to_string());
+            }
+            "off" | "on" if !rest.is_empty() => {
+                let Some(default) = self
+                    .default_headers()
+                    .into_iter()
+                    .find(|d| d.name.eq_ignore_ascii_case(rest))
+                else {
+                    self.show_error(format!("No default header named {}", rest));
+                    return;
+                };
+                let disabled = &mut self.active_tab_mut().disabled_defaults;
+                disabled.retain(|k| !k.eq_ignore_ascii_case(&default.name));
+                if verb == "off" {
+                    disabled.push(default.name.clone());
+                    self.show_success(format!("{} left out of this request", default.name));
+                } else {
+                    self.show_success(format!("{} sent again", default.name));
+                }
+            }
+            _ => self.show_notification(
+                "Usage: header add <Name>: <value> | rm <Name> | clear | off <Name> | on <Name>"
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// `:env` shows the active environment, `:env list` all of them, `:env use <name>`
+    /// (or just `:env <name>`) switches
+    pub fn env_command(&mut self, args: &str) {
+        let tokens = crate::features::command::tokenize(args);
+        let name = match tokens
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .as_slice()
+        {
+            [] => {
+                let msg = format!("Environment: {}", self.get_active_env().name);
+                self.show_notification(msg);
+                return;
+            }
+            ["list"] => {
+                let names: Vec<&str> = self.environments.iter().map(|e| e.name.as_str()).collect();
+                let msg = format!("Environments: {}", names.join(", "));
+                self.show_notification(msg);
+                return;
+            }
+            ["use", name] | [name] => name.to_string(),
+            _ => {
+                self.show_notification("Usage: env [use <name> | list]".to_string());
+                return;
+            }
+        };
+        match self
+            .environments
+            .iter()
+            .position(|e| e.name.eq_ignore_ascii_case(&name))
+        {
+            Some(i) => self.use_environment(i),
+            None => self.show_error(format!("No environment named '{}'", name)),
+        }
+    }
 
-    pub show_command_palette: bool,
-    pub command_query: String,
-    pub command_index: usize,
-    pub command_input: String,
+    fn use_environment(&mut self, index: usize) {
+        self.selected_env_index = index;
+        self.save_config();
+        let msg = format!("Environment: {}", self.environments[index].name);
+        self.show_success(msg);
+    }
 
-    pub show_cookie_modal: bool,
-    pub cookie_list_state: ListState,
+    /// `:open <collection>/<request>` loads a saved request into the tab
+    pub fn open_request(&mut self, path: &str) {
+        let path = path.trim().trim_matches('"');
+        let found = path.split_once('/').and_then(|(collection, request)| {
+            let collection = self
+                .collections
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(collection.trim()))?;
+            collection
+                .requests
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(request.trim()))
+                .map(|(name, config)| (collection.name.clone(), name.clone(), config.clone()))
+        });
+        match found {
+            Some((collection, name, config)) => {
+                self.apply_request_config(config);
+                self.active_tab_mut().collection = Some(collection);
+                self.active_tab_mut().request_name = Some(name);
+            }
+            None => self.show_error(format!(
+                "No request '{}' (use <collection>/<request>, Tab completes)",
+                path
+            )),
+        }
+    }
 
-    pub cookie_jar: std::collections::HashMap<String, Vec<String>>,
+    /// `:run <collection> [--tags a,b]` switches to the runner and starts that
+    /// collection, only the requests with those tags when given (which also become
+    /// the tag filter)
+    pub fn run_collection_by_name(&mut self, args: &str) {
+        let (name, tags) = match args.split_once("--tags") {
+            Some((name, tags)) => (name, Some(crate::domain::collection::parse_tags(tags))),
+            None => (args, None),
+        };
+        let name = name.trim().trim_matches('"');
+        let Some(idx) = self
+            .collections
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(name))
+        else {
+            self.show_error(format!("No collection named '{}'", name));
+            return;
+        };
+        if self.runner_result.as_ref().is_some_and(|r| r.running) {
+            self.show_notification("Run already in progress...".to_string());
+            return;
+        }
+        if let Some(tags) = tags {
+            self.tag_filter = tags;
+        }
+        self.runner_mode = true;
+        self.collection_state.select(Some(idx));
+        self.should_run_collection = true;
+    }
 
-    // Tabs
-    pub tabs: Vec<RequestTab>,
-    pub active_tab: usize,
-    pub next_request_id: usize,
+    /// The runner's copy of `collections[idx]`: only the requests the tag filter
+    /// keeps, or an error when that leaves nothing to run
+    pub fn collection_to_run(&self, idx: usize) -> Result<Collection, String> {
+        let collection = self
+            .collections
+            .get(idx)
+            .ok_or_else(|| "No collection selected".to_string())?;
+        let filtered = collection.only_tagged(&self.tag_filter);
+        if filtered.requests.is_empty() && !self.tag_filter.is_empty() {
+            return Err(format!(
+                "No requests in {} tagged {}",
+                collection.name,
+                self.tag_filter.join(", ")
+            ));
+        }
+        Ok(filtered.with_default_headers(&self.collection_default_headers()))
+    }
 
-    // Collection Runner (Global)
-    pub runner_mode: bool,
-    pub runner_result: Option<crate::features::runner::CollectionRunResult>,
-    pub runner_scroll: usize,
+    /// Default headers for requests sent from a collection rather than a tab
+    pub fn collection_default_headers(&self) -> std::collections::BTreeMap<String, String> {
+        crate::domain::collection::merge_default_headers(
+            &self.default_headers,
+            &self.get_active_env().headers,
+        )
+    }
 
-    // Splash screen
-    pub show_splash: bool,
+    pub fn current_profile(&self) -> Option<&crate::domain::profile::Profile> {
+        self.active_profile.and_then(|i| self.profiles.get(i))
+    }
 
-    // Theme
-    pub theme: Theme,
-    pub theme_index: usize,
+    /// `:workspace` shows the workspace, `:workspace list` the named ones and
+    /// `:workspace <name | dir>` switches
+    pub fn workspace_command(&mut self, args: &str) {
+        match args.trim() {
+            "" => {
+                let msg = format!(
+                    "Workspace: {} ({})",
+                    self.workspace.label(),
+                    self.workspace.dir().display()
+                );
+                self.show_notification(msg);
+            }
+            "list" => {
+                let msg = format!("Workspaces: {}", self.known_workspaces().join(", "));
+                self.show_notification(msg);
+            }
+            arg => self.switch_workspace(arg),
+        }
+    }
 
-    // Diff
-    pub diff_base_index: Option<usize>,
-    pub show_diff_view: bool,
-    pub diff_target_index: Option<usize>,
-    pub diff_list_state: ListState,
+    /// Saves the current workspace and loads everything from another one, creating it
+    /// if it doesn't exist yet. Open tabs stay as they are.
+    pub fn switch_workspace(&mut self, arg: &str) {
+        let workspace = match crate::domain::workspace::resolve(arg) {
+            Ok(workspace) => workspace,
+            Err(e) => {
+                self.show_error(e);
+                return;
+            }
+        };
+        if workspace == self.workspace {
+            let msg = format!("Already in workspace {}", workspace.label());
+            self.show_notification(msg);
+            return;
+        }
+        if let Err(e) = workspace.create() {
+            self.show_error(format!(
+                "Could not create workspace {}: {}",
+                workspace.label(),
+                e
+            ));
+            return;
+        }
+        self.save_config();
+        self.workspace = workspace;
+        self.load_workspace();
+        if let Some(handle) = &self.mock_server_handle {
+            handle.update_routes(self.mock_routes.clone());
+        }
+        let msg = format!(
+            "Workspace: {} ({} collections)",
+            self.workspace.label(),
+            self.collections.len()
+        );
+        self.show_success(msg);
+    }
 
-    // Mock Server
-    pub mock_mode: bool,
-    pub mock_server_running: bool,
-    pub mock_server_port: u16,
-    pub mock_routes: Vec<crate::net::mock_server::MockRoute>,
-    pub mock_list_state: ListState,
-    pub mock_server_handle: Option<crate::net::mock_server::MockServerHandle>,
-    pub image_picker: Option<Picker>,
-    pub clipboard: Option<Clipboard>,
+    /// Named workspaces, plus the one in use when it isn't one of them
+    pub fn known_workspaces(&self) -> Vec<String> {
+        let mut names = crate::domain::workspace::list_named();
+        names.insert(0, "default".to_string());
+        let current = self.workspace.label();
+        if !names.contains(&current) {
+            names.insert(0, current);
+        }
+        names
+    }
 
-    // Stress Testing State
-    pub show_stress_modal: bool,
-    pub stress_vus_input: String,
-    pub stress_duration_input: String,
-    pub stress_running: bool,
-    pub stress_stats: Option<crate::features::stress::StressStats>,
-    pub stress_progress: Option<(u64, u64)>,
-    pub should_run_stress_test: bool,
+    fn keyring_account(&self) -> String {
+        self.workspace.keyring_account()
+    }
 
-    // SSL Configuration
-    pub ssl_verify: bool,                 // Whether to verify SSL certificates
-    pub ssl_ca_cert_path: Option<String>, // Path to custom CA certificate
-    pub ssl_client_cert_path: Option<String>, // Path to client certificate (for mTLS)
-    pub ssl_client_key_path: Option<String>, // Path to client key (for mTLS)
+    /// With encryption on, unlocks from the keyring or `POSTDAD_PASSPHRASE`, otherwise
+    /// asks for the passphrase. History and cookies stay unloaded until then.
+    fn unlock_on_load(&mut self) {
+        use crate::features::vault::{self, KeySource};
+        self.vault = None;
+        self.passphrase_prompt = None;
+        if !self.encryption.enabled {
+            return;
+        }
+        let unlocked = match self.encryption.key_source {
+            KeySource::Keyring => {
+                vault::unlock_with_keyring(&self.encryption, &self.keyring_account())
+            }
+            KeySource::Passphrase => match std::env::var(vault::PASSPHRASE_ENV) {
+                Ok(passphrase) => vault::unlock_with_passphrase(&self.encryption, &passphrase),
+                Err(_) => {
+                    self.passphrase_prompt = Some(PassphrasePrompt {
+                        purpose: PassphrasePurpose::Unlock,
+                        input: String::new(),
+                    });
+                    return;
+                }
+            },
+        };
+        match unlocked {
+            Ok(vault) => self.vault = Some(vault),
+            Err(e) => self.show_error(format!("History and cookies locked: {}", e)),
+        }
+    }
 
-    // Proxy Configuration
-    pub proxy_url: Option<String>, // HTTP/HTTPS proxy URL (e.g., http://proxy:8080)
-    pub proxy_auth_user: Option<String>, // Proxy authentication username
-    pub proxy_auth_pass: Option<String>, // Proxy authentication password
-    pub no_proxy: Option<String>,  // Comma-separated list of hosts to bypass proxy
+    /// `enc:v1:` values in environments.hcl, in memory only
+    fn decrypt_env_secrets(&mut self) {
+        let mut failed = 0;
+        for env in &mut self.environments {
+            for value in env.variables.values_mut() {
+                if !value.starts_with(crate::features::vault::SECRET_PREFIX) {
+                    continue;
+                }
+                match self.vault.as_ref().map(|v| v.decrypt_secret(value)) {
+                    Some(Ok(plain)) => *value = plain,
+                    _ => failed += 1,
+                }
+            }
+        }
+        if failed > 0 && self.vault.is_some() {
+            self.show_error(format!(
+                "{} environment secret(s) could not be decrypted",
+                failed
+            ));
+        }
+    }
 
-    // cURL Import
-    pub curl_import_input: String, // Input field for curl command
+    /// Unlocks with `passphrase` and loads what was held back. Anything recorded while
+    /// locked is kept, newest first.
+    pub fn unlock(&mut self, passphrase: &str) -> bool {
+        match crate::features::vault::unlock_with_passphrase(&self.encryption, passphrase) {
+            Ok(vault) => self.vault = Some(vault),
+            Err(e) => {
+                self.show_error(e);
+                return false;
+            }
+        }
+        self.environments = Environment::load_from_file(&self.workspace.path("environments.hcl"))
+            .unwrap_or_default();
+        self.decrypt_env_secrets();
+
+        let recent = std::mem::take(&mut self.request_history);
+        self.request_history = recent.into_iter().chain(self.load_history()).collect();
+        crate::features::history::trim(&mut self.request_history, self.history_settings.limit);
+        let mut cookies = self.load_cookies();
+        cookies.extend(std::mem::take(&mut self.cookie_jar));
+        self.cookie_jar = cookies;
+        let mut commands = self.load_command_history();
+        for command in std::mem::take(&mut self.command_history) {
+            crate::features::command::push_history(&mut commands, &command);
+        }
+        self.command_history = commands;
 
-    // Sentinel Mode
-    pub sentinel_mode: bool,
-    pub sentinel_state: Option<crate::features::sentinel::SentinelState>,
-    pub should_start_sentinel: bool,
-    pub sentinel_interval_input: String,
-}
+        self.save_history();
+        self.save_cookies();
+        self.save_command_history();
+        self.show_success("Unlocked".to_string());
+        if self.recovery.baseline.is_some() {
+            self.offer_recovery();
+        }
+        true
+    }
+
+    /// `:encrypt` shows the state, `:encrypt on` asks for a passphrase,
+    /// `:encrypt keyring` uses a key kept in the OS keyring, `:encrypt off` decrypts
+    pub fn encrypt_command(&mut self, args: &str) {
+        let locked = self.encryption.enabled && self.vault.is_none();
+        match args.trim() {
+            "" => {
+                let msg = match (self.encryption.enabled, locked) {
+                    (false, _) => "Encryption off (:encrypt on | keyring)".to_string(),
+                    (true, true) => "Encryption on, locked (:unlock)".to_string(),
+                    (true, false) => format!(
+                        "Encryption on, key from {}",
+                        match self.encryption.key_source {
+                            crate::features::vault::KeySource::Passphrase => "passphrase",
+                            crate::features::vault::KeySource::Keyring => "keyring",
+                        }
+                    ),
+                };
+                self.show_notification(msg);
+            }
+            _ if locked => self.show_error("Locked, :unlock first".to_string()),
+            "on" | "passphrase" => {
+                self.passphrase_prompt = Some(PassphrasePrompt {
+                    purpose: PassphrasePurpose::Enable,
+                    input: String::new(),
+                });
+            }
+            "keyring" => match crate::features::vault::setup_keyring(&self.keyring_account()) {
+                Ok((settings, vault)) => self.enable_encryption(settings, vault),
+                Err(e) => self.show_error(e),
+            },
+            "off" => {
+                if !self.encryption.enabled {
+                    self.show_notification("Encryption is already off".to_string());
+                    return;
+                }
+                self.encryption = Default::default();
+                self.vault = None;
+                self.save_history();
+                self.save_cookies();
+                self.save_command_history();
+                self.save_config();
+                self.show_success(
+                    "Encryption off, history and cookies are plain JSON again".to_string(),
+                );
+            }
+            other => self.show_error(format!(
+                "Unknown :encrypt option '{}' (on, keyring, off)",
+                other
+            )),
+        }
+    }
+
+    /// Rewrites the stores with the new key
+    pub fn enable_encryption(
+        &mut self,
+        settings: crate::features::vault::EncryptionSettings,
+        vault: crate::features::vault::Vault,
+    ) {
+        self.encryption = settings;
+        self.vault = Some(vault);
+        self.save_history();
+        self.save_cookies();
+        self.save_command_history();
+        self.save_config();
+        // Sealed at the next autosave
+        self.recovery.last = None;
+        self.show_success("History and cookies are now encrypted".to_string());
+    }
+
+    pub fn unlock_command(&mut self) {
+        if !self.encryption.enabled {
+            self.show_notification("Encryption is off, nothing to unlock".to_string());
+        } else if self.vault.is_some() {
+            self.show_notification("Already unlocked".to_string());
+        } else {
+            self.passphrase_prompt = Some(PassphrasePrompt {
+                purpose: PassphrasePurpose::Unlock,
+                input: String::new(),
+            });
+        }
+    }
+
+    /// `:secret <value>` copies the `enc:v1:` form to paste into environments.hcl
+    pub fn secret_command(&mut self, value: &str) {
+        let Some(vault) = &self.vault else {
+            self.show_error("Turn on encryption first (:encrypt on)".to_string());
+            return;
+        };
+        if value.is_empty() {
+            self.show_error("Usage: :secret <value>".to_string());
+            return;
+        }
+        match vault.encrypt_secret(value) {
+            Ok(sealed) => self.copy_to_clipboard_as(
+                sealed,
+                "Copied encrypted value, paste it into environments.hcl",
+            ),
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Enter in the passphrase prompt
+    pub fn submit_passphrase(&mut self) {
+        let Some(prompt) = self.passphrase_prompt.take() else {
+            return;
+        };
+        match prompt.purpose {
+            PassphrasePurpose::Unlock => {
+                if !self.unlock(&prompt.input) {
+                    self.passphrase_prompt = Some(PassphrasePrompt {
+                        purpose: PassphrasePurpose::Unlock,
+                        input: String::new(),
+                    });
+                }
+            }
+            PassphrasePurpose::Enable => {
+                if prompt.input.chars().count() < 8 {
+                    self.show_error("Use a passphrase of at least 8 characters".to_string());
+                    self.passphrase_prompt = Some(PassphrasePrompt {
+                        purpose: PassphrasePurpose::Enable,
+                        input: String::new(),
+                    });
+                    return;
+                }
+                self.passphrase_prompt = Some(PassphrasePrompt {
+                    purpose: PassphrasePurpose::Confirm(prompt.input),
+                    input: String::new(),
+                });
+            }
+            PassphrasePurpose::Confirm(first) => {
+                if first != prompt.input {
+                    self.show_error("Passphrases don't match, nothing changed".to_string());
+                    return;
+                }
+                match crate::features::vault::setup_passphrase(
+                    &first,
+                    crate::features::vault::DEFAULT_ITERATIONS,
+                ) {
+                    Ok((settings, vault)) => self.enable_encryption(settings, vault),
+                    Err(e) => self.show_error(e),
+                }
+            }
+            PassphrasePurpose::ExportBundle(path) => {
+                if prompt.input.chars().count() < 8 {
+                    self.show_error("Use a passphrase of at least 8 characters".to_string());
+                    self.passphrase_prompt = Some(PassphrasePrompt {
+                        purpose: PassphrasePurpose::ExportBundle(path),
+                        input: String::new(),
+                    });
+                    return;
+                }
+                self.passphrase_prompt = Some(PassphrasePrompt {
+                    purpose: PassphrasePurpose::ConfirmExport(path, prompt.input),
+                    input: String::new(),
+                });
+            }
+            PassphrasePurpose::ConfirmExport(path, first) => {
+                if first != prompt.input {
+                    self.show_error("Passphrases don't match, nothing exported".to_string());
+                    return;
+                }
+                self.export_bundle(&path, Some(&first));
+            }
+            PassphrasePurpose::ImportBundle(path) => {
+                let bundle = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Could not read {}: {}", path, e))
+                    .and_then(|content| crate::features::bundle::parse(&content))
+                    .and_then(|mut bundle| {
+                        crate::features::bundle::unseal(&mut bundle, &prompt.input)?;
+                        Ok(bundle)
+                    });
+                match bundle {
+                    Ok(bundle) => self.begin_bundle_import(path, bundle),
+                    Err(e) => self.show_error(e),
+                }
+            }
+        }
+    }
+
+    /// `:bundle export [file] [secrets]`, `:bundle import <file>`
+    pub fn bundle_command(&mut self, args: &str) {
+        let words: Vec<&str> = args.split_whitespace().collect();
+        match words.as_slice() {
+            ["export", rest @ ..] => {
+                let secrets = rest.contains(&"secrets");
+                let path = rest
+                    .iter()
+                    .find(|w| **w != "secrets")
+                    .copied()
+                    .unwrap_or(crate::features::bundle::DEFAULT_FILE)
+                    .to_string();
+                if secrets {
+                    self.passphrase_prompt = Some(PassphrasePrompt {
+                        purpose: PassphrasePurpose::ExportBundle(path),
+                        input: String::new(),
+                    });
+                } else {
+                    self.export_bundle(&path, None);
+                }
+            }
+            ["import", path] => self.import_bundle(path),
+            _ => self.show_error(
+                "Usage: :bundle export [file] [secrets] | :bundle import <file>".to_string(),
+            ),
+        }
+    }
+
+    /// Writes collections, environments and mock routes to `path`. Secrets are sealed
+    /// with `passphrase`, or left out without one.
+    pub fn export_bundle(&mut self, path: &str, passphrase: Option<&str>) {
+        use crate::features::bundle;
+
+        let collections =
+            crate::features::gist_sync::local_files(&self.workspace.path("collections"));
+        let result = passphrase
+            .map(|p| {
+                crate::features::vault::setup_passphrase(
+                    p,
+                    crate::features::vault::DEFAULT_ITERATIONS,
+                )
+            })
+            .transpose()
+            .and_then(|seal| {
+                bundle::build(collections, &self.environments, &self.mock_routes, seal)
+            })
+            .and_then(|b| {
+                let json = serde_json::to_string_pretty(&b).map_err(|e| e.to_string())?;
+                std::fs::write(path, json)
+                    .map_err(|e| format!("Could not write {}: {}", path, e))?;
+                Ok(b)
+            });
+        match result {
+            Ok(b) => {
+                let excluded: usize = b
+                    .environments
+                    .iter()
+                    .map(|e| e.excluded.len() + e.excluded_headers.len())
+                    .sum();
+                let secrets = match (passphrase, excluded) {
+                    (Some(_), 0) => "secrets sealed".to_string(),
+                    (_, 0) => "no secrets found".to_string(),
+                    (Some(_), n) => format!("secrets sealed, {} locked one(s) left out", n),
+                    (None, n) => format!("{} secret(s) left out", n),
+                };
+                self.show_success(format!(
+                    "Bundle saved to {}: {} collection(s), {} environment(s), {} mock route(s), {}",
+                    path,
+                    b.collections.len(),
+                    b.environments.len(),
+                    b.mocks.len(),
+                    secrets
+                ));
+            }
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Reads a bundle; sealed secrets ask for the passphrase first
+    pub fn import_bundle(&mut self, path: &str) {
+        let bundle = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path, e))
+            .and_then(|content| crate::features::bundle::parse(&content));
+        match bundle {
+            Ok(bundle) if bundle.encryption.is_some() => {
+                self.passphrase_prompt = Some(PassphrasePrompt {
+                    purpose: PassphrasePurpose::ImportBundle(path.to_string()),
+                    input: String::new(),
+                });
+            }
+            Ok(bundle) => self.begin_bundle_import(path.to_string(), bundle),
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// The workspace as on disk, secrets still sealed, for a bundle to merge into
+    fn bundle_target(&self) -> crate::features::bundle::Existing {
+        crate::features::bundle::Existing {
+            collections: crate::features::gist_sync::local_files(
+                &self.workspace.path("collections"),
+            ),
+            environments: Environment::load_from_file(&self.workspace.path("environments.hcl"))
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|e| e.name != "None")
+                .collect(),
+            mocks: self.mock_routes.clone(),
+        }
+    }
+
+    fn begin_bundle_import(&mut self, path: String, bundle: crate::features::bundle::Bundle) {
+        let collisions = crate::features::bundle::collisions(&bundle, &self.bundle_target());
+        let prompt = crate::features::bundle::ImportPrompt {
+            path,
+            bundle,
+            collisions,
+        };
+        if prompt.current().is_some() {
+            self.bundle_prompt = Some(prompt);
+        } else {
+            self.finish_bundle_import(prompt);
+        }
+    }
+
+    /// Answers the collision on screen, or with `rest` every one still open
+    pub fn resolve_bundle_collision(
+        &mut self,
+        resolution: crate::features::bundle::Resolution,
+        rest: bool,
+    ) {
+        use crate::features::bundle::{Kind, Resolution};
+
+        let Some(prompt) = self.bundle_prompt.as_mut() else {
+            return;
+        };
+        for collision in prompt
+            .collisions
+            .iter_mut()
+            .filter(|c| c.resolution.is_none())
+        {
+            // Mock routes can't be kept twice, they'd answer the same requests
+            if collision.kind == Kind::Mock && resolution == Resolution::KeepBoth {
+                if !rest {
+                    return;
+                }
+                continue;
+            }
+            collision.resolution = Some(resolution);
+            if !rest {
+                break;
+            }
+        }
+        if self
+            .bundle_prompt
+            .as_ref()
+            .is_some_and(|p| p.current().is_none())
+            && let Some(prompt) = self.bundle_prompt.take()
+        {
+            self.finish_bundle_import(prompt);
+        }
+    }
+
+    /// Esc on a collision: nothing from the bundle is written
+    pub fn cancel_bundle_import(&mut self) {
+        if self.bundle_prompt.take().is_some() {
+            self.show_notification("Import cancelled, nothing changed".to_string());
+        }
+    }
+
+    fn finish_bundle_import(&mut self, prompt: crate::features::bundle::ImportPrompt) {
+        let before = self.bundle_target();
+        let mut merged = before.clone();
+        let seal = self.vault.clone().filter(|_| self.encryption.enabled);
+        let summary = match crate::features::bundle::merge(
+            &prompt.bundle,
+            &prompt.collisions,
+            &mut merged,
+            seal.as_ref(),
+        ) {
+            Ok(summary) => summary,
+            Err(e) => return self.show_error(e),
+        };
+        if let Err(e) = self.write_bundle_merge(&before, &merged) {
+            return self.show_error(format!("Import stopped: {}", e));
+        }
+        let mut msg = format!(
+            "Imported {}: {} added, {} replaced, {} skipped",
+            prompt.path, summary.added, summary.replaced, summary.skipped
+        );
+        if !summary.missing_secrets.is_empty() {
+            msg.push_str(&format!(
+                "; fill in {} in environments.hcl",
+                summary.missing_secrets.join(", ")
+            ));
+        }
+        self.show_success(msg);
+    }
+
+    fn write_bundle_merge(
+        &mut self,
+        before: &crate::features::bundle::Existing,
+        merged: &crate::features::bundle::Existing,
+    ) -> Result<(), String> {
+        let dir = self.workspace.path("collections");
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        for (name, content) in &merged.collections {
+            if before.collections.get(name) != Some(content) {
+                // Names come from the bundle; keep them inside collections/
+                if name.contains(['/', '\\']) || name.starts_with('.') {
+                    return Err(format!("Refusing to write collection '{}'", name));
+                }
+                std::fs::write(std::path::Path::new(&dir).join(name), content)
+                    .map_err(|e| format!("Could not write {}: {}", name, e))?;
+            }
+        }
+        self.collections = Collection::load_from_dir(&dir).unwrap_or_default();
+
+        let env_names = |envs: &[Environment]| {
+            envs.iter()
+                .map(|e| (e.name.clone(), e.variables.clone()))
+                .collect::<Vec<_>>()
+        };
+        if env_names(&before.environments) != env_names(&merged.environments) {
+            let content = crate::features::bundle::environments_hcl(&merged.environments)?;
+            std::fs::write(self.workspace.path("environments.hcl"), content)
+                .map_err(|e| format!("Could not write environments.hcl: {}", e))?;
+            self.environments =
+                Environment::load_from_file(&self.workspace.path("environments.hcl"))
+                    .unwrap_or_default();
+            self.decrypt_env_secrets();
+        }
+
+        if before.mocks != merged.mocks {
+            crate::net::mock_server::save_routes(
+                &self.workspace.path(crate::net::mock_server::MOCKS_FILE),
+                &merged.mocks,
+            )?;
+            self.mock_routes = merged.mocks.clone();
+        }
+        Ok(())
+    }
+
+    /// Esc in the passphrase prompt. Skipping the unlock leaves history and cookies
+    /// unloaded and unsaved until `:unlock`.
+    pub fn cancel_passphrase(&mut self) {
+        if let Some(prompt) = self.passphrase_prompt.take() {
+            let msg = match prompt.purpose {
+                PassphrasePurpose::Unlock => "Locked: history and cookies not loaded (:unlock)",
+                _ => "Nothing changed",
+            };
+            self.show_notification(msg.to_string());
+        }
+    }
+
+    /// Palette "Switch Workspace" moves on to the next known workspace
+    pub fn next_workspace(&mut self) {
+        let names = self.known_workspaces();
+        if names.len() < 2 {
+            self.show_notification(
+                "No other workspaces, create one with :workspace <name>".to_string(),
+            );
+            return;
+        }
+        let current = self.workspace.label();
+        let i = names.iter().position(|n| *n == current).unwrap_or(0);
+        let next = names[(i + 1) % names.len()].clone();
+        self.switch_workspace(&next);
+    }
+
+    /// `:profile` lists the profiles, `:profile <name>` switches, `:profile off` drops it
+    pub fn switch_profile(&mut self, name: &str) {
+        if name.is_empty() {
+            let names: Vec<&str> = self.profiles.iter().map(|p| p.name.as_str()).collect();
+            let msg = if names.is_empty() {
+                format!(
+                    "No profiles, define them in {}",
+                    crate::domain::profile::PROFILES_FILE
+                )
+            } else {
+                format!(
+                    "Profile: {} (available: {})",
+                    self.current_profile()
+                        .map(|p| p.name.as_str())
+                        .unwrap_or("none"),
+                    names.join(", ")
+                )
+            };
+            self.show_notification(msg);
+            return;
+        }
+        if name == "off" || name == "none" {
+            self.active_profile = None;
+        } else if let Some(i) = self
+            .profiles
+            .iter()
+            .position(|p| p.name.eq_ignore_ascii_case(name))
+        {
+            self.active_profile = Some(i);
+        } else {
+            self.show_error(format!("No profile named '{}'", name));
+            return;
+        }
+        self.save_config();
+        let msg = match self.current_profile() {
+            Some(p) => format!("Profile: {}", p.name),
+            None => "Profile off".to_string(),
+        };
+        self.show_success(msg);
+    }
+
+    /// Cycles through the profiles and then "no profile"
+    pub fn next_profile(&mut self) {
+        if self.profiles.is_empty() {
+            self.switch_profile("");
+            return;
+        }
+        self.active_profile = match self.active_profile {
+            None => Some(0),
+            Some(i) if i + 1 < self.profiles.len() => Some(i + 1),
+            Some(_) => None,
+        };
+        let name = self
+            .current_profile()
+            .map(|p| p.name.clone())
+            .unwrap_or("off".to_string());
+        self.switch_profile(&name);
+    }
 
-#[derive(Serialize, Deserialize, Default)]
-struct AppConfig {
-    theme_index: usize,
-    selected_env_index: usize,
-    zen_mode: bool,
-}
+    /// What the active profile says about the request in the current tab
+    pub fn current_guard(&self) -> crate::domain::profile::Guard {
+        match self.current_profile() {
+            Some(profile) => profile.guard_for(&self.active_tab().method, &self.process_url()),
+            None => crate::domain::profile::Guard::Allow,
+        }
+    }
 
-use crate::domain::collection::Collection;
-use crate::domain::environment::Environment;
-use arboard::Clipboard;
-use ratatui::widgets::ListState;
+    /// The strictest guard among the requests of a collection stress test
+    fn stress_guard(&self) -> crate::domain::profile::Guard {
+        let (Some(profile), Ok(requests)) = (self.current_profile(), self.stress_requests()) else {
+            return crate::domain::profile::Guard::Allow;
+        };
+        requests
+            .iter()
+            .map(|r| profile.guard_for(&r.method, &r.url))
+            .max()
+            .unwrap_or_default()
+    }
 
-impl App {
-    pub fn new() -> App {
-        let (cols, col_state) = match Collection::load_from_dir("collections") {
-            Ok(c) => (c, ListState::default()),
-            Err(_) => (Vec::new(), ListState::default()),
+    /// The strictest guard among the tabs picked for `:send`
+    fn batch_guard(&mut self) -> crate::domain::profile::Guard {
+        let Some(profile) = self.current_profile().cloned() else {
+            return crate::domain::profile::Guard::Allow;
         };
+        let current = self.active_tab;
+        let mut guard = crate::domain::profile::Guard::default();
+        for tab in self.batch_tabs.clone() {
+            if tab >= self.tabs.len() {
+                continue;
+            }
+            self.active_tab = tab;
+            guard = guard.max(profile.guard_for(&self.active_tab().method, &self.process_url()));
+        }
+        self.active_tab = current;
+        guard
+    }
 
-        let (envs, env_idx) = match Environment::load_from_file("environments.hcl") {
-            Ok(e) => (e, 0),
-            Err(_) => (Vec::new(), 0),
+    /// Whether `action` may go ahead under the active profile. Blocked actions report
+    /// why, ones needing confirmation open the prompt and run again once confirmed.
+    pub fn check_guard(&mut self, action: GuardedAction) -> bool {
+        use crate::domain::profile::Guard;
+        let collection_stress =
+            action == GuardedAction::StressTest && !self.stress_target.is_empty();
+        let guard = if collection_stress {
+            self.stress_guard()
+        } else if action == GuardedAction::SendTabs {
+            self.batch_guard()
+        } else {
+            self.current_guard()
+        };
+        let profile = self
+            .current_profile()
+            .map(|p| p.name.clone())
+            .unwrap_or_default();
+        let target = format!("{} {}", self.active_tab().method, self.process_url());
+        let what = match action {
+            GuardedAction::Send => target,
+            GuardedAction::StressTest if collection_stress => {
+                format!("stress test of collection '{}'", self.stress_target)
+            }
+            GuardedAction::StressTest => format!("stress test against {}", target),
+            GuardedAction::Fuzz => format!("fuzzing {}", target),
+            GuardedAction::SendTabs => format!("sending {} tab(s)", self.batch_tabs.len()),
+            GuardedAction::Paginate => format!("fetching every page of {}", target),
         };
 
-        let mut app = App {
-            spinner_state: 0,
-            popup_message: None,
-            collections: cols,
-            collection_state: col_state,
-            active_sidebar: false,
-            sidebar_filter: String::new(),
-            show_sidebar_filter: false,
-            environments: envs,
-            selected_env_index: env_idx,
-            request_history: App::load_history(),
-            notification_time: None,
-            editor_mode: EditorMode::None,
-            zen_mode: false,
-            show_help: false,
-            help_scroll: 0,
-            show_command_palette: false,
-            command_query: String::new(),
-            command_index: 0,
-            command_input: String::new(),
-            cookie_jar: std::collections::HashMap::new(),
+        match guard {
+            Guard::Allow => true,
+            Guard::Block => {
+                self.show_error(format!(
+                    "Profile '{}' blocks {} (production)",
+                    profile, what
+                ));
+                false
+            }
+            Guard::Confirm if self.guard_confirmed => {
+                self.guard_confirmed = false;
+                true
+            }
+            Guard::Confirm => {
+                self.guard_prompt = Some(GuardPrompt {
+                    message: format!("{} hits production. Go ahead?", what),
+                    action,
+                });
+                false
+            }
+        }
+    }
 
-            tabs: vec![RequestTab::new()],
-            active_tab: 0,
-            next_request_id: 1,
+    /// Answers the production prompt
+    pub fn resolve_guard_prompt(&mut self, confirmed: bool) {
+        let Some(prompt) = self.guard_prompt.take() else {
+            return;
+        };
+        if !confirmed {
+            self.show_notification("Cancelled".to_string());
+            return;
+        }
+        self.guard_confirmed = true;
+        match prompt.action {
+            GuardedAction::Send => self.active_tab_mut().resend_requested = true,
+            GuardedAction::StressTest => self.should_run_stress_test = true,
+            GuardedAction::Fuzz => self.should_run_fuzz = true,
+            GuardedAction::SendTabs => self.should_run_batch = true,
+            GuardedAction::Paginate => self.should_paginate = true,
+        }
+    }
 
-            runner_mode: false,
-            runner_result: None,
-            runner_scroll: 0,
-            show_splash: true,
-            theme: Theme::default_theme(),
-            theme_index: 0,
+    fn sync_state(
+        &self,
+        target: crate::features::gist_sync::Target,
+    ) -> crate::features::gist_sync::SyncState {
+        crate::features::gist_sync::load_state(&self.workspace.path(target.state_file()))
+    }
 
-            diff_base_index: None,
-            show_diff_view: false,
-            diff_target_index: None,
-            diff_list_state: ListState::default(),
+    /// The gist id, or the storage location; hashes recorded under another one don't count
+    fn sync_id(&self, target: crate::features::gist_sync::Target) -> String {
+        match target {
+            crate::features::gist_sync::Target::Gist => self.gist.gist_id.clone(),
+            crate::features::gist_sync::Target::Storage => self.storage.location(),
+        }
+    }
 
-            mock_mode: false,
-            mock_server_running: false,
-            mock_server_port: 3000,
-            mock_routes: Vec::new(),
-            mock_list_state: ListState::default(),
-            mock_server_handle: None,
-            image_picker: if std::env::var("TERM_PROGRAM")
-                .map(|v| v == "vscode")
-                .unwrap_or(false)
-            {
-                Some(Picker::halfblocks())
-            } else {
-                Picker::from_query_stdio()
-                    .ok()
-                    .or(Some(Picker::halfblocks()))
-            },
-            clipboard: Clipboard::new().ok(),
+    /// The gist gets the collections by file name, the storage the whole workspace
+    fn sync_local(
+        &self,
+        target: crate::features::gist_sync::Target,
+    ) -> std::collections::BTreeMap<String, String> {
+        match target {
+            crate::features::gist_sync::Target::Gist => {
+                crate::features::gist_sync::local_files(&self.workspace.path("collections"))
+            }
+            crate::features::gist_sync::Target::Storage => {
+                crate::features::storage::workspace_files(&self.workspace)
+            }
+        }
+    }
 
-            show_stress_modal: false,
-            stress_vus_input: "50".to_string(), // Default 50 VUs
-            stress_duration_input: "10".to_string(), // Default 10s
-            stress_running: false,
-            stress_stats: None,
-            stress_progress: None,
-            should_run_stress_test: false,
+    /// Settings for the main loop's sync task
+    pub fn sync_endpoint(
+        &self,
+        target: crate::features::gist_sync::Target,
+    ) -> crate::features::gist_sync::Endpoint {
+        match target {
+            crate::features::gist_sync::Target::Gist => {
+                crate::features::gist_sync::Endpoint::Gist(self.gist.clone())
+            }
+            crate::features::gist_sync::Target::Storage => {
+                crate::features::gist_sync::Endpoint::Storage(self.storage.clone())
+            }
+        }
+    }
 
-            show_cookie_modal: false,
-            cookie_list_state: ListState::default(),
+    /// `:sync [push|pull|status|link <gist id>]`
+    pub fn sync_command(&mut self, args: &str) {
+        use crate::features::gist_sync::{Direction, Target};
 
-            // SSL: Load from environment variables or use defaults
-            ssl_verify: std::env::var("POSTDAD_SSL_VERIFY")
-                .map(|v| v != "false" && v != "0")
-                .unwrap_or(true),
-            ssl_ca_cert_path: std::env::var("POSTDAD_CA_CERT").ok(),
-            ssl_client_cert_path: std::env::var("POSTDAD_CLIENT_CERT").ok(),
-            ssl_client_key_path: std::env::var("POSTDAD_CLIENT_KEY").ok(),
+        let (word, rest) = args.split_once(' ').unwrap_or((args, ""));
+        match word {
+            "status" => {
+                let status = self.sync_summary(Target::Gist);
+                self.show_notification(status);
+            }
+            "link" if !rest.trim().is_empty() => {
+                self.gist.gist_id = rest.trim().to_string();
+                self.save_config();
+                self.show_success(format!(
+                    "Linked to gist {}, :sync pull to fetch it",
+                    self.gist.gist_id
+                ));
+            }
+            _ => match Direction::parse(word) {
+                Some(direction) => self.start_sync(Target::Gist, direction),
+                None => {
+                    self.show_error("Usage: :sync [push|pull|status|link <gist id>]".to_string())
+                }
+            },
+        }
+    }
 
-            // Proxy: Load from standard environment variables
-            proxy_url: std::env::var("HTTPS_PROXY")
-                .or_else(|_| std::env::var("https_proxy"))
-                .or_else(|_| std::env::var("HTTP_PROXY"))
-                .or_else(|_| std::env::var("http_proxy"))
-                .ok(),
-            proxy_auth_user: std::env::var("POSTDAD_PROXY_USER").ok(),
-            proxy_auth_pass: std::env::var("POSTDAD_PROXY_PASS").ok(),
-            no_proxy: std::env::var("NO_PROXY")
-                .or_else(|_| std::env::var("no_proxy"))
-                .ok(),
+    /// `:storage [push|pull|status]`, the workspace against the storage in config.json
+    pub fn storage_command(&mut self, args: &str) {
+        use crate::features::gist_sync::{Direction, Target};
 
-            curl_import_input: String::new(),
+        match (args, Direction::parse(args)) {
+            ("status", _) => {
+                let status = self.sync_summary(Target::Storage);
+                self.show_notification(status);
+            }
+            (_, Some(direction)) => self.start_sync(Target::Storage, direction),
+            _ => self.show_error("Usage: :storage [push|pull|status]".to_string()),
+        }
+    }
 
-            sentinel_mode: false,
-            sentinel_state: Some(crate::features::sentinel::SentinelState::new()),
-            should_start_sentinel: false,
-            sentinel_interval_input: "2".to_string(),
+    /// Where it syncs to, last sync and what changed here since, e.g. for `:sync status`
+    pub fn sync_summary(&self, target: crate::features::gist_sync::Target) -> String {
+        use crate::features::gist_sync::Target;
+
+        let id = self.sync_id(target);
+        match target {
+            Target::Gist if id.is_empty() => {
+                return "Not synced yet: :sync push creates a secret gist".to_string();
+            }
+            Target::Storage if !self.storage.is_configured() => {
+                return "No storage set up: add storage to config.json".to_string();
+            }
+            _ => {}
+        }
+        let place = match target {
+            Target::Gist => format!("Gist {}", id),
+            Target::Storage => format!("Storage {}", id),
         };
+        let state = self.sync_state(target);
+        let changed = self
+            .sync_local(target)
+            .iter()
+            .filter(|(name, content)| {
+                state.gist_id != id
+                    || state.files.get(*name) != Some(&crate::features::gist_sync::hash(content))
+            })
+            .count();
+        match state.synced_at {
+            0 => format!("{}: never synced", place),
+            at => format!(
+                "{}: synced {}, {} local change(s) since",
+                place,
+                crate::features::gist_sync::ago(crate::features::history::now().saturating_sub(at)),
+                changed
+            ),
+        }
+    }
 
-        // Load persisted config and state
-        let config = App::load_config();
-        app.theme_index = config.theme_index;
-        app.zen_mode = config.zen_mode;
+    pub fn start_sync(
+        &mut self,
+        target: crate::features::gist_sync::Target,
+        direction: crate::features::gist_sync::Direction,
+    ) {
+        use crate::features::gist_sync::{self, Direction, SyncRequest, SyncStatus, Target};
 
-        // Bounds check env index
-        if config.selected_env_index < app.environments.len() {
-            app.selected_env_index = config.selected_env_index;
+        if self.sync_request.is_some() || self.sync_status == SyncStatus::Busy {
+            self.show_notification("A sync is already running".to_string());
+            return;
+        }
+        if target == Target::Storage && !self.storage.is_configured() {
+            self.show_error(
+                "No storage set up: add storage (local, webdav or s3) to config.json".to_string(),
+            );
+            return;
+        }
+        if target == Target::Gist && self.gist.gist_id.is_empty() {
+            if direction == Direction::Pull {
+                self.show_error(
+                    "No gist to pull from: :sync push creates one, :sync link <id> uses yours"
+                        .to_string(),
+                );
+                return;
+            }
+            // Nothing to compare with yet: everything goes up into a new gist
+            let local = gist_sync::local_files(&self.workspace.path("collections"));
+            if local.is_empty() {
+                self.show_error("No collections to push".to_string());
+                return;
+            }
+            let plan = gist_sync::plan(&local, &Default::default(), &Default::default());
+            let outcome = gist_sync::outcome(&plan, Direction::Push, "", &Default::default());
+            self.sync_request = Some(SyncRequest::Push {
+                target,
+                files: outcome.pushes,
+                state: outcome.state,
+            });
         } else {
-            app.selected_env_index = 0;
+            self.sync_request = Some(SyncRequest::Fetch(target, direction));
         }
+        self.sync_status = SyncStatus::Busy;
+        self.show_notification(format!("Syncing with {}...", target.label()));
+    }
 
-        app.cookie_jar = App::load_cookies();
-        app.request_history = App::load_history();
-
-        // Apply loaded theme
-        app.apply_theme();
+    /// Every local file against the remote copy and the last sync
+    pub fn sync_plan(
+        &self,
+        target: crate::features::gist_sync::Target,
+        remote: &crate::features::gist_sync::RemoteFiles,
+    ) -> Vec<crate::features::gist_sync::FileSync> {
+        let state = self.sync_state(target);
+        let base = if state.gist_id == self.sync_id(target) {
+            state.files
+        } else {
+            Default::default()
+        };
+        crate::features::gist_sync::plan(&self.sync_local(target), &remote.files, &base)
+    }
 
-        app
+    /// Writes what the plan pulls and reloads what changed. What's left to push is in
+    /// the returned outcome.
+    pub fn apply_sync(
+        &mut self,
+        target: crate::features::gist_sync::Target,
+        direction: crate::features::gist_sync::Direction,
+        plan: &[crate::features::gist_sync::FileSync],
+    ) -> Result<crate::features::gist_sync::Outcome, String> {
+        use crate::features::gist_sync::Target;
+
+        let outcome = crate::features::gist_sync::outcome(
+            plan,
+            direction,
+            &self.sync_id(target),
+            &self.sync_state(target),
+        );
+        let dir = self.workspace.path("collections");
+        match target {
+            Target::Gist => crate::features::gist_sync::write_pulls(&dir, &outcome.pulls)?,
+            Target::Storage => {
+                crate::features::storage::write_pulls(&self.workspace, &outcome.pulls)?
+            }
+        }
+        let pulled = |wanted: fn(&str) -> bool| outcome.pulls.iter().any(|(name, _)| wanted(name));
+        let collections = match target {
+            Target::Gist => !outcome.pulls.is_empty(),
+            Target::Storage => pulled(|name| name.starts_with("collections/")),
+        };
+        if collections {
+            self.collections = Collection::load_from_dir(&dir).unwrap_or_default();
+        }
+        if target == Target::Storage && pulled(|name| name == "environments.hcl") {
+            self.environments =
+                Environment::load_from_file(&self.workspace.path("environments.hcl"))
+                    .unwrap_or_default();
+            self.decrypt_env_secrets();
+        }
+        Ok(outcome)
     }
 
-    pub fn active_tab(&self) -> &RequestTab {
-        &self.tabs[self.active_tab]
+    /// Remembers the gist (a first push creates it) and the hashes both sides agree on
+    pub fn record_sync(
+        &mut self,
+        target: crate::features::gist_sync::Target,
+        id: &str,
+        mut state: crate::features::gist_sync::SyncState,
+    ) -> Result<(), String> {
+        if target == crate::features::gist_sync::Target::Gist && self.gist.gist_id != id {
+            self.gist.gist_id = id.to_string();
+            self.save_config();
+        }
+        state.gist_id = id.to_string();
+        crate::features::gist_sync::save_state(&self.workspace.path(target.state_file()), &state)?;
+        self.sync_status = crate::features::gist_sync::SyncStatus::Synced(state.synced_at);
+        Ok(())
     }
 
-    pub fn active_tab_mut(&mut self) -> &mut RequestTab {
-        &mut self.tabs[self.active_tab]
+    /// The remote files came back: conflicts go to the prompt, otherwise the sync
+    /// carries on
+    pub fn sync_fetched(
+        &mut self,
+        target: crate::features::gist_sync::Target,
+        direction: crate::features::gist_sync::Direction,
+        remote: Result<crate::features::gist_sync::RemoteFiles, String>,
+    ) {
+        use crate::features::gist_sync::{SyncPrompt, SyncStatus, conflicts};
+
+        let remote = match remote {
+            Ok(remote) => remote,
+            Err(e) => return self.sync_failed(e),
+        };
+        let plan = self.sync_plan(target, &remote);
+        let open = conflicts(&plan);
+        if open > 0 {
+            self.sync_status = SyncStatus::Conflicts(open);
+            self.sync_prompt = Some(SyncPrompt {
+                target,
+                direction,
+                plan,
+                remote_updated_at: remote.updated_at,
+                show_diff: false,
+                scroll: 0,
+            });
+            return;
+        }
+        self.continue_sync(target, direction, &plan);
     }
 
-    pub fn apply_theme(&mut self) {
-        self.theme = match self.theme_index {
-            0 => Theme::default_theme(),
-            1 => Theme::matrix(),
-            2 => Theme::cyberpunk(),
-            3 => Theme::dracula(),
-            _ => Theme::default_theme(),
+    fn continue_sync(
+        &mut self,
+        target: crate::features::gist_sync::Target,
+        direction: crate::features::gist_sync::Direction,
+        plan: &[crate::features::gist_sync::FileSync],
+    ) {
+        let outcome = match self.apply_sync(target, direction, plan) {
+            Ok(outcome) => outcome,
+            Err(e) => return self.sync_failed(e),
+        };
+        let skipped = match outcome.skipped {
+            0 => String::new(),
+            n => format!(", {} change(s) left for the other direction", n),
         };
+        if outcome.pushes.is_empty() {
+            let id = self.sync_id(target);
+            match self.record_sync(target, &id, outcome.state) {
+                Ok(()) => self.show_success(format!(
+                    "Synced: {} file(s) pulled{}",
+                    outcome.pulls.len(),
+                    skipped
+                )),
+                Err(e) => self.sync_failed(e),
+            }
+            return;
+        }
+        if !outcome.pulls.is_empty() {
+            self.show_notification(format!(
+                "Pulled {} file(s), pushing {}...",
+                outcome.pulls.len(),
+                outcome.pushes.len()
+            ));
+        }
+        self.sync_request = Some(crate::features::gist_sync::SyncRequest::Push {
+            target,
+            files: outcome.pushes,
+            state: outcome.state,
+        });
     }
 
-    pub fn next_theme(&mut self) {
-        self.theme_index = (self.theme_index + 1) % 4;
-        self.apply_theme();
-        self.save_config();
+    /// The upload finished; `files` is how many went up
+    pub fn sync_pushed(
+        &mut self,
+        target: crate::features::gist_sync::Target,
+        result: Result<String, String>,
+        state: crate::features::gist_sync::SyncState,
+        files: usize,
+    ) {
+        match result.and_then(|id| self.record_sync(target, &id, state)) {
+            Ok(()) => self.show_success(format!(
+                "Synced: {} file(s) pushed to {} {}",
+                files,
+                target.label(),
+                self.sync_id(target)
+            )),
+            Err(e) => self.sync_failed(e),
+        }
     }
 
-    pub fn add_tab(&mut self) {
-        let mut tab = RequestTab::new();
-        tab.name = format!("Req {}", self.next_request_id);
-        self.next_request_id += 1;
-        self.tabs.push(tab);
-        self.active_tab = self.tabs.len() - 1;
+    fn sync_failed(&mut self, error: String) {
+        self.sync_status = crate::features::gist_sync::SyncStatus::Failed;
+        self.show_error(format!("Sync failed: {}", error));
     }
 
-    pub fn close_tab(&mut self) {
-        if self.tabs.len() > 1 {
-            self.tabs.remove(self.active_tab);
-            if self.active_tab >= self.tabs.len() {
-                self.active_tab = self.tabs.len() - 1;
-            }
+    /// Settles the conflict on screen; the sync goes on once none are left
+    pub fn resolve_sync_conflict(&mut self, keep_local: bool) {
+        use crate::features::gist_sync::{Action, SyncStatus, conflicts};
+
+        let Some(prompt) = self.sync_prompt.as_mut() else {
+            return;
+        };
+        if let Some(i) = prompt.current() {
+            prompt.plan[i].action = if keep_local {
+                Action::KeepLocal
+            } else {
+                Action::KeepRemote
+            };
+            prompt.show_diff = false;
+            prompt.scroll = 0;
+        }
+        let open = conflicts(&prompt.plan);
+        if open > 0 {
+            self.sync_status = SyncStatus::Conflicts(open);
+        } else if let Some(prompt) = self.sync_prompt.take() {
+            self.continue_sync(prompt.target, prompt.direction, &prompt.plan);
         }
     }
 
-    pub fn next_tab(&mut self) {
-        if self.tabs.len() > 1 {
-            self.active_tab = (self.active_tab + 1) % self.tabs.len();
+    /// Esc on the conflict prompt: nothing is written or uploaded
+    pub fn cancel_sync_prompt(&mut self) {
+        if let Some(prompt) = self.sync_prompt.take() {
+            self.sync_status = crate::features::gist_sync::SyncStatus::Conflicts(
+                crate::features::gist_sync::conflicts(&prompt.plan),
+            );
+            self.show_notification("Sync cancelled, nothing changed".to_string());
         }
     }
 
-    pub fn prev_tab(&mut self) {
-        if self.tabs.len() > 1 {
-            if self.active_tab == 0 {
-                self.active_tab = self.tabs.len() - 1;
-            } else {
-                self.active_tab -= 1;
-            }
+    /// The open tabs' requests, as `recovery.json` keeps them
+    pub fn drafts(&self) -> crate::features::recovery::Snapshot {
+        crate::features::recovery::Snapshot {
+            saved_at: crate::features::history::now(),
+            active_tab: self.active_tab,
+            tabs: self
+                .tabs
+                .iter()
+                .map(|tab| crate::features::recovery::DraftTab {
+                    name: tab.name.clone(),
+                    collection: tab.collection.clone(),
+                    request_name: tab.request_name.clone(),
+                    request: tab.to_request_config(),
+                })
+                .collect(),
         }
     }
 
-    pub fn add_cookies(&mut self, url: &str, new_cookies: Vec<String>) {
-        if new_cookies.is_empty() {
+    /// Turns autosave on, the tabs as they are now being the ones not worth keeping,
+    /// and offers back what a session that didn't exit cleanly left behind
+    pub fn start_recovery(&mut self) {
+        self.recovery.baseline = serde_json::to_string(&self.drafts().tabs).ok();
+        self.offer_recovery();
+    }
+
+    /// Asks about `recovery.json` if there's one. Asked again once unlocked when
+    /// it's encrypted.
+    fn offer_recovery(&mut self) {
+        use crate::features::recovery;
+        if self.recovery.written || self.recovery_prompt.is_some() {
             return;
         }
+        let Some(content) = self.read_store(recovery::FILE) else {
+            return;
+        };
+        match serde_json::from_str::<recovery::Snapshot>(&content) {
+            Ok(snapshot) if !snapshot.tabs.is_empty() => self.recovery_prompt = Some(snapshot),
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!(error = %e, "recovery file not read");
+                self.show_error(format!("{} not read: {}", recovery::FILE, e));
+            }
+        }
+    }
 
-        if let Ok(parsed) = reqwest::Url::parse(url)
-            && let Some(host) = parsed.host_str()
-        {
-            let entry = self.cookie_jar.entry(host.to_string()).or_default();
-            for raw_cookie in new_cookies {
-                let name_val = raw_cookie
-                    .split(';')
-                    .next()
-                    .unwrap_or("")
-                    .trim()
-                    .to_string();
-                if name_val.is_empty() {
-                    continue;
-                }
-
-                let name = name_val.split('=').next().unwrap_or("").trim();
-                entry.retain(|c| !c.starts_with(&format!("{}=", name)));
+    /// `y` / `n` on the recovery prompt. Restored tabs replace the ones PostDad
+    /// started with; discarded ones are gone for good.
+    pub fn resolve_recovery(&mut self, restore: bool) {
+        let Some(snapshot) = self.recovery_prompt.take() else {
+            return;
+        };
+        if !restore {
+            let _ = std::fs::remove_file(self.workspace.path(crate::features::recovery::FILE));
+            self.show_notification("Recovered tabs discarded".to_string());
+            return;
+        }
+        let started_with = self.tabs.len();
+        let count = crate::features::recovery::describe(&snapshot);
+        for draft in snapshot.tabs {
+            self.add_tab();
+            self.load_request_config(draft.request);
+            let tab = self.active_tab_mut();
+            tab.name = draft.name;
+            tab.collection = draft.collection;
+            tab.request_name = draft.request_name;
+        }
+        self.tabs.drain(..started_with);
+        self.active_tab = snapshot.active_tab.min(self.tabs.len() - 1);
+        self.show_success(format!(
+            "Restored {} from {}",
+            count,
+            crate::features::gist_sync::ago(
+                crate::features::history::now().saturating_sub(snapshot.saved_at)
+            )
+        ));
+    }
 
-                entry.push(name_val);
+    /// Looks for changed tabs every `recovery::CHECK` and writes them out at most
+    /// every `recovery::SAVE`. The panic hook writes what the last look found.
+    /// Tabs back the way they started leave no file behind.
+    pub fn autosave_drafts(&mut self, now: std::time::Instant) {
+        use crate::features::recovery;
+        if !self.recovery.due(now) || self.recovery_prompt.is_some() {
+            return;
+        }
+        self.recovery.last_check = Some(now);
+        let snapshot = self.drafts();
+        let key = serde_json::to_string(&snapshot.tabs).ok();
+        if key != self.recovery.last {
+            let content = if key == self.recovery.baseline {
+                None
+            } else {
+                serde_json::to_string(&snapshot)
+                    .ok()
+                    .and_then(|json| self.seal_store(json))
+                    .map(|sealed| (self.workspace.path(recovery::FILE).into(), sealed))
+            };
+            if let Ok(mut pending) = self.recovery.pending.lock() {
+                *pending = content;
+            }
+            self.recovery.last = key;
+            self.recovery.unsaved = true;
+        }
+        if self.recovery.save_due(now) {
+            self.recovery.unsaved = false;
+            let pending = self.recovery.pending.lock().ok().and_then(|p| p.clone());
+            match pending {
+                Some((path, content)) => {
+                    self.recovery.written = std::fs::write(path, content).is_ok();
+                    self.recovery.last_save = Some(now);
+                }
+                None if self.recovery.written => {
+                    let _ = std::fs::remove_file(self.workspace.path(recovery::FILE));
+                    self.recovery.written = false;
+                }
+                None => {}
             }
-            self.save_cookies();
         }
     }
 
-    pub fn get_cookie_header(&self, url: &str) -> Option<String> {
-        if let Ok(parsed) = reqwest::Url::parse(url)
-            && let Some(host) = parsed.host_str()
-            && let Some(cookies) = self.cookie_jar.get(host)
-            && !cookies.is_empty()
-        {
-            return Some(cookies.join("; "));
+    /// On a clean exit: nothing to recover next time. A recovery prompt still open
+    /// keeps its file for the next start.
+    pub fn discard_drafts(&mut self) {
+        if self.recovery.baseline.is_some() && self.recovery_prompt.is_none() {
+            let _ = std::fs::remove_file(self.workspace.path(crate::features::recovery::FILE));
         }
-        None
     }
 
-    pub fn get_flattened_cookies(&self) -> Vec<(String, String)> {
-        let mut flattened = Vec::new();
-        for (host, cookies) in &self.cookie_jar {
-            for cookie in cookies {
-                flattened.push((host.clone(), cookie.clone()));
+    /// Forgets pooled connections and cached DNS answers; the next request starts cold
+    pub fn flush_connections(&mut self) {
+        self.should_flush_connections = true;
+        self.show_success("Connections and DNS cache flushed".to_string());
+    }
+
+    /// `:pool ...`, see `pool::apply_pool_command`. Changes take effect on the next request.
+    pub fn pool_command(&mut self, args: &str) {
+        match crate::net::pool::apply_pool_command(&mut self.connection, args) {
+            Ok(summary) => {
+                if !args.trim().is_empty() {
+                    self.save_config();
+                }
+                self.show_notification(summary);
             }
+            Err(e) => self.show_error(e),
         }
-        flattened.sort_by(|a, b| a.0.cmp(&b.0));
-        flattened
     }
 
-    pub fn delete_cookie_at_index(&mut self, index: usize) {
-        let flattened = self.get_flattened_cookies();
-        if let Some((host, cookie_val)) = flattened.get(index)
-            && let Some(cookies) = self.cookie_jar.get_mut(host)
-            && let Some(pos) = cookies.iter().position(|c| c == cookie_val)
-        {
-            cookies.remove(pos);
-            if cookies.is_empty() {
-                self.cookie_jar.remove(host);
+    /// `:ratelimit ...`, see `rate_limit::apply_rate_limit_command`. Runs, stress
+    /// tests and sentinel started after this go by the new limits.
+    pub fn rate_limit_command(&mut self, args: &str) {
+        match crate::net::rate_limit::apply_rate_limit_command(&mut self.rate_limit, args) {
+            Ok(summary) => {
+                if !args.trim().is_empty() {
+                    self.save_config();
+                }
+                self.show_notification(format!("Rate limits: {}", summary));
             }
-            self.save_cookies();
+            Err(e) => self.show_error(e),
         }
     }
 
-    pub fn should_open_editor(&self) -> bool {
-        self.editor_mode != EditorMode::None
+    /// Adds the active tab's request, as it is right now, to its timeline
+    pub fn record_send(&mut self) {
+        let tab = self.active_tab_mut();
+        tab.timeline.push(TimelineEntry {
+            sent_at: std::time::Instant::now(),
+            request: RequestSnapshot::capture(tab),
+            status: None,
+            latency: None,
+            error: None,
+        });
+        if tab.timeline.len() > TIMELINE_LIMIT {
+            tab.timeline.remove(0);
+        }
     }
 
-    pub fn get_active_env(&self) -> &Environment {
-        &self.environments[self.selected_env_index]
+    /// Newest timeline entry first, as the browser lists them
+    pub fn timeline_entry(&self, index: usize) -> Option<&TimelineEntry> {
+        self.active_tab().timeline.iter().rev().nth(index)
     }
 
-    pub fn next_env(&mut self) {
-        if self.environments.is_empty() {
+    pub fn open_timeline(&mut self) {
+        if self.active_tab().timeline.is_empty() {
+            self.show_notification("Nothing sent from this tab yet".to_string());
             return;
         }
-        self.selected_env_index = (self.selected_env_index + 1) % self.environments.len();
-        self.save_config();
+        self.show_timeline = true;
+        self.timeline_state.select(Some(0));
     }
 
-    pub fn process_url(&self) -> String {
-        let mut final_url = self.active_tab().url.clone();
-        let env = self.get_active_env();
+    /// The bytes the hex pane is showing: the response as received (text responses
+    /// included) or the request as sent
+    pub fn hex_bytes(&self) -> Option<&[u8]> {
+        let tab = self.active_tab();
+        match self.hex_view.as_ref()?.source {
+            crate::features::hex::Source::Response => tab
+                .response_bytes
+                .as_deref()
+                .or(tab.response.as_deref().map(str::as_bytes)),
+            crate::features::hex::Source::Encoded => {
+                tab.response_encoding.as_ref().map(|e| e.raw.as_slice())
+            }
+            crate::features::hex::Source::Request => tab.request_bytes.as_deref(),
+        }
+    }
 
-        for (key, val) in &env.variables {
-            let placeholder = format!("{{{{{}}}}}", key);
-            final_url = final_url.replace(&placeholder, val);
+    /// `U`: the compressed body as it came, in the hex pane, or back to the decoded one
+    pub fn toggle_undecoded(&mut self) {
+        use crate::features::hex::{HexView, Source};
+
+        let Some(encoding) = self.active_tab().response_encoding.as_ref() else {
+            self.show_notification("The response didn't come with a Content-Encoding".to_string());
+            return;
+        };
+        let source = match self.hex_view.as_ref().map(|v| v.source) {
+            Some(Source::Encoded) => Source::Response,
+            _ => Source::Encoded,
+        };
+        if source == Source::Encoded {
+            let message = match &encoding.error {
+                Some(e) => format!("{} body, not decoded: {}", encoding.encoding, e),
+                None => format!("{} body as received", encoding.encoding),
+            };
+            self.show_notification(message);
         }
-        final_url
+        let query = self
+            .hex_view
+            .as_ref()
+            .map(|v| v.query.clone())
+            .unwrap_or_default();
+        self.hex_view = Some(HexView {
+            query,
+            ..HexView::new(source)
+        });
+        self.hex_search();
     }
 
-    pub fn sync_url_to_params(&mut self) {
-        let tab = self.active_tab_mut();
-        if let Ok(u) = reqwest::Url::parse(&tab.url) {
-            tab.params = u.query_pairs().into_owned().collect();
+    /// `B`: opens the hex pane on the response, or on the sent request when asked for
+    /// or when there's no response
+    pub fn open_hex_view(&mut self, request: bool) {
+        use crate::features::hex::{HexView, Source};
+
+        let tab = self.active_tab();
+        let has_response = tab.response_bytes.is_some() || tab.response.is_some();
+        let source = if has_response && !request {
+            Source::Response
+        } else if tab.request_bytes.is_some() {
+            Source::Request
         } else {
-            tab.params.clear();
-        }
+            self.show_notification("Nothing sent from this tab yet".to_string());
+            return;
+        };
+        self.hex_view = Some(HexView::new(source));
     }
 
-    pub fn sync_params_to_url(&mut self) {
-        let tab = self.active_tab_mut();
-        if let Ok(mut u) = reqwest::Url::parse(&tab.url) {
-            u.query_pairs_mut().clear().extend_pairs(&tab.params);
-            tab.url = u.to_string();
+    /// Flips the hex pane between the response and the request that was sent
+    pub fn toggle_hex_source(&mut self) {
+        use crate::features::hex::{HexView, Source};
+
+        let Some(view) = self.hex_view.as_ref() else {
+            return;
+        };
+        let source = match view.source {
+            Source::Request => Source::Response,
+            Source::Response | Source::Encoded => Source::Request,
+        };
+        let query = view.query.clone();
+        self.hex_view = Some(HexView {
+            query,
+            ..HexView::new(source)
+        });
+        if self.hex_bytes().is_none() {
+            self.show_notification(format!("No {} bytes yet", source.label().to_lowercase()));
         }
+        self.hex_search();
     }
 
-    pub fn add_history(
-        &mut self,
-        method: String,
-        url: String,
-        duration: u128,
-        status: u16,
-        body: Option<String>,
-        headers: std::collections::HashMap<String, String>,
-        response_bytes: Option<Vec<u8>>,
-        is_binary: bool,
-    ) {
-        let log = RequestLog {
-            method,
-            url,
-            status,
-            latency: duration,
-            body,
-            headers,
-            response_bytes,
-            is_binary,
+    /// Runs the hex pane's query again, e.g. after Enter or when new bytes arrive
+    pub fn hex_search(&mut self) {
+        let bytes = self.hex_bytes().map(<[u8]>::to_vec).unwrap_or_default();
+        let Some(view) = self.hex_view.as_mut() else {
+            return;
         };
-        self.request_history.insert(0, log);
-        if self.request_history.len() > 50 {
-            self.request_history.pop();
+        if view.query.is_empty() {
+            view.matches.clear();
+            return;
+        }
+        view.search(&bytes);
+        if view.matches.is_empty() {
+            let query = view.query.clone();
+            self.show_notification(format!("No match for {}", query));
         }
-        self.save_history();
     }
 
-    fn load_history() -> Vec<RequestLog> {
-        if let Ok(content) = std::fs::read_to_string("history.json")
-            && let Ok(history) = serde_json::from_str(&content)
-        {
-            return history;
+    /// Re-reads keymap.toml, keeping the current keys if it doesn't parse
+    pub fn reload_keymap(&mut self) {
+        match crate::domain::keymap::Keymap::load(
+            &self.workspace.path(crate::domain::keymap::KEYMAP_FILE),
+        ) {
+            Ok(keymap) => {
+                self.show_success(format!("Keymap reloaded ({} profile)", keymap.profile));
+                self.keymap = keymap;
+            }
+            Err(e) => self.show_error(format!("Keymap not reloaded: {}", e)),
         }
-        Vec::new()
     }
 
-    fn save_history(&self) {
-        if let Ok(json) = serde_json::to_string_pretty(&self.request_history) {
-            let _ = std::fs::write("history.json", json);
+    fn doctor_probe(&self) -> crate::features::doctor::Probe {
+        let protocol = self
+            .image_picker
+            .as_ref()
+            .map(|p| format!("{:?}", p.protocol_type()));
+        crate::features::doctor::Probe::detect(protocol, &self.workspace)
+    }
+
+    /// Runs every doctor check (including the proxy) and opens the report
+    pub fn run_doctor(&mut self) {
+        let checks = crate::features::doctor::run(&self.doctor_probe(), true);
+        self.doctor_report = Some(checks);
+        self.doctor_scroll = 0;
+    }
+
+    /// Grades the active response's security headers, cookies and CORS, and for HTTPS
+    /// the TLS connection, which is probed again for the details
+    pub fn run_audit(&mut self) {
+        use crate::features::audit;
+        let tab = self.active_tab();
+        if tab.status_code.is_none() {
+            self.show_notification(
+                "Send the request first, the audit looks at the response".to_string(),
+            );
+            return;
         }
+        let url = self.process_url();
+        let Ok(parsed) = reqwest::Url::parse(&url) else {
+            self.show_error(format!("Can't audit '{}': not a URL", url));
+            return;
+        };
+        let tls = (parsed.scheme() == "https").then(|| match parsed.host_str() {
+            Some(host) => audit::probe_tls(
+                host.trim_start_matches('[').trim_end_matches(']'),
+                parsed.port_or_known_default().unwrap_or(443),
+                audit::TLS_TIMEOUT,
+            ),
+            None => Err("no host in the URL".to_string()),
+        });
+        let origin = tab
+            .request_headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("origin"))
+            .map(|(_, v)| self.process_text(v));
+        let report = audit::audit(
+            &url,
+            &tab.response_headers,
+            &tab.response_cookies,
+            origin.as_deref(),
+            tls,
+        );
+        self.audit_report = Some(report);
+        self.audit_scroll = 0;
     }
 
-    pub fn toggle_diff_selection(&mut self, history_index: usize) {
-        if let Some(base) = self.diff_base_index {
-            if base == history_index {
-                // Deselect if same
-                self.diff_base_index = None;
-                self.diff_target_index = None;
-                self.show_diff_view = false;
-            } else {
-                // Set target and show diff
-                self.diff_target_index = Some(history_index);
-                self.show_diff_view = true;
-                self.diff_list_state.select(Some(0));
+    /// `:coldiff <old> [new]`: each a collection name or .hcl file, optionally at a
+    /// git revision (`HEAD~1:users`); `new` defaults to the working copy of `old`
+    pub fn collection_diff_command(&mut self, args: &str) {
+        let args = crate::features::command::tokenize(args);
+        let Some(old) = args.first() else {
+            self.show_error("Usage: coldiff <old> [new], e.g. coldiff HEAD:users".to_string());
+            return;
+        };
+        let dir = self.workspace.path("collections");
+        match crate::features::collection_diff::compare_sources(
+            old,
+            args.get(1).map(String::as_str),
+            std::path::Path::new(&dir),
+        ) {
+            Ok(report) => {
+                self.collection_diff = Some(report);
+                self.collection_diff_scroll = 0;
             }
-        } else {
-            self.diff_base_index = Some(history_index);
+            Err(e) => self.show_error(e),
         }
     }
 
-    fn load_config() -> AppConfig {
-        if let Ok(content) = std::fs::read_to_string("config.json")
-            && let Ok(config) = serde_json::from_str(&content)
-        {
-            return config;
+    /// Puts the tab back exactly as it was at the selected send
+    pub fn restore_timeline_entry(&mut self) {
+        let Some(entry) = self
+            .timeline_state
+            .selected()
+            .and_then(|i| self.timeline_entry(i))
+            .cloned()
+        else {
+            return;
+        };
+        entry.request.restore(self.active_tab_mut());
+        self.show_timeline = false;
+        self.show_success(format!(
+            "Restored {} {} as sent {}",
+            entry.request.method,
+            entry.request.url,
+            entry.age()
+        ));
+    }
+
+    /// Re-sends the current request and diffs the new response against the one shown now
+    pub fn resend_and_compare(&mut self) {
+        let tab = self.active_tab_mut();
+        if tab.is_loading {
+            self.show_notification("Request already in flight".to_string());
+            return;
         }
-        AppConfig::default()
+        let Some(body) = tab.response.clone().filter(|_| !tab.response_is_binary) else {
+            self.show_error("Nothing to compare yet, send the request first".to_string());
+            return;
+        };
+        tab.compare_baseline = Some(ResponseSnapshot {
+            status: tab.status_code,
+            latency: tab.latency,
+            body,
+        });
+        tab.resend_requested = true;
     }
 
-    pub fn save_config(&self) {
-        let config = AppConfig {
-            theme_index: self.theme_index,
-            selected_env_index: self.selected_env_index,
-            zen_mode: self.zen_mode,
+    /// Builds the comparison once the re-sent response is in
+    pub fn finish_compare(&mut self) {
+        let tab = self.active_tab_mut();
+        let Some(baseline) = tab.compare_baseline.take() else {
+            return;
         };
-        if let Ok(json) = serde_json::to_string_pretty(&config) {
-            let _ = std::fs::write("config.json", json);
-        }
+        let new_body = tab.response.clone().unwrap_or_default();
+        let comparison = ResponseComparison {
+            old_status: baseline.status,
+            new_status: tab.status_code,
+            old_latency: baseline.latency,
+            new_latency: tab.latency,
+            body: crate::features::json_diff::diff_bodies(&baseline.body, &new_body),
+        };
+        self.response_compare = Some(comparison);
+        self.response_compare_scroll = 0;
     }
 
-    fn load_cookies() -> std::collections::HashMap<String, Vec<String>> {
-        if let Ok(content) = std::fs::read_to_string("cookies.json")
-            && let Ok(cookies) = serde_json::from_str(&content)
-        {
-            return cookies;
-        }
-        std::collections::HashMap::new()
+    /// Header and body changes between the two selected history entries
+    pub fn structural_history_diff(
+        &self,
+    ) -> Option<(
+        Vec<crate::features::json_diff::JsonChange>,
+        crate::features::json_diff::BodyDiff,
+    )> {
+        let base = self.request_history.get(self.diff_base_index?)?;
+        let target = self.request_history.get(self.diff_target_index?)?;
+        Some((
+            crate::features::json_diff::diff_headers(&base.headers, &target.headers),
+            crate::features::json_diff::diff_bodies(
+                base.body.as_deref().unwrap_or(""),
+                target.body.as_deref().unwrap_or(""),
+            ),
+        ))
     }
 
-    fn save_cookies(&self) {
-        if let Ok(json) = serde_json::to_string_pretty(&self.cookie_jar) {
-            let _ = std::fs::write("cookies.json", json);
+    pub fn toggle_diff_json_mode(&mut self) {
+        self.diff_json_mode = !self.diff_json_mode;
+        self.diff_list_state.select(Some(0));
+    }
+
+    /// Key binding sections that apply to what's on screen right now
+    pub fn key_contexts(&self) -> Vec<KeyContext> {
+        if self.help_show_all {
+            return crate::domain::keymap::all_contexts();
+        }
+
+        let mut contexts = vec![KeyContext::Global];
+        if self.runner_mode {
+            contexts.push(KeyContext::Runner);
+            return contexts;
+        }
+        if self.mock_mode {
+            contexts.push(KeyContext::Mock);
+            if self.mock_editor.is_some() {
+                contexts.push(KeyContext::MockEditor);
+            }
+            return contexts;
+        }
+        if self.show_diff_view {
+            contexts.push(KeyContext::Diff);
+            return contexts;
+        }
+
+        let tab = self.active_tab();
+        contexts.push(KeyContext::Tabs);
+        if tab.app_mode == AppMode::WebSocket {
+            contexts.extend([KeyContext::WebSocket, KeyContext::Modes]);
+            return contexts;
+        }
+        if self.active_sidebar {
+            contexts.push(KeyContext::Sidebar);
+            return contexts;
+        }
+
+        contexts.extend([
+            KeyContext::Navigation,
+            KeyContext::Request,
+            KeyContext::TextInput,
+        ]);
+        match tab.selected_tab {
+            0 | 1 | 4 => contexts.push(KeyContext::Items),
+            2 if tab.body_type == BodyType::Grpc => {
+                contexts.extend([KeyContext::Body, KeyContext::Grpc])
+            }
+            2 if matches!(tab.body_type, BodyType::FormData | BodyType::UrlEncoded) => {
+                contexts.extend([KeyContext::Body, KeyContext::Items])
+            }
+            2 => contexts.push(KeyContext::Body),
+            3 => contexts.push(KeyContext::Auth),
+            _ => {}
         }
+        contexts.extend([
+            KeyContext::Response,
+            KeyContext::Codegen,
+            KeyContext::Scripts,
+            KeyContext::Modes,
+        ]);
+        contexts
+    }
+
+    pub fn close_help(&mut self) {
+        self.show_help = false;
+        self.help_scroll = 0;
+        self.help_query.clear();
+        self.help_search_active = false;
+        self.help_show_all = false;
     }
 
     pub fn close_diff(&mut self) {
@@ -871,44 +6160,247 @@ impl App {
         self.diff_target_index = None;
     }
 
-    pub fn cycle_method(&mut self) {
-        let methods = ["GET", "POST", "PUT", "DELETE", "PATCH"];
-        let tab = self.active_tab_mut();
-        let current_pos = methods.iter().position(|&m| m == tab.method).unwrap_or(0);
-        let next = (current_pos + 1) % methods.len();
-        tab.method = methods[next].to_string();
+    pub fn cycle_method(&mut self) {
+        let methods = ["GET", "POST", "PUT", "DELETE", "PATCH"];
+        let tab = self.active_tab_mut();
+        let current_pos = methods.iter().position(|&m| m == tab.method).unwrap_or(0);
+        let next = (current_pos + 1) % methods.len();
+        tab.method = methods[next].to_string();
+    }
+
+    pub fn trigger_editor(&mut self) {
+        self.editor_mode = EditorMode::Body;
+    }
+
+    pub fn trigger_header_editor(&mut self) {
+        self.editor_mode = EditorMode::Headers;
+    }
+
+    pub fn show_notification(&mut self, msg: String) {
+        self.notifications.push(msg, NotificationLevel::Info);
+    }
+
+    pub fn show_success(&mut self, msg: String) {
+        self.notifications.push(msg, NotificationLevel::Success);
+    }
+
+    pub fn show_error(&mut self, msg: String) {
+        // Notifications fade, the log keeps them
+        tracing::error!(
+            tab = self.active_tab().name,
+            request_id = self.active_tab().request_id,
+            "{}",
+            msg
+        );
+        self.notifications.push(msg, NotificationLevel::Error);
+    }
+
+    /// `:describe` edits the request's description, `:describe <collection>` the
+    /// collection's, both in the external editor
+    pub fn describe_command(&mut self, args: &str) {
+        let name = args.trim().trim_matches('"');
+        if name.is_empty() {
+            self.editor_mode = EditorMode::Description;
+            return;
+        }
+        match self
+            .collections
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(name))
+        {
+            Some(index) => self.editor_mode = EditorMode::CollectionDescription(index),
+            None => self.show_error(format!("No collection named '{}'", name)),
+        }
+    }
+
+    /// Saves an edited collection description next to the collection file
+    pub fn set_collection_description(&mut self, index: usize, text: String) {
+        let Some(name) = self.collections.get(index).map(|c| c.name.clone()) else {
+            return;
+        };
+        match Collection::save_description(&self.workspace.path("collections"), &name, &text) {
+            Ok(path) => {
+                self.collections[index].description = Some(text).filter(|t| !t.trim().is_empty());
+                self.show_success(format!("Description saved to {}", path.display()));
+            }
+            Err(e) => self.show_error(format!("Description not saved: {}", e)),
+        }
+    }
+
+    /// `:snapshot [save|check|ignore <path>|unignore <path>|delete]` for the open
+    /// saved request. Saving keeps the ignored paths of the snapshot it replaces.
+    pub fn snapshot_command(&mut self, args: &str) {
+        let tab = self.active_tab();
+        let (Some(collection), Some(name)) = (tab.collection.clone(), tab.request_name.clone())
+        else {
+            self.show_error(
+                "Snapshots are kept per saved request: open one from a collection first"
+                    .to_string(),
+            );
+            return;
+        };
+        let Some(index) = self.collections.iter().position(|c| c.name == collection) else {
+            self.show_error(format!("Collection '{}' isn't loaded", collection));
+            return;
+        };
+        let (action, arg) = args
+            .trim()
+            .split_once(char::is_whitespace)
+            .map(|(a, rest)| (a, rest.trim()))
+            .unwrap_or((args.trim(), ""));
+        let mut snapshots = self.collections[index].snapshots.clone();
+        let message = match (action, snapshots.get_mut(&name)) {
+            ("" | "save", existing) => {
+                let tab = self.active_tab();
+                let (Some(status), Some(body)) = (tab.status_code, tab.body_text()) else {
+                    self.show_error("No text response to snapshot yet".to_string());
+                    return;
+                };
+                let ignore = existing.map(|s| s.ignore.clone()).unwrap_or_default();
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default();
+                snapshots.insert(
+                    name.clone(),
+                    crate::features::snapshot::capture(status, &body, ignore, now),
+                );
+                format!("Snapshot of '{}' saved", name)
+            }
+            ("check", Some(_)) => {
+                self.check_snapshot();
+                return;
+            }
+            ("ignore", Some(snapshot)) if !arg.is_empty() => {
+                if !snapshot.ignore.iter().any(|p| p == arg) {
+                    snapshot.ignore.push(arg.to_string());
+                }
+                format!(
+                    "Snapshot of '{}' ignores {}",
+                    name,
+                    snapshot.ignore.join(", ")
+                )
+            }
+            ("unignore", Some(snapshot)) if !arg.is_empty() => {
+                if !snapshot.ignore.iter().any(|p| p == arg) {
+                    self.show_error(format!("{} isn't ignored", arg));
+                    return;
+                }
+                snapshot.ignore.retain(|p| p != arg);
+                format!("Snapshot of '{}' compares {} again", name, arg)
+            }
+            ("delete", Some(_)) => {
+                snapshots.remove(&name);
+                format!("Snapshot of '{}' deleted", name)
+            }
+            ("check" | "ignore" | "unignore" | "delete", None) => {
+                self.show_error(format!(
+                    "No snapshot of '{}' yet, :snapshot saves one",
+                    name
+                ));
+                return;
+            }
+            _ => {
+                self.show_error(
+                    "Usage: :snapshot [save|check|ignore <path>|unignore <path>|delete]"
+                        .to_string(),
+                );
+                return;
+            }
+        };
+        let dir = self.workspace.path("collections");
+        match crate::domain::snapshot::save(&dir, &collection, &snapshots) {
+            Ok(path) => {
+                self.collections[index].snapshots = snapshots;
+                self.check_snapshot();
+                self.show_success(format!("{} ({})", message, path.display()));
+            }
+            Err(e) => self.show_error(format!("Snapshot not saved: {}", e)),
+        }
+    }
+
+    /// The open request's snapshot, if it has one
+    pub fn current_snapshot(&self) -> Option<&crate::domain::snapshot::Snapshot> {
+        let tab = self.active_tab();
+        self.collections
+            .iter()
+            .find(|c| Some(&c.name) == tab.collection.as_ref())?
+            .snapshots
+            .get(tab.request_name.as_ref()?)
     }
 
-    pub fn trigger_editor(&mut self) {
-        self.editor_mode = EditorMode::Body;
+    /// Compares the response with the request's snapshot, the outcome going in
+    /// with the tab's other test results (in place of the last comparison's)
+    pub fn check_snapshot(&mut self) {
+        let tab = self.active_tab();
+        let tests = match (self.current_snapshot(), tab.status_code, tab.body_text()) {
+            (Some(snapshot), Some(status), Some(body)) => {
+                crate::features::snapshot::tests(snapshot, status, &body)
+            }
+            _ => Vec::new(),
+        };
+        let failed = tests.iter().filter(|(_, passed)| !passed).count();
+        let tab = self.active_tab_mut();
+        tab.test_results
+            .retain(|(name, _)| !crate::features::snapshot::is_snapshot_test(name));
+        let checked = !tests.is_empty();
+        tab.test_results.extend(tests);
+        if failed > 0 {
+            self.show_error(format!(
+                "Response drifted from its snapshot ({} difference{}, see Tests)",
+                failed,
+                if failed == 1 { "" } else { "s" }
+            ));
+        } else if checked {
+            self.show_notification("Response matches its snapshot".to_string());
+        }
     }
 
-    pub fn trigger_header_editor(&mut self) {
-        self.editor_mode = EditorMode::Headers;
+    /// `K`: the Docs sub-tab (the request's description) in place of the response
+    pub fn toggle_docs(&mut self) {
+        let tab = self.active_tab_mut();
+        tab.show_docs = !tab.show_docs;
+        tab.response_scroll = (0, 0);
+        if tab.show_docs && tab.description.trim().is_empty() {
+            self.show_notification("No description yet, :describe writes one".to_string());
+        }
     }
 
-    pub fn show_notification(&mut self, msg: String) {
-        self.popup_message = Some(msg);
-        self.notification_time = Some(std::time::Instant::now());
+    pub fn generate_docs(&mut self) {
+        let settings = self.docs_settings.clone();
+        self.write_docs(&settings, None);
     }
 
-    pub fn generate_docs(&mut self) {
-        let md_res = crate::features::doc_gen::save_docs(&self.collections);
-        let html_res = crate::features::doc_gen::save_html_docs(&self.collections);
+    /// `:docs [collection] [split] [examples] [out <dir>] [template <file>]`,
+    /// the saved `docs` settings changed for this run only
+    pub fn docs_command(&mut self, args: &str) {
+        match crate::features::doc_gen::parse_args(args, &self.docs_settings) {
+            Ok((settings, only)) => self.write_docs(&settings, only.as_deref()),
+            Err(e) => self.show_error(e),
+        }
+    }
 
-        match (md_res, html_res) {
-            (Ok(md_path), Ok(html_path)) => {
-                self.show_notification(format!("Docs Generated: {}, {}", md_path, html_path))
-            }
-            (Ok(md_path), Err(_)) => {
-                self.show_notification(format!("Docs Generated: {} (HTML failed)", md_path))
-            }
-            (Err(_), Ok(html_path)) => {
-                self.show_notification(format!("Docs Generated: {} (MD failed)", html_path))
-            }
-            (Err(e1), Err(e2)) => {
-                self.show_notification(format!("Docs Error: MD:{}, HTML:{}", e1, e2))
+    fn write_docs(
+        &mut self,
+        settings: &crate::features::doc_gen::DocsSettings,
+        only: Option<&str>,
+    ) {
+        let collections: Vec<Collection> = match only {
+            Some(name) => match self.collections.iter().find(|c| c.name == name) {
+                Some(col) => vec![col.clone()],
+                None => {
+                    self.show_error(format!("No collection named '{}'", name));
+                    return;
+                }
+            },
+            None => self.collections.clone(),
+        };
+        match crate::features::doc_gen::generate(&collections, &self.request_history, settings) {
+            Ok(paths) => {
+                let names: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+                self.show_success(format!("Docs Generated: {}", names.join(", ")))
             }
+            Err(e) => self.show_error(format!("Docs Error: {}", e)),
         }
     }
 
@@ -916,18 +6408,103 @@ impl App {
         if self.mock_server_running {
             return;
         }
-        let handle = crate::net::mock_server::start_mock_server(
+        let acceptor = match &self.mock_settings.tls {
+            Some(tls) if tls.enabled => {
+                // Certificate paths are relative to mocks.hcl
+                let mocks = self.workspace.path(crate::net::mock_server::MOCKS_FILE);
+                let dir = std::path::Path::new(&mocks)
+                    .parent()
+                    .unwrap_or(std::path::Path::new("."));
+                let made = crate::net::mock_tls::identity(dir, tls).and_then(|identity| {
+                    crate::net::mock_tls::acceptor(&identity.cert, &identity.key)
+                        .map(|acceptor| (acceptor, identity.created))
+                });
+                match made {
+                    Ok((acceptor, created)) => {
+                        if let Some(path) = created {
+                            self.show_notification(format!(
+                                "Self-signed certificate saved to {}",
+                                path.display()
+                            ));
+                        }
+                        Some(acceptor)
+                    }
+                    Err(e) => {
+                        self.show_error(format!("Mock HTTPS: {}", e));
+                        return;
+                    }
+                }
+            }
+            _ => None,
+        };
+        let handle = crate::net::mock_server::start_mock_server_with(
             self.mock_server_port,
             self.mock_routes.clone(),
+            &self.mock_settings,
+            acceptor,
         );
         self.mock_server_handle = Some(handle);
         self.mock_server_running = true;
         self.show_notification(format!(
-            "Mock Server Starting on port {}",
-            self.mock_server_port
+            "Mock Server Starting on {} (CORS {})",
+            self.mock_settings.base_url(self.mock_server_port),
+            if self.mock_settings.cors_on() {
+                "on"
+            } else {
+                "off"
+            }
+        ));
+    }
+
+    /// Turns CORS on the mock server on or off, live, and saves it to mocks.hcl
+    pub fn toggle_mock_cors(&mut self) {
+        let cors = match &mut self.mock_settings.cors {
+            Some(cors) => {
+                cors.enabled = !cors.enabled;
+                cors.clone()
+            }
+            none => none.insert(Default::default()).clone(),
+        };
+        if let Some(handle) = &self.mock_server_handle {
+            handle.update_cors(Some(cors.clone()));
+        }
+        self.save_mock_settings();
+        self.show_notification(format!(
+            "Mock CORS {}",
+            if cors.enabled { "on" } else { "off" }
         ));
     }
 
+    /// Switches the mock server between HTTP and HTTPS, restarting it if it runs
+    pub fn toggle_mock_tls(&mut self) {
+        match &mut self.mock_settings.tls {
+            Some(tls) => tls.enabled = !tls.enabled,
+            none => *none = Some(Default::default()),
+        }
+        self.save_mock_settings();
+        if self.mock_server_running {
+            if let Some(handle) = self.mock_server_handle.take() {
+                handle.handle.abort();
+            }
+            self.mock_server_running = false;
+            self.start_mock_server();
+        } else {
+            self.show_notification(format!(
+                "Mock server will serve {}",
+                self.mock_settings.base_url(self.mock_server_port)
+            ));
+        }
+    }
+
+    fn save_mock_settings(&mut self) {
+        if let Err(e) = crate::net::mock_server::save_settings(
+            &self.workspace.path(crate::net::mock_server::MOCKS_FILE),
+            &self.mock_settings,
+        ) {
+            self.show_error(format!("Failed to save mocks: {}", e));
+        }
+    }
+
     pub fn stop_mock_server(&mut self) {
         if let Some(handle) = self.mock_server_handle.take() {
             handle.handle.abort();
@@ -944,10 +6521,63 @@ impl App {
         }
     }
 
-    pub fn restart_mock_server_if_running(&mut self) {
-        if self.mock_server_running {
-            self.stop_mock_server();
-            self.start_mock_server();
+    /// Saves routes to `mocks.hcl` and pushes them into the running server
+    pub fn mock_routes_changed(&mut self) {
+        if let Some(handle) = &self.mock_server_handle {
+            handle.update_routes(self.mock_routes.clone());
+        }
+        if let Err(e) = crate::net::mock_server::save_routes(
+            &self.workspace.path(crate::net::mock_server::MOCKS_FILE),
+            &self.mock_routes,
+        ) {
+            self.show_error(format!("Failed to save mocks: {}", e));
+        }
+    }
+
+    pub fn open_mock_editor(&mut self, index: Option<usize>) {
+        self.mock_editor = match index.and_then(|i| self.mock_routes.get(i).map(|r| (i, r))) {
+            Some((i, route)) => Some(MockRouteEditor::from_route(i, route)),
+            None => Some(MockRouteEditor::new_route()),
+        };
+    }
+
+    pub fn save_mock_editor(&mut self) {
+        let Some(editor) = &self.mock_editor else {
+            return;
+        };
+        let route = match editor.to_route() {
+            Ok(route) => route,
+            Err(e) => {
+                self.show_error(e);
+                return;
+            }
+        };
+        let selected = match editor.index.filter(|i| *i < self.mock_routes.len()) {
+            Some(i) => {
+                self.mock_routes[i] = route;
+                i
+            }
+            None => {
+                self.mock_routes.push(route);
+                self.mock_routes.len() - 1
+            }
+        };
+        self.mock_editor = None;
+        self.mock_list_state.select(Some(selected));
+        self.mock_routes_changed();
+        self.show_success("Mock route saved".to_string());
+    }
+
+    pub fn delete_mock_route(&mut self, index: usize) {
+        if index < self.mock_routes.len() {
+            self.mock_routes.remove(index);
+            if self.mock_routes.is_empty() {
+                self.mock_list_state.select(None);
+            } else {
+                self.mock_list_state
+                    .select(Some(index.min(self.mock_routes.len() - 1)));
+            }
+            self.mock_routes_changed();
         }
     }
 
@@ -956,33 +6586,186 @@ impl App {
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs();
-        let name = format!("Saved Request {}", timestamp);
-
         let tab = self.active_tab();
-        let body_type_str = match tab.body_type {
-            BodyType::Raw => "Raw",
-            BodyType::FormData => "FormData",
-            BodyType::GraphQL => "GraphQL",
-            BodyType::Grpc => "Grpc",
+        let name = if tab.has_default_name() {
+            format!("Saved Request {}", timestamp)
+        } else {
+            tab.name.clone()
         };
 
-        if let Err(e) = Collection::save_to_file(
+        if let Err(e) = Collection::append_request(
+            &self.workspace.path("collections"),
+            "saved",
             &name,
-            &tab.method,
-            &tab.url,
-            &tab.request_body,
-            &tab.request_headers,
-            &tab.extract_rules,
-            &tab.form_data,
-            body_type_str,
-            &tab.graphql_query,
-            &tab.graphql_variables,
-            &tab.pre_request_script,
-            &tab.post_request_script,
+            &tab.to_request_config(),
         ) {
-            self.show_notification(format!("Save Failed: {}", e));
+            self.show_error(format!("Save Failed: {}", e));
+        } else {
+            self.show_success("Saved to collections/saved.hcl (Restart to view)".to_string());
+        }
+    }
+
+    /// Collection entries for every open tab. Names get a zero-padded position
+    /// prefix so the sidebar (which sorts by name) keeps the tab order.
+    pub fn tabs_as_requests(&self) -> Vec<(String, RequestConfig)> {
+        let width = self.tabs.len().to_string().len().max(2);
+        self.tabs
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| {
+                let label = format!("{:0width$} {}", i + 1, tab.name, width = width);
+                (label, tab.to_request_config())
+            })
+            .collect()
+    }
+
+    /// `:dedupe [collection]`: requests with the same method and URL, side by side
+    pub fn dedupe_command(&mut self, args: &str) {
+        let name = args.trim().trim_matches('"');
+        let collection = if name.is_empty() {
+            None
         } else {
-            self.show_notification("Saved to collections/saved.hcl (Restart to view)".to_string());
+            match self
+                .collections
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(name))
+            {
+                Some(c) => Some(c.name.clone()),
+                None => {
+                    self.show_error(format!("No collection named '{}'", name));
+                    return;
+                }
+            }
+        };
+        let groups = self.duplicate_groups(collection.as_deref());
+        if groups.is_empty() {
+            self.show_notification("No duplicate requests".to_string());
+            return;
+        }
+        self.dedupe = Some(crate::features::dedupe::DedupeView {
+            collection,
+            groups,
+            group: 0,
+            other: 1,
+            scroll: 0,
+        });
+    }
+
+    fn duplicate_groups(&self, collection: Option<&str>) -> Vec<crate::features::dedupe::Group> {
+        match collection {
+            Some(name) => crate::features::dedupe::find_duplicates(
+                &self
+                    .collections
+                    .iter()
+                    .filter(|c| c.name == name)
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            ),
+            None => crate::features::dedupe::find_duplicates(&self.collections),
+        }
+    }
+
+    fn saved_request(&self, member: &crate::features::dedupe::Member) -> Option<&RequestConfig> {
+        self.collections
+            .iter()
+            .find(|c| c.name == member.collection)?
+            .requests
+            .get(&member.request)
+    }
+
+    /// The left and right requests of the dedupe view
+    pub fn dedupe_pair(&self) -> Option<(RequestConfig, RequestConfig)> {
+        let (keep, other) = self.dedupe.as_ref()?.pair()?;
+        Some((
+            self.saved_request(keep)?.clone(),
+            self.saved_request(other)?.clone(),
+        ))
+    }
+
+    /// Merges the right-hand duplicate into the one kept, then deletes it from its file
+    pub fn dedupe_merge(&mut self) {
+        let Some((keep, other)) = self.dedupe.as_ref().and_then(|v| v.pair()) else {
+            return;
+        };
+        let (keep, other) = (keep.clone(), other.clone());
+        let Some((left, right)) = self.dedupe_pair() else {
+            return;
+        };
+        let merged = crate::features::dedupe::merge(&left, &right);
+        let dir = self.workspace.path("collections");
+        let result = Collection::replace_request(&dir, &keep.collection, &keep.request, &merged)
+            .and_then(|_| Collection::remove_request(&dir, &other.collection, &other.request));
+        match result {
+            Ok(_) => {
+                self.show_success(format!("Merged {} into {}", other.path(), keep.path()));
+                self.refresh_dedupe();
+            }
+            Err(e) => self.show_error(format!("Merge failed: {}", e)),
+        }
+    }
+
+    /// Deletes the right-hand duplicate from its collection file
+    pub fn dedupe_delete(&mut self) {
+        let Some(other) = self
+            .dedupe
+            .as_ref()
+            .and_then(|v| v.pair())
+            .map(|(_, other)| other.clone())
+        else {
+            return;
+        };
+        let dir = self.workspace.path("collections");
+        match Collection::remove_request(&dir, &other.collection, &other.request) {
+            Ok(_) => {
+                self.show_success(format!("Deleted {}", other.path()));
+                self.refresh_dedupe();
+            }
+            Err(e) => self.show_error(format!("Delete failed: {}", e)),
+        }
+    }
+
+    /// Reloads the collections after a merge or delete and finds the duplicates again,
+    /// staying on the same group where there still is one
+    fn refresh_dedupe(&mut self) {
+        if let Ok(cols) = Collection::load_from_dir(&self.workspace.path("collections")) {
+            self.collections = cols;
+        }
+        let Some(view) = self.dedupe.as_ref() else {
+            return;
+        };
+        let collection = view.collection.clone();
+        let position = view.group;
+        let groups = self.duplicate_groups(collection.as_deref());
+        if groups.is_empty() {
+            self.dedupe = None;
+            self.show_success("No duplicates left".to_string());
+            return;
+        }
+        let group = position.min(groups.len() - 1);
+        self.dedupe = Some(crate::features::dedupe::DedupeView {
+            collection,
+            groups,
+            group,
+            other: 1,
+            scroll: 0,
+        });
+    }
+
+    /// Saves all open tabs as a new collection and reloads the sidebar
+    pub fn save_tabs_as_collection(&mut self, name: &str) {
+        let requests = self.tabs_as_requests();
+        match Collection::write_new(&self.workspace.path("collections"), name, &requests) {
+            Ok(path) => {
+                if let Ok(cols) = Collection::load_from_dir(&self.workspace.path("collections")) {
+                    self.collections = cols;
+                }
+                self.show_success(format!(
+                    "Saved {} tabs to {}",
+                    requests.len(),
+                    path.display()
+                ));
+            }
+            Err(e) => self.show_error(format!("Save Failed: {}", e)),
         }
     }
 
@@ -1005,98 +6788,510 @@ impl App {
         self.collection_state.select(Some(i));
     }
 
-    pub fn previous_collection_item(&mut self) {
-        let total_items = self.flattened_count();
-        if total_items == 0 {
+    pub fn previous_collection_item(&mut self) {
+        let total_items = self.flattened_count();
+        if total_items == 0 {
+            return;
+        }
+
+        let i = match self.collection_state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    total_items - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => 0,
+        };
+        self.collection_state.select(Some(i));
+    }
+
+    pub fn load_selected_request(&mut self) {
+        if let Some(idx) = self.collection_state.selected() {
+            let collection_count = self.flattened_collection_only_count();
+
+            if idx > 0 && idx <= collection_count {
+                let req_config =
+                    self.get_request_at_visual_index(idx)
+                        .map(|(collection, name, request)| {
+                            (collection.name.clone(), name.clone(), request.clone())
+                        });
+
+                if let Some((collection, name, config)) = req_config {
+                    self.apply_request_config(config);
+                    self.active_tab_mut().collection = Some(collection);
+                    self.active_tab_mut().request_name = Some(name);
+                }
+            } else if idx > collection_count + 2 {
+                let history_idx = idx - (collection_count + 3);
+                self.restore_history_entry(history_idx);
+            }
+        }
+    }
+
+    /// Puts a history entry's request and response back into the active tab
+    pub fn restore_history_entry(&mut self, index: usize) {
+        let Some(log) = self.request_history.get(index).cloned() else {
+            return;
+        };
+        let tab = self.active_tab_mut();
+        tab.method = log.method.clone();
+        tab.url = log.url.clone();
+        // gRPC entries come back as the call: address, method, proto and payload
+        if let Some(request) = log
+            .request
+            .as_ref()
+            .filter(|r| r.body_type.as_deref() == Some("Grpc"))
+        {
+            tab.body_type = BodyType::Grpc;
+            tab.method = request.method.clone();
+            tab.url = request.url.clone();
+            tab.grpc_service = request.grpc_service.clone().unwrap_or_default();
+            tab.grpc_method.clear();
+            tab.grpc_proto_path = request.grpc_proto_path.clone().unwrap_or_default();
+            tab.request_body = request.body.clone().unwrap_or_default();
+        }
+        tab.status_code = Some(log.status);
+        tab.latency = Some(log.latency);
+        tab.grpc_metadata = None;
+        tab.trace = None;
+        tab.forget_request();
+
+        tab.response = log.body.clone();
+        tab.response_headers = log.headers.clone();
+        tab.response_bytes = log.response_bytes.clone();
+        tab.response_is_binary = log.is_binary;
+        tab.reinterpret_response();
+
+        self.show_notification("Restored from history".to_string());
+    }
+
+    /// The requests the dashboard runs, by collection, with the default headers in
+    pub fn dashboard_checks(&self) -> Vec<Collection> {
+        let defaults = self.collection_default_headers();
+        crate::features::dashboard::health_checks(&self.collections, &self.dashboard_settings.tag)
+            .into_iter()
+            .map(|c| c.with_default_headers(&defaults))
+            .collect()
+    }
+
+    /// `:dashboard [seconds]`: opens the health board and starts its checks
+    pub fn dashboard_command(&mut self, args: &str) {
+        if !args.is_empty() {
+            match args.parse::<u64>() {
+                Ok(secs) if secs > 0 => {
+                    self.dashboard_settings.interval_secs = secs;
+                    self.save_config();
+                }
+                _ => {
+                    self.show_error(format!("Not an interval in seconds: {}", args));
+                    return;
+                }
+            }
+        }
+        let checks = self.dashboard_checks();
+        if checks.is_empty() {
+            self.show_error(format!(
+                "No health checks: tag requests with tags = [\"{}\"]",
+                self.dashboard_settings.tag
+            ));
+            return;
+        }
+        self.stop_dashboard();
+        self.dashboard = Some(crate::features::dashboard::Dashboard::new(&checks));
+        self.should_start_dashboard = true;
+    }
+
+    pub fn stop_dashboard(&mut self) {
+        if let Some(stop_tx) = self.dashboard.as_mut().and_then(|d| d.stop_tx.take()) {
+            let _ = stop_tx.try_send(());
+        }
+    }
+
+    /// `s` on the board: pause the checks, or run them again
+    pub fn toggle_dashboard(&mut self) {
+        match &self.dashboard {
+            Some(dashboard) if dashboard.is_running() => {
+                self.stop_dashboard();
+                self.show_notification("Health checks paused".to_string());
+            }
+            Some(_) => self.should_start_dashboard = true,
+            None => {}
+        }
+    }
+
+    pub fn close_dashboard(&mut self) {
+        self.stop_dashboard();
+        self.dashboard = None;
+        self.should_start_dashboard = false;
+    }
+
+    /// Enter on a tile: the request in the tab, to look into what's wrong
+    pub fn open_dashboard_tile(&mut self) {
+        let Some(tile) = self
+            .dashboard
+            .as_ref()
+            .and_then(|d| d.tiles.get(d.selected))
+        else {
+            return;
+        };
+        let path = format!("{}/{}", tile.collection, tile.name);
+        self.close_dashboard();
+        self.open_request(&path);
+    }
+
+    /// `:logs [text]`, only the records mentioning `text` when given
+    pub fn open_logs(&mut self, filter: &str) {
+        self.log_view = Some(crate::features::logging::LogView {
+            filter: filter.to_string(),
+            ..Default::default()
+        });
+    }
+
+    /// `r` in the log view: only the active tab's last send, or everything again
+    pub fn toggle_log_request(&mut self) {
+        let request_id = self.active_tab().request_id.clone();
+        let Some(view) = self.log_view.as_mut() else {
+            return;
+        };
+        if view.request_id.is_some() {
+            view.request_id = None;
+        } else if request_id.is_some() {
+            view.request_id = request_id;
+            view.scroll = 0;
+        } else {
+            self.show_notification("Nothing sent from this tab yet".to_string());
+        }
+    }
+
+    /// Adds a send of the active tab to its trend, if it's a saved request
+    pub fn record_latency(&mut self, ms: u64, status: u16) {
+        let tab = self.active_tab();
+        let (Some(collection), Some(request)) = (&tab.collection, &tab.request_name) else {
+            return;
+        };
+        let key = crate::features::latency::key(collection, request);
+        self.latency_trends.record(
+            key,
+            crate::features::latency::Sample {
+                at: crate::features::history::now(),
+                ms,
+                status,
+            },
+        );
+        if let Ok(json) = serde_json::to_string(&self.latency_trends) {
+            self.write_store(crate::features::latency::FILE, json);
+        }
+    }
+
+    /// `:trend [collection/request]`, the active tab's request when left out
+    pub fn open_latency_chart(&mut self, args: &str) {
+        let key = if args.is_empty() {
+            let tab = self.active_tab();
+            match (&tab.collection, &tab.request_name) {
+                (Some(collection), Some(request)) => {
+                    crate::features::latency::key(collection, request)
+                }
+                _ => {
+                    self.show_error(
+                        "Latency trends are kept for saved requests, open one from the sidebar"
+                            .to_string(),
+                    );
+                    return;
+                }
+            }
+        } else {
+            args.to_string()
+        };
+        if self.latency_trends.samples(&key).is_empty() {
+            self.show_notification(format!("No latency recorded for {} yet", key));
+            return;
+        }
+        self.latency_chart = Some(key);
+    }
+
+    /// The active tab's grpcurl proto arguments: its own file, else the registry
+    pub fn grpc_proto_args(&self) -> Vec<String> {
+        self.proto_registry.args(
+            self.workspace.dir(),
+            Some(self.active_tab().grpc_proto_path.as_str()),
+        )
+    }
+
+    /// `:protos`, and `r` in it to look for new files
+    pub fn open_proto_view(&mut self) {
+        let files = self.registered_protos();
+        match self.proto_view.as_mut() {
+            Some(view) => view.files = files,
+            None => self.proto_view = Some(crate::features::protos::ProtoView::new(files)),
+        }
+    }
+
+    fn registered_protos(&self) -> Vec<String> {
+        self.proto_registry
+            .files(self.workspace.dir())
+            .into_iter()
+            .flat_map(|(root, files)| {
+                files
+                    .into_iter()
+                    .map(move |file| format!("{}: {}", root, file))
+            })
+            .collect()
+    }
+
+    /// Enter while typing a directory in the proto view
+    pub fn add_proto_dir(&mut self) {
+        let Some(view) = self.proto_view.as_mut() else {
+            return;
+        };
+        let Some(dir) = view.adding.take() else {
+            return;
+        };
+        let dir = dir.trim().trim_end_matches('/').to_string();
+        let section = view.section;
+        if dir.is_empty() {
+            return;
+        }
+        if !self.workspace.dir().join(&dir).is_dir() {
+            self.show_error(format!("No directory {}", dir));
+            return;
+        }
+        let list = self.proto_registry.list_mut(section);
+        if list.contains(&dir) {
+            self.show_notification(format!("{} is already registered", dir));
+            return;
+        }
+        list.push(dir);
+        let selected = list.len() - 1;
+        self.save_config();
+        let files = self.registered_protos();
+        if let Some(view) = self.proto_view.as_mut() {
+            view.selected = selected;
+            view.files = files;
+        }
+    }
+
+    /// `d` in the proto view
+    pub fn remove_proto_dir(&mut self) {
+        let Some(view) = self.proto_view.as_mut() else {
+            return;
+        };
+        let list = self.proto_registry.list_mut(view.section);
+        if view.selected >= list.len() {
+            return;
+        }
+        let removed = list.remove(view.selected);
+        view.selected = view.selected.min(list.len().saturating_sub(1));
+        self.save_config();
+        let files = self.registered_protos();
+        if let Some(view) = self.proto_view.as_mut() {
+            view.files = files;
+        }
+        self.show_notification(format!("Removed {}", removed));
+    }
+
+    /// Enter on a method in the gRPC description: call it from now on, and describe
+    /// its input message to fill the body with a payload to start from
+    pub fn use_grpc_method(&mut self) {
+        let tab = self.active_tab_mut();
+        let Some(method) = tab.grpc_methods.get(tab.grpc_method_selected).cloned() else {
             return;
+        };
+        tab.grpc_service = format!("{}/{}", tab.grpc_service_to_describe, method.name);
+        tab.grpc_method.clear();
+        tab.body_type = BodyType::Grpc;
+        tab.show_grpc_description_modal = false;
+        tab.scaffold_grpc_message = Some(method.input.clone());
+        let mut note = format!("Method: {}", method.name);
+        if method.client_streaming || method.server_streaming {
+            note.push_str(" (streaming, grpcurl sends the payload once)");
         }
+        self.show_notification(note);
+    }
 
-        let i = match self.collection_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    total_items - 1
-                } else {
-                    i - 1
-                }
+    /// Puts a payload skeleton for `message` built from its description into the body
+    pub fn apply_grpc_skeleton(&mut self, message: &str, description: Result<String, String>) {
+        let description = match description {
+            Ok(description) => description,
+            Err(e) => {
+                self.show_error(e);
+                return;
             }
-            None => 0,
         };
-        self.collection_state.select(Some(i));
+        match crate::net::grpc::skeleton(&description) {
+            Some(payload) => {
+                let tab = self.active_tab_mut();
+                tab.request_body = payload;
+                tab.body_type = BodyType::Grpc;
+                self.show_success(format!(
+                    "Body scaffolded from {}",
+                    message.trim_start_matches('.')
+                ));
+            }
+            None => self.show_error(format!(
+                "No fields found in {}, body left as is",
+                message.trim_start_matches('.')
+            )),
+        }
     }
 
-    pub fn load_selected_request(&mut self) {
-        if let Some(idx) = self.collection_state.selected() {
-            let collection_count = self.flattened_collection_only_count();
+    /// Loads a saved request into the active tab (sidebar Enter, `:open`)
+    pub fn apply_request_config(&mut self, config: RequestConfig) {
+        self.load_request_config(config);
+        let method = self.active_tab().method.clone();
+        let url = self.active_tab().url.clone();
+        self.show_notification(format!("Loaded: {} {}", method, url));
+    }
 
-            if idx > 0 && idx <= collection_count {
-                let req_config = if let Some((_, request)) = self.get_request_at_visual_index(idx) {
-                    Some(request.clone())
-                } else {
-                    None
-                };
+    /// `apply_request_config` without the toast
+    fn load_request_config(&mut self, config: RequestConfig) {
+        {
+            let tab = self.active_tab_mut();
+            tab.url = config.url;
+            tab.method = config.method;
+            tab.request_body = config.body.unwrap_or_default();
+            tab.request_headers = config.headers.unwrap_or_default();
+            tab.disabled_defaults = config.disabled_default_headers;
+            tab.disabled_headers = config.disabled_headers;
+            tab.header_draft = None;
+            // Ordered by the URL in sync_url_to_params below
+            tab.path_params = config.path_params.into_iter().collect();
+
+            tab.extract_rules = config
+                .extract
+                .map(|m| m.into_iter().collect())
+                .unwrap_or_default();
+            tab.form_data = config.form_data.unwrap_or_default();
+            tab.graphql_query = config.graphql_query.unwrap_or_default();
+            tab.graphql_variables = config.graphql_variables.unwrap_or_default();
+            tab.pre_request_script = config.pre_request_script.unwrap_or_default();
+            tab.post_request_script = config.post_request_script.unwrap_or_default();
+            tab.stress_stages = config.stress_stages.unwrap_or_default();
+            tab.timeout_ms = config
+                .timeout_ms
+                .unwrap_or(crate::net::timeouts::DEFAULT_TOTAL_MS);
+            tab.connect_timeout_ms = config.connect_timeout_ms;
+            tab.read_timeout_ms = config.read_timeout_ms;
+            tab.description = config.description.unwrap_or_default();
+            tab.variables = config.variables;
+            tab.tags = config.tags;
+            tab.paginate = config.paginate;
+            let treat_as = config.treat_as.as_deref().and_then(ResponseType::parse);
+            if tab.treat_as != treat_as {
+                tab.treat_as = treat_as;
+                tab.reinterpret_response();
+            }
+            tab.collection = None;
+            tab.request_name = None;
+            tab.dismissed_warnings.clear();
+
+            tab.grpc_service = config.grpc_service.unwrap_or_default();
+            tab.grpc_method.clear();
+            tab.grpc_proto_path = config.grpc_proto_path.unwrap_or_default();
+
+            tab.body_type = match config.body_type.as_deref() {
+                Some("FormData") => BodyType::FormData,
+                Some("UrlEncoded") => BodyType::UrlEncoded,
+                Some("GraphQL") => BodyType::GraphQL,
+                Some("Grpc") => BodyType::Grpc,
+                _ => BodyType::Raw,
+            };
+            if tab.body_type == BodyType::UrlEncoded {
+                tab.urlencoded = parse_urlencoded(&tab.request_body);
+            }
+        }
+        self.sync_url_to_params();
+    }
 
-                if let Some(config) = req_config {
-                    {
-                        let tab = self.active_tab_mut();
-                        tab.url = config.url;
-                        tab.method = config.method;
-                        tab.request_body = config.body.unwrap_or_default();
-                        tab.request_headers = config.headers.unwrap_or_default();
-
-                        tab.extract_rules = config
-                            .extract
-                            .map(|m| m.into_iter().collect())
-                            .unwrap_or_default();
-                        tab.form_data = config.form_data.unwrap_or_default();
-                        tab.graphql_query = config.graphql_query.unwrap_or_default();
-                        tab.graphql_variables = config.graphql_variables.unwrap_or_default();
-                        tab.pre_request_script = config.pre_request_script.unwrap_or_default();
-                        tab.post_request_script = config.post_request_script.unwrap_or_default();
-
-                        tab.body_type = match config.body_type.as_deref() {
-                            Some("FormData") => BodyType::FormData,
-                            Some("GraphQL") => BodyType::GraphQL,
-                            _ => BodyType::Raw,
-                        };
-                    }
-                    self.sync_url_to_params();
+    /// Opens the stress modal against a collection, or the active tab when `target` is empty
+    pub fn open_stress_modal(&mut self, target: &str) {
+        let target = target.trim().trim_matches('"');
+        if target.is_empty() {
+            self.stress_target.clear();
+        } else {
+            let Some(collection) = self
+                .collections
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(target))
+            else {
+                self.show_error(format!("No collection named '{}'", target));
+                return;
+            };
+            self.stress_target = collection.name.clone();
+        }
+        self.show_stress_modal = true;
+        self.active_tab_mut().input_mode = InputMode::EditingStressVUs;
+    }
 
-                    let method = self.active_tab().method.clone();
-                    let url = self.active_tab().url.clone();
-                    self.show_notification(format!("Loaded: {} {}", method, url));
-                }
-            } else if idx > collection_count + 2 {
-                let history_idx = idx - (collection_count + 3);
-                if history_idx < self.request_history.len()
-                    && let Some(log) = self.request_history.get(history_idx).cloned()
-                {
-                    let tab = self.active_tab_mut();
-                    tab.method = log.method.clone();
-                    tab.url = log.url.clone();
-                    tab.status_code = Some(log.status);
-                    tab.latency = Some(log.latency);
-
-                    tab.response = log.body.clone();
-                    tab.response_headers = log.headers.clone();
-                    tab.response_bytes = log.response_bytes.clone();
-                    tab.response_is_binary = log.is_binary;
-
-                    if let Some(body_text) = &log.body {
-                        if let Ok(val) = serde_json::from_str::<Value>(body_text) {
-                            let root =
-                                crate::app::JsonEntry::from_value("root".to_string(), &val, 0);
-                            tab.response_json = Some(vec![root]);
-                        } else {
-                            tab.response_json = None;
-                        }
-                    } else {
-                        tab.response_json = None;
-                    }
+    /// ←/→ on the modal's target: the active tab, then each collection
+    pub fn cycle_stress_target(&mut self, forward: bool) {
+        let mut targets = vec![String::new()];
+        targets.extend(self.collections.iter().map(|c| c.name.clone()));
+        let current = targets
+            .iter()
+            .position(|t| *t == self.stress_target)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % targets.len()
+        } else {
+            (current + targets.len() - 1) % targets.len()
+        };
+        self.stress_target = targets.swap_remove(next);
+    }
 
-                    self.popup_message = Some("Restored from history".to_string());
-                }
-            }
+    /// What the stress test sends: the active tab, or every request of the target
+    /// collection (sorted by name, as the runner does) with variables substituted
+    pub fn stress_requests(&self) -> Result<Vec<crate::features::stress::StressRequest>, String> {
+        use crate::features::stress::StressRequest;
+        if self.stress_target.is_empty() {
+            let tab = self.active_tab();
+            return Ok(vec![StressRequest {
+                name: tab.name.clone(),
+                url: self.substitute_env(&self.resolved_url()),
+                method: tab.method.clone(),
+                // Auth handling skipped, set the header yourself
+                headers: self
+                    .sent_headers()
+                    .iter()
+                    .map(|(k, v)| (k.clone(), self.substitute_env(v)))
+                    .collect(),
+                body: if tab.request_body.is_empty() {
+                    None
+                } else {
+                    Some(self.substitute_env(&tab.request_body))
+                },
+            }]);
         }
+        let collection = self
+            .collections
+            .iter()
+            .find(|c| c.name == self.stress_target)
+            .ok_or_else(|| format!("No collection named '{}'", self.stress_target))?;
+        crate::features::stress::collection_workload(
+            &collection
+                .clone()
+                .with_default_headers(&self.collection_default_headers()),
+            self.get_active_env().base_url(),
+            &|text| self.substitute_env(text),
+        )
+    }
+
+    pub fn stress_thresholds(&self) -> Result<Vec<crate::features::stress::Threshold>, String> {
+        crate::features::stress::parse_thresholds(&self.stress_thresholds_input)
+    }
+
+    /// The stress modal's VUs, duration and stages as a load profile
+    pub fn stress_stages(&self) -> Result<Vec<crate::features::stress::Stage>, String> {
+        let vus = self
+            .stress_vus_input
+            .parse()
+            .map_err(|_| "Virtual users must be a number".to_string())?;
+        let duration = self
+            .stress_duration_input
+            .parse()
+            .map_err(|_| "Duration must be a number of seconds".to_string())?;
+        crate::features::stress::parse_stages(&self.active_tab().stress_stages, vus, duration)
     }
 
     pub fn get_selected_history_index(&self) -> Option<usize> {
@@ -1124,25 +7319,123 @@ impl App {
     }
 
     fn flattened_collection_only_count(&self) -> usize {
-        self.collections.iter().map(|c| c.requests.len()).sum()
+        self.sidebar_requests().len()
     }
 
-    pub fn get_request_at_visual_index(
-        &self,
-        visual_index: usize,
-    ) -> Option<(&String, &crate::domain::collection::RequestConfig)> {
-        let mut current = 1;
+    /// The saved requests the sidebar lists, in order. The filter text keeps the
+    /// collections whose name or any request name has it; the tag filter keeps the
+    /// requests with one of its tags.
+    pub fn sidebar_requests(&self) -> Vec<(&Collection, &String, &RequestConfig)> {
+        let filter_text = self.sidebar_filter.to_lowercase();
+        let mut items = Vec::new();
         for col in &self.collections {
             let mut keys: Vec<&String> = col.requests.keys().collect();
             keys.sort();
+            let visible = filter_text.is_empty()
+                || col.name.to_lowercase().contains(&filter_text)
+                || keys.iter().any(|k| k.to_lowercase().contains(&filter_text));
+            if !visible {
+                continue;
+            }
             for key in keys {
-                if current == visual_index {
-                    return col.requests.get(key).map(|r| (key, r));
+                let request = &col.requests[key];
+                if request.has_any_tag(&self.tag_filter) {
+                    items.push((col, key, request));
                 }
-                current += 1;
             }
         }
-        None
+        items
+    }
+
+    pub fn get_request_at_visual_index(
+        &self,
+        visual_index: usize,
+    ) -> Option<(
+        &Collection,
+        &String,
+        &crate::domain::collection::RequestConfig,
+    )> {
+        self.sidebar_requests()
+            .into_iter()
+            .nth(visual_index.checked_sub(1)?)
+    }
+
+    /// `:tag add|rm <tags>` on the active request, `:tag filter [tags]` for the
+    /// sidebar and runner, `:tag` for the tags in use
+    pub fn tag_command(&mut self, args: &str) {
+        use crate::domain::collection::parse_tags;
+        let (word, rest) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+        let tags = parse_tags(rest);
+        match word {
+            "" | "list" => {
+                let mut counts: std::collections::BTreeMap<String, usize> = Default::default();
+                for tag in self
+                    .collections
+                    .iter()
+                    .flat_map(|c| c.requests.values())
+                    .flat_map(|r| r.tags.iter())
+                {
+                    *counts.entry(tag.to_lowercase()).or_default() += 1;
+                }
+                let mut msg = if counts.is_empty() {
+                    "No tagged requests".to_string()
+                } else {
+                    let list: Vec<String> = counts
+                        .iter()
+                        .map(|(tag, n)| format!("{} ({})", tag, n))
+                        .collect();
+                    format!("Tags: {}", list.join(", "))
+                };
+                if !self.tag_filter.is_empty() {
+                    msg.push_str(&format!("; showing {}", self.tag_filter.join(", ")));
+                }
+                self.show_notification(msg);
+            }
+            "add" | "rm" | "remove" if tags.is_empty() => {
+                self.show_error(format!("Usage: :tag {} <tag>[,tag]", word))
+            }
+            "add" => {
+                let tab = self.active_tab_mut();
+                for tag in tags {
+                    if !tab.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                        tab.tags.push(tag);
+                    }
+                }
+                let msg = format!("Tags: {} (saved with the request)", tab.tags.join(", "));
+                self.show_notification(msg);
+            }
+            "rm" | "remove" => {
+                let tab = self.active_tab_mut();
+                tab.tags
+                    .retain(|t| !tags.iter().any(|gone| gone.eq_ignore_ascii_case(t)));
+                let msg = if tab.tags.is_empty() {
+                    "No tags left on this request".to_string()
+                } else {
+                    format!("Tags: {}", tab.tags.join(", "))
+                };
+                self.show_notification(msg);
+            }
+            "filter" | "only" => self.set_tag_filter(tags),
+            "clear" | "off" => self.set_tag_filter(Vec::new()),
+            _ => self.show_error("Usage: :tag [add|rm <tags> | filter [tags] | clear]".to_string()),
+        }
+    }
+
+    /// Narrows the sidebar and the runner to requests with one of `tags` (all of
+    /// them when empty)
+    pub fn set_tag_filter(&mut self, tags: Vec<String>) {
+        self.tag_filter = tags;
+        self.collection_state.select(Some(0));
+        if self.tag_filter.is_empty() {
+            self.show_notification("Tag filter cleared".to_string());
+        } else {
+            let msg = format!(
+                "Showing {} requests tagged {}",
+                self.sidebar_requests().len(),
+                self.tag_filter.join(", ")
+            );
+            self.show_notification(msg);
+        }
     }
 
     pub fn guess_extension(&self) -> Option<String> {
@@ -1175,54 +7468,262 @@ impl App {
                 return Some("txt".to_string());
             }
         }
-        None
+        // GIF, WebP and the like, going by the bytes
+        self.active_tab()
+            .response_bytes
+            .as_deref()
+            .and_then(crate::features::image_view::extension)
+            .map(str::to_string)
+    }
+
+    /// A file name for the response: the server's (Content-Disposition), the
+    /// URL's last segment when it has an extension, or `response_<time>.<ext>`
+    pub fn suggested_filename(&self) -> String {
+        let tab = self.active_tab();
+        let disposition = tab
+            .response_headers
+            .get("content-disposition")
+            .and_then(|cd| {
+                let rest = &cd[cd.find("filename=")? + 9..];
+                let end = rest.find(';').unwrap_or(rest.len());
+                Some(rest[..end].trim().trim_matches('"').to_string())
+            })
+            .filter(|name| !name.is_empty());
+        if let Some(name) = disposition {
+            return name;
+        }
+
+        let path = tab.url.split(['?', '#']).next().unwrap_or_default();
+        let last = path.rsplit('/').next().unwrap_or_default();
+        if path.contains("://") && path.matches('/').count() > 2 && last.contains('.') {
+            return last.to_string();
+        }
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let ext = self.guess_extension().unwrap_or_else(|| "bin".to_string());
+        format!("response_{}.{}", timestamp, ext)
     }
 
+    /// `D`: the command line, filled in with `saveas <suggested name>` to edit
     pub fn download_response(&mut self) {
-        if let Some(bytes) = &self.active_tab().response_bytes {
-            // Try to find a good filename
-            let mut filename = "response".to_string();
-
-            // Check Content-Disposition header
-            if let Some(cd) = self
-                .active_tab()
-                .response_headers
-                .get("content-disposition")
-            {
-                if let Some(start) = cd.find("filename=") {
-                    let rest = &cd[start + 9..];
-                    let end = rest.find(';').unwrap_or(rest.len());
-                    let name = rest[..end].trim_matches('"').to_string();
-                    if !name.is_empty() {
-                        filename = name;
-                    }
-                }
-            } else {
-                // Use fallback with timestamp
-                let timestamp = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
-                filename = format!("response_{}", timestamp);
-                if let Some(ext) = self.guess_extension() {
-                    filename = format!("{}.{}", filename, ext);
-                } else {
-                    filename = format!("{}.bin", filename);
-                }
+        if self.active_tab().response_bytes.is_none() {
+            self.show_notification("No response content to download".to_string());
+            return;
+        }
+        self.show_command_palette = false;
+        self.command_input = format!("saveas {}", self.suggested_filename());
+        self.input_cursor = crate::domain::line_edit::CURSOR_END;
+        self.active_tab_mut().input_mode = InputMode::Command;
+    }
+
+    /// `:saveas <file>`: writes the response body, as received, to `file`
+    pub fn save_response_as(&mut self, file: &str) {
+        let file = file.trim();
+        if file.is_empty() {
+            self.show_error("Usage: :saveas <file>".to_string());
+            return;
+        }
+        let Some(bytes) = self.active_tab().response_bytes.clone() else {
+            self.show_notification("No response content to download".to_string());
+            return;
+        };
+        let path = match (file.strip_prefix("~/"), std::env::var("HOME")) {
+            (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+            _ => file.to_string(),
+        };
+        let replaced = std::path::Path::new(&path).exists();
+        match std::fs::write(&path, &bytes) {
+            Ok(()) if replaced => self.show_success(format!("Saved: {} (replaced)", file)),
+            Ok(()) => self.show_success(format!("Saved: {}", file)),
+            Err(e) => self.show_error(format!("Failed to save {}: {}", file, e)),
+        }
+    }
+
+    /// `:export history|run [file]`: the request history or the last collection run
+    /// as CSV, or JSON when the file ends in .json. `:export responses [dir]`: every
+    /// response of the last run as files
+    pub fn export_command(&mut self, args: &str) {
+        let (what, file) = args.split_once(' ').unwrap_or((args, ""));
+        match what {
+            "history" => self.export_history(file.trim()),
+            "run" => self.export_run(file.trim()),
+            "responses" => self.export_run_responses(file.trim()),
+            _ => self.show_error(
+                "Usage: :export history|run [file.csv|file.json] | responses [dir]".to_string(),
+            ),
+        }
+    }
+
+    /// To `file`, `history.csv` when empty
+    pub fn export_history(&mut self, file: &str) {
+        use crate::features::results_export::{self, Format};
+        if self.request_history.is_empty() {
+            self.show_notification("No history to export".to_string());
+            return;
+        }
+        let file = if file.is_empty() { "history.csv" } else { file };
+        let content = results_export::history(&self.request_history, Format::for_path(file));
+        let what = format!("{} history entries", self.request_history.len());
+        self.write_export(file, content, what);
+    }
+
+    /// The runner's last results to `file`, `<collection>-results.csv` when empty
+    pub fn export_run(&mut self, file: &str) {
+        use crate::features::results_export::{self, Format};
+        let Some(run) = self.runner_result.as_ref().filter(|r| !r.running) else {
+            self.show_notification("No finished collection run to export".to_string());
+            return;
+        };
+        let file = if file.is_empty() {
+            format!("{}-results.csv", run.collection_name)
+        } else {
+            file.to_string()
+        };
+        let content =
+            results_export::run(&run.collection_name, &run.results, Format::for_path(&file));
+        let what = format!("{} results", run.results.len());
+        self.write_export(&file, content, what);
+    }
+
+    /// The bodies and headers of the runner's last results, a directory per request
+    /// under `dir` (`<collection>-responses` when empty)
+    pub fn export_run_responses(&mut self, dir: &str) {
+        use crate::features::response_dump;
+        let Some(run) = self.runner_result.as_ref().filter(|r| !r.running) else {
+            self.show_notification("No finished collection run to export".to_string());
+            return;
+        };
+        let dir = if dir.is_empty() {
+            format!("{}-responses", run.collection_name)
+        } else {
+            dir.to_string()
+        };
+        let path = match (dir.strip_prefix("~/"), std::env::var("HOME")) {
+            (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+            _ => dir.clone(),
+        };
+        match response_dump::write(std::path::Path::new(&path), &run.results) {
+            Ok(n) => self.show_success(format!("Saved {} responses to {}/", n, dir)),
+            Err(e) => self.show_error(format!("Failed to save responses: {}", e)),
+        }
+    }
+
+    fn write_export(&mut self, file: &str, content: String, what: String) {
+        let path = match (file.strip_prefix("~/"), std::env::var("HOME")) {
+            (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+            _ => file.to_string(),
+        };
+        match std::fs::write(&path, content) {
+            Ok(()) => self.show_success(format!("Exported {} to {}", what, file)),
+            Err(e) => self.show_error(format!("Failed to write {}: {}", file, e)),
+        }
+    }
+
+    /// `:trace [on|off|b3 on|off|link <template>|copy|open]`
+    pub fn trace_command(&mut self, args: &str) {
+        let (word, rest) = args.split_once(' ').unwrap_or((args, ""));
+        let rest = rest.trim();
+        match (word, rest) {
+            ("", _) => {
+                let settings = &self.trace_settings;
+                let msg = match (settings.enabled, settings.b3) {
+                    (false, _) => "Trace headers off (:trace on)".to_string(),
+                    (true, false) => "Sending traceparent with every request".to_string(),
+                    (true, true) => "Sending traceparent and B3 with every request".to_string(),
+                };
+                self.show_notification(msg);
+                return;
             }
+            ("on" | "off", "") => self.trace_settings.enabled = word == "on",
+            ("b3", "on" | "off") => {
+                self.trace_settings.b3 = rest == "on";
+                self.trace_settings.enabled |= self.trace_settings.b3;
+            }
+            ("link", template) => self.trace_settings.link = template.to_string(),
+            ("copy", "") => return self.copy_trace_link(),
+            ("open", "") => return self.open_trace_link(),
+            _ => {
+                self.show_error(
+                    "Usage: :trace on|off, :trace b3 on|off, :trace link <url with {trace_id}>, :trace copy|open"
+                        .to_string(),
+                );
+                return;
+            }
+        }
+        self.save_config();
+        let settings = &self.trace_settings;
+        let msg = match (word, settings.enabled) {
+            ("link", _) if settings.link.is_empty() => "Trace link cleared".to_string(),
+            ("link", _) => format!("Trace link: {}", settings.link),
+            (_, false) => "Trace headers off".to_string(),
+            (_, true) if settings.b3 => "Trace headers on: traceparent and B3".to_string(),
+            (_, true) => "Trace headers on: traceparent".to_string(),
+        };
+        self.show_success(msg);
+    }
 
-            let mut path =
-                std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
-            path.push(&filename);
+    /// The last send's trace: its link when there's a template, else the trace id
+    fn trace_target(&mut self) -> Option<String> {
+        let Some(trace) = self.active_tab().trace.clone() else {
+            let msg = if self.trace_settings.enabled {
+                "No trace yet, send the request first"
+            } else {
+                "Trace headers are off (:trace on)"
+            };
+            self.show_notification(msg.to_string());
+            return None;
+        };
+        Some(
+            trace
+                .link(&self.trace_settings.link)
+                .unwrap_or(trace.trace_id),
+        )
+    }
 
-            if std::fs::write(&path, bytes).is_ok() {
-                self.show_notification(format!("Saved: {}", filename));
+    pub fn copy_trace_link(&mut self) {
+        if let Some(target) = self.trace_target() {
+            let msg = if target.contains("://") {
+                "Copied trace link"
             } else {
-                self.show_notification(format!("Failed to save {}", filename));
-            }
-        } else {
-            self.show_notification("No response content to download".to_string());
+                "Copied trace id (set a link with :trace link)"
+            };
+            self.copy_to_clipboard_as(target, msg);
+        }
+    }
+
+    pub fn open_trace_link(&mut self) {
+        let Some(target) = self.trace_target() else {
+            return;
+        };
+        if !target.contains("://") {
+            self.show_notification(
+                "Set where traces open first, e.g. :trace link http://localhost:16686/trace/{trace_id}"
+                    .to_string(),
+            );
+            return;
+        }
+        match webbrowser::open(&target) {
+            Ok(()) => self.show_success(format!("Opened {}", target)),
+            Err(e) => self.show_error(format!("Could not open {}: {}", target, e)),
+        }
+    }
+
+    /// `z` on an image response: fit, fill the pane, actual size
+    pub fn cycle_image_zoom(&mut self) {
+        if self.active_tab().response_image.is_none() {
+            self.show_notification("Zoom is for image responses".to_string());
+            return;
         }
+        self.image_zoom = self.image_zoom.next();
+        let message = if self.image_picker.is_some() {
+            format!("Image: {}", self.image_zoom.label())
+        } else {
+            "This terminal can't show images, P opens it in a viewer".to_string()
+        };
+        self.show_notification(message);
     }
 
     pub fn preview_response(&mut self) {
@@ -1245,10 +7746,10 @@ impl App {
                 if webbrowser::open(file_path.to_str().unwrap()).is_ok() {
                     self.show_notification("Opened default viewer".to_string());
                 } else {
-                    self.show_notification("Failed to open viewer".to_string());
+                    self.show_error("Failed to open viewer".to_string());
                 }
             } else {
-                self.show_notification("Failed to write temp file".to_string());
+                self.show_error("Failed to write temp file".to_string());
             }
         } else {
             self.show_notification("No response content to preview".to_string());
@@ -1276,731 +7777,1447 @@ impl App {
                         schema_types.push(name.to_string());
                     }
                 }
-                schema_types.sort();
-                self.active_tab_mut().graphql_schema_types = schema_types;
-                self.active_tab_mut().show_schema_modal = true; // Show modal with types
-                self.show_notification("Schema Introspection Complete".to_string());
-            } else {
-                self.show_notification("Invalid Schema Response".to_string());
+                schema_types.sort();
+                self.active_tab_mut().graphql_schema_types = schema_types;
+                self.active_tab_mut().show_schema_modal = true; // Show modal with types
+                self.show_success("Schema Introspection Complete".to_string());
+            } else {
+                self.show_error("Invalid Schema Response".to_string());
+            }
+        } else {
+            self.show_error("Failed to parse Schema JSON".to_string());
+        }
+    }
+
+    pub fn close_schema_modal(&mut self) {
+        self.active_tab_mut().show_schema_modal = false;
+    }
+
+    pub fn generate_curl_command(&self) -> String {
+        self.curl_command(crate::features::curl_export::Shell::current(), true)
+    }
+
+    /// The active request as a curl command, including cookies from the jar and the
+    /// proxy / TLS settings PostDad itself would use to send it
+    pub fn curl_command(
+        &self,
+        shell: crate::features::curl_export::Shell,
+        multiline: bool,
+    ) -> String {
+        let tab = self.active_tab();
+        let url = self.process_url();
+        let mut curl = crate::features::curl_export::CurlBuilder::new(shell, &tab.method, &url);
+
+        match &tab.auth_type {
+            AuthType::Bearer | AuthType::OAuth2 => {
+                if !tab.auth_token.is_empty() {
+                    curl.header("Authorization", &format!("Bearer {}", tab.auth_token));
+                }
+            }
+            AuthType::Basic => {
+                curl.arg(
+                    "--user",
+                    &format!("{}:{}", tab.basic_auth_user, tab.basic_auth_pass),
+                );
+            }
+            AuthType::None => {}
+        }
+
+        let sent_headers = self.sent_headers();
+        let mut headers: Vec<(&String, &String)> = sent_headers.iter().collect();
+        headers.sort();
+        for (k, v) in &headers {
+            curl.header(k, &self.process_text(v));
+        }
+        let has_header = |name: &str| headers.iter().any(|(k, _)| k.eq_ignore_ascii_case(name));
+
+        if !has_header("cookie")
+            && let Some(cookies) = self.get_cookie_header(&url)
+        {
+            curl.arg("-b", &cookies);
+        }
+
+        match tab.body_type {
+            BodyType::Raw => {
+                if !tab.request_body.is_empty() {
+                    let body = self.process_text(&tab.request_body);
+                    // -d would read a file for bodies starting with @
+                    let flag = if body.starts_with('@') {
+                        "--data-raw"
+                    } else {
+                        "-d"
+                    };
+                    curl.data(flag, &body);
+                }
+            }
+            BodyType::FormData => {
+                for (k, v, is_file) in &tab.form_data {
+                    let value = if *is_file {
+                        format!("{}=@{}", k, v)
+                    } else {
+                        format!("{}={}", k, self.process_text(v))
+                    };
+                    curl.data("-F", &value);
+                }
+            }
+            BodyType::UrlEncoded => {
+                for (k, v) in &tab.urlencoded {
+                    curl.data(
+                        "--data-urlencode",
+                        &format!("{}={}", k, self.process_text(v)),
+                    );
+                }
+            }
+            BodyType::GraphQL => {
+                let variables = serde_json::from_str::<serde_json::Value>(
+                    &self.process_text(&tab.graphql_variables),
+                )
+                .unwrap_or(serde_json::json!({}));
+                let payload = serde_json::json!({
+                    "query": self.process_text(&tab.graphql_query),
+                    "variables": variables,
+                });
+                if !has_header("content-type") {
+                    curl.header("Content-Type", "application/json");
+                }
+                curl.data("-d", &payload.to_string());
+            }
+            BodyType::Grpc => {
+                curl.flag("# gRPC not fully supported in CURL generator");
+            }
+        }
+
+        if tab.timeout_ms != 30000 {
+            curl.arg("--max-time", &format!("{}", tab.timeout_ms as f64 / 1000.0));
+        }
+        if !self.ssl_verify {
+            curl.flag("--insecure");
+        }
+        if let Some((host, port, addrs)) = self.host_override() {
+            curl.arg(
+                "--resolve",
+                &format!("{}:{}:{}", host, port, addrs.replace(' ', "")),
+            );
+        }
+        if let Some(path) = &self.ssl_ca_cert_path {
+            curl.arg("--cacert", path);
+        }
+        if let Some(path) = &self.ssl_client_cert_path {
+            curl.arg("--cert", path);
+        }
+        if let Some(path) = &self.ssl_client_key_path {
+            curl.arg("--key", path);
+        }
+        if let Some(proxy) = &self.proxy_url {
+            curl.arg("--proxy", proxy);
+            if let (Some(user), Some(pass)) = (&self.proxy_auth_user, &self.proxy_auth_pass) {
+                curl.arg("--proxy-user", &format!("{}:{}", user, pass));
+            }
+            if let Some(no_proxy) = &self.no_proxy {
+                curl.arg("--noproxy", no_proxy);
+            }
+        }
+
+        curl.build(multiline)
+    }
+
+    /// `:curl [oneline] [cmd|posix]` copies the request as curl for a specific shell
+    pub fn copy_curl_command(&mut self, args: &str) {
+        use crate::features::curl_export::Shell;
+        let mut shell = Shell::current();
+        let mut multiline = true;
+        for arg in args.split_whitespace() {
+            match arg {
+                "oneline" | "single" => multiline = false,
+                "cmd" | "windows" => shell = Shell::Cmd,
+                "posix" | "sh" | "bash" => shell = Shell::Posix,
+                other => {
+                    self.show_error(format!(
+                        "Unknown option '{}'. Usage: curl [oneline] [cmd|posix]",
+                        other
+                    ));
+                    return;
+                }
+            }
+        }
+        let cmd = self.curl_command(shell, multiline);
+        self.copy_to_clipboard(cmd);
+    }
+
+    /// `:import-curl <file> [name]`: every curl command in the file becomes a request
+    /// of a new collection
+    pub fn import_curl_file(&mut self, args: &str) {
+        let mut parts = args.split_whitespace();
+        let Some(file) = parts.next() else {
+            self.show_notification("Usage: import-curl <file> [collection_name]".to_string());
+            return;
+        };
+        let name = parts
+            .next()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| crate::features::curl_import::collection_name_for(file));
+
+        // So `~/.zsh_history` works
+        let path = match (file.strip_prefix("~/"), std::env::var("HOME")) {
+            (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+            _ => file.to_string(),
+        };
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Could not read {}: {}", file, e))
+            .and_then(|text| {
+                crate::features::curl_import::import_to_collection(
+                    &text,
+                    &name,
+                    &self.workspace.path("collections"),
+                )
+            });
+        match result {
+            Ok((batch, path)) => {
+                if let Ok(cols) = Collection::load_from_dir(&self.workspace.path("collections")) {
+                    self.collections = cols;
+                }
+                self.show_success(format!("Imported {} to {}", batch.summary(), path));
             }
-        } else {
-            self.show_notification("Failed to parse Schema JSON".to_string());
+            Err(e) => self.show_error(e),
         }
     }
 
-    pub fn close_schema_modal(&mut self) {
-        self.active_tab_mut().show_schema_modal = false;
+    /// Parse a curl command and populate the current request tab
+    pub fn import_from_curl(&mut self, curl_cmd: &str) -> Result<(), String> {
+        self.active_tab_mut().apply_curl(curl_cmd)
     }
 
-    pub fn generate_curl_command(&self) -> String {
+    /// The active request as template variables for the code generators
+    pub fn codegen_context(&self) -> serde_json::Value {
+        use serde_json::json;
         let tab = self.active_tab();
-        let mut cmd = format!("curl -X {} \"{}\"", tab.method, self.process_url());
 
-        match &tab.auth_type {
-            AuthType::Bearer => {
-                if !tab.auth_token.is_empty() {
-                    cmd.push_str(&format!(" -H \"Authorization: Bearer {}\"", tab.auth_token));
-                }
-            }
-            AuthType::Basic => {
-                let creds = format!("{}:{}", tab.basic_auth_user, tab.basic_auth_pass);
-                let _encoded = arboard::Clipboard::new()
-                    .map(|_| "ENCODING_SKIPPED".to_string())
-                    .unwrap_or("".to_string());
-                cmd.push_str(&format!(" --user \"{}\"", creds));
-            }
-            AuthType::OAuth2 => {
-                if !tab.auth_token.is_empty() {
-                    cmd.push_str(&format!(" -H \"Authorization: Bearer {}\"", tab.auth_token));
-                }
+        let sent_headers = self.sent_headers();
+        let mut headers: Vec<(&String, &String)> = sent_headers.iter().collect();
+        headers.sort();
+        let has_content_type = headers
+            .iter()
+            .any(|(k, _)| k.eq_ignore_ascii_case("content-type"));
+        let headers: Vec<serde_json::Value> = headers
+            .iter()
+            .map(|(k, v)| json!({ "name": k, "value": self.process_text(v) }))
+            .collect();
+
+        let bearer_token = match tab.auth_type {
+            AuthType::Bearer | AuthType::OAuth2 => tab.auth_token.clone(),
+            _ => String::new(),
+        };
+        let (basic_user, basic_pass) = match tab.auth_type {
+            AuthType::Basic => (tab.basic_auth_user.clone(), tab.basic_auth_pass.clone()),
+            _ => (String::new(), String::new()),
+        };
+
+        let (body_type, body) = match tab.body_type {
+            BodyType::Raw => ("raw", self.process_text(&tab.request_body)),
+            BodyType::FormData => ("form", String::new()),
+            BodyType::UrlEncoded => ("urlencoded", String::new()),
+            BodyType::GraphQL => {
+                let variables = serde_json::from_str::<serde_json::Value>(
+                    &self.process_text(&tab.graphql_variables),
+                )
+                .unwrap_or(json!({}));
+                let payload = json!({
+                    "query": self.process_text(&tab.graphql_query),
+                    "variables": variables,
+                });
+                ("graphql", payload.to_string())
             }
-            _ => {}
-        }
+            BodyType::Grpc => ("grpc", String::new()),
+        };
+        let form: Vec<serde_json::Value> = match tab.body_type {
+            BodyType::FormData => tab
+                .form_data
+                .iter()
+                .map(|(k, v, is_file)| {
+                    let value = if *is_file {
+                        v.clone()
+                    } else {
+                        self.process_text(v)
+                    };
+                    json!({ "name": k, "value": value, "file": is_file })
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let urlencoded: Vec<serde_json::Value> = match tab.body_type {
+            BodyType::UrlEncoded => tab
+                .urlencoded
+                .iter()
+                .map(|(k, v)| json!({ "name": k, "value": self.process_text(v) }))
+                .collect(),
+            _ => Vec::new(),
+        };
 
-        for (k, v) in &tab.request_headers {
-            cmd.push_str(&format!(" -H \"{}: {}\"", k, v));
+        json!({
+            "method": tab.method,
+            "url": self.process_url(),
+            "headers": headers,
+            "has_content_type": has_content_type,
+            "bearer_token": bearer_token,
+            "basic_user": basic_user,
+            "basic_pass": basic_pass,
+            "body_type": body_type,
+            "body": body,
+            "form": form,
+            "urlencoded": urlencoded,
+        })
+    }
+
+    /// Code for the active request in the target with this id or name (see `:codegen`)
+    pub fn generate_code(&self, target: &str) -> Result<String, String> {
+        use crate::features::codegen;
+        let targets = codegen::load_targets(&self.workspace.path(codegen::CODEGEN_DIR));
+        let target = codegen::find_target(&targets, target)
+            .ok_or_else(|| format!("Unknown code generator '{}'", target.trim()))?;
+        match target.template {
+            None => Ok(self.generate_curl_command()),
+            Some(_) => codegen::render_target(target, &self.codegen_context()),
         }
+    }
 
-        match tab.body_type {
-            BodyType::Raw => {
-                if !tab.request_body.is_empty() {
-                    let escaped = tab.request_body.replace("'", "'\\''");
-                    cmd.push_str(&format!(" -d '{}'", escaped));
-                }
-            }
-            BodyType::FormData => {
-                for (k, v, is_file) in &tab.form_data {
-                    if *is_file {
-                        cmd.push_str(&format!(" -F \"{} = @{}\"", k, v));
-                    } else {
-                        cmd.push_str(&format!(" -F \"{} = {}\"", k, v));
-                    }
-                }
-            }
-            BodyType::GraphQL => {
-                let vars = if tab.graphql_variables.trim().is_empty() {
-                    "{}"
-                } else {
-                    &tab.graphql_variables
-                };
-                let query = tab.graphql_query.replace("\n", " ").replace("'", "'\\''");
-                let json_body = format!(r#"{{"query": "{}", "variables": {}}}"#, query, vars);
-                cmd.push_str(&format!(" -d '{}'", json_body));
-            }
-            BodyType::Grpc => {
-                cmd.push_str(" # gRPC not fully supported in CURL generator");
-            }
+    pub fn copy_generated_code(&mut self, target: &str) {
+        match self.generate_code(target) {
+            Ok(code) => self.copy_to_clipboard_as(code, &format!("Copied {} code", target.trim())),
+            Err(e) => self.show_error(e),
         }
+    }
 
-        cmd
+    /// Opens the code generator picker, re-reading `codegen/` for new templates
+    pub fn open_codegen_picker(&mut self) {
+        self.codegen_targets = crate::features::codegen::load_targets(
+            &self.workspace.path(crate::features::codegen::CODEGEN_DIR),
+        );
+        self.codegen_query.clear();
+        self.codegen_index = 0;
+        self.show_codegen_picker = true;
+        self.active_tab_mut().input_mode = InputMode::CodegenPicker;
     }
 
-    /// Parse a curl command and populate the current request tab
-    pub fn import_from_curl(&mut self, curl_cmd: &str) -> Result<(), String> {
-        // Normalize the command: handle line continuations and clean up
-        let cmd = curl_cmd
-            .replace("\\\n", " ")
-            .replace("\\\r\n", " ")
-            .trim()
-            .to_string();
+    pub fn close_codegen_picker(&mut self) {
+        self.show_codegen_picker = false;
+        self.codegen_query.clear();
+        self.active_tab_mut().input_mode = InputMode::Normal;
+    }
 
-        // Must start with 'curl'
-        if !cmd.to_lowercase().starts_with("curl") {
-            return Err("Command must start with 'curl'".to_string());
-        }
+    /// Picker entries matching the typed filter
+    pub fn filtered_codegen_targets(&self) -> Vec<&crate::features::codegen::Target> {
+        self.codegen_targets
+            .iter()
+            .filter(|t| t.matches(&self.codegen_query))
+            .collect()
+    }
 
-        // Simple tokenizer that respects quotes
-        let tokens = Self::tokenize_curl(&cmd)?;
+    /// Opens the snippet picker for the `target` script, re-reading `snippets/`
+    pub fn open_snippet_picker(&mut self, target: crate::features::snippets::ScriptKind) {
+        self.snippets = crate::features::snippets::load_snippets(
+            &self.workspace.path(crate::features::snippets::SNIPPETS_DIR),
+        );
+        self.snippet_query.clear();
+        self.snippet_index = 0;
+        self.snippet_target = target;
+        self.show_snippet_picker = true;
+        self.active_tab_mut().input_mode = InputMode::SnippetPicker;
+    }
 
-        let mut url = String::new();
-        let mut method = "GET".to_string();
-        let mut headers: Vec<(String, String)> = Vec::new();
-        let mut body = String::new();
-        let mut form_data: Vec<(String, String, bool)> = Vec::new();
-        let mut auth_user = String::new();
-        let mut auth_pass = String::new();
+    pub fn close_snippet_picker(&mut self) {
+        self.show_snippet_picker = false;
+        self.snippet_query.clear();
+        self.active_tab_mut().input_mode = InputMode::Normal;
+    }
 
-        let mut i = 1; // Skip 'curl'
-        while i < tokens.len() {
-            let token = &tokens[i];
-            match token.as_str() {
-                "-X" | "--request" => {
-                    if i + 1 < tokens.len() {
-                        method = tokens[i + 1].to_uppercase();
-                        i += 1;
-                    }
-                }
-                "-H" | "--header" => {
-                    if i + 1 < tokens.len() {
-                        let header = &tokens[i + 1];
-                        if let Some(colon_pos) = header.find(':') {
-                            let key = header[..colon_pos].trim().to_string();
-                            let value = header[colon_pos + 1..].trim().to_string();
-                            headers.push((key, value));
-                        }
-                        i += 1;
-                    }
-                }
-                "-d" | "--data" | "--data-raw" | "--data-binary" => {
-                    if i + 1 < tokens.len() {
-                        body = tokens[i + 1].clone();
-                        if method == "GET" {
-                            method = "POST".to_string();
+    /// Picker entries matching the typed filter
+    pub fn filtered_snippets(&self) -> Vec<&crate::features::snippets::Snippet> {
+        self.snippets
+            .iter()
+            .filter(|s| s.matches(&self.snippet_query))
+            .collect()
+    }
+
+    /// Inserts the highlighted snippet and closes the picker
+    pub fn confirm_snippet_picker(&mut self) {
+        let selected = self
+            .filtered_snippets()
+            .get(self.snippet_index)
+            .map(|s| (*s).clone());
+        let target = self.snippet_target;
+        self.close_snippet_picker();
+        if let Some(snippet) = selected {
+            self.insert_snippet(&snippet, target);
+        }
+    }
+
+    /// Adds `snippet` to the end of the active tab's `target` script
+    pub fn insert_snippet(
+        &mut self,
+        snippet: &crate::features::snippets::Snippet,
+        target: crate::features::snippets::ScriptKind,
+    ) {
+        use crate::features::snippets::{ScriptKind, insert};
+        let tab = self.active_tab_mut();
+        let script = match target {
+            ScriptKind::Pre => &mut tab.pre_request_script,
+            ScriptKind::Post => &mut tab.post_request_script,
+        };
+        *script = insert(script, snippet);
+        let note = match snippet.kind {
+            Some(kind) if kind != target => format!(" (it's written for the {})", kind.title()),
+            _ => String::new(),
+        };
+        self.show_success(format!(
+            "Inserted '{}' into the {}{}",
+            snippet.name,
+            target.title(),
+            note
+        ));
+    }
+
+    /// `:snippet [pre|post] [name]`, `:snippet save [pre|post] <name>`,
+    /// `:snippet rm <name>`
+    pub fn snippet_command(&mut self, args: &str) {
+        use crate::features::snippets::{self, ScriptKind};
+        let dir = self.workspace.path(snippets::SNIPPETS_DIR);
+        let mut words: Vec<&str> = args.split_whitespace().collect();
+        let action = match words.first() {
+            Some(&"save") | Some(&"rm") | Some(&"delete") => Some(words.remove(0)),
+            _ => None,
+        };
+        let kind = words.first().and_then(|w| ScriptKind::parse(w));
+        if kind.is_some() {
+            words.remove(0);
+        }
+        let name = words.join(" ");
+
+        match action {
+            Some("save") => {
+                let kind = kind.unwrap_or(ScriptKind::Pre);
+                let tab = self.active_tab();
+                let code = match kind {
+                    ScriptKind::Pre => tab.pre_request_script.clone(),
+                    ScriptKind::Post => tab.post_request_script.clone(),
+                };
+                if name.is_empty() {
+                    self.show_error("Usage: :snippet save [pre|post] <name>".to_string());
+                } else if code.trim().is_empty() {
+                    self.show_error(format!("The {} is empty", kind.title()));
+                } else {
+                    match snippets::save(&dir, &name, kind, &code) {
+                        Ok(path) => {
+                            self.show_success(format!("Snippet '{}' saved to {}", name, path))
                         }
-                        i += 1;
+                        Err(e) => self.show_error(format!("Snippet not saved: {}", e)),
                     }
                 }
-                "-F" | "--form" => {
-                    if i + 1 < tokens.len() {
-                        let form_item = &tokens[i + 1];
-                        if let Some(eq_pos) = form_item.find('=') {
-                            let key = form_item[..eq_pos].trim().to_string();
-                            let value = form_item[eq_pos + 1..].trim().to_string();
-                            let is_file = value.starts_with('@');
-                            let clean_value = if is_file {
-                                value[1..].to_string()
-                            } else {
-                                value
-                            };
-                            form_data.push((key, clean_value, is_file));
-                        }
-                        if method == "GET" {
-                            method = "POST".to_string();
-                        }
-                        i += 1;
+            }
+            Some(_) => {
+                let all = snippets::load_snippets(&dir);
+                match snippets::find_snippet(&all, &name) {
+                    Some(s) if s.builtin => {
+                        self.show_error(format!("'{}' is built in and can't be removed", s.name))
                     }
-                }
-                "-u" | "--user" => {
-                    if i + 1 < tokens.len() {
-                        let auth = &tokens[i + 1];
-                        if let Some(colon_pos) = auth.find(':') {
-                            auth_user = auth[..colon_pos].to_string();
-                            auth_pass = auth[colon_pos + 1..].to_string();
+                    Some(s) => {
+                        let path = std::path::Path::new(&dir).join(format!("{}.rhai", s.id));
+                        match std::fs::remove_file(&path) {
+                            Ok(()) => self.show_success(format!("Snippet '{}' removed", s.name)),
+                            Err(e) => self.show_error(format!("Snippet not removed: {}", e)),
                         }
-                        i += 1;
-                    }
-                }
-                "-A" | "--user-agent" => {
-                    if i + 1 < tokens.len() {
-                        headers.push(("User-Agent".to_string(), tokens[i + 1].clone()));
-                        i += 1;
-                    }
-                }
-                "-b" | "--cookie" => {
-                    if i + 1 < tokens.len() {
-                        headers.push(("Cookie".to_string(), tokens[i + 1].clone()));
-                        i += 1;
-                    }
-                }
-                "-e" | "--referer" => {
-                    if i + 1 < tokens.len() {
-                        headers.push(("Referer".to_string(), tokens[i + 1].clone()));
-                        i += 1;
                     }
+                    None => self.show_error(format!("No snippet '{}'", name)),
                 }
-                // Skip flags we don't care about
-                "-k" | "--insecure" | "-v" | "--verbose" | "-s" | "--silent" | "-L"
-                | "--location" | "-i" | "--include" | "--compressed" => {}
-                // Skip flags with arguments we ignore
-                "-o" | "--output" | "--connect-timeout" | "-m" | "--max-time" => {
-                    i += 1; // Skip the argument too
-                }
-                _ => {
-                    // If it looks like a URL
-                    if token.starts_with("http://")
-                        || token.starts_with("https://")
-                        || token.starts_with("ws://")
-                        || token.starts_with("wss://")
-                    {
-                        url = token.clone();
-                    } else if !token.starts_with('-') && url.is_empty() {
-                        // Might be a URL without protocol
-                        url = token.clone();
+            }
+            None if name.is_empty() => self.open_snippet_picker(kind.unwrap_or(ScriptKind::Pre)),
+            None => {
+                let all = snippets::load_snippets(&dir);
+                match snippets::find_snippet(&all, &name) {
+                    Some(s) => {
+                        let target = kind.or(s.kind).unwrap_or(ScriptKind::Pre);
+                        let s = s.clone();
+                        self.insert_snippet(&s, target);
                     }
+                    None => self.show_error(format!("No snippet '{}'", name)),
                 }
             }
-            i += 1;
         }
+    }
 
-        if url.is_empty() {
-            return Err("No URL found in curl command".to_string());
-        }
+    /// Opens the fuzzy finder over tabs, saved requests, history and environments
+    pub fn open_finder(&mut self) {
+        self.show_command_palette = false;
+        self.finder_query.clear();
+        self.finder_index = 0;
+        self.show_finder = true;
+        self.active_tab_mut().input_mode = InputMode::Finder;
+    }
 
-        // Populate the current tab
-        let tab = self.active_tab_mut();
-        tab.url = url;
-        tab.method = method;
-        tab.request_headers = headers.into_iter().collect();
+    pub fn close_finder(&mut self) {
+        self.show_finder = false;
+        self.finder_query.clear();
+        self.active_tab_mut().input_mode = InputMode::Normal;
+    }
 
-        if !form_data.is_empty() {
-            tab.body_type = BodyType::FormData;
-            tab.form_data = form_data;
-        } else if !body.is_empty() {
-            tab.body_type = BodyType::Raw;
-            tab.request_body = body;
-        }
+    /// Opens the history view, filtered by `query` (see `history::parse_query`)
+    pub fn open_history_view(&mut self, query: &str) {
+        self.show_command_palette = false;
+        self.history_query = query.to_string();
+        self.history_index = 0;
+        self.history_marked.clear();
+        self.show_history_view = true;
+        self.active_tab_mut().input_mode = InputMode::HistoryView;
+    }
 
-        if !auth_user.is_empty() {
-            tab.auth_type = AuthType::Basic;
-            tab.basic_auth_user = auth_user;
-            tab.basic_auth_pass = auth_pass;
-        }
+    pub fn close_history_view(&mut self) {
+        self.show_history_view = false;
+        self.history_query.clear();
+        self.history_marked.clear();
+        self.active_tab_mut().input_mode = InputMode::Normal;
+    }
 
-        // Sync URL to params
-        self.sync_url_to_params();
+    pub fn history_rows(&self) -> Vec<crate::features::history::Row> {
+        crate::features::history::rows(
+            &self.request_history,
+            &self.history_query,
+            crate::features::history::now(),
+        )
+    }
 
-        Ok(())
+    /// History indices in the order the view shows them
+    pub fn history_view_entries(&self) -> Vec<usize> {
+        use crate::features::history::Row;
+        self.history_rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                Row::Entry(i) => Some(i),
+                Row::Group(_) => None,
+            })
+            .collect()
     }
 
-    /// Tokenize a curl command respecting quoted strings
-    fn tokenize_curl(cmd: &str) -> Result<Vec<String>, String> {
-        let mut tokens = Vec::new();
-        let mut current = String::new();
-        let mut in_single_quote = false;
-        let mut in_double_quote = false;
-        let mut escape_next = false;
+    pub fn selected_history_view_entry(&self) -> Option<usize> {
+        self.history_view_entries().get(self.history_index).copied()
+    }
 
-        for ch in cmd.chars() {
-            if escape_next {
-                current.push(ch);
-                escape_next = false;
-                continue;
+    pub fn move_history_view(&mut self, down: bool) {
+        let count = self.history_view_entries().len();
+        if down {
+            if self.history_index + 1 < count {
+                self.history_index += 1;
             }
+        } else {
+            self.history_index = self.history_index.saturating_sub(1);
+        }
+    }
 
-            match ch {
-                '\\' if !in_single_quote => {
-                    escape_next = true;
-                }
-                '\'' if !in_double_quote => {
-                    in_single_quote = !in_single_quote;
-                }
-                '"' if !in_single_quote => {
-                    in_double_quote = !in_double_quote;
-                }
-                ' ' | '\t' if !in_single_quote && !in_double_quote => {
-                    if !current.is_empty() {
-                        tokens.push(current.clone());
-                        current.clear();
-                    }
-                }
-                _ => {
-                    current.push(ch);
-                }
-            }
+    /// Opens the highlighted entry in the current tab and closes the view
+    pub fn confirm_history_view(&mut self) {
+        let selected = self.selected_history_view_entry();
+        self.close_history_view();
+        if let Some(i) = selected {
+            self.restore_history_entry(i);
+        }
+    }
+
+    pub fn toggle_history_pin(&mut self) {
+        let Some(i) = self.selected_history_view_entry() else {
+            return;
+        };
+        let log = &mut self.request_history[i];
+        log.pinned = !log.pinned;
+        let msg = if log.pinned { "Pinned" } else { "Unpinned" };
+        self.save_history();
+        self.show_notification(msg.to_string());
+        // The entry moved in or out of the pinned group: keep it highlighted
+        if let Some(pos) = self.history_view_entries().iter().position(|&e| e == i) {
+            self.history_index = pos;
+        }
+    }
+
+    pub fn toggle_history_mark(&mut self) {
+        let Some(i) = self.selected_history_view_entry() else {
+            return;
+        };
+        if !self.history_marked.remove(&i) {
+            self.history_marked.insert(i);
         }
+        self.move_history_view(true);
+    }
 
-        if !current.is_empty() {
-            tokens.push(current);
+    /// Marks every unpinned entry shown, or clears the marks if there are any
+    pub fn toggle_history_mark_all(&mut self) {
+        if !self.history_marked.is_empty() {
+            self.history_marked.clear();
+            return;
         }
+        self.history_marked = self
+            .history_view_entries()
+            .into_iter()
+            .filter(|&i| !self.request_history[i].pinned)
+            .collect();
+    }
 
-        if in_single_quote || in_double_quote {
-            return Err("Unclosed quote in curl command".to_string());
+    /// Deletes the marked entries, or the highlighted one when nothing is marked
+    pub fn delete_history_view_entries(&mut self) {
+        let doomed: std::collections::BTreeSet<usize> = if self.history_marked.is_empty() {
+            self.selected_history_view_entry().into_iter().collect()
+        } else {
+            std::mem::take(&mut self.history_marked)
+        };
+        if doomed.is_empty() {
+            return;
         }
+        let mut i = 0;
+        self.request_history.retain(|_| {
+            i += 1;
+            !doomed.contains(&(i - 1))
+        });
+        self.diff_base_index = None;
+        self.save_history();
+        let count = self.history_view_entries().len();
+        self.history_index = self.history_index.min(count.saturating_sub(1));
+        self.show_notification(format!("Deleted {} history entries", doomed.len()));
+    }
 
-        Ok(tokens)
+    /// Asks where to save a history entry, as `collection/name`
+    pub fn open_save_history(&mut self, index: usize) {
+        let Some(log) = self.request_history.get(index) else {
+            return;
+        };
+        let path = reqwest::Url::parse(&log.url)
+            .map(|u| u.path().to_string())
+            .unwrap_or_else(|_| log.url.clone());
+        self.save_history_input = format!("saved/{} {}", log.method, path);
+        self.save_history_entry = Some(index);
+        self.show_command_palette = false;
+        // The sidebar would take the keys meant for the prompt
+        self.active_sidebar = false;
+        self.active_tab_mut().input_mode = InputMode::SaveHistory;
     }
 
-    pub fn generate_python_code(&self) -> String {
-        let tab = self.active_tab();
-        let mut code = String::from("import requests\n\n");
-        code.push_str(&format!("url = \"{}\"\n", self.process_url()));
+    pub fn close_save_history(&mut self) {
+        self.save_history_entry = None;
+        self.save_history_input.clear();
+        self.active_tab_mut().input_mode = InputMode::Normal;
+    }
 
-        code.push_str("headers = {\n");
-        for (k, v) in &tab.request_headers {
-            code.push_str(&format!("    \"{}\": \"{}\",\n", k, v));
-        }
-        if (tab.auth_type == AuthType::Bearer || tab.auth_type == AuthType::OAuth2)
-            && !tab.auth_token.is_empty()
-        {
-            code.push_str(&format!(
-                "    \"Authorization\": \"Bearer {}\",\n",
-                tab.auth_token
-            ));
-        }
-        code.push_str("}\n\n");
+    /// Tab in the save prompt completes the collection part from the existing ones
+    pub fn complete_save_history_collection(&mut self) {
+        let typed = self
+            .save_history_input
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let rest = self.save_history_input[typed.len()..]
+            .trim_start_matches('/')
+            .to_string();
+        let candidates: Vec<String> = self
+            .collections
+            .iter()
+            .map(|c| c.name.clone())
+            .filter(|name| name.starts_with(&typed))
+            .collect();
+        let completed = match candidates.as_slice() {
+            [] => return,
+            [only] => only.clone(),
+            many => crate::features::command::common_prefix(many),
+        };
+        self.save_history_input = format!("{}/{}", completed, rest);
+        self.input_cursor = crate::domain::line_edit::CURSOR_END;
+    }
 
-        match tab.body_type {
-            BodyType::Raw => {
-                if !tab.request_body.is_empty() {
-                    code.push_str(&format!("payload = '''{}'''\n\n", tab.request_body));
-                    code.push_str(&format!(
-                        "response = requests.request(\"{}\", url, headers=headers, data=payload)",
-                        tab.method
-                    ));
-                } else {
-                    code.push_str(&format!(
-                        "response = requests.request(\"{}\", url, headers=headers)",
-                        tab.method
-                    ));
-                }
+    /// Writes the entry into the collection. The prompt stays open on a mistake.
+    pub fn confirm_save_history(&mut self) {
+        let Some(log) = self
+            .save_history_entry
+            .and_then(|i| self.request_history.get(i))
+            .cloned()
+        else {
+            self.close_save_history();
+            return;
+        };
+        let target = self.save_history_input.trim().to_string();
+        let (collection, name) = match target.split_once('/') {
+            Some((c, n)) if !c.trim().is_empty() && !n.trim().is_empty() => (c.trim(), n.trim()),
+            _ => {
+                self.show_error("Save as collection/name".to_string());
+                return;
             }
-            BodyType::FormData => {
-                code.push_str("files = [\n");
-                for (k, v, is_file) in &tab.form_data {
-                    if *is_file {
-                        code.push_str(&format!("    ('{}', open('{}', 'rb')),\n", k, v));
-                    } else {
-                        code.push_str(&format!("    ('{}', (None, '{}')),\n", k, v));
-                    }
+        };
+        let exists = self
+            .collections
+            .iter()
+            .any(|c| c.name == collection && c.requests.contains_key(name));
+        if exists {
+            self.show_error(format!("{}/{} already exists", collection, name));
+            return;
+        }
+        let config = log.request.unwrap_or(RequestConfig {
+            url: log.url.clone(),
+            method: log.method.clone(),
+            body: None,
+            headers: None,
+            extract: None,
+            body_type: None,
+            form_data: None,
+            graphql_query: None,
+            graphql_variables: None,
+            expected_status: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            read_timeout_ms: None,
+            pre_request_script: None,
+            post_request_script: None,
+            stress_stages: None,
+            disabled_default_headers: Vec::new(),
+            disabled_headers: std::collections::HashMap::new(),
+            path_params: std::collections::HashMap::new(),
+            description: None,
+            variables: std::collections::HashMap::new(),
+            grpc_service: None,
+            grpc_proto_path: None,
+            tags: Vec::new(),
+            treat_as: None,
+            paginate: None,
+        });
+        match Collection::append_request(
+            &self.workspace.path("collections"),
+            collection,
+            name,
+            &config,
+        ) {
+            Ok(path) => {
+                if let Ok(cols) = Collection::load_from_dir(&self.workspace.path("collections")) {
+                    self.collections = cols;
                 }
-                code.push_str("]\n\n");
-                code.push_str(&format!(
-                    "response = requests.request(\"{}\", url, headers=headers, files=files)",
-                    tab.method
-                ));
-            }
-            _ => {
-                code.push_str(&format!(
-                    "response = requests.request(\"{}\", url, headers=headers)",
-                    tab.method
-                ));
+                self.close_save_history();
+                self.show_success(format!("Saved '{}' to {}", name, path.display()));
             }
+            Err(e) => self.show_error(format!("Save Failed: {}", e)),
         }
-
-        code.push_str("\n\nprint(response.text)");
-        code
     }
 
-    pub fn generate_javascript_code(&self) -> String {
+    /// `:wsexport [file]`: the WebSocket messages on screen, both ways, with
+    /// their times
+    pub fn export_ws_session(&mut self, path: &str) {
+        use crate::features::ws_session::{self, SessionLog};
         let tab = self.active_tab();
-        let mut code = format!(
-            "const url = \"{}\";\nconst options = {{\n  method: '{}',\n  headers: {{\n",
-            self.process_url(),
-            tab.method
-        );
-
-        for (k, v) in &tab.request_headers {
-            code.push_str(&format!("    '{}': '{}',\n", k, v));
+        if tab.ws_messages.is_empty() {
+            self.show_error("No WebSocket messages to export".to_string());
+            return;
         }
-        if (tab.auth_type == AuthType::Bearer || tab.auth_type == AuthType::OAuth2)
-            && !tab.auth_token.is_empty()
-        {
-            code.push_str(&format!(
-                "    'Authorization': 'Bearer {}',\n",
-                tab.auth_token
-            ));
+        let log = SessionLog::from_messages(&tab.ws_url, &tab.ws_messages);
+        let path = match path.trim() {
+            "" => ws_session::default_file_name(crate::features::history::now()),
+            path => path.to_string(),
+        };
+        match std::fs::write(&path, log.to_json()) {
+            Ok(()) => self.show_success(format!(
+                "Saved {} WebSocket messages to {}",
+                log.messages.len(),
+                path
+            )),
+            Err(e) => self.show_error(format!("Couldn't write {}: {}", path, e)),
         }
-        code.push_str("  },\n");
+    }
 
-        if tab.body_type == BodyType::Raw && !tab.request_body.is_empty() {
-            code.push_str(&format!("  body: JSON.stringify({})\n", tab.request_body));
-        } else if tab.body_type == BodyType::FormData {
-            code.push_str("  body: formData\n");
+    /// `:wsreplay <file>` sends a session log's sent messages again, `:wsreplay`
+    /// the ones on screen, `:wsreplay stop` stops. It goes over a new connection to
+    /// the URL in the WebSocket bar.
+    pub fn ws_replay_command(&mut self, args: &str) {
+        use crate::features::ws_session::SessionLog;
+        let log = match args.trim() {
+            "stop" => {
+                self.stop_ws_replay();
+                return;
+            }
+            "" => {
+                let tab = self.active_tab();
+                SessionLog::from_messages(&tab.ws_url, &tab.ws_messages)
+            }
+            path => match std::fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| SessionLog::parse(&content))
+            {
+                Ok(log) => log,
+                Err(e) => {
+                    self.show_error(format!("Couldn't replay {}: {}", path, e));
+                    return;
+                }
+            },
+        };
+        let schedule = log.replay_schedule();
+        if schedule.is_empty() {
+            self.show_error("No sent messages to replay".to_string());
+            return;
         }
+        let tab = self.active_tab_mut();
+        tab.app_mode = AppMode::WebSocket;
+        tab.input_mode = InputMode::Normal;
+        tab.ws_messages.clear();
+        tab.ws_scroll = 0;
+        tab.ws_replay = Some((0, schedule.len()));
+        let url = tab.ws_url.clone();
+        self.show_notification(format!("Replaying {} messages to {}", schedule.len(), url));
+        self.ws_replay_request = Some(schedule);
+    }
 
-        code.push_str("};\n\n");
+    pub fn stop_ws_replay(&mut self) {
+        if self.active_tab_mut().ws_replay.take().is_some() {
+            self.should_stop_ws_replay = true;
+            self.show_notification("Replay stopped".to_string());
+        }
+    }
 
-        if tab.body_type == BodyType::FormData {
-            code.push_str("// Note: Construct FormData manually if needed\n\n");
+    /// A replayed message went out
+    pub fn ws_replayed(&mut self, content: String) {
+        let tab = self.active_tab_mut();
+        tab.ws_messages
+            .push(crate::net::websocket::WsMessage::new(content, true));
+        if let Some((sent, _)) = &mut tab.ws_replay {
+            *sent += 1;
         }
+    }
 
-        code.push_str("try {\n  const response = await fetch(url, options);\n  const data = await response.json();\n  console.log(data);\n} catch (error) {\n  console.error(error);\n}");
-        code
+    pub fn ws_replay_finished(&mut self, count: usize) {
+        self.active_tab_mut().ws_replay = None;
+        self.show_success(format!("Replay done: {} messages sent", count));
     }
 
-    pub fn generate_go_code(&self) -> String {
-        let tab = self.active_tab();
-        let mut code =
-            String::from("package main\n\nimport (\n\t\"fmt\"\n\t\"net/http\"\n\t\"io/ioutil\"\n");
+    pub fn ws_replay_stopped(&mut self, reason: &str) {
+        self.active_tab_mut().ws_replay = None;
+        self.show_error(format!("Replay stopped: {}", reason));
+    }
 
-        if tab.body_type == BodyType::Raw && !tab.request_body.is_empty() {
-            code.push_str("\t\"strings\"\n");
+    /// `:history [query]` opens the view, `:history limit <n>` sets how many unpinned
+    /// entries are kept
+    pub fn history_command(&mut self, args: &str) {
+        let mut words = args.split_whitespace();
+        if words.next() != Some("limit") {
+            self.open_history_view(args);
+            return;
         }
-        if tab.body_type == BodyType::FormData {
-            code.push_str(
-                "\t\"bytes\"\n\t\"mime/multipart\"\n\t\"os\"\n\t\"io\"\n\t\"path/filepath\"\n",
-            );
+        match words.next().map(str::parse::<usize>) {
+            None => {
+                let msg = format!("History keeps {} entries", self.history_settings.limit);
+                self.show_notification(msg);
+            }
+            Some(Ok(limit)) if limit > 0 => {
+                self.history_settings.limit = limit;
+                crate::features::history::trim(&mut self.request_history, limit);
+                self.save_history();
+                self.save_config();
+                self.show_success(format!("History keeps {} entries (plus pinned)", limit));
+            }
+            _ => self.show_error("Usage: :history limit <number above 0>".to_string()),
         }
-        code.push_str(")\n\nfunc main() {\n");
-        code.push_str(&format!("\turl := \"{}\"\n", self.process_url()));
-        code.push_str(&format!("\tmethod := \"{}\"\n", tab.method));
+    }
 
-        if tab.body_type == BodyType::Raw && !tab.request_body.is_empty() {
-            let safe_body = tab.request_body.replace("`", "` + \"`\" + `");
-            code.push_str(&format!(
-                "\tpayload := strings.NewReader(`{}`)\n",
-                safe_body
-            ));
-            code.push_str("\n\tclient := &http.Client{}\n");
-            code.push_str("\treq, err := http.NewRequest(method, url, payload)\n");
-        } else if tab.body_type == BodyType::FormData {
-            code.push_str("\tpayload := &bytes.Buffer{}\n");
-            code.push_str("\twriter := multipart.NewWriter(payload)\n");
-            for (k, v, is_file) in &tab.form_data {
-                if *is_file {
-                    code.push_str(&format!("\tfile, err := os.Open(\"{}\")\n", v));
-                    code.push_str("\tif err != nil {\n\t\tfmt.Println(err)\n\t\treturn\n\t}\n\tdefer file.Close()\n");
-                    code.push_str(&format!(
-                        "\tpart, err := writer.CreateFormFile(\"{}\", filepath.Base(\"{}\"))\n",
-                        k, v
-                    ));
-                    code.push_str("\t_, err = io.Copy(part, file)\n");
-                } else {
-                    code.push_str(&format!("\t_ = writer.WriteField(\"{}\", \"{}\")\n", k, v));
-                }
+    /// Everything the finder can jump to, in the order shown for an empty query
+    pub fn finder_items(&self) -> Vec<crate::features::finder::Item> {
+        use crate::features::finder::{Item, ItemKind, Target};
+        let mut items = Vec::new();
+        for (i, tab) in self.tabs.iter().enumerate() {
+            items.push(Item {
+                kind: ItemKind::Tab,
+                label: tab.name.clone(),
+                detail: format!("{} {}", tab.method, tab.url),
+                target: Target::Tab(i),
+            });
+        }
+        for collection in &self.collections {
+            let mut names: Vec<&String> = collection.requests.keys().collect();
+            names.sort();
+            for name in names {
+                let request = &collection.requests[name];
+                items.push(Item {
+                    kind: ItemKind::Request,
+                    label: format!("{}/{}", collection.name, name),
+                    detail: format!("{} {}", request.method, request.url),
+                    target: Target::Request {
+                        collection: collection.name.clone(),
+                        request: name.clone(),
+                    },
+                });
             }
-            code.push_str("\terr := writer.Close()\n");
-            code.push_str("\tif err != nil {\n\t\tfmt.Println(err)\n\t\treturn\n\t}\n");
-
-            code.push_str("\n\tclient := &http.Client{}\n");
-            code.push_str("\treq, err := http.NewRequest(method, url, payload)\n");
-            code.push_str("\treq.Header.Set(\"Content-Type\", writer.FormDataContentType())\n");
-        } else {
-            code.push_str("\n\tclient := &http.Client{}\n");
-            code.push_str("\treq, err := http.NewRequest(method, url, nil)\n");
         }
+        for (i, log) in self.request_history.iter().enumerate() {
+            items.push(Item {
+                kind: ItemKind::History,
+                label: format!("{} {}", log.method, log.url),
+                detail: format!("{} · {}ms", log.status, log.latency),
+                target: Target::History(i),
+            });
+        }
+        for (i, env) in self.environments.iter().enumerate() {
+            let active = if i == self.selected_env_index {
+                " (active)"
+            } else {
+                ""
+            };
+            items.push(Item {
+                kind: ItemKind::Environment,
+                label: env.name.clone(),
+                detail: format!("{} variables{}", env.variables.len(), active),
+                target: Target::Environment(i),
+            });
+        }
+        items
+    }
 
-        code.push_str("\tif err != nil {\n\t\tfmt.Println(err)\n\t\treturn\n\t}\n");
+    pub fn finder_matches(&self) -> Vec<crate::features::finder::Match> {
+        crate::features::finder::search(&self.finder_items(), &self.finder_query)
+    }
 
-        for (k, v) in &tab.request_headers {
-            code.push_str(&format!("\treq.Header.Add(\"{}\", \"{}\")\n", k, v));
+    /// Jumps to the highlighted finder entry and closes the finder
+    pub fn confirm_finder(&mut self) {
+        use crate::features::finder::Target;
+        let selected = self
+            .finder_matches()
+            .into_iter()
+            .nth(self.finder_index)
+            .map(|m| m.item.target);
+        self.close_finder();
+        match selected {
+            Some(Target::Tab(i)) if i < self.tabs.len() => self.active_tab = i,
+            Some(Target::Request {
+                collection,
+                request,
+            }) => self.open_request(&format!("{}/{}", collection, request)),
+            Some(Target::History(i)) => self.restore_history_entry(i),
+            Some(Target::Environment(i)) if i < self.environments.len() => self.use_environment(i),
+            _ => {}
         }
+    }
 
-        if (tab.auth_type == AuthType::Bearer || tab.auth_type == AuthType::OAuth2)
-            && !tab.auth_token.is_empty()
-        {
-            code.push_str(&format!(
-                "\treq.Header.Add(\"Authorization\", \"Bearer {}\")\n",
-                tab.auth_token
-            ));
+    /// Copies the code for the highlighted picker entry and closes the picker
+    pub fn confirm_codegen_picker(&mut self) {
+        let selected = self
+            .filtered_codegen_targets()
+            .get(self.codegen_index)
+            .map(|t| t.name.clone());
+        self.close_codegen_picker();
+        if let Some(name) = selected {
+            self.copy_generated_code(&name);
         }
+    }
 
-        code.push_str("\n\tres, err := client.Do(req)\n");
-        code.push_str("\tif err != nil {\n\t\tfmt.Println(err)\n\t\treturn\n\t}\n");
-        code.push_str("\tdefer res.Body.Close()\n\n");
-        code.push_str("\tbody, err := ioutil.ReadAll(res.Body)\n");
-        code.push_str("\tif err != nil {\n\t\tfmt.Println(err)\n\t\treturn\n\t}\n");
-        code.push_str("\tfmt.Println(string(body))\n}\n");
-
-        code
+    pub fn copy_to_clipboard(&mut self, text: String) {
+        self.copy_to_clipboard_as(text, "Copied to clipboard!");
     }
 
-    pub fn generate_rust_code(&self) -> String {
-        let tab = self.active_tab();
-        let mut code = String::from(
-            "#[tokio::main]\nasync fn main() -> Result<(), Box<dyn std::error::Error>> {\n",
-        );
-        code.push_str("\tlet client = reqwest::Client::new();\n");
+    /// Copies `text` and reports `success_msg`, or the clipboard error if it failed
+    pub fn copy_to_clipboard_as(&mut self, text: String, success_msg: &str) {
+        if self.clipboard.is_none() {
+            // Try to re-initialize if it failed initially
+            self.clipboard = Clipboard::new().ok();
+        }
 
-        if tab.body_type == BodyType::FormData {
-            code.push_str("\tlet form = reqwest::multipart::Form::new()\n");
-            for (k, v, is_file) in &tab.form_data {
-                if *is_file {
-                    code.push_str(&format!("\t\t.file(\"{}\", \"{}\").await?\n", k, v));
-                } else {
-                    code.push_str(&format!("\t\t.text(\"{}\", \"{}\")\n", k, v));
-                }
+        if let Some(clipboard) = &mut self.clipboard {
+            if let Err(e) = clipboard.set_text(text) {
+                self.show_error(format!("Clipboard Error: {}", e));
+            } else {
+                self.show_success(success_msg.to_string());
             }
-            code.push_str("\t\t;\n");
+        } else {
+            self.show_error("Clipboard unavailable".to_string());
         }
+    }
 
-        code.push_str(&format!(
-            "\tlet res = client.request(reqwest::Method::{}, \"{}\")\n",
-            tab.method.to_uppercase(),
-            self.process_url()
-        ));
-
-        for (k, v) in &tab.request_headers {
-            code.push_str(&format!("\t\t.header(\"{}\", \"{}\")\n", k, v));
+    /// Text on the system clipboard, reporting why there is none
+    pub fn clipboard_text(&mut self) -> Option<String> {
+        if self.clipboard.is_none() {
+            self.clipboard = Clipboard::new().ok();
+        }
+        let Some(clipboard) = &mut self.clipboard else {
+            self.show_error("Clipboard unavailable".to_string());
+            return None;
+        };
+        match clipboard.get_text() {
+            Ok(text) => Some(text),
+            Err(e) => {
+                self.show_error(format!("Clipboard Error: {}", e));
+                None
+            }
         }
+    }
 
-        if (tab.auth_type == AuthType::Bearer || tab.auth_type == AuthType::OAuth2)
-            && !tab.auth_token.is_empty()
-        {
-            code.push_str(&format!("\t\t.bearer_auth(\"{}\")\n", tab.auth_token));
+    /// `=` / `-`: pretty-prints or minifies the request body (JSON or XML) in place;
+    /// for GraphQL it's the variables
+    pub fn format_body(&mut self, minify: bool) {
+        use crate::features::format;
+
+        let tab = self.active_tab_mut();
+        tab.selected_tab = 2;
+        let body = match tab.body_type {
+            BodyType::Raw => &mut tab.request_body,
+            BodyType::GraphQL => &mut tab.graphql_variables,
+            _ => {
+                self.show_notification(
+                    "Only raw and GraphQL bodies can be formatted here".to_string(),
+                );
+                return;
+            }
+        };
+        if body.trim().is_empty() {
+            self.show_notification("The body is empty".to_string());
+            return;
+        }
+        let result = if minify {
+            format::minify(body)
+        } else {
+            format::pretty(body)
+        };
+        match result {
+            Ok((kind, text)) => {
+                *body = text;
+                let action = if minify { "minified" } else { "pretty-printed" };
+                self.show_success(format!("Body {} ({})", action, kind.label()));
+            }
+            Err(e) => self.show_error(format!("Body not formatted: {}", e)),
         }
+    }
+
+    /// `J`: the response as received, or pretty (JSON tree, indented XML)
+    pub fn toggle_response_raw(&mut self) {
+        let tab = self.active_tab_mut();
+        tab.response_raw = !tab.response_raw;
+        tab.response_scroll = (0, 0);
+        let message = if tab.response_raw {
+            "Response shown raw, as received"
+        } else {
+            "Response shown pretty"
+        };
+        self.show_notification(message.to_string());
+    }
+
+    /// `V`: auto, then JSON, XML, HTML, text, binary, and back to auto
+    pub fn cycle_treat_as(&mut self) {
+        let next = match self.active_tab().treat_as {
+            None => Some(ResponseType::ALL[0]),
+            Some(t) => {
+                let i = ResponseType::ALL.iter().position(|a| *a == t).unwrap_or(0);
+                ResponseType::ALL.get(i + 1).copied()
+            }
+        };
+        self.set_treat_as(next);
+    }
 
-        if tab.body_type == BodyType::Raw && !tab.request_body.is_empty() {
-            let safe_body = tab.request_body.replace("\"", "\\\"");
-            code.push_str(&format!("\t\t.body(\"{}\")\n", safe_body));
-        } else if tab.body_type == BodyType::FormData {
-            code.push_str("\t\t.multipart(form)\n");
+    /// `:treat json|xml|html|text|binary|auto`
+    pub fn treat_command(&mut self, args: &str) {
+        match args.trim() {
+            "" => self.cycle_treat_as(),
+            "auto" => self.set_treat_as(None),
+            name => match ResponseType::parse(name) {
+                Some(t) => self.set_treat_as(Some(t)),
+                None => self.show_error(format!(
+                    "Unknown type '{}' (json, xml, html, text, binary or auto)",
+                    name
+                )),
+            },
         }
+    }
 
-        code.push_str("\t\t.send()\n\t\t.await?;\n");
-        code.push_str("\tprintln!(\"{}\", res.text().await?);\n");
-        code.push_str("\tOk(())\n}\n");
-        code
+    /// Re-renders the response as `treat_as`, or by its Content-Type again when
+    /// None
+    pub fn set_treat_as(&mut self, treat_as: Option<ResponseType>) {
+        let tab = self.active_tab_mut();
+        tab.treat_as = treat_as;
+        tab.response_scroll = (0, 0);
+        tab.json_list_state.select(None);
+        tab.reinterpret_response();
+        let unparsed = treat_as == Some(ResponseType::Json)
+            && tab.response_json.is_none()
+            && tab.response.is_some();
+        self.hex_view = None;
+        self.remember_treat_as();
+        match treat_as {
+            None => self.show_notification("Response type: from Content-Type".to_string()),
+            Some(_) if unparsed => {
+                self.show_error("Response isn't valid JSON, shown as highlighted text".to_string())
+            }
+            Some(t) => self.show_notification(format!("Response treated as {}", t.name())),
+        }
     }
 
-    pub fn generate_ruby_code(&self) -> String {
+    /// Opening the saved request again in this session brings the type back; `s`
+    /// writes it to the collection file
+    fn remember_treat_as(&mut self) {
         let tab = self.active_tab();
-        let mut code = String::from("require 'uri'\nrequire 'net/http'\n\n");
-        code.push_str(&format!("url = URI(\"{}\")\n\n", self.process_url()));
-        code.push_str("http = Net::HTTP.new(url.host, url.port)\n");
-        code.push_str("http.use_ssl = true\n\n");
-
-        let method_lower = tab.method.to_lowercase();
-        let method_start = method_lower.chars().next().unwrap_or('g').to_uppercase();
-        let method_rest = if method_lower.len() > 1 {
-            &method_lower[1..]
-        } else {
-            ""
+        let (Some(collection), Some(name)) = (tab.collection.clone(), tab.request_name.clone())
+        else {
+            return;
         };
-        let method_class = format!("{}{}", method_start, method_rest);
+        let treat_as = tab.treat_as.map(|t| t.name().to_string());
+        if let Some(request) = self
+            .collections
+            .iter_mut()
+            .find(|c| c.name == collection)
+            .and_then(|c| c.requests.get_mut(&name))
+        {
+            request.treat_as = treat_as;
+        }
+    }
+
+    /// What the response is highlighted as
+    pub fn response_extension(&self) -> String {
+        match self.active_tab().treat_as {
+            Some(t) => t.extension().to_string(),
+            None => self.guess_extension().unwrap_or("txt".to_string()),
+        }
+    }
 
-        code.push_str(&format!("request = Net::HTTP::{}.new(url)\n", method_class));
+    pub fn copy_response(&mut self) {
+        let tab = self.active_tab();
+        if tab.response_is_binary {
+            self.show_error("Cannot copy binary response to clipboard".to_string());
+            return;
+        }
 
-        for (k, v) in &tab.request_headers {
-            code.push_str(&format!("request[\"{}\"] = \"{}\"\n", k, v));
+        if let Some(ref response) = tab.response {
+            let text = response.clone();
+            self.copy_to_clipboard(text);
+        } else {
+            self.show_notification("No response to copy".to_string());
         }
+    }
 
-        if (tab.auth_type == AuthType::Bearer || tab.auth_type == AuthType::OAuth2)
-            && !tab.auth_token.is_empty()
-        {
-            code.push_str(&format!(
-                "request[\"Authorization\"] = \"Bearer {}\"\n",
-                tab.auth_token
-            ));
+    /// Sets the `Range` header from a spec like `0-1023` or `first 64k`; `off` removes it
+    pub fn set_range(&mut self, spec: &str) {
+        let tab = self.active_tab_mut();
+        tab.request_headers
+            .retain(|k, _| !k.eq_ignore_ascii_case("range"));
+        if matches!(spec.trim(), "off" | "none" | "clear") {
+            tab.resume_download = None;
+            self.show_notification("Range header removed".to_string());
+            return;
         }
 
-        if tab.body_type == BodyType::Raw && !tab.request_body.is_empty() {
-            let safe_body = tab.request_body.replace("\"", "\\\"");
-            code.push_str(&format!("request.body = \"{}\"\n", safe_body));
-        } else if tab.body_type == BodyType::FormData {
-            code.push_str("boundary = \"PostDadBoundary\"\n");
-            code.push_str(
-                "request[\"Content-Type\"] = \"multipart/form-data; boundary=#{boundary}\"\n",
-            );
-            code.push_str("body = []\n");
-            for (k, v, is_file) in &tab.form_data {
-                if *is_file {
-                    code.push_str("body << \"--#{boundary}\\r\\n\"\n");
-                    code.push_str(&format!("body << \"Content-Disposition: form-data; name=\\\"{}\\\"; filename=\\\"{}\\\"\\r\\n\"\n", k, v));
-                    code.push_str(
-                        "body << \"Content-Type: application/octet-stream\\r\\n\\r\\n\"\n",
-                    );
-                    code.push_str(&format!("body << File.read(\"{}\")\n", v));
-                    code.push_str("body << \"\\r\\n\"\n");
-                } else {
-                    code.push_str("body << \"--#{boundary}\\r\\n\"\n");
-                    code.push_str(&format!("body << \"Content-Disposition: form-data; name=\\\"{}\\\";\\r\\n\\r\\n\"\n", k));
-                    code.push_str(&format!("body << \"{}\\r\\n\"\n", v));
-                }
+        match crate::features::range::parse_range_spec(spec) {
+            Ok(value) => {
+                self.active_tab_mut()
+                    .request_headers
+                    .insert("Range".to_string(), value.clone());
+                self.show_notification(format!("Range: {}", value));
             }
-            code.push_str("body << \"--#{boundary}--\\r\\n\"\n");
-            code.push_str("request.body = body.join\n");
+            Err(e) => self.show_error(e),
         }
+    }
 
-        code.push_str("\nresponse = http.request(request)\n");
-        code.push_str("puts response.read_body\n");
-        code
+    /// Prepares the request to continue a partial download into `path`
+    pub fn resume_download(&mut self, path: &str) {
+        let value = crate::features::range::resume_range_header(path);
+        let tab = self.active_tab_mut();
+        tab.request_headers
+            .retain(|k, _| !k.eq_ignore_ascii_case("range"));
+        tab.request_headers
+            .insert("Range".to_string(), value.clone());
+        tab.resume_download = Some(path.to_string());
+        self.show_notification(format!("Range: {} - send to resume into {}", value, path));
     }
 
-    pub fn generate_php_code(&self) -> String {
-        let tab = self.active_tab();
-        let mut code =
-            String::from("<?php\n\n$curl = curl_init();\n\ncurl_setopt_array($curl, array(\n");
-        code.push_str(&format!("  CURLOPT_URL => '{}',\n", self.process_url()));
-        code.push_str("  CURLOPT_RETURNTRANSFER => true,\n  CURLOPT_ENCODING => '',\n  CURLOPT_MAXREDIRS => 10,\n  CURLOPT_TIMEOUT => 0,\n  CURLOPT_FOLLOWLOCATION => true,\n  CURLOPT_HTTP_VERSION => CURL_HTTP_VERSION_1_1,\n");
-        code.push_str(&format!("  CURLOPT_CUSTOMREQUEST => '{}',\n", tab.method));
-
-        if tab.body_type == BodyType::Raw && !tab.request_body.is_empty() {
-            let safe_body = tab.request_body.replace("'", "\\'");
-            code.push_str(&format!("  CURLOPT_POSTFIELDS => '{}',\n", safe_body));
-        } else if tab.body_type == BodyType::FormData {
-            code.push_str("  CURLOPT_POSTFIELDS => array(\n");
-            for (k, v, is_file) in &tab.form_data {
-                if *is_file {
-                    code.push_str(&format!("    '{}' => new CURLFile('{}'),\n", k, v));
-                } else {
-                    code.push_str(&format!("    '{}' => '{}',\n", k, v));
-                }
+    /// Writes the response into the pending resume file, if there is one
+    pub fn finish_resumed_download(&mut self) {
+        let tab = self.active_tab_mut();
+        let Some(path) = tab.resume_download.take() else {
+            return;
+        };
+        tab.request_headers
+            .retain(|k, _| !k.eq_ignore_ascii_case("range"));
+
+        let status = tab.status_code.unwrap_or(0);
+        let bytes = tab.response_bytes.clone().unwrap_or_default();
+        let range = tab
+            .response_headers
+            .get("content-range")
+            .and_then(|v| crate::features::range::parse_content_range(v));
+
+        let result = match status {
+            206 if range.is_some() => {
+                crate::features::range::write_resumed(&path, range.as_ref(), &bytes)
             }
-            code.push_str("  ),\n");
+            200 => crate::features::range::write_resumed(&path, None, &bytes),
+            // Nothing left to fetch
+            416 => Err(format!("{} is already complete", path)),
+            _ => Err(format!("Not resuming, server answered {}", status)),
+        };
+        match result {
+            Ok(size) if status == 206 => {
+                self.show_success(format!("Resumed {} ({} bytes)", path, size))
+            }
+            Ok(size) => self.show_success(format!(
+                "Server ignored Range, saved full {} ({} bytes)",
+                path, size
+            )),
+            Err(e) => self.show_error(e),
         }
+    }
 
-        code.push_str("  CURLOPT_HTTPHEADER => array(\n");
-        for (k, v) in &tab.request_headers {
-            code.push_str(&format!("    '{}: {}',\n", k, v));
-        }
-        if (tab.auth_type == AuthType::Bearer || tab.auth_type == AuthType::OAuth2)
-            && !tab.auth_token.is_empty()
-        {
-            code.push_str(&format!(
-                "    'Authorization: Bearer {}',\n",
-                tab.auth_token
-            ));
+    /// Evaluates a JSONPath against the current response and shows only the matches in the explorer
+    pub fn run_jsonpath_query(&mut self, query: &str) {
+        let Some(root) = self
+            .active_tab()
+            .response
+            .as_deref()
+            .and_then(|text| serde_json::from_str::<Value>(text).ok())
+        else {
+            self.show_error("Response is not JSON".to_string());
+            return;
+        };
+
+        match jsonpath_lib::select(&root, query) {
+            Ok(matches) => {
+                let count = matches.len();
+                let matches = Value::Array(matches.into_iter().cloned().collect());
+                let tab = self.active_tab_mut();
+                tab.set_response_json(Some(vec![JsonEntry::from_parsed(
+                    "matches".to_string(),
+                    matches,
+                    0,
+                )]));
+                tab.jsonpath_query = Some(query.to_string());
+                tab.search_query.clear();
+                tab.json_list_state.select(Some(0));
+                self.show_notification(format!("{} match(es) for {}", count, query));
+            }
+            Err(e) => self.show_error(format!("Invalid JSONPath: {}", e)),
         }
-        code.push_str(
-            "  ),\n));\n\n$response = curl_exec($curl);\n\ncurl_close($curl);\necho $response;\n",
+    }
+
+    /// Drops the JSONPath query and brings back the full response tree
+    pub fn clear_jsonpath_query(&mut self) {
+        let tab = self.active_tab_mut();
+        tab.jsonpath_query = None;
+        tab.set_response_json(
+            tab.response
+                .as_deref()
+                .and_then(|text| serde_json::from_str::<Value>(text).ok())
+                .map(|val| vec![JsonEntry::from_parsed("root".to_string(), val, 0)]),
         );
-        code
+        tab.json_list_state.select(Some(0));
     }
 
-    pub fn generate_csharp_code(&self) -> String {
-        let tab = self.active_tab();
-        let mut code = String::from("var client = new HttpClient();\n");
-        let method_start = tab.method.chars().next().unwrap_or('G').to_uppercase();
-        let method_rest = if tab.method.len() > 1 {
-            tab.method[1..].to_lowercase()
-        } else {
-            String::new()
+    /// Adds the active JSONPath query as a Chain extract rule for `var`
+    pub fn add_jsonpath_to_chain(&mut self, var: &str) {
+        let Some(query) = self.active_tab().jsonpath_query.clone() else {
+            self.show_error("No JSONPath query active (use :jsonpath <path> first)".to_string());
+            return;
         };
-        let method = format!("{}{}", method_start, method_rest);
-        code.push_str(&format!(
-            "var request = new HttpRequestMessage(HttpMethod.{}, \"{}\");\n",
-            method,
-            self.process_url()
-        ));
+        let tab = self.active_tab_mut();
+        if let Some(rule) = tab.extract_rules.iter_mut().find(|(k, _)| k == var) {
+            rule.1 = query.clone();
+        } else {
+            tab.extract_rules.push((var.to_string(), query.clone()));
+        }
+        self.show_success(format!("Chain: {} <- {}", var, query));
+    }
 
-        for (k, v) in &tab.request_headers {
-            code.push_str(&format!("request.Headers.Add(\"{}\", \"{}\");\n", k, v));
+    /// The JSON node under the cursor in the response tree, or the leaf in the flat view
+    pub fn selected_json_entry(&self) -> Option<std::borrow::Cow<'_, JsonEntry>> {
+        let tab = self.active_tab();
+        let selected_idx = tab.json_list_state.selected()?;
+        let entries = tab.response_json.as_ref()?;
+        if tab.json_flat {
+            return JsonEntry::flatten(entries, &tab.search_query)
+                .into_iter()
+                .nth(selected_idx)
+                .map(|(_, entry)| std::borrow::Cow::Owned(entry));
         }
+        let path = tab
+            .json_rows
+            .path(entries, &tab.search_query, selected_idx)?;
+        crate::ui::json_rows::entry(entries, &path).map(std::borrow::Cow::Borrowed)
+    }
 
-        if (tab.auth_type == AuthType::Bearer || tab.auth_type == AuthType::OAuth2)
-            && !tab.auth_token.is_empty()
-        {
-            code.push_str(&format!(
-                "request.Headers.Add(\"Authorization\", \"Bearer {}\");\n",
-                tab.auth_token
-            ));
+    /// JSONPath of the node under the cursor
+    pub fn selected_json_path(&self) -> Option<String> {
+        let tab = self.active_tab();
+        let selected_idx = tab.json_list_state.selected()?;
+        let entries = tab.response_json.as_ref()?;
+        if tab.json_flat {
+            return JsonEntry::flatten(entries, &tab.search_query)
+                .into_iter()
+                .nth(selected_idx)
+                .map(|(path, _)| JsonEntry::flat_json_path(&path));
         }
+        let path = tab
+            .json_rows
+            .path(entries, &tab.search_query, selected_idx)?;
+        Some(crate::ui::json_rows::json_path(entries, &path))
+    }
 
-        if tab.body_type == BodyType::Raw && !tab.request_body.is_empty() {
-            let safe_body = tab.request_body.replace("\"", "\\\"");
-            code.push_str(&format!(
-                "var content = new StringContent(\"{}\", null, \"application/json\");\n",
-                safe_body
-            ));
-            code.push_str("request.Content = content;\n");
-        } else if tab.body_type == BodyType::FormData {
-            code.push_str("var content = new MultipartFormDataContent();\n");
-            for (k, v, is_file) in &tab.form_data {
-                if *is_file {
-                    code.push_str(&format!("content.Add(new ByteArrayContent(File.ReadAllBytes(\"{}\")), \"{}\", \"{}\");\n", v, k, v));
+    /// Switches the JSON response between the tree and the flat list of paths
+    pub fn toggle_json_flat(&mut self) {
+        let tab = self.active_tab_mut();
+        tab.json_flat = !tab.json_flat;
+        tab.json_list_state.select(Some(0));
+        let mode = if tab.json_flat {
+            "Flat paths (/ searches paths and values)"
+        } else {
+            "Tree view"
+        };
+        self.show_notification(mode.to_string());
+    }
+
+    /// `o` on a CSV response: sort by the column under the cursor, ascending, then
+    /// descending, then as received
+    pub fn sort_csv_column(&mut self) {
+        let Some(csv) = self.active_tab_mut().shown_csv() else {
+            self.show_notification("Sorting is for CSV responses".to_string());
+            return;
+        };
+        if csv.table.columns.is_empty() {
+            self.show_notification("The table has no columns".to_string());
+            return;
+        }
+        csv.cycle_sort();
+        let message = match csv.sort {
+            Some((column, descending)) => format!(
+                "Sorted by {} {}",
+                csv.table.columns[column],
+                if descending {
+                    "descending"
                 } else {
-                    code.push_str(&format!(
-                        "content.Add(new StringContent(\"{}\"), \"{}\");\n",
-                        v, k
-                    ));
+                    "ascending"
                 }
+            ),
+            None => "Rows in the order received".to_string(),
+        };
+        self.show_notification(message);
+    }
+
+    pub fn copy_json_value(&mut self) {
+        if let Some(csv) = self.active_tab_mut().shown_csv() {
+            match csv.cell().map(str::to_string) {
+                Some(text) => self.copy_to_clipboard_as(text, "Copied cell"),
+                None => self.show_notification("The table has no rows".to_string()),
             }
-            code.push_str("request.Content = content;\n");
+            return;
+        }
+        match self.selected_json_entry().map(|e| e.value_text()) {
+            Some(text) => self.copy_to_clipboard_as(text, "Copied value"),
+            None => self.show_notification("No JSON node selected".to_string()),
         }
-
-        code.push_str("var response = await client.SendAsync(request);\n");
-        code.push_str("response.EnsureSuccessStatusCode();\n");
-        code.push_str("Console.WriteLine(await response.Content.ReadAsStringAsync());\n");
-
-        code
     }
 
-    pub fn copy_to_clipboard(&mut self, text: String) {
-        if self.clipboard.is_none() {
-            // Try to re-initialize if it failed initially
-            self.clipboard = Clipboard::new().ok();
+    pub fn copy_json_subtree(&mut self) {
+        match self.selected_json_entry().map(|e| e.subtree_json()) {
+            Some(text) => self.copy_to_clipboard_as(text, "Copied subtree as JSON"),
+            None => self.show_notification("No JSON node selected".to_string()),
         }
+    }
 
-        if let Some(clipboard) = &mut self.clipboard {
-            if let Err(e) = clipboard.set_text(text) {
-                self.popup_message = Some(format!("Clipboard Error: {}", e));
-            } else {
-                self.popup_message = Some("Copied to clipboard!".to_string());
-            }
+    /// Writes the selected subtree to `{key}_{timestamp}.json` in the current directory
+    pub fn export_json_subtree(&mut self) {
+        let Some((key, json)) = self
+            .selected_json_entry()
+            .map(|e| (e.key.clone(), e.subtree_json()))
+        else {
+            self.show_notification("No JSON node selected".to_string());
+            return;
+        };
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let safe_key: String = key
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let safe_key = safe_key.trim_matches('_');
+        let filename = if safe_key.is_empty() {
+            format!("node_{}.json", timestamp)
         } else {
-            self.popup_message = Some("Clipboard unavailable".to_string());
+            format!("{}_{}.json", safe_key, timestamp)
+        };
+
+        match std::fs::write(&filename, json) {
+            Ok(()) => self.show_success(format!("Exported node to {}", filename)),
+            Err(e) => self.show_error(format!("Failed to export {}: {}", filename, e)),
         }
     }
 
-    pub fn copy_response(&mut self) {
+    /// JSON to export as a table: the selected node if it's an array, otherwise the whole response
+    fn table_source(&self) -> Option<Value> {
+        if let Some(entry) = self.selected_json_entry()
+            && entry.value().is_array()
+        {
+            return Some(entry.value().clone());
+        }
         let tab = self.active_tab();
-        if tab.response_is_binary {
-            self.popup_message = Some("Cannot copy binary response to clipboard".to_string());
+        serde_json::from_str(tab.response.as_deref()?).ok()
+    }
+
+    /// `:csv` / `:tsv`: array of objects to CSV or TSV, optionally only some columns,
+    /// to a file when `> name` is given, otherwise to the clipboard
+    pub fn export_table(&mut self, delimiter: char, args: &str) {
+        use crate::features::csv_export;
+
+        let Some(source) = self.table_source() else {
+            self.show_error("No JSON response to export".to_string());
             return;
-        }
+        };
+        let (columns, file) = csv_export::parse_export_args(args);
+        let table = match csv_export::to_table(&source).and_then(|t| t.select(&columns)) {
+            Ok(table) => table,
+            Err(e) => {
+                self.show_error(e);
+                return;
+            }
+        };
+        let text = table.to_delimited(delimiter);
+        let kind = if delimiter == '\t' { "TSV" } else { "CSV" };
+        let summary = format!(
+            "{} rows × {} columns",
+            table.rows.len(),
+            table.columns.len()
+        );
 
-        if let Some(ref response) = tab.response {
-            let text = response.clone();
-            self.copy_to_clipboard(text);
-            self.popup_message = Some("Response copied to clipboard!".to_string());
-        } else {
-            self.popup_message = Some("No response to copy".to_string());
+        match file {
+            Some(path) => match std::fs::write(&path, text) {
+                Ok(()) => self.show_success(format!("Exported {} ({}) to {}", kind, summary, path)),
+                Err(e) => self.show_error(format!("Failed to write {}: {}", path, e)),
+            },
+            None => self.copy_to_clipboard_as(text, &format!("Copied {} ({})", kind, summary)),
         }
     }
 
     pub fn toggle_current_selection(&mut self) {
+        self.update_selected_node(|node| node.set_expanded(!node.is_expanded));
+    }
+
+    /// Changes the tree node under the cursor, the explorer's rows with it
+    fn update_selected_node(&mut self, change: impl FnOnce(&mut JsonEntry)) {
         let tab = self.active_tab_mut();
-        if let Some(selected_idx) = tab.json_list_state.selected()
-            && let Some(entries) = &mut tab.response_json
+        if tab.json_flat {
+            return;
+        }
+        let Some(path) = tab.json_list_state.selected().and_then(|selected| {
+            let entries = tab.response_json.as_ref()?;
+            tab.json_rows.path(entries, &tab.search_query, selected)
+        }) else {
+            return;
+        };
+        if let Some(node) = tab
+            .response_json
+            .as_mut()
+            .and_then(|entries| crate::ui::json_rows::entry_mut(entries, &path))
         {
-            let mut current_idx = selected_idx;
-            if let Some(node) = Self::get_mut_node_at_index(entries, &mut current_idx) {
-                node.is_expanded = !node.is_expanded;
-            }
+            change(node);
+            tab.json_rows.invalidate();
         }
     }
 
     pub fn set_expanded_current_selection(&mut self, expanded: bool) {
         let tab = self.active_tab_mut();
-        if let Some(selected_idx) = tab.json_list_state.selected()
-            && let Some(entries) = &mut tab.response_json
-        {
-            let mut current_idx = selected_idx;
-            if let Some(node) = Self::get_mut_node_at_index(entries, &mut current_idx) {
-                node.is_expanded = expanded;
-            }
+        if let Some(csv) = tab.shown_csv() {
+            csv.move_column(if expanded { 1 } else { -1 });
+            return;
         }
+        self.update_selected_node(|node| node.set_expanded(expanded));
     }
 
     pub fn duplicate_tab(&mut self) {
@@ -2012,7 +9229,7 @@ impl App {
         new_tab.response_bytes = None;
         new_tab.response_is_binary = false;
         new_tab.response_image = None;
-        new_tab.response_json = None;
+        new_tab.set_response_json(None);
         new_tab.response_headers = std::collections::HashMap::new();
         new_tab.status_code = None;
         new_tab.latency = None;
@@ -2028,10 +9245,139 @@ impl App {
         self.show_notification("Tab Duplicated".to_string());
     }
 
+    /// Every bearer token in use (tab auth and environment variables), with a label for alerts
+    fn tracked_tokens(&self) -> Vec<(String, String)> {
+        let mut tokens: Vec<(String, String)> = Vec::new();
+        for tab in &self.tabs {
+            if matches!(tab.auth_type, AuthType::Bearer | AuthType::OAuth2)
+                && !tab.auth_token.is_empty()
+                && !tokens.iter().any(|(t, _)| *t == tab.auth_token)
+            {
+                tokens.push((tab.auth_token.clone(), format!("tab '{}'", tab.name)));
+            }
+        }
+        for env in &self.environments {
+            let mut vars: Vec<_> = env.variables.iter().collect();
+            vars.sort();
+            for (key, value) in vars {
+                if (self.oauth_grants.contains_key(value)
+                    || token_refresh::jwt_expiry(value).is_some())
+                    && !tokens.iter().any(|(t, _)| t == value)
+                {
+                    tokens.push((value.clone(), format!("{}.{}", env.name, key)));
+                }
+            }
+        }
+        tokens
+    }
+
+    /// Looks for tokens close to expiry. Alerts once per token and returns
+    /// the ones that can be refreshed in the background.
+    pub fn due_token_refreshes(&mut self, now: u64) -> Vec<(String, OAuthGrant)> {
+        let mut jobs = Vec::new();
+        for (token, label) in self.tracked_tokens() {
+            let grant = self.oauth_grants.get(&token).cloned();
+            let Some(expires_at) = token_refresh::token_expiry(&token, grant.as_ref()) else {
+                continue;
+            };
+            let can_refresh = grant.as_ref().is_some_and(|g| g.refresh_token.is_some())
+                && !self.token_alerts.contains(&token);
+
+            let alert = match token_refresh::action_for(expires_at, now, can_refresh) {
+                Some(TokenAction::Refresh) => {
+                    if let Some(grant) = grant
+                        && self.refreshing_tokens.insert(token.clone())
+                    {
+                        jobs.push((token.clone(), grant));
+                    }
+                    None
+                }
+                Some(TokenAction::Warn) => Some(format!(
+                    "Token for {} expires in {}s",
+                    label,
+                    expires_at.saturating_sub(now)
+                )),
+                Some(TokenAction::Expired) => Some(format!("Token for {} has expired", label)),
+                None => None,
+            };
+            if let Some(msg) = alert
+                && self.token_alerts.insert(token)
+            {
+                self.show_error(msg);
+            }
+        }
+        jobs
+    }
+
+    /// Remembers how to refresh a freshly obtained OAuth token
+    pub fn track_oauth_token(
+        &mut self,
+        response: &TokenResponse,
+        token_url: &str,
+        client_id: &str,
+    ) {
+        self.oauth_grants.insert(
+            response.access_token.clone(),
+            OAuthGrant {
+                token_url: token_url.to_string(),
+                client_id: client_id.to_string(),
+                refresh_token: response.refresh_token.clone(),
+                expires_at: response
+                    .expires_in
+                    .map(|secs| token_refresh::now_secs() + secs),
+            },
+        );
+    }
+
+    /// Swaps a refreshed token in everywhere the old one was used
+    pub fn apply_refreshed_token(&mut self, old_token: &str, response: TokenResponse) {
+        self.refreshing_tokens.remove(old_token);
+        let Some(old_grant) = self.oauth_grants.remove(old_token) else {
+            return;
+        };
+
+        let new_token = response.access_token.clone();
+        let mut replaced = 0;
+        for tab in &mut self.tabs {
+            if tab.auth_token == old_token {
+                tab.auth_token = new_token.clone();
+                replaced += 1;
+            }
+        }
+        for env in &mut self.environments {
+            for value in env.variables.values_mut() {
+                if value == old_token {
+                    *value = new_token.clone();
+                    replaced += 1;
+                }
+            }
+        }
+
+        let response = TokenResponse {
+            refresh_token: response.refresh_token.or(old_grant.refresh_token),
+            ..response
+        };
+        self.track_oauth_token(&response, &old_grant.token_url, &old_grant.client_id);
+        self.show_success(format!("Token refreshed ({} places updated)", replaced));
+    }
+
+    /// Refresh didn't work: stop retrying and tell the user once
+    pub fn token_refresh_failed(&mut self, old_token: &str, error: String) {
+        self.refreshing_tokens.remove(old_token);
+        self.token_alerts.insert(old_token.to_string());
+        self.show_error(format!("Token refresh failed: {}", error));
+    }
+
+    /// Pinned entries stay, unpin them in the history view first
     pub fn clear_history(&mut self) {
-        self.request_history.clear();
+        self.request_history.retain(|log| log.pinned);
+        self.history_marked.clear();
+        self.diff_base_index = None;
         self.save_history();
-        self.show_notification("Request History Cleared".to_string());
+        match self.request_history.len() {
+            0 => self.show_notification("Request History Cleared".to_string()),
+            n => self.show_notification(format!("Request History Cleared ({} pinned kept)", n)),
+        }
     }
 
     pub fn clear_cookies(&mut self) {
@@ -2040,43 +9386,47 @@ impl App {
         self.show_notification("Cookies Cleared".to_string());
     }
 
-    fn get_mut_node_at_index<'a>(
-        entries: &'a mut Vec<JsonEntry>,
-        target_index: &mut usize,
-    ) -> Option<&'a mut JsonEntry> {
-        for entry in entries {
-            if *target_index == 0 {
-                return Some(entry);
-            }
-            *target_index -= 1;
-
-            if entry.is_expanded
-                && let Some(child) = Self::get_mut_node_at_index(&mut entry.children, target_index)
-            {
-                return Some(child);
-            }
-        }
-        None
-    }
-
     pub fn calculate_visible_item_count(&self) -> usize {
         let tab = self.active_tab();
-        if let Some(entries) = &tab.response_json {
-            Self::count_visible(entries)
-        } else {
-            0
+        match &tab.response_json {
+            Some(entries) if tab.json_flat => JsonEntry::flatten(entries, &tab.search_query).len(),
+            Some(entries) => tab.json_rows.len(entries, &tab.search_query),
+            None => 0,
         }
     }
 
-    fn count_visible(entries: &[JsonEntry]) -> usize {
-        let mut count = 0;
-        for entry in entries {
-            count += 1;
-            if entry.is_expanded {
-                count += Self::count_visible(&entry.children);
-            }
+    /// A streamed response's headers are in: its body shows as it arrives
+    pub fn stream_started(
+        &mut self,
+        status: u16,
+        headers: std::collections::HashMap<String, String>,
+    ) {
+        let tab = self.active_tab_mut();
+        tab.status_code = Some(status);
+        tab.response_headers = headers;
+        tab.response = Some(String::new());
+        tab.set_response_json(None);
+        tab.response_is_binary = false;
+        tab.response_image = None;
+        tab.response_scroll = (0, 0);
+        tab.stream = Some(Default::default());
+    }
+
+    pub fn stream_chunk(&mut self, bytes: &[u8]) {
+        let tab = self.active_tab_mut();
+        if let Some(stream) = tab.stream.as_mut() {
+            stream.push(bytes, tab.response.get_or_insert_default());
         }
-        count
+    }
+
+    /// `O`: keep the end of a streaming response in view, or stop where it is
+    pub fn toggle_follow_stream(&mut self) {
+        self.follow_stream = !self.follow_stream;
+        self.show_notification(if self.follow_stream {
+            "Following streamed responses".to_string()
+        } else {
+            "Stopped following streamed responses".to_string()
+        });
     }
 
     pub fn scroll_down(&mut self) {
@@ -2084,6 +9434,9 @@ impl App {
     }
 
     pub fn scroll_up(&mut self) {
+        if self.active_tab().stream.is_some() {
+            self.follow_stream = false;
+        }
         let tab = self.active_tab_mut();
         if tab.response_scroll.0 > 0 {
             tab.response_scroll.0 -= 1;
@@ -2091,29 +9444,36 @@ impl App {
     }
 
     pub fn scroll_page_down(&mut self) {
+        let count = self.calculate_visible_item_count();
         let tab = self.active_tab_mut();
-        if let Some(entries) = &tab.response_json {
-            let count = Self::count_visible(entries);
-            if count > 0 {
-                let current = tab.json_list_state.selected().unwrap_or(0);
-                let next = (current + 10).min(count - 1);
-                tab.json_list_state.select(Some(next));
-                return;
-            }
+        if let Some(csv) = tab.shown_csv() {
+            csv.move_row(10);
+            return;
+        }
+        if tab.response_json.is_some() && count > 0 {
+            let current = tab.json_list_state.selected().unwrap_or(0);
+            let next = (current + 10).min(count - 1);
+            tab.json_list_state.select(Some(next));
+            return;
         }
         tab.response_scroll.0 += 10;
     }
 
     pub fn scroll_page_up(&mut self) {
+        if self.active_tab().stream.is_some() {
+            self.follow_stream = false;
+        }
+        let count = self.calculate_visible_item_count();
         let tab = self.active_tab_mut();
-        if let Some(entries) = &tab.response_json {
-            let count = Self::count_visible(entries);
-            if count > 0 {
-                let current = tab.json_list_state.selected().unwrap_or(0);
-                let next = current.saturating_sub(10);
-                tab.json_list_state.select(Some(next));
-                return;
-            }
+        if let Some(csv) = tab.shown_csv() {
+            csv.move_row(-10);
+            return;
+        }
+        if tab.response_json.is_some() && count > 0 {
+            let current = tab.json_list_state.selected().unwrap_or(0);
+            let next = current.saturating_sub(10);
+            tab.json_list_state.select(Some(next));
+            return;
         }
         if tab.response_scroll.0 > 10 {
             tab.response_scroll.0 -= 10;
@@ -2123,6 +9483,14 @@ impl App {
     }
 
     pub fn next_item(&mut self) {
+        if self.active_tab().show_docs {
+            self.scroll_down();
+            return;
+        }
+        if let Some(csv) = self.active_tab_mut().shown_csv() {
+            csv.move_row(1);
+            return;
+        }
         let count = self.calculate_visible_item_count();
         if count == 0 {
             self.scroll_down();
@@ -2144,6 +9512,14 @@ impl App {
     }
 
     pub fn previous_item(&mut self) {
+        if self.active_tab().show_docs {
+            self.scroll_up();
+            return;
+        }
+        if let Some(csv) = self.active_tab_mut().shown_csv() {
+            csv.move_row(-1);
+            return;
+        }
         let count = self.calculate_visible_item_count();
         if count == 0 {
             self.scroll_up();
@@ -2163,6 +9539,210 @@ impl App {
         };
         tab.json_list_state.select(Some(i));
     }
+
+    /// Mouse wheel over the response, `rows` down or up when negative: lines of
+    /// text, or rows of the JSON tree or the CSV table
+    pub fn scroll_response(&mut self, rows: isize) {
+        if rows < 0 && self.active_tab().stream.is_some() {
+            self.follow_stream = false;
+        }
+        let count = self.calculate_visible_item_count();
+        let tab = self.active_tab_mut();
+        if tab.show_docs {
+            tab.response_scroll.0 = tab.response_scroll.0.saturating_add_signed(rows as i16);
+            return;
+        }
+        if let Some(csv) = tab.shown_csv() {
+            csv.move_row(rows);
+            return;
+        }
+        if tab.response_json.is_some() && count > 0 {
+            let current = tab.json_list_state.selected().unwrap_or(0);
+            let next = current.saturating_add_signed(rows).min(count - 1);
+            tab.json_list_state.select(Some(next));
+            return;
+        }
+        tab.response_scroll.0 = tab.response_scroll.0.saturating_add_signed(rows as i16);
+    }
+
+    /// A click on the `row`th line of the JSON tree: selects it, or expands or
+    /// collapses it when it already was
+    pub fn click_response_row(&mut self, row: usize) {
+        let count = self.calculate_visible_item_count();
+        let tab = self.active_tab_mut();
+        if tab.response_json.is_none() || tab.show_docs {
+            return;
+        }
+        let index = tab.json_list_state.offset() + row;
+        if index >= count {
+            return;
+        }
+        if tab.json_list_state.selected() == Some(index) {
+            self.toggle_current_selection();
+        } else {
+            tab.json_list_state.select(Some(index));
+        }
+    }
+
+    /// The list on the current config tab: its selected row, length and first
+    /// row on screen. None on tabs without one.
+    fn config_list(&self) -> Option<(Option<usize>, usize, usize)> {
+        let tab = self.active_tab();
+        let state = match (tab.selected_tab, tab.body_type) {
+            (0, _) => {
+                return Some((
+                    self.params_row(),
+                    tab.path_params.len() + tab.params.len(),
+                    tab.params_list_state.offset(),
+                ));
+            }
+            (1, _) => {
+                return Some((
+                    tab.headers_list_state.selected(),
+                    self.header_row_count(),
+                    tab.headers_list_state.offset(),
+                ));
+            }
+            (2, BodyType::FormData) => (&tab.form_list_state, tab.form_data.len()),
+            (2, BodyType::UrlEncoded) => (&tab.urlencoded_list_state, tab.urlencoded.len()),
+            (4, _) => (&tab.extract_list_state, tab.extract_rules.len()),
+            (5, _) => (
+                &tab.settings_list_state,
+                crate::net::timeouts::Phase::ALL.len(),
+            ),
+            _ => return None,
+        };
+        Some((state.0.selected(), state.1, state.0.offset()))
+    }
+
+    fn select_config_row(&mut self, row: usize) {
+        let tab = self.active_tab_mut();
+        match (tab.selected_tab, tab.body_type) {
+            (0, _) => self.select_params_row(row),
+            (1, _) => tab.headers_list_state.select(Some(row)),
+            (2, BodyType::FormData) => tab.form_list_state.select(Some(row)),
+            (2, BodyType::UrlEncoded) => tab.urlencoded_list_state.select(Some(row)),
+            (4, _) => tab.extract_list_state.select(Some(row)),
+            (5, _) => tab.settings_list_state.select(Some(row)),
+            _ => {}
+        }
+    }
+
+    /// A click on the `row`th line of the params, headers, form or chain list
+    pub fn click_config_row(&mut self, row: usize) {
+        if let Some((_, len, offset)) = self.config_list()
+            && offset + row < len
+        {
+            self.select_config_row(offset + row);
+        }
+    }
+
+    /// Mouse wheel over the config pane: moves the list's selection, stopping at
+    /// the ends instead of wrapping like `j` / `k`
+    pub fn scroll_config(&mut self, rows: isize) {
+        if let Some((selected, len, _)) = self.config_list()
+            && len > 0
+        {
+            let row = selected.map_or(0, |s| s.saturating_add_signed(rows).min(len - 1));
+            self.select_config_row(row);
+        }
+    }
+
+    /// A click on the `row`th line of the sidebar: selects it, or opens it when it
+    /// already was
+    pub fn click_sidebar_row(&mut self, row: usize) {
+        let index = self.collection_state.offset() + row;
+        if index >= self.flattened_count() {
+            return;
+        }
+        if self.collection_state.selected() == Some(index) {
+            self.load_selected_request();
+        } else {
+            self.collection_state.select(Some(index));
+        }
+    }
+
+    /// Mouse wheel over the sidebar
+    pub fn scroll_sidebar(&mut self, rows: isize) {
+        let len = self.flattened_count();
+        if len > 0 {
+            let row = self
+                .collection_state
+                .selected()
+                .map_or(0, |s| s.saturating_add_signed(rows).min(len - 1));
+            self.collection_state.select(Some(row));
+        }
+    }
+
+    /// Mouse wheel over the WebSocket messages
+    pub fn scroll_ws_messages(&mut self, rows: isize) {
+        let tab = self.active_tab_mut();
+        let last = tab.ws_messages.len().saturating_sub(1);
+        tab.ws_scroll = tab.ws_scroll.saturating_add_signed(rows).min(last);
+    }
+
+    /// Clicked on the URL `columns` display columns into it: starts editing there
+    pub fn click_url(&mut self, columns: u16) {
+        let tab = self.active_tab_mut();
+        tab.url_cursor_index = crate::ui::layout::byte_at(&tab.url, columns);
+        tab.input_mode = InputMode::Editing;
+        self.active_sidebar = false;
+    }
+
+    /// Dragged the sidebar's edge: `percent` of the width, within the limits
+    pub fn resize_sidebar(&mut self, percent: u16) {
+        self.sidebar_width = percent.clamp(
+            crate::ui::layout::MIN_SIDEBAR_PERCENT,
+            crate::ui::layout::MAX_SIDEBAR_PERCENT,
+        );
+    }
+
+    /// Something drawn over the main screen that the mouse shouldn't reach through
+    pub fn overlay_open(&self) -> bool {
+        let tab = self.active_tab();
+        self.response_compare.is_some()
+            || self.guard_prompt.is_some()
+            || self.send_warnings.is_some()
+            || self.sync_prompt.is_some()
+            || self.bundle_prompt.is_some()
+            || self.passphrase_prompt.is_some()
+            || self.recovery_prompt.is_some()
+            || self.show_notification_history
+            || self.show_timeline
+            || self.wire_view.is_some()
+            || self.fuzz_progress.is_some()
+            || self.show_fuzz_report
+            || self.show_batch
+            || self.show_ndjson
+            || self.doctor_report.is_some()
+            || self.audit_report.is_some()
+            || self.collection_diff.is_some()
+            || self.variables_inspector.is_some()
+            || self.env_usage.is_some()
+            || self.dedupe.is_some()
+            || self.latency_chart.is_some()
+            || self.log_view.is_some()
+            || self.proto_view.is_some()
+            || self.dashboard.is_some()
+            || self.show_keymap
+            || self.stress_running
+            || self.show_stress_modal
+            || self.stress_stats.is_some()
+            || self.show_command_palette
+            || self.show_codegen_picker
+            || self.show_snippet_picker
+            || self.show_finder
+            || self.show_history_view
+            || self.show_cookie_modal
+            || self.theme_picker.is_some()
+            || tab.show_schema_modal
+            || tab.show_grpc_services_modal
+            || tab.show_grpc_description_modal
+            || matches!(
+                tab.input_mode,
+                InputMode::ImportCurl | InputMode::SaveHistory
+            )
+    }
 }
 
 // Commands for Command Palette
@@ -2174,10 +9754,18 @@ pub struct CommandAction {
 
 pub fn get_available_commands() -> Vec<CommandAction> {
     vec![
+        CommandAction {
+            name: "Find Anything",
+            desc: "Fuzzy search requests, tabs, history and environments",
+        },
         CommandAction {
             name: "New Tab",
             desc: "Open a new request tab",
         },
+        CommandAction {
+            name: "Rename Tab",
+            desc: "Name the current tab (also the name it's saved under)",
+        },
         CommandAction {
             name: "Duplicate Tab",
             desc: "Duplicate current tab",
@@ -2206,6 +9794,10 @@ pub fn get_available_commands() -> Vec<CommandAction> {
             name: "Switch Theme",
             desc: "Rotate through themes",
         },
+        CommandAction {
+            name: "Choose Theme",
+            desc: "Pick a theme with a preview, themes/*.toml included",
+        },
         CommandAction {
             name: "Toggle WebSocket",
             desc: "Switch between HTTP/WebSocket",
@@ -2214,9 +9806,13 @@ pub fn get_available_commands() -> Vec<CommandAction> {
             name: "Filter Collections",
             desc: "Search/Filter sidebar",
         },
+        CommandAction {
+            name: "Browse History",
+            desc: "Search, pin and delete history entries",
+        },
         CommandAction {
             name: "Clear History",
-            desc: "Clear request history",
+            desc: "Clear request history (pinned entries stay)",
         },
         CommandAction {
             name: "Clear Cookies",
@@ -2226,10 +9822,146 @@ pub fn get_available_commands() -> Vec<CommandAction> {
             name: "Manage Cookies",
             desc: "View and delete cookies",
         },
+        CommandAction {
+            name: "Re-send and Compare",
+            desc: "Send again and diff against the current response",
+        },
+        CommandAction {
+            name: "Switch Workspace",
+            desc: "Load the next workspace (collections, history, cookies)",
+        },
+        CommandAction {
+            name: "Encrypt History & Cookies",
+            desc: "Keep history, cookies and env secrets encrypted (:encrypt)",
+        },
+        CommandAction {
+            name: "Sync with Gist",
+            desc: "Push and pull collections, asking about conflicts (:sync)",
+        },
+        CommandAction {
+            name: "Sync with Storage",
+            desc: "Mirror the workspace to WebDAV, S3 or a shared folder (:storage)",
+        },
+        CommandAction {
+            name: "Export Workspace Bundle",
+            desc: "Collections, envs and mocks in one file, no secrets (:bundle)",
+        },
+        CommandAction {
+            name: "Switch Profile",
+            desc: "Cycle workspace profiles (profiles.hcl)",
+        },
+        CommandAction {
+            name: "Generate Code",
+            desc: "Copy the request as code (templates in codegen/)",
+        },
+        CommandAction {
+            name: "Fetch All Pages",
+            desc: "Walk a paged API and join the items (:paginate)",
+        },
+        CommandAction {
+            name: "Insert Snippet",
+            desc: "Add a script snippet to the request (snippets/)",
+        },
+        CommandAction {
+            name: "Flush Connections / DNS",
+            desc: "Drop pooled connections and cached DNS (:pool to configure)",
+        },
+        CommandAction {
+            name: "Save Response Snapshot",
+            desc: "Expect this response from the request from now on (:snapshot)",
+        },
+        CommandAction {
+            name: "Treat Response As",
+            desc: "Render as JSON, XML, HTML, text or binary, then back to auto",
+        },
+        CommandAction {
+            name: "Copy Response as CSV",
+            desc: "Array of objects as CSV (:csv cols > file for more)",
+        },
+        CommandAction {
+            name: "Notification History",
+            desc: "Show past notifications",
+        },
+        CommandAction {
+            name: "Request Timeline",
+            desc: "Restore the tab to how it was at an earlier send",
+        },
+        CommandAction {
+            name: "Security Audit",
+            desc: "Grade the response's security headers, cookies, CORS and TLS (A)",
+        },
+        CommandAction {
+            name: "Send All Tabs",
+            desc: "Send every open tab at once and sum up status and latency (:send all)",
+        },
+        CommandAction {
+            name: "Fuzz Request",
+            desc: "Send mutated variants and group the responses (:fuzz)",
+        },
+        CommandAction {
+            name: "Preview Request",
+            desc: "The raw request the next send puts on the wire (:wire)",
+        },
+        CommandAction {
+            name: "Show Keymap",
+            desc: "Every remappable action and the keys it is on (keymap.toml)",
+        },
+        CommandAction {
+            name: "Doctor",
+            desc: "Check terminal, tools, workspace files and proxy",
+        },
+        CommandAction {
+            name: "Save Tabs as Collection",
+            desc: "Snapshot all open tabs into a new collection",
+        },
         CommandAction {
             name: "Export HTML Docs",
             desc: "Generate API_DOCS.html",
         },
+        CommandAction {
+            name: "Health Dashboard",
+            desc: "Requests tagged health across collections, checked on an interval (:dashboard)",
+        },
+        CommandAction {
+            name: "Clear Tag Filter",
+            desc: "Show requests with any tags again (:tag filter <tags> narrows it)",
+        },
+        CommandAction {
+            name: "Find Duplicate Requests",
+            desc: "Same method and URL across collections: compare, merge or delete (:dedupe)",
+        },
+        CommandAction {
+            name: "Environment Variable Usage",
+            desc: "The environment's variables and the saved requests using them (:vars env)",
+        },
+        CommandAction {
+            name: "Export History",
+            desc: "Request history to history.csv: status, latency, size, tests (:export)",
+        },
+        CommandAction {
+            name: "Export Run Responses",
+            desc: "Every body and header set from the last run, a folder per request (:export responses)",
+        },
+        CommandAction {
+            name: "Copy Trace Link",
+            desc: "The last send's trace link, or its trace id (:trace copy)",
+        },
+        CommandAction {
+            name: "Open Trace",
+            desc: "Open the last send's trace in Jaeger, Tempo, ... (:trace open)",
+        },
+        CommandAction {
+            name: "Logs",
+            desc: "What happened to each request: network, scripts, runner (:logs)",
+        },
+        CommandAction {
+            name: "Latency Trend",
+            desc: "The request's latency across sessions, with percentiles (:trend)",
+        },
+        CommandAction {
+            name: "Proto Registry",
+            desc: "Proto roots and import paths for gRPC requests (:protos)",
+        },
         CommandAction {
             name: "Help",
             desc: "Show keyboard shortcuts",
@@ -2240,3 +9972,35 @@ pub fn get_available_commands() -> Vec<CommandAction> {
         },
     ]
 }
+
+/// Case-insensitive (ASCII) substring search, returns `(line, byte offset)` of each match
+pub fn find_text_matches(text: &str, query: &str) -> Vec<(usize, usize)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let query = query.to_ascii_lowercase();
+    let mut matches = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        let lower = line.to_ascii_lowercase();
+        let mut start = 0;
+        while let Some(pos) = lower[start..].find(&query) {
+            matches.push((line_no, start + pos));
+            start += pos + query.len();
+        }
+    }
+    matches
+}
+
+/// Encodes key/value pairs as an `application/x-www-form-urlencoded` string
+pub fn encode_urlencoded(pairs: &[(String, String)]) -> String {
+    form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(pairs.iter())
+        .finish()
+}
+
+/// Parses an `application/x-www-form-urlencoded` string into key/value pairs
+pub fn parse_urlencoded(body: &str) -> Vec<(String, String)> {
+    form_urlencoded::parse(body.trim().as_bytes())
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect()
+}
@@ -1,10 +1,10 @@
 use hcl::Body;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RequestConfig {
     pub url: String,
     pub method: String,
@@ -19,17 +19,218 @@ pub struct RequestConfig {
     pub expected_status: Option<u16>,
     #[serde(default)]
     pub timeout_ms: Option<u64>,
+    /// How long opening the connection may take, within `timeout_ms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_ms: Option<u64>,
+    /// How long to wait for each read of the response, within `timeout_ms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_timeout_ms: Option<u64>,
     pub pre_request_script: Option<String>,
     pub post_request_script: Option<String>,
+    /// Stress test load profile, e.g. `"30s:20, 1m:20, 10s:0"` or `"ramp"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stress_stages: Option<String>,
+    /// Workspace or environment default headers this request doesn't send
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_default_headers: Vec<String>,
+    /// The request's own headers, turned off but kept for later
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub disabled_headers: HashMap<String, String>,
+    /// Values for `:id` / `{id}` in the URL's path
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub path_params: HashMap<String, String>,
+    /// What the request is for, in markdown (Docs view, generated docs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// `variables = { page = "1" }`: over the collection's and environment's values,
+    /// for this request only
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, String>,
+    /// `package.Service/Method` of a gRPC request (`body_type = "Grpc"`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grpc_service: Option<String>,
+    /// The .proto file, when the server has no reflection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grpc_proto_path: Option<String>,
+    /// `tags = ["health", "smoke"]`: `health` puts the request on the dashboard
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// `treat_as = "json"`: how the response is shown, over its Content-Type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub treat_as: Option<String>,
+    /// `paginate = { next = "$.links.next", items = "$.data" }`: how `:paginate`
+    /// walks the pages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paginate: Option<crate::features::paginate::PaginateConfig>,
+}
+
+/// A single expectation for an incoming WebSocket message.
+/// `path` selects a value via JSONPath (compared against `equals` if set),
+/// `regex` matches against the raw message text.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WsExpectation {
+    pub path: Option<String>,
+    pub equals: Option<String>,
+    pub regex: Option<String>,
+}
+
+/// A WebSocket test scenario: connect, send messages, wait for expected replies, close.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WsScenarioConfig {
+    pub url: String,
+    #[serde(default)]
+    pub send: Vec<String>,
+    #[serde(default)]
+    pub expect: Vec<WsExpectation>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Collection {
     pub name: String,
     pub requests: HashMap<String, RequestConfig>,
+    pub ws_scenarios: HashMap<String, WsScenarioConfig>,
+    /// Markdown from `{name}.md` next to the collection file, or its top-level
+    /// `description` attribute
+    pub description: Option<String>,
+    /// `variables = { ... }` at the top of the file: over the environment's values
+    /// for every request in the collection
+    pub variables: HashMap<String, String>,
+    /// Expected responses by request name, from `{name}.snapshots.json`
+    pub snapshots: crate::domain::snapshot::Snapshots,
+}
+
+/// A collection's description: the `.md` file beside `hcl_path` wins over a
+/// `description = "..."` attribute at the top of the file
+pub fn read_description(hcl_path: &Path, body: &Body) -> Option<String> {
+    fs::read_to_string(hcl_path.with_extension("md"))
+        .ok()
+        .or_else(|| {
+            body.attributes()
+                .find(|a| a.key() == "description")
+                .and_then(|a| match a.expr() {
+                    hcl::Expression::String(s) => Some(s.clone()),
+                    _ => None,
+                })
+        })
+        .filter(|d| !d.trim().is_empty())
+}
+
+/// The `variables = { ... }` table at the top of a collection file. Numbers and
+/// bools are taken as their text.
+pub fn read_variables(body: &Body) -> Result<HashMap<String, String>, String> {
+    let Some(attr) = body.attributes().find(|a| a.key() == "variables") else {
+        return Ok(HashMap::new());
+    };
+    let hcl::Value::Object(table) = hcl::Value::from(attr.expr().clone()) else {
+        return Err("variables should be a table like { page = \"1\" }".to_string());
+    };
+    table
+        .into_iter()
+        .map(|(key, value)| match value {
+            hcl::Value::String(value) => Ok((key, value)),
+            hcl::Value::Number(n) => Ok((key, n.to_string())),
+            hcl::Value::Bool(b) => Ok((key, b.to_string())),
+            other => Err(format!(
+                "variable {} should be a string, not {}",
+                key, other
+            )),
+        })
+        .collect()
+}
+
+/// `variables = { ... }` for the top of a collection file, nothing when there are none
+pub fn variables_hcl(variables: &HashMap<String, String>) -> Result<String, hcl::Error> {
+    #[derive(Serialize)]
+    struct Top<'a> {
+        variables: std::collections::BTreeMap<&'a String, &'a String>,
+    }
+    if variables.is_empty() {
+        return Ok(String::new());
+    }
+    hcl::to_string(&Top {
+        variables: variables.iter().collect(),
+    })
+}
+
+impl RequestConfig {
+    /// Adds the `defaults` this request doesn't set itself or turn off
+    pub fn add_default_headers(&mut self, defaults: &BTreeMap<String, String>) {
+        for (name, value) in defaults {
+            let headers = self.headers.get_or_insert_default();
+            let taken = headers.keys().any(|k| k.eq_ignore_ascii_case(name))
+                || self
+                    .disabled_default_headers
+                    .iter()
+                    .any(|k| k.eq_ignore_ascii_case(name));
+            if !taken {
+                headers.insert(name.clone(), value.clone());
+            }
+        }
+    }
+
+    /// Whether the request has one of `tags`, ignoring case; true when `tags` is empty
+    pub fn has_any_tag(&self, tags: &[String]) -> bool {
+        tags.is_empty()
+            || self
+                .tags
+                .iter()
+                .any(|t| tags.iter().any(|want| want.eq_ignore_ascii_case(t)))
+    }
+}
+
+/// The workspace's `default_headers` with an environment's `headers` over them, names
+/// compared case-insensitively, the way a tab sends them (`App::default_headers`)
+pub fn merge_default_headers(
+    workspace: &BTreeMap<String, String>,
+    env: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    let mut merged: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in workspace.iter().chain(env) {
+        merged.retain(|k, _| !k.eq_ignore_ascii_case(name));
+        merged.insert(name.clone(), value.clone());
+    }
+    merged
+}
+
+/// `smoke, auth` or `smoke auth` -> `["smoke", "auth"]`
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
 }
 
 impl Collection {
+    /// Every request with `defaults` added, for the runner and stress tests, which
+    /// send saved requests rather than tabs
+    pub fn with_default_headers(mut self, defaults: &BTreeMap<String, String>) -> Collection {
+        for request in self.requests.values_mut() {
+            request.add_default_headers(defaults);
+        }
+        self
+    }
+
+    /// The collection with only the requests tagged with one of `tags`. WebSocket
+    /// scenarios have no tags, so they're left out unless `tags` is empty.
+    pub fn only_tagged(&self, tags: &[String]) -> Collection {
+        if tags.is_empty() {
+            return self.clone();
+        }
+        let mut filtered = self.clone();
+        filtered.requests.retain(|_, r| r.has_any_tag(tags));
+        filtered.ws_scenarios.clear();
+        filtered
+    }
+
     pub fn load_from_dir(dir: &str) -> std::io::Result<Vec<Collection>> {
         let path = Path::new(dir);
         if !path.exists() {
@@ -64,6 +265,7 @@ request "JSONPlaceholder" {
                     .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
 
                 let mut requests = HashMap::new();
+                let mut ws_scenarios = HashMap::new();
 
                 for block in body.blocks() {
                     if block.identifier() == "request"
@@ -72,11 +274,32 @@ request "JSONPlaceholder" {
                         let config: RequestConfig = hcl::from_body(block.body().clone())
                             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
                         requests.insert(label.as_str().to_string(), config);
+                    } else if block.identifier() == "websocket"
+                        && let Some(label) = block.labels().first()
+                    {
+                        let config: WsScenarioConfig = hcl::from_body(block.body().clone())
+                            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                        ws_scenarios.insert(label.as_str().to_string(), config);
                     }
                 }
 
                 let name = path.file_stem().unwrap().to_string_lossy().to_string();
-                collections.push(Collection { name, requests });
+                let variables = read_variables(&body).map_err(|e| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("{}: {}", path.display(), e),
+                    )
+                })?;
+                let snapshots = crate::domain::snapshot::load(&path)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                collections.push(Collection {
+                    name,
+                    requests,
+                    ws_scenarios,
+                    description: read_description(&path, &body),
+                    variables,
+                    snapshots,
+                });
             }
         }
 
@@ -85,100 +308,183 @@ request "JSONPlaceholder" {
         Ok(collections)
     }
 
-    pub fn save_to_file(
+    /// One collection from HCL text, e.g. a file as it was at a git revision; the
+    /// description only comes from the attribute here
+    pub fn from_hcl(name: &str, content: &str) -> Result<Collection, String> {
+        let body: Body = hcl::from_str(content).map_err(|e| e.to_string())?;
+        let mut requests = HashMap::new();
+        let mut ws_scenarios = HashMap::new();
+        for block in body.blocks() {
+            let Some(label) = block.labels().first() else {
+                continue;
+            };
+            if block.identifier() == "request" {
+                let config: RequestConfig = hcl::from_body(block.body().clone())
+                    .map_err(|e| format!("request '{}': {}", label.as_str(), e))?;
+                requests.insert(label.as_str().to_string(), config);
+            } else if block.identifier() == "websocket" {
+                let config: WsScenarioConfig = hcl::from_body(block.body().clone())
+                    .map_err(|e| format!("websocket '{}': {}", label.as_str(), e))?;
+                ws_scenarios.insert(label.as_str().to_string(), config);
+            }
+        }
+        let description = body
+            .attributes()
+            .find(|a| a.key() == "description")
+            .and_then(|a| match a.expr() {
+                hcl::Expression::String(s) => Some(s.clone()),
+                _ => None,
+            });
+        Ok(Collection {
+            name: name.to_string(),
+            requests,
+            ws_scenarios,
+            description,
+            variables: read_variables(&body)?,
+            snapshots: Default::default(),
+        })
+    }
+
+    /// Appends one request to `{dir}/{collection}.hcl`, creating the file if needed
+    pub fn append_request(
+        dir: &str,
+        collection: &str,
         name: &str,
-        method: &str,
-        url: &str,
-        body: &str,
-        headers: &HashMap<String, String>,
-        extract: &[(String, String)],
-        form_data: &[(String, String, bool)],
-        body_type: &str,
-        graphql_query: &str,
-        graphql_variables: &str,
-        pre_request_script: &str,
-        post_request_script: &str,
-    ) -> std::io::Result<()> {
-        let path = Path::new("collections/saved.hcl");
-
-        let extract_map = if extract.is_empty() {
-            None
-        } else {
-            Some(extract.iter().cloned().collect::<HashMap<_, _>>())
-        };
+        config: &RequestConfig,
+    ) -> std::io::Result<PathBuf> {
+        let safe_name = collection.trim().replace(" ", "_").to_lowercase();
+        if safe_name.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "collection name is empty",
+            ));
+        }
 
-        let headers_map = if headers.is_empty() {
-            None
-        } else {
-            Some(headers.clone())
-        };
+        fs::create_dir_all(dir)?;
+        let path = Path::new(dir).join(format!("{}.hcl", safe_name));
+        let body_hcl = hcl::to_string(config).map_err(std::io::Error::other)?;
+        let entry = format!(
+            "\nrequest \"{}\" {{\n{}\n}}\n",
+            name.replace('"', "'"),
+            body_hcl
+        );
 
-        let form_data_opt = if form_data.is_empty() {
-            None
-        } else {
-            Some(form_data.to_vec())
-        };
+        use std::io::Write;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        file.write_all(entry.as_bytes())?;
+        Ok(path)
+    }
 
-        let body_opt = if body.trim().is_empty() {
-            None
-        } else {
-            Some(body.to_string())
-        };
-        let body_type_opt = if body_type == "Raw" {
-            None
-        } else {
-            Some(body_type.to_string())
-        };
+    /// Writes `requests` to a new `{dir}/{name}.hcl` file in the given order.
+    /// Fails instead of overwriting if a collection with that name already exists.
+    pub fn write_new(
+        dir: &str,
+        name: &str,
+        requests: &[(String, RequestConfig)],
+    ) -> std::io::Result<PathBuf> {
+        let safe_name = name.trim().replace(" ", "_").to_lowercase();
+        if safe_name.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "collection name is empty",
+            ));
+        }
 
-        let graphql_query_opt = if graphql_query.trim().is_empty() {
-            None
-        } else {
-            Some(graphql_query.to_string())
-        };
-        let graphql_variables_opt = if graphql_variables.trim().is_empty() {
-            None
-        } else {
-            Some(graphql_variables.to_string())
-        };
+        fs::create_dir_all(dir)?;
+        let path = Path::new(dir).join(format!("{}.hcl", safe_name));
 
-        let pre_request_script_opt = if pre_request_script.trim().is_empty() {
-            None
-        } else {
-            Some(pre_request_script.to_string())
-        };
-        let post_request_script_opt = if post_request_script.trim().is_empty() {
-            None
-        } else {
-            Some(post_request_script.to_string())
-        };
-
-        let config = RequestConfig {
-            url: url.to_string(),
-            method: method.to_string(),
-            body: body_opt,
-            headers: headers_map,
-            extract: extract_map,
-            body_type: body_type_opt,
-            form_data: form_data_opt,
-            graphql_query: graphql_query_opt,
-            graphql_variables: graphql_variables_opt,
-            expected_status: None,
-            timeout_ms: None,
-            pre_request_script: pre_request_script_opt,
-            post_request_script: post_request_script_opt,
-        };
-
-        let body_hcl = hcl::to_string(&config).map_err(std::io::Error::other)?;
-
-        let entry = format!("\nrequest \"{}\" {{\n{}\n}}\n", name, body_hcl);
+        let mut content = String::new();
+        for (label, config) in requests {
+            let body_hcl = hcl::to_string(config).map_err(std::io::Error::other)?;
+            content.push_str(&format!(
+                "\nrequest \"{}\" {{\n{}\n}}\n",
+                label.replace('"', "'"),
+                body_hcl
+            ));
+        }
 
         use std::io::Write;
         let mut file = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)?;
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        file.write_all(content.as_bytes())?;
+        Ok(path)
+    }
 
-        file.write_all(entry.as_bytes())?;
-        Ok(())
+    /// Writes the collection's description to `{dir}/{name}.md`, removing the
+    /// file when it's blank
+    pub fn save_description(dir: &str, name: &str, text: &str) -> std::io::Result<PathBuf> {
+        let path = Path::new(dir).join(format!("{}.md", name));
+        if text.trim().is_empty() {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+        } else {
+            fs::write(&path, text)?;
+        }
+        Ok(path)
+    }
+
+    /// Replaces the body of request `label` in `{dir}/{name}.hcl` with `config`.
+    /// The rest of the file (comments, other blocks, variables) stays as it was.
+    pub fn replace_request(
+        dir: &str,
+        name: &str,
+        label: &str,
+        config: &RequestConfig,
+    ) -> std::io::Result<PathBuf> {
+        let body_hcl = hcl::to_string(config).map_err(std::io::Error::other)?;
+        let parsed =
+            hcl::edit::parser::parse_body(&format!("request \"x\" {{\n{}\n}}\n", body_hcl))
+                .map_err(std::io::Error::other)?;
+        let new_body = parsed
+            .into_blocks()
+            .next()
+            .map(|block| block.body)
+            .ok_or_else(|| std::io::Error::other("empty request"))?;
+        Self::edit_file(dir, name, label, |body, index| {
+            if let Some(block) = body.get_mut(index).and_then(|s| s.as_block_mut()) {
+                block.body = new_body;
+            }
+        })
+    }
+
+    /// Deletes request `label` from `{dir}/{name}.hcl`, leaving the rest of the file alone
+    pub fn remove_request(dir: &str, name: &str, label: &str) -> std::io::Result<PathBuf> {
+        Self::edit_file(dir, name, label, |body, index| {
+            body.remove(index);
+        })
+    }
+
+    fn edit_file(
+        dir: &str,
+        name: &str,
+        label: &str,
+        edit: impl FnOnce(&mut hcl::edit::structure::Body, usize),
+    ) -> std::io::Result<PathBuf> {
+        let path = Path::new(dir).join(format!("{}.hcl", name));
+        let content = fs::read_to_string(&path)?;
+        let mut body = hcl::edit::parser::parse_body(&content).map_err(std::io::Error::other)?;
+        let index = body
+            .iter()
+            .position(|s| {
+                s.as_block().is_some_and(|block| {
+                    block.has_ident("request")
+                        && block.labels.first().is_some_and(|l| l.as_str() == label)
+                })
+            })
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("no request '{}' in {}", label, path.display()),
+                )
+            })?;
+        edit(&mut body, index);
+        fs::write(&path, body.to_string())?;
+        Ok(path)
     }
 }
@@ -1,16 +1,47 @@
 use hcl::Body;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Environment {
     pub name: String,
     pub variables: HashMap<String, String>,
+    /// `resolve = { "api.example.com" = "10.0.0.5" }`: hosts pinned to addresses, like
+    /// curl's `--resolve`
+    #[serde(default)]
+    pub resolve: BTreeMap<String, String>,
+    /// `headers = { Accept = "application/json" }`: sent with every request while the
+    /// environment is active, over the workspace's `default_headers`
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// The variable relative request URLs (`/users/{{id}}`) are resolved against
+pub const BASE_URL: &str = "base_url";
+
+/// A URL starting with `/` is a path on the environment's `base_url`
+pub fn is_relative(url: &str) -> bool {
+    url.trim_start().starts_with('/')
+}
+
+/// `url` put after `base_url` with one slash between them. Absolute URLs, and
+/// relative ones when there's no base, come back as they are.
+pub fn resolve_url(url: &str, base_url: Option<&str>) -> String {
+    match base_url {
+        Some(base) if is_relative(url) => {
+            format!("{}{}", base.trim_end_matches('/'), url.trim_start())
+        }
+        _ => url.to_string(),
+    }
 }
 
 impl Environment {
+    pub fn base_url(&self) -> Option<&str> {
+        self.variables.get(BASE_URL).map(String::as_str)
+    }
+
     pub fn load_from_file(path: &str) -> std::io::Result<Vec<Environment>> {
         let path = Path::new(path);
         let mut envs = Vec::new();
@@ -38,12 +69,42 @@ env "prod" {
             if block.identifier() == "env"
                 && let Some(label) = block.labels().first()
             {
-                let variables: HashMap<String, String> = hcl::from_body(block.body().clone())
-                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+                // `resolve` and `headers` are tables, not variables
+                let mut resolve = BTreeMap::new();
+                let mut headers = BTreeMap::new();
+                let mut rest = Vec::new();
+                for structure in block.body().iter() {
+                    match structure {
+                        hcl::Structure::Attribute(attr) if attr.key.as_str() == "resolve" => {
+                            resolve = parse_table(
+                                label.as_str(),
+                                "resolve",
+                                "{ \"api.example.com\" = \"10.0.0.5\" }",
+                                attr.expr.clone(),
+                            )
+                            .map_err(invalid)?;
+                        }
+                        hcl::Structure::Attribute(attr) if attr.key.as_str() == "headers" => {
+                            headers = parse_table(
+                                label.as_str(),
+                                "headers",
+                                "{ Accept = \"application/json\" }",
+                                attr.expr.clone(),
+                            )
+                            .map_err(invalid)?;
+                        }
+                        other => rest.push(other.clone()),
+                    }
+                }
+                let variables: HashMap<String, String> =
+                    hcl::from_body(Body::from_iter(rest)).map_err(|e| invalid(e.to_string()))?;
 
                 envs.push(Environment {
                     name: label.as_str().to_string(),
                     variables,
+                    resolve,
+                    headers,
                 });
             }
         }
@@ -53,9 +114,36 @@ env "prod" {
             Environment {
                 name: "None".to_string(),
                 variables: HashMap::new(),
+                resolve: BTreeMap::new(),
+                headers: BTreeMap::new(),
             },
         );
 
         Ok(envs)
     }
 }
+
+/// A string-to-string table attribute of an env block, e.g. `resolve` or `headers`
+fn parse_table(
+    env: &str,
+    name: &str,
+    example: &str,
+    expr: hcl::Expression,
+) -> Result<BTreeMap<String, String>, String> {
+    let hcl::Value::Object(table) = hcl::Value::from(expr) else {
+        return Err(format!(
+            "env \"{}\": {} should be a table like {}",
+            env, name, example
+        ));
+    };
+    table
+        .into_iter()
+        .map(|(key, value)| match value {
+            hcl::Value::String(value) => Ok((key, value)),
+            other => Err(format!(
+                "env \"{}\": {} value for {} should be a string, not {}",
+                env, name, key, other
+            )),
+        })
+        .collect()
+}
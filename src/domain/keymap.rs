@@ -0,0 +1,848 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Where a key binding applies. The cheat sheet only shows the contexts that are
+/// active right now, see `App::key_contexts`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyContext {
+    Global,
+    Tabs,
+    Sidebar,
+    Diff,
+    Navigation,
+    Request,
+    TextInput,
+    Items,
+    Auth,
+    Body,
+    Grpc,
+    Codegen,
+    Response,
+    Scripts,
+    Modes,
+    WebSocket,
+    Runner,
+    Mock,
+    MockEditor,
+}
+
+impl KeyContext {
+    pub fn title(&self) -> &'static str {
+        match self {
+            KeyContext::Global => "General",
+            KeyContext::Tabs => "Request Tabs",
+            KeyContext::Sidebar => "Sidebar / History",
+            KeyContext::Diff => "Diff View",
+            KeyContext::Navigation => "Navigation",
+            KeyContext::Request => "Request",
+            KeyContext::TextInput => "Editing a Field (URL, Params, Auth, :, ...)",
+            KeyContext::Items => "Params / Headers / Chain / Form",
+            KeyContext::Auth => "Auth Tab",
+            KeyContext::Body => "Body Tab",
+            KeyContext::Grpc => "gRPC (Body Tab -> 't' to gRPC mode)",
+            KeyContext::Codegen => "Code Generators (copy to clipboard)",
+            KeyContext::Response => "Response",
+            KeyContext::Scripts => "Scripts & Testing",
+            KeyContext::Modes => "Modes",
+            KeyContext::WebSocket => "WebSocket Mode",
+            KeyContext::Runner => "Collection Runner",
+            KeyContext::Mock => "Mock Server Manager",
+            KeyContext::MockEditor => "Mock Route Editor",
+        }
+    }
+
+    /// Whether keymap.toml applies here. Text fields keep their editing keys.
+    pub fn remappable(&self) -> bool {
+        !matches!(self, KeyContext::TextInput | KeyContext::MockEditor)
+    }
+}
+
+/// One row of the binding table
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Binding {
+    pub keys: &'static str,
+    pub action: &'static str,
+    pub context: KeyContext,
+}
+
+const fn bind(context: KeyContext, keys: &'static str, action: &'static str) -> Binding {
+    Binding {
+        keys,
+        action,
+        context,
+    }
+}
+
+/// Every key binding PostDad knows about, in display order.
+/// The help overlay is generated from this, so add new keys here.
+pub const BINDINGS: &[Binding] = &[
+    bind(KeyContext::Global, "q", "Quit"),
+    bind(KeyContext::Global, "?", "Toggle Help"),
+    bind(KeyContext::Global, "Ctrl+h", "Focus Sidebar / Main"),
+    bind(KeyContext::Global, "Ctrl+e", "Switch Environment"),
+    bind(KeyContext::Global, "Ctrl+t", "Cycle Themes"),
+    bind(KeyContext::Global, "Ctrl+z", "Toggle Zen Mode"),
+    bind(KeyContext::Global, "Ctrl+p", "Command Palette"),
+    bind(
+        KeyContext::Global,
+        "Ctrl+o / :find",
+        "Fuzzy Find Requests, Tabs, History, Envs",
+    ),
+    bind(
+        KeyContext::Global,
+        "Ctrl+v",
+        "Paste into the Field Being Edited",
+    ),
+    bind(KeyContext::Global, ":", "Command Mode"),
+    bind(
+        KeyContext::Global,
+        "Tab / ↑ ↓ (in :)",
+        "Complete Command / Command History",
+    ),
+    bind(KeyContext::Global, "N", "Notification History"),
+    bind(
+        KeyContext::Global,
+        ":workspace <name | dir>",
+        "Switch Workspace (list to show them)",
+    ),
+    bind(
+        KeyContext::Global,
+        "Ctrl+g / :history [query]",
+        "Browse History (Ctrl+p pin, Ctrl+s save, Ctrl+x delete)",
+    ),
+    bind(
+        KeyContext::Global,
+        ":history limit <n>",
+        "Entries Kept in History (pinned don't count)",
+    ),
+    bind(
+        KeyContext::Global,
+        ":encrypt [on | keyring | off]",
+        "Encrypt History, Cookies & Env Secrets",
+    ),
+    bind(
+        KeyContext::Global,
+        ":unlock / :secret <value>",
+        "Enter Passphrase / Copy Encrypted Env Value",
+    ),
+    bind(
+        KeyContext::Global,
+        ":profile <name>",
+        "Switch Workspace Profile (off to disable)",
+    ),
+    bind(
+        KeyContext::Global,
+        ":pool / :flush",
+        "Connection Pool & DNS Cache / Flush",
+    ),
+    bind(
+        KeyContext::Global,
+        ":keymap [reload]",
+        "Show Remappable Keys (keymap.toml)",
+    ),
+    bind(
+        KeyContext::Global,
+        ":doctor",
+        "Check Terminal, Tools and Workspace",
+    ),
+    bind(KeyContext::Global, "Esc", "Dismiss Newest Notification"),
+    bind(KeyContext::Tabs, "Ctrl+n", "New Tab"),
+    bind(KeyContext::Tabs, "Ctrl+x", "Close Tab"),
+    bind(KeyContext::Tabs, "[ / ]", "Cycle Open Tabs"),
+    bind(KeyContext::Tabs, "E / :rename <name>", "Rename Tab"),
+    bind(KeyContext::Tabs, "I", "Import cURL Command"),
+    bind(
+        KeyContext::Tabs,
+        ":import-curl <file>",
+        "Import All cURL Commands as a Collection",
+    ),
+    bind(KeyContext::Sidebar, "j / k", "Move Up / Down"),
+    bind(KeyContext::Sidebar, "Enter", "Load Request"),
+    bind(KeyContext::Sidebar, "f", "Filter Sidebar"),
+    bind(
+        KeyContext::Sidebar,
+        "D",
+        "Diff: Select Base (1st) then Target (2nd)",
+    ),
+    bind(
+        KeyContext::Sidebar,
+        "s",
+        "Save History Item to a Collection",
+    ),
+    bind(
+        KeyContext::Diff,
+        "j",
+        "Toggle Structural JSON / Header Diff",
+    ),
+    bind(KeyContext::Diff, "↑ / ↓", "Scroll"),
+    bind(KeyContext::Diff, "Esc / q", "Close Diff"),
+    bind(KeyContext::Navigation, "j / k", "Move Up / Down"),
+    bind(
+        KeyContext::Navigation,
+        "h / l",
+        "Collapse / Expand JSON, CSV Column",
+    ),
+    bind(KeyContext::Navigation, "Space", "Toggle JSON / Form File"),
+    bind(
+        KeyContext::Navigation,
+        "Tab",
+        "Cycle Tabs (Params, Headers, ...)",
+    ),
+    bind(KeyContext::Request, "e", "Edit URL (Tab to Cycle Method)"),
+    bind(KeyContext::Request, "m", "Cycle Method (GET, POST, ...)"),
+    bind(KeyContext::Request, "H", "Edit Headers (Ext. Editor)"),
+    bind(KeyContext::Request, "f", "Toggle Fullscreen"),
+    bind(KeyContext::Request, "s", "Save Request"),
+    bind(KeyContext::Request, "Enter", "Send Request"),
+    bind(
+        KeyContext::Request,
+        "R",
+        "Request Timeline: Restore an Earlier Send",
+    ),
+    bind(
+        KeyContext::Request,
+        "W",
+        "Raw Request: Preview Before Sending (Tab: Last Sent)",
+    ),
+    bind(KeyContext::TextInput, "← / →", "Move Cursor"),
+    bind(
+        KeyContext::TextInput,
+        "Ctrl+← / Ctrl+→",
+        "Move by Word (also Alt+b / Alt+f)",
+    ),
+    bind(
+        KeyContext::TextInput,
+        "Home / End",
+        "Start / End (also Ctrl+a / Ctrl+e)",
+    ),
+    bind(
+        KeyContext::TextInput,
+        "Backspace / Del",
+        "Delete Before / Under Cursor",
+    ),
+    bind(
+        KeyContext::TextInput,
+        "Ctrl+w",
+        "Delete Word Before (also Alt+Backspace)",
+    ),
+    bind(KeyContext::TextInput, "Alt+d", "Delete Word After"),
+    bind(
+        KeyContext::TextInput,
+        "Ctrl+u / Ctrl+k",
+        "Delete to Start / End",
+    ),
+    bind(KeyContext::Items, "a", "Add Item"),
+    bind(KeyContext::Items, "d", "Delete Item"),
+    bind(KeyContext::Items, "e", "Edit Item"),
+    bind(KeyContext::Items, "Space", "Header On / Off (Headers Tab)"),
+    bind(KeyContext::Auth, "t", "Switch Auth Type"),
+    bind(KeyContext::Auth, "u / p", "Edit User / Pass (Basic)"),
+    bind(KeyContext::Auth, "i / 1 / 2", "Edit OAuth ID / URLs"),
+    bind(KeyContext::Body, "t", "Switch Body Type"),
+    bind(KeyContext::Body, "b", "Edit Body (Ext. Editor)"),
+    bind(
+        KeyContext::Body,
+        "= / -",
+        "Pretty-print / Minify Body (JSON, XML)",
+    ),
+    bind(KeyContext::Body, "Q / V", "Edit GraphQL Query / Vars"),
+    bind(KeyContext::Grpc, "u", "Edit Service/Method"),
+    bind(KeyContext::Grpc, "p", "Edit Proto file path"),
+    bind(KeyContext::Grpc, "L", "List services (reflection)"),
+    bind(KeyContext::Grpc, "D", "Describe service (in modal)"),
+    bind(KeyContext::Grpc, "Enter", "Send gRPC request"),
+    bind(KeyContext::Codegen, "c", "cURL command"),
+    bind(
+        KeyContext::Codegen,
+        ":curl [oneline] [cmd]",
+        "cURL as One Line / for Windows cmd",
+    ),
+    bind(
+        KeyContext::Codegen,
+        "G",
+        "Pick a Target (Python, JS, Go, Rust, Ruby, PHP, C#, HTTPie, custom)",
+    ),
+    bind(
+        KeyContext::Codegen,
+        ":codegen <target>",
+        "Copy Code for a Target by Name",
+    ),
+    bind(KeyContext::Response, "C", "Copy Response Output"),
+    bind(KeyContext::Response, "J", "Raw / Pretty Response"),
+    bind(
+        KeyContext::Response,
+        "V",
+        "Treat as JSON / XML / HTML / Text / Binary, Then Auto",
+    ),
+    bind(
+        KeyContext::Response,
+        "D",
+        "Save Response As (Edit the Name, Enter)",
+    ),
+    bind(KeyContext::Response, "z", "Image: Fit / Fill / Actual Size"),
+    bind(
+        KeyContext::Response,
+        "K",
+        "Docs: the Request's Description (:describe Edits)",
+    ),
+    bind(KeyContext::Response, "P", "Preview Response (External)"),
+    bind(
+        KeyContext::Response,
+        "B",
+        "Hex View (Tab: Sent Request, / Search, c: Copy Dump)",
+    ),
+    bind(
+        KeyContext::Response,
+        "U",
+        "Undecoded Body (gzip / deflate) in Hex",
+    ),
+    bind(
+        KeyContext::Response,
+        "A",
+        "Security Audit (Headers, Cookies, CORS, TLS)",
+    ),
+    bind(KeyContext::Response, "y", "Copy JSON Path"),
+    bind(
+        KeyContext::Response,
+        "v / Y",
+        "Copy JSON Value (CSV Cell) / Subtree",
+    ),
+    bind(KeyContext::Response, "X", "Export JSON Subtree to File"),
+    bind(
+        KeyContext::Response,
+        "Z",
+        "Flat JSON Paths (Search Keys and Values)",
+    ),
+    bind(KeyContext::Response, "/", "Search / Filter JSON"),
+    bind(
+        KeyContext::Response,
+        "o",
+        "Sort CSV by Column (Again: Descending, Then Off)",
+    ),
+    bind(
+        KeyContext::Response,
+        "L",
+        "NDJSON Lines (Enter: Record in Tree, /: Filter)",
+    ),
+    bind(
+        KeyContext::Response,
+        "O",
+        "Follow a Streaming Response (Scrolling Up Stops)",
+    ),
+    bind(
+        KeyContext::Response,
+        "F",
+        "Find in Raw Response (n / N: Next / Prev)",
+    ),
+    bind(
+        KeyContext::Response,
+        ":jp <path>",
+        "JSONPath Query (:chain <var> to extract)",
+    ),
+    bind(
+        KeyContext::Response,
+        ":csv / :tsv",
+        "Array of Objects to CSV / TSV (cols > file)",
+    ),
+    bind(
+        KeyContext::Response,
+        ":compare",
+        "Re-send and Diff Against Current Response",
+    ),
+    bind(
+        KeyContext::Scripts,
+        "P",
+        "Edit Pre-Request Script (If not binary)",
+    ),
+    bind(KeyContext::Scripts, "T", "Edit Post/Test Script"),
+    bind(KeyContext::Scripts, "%", "Stress Test (Shift+5)"),
+    bind(KeyContext::Scripts, "S", "Sentinel Mode (Live Monitor)"),
+    bind(KeyContext::Scripts, "M", "Generate API Docs (MD + HTML)"),
+    bind(KeyContext::Modes, "Ctrl+w", "Toggle WebSocket Mode"),
+    bind(KeyContext::Modes, "Ctrl+r", "Toggle Collection Runner"),
+    bind(KeyContext::Modes, "Ctrl+k", "Mock Server Manager"),
+    bind(KeyContext::Modes, "Ctrl+j", "Cookie Manager"),
+    bind(KeyContext::WebSocket, "e", "Edit WebSocket URL"),
+    bind(KeyContext::WebSocket, "Enter", "Connect / Disconnect"),
+    bind(KeyContext::WebSocket, "i", "Start typing message"),
+    bind(
+        KeyContext::WebSocket,
+        "Enter",
+        "Send message (while typing)",
+    ),
+    bind(KeyContext::WebSocket, "Esc", "Cancel typing"),
+    bind(KeyContext::WebSocket, "j / k", "Scroll messages Up / Down"),
+    bind(KeyContext::WebSocket, "x", "Clear message history"),
+    bind(
+        KeyContext::WebSocket,
+        "s",
+        "Save session log (:wsexport [file])",
+    ),
+    bind(
+        KeyContext::WebSocket,
+        "r",
+        "Replay sent messages, timed (:wsreplay <file>)",
+    ),
+    bind(KeyContext::Runner, "Ctrl+r / Esc", "Exit Runner Mode"),
+    bind(
+        KeyContext::Runner,
+        "j / k",
+        "Navigate collections / results",
+    ),
+    bind(KeyContext::Runner, "Enter", "Run selected collection"),
+    bind(KeyContext::Runner, "x", "Clear results"),
+    bind(KeyContext::Runner, "%", "Stress test selected collection"),
+    bind(KeyContext::Mock, "a", "Add Route"),
+    bind(KeyContext::Mock, "e / Enter", "Edit Route"),
+    bind(KeyContext::Mock, "d", "Delete Route"),
+    bind(KeyContext::Mock, "s", "Start / Stop Server"),
+    bind(KeyContext::Mock, "j / k", "Move Up / Down"),
+    bind(KeyContext::Mock, "Esc", "Exit Mock Manager"),
+    bind(KeyContext::MockEditor, "Tab", "Next Field"),
+    bind(KeyContext::MockEditor, "← / →", "Cycle Method"),
+    bind(KeyContext::MockEditor, "Ctrl+s", "Save Route"),
+    bind(KeyContext::MockEditor, "Esc", "Cancel"),
+];
+
+/// A cheat sheet row with the keys as they are mapped right now
+#[derive(Clone, Debug, PartialEq)]
+pub struct HelpRow {
+    pub keys: String,
+    pub action: &'static str,
+}
+
+/// Bindings for the given contexts, grouped in context order. An empty `query` keeps
+/// everything, otherwise keys, action and section title are matched case-insensitively.
+pub fn cheat_sheet(
+    contexts: &[KeyContext],
+    query: &str,
+    keymap: &Keymap,
+) -> Vec<(KeyContext, Vec<HelpRow>)> {
+    let query = query.trim().to_lowercase();
+    contexts
+        .iter()
+        .filter_map(|context| {
+            let rows: Vec<HelpRow> = BINDINGS
+                .iter()
+                .filter(|b| b.context == *context)
+                .map(|b| HelpRow {
+                    keys: if context.remappable() {
+                        keymap.describe(b.keys)
+                    } else {
+                        b.keys.to_string()
+                    },
+                    action: b.action,
+                })
+                .filter(|row| {
+                    query.is_empty()
+                        || row.keys.to_lowercase().contains(&query)
+                        || row.action.to_lowercase().contains(&query)
+                        || context.title().to_lowercase().contains(&query)
+                })
+                .collect();
+            (!rows.is_empty()).then_some((*context, rows))
+        })
+        .collect()
+}
+
+/// Every context, for the "show all" view of the cheat sheet
+pub fn all_contexts() -> Vec<KeyContext> {
+    let mut contexts: Vec<KeyContext> = Vec::new();
+    for binding in BINDINGS {
+        if !contexts.contains(&binding.context) {
+            contexts.push(binding.context);
+        }
+    }
+    contexts
+}
+
+pub const KEYMAP_FILE: &str = "keymap.toml";
+
+/// A single key press as written in keymap.toml: `j`, `G`, `%`, `Ctrl+n`, `Alt+x`,
+/// `Enter`, `PageDown`, `F5`, ...
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Chord {
+    pub fn parse(text: &str) -> Result<Chord, String> {
+        let text = text.trim();
+        // A lone "+" is the key itself, not a separator
+        let (mods, key) = match text.rsplit_once('+') {
+            Some((mods, "")) => (mods.strip_suffix('+').unwrap_or(mods), "+"),
+            Some((mods, key)) => (mods, key),
+            None => ("", text),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for m in mods.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match m.to_lowercase().as_str() {
+                "ctrl" | "control" | "c" => KeyModifiers::CONTROL,
+                "alt" | "meta" | "m" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => return Err(format!("Unknown modifier '{}' in '{}'", m, text)),
+            };
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                f => match f.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("Unknown key '{}' in '{}'", key, text)),
+                },
+            },
+        };
+        Ok(Chord { code, modifiers }.normalized())
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Chord {
+        Chord {
+            code: key.code,
+            modifiers: key.modifiers,
+        }
+        .normalized()
+    }
+
+    pub fn to_event(self) -> KeyEvent {
+        KeyEvent::new(self.code, self.modifiers)
+    }
+
+    /// Terminals disagree on Shift with characters (`G` vs `Shift+g` vs `Shift+G`),
+    /// so characters carry their case and never Shift
+    fn normalized(mut self) -> Chord {
+        if let KeyCode::Char(c) = self.code {
+            if self.modifiers.contains(KeyModifiers::SHIFT) {
+                self.code = KeyCode::Char(c.to_ascii_uppercase());
+            } else if self.modifiers.contains(KeyModifiers::CONTROL) {
+                self.code = KeyCode::Char(c.to_ascii_lowercase());
+            }
+            self.modifiers.remove(KeyModifiers::SHIFT);
+        } else if self.code == KeyCode::Tab && self.modifiers.contains(KeyModifiers::SHIFT) {
+            self.code = KeyCode::BackTab;
+            self.modifiers.remove(KeyModifiers::SHIFT);
+        }
+        self.modifiers &= KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT;
+        self
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Something a key does in the normal (not typing) screens. `default` is the key the
+/// handlers listen for; remapping a key makes it act as that default key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActionDef {
+    pub id: &'static str,
+    pub default: &'static str,
+    pub desc: &'static str,
+}
+
+const fn action(id: &'static str, default: &'static str, desc: &'static str) -> ActionDef {
+    ActionDef { id, default, desc }
+}
+
+/// Every action keymap.toml can rebind, in display order
+pub const ACTIONS: &[ActionDef] = &[
+    action("quit", "q", "Quit"),
+    action("help", "?", "Toggle help"),
+    action("command_mode", ":", "Command mode"),
+    action("command_palette", "Ctrl+p", "Command palette"),
+    action(
+        "fuzzy_finder",
+        "Ctrl+o",
+        "Find requests, tabs, history, envs",
+    ),
+    action("history_view", "Ctrl+g", "Browse history"),
+    action("toggle_sidebar", "Ctrl+h", "Focus sidebar / main"),
+    action("next_env", "Ctrl+e", "Switch environment"),
+    action("next_theme", "Ctrl+t", "Cycle themes"),
+    action("zen_mode", "Ctrl+z", "Toggle zen mode"),
+    action("notification_history", "N", "Notification history"),
+    action("new_tab", "Ctrl+n", "New tab"),
+    action("close_tab", "Ctrl+x", "Close tab"),
+    action("duplicate_tab", "Ctrl+d", "Duplicate tab"),
+    action("rename_tab", "E", "Rename tab"),
+    action("prev_tab", "[", "Previous tab"),
+    action("next_tab", "]", "Next tab"),
+    action("import_curl", "I", "Import cURL command"),
+    action("move_down", "j", "Move down"),
+    action("move_up", "k", "Move up"),
+    action("collapse", "h", "Collapse JSON"),
+    action("expand", "l", "Expand JSON"),
+    action("toggle", "Space", "Toggle JSON node / form file"),
+    action("next_section", "Tab", "Cycle Params, Headers, ..."),
+    action("page_down", "PageDown", "Scroll a page down"),
+    action("page_up", "PageUp", "Scroll a page up"),
+    action("edit", "e", "Edit URL / selected item"),
+    action("cycle_method", "m", "Cycle method"),
+    action("edit_headers", "H", "Edit headers (ext. editor)"),
+    action("fullscreen", "f", "Toggle fullscreen"),
+    action("save_request", "s", "Save request"),
+    action("send", "Enter", "Send request"),
+    action("timeline", "R", "Request timeline"),
+    action("add_item", "a", "Add item"),
+    action("delete_item", "d", "Delete item"),
+    action("switch_type", "t", "Switch body / auth type"),
+    action("edit_body", "b", "Edit body (ext. editor)"),
+    action("copy_response", "C", "Copy response"),
+    action("download", "D", "Save response as"),
+    action("image_zoom", "z", "Image fit / fill / actual size"),
+    action("docs", "K", "Request docs"),
+    action("hex_view", "B", "Hex view (response / sent request)"),
+    action("wire_view", "W", "Preview raw request"),
+    action("security_audit", "A", "Security audit of the response"),
+    action("undecoded", "U", "Compressed response as received"),
+    action("pretty_body", "=", "Pretty-print body"),
+    action("minify_body", "-", "Minify body"),
+    action("raw_response", "J", "Raw / pretty response"),
+    action("treat_as", "V", "Treat response as JSON, XML, ..."),
+    action("copy_path", "y", "Copy JSON path"),
+    action("search_json", "/", "Search / filter JSON"),
+    action("find_raw", "F", "Find in raw response"),
+    action("ndjson_lines", "L", "NDJSON lines"),
+    action("follow_stream", "O", "Follow a streaming response"),
+    action("flat_paths", "Z", "Flat JSON paths"),
+    action("sort_column", "o", "Sort CSV by column"),
+    action("export_subtree", "X", "Export JSON subtree"),
+    action("edit_test_script", "T", "Edit post/test script"),
+    action("stress_test", "%", "Stress test"),
+    action("sentinel", "S", "Sentinel mode"),
+    action("generate_docs", "M", "Generate API docs"),
+    action("websocket_mode", "Ctrl+w", "Toggle WebSocket mode"),
+    action("runner_mode", "Ctrl+r", "Toggle collection runner"),
+    action("mock_mode", "Ctrl+k", "Mock server manager"),
+    action("cookies", "Ctrl+j", "Cookie manager"),
+];
+
+type ProfileKeys = &'static [(&'static str, &'static [&'static str])];
+
+/// Built-in profiles, picked with `profile = "..."` in keymap.toml. Each only lists
+/// the actions it changes; the defaults are already hjkl.
+pub const PROFILES: &[(&str, ProfileKeys)] = &[
+    ("default", &[]),
+    (
+        "vim",
+        &[
+            ("page_down", &["PageDown", "Ctrl+f"]),
+            ("page_up", &["PageUp", "Ctrl+b"]),
+        ],
+    ),
+    (
+        "emacs",
+        &[
+            ("move_down", &["Ctrl+n", "j"]),
+            ("move_up", &["Ctrl+p", "k"]),
+            ("collapse", &["Ctrl+b", "h"]),
+            ("expand", &["Ctrl+f", "l"]),
+            ("page_down", &["PageDown", "Ctrl+v"]),
+            ("page_up", &["PageUp", "Alt+v"]),
+            ("command_palette", &["Alt+x"]),
+            ("new_tab", &["Alt+n"]),
+        ],
+    ),
+];
+
+/// `move_down = ["Ctrl+n", "j"]` under `[keys]` takes one key or a list
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize, Default)]
+struct KeymapFile {
+    profile: Option<String>,
+    #[serde(default)]
+    keys: HashMap<String, KeyList>,
+}
+
+/// The keys in effect: the defaults, then the profile, then keymap.toml's own `keys`
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    pub profile: String,
+    /// Effective keys per action, in `ACTIONS` order
+    pub keys: Vec<(&'static ActionDef, Vec<Chord>)>,
+    by_chord: HashMap<Chord, Chord>,
+    defaults: HashMap<Chord, &'static str>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::build("default", &HashMap::new()).expect("built-in keymap is valid")
+    }
+}
+
+impl Keymap {
+    /// Builds a keymap from a profile plus per-action overrides
+    pub fn build(
+        profile: &str,
+        overrides: &HashMap<String, Vec<String>>,
+    ) -> Result<Keymap, String> {
+        let Some((_, profile_keys)) = PROFILES.iter().find(|(name, _)| *name == profile) else {
+            let names: Vec<&str> = PROFILES.iter().map(|(name, _)| *name).collect();
+            return Err(format!(
+                "Unknown keymap profile '{}' (try {})",
+                profile,
+                names.join(", ")
+            ));
+        };
+        if let Some(id) = overrides
+            .keys()
+            .find(|id| !ACTIONS.iter().any(|a| a.id == id.as_str()))
+        {
+            return Err(format!("Unknown action '{}' in {}", id, KEYMAP_FILE));
+        }
+
+        let mut keys = Vec::new();
+        let mut by_chord = HashMap::new();
+        let mut owner: HashMap<Chord, &str> = HashMap::new();
+        let mut defaults = HashMap::new();
+        for def in ACTIONS {
+            let default = Chord::parse(def.default)?;
+            defaults.insert(default, def.id);
+            let texts: Vec<&str> = match overrides.get(def.id) {
+                Some(list) => list.iter().map(String::as_str).collect(),
+                None => match profile_keys.iter().find(|(id, _)| *id == def.id) {
+                    Some((_, list)) => list.to_vec(),
+                    None => vec![def.default],
+                },
+            };
+            let mut chords = Vec::new();
+            for text in texts {
+                let chord = Chord::parse(text)?;
+                if let Some(other) = owner.insert(chord, def.id)
+                    && other != def.id
+                {
+                    return Err(format!(
+                        "{} is bound to both {} and {}",
+                        chord, other, def.id
+                    ));
+                }
+                by_chord.insert(chord, default);
+                chords.push(chord);
+            }
+            keys.push((def, chords));
+        }
+
+        Ok(Keymap {
+            profile: profile.to_string(),
+            keys,
+            by_chord,
+            defaults,
+        })
+    }
+
+    /// Reads keymap.toml. A missing file means the default profile.
+    pub fn load(path: &str) -> Result<Keymap, String> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Keymap::default()),
+            Err(e) => return Err(e.to_string()),
+        };
+        Keymap::parse(&content).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn parse(content: &str) -> Result<Keymap, String> {
+        let file: KeymapFile = toml::from_str(content).map_err(|e| e.message().to_string())?;
+        let overrides = file
+            .keys
+            .into_iter()
+            .map(|(id, keys)| {
+                let keys = match keys {
+                    KeyList::One(key) => vec![key],
+                    KeyList::Many(keys) => keys,
+                };
+                (id, keys)
+            })
+            .collect();
+        Keymap::build(file.profile.as_deref().unwrap_or("default"), &overrides)
+    }
+
+    /// The key the handlers should see for a key press. A remapped key becomes its
+    /// action's default key; a default key whose action moved elsewhere does nothing.
+    pub fn translate(&self, key: KeyEvent) -> Option<KeyEvent> {
+        let chord = Chord::from_event(&key);
+        match self.by_chord.get(&chord) {
+            Some(default) if *default == chord => Some(key),
+            Some(default) => Some(default.to_event()),
+            None if self.defaults.contains_key(&chord) => None,
+            None => Some(key),
+        }
+    }
+
+    /// The effective keys of one action, e.g. "Ctrl+n, j"
+    pub fn keys_for(&self, id: &str) -> String {
+        self.keys
+            .iter()
+            .find(|(def, _)| def.id == id)
+            .map(|(_, chords)| join_chords(chords))
+            .unwrap_or_default()
+    }
+
+    /// Rewrites a cheat sheet key column ("j / k") with the keys mapped right now
+    pub fn describe(&self, keys: &str) -> String {
+        keys.split(" / ")
+            .map(|token| match Chord::parse(token) {
+                Ok(chord) => match self.defaults.get(&chord) {
+                    Some(id) => self.keys_for(id),
+                    None => token.to_string(),
+                },
+                Err(_) => token.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+fn join_chords(chords: &[Chord]) -> String {
+    if chords.is_empty() {
+        return "(unbound)".to_string();
+    }
+    chords
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
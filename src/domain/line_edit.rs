@@ -0,0 +1,157 @@
+// Readline-style editing for the one-line inputs (URL, params, auth, command bar, ...).
+// The text lives wherever the input mode keeps it; this only knows a string and a byte
+// cursor, so every field gets the same keys.
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// The cursor value meaning "end of the text", used when an input is first focused
+pub const CURSOR_END: usize = usize::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edit {
+    Insert(char),
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Home,
+    End,
+    /// Ctrl+W, Alt+Backspace
+    DeleteWordBack,
+    /// Alt+D
+    DeleteWordForward,
+    /// Ctrl+U
+    KillToStart,
+    /// Ctrl+K
+    KillToEnd,
+}
+
+impl Edit {
+    /// The edit a key press stands for, None for keys inputs don't use (Enter, Tab, ...)
+    pub fn from_key(key: &KeyEvent) -> Option<Edit> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        // AltGr arrives as Ctrl+Alt and types a character ('@', '{', ...)
+        let (ctrl, alt) = if ctrl && alt && matches!(key.code, KeyCode::Char(_)) {
+            (false, false)
+        } else {
+            (ctrl, alt)
+        };
+        Some(match key.code {
+            KeyCode::Left if ctrl || alt => Edit::WordLeft,
+            KeyCode::Right if ctrl || alt => Edit::WordRight,
+            KeyCode::Left => Edit::Left,
+            KeyCode::Right => Edit::Right,
+            KeyCode::Home => Edit::Home,
+            KeyCode::End => Edit::End,
+            KeyCode::Backspace if ctrl || alt => Edit::DeleteWordBack,
+            KeyCode::Backspace => Edit::Backspace,
+            KeyCode::Delete => Edit::Delete,
+            KeyCode::Char(c) if ctrl => match c {
+                'a' => Edit::Home,
+                'e' => Edit::End,
+                'b' => Edit::Left,
+                'f' => Edit::Right,
+                'd' => Edit::Delete,
+                'w' => Edit::DeleteWordBack,
+                'u' => Edit::KillToStart,
+                'k' => Edit::KillToEnd,
+                _ => return None,
+            },
+            KeyCode::Char(c) if alt => match c {
+                'b' => Edit::WordLeft,
+                'f' => Edit::WordRight,
+                'd' => Edit::DeleteWordForward,
+                _ => return None,
+            },
+            KeyCode::Char(c) => Edit::Insert(c),
+            _ => return None,
+        })
+    }
+
+    /// Applies the edit, returns whether the text changed
+    pub fn apply(self, text: &mut String, cursor: &mut usize) -> bool {
+        *cursor = clamp(text, *cursor);
+        let at = *cursor;
+        let (start, end, moved_to) = match self {
+            Edit::Insert(c) => {
+                text.insert(at, c);
+                *cursor = at + c.len_utf8();
+                return true;
+            }
+            Edit::Left => (at, at, prev_char(text, at)),
+            Edit::Right => (at, at, next_char(text, at)),
+            Edit::WordLeft => (at, at, prev_word(text, at)),
+            Edit::WordRight => (at, at, next_word(text, at)),
+            Edit::Home => (at, at, 0),
+            Edit::End => (at, at, text.len()),
+            Edit::Backspace => (prev_char(text, at), at, prev_char(text, at)),
+            Edit::Delete => (at, next_char(text, at), at),
+            Edit::DeleteWordBack => (prev_word(text, at), at, prev_word(text, at)),
+            Edit::DeleteWordForward => (at, next_word(text, at), at),
+            Edit::KillToStart => (0, at, 0),
+            Edit::KillToEnd => (at, text.len(), at),
+        };
+        *cursor = moved_to;
+        if start == end {
+            return false;
+        }
+        text.replace_range(start..end, "");
+        true
+    }
+}
+
+/// Inserts `s` at the cursor and moves past it (pastes)
+pub fn insert_str(text: &mut String, cursor: &mut usize, s: &str) {
+    let at = clamp(text, *cursor);
+    text.insert_str(at, s);
+    *cursor = at + s.len();
+}
+
+/// Keeps a stored cursor inside the text and on a character boundary
+pub fn clamp(text: &str, cursor: usize) -> usize {
+    let mut at = cursor.min(text.len());
+    while !text.is_char_boundary(at) {
+        at -= 1;
+    }
+    at
+}
+
+/// The text with `marker` drawn where the cursor is, for inputs rendered as plain text
+pub fn with_cursor(text: &str, cursor: usize, marker: char) -> String {
+    let at = clamp(text, cursor);
+    format!("{}{}{}", &text[..at], marker, &text[at..])
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn prev_char(text: &str, at: usize) -> usize {
+    text[..at].char_indices().next_back().map_or(0, |(i, _)| i)
+}
+
+fn next_char(text: &str, at: usize) -> usize {
+    text[at..].chars().next().map_or(at, |c| at + c.len_utf8())
+}
+
+/// Start of the word before the cursor, skipping separators (`/`, `.`, `=`, spaces) first
+fn prev_word(text: &str, at: usize) -> usize {
+    let mut chars = text[..at].char_indices().rev().peekable();
+    while chars.next_if(|(_, c)| !is_word(*c)).is_some() {}
+    let mut start = chars.peek().map_or(0, |(i, _)| *i);
+    while let Some((i, _)) = chars.next_if(|(_, c)| is_word(*c)) {
+        start = i;
+    }
+    start
+}
+
+/// End of the word after the cursor, skipping separators first
+fn next_word(text: &str, at: usize) -> usize {
+    let rest = &text[at..];
+    let mut chars = rest.char_indices().peekable();
+    while chars.next_if(|(_, c)| !is_word(*c)).is_some() {}
+    while chars.next_if(|(_, c)| is_word(*c)).is_some() {}
+    at + chars.peek().map_or(rest.len(), |(i, _)| *i)
+}
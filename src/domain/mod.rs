@@ -1,2 +1,11 @@
 pub mod collection;
 pub mod environment;
+pub mod keymap;
+pub mod line_edit;
+pub mod notification;
+pub mod path_params;
+pub mod profile;
+pub mod snapshot;
+pub mod theme;
+pub mod variables;
+pub mod workspace;
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How many past notifications the history panel keeps
+const HISTORY_LIMIT: usize = 100;
+/// How many toasts are shown at once, newest first
+const MAX_VISIBLE: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NotificationLevel {
+    Info,
+    Success,
+    Error,
+}
+
+impl NotificationLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationLevel::Info => "INFO",
+            NotificationLevel::Success => "OK",
+            NotificationLevel::Error => "ERROR",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub message: String,
+    pub level: NotificationLevel,
+    pub created: Instant,
+}
+
+impl Notification {
+    /// Short relative age for the history panel, e.g. `42s ago`
+    pub fn age(&self) -> String {
+        let secs = self.created.elapsed().as_secs();
+        if secs < 60 {
+            format!("{}s ago", secs)
+        } else if secs < 3600 {
+            format!("{}m ago", secs / 60)
+        } else {
+            format!("{}h ago", secs / 3600)
+        }
+    }
+}
+
+/// Auto-dismiss times per level, in seconds. Stored in `config.json`.
+/// A value of 0 keeps the toast until it is dismissed with Esc.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationTimeouts {
+    pub info_secs: u64,
+    pub success_secs: u64,
+    pub error_secs: u64,
+}
+
+impl Default for NotificationTimeouts {
+    fn default() -> Self {
+        Self {
+            info_secs: 3,
+            success_secs: 3,
+            error_secs: 8,
+        }
+    }
+}
+
+impl NotificationTimeouts {
+    pub fn for_level(&self, level: NotificationLevel) -> Option<Duration> {
+        let secs = match level {
+            NotificationLevel::Info => self.info_secs,
+            NotificationLevel::Success => self.success_secs,
+            NotificationLevel::Error => self.error_secs,
+        };
+        if secs == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(secs))
+        }
+    }
+}
+
+/// Stacked toasts plus a history of everything that was shown
+#[derive(Debug, Default)]
+pub struct NotificationCenter {
+    pub active: Vec<Notification>,
+    pub history: VecDeque<Notification>,
+    pub timeouts: NotificationTimeouts,
+}
+
+impl NotificationCenter {
+    pub fn push(&mut self, message: String, level: NotificationLevel) {
+        let notification = Notification {
+            message,
+            level,
+            created: Instant::now(),
+        };
+
+        self.history.push_front(notification.clone());
+        self.history.truncate(HISTORY_LIMIT);
+        self.active.push(notification);
+    }
+
+    /// Drops toasts whose auto-dismiss time has passed
+    pub fn expire(&mut self, now: Instant) {
+        let timeouts = &self.timeouts;
+        self.active.retain(|n| match timeouts.for_level(n.level) {
+            Some(ttl) => now.duration_since(n.created) < ttl,
+            None => true,
+        });
+    }
+
+    /// Dismisses the newest toast
+    pub fn dismiss(&mut self) {
+        self.active.pop();
+    }
+
+    pub fn dismiss_all(&mut self) {
+        self.active.clear();
+    }
+
+    /// Toasts to draw, newest first
+    pub fn visible(&self) -> impl Iterator<Item = &Notification> {
+        self.active.iter().rev().take(MAX_VISIBLE)
+    }
+}
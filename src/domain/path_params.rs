@@ -0,0 +1,84 @@
+// Path parameters: `/users/:id` and `/users/{id}` in a request URL, listed in the
+// Params tab and filled in when the request goes out. `{{var}}` stays the
+// environment's.
+use std::ops::Range;
+
+/// Byte range of the URL's path: after the scheme and host, before the query
+fn path_range(url: &str) -> Range<usize> {
+    let end = url.find(['?', '#']).unwrap_or(url.len());
+    let after_scheme = url[..end].find("://").map_or(0, |p| p + 3);
+    let start = url[after_scheme..end]
+        .find('/')
+        .map_or(end, |p| after_scheme + p);
+    start..end
+}
+
+/// Length of the name at the start of `text`: letters, digits and `_`, not
+/// starting with a digit
+fn name_len(text: &str) -> usize {
+    let len = text
+        .bytes()
+        .take_while(|b| b.is_ascii_alphanumeric() || *b == b'_')
+        .count();
+    if text.bytes().next().is_some_and(|b| b.is_ascii_digit()) {
+        0
+    } else {
+        len
+    }
+}
+
+/// Each placeholder's span in the URL and its name
+fn placeholders(url: &str) -> Vec<(Range<usize>, &str)> {
+    let path = path_range(url);
+    let bytes = url.as_bytes();
+    let mut found = Vec::new();
+    let mut i = path.start;
+    while i < path.end {
+        match bytes[i] {
+            b'{' if bytes.get(i + 1) == Some(&b'{') => {
+                i = url[i..path.end].find("}}").map_or(path.end, |p| i + p + 2);
+            }
+            b'{' => {
+                let len = name_len(&url[i + 1..path.end]);
+                if len > 0 && bytes.get(i + 1 + len) == Some(&b'}') {
+                    found.push((i..i + len + 2, &url[i + 1..i + 1 + len]));
+                    i += len + 2;
+                } else {
+                    i += 1;
+                }
+            }
+            b':' if bytes[i - 1] == b'/' => {
+                let len = name_len(&url[i + 1..path.end]);
+                if len > 0 {
+                    found.push((i..i + 1 + len, &url[i + 1..i + 1 + len]));
+                }
+                i += 1 + len;
+            }
+            _ => i += 1,
+        }
+    }
+    found
+}
+
+/// The path parameter names in `url`, in order and without repeats
+pub fn names(url: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (_, name) in placeholders(url) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// `url` with each path parameter replaced by its value. One without a value
+/// stays as written, so the request shows what's missing.
+pub fn fill<'a>(url: &str, value: impl Fn(&str) -> Option<&'a str>) -> String {
+    let mut filled = url.to_string();
+    for (span, name) in placeholders(url).into_iter().rev() {
+        if let Some(v) = value(name).filter(|v| !v.is_empty()) {
+            filled.replace_range(span, v);
+        }
+    }
+    filled
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+/// Where workspace profiles are defined
+pub const PROFILES_FILE: &str = "profiles.hcl";
+
+/// What happens to a request a profile cares about, least strict first
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Guard {
+    #[default]
+    Allow,
+    Confirm,
+    Block,
+}
+
+/// A named set of rules, e.g. "read-only intern" or "full access"
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(skip)]
+    pub name: String,
+    /// Host patterns that count as production, `*` matches anything (`*.prod.example.com`)
+    #[serde(default)]
+    pub production_hosts: Vec<String>,
+    /// POST, PUT, PATCH, DELETE, ... against production
+    #[serde(default)]
+    pub destructive: Guard,
+    /// GET, HEAD and OPTIONS against production
+    #[serde(default)]
+    pub safe: Guard,
+}
+
+/// Case-insensitive glob match, `*` standing for any run of characters
+pub fn host_matches(pattern: &str, host: &str) -> bool {
+    fn matches(pattern: &[u8], host: &[u8]) -> bool {
+        match pattern.split_first() {
+            None => host.is_empty(),
+            Some((b'*', rest)) => (0..=host.len()).any(|i| matches(rest, &host[i..])),
+            Some((c, rest)) => host
+                .split_first()
+                .is_some_and(|(h, host_rest)| h == c && matches(rest, host_rest)),
+        }
+    }
+    matches(
+        pattern.trim().to_lowercase().as_bytes(),
+        host.to_lowercase().as_bytes(),
+    )
+}
+
+pub fn is_safe_method(method: &str) -> bool {
+    matches!(method.to_uppercase().as_str(), "GET" | "HEAD" | "OPTIONS")
+}
+
+impl Profile {
+    pub fn is_production(&self, url: &str) -> bool {
+        let Some(host) = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+        else {
+            return false;
+        };
+        self.production_hosts
+            .iter()
+            .any(|pattern| host_matches(pattern, &host))
+    }
+
+    /// The rule that applies to sending `method` to `url`
+    pub fn guard_for(&self, method: &str, url: &str) -> Guard {
+        if !self.is_production(url) {
+            Guard::Allow
+        } else if is_safe_method(method) {
+            self.safe
+        } else {
+            self.destructive
+        }
+    }
+}
+
+/// Reads `profile "name" { ... }` blocks. A missing file means no profiles.
+pub fn load_profiles(path: &str) -> Result<Vec<Profile>, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let body: hcl::Body =
+        hcl::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path, e))?;
+
+    let mut profiles = Vec::new();
+    for block in body.blocks() {
+        if block.identifier() == "profile"
+            && let Some(label) = block.labels().first()
+        {
+            let mut profile: Profile = hcl::from_body(block.body().clone())
+                .map_err(|e| format!("Invalid profile in {}: {}", path, e))?;
+            profile.name = label.as_str().to_string();
+            profiles.push(profile);
+        }
+    }
+    Ok(profiles)
+}
@@ -0,0 +1,60 @@
+// Expected responses, saved per request with `:snapshot` and kept beside the
+// collection file in `{collection}.snapshots.json`. Comparing a response against one
+// is in features::snapshot.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub status: u16,
+    /// The body when it was JSON: compared value by value, key order aside
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json: Option<Value>,
+    /// The body when it wasn't: compared as it is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// JSONPaths left out of the comparison, e.g. `$.updated_at`, `$.items[*].id`,
+    /// `$..timestamp`. Everything under a path is left out with it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+    /// Seconds since the epoch
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+/// Request name -> its snapshot
+pub type Snapshots = BTreeMap<String, Snapshot>;
+
+/// `users.hcl` -> `users.snapshots.json`
+pub fn path_for(hcl_path: &Path) -> PathBuf {
+    hcl_path.with_extension("snapshots.json")
+}
+
+/// The snapshots beside `hcl_path`; none when there's no file
+pub fn load(hcl_path: &Path) -> Result<Snapshots, String> {
+    let path = path_for(hcl_path);
+    match fs::read_to_string(&path) {
+        Ok(content) => {
+            serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+        }
+        Err(_) => Ok(Snapshots::new()),
+    }
+}
+
+/// Writes `{dir}/{collection}.snapshots.json`, or removes it when nothing's left
+pub fn save(dir: &str, collection: &str, snapshots: &Snapshots) -> std::io::Result<PathBuf> {
+    let path = path_for(&Path::new(dir).join(format!("{}.hcl", collection)));
+    if snapshots.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+    } else {
+        let json = serde_json::to_string_pretty(snapshots)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        fs::write(&path, json)?;
+    }
+    Ok(path)
+}
@@ -0,0 +1,392 @@
+// Colour themes: the built-in ones and the user's `themes/*.toml`. A theme file can
+// set any of the UI colours and the syntax highlighting colours; what it leaves out
+// comes from the theme it `extends`, the default one unless it names another.
+//
+//     name = "Nord"
+//     extends = "dracula"
+//
+//     [colors]
+//     background = "#2e3440"
+//     border_focus = "#88c0d0"
+//
+//     [syntax]
+//     base = "base16-ocean.dark"
+//     string = "#a3be8c"
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// In the workspace, one `.toml` file per theme
+pub const THEMES_DIR: &str = "themes";
+
+/// Built-in themes, in the order `Ctrl+t` goes through them
+pub const BUILTIN: &[&str] = &[
+    "default",
+    "matrix",
+    "cyberpunk",
+    "dracula",
+    "light",
+    "solarized-light",
+];
+
+/// The keys of a theme file's `[colors]`
+pub const COLOR_KEYS: &[&str] = &[
+    "background",
+    "border",
+    "border_focus",
+    "text_primary",
+    "text_secondary",
+    "highlight",
+    "success",
+    "error",
+    "accent",
+];
+
+/// The keys of a theme file's `[syntax]`, besides `base`
+pub const SYNTAX_KEYS: &[&str] = &[
+    "text",
+    "string",
+    "number",
+    "keyword",
+    "comment",
+    "key",
+    "punctuation",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// What `:theme` and config.json call it: the built-in's name or the file's stem
+    pub id: String,
+    pub name: String,
+    /// The file it was loaded from, None for a built-in
+    pub file: Option<String>,
+    pub background: Color,
+    pub border: Color,
+    pub border_focus: Color,
+    pub text_primary: Color,
+    pub text_secondary: Color,
+    pub highlight: Color,
+    pub success: Color,
+    pub error: Color,
+    pub accent: Color,
+    /// How responses, bodies and scripts are highlighted
+    pub syntax: SyntaxColors,
+}
+
+/// Highlighting colours: one of syntect's bundled themes, with any of the token
+/// kinds recoloured
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SyntaxColors {
+    /// The syntect theme underneath, `base16-ocean.dark` when empty
+    pub base: String,
+    /// Anything without a colour of its own
+    pub text: Option<Color>,
+    pub string: Option<Color>,
+    /// Numbers
+    pub number: Option<Color>,
+    /// Keywords and `true` / `false` / `null`
+    pub keyword: Option<Color>,
+    pub comment: Option<Color>,
+    /// JSON keys, XML and HTML tags and attributes
+    pub key: Option<Color>,
+    pub punctuation: Option<Color>,
+}
+
+impl SyntaxColors {
+    fn on(base: &str) -> Self {
+        SyntaxColors {
+            base: base.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn slot(&mut self, key: &str) -> Option<&mut Option<Color>> {
+        Some(match key {
+            "text" => &mut self.text,
+            "string" => &mut self.string,
+            "number" => &mut self.number,
+            "keyword" => &mut self.keyword,
+            "comment" => &mut self.comment,
+            "key" => &mut self.key,
+            "punctuation" => &mut self.punctuation,
+            _ => return None,
+        })
+    }
+}
+
+impl Theme {
+    pub fn default_theme() -> Self {
+        Theme {
+            id: "default".to_string(),
+            name: "Default".to_string(),
+            file: None,
+            background: Color::Reset,
+            border: Color::DarkGray,
+            border_focus: Color::Cyan,
+            text_primary: Color::White,
+            text_secondary: Color::Gray,
+            highlight: Color::Yellow,
+            success: Color::Green,
+            error: Color::Red,
+            accent: Color::Cyan,
+            syntax: SyntaxColors::default(),
+        }
+    }
+
+    pub fn matrix() -> Self {
+        Theme {
+            id: "matrix".to_string(),
+            name: "Matrix".to_string(),
+            file: None,
+            background: Color::Black,
+            border: Color::DarkGray,
+            border_focus: Color::Green,
+            text_primary: Color::Green,
+            text_secondary: Color::DarkGray,
+            highlight: Color::LightGreen,
+            success: Color::Green,
+            error: Color::Red,
+            accent: Color::Green,
+            syntax: SyntaxColors::default(),
+        }
+    }
+
+    pub fn cyberpunk() -> Self {
+        Theme {
+            id: "cyberpunk".to_string(),
+            name: "Cyberpunk".to_string(),
+            file: None,
+            background: Color::Black,
+            border: Color::Magenta,
+            border_focus: Color::Yellow,
+            text_primary: Color::Cyan,
+            text_secondary: Color::Magenta,
+            highlight: Color::Yellow,
+            success: Color::LightGreen,
+            error: Color::Red,
+            accent: Color::LightCyan,
+            syntax: SyntaxColors::default(),
+        }
+    }
+
+    pub fn dracula() -> Self {
+        Theme {
+            id: "dracula".to_string(),
+            name: "Dracula".to_string(),
+            file: None,
+            background: Color::Rgb(40, 42, 54),
+            border: Color::Rgb(98, 114, 164),
+            border_focus: Color::Rgb(189, 147, 249),
+            text_primary: Color::Rgb(248, 248, 242),
+            text_secondary: Color::Rgb(98, 114, 164),
+            highlight: Color::Rgb(255, 121, 198),
+            success: Color::Rgb(80, 250, 123),
+            error: Color::Rgb(255, 85, 85),
+            accent: Color::Rgb(139, 233, 253),
+            syntax: SyntaxColors {
+                text: Some(Color::Rgb(248, 248, 242)),
+                string: Some(Color::Rgb(241, 250, 140)),
+                number: Some(Color::Rgb(189, 147, 249)),
+                keyword: Some(Color::Rgb(255, 121, 198)),
+                comment: Some(Color::Rgb(98, 114, 164)),
+                key: Some(Color::Rgb(139, 233, 253)),
+                punctuation: Some(Color::Rgb(248, 248, 242)),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// For light terminals, in the terminal's own palette so it follows its
+    /// background
+    pub fn light() -> Self {
+        Theme {
+            id: "light".to_string(),
+            name: "Light".to_string(),
+            file: None,
+            background: Color::Reset,
+            border: Color::Gray,
+            border_focus: Color::Blue,
+            text_primary: Color::Black,
+            text_secondary: Color::DarkGray,
+            highlight: Color::Magenta,
+            success: Color::Green,
+            error: Color::Red,
+            accent: Color::Blue,
+            syntax: SyntaxColors::on("InspiredGitHub"),
+        }
+    }
+
+    pub fn solarized_light() -> Self {
+        Theme {
+            id: "solarized-light".to_string(),
+            name: "Solarized Light".to_string(),
+            file: None,
+            background: Color::Rgb(253, 246, 227),
+            border: Color::Rgb(147, 161, 161),
+            border_focus: Color::Rgb(38, 139, 210),
+            text_primary: Color::Rgb(88, 110, 117),
+            text_secondary: Color::Rgb(147, 161, 161),
+            highlight: Color::Rgb(181, 137, 0),
+            success: Color::Rgb(133, 153, 0),
+            error: Color::Rgb(220, 50, 47),
+            accent: Color::Rgb(42, 161, 152),
+            syntax: SyntaxColors::on("Solarized (light)"),
+        }
+    }
+
+    pub fn builtin(id: &str) -> Option<Theme> {
+        Some(match id.to_ascii_lowercase().as_str() {
+            "default" => Theme::default_theme(),
+            "matrix" => Theme::matrix(),
+            "cyberpunk" => Theme::cyberpunk(),
+            "dracula" => Theme::dracula(),
+            "light" => Theme::light(),
+            "solarized-light" => Theme::solarized_light(),
+            _ => return None,
+        })
+    }
+
+    fn color_slot(&mut self, key: &str) -> Option<&mut Color> {
+        Some(match key {
+            "background" => &mut self.background,
+            "border" => &mut self.border,
+            "border_focus" => &mut self.border_focus,
+            "text_primary" => &mut self.text_primary,
+            "text_secondary" => &mut self.text_secondary,
+            "highlight" => &mut self.highlight,
+            "success" => &mut self.success,
+            "error" => &mut self.error,
+            "accent" => &mut self.accent,
+            _ => return None,
+        })
+    }
+
+    /// Answers to `:theme` by id or by name, any case
+    pub fn is_called(&self, name: &str) -> bool {
+        self.id.eq_ignore_ascii_case(name) || self.name.eq_ignore_ascii_case(name)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    name: Option<String>,
+    extends: Option<String>,
+    #[serde(default)]
+    colors: BTreeMap<String, String>,
+    #[serde(default)]
+    syntax: BTreeMap<String, String>,
+}
+
+/// A colour name (`blue`, `light-red`, `reset`), `#rrggbb`, `#rgb` or a 256-colour
+/// palette index
+pub fn parse_color(text: &str) -> Result<Color, String> {
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix('#')
+        && hex.len() == 3
+        && hex.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        let double: String = hex.chars().flat_map(|c| [c, c]).collect();
+        return Color::from_str(&format!("#{}", double)).map_err(|e| e.to_string());
+    }
+    Color::from_str(text).map_err(|_| {
+        format!(
+            "'{}' isn't a colour (a name like \"blue\", \"#1e1e2e\" or 0-255)",
+            text
+        )
+    })
+}
+
+/// A theme file's content, `id` being its file stem. `extends` looks among the
+/// built-ins.
+pub fn parse(id: &str, content: &str) -> Result<Theme, String> {
+    let file: ThemeFile = toml::from_str(content).map_err(|e| e.message().to_string())?;
+    let mut theme = match &file.extends {
+        Some(base) => Theme::builtin(base).ok_or_else(|| {
+            format!(
+                "extends '{}', not a built-in theme (one of {})",
+                base,
+                BUILTIN.join(", ")
+            )
+        })?,
+        None => Theme::default_theme(),
+    };
+    theme.id = id.to_string();
+    theme.file = Some(format!("{}/{}.toml", THEMES_DIR, id));
+    theme.name = file.name.unwrap_or_else(|| id.to_string());
+
+    for (key, value) in &file.colors {
+        let color = parse_color(value).map_err(|e| format!("colors.{}: {}", key, e))?;
+        *theme.color_slot(key).ok_or_else(|| {
+            format!(
+                "unknown colour '{}' (one of {})",
+                key,
+                COLOR_KEYS.join(", ")
+            )
+        })? = color;
+    }
+    for (key, value) in &file.syntax {
+        if key == "base" {
+            let bases = crate::ui::syntax::base_themes();
+            if !bases.contains(value) {
+                return Err(format!(
+                    "syntax.base: no highlighting theme '{}' (one of {})",
+                    value,
+                    bases.join(", ")
+                ));
+            }
+            theme.syntax.base = value.clone();
+            continue;
+        }
+        let color = parse_color(value).map_err(|e| format!("syntax.{}: {}", key, e))?;
+        *theme.syntax.slot(key).ok_or_else(|| {
+            format!(
+                "unknown syntax colour '{}' (base or one of {})",
+                key,
+                SYNTAX_KEYS.join(", ")
+            )
+        })? = Some(color);
+    }
+    Ok(theme)
+}
+
+/// The built-ins, then the themes in `dir` by file name. A file named after a
+/// built-in takes its place. Files that don't parse are left out and come back as
+/// "file.toml: what's wrong" messages.
+pub fn load_all(dir: &str) -> (Vec<Theme>, Vec<String>) {
+    let mut themes: Vec<Theme> = BUILTIN.iter().filter_map(|id| Theme::builtin(id)).collect();
+    let mut errors = Vec::new();
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == "toml"))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+
+    for path in paths {
+        let file = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let id = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let theme = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| parse(&id, &content));
+        match theme {
+            Ok(theme) => match themes.iter_mut().find(|t| t.id.eq_ignore_ascii_case(&id)) {
+                Some(builtin) => *builtin = theme,
+                None => themes.push(theme),
+            },
+            Err(e) => errors.push(format!("{}: {}", file, e)),
+        }
+    }
+    (themes, errors)
+}
@@ -0,0 +1,123 @@
+// Where `{{name}}` gets its value. Each scope goes over the ones before it:
+// global (config.json) < environment < collection < request.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Scope {
+    Global,
+    Environment,
+    Collection,
+    Request,
+}
+
+impl Scope {
+    pub fn label(self) -> &'static str {
+        match self {
+            Scope::Global => "global",
+            Scope::Environment => "environment",
+            Scope::Collection => "collection",
+            Scope::Request => "request",
+        }
+    }
+}
+
+/// One scope's variables, `source` naming where they come from (the environment,
+/// the collection)
+#[derive(Clone, Debug)]
+pub struct Layer {
+    pub scope: Scope,
+    pub source: String,
+    pub variables: HashMap<String, String>,
+}
+
+/// A variable's value, the scope it came from and the values it hides, nearest first
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resolved {
+    pub name: String,
+    pub value: String,
+    pub scope: Scope,
+    pub source: String,
+    pub shadowed: Vec<(Scope, String)>,
+}
+
+/// The values that win, lowest scope first in `layers`
+pub fn merge(layers: &[Layer]) -> HashMap<String, String> {
+    let mut merged = HashMap::new();
+    for layer in layers {
+        merged.extend(layer.variables.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    merged
+}
+
+/// Every variable by name with the scope it resolves from
+pub fn resolve(layers: &[Layer]) -> Vec<Resolved> {
+    let mut names: BTreeMap<&str, Vec<&Layer>> = BTreeMap::new();
+    for layer in layers {
+        for name in layer.variables.keys() {
+            names.entry(name).or_default().push(layer);
+        }
+    }
+    names
+        .into_iter()
+        .map(|(name, defined)| {
+            let (winner, hidden) = defined.split_last().expect("defined at least once");
+            Resolved {
+                name: name.to_string(),
+                value: winner.variables[name].clone(),
+                scope: winner.scope,
+                source: winner.source.clone(),
+                shadowed: hidden
+                    .iter()
+                    .rev()
+                    .map(|l| (l.scope, l.variables[name].clone()))
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// `{{name}}` placeholders in `text`, leaving out `{{$template}}` functions
+pub fn references(text: &str) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("}}") else {
+            break;
+        };
+        let name = &rest[..end];
+        if !name.is_empty() && !name.starts_with('$') && !name.contains("{{") {
+            names.insert(name.to_string());
+        }
+        rest = &rest[end + 2..];
+    }
+    names
+}
+
+/// What the resolved-variables inspector shows for a request
+#[derive(Clone, Debug, Default)]
+pub struct Inspection {
+    /// The scopes looked in, lowest first
+    pub layers: Vec<(Scope, String)>,
+    pub variables: Vec<Resolved>,
+    /// Names the request refers to
+    pub used: BTreeSet<String>,
+    /// Names the request refers to that no scope defines
+    pub missing: Vec<String>,
+}
+
+pub fn inspect<'a>(layers: &[Layer], texts: impl IntoIterator<Item = &'a str>) -> Inspection {
+    let variables = resolve(layers);
+    let used: BTreeSet<String> = texts.into_iter().flat_map(references).collect();
+    let missing = used
+        .iter()
+        .filter(|name| !variables.iter().any(|v| &v.name == *name))
+        .cloned()
+        .collect();
+    Inspection {
+        layers: layers.iter().map(|l| (l.scope, l.source.clone())).collect(),
+        variables,
+        used,
+        missing,
+    }
+}
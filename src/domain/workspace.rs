@@ -0,0 +1,291 @@
+use std::path::{Path, PathBuf};
+
+/// Overrides where the default workspace lives (`POSTDAD_HOME=.` for the old behaviour)
+pub const HOME_ENV: &str = "POSTDAD_HOME";
+
+/// Settings rather than data: kept in the config dir for the default workspace
+pub const CONFIG_FILES: &[&str] = &["config.json", "keymap.toml", "profiles.hcl"];
+
+/// What PostDad used to keep in the directory it was started from
+const LEGACY_FILES: &[&str] = &[
+    "config.json",
+    "keymap.toml",
+    "profiles.hcl",
+    "environments.hcl",
+    "mocks.hcl",
+    "history.json",
+    "cookies.json",
+    "command_history.json",
+];
+const LEGACY_DIRS: &[&str] = &["collections", "codegen"];
+
+/// Collections, environments, history, cookies and config all live under one directory,
+/// so two projects never share (or clobber) each other's history.json and cookies.json.
+/// An empty root is the current directory.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Workspace {
+    pub root: PathBuf,
+    /// Where `CONFIG_FILES` go instead of `root`, only set for the default workspace
+    pub config_root: Option<PathBuf>,
+}
+
+impl Workspace {
+    pub fn at(root: impl Into<PathBuf>) -> Workspace {
+        Workspace {
+            root: root.into(),
+            config_root: None,
+        }
+    }
+
+    /// `name` inside the workspace, in the form the loaders take
+    pub fn path(&self, name: &str) -> String {
+        let root = match &self.config_root {
+            Some(config) if CONFIG_FILES.contains(&name) => config,
+            _ => &self.root,
+        };
+        root.join(name).to_string_lossy().into_owned()
+    }
+
+    pub fn dir(&self) -> &Path {
+        if self.root.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            &self.root
+        }
+    }
+
+    pub fn is_current_dir(&self) -> bool {
+        self.root.as_os_str().is_empty()
+    }
+
+    /// The XDG default (or the current directory): not worth a status bar badge
+    pub fn is_default(&self) -> bool {
+        self.config_root.is_some() || self.is_current_dir()
+    }
+
+    /// Creates the directories the workspace writes to
+    pub fn create(&self) -> std::io::Result<()> {
+        std::fs::create_dir_all(self.dir())?;
+        if let Some(config) = &self.config_root {
+            std::fs::create_dir_all(config)?;
+        }
+        Ok(())
+    }
+
+    /// Keyring entries are per workspace, keyed by its directory
+    pub fn keyring_account(&self) -> String {
+        let dir = self.dir();
+        std::fs::canonicalize(dir)
+            .unwrap_or_else(|_| dir.to_path_buf())
+            .display()
+            .to_string()
+    }
+
+    /// Named workspaces by name, anything else by its directory
+    pub fn label(&self) -> String {
+        if self.is_current_dir() {
+            return ".".to_string();
+        }
+        if self.config_root.is_some() {
+            return "default".to_string();
+        }
+        if let Some(dir) = workspaces_dir()
+            && self.root.parent() == Some(dir.as_path())
+            && let Some(name) = self.root.file_name()
+        {
+            return name.to_string_lossy().into_owned();
+        }
+        self.root.display().to_string()
+    }
+}
+
+/// Where PostDad keeps things when no `--workspace` is given: `$POSTDAD_HOME` if set,
+/// otherwise the data dir with settings in the config dir. Falls back to the current
+/// directory when neither can be found.
+pub fn default_workspace() -> Workspace {
+    if let Some(home) = std::env::var_os(HOME_ENV).filter(|h| !h.is_empty()) {
+        return match home.to_str() {
+            Some(".") => Workspace::default(),
+            _ => Workspace::at(home),
+        };
+    }
+    match (data_dir(), config_dir()) {
+        (Some(data), Some(config)) => Workspace {
+            root: data,
+            config_root: Some(config),
+        },
+        _ => Workspace::default(),
+    }
+}
+
+/// `--workspace` / `:workspace` argument: `.`, a path (`./api`, `~/work/shop`, or any
+/// directory that exists) is used as is, `default` is the default workspace, and any
+/// other bare name is a named workspace kept under the data directory
+pub fn resolve(arg: &str) -> Result<Workspace, String> {
+    if arg.trim() == "default" {
+        return Ok(default_workspace());
+    }
+    resolve_in(arg, workspaces_dir())
+}
+
+pub fn resolve_in(arg: &str, workspaces: Option<PathBuf>) -> Result<Workspace, String> {
+    let arg = arg.trim();
+    if arg.is_empty() {
+        return Err("Workspace name is empty".to_string());
+    }
+    if arg == "." {
+        return Ok(Workspace::default());
+    }
+    if let Some(rest) = arg.strip_prefix("~/") {
+        let home = std::env::var_os("HOME").ok_or("HOME is not set")?;
+        return Ok(Workspace::at(PathBuf::from(home).join(rest)));
+    }
+    let path = Path::new(arg);
+    if arg.contains(['/', '\\']) || path.is_dir() {
+        return Ok(Workspace::at(path));
+    }
+    let dir = workspaces.ok_or("No data directory (set XDG_DATA_HOME or HOME)")?;
+    Ok(Workspace::at(dir.join(arg)))
+}
+
+/// `$XDG_DATA_HOME/postdad`, else `~/.local/share/postdad` (`%APPDATA%\postdad` on Windows)
+pub fn data_dir() -> Option<PathBuf> {
+    data_dir_from(
+        std::env::var_os("XDG_DATA_HOME").map(PathBuf::from),
+        home(),
+        std::env::var_os("APPDATA").map(PathBuf::from),
+    )
+}
+
+/// `$XDG_CONFIG_HOME/postdad`, else `~/.config/postdad` (`%APPDATA%\postdad` on Windows)
+pub fn config_dir() -> Option<PathBuf> {
+    config_dir_from(
+        std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from),
+        home(),
+        std::env::var_os("APPDATA").map(PathBuf::from),
+    )
+}
+
+/// `$HOME`, except on Windows: shells like Git Bash set it there too, and the files
+/// belong in `%APPDATA%` all the same. macOS gets the XDG layout on purpose, like
+/// other terminal tools, rather than `~/Library/Application Support`.
+fn home() -> Option<PathBuf> {
+    if cfg!(windows) {
+        return None;
+    }
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+pub fn data_dir_from(
+    xdg_data_home: Option<PathBuf>,
+    home: Option<PathBuf>,
+    appdata: Option<PathBuf>,
+) -> Option<PathBuf> {
+    xdg_dir(xdg_data_home, home, ".local/share", appdata)
+}
+
+pub fn config_dir_from(
+    xdg_config_home: Option<PathBuf>,
+    home: Option<PathBuf>,
+    appdata: Option<PathBuf>,
+) -> Option<PathBuf> {
+    xdg_dir(xdg_config_home, home, ".config", appdata)
+}
+
+fn xdg_dir(
+    xdg: Option<PathBuf>,
+    home: Option<PathBuf>,
+    under_home: &str,
+    appdata: Option<PathBuf>,
+) -> Option<PathBuf> {
+    // The spec says a relative XDG_*_HOME is invalid and should be ignored
+    let base = xdg
+        .filter(|p| p.is_absolute())
+        .or_else(|| home.map(|h| h.join(under_home)))
+        .or(appdata)?;
+    Some(base.join("postdad"))
+}
+
+pub fn workspaces_dir() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("workspaces"))
+}
+
+/// Named workspaces that exist, sorted
+pub fn list_named() -> Vec<String> {
+    workspaces_dir().map(|d| list_in(&d)).unwrap_or_default()
+}
+
+pub fn list_in(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Whether `from` looks like a directory an older PostDad ran in
+pub fn has_legacy_files(from: &Path) -> bool {
+    from.join("environments.hcl").is_file() || from.join("collections").is_dir()
+}
+
+/// Copies what an older PostDad left in `from` into the workspace, never overwriting.
+/// The originals stay where they are. Returns what was copied.
+pub fn migrate(from: &Path, to: &Workspace) -> Result<Vec<String>, String> {
+    let copy = |src: &Path, dst: &Path| {
+        if let Some(parent) = dst.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(src, dst).map(|_| ())
+    };
+    let mut copied = Vec::new();
+    for name in LEGACY_FILES {
+        let (src, dst) = (from.join(name), PathBuf::from(to.path(name)));
+        if src.is_file() && !dst.exists() {
+            copy(&src, &dst).map_err(|e| format!("Could not copy {}: {}", name, e))?;
+            copied.push(name.to_string());
+        }
+    }
+    for dir in LEGACY_DIRS {
+        let Ok(entries) = std::fs::read_dir(from.join(dir)) else {
+            continue;
+        };
+        let mut files: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file())
+            .collect();
+        files.sort();
+        for src in files {
+            let name = format!(
+                "{}/{}",
+                dir,
+                src.file_name().unwrap_or_default().to_string_lossy()
+            );
+            let dst = PathBuf::from(to.path(&name));
+            if !dst.exists() {
+                copy(&src, &dst).map_err(|e| format!("Could not copy {}: {}", name, e))?;
+                copied.push(name);
+            }
+        }
+    }
+    Ok(copied)
+}
+
+/// First start with the default workspace: brings over the files from the current
+/// directory, if it holds an older setup. None when there was nothing to do.
+pub fn migrate_on_first_run(workspace: &Workspace) -> Option<Result<Vec<String>, String>> {
+    if workspace.config_root.is_none() || workspace.root.exists() {
+        return None;
+    }
+    let cwd = std::env::current_dir().ok()?;
+    if !has_legacy_files(&cwd) {
+        return None;
+    }
+    Some(migrate(&cwd, workspace))
+}
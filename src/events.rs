@@ -0,0 +1,138 @@
+// Everything the main loop reacts to, from one place: terminal input, the redraw
+// tick and the channels the background tasks report on. The loop awaits the next
+// one instead of polling each channel in turn, and only redraws when something
+// actually changed.
+use crate::features::{batch, dashboard, fuzz, gist_sync, paginate, runner, sentinel, stress};
+use crate::net::{http::NetworkEvent, websocket::WsEvent};
+use crossterm::event::{self, Event};
+use futures_util::FutureExt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// How often the spinner moves and toasts are checked for expiry
+pub const TICK: Duration = Duration::from_millis(100);
+/// Ticks between redraws when nothing is moving, for the "3s ago" kind of text
+pub const IDLE_REDRAW_TICKS: u64 = 10;
+/// Events handled before the screen is redrawn when they arrive in a burst
+pub const BATCH: usize = 64;
+
+pub enum AppEvent {
+    Input(Event),
+    Tick,
+    /// Boxed, it's by far the biggest
+    Network(Box<NetworkEvent>),
+    Ws(WsEvent),
+    Runner(runner::RunnerEvent),
+    Stress(stress::StressEvent),
+    Fuzz(fuzz::FuzzEvent),
+    Batch(batch::BatchEvent),
+    Paginate(paginate::PaginateEvent),
+    Sync(gist_sync::SyncEvent),
+    Sentinel(sentinel::SentinelResult),
+    Dashboard(dashboard::DashboardEvent),
+}
+
+/// Reads the terminal on its own thread so the loop never blocks on it. Paused
+/// while an external editor owns the terminal, which would otherwise lose keys to it.
+pub struct InputReader {
+    paused: Arc<AtomicBool>,
+}
+
+impl InputReader {
+    pub fn spawn(tx: mpsc::Sender<Event>) -> Self {
+        let paused = Arc::new(AtomicBool::new(false));
+        let flag = paused.clone();
+        std::thread::spawn(move || {
+            while !tx.is_closed() {
+                if flag.load(Ordering::Acquire) {
+                    std::thread::sleep(Duration::from_millis(20));
+                    continue;
+                }
+                // Short polls so a pause takes effect quickly
+                match event::poll(Duration::from_millis(50)) {
+                    Ok(true) => match event::read() {
+                        Ok(event) => {
+                            if tx.blocking_send(event).is_err() {
+                                break;
+                            }
+                        }
+                        Err(_) => break,
+                    },
+                    Ok(false) => {}
+                    Err(_) => break,
+                }
+            }
+        });
+        InputReader { paused }
+    }
+
+    /// Stops reading, returning once a poll that was under way has finished
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Release);
+        std::thread::sleep(Duration::from_millis(60));
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Release);
+    }
+}
+
+/// The receiving ends of every channel the loop listens on
+pub struct EventSources {
+    pub input: mpsc::Receiver<Event>,
+    pub tick: tokio::time::Interval,
+    pub network: mpsc::Receiver<NetworkEvent>,
+    pub ws: mpsc::Receiver<WsEvent>,
+    pub runner: mpsc::Receiver<runner::RunnerEvent>,
+    pub stress: mpsc::Receiver<stress::StressEvent>,
+    pub fuzz: mpsc::Receiver<fuzz::FuzzEvent>,
+    pub batch: mpsc::Receiver<batch::BatchEvent>,
+    pub paginate: mpsc::Receiver<paginate::PaginateEvent>,
+    pub sync: mpsc::Receiver<gist_sync::SyncEvent>,
+    pub sentinel: mpsc::Receiver<sentinel::SentinelResult>,
+    pub dashboard: mpsc::Receiver<dashboard::DashboardEvent>,
+}
+
+impl EventSources {
+    /// Ticks it makes are skipped, not bunched up, when the loop falls behind
+    pub fn tick() -> tokio::time::Interval {
+        let mut tick = tokio::time::interval(TICK);
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        tick
+    }
+
+    /// Waits for whichever comes first. Input goes before the rest so typing stays
+    /// responsive while a stream or a stress test floods its channel. None once the
+    /// terminal can't be read anymore.
+    pub async fn next(&mut self) -> Option<AppEvent> {
+        tokio::select! {
+            biased;
+            event = self.input.recv() => event.map(AppEvent::Input),
+            Some(event) = self.network.recv() => Some(AppEvent::Network(Box::new(event))),
+            Some(event) = self.ws.recv() => Some(AppEvent::Ws(event)),
+            Some(event) = self.runner.recv() => Some(AppEvent::Runner(event)),
+            Some(event) = self.stress.recv() => Some(AppEvent::Stress(event)),
+            Some(event) = self.fuzz.recv() => Some(AppEvent::Fuzz(event)),
+            Some(event) = self.batch.recv() => Some(AppEvent::Batch(event)),
+            Some(event) = self.paginate.recv() => Some(AppEvent::Paginate(event)),
+            Some(event) = self.sync.recv() => Some(AppEvent::Sync(event)),
+            Some(event) = self.sentinel.recv() => Some(AppEvent::Sentinel(event)),
+            Some(event) = self.dashboard.recv() => Some(AppEvent::Dashboard(event)),
+            _ = self.tick.tick() => Some(AppEvent::Tick),
+        }
+    }
+
+    /// The events that are already waiting, up to `BATCH`, so a burst is drawn once
+    pub fn ready(&mut self) -> Vec<AppEvent> {
+        let mut events = Vec::new();
+        while events.len() < BATCH {
+            match self.next().now_or_never() {
+                Some(Some(AppEvent::Tick)) | Some(None) | None => break,
+                Some(Some(event)) => events.push(event),
+            }
+        }
+        events
+    }
+}
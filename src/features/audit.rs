@@ -0,0 +1,523 @@
+// `A` on a response: security headers, cookies, CORS and the TLS connection, as a
+// graded checklist in the doctor's format.
+use crate::features::doctor::{Check, Level};
+use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
+use openssl::x509::{X509Ref, X509VerifyResult};
+use std::collections::HashMap;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+pub const TLS_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// HSTS max-age below this (180 days) is too short to count
+const HSTS_MIN_AGE: u64 = 15_552_000;
+
+/// Certificates expiring within this many days are flagged
+const EXPIRY_WARN_DAYS: i32 = 30;
+
+#[derive(Clone, Debug)]
+pub struct AuditReport {
+    pub url: String,
+    pub grade: char,
+    pub checks: Vec<Check>,
+}
+
+/// One certificate of the chain the server sent, leaf first
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub not_after: String,
+    /// Negative once it has expired
+    pub days_left: i32,
+    /// e.g. `RSA 2048` or `EC 256`
+    pub key: String,
+    pub signature: String,
+}
+
+/// What the handshake settled on
+#[derive(Clone, Debug, PartialEq)]
+pub struct TlsDetails {
+    pub version: String,
+    pub cipher: String,
+    /// Why the chain didn't verify against the system roots, if it didn't
+    pub verify_error: Option<String>,
+    pub chain: Vec<CertInfo>,
+}
+
+fn header<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.trim())
+}
+
+/// The same value for every problem in a check: the worst one wins
+fn worst(problems: &[(Level, String)]) -> Level {
+    problems
+        .iter()
+        .map(|(level, _)| *level)
+        .max()
+        .unwrap_or(Level::Ok)
+}
+
+pub fn header_checks(headers: &HashMap<String, String>, https: bool) -> Vec<Check> {
+    const AREA: &str = "headers";
+    let mut checks = Vec::new();
+    let html = header(headers, "content-type").is_some_and(|t| t.contains("html"));
+
+    if https {
+        checks.push(match header(headers, "strict-transport-security") {
+            None => Check::problem(
+                AREA,
+                Level::Fail,
+                "Strict-Transport-Security",
+                "missing",
+                "Send `Strict-Transport-Security: max-age=31536000; includeSubDomains`",
+            ),
+            Some(value) => {
+                let max_age = value
+                    .split(';')
+                    .filter_map(|part| part.trim().strip_prefix("max-age="))
+                    .find_map(|age| age.trim_matches('"').parse::<u64>().ok())
+                    .unwrap_or(0);
+                if max_age < HSTS_MIN_AGE {
+                    Check::problem(
+                        AREA,
+                        Level::Warn,
+                        "Strict-Transport-Security",
+                        format!("max-age={} is under 180 days", max_age),
+                        "Raise max-age to at least 15552000",
+                    )
+                } else {
+                    Check::ok(AREA, "Strict-Transport-Security", value)
+                }
+            }
+        });
+    }
+
+    checks.push(match header(headers, "content-security-policy") {
+        None => Check::problem(
+            AREA,
+            if html { Level::Fail } else { Level::Warn },
+            "Content-Security-Policy",
+            "missing",
+            if html {
+                "Send a policy, starting from `default-src 'self'`"
+            } else {
+                "APIs can send `default-src 'none'; frame-ancestors 'none'`"
+            },
+        ),
+        Some(policy) => {
+            let weak: Vec<&str> = ["'unsafe-inline'", "'unsafe-eval'"]
+                .into_iter()
+                .filter(|w| policy.contains(w))
+                .collect();
+            if weak.is_empty() {
+                Check::ok(AREA, "Content-Security-Policy", policy)
+            } else {
+                Check::problem(
+                    AREA,
+                    Level::Warn,
+                    "Content-Security-Policy",
+                    format!("allows {}", weak.join(" and ")),
+                    "Use nonces or hashes instead",
+                )
+            }
+        }
+    });
+
+    checks.push(match header(headers, "x-content-type-options") {
+        Some(v) if v.eq_ignore_ascii_case("nosniff") => {
+            Check::ok(AREA, "X-Content-Type-Options", "nosniff")
+        }
+        other => Check::problem(
+            AREA,
+            Level::Warn,
+            "X-Content-Type-Options",
+            other.map_or("missing".to_string(), |v| format!("'{}'", v)),
+            "Send `X-Content-Type-Options: nosniff`",
+        ),
+    });
+
+    if html {
+        let framing = header(headers, "x-frame-options")
+            .map(str::to_string)
+            .or_else(|| {
+                header(headers, "content-security-policy")
+                    .filter(|csp| csp.contains("frame-ancestors"))
+                    .map(|_| "CSP frame-ancestors".to_string())
+            });
+        checks.push(match framing {
+            Some(how) => Check::ok(AREA, "Clickjacking", how),
+            None => Check::problem(
+                AREA,
+                Level::Warn,
+                "Clickjacking",
+                "page can be framed by any site",
+                "Send `X-Frame-Options: DENY` or CSP `frame-ancestors 'none'`",
+            ),
+        });
+        checks.push(match header(headers, "referrer-policy") {
+            Some(policy) => Check::ok(AREA, "Referrer-Policy", policy),
+            None => Check::problem(
+                AREA,
+                Level::Warn,
+                "Referrer-Policy",
+                "missing",
+                "Send `Referrer-Policy: strict-origin-when-cross-origin`",
+            ),
+        });
+    }
+
+    for name in ["server", "x-powered-by"] {
+        if let Some(value) = header(headers, name).filter(|v| v.chars().any(|c| c.is_ascii_digit()))
+        {
+            checks.push(Check::problem(
+                AREA,
+                Level::Warn,
+                if name == "server" {
+                    "Server"
+                } else {
+                    "X-Powered-By"
+                },
+                format!("'{}' gives the version away", value),
+                "Drop the version, or the header",
+            ));
+        }
+    }
+    checks
+}
+
+/// `origin` is the Origin the request sent, to tell a reflected one from a fixed list
+pub fn cors_checks(headers: &HashMap<String, String>, origin: Option<&str>) -> Vec<Check> {
+    const AREA: &str = "cors";
+    let credentials = header(headers, "access-control-allow-credentials")
+        .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+    let check = match header(headers, "access-control-allow-origin") {
+        None => Check::ok(
+            AREA,
+            "Access-Control-Allow-Origin",
+            "not set, same origin only",
+        ),
+        Some("null") => Check::problem(
+            AREA,
+            Level::Fail,
+            "Access-Control-Allow-Origin",
+            "'null' lets sandboxed iframes and file:// pages in",
+            "List the origins that should have access",
+        ),
+        Some("*") if credentials => Check::problem(
+            AREA,
+            Level::Fail,
+            "Access-Control-Allow-Origin",
+            "'*' together with Allow-Credentials: true",
+            "Browsers refuse this; list the origins instead of '*'",
+        ),
+        Some("*") => Check::problem(
+            AREA,
+            Level::Warn,
+            "Access-Control-Allow-Origin",
+            "'*': any site can read these responses",
+            "Fine for public data, otherwise list the origins",
+        ),
+        Some(allowed) if credentials && origin == Some(allowed) => Check::problem(
+            AREA,
+            Level::Warn,
+            "Access-Control-Allow-Origin",
+            format!("echoes the request's Origin ({}) with credentials", allowed),
+            "Resend with another Origin header: if it's echoed too, any site gets in",
+        ),
+        Some(allowed) => Check::ok(AREA, "Access-Control-Allow-Origin", allowed),
+    };
+    vec![check]
+}
+
+/// One check per Set-Cookie, with every attribute it's missing
+pub fn cookie_checks(cookies: &[String], https: bool) -> Vec<Check> {
+    const AREA: &str = "cookies";
+    cookies
+        .iter()
+        .map(|cookie| {
+            let mut parts = cookie.split(';').map(str::trim);
+            let name = parts
+                .next()
+                .and_then(|pair| pair.split('=').next())
+                .unwrap_or("")
+                .to_string();
+            let attributes: Vec<String> = parts.map(|p| p.to_ascii_lowercase()).collect();
+            let has = |attr: &str| {
+                attributes
+                    .iter()
+                    .any(|a| a == attr || a.starts_with(&format!("{}=", attr)))
+            };
+            let same_site = attributes
+                .iter()
+                .find_map(|a| a.strip_prefix("samesite="))
+                .map(str::to_string);
+
+            let mut problems = Vec::new();
+            if !has("secure") {
+                problems.push((
+                    if https { Level::Fail } else { Level::Warn },
+                    "no Secure".to_string(),
+                ));
+            }
+            if !has("httponly") {
+                problems.push((Level::Warn, "no HttpOnly".to_string()));
+            }
+            match same_site.as_deref() {
+                None => problems.push((Level::Warn, "no SameSite".to_string())),
+                Some("none") if !has("secure") => {
+                    problems.push((Level::Fail, "SameSite=None without Secure".to_string()))
+                }
+                _ => {}
+            }
+            if problems.is_empty() {
+                Check::ok(AREA, name, "Secure, HttpOnly, SameSite")
+            } else {
+                let detail: Vec<&str> = problems.iter().map(|(_, p)| p.as_str()).collect();
+                Check::problem(
+                    AREA,
+                    worst(&problems),
+                    name,
+                    detail.join(", "),
+                    "Set `Secure; HttpOnly; SameSite=Lax` (or Strict)",
+                )
+            }
+        })
+        .collect()
+}
+
+fn cert_info(cert: &X509Ref) -> CertInfo {
+    let name = |name: &openssl::x509::X509NameRef| {
+        let entries: Vec<_> = name.entries().collect();
+        entries
+            .iter()
+            .find(|e| e.object().nid() == openssl::nid::Nid::COMMONNAME)
+            .or(entries.first())
+            .and_then(|e| e.data().as_utf8().ok())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "?".to_string())
+    };
+    let days_left = openssl::asn1::Asn1Time::days_from_now(0)
+        .ok()
+        .and_then(|now| now.diff(cert.not_after()).ok())
+        .map_or(0, |diff| diff.days);
+    let key = cert.public_key().map_or("?".to_string(), |key| {
+        let kind = match key.id() {
+            openssl::pkey::Id::RSA => "RSA",
+            openssl::pkey::Id::EC => "EC",
+            openssl::pkey::Id::ED25519 => "Ed25519",
+            _ => "key",
+        };
+        format!("{} {}", kind, key.bits())
+    });
+    CertInfo {
+        subject: name(cert.subject_name()),
+        issuer: name(cert.issuer_name()),
+        not_after: cert.not_after().to_string(),
+        days_left,
+        key,
+        signature: cert
+            .signature_algorithm()
+            .object()
+            .nid()
+            .short_name()
+            .unwrap_or("?")
+            .to_string(),
+    }
+}
+
+/// Connects on its own and looks at the handshake. Verification is left on for the
+/// result but doesn't stop the handshake, so a bad chain can still be shown.
+pub fn probe_tls(host: &str, port: u16, timeout: Duration) -> Result<TlsDetails, String> {
+    let addr = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("can't resolve {}: {}", host, e))?
+        .next()
+        .ok_or_else(|| format!("no address for {}", host))?;
+    let stream = TcpStream::connect_timeout(&addr, timeout).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .map_err(|e| e.to_string())?;
+
+    let mut builder = SslConnector::builder(SslMethod::tls()).map_err(|e| e.to_string())?;
+    builder.set_verify(SslVerifyMode::NONE);
+    let stream = builder
+        .build()
+        .configure()
+        .map_err(|e| e.to_string())?
+        .connect(host, stream)
+        .map_err(|e| format!("handshake failed: {}", e))?;
+    let ssl = stream.ssl();
+
+    let verify = ssl.verify_result();
+    Ok(TlsDetails {
+        version: ssl.version_str().to_string(),
+        cipher: ssl
+            .current_cipher()
+            .map_or("?".to_string(), |c| c.name().to_string()),
+        verify_error: (verify != X509VerifyResult::OK).then(|| verify.error_string().to_string()),
+        chain: ssl
+            .peer_cert_chain()
+            .map(|chain| chain.iter().map(cert_info).collect())
+            .unwrap_or_default(),
+    })
+}
+
+pub fn tls_checks(tls: &Result<TlsDetails, String>) -> Vec<Check> {
+    const AREA: &str = "tls";
+    let tls = match tls {
+        Ok(tls) => tls,
+        Err(e) => {
+            return vec![Check::problem(
+                AREA,
+                Level::Fail,
+                "Connection",
+                e.clone(),
+                "Check the host is reachable directly (the audit doesn't use the proxy)",
+            )];
+        }
+    };
+    let mut checks = Vec::new();
+
+    checks.push(if matches!(tls.version.as_str(), "TLSv1.3" | "TLSv1.2") {
+        Check::ok(AREA, "Version", tls.version.clone())
+    } else {
+        Check::problem(
+            AREA,
+            Level::Fail,
+            "Version",
+            format!("{} is deprecated", tls.version),
+            "Allow TLS 1.2 and 1.3 only",
+        )
+    });
+    checks.push(
+        if ["GCM", "CHACHA20", "CCM"]
+            .iter()
+            .any(|aead| tls.cipher.contains(aead))
+        {
+            Check::ok(AREA, "Cipher", tls.cipher.clone())
+        } else {
+            Check::problem(
+                AREA,
+                Level::Warn,
+                "Cipher",
+                format!("{} isn't an AEAD cipher", tls.cipher),
+                "Prefer AES-GCM or ChaCha20-Poly1305 suites",
+            )
+        },
+    );
+    checks.push(match &tls.verify_error {
+        None => Check::ok(AREA, "Chain", "trusted, matches the host"),
+        Some(e) => Check::problem(
+            AREA,
+            Level::Fail,
+            "Chain",
+            e.clone(),
+            "Serve the full chain from a trusted CA, for this host name",
+        ),
+    });
+
+    for (i, cert) in tls.chain.iter().enumerate() {
+        let mut problems = Vec::new();
+        if cert.days_left < 0 {
+            problems.push((Level::Fail, "expired".to_string()));
+        } else if cert.days_left < EXPIRY_WARN_DAYS {
+            problems.push((Level::Warn, format!("expires in {} days", cert.days_left)));
+        }
+        let sha1 = cert.signature.to_ascii_lowercase().contains("sha1");
+        // A root's own signature isn't checked by anyone
+        if sha1 && i + 1 < tls.chain.len() {
+            problems.push((Level::Fail, "signed with SHA-1".to_string()));
+        }
+        if cert
+            .key
+            .strip_prefix("RSA ")
+            .and_then(|bits| bits.parse::<u32>().ok())
+            .is_some_and(|bits| bits < 2048)
+        {
+            problems.push((Level::Fail, format!("{} is too short", cert.key)));
+        }
+        let name = format!("#{} {}", i, cert.subject);
+        let detail = format!(
+            "issued by {}, {}, {}, until {}",
+            cert.issuer, cert.key, cert.signature, cert.not_after
+        );
+        checks.push(if problems.is_empty() {
+            Check::ok(AREA, name, detail)
+        } else {
+            let what: Vec<&str> = problems.iter().map(|(_, p)| p.as_str()).collect();
+            Check::problem(
+                AREA,
+                worst(&problems),
+                name,
+                format!("{}; {}", what.join(", "), detail),
+                "Renew or reissue the certificate",
+            )
+        });
+    }
+    checks
+}
+
+/// A to F from the share of passing checks (a warning counts half); any failure
+/// caps it at C
+pub fn grade(checks: &[Check]) -> char {
+    if checks.is_empty() {
+        return 'A';
+    }
+    let points: f64 = checks
+        .iter()
+        .map(|c| match c.level {
+            Level::Ok => 1.0,
+            Level::Warn => 0.5,
+            Level::Fail => 0.0,
+        })
+        .sum();
+    let score = points / checks.len() as f64;
+    let grade = match score {
+        s if s >= 0.9 => 'A',
+        s if s >= 0.8 => 'B',
+        s if s >= 0.65 => 'C',
+        s if s >= 0.5 => 'D',
+        _ => 'F',
+    };
+    if checks.iter().any(|c| c.level == Level::Fail) {
+        grade.max('C')
+    } else {
+        grade
+    }
+}
+
+/// Audits a response to `url`. `tls` is the probe's result for HTTPS URLs, None for
+/// plain HTTP.
+pub fn audit(
+    url: &str,
+    headers: &HashMap<String, String>,
+    cookies: &[String],
+    origin: Option<&str>,
+    tls: Option<Result<TlsDetails, String>>,
+) -> AuditReport {
+    let https = tls.is_some();
+    let mut checks = Vec::new();
+    match &tls {
+        Some(tls) => checks.extend(tls_checks(tls)),
+        None => checks.push(Check::problem(
+            "tls",
+            Level::Fail,
+            "Transport",
+            "plain HTTP, nothing is encrypted",
+            "Serve over HTTPS and redirect HTTP to it",
+        )),
+    }
+    checks.extend(header_checks(headers, https));
+    checks.extend(cookie_checks(cookies, https));
+    checks.extend(cors_checks(headers, origin));
+    AuditReport {
+        url: url.to_string(),
+        grade: grade(&checks),
+        checks,
+    }
+}
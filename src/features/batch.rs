@@ -0,0 +1,170 @@
+// `:send all` / `:send 1,3-4`: several tabs at once, each answer landing in a
+// compact status and latency summary as it comes in.
+use crate::app::{App, OutgoingRequest};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// One tab of the batch, as listed in the summary
+#[derive(Debug, Clone)]
+pub struct BatchRow {
+    /// Index into `App::tabs`
+    pub tab: usize,
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    /// None while the request is still out
+    pub result: Option<BatchResult>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResult {
+    /// None when no response came back
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    /// Body bytes as decoded
+    pub size: usize,
+    pub error: Option<String>,
+}
+
+impl BatchResult {
+    /// Also for tabs that can't go in a batch, so the summary says why
+    pub fn failed(error: impl Into<String>) -> BatchResult {
+        BatchResult {
+            status: None,
+            latency_ms: 0,
+            size: 0,
+            error: Some(error.into()),
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.status.is_some_and(|s| s < 400)
+    }
+}
+
+/// A request to send for the row at the same position
+#[derive(Debug, Clone)]
+pub struct BatchRequest {
+    pub row: usize,
+    pub request: OutgoingRequest,
+    pub timeout_ms: u64,
+    /// How the tab's own send would build its client
+    pub client: crate::net::http::ClientConfig,
+}
+
+#[derive(Debug)]
+pub enum BatchEvent {
+    /// The row's request came back (or didn't)
+    Done(usize, BatchResult),
+}
+
+/// Tab numbers as shown in the tab bar (`1,3-4`, or `all`/nothing for every tab)
+/// to indices, in order and without repeats
+pub fn parse_tabs(spec: &str, count: usize) -> Result<Vec<usize>, String> {
+    let spec = spec.trim();
+    if spec.is_empty() || spec.eq_ignore_ascii_case("all") {
+        return Ok((0..count).collect());
+    }
+    let number = |s: &str| -> Result<usize, String> {
+        match s.trim().parse::<usize>() {
+            Ok(n) if (1..=count).contains(&n) => Ok(n - 1),
+            Ok(n) => Err(format!("no tab {} (there are {})", n, count)),
+            Err(_) => Err(format!("'{}' isn't a tab number", s.trim())),
+        }
+    };
+    let mut tabs = Vec::new();
+    for part in spec.split(',').filter(|p| !p.trim().is_empty()) {
+        let (from, to) = match part.split_once('-') {
+            Some((from, to)) => (number(from)?, number(to)?),
+            None => {
+                let n = number(part)?;
+                (n, n)
+            }
+        };
+        if from > to {
+            return Err(format!("'{}' runs backwards", part.trim()));
+        }
+        for tab in from..=to {
+            if !tabs.contains(&tab) {
+                tabs.push(tab);
+            }
+        }
+    }
+    Ok(tabs)
+}
+
+/// `3 sent: 2 ok, 1 failed, slowest 420ms (Users)`
+pub fn summary(rows: &[BatchRow]) -> String {
+    let results: Vec<(&BatchRow, &BatchResult)> = rows
+        .iter()
+        .filter_map(|row| Some((row, row.result.as_ref()?)))
+        .collect();
+    let ok = results.iter().filter(|(_, r)| r.is_ok()).count();
+    let mut text = format!(
+        "{} sent: {} ok, {} failed",
+        results.len(),
+        ok,
+        results.len() - ok
+    );
+    if let Some((row, slowest)) = results
+        .iter()
+        .filter(|(_, r)| r.status.is_some())
+        .max_by_key(|(_, r)| r.latency_ms)
+    {
+        text.push_str(&format!(
+            ", slowest {}ms ({})",
+            slowest.latency_ms, row.name
+        ));
+    }
+    text
+}
+
+async fn send(client: &reqwest::Client, batch: &BatchRequest) -> BatchResult {
+    let start = Instant::now();
+    let mut request = match App::build_outgoing(client, &batch.request) {
+        Ok(request) => request,
+        Err(e) => return BatchResult::failed(e),
+    };
+    *request.timeout_mut() = Some(Duration::from_millis(batch.timeout_ms));
+    match client.execute(request).await {
+        Ok(response) => {
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+                .collect();
+            let raw = response
+                .bytes()
+                .await
+                .map(|b| b.to_vec())
+                .unwrap_or_default();
+            let (body, _) = crate::net::encoding::decode_body(&headers, raw);
+            BatchResult {
+                status: Some(status),
+                latency_ms: start.elapsed().as_millis() as u64,
+                size: body.len(),
+                error: None,
+            }
+        }
+        Err(e) => BatchResult {
+            latency_ms: start.elapsed().as_millis() as u64,
+            ..BatchResult::failed(e.to_string())
+        },
+    }
+}
+
+/// Sends every request at once and reports each as it finishes, each on a client
+/// built like its tab's send. Tabs with the same settings share one.
+pub async fn run_batch(requests: Vec<BatchRequest>, tx: mpsc::Sender<BatchEvent>) {
+    let mut clients = crate::net::pool::ClientCache::new(requests.len());
+    let sends = requests.into_iter().map(|batch| {
+        let (client, _) = clients.get_or_build(batch.client.clone(), || batch.client.build());
+        let tx = tx.clone();
+        async move {
+            let result = send(&client, &batch).await;
+            let _ = tx.send(BatchEvent::Done(batch.row, result)).await;
+        }
+    });
+    futures_util::future::join_all(sends).await;
+}
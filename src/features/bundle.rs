@@ -0,0 +1,450 @@
+// Workspace bundles: collections, environments and mock routes in one JSON file to hand
+// to a teammate. Secrets are left out, or sealed with a passphrase of the bundle's own.
+// Importing merges into the current workspace; clashes are settled one at a time.
+
+use crate::domain::environment::Environment;
+use crate::features::vault::{self, EncryptionSettings, Vault};
+use crate::net::mock_server::MockRoute;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const FORMAT_VERSION: u32 = 1;
+/// Where the palette action writes, in the current directory
+pub const DEFAULT_FILE: &str = "postdad-bundle.json";
+
+/// Variable names that hint at a secret; `enc:v1:` values always are one
+const SECRET_WORDS: &[&str] = &[
+    "token",
+    "secret",
+    "pass",
+    "key",
+    "auth",
+    "credential",
+    "cookie",
+    "session",
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    pub postdad_bundle: u32,
+    pub created_at: u64,
+    /// File name to content, as in `collections/`
+    #[serde(default)]
+    pub collections: BTreeMap<String, String>,
+    #[serde(default)]
+    pub environments: Vec<BundleEnv>,
+    #[serde(default)]
+    pub mocks: Vec<MockRoute>,
+    /// Salt and check value of the passphrase, when secrets are sealed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<EncryptionSettings>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BundleEnv {
+    pub name: String,
+    pub variables: BTreeMap<String, String>,
+    /// Secrets that were left out, for whoever imports it to fill in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded: Vec<String>,
+    /// Host overrides, as in the environment
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resolve: BTreeMap<String, String>,
+    /// Default headers, as in the environment; secret ones like variables
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Secret headers that were left out
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_headers: Vec<String>,
+}
+
+pub fn is_secret(key: &str, value: &str) -> bool {
+    let key = key.to_lowercase();
+    value.starts_with(vault::SECRET_PREFIX) || SECRET_WORDS.iter().any(|w| key.contains(w))
+}
+
+/// Copies `values` into `kept`, secrets sealed with `seal` or named in `excluded`
+fn pack<'a>(
+    values: impl IntoIterator<Item = (&'a String, &'a String)>,
+    seal: Option<&Vault>,
+    kept: &mut BTreeMap<String, String>,
+    excluded: &mut Vec<String>,
+) -> Result<(), String> {
+    for (key, value) in values {
+        if !is_secret(key, value) {
+            kept.insert(key.clone(), value.clone());
+            continue;
+        }
+        match seal {
+            Some(key_vault) if !value.starts_with(vault::SECRET_PREFIX) => {
+                kept.insert(key.clone(), key_vault.encrypt_secret(value)?);
+            }
+            _ => excluded.push(key.clone()),
+        }
+    }
+    excluded.sort();
+    Ok(())
+}
+
+/// Packs the workspace. With `seal` (a passphrase key from `vault::setup_passphrase`),
+/// secrets go in encrypted; without, they are left out. Values still encrypted with
+/// the workspace key (locked) can't be re-sealed and are always left out.
+pub fn build(
+    collections: BTreeMap<String, String>,
+    environments: &[Environment],
+    mocks: &[MockRoute],
+    seal: Option<(EncryptionSettings, Vault)>,
+) -> Result<Bundle, String> {
+    let mut envs = Vec::new();
+    // "None" is the built-in no-environment entry
+    for env in environments.iter().filter(|e| e.name != "None") {
+        let mut out = BundleEnv {
+            name: env.name.clone(),
+            resolve: env.resolve.clone(),
+            ..Default::default()
+        };
+        let key_vault = seal.as_ref().map(|(_, v)| v);
+        pack(
+            &env.variables,
+            key_vault,
+            &mut out.variables,
+            &mut out.excluded,
+        )?;
+        pack(
+            &env.headers,
+            key_vault,
+            &mut out.headers,
+            &mut out.excluded_headers,
+        )?;
+        envs.push(out);
+    }
+    Ok(Bundle {
+        postdad_bundle: FORMAT_VERSION,
+        created_at: crate::features::history::now(),
+        collections,
+        environments: envs,
+        mocks: mocks.to_vec(),
+        encryption: seal.map(|(settings, _)| settings),
+    })
+}
+
+pub fn parse(content: &str) -> Result<Bundle, String> {
+    let bundle: Bundle =
+        serde_json::from_str(content).map_err(|e| format!("Not a PostDad bundle: {}", e))?;
+    if bundle.postdad_bundle == 0 || bundle.postdad_bundle > FORMAT_VERSION {
+        return Err(format!(
+            "Bundle format {} is not supported, update PostDad",
+            bundle.postdad_bundle
+        ));
+    }
+    Ok(bundle)
+}
+
+/// Decrypts the sealed secrets in place
+pub fn unseal(bundle: &mut Bundle, passphrase: &str) -> Result<(), String> {
+    let Some(settings) = &bundle.encryption else {
+        return Ok(());
+    };
+    let key_vault = vault::unlock_with_passphrase(settings, passphrase)?;
+    for env in &mut bundle.environments {
+        for value in env.variables.values_mut().chain(env.headers.values_mut()) {
+            *value = key_vault.decrypt_secret(value)?;
+        }
+    }
+    bundle.encryption = None;
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Collection,
+    Environment,
+    Mock,
+}
+
+impl Kind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Kind::Collection => "Collection",
+            Kind::Environment => "Environment",
+            Kind::Mock => "Mock route",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resolution {
+    Overwrite,
+    /// Keep what's in the workspace
+    Skip,
+    /// Import under a new name; not for mock routes, which are matched by method and path
+    KeepBoth,
+}
+
+/// Something in the bundle that the workspace already has, with different content
+#[derive(Clone, Debug, PartialEq)]
+pub struct Collision {
+    pub kind: Kind,
+    /// File name, environment name, or `METHOD /path`
+    pub name: String,
+    pub resolution: Option<Resolution>,
+}
+
+fn route_key(route: &MockRoute) -> String {
+    format!("{} {}", route.method.to_uppercase(), route.path)
+}
+
+/// What the workspace has, as found on disk
+#[derive(Clone, Debug, Default)]
+pub struct Existing {
+    pub collections: BTreeMap<String, String>,
+    /// Without the "None" entry, secrets as written in the file
+    pub environments: Vec<Environment>,
+    pub mocks: Vec<MockRoute>,
+}
+
+pub fn collisions(bundle: &Bundle, existing: &Existing) -> Vec<Collision> {
+    let collision = |kind, name: &str| Collision {
+        kind,
+        name: name.to_string(),
+        resolution: None,
+    };
+    let mut found = Vec::new();
+    for (name, content) in &bundle.collections {
+        if existing.collections.get(name).is_some_and(|c| c != content) {
+            found.push(collision(Kind::Collection, name));
+        }
+    }
+    for env in &bundle.environments {
+        if let Some(mine) = existing.environments.iter().find(|e| e.name == env.name)
+            && !same_variables(mine, env)
+        {
+            found.push(collision(Kind::Environment, &env.name));
+        }
+    }
+    for route in &bundle.mocks {
+        let key = route_key(route);
+        if existing
+            .mocks
+            .iter()
+            .any(|r| route_key(r) == key && r != route)
+        {
+            found.push(collision(Kind::Mock, &key));
+        }
+    }
+    found
+}
+
+/// Left-out secrets don't count: importing would leave the value that's there
+fn same_variables(mine: &Environment, theirs: &BundleEnv) -> bool {
+    mine.resolve == theirs.resolve
+        && theirs
+            .headers
+            .iter()
+            .all(|(k, v)| mine.headers.get(k) == Some(v))
+        && mine
+            .headers
+            .keys()
+            .all(|k| theirs.headers.contains_key(k) || theirs.excluded_headers.contains(k))
+        && theirs
+            .variables
+            .iter()
+            .all(|(k, v)| mine.variables.get(k) == Some(v))
+        && mine
+            .variables
+            .keys()
+            .all(|k| theirs.variables.contains_key(k) || theirs.excluded.contains(k))
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    pub added: usize,
+    pub replaced: usize,
+    pub skipped: usize,
+    /// Left-out secrets, `env.name`, still to be filled in
+    pub missing_secrets: Vec<String>,
+}
+
+fn resolution(collisions: &[Collision], kind: Kind, name: &str) -> Option<Resolution> {
+    collisions
+        .iter()
+        .find(|c| c.kind == kind && c.name == name)
+        .map(|c| c.resolution.unwrap_or(Resolution::Skip))
+}
+
+/// `name_2`, `name_3`... the first one `taken` says is free
+fn free_name(stem: &str, suffix: &str, taken: impl Fn(&str) -> bool) -> String {
+    (2..)
+        .map(|n| format!("{}_{}{}", stem, n, suffix))
+        .find(|name| !taken(name))
+        .unwrap_or_default()
+}
+
+/// Merges the bundle into `existing` following the settled collisions (an unsettled
+/// one is skipped) and returns what changed. Environment secrets are re-sealed with
+/// `seal`, the workspace key, when encryption is on.
+pub fn merge(
+    bundle: &Bundle,
+    collisions: &[Collision],
+    existing: &mut Existing,
+    seal: Option<&Vault>,
+) -> Result<Summary, String> {
+    let mut summary = Summary::default();
+
+    for (name, content) in &bundle.collections {
+        let target = match existing.collections.get(name) {
+            None => {
+                summary.added += 1;
+                name.clone()
+            }
+            Some(mine) if mine == content => continue,
+            Some(_) => match resolution(collisions, Kind::Collection, name) {
+                Some(Resolution::Overwrite) => {
+                    summary.replaced += 1;
+                    name.clone()
+                }
+                Some(Resolution::KeepBoth) => {
+                    summary.added += 1;
+                    let stem = name.strip_suffix(".hcl").unwrap_or(name);
+                    free_name(stem, ".hcl", |n| existing.collections.contains_key(n))
+                }
+                _ => {
+                    summary.skipped += 1;
+                    continue;
+                }
+            },
+        };
+        existing.collections.insert(target, content.clone());
+    }
+
+    for env in &bundle.environments {
+        let mut variables = std::collections::HashMap::new();
+        for (key, value) in &env.variables {
+            let value = match seal {
+                Some(key_vault) if is_secret(key, value) => key_vault.encrypt_secret(value)?,
+                _ => value.clone(),
+            };
+            variables.insert(key.clone(), value);
+        }
+        let mut headers = env.headers.clone();
+        let mut replace = None;
+        let name = match existing
+            .environments
+            .iter()
+            .position(|e| e.name == env.name)
+        {
+            Some(i) if same_variables(&existing.environments[i], env) => continue,
+            None => {
+                summary.added += 1;
+                env.name.clone()
+            }
+            Some(i) => match resolution(collisions, Kind::Environment, &env.name) {
+                Some(Resolution::Overwrite) => {
+                    summary.replaced += 1;
+                    // Secrets left out of the bundle keep the value that was there
+                    for key in &env.excluded {
+                        if let Some(value) = existing.environments[i].variables.get(key) {
+                            variables.insert(key.clone(), value.clone());
+                        }
+                    }
+                    for key in &env.excluded_headers {
+                        if let Some(value) = existing.environments[i].headers.get(key) {
+                            headers.insert(key.clone(), value.clone());
+                        }
+                    }
+                    replace = Some(i);
+                    env.name.clone()
+                }
+                Some(Resolution::KeepBoth) => {
+                    summary.added += 1;
+                    free_name(&env.name, "", |n| {
+                        existing.environments.iter().any(|e| e.name == n)
+                    })
+                }
+                _ => {
+                    summary.skipped += 1;
+                    continue;
+                }
+            },
+        };
+        for key in &env.excluded {
+            if !variables.contains_key(key) {
+                variables.insert(key.clone(), String::new());
+                summary.missing_secrets.push(format!("{}.{}", name, key));
+            }
+        }
+        // An empty header would still be sent, so a left-out one is only named
+        for key in &env.excluded_headers {
+            if !headers.contains_key(key) {
+                summary
+                    .missing_secrets
+                    .push(format!("{}.headers.{}", name, key));
+            }
+        }
+        let merged = Environment {
+            name,
+            variables,
+            resolve: env.resolve.clone(),
+            headers,
+        };
+        match replace {
+            Some(i) => existing.environments[i] = merged,
+            None => existing.environments.push(merged),
+        }
+    }
+
+    for route in &bundle.mocks {
+        let key = route_key(route);
+        match existing.mocks.iter().position(|r| route_key(r) == key) {
+            None => {
+                summary.added += 1;
+                existing.mocks.push(route.clone());
+            }
+            Some(i) if existing.mocks[i] == *route => {}
+            Some(i) => match resolution(collisions, Kind::Mock, &key) {
+                Some(Resolution::Overwrite) => {
+                    summary.replaced += 1;
+                    existing.mocks[i] = route.clone();
+                }
+                _ => summary.skipped += 1,
+            },
+        }
+    }
+    Ok(summary)
+}
+
+/// environments.hcl content, one `env` block each
+pub fn environments_hcl(environments: &[Environment]) -> Result<String, String> {
+    let mut content = String::new();
+    for env in environments {
+        let variables: BTreeMap<&String, &String> = env.variables.iter().collect();
+        let mut body = hcl::to_string(&variables).map_err(|e| e.to_string())?;
+        for (name, table) in [("resolve", &env.resolve), ("headers", &env.headers)] {
+            if table.is_empty() {
+                continue;
+            }
+            body.push_str(&format!("{} = {{\n", name));
+            for (key, value) in table {
+                body.push_str(&format!("  {:?} = {:?}\n", key, value));
+            }
+            body.push_str("}\n");
+        }
+        content.push_str(&format!("env {:?} {{\n{}}}\n\n", env.name, body));
+    }
+    Ok(content)
+}
+
+/// Collisions waiting for an answer before the import is written
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportPrompt {
+    pub path: String,
+    pub bundle: Bundle,
+    pub collisions: Vec<Collision>,
+}
+
+impl ImportPrompt {
+    /// Index of the collision on screen
+    pub fn current(&self) -> Option<usize> {
+        self.collisions.iter().position(|c| c.resolution.is_none())
+    }
+}
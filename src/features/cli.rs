@@ -1,7 +1,14 @@
 // CLI mode for running requests without the TUI
 use crate::domain::collection::Collection;
 use crate::domain::environment::Environment;
+use crate::domain::workspace::{self, Workspace};
+use crate::features::response_dump;
+use crate::features::results_export::{self, Format};
+use crate::features::run_hooks::{self, RunHooks};
 use crate::features::runner::{self, RunResult, RunnerEvent};
+use crate::features::stress::{self, StressEvent, StressRequest, StressStats};
+use crate::features::vault;
+use crate::net::rate_limit::RateLimitSettings;
 use std::collections::HashMap;
 use std::path::Path;
 use tokio::sync::mpsc;
@@ -18,214 +25,2314 @@ mod colors {
     pub const MAGENTA: &str = "\x1b[35m";
 }
 
+/// The parts of the workspace's config.json the command line uses
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct WorkspaceConfig {
+    pub encryption: crate::features::vault::EncryptionSettings,
+    pub default_headers: std::collections::BTreeMap<String, String>,
+    pub rate_limit: RateLimitSettings,
+}
+
+impl WorkspaceConfig {
+    pub fn load(workspace: &Workspace) -> WorkspaceConfig {
+        std::fs::read_to_string(workspace.path("config.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// `flags` (`--rps`, `--host-rps`) over the workspace's host limits. The run pace
+    /// only comes from `--rps`: `:ratelimit run` is for runs started in the TUI.
+    pub fn limits(&self, flags: &RateLimitSettings) -> RateLimitSettings {
+        RateLimitSettings {
+            runner_rps: flags.runner_rps,
+            per_host_rps: flags.per_host_rps.or(self.rate_limit.per_host_rps),
+            hosts: self.rate_limit.hosts.clone(),
+        }
+        .sanitized()
+    }
+}
+
+/// `-e`, `--env-file` and `--var` of run and stress
+#[derive(Default)]
+pub struct EnvArgs {
+    /// An environment name, or an .hcl file (its first environment)
+    pub env: Option<String>,
+    /// Where to look `env` up instead of the workspace's environments.hcl
+    pub env_file: Option<String>,
+    /// `--var key=value`, applied over the environment
+    pub vars: Vec<(String, String)>,
+}
+
+impl EnvArgs {
+    /// Takes `flag value` if the flag is one of these
+    fn take(&mut self, flag: &str, value: &str) -> bool {
+        match flag {
+            "-e" | "--env" => self.env = Some(value.to_string()),
+            "--env-file" => self.env_file = Some(value.to_string()),
+            "--var" => match value.split_once('=') {
+                Some((key, value)) => self.vars.push((key.trim().to_string(), value.to_string())),
+                None => {
+                    eprintln!("--var expects key=value, got '{}'", value);
+                    std::process::exit(1);
+                }
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /// The environment chosen, None without `-e` or `--env-file`
+    fn environment(&self, workspace: &Workspace) -> Result<Option<Environment>, String> {
+        match (&self.env, &self.env_file) {
+            (None, None) => Ok(None),
+            // `-e staging.hcl`, as before names were supported
+            (Some(path), None) if Path::new(path).is_file() => first_environment(path),
+            (None, Some(file)) => first_environment(file),
+            (Some(name), file) => {
+                let path = file
+                    .clone()
+                    .unwrap_or_else(|| workspace.path("environments.hcl"));
+                let envs = read_environments(&path)?;
+                let names: Vec<String> = envs.iter().map(|e| e.name.clone()).collect();
+                envs.into_iter()
+                    .find(|e| e.name.eq_ignore_ascii_case(name))
+                    .map(Some)
+                    .ok_or_else(|| {
+                        format!(
+                            "No environment named '{}' in {} (there is: {})",
+                            name,
+                            path,
+                            names.join(", ")
+                        )
+                    })
+            }
+        }
+    }
+
+    /// The workspace's `default_headers` with the environment's `headers` over them
+    pub fn default_headers(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<std::collections::BTreeMap<String, String>, String> {
+        let env = self
+            .environment(workspace)?
+            .map(|e| e.headers)
+            .unwrap_or_default();
+        Ok(crate::domain::collection::merge_default_headers(
+            &WorkspaceConfig::load(workspace).default_headers,
+            &env,
+        ))
+    }
+
+    /// The environment's pinned hosts (its `resolve` table)
+    pub fn host_overrides(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<crate::net::pool::HostOverrides, String> {
+        match self.environment(workspace)? {
+            Some(env) => crate::net::pool::parse_overrides(&env.resolve),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// The variables the run uses
+    pub fn resolve(&self, workspace: &Workspace) -> Result<HashMap<String, String>, String> {
+        let mut vars = self
+            .environment(workspace)?
+            .map(|e| e.variables)
+            .unwrap_or_default();
+        decrypt_secrets(
+            &mut vars,
+            &WorkspaceConfig::load(workspace).encryption,
+            &workspace.keyring_account(),
+            std::env::var(vault::PASSPHRASE_ENV).ok(),
+        )?;
+        vars.extend(self.vars.iter().cloned());
+        Ok(vars)
+    }
+}
+
+/// Decrypts `enc:v1:` values the way the TUI unlocks: from the keyring, or with
+/// `passphrase` (`POSTDAD_PASSPHRASE`) for passphrase keys. Nothing is sent encrypted.
+pub fn decrypt_secrets(
+    vars: &mut HashMap<String, String>,
+    encryption: &vault::EncryptionSettings,
+    keyring_account: &str,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    if !vars.values().any(|v| v.starts_with(vault::SECRET_PREFIX)) {
+        return Ok(());
+    }
+    if !encryption.enabled {
+        return Err(
+            "The environment has encrypted values, but encryption is off in this workspace"
+                .to_string(),
+        );
+    }
+    let unlocked = match encryption.key_source {
+        vault::KeySource::Keyring => vault::unlock_with_keyring(encryption, keyring_account),
+        vault::KeySource::Passphrase => match passphrase {
+            Some(passphrase) => vault::unlock_with_passphrase(encryption, &passphrase),
+            None => Err(format!(
+                "set {} to decrypt the environment's secrets",
+                vault::PASSPHRASE_ENV
+            )),
+        },
+    };
+    let vault = unlocked.map_err(|e| format!("Could not unlock environment secrets: {}", e))?;
+    for (key, value) in vars.iter_mut() {
+        if value.starts_with(vault::SECRET_PREFIX) {
+            *value = vault
+                .decrypt_secret(value)
+                .map_err(|e| format!("Could not decrypt '{}': {}", key, e))?;
+        }
+    }
+    Ok(())
+}
+
 /// CLI arguments for run command
 pub struct RunArgs {
     pub collection_path: String,
-    pub env_path: Option<String>,
+    pub env: EnvArgs,
     pub verbose: bool,
     pub json_output: bool,
+    pub hooks: RunHooks,
+    /// Also write the results here, CSV or JSON by extension
+    pub export: Option<String>,
+    /// Also write every response's body and headers under this directory
+    pub output_dir: Option<String>,
+    /// Only the requests with one of these tags, all of them when empty
+    pub tags: Vec<String>,
+    /// `--rps` and `--host-rps`
+    pub rate_limit: RateLimitSettings,
+}
+
+/// CLI arguments for the stress command
+pub struct StressArgs {
+    /// A collection file, or a URL to hit on its own
+    pub target: String,
+    pub method: String,
+    pub env: EnvArgs,
+    pub vus: u32,
+    pub duration_secs: u64,
+    pub stages: String,
+    pub thresholds: Vec<stress::Threshold>,
+    pub json_output: bool,
+    /// `--host-rps`
+    pub rate_limit: RateLimitSettings,
+}
+
+/// CLI arguments for the req command
+pub struct ReqArgs {
+    pub method: Option<String>,
+    /// A URL, or `<collection>/<request>` for a saved one
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+    /// `-d` as given: the body itself, `@file`, or `@-` for stdin
+    pub data: Option<String>,
+    pub env: Option<String>,
+    /// `user:pass` for basic auth
+    pub user: Option<String>,
+    pub bearer: Option<String>,
+    pub include: bool,
+    pub json_output: bool,
+    /// Exit non-zero on 4xx/5xx, like `curl --fail`
+    pub fail: bool,
+    /// Go ahead when the workspace profile asks for confirmation
+    pub yes: bool,
+}
+
+/// CLI arguments for the sync and storage commands
+pub struct SyncArgs {
+    pub target: crate::features::gist_sync::Target,
+    /// push, pull, status, or empty for both ways
+    pub command: String,
+    /// Settles every conflict with `local` or `remote` instead of failing
+    pub prefer: Option<String>,
+}
+
+/// CLI arguments for the mock command
+pub struct MockArgs {
+    pub port: u16,
+    /// The routes file, the workspace's mocks.hcl when not given
+    pub routes: Option<String>,
+    /// 127.0.0.1 when not given; 0.0.0.0 to be reachable from other containers
+    pub host: Option<std::net::IpAddr>,
+    /// One JSON object per request instead of a log line
+    pub json_output: bool,
+}
+
+/// CLI arguments for the monitor command
+pub struct MonitorArgs {
+    pub collection_path: String,
+    pub env: EnvArgs,
+    pub settings: crate::features::monitor::MonitorSettings,
+    /// POSTed when a request goes down or comes back, and on a breach
+    pub webhook: Option<String>,
+    pub tags: Vec<String>,
+    /// JSON events, one a line, instead of the status line
+    pub json_output: bool,
+    /// `--rps` and `--host-rps`
+    pub rate_limit: RateLimitSettings,
+}
+
+/// `--workspace <dir | name>` (or `-w`) can go anywhere; the rest is parsed without it
+fn split_workspace_arg() -> (Vec<String>, Option<String>) {
+    let mut args = Vec::new();
+    let mut workspace = None;
+    let mut iter = std::env::args();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--workspace" | "-w" => match iter.next() {
+                Some(value) => workspace = Some(value),
+                None => {
+                    eprintln!("Usage: PostDad --workspace <dir | name> ...");
+                    std::process::exit(1);
+                }
+            },
+            _ => args.push(arg),
+        }
+    }
+    (args, workspace)
+}
+
+/// The workspace chosen with `--workspace`, the default one without it. With `migrate`
+/// (the TUI and `run`) the first run of the default workspace also copies an older
+/// setup over and brings back a note about what it migrated.
+pub fn workspace_from_args(migrate: bool) -> (Workspace, Option<String>) {
+    let workspace = match split_workspace_arg().1 {
+        Some(arg) => match workspace::resolve(&arg) {
+            Ok(workspace) => workspace,
+            Err(e) => {
+                eprintln!("Workspace error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => workspace::default_workspace(),
+    };
+    let migrated = migrate
+        .then(|| workspace::migrate_on_first_run(&workspace))
+        .flatten();
+    let note = match migrated {
+        Some(Ok(copied)) if !copied.is_empty() => Some(format!(
+            "Copied {} file(s) from this directory to {} (the originals are untouched)",
+            copied.len(),
+            workspace.dir().display()
+        )),
+        Some(Err(e)) => Some(format!("Migration stopped: {}", e)),
+        _ => None,
+    };
+    if let Err(e) = workspace.create() {
+        eprintln!("Could not create workspace {}: {}", workspace.label(), e);
+        std::process::exit(1);
+    }
+    (workspace, note)
 }
 
 /// Parse CLI arguments and return the action to take
 pub fn parse_args() -> Option<CliAction> {
-    let args: Vec<String> = std::env::args().collect();
+    let args = split_workspace_arg().0;
 
     if args.len() < 2 {
         return None; // No args, launch TUI
     }
 
-    match args[1].as_str() {
-        "--import" => {
-            if args.len() >= 3 {
-                Some(CliAction::Import(args[2].clone()))
-            } else {
-                eprintln!("Usage: PostDad --import <postman_collection.json>");
-                std::process::exit(1);
+    match args[1].as_str() {
+        "--import" | "import" => {
+            let usage =
+                "Usage: PostDad import <file>... [--format postman|openapi|insomnia|thunder|curl]";
+            let mut files = Vec::new();
+            let mut format = None;
+            let mut i = 2;
+            while i < args.len() {
+                match args[i].as_str() {
+                    "-f" | "--format" if i + 1 < args.len() => {
+                        match crate::features::import::Format::parse(&args[i + 1]) {
+                            Some(f) => format = Some(f),
+                            None => {
+                                eprintln!("Unknown format '{}'\n{}", args[i + 1], usage);
+                                std::process::exit(1);
+                            }
+                        }
+                        i += 1;
+                    }
+                    file => files.push(file.to_string()),
+                }
+                i += 1;
+            }
+            if files.is_empty() {
+                eprintln!("{}", usage);
+                std::process::exit(1);
+            }
+            Some(CliAction::Import { files, format })
+        }
+        "--import-curl" => {
+            if args.len() >= 3 {
+                Some(CliAction::ImportCurl {
+                    source: args[2].clone(),
+                    name: args.get(3).cloned(),
+                })
+            } else {
+                eprintln!("Usage: PostDad --import-curl <file | -> [collection_name]");
+                std::process::exit(1);
+            }
+        }
+        "run" => {
+            if args.len() < 3 {
+                eprintln!(
+                    "Usage: PostDad run <collection.hcl> [-e <name | env.hcl>] [--env-file <file.hcl>] [--var key=value]... [-v] [--json] [--webhook <url>] [--on-finish <cmd>] [--hook-threshold <n>] [--export <file.csv|json>] [--output-dir <dir>] [--tags <a,b>]"
+                );
+                std::process::exit(1);
+            }
+
+            let collection_path = args[2].clone();
+            let mut env = EnvArgs::default();
+            let mut verbose = false;
+            let mut json_output = false;
+            let mut hooks = RunHooks::default();
+            let mut export = None;
+            let mut output_dir = None;
+            let mut tags = Vec::new();
+            let mut rate_limit = RateLimitSettings::default();
+
+            let mut i = 3;
+            while i < args.len() {
+                match args[i].as_str() {
+                    flag if i + 1 < args.len() && env.take(flag, &args[i + 1]) => i += 1,
+                    "-v" | "--verbose" => verbose = true,
+                    "--json" => json_output = true,
+                    "--webhook" if i + 1 < args.len() => {
+                        hooks.webhook = Some(args[i + 1].clone());
+                        i += 1;
+                    }
+                    "--export" if i + 1 < args.len() => {
+                        export = Some(args[i + 1].clone());
+                        i += 1;
+                    }
+                    "--output-dir" if i + 1 < args.len() => {
+                        output_dir = Some(args[i + 1].clone());
+                        i += 1;
+                    }
+                    "--tags" if i + 1 < args.len() => {
+                        tags = crate::domain::collection::parse_tags(&args[i + 1]);
+                        i += 1;
+                    }
+                    "--on-finish" if i + 1 < args.len() => {
+                        hooks.command = Some(args[i + 1].clone());
+                        i += 1;
+                    }
+                    flag @ ("--rps" | "--host-rps") if i + 1 < args.len() => {
+                        let rps = parse_rps(flag, &args[i + 1]);
+                        if flag == "--rps" {
+                            rate_limit.runner_rps = Some(rps);
+                        } else {
+                            rate_limit.per_host_rps = Some(rps);
+                        }
+                        i += 1;
+                    }
+                    "--hook-threshold" if i + 1 < args.len() => {
+                        match args[i + 1].parse() {
+                            Ok(n) => hooks.failure_threshold = Some(n),
+                            Err(_) => {
+                                eprintln!("--hook-threshold expects a number");
+                                std::process::exit(1);
+                            }
+                        }
+                        i += 1;
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+
+            Some(CliAction::Run(RunArgs {
+                collection_path,
+                env,
+                verbose,
+                json_output,
+                hooks,
+                export,
+                output_dir,
+                tags,
+                rate_limit,
+            }))
+        }
+        "stress" => Some(CliAction::Stress(parse_stress_args(&args))),
+        "mock" => Some(CliAction::Mock(parse_mock_args(&args))),
+        "monitor" => Some(CliAction::Monitor(parse_monitor_args(&args))),
+        "req" => Some(CliAction::Request(parse_req_args(&args))),
+        "sync" | "storage" => {
+            let mut sync_args = SyncArgs {
+                target: if args[1] == "storage" {
+                    crate::features::gist_sync::Target::Storage
+                } else {
+                    crate::features::gist_sync::Target::Gist
+                },
+                command: String::new(),
+                prefer: None,
+            };
+            let mut i = 2;
+            while i < args.len() {
+                match args[i].as_str() {
+                    "--prefer" if i + 1 < args.len() => {
+                        sync_args.prefer = Some(args[i + 1].clone());
+                        i += 1;
+                    }
+                    word => sync_args.command = word.to_string(),
+                }
+                i += 1;
+            }
+            Some(CliAction::Sync(sync_args))
+        }
+        "doctor" => Some(CliAction::Doctor),
+        "history" => {
+            let mut json_output = false;
+            let mut export = None;
+            let mut i = 2;
+            while i < args.len() {
+                match args[i].as_str() {
+                    "--json" => json_output = true,
+                    "--export" if i + 1 < args.len() => {
+                        export = Some(args[i + 1].clone());
+                        i += 1;
+                    }
+                    other => {
+                        eprintln!(
+                            "Unknown option '{}'\nUsage: PostDad history [--json] [--export <file.csv|json>]",
+                            other
+                        );
+                        std::process::exit(2);
+                    }
+                }
+                i += 1;
+            }
+            Some(CliAction::History {
+                json_output,
+                export,
+            })
+        }
+        "diff" => match (args.get(2), args.get(3)) {
+            (Some(old), new) => Some(CliAction::Diff {
+                old: old.clone(),
+                new: new.cloned(),
+            }),
+            (None, _) => {
+                eprintln!("Usage: PostDad diff <old> [new]   (e.g. PostDad diff HEAD~1:users)");
+                std::process::exit(2);
+            }
+        },
+        "--help" | "-h" => {
+            print_help();
+            std::process::exit(0);
+        }
+        "--version" | "-V" => {
+            println!("PostDad {}", env!("CARGO_PKG_VERSION"));
+            std::process::exit(0);
+        }
+        _ => None, // Unknown arg, launch TUI
+    }
+}
+
+fn parse_stress_args(args: &[String]) -> StressArgs {
+    let usage = "Usage: PostDad stress <collection.hcl | url> [-X method] [-e <name | env.hcl>] [--env-file <file.hcl>] [--var key=value]... [--vus n] [--duration secs] [--stages spec] [--threshold p95=300]... [--json]";
+    let fail = |msg: String| -> ! {
+        eprintln!("{}", msg);
+        std::process::exit(1);
+    };
+    if args.len() < 3 {
+        fail(usage.to_string());
+    }
+    let mut stress_args = StressArgs {
+        target: args[2].clone(),
+        method: "GET".to_string(),
+        env: EnvArgs::default(),
+        vus: 10,
+        duration_secs: 10,
+        stages: String::new(),
+        thresholds: Vec::new(),
+        json_output: false,
+        rate_limit: RateLimitSettings::default(),
+    };
+    let mut i = 3;
+    while i < args.len() {
+        let value = args.get(i + 1);
+        match (args[i].as_str(), value) {
+            ("--json", _) => {
+                stress_args.json_output = true;
+                i += 1;
+                continue;
+            }
+            ("-X" | "--method", Some(v)) => stress_args.method = v.to_uppercase(),
+            (flag, Some(v)) if stress_args.env.take(flag, v) => {}
+            ("--vus", Some(v)) => {
+                stress_args.vus = v
+                    .parse()
+                    .unwrap_or_else(|_| fail("--vus expects a number".to_string()))
+            }
+            ("--duration", Some(v)) => {
+                stress_args.duration_secs = stress::parse_duration(v).unwrap_or_else(|e| fail(e))
+            }
+            ("--stages", Some(v)) => stress_args.stages = v.clone(),
+            ("--host-rps", Some(v)) => {
+                stress_args.rate_limit.per_host_rps = Some(parse_rps("--host-rps", v))
+            }
+            ("--threshold", Some(v)) => stress_args
+                .thresholds
+                .extend(stress::parse_thresholds(v).unwrap_or_else(|e| fail(e))),
+            _ => fail(usage.to_string()),
+        }
+        i += 2;
+    }
+    stress_args
+}
+
+fn parse_mock_args(args: &[String]) -> MockArgs {
+    let usage = "Usage: PostDad mock [--port <n>] [--routes <mocks.hcl>] [--host <ip>] [--json]";
+    let fail = |msg: &str| -> ! {
+        eprintln!("{}", msg);
+        std::process::exit(1);
+    };
+    let mut mock_args = MockArgs {
+        port: 3000,
+        routes: None,
+        host: None,
+        json_output: false,
+    };
+    let mut i = 2;
+    while i < args.len() {
+        let value = args.get(i + 1);
+        match (args[i].as_str(), value) {
+            ("--json", _) => {
+                mock_args.json_output = true;
+                i += 1;
+                continue;
+            }
+            ("-p" | "--port", Some(v)) => {
+                mock_args.port = v
+                    .parse()
+                    .unwrap_or_else(|_| fail("--port expects a port number"))
+            }
+            ("-r" | "--routes", Some(v)) => mock_args.routes = Some(v.clone()),
+            ("--host", Some(v)) => {
+                mock_args.host = Some(
+                    v.parse()
+                        .unwrap_or_else(|_| fail("--host expects an IP address, e.g. 0.0.0.0")),
+                )
+            }
+            _ => fail(usage),
+        }
+        i += 2;
+    }
+    mock_args
+}
+
+fn parse_monitor_args(args: &[String]) -> MonitorArgs {
+    let usage = "Usage: PostDad monitor <collection.hcl> [--interval secs] [--webhook <url>] [--max-failures n] [--max-error-rate pct] [--rounds n] [-e <name | env.hcl>] [--env-file <file.hcl>] [--var key=value]... [--tags <a,b>] [--rps n] [--host-rps n] [--json]";
+    let fail = |msg: String| -> ! {
+        eprintln!("{}", msg);
+        std::process::exit(1);
+    };
+    if args.len() < 3 || args[2].starts_with('-') {
+        fail(usage.to_string());
+    }
+    let mut monitor_args = MonitorArgs {
+        collection_path: args[2].clone(),
+        env: EnvArgs::default(),
+        settings: Default::default(),
+        webhook: None,
+        tags: Vec::new(),
+        json_output: false,
+        rate_limit: RateLimitSettings::default(),
+    };
+    let settings = &mut monitor_args.settings;
+    let mut i = 3;
+    while i < args.len() {
+        let value = args.get(i + 1);
+        match (args[i].as_str(), value) {
+            ("--json", _) => {
+                monitor_args.json_output = true;
+                i += 1;
+                continue;
+            }
+            (flag, Some(v)) if monitor_args.env.take(flag, v) => {}
+            ("--interval", Some(v)) => {
+                settings.interval_secs = stress::parse_duration(v)
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .unwrap_or_else(|| fail("--interval expects e.g. 30, 30s or 5m".to_string()))
+            }
+            ("--webhook", Some(v)) => monitor_args.webhook = Some(v.clone()),
+            ("--max-failures", Some(v)) => {
+                settings.max_failures = v
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .unwrap_or_else(|| fail("--max-failures expects a number".to_string()))
+            }
+            ("--max-error-rate", Some(v)) => {
+                settings.max_error_rate = Some(
+                    v.trim_end_matches('%')
+                        .parse()
+                        .ok()
+                        .filter(|pct: &f64| (0.0..=100.0).contains(pct))
+                        .unwrap_or_else(|| {
+                            fail("--max-error-rate expects a percentage".to_string())
+                        }),
+                )
+            }
+            ("--rounds", Some(v)) => {
+                settings.rounds = Some(
+                    v.parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .unwrap_or_else(|| fail("--rounds expects a number".to_string())),
+                )
+            }
+            ("--tags", Some(v)) => monitor_args.tags = crate::domain::collection::parse_tags(v),
+            ("--rps", Some(v)) => monitor_args.rate_limit.runner_rps = Some(parse_rps("--rps", v)),
+            ("--host-rps", Some(v)) => {
+                monitor_args.rate_limit.per_host_rps = Some(parse_rps("--host-rps", v))
+            }
+            _ => fail(usage.to_string()),
+        }
+        i += 2;
+    }
+    monitor_args
+}
+
+/// A number of requests a second, at least `rate_limit::MIN_RPS`, or exit
+fn parse_rps(flag: &str, value: &str) -> f64 {
+    match value.parse::<f64>() {
+        Ok(rps) if crate::net::rate_limit::is_rate(rps) => rps,
+        _ => {
+            eprintln!(
+                "{} expects a number of requests a second, at least {}",
+                flag,
+                crate::net::rate_limit::MIN_RPS
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_req_args(args: &[String]) -> ReqArgs {
+    let usage = "Usage: PostDad req [METHOD] <url | collection/request> [-H 'Name: value']... [-d data | -d @file] [-e env] [-u user:pass] [--bearer token] [-i] [--json] [-f] [-y]";
+    let fail = |msg: &str| -> ! {
+        eprintln!("{}", msg);
+        std::process::exit(1);
+    };
+    let mut req_args = ReqArgs {
+        method: None,
+        target: String::new(),
+        headers: Vec::new(),
+        data: None,
+        env: None,
+        user: None,
+        bearer: None,
+        include: false,
+        json_output: false,
+        fail: false,
+        yes: false,
+    };
+    let mut i = 2;
+    while i < args.len() {
+        let value = args.get(i + 1).cloned();
+        let mut takes_value = true;
+        match (args[i].as_str(), value) {
+            ("-i" | "--include", _) => (req_args.include, takes_value) = (true, false),
+            ("--json", _) => (req_args.json_output, takes_value) = (true, false),
+            ("-f" | "--fail", _) => (req_args.fail, takes_value) = (true, false),
+            ("-y" | "--yes", _) => (req_args.yes, takes_value) = (true, false),
+            ("-H" | "--header", Some(v)) => match v.split_once(':') {
+                Some((name, value)) => req_args
+                    .headers
+                    .push((name.trim().to_string(), value.trim().to_string())),
+                None => fail(&format!("Header '{}' should look like 'Name: value'", v)),
+            },
+            ("-d" | "--data", Some(v)) => req_args.data = Some(v),
+            ("-e" | "--env", Some(v)) => req_args.env = Some(v),
+            ("-u" | "--user", Some(v)) => req_args.user = Some(v),
+            ("--bearer", Some(v)) => req_args.bearer = Some(v),
+            (arg, _) if !arg.starts_with('-') => {
+                takes_value = false;
+                let upper = arg.to_uppercase();
+                if req_args.method.is_none()
+                    && req_args.target.is_empty()
+                    && crate::features::command::METHODS.contains(&upper.as_str())
+                {
+                    req_args.method = Some(upper);
+                } else if req_args.target.is_empty() {
+                    req_args.target = arg.to_string();
+                } else {
+                    fail(usage);
+                }
+            }
+            _ => fail(usage),
+        }
+        i += if takes_value { 2 } else { 1 };
+    }
+    if req_args.target.is_empty() {
+        fail(usage);
+    }
+    req_args
+}
+
+pub enum CliAction {
+    /// Postman, OpenAPI, Insomnia, Thunder Client or curl files into the workspace
+    Import {
+        files: Vec<String>,
+        format: Option<crate::features::import::Format>,
+    },
+    /// Many curl commands (a file, shell history, or `-` for stdin) into one collection
+    ImportCurl {
+        source: String,
+        name: Option<String>,
+    },
+    Run(RunArgs),
+    /// One request, curl-style, printed to stdout
+    Request(ReqArgs),
+    /// Load test from the command line, failing on breached thresholds
+    Stress(StressArgs),
+    /// The mock server on its own, logging requests until Ctrl+C or SIGTERM
+    Mock(MockArgs),
+    /// A collection checked on an interval, exiting 1 when a threshold is crossed
+    Monitor(MonitorArgs),
+    /// Gist sync of the workspace's collections, or storage sync of the whole workspace
+    Sync(SyncArgs),
+    /// Environment checks with suggested fixes
+    Doctor,
+    /// Requests added, removed and changed between two versions of a collection
+    Diff {
+        old: String,
+        new: Option<String>,
+    },
+    /// The request history as CSV or JSON, to stdout or a file
+    History {
+        json_output: bool,
+        export: Option<String>,
+    },
+}
+
+fn print_help() {
+    println!(
+        r#"{}PostDad{} - A fast API client for your terminal
+
+{}USAGE:{}
+    PostDad                              Launch the TUI
+    PostDad run <collection.hcl>         Run a collection
+    PostDad stress <collection.hcl|url>  Load test a collection or a single URL
+    PostDad req [METHOD] <url|coll/req>  Send one request and print the response
+    PostDad mock [--port <n>]            Serve the mock routes without the TUI,
+                                         logging each request
+    PostDad monitor <collection.hcl>     Check a collection every interval, like
+                                         sentinel; exits 1 past a threshold
+    PostDad sync [push|pull|status]      Sync collections with a GitHub gist
+    PostDad storage [push|pull|status]   Sync the workspace with the WebDAV, S3
+                                         or folder storage in config.json
+    PostDad doctor                       Check the terminal, tools, workspace
+                                         files and proxy, and suggest fixes
+    PostDad diff <old> [new]             Compare two versions of a collection:
+                                         .hcl files, names, or <git rev>:<either>
+                                         (new defaults to the working copy)
+    PostDad history [--json]             Print the request history as CSV (or
+                                         JSON): status, latency, size, tests
+    PostDad import <file>... [--format <f>]
+                                         Import Postman, OpenAPI, Insomnia v4 or
+                                         Thunder Client exports (collections and
+                                         environments); the format is detected
+                                         unless given (postman, openapi,
+                                         insomnia, thunder, curl)
+    PostDad --import-curl <file|-> [name]
+                                         Import every curl command in a file or
+                                         shell history (- reads stdin)
+
+{}OPTIONS:{}
+    -e, --env <name|file>   Environment to use: a name from the workspace's
+                            environments.hcl, or a file (its first one)
+    --env-file <file.hcl>   Look the -e name up in this file instead
+    --var <key=value>       Set a variable over the environment (repeatable)
+    -v, --verbose           Show request/response details
+    --json                  Output results as JSON
+    --webhook <url>         POST a JSON summary when the run finishes
+    --on-finish <cmd>       Run a shell command when the run finishes
+                            (POSTDAD_PASSED, POSTDAD_FAILED, ... are set)
+    --hook-threshold <n>    Only fire hooks when at least n requests failed
+    --export <file>         Write the results to a .csv or .json file too
+                            (also for PostDad history)
+    --output-dir <dir>      Save every response's body and headers, a
+                            folder per request
+    --tags <a,b>            Only run requests tagged with one of these
+    --rps <n>               Send at most n requests a second
+    --host-rps <n>          At most n requests a second to any one host
+                            (also for PostDad stress and monitor; the
+                            workspace's per-host limits apply too)
+
+{}STRESS OPTIONS:{}
+    --vus <n>               Virtual users (default 10)
+    --duration <time>       How long to run, e.g. 30, 30s, 2m (default 10s)
+    --stages <spec>         ramp, step, or e.g. "30s:20, 1m:20, 10s:0"
+    --threshold <cond>      Fail (exit 1) unless e.g. p95=300, error_rate<1%,
+                            rps>=100 holds; repeat or comma-separate
+    -X, --method <method>   Method when the target is a URL (default GET)
+
+{}REQ OPTIONS:{}
+    -H, --header <h>        Add a header, 'Name: value' (repeatable)
+    -d, --data <body>       Request body; @file reads a file, @- stdin
+                            (the method defaults to POST then)
+    -e, --env <name>        Environment from the workspace to use
+    -u, --user <user:pass>  Basic auth
+    --bearer <token>        Bearer token
+    -i, --include           Print the status line and headers too
+    --json                  Print status, headers, timing and body as JSON
+    -f, --fail              Exit with 22 on a 4xx/5xx response
+    -y, --yes               Send past pre-send warnings and the profile's
+                            confirmation
+
+{}MOCK OPTIONS:{}
+    -p, --port <n>          Port to listen on (default 3000)
+    -r, --routes <file>     Routes file (default: the workspace's mocks.hcl);
+                            its cors and tls blocks apply too
+    --host <ip>             Address to listen on (default 127.0.0.1; use
+                            0.0.0.0 in a container)
+    --json                  Log each request as a JSON line
+
+{}MONITOR OPTIONS:{}
+    --interval <time>       Time between rounds, e.g. 30, 30s, 5m (default 30s)
+    --webhook <url>         POST when a request goes down or comes back, and
+                            when a threshold is crossed
+    --max-failures <n>      Exit 1 once a request failed n rounds in a row
+                            (default 3)
+    --max-error-rate <pct>  Exit 1 once more than pct% of the checks in the
+                            last 10 rounds failed
+    --rounds <n>            Stop after n rounds (exit 0 if nothing crossed)
+    --json                  JSON events (check, round, down, up, breach)
+                            instead of the status line
+    -e, --env, --env-file, --var, --tags, --rps and --host-rps work as
+                            for run
+
+{}SYNC OPTIONS:{}
+    --prefer <local|remote> Settle conflicts this way instead of failing
+                            (token from $GITHUB_TOKEN, see gist in config.json;
+                            the same goes for PostDad storage)
+    -w, --workspace <dir>   Read and save everything under <dir>; a bare
+                            name is a workspace in ~/.local/share/postdad
+                            (default: ~/.local/share/postdad, settings in
+                            ~/.config/postdad, or $POSTDAD_HOME)
+    -h, --help              Show this help
+    -V, --version           Show version
+
+{}EXAMPLES:{}
+    PostDad run api_tests.hcl
+    PostDad run api_tests.hcl -e production.hcl
+    PostDad run api_tests.hcl -e staging --var base_url=http://localhost:8080 --var token=$TOKEN
+    PostDad run api_tests.hcl --json > results.json
+    PostDad run api_tests.hcl --export nightly.csv
+    PostDad run api_tests.hcl --output-dir responses/
+    PostDad run api_tests.hcl --tags smoke,auth
+    PostDad history --export history.json
+    PostDad --workspace shop
+    PostDad run api_tests.hcl --webhook https://hooks.slack.com/services/... --hook-threshold 1
+    PostDad stress api_tests.hcl -e staging.hcl --stages ramp --threshold p95=300 --threshold error_rate=1
+    PostDad req POST {{base_url}}/users -H 'Content-Type: application/json' -d @user.json -e staging
+    PostDad req shop/List\ orders --json | jq .status
+    PostDad mock --port 4000 --routes mocks.hcl --host 0.0.0.0
+    PostDad monitor health.hcl -e prod --interval 30 --webhook https://hooks.slack.com/services/...
+    PostDad monitor health.hcl --rounds 1 --max-failures 1 --json
+    PostDad import Insomnia_2024-05-01.json
+    PostDad import thunder-collection_shop.json thunder-environment_dev.json
+    PostDad sync pull --prefer remote
+    PostDad storage push --prefer local
+    PostDad diff HEAD~1:users
+    PostDad diff old/shop.hcl collections/shop.hcl
+"#,
+        colors::BOLD,
+        colors::RESET,
+        colors::BOLD,
+        colors::RESET,
+        colors::BOLD,
+        colors::RESET,
+        colors::BOLD,
+        colors::RESET,
+        colors::BOLD,
+        colors::RESET,
+        colors::BOLD,
+        colors::RESET,
+        colors::BOLD,
+        colors::RESET,
+        colors::BOLD,
+        colors::RESET,
+        colors::BOLD,
+        colors::RESET,
+    );
+}
+
+/// Serves the mock routes until Ctrl+C or SIGTERM, then lets the requests in
+/// flight finish
+pub async fn run_mock_cli(args: MockArgs, workspace: &Workspace) -> i32 {
+    serve_mock_cli(args, workspace, shutdown_signal()).await
+}
+
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        if let Ok(mut term) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = term.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// `run_mock_cli` stopping when `shutdown` completes
+pub async fn serve_mock_cli(
+    args: MockArgs,
+    workspace: &Workspace,
+    shutdown: impl std::future::Future<Output = ()>,
+) -> i32 {
+    use crate::net::mock_server;
+
+    let fail = |msg: String| {
+        eprintln!("{}Error:{} {}", colors::RED, colors::RESET, msg);
+        1
+    };
+    let path = args
+        .routes
+        .clone()
+        .unwrap_or_else(|| workspace.path(mock_server::MOCKS_FILE));
+    if args.routes.is_some() && !Path::new(&path).exists() {
+        return fail(format!("No routes file at {}", path));
+    }
+    let routes = match mock_server::load_routes(&path) {
+        Ok(routes) => routes,
+        Err(e) => return fail(e),
+    };
+    let mut settings = match mock_server::load_settings(&path) {
+        Ok(settings) => settings,
+        Err(e) => return fail(e),
+    };
+    settings.host = args.host;
+
+    let acceptor = match &settings.tls {
+        Some(tls) if tls.enabled => {
+            let dir = Path::new(&path).parent().unwrap_or(Path::new("."));
+            let identity = match crate::net::mock_tls::identity(dir, tls) {
+                Ok(identity) => identity,
+                Err(e) => return fail(e),
+            };
+            if let Some(cert) = &identity.created {
+                eprintln!("Self-signed certificate saved to {}", cert.display());
+            }
+            match crate::net::mock_tls::acceptor(&identity.cert, &identity.key) {
+                Ok(acceptor) => Some(acceptor),
+                Err(e) => return fail(e),
+            }
+        }
+        _ => None,
+    };
+
+    // The banner goes to stderr with --json, so stdout stays one object per line
+    let banner = format!(
+        "{}▶ Mock server:{} {} ({} routes, CORS {}) from {}",
+        colors::CYAN,
+        colors::RESET,
+        settings.base_url(args.port),
+        routes.len(),
+        if settings.cors_on() { "on" } else { "off" },
+        path
+    );
+    let mut lines = vec![banner];
+    for route in &routes {
+        lines.push(format!(
+            "{}  {:<7} {} -> {}{}",
+            colors::DIM,
+            route.method,
+            route.path,
+            route.status,
+            colors::RESET
+        ));
+    }
+    lines.push(format!(
+        "{}{}{}",
+        colors::DIM,
+        "─".repeat(50),
+        colors::RESET
+    ));
+    for line in lines {
+        if args.json_output {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    let mut server = mock_server::start_mock_server_with(args.port, routes, &settings, acceptor);
+    let mut hits = server.subscribe();
+    let mut served = 0usize;
+    let print_hit = |hit: &mock_server::MockHit| {
+        if args.json_output {
+            if let Ok(json) = serde_json::to_string(hit) {
+                println!("{}", json);
+            }
+        } else {
+            let color = match hit.status {
+                500.. => colors::RED,
+                400..=499 => colors::YELLOW,
+                _ => colors::GREEN,
+            };
+            println!("{}{}{}", color, hit.line(), colors::RESET);
+        }
+    };
+
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            Some(hit) = hits.recv() => {
+                served += 1;
+                print_hit(&hit);
+            }
+            ended = &mut server.handle => {
+                return fail(match ended {
+                    Ok(Err(e)) => e,
+                    Ok(Ok(())) => "Mock server stopped".to_string(),
+                    Err(e) => e.to_string(),
+                });
+            }
+            _ = &mut shutdown => break,
+        }
+    }
+
+    eprintln!("Stopping, letting requests in flight finish...");
+    let stopped = tokio::time::timeout(std::time::Duration::from_secs(10), server.shutdown()).await;
+    while let Ok(hit) = hits.try_recv() {
+        served += 1;
+        print_hit(&hit);
+    }
+    match stopped {
+        Ok(Ok(())) => {
+            eprintln!("Mock server stopped after {} requests", served);
+            0
+        }
+        Ok(Err(e)) => fail(e),
+        Err(_) => fail("Requests still running after 10s, stopped anyway".to_string()),
+    }
+}
+
+/// Checks the collection every interval until a threshold is crossed (exit 1),
+/// `--rounds` are done, or Ctrl+C / SIGTERM (exit 0)
+pub async fn run_monitor_cli(args: MonitorArgs, workspace: &Workspace) -> i32 {
+    monitor_cli(args, workspace, shutdown_signal()).await
+}
+
+/// `run_monitor_cli` stopping when `shutdown` completes
+pub async fn monitor_cli(
+    args: MonitorArgs,
+    workspace: &Workspace,
+    shutdown: impl std::future::Future<Output = ()>,
+) -> i32 {
+    use crate::features::monitor::{Monitor, webhook_payload};
+    use std::io::{IsTerminal, Write};
+
+    let fail = |msg: String| {
+        eprintln!("{}Error:{} {}", colors::RED, colors::RESET, msg);
+        1
+    };
+    let collection = match load_collection(&args.collection_path) {
+        Ok(c) => c.only_tagged(&args.tags),
+        Err(e) => return fail(format!("Failed to load collection: {}", e)),
+    };
+    if collection.requests.is_empty() && collection.ws_scenarios.is_empty() {
+        return fail(format!("Nothing to check in {}", collection.name));
+    }
+    let env_vars = match args.env.resolve(workspace) {
+        Ok(vars) => vars,
+        Err(e) => return fail(format!("Failed to load environment: {}", e)),
+    };
+    let collection = match args.env.default_headers(workspace) {
+        Ok(defaults) => collection.with_default_headers(&defaults),
+        Err(e) => return fail(format!("Failed to load environment: {}", e)),
+    };
+    let resolve = match args.env.host_overrides(workspace) {
+        Ok(resolve) => resolve,
+        Err(e) => return fail(format!("Failed to load environment: {}", e)),
+    };
+
+    let json = args.json_output;
+    // A status line rewritten in place on a terminal, a line a round otherwise
+    let rolling = !json && std::io::stdout().is_terminal();
+    let emit = |event: serde_json::Value| println!("{}", event);
+    let note = |line: String| {
+        if rolling {
+            print!("\r\x1b[2K");
+        }
+        println!("{}", line);
+    };
+    let webhook = |event: &'static str, text: String, detail: serde_json::Value| {
+        let url = args.webhook.clone();
+        let payload = webhook_payload(&collection.name, event, text, detail);
+        async move {
+            if let Some(url) = url
+                && let Err(e) = run_hooks::post_webhook(&url, &payload).await
+            {
+                eprintln!("{}", e);
+            }
+        }
+    };
+
+    let banner = format!(
+        "{}▶ Monitoring:{} {} ({} checks) every {}s, stop after {} failed rounds in a row{}",
+        colors::CYAN,
+        colors::RESET,
+        collection.name,
+        collection.requests.len() + collection.ws_scenarios.len(),
+        args.settings.interval_secs,
+        args.settings.max_failures,
+        args.settings
+            .max_error_rate
+            .map(|pct| format!(" or over {}% errors", pct))
+            .unwrap_or_default()
+    );
+    if json {
+        eprintln!("{}", banner);
+    } else {
+        println!("{}", banner);
+    }
+
+    let mut monitor = Monitor::new(args.settings.clone());
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+        monitor.settings.interval_secs,
+    ));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    tokio::pin!(shutdown);
+    let limits = WorkspaceConfig::load(workspace).limits(&args.rate_limit);
+
+    let code = loop {
+        tokio::select! {
+            _ = &mut shutdown => break 0,
+            _ = interval.tick() => {}
+        }
+        let (tx, mut rx) = mpsc::channel::<RunnerEvent>(32);
+        let collect = async {
+            let mut results = Vec::new();
+            while let Some(event) = rx.recv().await {
+                if let RunnerEvent::RequestCompleted(result) = event {
+                    results.push(result);
+                }
+            }
+            results
+        };
+        let round = async {
+            tokio::join!(
+                runner::run_collection(&collection, &env_vars, &limits, &resolve, tx),
+                collect
+            )
+            .1
+        };
+        let results = tokio::select! {
+            _ = &mut shutdown => break 0,
+            results = round => results,
+        };
+
+        let transitions = monitor.record_round(&results);
+        let at = crate::features::history::now();
+        if json {
+            for r in &results {
+                emit(serde_json::json!({
+                    "event": "check",
+                    "at": at,
+                    "round": monitor.round,
+                    "name": r.name,
+                    "method": r.method,
+                    "url": r.url,
+                    "status": r.status,
+                    "latency_ms": r.latency_ms,
+                    "passed": r.passed,
+                    "error": r.error,
+                }));
+            }
+        }
+        for t in &transitions {
+            let text = match t.event {
+                "down" => format!(
+                    "✗ {} down: {}",
+                    t.name,
+                    t.error
+                        .clone()
+                        .or(t.status.map(|s| s.to_string()))
+                        .unwrap_or_default()
+                ),
+                _ => format!("✓ {} back up after {} failed rounds", t.name, t.streak),
+            };
+            if json {
+                let mut event = serde_json::to_value(t).unwrap_or_default();
+                event["at"] = at.into();
+                event["round"] = monitor.round.into();
+                emit(event);
+            } else {
+                let color = if t.event == "down" {
+                    colors::RED
+                } else {
+                    colors::GREEN
+                };
+                note(format!("{}{}{}", color, text, colors::RESET));
+            }
+            webhook(t.event, text, serde_json::to_value(t).unwrap_or_default()).await;
+        }
+        if json {
+            emit(serde_json::json!({
+                "event": "round",
+                "at": at,
+                "round": monitor.round,
+                "up": results.iter().filter(|r| r.passed).count(),
+                "total": results.len(),
+                "error_rate": monitor.error_rate(),
+            }));
+        } else if rolling {
+            print!("\r\x1b[2K{}", monitor.status_line(&results));
+            let _ = std::io::stdout().flush();
+        } else {
+            println!("{}", monitor.status_line(&results));
+        }
+
+        if let Some(reason) = monitor.breach() {
+            if json {
+                emit(serde_json::json!({
+                    "event": "breach",
+                    "at": at,
+                    "round": monitor.round,
+                    "reason": reason,
+                }));
+            } else {
+                note(format!(
+                    "{}✗ Threshold crossed:{} {}",
+                    colors::RED,
+                    colors::RESET,
+                    reason
+                ));
+            }
+            webhook(
+                "breach",
+                format!("✗ {}", reason),
+                serde_json::json!({ "reason": reason, "round": monitor.round }),
+            )
+            .await;
+            break 1;
+        }
+        if monitor.is_done() {
+            break 0;
+        }
+    };
+
+    if rolling && monitor.round > 0 && code == 0 {
+        println!();
+    }
+    eprintln!(
+        "Monitored {} rounds: {} of {} checks failed",
+        monitor.round, monitor.total_failed, monitor.total_checks
+    );
+    code
+}
+
+/// Run a collection in CLI mode
+pub async fn run_collection_cli(args: RunArgs, workspace: &Workspace) -> i32 {
+    // Load collection
+    let collection = match load_collection(&args.collection_path) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!(
+                "{}Error:{} Failed to load collection: {}",
+                colors::RED,
+                colors::RESET,
+                e
+            );
+            return 1;
+        }
+    };
+    let collection = collection.only_tagged(&args.tags);
+    if !args.tags.is_empty() && collection.requests.is_empty() {
+        eprintln!(
+            "{}Error:{} No requests in {} tagged {}",
+            colors::RED,
+            colors::RESET,
+            collection.name,
+            args.tags.join(", ")
+        );
+        return 1;
+    }
+
+    let (env_vars, defaults, resolve) = match args.env.resolve(workspace).and_then(|vars| {
+        Ok((
+            vars,
+            args.env.default_headers(workspace)?,
+            args.env.host_overrides(workspace)?,
+        ))
+    }) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!(
+                "{}Error:{} Failed to load environment: {}",
+                colors::RED,
+                colors::RESET,
+                e
+            );
+            return 1;
+        }
+    };
+    let collection = collection.with_default_headers(&defaults);
+
+    let total_requests = collection.requests.len() + collection.ws_scenarios.len();
+
+    if !args.json_output {
+        println!();
+        println!(
+            "{}▶ Running:{} {} ({} requests)",
+            colors::CYAN,
+            colors::RESET,
+            collection.name,
+            total_requests
+        );
+        println!("{}{}{}", colors::DIM, "─".repeat(50), colors::RESET);
+    }
+
+    // Create channel for runner events
+    let (tx, mut rx) = mpsc::channel::<RunnerEvent>(32);
+
+    // Spawn the runner
+    let collection_clone = collection.clone();
+    let env_vars_clone = env_vars.clone();
+    let limits = WorkspaceConfig::load(workspace).limits(&args.rate_limit);
+    tokio::spawn(async move {
+        runner::run_collection(&collection_clone, &env_vars_clone, &limits, &resolve, tx).await;
+    });
+
+    let mut results: Vec<RunResult> = Vec::new();
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut final_result = None;
+
+    // Process events
+    while let Some(event) = rx.recv().await {
+        match event {
+            RunnerEvent::RequestCompleted(result) => {
+                if !args.json_output {
+                    print_result(&result, args.verbose);
+                }
+
+                if result.passed {
+                    passed += 1;
+                } else {
+                    failed += 1;
+                }
+                results.push(result);
+            }
+            RunnerEvent::Throttled { wait_ms, reason } if args.verbose && !args.json_output => {
+                println!(
+                    "  {}⏳ waiting {}ms ({}){}",
+                    colors::DIM,
+                    wait_ms,
+                    reason,
+                    colors::RESET
+                );
+            }
+            RunnerEvent::Finished(result) => {
+                final_result = Some(result);
+                break;
+            }
+            RunnerEvent::Error(e) => {
+                if args.json_output {
+                    println!(r#"{{"error": "{}"}}"#, e);
+                } else {
+                    eprintln!("{}Error:{} {}", colors::RED, colors::RESET, e);
+                }
+                return 1;
+            }
+            _ => {}
+        }
+    }
+
+    // Output results
+    if args.json_output {
+        println!(
+            "{}",
+            results_export::run(&collection.name, &results, Format::Json)
+        );
+    } else {
+        println!("{}{}{}", colors::DIM, "─".repeat(50), colors::RESET);
+        print_summary(passed, failed, total_requests);
+    }
+    if let Some(path) = &args.export {
+        let content = results_export::run(&collection.name, &results, Format::for_path(path));
+        match std::fs::write(path, content) {
+            Ok(()) => eprintln!(
+                "{}✓ Results written to {}{}",
+                colors::DIM,
+                path,
+                colors::RESET
+            ),
+            Err(e) => eprintln!(
+                "{}Error:{} Could not write {}: {}",
+                colors::RED,
+                colors::RESET,
+                path,
+                e
+            ),
+        }
+    }
+    if let Some(dir) = &args.output_dir {
+        match response_dump::write(Path::new(dir), &results) {
+            Ok(n) => eprintln!(
+                "{}✓ {} responses written to {}{}",
+                colors::DIM,
+                n,
+                dir,
+                colors::RESET
+            ),
+            Err(e) => eprintln!(
+                "{}Error:{} Could not write responses: {}",
+                colors::RED,
+                colors::RESET,
+                e
+            ),
+        }
+    }
+
+    // Post-run hooks report on stderr so `--json` output stays clean
+    if let Some(result) = final_result {
+        for outcome in run_hooks::fire(&args.hooks, &result).await {
+            match outcome {
+                Ok(msg) => eprintln!("{}✓ {}{}", colors::DIM, msg, colors::RESET),
+                Err(e) => eprintln!("{}Hook error:{} {}", colors::RED, colors::RESET, e),
+            }
+        }
+    }
+
+    // Exit code: 0 if all passed, 1 if any failed
+    if failed > 0 { 1 } else { 0 }
+}
+
+/// Run a stress test in CLI mode. Exit code 1 when a threshold is breached or nothing
+/// could be sent.
+pub async fn run_stress_cli(args: StressArgs, workspace: &Workspace) -> i32 {
+    let fail = |e: String| {
+        eprintln!("{}Error:{} {}", colors::RED, colors::RESET, e);
+        1
+    };
+    let env_vars = match args.env.resolve(workspace) {
+        Ok(vars) => vars,
+        Err(e) => return fail(e),
+    };
+    let defaults = match args.env.default_headers(workspace) {
+        Ok(defaults) => defaults,
+        Err(e) => return fail(e),
+    };
+    let resolve = match args.env.host_overrides(workspace) {
+        Ok(resolve) => resolve,
+        Err(e) => return fail(e),
+    };
+    let substitute = |text: &str| {
+        env_vars.iter().fold(text.to_string(), |text, (k, v)| {
+            text.replace(&format!("{{{{{}}}}}", k), v)
+        })
+    };
+    let requests = if Path::new(&args.target).is_file() {
+        match load_collection(&args.target).and_then(|c| {
+            stress::collection_workload(
+                &c.with_default_headers(&defaults),
+                env_vars
+                    .get(crate::domain::environment::BASE_URL)
+                    .map(String::as_str),
+                &substitute,
+            )
+        }) {
+            Ok(requests) => requests,
+            Err(e) => return fail(e),
+        }
+    } else if args.target.starts_with("http://") || args.target.starts_with("https://") {
+        vec![StressRequest {
+            name: args.target.clone(),
+            url: substitute(&args.target),
+            method: args.method.clone(),
+            headers: defaults
+                .iter()
+                .map(|(k, v)| (k.clone(), substitute(v)))
+                .collect(),
+            body: None,
+        }]
+    } else {
+        return fail(format!("'{}' is neither a file nor a URL", args.target));
+    };
+    let stages = match stress::parse_stages(&args.stages, args.vus, args.duration_secs) {
+        Ok(stages) => stages,
+        Err(e) => return fail(e),
+    };
+
+    if !args.json_output {
+        println!();
+        println!(
+            "{}▶ Stress:{} {} ({} request(s), up to {} VUs, {}s)",
+            colors::CYAN,
+            colors::RESET,
+            args.target,
+            requests.len(),
+            stress::max_vus(&stages),
+            stress::total_secs(&stages)
+        );
+        println!("{}{}{}", colors::DIM, "─".repeat(50), colors::RESET);
+    }
+
+    let (tx, mut rx) = mpsc::channel(32);
+    tokio::spawn(stress::run_stress_test(
+        stress::StressConfig {
+            requests,
+            stages,
+            thresholds: args.thresholds.clone(),
+            rate_limit: WorkspaceConfig::load(workspace).limits(&args.rate_limit),
+            resolve,
+        },
+        tx,
+    ));
+
+    // Progress goes to stderr so `--json` output stays clean
+    let mut stats = None;
+    while let Some(event) = rx.recv().await {
+        match event {
+            StressEvent::Progress(p) => eprint!(
+                "\r{}{} reqs | {}s / {}s | {} VUs | stage {}/{}{}{}  ",
+                colors::DIM,
+                p.requests_done,
+                p.elapsed_secs,
+                p.total_secs,
+                p.active_vus,
+                p.stage,
+                p.stages,
+                if p.throttled > 0 {
+                    format!(" | {} throttled", p.throttled)
+                } else {
+                    String::new()
+                },
+                colors::RESET
+            ),
+            StressEvent::Finished(s) => {
+                stats = Some(s);
+                break;
+            }
+            StressEvent::Error(e) => {
+                eprintln!();
+                return fail(e);
+            }
+        }
+    }
+    eprintln!();
+    let Some(stats) = stats else {
+        return fail("Stress test stopped unexpectedly".to_string());
+    };
+
+    if args.json_output {
+        print_json_stress(&args.target, &stats);
+    } else {
+        print_stress_summary(&stats);
+    }
+    if stats.thresholds_passed() { 0 } else { 1 }
+}
+
+fn print_stress_summary(stats: &StressStats) {
+    println!(
+        "{}Requests:{} {} ({:.1}/s), {:.2}% failed",
+        colors::BOLD,
+        colors::RESET,
+        stats.total_requests,
+        stats.rps,
+        stats.error_rate()
+    );
+    println!(
+        "{}Latency:{}  avg {:.1}ms  p50 {}ms  p90 {}ms  p95 {}ms  p99 {}ms  max {}ms",
+        colors::BOLD,
+        colors::RESET,
+        stats.avg_latency_ms,
+        stats.p50_latency_ms,
+        stats.p90_latency_ms,
+        stats.p95_latency_ms,
+        stats.p99_latency_ms,
+        stats.max_latency_ms
+    );
+    for request in &stats.per_request {
+        println!(
+            "  {}{:<7}{} {}  {} reqs, avg {:.1}ms, p99 {}ms",
+            colors::CYAN,
+            request.method,
+            colors::RESET,
+            request.name,
+            request.total_requests,
+            request.avg_latency_ms,
+            request.p99_latency_ms
+        );
+    }
+    if !stats.thresholds.is_empty() {
+        println!();
+        for result in &stats.thresholds {
+            let icon = if result.passed {
+                format!("{}✓{}", colors::GREEN, colors::RESET)
+            } else {
+                format!("{}✗{}", colors::RED, colors::RESET)
+            };
+            println!("  {} {} (got {:.2})", icon, result.threshold, result.actual);
+        }
+    }
+    println!();
+    if stats.thresholds_passed() {
+        println!("{}✓ Stress test passed{}", colors::GREEN, colors::RESET);
+    } else {
+        let breached = stats.thresholds.iter().filter(|t| !t.passed).count();
+        println!(
+            "{}✗ {} threshold(s) breached{}",
+            colors::RED,
+            breached,
+            colors::RESET
+        );
+    }
+    println!();
+}
+
+fn print_json_stress(target: &str, stats: &StressStats) {
+    let output = serde_json::json!({
+        "target": target,
+        "total": stats.total_requests,
+        "rps": stats.rps,
+        "error_rate": stats.error_rate(),
+        "latency_ms": {
+            "avg": stats.avg_latency_ms,
+            "min": stats.min_latency_ms,
+            "p50": stats.p50_latency_ms,
+            "p90": stats.p90_latency_ms,
+            "p95": stats.p95_latency_ms,
+            "p99": stats.p99_latency_ms,
+            "max": stats.max_latency_ms,
+        },
+        "requests": stats.per_request.iter().map(|r| serde_json::json!({
+            "name": r.name,
+            "method": r.method,
+            "total": r.total_requests,
+            "errors": r.errors_count,
+            "avg_ms": r.avg_latency_ms,
+            "p90_ms": r.p90_latency_ms,
+            "p99_ms": r.p99_latency_ms,
+        })).collect::<Vec<_>>(),
+        "thresholds": stats.thresholds.iter().map(|t| serde_json::json!({
+            "threshold": t.threshold.to_string(),
+            "actual": t.actual,
+            "passed": t.passed,
+        })).collect::<Vec<_>>(),
+        "passed": stats.thresholds_passed(),
+    });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&output).unwrap_or_default()
+    );
+}
+
+/// The app `PostDad req` sends from: the workspace loaded, the environment picked
+/// and the active tab filled in from the arguments
+pub fn request_app(args: &ReqArgs, workspace: Workspace) -> Result<crate::app::App, String> {
+    use crate::app::{AuthType, BodyType};
+    let mut app = crate::app::App::with_workspace(workspace);
+    app.show_splash = false;
+    if let Some(name) = &args.env {
+        app.selected_env_index = app
+            .environments
+            .iter()
+            .position(|e| e.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("No environment named '{}'", name))?;
+    }
+
+    let saved = !args.target.contains("://")
+        && args
+            .target
+            .split_once('/')
+            .is_some_and(|(collection, request)| {
+                app.collections.iter().any(|c| {
+                    c.name.eq_ignore_ascii_case(collection)
+                        && c.requests.keys().any(|r| r.eq_ignore_ascii_case(request))
+                })
+            });
+    if saved {
+        app.open_request(&args.target);
+    } else {
+        app.active_tab_mut().url = args.target.clone();
+        app.sync_url_to_params();
+    }
+
+    let body = match args.data.as_deref() {
+        Some("@-") => Some(
+            std::io::read_to_string(std::io::stdin())
+                .map_err(|e| format!("Could not read stdin: {}", e))?,
+        ),
+        Some(data) => match data.strip_prefix('@') {
+            Some(path) => Some(
+                std::fs::read_to_string(path)
+                    .map_err(|e| format!("Could not read {}: {}", path, e))?,
+            ),
+            None => Some(data.to_string()),
+        },
+        None => None,
+    };
+    let tab = app.active_tab_mut();
+    if let Some(method) = &args.method {
+        tab.method = method.clone();
+    } else if body.is_some() && !saved {
+        tab.method = "POST".to_string();
+    }
+    for (name, value) in &args.headers {
+        tab.request_headers.insert(name.clone(), value.clone());
+    }
+    if let Some(body) = body {
+        tab.body_type = BodyType::Raw;
+        tab.request_body = body;
+    }
+    if let Some(user) = &args.user {
+        let (name, pass) = user.split_once(':').unwrap_or((user, ""));
+        tab.auth_type = AuthType::Basic;
+        tab.basic_auth_user = name.to_string();
+        tab.basic_auth_pass = pass.to_string();
+    }
+    if let Some(token) = &args.bearer {
+        tab.auth_type = AuthType::Bearer;
+        tab.auth_token = token.clone();
+    }
+    app.guard_confirmed = args.yes;
+    if args.yes {
+        let warnings = crate::features::presend::check(&app.unscripted_request(), &app.send_checks);
+        app.active_tab_mut()
+            .dismissed_warnings
+            .extend(warnings.into_iter().map(|w| w.message));
+    }
+    Ok(app)
+}
+
+/// Prints what came back for `PostDad req` and returns the exit code: 1 when nothing
+/// was sent, the request failed or a post-request test failed, 22 on 4xx/5xx with
+/// `--fail`. `event` is None when the request never went out.
+pub fn finish_request_cli(
+    app: &mut crate::app::App,
+    event: Option<crate::net::http::NetworkEvent>,
+    args: &ReqArgs,
+) -> i32 {
+    use crate::net::http::NetworkEvent;
+    use std::io::Write;
+    let fail = |e: &str| {
+        eprintln!("{}Error:{} {}", colors::RED, colors::RESET, e);
+        1
+    };
+    match event {
+        None => {
+            if let Some(warnings) = &app.send_warnings {
+                for warning in warnings {
+                    eprintln!(
+                        "{}Warning:{} {}",
+                        colors::YELLOW,
+                        colors::RESET,
+                        warning.message
+                    );
+                }
+                return fail("Not sent. Pass --yes to send it anyway.");
             }
+            if let Some(prompt) = &app.guard_prompt {
+                return fail(&format!("{} Pass --yes to send it.", prompt.message));
+            }
+            let reason = app
+                .notifications
+                .active
+                .last()
+                .map(|n| n.message.clone())
+                .unwrap_or_else(|| "The request was not sent".to_string());
+            fail(&reason)
         }
-        "run" => {
-            if args.len() < 3 {
-                eprintln!("Usage: PostDad run <collection.hcl> [-e env.hcl] [-v] [--json]");
-                std::process::exit(1);
+        Some(NetworkEvent::Failed { error, .. }) => fail(&error),
+        Some(NetworkEvent::GotGrpcResponse {
+            request_id: _,
+            success,
+            body,
+            error,
+            latency_ms,
+            metadata,
+        }) => {
+            println!("{}", body);
+            eprintln!("{}gRPC · {}ms{}", colors::DIM, latency_ms, colors::RESET);
+            if let Some(status) = metadata.status.as_ref().filter(|s| s.code != 0) {
+                for detail in &status.details {
+                    eprintln!(
+                        "{}{}{}",
+                        colors::DIM,
+                        serde_json::to_string_pretty(detail).unwrap_or_default(),
+                        colors::RESET
+                    );
+                }
             }
+            match error {
+                Some(e) if !success => fail(&e),
+                _ => 0,
+            }
+        }
+        Some(NetworkEvent::GotResponse(
+            _,
+            bytes,
+            status,
+            duration,
+            cookies,
+            url,
+            headers,
+            encoding,
+        )) => {
+            app.add_cookies(&url, cookies);
+            if let Some(error) = encoding.as_ref().and_then(|e| e.error.as_ref()) {
+                eprintln!(
+                    "{}Body left encoded:{} {}",
+                    colors::YELLOW,
+                    colors::RESET,
+                    error
+                );
+            }
+            let text = String::from_utf8(bytes.clone()).ok();
+            let script = app.active_tab().post_request_script.clone();
+            let tests = match &text {
+                Some(text) if !script.trim().is_empty() => {
+                    let result = crate::features::scripting::run_post_script(
+                        &script,
+                        status,
+                        text,
+                        &headers,
+                        duration,
+                        &app.script_history(),
+                    );
+                    for e in &result.errors {
+                        eprintln!("{}Script:{} {}", colors::YELLOW, colors::RESET, e);
+                    }
+                    result.tests
+                }
+                _ => Vec::new(),
+            };
+            let json = text
+                .as_deref()
+                .and_then(|t| serde_json::from_str::<serde_json::Value>(t).ok());
+            let reason = reqwest::StatusCode::from_u16(status)
+                .ok()
+                .and_then(|s| s.canonical_reason())
+                .unwrap_or("");
+            let mut header_list: Vec<(&String, &String)> = headers.iter().collect();
+            header_list.sort();
 
-            let collection_path = args[2].clone();
-            let mut env_path = None;
-            let mut verbose = false;
-            let mut json_output = false;
-
-            let mut i = 3;
-            while i < args.len() {
-                match args[i].as_str() {
-                    "-e" | "--env" => {
-                        if i + 1 < args.len() {
-                            env_path = Some(args[i + 1].clone());
-                            i += 1;
-                        }
+            if args.json_output {
+                use base64::Engine;
+                let mut output = serde_json::json!({
+                    "url": url,
+                    "status": status,
+                    "latency_ms": duration,
+                    "headers": headers,
+                    "tests": tests.iter().map(|(name, passed)| {
+                        serde_json::json!({"name": name, "passed": passed})
+                    }).collect::<Vec<_>>(),
+                });
+                output["body"] = match (&json, &text) {
+                    (Some(json), _) => json.clone(),
+                    (None, Some(text)) => serde_json::Value::String(text.clone()),
+                    (None, None) => serde_json::Value::Null,
+                };
+                if let Some(trace) = &app.active_tab().trace {
+                    output["trace_id"] = serde_json::Value::String(trace.trace_id.clone());
+                }
+                if text.is_none() {
+                    output["body_base64"] = serde_json::Value::String(
+                        base64::engine::general_purpose::STANDARD.encode(&bytes),
+                    );
+                }
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&output).unwrap_or_default()
+                );
+            } else {
+                if args.include {
+                    println!("HTTP {} {}", status, reason);
+                    for (name, value) in &header_list {
+                        println!("{}: {}", name, value);
                     }
-                    "-v" | "--verbose" => verbose = true,
-                    "--json" => json_output = true,
-                    _ => {}
+                    println!();
+                }
+                match (&json, &text) {
+                    (Some(json), _) => {
+                        println!("{}", serde_json::to_string_pretty(json).unwrap_or_default())
+                    }
+                    (None, Some(text)) => println!("{}", text),
+                    (None, None) => {
+                        let _ = std::io::stdout().write_all(&bytes);
+                    }
+                }
+                for (name, passed) in &tests {
+                    let icon = if *passed {
+                        format!("{}✓{}", colors::GREEN, colors::RESET)
+                    } else {
+                        format!("{}✗{}", colors::RED, colors::RESET)
+                    };
+                    eprintln!("{} {}", icon, name);
+                }
+                let wire = match &encoding {
+                    Some(e) if e.error.is_none() => {
+                        format!(" ({} {} bytes)", e.encoding, e.raw.len())
+                    }
+                    _ => String::new(),
+                };
+                eprintln!(
+                    "{}{} {} · {}ms · {} bytes{}{}",
+                    colors::DIM,
+                    status,
+                    reason,
+                    duration,
+                    bytes.len(),
+                    wire,
+                    colors::RESET
+                );
+                if let Some(trace) = &app.active_tab().trace {
+                    let link = trace.link(&app.trace_settings.link);
+                    eprintln!(
+                        "{}trace {}{}",
+                        colors::DIM,
+                        link.unwrap_or_else(|| trace.trace_id.clone()),
+                        colors::RESET
+                    );
                 }
-                i += 1;
             }
 
-            Some(CliAction::Run(RunArgs {
-                collection_path,
-                env_path,
-                verbose,
-                json_output,
-            }))
+            if tests.iter().any(|(_, passed)| !passed) {
+                1
+            } else if args.fail && status >= 400 {
+                22
+            } else {
+                0
+            }
         }
-        "--help" | "-h" => {
-            print_help();
-            std::process::exit(0);
+        Some(_) => fail("Unexpected reply from the network task"),
+    }
+}
+
+/// `PostDad doctor`. Exit code 1 when any check failed (warnings don't count).
+/// `PostDad sync` and `PostDad storage`: both ways unless push or pull is given.
+/// Conflicts fail the sync (exit 1) unless `--prefer` settles them; `status` only looks.
+pub async fn run_sync_cli(args: SyncArgs, workspace: &Workspace) -> i32 {
+    use crate::features::gist_sync::{self, Action, Direction, Target};
+
+    let fail = |msg: String| {
+        eprintln!("{}✗ {}{}", colors::RED, msg, colors::RESET);
+        1
+    };
+    let mut app = crate::app::App::with_workspace(workspace.clone());
+    app.show_splash = false;
+
+    let direction = match (args.command.as_str(), Direction::parse(&args.command)) {
+        ("status", _) => {
+            println!("{}", app.sync_summary(args.target));
+            None
         }
-        "--version" | "-V" => {
-            println!("PostDad {}", env!("CARGO_PKG_VERSION"));
-            std::process::exit(0);
+        (_, Some(direction)) => Some(direction),
+        (other, None) => {
+            return fail(format!(
+                "Unknown sync command '{}', use push, pull or status",
+                other
+            ));
         }
-        _ => None, // Unknown arg, launch TUI
+    };
+    let target = args.target;
+    if target == Target::Storage && !app.storage.is_configured() {
+        return match direction {
+            None => 0,
+            Some(_) => fail("No storage set up: add storage to config.json".to_string()),
+        };
+    }
+    if target == Target::Gist && app.gist.gist_id.is_empty() {
+        return match direction {
+            // Nothing to compare with yet
+            None => 0,
+            Some(Direction::Pull) => fail(
+                "No gist to pull from: push first, or set gist.gist_id in config.json".to_string(),
+            ),
+            Some(_) => sync_upload(&mut app).await,
+        };
     }
-}
 
-pub enum CliAction {
-    Import(String),
-    Run(RunArgs),
-}
+    let endpoint = app.sync_endpoint(target);
+    let remote = match endpoint.fetch().await {
+        Ok(remote) => remote,
+        Err(e) => return fail(e),
+    };
+    let mut plan = app.sync_plan(target, &remote);
+    let Some(direction) = direction else {
+        for file in plan.iter().filter(|f| f.action != Action::Unchanged) {
+            let what = match file.action {
+                Action::Push => "local change, to push",
+                Action::Pull if target == Target::Gist => "changed in the gist, to pull",
+                Action::Pull => "changed in the storage, to pull",
+                _ => "conflict",
+            };
+            println!(
+                "  {:<24} {}{}{}",
+                file.name,
+                colors::DIM,
+                what,
+                colors::RESET
+            );
+        }
+        return 0;
+    };
 
-fn print_help() {
+    let conflicts: Vec<&str> = plan
+        .iter()
+        .filter(|f| f.action == Action::Conflict)
+        .map(|f| f.name.as_str())
+        .collect();
+    if !conflicts.is_empty() {
+        match &args.prefer {
+            Some(prefer) => {
+                if let Err(e) = gist_sync::resolve_all(&mut plan, prefer) {
+                    return fail(e);
+                }
+            }
+            None => {
+                return fail(format!(
+                    "Changed here and in the {}: {} (use --prefer local or --prefer remote)",
+                    target.label(),
+                    conflicts.join(", ")
+                ));
+            }
+        }
+    }
+
+    let outcome = match app.apply_sync(target, direction, &plan) {
+        Ok(outcome) => outcome,
+        Err(e) => return fail(e),
+    };
+    for (name, _) in &outcome.pulls {
+        println!("  {}↓{} {}", colors::CYAN, colors::RESET, name);
+    }
+    let id = if outcome.pushes.is_empty() {
+        remote.id.clone()
+    } else {
+        match endpoint.push(&outcome.pushes).await {
+            Ok(id) => id,
+            Err(e) => return fail(e),
+        }
+    };
+    for (name, _) in &outcome.pushes {
+        println!("  {}↑{} {}", colors::MAGENTA, colors::RESET, name);
+    }
+    if let Err(e) = app.record_sync(target, &id, outcome.state) {
+        return fail(e);
+    }
     println!(
-        r#"{}PostDad{} - A fast API client for your terminal
+        "{}✓ Synced with {} {}: {} pulled, {} pushed{}",
+        colors::GREEN,
+        target.label(),
+        id,
+        outcome.pulls.len(),
+        outcome.pushes.len(),
+        colors::RESET
+    );
+    if outcome.skipped > 0 {
+        println!(
+            "  {}{} change(s) left for the other direction{}",
+            colors::DIM,
+            outcome.skipped,
+            colors::RESET
+        );
+    }
+    0
+}
 
-{}USAGE:{}
-    PostDad                              Launch the TUI
-    PostDad run <collection.hcl>         Run a collection
-    PostDad --import <file.json>         Import a Postman collection
+/// First push: every collection into a new secret gist
+async fn sync_upload(app: &mut crate::app::App) -> i32 {
+    use crate::features::gist_sync;
 
-{}OPTIONS:{}
-    -e, --env <file.hcl>    Environment file to use
-    -v, --verbose           Show request/response details
-    --json                  Output results as JSON
-    -h, --help              Show this help
-    -V, --version           Show version
+    let local = gist_sync::local_files(&app.workspace.path("collections"));
+    if local.is_empty() {
+        eprintln!("{}✗ No collections to push{}", colors::RED, colors::RESET);
+        return 1;
+    }
+    let plan = gist_sync::plan(&local, &Default::default(), &Default::default());
+    let outcome = gist_sync::outcome(&plan, gist_sync::Direction::Push, "", &Default::default());
+    let result = gist_sync::push(&app.gist, &outcome.pushes)
+        .await
+        .and_then(|id| {
+            app.record_sync(gist_sync::Target::Gist, &id, outcome.state)
+                .map(|_| id)
+        });
+    match result {
+        Ok(id) => {
+            println!(
+                "{}✓ Pushed {} file(s) to new gist {}{}",
+                colors::GREEN,
+                outcome.pushes.len(),
+                id,
+                colors::RESET
+            );
+            0
+        }
+        Err(e) => {
+            eprintln!("{}✗ {}{}", colors::RED, e, colors::RESET);
+            1
+        }
+    }
+}
 
-{}EXAMPLES:{}
-    PostDad run api_tests.hcl
-    PostDad run api_tests.hcl -e production.hcl
-    PostDad run api_tests.hcl --json > results.json
-"#,
-        colors::BOLD,
-        colors::RESET,
-        colors::BOLD,
-        colors::RESET,
+/// Exits like diff(1): 0 when the versions match, 1 when they differ, 2 on errors
+pub fn run_diff_cli(old: &str, new: Option<&str>, workspace: &Workspace) -> i32 {
+    use crate::features::collection_diff::{self, Tone};
+    let dir = workspace.path("collections");
+    let report = match collection_diff::compare_sources(old, new, Path::new(&dir)) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("{}✗ {}{}", colors::RED, e, colors::RESET);
+            return 2;
+        }
+    };
+
+    println!(
+        "{}{} → {}{}",
         colors::BOLD,
-        colors::RESET,
+        report.old,
+        report.new,
+        colors::RESET
+    );
+    println!();
+    for line in report.lines() {
+        let color = match line.tone {
+            Tone::Added => colors::GREEN,
+            Tone::Removed => colors::RED,
+            Tone::Changed => colors::YELLOW,
+            Tone::Plain => colors::DIM,
+        };
+        println!("{}{}{}", color, line.text, colors::RESET);
+    }
+    if !report.is_empty() {
+        println!();
+    }
+    println!(
+        "{}Summary:{} {}",
         colors::BOLD,
         colors::RESET,
+        report.summary()
     );
+    if report.is_empty() { 0 } else { 1 }
 }
 
-/// Run a collection in CLI mode
-pub async fn run_collection_cli(args: RunArgs) -> i32 {
-    // Load collection
-    let collection = match load_collection(&args.collection_path) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!(
-                "{}Error:{} Failed to load collection: {}",
-                colors::RED,
-                colors::RESET,
-                e
-            );
-            return 1;
-        }
-    };
-
-    // Load environment if specified
-    let env_vars: HashMap<String, String> = if let Some(env_path) = &args.env_path {
-        match load_environment(env_path) {
-            Ok(vars) => vars,
+/// `PostDad history`: the history to stdout, or to `export`
+pub fn run_history_cli(json_output: bool, export: Option<&str>, workspace: &Workspace) -> i32 {
+    let mut app = crate::app::App::with_workspace(workspace.clone());
+    if app.passphrase_prompt.is_some() {
+        eprintln!(
+            "{}Error:{} The history is encrypted, set {} to unlock it",
+            colors::RED,
+            colors::RESET,
+            crate::features::vault::PASSPHRASE_ENV
+        );
+        return 1;
+    }
+    app.show_splash = false;
+    let history = &app.request_history;
+    match export {
+        Some(path) => match std::fs::write(
+            path,
+            results_export::history(history, Format::for_path(path)),
+        ) {
+            Ok(()) => {
+                eprintln!("{} history entries written to {}", history.len(), path);
+                0
+            }
             Err(e) => {
                 eprintln!(
-                    "{}Error:{} Failed to load environment: {}",
+                    "{}Error:{} Could not write {}: {}",
                     colors::RED,
                     colors::RESET,
+                    path,
                     e
                 );
-                return 1;
+                1
+            }
+        },
+        None => {
+            let format = if json_output {
+                Format::Json
+            } else {
+                Format::Csv
+            };
+            print!("{}", results_export::history(history, format));
+            if json_output {
+                println!();
             }
+            0
         }
+    }
+}
+
+pub fn run_doctor_cli(workspace: &Workspace) -> i32 {
+    use crate::features::doctor::{self, Level, Probe};
+    use std::io::IsTerminal;
+
+    // Only a real terminal can answer the graphics query
+    let image_protocol = if std::io::stdout().is_terminal() {
+        ratatui_image::picker::Picker::from_query_stdio()
+            .ok()
+            .map(|p| format!("{:?}", p.protocol_type()))
     } else {
-        HashMap::new()
+        None
     };
+    let checks = doctor::run(&Probe::detect(image_protocol, workspace), true);
 
-    let total_requests = collection.requests.len();
-
-    if !args.json_output {
-        println!();
+    println!();
+    let mut area = "";
+    for check in &checks {
+        if check.area != area {
+            area = check.area;
+            println!("{}{}{}", colors::BOLD, area, colors::RESET);
+        }
+        let icon = match check.level {
+            Level::Ok => format!("{}✓{}", colors::GREEN, colors::RESET),
+            Level::Warn => format!("{}!{}", colors::YELLOW, colors::RESET),
+            Level::Fail => format!("{}✗{}", colors::RED, colors::RESET),
+        };
         println!(
-            "{}▶ Running:{} {} ({} requests)",
-            colors::CYAN,
-            colors::RESET,
-            collection.name,
-            total_requests
+            "  {} {} {}{}{}",
+            icon,
+            check.name,
+            colors::DIM,
+            check.detail,
+            colors::RESET
         );
-        println!("{}{}{}", colors::DIM, "─".repeat(50), colors::RESET);
-    }
-
-    // Create channel for runner events
-    let (tx, mut rx) = mpsc::channel::<RunnerEvent>(32);
-
-    // Spawn the runner
-    let collection_clone = collection.clone();
-    let env_vars_clone = env_vars.clone();
-    tokio::spawn(async move {
-        runner::run_collection(&collection_clone, &env_vars_clone, tx).await;
-    });
-
-    let mut results: Vec<RunResult> = Vec::new();
-    let mut passed = 0;
-    let mut failed = 0;
-
-    // Process events
-    while let Some(event) = rx.recv().await {
-        match event {
-            RunnerEvent::RequestCompleted(result) => {
-                if !args.json_output {
-                    print_result(&result, args.verbose);
-                }
-
-                if result.passed {
-                    passed += 1;
-                } else {
-                    failed += 1;
-                }
-                results.push(result);
-            }
-            RunnerEvent::Finished(_) => break,
-            RunnerEvent::Error(e) => {
-                if args.json_output {
-                    println!(r#"{{"error": "{}"}}"#, e);
-                } else {
-                    eprintln!("{}Error:{} {}", colors::RED, colors::RESET, e);
-                }
-                return 1;
-            }
-            _ => {}
+        if let Some(fix) = &check.fix {
+            println!("      {}→ {}{}", colors::CYAN, fix, colors::RESET);
         }
     }
 
-    // Output results
-    if args.json_output {
-        print_json_results(&collection.name, &results, passed, failed);
+    let (warnings, failures) = doctor::counts(&checks);
+    println!();
+    if warnings + failures == 0 {
+        println!("{}✓ Everything looks good{}", colors::GREEN, colors::RESET);
     } else {
-        println!("{}{}{}", colors::DIM, "─".repeat(50), colors::RESET);
-        print_summary(passed, failed, total_requests);
+        println!(
+            "{}Summary:{} {}{} failed{}, {}{} warnings{}",
+            colors::BOLD,
+            colors::RESET,
+            colors::RED,
+            failures,
+            colors::RESET,
+            colors::YELLOW,
+            warnings,
+            colors::RESET
+        );
     }
+    println!();
 
-    // Exit code: 0 if all passed, 1 if any failed
-    if failed > 0 { 1 } else { 0 }
+    if failures > 0 { 1 } else { 0 }
 }
 
 fn load_collection(path: &str) -> Result<Collection, String> {
@@ -242,6 +2349,7 @@ fn load_collection(path: &str) -> Result<Collection, String> {
         hcl::from_str(&content).map_err(|e| format!("Failed to parse HCL: {}", e))?;
 
     let mut requests = HashMap::new();
+    let mut ws_scenarios = HashMap::new();
 
     for block in body.blocks() {
         if block.identifier() == "request"
@@ -251,10 +2359,18 @@ fn load_collection(path: &str) -> Result<Collection, String> {
                 hcl::from_body(block.body().clone())
                     .map_err(|e| format!("Failed to parse request '{}': {}", label.as_str(), e))?;
             requests.insert(label.as_str().to_string(), config);
+        } else if block.identifier() == "websocket"
+            && let Some(label) = block.labels().first()
+        {
+            let config: crate::domain::collection::WsScenarioConfig =
+                hcl::from_body(block.body().clone()).map_err(|e| {
+                    format!("Failed to parse websocket '{}': {}", label.as_str(), e)
+                })?;
+            ws_scenarios.insert(label.as_str().to_string(), config);
         }
     }
 
-    if requests.is_empty() {
+    if requests.is_empty() && ws_scenarios.is_empty() {
         return Err("No requests found in collection".to_string());
     }
 
@@ -264,23 +2380,29 @@ fn load_collection(path: &str) -> Result<Collection, String> {
         .unwrap_or("collection")
         .to_string();
 
-    Ok(Collection { name, requests })
+    Ok(Collection {
+        name,
+        requests,
+        ws_scenarios,
+        description: crate::domain::collection::read_description(path, &body),
+        variables: crate::domain::collection::read_variables(&body)?,
+        snapshots: crate::domain::snapshot::load(path)?,
+    })
 }
 
-fn load_environment(path: &str) -> Result<HashMap<String, String>, String> {
+/// Environments in an .hcl file, without the built-in "None"
+fn read_environments(path: &str) -> Result<Vec<Environment>, String> {
+    // load_from_file writes a sample file when there is none
+    if !Path::new(path).is_file() {
+        return Err(format!("File not found: {}", path));
+    }
     let envs = Environment::load_from_file(path)
         .map_err(|e| format!("Failed to load environment: {}", e))?;
+    Ok(envs.into_iter().filter(|e| e.name != "None").collect())
+}
 
-    // Use the first non-"None" environment, or merge all
-    let mut vars = HashMap::new();
-    for env in envs {
-        if env.name != "None" {
-            vars.extend(env.variables);
-            break; // Use first environment found
-        }
-    }
-
-    Ok(vars)
+fn first_environment(path: &str) -> Result<Option<Environment>, String> {
+    Ok(read_environments(path)?.into_iter().next())
 }
 
 fn print_result(result: &RunResult, verbose: bool) {
@@ -369,37 +2491,3 @@ fn print_summary(passed: usize, failed: usize, total: usize) {
 
     println!();
 }
-
-fn print_json_results(collection_name: &str, results: &[RunResult], passed: usize, failed: usize) {
-    let results_json: Vec<serde_json::Value> = results
-        .iter()
-        .map(|r| {
-            serde_json::json!({
-                "name": r.name,
-                "method": r.method,
-                "url": r.url,
-                "status": r.status,
-                "latency_ms": r.latency_ms,
-                "expected_status": r.expected_status,
-                "passed": r.passed,
-                "error": r.error,
-                "tests": r.tests.iter().map(|(name, passed)| {
-                    serde_json::json!({"name": name, "passed": passed})
-                }).collect::<Vec<_>>()
-            })
-        })
-        .collect();
-
-    let output = serde_json::json!({
-        "collection": collection_name,
-        "total": results.len(),
-        "passed": passed,
-        "failed": failed,
-        "results": results_json
-    });
-
-    println!(
-        "{}",
-        serde_json::to_string_pretty(&output).unwrap_or_default()
-    );
-}
@@ -0,0 +1,325 @@
+// A small Tera-like template language for code generators:
+//   {{ url | escape_double }}              values, with filters
+//   {% for h in headers %}...{% endfor %}  loops (loop.first, loop.last, loop.index)
+//   {% if body_type == "form" %}...{% elif body %}...{% else %}...{% endif %}
+//   {# comment #}
+// A `{% %}` or `{# #}` tag alone on its line takes the whole line with it, so templates
+// can be indented and laid out like the code they produce.
+use serde_json::Value;
+
+#[derive(Clone, Debug)]
+enum Token {
+    Text(String),
+    Expr(String),
+    Tag(String),
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Text(String),
+    Expr(String),
+    For {
+        var: String,
+        source: String,
+        body: Vec<Node>,
+    },
+    If {
+        branches: Vec<(String, Vec<Node>)>,
+        otherwise: Vec<Node>,
+    },
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    // Whether `rest` starts at the beginning of a line
+    let mut at_line_start = true;
+
+    loop {
+        let start = ["{{", "{%", "{#"]
+            .iter()
+            .filter_map(|open| rest.find(open))
+            .min();
+        let Some(start) = start else {
+            if !rest.is_empty() {
+                tokens.push(Token::Text(rest.to_string()));
+            }
+            break;
+        };
+
+        let mut text = rest[..start].to_string();
+        let open = &rest[start..start + 2];
+        let close = match open {
+            "{{" => "}}",
+            "{%" => "%}",
+            _ => "#}",
+        };
+        let after_open = &rest[start + 2..];
+        let end = after_open
+            .find(close)
+            .ok_or_else(|| format!("Unclosed '{}'", open))?;
+        let inner = after_open[..end].trim().to_string();
+        let mut next = &after_open[end + 2..];
+
+        let mut standalone = false;
+        if open != "{{" {
+            let line_start = text.rfind('\n').map(|i| i + 1);
+            let leading_blank = text[line_start.unwrap_or(0)..].trim().is_empty()
+                && (line_start.is_some() || at_line_start);
+            let line_end = next.find('\n');
+            let trailing = &next[..line_end.unwrap_or(next.len())];
+            if leading_blank && trailing.trim().is_empty() {
+                text.truncate(line_start.unwrap_or(0));
+                next = &next[line_end.map(|i| i + 1).unwrap_or(next.len())..];
+                standalone = true;
+            }
+        }
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(text));
+        }
+        match open {
+            "{{" => tokens.push(Token::Expr(inner)),
+            "{%" => tokens.push(Token::Tag(inner)),
+            _ => {}
+        }
+        at_line_start = standalone;
+        rest = next;
+    }
+    Ok(tokens)
+}
+
+/// Parses nodes until one of `until` tags (returned) or the end of input
+fn parse(
+    tokens: &[Token],
+    pos: &mut usize,
+    until: &[&str],
+) -> Result<(Vec<Node>, Option<String>), String> {
+    let mut nodes = Vec::new();
+    while *pos < tokens.len() {
+        let token = tokens[*pos].clone();
+        *pos += 1;
+        match token {
+            Token::Text(t) => nodes.push(Node::Text(t)),
+            Token::Expr(e) => nodes.push(Node::Expr(e)),
+            Token::Tag(tag) => {
+                let keyword = tag.split_whitespace().next().unwrap_or("");
+                if until.contains(&keyword) {
+                    return Ok((nodes, Some(tag)));
+                }
+                match keyword {
+                    "for" => {
+                        let parts: Vec<&str> = tag.split_whitespace().collect();
+                        let [_, var, "in", source] = parts.as_slice() else {
+                            return Err(format!("Expected 'for x in list', got '{}'", tag));
+                        };
+                        let (body, end) = parse(tokens, pos, &["endfor"])?;
+                        if end.is_none() {
+                            return Err(format!("Missing endfor for '{}'", tag));
+                        }
+                        nodes.push(Node::For {
+                            var: var.to_string(),
+                            source: source.to_string(),
+                            body,
+                        });
+                    }
+                    "if" => {
+                        let mut branches = Vec::new();
+                        let mut condition = tag["if".len()..].trim().to_string();
+                        let mut otherwise = Vec::new();
+                        loop {
+                            let (body, end) = parse(tokens, pos, &["elif", "else", "endif"])?;
+                            branches.push((condition.clone(), body));
+                            let end = end.ok_or_else(|| format!("Missing endif for '{}'", tag))?;
+                            if let Some(cond) = end.strip_prefix("elif") {
+                                condition = cond.trim().to_string();
+                            } else if end == "else" {
+                                let (body, end) = parse(tokens, pos, &["endif"])?;
+                                if end.is_none() {
+                                    return Err(format!("Missing endif for '{}'", tag));
+                                }
+                                otherwise = body;
+                                break;
+                            } else {
+                                break;
+                            }
+                        }
+                        nodes.push(Node::If {
+                            branches,
+                            otherwise,
+                        });
+                    }
+                    _ => return Err(format!("Unexpected tag '{}'", tag)),
+                }
+            }
+        }
+    }
+    Ok((nodes, None))
+}
+
+struct Scope<'a> {
+    root: &'a Value,
+    locals: Vec<(String, Value)>,
+}
+
+impl Scope<'_> {
+    fn lookup(&self, path: &str) -> Value {
+        let mut segments = path.split('.');
+        let first = segments.next().unwrap_or("");
+        let mut value = self
+            .locals
+            .iter()
+            .rev()
+            .find(|(name, _)| name == first)
+            .map(|(_, v)| v)
+            .or_else(|| self.root.get(first))
+            .unwrap_or(&Value::Null);
+        for segment in segments {
+            value = value.get(segment).unwrap_or(&Value::Null);
+        }
+        value.clone()
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn apply_filter(name: &str, text: String) -> Result<String, String> {
+    Ok(match name {
+        "upper" => text.to_uppercase(),
+        "lower" => text.to_lowercase(),
+        "capitalize" => {
+            let lower = text.to_lowercase();
+            let mut chars = lower.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        }
+        // Inside "..." in C-like languages
+        "escape_double" => text
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+            .replace('\t', "\\t"),
+        // Inside '...' in Python, PHP, JS
+        "escape_single" => text.replace('\\', "\\\\").replace('\'', "\\'"),
+        // A complete JSON string literal, quotes included
+        "json" => Value::String(text).to_string(),
+        // Inside a Go `raw string`
+        "go_raw" => text.replace('`', "` + \"`\" + `"),
+        // A complete POSIX shell word
+        "shell" => format!("'{}'", text.replace('\'', "'\\''")),
+        other => return Err(format!("Unknown filter '{}'", other)),
+    })
+}
+
+fn eval_expr(expr: &str, scope: &Scope) -> Result<String, String> {
+    let mut parts = expr.split('|').map(str::trim);
+    let path = parts.next().unwrap_or("");
+    let mut text = as_text(&scope.lookup(path));
+    for filter in parts {
+        text = apply_filter(filter, text)?;
+    }
+    Ok(text)
+}
+
+/// `path`, `not path`, `path == "text"`, `path != "text"`
+fn eval_condition(condition: &str, scope: &Scope) -> Result<bool, String> {
+    if let Some(inner) = condition.strip_prefix("not ") {
+        return Ok(!eval_condition(inner.trim(), scope)?);
+    }
+    for (op, equal) in [("==", true), ("!=", false)] {
+        if let Some((left, right)) = condition.split_once(op) {
+            let right = right.trim();
+            let literal = right
+                .strip_prefix('"')
+                .and_then(|r| r.strip_suffix('"'))
+                .ok_or_else(|| format!("Expected a \"string\" after {} in '{}'", op, condition))?;
+            return Ok((as_text(&scope.lookup(left.trim())) == literal) == equal);
+        }
+    }
+    Ok(truthy(&scope.lookup(condition)))
+}
+
+fn render_nodes(nodes: &[Node], scope: &mut Scope, out: &mut String) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(t) => out.push_str(t),
+            Node::Expr(e) => out.push_str(&eval_expr(e, scope)?),
+            Node::For { var, source, body } => {
+                let items = match scope.lookup(source) {
+                    Value::Array(items) => items,
+                    Value::Null => Vec::new(),
+                    _ => return Err(format!("'{}' is not a list", source)),
+                };
+                let count = items.len();
+                for (i, item) in items.into_iter().enumerate() {
+                    scope.locals.push((var.clone(), item));
+                    scope.locals.push((
+                        "loop".to_string(),
+                        serde_json::json!({
+                            "index": i + 1,
+                            "first": i == 0,
+                            "last": i + 1 == count,
+                        }),
+                    ));
+                    let result = render_nodes(body, scope, out);
+                    scope.locals.truncate(scope.locals.len() - 2);
+                    result?;
+                }
+            }
+            Node::If {
+                branches,
+                otherwise,
+            } => {
+                let mut taken = false;
+                for (condition, body) in branches {
+                    if eval_condition(condition, scope)? {
+                        render_nodes(body, scope, out)?;
+                        taken = true;
+                        break;
+                    }
+                }
+                if !taken {
+                    render_nodes(otherwise, scope, out)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Renders `template` against `context` (a JSON object)
+pub fn render(template: &str, context: &Value) -> Result<String, String> {
+    let tokens = tokenize(template)?;
+    let mut pos = 0;
+    let (nodes, stray) = parse(&tokens, &mut pos, &["endfor", "endif", "elif", "else"])?;
+    if let Some(tag) = stray {
+        return Err(format!("Unexpected tag '{}'", tag));
+    }
+    let mut scope = Scope {
+        root: context,
+        locals: Vec::new(),
+    };
+    let mut out = String::new();
+    render_nodes(&nodes, &mut scope, &mut out)?;
+    Ok(out)
+}
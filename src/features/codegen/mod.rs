@@ -0,0 +1,117 @@
+// Code generators: cURL is built natively, every other target is a template. Users add
+// their own (Kotlin, Swift, axios, ...) by dropping `<id>.tmpl` files into `codegen/`.
+pub mod engine;
+
+use serde_json::Value;
+
+/// Where user templates live, relative to the working directory
+pub const CODEGEN_DIR: &str = "codegen";
+
+/// Id of the target that uses the native curl builder instead of a template
+pub const CURL_TARGET: &str = "curl";
+
+const BUILTIN_TEMPLATES: [(&str, &str); 8] = [
+    ("python", include_str!("templates/python.tmpl")),
+    ("javascript", include_str!("templates/javascript.tmpl")),
+    ("go", include_str!("templates/go.tmpl")),
+    ("rust", include_str!("templates/rust.tmpl")),
+    ("ruby", include_str!("templates/ruby.tmpl")),
+    ("php", include_str!("templates/php.tmpl")),
+    ("csharp", include_str!("templates/csharp.tmpl")),
+    ("httpie", include_str!("templates/httpie.tmpl")),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    /// File stem, used by `:codegen <id>`
+    pub id: String,
+    /// Shown in the picker
+    pub name: String,
+    /// None for the native cURL generator
+    pub template: Option<String>,
+    /// Shipped with PostDad rather than loaded from `codegen/`
+    pub builtin: bool,
+}
+
+impl Target {
+    fn from_template(id: &str, template: &str, builtin: bool) -> Self {
+        Target {
+            id: id.to_string(),
+            name: template_name(template).unwrap_or_else(|| id.to_string()),
+            template: Some(template.to_string()),
+            builtin,
+        }
+    }
+
+    /// Picker filter: every word of `query` appears in the id or name
+    pub fn matches(&self, query: &str) -> bool {
+        let haystack = format!("{} {}", self.id, self.name).to_lowercase();
+        query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| haystack.contains(word))
+    }
+}
+
+/// The display name from a leading `{# name: Kotlin (OkHttp) #}` comment
+fn template_name(template: &str) -> Option<String> {
+    let first = template.lines().next()?.trim();
+    let inner = first.strip_prefix("{#")?.strip_suffix("#}")?.trim();
+    let name = inner.strip_prefix("name:")?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// cURL, the built-in templates, then the `*.tmpl` files in `dir` sorted by id.
+/// A user template with a built-in's id replaces it.
+pub fn load_targets(dir: &str) -> Vec<Target> {
+    let mut targets = vec![Target {
+        id: CURL_TARGET.to_string(),
+        name: "cURL".to_string(),
+        template: None,
+        builtin: true,
+    }];
+    targets.extend(
+        BUILTIN_TEMPLATES
+            .iter()
+            .map(|(id, template)| Target::from_template(id, template, true)),
+    );
+
+    let mut custom: Vec<Target> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "tmpl"))
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_string_lossy().to_lowercase();
+            let template = std::fs::read_to_string(&path).ok()?;
+            Some(Target::from_template(&id, &template, false))
+        })
+        .collect();
+    custom.sort_by(|a, b| a.id.cmp(&b.id));
+
+    for target in custom {
+        match targets.iter_mut().find(|t| t.id == target.id) {
+            Some(existing) => *existing = target,
+            None => targets.push(target),
+        }
+    }
+    targets
+}
+
+/// Looks a target up by id or display name, ignoring case
+pub fn find_target<'a>(targets: &'a [Target], key: &str) -> Option<&'a Target> {
+    let key = key.trim().to_lowercase();
+    targets
+        .iter()
+        .find(|t| t.id == key || t.name.to_lowercase() == key)
+}
+
+/// Renders a template target against a request context (see `App::codegen_context`)
+pub fn render_target(target: &Target, context: &Value) -> Result<String, String> {
+    let template = target
+        .template
+        .as_deref()
+        .ok_or_else(|| format!("'{}' has no template", target.id))?;
+    engine::render(template, context).map_err(|e| format!("{}: {}", target.id, e))
+}
@@ -0,0 +1,437 @@
+// Collection diffs: two versions of a collection file, on disk or at a git
+// revision, compared request by request for reviewing edits before a sync.
+use crate::domain::collection::{Collection, RequestConfig};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tone {
+    Added,
+    Removed,
+    Changed,
+    Plain,
+}
+
+/// One line of the readable diff, coloured by its tone
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffLine {
+    pub tone: Tone,
+    pub text: String,
+}
+
+/// A field of a request (`url`, `header Accept`, `body`, ...) that differs
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestDiff {
+    pub name: String,
+    /// Added, Removed or Changed
+    pub tone: Tone,
+    /// Every field for added and removed requests, only the differences otherwise
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub old: String,
+    pub new: String,
+    /// The collection's own description and variables
+    pub collection: Vec<FieldChange>,
+    pub requests: Vec<RequestDiff>,
+}
+
+fn sorted<V: Clone>(map: &std::collections::HashMap<String, V>) -> Vec<(String, V)> {
+    let mut entries: Vec<(String, V)> = map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries
+}
+
+/// A request as named fields in display order, empty ones left out
+fn fields(req: &RequestConfig) -> Vec<(String, String)> {
+    let mut fields = vec![
+        ("method".to_string(), req.method.clone()),
+        ("url".to_string(), req.url.clone()),
+    ];
+    let mut push = |name: String, value: Option<String>| {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            fields.push((name, value));
+        }
+    };
+    for (key, value) in req.headers.as_ref().map(sorted).unwrap_or_default() {
+        push(format!("header {}", key), Some(value));
+    }
+    for (key, value) in sorted(&req.disabled_headers) {
+        push(format!("disabled header {}", key), Some(value));
+    }
+    if !req.disabled_default_headers.is_empty() {
+        push(
+            "skipped default headers".to_string(),
+            Some(req.disabled_default_headers.join(", ")),
+        );
+    }
+    for (key, value) in sorted(&req.path_params) {
+        push(format!("path param {}", key), Some(value));
+    }
+    for (key, value) in sorted(&req.variables) {
+        push(format!("variable {}", key), Some(value));
+    }
+    if !req.tags.is_empty() {
+        push("tags".to_string(), Some(req.tags.join(", ")));
+    }
+    push("body type".to_string(), req.body_type.clone());
+    push("treat response as".to_string(), req.treat_as.clone());
+    push("body".to_string(), req.body.clone());
+    for (key, value, is_file) in req.form_data.iter().flatten() {
+        let value = if *is_file {
+            format!("@{}", value)
+        } else {
+            value.clone()
+        };
+        push(format!("form {}", key), Some(value));
+    }
+    push("gRPC method".to_string(), req.grpc_service.clone());
+    push("proto file".to_string(), req.grpc_proto_path.clone());
+    push("GraphQL query".to_string(), req.graphql_query.clone());
+    push(
+        "GraphQL variables".to_string(),
+        req.graphql_variables.clone(),
+    );
+    for (key, value) in req.extract.as_ref().map(sorted).unwrap_or_default() {
+        push(format!("extract {}", key), Some(value));
+    }
+    push(
+        "expected status".to_string(),
+        req.expected_status.map(|s| s.to_string()),
+    );
+    push(
+        "timeout".to_string(),
+        req.timeout_ms.map(|ms| format!("{}ms", ms)),
+    );
+    push("stress stages".to_string(), req.stress_stages.clone());
+    push(
+        "pre-request script".to_string(),
+        req.pre_request_script.clone(),
+    );
+    push(
+        "post-request script".to_string(),
+        req.post_request_script.clone(),
+    );
+    push("description".to_string(), req.description.clone());
+    fields
+}
+
+/// Fields that differ, in the old request's order with new ones after
+fn compare(old: &[(String, String)], new: &[(String, String)]) -> Vec<FieldChange> {
+    let find = |fields: &[(String, String)], name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, v)| v.clone())
+    };
+    let mut names: Vec<&String> = old.iter().map(|(name, _)| name).collect();
+    names.extend(
+        new.iter()
+            .map(|(name, _)| name)
+            .filter(|name| !old.iter().any(|(n, _)| n == *name)),
+    );
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let before = find(old, name);
+            let after = find(new, name);
+            (before != after).then(|| FieldChange {
+                field: name.clone(),
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+pub fn diff(old: &Collection, new: &Collection) -> Vec<RequestDiff> {
+    let mut names: Vec<&String> = old.requests.keys().chain(new.requests.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let before = old.requests.get(name).map(fields).unwrap_or_default();
+            let after = new.requests.get(name).map(fields).unwrap_or_default();
+            let tone = match (
+                old.requests.contains_key(name),
+                new.requests.contains_key(name),
+            ) {
+                (false, _) => Tone::Added,
+                (_, false) => Tone::Removed,
+                _ => Tone::Changed,
+            };
+            let changes = compare(&before, &after);
+            (!changes.is_empty()).then(|| RequestDiff {
+                name: name.clone(),
+                tone,
+                changes,
+            })
+        })
+        .collect()
+}
+
+/// `users`, `path/to/users.hcl`, or either after `<rev>:` for the file as it
+/// was at that git revision; a bare name is looked up in `collections_dir`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Source {
+    pub rev: Option<String>,
+    pub path: PathBuf,
+}
+
+impl Source {
+    pub fn parse(spec: &str, collections_dir: &Path) -> Source {
+        let (rev, target) = match spec.split_once(':') {
+            Some((rev, target)) if !Path::new(spec).exists() && !rev.is_empty() => {
+                (Some(rev.to_string()), target)
+            }
+            _ => (None, spec),
+        };
+        let path = if target.ends_with(".hcl") || target.contains('/') {
+            PathBuf::from(target)
+        } else {
+            collections_dir.join(format!("{}.hcl", target))
+        };
+        Source { rev, path }
+    }
+
+    pub fn label(&self) -> String {
+        match &self.rev {
+            Some(rev) => format!("{}:{}", rev, self.path.display()),
+            None => self.path.display().to_string(),
+        }
+    }
+
+    /// `path` as it is now, or as it was at the revision
+    fn read(&self, path: &Path) -> Result<String, String> {
+        let Some(rev) = &self.rev else {
+            return std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e));
+        };
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        // `./` makes git take the path relative to `-C`, not the repository root
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .arg("show")
+            .arg(format!("{}:./{}", rev, file))
+            .output()
+            .map_err(|e| format!("git: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "git show {}: {}",
+                self.label(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// The collection, its description from the `.md` beside it when there is one
+    pub fn load(&self) -> Result<Collection, String> {
+        let name = self
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let content = self.read(&self.path)?;
+        let mut collection = Collection::from_hcl(&name, &content)
+            .map_err(|e| format!("{}: {}", self.label(), e))?;
+        if let Ok(md) = self.read(&self.path.with_extension("md"))
+            && !md.trim().is_empty()
+        {
+            collection.description = Some(md);
+        }
+        Ok(collection)
+    }
+}
+
+/// Compares `old` with `new`, which defaults to the working copy of a
+/// revision's file (`HEAD:users` against `users`)
+pub fn compare_sources(
+    old: &str,
+    new: Option<&str>,
+    collections_dir: &Path,
+) -> Result<Report, String> {
+    let old = Source::parse(old, collections_dir);
+    let new = match new {
+        Some(spec) => Source::parse(spec, collections_dir),
+        None if old.rev.is_some() => Source {
+            rev: None,
+            path: old.path.clone(),
+        },
+        None => {
+            return Err(
+                "Give two versions, or a git revision like HEAD:users to compare with".to_string(),
+            );
+        }
+    };
+    let (before, after) = (old.load()?, new.load()?);
+    let description = |c: &Collection| {
+        let mut fields: Vec<(String, String)> = c
+            .description
+            .clone()
+            .map(|d| vec![("description".to_string(), d)])
+            .unwrap_or_default();
+        fields.extend(
+            sorted(&c.variables)
+                .into_iter()
+                .map(|(key, value)| (format!("variable {}", key), value)),
+        );
+        fields
+    };
+    Ok(Report {
+        old: old.label(),
+        new: new.label(),
+        collection: compare(&description(&before), &description(&after)),
+        requests: diff(&before, &after),
+    })
+}
+
+fn is_multiline(change: &FieldChange) -> bool {
+    [&change.before, &change.after]
+        .into_iter()
+        .flatten()
+        .any(|v| v.contains('\n'))
+}
+
+fn field_lines(change: &FieldChange, indent: &str, lines: &mut Vec<DiffLine>) {
+    let line = |tone, text: String| DiffLine { tone, text };
+    if is_multiline(change) {
+        let (before, after) = (
+            change.before.as_deref().unwrap_or_default(),
+            change.after.as_deref().unwrap_or_default(),
+        );
+        let tone = match (&change.before, &change.after) {
+            (None, _) => Tone::Added,
+            (_, None) => Tone::Removed,
+            _ => Tone::Changed,
+        };
+        lines.push(line(tone, format!("{}{}:", indent, change.field)));
+        for op in similar::TextDiff::from_lines(before, after).iter_all_changes() {
+            let (tone, sign) = match op.tag() {
+                similar::ChangeTag::Delete => (Tone::Removed, '-'),
+                similar::ChangeTag::Insert => (Tone::Added, '+'),
+                similar::ChangeTag::Equal => (Tone::Plain, ' '),
+            };
+            lines.push(line(
+                tone,
+                format!("{}  {} {}", indent, sign, op.value().trim_end_matches('\n')),
+            ));
+        }
+        return;
+    }
+    lines.push(match (&change.before, &change.after) {
+        (None, Some(after)) => line(
+            Tone::Added,
+            format!("{}+ {}: {}", indent, change.field, after),
+        ),
+        (Some(before), None) => line(
+            Tone::Removed,
+            format!("{}- {}: {}", indent, change.field, before),
+        ),
+        (before, after) => line(
+            Tone::Changed,
+            format!(
+                "{}~ {}: {} → {}",
+                indent,
+                change.field,
+                before.as_deref().unwrap_or_default(),
+                after.as_deref().unwrap_or_default()
+            ),
+        ),
+    });
+}
+
+impl Report {
+    pub fn is_empty(&self) -> bool {
+        self.collection.is_empty() && self.requests.is_empty()
+    }
+
+    /// `2 added, 1 removed, 3 changed`
+    pub fn summary(&self) -> String {
+        let count = |tone| self.requests.iter().filter(|r| r.tone == tone).count();
+        if self.is_empty() {
+            return "no changes".to_string();
+        }
+        format!(
+            "{} added, {} removed, {} changed",
+            count(Tone::Added),
+            count(Tone::Removed),
+            count(Tone::Changed)
+        )
+    }
+
+    /// Added requests with their method and URL, removed ones likewise, and each
+    /// changed one with the fields that differ (multi-line ones line by line)
+    pub fn lines(&self) -> Vec<DiffLine> {
+        let mut lines = Vec::new();
+        for change in &self.collection {
+            field_lines(change, "", &mut lines);
+        }
+        for request in &self.requests {
+            if !lines.is_empty() {
+                lines.push(DiffLine {
+                    tone: Tone::Plain,
+                    text: String::new(),
+                });
+            }
+            let value = |field: &str| {
+                request
+                    .changes
+                    .iter()
+                    .find(|c| c.field == field)
+                    .and_then(|c| c.after.clone().or_else(|| c.before.clone()))
+                    .unwrap_or_default()
+            };
+            match request.tone {
+                Tone::Added | Tone::Removed => {
+                    let sign = if request.tone == Tone::Added {
+                        '+'
+                    } else {
+                        '-'
+                    };
+                    lines.push(DiffLine {
+                        tone: request.tone,
+                        text: format!(
+                            "{} {}  {} {}",
+                            sign,
+                            request.name,
+                            value("method"),
+                            value("url")
+                        ),
+                    });
+                    if request.tone == Tone::Added {
+                        for change in &request.changes {
+                            if change.field != "method" && change.field != "url" {
+                                field_lines(change, "    ", &mut lines);
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    lines.push(DiffLine {
+                        tone: Tone::Changed,
+                        text: format!("~ {}", request.name),
+                    });
+                    for change in &request.changes {
+                        field_lines(change, "    ", &mut lines);
+                    }
+                }
+            }
+        }
+        lines
+    }
+}
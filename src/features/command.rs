@@ -0,0 +1,468 @@
+// The `:` command language: the command table, quoting-aware tokenizing and Tab
+// completion. Running a command stays in the handler, which owns the App.
+
+pub const HISTORY_FILE: &str = "command_history.json";
+pub const HISTORY_LIMIT: usize = 200;
+
+pub const METHODS: &[&str] = &["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub desc: &'static str,
+}
+
+const fn spec(name: &'static str, usage: &'static str, desc: &'static str) -> CommandSpec {
+    CommandSpec { name, usage, desc }
+}
+
+/// Every command, in the order Tab offers them
+pub const COMMANDS: &[CommandSpec] = &[
+    spec(
+        "send",
+        "send [all | 1,3-4]",
+        "Send the current request, or several tabs at once",
+    ),
+    spec("method", "method <GET|POST|...>", "Set the request method"),
+    spec(
+        "header",
+        "header add <Name>: <value> | rm <Name> | clear | off <Name> | on <Name>",
+        "Edit request headers, or leave a default one out",
+    ),
+    spec(
+        "env",
+        "env [use <name> | list]",
+        "Show or switch environment",
+    ),
+    spec(
+        "open",
+        "open <collection>/<request>",
+        "Load a saved request into the tab",
+    ),
+    spec(
+        "run",
+        "run <collection> [--tags a,b]",
+        "Run a collection in the runner, or only its tagged requests",
+    ),
+    spec(
+        "tag",
+        "tag [add|rm <tags> | filter [tags] | clear]",
+        "Tag the request, or show only requests with some tags",
+    ),
+    spec(
+        "stress",
+        "stress [collection]",
+        "Stress test the current request, or a whole collection",
+    ),
+    spec("new", "new", "Open a new tab"),
+    spec("close", "close", "Close the current tab"),
+    spec("rename", "rename <name>", "Rename the current tab"),
+    spec(
+        "saveas",
+        "saveas <file>",
+        "Save the response body to a file",
+    ),
+    spec(
+        "describe",
+        "describe [collection]",
+        "Edit the request's or a collection's description",
+    ),
+    spec(
+        "snapshot",
+        "snapshot [save|check|ignore <path>|unignore <path>|delete]",
+        "Save the response as the request's expected one",
+    ),
+    spec(
+        "coldiff",
+        "coldiff <old> [new]",
+        "Diff collection versions: files, names, or git rev:name",
+    ),
+    spec(
+        "vars",
+        "vars [env]",
+        "Resolved variables, or with env where the environment's are used",
+    ),
+    spec(
+        "dedupe",
+        "dedupe [collection]",
+        "Requests with the same method and URL: compare, merge or delete",
+    ),
+    spec(
+        "export",
+        "export history|run [file] | responses [dir]",
+        "History or the last run to CSV/JSON, or the run's responses as files",
+    ),
+    spec(
+        "logs",
+        "logs [text]",
+        "Log of every request, script and run, with request ids",
+    ),
+    spec(
+        "trace",
+        "trace [on|off|b3 on|off|link <url>|copy|open]",
+        "W3C traceparent on every request, and the link to its trace",
+    ),
+    spec(
+        "trend",
+        "trend [collection/request]",
+        "Latency of a saved request over time, with percentiles",
+    ),
+    spec(
+        "dashboard",
+        "dashboard [seconds]",
+        "Health board: requests tagged health, checked on an interval",
+    ),
+    spec(
+        "protos",
+        "protos",
+        "Proto roots and import paths gRPC requests resolve against",
+    ),
+    spec(
+        "docs",
+        "docs [collection] [split] [examples] [out <dir>] [template <file>]",
+        "Generate Markdown and HTML docs",
+    ),
+    spec(
+        "theme",
+        "theme [name]",
+        "Switch theme, or pick one with a preview",
+    ),
+    spec("zen", "zen", "Toggle zen mode"),
+    spec("range", "range <spec | off>", "Request a byte range"),
+    spec("resume", "resume <file>", "Resume a download"),
+    spec("compare", "compare", "Re-send and diff the response"),
+    spec("csv", "csv [cols] [> file]", "Export JSON array as CSV"),
+    spec("tsv", "tsv [cols] [> file]", "Export JSON array as TSV"),
+    spec(
+        "profile",
+        "profile <name | off>",
+        "Switch workspace profile",
+    ),
+    spec(
+        "workspace",
+        "workspace [list | <name> | <dir>]",
+        "Show or switch workspace",
+    ),
+    spec(
+        "history",
+        "history [query | limit <n>]",
+        "Browse history (GET 4xx users)",
+    ),
+    spec(
+        "encrypt",
+        "encrypt [on | keyring | off]",
+        "Encrypt history and cookies",
+    ),
+    spec("unlock", "unlock", "Enter the encryption passphrase"),
+    spec(
+        "bundle",
+        "bundle export [file] [secrets] | import <file>",
+        "Share collections, envs and mocks",
+    ),
+    spec(
+        "sync",
+        "sync [push|pull|status|link <id>]",
+        "Sync collections with a gist",
+    ),
+    spec(
+        "storage",
+        "storage [push|pull|status]",
+        "Sync the workspace with remote storage",
+    ),
+    spec(
+        "secret",
+        "secret <value>",
+        "Copy a value encrypted for envs",
+    ),
+    spec(
+        "pool",
+        "pool [on|off|idle|timeout|dns|fresh]",
+        "Connection pool settings",
+    ),
+    spec("flush", "flush", "Drop pooled connections"),
+    spec(
+        "ratelimit",
+        "ratelimit [run|host [name]] <rps|off>",
+        "Pace runs, stress and sentinel",
+    ),
+    spec("curl", "curl [oneline] [cmd]", "Copy as cURL"),
+    spec("import-curl", "import-curl <file>", "Import cURL commands"),
+    spec("codegen", "codegen [target]", "Copy code for a target"),
+    spec(
+        "paginate",
+        "paginate [next <path>|cursor <header> [param]|page <param> [start] [step]|items|more|max|show|off]",
+        "Fetch every page, or set how",
+    ),
+    spec(
+        "timeout",
+        "timeout [total|connect|read] <ms|5s|off>",
+        "Show or set the request's timeouts",
+    ),
+    spec(
+        "snippet",
+        "snippet [pre|post] [name] | save [pre|post] <name> | rm <name>",
+        "Insert or save a script snippet",
+    ),
+    spec("flat", "flat", "Toggle flat JSON paths"),
+    spec("jp", "jp <path>", "JSONPath query"),
+    spec("chain", "chain <variable>", "Chain the JSONPath result"),
+    spec(
+        "find",
+        "find [query]",
+        "Fuzzy find requests, tabs, history, envs",
+    ),
+    spec("timeline", "timeline", "Request timeline"),
+    spec("ndjson", "ndjson", "NDJSON response, one line per record"),
+    spec(
+        "wsexport",
+        "wsexport [file]",
+        "Save the WebSocket session with timings",
+    ),
+    spec(
+        "wsreplay",
+        "wsreplay [file | stop]",
+        "Re-send a WebSocket session's messages",
+    ),
+    spec(
+        "treat",
+        "treat <json|xml|html|text|binary|auto>",
+        "Render the response as another type",
+    ),
+    spec(
+        "hex",
+        "hex [request]",
+        "Hex view of the response or the sent request",
+    ),
+    spec(
+        "wire",
+        "wire [sent]",
+        "The raw request before sending, or as last sent",
+    ),
+    spec(
+        "fuzz",
+        "fuzz [rate | stop | report]",
+        "Send mutated variants, report anomalies",
+    ),
+    spec("notifications", "notifications", "Notification history"),
+    spec(
+        "savetabs",
+        "savetabs <name>",
+        "Save open tabs as a collection",
+    ),
+    spec("keymap", "keymap [reload]", "Show remappable keys"),
+    spec("doctor", "doctor", "Check terminal, tools and workspace"),
+    spec(
+        "audit",
+        "audit",
+        "Security headers, cookies, CORS and TLS of the response",
+    ),
+    spec("quit", "quit", "Quit PostDad"),
+];
+
+/// Splits on whitespace, keeping "double" or 'single' quoted runs together
+pub fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut in_token = false;
+    for c in input.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_token = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if in_token {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// `X-Foo: bar`, `X-Foo bar` or `X-Foo=bar` -> ("X-Foo", "bar")
+pub fn parse_header(text: &str) -> Option<(String, String)> {
+    let text = text.trim();
+    let split = text
+        .find(':')
+        .or_else(|| text.find('='))
+        .map(|i| (&text[..i], &text[i + 1..]))
+        .or_else(|| text.split_once(char::is_whitespace))?;
+    let name = split.0.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((name.to_string(), split.1.trim().to_string()))
+}
+
+/// What Tab can offer besides fixed keywords, gathered from the App
+#[derive(Clone, Debug, Default)]
+pub struct Completions {
+    pub environments: Vec<String>,
+    /// `collection/request`
+    pub requests: Vec<String>,
+    pub collections: Vec<String>,
+    pub headers: Vec<String>,
+    /// Workspace and environment default headers, for `header off|on`
+    pub default_headers: Vec<String>,
+    pub themes: Vec<String>,
+    pub codegen_targets: Vec<String>,
+    pub snippets: Vec<String>,
+    /// Every tag on a saved request, lowercased
+    pub tags: Vec<String>,
+    pub profiles: Vec<String>,
+    pub workspaces: Vec<String>,
+}
+
+/// Every full command line Tab could turn `input` into, best match first.
+/// Matching is a case-insensitive prefix on the word being typed.
+pub fn complete(input: &str, words: &Completions) -> Vec<String> {
+    let input = input.trim_start();
+    let Some((command, rest)) = input.split_once(char::is_whitespace) else {
+        let names = COMMANDS.iter().map(|c| c.name.to_string());
+        return matching(names, input, "");
+    };
+    let rest = rest.trim_start();
+
+    // Collection and request names can have spaces, the whole rest is one argument
+    match command {
+        "open" => return matching(words.requests.iter().cloned(), rest, "open "),
+        "run" => return matching(words.collections.iter().cloned(), rest, "run "),
+        "stress" => return matching(words.collections.iter().cloned(), rest, "stress "),
+        "dedupe" => return matching(words.collections.iter().cloned(), rest, "dedupe "),
+        _ => {}
+    }
+
+    let (before, partial) = match rest.rfind(char::is_whitespace) {
+        Some(i) => (&rest[..=i], &rest[i + 1..]),
+        None => ("", rest),
+    };
+    let args: Vec<&str> = before.split_whitespace().collect();
+    let keywords = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let options: Vec<String> = match (command, args.as_slice()) {
+        ("method", []) => keywords(METHODS),
+        ("header", []) => keywords(&["add", "set", "rm", "clear", "off", "on"]),
+        ("header", ["off" | "on"]) => words.default_headers.clone(),
+        ("header", ["rm" | "del" | "remove"]) => words.headers.clone(),
+        ("env", []) => {
+            let mut options = keywords(&["use", "list"]);
+            options.extend(words.environments.iter().cloned());
+            options
+        }
+        ("env", ["use"]) => words.environments.clone(),
+        ("theme", []) => words.themes.clone(),
+        ("codegen" | "gen", []) => words.codegen_targets.clone(),
+        ("paginate" | "pages", []) => keywords(&[
+            "next", "cursor", "page", "items", "more", "max", "show", "off",
+        ]),
+        ("timeout", []) => keywords(&["total", "connect", "read"]),
+        ("timeout", [_]) => keywords(&["off"]),
+        ("snippet" | "snip", []) => {
+            let mut options = keywords(&["pre", "post", "save", "rm"]);
+            options.extend(words.snippets.iter().cloned());
+            options
+        }
+        ("snippet" | "snip", ["pre" | "post"]) | ("snippet" | "snip", ["rm" | "delete"]) => {
+            words.snippets.clone()
+        }
+        ("snippet" | "snip", ["save"]) => keywords(&["pre", "post"]),
+        ("profile", []) => {
+            let mut options = words.profiles.clone();
+            options.push("off".to_string());
+            options
+        }
+        ("workspace" | "ws", []) => {
+            let mut options = keywords(&["list"]);
+            options.extend(words.workspaces.iter().cloned());
+            options
+        }
+        ("history" | "hist", []) => keywords(&["limit"]),
+        ("encrypt", []) => keywords(&["on", "keyring", "off"]),
+        ("sync", []) => keywords(&["push", "pull", "status", "link"]),
+        ("storage", []) => keywords(&["push", "pull", "status"]),
+        ("bundle", []) => keywords(&["export", "import"]),
+        ("vars", []) => keywords(&["env"]),
+        ("tag" | "tags", []) => keywords(&["add", "rm", "filter", "clear", "list"]),
+        ("tag" | "tags", ["filter" | "only" | "rm" | "remove", ..]) => words.tags.clone(),
+        ("export", []) => keywords(&["history", "run", "responses"]),
+        ("trace", []) => keywords(&["on", "off", "b3", "link", "copy", "open"]),
+        ("trace", ["b3"]) => keywords(&["on", "off"]),
+        ("pool", []) => keywords(&["on", "off", "idle", "timeout", "dns", "fresh"]),
+        ("pool", ["fresh"]) => keywords(&["on", "off"]),
+        ("ratelimit", []) => keywords(&["run", "host", "off"]),
+        ("keymap", []) => keywords(&["reload"]),
+        ("hex", []) => keywords(&["request"]),
+        ("wsreplay", []) => keywords(&["stop"]),
+        ("snapshot", []) => keywords(&["save", "check", "ignore", "unignore", "delete"]),
+        ("treat", []) => keywords(&["json", "xml", "html", "text", "binary", "auto"]),
+        ("wire", []) => keywords(&["sent"]),
+        ("fuzz", []) => keywords(&["stop", "report"]),
+        ("send", []) => keywords(&["all"]),
+        ("curl", _) => keywords(&["oneline", "cmd"]),
+        _ => Vec::new(),
+    };
+    matching(
+        options.into_iter(),
+        partial,
+        &format!("{} {}", command, before),
+    )
+}
+
+fn matching(options: impl Iterator<Item = String>, partial: &str, prefix: &str) -> Vec<String> {
+    let partial = partial.to_lowercase();
+    let mut found: Vec<String> = Vec::new();
+    for option in options {
+        if option.to_lowercase().starts_with(&partial) {
+            let line = format!("{}{}", prefix, option);
+            if !found.contains(&line) {
+                found.push(line);
+            }
+        }
+    }
+    found
+}
+
+/// Longest prefix shared by every candidate, what a single Tab fills in when several match
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+    let mut len = first.len();
+    for c in &candidates[1..] {
+        len = first
+            .char_indices()
+            .zip(c.chars())
+            .take_while(|((_, a), b)| a.eq_ignore_ascii_case(b))
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8())
+            .min(len);
+    }
+    first[..len].to_string()
+}
+
+/// Adds a command to the history, dropping an exact repeat of the previous one.
+/// `:secret` is never kept, its argument is the plaintext.
+pub fn push_history(history: &mut Vec<String>, command: &str) {
+    if command.is_empty()
+        || command.split_whitespace().next() == Some("secret")
+        || history.last().is_some_and(|last| last == command)
+    {
+        return;
+    }
+    history.push(command.to_string());
+    if history.len() > HISTORY_LIMIT {
+        history.remove(0);
+    }
+}
@@ -0,0 +1,136 @@
+use serde_json::Value;
+
+/// A JSON array of objects flattened into columns and rows
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Finds something table-shaped: the value itself if it's an array of objects,
+/// otherwise the first such array one level down (`{"data": [...]}` style envelopes).
+pub fn find_table_array(value: &Value) -> Option<&Vec<Value>> {
+    let is_table = |v: &Value| {
+        v.as_array()
+            .is_some_and(|items| !items.is_empty() && items.iter().all(Value::is_object))
+    };
+    if is_table(value) {
+        return value.as_array();
+    }
+    value
+        .as_object()?
+        .values()
+        .find(|v| is_table(v))
+        .and_then(Value::as_array)
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        // Nested objects/arrays stay as compact JSON inside the cell
+        other => other.to_string(),
+    }
+}
+
+/// Builds a table from an array of objects. Columns are the union of all keys in
+/// first-seen order, missing values are left empty.
+pub fn to_table(value: &Value) -> Result<Table, String> {
+    let items = find_table_array(value).ok_or("Response is not an array of objects")?;
+
+    let mut columns: Vec<String> = Vec::new();
+    for item in items {
+        if let Some(obj) = item.as_object() {
+            for key in obj.keys() {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+    }
+
+    let rows = items
+        .iter()
+        .map(|item| {
+            columns
+                .iter()
+                .map(|col| item.get(col).map(cell).unwrap_or_default())
+                .collect()
+        })
+        .collect();
+
+    Ok(Table { columns, rows })
+}
+
+impl Table {
+    /// Keeps only the given columns, in the given order. Unknown names are an error.
+    pub fn select(&self, wanted: &[String]) -> Result<Table, String> {
+        if wanted.is_empty() {
+            return Ok(self.clone());
+        }
+        let indexes: Vec<usize> = wanted
+            .iter()
+            .map(|name| {
+                self.columns.iter().position(|c| c == name).ok_or_else(|| {
+                    format!(
+                        "Unknown column '{}' (have: {})",
+                        name,
+                        self.columns.join(", ")
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Table {
+            columns: indexes.iter().map(|i| self.columns[*i].clone()).collect(),
+            rows: self
+                .rows
+                .iter()
+                .map(|row| indexes.iter().map(|i| row[*i].clone()).collect())
+                .collect(),
+        })
+    }
+
+    /// CSV (RFC 4180 quoting) or TSV depending on `delimiter`
+    pub fn to_delimited(&self, delimiter: char) -> String {
+        let field = |text: &str| {
+            if delimiter == '\t' {
+                // TSV has no quoting, so tabs and newlines become spaces
+                text.replace(['\t', '\n', '\r'], " ")
+            } else if text.contains([delimiter, '"', '\n', '\r']) {
+                format!("\"{}\"", text.replace('"', "\"\""))
+            } else {
+                text.to_string()
+            }
+        };
+        let line = |cells: &[String]| {
+            cells
+                .iter()
+                .map(|c| field(c))
+                .collect::<Vec<_>>()
+                .join(&delimiter.to_string())
+        };
+
+        let mut out = line(&self.columns);
+        out.push('\n');
+        for row in &self.rows {
+            out.push_str(&line(row));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Parses the `:csv` / `:tsv` argument: `[col1,col2] [> file]`
+pub fn parse_export_args(args: &str) -> (Vec<String>, Option<String>) {
+    let (cols, file) = match args.split_once('>') {
+        Some((cols, file)) => (cols, Some(file.trim().to_string())),
+        None => (args, None),
+    };
+    let columns = cols
+        .split(',')
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+    (columns, file.filter(|f| !f.is_empty()))
+}
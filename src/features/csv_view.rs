@@ -0,0 +1,221 @@
+// `text/csv` (and tab-separated) responses shown as a table: columns lined up,
+// h / l across the columns, `o` sorts by the one under the cursor, `v` copies a cell.
+use crate::features::csv_export::Table;
+use std::cmp::Ordering;
+
+/// Widest a column gets, longer cells are cut short with `…`
+pub const MAX_COLUMN_WIDTH: usize = 40;
+
+/// The field delimiter for a table content type, None for anything else
+pub fn delimiter(content_type: &str) -> Option<char> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match mime.as_str() {
+        "text/csv" | "application/csv" => Some(','),
+        "text/tab-separated-values" | "text/tsv" => Some('\t'),
+        _ => None,
+    }
+}
+
+/// Splits delimited text into records. Quoted fields (RFC 4180) may hold the
+/// delimiter, `""` and line breaks.
+fn records(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            c if quoted => field.push(c),
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                // A blank line is skipped
+                record.clear();
+            }
+            c => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+/// The first record is the header. Short rows are padded, long ones get
+/// numbered columns of their own.
+pub fn parse(text: &str, delimiter: char) -> Table {
+    let mut records = records(text, delimiter).into_iter();
+    let mut columns = records.next().unwrap_or_default();
+    let mut rows: Vec<Vec<String>> = records.collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    while columns.len() < width {
+        columns.push(format!("column {}", columns.len() + 1));
+    }
+    for row in &mut rows {
+        row.resize(columns.len(), String::new());
+    }
+    Table { columns, rows }
+}
+
+/// Numbers compare as numbers, everything else as text ignoring case. Empty
+/// cells go last.
+fn compare(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.trim(), b.trim());
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        _ => {}
+    }
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+/// A CSV response as the table viewer shows it
+#[derive(Clone, Debug)]
+pub struct CsvView {
+    pub table: Table,
+    /// Indices into `table.rows` in display order
+    pub order: Vec<usize>,
+    /// Column sorted by and whether descending
+    pub sort: Option<(usize, bool)>,
+    /// Selected row (in display order) and column
+    pub row: usize,
+    pub column: usize,
+    /// First row and column in view
+    pub top: usize,
+    pub left: usize,
+}
+
+impl CsvView {
+    pub fn new(table: Table) -> Self {
+        CsvView {
+            order: (0..table.rows.len()).collect(),
+            table,
+            sort: None,
+            row: 0,
+            column: 0,
+            top: 0,
+            left: 0,
+        }
+    }
+
+    pub fn move_row(&mut self, delta: isize) {
+        let last = self.order.len().saturating_sub(1);
+        self.row = self.row.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn move_column(&mut self, delta: isize) {
+        let last = self.table.columns.len().saturating_sub(1);
+        self.column = self.column.saturating_add_signed(delta).min(last);
+    }
+
+    /// Ascending, then descending, then back to the order received
+    pub fn cycle_sort(&mut self) {
+        if self.table.columns.is_empty() {
+            return;
+        }
+        self.sort = match self.sort {
+            Some((column, false)) if column == self.column => Some((column, true)),
+            Some((column, true)) if column == self.column => None,
+            _ => Some((self.column, false)),
+        };
+        let selected = self.order.get(self.row).copied();
+        self.order = (0..self.table.rows.len()).collect();
+        if let Some((column, descending)) = self.sort {
+            let rows = &self.table.rows;
+            // A short row's missing cells sort as empty ones
+            let cell = |row: usize| rows[row].get(column).map(String::as_str).unwrap_or("");
+            // Stable, so equal cells keep the order they came in
+            self.order.sort_by(|a, b| {
+                let ordering = compare(cell(*a), cell(*b));
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+        // The cursor stays on the same record
+        if let Some(selected) = selected {
+            self.row = self.order.iter().position(|r| *r == selected).unwrap_or(0);
+        }
+    }
+
+    /// The text under the cursor
+    pub fn cell(&self) -> Option<&str> {
+        let row = self.table.rows.get(*self.order.get(self.row)?)?;
+        row.get(self.column).map(String::as_str)
+    }
+
+    /// Rows in display order
+    pub fn rows(&self) -> impl Iterator<Item = &Vec<String>> {
+        self.order.iter().map(|i| &self.table.rows[*i])
+    }
+
+    /// Each column's width: its widest cell or header, capped
+    pub fn widths(&self) -> Vec<usize> {
+        self.table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                self.table
+                    .rows
+                    .iter()
+                    .map(|row| row.get(i).map_or(0, |cell| cell.chars().count()))
+                    // Room for the header's sort arrow
+                    .chain([name.chars().count() + 2])
+                    .max()
+                    .unwrap_or(0)
+                    .min(MAX_COLUMN_WIDTH)
+            })
+            .collect()
+    }
+
+    /// Moves `top` and `left` so the cursor is in a view of `height` rows and
+    /// `width` columns of text. Returns the range of columns that fit.
+    pub fn scroll_into_view(&mut self, height: usize, width: usize) -> std::ops::Range<usize> {
+        let height = height.max(1);
+        if self.row < self.top {
+            self.top = self.row;
+        } else if self.row >= self.top + height {
+            self.top = self.row + 1 - height;
+        }
+
+        let widths = self.widths();
+        let fits = |left: usize| {
+            let mut used = 0;
+            let mut end = left;
+            while end < widths.len() && (end == left || used + widths[end] < width) {
+                used += widths[end] + 1;
+                end += 1;
+            }
+            end
+        };
+        self.left = self.left.min(self.column);
+        while fits(self.left) <= self.column {
+            self.left += 1;
+        }
+        self.left..fits(self.left)
+    }
+}
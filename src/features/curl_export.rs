@@ -0,0 +1,143 @@
+// Builds copy-pasteable curl commands for POSIX shells and Windows cmd
+
+/// Which shell the command is meant to be pasted into
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shell {
+    /// bash, zsh, fish, ...: `\` continuations, single quotes for bodies
+    Posix,
+    /// Windows cmd.exe: `^` continuations, double quotes only
+    Cmd,
+}
+
+impl Shell {
+    /// The shell of the machine PostDad runs on
+    pub fn current() -> Shell {
+        if cfg!(windows) {
+            Shell::Cmd
+        } else {
+            Shell::Posix
+        }
+    }
+
+    /// Quotes a value so the shell passes it through unchanged. On POSIX double quotes
+    /// are used unless the value has characters the shell would still expand inside them.
+    pub fn quote(&self, value: &str) -> String {
+        match self {
+            Shell::Posix if value.contains(['"', '$', '`', '\\', '!']) => {
+                self.quote_verbatim(value)
+            }
+            Shell::Posix => format!("\"{}\"", value),
+            Shell::Cmd => cmd_quote(value),
+        }
+    }
+
+    /// Quotes a value that must reach curl byte for byte (bodies, form fields)
+    pub fn quote_verbatim(&self, value: &str) -> String {
+        match self {
+            Shell::Posix => format!("'{}'", value.replace('\'', "'\\''")),
+            Shell::Cmd => self.quote(value),
+        }
+    }
+
+    fn continuation(&self) -> &'static str {
+        match self {
+            Shell::Posix => " \\\n  ",
+            Shell::Cmd => " ^\n  ",
+        }
+    }
+}
+
+/// Double quotes for cmd.exe and the argument parsing of curl.exe: `"` doubled, which
+/// keeps cmd inside the quotes so `&|<>` stay literal, and `%` and line breaks put
+/// between quotes as `^%` and `^` plus an empty line, the only way cmd takes them
+/// literally at the prompt.
+fn cmd_quote(value: &str) -> String {
+    // Backslashes right before a quote are doubled, or curl.exe reads `\"` as a quote
+    fn quote(out: &mut String, backslashes: &mut usize, s: &str) {
+        out.push_str(&"\\".repeat(*backslashes));
+        *backslashes = 0;
+        out.push_str(s);
+    }
+    let mut out = String::from("\"");
+    let mut backslashes = 0;
+    for c in value.replace("\r\n", "\n").chars() {
+        match c {
+            '\\' => {
+                out.push('\\');
+                backslashes += 1;
+            }
+            '"' => quote(&mut out, &mut backslashes, "\"\""),
+            '%' => quote(&mut out, &mut backslashes, "\"^%\""),
+            '\n' => quote(&mut out, &mut backslashes, "\"^\n\n\""),
+            c => {
+                out.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    quote(&mut out, &mut backslashes, "\"");
+    out
+}
+
+/// A curl invocation assembled flag by flag
+#[derive(Clone, Debug)]
+pub struct CurlBuilder {
+    shell: Shell,
+    /// First line: `curl -X METHOD url`
+    head: String,
+    /// One entry per flag, each becomes its own line in multiline output
+    args: Vec<String>,
+}
+
+impl CurlBuilder {
+    pub fn new(shell: Shell, method: &str, url: &str) -> Self {
+        // Let reqwest normalise the URL (percent-encodes spaces etc. in query params)
+        let url = reqwest::Url::parse(url)
+            .map(|u| u.to_string())
+            .unwrap_or_else(|_| url.to_string());
+        CurlBuilder {
+            shell,
+            head: format!("curl -X {} {}", method, shell.quote(&url)),
+            args: Vec::new(),
+        }
+    }
+
+    /// A flag without a value, e.g. `--insecure`
+    pub fn flag(&mut self, flag: &str) -> &mut Self {
+        self.args.push(flag.to_string());
+        self
+    }
+
+    /// A flag with a value, e.g. `-H "Accept: */*"`
+    pub fn arg(&mut self, flag: &str, value: &str) -> &mut Self {
+        self.args
+            .push(format!("{} {}", flag, self.shell.quote(value)));
+        self
+    }
+
+    /// A flag whose value must not be touched by the shell, e.g. `-d '{"a": 1}'`
+    pub fn data(&mut self, flag: &str, value: &str) -> &mut Self {
+        self.args
+            .push(format!("{} {}", flag, self.shell.quote_verbatim(value)));
+        self
+    }
+
+    pub fn header(&mut self, name: &str, value: &str) -> &mut Self {
+        self.arg("-H", &format!("{}: {}", name, value))
+    }
+
+    /// Multiline with one flag per line, or everything on a single line
+    pub fn build(&self, multiline: bool) -> String {
+        let separator = if multiline {
+            self.shell.continuation()
+        } else {
+            " "
+        };
+        let mut cmd = self.head.clone();
+        for arg in &self.args {
+            cmd.push_str(separator);
+            cmd.push_str(arg);
+        }
+        cmd
+    }
+}
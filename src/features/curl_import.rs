@@ -0,0 +1,251 @@
+// Bulk import of curl commands from a file or shell history
+use crate::app::RequestTab;
+use crate::domain::collection::{Collection, RequestConfig};
+use std::collections::HashSet;
+
+/// Result of turning a pile of curl commands into requests
+#[derive(Debug, Default)]
+pub struct CurlBatch {
+    /// Named requests in the order they first appeared
+    pub requests: Vec<(String, RequestConfig)>,
+    /// Commands skipped because an identical request was already imported
+    pub duplicates: usize,
+    /// Commands that looked like curl but could not be parsed, with the reason
+    pub failed: Vec<(String, String)>,
+}
+
+impl CurlBatch {
+    pub fn summary(&self) -> String {
+        let mut text = format!("{} requests", self.requests.len());
+        if self.duplicates > 0 {
+            text.push_str(&format!(", {} duplicates skipped", self.duplicates));
+        }
+        if !self.failed.is_empty() {
+            text.push_str(&format!(", {} unparseable", self.failed.len()));
+        }
+        text
+    }
+}
+
+/// Tracks quoting while scanning, same rules as the single-command tokenizer
+#[derive(Default)]
+struct QuoteState {
+    single: bool,
+    double: bool,
+    escape: bool,
+}
+
+impl QuoteState {
+    /// Feeds one char, returns true if it is outside any quote or escape
+    fn feed(&mut self, ch: char) -> bool {
+        if self.escape {
+            self.escape = false;
+            return false;
+        }
+        match ch {
+            '\\' if !self.single => {
+                self.escape = true;
+                false
+            }
+            '\'' if !self.double => {
+                self.single = !self.single;
+                false
+            }
+            '"' if !self.single => {
+                self.double = !self.double;
+                false
+            }
+            _ => !self.single && !self.double,
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        self.single || self.double
+    }
+}
+
+/// Strips the timestamp prefixes zsh (`: 1700000000:0;`) and fish (`- cmd: `) put on
+/// history entries. Bash `#1700000000` timestamp lines are dropped entirely.
+fn strip_history_prefix(line: &str) -> Option<&str> {
+    if line.starts_with('#') {
+        return None;
+    }
+    if let Some(rest) = line.strip_prefix(": ")
+        && let Some((stamp, cmd)) = rest.split_once(';')
+        && stamp.chars().all(|c| c.is_ascii_digit() || c == ':')
+    {
+        return Some(cmd);
+    }
+    if let Some(cmd) = line.strip_prefix("- cmd: ") {
+        return Some(cmd);
+    }
+    Some(line)
+}
+
+/// Joins continuation lines (trailing `\` or an unclosed quote) into whole commands
+fn logical_lines(text: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut pending: Option<String> = None;
+
+    for raw in text.lines() {
+        let line = match &pending {
+            Some(_) => raw,
+            None => match strip_history_prefix(raw) {
+                Some(line) => line,
+                None => continue,
+            },
+        };
+        let mut current = match pending.take() {
+            Some(mut prev) => {
+                prev.push('\n');
+                prev.push_str(line);
+                prev
+            }
+            None => line.to_string(),
+        };
+
+        let mut state = QuoteState::default();
+        current.chars().for_each(|ch| {
+            state.feed(ch);
+        });
+        if state.is_open() || current.ends_with('\\') {
+            pending = Some(current);
+        } else {
+            lines.push(std::mem::take(&mut current));
+        }
+    }
+    lines.extend(pending);
+    lines
+}
+
+/// Splits a shell line on unquoted `|`, `;`, `&`, `>` and `<`, keeping the parts that
+/// are curl invocations. `cd api && curl ... | jq .` yields just the curl part.
+fn curl_segments(line: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut state = QuoteState::default();
+
+    for ch in line.chars() {
+        let unquoted = state.feed(ch);
+        if unquoted && matches!(ch, '|' | ';' | '&' | '>' | '<') {
+            segments.push(std::mem::take(&mut current));
+        } else {
+            current.push(ch);
+        }
+    }
+    segments.push(current);
+
+    segments
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| s.split_whitespace().next() == Some("curl"))
+        .collect()
+}
+
+/// Every curl command found in `text`, in order
+pub fn extract_commands(text: &str) -> Vec<String> {
+    logical_lines(text)
+        .iter()
+        .flat_map(|line| curl_segments(line))
+        .collect()
+}
+
+/// Two commands are duplicates if they would send the same request
+fn dedupe_key(config: &RequestConfig) -> String {
+    let mut headers: Vec<String> = config
+        .headers
+        .iter()
+        .flatten()
+        .map(|(k, v)| format!("{}:{}", k.to_lowercase(), v))
+        .collect();
+    headers.sort();
+    format!(
+        "{} {}\n{}\n{}\n{:?}",
+        config.method,
+        config.url,
+        headers.join("\n"),
+        config.body.as_deref().unwrap_or(""),
+        config.form_data
+    )
+}
+
+/// `GET api.example.com/users`, numbered if the name is already taken
+fn request_name(config: &RequestConfig, taken: &HashSet<String>) -> String {
+    let target = match reqwest::Url::parse(&config.url) {
+        Ok(u) => format!("{}{}", u.host_str().unwrap_or(""), u.path()),
+        Err(_) => config.url.clone(),
+    };
+    let base = format!("{} {}", config.method, target.trim_end_matches('/'));
+    let mut name = base.clone();
+    let mut n = 2;
+    while taken.contains(&name) {
+        name = format!("{} ({})", base, n);
+        n += 1;
+    }
+    name
+}
+
+/// Parses every curl command in `text` into a request, dropping duplicates
+pub fn parse_batch(text: &str) -> CurlBatch {
+    let mut batch = CurlBatch::default();
+    let mut seen = HashSet::new();
+    let mut names = HashSet::new();
+
+    for command in extract_commands(text) {
+        let mut tab = RequestTab::new();
+        if let Err(e) = tab.apply_curl(&command) {
+            batch.failed.push((command, e));
+            continue;
+        }
+        let mut config = tab.to_request_config();
+        // The collection format has no auth block, so -u becomes a header
+        if !tab.basic_auth_user.is_empty() {
+            use base64::prelude::*;
+            let credentials = format!("{}:{}", tab.basic_auth_user, tab.basic_auth_pass);
+            config.headers.get_or_insert_default().insert(
+                "Authorization".to_string(),
+                format!("Basic {}", BASE64_STANDARD.encode(credentials)),
+            );
+        }
+
+        if !seen.insert(dedupe_key(&config)) {
+            batch.duplicates += 1;
+            continue;
+        }
+        let name = request_name(&config, &names);
+        names.insert(name.clone());
+        batch.requests.push((name, config));
+    }
+    batch
+}
+
+/// Parses `text` and writes the requests to a new `{dir}/{name}.hcl`
+pub fn import_to_collection(
+    text: &str,
+    name: &str,
+    dir: &str,
+) -> Result<(CurlBatch, String), String> {
+    let batch = parse_batch(text);
+    if batch.requests.is_empty() {
+        return Err("No curl commands found".to_string());
+    }
+    let path = Collection::write_new(dir, name, &batch.requests)
+        .map_err(|e| format!("Could not write collection '{}': {}", name, e))?;
+    Ok((batch, path.display().to_string()))
+}
+
+/// Collection name for an import source: the file name without dots, e.g. `zsh_history`
+pub fn collection_name_for(source: &str) -> String {
+    if source == "-" {
+        return "curl_import".to_string();
+    }
+    let stem = std::path::Path::new(source)
+        .file_stem()
+        .map(|s| s.to_string_lossy().trim_start_matches('.').to_string())
+        .unwrap_or_default();
+    if stem.is_empty() {
+        "curl_import".to_string()
+    } else {
+        stem
+    }
+}
@@ -0,0 +1,206 @@
+// Health board: every request tagged as a health check, across collections, run
+// through the runner on an interval, one green/red tile each.
+use crate::domain::collection::Collection;
+use crate::features::runner::{self, RunResult, RunnerEvent};
+use crate::net::pool::HostOverrides;
+use crate::net::rate_limit::RateLimitSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// How many past checks a tile keeps for its strip
+pub const RECENT: usize = 20;
+
+/// Stored under `dashboard` in `config.json`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DashboardSettings {
+    /// The tag that makes a request a health check
+    pub tag: String,
+    /// Seconds between rounds
+    pub interval_secs: u64,
+}
+
+impl Default for DashboardSettings {
+    fn default() -> Self {
+        DashboardSettings {
+            tag: "health".to_string(),
+            interval_secs: 30,
+        }
+    }
+}
+
+/// Each collection cut down to its requests tagged `tag`, the ones without any left out
+pub fn health_checks(collections: &[Collection], tag: &str) -> Vec<Collection> {
+    collections
+        .iter()
+        .filter_map(|collection| {
+            let requests: HashMap<_, _> = collection
+                .requests
+                .iter()
+                .filter(|(_, r)| r.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+                .map(|(name, r)| (name.clone(), r.clone()))
+                .collect();
+            if requests.is_empty() {
+                return None;
+            }
+            Some(Collection {
+                requests,
+                ws_scenarios: HashMap::new(),
+                ..collection.clone()
+            })
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct Tile {
+    pub collection: String,
+    pub name: String,
+    pub method: String,
+    pub last: Option<RunResult>,
+    /// Unix seconds of the last check
+    pub checked_at: Option<u64>,
+    /// Pass/fail of the last `RECENT` checks, oldest first
+    pub recent: VecDeque<bool>,
+    pub checks: u64,
+    pub failures: u64,
+}
+
+impl Tile {
+    /// None until the first check comes back
+    pub fn healthy(&self) -> Option<bool> {
+        self.last.as_ref().map(|r| r.passed)
+    }
+}
+
+#[derive(Debug)]
+pub struct DashboardEvent {
+    pub collection: String,
+    pub result: RunResult,
+}
+
+pub struct Dashboard {
+    /// Collection then request name order
+    pub tiles: Vec<Tile>,
+    pub selected: usize,
+    pub stop_tx: Option<mpsc::Sender<()>>,
+}
+
+impl Dashboard {
+    pub fn new(checks: &[Collection]) -> Self {
+        let mut tiles: Vec<Tile> = checks
+            .iter()
+            .flat_map(|collection| {
+                collection.requests.iter().map(|(name, request)| Tile {
+                    collection: collection.name.clone(),
+                    name: name.clone(),
+                    method: request.method.clone(),
+                    last: None,
+                    checked_at: None,
+                    recent: VecDeque::with_capacity(RECENT),
+                    checks: 0,
+                    failures: 0,
+                })
+            })
+            .collect();
+        tiles.sort_by(|a, b| (&a.collection, &a.name).cmp(&(&b.collection, &b.name)));
+        Dashboard {
+            tiles,
+            selected: 0,
+            stop_tx: None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.stop_tx.is_some()
+    }
+
+    pub fn record(&mut self, event: DashboardEvent) {
+        let Some(tile) = self
+            .tiles
+            .iter_mut()
+            .find(|t| t.collection == event.collection && t.name == event.result.name)
+        else {
+            return;
+        };
+        tile.checks += 1;
+        if !event.result.passed {
+            tile.failures += 1;
+        }
+        if tile.recent.len() >= RECENT {
+            tile.recent.pop_front();
+        }
+        tile.recent.push_back(event.result.passed);
+        tile.checked_at = Some(crate::features::history::now());
+        tile.last = Some(event.result);
+    }
+
+    /// Up, down and not checked yet
+    pub fn summary(&self) -> (usize, usize, usize) {
+        self.tiles
+            .iter()
+            .fold((0, 0, 0), |(up, down, pending), t| match t.healthy() {
+                Some(true) => (up + 1, down, pending),
+                Some(false) => (up, down + 1, pending),
+                None => (up, down, pending + 1),
+            })
+    }
+}
+
+/// Runs every check right away, then every `interval_secs`, until `stop_rx` fires.
+/// The checks wait their turn under `limits` like any collection run.
+pub async fn run_dashboard(
+    checks: Vec<Collection>,
+    env_vars: HashMap<String, String>,
+    interval_secs: u64,
+    limits: RateLimitSettings,
+    resolve: HostOverrides,
+    tx: mpsc::Sender<DashboardEvent>,
+    mut stop_rx: mpsc::Receiver<()>,
+) {
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let round = run_round(&checks, &env_vars, &limits, &resolve, &tx);
+                tokio::select! {
+                    _ = round => {}
+                    _ = stop_rx.recv() => break,
+                }
+            }
+            _ = stop_rx.recv() => break,
+        }
+    }
+}
+
+/// One pass over the checks: the collections side by side, each in runner order
+async fn run_round(
+    checks: &[Collection],
+    env_vars: &HashMap<String, String>,
+    limits: &RateLimitSettings,
+    resolve: &HostOverrides,
+    tx: &mpsc::Sender<DashboardEvent>,
+) {
+    let runs = checks.iter().map(|collection| async move {
+        let (runner_tx, mut runner_rx) = mpsc::channel(32);
+        let forward = async {
+            while let Some(event) = runner_rx.recv().await {
+                if let RunnerEvent::RequestCompleted(result) = event {
+                    let _ = tx
+                        .send(DashboardEvent {
+                            collection: collection.name.clone(),
+                            result,
+                        })
+                        .await;
+                }
+            }
+        };
+        tokio::join!(
+            runner::run_collection(collection, env_vars, limits, resolve, runner_tx),
+            forward
+        );
+    });
+    futures_util::future::join_all(runs).await;
+}
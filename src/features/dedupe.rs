@@ -0,0 +1,230 @@
+// Near-duplicate requests across collections (`:dedupe`): same method and the same
+// URL once variables, path parameters and query values are set aside. Each group
+// is compared side by side, and a duplicate merged into the one kept or deleted.
+use crate::domain::collection::{Collection, RequestConfig};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// A saved request: its collection and its name there
+#[derive(Clone, Debug, PartialEq)]
+pub struct Member {
+    pub collection: String,
+    pub request: String,
+}
+
+impl Member {
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.collection, self.request)
+    }
+}
+
+/// Requests that look the same
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    /// `GET {{}}/users/{{}}?page`
+    pub key: String,
+    pub members: Vec<Member>,
+}
+
+/// The `:dedupe` view: one group at a time, its first member on the left and the
+/// duplicate being looked at on the right
+#[derive(Clone, Debug, Default)]
+pub struct DedupeView {
+    /// Only this collection's requests, for `:dedupe <collection>`
+    pub collection: Option<String>,
+    pub groups: Vec<Group>,
+    pub group: usize,
+    /// The right-hand member, 1.. in the group
+    pub other: usize,
+    pub scroll: u16,
+}
+
+impl DedupeView {
+    pub fn current(&self) -> Option<&Group> {
+        self.groups.get(self.group)
+    }
+
+    /// The member kept and the duplicate shown beside it
+    pub fn pair(&self) -> Option<(&Member, &Member)> {
+        let group = self.current()?;
+        Some((group.members.first()?, group.members.get(self.other)?))
+    }
+
+    /// Moves to the next (or previous) group
+    pub fn step_group(&mut self, forward: bool) {
+        let count = self.groups.len();
+        if count == 0 {
+            return;
+        }
+        self.group = if forward {
+            (self.group + 1) % count
+        } else {
+            (self.group + count - 1) % count
+        };
+        self.other = 1;
+        self.scroll = 0;
+    }
+
+    /// Shows the group's next duplicate on the right
+    pub fn step_other(&mut self) {
+        if let Some(group) = self.current() {
+            let others = group.members.len().saturating_sub(1).max(1);
+            self.other = self.other % others + 1;
+            self.scroll = 0;
+        }
+    }
+
+    /// Keeps the right-hand member instead
+    pub fn swap(&mut self) {
+        let other = self.other;
+        if let Some(group) = self.groups.get_mut(self.group)
+            && other < group.members.len()
+        {
+            group.members.swap(0, other);
+        }
+    }
+}
+
+/// `{{base_url}}/users/:id?page=2` -> `{{}}/users/{{}}?page`: variables and path
+/// parameters become `{{}}`, query values go, the rest is lowercased without a
+/// trailing slash
+pub fn normalize_url(url: &str) -> String {
+    let mut text = String::new();
+    let mut rest = url.trim();
+    while let Some(start) = rest.find("{{") {
+        text.push_str(&rest[..start]);
+        match rest[start..].find("}}") {
+            Some(end) => {
+                text.push_str("{{}}");
+                rest = &rest[start + end + 2..];
+            }
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    text.push_str(rest);
+
+    let (path, query) = text.split_once('?').unwrap_or((&text, ""));
+    let path = path
+        .split('/')
+        .map(|segment| {
+            if segment.starts_with(':') && segment.len() > 1 {
+                "{{}}".to_string()
+            } else {
+                segment.to_lowercase()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    let path = path.trim_end_matches('/');
+    let mut keys: Vec<&str> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| pair.split_once('=').map_or(pair, |(k, _)| k))
+        .collect();
+    keys.sort();
+    keys.dedup();
+    if keys.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}?{}", path, keys.join("&"))
+    }
+}
+
+/// What two requests are compared on
+pub fn request_key(config: &RequestConfig) -> String {
+    format!(
+        "{} {}",
+        config.method.trim().to_uppercase(),
+        normalize_url(&config.url)
+    )
+}
+
+/// Every set of two or more requests with the same key, across `collections`.
+/// Groups and their members are sorted by collection and request name.
+pub fn find_duplicates(collections: &[Collection]) -> Vec<Group> {
+    let mut by_key: BTreeMap<String, Vec<Member>> = BTreeMap::new();
+    for collection in collections {
+        for (name, config) in &collection.requests {
+            by_key.entry(request_key(config)).or_default().push(Member {
+                collection: collection.name.clone(),
+                request: name.clone(),
+            });
+        }
+    }
+    let mut groups: Vec<Group> = by_key
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(key, mut members)| {
+            members.sort_by(|a, b| (&a.collection, &a.request).cmp(&(&b.collection, &b.request)));
+            Group { key, members }
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        (&a.members[0].collection, &a.members[0].request)
+            .cmp(&(&b.members[0].collection, &b.members[0].request))
+    });
+    groups
+}
+
+fn merge_values(keep: &mut Value, other: &Value) {
+    match (keep, other) {
+        (Value::Object(keep), Value::Object(other)) => {
+            for (key, value) in other {
+                match keep.get_mut(key) {
+                    None | Some(Value::Null) => {
+                        keep.insert(key.clone(), value.clone());
+                    }
+                    Some(Value::String(s)) if s.trim().is_empty() => {
+                        keep.insert(key.clone(), value.clone());
+                    }
+                    Some(existing @ Value::Object(_)) => merge_values(existing, value),
+                    Some(Value::Array(items)) => {
+                        for item in value.as_array().into_iter().flatten() {
+                            if !items.contains(item) {
+                                items.push(item.clone());
+                            }
+                        }
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        (keep @ Value::Null, other) => *keep = other.clone(),
+        _ => {}
+    }
+}
+
+/// `keep` with what only `other` has: fields `keep` leaves empty, and headers,
+/// variables, tags and the like it doesn't have. Where both have a value, `keep`'s stays.
+pub fn merge(keep: &RequestConfig, other: &RequestConfig) -> RequestConfig {
+    let (Ok(mut merged), Ok(other)) = (serde_json::to_value(keep), serde_json::to_value(other))
+    else {
+        return keep.clone();
+    };
+    merge_values(&mut merged, &other);
+    serde_json::from_value(merged).unwrap_or_else(|_| keep.clone())
+}
+
+/// One side of the comparison: each line, and whether the other side lacks it
+pub type Side = Vec<(String, bool)>;
+
+/// Both requests as HCL, line by line
+pub fn side_by_side(left: &RequestConfig, right: &RequestConfig) -> (Side, Side) {
+    let text = |config: &RequestConfig| hcl::to_string(config).unwrap_or_default();
+    let (left, right) = (text(left), text(right));
+    let left_lines: Vec<&str> = left.lines().collect();
+    let right_lines: Vec<&str> = right.lines().collect();
+    let mark = |lines: &[&str], other: &[&str]| {
+        lines
+            .iter()
+            .map(|line| (line.to_string(), !other.contains(line)))
+            .collect::<Vec<_>>()
+    };
+    (
+        mark(&left_lines, &right_lines),
+        mark(&right_lines, &left_lines),
+    )
+}
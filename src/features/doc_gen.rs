@@ -1,13 +1,84 @@
-use crate::domain::collection::Collection;
+use crate::app::RequestLog;
+use crate::domain::collection::{Collection, RequestConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `docs` in config.json; `:docs` can change any of them for one run
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocsSettings {
+    /// Where the files go, the current directory when empty
+    pub out_dir: String,
+    /// A `<collection>.md` / `.html` pair per collection instead of `API_DOCS.*`
+    pub per_collection: bool,
+    /// Each request's latest recorded response as its example
+    pub examples: bool,
+    /// Handlebars-style template file for the HTML, see `doc_template`
+    pub template: Option<String>,
+}
+
+/// Longest example body put in the docs, in characters
+const EXAMPLE_LIMIT: usize = 4000;
+
+/// A request's latest recorded response
+#[derive(Clone, Debug, PartialEq)]
+pub struct Example {
+    pub status: u16,
+    pub content_type: String,
+    pub body: String,
+}
+
+/// The newest history entry sent from this request (same method and URL as
+/// written, or as resolved when the entry predates that), JSON pretty-printed
+pub fn latest_example(history: &[RequestLog], req: &RequestConfig) -> Option<Example> {
+    let log = history.iter().find(|log| {
+        log.method.eq_ignore_ascii_case(&req.method)
+            && !log.is_binary
+            && log.body.is_some()
+            && log
+                .request
+                .as_ref()
+                .map_or(log.url == req.url, |r| r.url == req.url)
+    })?;
+    let body = log.body.as_deref().unwrap_or_default();
+    let mut body = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|v| serde_json::to_string_pretty(&v).ok())
+        .unwrap_or_else(|| body.to_string());
+    if let Some((cut, _)) = body.char_indices().nth(EXAMPLE_LIMIT) {
+        body.truncate(cut);
+        body.push_str("\n…");
+    }
+    Some(Example {
+        status: log.status,
+        content_type: log.headers.get("content-type").cloned().unwrap_or_default(),
+        body,
+    })
+}
+
+fn code_lang(content_type: &str, body: &str) -> &'static str {
+    if content_type.contains("json") || body.trim_start().starts_with(['{', '[']) {
+        "json"
+    } else if content_type.contains("xml") || body.trim_start().starts_with('<') {
+        "xml"
+    } else {
+        "text"
+    }
+}
 
-pub fn generate_markdown(collections: &[Collection]) -> String {
+pub fn generate_markdown(collections: &[Collection], history: &[RequestLog]) -> String {
     let mut md = String::new();
     md.push_str("# API Documentation\n\n");
     md.push_str("> Generated by **Postdad** 👟\n\n");
 
     for col in collections {
         md.push_str(&format!("## Collection: {}\n\n", col.name));
+        if let Some(description) = &col.description {
+            md.push_str(description.trim());
+            md.push_str("\n\n");
+        }
 
         let mut sorted_keys: Vec<_> = col.requests.keys().collect();
         sorted_keys.sort();
@@ -16,6 +87,10 @@ pub fn generate_markdown(collections: &[Collection]) -> String {
             if let Some(req) = col.requests.get(key) {
                 md.push_str(&format!("### {}\n\n", key));
                 md.push_str(&format!("**{}** `{}`\n\n", req.method, req.url));
+                if let Some(description) = &req.description {
+                    md.push_str(description.trim());
+                    md.push_str("\n\n");
+                }
 
                 if let Some(headers) = &req.headers
                     && !headers.is_empty()
@@ -60,6 +135,16 @@ pub fn generate_markdown(collections: &[Collection]) -> String {
                     md.push_str(gql);
                     md.push_str("\n```\n\n");
                 }
+
+                if let Some(example) = latest_example(history, req) {
+                    md.push_str(&format!("#### Example Response ({})\n", example.status));
+                    md.push_str(&format!(
+                        "```{}\n",
+                        code_lang(&example.content_type, &example.body)
+                    ));
+                    md.push_str(&example.body);
+                    md.push_str("\n```\n\n");
+                }
             }
         }
         md.push_str("---\n\n");
@@ -68,14 +153,21 @@ pub fn generate_markdown(collections: &[Collection]) -> String {
     md
 }
 
-pub fn save_docs(collections: &[Collection]) -> std::io::Result<String> {
-    let md = generate_markdown(collections);
-    let path = "API_DOCS.md";
-    fs::write(path, md)?;
-    Ok(path.to_string())
+/// A markdown description as escaped HTML paragraphs, line breaks kept
+fn description_html(text: &str) -> String {
+    let escaped = text
+        .trim()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    escaped
+        .split("\n\n")
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| format!("<p>{}</p>", p.trim().replace('\n', "<br>")))
+        .collect()
 }
 
-pub fn generate_html(collections: &[Collection]) -> String {
+pub fn generate_html(collections: &[Collection], history: &[RequestLog]) -> String {
     let mut html = String::new();
 
     // Header & CSS
@@ -379,6 +471,12 @@ pub fn generate_html(collections: &[Collection]) -> String {
             border-radius: 10px;
         }
 
+        .description {
+            color: var(--text-dim);
+            line-height: 1.6;
+            margin: 0 0 24px;
+        }
+
         ::-webkit-scrollbar-thumb:hover {
             background: rgba(255, 255, 255, 0.15);
         }
@@ -424,6 +522,13 @@ pub fn generate_html(collections: &[Collection]) -> String {
 
     // Generate Content
     for (c_idx, col) in collections.iter().enumerate() {
+        if let Some(description) = &col.description {
+            html.push_str(&format!(
+                r#"<div class="description"><h2>{}</h2>{}</div>"#,
+                col.name,
+                description_html(description)
+            ));
+        }
         let mut sorted_keys: Vec<_> = col.requests.keys().collect();
         sorted_keys.sort();
 
@@ -452,6 +557,13 @@ pub fn generate_html(collections: &[Collection]) -> String {
                 html.push_str("</div>");
                 html.push_str("</div>");
 
+                if let Some(description) = &req.description {
+                    html.push_str(&format!(
+                        r#"<div class="description">{}</div>"#,
+                        description_html(description)
+                    ));
+                }
+
                 // Headers
                 if let Some(headers) = &req.headers
                     && !headers.is_empty()
@@ -500,6 +612,13 @@ pub fn generate_html(collections: &[Collection]) -> String {
                     html.push_str(&format!("<pre><code>{}</code></pre></div>", escaped));
                 }
 
+                // Example response
+                if let Some(example) = latest_example(history, req) {
+                    html.push_str(&format!(r#"<div class="section"><div class="section-header"><div class="section-title">Example Response · {}</div><div class="section-line"></div></div>"#, example.status));
+                    let escaped = example.body.replace("<", "&lt;").replace(">", "&gt;");
+                    html.push_str(&format!("<pre><code>{}</code></pre></div>", escaped));
+                }
+
                 html.push_str("</div>"); // Close endpoint
             }
         }
@@ -535,9 +654,132 @@ pub fn generate_html(collections: &[Collection]) -> String {
     html
 }
 
-pub fn save_html_docs(collections: &[Collection]) -> std::io::Result<String> {
-    let html = generate_html(collections);
-    let path = "API_DOCS.html";
-    fs::write(path, html)?;
-    Ok(path.to_string())
+/// What a docs template sees: `title` and `collections`, each with `name`,
+/// `description` / `description_html` and `requests` (`name`, `method`, `url`,
+/// `anchor`, `description`, `headers` as `key` / `value`, `body`,
+/// `graphql_query`, `form_data`, and `example` with `status`, `content_type`, `body`)
+pub fn template_context(collections: &[Collection], history: &[RequestLog]) -> Value {
+    let collections: Vec<Value> = collections
+        .iter()
+        .map(|col| {
+            let mut names: Vec<&String> = col.requests.keys().collect();
+            names.sort();
+            let requests: Vec<Value> = names
+                .into_iter()
+                .map(|name| {
+                    let req = &col.requests[name];
+                    let mut headers: Vec<(&String, &String)> =
+                        req.headers.iter().flatten().collect();
+                    headers.sort();
+                    json!({
+                        "name": name,
+                        "method": req.method,
+                        "method_lower": req.method.to_lowercase(),
+                        "url": req.url,
+                        "anchor": format!("{}-{}", col.name, name).replace(' ', "-").to_lowercase(),
+                        "description": req.description,
+                        "description_html": req.description.as_deref().map(description_html),
+                        "headers": headers
+                            .into_iter()
+                            .map(|(k, v)| json!({"key": k, "value": v}))
+                            .collect::<Vec<_>>(),
+                        "body": req.body.as_deref().filter(|b| !b.trim().is_empty()),
+                        "graphql_query": req.graphql_query,
+                        "form_data": req.form_data.iter().flatten()
+                            .map(|(k, v, is_file)| json!({"key": k, "value": v, "is_file": is_file}))
+                            .collect::<Vec<_>>(),
+                        "example": latest_example(history, req).map(|e| json!({
+                            "status": e.status,
+                            "content_type": e.content_type,
+                            "body": e.body,
+                        })),
+                    })
+                })
+                .collect();
+            json!({
+                "name": col.name,
+                "description": col.description,
+                "description_html": col.description.as_deref().map(description_html),
+                "requests": requests,
+            })
+        })
+        .collect();
+    json!({
+        "title": "API Documentation",
+        "collections": collections,
+    })
+}
+
+/// Writes the docs as `settings` say, returning the files written
+pub fn generate(
+    collections: &[Collection],
+    history: &[RequestLog],
+    settings: &DocsSettings,
+) -> Result<Vec<PathBuf>, String> {
+    let history = if settings.examples { history } else { &[] };
+    let template = match &settings.template {
+        Some(path) => {
+            Some(fs::read_to_string(path).map_err(|e| format!("Template {}: {}", path, e))?)
+        }
+        None => None,
+    };
+    let dir = if settings.out_dir.trim().is_empty() {
+        Path::new(".")
+    } else {
+        Path::new(settings.out_dir.trim())
+    };
+    fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+    let groups: Vec<(String, &[Collection])> = if settings.per_collection {
+        collections
+            .iter()
+            .map(|col| (col.name.clone(), std::slice::from_ref(col)))
+            .collect()
+    } else {
+        vec![("API_DOCS".to_string(), collections)]
+    };
+
+    let mut written = Vec::new();
+    for (stem, group) in groups {
+        let html = match &template {
+            Some(template) => {
+                crate::features::doc_template::render(template, &template_context(group, history))
+                    .map_err(|e| format!("Template: {}", e))?
+            }
+            None => generate_html(group, history),
+        };
+        for (ext, text) in [("md", generate_markdown(group, history)), ("html", html)] {
+            let path = dir.join(format!("{}.{}", stem, ext));
+            fs::write(&path, text).map_err(|e| format!("{}: {}", path.display(), e))?;
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+/// `:docs [collection] [split] [examples] [out <dir>] [template <file>]` on top
+/// of the saved settings; the collection is returned to narrow the docs to it
+pub fn parse_args(
+    args: &str,
+    settings: &DocsSettings,
+) -> Result<(DocsSettings, Option<String>), String> {
+    let mut settings = settings.clone();
+    let mut collection = None;
+    let mut words = args.split_whitespace();
+    while let Some(word) = words.next() {
+        match word {
+            "split" => settings.per_collection = true,
+            "single" => settings.per_collection = false,
+            "examples" => settings.examples = true,
+            "no-examples" => settings.examples = false,
+            "out" => settings.out_dir = words.next().ok_or("out needs a directory")?.to_string(),
+            "template" => {
+                settings.template = Some(words.next().ok_or("template needs a file")?.to_string())
+            }
+            "no-template" => settings.template = None,
+            name if collection.is_none() => collection = Some(name.to_string()),
+            other => return Err(format!("Unknown docs option '{}'", other)),
+        }
+    }
+    Ok((settings, collection))
 }
@@ -0,0 +1,199 @@
+// Handlebars-style templates for the generated HTML docs: `{{name}}` (escaped),
+// `{{{description_html}}}` (as is), `{{#each requests}}`, `{{#if example}}` with
+// `{{else}}`, `{{@index}}`, `{{this}}`, `{{../name}}` and `{{! comments }}`.
+use serde_json::Value;
+
+#[derive(Debug, PartialEq)]
+enum Node {
+    Text(String),
+    Value { path: String, escape: bool },
+    Each(String, Vec<Node>, Vec<Node>),
+    If(String, Vec<Node>, Vec<Node>),
+}
+
+/// Splits the template into nodes; `end` is the block being closed, if any
+fn parse(src: &mut &str, end: Option<&str>) -> Result<(Vec<Node>, Vec<Node>), String> {
+    let mut nodes = Vec::new();
+    let mut else_nodes = Vec::new();
+    let mut in_else = false;
+    loop {
+        let Some(start) = src.find("{{") else {
+            if let Some(block) = end {
+                return Err(format!("{{{{#{}}}}} is never closed", block));
+            }
+            push(
+                &mut nodes,
+                &mut else_nodes,
+                in_else,
+                Node::Text(src.to_string()),
+            );
+            *src = "";
+            return Ok((nodes, else_nodes));
+        };
+        if start > 0 {
+            push(
+                &mut nodes,
+                &mut else_nodes,
+                in_else,
+                Node::Text(src[..start].to_string()),
+            );
+        }
+        let raw = src[start..].starts_with("{{{");
+        let (open, close) = if raw { (3, "}}}") } else { (2, "}}") };
+        let tag_end = src[start + open..]
+            .find(close)
+            .ok_or("A {{ tag is never closed")?;
+        let tag = src[start + open..start + open + tag_end].trim().to_string();
+        *src = &src[start + open + tag_end + close.len()..];
+
+        if tag.starts_with('!') {
+            continue;
+        }
+        if let Some(block) = tag.strip_prefix('/') {
+            return match end {
+                Some(open) if open == block.trim() => Ok((nodes, else_nodes)),
+                Some(open) => Err(format!(
+                    "{{{{/{}}}}} closes {{{{#{}}}}}",
+                    block.trim(),
+                    open
+                )),
+                None => Err(format!("{{{{/{}}}}} without an opening tag", block.trim())),
+            };
+        }
+        if tag == "else" && end.is_some() {
+            in_else = true;
+            continue;
+        }
+        let node = if let Some(rest) = tag.strip_prefix('#') {
+            let (helper, path) = rest.split_once(' ').unwrap_or((rest, "this"));
+            let (body, otherwise) = parse(src, Some(helper))?;
+            match helper {
+                "each" => Node::Each(path.trim().to_string(), body, otherwise),
+                "if" => Node::If(path.trim().to_string(), body, otherwise),
+                "unless" => Node::If(path.trim().to_string(), otherwise, body),
+                other => return Err(format!("Unknown block helper #{}", other)),
+            }
+        } else {
+            Node::Value {
+                path: tag,
+                escape: !raw,
+            }
+        };
+        push(&mut nodes, &mut else_nodes, in_else, node);
+    }
+}
+
+fn push(nodes: &mut Vec<Node>, else_nodes: &mut Vec<Node>, in_else: bool, node: Node) {
+    if in_else {
+        else_nodes.push(node)
+    } else {
+        nodes.push(node)
+    }
+}
+
+/// One level of `#each`: the item, and its position for `@index`
+#[derive(Clone, Copy)]
+struct Scope<'a> {
+    value: &'a Value,
+    index: Option<usize>,
+}
+
+fn lookup(scopes: &[Scope], path: &str) -> Option<Value> {
+    let mut depth = scopes.len() - 1;
+    let mut path = path;
+    while let Some(rest) = path.strip_prefix("../") {
+        depth = depth.saturating_sub(1);
+        path = rest;
+    }
+    if path == "@index" {
+        return scopes[depth].index.map(Value::from);
+    }
+    if path == "this" || path == "." {
+        return Some(scopes[depth].value.clone());
+    }
+    let path = path.strip_prefix("this.").unwrap_or(path);
+    // A name not on the current item is looked for further out, like Handlebars' helpers
+    (0..=depth).rev().find_map(|d| {
+        let mut value = scopes[d].value;
+        for key in path.split('.') {
+            value = match value {
+                Value::Object(map) => map.get(key)?,
+                Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(value.clone())
+    })
+}
+
+fn truthy(value: &Option<Value>) -> bool {
+    match value {
+        None | Some(Value::Null) | Some(Value::Bool(false)) => false,
+        Some(Value::String(s)) => !s.is_empty(),
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(Value::Number(n)) => n.as_f64() != Some(0.0),
+        _ => true,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_nodes(nodes: &[Node], scopes: &[Scope], out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Value { path, escape: esc } => {
+                let text = match lookup(scopes, path) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(s)) => s,
+                    Some(other) => other.to_string(),
+                };
+                out.push_str(&if *esc { escape(&text) } else { text });
+            }
+            Node::If(path, body, otherwise) => {
+                let branch = if truthy(&lookup(scopes, path)) {
+                    body
+                } else {
+                    otherwise
+                };
+                render_nodes(branch, scopes, out);
+            }
+            Node::Each(path, body, otherwise) => {
+                let items = match lookup(scopes, path) {
+                    Some(Value::Array(items)) => items,
+                    Some(Value::Object(map)) => map.into_iter().map(|(_, v)| v).collect(),
+                    _ => Vec::new(),
+                };
+                if items.is_empty() {
+                    render_nodes(otherwise, scopes, out);
+                }
+                for (i, item) in items.iter().enumerate() {
+                    let mut inner = scopes.to_vec();
+                    inner.push(Scope {
+                        value: item,
+                        index: Some(i),
+                    });
+                    render_nodes(body, &inner, out);
+                }
+            }
+        }
+    }
+}
+
+/// Fills `template` from `context`
+pub fn render(template: &str, context: &Value) -> Result<String, String> {
+    let mut src = template;
+    let (nodes, _) = parse(&mut src, None)?;
+    let mut out = String::new();
+    let root = Scope {
+        value: context,
+        index: None,
+    };
+    render_nodes(&nodes, &[root], &mut out);
+    Ok(out)
+}
@@ -0,0 +1,220 @@
+// Which saved requests use each variable of the active environment (`:vars env`),
+// found by scanning every collection, and the `{{names}}` that nothing defines.
+use crate::domain::collection::{Collection, RequestConfig};
+use crate::domain::variables::references;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// A saved request and the parts of it that mention a variable
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reference {
+    pub collection: String,
+    pub request: String,
+    /// `url`, `headers`, `pre-request script`, ...
+    pub places: Vec<&'static str>,
+}
+
+impl Reference {
+    /// `collection/request`, as `open_request` takes it
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.collection, self.request)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Usage {
+    pub name: String,
+    /// Its value in the environment, None for an undefined reference
+    pub value: Option<String>,
+    pub references: Vec<Reference>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UsageReport {
+    pub environment: String,
+    /// Referenced somewhere but defined in no scope (and set by no script or extract
+    /// rule of the collection), sorted by name
+    pub undefined: Vec<Usage>,
+    /// Every variable of the environment, sorted by name
+    pub variables: Vec<Usage>,
+}
+
+/// One line of the view: a variable, or one of its references
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Row {
+    Undefined(usize),
+    UndefinedReference(usize, usize),
+    Variable(usize),
+    Reference(usize, usize),
+}
+
+/// The `:vars env` view
+#[derive(Clone, Debug, Default)]
+pub struct UsageView {
+    pub report: UsageReport,
+    pub selected: usize,
+}
+
+impl UsageReport {
+    /// Undefined names first, each followed by its references
+    pub fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (i, usage) in self.undefined.iter().enumerate() {
+            rows.push(Row::Undefined(i));
+            rows.extend((0..usage.references.len()).map(|r| Row::UndefinedReference(i, r)));
+        }
+        for (i, usage) in self.variables.iter().enumerate() {
+            rows.push(Row::Variable(i));
+            rows.extend((0..usage.references.len()).map(|r| Row::Reference(i, r)));
+        }
+        rows
+    }
+
+    /// The request a row jumps to: its own, or a variable's first reference
+    pub fn target(&self, row: Row) -> Option<&Reference> {
+        match row {
+            Row::Undefined(i) => self.undefined.get(i)?.references.first(),
+            Row::UndefinedReference(i, r) => self.undefined.get(i)?.references.get(r),
+            Row::Variable(i) => self.variables.get(i)?.references.first(),
+            Row::Reference(i, r) => self.variables.get(i)?.references.get(r),
+        }
+    }
+}
+
+/// `get_var("name")` calls in a script
+fn script_reads(script: &str) -> BTreeSet<String> {
+    quoted_args(script, "get_var(")
+}
+
+/// Names a script gives a value with `set_var("name", ...)`
+fn script_writes(script: &str) -> BTreeSet<String> {
+    quoted_args(script, "set_var(")
+}
+
+fn quoted_args(script: &str, call: &str) -> BTreeSet<String> {
+    script
+        .match_indices(call)
+        .filter_map(|(i, _)| {
+            let rest = script[i + call.len()..].trim_start();
+            let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let rest = &rest[1..];
+            Some(rest[..rest.find(quote)?].to_string())
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Each name `config` refers to, with where
+pub fn request_references(config: &RequestConfig) -> BTreeMap<String, Vec<&'static str>> {
+    let mut found: BTreeMap<String, Vec<&'static str>> = BTreeMap::new();
+    let mut add = |names: BTreeSet<String>, place: &'static str| {
+        for name in names {
+            let places = found.entry(name).or_default();
+            if !places.contains(&place) {
+                places.push(place);
+            }
+        }
+    };
+    add(references(&config.url), "url");
+    for value in config.path_params.values() {
+        add(references(value), "params");
+    }
+    for (key, value) in config.headers.iter().flatten() {
+        add(references(key), "headers");
+        add(references(value), "headers");
+    }
+    add(references(config.body.as_deref().unwrap_or("")), "body");
+    for (key, value, _) in config.form_data.iter().flatten() {
+        add(references(key), "form");
+        add(references(value), "form");
+    }
+    for text in [&config.graphql_query, &config.graphql_variables] {
+        add(references(text.as_deref().unwrap_or("")), "graphql");
+    }
+    for value in config.variables.values() {
+        add(references(value), "variables");
+    }
+    for (script, place) in [
+        (&config.pre_request_script, "pre-request script"),
+        (&config.post_request_script, "test script"),
+    ] {
+        let script = script.as_deref().unwrap_or("");
+        let mut names = references(script);
+        names.extend(script_reads(script));
+        add(names, place);
+    }
+    found
+}
+
+/// Names a collection defines for its own requests: its variables, the requests'
+/// variables and extract rules, and what its scripts `set_var`
+fn collection_defines(collection: &Collection) -> BTreeSet<String> {
+    let mut names: BTreeSet<String> = collection.variables.keys().cloned().collect();
+    for config in collection.requests.values() {
+        names.extend(config.variables.keys().cloned());
+        names.extend(config.extract.iter().flatten().map(|(k, _)| k.clone()));
+        for script in [&config.pre_request_script, &config.post_request_script] {
+            names.extend(script_writes(script.as_deref().unwrap_or("")));
+        }
+    }
+    names
+}
+
+/// Every variable of `env`, where each is used across `collections`, and the
+/// references nothing defines: not `env`, not `globals`, not the request's collection
+pub fn report(
+    env_name: &str,
+    env: &HashMap<String, String>,
+    globals: &BTreeMap<String, String>,
+    collections: &[Collection],
+) -> UsageReport {
+    let mut used: BTreeMap<String, Vec<Reference>> = BTreeMap::new();
+    let mut undefined: BTreeMap<String, Vec<Reference>> = BTreeMap::new();
+    let mut sorted: Vec<&Collection> = collections.iter().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+    for collection in sorted {
+        let defines = collection_defines(collection);
+        let mut requests: Vec<(&String, &RequestConfig)> = collection.requests.iter().collect();
+        requests.sort_by(|a, b| a.0.cmp(b.0));
+        for (request, config) in requests {
+            for (name, places) in request_references(config) {
+                let reference = Reference {
+                    collection: collection.name.clone(),
+                    request: request.clone(),
+                    places,
+                };
+                if !env.contains_key(&name)
+                    && !globals.contains_key(&name)
+                    && !defines.contains(&name)
+                {
+                    undefined
+                        .entry(name.clone())
+                        .or_default()
+                        .push(reference.clone());
+                }
+                used.entry(name).or_default().push(reference);
+            }
+        }
+    }
+
+    let mut names: Vec<&String> = env.keys().collect();
+    names.sort();
+    UsageReport {
+        environment: env_name.to_string(),
+        undefined: undefined
+            .into_iter()
+            .map(|(name, references)| Usage {
+                name,
+                value: None,
+                references,
+            })
+            .collect(),
+        variables: names
+            .into_iter()
+            .map(|name| Usage {
+                name: name.clone(),
+                value: Some(env[name].clone()),
+                references: used.get(name).cloned().unwrap_or_default(),
+            })
+            .collect(),
+    }
+}
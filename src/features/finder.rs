@@ -0,0 +1,147 @@
+// Fuzzy finder over everything you can jump to: saved requests, open tabs, history
+// and environments. The App gathers the items and acts on the pick.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ItemKind {
+    Tab,
+    Request,
+    History,
+    Environment,
+}
+
+impl ItemKind {
+    pub fn tag(&self) -> &'static str {
+        match self {
+            ItemKind::Tab => "tab",
+            ItemKind::Request => "req",
+            ItemKind::History => "hist",
+            ItemKind::Environment => "env",
+        }
+    }
+}
+
+/// What picking an item does
+#[derive(Clone, Debug, PartialEq)]
+pub enum Target {
+    Tab(usize),
+    Request { collection: String, request: String },
+    History(usize),
+    Environment(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub label: String,
+    pub detail: String,
+    pub target: Target,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    pub item: Item,
+    pub score: i64,
+    /// Char indices in `item.label` that matched, for highlighting
+    pub positions: Vec<usize>,
+}
+
+/// Matching only the detail (URL, status...) ranks below any label match
+const DETAIL_PENALTY: i64 = 30;
+
+/// Scores `query` as a case-insensitive subsequence of `text`. Consecutive characters
+/// and characters at the start of a word score higher, gaps cost a little.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+
+    // Greedy from every place the first character occurs, keep the best
+    let mut best: Option<(i64, Vec<usize>)> = None;
+    for start in (0..lower.len()).filter(|&i| lower[i] == query[0]) {
+        let Some(positions) = positions_from(&lower, &query, start) else {
+            break;
+        };
+        let score = score_positions(&chars, &positions);
+        if best.as_ref().is_none_or(|(s, _)| score > *s) {
+            best = Some((score, positions));
+        }
+    }
+    best
+}
+
+fn positions_from(lower: &[char], query: &[char], start: usize) -> Option<Vec<usize>> {
+    let mut positions = vec![start];
+    let mut i = start + 1;
+    for q in &query[1..] {
+        while i < lower.len() && lower[i] != *q {
+            i += 1;
+        }
+        if i == lower.len() {
+            return None;
+        }
+        positions.push(i);
+        i += 1;
+    }
+    Some(positions)
+}
+
+fn score_positions(chars: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    for (n, &i) in positions.iter().enumerate() {
+        score += 10;
+        if is_word_start(chars, i) {
+            score += 8;
+        }
+        if n > 0 {
+            let gap = i - positions[n - 1] - 1;
+            if gap == 0 {
+                score += 6;
+            } else {
+                score -= gap.min(10) as i64;
+            }
+        }
+    }
+    // Earlier and shorter wins among otherwise equal matches
+    score - positions[0].min(10) as i64 - (chars.len() / 20) as i64
+}
+
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    if i == 0 {
+        return true;
+    }
+    let prev = chars[i - 1];
+    !prev.is_alphanumeric() || (prev.is_lowercase() && chars[i].is_uppercase())
+}
+
+/// Items matching `query`, best first; ties keep the gathering order
+pub fn search(items: &[Item], query: &str) -> Vec<Match> {
+    let mut found: Vec<Match> = items
+        .iter()
+        .filter_map(|item| {
+            let (score, positions) = match fuzzy_match(query, &item.label) {
+                Some(hit) => hit,
+                None => {
+                    let (score, _) = fuzzy_match(query, &item.detail)?;
+                    (score - DETAIL_PENALTY, Vec::new())
+                }
+            };
+            Some(Match {
+                item: item.clone(),
+                score,
+                positions,
+            })
+        })
+        .collect();
+    found.sort_by_key(|m| std::cmp::Reverse(m.score));
+    found
+}
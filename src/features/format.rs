@@ -0,0 +1,266 @@
+/// Indent used by `pretty`, the same two spaces `serde_json` uses
+const INDENT: &str = "  ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Json,
+    Xml,
+}
+
+impl Kind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Kind::Json => "JSON",
+            Kind::Xml => "XML",
+        }
+    }
+}
+
+/// JSON by its first bracket, XML by its first tag. Only the shape is looked at, so a
+/// body with `{{placeholders}}` in it still counts.
+pub fn detect(text: &str) -> Option<Kind> {
+    match text.trim_start().chars().next()? {
+        '{' | '[' => Some(Kind::Json),
+        '<' => Some(Kind::Xml),
+        _ => None,
+    }
+}
+
+pub fn pretty(text: &str) -> Result<(Kind, String), String> {
+    match detect(text) {
+        Some(Kind::Json) => Ok((Kind::Json, pretty_json(text)?)),
+        Some(Kind::Xml) => Ok((Kind::Xml, pretty_xml(text)?)),
+        None => Err("not JSON or XML".to_string()),
+    }
+}
+
+pub fn minify(text: &str) -> Result<(Kind, String), String> {
+    match detect(text) {
+        Some(Kind::Json) => Ok((Kind::Json, minify_json(text)?)),
+        Some(Kind::Xml) => Ok((Kind::Xml, minify_xml(text)?)),
+        None => Err("not JSON or XML".to_string()),
+    }
+}
+
+/// One JSON token: a bracket, a separator, or anything else kept exactly as written
+/// (strings, numbers, literals, `{{placeholders}}`)
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Open(char),
+    Close(char),
+    Comma,
+    Colon,
+    Value(&'a str),
+}
+
+/// Splits JSON into tokens without parsing values, so numbers keep their spelling,
+/// key order stays and template placeholders pass through
+fn json_tokens(text: &str) -> Result<Vec<Token<'_>>, String> {
+    let bytes = text.as_bytes();
+    let mut tokens = Vec::new();
+    let mut stack = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        match bytes[i] {
+            b if b.is_ascii_whitespace() => i += 1,
+            b'{' if bytes.get(i + 1) == Some(&b'{') => {
+                let end = text[i..].find("}}").ok_or("unclosed {{ placeholder")?;
+                i += end + 2;
+                tokens.push(Token::Value(&text[start..i]));
+            }
+            b'{' | b'[' => {
+                stack.push(bytes[i]);
+                tokens.push(Token::Open(bytes[i] as char));
+                i += 1;
+            }
+            b'}' | b']' => {
+                let open = if bytes[i] == b'}' { b'{' } else { b'[' };
+                if stack.pop() != Some(open) {
+                    return Err(format!("unexpected '{}' at byte {}", bytes[i] as char, i));
+                }
+                tokens.push(Token::Close(bytes[i] as char));
+                i += 1;
+            }
+            b',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            b':' => {
+                tokens.push(Token::Colon);
+                i += 1;
+            }
+            b'"' => {
+                i += 1;
+                loop {
+                    match bytes.get(i) {
+                        None => return Err("unterminated string".to_string()),
+                        Some(b'\\') => i += 2,
+                        Some(b'"') => break,
+                        Some(_) => i += 1,
+                    }
+                }
+                i += 1;
+                tokens.push(Token::Value(&text[start..i]));
+            }
+            _ => {
+                while i < bytes.len()
+                    && !bytes[i].is_ascii_whitespace()
+                    && !b"{}[],:\"".contains(&bytes[i])
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Value(&text[start..i]));
+            }
+        }
+    }
+    match stack.last() {
+        Some(&open) => Err(format!("unclosed '{}'", open as char)),
+        None => Ok(tokens),
+    }
+}
+
+pub fn pretty_json(text: &str) -> Result<String, String> {
+    let tokens = json_tokens(text)?;
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut depth = 0;
+    let newline = |out: &mut String, depth: usize| {
+        out.push('\n');
+        out.push_str(&INDENT.repeat(depth));
+    };
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Open(c) => {
+                out.push(*c);
+                // Empty ones stay `{}` and `[]`
+                if !matches!(tokens.get(i + 1), Some(Token::Close(_))) {
+                    depth += 1;
+                    newline(&mut out, depth);
+                }
+            }
+            Token::Close(c) => {
+                if !matches!(tokens[i - 1], Token::Open(_)) {
+                    depth -= 1;
+                    newline(&mut out, depth);
+                }
+                out.push(*c);
+            }
+            Token::Comma => {
+                out.push(',');
+                newline(&mut out, depth);
+            }
+            Token::Colon => out.push_str(": "),
+            Token::Value(v) => out.push_str(v),
+        }
+    }
+    Ok(out)
+}
+
+pub fn minify_json(text: &str) -> Result<String, String> {
+    let tokens = json_tokens(text)?;
+    let mut out = String::with_capacity(text.len());
+    for token in tokens {
+        match token {
+            Token::Open(c) | Token::Close(c) => out.push(c),
+            Token::Comma => out.push(','),
+            Token::Colon => out.push(':'),
+            Token::Value(v) => out.push_str(v),
+        }
+    }
+    Ok(out)
+}
+
+#[derive(Debug, PartialEq)]
+enum Node<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    /// Self-closing tags, declarations, comments, CDATA
+    Single(&'a str),
+    Text(&'a str),
+}
+
+fn xml_nodes(text: &str) -> Result<Vec<Node<'_>>, String> {
+    let mut nodes = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            nodes.push(Node::Text(&rest[..end]));
+            rest = &rest[end..];
+            continue;
+        }
+        let closer = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else if rest.starts_with("<?") {
+            "?>"
+        } else {
+            ">"
+        };
+        let end = rest
+            .find(closer)
+            .ok_or_else(|| format!("unclosed {}", rest.chars().take(20).collect::<String>()))?
+            + closer.len();
+        let tag = &rest[..end];
+        nodes.push(if tag.starts_with("</") {
+            Node::Close(tag)
+        } else if tag.starts_with("<!") || tag.starts_with("<?") || tag.ends_with("/>") {
+            Node::Single(tag)
+        } else {
+            Node::Open(tag)
+        });
+        rest = &rest[end..];
+    }
+    Ok(nodes)
+}
+
+/// One tag per line, indented by nesting; an element holding only text stays on
+/// one line
+pub fn pretty_xml(text: &str) -> Result<String, String> {
+    let nodes: Vec<Node> = xml_nodes(text)?
+        .into_iter()
+        .filter(|n| !matches!(n, Node::Text(t) if t.trim().is_empty()))
+        .collect();
+    let mut lines = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < nodes.len() {
+        let indent = INDENT.repeat(depth);
+        match (&nodes[i], nodes.get(i + 1), nodes.get(i + 2)) {
+            (Node::Open(open), Some(Node::Text(t)), Some(Node::Close(close))) => {
+                lines.push(format!("{}{}{}{}", indent, open, t.trim(), close));
+                i += 3;
+                continue;
+            }
+            (Node::Open(open), Some(Node::Close(close)), _) => {
+                lines.push(format!("{}{}{}", indent, open, close));
+                i += 2;
+                continue;
+            }
+            (Node::Open(open), _, _) => {
+                lines.push(format!("{}{}", indent, open));
+                depth += 1;
+            }
+            (Node::Close(close), _, _) => {
+                depth = depth.saturating_sub(1);
+                lines.push(format!("{}{}", INDENT.repeat(depth), close));
+            }
+            (Node::Single(tag), _, _) => lines.push(format!("{}{}", indent, tag)),
+            (Node::Text(t), _, _) => lines.push(format!("{}{}", indent, t.trim())),
+        }
+        i += 1;
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Drops the whitespace between tags; text with anything else in it is kept as is
+pub fn minify_xml(text: &str) -> Result<String, String> {
+    Ok(xml_nodes(text)?
+        .into_iter()
+        .map(|node| match node {
+            Node::Text(t) if t.trim().is_empty() => "",
+            Node::Open(s) | Node::Close(s) | Node::Single(s) | Node::Text(s) => s,
+        })
+        .collect())
+}
@@ -0,0 +1,590 @@
+use crate::app::{App, OutgoingRequest};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Requests per second unless `:fuzz` is given another rate
+pub const DEFAULT_RATE: u32 = 10;
+
+/// Variants past this many are left out
+pub const MAX_CASES: usize = 1000;
+
+/// Length of the oversized string payload
+const LONG: usize = 8192;
+
+/// Strings that tend to break parsers, queries and templates
+const INJECTION: &[&str] = &[
+    "' OR '1'='1",
+    "\"; DROP TABLE users; --",
+    "<script>alert(1)</script>",
+    "../../../../etc/passwd",
+    "{{7*7}}${7*7}<%= 7*7 %>",
+    "; id",
+    "%s%s%s%n",
+    "\u{0}",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// A value of another type than the original
+    TypeConfusion,
+    /// Limits: empty, negative, overflowing, very long, odd Unicode
+    Boundary,
+    Injection,
+}
+
+impl Category {
+    pub fn label(self) -> &'static str {
+        match self {
+            Category::TypeConfusion => "type",
+            Category::Boundary => "boundary",
+            Category::Injection => "injection",
+        }
+    }
+}
+
+/// What a variant changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Param(String),
+    Header(String),
+    /// A JSON pointer into the body, e.g. `/user/tags/0`
+    Body(String),
+}
+
+impl Target {
+    pub fn label(&self) -> String {
+        match self {
+            Target::Param(name) => format!("?{}", name),
+            Target::Header(name) => format!("{}:", name),
+            Target::Body(pointer) => format!("body{}", pointer.replace('/', ".")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzCase {
+    pub target: Target,
+    pub category: Category,
+    /// The value put in, as written on the wire
+    pub payload: String,
+    pub request: OutgoingRequest,
+}
+
+impl FuzzCase {
+    /// `?page = -1`, with long payloads cut short
+    pub fn label(&self) -> String {
+        let payload = if self.payload.chars().count() > 32 {
+            format!(
+                "{}… ({} chars)",
+                self.payload.chars().take(16).collect::<String>(),
+                self.payload.chars().count()
+            )
+        } else {
+            self.payload.escape_debug().to_string()
+        };
+        format!("{} = {}", self.target.label(), payload)
+    }
+}
+
+fn boundary_strings() -> Vec<String> {
+    let mut values: Vec<String> = [
+        "",
+        "-1",
+        "0",
+        "2147483648",
+        "9223372036854775808",
+        "1e309",
+        "𝕏\u{202e}ﷺ",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    values.push("A".repeat(LONG));
+    values
+}
+
+/// Query parameter and header values are always strings, so type confusion means
+/// spelling another type
+fn confused_strings(original: &str) -> Vec<String> {
+    let mut values: Vec<String> = ["null", "true", "[]", "{}"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    if original.parse::<f64>().is_ok() {
+        values.push("abc".to_string());
+    }
+    values
+}
+
+fn string_payloads(original: &str) -> Vec<(Category, String)> {
+    let mut payloads: Vec<(Category, String)> = confused_strings(original)
+        .into_iter()
+        .map(|v| (Category::TypeConfusion, v))
+        .collect();
+    payloads.extend(
+        boundary_strings()
+            .into_iter()
+            .map(|v| (Category::Boundary, v)),
+    );
+    payloads.extend(
+        INJECTION
+            .iter()
+            .map(|v| (Category::Injection, v.to_string())),
+    );
+    payloads.retain(|(_, v)| v != original);
+    payloads
+}
+
+fn same_type(a: &Value, b: &Value) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+fn json_payloads(original: &Value) -> Vec<(Category, Value)> {
+    let mut payloads: Vec<(Category, Value)> = [
+        Value::Null,
+        Value::Bool(true),
+        Value::from(0),
+        Value::from(""),
+        Value::Array(Vec::new()),
+        Value::Object(Default::default()),
+    ]
+    .into_iter()
+    .filter(|v| !same_type(v, original))
+    .map(|v| (Category::TypeConfusion, v))
+    .collect();
+    // The same value as the other type, "42" for 42 and back
+    match original {
+        Value::Number(n) => payloads.push((Category::TypeConfusion, Value::from(n.to_string()))),
+        Value::String(s) => {
+            if let Ok(n) = s.parse::<i64>() {
+                payloads.push((Category::TypeConfusion, Value::from(n)));
+            }
+        }
+        _ => {}
+    }
+    payloads.extend(
+        [
+            Value::from(-1),
+            Value::from(0),
+            Value::from(2147483648u64),
+            Value::from(i64::MIN),
+            Value::from(u64::MAX),
+            Value::from(f64::MAX),
+            Value::from(""),
+            Value::from("𝕏\u{202e}ﷺ"),
+            Value::from("A".repeat(LONG)),
+        ]
+        .into_iter()
+        .filter(|v| v != original)
+        .map(|v| (Category::Boundary, v)),
+    );
+    payloads.extend(
+        INJECTION
+            .iter()
+            .map(|v| (Category::Injection, Value::from(*v))),
+    );
+    payloads
+}
+
+/// JSON pointers to every leaf of `value`, and to empty objects and arrays
+fn leaves(value: &Value, pointer: String, out: &mut Vec<String>) {
+    let escape = |key: &str| key.replace('~', "~0").replace('/', "~1");
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                leaves(child, format!("{}/{}", pointer, escape(key)), out);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (i, child) in items.iter().enumerate() {
+                leaves(child, format!("{}/{}", pointer, i), out);
+            }
+        }
+        _ => out.push(pointer),
+    }
+}
+
+/// Headers that are the client's business, or that would only make the request unsendable
+fn skip_header(name: &str) -> bool {
+    ["cookie", "content-length", "host", "transfer-encoding"]
+        .iter()
+        .any(|h| name.eq_ignore_ascii_case(h))
+}
+
+/// Every variant of `base`: each query parameter, header and JSON body field in turn,
+/// with each payload that fits it. Capped at `MAX_CASES`.
+pub fn cases(base: &OutgoingRequest) -> Vec<FuzzCase> {
+    let mut cases = Vec::new();
+
+    if let Ok(url) = reqwest::Url::parse(&base.url) {
+        let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        for (i, (name, value)) in pairs.iter().enumerate() {
+            for (category, payload) in string_payloads(value) {
+                let mut mutated = url.clone();
+                mutated.query_pairs_mut().clear().extend_pairs(
+                    pairs
+                        .iter()
+                        .enumerate()
+                        .map(|(j, (k, v))| (k, if i == j { &payload } else { v })),
+                );
+                let mut request = base.clone();
+                request.url = mutated.to_string();
+                cases.push(FuzzCase {
+                    target: Target::Param(name.clone()),
+                    category,
+                    payload,
+                    request,
+                });
+            }
+        }
+    }
+
+    let mut names: Vec<&String> = base.headers.keys().filter(|k| !skip_header(k)).collect();
+    names.sort();
+    for name in names {
+        for (category, payload) in string_payloads(&base.headers[name]) {
+            if reqwest::header::HeaderValue::from_str(&payload).is_err() {
+                continue;
+            }
+            let mut request = base.clone();
+            request.headers.insert(name.clone(), payload.clone());
+            cases.push(FuzzCase {
+                target: Target::Header(name.clone()),
+                category,
+                payload,
+                request,
+            });
+        }
+    }
+
+    if let Some(body) = base.body.as_deref()
+        && let Ok(json) = serde_json::from_str::<Value>(body)
+    {
+        let mut pointers = Vec::new();
+        leaves(&json, String::new(), &mut pointers);
+        for pointer in pointers {
+            let Some(original) = json.pointer(&pointer) else {
+                continue;
+            };
+            for (category, payload) in json_payloads(original) {
+                let mut mutated = json.clone();
+                match mutated.pointer_mut(&pointer) {
+                    Some(slot) => *slot = payload.clone(),
+                    // The whole body is a scalar
+                    None => mutated = payload.clone(),
+                }
+                let mut request = base.clone();
+                request.body = Some(mutated.to_string());
+                cases.push(FuzzCase {
+                    target: Target::Body(pointer.clone()),
+                    category,
+                    payload: payload.to_string(),
+                    request,
+                });
+            }
+        }
+    }
+
+    cases.truncate(MAX_CASES);
+    cases
+}
+
+/// The structure of a response body with the values left out, so responses can be
+/// told apart by what they hold: `{error:string,code:number}`, `[object]`, `text`
+pub fn shape(body: &str) -> String {
+    fn kind(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "bool",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+    let trimmed = body.trim();
+    if trimmed.is_empty() {
+        return "empty".to_string();
+    }
+    match serde_json::from_str::<Value>(trimmed) {
+        Ok(Value::Object(map)) => {
+            let mut fields: Vec<String> = map
+                .iter()
+                .map(|(k, v)| format!("{}:{}", k, kind(v)))
+                .collect();
+            fields.sort();
+            format!("{{{}}}", fields.join(","))
+        }
+        Ok(Value::Array(items)) => match items.first() {
+            Some(first) => format!("[{}]", kind(first)),
+            None => "[]".to_string(),
+        },
+        Ok(other) => kind(&other).to_string(),
+        Err(_) if trimmed.starts_with('<') => "markup".to_string(),
+        Err(_) => "text".to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Flag {
+    /// 5xx
+    ServerError,
+    /// Timed out or the connection failed
+    NoResponse,
+    /// An injection payload came back verbatim in the body
+    Reflected,
+    /// Success like the original, but a body shaped differently
+    NewShape,
+    /// Over a second, and five times the original
+    Slow,
+}
+
+impl Flag {
+    pub fn label(self) -> &'static str {
+        match self {
+            Flag::ServerError => "5xx",
+            Flag::NoResponse => "no response",
+            Flag::Reflected => "reflected",
+            Flag::NewShape => "new shape",
+            Flag::Slow => "slow",
+        }
+    }
+}
+
+/// How one send went
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    /// `shape` of the body, or the error when there was no response
+    pub shape: String,
+    pub flags: Vec<Flag>,
+}
+
+/// What makes `outcome` stand out from the unmodified request
+pub fn flags(case: &FuzzCase, outcome: &Outcome, body: &str, baseline: &Outcome) -> Vec<Flag> {
+    let mut flags = Vec::new();
+    match outcome.status {
+        None => flags.push(Flag::NoResponse),
+        Some(status) if status >= 500 => flags.push(Flag::ServerError),
+        Some(status) => {
+            let success = |s: Option<u16>| s.is_some_and(|s| (200..300).contains(&s));
+            if success(Some(status)) && success(baseline.status) && outcome.shape != baseline.shape
+            {
+                flags.push(Flag::NewShape);
+            }
+        }
+    }
+    if case.category == Category::Injection
+        && case.payload.len() >= 4
+        && body.contains(&case.payload)
+    {
+        flags.push(Flag::Reflected);
+    }
+    if outcome.latency_ms > 1000 && outcome.latency_ms > baseline.latency_ms * 5 {
+        flags.push(Flag::Slow);
+    }
+    flags
+}
+
+/// Variants that got the same status and the same body shape
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub status: Option<u16>,
+    pub shape: String,
+    /// Indexes into `FuzzReport::cases`
+    pub cases: Vec<usize>,
+    /// Every flag any of them got
+    pub flags: Vec<Flag>,
+}
+
+/// Groups the outcomes by status and shape, flagged groups first and bigger groups
+/// before smaller ones
+pub fn group(outcomes: &[Outcome]) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    for (i, outcome) in outcomes.iter().enumerate() {
+        let group = match groups
+            .iter_mut()
+            .position(|g| g.status == outcome.status && g.shape == outcome.shape)
+        {
+            Some(at) => &mut groups[at],
+            None => {
+                groups.push(Group {
+                    status: outcome.status,
+                    shape: outcome.shape.clone(),
+                    cases: Vec::new(),
+                    flags: Vec::new(),
+                });
+                groups.last_mut().unwrap()
+            }
+        };
+        group.cases.push(i);
+        for flag in &outcome.flags {
+            if !group.flags.contains(flag) {
+                group.flags.push(*flag);
+            }
+        }
+    }
+    for group in &mut groups {
+        group.flags.sort();
+    }
+    groups.sort_by_key(|g| (g.flags.is_empty(), std::cmp::Reverse(g.cases.len())));
+    groups
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzConfig {
+    pub base: OutgoingRequest,
+    pub cases: Vec<FuzzCase>,
+    /// Requests per second
+    pub rate: u32,
+    pub ssl_verify: bool,
+    /// The environment's pinned hosts
+    pub resolve: crate::net::pool::HostOverrides,
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzReport {
+    pub method: String,
+    pub url: String,
+    pub rate: u32,
+    /// The unmodified request, sent first
+    pub baseline: Outcome,
+    /// The variants that were sent, in order; `outcomes[i]` is how `cases[i]` went
+    pub cases: Vec<FuzzCase>,
+    pub outcomes: Vec<Outcome>,
+    pub groups: Vec<Group>,
+    /// Stopped with `:fuzz stop` before every variant went out
+    pub stopped: bool,
+}
+
+impl FuzzReport {
+    pub fn flagged(&self) -> usize {
+        self.outcomes.iter().filter(|o| !o.flags.is_empty()).count()
+    }
+}
+
+#[derive(Debug)]
+pub enum FuzzEvent {
+    /// Sent so far, out of how many, and how many of those were flagged
+    Progress(usize, usize, usize),
+    Finished(FuzzReport),
+    Error(String),
+}
+
+async fn send(client: &reqwest::Client, request: &OutgoingRequest) -> (Outcome, String) {
+    let start = Instant::now();
+    let result = match App::build_outgoing(client, request) {
+        Ok(built) => client.execute(built).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    let response = match result {
+        Ok(response) => {
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+                .collect();
+            let raw = response
+                .bytes()
+                .await
+                .map(|b| b.to_vec())
+                .unwrap_or_default();
+            let (body, _) = crate::net::encoding::decode_body(&headers, raw);
+            Ok((status, String::from_utf8_lossy(&body).into_owned()))
+        }
+        Err(e) => Err(e),
+    };
+    let latency_ms = start.elapsed().as_millis() as u64;
+    match response {
+        Ok((status, body)) => (
+            Outcome {
+                status: Some(status),
+                latency_ms,
+                shape: shape(&body),
+                flags: Vec::new(),
+            },
+            body,
+        ),
+        Err(e) => (
+            Outcome {
+                status: None,
+                latency_ms,
+                shape: e,
+                flags: Vec::new(),
+            },
+            String::new(),
+        ),
+    }
+}
+
+/// Sends the unmodified request, then each variant, at no more than `config.rate` a
+/// second and one at a time. Anything on `stop_rx` ends it early with what came back.
+pub async fn run_fuzz(
+    config: FuzzConfig,
+    tx: mpsc::Sender<FuzzEvent>,
+    mut stop_rx: mpsc::Receiver<()>,
+) {
+    let client = crate::net::pool::apply_overrides(
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .danger_accept_invalid_certs(!config.ssl_verify)
+            .no_gzip(),
+        &config.resolve,
+    )
+    .build()
+    .unwrap_or_else(|_| reqwest::Client::new());
+    let mut interval =
+        tokio::time::interval(Duration::from_secs_f64(1.0 / config.rate.max(1) as f64));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    interval.tick().await;
+    let (baseline, _) = send(&client, &config.base).await;
+    if baseline.status.is_none() {
+        let _ = tx
+            .send(FuzzEvent::Error(format!(
+                "the unmodified request failed: {}",
+                baseline.shape
+            )))
+            .await;
+        return;
+    }
+
+    let total = config.cases.len();
+    let mut outcomes = Vec::with_capacity(total);
+    let mut flagged = 0;
+    let mut stopped = false;
+    for case in &config.cases {
+        if !matches!(stop_rx.try_recv(), Err(mpsc::error::TryRecvError::Empty)) {
+            stopped = true;
+            break;
+        }
+        interval.tick().await;
+        let (mut outcome, body) = send(&client, &case.request).await;
+        outcome.flags = flags(case, &outcome, &body, &baseline);
+        if !outcome.flags.is_empty() {
+            flagged += 1;
+        }
+        outcomes.push(outcome);
+        let _ = tx
+            .send(FuzzEvent::Progress(outcomes.len(), total, flagged))
+            .await;
+    }
+
+    let mut cases = config.cases;
+    cases.truncate(outcomes.len());
+    let _ = tx
+        .send(FuzzEvent::Finished(FuzzReport {
+            method: config.base.method,
+            url: config.base.url,
+            rate: config.rate,
+            baseline,
+            groups: group(&outcomes),
+            cases,
+            outcomes,
+            stopped,
+        }))
+        .await;
+}
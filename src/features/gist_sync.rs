@@ -0,0 +1,526 @@
+// Two-way sync of the workspace's collections with a GitHub Gist, or of the whole
+// workspace with remote storage (see storage.rs). Each side is compared with the hashes
+// recorded at the last sync, so a file changed on one side only goes the other way, and
+// a file changed on both is a conflict for the user to settle.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Per-file hashes from the last sync, kept in the workspace
+pub const STATE_FILE: &str = "sync_state.json";
+pub const DEFAULT_API: &str = "https://api.github.com";
+const USER_AGENT: &str = concat!("PostDad/", env!("CARGO_PKG_VERSION"));
+
+/// `gist` in config.json
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GistSettings {
+    /// Empty until the first push creates the gist (or `:sync link <id>`)
+    pub gist_id: String,
+    /// Environment variable holding a token with the `gist` scope
+    pub token_env: String,
+    pub api_url: String,
+}
+
+impl Default for GistSettings {
+    fn default() -> Self {
+        GistSettings {
+            gist_id: String::new(),
+            token_env: "GITHUB_TOKEN".to_string(),
+            api_url: DEFAULT_API.to_string(),
+        }
+    }
+}
+
+impl GistSettings {
+    pub fn token(&self) -> Option<String> {
+        std::env::var(&self.token_env)
+            .ok()
+            .filter(|t| !t.trim().is_empty())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncState {
+    /// The gist (or storage location) the hashes belong to; another one starts from scratch
+    pub gist_id: String,
+    pub synced_at: u64,
+    /// File name to the SHA-256 both sides had when they last agreed
+    pub files: BTreeMap<String, String>,
+}
+
+pub fn load_state(path: &str) -> SyncState {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_state(path: &str, state: &SyncState) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Could not write {}: {}", STATE_FILE, e))
+}
+
+/// What `:sync` (the gist) and `:storage` sync with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    Gist,
+    Storage,
+}
+
+impl Target {
+    pub fn state_file(self) -> &'static str {
+        match self {
+            Target::Gist => STATE_FILE,
+            Target::Storage => crate::features::storage::STATE_FILE,
+        }
+    }
+
+    /// "gist" or "storage", for messages
+    pub fn label(self) -> &'static str {
+        match self {
+            Target::Gist => "gist",
+            Target::Storage => "storage",
+        }
+    }
+}
+
+/// The settings a sync needs, cloned off the app for the background task
+#[derive(Clone, Debug)]
+pub enum Endpoint {
+    Gist(GistSettings),
+    Storage(crate::features::storage::StorageSettings),
+}
+
+impl Endpoint {
+    pub async fn fetch(&self) -> Result<RemoteFiles, String> {
+        match self {
+            Endpoint::Gist(settings) => fetch(settings).await,
+            Endpoint::Storage(settings) => crate::features::storage::fetch(settings).await,
+        }
+    }
+
+    /// Returns the id to record the sync under
+    pub async fn push(&self, files: &[(String, String)]) -> Result<String, String> {
+        match self {
+            Endpoint::Gist(settings) => push(settings, files).await,
+            Endpoint::Storage(settings) => crate::features::storage::push(settings, files).await,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Push,
+    Pull,
+    /// Both ways: `:sync` and `PostDad sync`
+    Both,
+}
+
+impl Direction {
+    pub fn parse(word: &str) -> Option<Direction> {
+        match word {
+            "push" => Some(Direction::Push),
+            "pull" => Some(Direction::Pull),
+            "" | "both" => Some(Direction::Both),
+            _ => None,
+        }
+    }
+
+    fn pushes(self) -> bool {
+        self != Direction::Pull
+    }
+
+    fn pulls(self) -> bool {
+        self != Direction::Push
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Unchanged,
+    /// Changed (or only) here
+    Push,
+    /// Changed (or only) on the other side
+    Pull,
+    /// Changed on both sides since the last sync
+    Conflict,
+    /// A conflict settled by the user; done whatever the direction
+    KeepLocal,
+    KeepRemote,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileSync {
+    pub name: String,
+    pub action: Action,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+}
+
+impl FileSync {
+    /// What this sync does with the file: `Some(true)` pushes, `Some(false)` pulls
+    fn transfer(&self, direction: Direction) -> Option<bool> {
+        match self.action {
+            Action::Push if direction.pushes() => Some(true),
+            Action::Pull if direction.pulls() => Some(false),
+            Action::KeepLocal => Some(true),
+            Action::KeepRemote => Some(false),
+            _ => None,
+        }
+    }
+}
+
+pub fn hash(content: &str) -> String {
+    openssl::sha::sha256(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Compares every file on either side with `base`. A file missing on one side that
+/// the other still has as it was at the last sync was deleted there, and is left alone
+/// rather than brought back.
+pub fn plan(
+    local: &BTreeMap<String, String>,
+    remote: &BTreeMap<String, String>,
+    base: &BTreeMap<String, String>,
+) -> Vec<FileSync> {
+    let names: std::collections::BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    names
+        .into_iter()
+        .map(|name| {
+            let (l, r) = (local.get(name), remote.get(name));
+            let base = base.get(name);
+            let unchanged = |content: &String| base == Some(&hash(content));
+            let action = match (l, r) {
+                (Some(l), Some(r)) if l == r => Action::Unchanged,
+                (Some(_), Some(r)) if unchanged(r) => Action::Push,
+                (Some(l), Some(_)) if unchanged(l) => Action::Pull,
+                (Some(_), Some(_)) => Action::Conflict,
+                (Some(l), None) if unchanged(l) => Action::Unchanged,
+                (Some(_), None) => Action::Push,
+                (None, Some(r)) if unchanged(r) => Action::Unchanged,
+                (None, _) => Action::Pull,
+            };
+            FileSync {
+                name: name.clone(),
+                action,
+                local: l.cloned(),
+                remote: r.cloned(),
+            }
+        })
+        .collect()
+}
+
+pub fn conflicts(plan: &[FileSync]) -> usize {
+    plan.iter().filter(|f| f.action == Action::Conflict).count()
+}
+
+/// `local`/`remote` settles every conflict that way, anything else leaves them
+pub fn resolve_all(plan: &mut [FileSync], prefer: &str) -> Result<(), String> {
+    let action = match prefer {
+        "local" => Action::KeepLocal,
+        "remote" => Action::KeepRemote,
+        other => return Err(format!("Unknown --prefer '{}', use local or remote", other)),
+    };
+    for file in plan.iter_mut().filter(|f| f.action == Action::Conflict) {
+        file.action = action;
+    }
+    Ok(())
+}
+
+/// The outcome of a plan: files to write locally, files to upload, and the state to
+/// record once the upload went through
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Outcome {
+    pub pulls: Vec<(String, String)>,
+    pub pushes: Vec<(String, String)>,
+    /// Files left as they are because of the direction, e.g. local changes on a pull
+    pub skipped: usize,
+    pub state: SyncState,
+}
+
+/// Works out what to do, given `base` from the last sync. Conflicts still open are
+/// skipped and keep their old hash, so they come up again next time.
+pub fn outcome(
+    plan: &[FileSync],
+    direction: Direction,
+    gist_id: &str,
+    base: &SyncState,
+) -> Outcome {
+    let mut files = if base.gist_id == gist_id {
+        base.files.clone()
+    } else {
+        BTreeMap::new()
+    };
+    let mut outcome = Outcome::default();
+    for file in plan {
+        match (file.transfer(direction), &file.local, &file.remote) {
+            (Some(true), Some(content), _) => {
+                outcome.pushes.push((file.name.clone(), content.clone()));
+                files.insert(file.name.clone(), hash(content));
+            }
+            (Some(false), _, Some(content)) => {
+                outcome.pulls.push((file.name.clone(), content.clone()));
+                files.insert(file.name.clone(), hash(content));
+            }
+            (None, Some(l), Some(r)) if l == r => {
+                files.insert(file.name.clone(), hash(l));
+            }
+            (None, ..) if file.action != Action::Unchanged => outcome.skipped += 1,
+            _ => {}
+        }
+    }
+    outcome.state = SyncState {
+        gist_id: gist_id.to_string(),
+        synced_at: crate::features::history::now(),
+        files,
+    };
+    outcome
+}
+
+/// `*.hcl` files in the collections directory, by file name
+pub fn local_files(dir: &str) -> BTreeMap<String, String> {
+    let mut files = BTreeMap::new();
+    for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.ends_with(".hcl")
+            && let Ok(content) = std::fs::read_to_string(entry.path())
+        {
+            files.insert(name, content);
+        }
+    }
+    files
+}
+
+/// Writes pulled files into the collections directory
+pub fn write_pulls(dir: &str, pulls: &[(String, String)]) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    for (name, content) in pulls {
+        // Gist file names come from outside; never let one leave the directory
+        if name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(format!("Refusing to write gist file '{}'", name));
+        }
+        std::fs::write(std::path::Path::new(dir).join(name), content)
+            .map_err(|e| format!("Could not write {}: {}", name, e))?;
+    }
+    Ok(())
+}
+
+/// Unified diff of the remote copy against the local one
+pub fn diff(name: &str, local: &str, remote: &str) -> Vec<String> {
+    similar::TextDiff::from_lines(remote, local)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("remote/{}", name), &format!("local/{}", name))
+        .to_string()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Status bar indicator
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum SyncStatus {
+    #[default]
+    Idle,
+    Busy,
+    Synced(u64),
+    Conflicts(usize),
+    Failed,
+}
+
+impl SyncStatus {
+    pub fn label(&self, now: u64) -> Option<String> {
+        match self {
+            SyncStatus::Idle => None,
+            SyncStatus::Busy => Some("⇅ syncing…".to_string()),
+            SyncStatus::Synced(at) => Some(format!("⇅ synced {}", ago(now.saturating_sub(*at)))),
+            SyncStatus::Conflicts(n) => Some(format!("⇅ {} conflict(s)", n)),
+            SyncStatus::Failed => Some("⇅ sync failed".to_string()),
+        }
+    }
+}
+
+/// "just now", "5m ago", ...
+pub fn ago(secs: u64) -> String {
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86_400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
+
+/// What the main loop should do next
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncRequest {
+    Fetch(Target, Direction),
+    /// Upload, then record `state` (gist id filled in from the response if new)
+    Push {
+        target: Target,
+        files: Vec<(String, String)>,
+        state: SyncState,
+    },
+}
+
+impl SyncRequest {
+    pub fn target(&self) -> Target {
+        match self {
+            SyncRequest::Fetch(target, _) | SyncRequest::Push { target, .. } => *target,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SyncEvent {
+    Fetched(Target, Direction, Result<RemoteFiles, String>),
+    Pushed {
+        target: Target,
+        result: Result<String, String>,
+        state: SyncState,
+        files: usize,
+    },
+}
+
+/// Conflicts waiting for keep local / keep remote, one file at a time
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncPrompt {
+    pub target: Target,
+    pub direction: Direction,
+    pub plan: Vec<FileSync>,
+    pub remote_updated_at: String,
+    pub show_diff: bool,
+    pub scroll: u16,
+}
+
+impl SyncPrompt {
+    /// Index in `plan` of the conflict on screen
+    pub fn current(&self) -> Option<usize> {
+        self.plan.iter().position(|f| f.action == Action::Conflict)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RemoteFiles {
+    pub id: String,
+    /// Empty when the other side doesn't say
+    pub updated_at: String,
+    /// `*.hcl` files only; anything else there is left alone
+    pub files: BTreeMap<String, String>,
+}
+
+fn request(
+    client: &reqwest::Client,
+    method: reqwest::Method,
+    url: &str,
+    token: Option<&str>,
+) -> reqwest::RequestBuilder {
+    let builder = client
+        .request(method, url)
+        .header("User-Agent", USER_AGENT)
+        .header("Accept", "application/vnd.github+json");
+    match token {
+        Some(token) => builder.header("Authorization", format!("Bearer {}", token)),
+        None => builder,
+    }
+}
+
+async fn send(builder: reqwest::RequestBuilder) -> Result<serde_json::Value, String> {
+    let resp = builder.send().await.map_err(|e| e.to_string())?;
+    let status = resp.status();
+    let text = resp.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        let message = serde_json::from_str::<serde_json::Value>(&text)
+            .ok()
+            .and_then(|v| v["message"].as_str().map(str::to_string))
+            .unwrap_or(text);
+        return Err(format!("GitHub returned {}: {}", status.as_u16(), message));
+    }
+    serde_json::from_str(&text).map_err(|e| format!("Unexpected reply from GitHub: {}", e))
+}
+
+pub async fn fetch(settings: &GistSettings) -> Result<RemoteFiles, String> {
+    if settings.gist_id.is_empty() {
+        return Err("No gist yet: push first, or :sync link <gist id>".to_string());
+    }
+    let client = reqwest::Client::new();
+    let token = settings.token();
+    let url = format!(
+        "{}/gists/{}",
+        settings.api_url.trim_end_matches('/'),
+        settings.gist_id
+    );
+    let gist = send(request(
+        &client,
+        reqwest::Method::GET,
+        &url,
+        token.as_deref(),
+    ))
+    .await?;
+
+    let mut files = BTreeMap::new();
+    for (name, file) in gist["files"].as_object().into_iter().flatten() {
+        if !name.ends_with(".hcl") {
+            continue;
+        }
+        // Big files come back cut short; the whole thing is at raw_url
+        let content = match (file["truncated"].as_bool(), file["raw_url"].as_str()) {
+            (Some(true), Some(raw)) => {
+                request(&client, reqwest::Method::GET, raw, token.as_deref())
+                    .send()
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .text()
+                    .await
+                    .map_err(|e| e.to_string())?
+            }
+            _ => file["content"].as_str().unwrap_or_default().to_string(),
+        };
+        files.insert(name.clone(), content);
+    }
+    Ok(RemoteFiles {
+        id: gist["id"].as_str().unwrap_or(&settings.gist_id).to_string(),
+        updated_at: gist["updated_at"].as_str().unwrap_or_default().to_string(),
+        files,
+    })
+}
+
+/// Uploads `files`, creating a secret gist when there is none yet. Returns the gist id.
+pub async fn push(settings: &GistSettings, files: &[(String, String)]) -> Result<String, String> {
+    let token = settings.token().ok_or(format!(
+        "Set {} to a GitHub token with the gist scope to push",
+        settings.token_env
+    ))?;
+    let body_files: serde_json::Map<String, serde_json::Value> = files
+        .iter()
+        .map(|(name, content)| (name.clone(), serde_json::json!({ "content": content })))
+        .collect();
+    let api = settings.api_url.trim_end_matches('/');
+    let client = reqwest::Client::new();
+    let builder = if settings.gist_id.is_empty() {
+        request(
+            &client,
+            reqwest::Method::POST,
+            &format!("{}/gists", api),
+            Some(&token),
+        )
+        .json(&serde_json::json!({
+            "description": "PostDad collections",
+            "public": false,
+            "files": body_files,
+        }))
+    } else {
+        request(
+            &client,
+            reqwest::Method::PATCH,
+            &format!("{}/gists/{}", api, settings.gist_id),
+            Some(&token),
+        )
+        .json(&serde_json::json!({ "files": body_files }))
+    };
+    let gist = send(builder).await?;
+    Ok(gist["id"].as_str().unwrap_or(&settings.gist_id).to_string())
+}
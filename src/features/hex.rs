@@ -0,0 +1,154 @@
+/// Bytes shown per row of the hex pane
+pub const BYTES_PER_ROW: usize = 16;
+
+/// Which bytes the pane is showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Response,
+    /// The response body as it came over the wire, before Content-Encoding was undone
+    Encoded,
+    /// The request as it went out, see `net::http::wire_bytes`
+    Request,
+}
+
+impl Source {
+    pub fn label(self) -> &'static str {
+        match self {
+            Source::Response => "Response",
+            Source::Encoded => "Response as received",
+            Source::Request => "Request",
+        }
+    }
+}
+
+/// State of the hex pane (`B` on the response)
+#[derive(Debug, Clone)]
+pub struct HexView {
+    pub source: Source,
+    /// First row on screen
+    pub scroll: usize,
+    pub query: String,
+    /// Offsets where the query was found
+    pub matches: Vec<usize>,
+    pub index: usize,
+    /// Typing the query after `/`
+    pub searching: bool,
+}
+
+impl HexView {
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            scroll: 0,
+            query: String::new(),
+            matches: Vec::new(),
+            index: 0,
+            searching: false,
+        }
+    }
+
+    /// Runs the query over `bytes` and jumps to the first match
+    pub fn search(&mut self, bytes: &[u8]) {
+        self.matches = find(bytes, &parse_query(&self.query));
+        self.index = 0;
+        if let Some(&offset) = self.matches.first() {
+            self.scroll = offset / BYTES_PER_ROW;
+        }
+    }
+
+    /// Next (or previous) match, wrapping around
+    pub fn step(&mut self, forward: bool) {
+        let count = self.matches.len();
+        if count == 0 {
+            return;
+        }
+        self.index = if forward {
+            (self.index + 1) % count
+        } else {
+            (self.index + count - 1) % count
+        };
+        self.scroll = self.matches[self.index] / BYTES_PER_ROW;
+    }
+
+    /// The offset of the selected match, if any
+    pub fn current(&self) -> Option<usize> {
+        self.matches.get(self.index).copied()
+    }
+}
+
+pub fn rows(len: usize) -> usize {
+    len.div_ceil(BYTES_PER_ROW)
+}
+
+/// One row: `00000010  48 54 54 50 ...  |HTTP/1.1 200 OK.|`
+pub fn format_row(bytes: &[u8], row: usize) -> String {
+    let start = row * BYTES_PER_ROW;
+    let chunk = &bytes[start.min(bytes.len())..(start + BYTES_PER_ROW).min(bytes.len())];
+    let mut hex = String::with_capacity(BYTES_PER_ROW * 3 + 1);
+    for i in 0..BYTES_PER_ROW {
+        if i == BYTES_PER_ROW / 2 {
+            hex.push(' ');
+        }
+        match chunk.get(i) {
+            Some(b) => hex.push_str(&format!("{:02x} ", b)),
+            None => hex.push_str("   "),
+        }
+    }
+    let ascii: String = chunk.iter().map(|&b| printable(b)).collect();
+    format!("{:08x}  {} |{}|", start, hex, ascii)
+}
+
+/// The whole dump, one row per line, e.g. for the clipboard
+pub fn dump(bytes: &[u8]) -> String {
+    (0..rows(bytes.len()))
+        .map(|row| format_row(bytes, row) + "\n")
+        .collect()
+}
+
+/// The ASCII column shows a dot for anything that isn't printable
+pub fn printable(b: u8) -> char {
+    if b.is_ascii_graphic() || b == b' ' {
+        b as char
+    } else {
+        '.'
+    }
+}
+
+/// `de ad be ef` or `0xdeadbeef` looks for bytes, anything else for the text itself;
+/// a leading `"` forces text
+pub fn parse_query(query: &str) -> Vec<u8> {
+    if let Some(text) = query.strip_prefix('"') {
+        return text.strip_suffix('"').unwrap_or(text).as_bytes().to_vec();
+    }
+    let trimmed = query.trim();
+    let tokens: Vec<&str> = trimmed
+        .trim_start_matches("0x")
+        .split_whitespace()
+        .collect();
+    let is_hex = (trimmed.starts_with("0x") || tokens.len() > 1)
+        && tokens
+            .iter()
+            .all(|t| t.len() % 2 == 0 && t.chars().all(|c| c.is_ascii_hexdigit()));
+    if is_hex && !tokens.is_empty() {
+        let digits = tokens.concat();
+        (0..digits.len())
+            .step_by(2)
+            .filter_map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+            .collect()
+    } else {
+        query.as_bytes().to_vec()
+    }
+}
+
+/// Every offset where `needle` starts, overlapping ones included
+pub fn find(bytes: &[u8], needle: &[u8]) -> Vec<usize> {
+    if needle.is_empty() || needle.len() > bytes.len() {
+        return Vec::new();
+    }
+    bytes
+        .windows(needle.len())
+        .enumerate()
+        .filter(|(_, window)| *window == needle)
+        .map(|(i, _)| i)
+        .collect()
+}
@@ -0,0 +1,173 @@
+// History view: searching by URL, method and status, grouping by day, and the
+// retention cap that pinned entries are exempt from.
+
+use crate::app::RequestLog;
+use serde::{Deserialize, Serialize};
+
+/// `history` in config.json
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistorySettings {
+    /// Unpinned entries kept, oldest dropped first
+    pub limit: usize,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        HistorySettings { limit: 50 }
+    }
+}
+
+/// One word of the search query; every word has to match
+#[derive(Clone, Debug, PartialEq)]
+pub enum Term {
+    /// `POST` or `method:post`
+    Method(String),
+    /// `404` or `status:404`
+    Status(u16),
+    /// `4xx` or `status:4xx`
+    StatusClass(u16),
+    /// Anything else, looked for in the URL
+    Text(String),
+}
+
+pub fn parse_query(query: &str) -> Vec<Term> {
+    query.split_whitespace().map(parse_term).collect()
+}
+
+fn parse_term(word: &str) -> Term {
+    let lower = word.to_lowercase();
+    if let Some(method) = lower.strip_prefix("method:") {
+        return Term::Method(method.to_uppercase());
+    }
+    let status = lower.strip_prefix("status:").unwrap_or(&lower);
+    if status.len() == 3 {
+        if let Ok(code) = status.parse::<u16>()
+            && (100..600).contains(&code)
+        {
+            return Term::Status(code);
+        }
+        if let Some(class) = status.strip_suffix("xx")
+            && let Ok(class) = class.parse::<u16>()
+            && (1..6).contains(&class)
+        {
+            return Term::StatusClass(class);
+        }
+    }
+    let upper = word.to_uppercase();
+    if crate::features::command::METHODS.contains(&upper.as_str()) {
+        return Term::Method(upper);
+    }
+    Term::Text(lower)
+}
+
+pub fn matches(log: &RequestLog, terms: &[Term]) -> bool {
+    terms.iter().all(|term| match term {
+        Term::Method(method) => log.method.eq_ignore_ascii_case(method),
+        Term::Status(code) => log.status == *code,
+        Term::StatusClass(class) => log.status / 100 == *class,
+        Term::Text(text) => log.url.to_lowercase().contains(text),
+    })
+}
+
+/// A line of the history view
+#[derive(Clone, Debug, PartialEq)]
+pub enum Row {
+    Group(String),
+    /// Index into the history
+    Entry(usize),
+}
+
+/// Matching entries, pinned ones first, the rest under a heading per day (newest first,
+/// as the history is kept)
+pub fn rows(history: &[RequestLog], query: &str, now: u64) -> Vec<Row> {
+    let terms = parse_query(query);
+    let shown: Vec<usize> = (0..history.len())
+        .filter(|&i| matches(&history[i], &terms))
+        .collect();
+
+    let mut rows = Vec::new();
+    let pinned: Vec<usize> = shown
+        .iter()
+        .copied()
+        .filter(|&i| history[i].pinned)
+        .collect();
+    if !pinned.is_empty() {
+        rows.push(Row::Group("Pinned".to_string()));
+        rows.extend(pinned.into_iter().map(Row::Entry));
+    }
+    let mut group = None;
+    for i in shown.into_iter().filter(|&i| !history[i].pinned) {
+        let label = day_label(history[i].timestamp, now);
+        if group.as_ref() != Some(&label) {
+            rows.push(Row::Group(label.clone()));
+            group = Some(label);
+        }
+        rows.push(Row::Entry(i));
+    }
+    rows
+}
+
+/// "Today", "Yesterday" or the date, in UTC. Entries recorded before timestamps were
+/// kept are "Earlier".
+pub fn day_label(timestamp: u64, now: u64) -> String {
+    if timestamp == 0 {
+        return "Earlier".to_string();
+    }
+    let (day, today) = (timestamp / 86_400, now / 86_400);
+    if day == today {
+        "Today".to_string()
+    } else if day + 1 == today {
+        "Yesterday".to_string()
+    } else {
+        let (y, m, d) = civil_from_days(day as i64);
+        format!("{:04}-{:02}-{:02}", y, m, d)
+    }
+}
+
+/// Days since 1970-01-01 to a calendar date (Howard Hinnant's algorithm)
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+/// Drops the oldest unpinned entries beyond `limit`. Returns where each entry ended
+/// up (None for the dropped ones), for anything that holds history positions
+pub fn trim(history: &mut Vec<RequestLog>, limit: usize) -> Vec<Option<usize>> {
+    let mut unpinned = 0;
+    let mut kept = 0;
+    let moved = history
+        .iter()
+        .map(|log| {
+            if !log.pinned {
+                unpinned += 1;
+                if unpinned > limit {
+                    return None;
+                }
+            }
+            kept += 1;
+            Some(kept - 1)
+        })
+        .collect::<Vec<_>>();
+    let mut i = 0;
+    history.retain(|_| {
+        i += 1;
+        moved[i - 1].is_some()
+    });
+    moved
+}
+
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
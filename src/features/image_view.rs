@@ -0,0 +1,60 @@
+// Image responses: what the picture is (format, pixels, bytes) above the preview,
+// and how it's drawn in the pane, cycled with `z`.
+use image::{DynamicImage, ImageFormat};
+use ratatui_image::Resize;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ImageZoom {
+    /// Shrunk to the pane when bigger, never blown up
+    #[default]
+    Fit,
+    /// Scaled to fill the pane either way
+    Fill,
+    /// One pixel per pixel, cut off at the pane's edges
+    Actual,
+}
+
+impl ImageZoom {
+    pub fn next(self) -> Self {
+        match self {
+            ImageZoom::Fit => ImageZoom::Fill,
+            ImageZoom::Fill => ImageZoom::Actual,
+            ImageZoom::Actual => ImageZoom::Fit,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ImageZoom::Fit => "Fit",
+            ImageZoom::Fill => "Fill",
+            ImageZoom::Actual => "Actual size",
+        }
+    }
+
+    pub fn resize(self) -> Resize {
+        match self {
+            ImageZoom::Fit => Resize::Fit(None),
+            ImageZoom::Fill => Resize::Scale(None),
+            ImageZoom::Actual => Resize::Crop(None),
+        }
+    }
+}
+
+/// The format the bytes are in, as the file extension it usually goes by
+pub fn extension(bytes: &[u8]) -> Option<&'static str> {
+    let format = image::guess_format(bytes).ok()?;
+    format.extensions_str().first().copied()
+}
+
+/// `PNG 640×480`, or just the size when the format can't be told
+pub fn describe(bytes: &[u8], image: &DynamicImage) -> String {
+    let size = format!("{}×{}", image.width(), image.height());
+    match image::guess_format(bytes) {
+        Ok(ImageFormat::Jpeg) => format!("JPEG {}", size),
+        Ok(format) => match format.extensions_str().first() {
+            Some(ext) => format!("{} {}", ext.to_ascii_uppercase(), size),
+            None => size,
+        },
+        Err(_) => size,
+    }
+}
@@ -1,17 +1,250 @@
 use crate::domain::collection::{Collection, RequestConfig};
+use crate::domain::environment::Environment;
+use crate::domain::workspace::Workspace;
+use crate::features::postman_script::{self, ScriptKind};
+use base64::Engine;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
+
+/// What `PostDad import --format` takes; left out, the file's shape decides
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Postman,
+    OpenApi,
+    Insomnia,
+    Thunder,
+    Curl,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name.to_lowercase().as_str() {
+            "postman" => Some(Format::Postman),
+            "openapi" | "swagger" => Some(Format::OpenApi),
+            "insomnia" => Some(Format::Insomnia),
+            "thunder" | "thunder-client" | "thunderclient" => Some(Format::Thunder),
+            "curl" => Some(Format::Curl),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Format::Postman => "Postman Collection",
+            Format::OpenApi => "OpenAPI v3",
+            Format::Insomnia => "Insomnia export",
+            Format::Thunder => "Thunder Client export",
+            Format::Curl => "curl commands",
+        }
+    }
+
+    pub fn detect(content: &str) -> Option<Format> {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else {
+            return (!crate::features::curl_import::extract_commands(content).is_empty())
+                .then_some(Format::Curl);
+        };
+        if json.get("openapi").is_some() {
+            Some(Format::OpenApi)
+        } else if json.get("info").is_some() && json.get("item").is_some() {
+            Some(Format::Postman)
+        } else if json.get("_type").and_then(|t| t.as_str()) == Some("export")
+            && json.get("resources").is_some()
+        {
+            Some(Format::Insomnia)
+        } else if json.get("client").and_then(|c| c.as_str()) == Some("Thunder Client")
+            || json.get("collectionName").is_some()
+            || json.get("environmentName").is_some()
+        {
+            Some(Format::Thunder)
+        } else {
+            None
+        }
+    }
+}
+
+/// Collections and environments from another client's export, before they're written
+#[derive(Debug, Default)]
+pub struct Imported {
+    pub collections: Vec<Collection>,
+    pub environments: Vec<Environment>,
+    /// What didn't make it across, e.g. OAuth 2.0 auth or Insomnia's `{% response %}` tags
+    pub warnings: Vec<String>,
+}
+
+/// `Authorization: Basic ...`, unless the credentials are `{{variables}}`, which
+/// only get their values at send time
+pub fn basic_auth(username: &str, password: &str) -> Option<String> {
+    if username.contains("{{") || password.contains("{{") {
+        return None;
+    }
+    let encoded =
+        base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+    Some(format!("Basic {}", encoded))
+}
+
+/// `url` with `name=value` pairs added to its query string
+pub fn with_query(url: &str, pairs: &[(String, String)]) -> String {
+    if pairs.is_empty() {
+        return url.to_string();
+    }
+    let query = pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{}{}{}", url, separator, query)
+}
+
+/// Writes `{dir}/{name}.hcl` (and the description beside it), replacing what was there
+pub fn write_collection(collection: &Collection, dir: &str) -> std::io::Result<PathBuf> {
+    let safe_name = collection.name.replace(" ", "_").to_lowercase();
+    let file_name = std::path::Path::new(dir).join(format!("{}.hcl", safe_name));
+
+    fs::create_dir_all(dir)?;
+
+    let mut names: Vec<&String> = collection.requests.keys().collect();
+    names.sort();
+    let mut hcl_content = crate::domain::collection::variables_hcl(&collection.variables)
+        .map_err(std::io::Error::other)?;
+    for name in names {
+        let body_hcl = hcl::to_string(&collection.requests[name]).map_err(std::io::Error::other)?;
+        let entry = format!(
+            "\nrequest \"{}\" {{\n{}\n}}\n",
+            name.replace('"', "'"),
+            body_hcl
+        );
+        hcl_content.push_str(&entry);
+    }
+
+    fs::write(&file_name, hcl_content)?;
+    if let Some(description) = &collection.description {
+        Collection::save_description(dir, &safe_name, description)?;
+    }
+    Ok(file_name)
+}
+
+/// Imported environments added to `existing`: new ones appended, and for a name
+/// that's already there only the variables it doesn't have yet
+pub fn merge_environments(existing: &mut Vec<Environment>, imported: &[Environment]) -> usize {
+    let mut changed = 0;
+    for env in imported {
+        match existing.iter_mut().find(|e| e.name == env.name) {
+            Some(current) => {
+                let before = current.variables.len() + current.headers.len();
+                for (key, value) in &env.variables {
+                    current
+                        .variables
+                        .entry(key.clone())
+                        .or_insert_with(|| value.clone());
+                }
+                for (key, value) in &env.headers {
+                    current
+                        .headers
+                        .entry(key.clone())
+                        .or_insert_with(|| value.clone());
+                }
+                if current.variables.len() + current.headers.len() > before {
+                    changed += 1;
+                }
+            }
+            None => {
+                existing.push(env.clone());
+                changed += 1;
+            }
+        }
+    }
+    changed
+}
+
+/// Writes the collections and merges the environments into the workspace's environments.hcl
+pub fn save_imported(imported: &Imported, workspace: &Workspace) -> std::io::Result<()> {
+    let dir = workspace.path("collections");
+    for collection in &imported.collections {
+        let path = write_collection(collection, &dir)?;
+        println!(
+            "Imported '{}' ({} requests) to '{}'",
+            collection.name,
+            collection.requests.len(),
+            path.display()
+        );
+    }
+    if !imported.environments.is_empty() {
+        let env_path = workspace.path("environments.hcl");
+        // A missing file starts empty rather than with the sample environments
+        let mut environments = if std::path::Path::new(&env_path).exists() {
+            Environment::load_from_file(&env_path)?
+        } else {
+            Vec::new()
+        };
+        // "None" is the built-in no-environment entry
+        environments.retain(|e| e.name != "None");
+        let changed = merge_environments(&mut environments, &imported.environments);
+        if changed > 0 {
+            let content = crate::features::bundle::environments_hcl(&environments)
+                .map_err(std::io::Error::other)?;
+            fs::write(&env_path, content)?;
+        }
+        let names: Vec<&str> = imported
+            .environments
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        println!(
+            "Environments: {} ({} added or updated in '{}')",
+            names.join(", "),
+            changed,
+            env_path
+        );
+    }
+    for warning in &imported.warnings {
+        println!("  ! {}", warning);
+    }
+    Ok(())
+}
 
 #[derive(Debug, Deserialize)]
 struct PostmanCollection {
     info: Info,
     item: Vec<Item>,
+    event: Option<Vec<Event>>,
+    /// Collection variables
+    #[serde(default)]
+    variable: Vec<Variable>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Variable {
+    key: String,
+    value: serde_json::Value,
+    disabled: bool,
 }
 
 #[derive(Debug, Deserialize)]
 struct Info {
     name: String,
+    description: Option<Description>,
+}
+
+/// Postman descriptions are plain strings or `{ "content": ..., "type": "text/markdown" }`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Description {
+    Text(String),
+    Object { content: Option<String> },
+}
+
+impl Description {
+    fn text(description: Option<&Description>) -> Option<String> {
+        let text = match description? {
+            Description::Text(text) => text.clone(),
+            Description::Object { content } => content.clone()?,
+        };
+        Some(text).filter(|t| !t.trim().is_empty())
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -19,6 +252,78 @@ struct Item {
     name: String,
     request: Option<Request>,
     item: Option<Vec<Item>>, // For nested folders
+    event: Option<Vec<Event>>,
+}
+
+/// A pre-request (`listen: "prerequest"`) or test (`listen: "test"`) script
+#[derive(Debug, Deserialize)]
+struct Event {
+    listen: String,
+    script: Option<Script>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Script {
+    exec: Option<Exec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Exec {
+    Lines(Vec<String>),
+    Text(String),
+}
+
+/// Collection and folder scripts run before the request's own, like in Postman
+#[derive(Clone, Debug, Default)]
+struct InheritedScripts {
+    pre_request: String,
+    test: String,
+}
+
+impl InheritedScripts {
+    fn with(&self, events: Option<&Vec<Event>>) -> Self {
+        let mut scripts = self.clone();
+        for event in events.into_iter().flatten() {
+            let code = match event.script.as_ref().and_then(|s| s.exec.as_ref()) {
+                Some(Exec::Lines(lines)) => lines.join("\n"),
+                Some(Exec::Text(text)) => text.clone(),
+                None => continue,
+            };
+            let target = match event.listen.as_str() {
+                "prerequest" => &mut scripts.pre_request,
+                "test" => &mut scripts.test,
+                _ => continue,
+            };
+            if code.trim().is_empty() {
+                continue;
+            }
+            if !target.is_empty() {
+                target.push('\n');
+            }
+            target.push_str(&code);
+        }
+        scripts
+    }
+}
+
+/// How much of the collection's scripting made it across
+#[derive(Debug, Default, PartialEq)]
+pub struct ScriptStats {
+    pub converted: usize,
+    pub skipped: usize,
+}
+
+impl ScriptStats {
+    fn transpile(&mut self, source: &str, kind: ScriptKind) -> Option<String> {
+        if source.trim().is_empty() {
+            return None;
+        }
+        let result = postman_script::transpile(source, kind);
+        self.converted += result.converted;
+        self.skipped += result.skipped;
+        Some(result.script)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -28,6 +333,7 @@ struct Request {
     url: Option<Url>,
     body: Option<Body>,
     auth: Option<Auth>,
+    description: Option<Description>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -67,49 +373,74 @@ struct Body {
     formdata: Option<Vec<KeyValue>>,
 }
 
-pub fn import_postman_collection(file_path: &str) -> std::io::Result<()> {
-    let content = fs::read_to_string(file_path)?;
-    let pm_collection: PostmanCollection = serde_json::from_str(&content)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+/// Parses a Postman v2 collection, converting its scripts to Rhai where possible
+pub fn parse_postman_collection(content: &str) -> Result<(Collection, ScriptStats), String> {
+    let pm_collection: PostmanCollection =
+        serde_json::from_str(content).map_err(|e| e.to_string())?;
 
     let mut requests = HashMap::new();
+    let mut stats = ScriptStats::default();
+    let scripts = InheritedScripts::default().with(pm_collection.event.as_ref());
 
     // Flatten items
-    flatten_items(&pm_collection.item, &mut requests, "");
+    flatten_items(&pm_collection.item, &mut requests, "", &scripts, &mut stats);
 
     let collection = Collection {
         name: pm_collection.info.name.clone(),
         requests,
+        ws_scenarios: HashMap::new(),
+        snapshots: Default::default(),
+        description: Description::text(pm_collection.info.description.as_ref()),
+        variables: pm_collection
+            .variable
+            .iter()
+            .filter(|v| !v.key.is_empty() && !v.disabled)
+            .map(|v| {
+                let value = match &v.value {
+                    serde_json::Value::String(s) => s.clone(),
+                    serde_json::Value::Null => String::new(),
+                    other => other.to_string(),
+                };
+                (v.key.clone(), value)
+            })
+            .collect(),
     };
+    Ok((collection, stats))
+}
 
-    let safe_name = collection.name.replace(" ", "_").to_lowercase();
-    let file_name = format!("collections/{}.hcl", safe_name);
-
-    if !std::path::Path::new("collections").exists() {
-        fs::create_dir("collections")?;
-    }
-
-    let mut hcl_content = String::new();
-
-    for (name, config) in &collection.requests {
-        let body_hcl = hcl::to_string(&config).map_err(std::io::Error::other)?;
-
-        let entry = format!("\nrequest \"{}\" {{\n{}\n}}\n", name, body_hcl);
-        hcl_content.push_str(&entry);
-    }
+pub fn import_postman_collection(file_path: &str, dir: &str) -> std::io::Result<()> {
+    let content = fs::read_to_string(file_path)?;
+    let (collection, stats) = parse_postman_collection(&content)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
 
-    fs::write(&file_name, hcl_content)?;
+    let file_name = write_collection(&collection, dir)?;
 
     println!(
         "Successfully imported '{}' to '{}'",
-        collection.name, file_name
+        collection.name,
+        file_name.display()
     );
+    if stats.converted + stats.skipped > 0 {
+        println!(
+            "Scripts: {} statements converted to Rhai, {} left as '{}' comments",
+            stats.converted,
+            stats.skipped,
+            postman_script::UNCONVERTED.trim()
+        );
+    }
 
     Ok(())
 }
 
-fn flatten_items(items: &[Item], requests: &mut HashMap<String, RequestConfig>, prefix: &str) {
+fn flatten_items(
+    items: &[Item],
+    requests: &mut HashMap<String, RequestConfig>,
+    prefix: &str,
+    inherited: &InheritedScripts,
+    stats: &mut ScriptStats,
+) {
     for item in items {
+        let scripts = inherited.with(item.event.as_ref());
         if let Some(req) = &item.request {
             // It's a request
             let name = if prefix.is_empty() {
@@ -169,11 +500,10 @@ fn flatten_items(items: &[Item], requests: &mut HashMap<String, RequestConfig>,
                     }
                     Some("urlencoded") => {
                         if let Some(ue) = &body.urlencoded {
-                            let params: Vec<String> = ue
-                                .iter()
-                                .map(|kv| format!("{}={}", kv.key, kv.value))
-                                .collect();
-                            (Some(params.join("&")), None, Some("Raw".to_string()))
+                            let encoded = form_urlencoded::Serializer::new(String::new())
+                                .extend_pairs(ue.iter().map(|kv| (&kv.key, &kv.value)))
+                                .finish();
+                            (Some(encoded), None, Some("UrlEncoded".to_string()))
                         } else {
                             (None, None, None)
                         }
@@ -196,8 +526,21 @@ fn flatten_items(items: &[Item], requests: &mut HashMap<String, RequestConfig>,
                 graphql_variables: None,
                 expected_status: None,
                 timeout_ms: None,
-                pre_request_script: None,
-                post_request_script: None,
+                connect_timeout_ms: None,
+                read_timeout_ms: None,
+                pre_request_script: stats.transpile(&scripts.pre_request, ScriptKind::PreRequest),
+                post_request_script: stats.transpile(&scripts.test, ScriptKind::Test),
+                stress_stages: None,
+                disabled_default_headers: Vec::new(),
+                disabled_headers: HashMap::new(),
+                path_params: HashMap::new(),
+                description: Description::text(req.description.as_ref()),
+                variables: HashMap::new(),
+                grpc_service: None,
+                grpc_proto_path: None,
+                tags: Vec::new(),
+                treat_as: None,
+                paginate: None,
             };
 
             requests.insert(name, config);
@@ -208,7 +551,7 @@ fn flatten_items(items: &[Item], requests: &mut HashMap<String, RequestConfig>,
             } else {
                 format!("{}/{}", prefix, item.name)
             };
-            flatten_items(sub_items, requests, &new_prefix);
+            flatten_items(sub_items, requests, &new_prefix, &scripts, stats);
         }
     }
 }
@@ -230,6 +573,7 @@ struct OpenApiInfo {
     title: String,
     #[serde(default)]
     version: String,
+    description: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -242,6 +586,7 @@ struct OpenApiServer {
 #[allow(dead_code)] // Fields retained for OpenAPI spec completeness
 struct OpenApiOperation {
     summary: Option<String>,
+    description: Option<String>,
     operation_id: Option<String>,
     parameters: Option<Vec<OpenApiParameter>>,
     request_body: Option<OpenApiRequestBody>,
@@ -282,7 +627,7 @@ struct OpenApiSchema {
     default: Option<serde_json::Value>,
 }
 
-pub fn import_openapi(file_path: &str) -> std::io::Result<()> {
+pub fn import_openapi(file_path: &str, dir: &str) -> std::io::Result<()> {
     let content = fs::read_to_string(file_path)?;
     let spec: OpenApiSpec = serde_json::from_str(&content).map_err(|e| {
         std::io::Error::new(
@@ -411,8 +756,24 @@ pub fn import_openapi(file_path: &str) -> std::io::Result<()> {
                 graphql_variables: None,
                 expected_status: None,
                 timeout_ms: None,
+                connect_timeout_ms: None,
+                read_timeout_ms: None,
                 pre_request_script: None,
                 post_request_script: None,
+                stress_stages: None,
+                disabled_default_headers: Vec::new(),
+                disabled_headers: HashMap::new(),
+                path_params: HashMap::new(),
+                description: operation
+                    .description
+                    .clone()
+                    .or_else(|| operation.summary.clone()),
+                variables: HashMap::new(),
+                grpc_service: None,
+                grpc_proto_path: None,
+                tags: Vec::new(),
+                treat_as: None,
+                paginate: None,
             };
 
             requests.insert(name, config);
@@ -429,29 +790,19 @@ pub fn import_openapi(file_path: &str) -> std::io::Result<()> {
     let collection = Collection {
         name: spec.info.title.clone(),
         requests,
+        ws_scenarios: HashMap::new(),
+        snapshots: Default::default(),
+        description: spec.info.description.clone(),
+        variables: HashMap::new(),
     };
 
-    let safe_name = collection.name.replace(" ", "_").to_lowercase();
-    let file_name = format!("collections/{}.hcl", safe_name);
-
-    if !std::path::Path::new("collections").exists() {
-        fs::create_dir("collections")?;
-    }
-
-    let mut hcl_content = String::new();
-
-    for (name, config) in &collection.requests {
-        let body_hcl = hcl::to_string(&config).map_err(std::io::Error::other)?;
-
-        let entry = format!("\nrequest \"{}\" {{\n{}\n}}\n", name, body_hcl);
-        hcl_content.push_str(&entry);
-    }
-
-    fs::write(&file_name, hcl_content)?;
+    let file_name = write_collection(&collection, dir)?;
 
     println!(
         "Successfully imported OpenAPI spec '{}' v{} to '{}'",
-        spec.info.title, spec.info.version, file_name
+        spec.info.title,
+        spec.info.version,
+        file_name.display()
     );
     println!("  → {} requests created", collection.requests.len());
 
@@ -531,25 +882,46 @@ fn schema_to_value(schema: &OpenApiSchema) -> serde_json::Value {
     }
 }
 
-/// Auto-detect file format and import accordingly
-pub fn import_auto(file_path: &str) -> std::io::Result<()> {
+/// Auto-detect file format and import accordingly into the `dir` collections directory
+/// Imports one file into the workspace, as `format` or whatever it looks like
+pub fn import_file(
+    file_path: &str,
+    format: Option<Format>,
+    workspace: &Workspace,
+) -> std::io::Result<()> {
     let content = fs::read_to_string(file_path)?;
+    let dir = workspace.path("collections");
+    let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
 
-    // Try to parse as JSON first
-    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-        // Check for OpenAPI v3 signature
-        if json.get("openapi").is_some() {
-            println!("Detected OpenAPI v3 format");
-            return import_openapi(file_path);
+    let format = match format.or_else(|| Format::detect(&content)) {
+        Some(format) => {
+            println!("Detected {}", format.label());
+            format
         }
-        // Check for Postman signature
-        if json.get("info").is_some() && json.get("item").is_some() {
-            println!("Detected Postman Collection format");
-            return import_postman_collection(file_path);
+        None => {
+            // Default to Postman for backwards compatibility
+            println!("Format not detected, attempting Postman import...");
+            Format::Postman
+        }
+    };
+    match format {
+        Format::Postman => import_postman_collection(file_path, &dir),
+        Format::OpenApi => import_openapi(file_path, &dir),
+        Format::Insomnia => {
+            let imported = crate::features::insomnia_import::parse(&content).map_err(invalid)?;
+            save_imported(&imported, workspace)
+        }
+        Format::Thunder => {
+            let imported = crate::features::thunder_import::parse(&content).map_err(invalid)?;
+            save_imported(&imported, workspace)
+        }
+        Format::Curl => {
+            let name = crate::features::curl_import::collection_name_for(file_path);
+            let (batch, path) =
+                crate::features::curl_import::import_to_collection(&content, &name, &dir)
+                    .map_err(invalid)?;
+            println!("Imported {} to '{}'", batch.summary(), path);
+            Ok(())
         }
     }
-
-    // Default to Postman for backwards compatibility
-    println!("Format not detected, attempting Postman import...");
-    import_postman_collection(file_path)
 }
@@ -0,0 +1,417 @@
+// Insomnia v4 exports (Application menu → Export Data → Insomnia v4 JSON): each
+// workspace becomes a collection with folders as `Folder/Request` names, and the
+// base environment plus its sub-environments become PostDad environments.
+use crate::domain::collection::{Collection, RequestConfig};
+use crate::domain::environment::Environment;
+use crate::features::import::{Imported, basic_auth, with_query};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+struct Export {
+    #[serde(rename = "__export_format")]
+    format: Option<u32>,
+    resources: Vec<Resource>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Resource {
+    #[serde(rename = "_id")]
+    id: String,
+    #[serde(rename = "_type")]
+    kind: String,
+    #[serde(rename = "parentId")]
+    parent_id: Option<String>,
+    name: String,
+    description: String,
+    method: String,
+    url: String,
+    body: Body,
+    headers: Vec<Pair>,
+    parameters: Vec<Pair>,
+    #[serde(rename = "pathParameters")]
+    path_parameters: Vec<Pair>,
+    authentication: Option<Value>,
+    /// Environment variables, nested objects allowed
+    data: Option<Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Body {
+    #[serde(rename = "mimeType")]
+    mime_type: Option<String>,
+    text: Option<String>,
+    params: Vec<Pair>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+struct Pair {
+    name: String,
+    value: String,
+    disabled: bool,
+    /// `file` for multipart file fields
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    #[serde(rename = "fileName")]
+    file_name: Option<String>,
+}
+
+/// Insomnia's `{{ _.name }}` as `{{name}}`, and the template tags PostDad has an
+/// equivalent for as dynamic values; other `{% tags %}` are left and reported
+fn convert_template(text: &str, warnings: &mut Vec<String>, context: &str) -> String {
+    let vars =
+        regex::Regex::new(r"\{\{\s*(?:_\.)?([A-Za-z0-9_\-\.\[\]']+?)\s*\}\}").expect("valid regex");
+    let text = vars.replace_all(text, "{{$1}}");
+    let tags = regex::Regex::new(r"\{%\s*(\w+)\s*([^%]*?)\s*%\}").expect("valid regex");
+    tags.replace_all(&text, |caps: &regex::Captures| {
+        let args = &caps[2];
+        match &caps[1] {
+            "uuid" => "{{$uuid}}".to_string(),
+            "now" if args.contains("millis") => "{{$timestampMs}}".to_string(),
+            "now" if args.contains("unix") => "{{$timestamp}}".to_string(),
+            tag => {
+                let warning = format!("{}: Insomnia's {{% {} %}} tag was kept as is", context, tag);
+                if !warnings.contains(&warning) {
+                    warnings.push(warning);
+                }
+                caps[0].to_string()
+            }
+        }
+    })
+    .into_owned()
+}
+
+/// Nested environment data as dotted names, `{ "api": { "url": .. } }` → `api.url`
+fn flatten(prefix: &str, value: &Value, out: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let name = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&name, value, out);
+            }
+        }
+        Value::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        Value::Null => {}
+        other => {
+            out.insert(prefix.to_string(), other.to_string());
+        }
+    }
+}
+
+struct Converter<'a> {
+    by_parent: HashMap<&'a str, Vec<&'a Resource>>,
+    warnings: Vec<String>,
+}
+
+impl<'a> Converter<'a> {
+    fn convert(&mut self, text: &str, context: &str) -> String {
+        convert_template(text, &mut self.warnings, context)
+    }
+
+    fn children(&self, id: &str, kind: &str) -> Vec<&'a Resource> {
+        self.by_parent
+            .get(id)
+            .map(|items| items.iter().filter(|r| r.kind == kind).copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Folder auth and headers are what its requests inherit
+    fn walk(
+        &mut self,
+        parent: &str,
+        prefix: &str,
+        inherited: (&[Pair], Option<&'a Value>),
+        requests: &mut HashMap<String, RequestConfig>,
+    ) {
+        for folder in self.children(parent, "request_group") {
+            let name = join(prefix, &folder.name);
+            let mut headers = inherited.0.to_vec();
+            headers.extend(folder.headers.iter().cloned());
+            let auth = active_auth(folder.authentication.as_ref()).or(inherited.1);
+            self.walk(&folder.id, &name, (&headers, auth), requests);
+        }
+        for request in self.children(parent, "request") {
+            let name = join(prefix, &request.name);
+            let auth = active_auth(request.authentication.as_ref()).or(inherited.1);
+            let config = self.request(&name, request, inherited.0, auth);
+            requests.insert(name, config);
+        }
+        for kind in ["grpc_request", "websocket_request"] {
+            for skipped in self.children(parent, kind) {
+                self.warnings.push(format!(
+                    "{}: {} requests aren't imported",
+                    join(prefix, &skipped.name),
+                    if kind == "grpc_request" {
+                        "gRPC"
+                    } else {
+                        "WebSocket"
+                    }
+                ));
+            }
+        }
+    }
+
+    fn request(
+        &mut self,
+        name: &str,
+        req: &Resource,
+        folder_headers: &[Pair],
+        auth: Option<&Value>,
+    ) -> RequestConfig {
+        let mut headers = HashMap::new();
+        let mut disabled_headers = HashMap::new();
+        for header in folder_headers.iter().chain(&req.headers) {
+            if header.name.is_empty() {
+                continue;
+            }
+            let target = if header.disabled {
+                &mut disabled_headers
+            } else {
+                &mut headers
+            };
+            target.insert(header.name.clone(), self.convert(&header.value, name));
+        }
+
+        let mut query: Vec<(String, String)> = req
+            .parameters
+            .iter()
+            .filter(|p| !p.disabled && !p.name.is_empty())
+            .map(|p| (p.name.clone(), self.convert(&p.value, name)))
+            .collect();
+
+        if let Some(auth) = auth {
+            let field = |key: &str| auth.get(key).and_then(Value::as_str).unwrap_or_default();
+            match field("type") {
+                "bearer" => {
+                    let prefix = match field("prefix") {
+                        "" => "Bearer",
+                        prefix => prefix,
+                    };
+                    let token = self.convert(field("token"), name);
+                    headers.insert("Authorization".to_string(), format!("{} {}", prefix, token));
+                }
+                "basic" => {
+                    let (user, pass) = (
+                        self.convert(field("username"), name),
+                        self.convert(field("password"), name),
+                    );
+                    match basic_auth(&user, &pass) {
+                        Some(value) => {
+                            headers.insert("Authorization".to_string(), value);
+                        }
+                        None => self.warnings.push(format!(
+                            "{}: basic auth uses variables, set it in the Auth tab",
+                            name
+                        )),
+                    }
+                }
+                "apikey" => {
+                    let (key, value) = (
+                        self.convert(field("key"), name),
+                        self.convert(field("value"), name),
+                    );
+                    match field("addTo") {
+                        "queryParams" => query.push((key, value)),
+                        "cookie" => {
+                            headers.insert("Cookie".to_string(), format!("{}={}", key, value));
+                        }
+                        _ => {
+                            headers.insert(key, value);
+                        }
+                    }
+                }
+                "none" | "" => {}
+                other => self.warnings.push(format!(
+                    "{}: {} auth isn't imported, set it in the Auth tab",
+                    name, other
+                )),
+            }
+        }
+
+        let mut config = RequestConfig {
+            url: with_query(&self.convert(&req.url, name), &query),
+            method: if req.method.is_empty() {
+                "GET".to_string()
+            } else {
+                req.method.to_uppercase()
+            },
+            headers: (!headers.is_empty()).then_some(headers),
+            disabled_headers,
+            path_params: req
+                .path_parameters
+                .iter()
+                .filter(|p| !p.name.is_empty())
+                .map(|p| (p.name.clone(), p.value.clone()))
+                .collect(),
+            description: Some(req.description.clone()).filter(|d| !d.trim().is_empty()),
+            ..Default::default()
+        };
+
+        let params: Vec<(&Pair, String)> = req
+            .body
+            .params
+            .iter()
+            .filter(|p| !p.disabled)
+            .map(|p| (p, self.convert(&p.value, name)))
+            .collect();
+        match req.body.mime_type.as_deref() {
+            Some("application/x-www-form-urlencoded") => {
+                let encoded = form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(params.iter().map(|(p, value)| (&p.name, value)))
+                    .finish();
+                config.body = Some(encoded).filter(|b| !b.is_empty());
+                config.body_type = Some("UrlEncoded".to_string());
+            }
+            Some("multipart/form-data") => {
+                config.form_data = Some(
+                    params
+                        .into_iter()
+                        .map(|(p, value)| match (p.kind.as_deref(), &p.file_name) {
+                            (Some("file"), Some(file)) => (p.name.clone(), file.clone(), true),
+                            _ => (p.name.clone(), value, false),
+                        })
+                        .collect(),
+                );
+                config.body_type = Some("FormData".to_string());
+            }
+            Some("application/graphql") => {
+                let text = req.body.text.clone().unwrap_or_default();
+                let graphql: Value = serde_json::from_str(&text).unwrap_or(Value::Null);
+                config.graphql_query = graphql
+                    .get("query")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .or(Some(text).filter(|t| !t.trim().is_empty()));
+                config.graphql_variables = graphql
+                    .get("variables")
+                    .filter(|v| !v.is_null())
+                    .and_then(|v| serde_json::to_string_pretty(v).ok());
+                config.body_type = Some("GraphQL".to_string());
+            }
+            _ => {
+                config.body = req
+                    .body
+                    .text
+                    .as_deref()
+                    .filter(|t| !t.is_empty())
+                    .map(|t| self.convert(t, name));
+            }
+        }
+        if let Some(mime) = &req.body.mime_type
+            && config.body.is_some()
+            && !config
+                .headers
+                .iter()
+                .flatten()
+                .any(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+            && config.body_type.is_none()
+        {
+            config
+                .headers
+                .get_or_insert_with(HashMap::new)
+                .insert("Content-Type".to_string(), mime.clone());
+        }
+        config
+    }
+}
+
+/// Auth that's set and switched on; `{}` means inherit from the folder
+fn active_auth(auth: Option<&Value>) -> Option<&Value> {
+    auth.filter(|a| {
+        a.get("type").and_then(Value::as_str).is_some()
+            && a.get("disabled").and_then(Value::as_bool) != Some(true)
+    })
+}
+
+fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", prefix, name)
+    }
+}
+
+pub fn parse(content: &str) -> Result<Imported, String> {
+    let export: Export =
+        serde_json::from_str(content).map_err(|e| format!("Invalid Insomnia export: {}", e))?;
+    if let Some(format) = export.format
+        && format != 4
+    {
+        return Err(format!(
+            "Insomnia export format {} isn't supported, export as Insomnia v4 JSON",
+            format
+        ));
+    }
+
+    let mut by_parent: HashMap<&str, Vec<&Resource>> = HashMap::new();
+    for resource in &export.resources {
+        if let Some(parent) = &resource.parent_id {
+            by_parent.entry(parent.as_str()).or_default().push(resource);
+        }
+    }
+    let mut converter = Converter {
+        by_parent,
+        warnings: Vec::new(),
+    };
+
+    let mut imported = Imported::default();
+    for workspace in export.resources.iter().filter(|r| r.kind == "workspace") {
+        let mut requests = HashMap::new();
+        converter.walk(&workspace.id, "", (&[], None), &mut requests);
+
+        for base in converter.children(&workspace.id, "environment") {
+            let mut variables = HashMap::new();
+            flatten(
+                "",
+                base.data.as_ref().unwrap_or(&Value::Null),
+                &mut variables,
+            );
+            let subs = converter.children(&base.id, "environment");
+            if subs.is_empty() && !variables.is_empty() {
+                imported.environments.push(Environment {
+                    name: workspace.name.clone(),
+                    variables: variables.clone(),
+                    ..Default::default()
+                });
+            }
+            // Sub-environments go over the base one, like in Insomnia
+            for sub in subs {
+                let mut merged = variables.clone();
+                flatten("", sub.data.as_ref().unwrap_or(&Value::Null), &mut merged);
+                imported.environments.push(Environment {
+                    name: sub.name.clone(),
+                    variables: merged,
+                    ..Default::default()
+                });
+            }
+        }
+
+        if requests.is_empty() {
+            continue;
+        }
+        imported.collections.push(Collection {
+            name: workspace.name.clone(),
+            requests,
+            ws_scenarios: HashMap::new(),
+            snapshots: Default::default(),
+            description: Some(workspace.description.clone()).filter(|d| !d.trim().is_empty()),
+            variables: HashMap::new(),
+        });
+    }
+    imported.warnings = converter.warnings;
+
+    if imported.collections.is_empty() && imported.environments.is_empty() {
+        return Err("No workspaces with requests or environments in the export".to_string());
+    }
+    Ok(imported)
+}
@@ -0,0 +1,193 @@
+use serde_json::Value;
+use similar::{ChangeTag, TextDiff};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// One real difference between two JSON documents, addressed by JSONPath
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonChange {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+impl JsonChange {
+    pub fn describe(&self) -> String {
+        match self.kind {
+            ChangeKind::Added => format!(
+                "+ {}: {}",
+                self.path,
+                preview(self.new.as_ref().unwrap_or(&Value::Null))
+            ),
+            ChangeKind::Removed => format!(
+                "- {}: {}",
+                self.path,
+                preview(self.old.as_ref().unwrap_or(&Value::Null))
+            ),
+            ChangeKind::Changed => format!(
+                "~ {}: {} → {}",
+                self.path,
+                preview(self.old.as_ref().unwrap_or(&Value::Null)),
+                preview(self.new.as_ref().unwrap_or(&Value::Null))
+            ),
+        }
+    }
+}
+
+/// Compact one-line rendering of a value, cut off when it gets long
+pub fn preview(value: &Value) -> String {
+    let text = value.to_string();
+    if text.chars().count() > 60 {
+        format!("{}…", text.chars().take(60).collect::<String>())
+    } else {
+        text
+    }
+}
+
+fn child_path(parent: &str, key: &str) -> String {
+    let simple = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if simple {
+        format!("{}.{}", parent, key)
+    } else {
+        format!("{}['{}']", parent, key.replace('\'', "\\'"))
+    }
+}
+
+/// Compares two values recursively. Object key order doesn't matter,
+/// arrays are compared position by position.
+pub fn diff_json(old: &Value, new: &Value) -> Vec<JsonChange> {
+    let mut changes = Vec::new();
+    diff_at("$", old, new, &mut changes);
+    changes
+}
+
+/// Header changes, names compared case-insensitively (paths look like `headers.content-type`)
+pub fn diff_headers(
+    old: &HashMap<String, String>,
+    new: &HashMap<String, String>,
+) -> Vec<JsonChange> {
+    let as_json = |headers: &HashMap<String, String>| {
+        Value::Object(
+            headers
+                .iter()
+                .map(|(k, v)| (k.to_lowercase(), Value::String(v.clone())))
+                .collect(),
+        )
+    };
+    let mut changes = Vec::new();
+    diff_at("headers", &as_json(old), &as_json(new), &mut changes);
+    changes
+}
+
+fn diff_at(path: &str, old: &Value, new: &Value, changes: &mut Vec<JsonChange>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = child_path(path, key);
+                match (a.get(key), b.get(key)) {
+                    (Some(x), Some(y)) => diff_at(&child, x, y, changes),
+                    (Some(x), None) => changes.push(JsonChange {
+                        path: child,
+                        kind: ChangeKind::Removed,
+                        old: Some(x.clone()),
+                        new: None,
+                    }),
+                    (None, Some(y)) => changes.push(JsonChange {
+                        path: child,
+                        kind: ChangeKind::Added,
+                        old: None,
+                        new: Some(y.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let child = format!("{}[{}]", path, i);
+                match (a.get(i), b.get(i)) {
+                    (Some(x), Some(y)) => diff_at(&child, x, y, changes),
+                    (Some(x), None) => changes.push(JsonChange {
+                        path: child,
+                        kind: ChangeKind::Removed,
+                        old: Some(x.clone()),
+                        new: None,
+                    }),
+                    (None, Some(y)) => changes.push(JsonChange {
+                        path: child,
+                        kind: ChangeKind::Added,
+                        old: None,
+                        new: Some(y.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old != new => changes.push(JsonChange {
+            path: path.to_string(),
+            kind: ChangeKind::Changed,
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BodyDiff {
+    /// Both bodies are JSON: only the real changes
+    Json(Vec<JsonChange>),
+    /// Fallback for anything else: changed lines, `-` for old and `+` for new
+    Text(Vec<(ChangeKind, String)>),
+}
+
+impl BodyDiff {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            BodyDiff::Json(changes) => changes.is_empty(),
+            BodyDiff::Text(lines) => lines.is_empty(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            BodyDiff::Json(changes) => changes.len(),
+            BodyDiff::Text(lines) => lines.len(),
+        }
+    }
+}
+
+pub fn diff_bodies(old: &str, new: &str) -> BodyDiff {
+    if let (Ok(a), Ok(b)) = (
+        serde_json::from_str::<Value>(old),
+        serde_json::from_str::<Value>(new),
+    ) {
+        return BodyDiff::Json(diff_json(&a, &b));
+    }
+
+    let lines = TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .filter_map(|change| {
+            let text = change.to_string().trim_end_matches('\n').to_string();
+            match change.tag() {
+                ChangeTag::Delete => Some((ChangeKind::Removed, text)),
+                ChangeTag::Insert => Some((ChangeKind::Added, text)),
+                ChangeTag::Equal => None,
+            }
+        })
+        .collect();
+    BodyDiff::Text(lines)
+}
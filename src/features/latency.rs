@@ -0,0 +1,103 @@
+// Latency of saved requests, kept across sessions in the workspace's latency.json
+// so a regression between two test sessions shows up on the chart.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const FILE: &str = "latency.json";
+/// Samples kept per request, oldest dropped first
+pub const LIMIT: usize = 1000;
+/// A pause this long (seconds) between two sends starts a new session
+pub const SESSION_GAP: u64 = 30 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    /// Unix seconds
+    pub at: u64,
+    pub ms: u64,
+    pub status: u16,
+}
+
+/// Samples by `collection/request`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LatencyStore(pub BTreeMap<String, Vec<Sample>>);
+
+pub fn key(collection: &str, request: &str) -> String {
+    format!("{}/{}", collection, request)
+}
+
+impl LatencyStore {
+    pub fn record(&mut self, key: String, sample: Sample) {
+        let samples = self.0.entry(key).or_default();
+        samples.push(sample);
+        if samples.len() > LIMIT {
+            samples.drain(..samples.len() - LIMIT);
+        }
+    }
+
+    pub fn samples(&self, key: &str) -> &[Sample] {
+        self.0.get(key).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Percentiles of a set of samples, nearest rank
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub count: usize,
+    pub min: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+impl Stats {
+    pub fn of(samples: &[Sample]) -> Option<Stats> {
+        let mut ms: Vec<u64> = samples.iter().map(|s| s.ms).collect();
+        if ms.is_empty() {
+            return None;
+        }
+        ms.sort_unstable();
+        let rank =
+            |p: f64| ms[((p / 100.0 * ms.len() as f64).ceil() as usize).clamp(1, ms.len()) - 1];
+        Some(Stats {
+            count: ms.len(),
+            min: ms[0],
+            p50: rank(50.0),
+            p90: rank(90.0),
+            p95: rank(95.0),
+            p99: rank(99.0),
+            max: ms[ms.len() - 1],
+        })
+    }
+}
+
+/// Runs of samples without a `SESSION_GAP` pause in them, oldest first
+pub fn sessions(samples: &[Sample]) -> Vec<&[Sample]> {
+    let mut sessions = Vec::new();
+    let mut start = 0;
+    for i in 1..samples.len() {
+        if samples[i].at.saturating_sub(samples[i - 1].at) > SESSION_GAP {
+            sessions.push(&samples[start..i]);
+            start = i;
+        }
+    }
+    if start < samples.len() {
+        sessions.push(&samples[start..]);
+    }
+    sessions
+}
+
+/// How the last session's median compares with the one before, in percent
+pub fn session_change(samples: &[Sample]) -> Option<f64> {
+    let sessions = sessions(samples);
+    let [.., before, last] = sessions.as_slice() else {
+        return None;
+    };
+    let (before, last) = (Stats::of(before)?.p50, Stats::of(last)?.p50);
+    if before == 0 {
+        return None;
+    }
+    Some((last as f64 - before as f64) / before as f64 * 100.0)
+}
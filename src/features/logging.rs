@@ -0,0 +1,283 @@
+// What happened to each request, kept where it can be read after the fact: every
+// `tracing` event from PostDad goes to a rotating JSON-lines file in the workspace
+// and to a buffer the `:logs` view shows. Events carry the request id of the send
+// they belong to, from their own `request_id` field or a `request` span around them.
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// In the workspace
+pub const FILE: &str = "logs/postdad.log";
+/// Size a log file grows to before it's rotated
+pub const MAX_BYTES: u64 = 1024 * 1024;
+/// Rotated files kept, `postdad.log.1` the newest
+pub const KEEP: usize = 3;
+/// Records the `:logs` view can go back through
+pub const BUFFER: usize = 2000;
+/// Sets the level: error, warn, info (the default), debug or trace
+pub const LEVEL_ENV: &str = "POSTDAD_LOG";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LogRecord {
+    /// Unix milliseconds
+    pub at_ms: u64,
+    pub level: String,
+    /// The module it came from, `PostDad::net::http`
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+}
+
+impl LogRecord {
+    pub fn is_problem(&self) -> bool {
+        self.level == "ERROR" || self.level == "WARN"
+    }
+
+    /// `HH:MM:SS.mmm` UTC
+    pub fn clock(&self) -> String {
+        let ms = self.at_ms % 86_400_000;
+        format!(
+            "{:02}:{:02}:{:02}.{:03}",
+            ms / 3_600_000,
+            ms / 60_000 % 60,
+            ms / 1000 % 60,
+            ms % 1000
+        )
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.message.to_lowercase().contains(&text)
+            || self.target.to_lowercase().contains(&text)
+            || self.request_id.as_deref() == Some(text.as_str())
+            || self
+                .fields
+                .values()
+                .any(|v| v.to_lowercase().contains(&text))
+    }
+}
+
+/// Shared between the layer writing records and the `:logs` view reading them
+pub type LogBuffer = Arc<Mutex<VecDeque<LogRecord>>>;
+
+/// A short id tying a send's log records together
+pub fn new_request_id() -> String {
+    format!("{:08x}", rand::random::<u32>())
+}
+
+/// The level `POSTDAD_LOG` asks for, info if it's unset or not a level
+pub fn level_from_env() -> Level {
+    std::env::var(LEVEL_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(Level::INFO)
+}
+
+/// A file that moves itself to `.1` (and `.1` to `.2`, ...) once it's `max_bytes`
+struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    file: Option<std::fs::File>,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, max_bytes: u64) -> Self {
+        let mut file = RotatingFile {
+            path,
+            max_bytes,
+            file: None,
+            size: 0,
+        };
+        file.reopen();
+        file
+    }
+
+    fn reopen(&mut self) {
+        if let Some(dir) = self.path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        self.file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .ok();
+        self.size = std::fs::metadata(&self.path).map_or(0, |m| m.len());
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) {
+        self.file = None;
+        let _ = std::fs::remove_file(self.rotated(KEEP));
+        for n in (1..KEEP).rev() {
+            let _ = std::fs::rename(self.rotated(n), self.rotated(n + 1));
+        }
+        let _ = std::fs::rename(&self.path, self.rotated(1));
+        self.reopen();
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.size > 0 && self.size + line.len() as u64 + 1 > self.max_bytes {
+            self.rotate();
+        }
+        if let Some(file) = self.file.as_mut()
+            && writeln!(file, "{}", line).is_ok()
+        {
+            self.size += line.len() as u64 + 1;
+        }
+    }
+}
+
+#[derive(Default)]
+struct Fields {
+    message: Option<String>,
+    values: BTreeMap<String, String>,
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+        } else {
+            self.values
+                .insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        } else {
+            self.values
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+}
+
+/// A span's `request_id`, kept in its extensions for the events inside it
+struct RequestId(String);
+
+pub struct LogLayer {
+    buffer: LogBuffer,
+    file: Option<Mutex<RotatingFile>>,
+    level: Level,
+}
+
+impl LogLayer {
+    /// Records at `level` and above into `buffer`, and into `file` when given
+    pub fn new(buffer: LogBuffer, file: Option<&Path>, level: Level, max_bytes: u64) -> Self {
+        LogLayer {
+            buffer,
+            file: file.map(|path| Mutex::new(RotatingFile::open(path.to_path_buf(), max_bytes))),
+            level,
+        }
+    }
+}
+
+impl<S> Layer<S> for LogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        // Only ours: reqwest, hyper and friends have their own idea of info
+        metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+            && (metadata.is_span() || *metadata.level() <= self.level)
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        if let (Some(request_id), Some(span)) = (fields.values.remove("request_id"), ctx.span(id)) {
+            span.extensions_mut().insert(RequestId(request_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let request_id = fields.values.remove("request_id").or_else(|| {
+            ctx.event_scope(event)?
+                .find_map(|span| span.extensions().get::<RequestId>().map(|id| id.0.clone()))
+        });
+        let metadata = event.metadata();
+        let record = LogRecord {
+            at_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            request_id,
+            message: fields.message.unwrap_or_default(),
+            fields: fields.values,
+        };
+        if let Some(file) = &self.file
+            && let (Ok(mut file), Ok(line)) = (file.lock(), serde_json::to_string(&record))
+        {
+            file.write_line(&line);
+        }
+        if let Ok(mut buffer) = self.buffer.lock() {
+            if buffer.len() >= BUFFER {
+                buffer.pop_front();
+            }
+            buffer.push_back(record);
+        }
+    }
+}
+
+/// Installs the layer for the whole process, writing to the workspace's log file.
+/// The returned buffer is what the `:logs` view reads.
+pub fn init(workspace: &crate::domain::workspace::Workspace) -> LogBuffer {
+    use tracing_subscriber::layer::SubscriberExt;
+    let buffer = LogBuffer::default();
+    let layer = LogLayer::new(
+        buffer.clone(),
+        Some(Path::new(&workspace.path(FILE))),
+        level_from_env(),
+        MAX_BYTES,
+    );
+    let _ = tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer));
+    buffer
+}
+
+/// The `:logs` view
+#[derive(Clone, Debug, Default)]
+pub struct LogView {
+    /// Rows up from the newest record, 0 follows new ones
+    pub scroll: usize,
+    /// Warnings and errors only
+    pub problems_only: bool,
+    /// Only records of this request id
+    pub request_id: Option<String>,
+    /// Only records mentioning this
+    pub filter: String,
+}
+
+impl LogView {
+    pub fn visible<'a>(&self, records: &'a VecDeque<LogRecord>) -> Vec<&'a LogRecord> {
+        records
+            .iter()
+            .filter(|r| !self.problems_only || r.is_problem())
+            .filter(|r| {
+                self.request_id
+                    .as_ref()
+                    .is_none_or(|id| r.request_id.as_ref() == Some(id))
+            })
+            .filter(|r| self.filter.is_empty() || r.matches(&self.filter))
+            .collect()
+    }
+}
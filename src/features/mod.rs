@@ -1,7 +1,53 @@
+pub mod audit;
+pub mod batch;
+pub mod bundle;
 pub mod cli;
+pub mod codegen;
+pub mod collection_diff;
+pub mod command;
+pub mod csv_export;
+pub mod csv_view;
+pub mod curl_export;
+pub mod curl_import;
+pub mod dashboard;
+pub mod dedupe;
 pub mod doc_gen;
+pub mod doc_template;
+pub mod doctor;
+pub mod env_usage;
+pub mod finder;
+pub mod format;
+pub mod fuzz;
+pub mod gist_sync;
+pub mod hex;
+pub mod history;
+pub mod image_view;
 pub mod import;
+pub mod insomnia_import;
+pub mod json_diff;
+pub mod latency;
+pub mod logging;
+pub mod monitor;
+pub mod ndjson;
+pub mod paginate;
+pub mod postman_script;
+pub mod presend;
+pub mod protos;
+pub mod range;
+pub mod recovery;
+pub mod response_dump;
+pub mod results_export;
+pub mod run_hooks;
 pub mod runner;
 pub mod scripting;
 pub mod sentinel;
+pub mod snapshot;
+pub mod snippets;
+pub mod storage;
 pub mod stress;
+pub mod template;
+pub mod thunder_import;
+pub mod token_refresh;
+pub mod trace;
+pub mod vault;
+pub mod ws_session;
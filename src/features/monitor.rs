@@ -0,0 +1,196 @@
+// Headless sentinel: `PostDad monitor` runs a collection through the runner every
+// interval, keeps each request's failure streak and the recent error rate, and says
+// when a threshold is crossed.
+use crate::features::runner::RunResult;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+
+/// Rounds the error rate is taken over
+pub const WINDOW: usize = 10;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorSettings {
+    pub interval_secs: u64,
+    /// Give up once a request has failed this many rounds in a row
+    pub max_failures: u32,
+    /// Give up once more than this share (percent) of the checks in the last
+    /// `WINDOW` rounds failed
+    pub max_error_rate: Option<f64>,
+    /// Stop after this many rounds, for cron; forever when None
+    pub rounds: Option<u64>,
+}
+
+impl Default for MonitorSettings {
+    fn default() -> Self {
+        MonitorSettings {
+            interval_secs: 30,
+            max_failures: 3,
+            max_error_rate: None,
+            rounds: None,
+        }
+    }
+}
+
+/// A request going down or coming back, what the webhook is told about
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Transition {
+    /// `down` or `up`
+    pub event: &'static str,
+    pub name: String,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    /// Failed rounds in a row it came back after, for `up`
+    pub streak: u32,
+}
+
+#[derive(Default)]
+pub struct Monitor {
+    pub settings: MonitorSettings,
+    pub round: u64,
+    /// Failed rounds in a row, per request
+    pub streaks: BTreeMap<String, u32>,
+    /// (checks, failed) of the last `WINDOW` rounds
+    pub window: VecDeque<(usize, usize)>,
+    pub total_checks: u64,
+    pub total_failed: u64,
+}
+
+impl Monitor {
+    pub fn new(settings: MonitorSettings) -> Self {
+        Monitor {
+            settings,
+            ..Default::default()
+        }
+    }
+
+    /// Counts a round in, returning the requests that went down or came back
+    pub fn record_round(&mut self, results: &[RunResult]) -> Vec<Transition> {
+        self.round += 1;
+        let failed = results.iter().filter(|r| !r.passed).count();
+        self.total_checks += results.len() as u64;
+        self.total_failed += failed as u64;
+        if self.window.len() >= WINDOW {
+            self.window.pop_front();
+        }
+        self.window.push_back((results.len(), failed));
+
+        let mut transitions = Vec::new();
+        for result in results {
+            let streak = self.streaks.entry(result.name.clone()).or_insert(0);
+            if result.passed {
+                if *streak > 0 {
+                    transitions.push(Transition {
+                        event: "up",
+                        name: result.name.clone(),
+                        status: result.status,
+                        error: None,
+                        streak: *streak,
+                    });
+                }
+                *streak = 0;
+            } else {
+                *streak += 1;
+                if *streak == 1 {
+                    transitions.push(Transition {
+                        event: "down",
+                        name: result.name.clone(),
+                        status: result.status,
+                        error: result.error.clone(),
+                        streak: 1,
+                    });
+                }
+            }
+        }
+        transitions
+    }
+
+    /// Failed share of the checks in the window, in percent
+    pub fn error_rate(&self) -> f64 {
+        let (checks, failed) = self
+            .window
+            .iter()
+            .fold((0, 0), |(c, f), (checks, failed)| (c + checks, f + failed));
+        if checks == 0 {
+            0.0
+        } else {
+            failed as f64 * 100.0 / checks as f64
+        }
+    }
+
+    /// Why monitoring should stop with an error, if a threshold is crossed
+    pub fn breach(&self) -> Option<String> {
+        let max = self.settings.max_failures.max(1);
+        let down: Vec<String> = self
+            .streaks
+            .iter()
+            .filter(|(_, streak)| **streak >= max)
+            .map(|(name, _)| name.clone())
+            .collect();
+        if !down.is_empty() {
+            return Some(format!(
+                "{} failed {} rounds in a row",
+                down.join(", "),
+                max
+            ));
+        }
+        let rate = self.error_rate();
+        match self.settings.max_error_rate {
+            Some(limit) if rate > limit => Some(format!(
+                "Error rate {:.1}% over the last {} rounds is above {}%",
+                rate,
+                self.window.len(),
+                limit
+            )),
+            _ => None,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.settings
+            .rounds
+            .is_some_and(|rounds| self.round >= rounds)
+    }
+
+    /// `round 5 · 7/8 up · avg 120ms · error rate 2.5% · down: Login (500, 2×)`
+    pub fn status_line(&self, results: &[RunResult]) -> String {
+        let up = results.iter().filter(|r| r.passed).count();
+        let latencies: Vec<u128> = results.iter().filter_map(|r| r.latency_ms).collect();
+        let mut line = format!("round {} · {}/{} up", self.round, up, results.len());
+        if !latencies.is_empty() {
+            let avg = latencies.iter().sum::<u128>() / latencies.len() as u128;
+            line.push_str(&format!(" · avg {}ms", avg));
+        }
+        line.push_str(&format!(" · error rate {:.1}%", self.error_rate()));
+        let down: Vec<String> = results
+            .iter()
+            .filter(|r| !r.passed)
+            .map(|r| {
+                let why = r
+                    .status
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "error".to_string());
+                let streak = self.streaks.get(&r.name).copied().unwrap_or(0);
+                format!("{} ({}, {}×)", r.name, why, streak)
+            })
+            .collect();
+        if !down.is_empty() {
+            line.push_str(&format!(" · down: {}", down.join(", ")));
+        }
+        line
+    }
+}
+
+/// The webhook body for a transition or a breach: a Slack-style `text` plus the details
+pub fn webhook_payload(
+    collection: &str,
+    event: &str,
+    text: String,
+    detail: serde_json::Value,
+) -> serde_json::Value {
+    serde_json::json!({
+        "text": format!("PostDad monitor: {} {}", collection, text),
+        "event": event,
+        "collection": collection,
+        "detail": detail,
+    })
+}
@@ -0,0 +1,62 @@
+// NDJSON / JSON Lines responses: one JSON document per line. `L` lists the lines,
+// filterable, and Enter opens one in the tree explorer.
+use serde_json::Value;
+
+/// One non-blank line of the body
+#[derive(Debug, Clone, PartialEq)]
+pub struct NdjsonLine {
+    /// 1-based, counting blank lines too, so it matches an editor
+    pub number: usize,
+    pub text: String,
+    /// Why the line isn't JSON, when it isn't
+    pub value: Result<Value, String>,
+}
+
+const CONTENT_TYPES: &[&str] = &["ndjson", "jsonl", "json-lines", "jsonlines", "json-seq"];
+
+/// Said so by the content type, or a body that isn't one JSON document but
+/// whose lines (at least two) each are
+pub fn is_ndjson(content_type: &str, text: &str) -> bool {
+    let content_type = content_type.to_ascii_lowercase();
+    if CONTENT_TYPES.iter().any(|t| content_type.contains(t)) {
+        return true;
+    }
+    if serde_json::from_str::<Value>(text).is_ok() {
+        return false;
+    }
+    let lines = parse(text);
+    lines.len() >= 2
+        && lines
+            .iter()
+            .all(|l| matches!(l.value, Ok(Value::Object(_) | Value::Array(_))))
+}
+
+/// Each non-blank line, parsed on its own. JSON text sequences' record
+/// separators (`application/json-seq`) are dropped.
+pub fn parse(text: &str) -> Vec<NdjsonLine> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = line.trim().trim_start_matches('\u{1e}');
+            if line.is_empty() {
+                return None;
+            }
+            Some(NdjsonLine {
+                number: i + 1,
+                text: line.to_string(),
+                value: serde_json::from_str(line).map_err(|e| e.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Indices of the lines containing `query`, ignoring case. Every line when it's empty.
+pub fn filter(lines: &[NdjsonLine], query: &str) -> Vec<usize> {
+    let query = query.to_lowercase();
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| query.is_empty() || l.text.to_lowercase().contains(&query))
+        .map(|(i, _)| i)
+        .collect()
+}
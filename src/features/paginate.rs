@@ -0,0 +1,479 @@
+// Pagination: `:paginate` walks a paged API from the active tab's request, page after
+// page, and shows every page's items as one JSON array. How the next page is found
+// and what counts as an item is saved with the request (`paginate = { ... }`).
+use crate::app::{App, OutgoingRequest};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Pages fetched when the request doesn't say
+pub const DEFAULT_MAX_PAGES: usize = 50;
+
+/// How to get from one page to the next, by the first of `next`, `cursor_header` or
+/// `page_param` that's set
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaginateConfig {
+    /// JSONPath to the next page's URL in the body, e.g. `$.links.next`; relative
+    /// URLs are taken from the page's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+    /// Response header with the next page's cursor, sent back in `cursor_param`.
+    /// `Link` is read for its `rel="next"` URL instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor_header: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor_param: Option<String>,
+    /// Query parameter counted up from `start` by `step` (both 1 when not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_param: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<i64>,
+    /// JSONPath to a page's items, e.g. `$.data`; when not set, the body if it's an
+    /// array, or its only array field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<String>,
+    /// JSONPath saying there's more, e.g. `$.has_more`: false, null, 0 or "" stops
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub more: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_pages: Option<usize>,
+}
+
+impl PaginateConfig {
+    pub fn max_pages(&self) -> usize {
+        self.max_pages.unwrap_or(DEFAULT_MAX_PAGES).max(1)
+    }
+
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(next) = &self.next {
+            parts.push(format!("next {}", next));
+        } else if let Some(header) = &self.cursor_header {
+            match &self.cursor_param {
+                Some(param) => parts.push(format!("cursor {} -> {}", header, param)),
+                None => parts.push(format!("cursor {}", header)),
+            }
+        } else if let Some(param) = &self.page_param {
+            parts.push(format!(
+                "page {} from {} by {}",
+                param,
+                self.start.unwrap_or(1),
+                self.step.unwrap_or(1)
+            ));
+        } else {
+            parts.push("no next page set".to_string());
+        }
+        if let Some(items) = &self.items {
+            parts.push(format!("items {}", items));
+        }
+        if let Some(more) = &self.more {
+            parts.push(format!("while {}", more));
+        }
+        parts.push(format!("max {} pages", self.max_pages()));
+        parts.join(", ")
+    }
+}
+
+/// `:paginate next <path>`, `cursor <header> [param]`, `page <param> [start] [step]`,
+/// `items <path>`, `more <path>`, `max <n>`; `items`/`more` with nothing clear them.
+/// Returns the new summary.
+pub fn apply_command(config: &mut PaginateConfig, args: &str) -> Result<String, String> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let number = |v: &str| -> Result<i64, String> {
+        v.parse::<i64>()
+            .map_err(|_| format!("'{}' isn't a number", v))
+    };
+    let path = |v: &str| -> Result<String, String> {
+        jsonpath_lib::Compiled::compile(v).map_err(|e| format!("Bad JSONPath {}: {}", v, e))?;
+        Ok(v.to_string())
+    };
+    match parts.as_slice() {
+        ["next", p] => {
+            *config = PaginateConfig {
+                next: Some(path(p)?),
+                ..without_mode(config)
+            }
+        }
+        ["cursor", header] | ["cursor", header, _] => {
+            *config = PaginateConfig {
+                cursor_header: Some(header.to_string()),
+                cursor_param: parts.get(2).map(|p| p.to_string()),
+                ..without_mode(config)
+            };
+            if config.cursor_param.is_none() && !header.eq_ignore_ascii_case("link") {
+                return Err(
+                    "Usage: :paginate cursor <header> <param> (only Link needs no param)"
+                        .to_string(),
+                );
+            }
+        }
+        ["page", param, rest @ ..] if rest.len() <= 2 => {
+            *config = PaginateConfig {
+                page_param: Some(param.to_string()),
+                start: rest.first().map(|v| number(v)).transpose()?,
+                step: rest.get(1).map(|v| number(v)).transpose()?,
+                ..without_mode(config)
+            }
+        }
+        ["items"] => config.items = None,
+        ["items", p] => config.items = Some(path(p)?),
+        ["more"] => config.more = None,
+        ["more", p] => config.more = Some(path(p)?),
+        ["max", n] => match n.parse::<usize>() {
+            Ok(n) if n > 0 => config.max_pages = Some(n),
+            _ => return Err(format!("'{}' isn't a page count", n)),
+        },
+        _ => {
+            return Err(
+                "Usage: :paginate [next <path>|cursor <header> [param]|page <param> [start] [step]|items <path>|more <path>|max <n>|off]"
+                    .to_string(),
+            );
+        }
+    }
+    Ok(config.describe())
+}
+
+/// `config` with none of the ways to the next page set, for switching to another
+fn without_mode(config: &PaginateConfig) -> PaginateConfig {
+    PaginateConfig {
+        next: None,
+        cursor_header: None,
+        cursor_param: None,
+        page_param: None,
+        start: None,
+        step: None,
+        ..config.clone()
+    }
+}
+
+fn select<'a>(json: &'a Value, path: &str) -> Vec<&'a Value> {
+    jsonpath_lib::select(json, path).unwrap_or_default()
+}
+
+/// A page's items, as `items` says
+pub fn page_items(config: &PaginateConfig, body: &str) -> Result<Vec<Value>, String> {
+    let json: Value = serde_json::from_str(body).map_err(|_| "the page isn't JSON".to_string())?;
+    if let Some(path) = &config.items {
+        let found = select(&json, path);
+        return Ok(match found.as_slice() {
+            [Value::Array(items)] => items.clone(),
+            _ => found.into_iter().cloned().collect(),
+        });
+    }
+    match &json {
+        Value::Array(items) => Ok(items.clone()),
+        Value::Object(map) => {
+            let arrays: Vec<&Vec<Value>> = map.values().filter_map(Value::as_array).collect();
+            match arrays.as_slice() {
+                [items] => Ok((*items).clone()),
+                _ => Err(
+                    "can't tell which field has the items, set them with :paginate items <path>"
+                        .to_string(),
+                ),
+            }
+        }
+        _ => Err("the page has no items array".to_string()),
+    }
+}
+
+/// `url` with `name` set to `value`, in place of any it had
+pub fn set_query(url: &str, name: &str, value: &str) -> Result<String, String> {
+    let mut parsed = reqwest::Url::parse(url).map_err(|e| format!("{}: {}", url, e))?;
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| k != name)
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    parsed
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(kept)
+        .append_pair(name, value);
+    Ok(parsed.to_string())
+}
+
+/// The first page's URL: page mode starts at `start`, the others as the request is
+pub fn first_url(config: &PaginateConfig, url: &str) -> Result<String, String> {
+    match (&config.next, &config.cursor_header, &config.page_param) {
+        (None, None, Some(param)) => set_query(url, param, &config.start.unwrap_or(1).to_string()),
+        _ => Ok(url.to_string()),
+    }
+}
+
+/// The `rel="next"` URL of a `Link` header
+fn link_next(link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let (target, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|p| {
+                let p = p.trim().replace(' ', "");
+                p == "rel=\"next\"" || p == "rel=next"
+            })
+            .then(|| {
+                target
+                    .trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+fn is_falsy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => true,
+        Some(Value::Bool(b)) => !b,
+        Some(Value::Number(n)) => n.as_f64() == Some(0.0),
+        Some(Value::String(s)) => s.is_empty() || s == "false",
+        Some(_) => false,
+    }
+}
+
+/// What comes after a page
+#[derive(Debug, Clone, PartialEq)]
+pub enum Next {
+    /// The next page's URL
+    Page(String),
+    /// That was the last page, and why
+    Last(String),
+    /// The next page can't be worked out
+    Failed(String),
+}
+
+/// What comes after `page` (1 for the first), fetched from `url`
+pub fn next_url(
+    config: &PaginateConfig,
+    page: usize,
+    url: &str,
+    body: &str,
+    headers: &HashMap<String, String>,
+) -> Next {
+    let json = serde_json::from_str::<Value>(body).ok();
+    if let Some(more) = &config.more {
+        let found = json.as_ref().map(|j| select(j, more)).unwrap_or_default();
+        if is_falsy(found.first().copied()) {
+            return Next::Last(format!("{} says it's the last page", more));
+        }
+    }
+    let resolve = |next: &str| match reqwest::Url::parse(url).and_then(|base| base.join(next)) {
+        Ok(next) => Next::Page(next.to_string()),
+        Err(e) => Next::Failed(format!("next page {}: {}", next, e)),
+    };
+    let query = |param: &str, value: &str| match set_query(url, param, value) {
+        Ok(next) => Next::Page(next),
+        Err(e) => Next::Failed(e),
+    };
+    if let Some(path) = &config.next {
+        let found = json.as_ref().map(|j| select(j, path)).unwrap_or_default();
+        return match found.first() {
+            Some(Value::String(next)) if !next.is_empty() => resolve(next),
+            _ => Next::Last(format!("no next page at {}", path)),
+        };
+    }
+    if let Some(header) = &config.cursor_header {
+        let value = headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(header))
+            .map(|(_, v)| v.trim())
+            .filter(|v| !v.is_empty());
+        let Some(value) = value else {
+            return Next::Last(format!("no {} header", header));
+        };
+        return match &config.cursor_param {
+            Some(param) => query(param, value),
+            None => match link_next(value) {
+                Some(next) => resolve(&next),
+                None => Next::Last(format!("no rel=\"next\" in {}", header)),
+            },
+        };
+    }
+    if let Some(param) = &config.page_param {
+        let number = config
+            .step
+            .unwrap_or(1)
+            .checked_mul(page as i64)
+            .and_then(|offset| config.start.unwrap_or(1).checked_add(offset));
+        return match number {
+            Some(number) => query(param, &number.to_string()),
+            None => Next::Failed(format!("page {} is past the largest page number", page + 1)),
+        };
+    }
+    Next::Failed("no way to the next page set (:paginate next|cursor|page)".to_string())
+}
+
+/// Everything `run_pagination` needs, taken from the active tab and the app
+#[derive(Debug, Clone)]
+pub struct PaginationJob {
+    /// Routes the result back to the tab, like a send's
+    pub request_id: String,
+    pub request: OutgoingRequest,
+    pub timeout_ms: u64,
+    pub config: PaginateConfig,
+    pub rate_limit: crate::net::rate_limit::RateLimitSettings,
+    /// Built like a send's client, so pages go through the same proxy and TLS
+    pub client: crate::net::http::ClientConfig,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaginationResult {
+    /// The request id the asking tab was given, see `App::tab_for_request`
+    pub request_id: String,
+    pub pages: usize,
+    /// Every page's items, in order
+    pub items: Vec<Value>,
+    /// The last page's status and headers; no status when no page came back
+    pub status: Option<u16>,
+    pub headers: HashMap<String, String>,
+    /// All the pages together
+    pub latency_ms: u64,
+    /// Why it stopped, e.g. `no next page at $.next`
+    pub stopped: String,
+    /// Set when a page failed rather than ran out
+    pub error: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum PaginateEvent {
+    Done(PaginationResult),
+}
+
+async fn fetch(
+    client: &reqwest::Client,
+    request: &OutgoingRequest,
+    timeout_ms: u64,
+) -> Result<(u16, String, HashMap<String, String>), String> {
+    let mut built = App::build_outgoing(client, request)?;
+    *built.timeout_mut() = Some(Duration::from_millis(timeout_ms));
+    let response = client.execute(built).await.map_err(|e| e.to_string())?;
+    let status = response.status().as_u16();
+    let headers: HashMap<String, String> = response
+        .headers()
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+        .collect();
+    let raw = response
+        .bytes()
+        .await
+        .map(|b| b.to_vec())
+        .unwrap_or_default();
+    let (body, _) = crate::net::encoding::decode_body(&headers, raw);
+    Ok((status, String::from_utf8_lossy(&body).into_owned(), headers))
+}
+
+/// Whether `a` and `b` share scheme, host and port
+pub fn same_origin(a: &str, b: &str) -> bool {
+    match (reqwest::Url::parse(a), reqwest::Url::parse(b)) {
+        (Ok(a), Ok(b)) => a.origin() == b.origin(),
+        _ => false,
+    }
+}
+
+/// `request` sent to `url`, without its auth and cookies when `url` is on another
+/// origin: a `next` link is the server's to choose
+pub fn page_request(request: &OutgoingRequest, url: &str) -> OutgoingRequest {
+    let mut page = request.clone();
+    page.url = url.to_string();
+    if !same_origin(&request.url, url) {
+        page.auth = None;
+        page.headers.retain(|k, _| {
+            !k.eq_ignore_ascii_case("authorization")
+                && !k.eq_ignore_ascii_case("proxy-authorization")
+                && !k.eq_ignore_ascii_case("cookie")
+        });
+    }
+    page
+}
+
+/// Fetches page after page of the job's request until one says it's the last, has
+/// no items, or `max_pages` is reached. Every page takes its slot under the rate
+/// limits and goes out on a client built like the send's.
+pub async fn run_pagination(job: PaginationJob, tx: mpsc::Sender<PaginateEvent>) {
+    let PaginationJob {
+        request_id,
+        request,
+        timeout_ms,
+        config,
+        rate_limit,
+        client,
+    } = job;
+    let client = client.build();
+    let start = Instant::now();
+    let mut result = PaginationResult {
+        request_id,
+        pages: 0,
+        items: Vec::new(),
+        status: None,
+        headers: HashMap::new(),
+        latency_ms: 0,
+        stopped: String::new(),
+        error: None,
+    };
+    let mut seen = Vec::new();
+    let mut next = match first_url(&config, &request.url) {
+        Ok(url) => Next::Page(url),
+        Err(e) => Next::Failed(e),
+    };
+
+    loop {
+        let current = match next {
+            Next::Page(url) => url,
+            Next::Last(reason) => {
+                result.stopped = reason;
+                break;
+            }
+            Next::Failed(e) => {
+                result.error = Some(e);
+                break;
+            }
+        };
+        if seen.contains(&current) {
+            result.stopped = "the next page is one already fetched".to_string();
+            break;
+        }
+        if result.pages >= config.max_pages() {
+            result.stopped = format!("stopped at {} pages", result.pages);
+            break;
+        }
+        seen.push(current.clone());
+        if let Some(throttle) = crate::net::rate_limit::reserve(&rate_limit, None, &current) {
+            tokio::time::sleep(throttle.wait).await;
+        }
+
+        let page = page_request(&request, &current);
+        let (status, body, headers) = match fetch(&client, &page, timeout_ms).await {
+            Ok(page) => page,
+            Err(e) => {
+                result.error = Some(format!("page {}: {}", result.pages + 1, e));
+                break;
+            }
+        };
+        result.pages += 1;
+        result.status = Some(status);
+        result.headers = headers.clone();
+        if status >= 400 {
+            result.error = Some(format!("page {}: HTTP {}", result.pages, status));
+            break;
+        }
+        let items = match page_items(&config, &body) {
+            Ok(items) => items,
+            Err(e) => {
+                result.error = Some(format!("page {}: {}", result.pages, e));
+                break;
+            }
+        };
+        if items.is_empty() {
+            result.stopped = format!("page {} was empty", result.pages);
+            break;
+        }
+        result.items.extend(items);
+        next = next_url(&config, result.pages, &current, &body, &headers);
+    }
+    result.latency_ms = start.elapsed().as_millis() as u64;
+    let _ = tx.send(PaginateEvent::Done(result)).await;
+}
@@ -0,0 +1,650 @@
+// Best-effort conversion of Postman `pm.*` scripts into PostDad's Rhai scripts.
+// Setting variables, request headers and simple status / body / JSON tests convert;
+// anything else is kept as a `// [postman]` comment so it can be ported by hand.
+
+/// Which PostDad script the Postman one becomes, they have different functions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScriptKind {
+    /// `set_header`, `set_var`, `get_var`, ...
+    PreRequest,
+    /// `test`, `status_code`, `json_path`, ...
+    Test,
+}
+
+/// Marks lines that could not be converted
+pub const UNCONVERTED: &str = "// [postman] ";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Transpiled {
+    pub script: String,
+    /// Statements (a whole `pm.test` counts as one) that became Rhai
+    pub converted: usize,
+    /// Statements left as comments
+    pub skipped: usize,
+}
+
+/// What's left in converted code means the conversion went wrong somewhere
+const FORBIDDEN: [&str; 15] = [
+    "pm.",
+    "postman.",
+    "JSON.",
+    "function",
+    "=>",
+    "new ",
+    "?",
+    "require",
+    "_.",
+    "CryptoJS",
+    "Math.",
+    "Date",
+    "undefined",
+    "null",
+    "typeof",
+];
+
+struct Converter {
+    kind: ScriptKind,
+    /// Variables holding the parsed response body, `var jsonData = pm.response.json()`
+    json_aliases: Vec<String>,
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Reads a JS string literal at the start of `s`, returns (value, bytes consumed)
+fn string_literal(s: &str) -> Option<(String, usize)> {
+    let quote = s.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let mut value = String::new();
+    let mut escape = false;
+    for (i, c) in s.char_indices().skip(1) {
+        if escape {
+            value.push(match c {
+                'n' => '\n',
+                't' => '\t',
+                other => other,
+            });
+            escape = false;
+        } else if c == '\\' {
+            escape = true;
+        } else if c == quote {
+            return Some((value, i + 1));
+        } else {
+            value.push(c);
+        }
+    }
+    None
+}
+
+fn rhai_string(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+/// Splits on `sep` outside strings, brackets and parentheses
+fn split_top_level(s: &str, seps: &[char]) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0i32;
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if (c == '"' || c == '\'')
+            && let Some((_, len)) = string_literal(&s[i..])
+        {
+            current.push_str(&s[i..i + len]);
+            while chars.peek().is_some_and(|(j, _)| *j < i + len) {
+                chars.next();
+            }
+            continue;
+        }
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 && seps.contains(&c) {
+            parts.push(std::mem::take(&mut current));
+        } else {
+            current.push(c);
+        }
+    }
+    parts.push(current);
+    parts
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Net parenthesis depth of a line, ignoring strings and `//` comments
+fn paren_delta(line: &str) -> i32 {
+    let mut delta = 0;
+    let mut i = 0;
+    while i < line.len() {
+        let rest = &line[i..];
+        if rest.starts_with("//") {
+            break;
+        }
+        if let Some((_, len)) = string_literal(rest) {
+            i += len;
+            continue;
+        }
+        let c = rest.chars().next().unwrap_or(' ');
+        match c {
+            '(' => delta += 1,
+            ')' => delta -= 1,
+            _ => {}
+        }
+        i += c.len_utf8();
+    }
+    delta
+}
+
+/// The argument list of a call whose `(` starts `s`, and the bytes consumed
+fn call_args(s: &str) -> Option<(&str, usize)> {
+    if !s.starts_with('(') {
+        return None;
+    }
+    let mut depth = 0;
+    let mut i = 0;
+    while i < s.len() {
+        let rest = &s[i..];
+        if let Some((_, len)) = string_literal(rest) {
+            i += len;
+            continue;
+        }
+        let c = rest.chars().next()?;
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((&s[1..i], i + 1));
+                }
+            }
+            _ => {}
+        }
+        i += c.len_utf8();
+    }
+    None
+}
+
+/// Numbers get a `.0` so they compare with `parse_float`
+fn float_literal(js: &str) -> Option<String> {
+    let n: f64 = js.trim().parse().ok()?;
+    Some(if n.fract() == 0.0 {
+        format!("{:.1}", n)
+    } else {
+        n.to_string()
+    })
+}
+
+impl Converter {
+    fn new(kind: ScriptKind) -> Self {
+        Converter {
+            kind,
+            json_aliases: Vec::new(),
+        }
+    }
+
+    /// `.data[0]["a b"]` after a JSON source becomes a JSONPath, with bytes consumed.
+    /// Stops before method calls and `.length`.
+    fn json_accessor(s: &str) -> (String, usize) {
+        let mut path = String::from("$");
+        let mut i = 0;
+        loop {
+            let rest = &s[i..];
+            if let Some(after_dot) = rest.strip_prefix('.') {
+                let ident: String = after_dot
+                    .chars()
+                    .take_while(|c| is_ident_char(*c))
+                    .collect();
+                let next = after_dot[ident.len()..].chars().next();
+                if ident.is_empty() || ident == "length" || next == Some('(') {
+                    break;
+                }
+                path.push('.');
+                path.push_str(&ident);
+                i += 1 + ident.len();
+            } else if let Some(inner) = rest.strip_prefix('[') {
+                if let Some((key, len)) = string_literal(inner)
+                    && inner[len..].starts_with(']')
+                {
+                    path.push_str(&format!("['{}']", key.replace('\'', "\\'")));
+                    i += len + 2;
+                } else {
+                    let digits: String = inner.chars().take_while(|c| c.is_ascii_digit()).collect();
+                    if digits.is_empty() || !inner[digits.len()..].starts_with(']') {
+                        break;
+                    }
+                    path.push_str(&format!("[{}]", digits));
+                    i += digits.len() + 2;
+                }
+            } else {
+                break;
+            }
+        }
+        (path, i)
+    }
+
+    /// A JSON source (`pm.response.json()`, an alias) at the start of `s`
+    fn json_source_len(&self, s: &str) -> Option<usize> {
+        for source in ["pm.response.json()", "JSON.parse(responseBody)"] {
+            if s.starts_with(source) {
+                return Some(source.len());
+            }
+        }
+        self.json_aliases
+            .iter()
+            .find(|alias| {
+                s.starts_with(alias.as_str())
+                    && !s[alias.len()..].chars().next().is_some_and(is_ident_char)
+            })
+            .map(|alias| alias.len())
+    }
+
+    /// Converts a JS expression, None if anything in it has no Rhai equivalent
+    fn expr(&self, js: &str) -> Option<String> {
+        let test = self.kind == ScriptKind::Test;
+        let pre = self.kind == ScriptKind::PreRequest;
+        // (JS, Rhai, allowed here)
+        let rules: [(&str, &str, bool); 18] = [
+            ("pm.environment.get(", "get_var(", pre),
+            ("pm.globals.get(", "get_var(", pre),
+            ("pm.collectionVariables.get(", "get_var(", pre),
+            ("pm.variables.get(", "get_var(", pre),
+            ("postman.getEnvironmentVariable(", "get_var(", pre),
+            ("postman.getGlobalVariable(", "get_var(", pre),
+            ("pm.response.headers.get(", "get_header(", test),
+            ("postman.getResponseHeader(", "get_header(", test),
+            ("pm.response.code", "status_code()", test),
+            ("responseCode.code", "status_code()", test),
+            ("pm.response.responseTime", "response_time()", test),
+            ("responseTime", "response_time()", test),
+            ("pm.response.text()", "response_body()", test),
+            ("responseBody", "response_body()", test),
+            ("Date.now()", "timestamp_ms()", pre),
+            ("new Date().getTime()", "timestamp_ms()", pre),
+            ("===", "==", true),
+            ("!==", "!=", true),
+        ];
+
+        let mut out = String::new();
+        // `out` without string literals, checked for leftover JS
+        let mut code = String::new();
+        let mut i = 0;
+        'scan: while i < js.len() {
+            let rest = &js[i..];
+            let at_boundary = !js[..i]
+                .chars()
+                .next_back()
+                .is_some_and(|c| is_ident_char(c) || c == '.');
+
+            if rest.starts_with('`') {
+                return None;
+            }
+            if let Some((value, len)) = string_literal(rest) {
+                out.push_str(&rhai_string(&value));
+                i += len;
+                continue;
+            }
+            if at_boundary
+                && test
+                && let Some(len) = self.json_source_len(rest)
+            {
+                let (path, consumed) = Self::json_accessor(&rest[len..]);
+                if rest[len + consumed..].starts_with(".length") {
+                    return None;
+                }
+                out.push_str(&format!("json_path(\"{}\")", path));
+                code.push_str("json_path()");
+                i += len + consumed;
+                continue;
+            }
+            if at_boundary {
+                for (from, to, allowed) in rules {
+                    let ident_end = from.ends_with(|c: char| is_ident_char(c));
+                    if allowed
+                        && rest.starts_with(from)
+                        && !(ident_end
+                            && rest[from.len()..].chars().next().is_some_and(is_ident_char))
+                    {
+                        out.push_str(to);
+                        code.push_str(to);
+                        i += from.len();
+                        continue 'scan;
+                    }
+                }
+            }
+            for (from, to) in [(".includes(", ".contains("), (".has(", ".contains(")] {
+                if rest.starts_with(from) {
+                    out.push_str(to);
+                    code.push_str(to);
+                    i += from.len();
+                    continue 'scan;
+                }
+            }
+            if rest.starts_with(".length") && !rest[7..].chars().next().is_some_and(is_ident_char) {
+                out.push_str(".len()");
+                code.push_str(".len()");
+                i += 7;
+                continue;
+            }
+
+            let c = rest.chars().next()?;
+            out.push(c);
+            code.push(c);
+            i += c.len_utf8();
+        }
+
+        let out = out.trim().to_string();
+        if out.is_empty() || FORBIDDEN.iter().any(|f| code.contains(f)) {
+            return None;
+        }
+        Some(out)
+    }
+
+    /// A Rhai expression producing a string, as `set_var` / `set_header` want
+    fn string_expr(&self, js: &str) -> Option<String> {
+        let expr = self.expr(js)?;
+        let is_literal = string_literal(js.trim()).is_some_and(|(_, len)| len == js.trim().len());
+        Some(if is_literal || expr.starts_with("json_path(") {
+            expr
+        } else {
+            format!("({}).to_string()", expr)
+        })
+    }
+
+    /// `pm.response.to.have.status(200)`, `pm.expect(a).to.eql(b)`, ... as a condition
+    fn assertion(&self, stmt: &str) -> Option<String> {
+        let stmt = stmt.trim().trim_end_matches(';');
+        if let Some(rest) = stmt.strip_prefix("pm.response.to.have.status") {
+            let (arg, _) = call_args(rest)?;
+            return Some(format!(
+                "status_code() == {}",
+                arg.trim().parse::<u16>().ok()?
+            ));
+        }
+        if matches!(
+            stmt,
+            "pm.response.to.be.ok" | "pm.response.to.be.success" | "pm.response.to.be.ok()"
+        ) {
+            return Some("status_code() >= 200 && status_code() < 300".to_string());
+        }
+        if let Some(rest) = stmt.strip_prefix("pm.response.to.have.header") {
+            let (arg, _) = call_args(rest)?;
+            let (name, _) = string_literal(arg.trim())?;
+            return Some(format!("get_header({}) != \"\"", rhai_string(&name)));
+        }
+
+        let rest = stmt.strip_prefix("pm.expect")?;
+        let (subject, len) = call_args(rest)?;
+        // Chai's `to` / `be` only read well, `not` may come before or after them
+        let mut chain = &rest[len..];
+        let mut negate = false;
+        for word in [".not", ".to", ".not", ".be"] {
+            if let Some(c) = chain.strip_prefix(word) {
+                chain = c;
+                negate |= word == ".not";
+            }
+        }
+        let method: String = chain
+            .trim_start_matches('.')
+            .chars()
+            .take_while(|c| is_ident_char(*c) || *c == '.')
+            .collect();
+        let arg = call_args(&chain.trim_start_matches('.')[method.len()..]).map(|(a, _)| a.trim());
+
+        // have.property("k") looks inside a JSON value
+        if method == "have.property" {
+            let (key, _) = string_literal(arg?)?;
+            let subject_expr = self.expr(&format!("{}[{}]", subject.trim(), rhai_string(&key)))?;
+            let op = if negate { "==" } else { "!=" };
+            return Some(format!("{} {} \"\"", subject_expr, op));
+        }
+
+        let left = self.expr(subject)?;
+        let json = left.starts_with("json_path(");
+        let condition = match method.as_str() {
+            "eql" | "equal" | "eq" | "equals" | "deep.equal" => {
+                let arg = arg?;
+                let right = if json && string_literal(arg).is_none() {
+                    // json_path returns text, so compare with the text form
+                    rhai_string(arg)
+                } else {
+                    self.expr(arg)?
+                };
+                format!("{} == {}", left, right)
+            }
+            "below" | "lessThan" | "lt" | "above" | "greaterThan" | "gt" => {
+                let op = if matches!(method.as_str(), "below" | "lessThan" | "lt") {
+                    "<"
+                } else {
+                    ">"
+                };
+                if json {
+                    format!("parse_float({}) {} {}", left, op, float_literal(arg?)?)
+                } else {
+                    format!("{} {} {}", left, op, self.expr(arg?)?)
+                }
+            }
+            "include" | "contain" | "contains" | "string" => {
+                format!("{}.contains({})", left, self.expr(arg?)?)
+            }
+            "true" | "false" if json => format!("{} == \"{}\"", left, method),
+            "true" | "false" => format!("{} == {}", left, method),
+            "exist" | "ok" if json => format!("{} != \"\"", left),
+            _ => return None,
+        };
+        Some(if negate {
+            format!("!({})", condition)
+        } else {
+            condition
+        })
+    }
+
+    /// Remembers `var data = pm.response.json()`, true if `stmt` was one
+    fn json_alias(&mut self, stmt: &str) -> bool {
+        let Some(decl) = ["var ", "let ", "const "]
+            .iter()
+            .find_map(|kw| stmt.strip_prefix(kw))
+        else {
+            return false;
+        };
+        let Some((name, value)) = decl.split_once('=') else {
+            return false;
+        };
+        let value = value.trim().trim_end_matches(';').trim();
+        if matches!(value, "pm.response.json()" | "JSON.parse(responseBody)") {
+            self.json_aliases.push(name.trim().to_string());
+            return true;
+        }
+        false
+    }
+
+    /// One top-level statement as Rhai
+    fn statement(&mut self, stmt: &str) -> Option<String> {
+        let stmt = stmt.trim().trim_end_matches(';').trim();
+        for setter in [
+            "pm.environment.set",
+            "pm.globals.set",
+            "pm.collectionVariables.set",
+            "pm.variables.set",
+            "postman.setEnvironmentVariable",
+            "postman.setGlobalVariable",
+        ] {
+            if let Some(rest) = stmt.strip_prefix(setter) {
+                let (args, len) = call_args(rest)?;
+                if len != rest.len() {
+                    return None;
+                }
+                let [name, value] = split_top_level(args, &[',']).try_into().ok()?;
+                let (name, _) = string_literal(&name)?;
+                return Some(format!(
+                    "set_var({}, {});",
+                    rhai_string(&name),
+                    self.string_expr(&value)?
+                ));
+            }
+        }
+
+        if self.kind == ScriptKind::PreRequest {
+            for adder in ["pm.request.headers.add", "pm.request.headers.upsert"] {
+                if let Some(rest) = stmt.strip_prefix(adder) {
+                    let (args, _) = call_args(rest)?;
+                    let object = args.trim().strip_prefix('{')?.strip_suffix('}')?;
+                    let mut key = None;
+                    let mut value = None;
+                    for field in split_top_level(object, &[',']) {
+                        let (k, v) = field.split_once(':')?;
+                        match k.trim().trim_matches(|c| c == '"' || c == '\'') {
+                            "key" => key = Some(string_literal(v.trim())?.0),
+                            "value" => value = Some(self.string_expr(v)?),
+                            _ => {}
+                        }
+                    }
+                    return Some(format!("set_header({}, {});", rhai_string(&key?), value?));
+                }
+            }
+        }
+
+        if let Some(rest) = stmt.strip_prefix("console.log") {
+            let (args, _) = call_args(rest)?;
+            let parts: Vec<String> = split_top_level(args, &[','])
+                .iter()
+                .map(|a| self.string_expr(a))
+                .collect::<Option<_>>()?;
+            return Some(format!("print({});", parts.join(" + \" \" + ")));
+        }
+
+        // Legacy sandbox: tests["Status is 200"] = responseCode.code === 200;
+        if let Some(rest) = stmt.strip_prefix("tests[")
+            && self.kind == ScriptKind::Test
+        {
+            let (name, len) = string_literal(rest)?;
+            let value = rest[len..].strip_prefix(']')?.trim().strip_prefix('=')?;
+            return Some(format!(
+                "test({}, {});",
+                rhai_string(&name),
+                self.expr(value)?
+            ));
+        }
+
+        for kw in ["var ", "let ", "const "] {
+            if let Some(decl) = stmt.strip_prefix(kw) {
+                let (name, value) = decl.split_once('=')?;
+                let name = name.trim();
+                if name.is_empty() || !name.chars().all(is_ident_char) {
+                    return None;
+                }
+                return Some(format!("let {} = {};", name, self.expr(value)?));
+            }
+        }
+        None
+    }
+
+    /// A whole `pm.test("name", function () { ... });` block
+    fn test_block(&mut self, block: &str) -> Option<String> {
+        let rest = block.trim().strip_prefix("pm.test")?;
+        let (args, _) = call_args(rest.trim_start())?;
+        let (name, _) = string_literal(args.trim())?;
+        let body_start = args.find('{')? + 1;
+        let body_end = args.rfind('}')?;
+        let body = &args[body_start..body_end];
+
+        let mut conditions = Vec::new();
+        for stmt in split_top_level(body, &[';', '\n']) {
+            if stmt.starts_with("//") || self.json_alias(&stmt) {
+                continue;
+            }
+            conditions.push(self.assertion(&stmt)?);
+        }
+        if conditions.is_empty() {
+            return None;
+        }
+        let condition = if conditions.len() == 1 {
+            conditions.remove(0)
+        } else {
+            conditions
+                .iter()
+                .map(|c| format!("({})", c))
+                .collect::<Vec<_>>()
+                .join(" && ")
+        };
+        Some(format!("test({}, {});", rhai_string(&name), condition))
+    }
+}
+
+fn comment_out(lines: &str, out: &mut Vec<String>) {
+    for line in lines.lines() {
+        out.push(format!("{}{}", UNCONVERTED, line.trim_end()));
+    }
+}
+
+/// Converts a Postman script (the `exec` lines joined) into Rhai
+pub fn transpile(source: &str, kind: ScriptKind) -> Transpiled {
+    let mut converter = Converter::new(kind);
+    let mut result = Transpiled::default();
+    let mut out: Vec<String> = Vec::new();
+    let mut lines = source.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            out.push(String::new());
+            continue;
+        }
+        if trimmed.starts_with("//") {
+            out.push(trimmed.to_string());
+            continue;
+        }
+
+        // Gather a multi-line call (pm.test blocks, objects spread over lines)
+        let mut block = line.to_string();
+        let mut depth = paren_delta(line);
+        while depth > 0
+            && let Some(next) = lines.next()
+        {
+            block.push('\n');
+            block.push_str(next);
+            depth += paren_delta(next);
+        }
+
+        let converted = if trimmed.starts_with("pm.test") {
+            converter.test_block(&block).map(|c| vec![c])
+        } else if converter.json_alias(block.trim()) {
+            Some(Vec::new())
+        } else {
+            split_top_level(&block.replace('\n', " "), &[';'])
+                .iter()
+                .map(|stmt| converter.statement(stmt))
+                .collect::<Option<Vec<_>>>()
+        };
+        match converted {
+            Some(rhai) => {
+                result.converted += 1;
+                out.extend(rhai);
+            }
+            None => {
+                result.skipped += 1;
+                comment_out(&block, &mut out);
+            }
+        }
+    }
+
+    let mut script = out.join("\n").trim().to_string();
+    if result.skipped > 0 {
+        script = format!(
+            "// Converted from Postman. Lines starting with {}need porting by hand.\n{}",
+            UNCONVERTED.trim_start_matches("// "),
+            script
+        );
+    }
+    result.script = script;
+    result
+}
@@ -0,0 +1,137 @@
+// Common mistakes caught before a request goes out: each one is a warning the
+// user can send anyway past, never a silent fix.
+use crate::app::OutgoingRequest;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Check {
+    /// `{{name}}` left in the URL, a header or the body
+    Variables,
+    /// A JSON content type on a body that doesn't parse
+    InvalidJson,
+    /// A body without a Content-Type header
+    MissingContentType,
+    /// GET or HEAD with a body, which servers and proxies often drop
+    BodyOnGet,
+    /// The same header twice, differing only in case
+    DuplicateHeaders,
+}
+
+impl Check {
+    /// The name in `send_checks.skip`
+    pub fn id(self) -> &'static str {
+        match self {
+            Check::Variables => "variables",
+            Check::InvalidJson => "json",
+            Check::MissingContentType => "content-type",
+            Check::BodyOnGet => "get-body",
+            Check::DuplicateHeaders => "duplicate-headers",
+        }
+    }
+}
+
+/// Stored under `send_checks` in `config.json`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SendChecks {
+    /// Look the request over before every send
+    pub enabled: bool,
+    /// Checks left out, by id (`"get-body"`, `"content-type"`, ...)
+    pub skip: Vec<String>,
+}
+
+impl Default for SendChecks {
+    fn default() -> Self {
+        SendChecks {
+            enabled: true,
+            skip: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    pub check: Check,
+    pub message: String,
+}
+
+fn header<'a>(request: &'a OutgoingRequest, name: &str) -> Option<&'a str> {
+    request
+        .headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// What's wrong with `request`, the variables already substituted
+pub fn check(request: &OutgoingRequest, settings: &SendChecks) -> Vec<Warning> {
+    if !settings.enabled {
+        return Vec::new();
+    }
+    let mut warnings = Vec::new();
+    let mut warn = |check: Check, message: String| {
+        if !settings
+            .skip
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(check.id()))
+        {
+            warnings.push(Warning { check, message });
+        }
+    };
+    let body = request.body.as_deref().filter(|b| !b.trim().is_empty());
+
+    let mut texts = vec![request.url.as_str()];
+    texts.extend(request.headers.values().map(String::as_str));
+    texts.extend(body);
+    let missing: std::collections::BTreeSet<String> = texts
+        .into_iter()
+        .flat_map(crate::domain::variables::references)
+        .collect();
+    if !missing.is_empty() {
+        let names: Vec<String> = missing.iter().map(|n| format!("{{{{{}}}}}", n)).collect();
+        warn(
+            Check::Variables,
+            format!("{} not defined in any scope, sent as is", names.join(", ")),
+        );
+    }
+
+    let content_type = header(request, "content-type");
+    if let (Some(body), Some(content_type)) = (body, content_type)
+        && content_type.to_lowercase().contains("json")
+        && let Err(e) = serde_json::from_str::<serde_json::Value>(body)
+    {
+        warn(
+            Check::InvalidJson,
+            format!(
+                "Body isn't valid JSON ({}) but Content-Type is {}",
+                e, content_type
+            ),
+        );
+    }
+    if body.is_some() && content_type.is_none() && request.form_data.is_none() {
+        warn(
+            Check::MissingContentType,
+            "Body without a Content-Type header".to_string(),
+        );
+    }
+
+    let method = request.method.to_uppercase();
+    if (body.is_some() || request.form_data.is_some()) && (method == "GET" || method == "HEAD") {
+        warn(
+            Check::BodyOnGet,
+            format!("{} with a body, many servers and proxies drop it", method),
+        );
+    }
+
+    let mut names: Vec<&String> = request.headers.keys().collect();
+    names.sort_by_key(|n| n.to_lowercase());
+    for pair in names.windows(2) {
+        if pair[0].eq_ignore_ascii_case(pair[1]) {
+            warn(
+                Check::DuplicateHeaders,
+                format!("Header sent twice: {} and {}", pair[0], pair[1]),
+            );
+        }
+    }
+    warnings
+}
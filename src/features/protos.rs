@@ -0,0 +1,133 @@
+// The workspace's .proto files, registered once: gRPC requests name a service and
+// grpcurl finds it in these instead of each request pointing at its own file.
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Stored under `protos` in `config.json`. Relative paths are from the workspace.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProtoRegistry {
+    /// Directories whose .proto files are all loaded (and imports resolved against)
+    pub roots: Vec<String>,
+    /// Directories only searched for imports (`google/api/...`, vendored protos)
+    pub import_paths: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Section {
+    Roots,
+    ImportPaths,
+}
+
+/// The `:protos` view
+#[derive(Clone, Debug)]
+pub struct ProtoView {
+    pub section: Section,
+    pub selected: usize,
+    /// A directory being typed in, added to `section` on Enter
+    pub adding: Option<String>,
+    /// What the roots load, `root: file` each
+    pub files: Vec<String>,
+}
+
+impl ProtoView {
+    pub fn new(files: Vec<String>) -> Self {
+        ProtoView {
+            section: Section::Roots,
+            selected: 0,
+            adding: None,
+            files,
+        }
+    }
+}
+
+impl ProtoRegistry {
+    pub fn list(&self, section: Section) -> &Vec<String> {
+        match section {
+            Section::Roots => &self.roots,
+            Section::ImportPaths => &self.import_paths,
+        }
+    }
+
+    pub fn list_mut(&mut self, section: Section) -> &mut Vec<String> {
+        match section {
+            Section::Roots => &mut self.roots,
+            Section::ImportPaths => &mut self.import_paths,
+        }
+    }
+
+    /// Every root with the .proto files under it, relative to the root
+    pub fn files(&self, base: &Path) -> Vec<(String, Vec<String>)> {
+        self.roots
+            .iter()
+            .map(|root| (root.clone(), find_protos(&base.join(root))))
+            .collect()
+    }
+
+    /// grpcurl's `-import-path`/`-proto` arguments for a request. Its own .proto
+    /// file wins over the roots, the import paths apply either way. Empty means
+    /// server reflection.
+    pub fn args(&self, base: &Path, request_proto: Option<&str>) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut import = |dir: &Path| {
+            args.push("-import-path".to_string());
+            args.push(dir.to_string_lossy().into_owned());
+        };
+        let mut protos = Vec::new();
+        match request_proto.filter(|p| !p.is_empty()) {
+            Some(proto) => {
+                let proto = Path::new(proto);
+                if let Some(parent) = proto.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    import(parent);
+                }
+                protos.push(proto.file_name().map_or_else(
+                    || proto.to_string_lossy().into_owned(),
+                    |name| name.to_string_lossy().into_owned(),
+                ));
+            }
+            None => {
+                for (root, files) in self.files(base) {
+                    import(&base.join(root));
+                    protos.extend(files);
+                }
+            }
+        }
+        if protos.is_empty() {
+            return Vec::new();
+        }
+        for dir in &self.import_paths {
+            import(&base.join(dir));
+        }
+        for proto in protos {
+            args.push("-proto".to_string());
+            args.push(proto);
+        }
+        args
+    }
+}
+
+/// .proto files under `root`, recursively, as paths relative to it with `/`
+pub fn find_protos(root: &Path) -> Vec<String> {
+    fn walk(dir: &Path, prefix: &str, found: &mut Vec<String>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let relative = format!("{}{}", prefix, name);
+            if path.is_dir() {
+                walk(&path, &format!("{}/", relative), found);
+            } else if name.ends_with(".proto") {
+                found.push(relative);
+            }
+        }
+    }
+    let mut found = Vec::new();
+    walk(root, "", &mut found);
+    found.sort();
+    found
+}
@@ -0,0 +1,160 @@
+use std::io::Write;
+
+/// A parsed `Content-Range: bytes start-end/total` header
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentRange {
+    pub start: u64,
+    pub end: u64,
+    /// `None` when the server answers with `*` (unknown length)
+    pub total: Option<u64>,
+}
+
+impl ContentRange {
+    pub fn byte_count(&self) -> u64 {
+        self.end.saturating_sub(self.start) + 1
+    }
+
+    /// A small bar showing which part of the file this response covers, e.g. `[░░██████░░]`
+    pub fn bar(&self, width: usize) -> String {
+        let Some(total) = self.total.filter(|t| *t > 0) else {
+            return String::new();
+        };
+        let cell = |offset: u64| ((offset as f64 / total as f64) * width as f64) as usize;
+        let from = cell(self.start).min(width.saturating_sub(1));
+        let to = cell(self.end + 1).clamp(from + 1, width);
+
+        let mut bar = String::from("[");
+        for i in 0..width {
+            bar.push(if i >= from && i < to { '█' } else { '░' });
+        }
+        bar.push(']');
+        bar
+    }
+
+    pub fn describe(&self) -> String {
+        match self.total {
+            Some(total) => format!(
+                "bytes {}-{} of {} ({:.1}%)",
+                self.start,
+                self.end,
+                total,
+                self.byte_count() as f64 * 100.0 / total.max(1) as f64
+            ),
+            None => format!("bytes {}-{} of ?", self.start, self.end),
+        }
+    }
+}
+
+/// Parses a `Content-Range` header value. Only `bytes` ranges are understood.
+pub fn parse_content_range(value: &str) -> Option<ContentRange> {
+    let rest = value.trim().strip_prefix("bytes")?.trim();
+    let (range, total) = rest.split_once('/')?;
+    let (start, end) = range.trim().split_once('-')?;
+    let total = match total.trim() {
+        "*" => None,
+        t => Some(t.parse().ok()?),
+    };
+    Some(ContentRange {
+        start: start.trim().parse().ok()?,
+        end: end.trim().parse().ok()?,
+        total,
+    })
+}
+
+fn parse_size(text: &str) -> Result<u64, String> {
+    let text = text.trim().to_lowercase();
+    let (number, multiplier) = if let Some(n) = text.strip_suffix("kb").or(text.strip_suffix('k')) {
+        (n, 1024)
+    } else if let Some(n) = text.strip_suffix("mb").or(text.strip_suffix('m')) {
+        (n, 1024 * 1024)
+    } else {
+        (text.strip_suffix('b').unwrap_or(&text), 1)
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .map(|n| n * multiplier)
+        .map_err(|_| format!("Invalid size: {}", text))
+}
+
+/// Turns a friendly range spec into a `Range` header value.
+///
+/// Accepts `0-1023`, `500-` (from offset), `-500` (last 500 bytes),
+/// `64k` / `first 64k` (first 64 KB) and `last 1m`.
+pub fn parse_range_spec(spec: &str) -> Result<String, String> {
+    let spec = spec.trim();
+    let spec = spec.strip_prefix("bytes=").unwrap_or(spec);
+    let lower = spec.to_lowercase();
+
+    if let Some(size) = lower.strip_prefix("first") {
+        let size = parse_size(size)?;
+        if size == 0 {
+            return Err("Range must not be empty".to_string());
+        }
+        return Ok(format!("bytes=0-{}", size - 1));
+    }
+    if let Some(size) = lower.strip_prefix("last") {
+        return Ok(format!("bytes=-{}", parse_size(size)?));
+    }
+
+    if let Some((start, end)) = lower.split_once('-') {
+        let start = start.trim();
+        let end = end.trim();
+        return match (start.is_empty(), end.is_empty()) {
+            (true, true) => Err("Empty range".to_string()),
+            (true, false) => Ok(format!("bytes=-{}", parse_size(end)?)),
+            (false, true) => Ok(format!("bytes={}-", parse_size(start)?)),
+            (false, false) => {
+                let (s, e) = (parse_size(start)?, parse_size(end)?);
+                if e < s {
+                    Err(format!("Range end {} is before start {}", e, s))
+                } else {
+                    Ok(format!("bytes={}-{}", s, e))
+                }
+            }
+        };
+    }
+
+    let size = parse_size(&lower)?;
+    if size == 0 {
+        return Err("Range must not be empty".to_string());
+    }
+    Ok(format!("bytes=0-{}", size - 1))
+}
+
+/// `Range` header to continue downloading into `path` (starts at 0 when the file doesn't exist yet)
+pub fn resume_range_header(path: &str) -> String {
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    format!("bytes={}-", size)
+}
+
+/// Stores a resumed download. A 206 whose range starts where the file ends is appended,
+/// a full (non-partial) response replaces the file. Returns the new file size.
+pub fn write_resumed(
+    path: &str,
+    range: Option<&ContentRange>,
+    bytes: &[u8],
+) -> Result<u64, String> {
+    match range {
+        Some(range) => {
+            let existing = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            if range.start != existing {
+                return Err(format!(
+                    "Server sent bytes from {} but {} has {} bytes",
+                    range.start, path, existing
+                ));
+            }
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| e.to_string())?;
+            file.write_all(bytes).map_err(|e| e.to_string())?;
+            Ok(existing + bytes.len() as u64)
+        }
+        None => {
+            std::fs::write(path, bytes).map_err(|e| e.to_string())?;
+            Ok(bytes.len() as u64)
+        }
+    }
+}
@@ -0,0 +1,91 @@
+// Unsent edits that survive a crash or a closed terminal. While the open tabs
+// differ from how PostDad started they're saved to `recovery.json` every few
+// seconds, and the file goes away on a clean exit. Finding it at startup means the
+// last session didn't end well, so its tabs are offered back.
+use crate::domain::collection::RequestConfig;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// In the workspace, encrypted like history when encryption is on
+pub const FILE: &str = "recovery.json";
+/// How often the tabs are looked at for changes
+pub const CHECK: Duration = Duration::from_millis(500);
+/// How often changed tabs are written out
+pub const SAVE: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DraftTab {
+    pub name: String,
+    /// Where `s` saves it, when it was opened from a collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_name: Option<String>,
+    pub request: RequestConfig,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Unix seconds
+    pub saved_at: u64,
+    #[serde(default)]
+    pub active_tab: usize,
+    pub tabs: Vec<DraftTab>,
+}
+
+/// The file content the panic hook writes: what the next save would have written
+pub type Pending = Arc<Mutex<Option<(PathBuf, String)>>>;
+
+/// Kept on the app between checks
+#[derive(Debug, Default)]
+pub struct Autosave {
+    /// The tabs as PostDad started with them, nothing worth keeping. Autosave is
+    /// off until `App::start_recovery` sets it.
+    pub baseline: Option<String>,
+    /// The tabs at the last check
+    pub last: Option<String>,
+    /// `last` isn't in the file yet
+    pub unsaved: bool,
+    /// The file holds tabs from this session
+    pub written: bool,
+    pub last_check: Option<Instant>,
+    pub last_save: Option<Instant>,
+    pub pending: Pending,
+}
+
+impl Autosave {
+    /// Time for another look at the tabs
+    pub fn due(&self, now: Instant) -> bool {
+        self.baseline.is_some()
+            && self
+                .last_check
+                .is_none_or(|at| now.duration_since(at) >= CHECK)
+    }
+
+    /// Time to write what changed
+    pub fn save_due(&self, now: Instant) -> bool {
+        self.unsaved
+            && self
+                .last_save
+                .is_none_or(|at| now.duration_since(at) >= SAVE)
+    }
+}
+
+/// Writes what the last check found, for when PostDad is going down with a panic
+pub fn write_pending(pending: &Pending) {
+    if let Ok(pending) = pending.lock()
+        && let Some((path, content)) = pending.as_ref()
+    {
+        let _ = std::fs::write(path, content);
+    }
+}
+
+/// "3 tabs", "1 tab"
+pub fn describe(snapshot: &Snapshot) -> String {
+    match snapshot.tabs.len() {
+        1 => "1 tab".to_string(),
+        n => format!("{} tabs", n),
+    }
+}
@@ -0,0 +1,92 @@
+// Every response from a collection run written out as files, one directory per
+// request, for reading or diffing full payloads outside the TUI. Used by
+// `:export responses` and `PostDad run --output-dir`.
+use crate::features::runner::RunResult;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// `Users/Get by id` -> `Users/Get-by-id`: a folder per name segment, each one safe
+/// to use as a file name
+pub fn request_dir(name: &str) -> PathBuf {
+    name.split('/')
+        .map(|segment| {
+            let cleaned: String = segment
+                .chars()
+                .map(|c| {
+                    if c.is_alphanumeric() || c == '_' || c == '.' {
+                        c
+                    } else {
+                        '-'
+                    }
+                })
+                .collect();
+            let parts: Vec<&str> = cleaned.split('-').filter(|part| !part.is_empty()).collect();
+            match parts.join("-").trim_matches('.') {
+                "" => "request".to_string(),
+                s => s.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// `json`, `xml`, `html` or `txt`, from the Content-Type and then the body itself
+pub fn body_extension(headers: &HashMap<String, String>, body: &str) -> &'static str {
+    let content_type = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.to_lowercase())
+        .unwrap_or_default();
+    if content_type.contains("json") {
+        "json"
+    } else if content_type.contains("xml") {
+        "xml"
+    } else if content_type.contains("html") {
+        "html"
+    } else if serde_json::from_str::<serde_json::Value>(body).is_ok() {
+        "json"
+    } else {
+        "txt"
+    }
+}
+
+/// Writes `<dir>/<request>/body.<ext>` (when there was a response) and
+/// `<dir>/<request>/response.json` (method, URL, status, error and headers) for each
+/// result. Two requests that end up with the same directory get `-2`, `-3`, ...
+/// Returns how many were written.
+pub fn write(dir: &Path, results: &[RunResult]) -> Result<usize, String> {
+    let mut used = HashSet::new();
+    for result in results {
+        let base = request_dir(&result.name);
+        let mut target = base.clone();
+        let mut n = 2;
+        while !used.insert(target.clone()) {
+            target = PathBuf::from(format!("{}-{}", base.display(), n));
+            n += 1;
+        }
+        let target = dir.join(target);
+        std::fs::create_dir_all(&target).map_err(|e| format!("{}: {}", target.display(), e))?;
+
+        if let Some(body) = &result.response_body {
+            let file = target.join(format!(
+                "body.{}",
+                body_extension(&result.response_headers, body)
+            ));
+            std::fs::write(&file, body).map_err(|e| format!("{}: {}", file.display(), e))?;
+        }
+        let headers: BTreeMap<&String, &String> = result.response_headers.iter().collect();
+        let meta = json!({
+            "request": result.name,
+            "method": result.method,
+            "url": result.url,
+            "status": result.status,
+            "passed": result.passed,
+            "error": result.error,
+            "headers": headers,
+        });
+        let file = target.join("response.json");
+        let content = serde_json::to_string_pretty(&meta).unwrap_or_default();
+        std::fs::write(&file, content + "\n").map_err(|e| format!("{}: {}", file.display(), e))?;
+    }
+    Ok(results.len())
+}
@@ -0,0 +1,206 @@
+// Request history and collection run results as CSV or JSON, for spreadsheets and
+// scripts. The TUI and the CLI write the same columns.
+use crate::app::RequestLog;
+use crate::features::csv_export::Table;
+use crate::features::runner::RunResult;
+use serde_json::{Value, json};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl Format {
+    /// JSON for a `.json` file, CSV for anything else
+    pub fn for_path(path: &str) -> Format {
+        if path.to_lowercase().ends_with(".json") {
+            Format::Json
+        } else {
+            Format::Csv
+        }
+    }
+}
+
+/// gRPC's OK is 0, everything else passes below 400
+fn status_ok(method: &str, status: u16) -> bool {
+    if method == "GRPC" {
+        status == 0
+    } else {
+        (1..400).contains(&status)
+    }
+}
+
+fn test_counts(tests: &[(String, bool)]) -> (usize, usize) {
+    let passed = tests.iter().filter(|(_, p)| *p).count();
+    (passed, tests.len() - passed)
+}
+
+fn failed_tests(tests: &[(String, bool)]) -> String {
+    tests
+        .iter()
+        .filter(|(_, p)| !*p)
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn tests_json(tests: &[(String, bool)]) -> Vec<Value> {
+    tests
+        .iter()
+        .map(|(name, passed)| json!({"name": name, "passed": passed}))
+        .collect()
+}
+
+/// A history entry passed if its status is a success and its tests all passed
+pub fn history_passed(log: &RequestLog) -> bool {
+    status_ok(&log.method, log.status) && log.tests.iter().all(|(_, p)| *p)
+}
+
+pub fn history_table(history: &[RequestLog]) -> Table {
+    let columns = [
+        "timestamp",
+        "method",
+        "url",
+        "status",
+        "latency_ms",
+        "size_bytes",
+        "passed",
+        "tests_passed",
+        "tests_failed",
+        "failed_tests",
+    ];
+    let rows = history
+        .iter()
+        .map(|log| {
+            let (passed, failed) = test_counts(&log.tests);
+            vec![
+                log.timestamp.to_string(),
+                log.method.clone(),
+                log.url.clone(),
+                log.status.to_string(),
+                log.latency.to_string(),
+                log.size.to_string(),
+                history_passed(log).to_string(),
+                passed.to_string(),
+                failed.to_string(),
+                failed_tests(&log.tests),
+            ]
+        })
+        .collect();
+    Table {
+        columns: columns.iter().map(|c| c.to_string()).collect(),
+        rows,
+    }
+}
+
+pub fn history_json(history: &[RequestLog]) -> Value {
+    Value::Array(
+        history
+            .iter()
+            .map(|log| {
+                json!({
+                    "timestamp": log.timestamp,
+                    "method": log.method,
+                    "url": log.url,
+                    "status": log.status,
+                    "latency_ms": log.latency,
+                    "size_bytes": log.size,
+                    "passed": history_passed(log),
+                    "tests": tests_json(&log.tests),
+                })
+            })
+            .collect(),
+    )
+}
+
+pub fn run_table(collection: &str, results: &[RunResult]) -> Table {
+    let columns = [
+        "collection",
+        "name",
+        "method",
+        "url",
+        "status",
+        "expected_status",
+        "latency_ms",
+        "size_bytes",
+        "passed",
+        "tests_passed",
+        "tests_failed",
+        "failed_tests",
+        "error",
+    ];
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let rows = results
+        .iter()
+        .map(|r| {
+            let (passed, failed) = test_counts(&r.tests);
+            vec![
+                collection.to_string(),
+                r.name.clone(),
+                r.method.clone(),
+                r.url.clone(),
+                optional(r.status.map(|s| s.to_string())),
+                optional(r.expected_status.map(|s| s.to_string())),
+                optional(r.latency_ms.map(|ms| ms.to_string())),
+                optional(r.size.map(|s| s.to_string())),
+                r.passed.to_string(),
+                passed.to_string(),
+                failed.to_string(),
+                failed_tests(&r.tests),
+                optional(r.error.clone()),
+            ]
+        })
+        .collect();
+    Table {
+        columns: columns.iter().map(|c| c.to_string()).collect(),
+        rows,
+    }
+}
+
+/// What `PostDad run --json` prints
+pub fn run_json(collection: &str, results: &[RunResult]) -> Value {
+    let passed = results.iter().filter(|r| r.passed).count();
+    let results: Vec<Value> = results
+        .iter()
+        .map(|r| {
+            json!({
+                "name": r.name,
+                "method": r.method,
+                "url": r.url,
+                "status": r.status,
+                "latency_ms": r.latency_ms,
+                "size_bytes": r.size,
+                "expected_status": r.expected_status,
+                "passed": r.passed,
+                "error": r.error,
+                "tests": tests_json(&r.tests),
+            })
+        })
+        .collect();
+    json!({
+        "collection": collection,
+        "total": results.len(),
+        "passed": passed,
+        "failed": results.len() - passed,
+        "results": results
+    })
+}
+
+/// The history in `format`
+pub fn history(history: &[RequestLog], format: Format) -> String {
+    match format {
+        Format::Csv => history_table(history).to_delimited(','),
+        Format::Json => serde_json::to_string_pretty(&history_json(history)).unwrap_or_default(),
+    }
+}
+
+/// A run's results in `format`
+pub fn run(collection: &str, results: &[RunResult], format: Format) -> String {
+    match format {
+        Format::Csv => run_table(collection, results).to_delimited(','),
+        Format::Json => {
+            serde_json::to_string_pretty(&run_json(collection, results)).unwrap_or_default()
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::features::runner::CollectionRunResult;
+use serde::{Deserialize, Serialize};
+
+/// What to do once a collection run finishes. Stored under `runner_hooks` in
+/// `config.json` for the TUI runner, or passed as flags to `PostDad run`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunHooks {
+    /// POSTed a JSON summary (Slack-compatible `text` field included)
+    pub webhook: Option<String>,
+    /// Run through the shell with `POSTDAD_*` result variables set
+    pub command: Option<String>,
+    /// Only fire when at least this many requests failed
+    pub failure_threshold: Option<usize>,
+}
+
+impl RunHooks {
+    pub fn is_empty(&self) -> bool {
+        self.webhook.is_none() && self.command.is_none()
+    }
+
+    pub fn should_fire(&self, failed: usize) -> bool {
+        !self.is_empty() && failed >= self.failure_threshold.unwrap_or(0)
+    }
+}
+
+/// One line for chat tools, e.g. `PostDad: api_tests ✗ 8/10 passed, 2 failed`
+pub fn summary_text(result: &CollectionRunResult) -> String {
+    let icon = if result.failed == 0 { "✓" } else { "✗" };
+    let mut text = format!(
+        "PostDad: {} {} {}/{} passed, {} failed",
+        result.collection_name, icon, result.passed, result.total, result.failed
+    );
+    let failures: Vec<&str> = result
+        .results
+        .iter()
+        .filter(|r| !r.passed)
+        .map(|r| r.name.as_str())
+        .collect();
+    if !failures.is_empty() {
+        text.push_str(&format!(" ({})", failures.join(", ")));
+    }
+    text
+}
+
+pub fn summary_json(result: &CollectionRunResult) -> serde_json::Value {
+    let results: Vec<serde_json::Value> = result
+        .results
+        .iter()
+        .map(|r| {
+            serde_json::json!({
+                "name": r.name,
+                "method": r.method,
+                "url": r.url,
+                "status": r.status,
+                "latency_ms": r.latency_ms,
+                "passed": r.passed,
+                "error": r.error,
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "text": summary_text(result),
+        "collection": result.collection_name,
+        "total": result.total,
+        "passed": result.passed,
+        "failed": result.failed,
+        "success": result.failed == 0,
+        "results": results,
+    })
+}
+
+/// Environment handed to the hook command
+pub fn hook_env(result: &CollectionRunResult) -> Vec<(String, String)> {
+    let failed_names: Vec<&str> = result
+        .results
+        .iter()
+        .filter(|r| !r.passed)
+        .map(|r| r.name.as_str())
+        .collect();
+
+    vec![
+        (
+            "POSTDAD_COLLECTION".to_string(),
+            result.collection_name.clone(),
+        ),
+        ("POSTDAD_TOTAL".to_string(), result.total.to_string()),
+        ("POSTDAD_PASSED".to_string(), result.passed.to_string()),
+        ("POSTDAD_FAILED".to_string(), result.failed.to_string()),
+        (
+            "POSTDAD_STATUS".to_string(),
+            if result.failed == 0 {
+                "passed"
+            } else {
+                "failed"
+            }
+            .to_string(),
+        ),
+        (
+            "POSTDAD_FAILED_REQUESTS".to_string(),
+            failed_names.join(","),
+        ),
+        ("POSTDAD_SUMMARY".to_string(), summary_text(result)),
+    ]
+}
+
+pub async fn post_webhook(url: &str, payload: &serde_json::Value) -> Result<String, String> {
+    let resp = reqwest::Client::new()
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(payload.to_string())
+        .send()
+        .await
+        .map_err(|e| format!("Webhook failed: {}", e))?;
+
+    let status = resp.status();
+    if status.is_success() {
+        Ok(format!("Webhook sent ({})", status.as_u16()))
+    } else {
+        Err(format!("Webhook returned {}", status.as_u16()))
+    }
+}
+
+async fn run_command(command: &str, env: Vec<(String, String)>) -> Result<String, String> {
+    let command = command.to_string();
+    let status = tokio::task::spawn_blocking(move || {
+        let mut cmd = if cfg!(windows) {
+            let mut c = std::process::Command::new("cmd");
+            c.arg("/C");
+            c
+        } else {
+            let mut c = std::process::Command::new("sh");
+            c.arg("-c");
+            c
+        };
+        cmd.arg(&command).envs(env).status()
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Hook command failed to start: {}", e))?;
+
+    if status.success() {
+        Ok("Hook command finished".to_string())
+    } else {
+        Err(format!(
+            "Hook command exited with {}",
+            status
+                .code()
+                .map(|c| c.to_string())
+                .unwrap_or("signal".to_string())
+        ))
+    }
+}
+
+/// Fires the configured hooks (if the failure threshold is met) and reports each outcome
+pub async fn fire(hooks: &RunHooks, result: &CollectionRunResult) -> Vec<Result<String, String>> {
+    let mut outcomes = Vec::new();
+    if !hooks.should_fire(result.failed) {
+        return outcomes;
+    }
+    if let Some(url) = &hooks.webhook {
+        outcomes.push(post_webhook(url, &summary_json(result)).await);
+    }
+    if let Some(command) = &hooks.command {
+        outcomes.push(run_command(command, hook_env(result)).await);
+    }
+    outcomes
+}
@@ -1,7 +1,12 @@
-use crate::domain::collection::{Collection, RequestConfig};
-use crate::features::scripting;
+use crate::domain::collection::{Collection, RequestConfig, WsExpectation, WsScenarioConfig};
+use crate::features::scripting::{self, HistoryEntry};
+use crate::features::{snapshot, template};
+use crate::net::pool::HostOverrides;
+use crate::net::rate_limit::{self, RateLimitSettings, RateLimiter};
+use futures_util::{SinkExt, StreamExt};
 use std::collections::HashMap;
 use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 /// Result of running a single request in the collection
 #[derive(Clone, Debug)]
@@ -11,10 +16,15 @@ pub struct RunResult {
     pub url: String,
     pub status: Option<u16>,
     pub latency_ms: Option<u128>,
+    /// Response body bytes
+    pub size: Option<usize>,
     pub expected_status: Option<u16>,
     pub passed: bool,
     pub error: Option<String>,
     pub tests: Vec<(String, bool)>,
+    /// What came back, kept for `:export responses` and `run --output-dir`
+    pub response_body: Option<String>,
+    pub response_headers: HashMap<String, String>,
 }
 
 /// Overall result of running a collection
@@ -27,6 +37,10 @@ pub struct CollectionRunResult {
     pub failed: usize,
     pub running: bool,
     pub current_index: usize,
+    /// Why the current request is waiting, while it waits for a rate limit
+    pub throttled: Option<String>,
+    /// Time spent waiting for rate limits over the whole run
+    pub throttled_ms: u64,
 }
 
 impl CollectionRunResult {
@@ -39,6 +53,8 @@ impl CollectionRunResult {
             failed: 0,
             running: true,
             current_index: 0,
+            throttled: None,
+            throttled_ms: 0,
         }
     }
 
@@ -50,6 +66,7 @@ impl CollectionRunResult {
         }
         self.results.push(result);
         self.current_index += 1;
+        self.throttled = None;
     }
 
     pub fn finish(&mut self) {
@@ -68,15 +85,26 @@ pub enum RunnerEvent {
         name: String,
         index: usize,
     },
+    /// The next request waits this long for a rate limit, and why
+    Throttled {
+        wait_ms: u64,
+        reason: String,
+    },
     RequestCompleted(RunResult),
     Finished(CollectionRunResult),
     Error(String),
+    /// Outcome of a post-run webhook or command
+    HookFinished(Result<String, String>),
 }
 
-/// Runs a collection of requests sequentially
+/// Runs a collection of requests sequentially. The collection's variables go over
+/// `env_vars`, and each request's own over both while it runs. Requests wait their
+/// turn under `limits`, and hosts pinned in `resolve` connect to their addresses.
 pub async fn run_collection(
     collection: &Collection,
     env_vars: &HashMap<String, String>,
+    limits: &RateLimitSettings,
+    resolve: &HostOverrides,
     event_tx: mpsc::Sender<RunnerEvent>,
 ) {
     let requests: Vec<(&String, &RequestConfig)> = {
@@ -85,7 +113,13 @@ pub async fn run_collection(
         items
     };
 
-    if requests.is_empty() {
+    let ws_scenarios: Vec<(&String, &WsScenarioConfig)> = {
+        let mut items: Vec<_> = collection.ws_scenarios.iter().collect();
+        items.sort_by(|a, b| a.0.cmp(b.0));
+        items
+    };
+
+    if requests.is_empty() && ws_scenarios.is_empty() {
         let _ = event_tx
             .send(RunnerEvent::Error(
                 "Collection contains no requests".to_string(),
@@ -94,7 +128,7 @@ pub async fn run_collection(
         return;
     }
 
-    let total = requests.len();
+    let total = requests.len() + ws_scenarios.len();
     let _ = event_tx
         .send(RunnerEvent::Started {
             collection_name: collection.name.clone(),
@@ -103,7 +137,11 @@ pub async fn run_collection(
         .await;
 
     let mut run_result = CollectionRunResult::new(&collection.name, total);
+    let pace = RateLimiter::new();
+    // What this run has sent so far, newest first, for the scripts
+    let mut history: Vec<HistoryEntry> = Vec::new();
     let mut current_env_vars = env_vars.clone();
+    current_env_vars.extend(collection.variables.clone());
 
     for (index, (name, config)) in requests.iter().enumerate() {
         // Notify that we're starting this request
@@ -114,31 +152,75 @@ pub async fn run_collection(
             })
             .await;
 
-        // Process URL with environment variables
-        let mut url = config.url.clone();
-        for (key, val) in &current_env_vars {
+        let span = tracing::info_span!(
+            "request",
+            request_id = crate::features::logging::new_request_id(),
+            collection = collection.name,
+            name = name.as_str()
+        );
+        let mut request_vars = current_env_vars.clone();
+        request_vars.extend(config.variables.clone());
+
+        // Process URL with path params and environment variables, a relative one
+        // after base_url
+        let url = crate::domain::path_params::fill(&config.url, |name| {
+            config.path_params.get(name).map(String::as_str)
+        });
+        let mut url = crate::domain::environment::resolve_url(
+            &url,
+            request_vars
+                .get(crate::domain::environment::BASE_URL)
+                .map(String::as_str),
+        );
+        for (key, val) in &request_vars {
             let placeholder = format!("{{{{{}}}}}", key);
             url = url.replace(&placeholder, val);
         }
 
-        // Build headers
-        let mut headers = config.headers.clone().unwrap_or_default();
+        url = template::expand(&url);
+
+        // Build headers, variables filled in as in the URL
+        let mut headers: HashMap<String, String> = config
+            .headers
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(k, v)| {
+                let v = request_vars.iter().fold(v, |v, (key, val)| {
+                    v.replace(&format!("{{{{{}}}}}", key), val)
+                });
+                (k, template::expand(&v))
+            })
+            .collect();
+        if config.body_type.as_deref() == Some("UrlEncoded")
+            && !headers
+                .keys()
+                .any(|k| k.eq_ignore_ascii_case("content-type"))
+        {
+            headers.insert(
+                "Content-Type".to_string(),
+                "application/x-www-form-urlencoded".to_string(),
+            );
+        }
 
         // Build request body
-        let mut body = config.body.clone();
+        let mut body = config.body.as_deref().map(template::expand);
 
         // Run Pre-Request Script
         if let Some(script) = &config.pre_request_script
             && !script.trim().is_empty()
         {
-            let script_result = scripting::run_script(
-                script,
-                &config.method,
-                &url,
-                &headers,
-                body.as_deref().unwrap_or(""),
-                &current_env_vars,
-            );
+            let script_result = span.in_scope(|| {
+                scripting::run_script(
+                    script,
+                    &config.method,
+                    &url,
+                    &headers,
+                    body.as_deref().unwrap_or(""),
+                    &request_vars,
+                    &history,
+                )
+            });
 
             // Apply script results
             headers = script_result.headers;
@@ -154,6 +236,18 @@ pub async fn run_collection(
             }
         }
 
+        if let Some(throttle) = rate_limit::reserve(limits, Some(&pace), &url) {
+            let wait_ms = throttle.wait.as_millis() as u64;
+            run_result.throttled_ms += wait_ms;
+            let _ = event_tx
+                .send(RunnerEvent::Throttled {
+                    wait_ms,
+                    reason: throttle.reason,
+                })
+                .await;
+            tokio::time::sleep(throttle.wait).await;
+        }
+
         // Execute the request
         let start = std::time::Instant::now();
         let result = execute_request(
@@ -162,6 +256,11 @@ pub async fn run_collection(
             &headers,
             body.as_deref(),
             config.timeout_ms,
+            crate::net::timeouts::PhaseTimeouts {
+                connect_ms: config.connect_timeout_ms,
+                read_ms: config.read_timeout_ms,
+            },
+            resolve,
         )
         .await;
         let latency = start.elapsed().as_millis();
@@ -176,15 +275,35 @@ pub async fn run_collection(
                 if let Some(script) = &config.post_request_script
                     && !script.trim().is_empty()
                 {
-                    let script_res = scripting::run_post_script(
-                        script,
-                        status,
-                        &response_body,
-                        &response_headers,
-                        latency,
-                    );
+                    let script_res = span.in_scope(|| {
+                        scripting::run_post_script(
+                            script,
+                            status,
+                            &response_body,
+                            &response_headers,
+                            latency,
+                            &history,
+                        )
+                    });
                     tests = script_res.tests;
+                    // Later requests see what the tests extracted (tokens, ids)
+                    current_env_vars.extend(script_res.variables);
                 }
+                if let Some(expected) = collection.snapshots.get(name.as_str()) {
+                    tests.extend(snapshot::tests(expected, status, &response_body));
+                }
+                history.insert(
+                    0,
+                    HistoryEntry {
+                        method: config.method.clone(),
+                        url: url.clone(),
+                        status,
+                        body: response_body.clone(),
+                        headers: response_headers.clone(),
+                        timestamp: crate::features::history::now(),
+                    },
+                );
+                history = scripting::guard_history(history, crate::features::history::now());
 
                 // Passed if status matches AND all tests passed
                 let tests_passed = tests.iter().all(|(_, p)| *p);
@@ -201,10 +320,13 @@ pub async fn run_collection(
                     url: url.clone(),
                     status: Some(status),
                     latency_ms: Some(latency),
+                    size: Some(response_body.len()),
                     expected_status: Some(expected),
                     passed,
                     error: None,
                     tests,
+                    response_body: Some(response_body),
+                    response_headers,
                 }
             }
             Err(e) => RunResult {
@@ -213,13 +335,44 @@ pub async fn run_collection(
                 url: url.clone(),
                 status: None,
                 latency_ms: Some(latency),
+                size: None,
                 expected_status: config.expected_status,
                 passed: false,
                 error: Some(e),
                 tests: Vec::new(),
+                response_body: None,
+                response_headers: HashMap::new(),
             },
         };
 
+        match &run_result_item.error {
+            Some(error) => tracing::error!(parent: &span, url, error, "runner request failed"),
+            None => tracing::info!(
+                parent: &span,
+                method = config.method,
+                url,
+                status = run_result_item.status,
+                latency_ms = latency as u64,
+                passed = run_result_item.passed,
+                "runner request"
+            ),
+        }
+        let _ = event_tx
+            .send(RunnerEvent::RequestCompleted(run_result_item.clone()))
+            .await;
+        run_result.add_result(run_result_item);
+    }
+
+    for (offset, (name, config)) in ws_scenarios.iter().enumerate() {
+        let _ = event_tx
+            .send(RunnerEvent::RequestStarted {
+                name: name.to_string(),
+                index: requests.len() + offset,
+            })
+            .await;
+
+        let run_result_item = run_ws_scenario(name, config, &current_env_vars).await;
+
         let _ = event_tx
             .send(RunnerEvent::RequestCompleted(run_result_item.clone()))
             .await;
@@ -236,23 +389,25 @@ async fn execute_request(
     headers: &HashMap<String, String>,
     body: Option<&str>,
     timeout_ms: Option<u64>,
+    phase_timeouts: crate::net::timeouts::PhaseTimeouts,
+    resolve: &HostOverrides,
 ) -> Result<(u16, String, HashMap<String, String>), String> {
+    use crate::net::timeouts::DEFAULT_TOTAL_MS;
     use std::time::Duration;
 
-    // Build client with timeout and default User-Agent
-    let client = if let Some(ms) = timeout_ms {
-        reqwest::Client::builder()
-            .timeout(Duration::from_millis(ms))
-            .user_agent("PostDad/1.0")
-            .build()
-            .map_err(|e| format!("Failed to create client: {}", e))?
-    } else {
-        reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
-            .user_agent("PostDad/1.0")
-            .build()
-            .map_err(|e| format!("Failed to create client: {}", e))?
-    };
+    // Build client with timeouts and default User-Agent
+    let client = crate::net::pool::apply_overrides(
+        phase_timeouts.apply(
+            reqwest::Client::builder()
+                .timeout(Duration::from_millis(
+                    timeout_ms.unwrap_or(DEFAULT_TOTAL_MS),
+                ))
+                .user_agent("PostDad/1.0"),
+        ),
+        resolve,
+    )
+    .build()
+    .map_err(|e| format!("Failed to create client: {}", e))?;
 
     let method = match method.to_uppercase().as_str() {
         "GET" => reqwest::Method::GET,
@@ -290,9 +445,22 @@ async fn execute_request(
         }
         Err(e) => {
             if e.is_timeout() {
+                let mut phases = Vec::new();
+                if let Some(ms) = phase_timeouts.connect_ms {
+                    phases.push(format!("connect {}ms", ms));
+                }
+                if let Some(ms) = phase_timeouts.read_ms {
+                    phases.push(format!("read {}ms", ms));
+                }
+                let phases = if phases.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", phases.join(", "))
+                };
                 Err(format!(
-                    "Request timed out after {}ms",
-                    timeout_ms.unwrap_or(30000)
+                    "Request timed out after {}ms{}",
+                    timeout_ms.unwrap_or(DEFAULT_TOTAL_MS),
+                    phases
                 ))
             } else {
                 Err(format!("Request failed: {}", e))
@@ -300,3 +468,161 @@ async fn execute_request(
         }
     }
 }
+
+fn substitute_vars(text: &str, env_vars: &HashMap<String, String>) -> String {
+    let mut out = text.to_string();
+    for (key, val) in env_vars {
+        let placeholder = format!("{{{{{}}}}}", key);
+        out = out.replace(&placeholder, val);
+    }
+    template::expand(&out)
+}
+
+/// Human readable label for an expectation, used as the test name in results
+pub fn describe_expectation(expectation: &WsExpectation) -> String {
+    match (&expectation.path, &expectation.equals, &expectation.regex) {
+        (Some(path), Some(value), _) => format!("{} == {}", path, value),
+        (Some(path), None, Some(re)) => format!("{} =~ /{}/", path, re),
+        (Some(path), None, None) => format!("{} exists", path),
+        (None, _, Some(re)) => format!("message =~ /{}/", re),
+        (None, Some(value), None) => format!("message == {}", value),
+        (None, None, None) => "any message".to_string(),
+    }
+}
+
+/// Checks whether a received WebSocket message satisfies an expectation
+pub fn message_matches(expectation: &WsExpectation, message: &str) -> bool {
+    let subject = if let Some(path) = &expectation.path {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(message) else {
+            return false;
+        };
+        let mut selector = jsonpath_lib::selector(&json);
+        match selector(path) {
+            Ok(matches) => match matches.first() {
+                Some(first) => first
+                    .as_str()
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| first.to_string()),
+                None => return false,
+            },
+            Err(_) => return false,
+        }
+    } else {
+        message.to_string()
+    };
+
+    if let Some(expected) = &expectation.equals
+        && &subject != expected
+    {
+        return false;
+    }
+
+    if let Some(pattern) = &expectation.regex {
+        match regex::Regex::new(pattern) {
+            Ok(re) => re.is_match(&subject),
+            Err(_) => false,
+        }
+    } else {
+        true
+    }
+}
+
+/// Runs a WebSocket scenario: connect, send all messages, then wait for each
+/// expectation in order (unmatched messages are skipped) until the timeout.
+pub async fn run_ws_scenario(
+    name: &str,
+    config: &WsScenarioConfig,
+    env_vars: &HashMap<String, String>,
+) -> RunResult {
+    use std::time::Duration;
+
+    let url = substitute_vars(&config.url, env_vars);
+    let timeout = Duration::from_millis(config.timeout_ms.unwrap_or(5000));
+    let start = std::time::Instant::now();
+
+    let mut result = RunResult {
+        name: name.to_string(),
+        method: "WS".to_string(),
+        url: url.clone(),
+        status: None,
+        latency_ms: None,
+        size: None,
+        expected_status: None,
+        passed: false,
+        error: None,
+        tests: Vec::new(),
+        response_body: None,
+        response_headers: HashMap::new(),
+    };
+
+    let stream = match tokio::time::timeout(timeout, connect_async(&url)).await {
+        Ok(Ok((stream, _))) => stream,
+        Ok(Err(e)) => {
+            result.error = Some(format!("Connection failed: {}", e));
+            result.latency_ms = Some(start.elapsed().as_millis());
+            return result;
+        }
+        Err(_) => {
+            result.error = Some(format!(
+                "Connection timed out after {}ms",
+                timeout.as_millis()
+            ));
+            result.latency_ms = Some(start.elapsed().as_millis());
+            return result;
+        }
+    };
+    result.status = Some(101);
+
+    let (mut write, mut read) = stream.split();
+
+    for msg in &config.send {
+        let text = substitute_vars(msg, env_vars);
+        if let Err(e) = write.send(Message::Text(text.into())).await {
+            result.error = Some(format!("Send failed: {}", e));
+            result.latency_ms = Some(start.elapsed().as_millis());
+            return result;
+        }
+    }
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut pending = config.expect.iter().peekable();
+
+    while let Some(expectation) = pending.peek() {
+        let next = tokio::time::timeout_at(deadline, read.next()).await;
+        let text = match next {
+            Ok(Some(Ok(Message::Text(t)))) => t.to_string(),
+            Ok(Some(Ok(Message::Binary(b)))) => String::from_utf8_lossy(&b).to_string(),
+            Ok(Some(Ok(Message::Close(_)))) | Ok(None) => {
+                result.error =
+                    Some("Connection closed before all expectations matched".to_string());
+                break;
+            }
+            Ok(Some(Ok(_))) => continue,
+            Ok(Some(Err(e))) => {
+                result.error = Some(e.to_string());
+                break;
+            }
+            Err(_) => {
+                result.error = Some(format!("Timed out after {}ms", timeout.as_millis()));
+                break;
+            }
+        };
+
+        if message_matches(expectation, &text) {
+            result.tests.push((describe_expectation(expectation), true));
+            pending.next();
+        }
+    }
+
+    for expectation in pending {
+        result
+            .tests
+            .push((describe_expectation(expectation), false));
+    }
+
+    let _ = write.send(Message::Close(None)).await;
+
+    result.latency_ms = Some(start.elapsed().as_millis());
+    result.passed = result.error.is_none() && result.tests.iter().all(|(_, p)| *p);
+    result
+}
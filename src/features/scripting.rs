@@ -1,7 +1,114 @@
-use rhai::{Engine, Scope};
+use rhai::{Array, Engine, Map, Scope};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+/// Scripts see at most this many past requests, newest first
+pub const HISTORY_MAX_ENTRIES: usize = 50;
+/// ... sent within this long
+pub const HISTORY_MAX_AGE_SECS: u64 = 60 * 60;
+/// ... and a body bigger than this comes back empty
+pub const HISTORY_MAX_BODY: usize = 256 * 1024;
+
+/// A past request and its response, as `last_response()`, `history()` and the rest
+/// hand them to scripts. Scripts only read these.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HistoryEntry {
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+    pub body: String,
+    pub headers: HashMap<String, String>,
+    /// Unix seconds
+    pub timestamp: u64,
+}
+
+/// The part of `entries` (newest first) scripts get to see, see `HISTORY_MAX_*`.
+/// Entries without a time are left out, their age is unknown.
+pub fn guard_history(
+    entries: impl IntoIterator<Item = HistoryEntry>,
+    now_secs: u64,
+) -> Vec<HistoryEntry> {
+    entries
+        .into_iter()
+        .filter(|e| e.timestamp > 0 && now_secs.saturating_sub(e.timestamp) <= HISTORY_MAX_AGE_SECS)
+        .take(HISTORY_MAX_ENTRIES)
+        .map(|mut e| {
+            if e.body.len() > HISTORY_MAX_BODY {
+                e.body.clear();
+            }
+            e
+        })
+        .collect()
+}
+
+/// The first value `query` selects in a JSON `body`, strings without their quotes;
+/// empty when there's none
+fn json_path_value(body: &str, query: &str) -> String {
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(body) {
+        let mut selector = jsonpath_lib::selector(&json);
+        if let Ok(matches) = selector(query)
+            && let Some(first) = matches.first()
+        {
+            return match first.as_str() {
+                Some(s) => s.to_string(),
+                None => first.to_string(),
+            };
+        }
+    }
+    String::new()
+}
+
+/// `last_response(url)`, `last_status(url)`, `last_header(url, name)`,
+/// `last_json(url, path)` and `history(n)`. `url` is matched anywhere in the past
+/// request's URL, "" takes the newest of all.
+fn register_history(engine: &mut Engine, history: &[HistoryEntry]) {
+    let history = Arc::new(history.to_vec());
+    let find = |history: &[HistoryEntry], url: &str| -> Option<HistoryEntry> {
+        history.iter().find(|e| e.url.contains(url)).cloned()
+    };
+
+    let h = history.clone();
+    engine.register_fn("last_response", move |url: &str| -> String {
+        find(&h, url).map(|e| e.body).unwrap_or_default()
+    });
+    let h = history.clone();
+    engine.register_fn("last_status", move |url: &str| -> i64 {
+        find(&h, url).map(|e| e.status as i64).unwrap_or(0)
+    });
+    let h = history.clone();
+    engine.register_fn("last_header", move |url: &str, name: &str| -> String {
+        find(&h, url)
+            .and_then(|e| {
+                e.headers
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                    .map(|(_, v)| v.clone())
+            })
+            .unwrap_or_default()
+    });
+    let h = history.clone();
+    engine.register_fn("last_json", move |url: &str, query: &str| -> String {
+        find(&h, url)
+            .map(|e| json_path_value(&e.body, query))
+            .unwrap_or_default()
+    });
+    engine.register_fn("history", move |n: i64| -> Array {
+        history
+            .iter()
+            .take(n.max(0) as usize)
+            .map(|e| {
+                let mut entry = Map::new();
+                entry.insert("method".into(), e.method.clone().into());
+                entry.insert("url".into(), e.url.clone().into());
+                entry.insert("status".into(), (e.status as i64).into());
+                entry.insert("body".into(), e.body.clone().into());
+                entry.insert("timestamp".into(), (e.timestamp as i64).into());
+                entry.into()
+            })
+            .collect()
+    });
+}
+
 /// Result of running a pre-request script
 #[derive(Debug, Clone, Default)]
 pub struct ScriptResult {
@@ -19,12 +126,14 @@ pub fn run_script(
     current_headers: &HashMap<String, String>,
     current_body: &str,
     env_vars: &HashMap<String, String>,
+    history: &[HistoryEntry],
 ) -> ScriptResult {
     if script.trim().is_empty() {
         return ScriptResult::default();
     }
 
     let mut engine = Engine::new();
+    register_history(&mut engine, history);
 
     // Shared state for the script to modify
     let headers: Arc<Mutex<HashMap<String, String>>> =
@@ -106,20 +215,7 @@ pub fn run_script(
     });
 
     // Register uuid
-    engine.register_fn("uuid", || -> String {
-        // Simple UUID v4 generation using random bytes
-        use rand::Rng;
-        let mut rng = rand::rng();
-        let bytes: [u8; 16] = rng.random();
-        format!(
-            "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
-            bytes[0], bytes[1], bytes[2], bytes[3],
-            bytes[4], bytes[5],
-            (bytes[6] & 0x0f) | 0x40, bytes[7],
-            (bytes[8] & 0x3f) | 0x80, bytes[9],
-            bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15]
-        )
-    });
+    engine.register_fn("uuid", crate::features::template::uuid_v4);
 
     // Register base64_encode
     engine.register_fn("base64_encode", |text: &str| -> String {
@@ -138,6 +234,14 @@ pub fn run_script(
         base64_decode_str(text).unwrap_or_default()
     });
 
+    // Hex digests, for request signing
+    engine.register_fn("sha256", crate::features::gist_sync::hash);
+    engine.register_fn("hmac_sha256", |key: &str, text: &str| -> String {
+        crate::features::storage::hmac(key.as_bytes(), text)
+            .map(|mac| mac.iter().map(|b| format!("{:02x}", b)).collect())
+            .unwrap_or_default()
+    });
+
     // Register print for debugging
     engine.register_fn("print", move |msg: &str| {
         if let Ok(mut l) = logs_clone.lock() {
@@ -157,10 +261,12 @@ pub fn run_script(
     match engine.compile(script) {
         Ok(ast) => {
             if let Err(e) = engine.run_ast_with_scope(&mut scope, &ast) {
+                tracing::warn!(error = %e, "pre-request script failed");
                 result.errors.push(format!("Script error: {}", e));
             }
         }
         Err(e) => {
+            tracing::warn!(error = %e, "pre-request script doesn't compile");
             result.errors.push(format!("Script compile error: {}", e));
         }
     }
@@ -183,6 +289,11 @@ pub fn run_script(
             result.errors.push(format!("[LOG] {}", log));
         }
     }
+    tracing::debug!(
+        headers = result.headers.len(),
+        variables = result.variables.len(),
+        "pre-request script ran"
+    );
 
     result
 }
@@ -292,6 +403,8 @@ fn base64_decode_str(input: &str) -> Option<String> {
 #[derive(Debug, Clone, Default)]
 pub struct PostScriptResult {
     pub tests: Vec<(String, bool)>,
+    /// Variables set with `set_var`, e.g. a token pulled out of a login response
+    pub variables: HashMap<String, String>,
     pub errors: Vec<String>,
 }
 
@@ -301,19 +414,24 @@ pub fn run_post_script(
     body: &str,
     headers: &HashMap<String, String>,
     latency: u128,
+    history: &[HistoryEntry],
 ) -> PostScriptResult {
     if script.trim().is_empty() {
         return PostScriptResult::default();
     }
 
     let mut engine = Engine::new();
+    register_history(&mut engine, history);
 
     // Shared state
     let tests: Arc<Mutex<Vec<(String, bool)>>> = Arc::new(Mutex::new(Vec::new()));
     let logs: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
 
+    let variables: Arc<Mutex<HashMap<String, String>>> = Arc::new(Mutex::new(HashMap::new()));
+
     let tests_clone = tests.clone();
     let logs_clone = logs.clone();
+    let vars_set = variables.clone();
 
     // Capture response data for closures
     let headers_arc = Arc::new(headers.clone());
@@ -327,6 +445,13 @@ pub fn run_post_script(
         }
     });
 
+    // Register set_var
+    engine.register_fn("set_var", move |name: &str, value: &str| {
+        if let Ok(mut v) = vars_set.lock() {
+            v.insert(name.to_string(), value.to_string());
+        }
+    });
+
     // Register status_code
     engine.register_fn("status_code", move || -> i64 { status as i64 });
 
@@ -348,20 +473,7 @@ pub fn run_post_script(
     // Register json_path
     let body_json = body_string.clone();
     engine.register_fn("json_path", move |query: &str| -> String {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&body_json) {
-            let mut selector = jsonpath_lib::selector(&json);
-            if let Ok(matches) = selector(query)
-                && let Some(first) = matches.first()
-            {
-                // Return raw string or json string?
-                if let Some(s) = first.as_str() {
-                    return s.to_string();
-                } else {
-                    return first.to_string();
-                }
-            }
-        }
-        String::new()
+        json_path_value(&body_json, query)
     });
 
     // Register print
@@ -376,6 +488,7 @@ pub fn run_post_script(
     match engine.eval::<()>(script) {
         Ok(_) => {}
         Err(e) => {
+            tracing::warn!(error = %e, "post-request script failed");
             result.errors.push(format!("Script error: {}", e));
         }
     }
@@ -383,11 +496,24 @@ pub fn run_post_script(
     if let Ok(t) = tests.lock() {
         result.tests = t.clone();
     }
+    if let Ok(v) = variables.lock() {
+        result.variables = v.clone();
+    }
     if let Ok(l) = logs.lock() {
         for log in l.iter() {
             result.errors.push(format!("[LOG] {}", log));
         }
     }
+    let passed = result.tests.iter().filter(|(_, p)| *p).count();
+    for (name, _) in result.tests.iter().filter(|(_, p)| !*p) {
+        tracing::warn!(test = name.as_str(), "test failed");
+    }
+    tracing::info!(
+        passed,
+        failed = result.tests.len() - passed,
+        variables = result.variables.len(),
+        "post-request script ran"
+    );
 
     result
 }
@@ -408,6 +534,7 @@ mod tests {
             &HashMap::new(),
             "",
             &HashMap::new(),
+            &[],
         );
 
         assert_eq!(result.headers.get("X-Custom"), Some(&"test".to_string()));
@@ -426,6 +553,7 @@ mod tests {
             &HashMap::new(),
             "",
             &HashMap::new(),
+            &[],
         );
 
         assert!(result.headers.contains_key("X-Timestamp"));
@@ -445,6 +573,7 @@ mod tests {
             "{}",
             &headers,
             100,
+            &[],
         );
 
         assert_eq!(result.tests.len(), 2);
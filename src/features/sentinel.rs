@@ -10,6 +10,10 @@ pub struct SentinelConfig {
     pub body: Option<String>,
     pub interval_secs: u64,
     pub failure_keyword: Option<String>,
+    /// Host limits hold checks back when other senders are busy with the same host
+    pub rate_limit: crate::net::rate_limit::RateLimitSettings,
+    /// The environment's pinned hosts
+    pub resolve: crate::net::pool::HostOverrides,
 }
 
 #[derive(Debug)]
@@ -17,6 +21,8 @@ pub struct SentinelResult {
     pub latency_ms: u64,
     pub status: Result<u16, String>,
     pub timestamp: u64,
+    /// Why the check waited before going out, if it did
+    pub throttled: Option<String>,
 }
 
 pub struct SentinelState {
@@ -28,6 +34,8 @@ pub struct SentinelState {
     pub failed_checks: u64,
     pub last_latency: u64,
     pub last_status: Option<u16>,
+    /// Set while checks are being held back by a rate limit
+    pub throttled: Option<String>,
     // Channel to stop the background task
     pub stop_tx: Option<mpsc::Sender<()>>,
 }
@@ -43,6 +51,7 @@ impl SentinelState {
             failed_checks: 0,
             last_latency: 0,
             last_status: None,
+            throttled: None,
             stop_tx: None,
         }
     }
@@ -50,6 +59,7 @@ impl SentinelState {
     pub fn add_result(&mut self, result: SentinelResult) {
         self.total_checks += 1;
         self.last_latency = result.latency_ms;
+        self.throttled = result.throttled;
 
         if self.latency_history.len() >= 100 {
             self.latency_history.pop_front();
@@ -112,16 +122,23 @@ pub async fn run_sentinel_task(
     res_tx: mpsc::Sender<SentinelResult>,
     mut stop_rx: mpsc::Receiver<()>,
 ) {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(5))
-        .build()
-        .unwrap_or_else(|_| reqwest::Client::new());
+    let client = crate::net::pool::apply_overrides(
+        reqwest::Client::builder().timeout(Duration::from_secs(5)),
+        &config.resolve,
+    )
+    .build()
+    .unwrap_or_else(|_| reqwest::Client::new());
 
     let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
 
     loop {
         tokio::select! {
             _ = interval.tick() => {
+                 let throttle =
+                     crate::net::rate_limit::reserve(&config.rate_limit, None, &config.url);
+                 if let Some(throttle) = &throttle {
+                     tokio::time::sleep(throttle.wait).await;
+                 }
                  let start = Instant::now();
                  let method = match config.method.as_str() {
                     "POST" => reqwest::Method::POST,
@@ -173,6 +190,7 @@ pub async fn run_sentinel_task(
                         .duration_since(std::time::UNIX_EPOCH)
                         .unwrap()
                         .as_secs(),
+                    throttled: throttle.map(|t| t.reason),
                 };
 
                 if res_tx.send(res).await.is_err() {
@@ -0,0 +1,146 @@
+// Snapshot testing: a response is compared with the one saved for its request, and
+// every difference outside the ignored paths is a failed test. Sends from the TUI
+// and runner runs both check, see `tests`.
+use crate::domain::snapshot::Snapshot;
+use crate::features::json_diff;
+use serde_json::Value;
+
+/// Differences listed one by one before the rest are counted
+const MAX_LISTED: usize = 10;
+
+/// A snapshot of this response; `ignore` carries over from the one it replaces
+pub fn capture(status: u16, body: &str, ignore: Vec<String>, now_secs: u64) -> Snapshot {
+    let json = serde_json::from_str::<Value>(body).ok();
+    Snapshot {
+        status,
+        text: json.is_none().then(|| body.to_string()),
+        json,
+        ignore,
+        saved_at: now_secs,
+    }
+}
+
+/// What's different about this response, one line per difference
+pub fn drift(snapshot: &Snapshot, status: u16, body: &str) -> Vec<String> {
+    let mut changes = Vec::new();
+    if status != snapshot.status {
+        changes.push(format!("status {} → {}", snapshot.status, status));
+    }
+    match (&snapshot.json, serde_json::from_str::<Value>(body)) {
+        (Some(expected), Ok(actual)) => changes.extend(
+            json_diff::diff_json(expected, &actual)
+                .iter()
+                .filter(|c| !is_ignored(&c.path, &snapshot.ignore))
+                .map(|c| c.describe()),
+        ),
+        (Some(_), Err(_)) => changes.push("body is no longer JSON".to_string()),
+        (None, _) => {
+            let expected = snapshot.text.as_deref().unwrap_or_default();
+            if expected != body {
+                changes.push(format!(
+                    "body changed ({} → {} bytes)",
+                    expected.len(),
+                    body.len()
+                ));
+            }
+        }
+    }
+    changes
+}
+
+/// `drift` as test results: one passing "snapshot matches", or a failure per
+/// difference (the first few, then how many more)
+pub fn tests(snapshot: &Snapshot, status: u16, body: &str) -> Vec<(String, bool)> {
+    let changes = drift(snapshot, status, body);
+    if changes.is_empty() {
+        return vec![("snapshot matches".to_string(), true)];
+    }
+    let mut tests: Vec<(String, bool)> = changes
+        .iter()
+        .take(MAX_LISTED)
+        .map(|c| (format!("snapshot: {}", c), false))
+        .collect();
+    if changes.len() > MAX_LISTED {
+        tests.push((
+            format!("snapshot: … and {} more", changes.len() - MAX_LISTED),
+            false,
+        ));
+    }
+    tests
+}
+
+/// Whether a test result came from `tests`
+pub fn is_snapshot_test(name: &str) -> bool {
+    name == "snapshot matches" || name.starts_with("snapshot: ")
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    /// `*` or `[*]`: any one key or index
+    Any,
+    /// `..`: any number of levels, none included
+    Descend,
+}
+
+/// `$.items[0]['a b']` as segments. A pattern can also have `*`, `[*]` and `..`.
+fn segments(path: &str) -> Vec<Segment> {
+    let mut out = Vec::new();
+    let mut rest = path.trim().strip_prefix('$').unwrap_or(path.trim());
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            out.push(Segment::Descend);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("['") {
+            let end = after.find("']").unwrap_or(after.len());
+            out.push(Segment::Key(after[..end].replace("\\'", "'")));
+            rest = after.get(end + 2..).unwrap_or_default();
+            continue;
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').unwrap_or(after.len());
+            let inner = after[..end].trim();
+            out.push(match inner.parse() {
+                Ok(index) => Segment::Index(index),
+                Err(_) => Segment::Any,
+            });
+            rest = after.get(end + 1..).unwrap_or_default();
+            continue;
+        } else if let Some(after) = rest.strip_prefix('.') {
+            rest = after;
+        }
+        let end = rest.find(['.', '[']).unwrap_or(rest.len());
+        if end > 0 {
+            let key = &rest[..end];
+            out.push(if key == "*" {
+                Segment::Any
+            } else {
+                Segment::Key(key.to_string())
+            });
+        }
+        rest = &rest[end..];
+    }
+    out
+}
+
+fn matches(pattern: &[Segment], path: &[Segment]) -> bool {
+    match pattern.split_first() {
+        // What's under an ignored path is ignored with it
+        None => true,
+        Some((Segment::Descend, rest)) => (0..=path.len()).any(|i| matches(rest, &path[i..])),
+        Some((segment, rest)) => match path.split_first() {
+            None => false,
+            Some((first, path_rest)) => {
+                (*segment == Segment::Any || segment == first) && matches(rest, path_rest)
+            }
+        },
+    }
+}
+
+/// Whether a change at `path` (as json_diff writes them) falls under any of `ignore`
+pub fn is_ignored(path: &str, ignore: &[String]) -> bool {
+    let path = segments(path);
+    ignore
+        .iter()
+        .any(|pattern| matches(&segments(pattern), &path))
+}
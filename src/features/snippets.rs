@@ -0,0 +1,175 @@
+// Script snippets: named Rhai fragments inserted into a request's pre-request or test
+// script from a picker. A few ship with PostDad; users keep their own as
+// `snippets/<id>.rhai`, saved from a script with `:snippet save <name>`.
+
+/// Where user snippets live, relative to the working directory
+pub const SNIPPETS_DIR: &str = "snippets";
+
+const BUILTIN_SNIPPETS: [(&str, &str); 3] = [
+    (
+        "bearer-refresh",
+        include_str!("snippets/bearer-refresh.rhai"),
+    ),
+    ("hmac-signing", include_str!("snippets/hmac-signing.rhai")),
+    ("pagination", include_str!("snippets/pagination.rhai")),
+];
+
+/// Which of a request's scripts a snippet goes into
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScriptKind {
+    Pre,
+    Post,
+}
+
+impl ScriptKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ScriptKind::Pre => "pre",
+            ScriptKind::Post => "post",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            ScriptKind::Pre => "pre-request script",
+            ScriptKind::Post => "test script",
+        }
+    }
+
+    pub fn parse(word: &str) -> Option<Self> {
+        match word.to_lowercase().as_str() {
+            "pre" => Some(ScriptKind::Pre),
+            "post" | "test" => Some(ScriptKind::Post),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snippet {
+    /// File stem, used by `:snippet <id>`
+    pub id: String,
+    /// Shown in the picker, from a leading `// name: ...` line
+    pub name: String,
+    /// The script it's written for, from a `// script: pre|post` line
+    pub kind: Option<ScriptKind>,
+    /// The code that gets inserted, without the header lines
+    pub code: String,
+    /// Shipped with PostDad rather than loaded from `snippets/`
+    pub builtin: bool,
+}
+
+impl Snippet {
+    /// Reads the `// name:` and `// script:` lines at the top of `source`
+    pub fn parse(id: &str, source: &str, builtin: bool) -> Self {
+        let mut name = None;
+        let mut kind = None;
+        let mut lines = source.lines().peekable();
+        while let Some(line) = lines.peek() {
+            let header = line.trim().strip_prefix("//").map(str::trim);
+            if let Some(value) = header.and_then(|h| h.strip_prefix("name:")) {
+                name = Some(value.trim().to_string());
+            } else if let Some(value) = header.and_then(|h| h.strip_prefix("script:")) {
+                kind = ScriptKind::parse(value.trim());
+            } else {
+                break;
+            }
+            lines.next();
+        }
+        let code = lines.collect::<Vec<_>>().join("\n");
+        Snippet {
+            id: id.to_string(),
+            name: name
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| id.to_string()),
+            kind,
+            code: code.trim_end().to_string(),
+            builtin,
+        }
+    }
+
+    /// Picker filter: every word of `query` appears in the id or name
+    pub fn matches(&self, query: &str) -> bool {
+        let haystack = format!("{} {}", self.id, self.name).to_lowercase();
+        query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| haystack.contains(word))
+    }
+}
+
+/// The built-in snippets, then the `*.rhai` files in `dir` sorted by id. A user
+/// snippet with a built-in's id replaces it.
+pub fn load_snippets(dir: &str) -> Vec<Snippet> {
+    let mut snippets: Vec<Snippet> = BUILTIN_SNIPPETS
+        .iter()
+        .map(|(id, source)| Snippet::parse(id, source, true))
+        .collect();
+
+    let mut custom: Vec<Snippet> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_string_lossy().to_lowercase();
+            let source = std::fs::read_to_string(&path).ok()?;
+            Some(Snippet::parse(&id, &source, false))
+        })
+        .collect();
+    custom.sort_by(|a, b| a.id.cmp(&b.id));
+
+    for snippet in custom {
+        match snippets.iter_mut().find(|s| s.id == snippet.id) {
+            Some(existing) => *existing = snippet,
+            None => snippets.push(snippet),
+        }
+    }
+    snippets
+}
+
+/// Looks a snippet up by id or display name, ignoring case
+pub fn find_snippet<'a>(snippets: &'a [Snippet], key: &str) -> Option<&'a Snippet> {
+    let key = key.trim().to_lowercase();
+    snippets
+        .iter()
+        .find(|s| s.id == key || s.name.to_lowercase() == key)
+}
+
+/// `script` with `snippet` added at the end, a blank line between them
+pub fn insert(script: &str, snippet: &Snippet) -> String {
+    let script = script.trim_end();
+    if script.is_empty() {
+        format!("{}\n", snippet.code)
+    } else {
+        format!("{}\n\n{}\n", script, snippet.code)
+    }
+}
+
+/// `Refresh Token` -> `refresh-token`, the file stem a saved snippet gets
+pub fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Writes `code` to `{dir}/{slug}.rhai` under a `// name:` header, returning its path
+pub fn save(dir: &str, name: &str, kind: ScriptKind, code: &str) -> Result<String, String> {
+    let id = slug(name);
+    if id.is_empty() {
+        return Err(format!("'{}' can't be a snippet name", name));
+    }
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let path = std::path::Path::new(dir).join(format!("{}.rhai", id));
+    let source = format!(
+        "// name: {}\n// script: {}\n{}\n",
+        name.trim(),
+        kind.name(),
+        code.trim_end()
+    );
+    std::fs::write(&path, source).map_err(|e| e.to_string())?;
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,667 @@
+// Remote storage for the workspace: collections and environments.hcl mirrored to a shared
+// directory, a WebDAV folder or an S3-compatible bucket, so a team can share one
+// workspace without git. Comparing and settling conflicts is the gist sync's job; this
+// only knows how to list, read and write files on each backend.
+
+use crate::domain::workspace::Workspace;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+/// Per-file hashes from the last storage sync, kept in the workspace
+pub const STATE_FILE: &str = "storage_state.json";
+const ENV_FILE: &str = "environments.hcl";
+const COLLECTIONS_DIR: &str = "collections/";
+/// SHA-256 of an empty body
+const EMPTY_HASH: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// A directory, e.g. a network share mounted on everyone's machine
+    #[default]
+    Local,
+    Webdav,
+    S3,
+}
+
+/// `storage` in config.json
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageSettings {
+    pub backend: Backend,
+    /// The directory (local), the folder URL (webdav) or the endpoint (s3), e.g.
+    /// `https://s3.eu-west-1.amazonaws.com` or `http://localhost:9000` for MinIO
+    pub url: String,
+    /// WebDAV basic auth; no auth when empty
+    pub username: String,
+    /// Environment variable holding the WebDAV password
+    pub password_env: String,
+    pub bucket: String,
+    pub region: String,
+    /// Key prefix in the bucket, e.g. `team/`
+    pub prefix: String,
+    /// `bucket.host` instead of `host/bucket`
+    pub virtual_hosted: bool,
+    /// Environment variables holding the S3 credentials
+    pub access_key_env: String,
+    pub secret_key_env: String,
+}
+
+impl Default for StorageSettings {
+    fn default() -> Self {
+        StorageSettings {
+            backend: Backend::Local,
+            url: String::new(),
+            username: String::new(),
+            password_env: "POSTDAD_STORAGE_PASSWORD".to_string(),
+            bucket: String::new(),
+            region: "us-east-1".to_string(),
+            prefix: String::new(),
+            virtual_hosted: false,
+            access_key_env: "AWS_ACCESS_KEY_ID".to_string(),
+            secret_key_env: "AWS_SECRET_ACCESS_KEY".to_string(),
+        }
+    }
+}
+
+impl StorageSettings {
+    pub fn is_configured(&self) -> bool {
+        !self.url.trim().is_empty()
+    }
+
+    /// Where the files go, e.g. `s3://bucket/team/`; the sync state is kept per location
+    pub fn location(&self) -> String {
+        match self.backend {
+            Backend::S3 => format!("s3://{}/{}", self.bucket, self.prefix),
+            _ => self.url.clone(),
+        }
+    }
+
+    fn env(name: &str) -> Option<String> {
+        std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+    }
+}
+
+/// What every backend can do. Keys are relative paths with `/`, e.g. `collections/shop.hcl`.
+pub trait Storage {
+    /// Every file under the root, a couple of levels deep
+    fn list(&self) -> impl Future<Output = Result<Vec<String>, String>> + Send;
+    fn get(&self, key: &str) -> impl Future<Output = Result<String, String>> + Send;
+    fn put(&self, key: &str, content: &str) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+/// The configured backend
+pub enum Store {
+    Local(LocalDir),
+    WebDav(WebDav),
+    S3(S3),
+}
+
+pub fn open(settings: &StorageSettings) -> Result<Store, String> {
+    if !settings.is_configured() {
+        return Err("No storage set up: add storage to config.json".to_string());
+    }
+    Ok(match settings.backend {
+        Backend::Local => Store::Local(LocalDir {
+            root: PathBuf::from(&settings.url),
+        }),
+        Backend::Webdav => Store::WebDav(WebDav::new(settings)?),
+        Backend::S3 => Store::S3(S3::new(settings)?),
+    })
+}
+
+impl Storage for Store {
+    async fn list(&self) -> Result<Vec<String>, String> {
+        match self {
+            Store::Local(store) => store.list().await,
+            Store::WebDav(store) => store.list().await,
+            Store::S3(store) => store.list().await,
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<String, String> {
+        match self {
+            Store::Local(store) => store.get(key).await,
+            Store::WebDav(store) => store.get(key).await,
+            Store::S3(store) => store.get(key).await,
+        }
+    }
+
+    async fn put(&self, key: &str, content: &str) -> Result<(), String> {
+        match self {
+            Store::Local(store) => store.put(key, content).await,
+            Store::WebDav(store) => store.put(key, content).await,
+            Store::S3(store) => store.put(key, content).await,
+        }
+    }
+}
+
+/// The files that are mirrored: `environments.hcl` and `collections/*.hcl`. Keys come
+/// from outside, so nothing that could leave the workspace passes.
+pub fn is_mirrored(key: &str) -> bool {
+    key == ENV_FILE
+        || key.strip_prefix(COLLECTIONS_DIR).is_some_and(|name| {
+            name.ends_with(".hcl") && !name.contains(['/', '\\']) && !name.starts_with('.')
+        })
+}
+
+/// The workspace's mirrored files, by key
+pub fn workspace_files(workspace: &Workspace) -> BTreeMap<String, String> {
+    let mut files: BTreeMap<String, String> =
+        crate::features::gist_sync::local_files(&workspace.path("collections"))
+            .into_iter()
+            .map(|(name, content)| (format!("{}{}", COLLECTIONS_DIR, name), content))
+            .collect();
+    if let Ok(content) = std::fs::read_to_string(workspace.path(ENV_FILE)) {
+        files.insert(ENV_FILE.to_string(), content);
+    }
+    files
+}
+
+pub fn write_pulls(workspace: &Workspace, pulls: &[(String, String)]) -> Result<(), String> {
+    for (key, content) in pulls {
+        if !is_mirrored(key) {
+            return Err(format!("Refusing to write stored file '{}'", key));
+        }
+        let path = workspace.path(key);
+        if let Some(parent) = Path::new(&path).parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, content).map_err(|e| format!("Could not write {}: {}", key, e))?;
+    }
+    Ok(())
+}
+
+/// Reads every mirrored file from the storage
+pub async fn fetch(
+    settings: &StorageSettings,
+) -> Result<crate::features::gist_sync::RemoteFiles, String> {
+    let store = open(settings)?;
+    let mut files = BTreeMap::new();
+    for key in store.list().await? {
+        if is_mirrored(&key) {
+            let content = store.get(&key).await?;
+            files.insert(key, content);
+        }
+    }
+    Ok(crate::features::gist_sync::RemoteFiles {
+        id: settings.location(),
+        updated_at: String::new(),
+        files,
+    })
+}
+
+/// Uploads `files`; returns the location, to record the sync under
+pub async fn push(
+    settings: &StorageSettings,
+    files: &[(String, String)],
+) -> Result<String, String> {
+    let store = open(settings)?;
+    for (key, content) in files {
+        store.put(key, content).await?;
+    }
+    Ok(settings.location())
+}
+
+pub struct LocalDir {
+    pub root: PathBuf,
+}
+
+impl LocalDir {
+    async fn list(&self) -> Result<Vec<String>, String> {
+        let mut keys = Vec::new();
+        let mut dirs = vec![(self.root.clone(), String::new())];
+        while let Some((dir, prefix)) = dirs.pop() {
+            let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+                continue;
+            };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let key = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+                match entry.file_type().await {
+                    Ok(kind) if kind.is_dir() && prefix.is_empty() => {
+                        dirs.push((entry.path(), format!("{}/", key)))
+                    }
+                    Ok(kind) if kind.is_file() => keys.push(key),
+                    _ => {}
+                }
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn get(&self, key: &str) -> Result<String, String> {
+        tokio::fs::read_to_string(self.root.join(key))
+            .await
+            .map_err(|e| format!("Could not read {}: {}", key, e))
+    }
+
+    async fn put(&self, key: &str, content: &str) -> Result<(), String> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        tokio::fs::write(&path, content)
+            .await
+            .map_err(|e| format!("Could not write {}: {}", key, e))
+    }
+}
+
+pub struct WebDav {
+    /// The folder, always ending in `/`
+    base: reqwest::Url,
+    username: String,
+    password: Option<String>,
+    client: reqwest::Client,
+}
+
+impl WebDav {
+    fn new(settings: &StorageSettings) -> Result<WebDav, String> {
+        let mut url = settings.url.trim().to_string();
+        if !url.ends_with('/') {
+            url.push('/');
+        }
+        Ok(WebDav {
+            base: reqwest::Url::parse(&url).map_err(|e| format!("Bad WebDAV URL: {}", e))?,
+            username: settings.username.clone(),
+            password: StorageSettings::env(&settings.password_env),
+            client: reqwest::Client::new(),
+        })
+    }
+
+    fn url(&self, key: &str) -> Result<reqwest::Url, String> {
+        let key = key
+            .replace('%', "%25")
+            .replace('#', "%23")
+            .replace('?', "%3F");
+        self.base.join(&key).map_err(|e| e.to_string())
+    }
+
+    fn request(&self, method: &[u8], url: reqwest::Url) -> reqwest::RequestBuilder {
+        let method = reqwest::Method::from_bytes(method).unwrap_or(reqwest::Method::GET);
+        let builder = self.client.request(method, url);
+        if self.username.is_empty() {
+            builder
+        } else {
+            builder.basic_auth(&self.username, self.password.as_ref())
+        }
+    }
+
+    async fn send(&self, builder: reqwest::RequestBuilder) -> Result<(u16, String), String> {
+        let resp = builder.send().await.map_err(|e| e.to_string())?;
+        let status = resp.status().as_u16();
+        let text = resp.text().await.map_err(|e| e.to_string())?;
+        Ok((status, text))
+    }
+
+    async fn list(&self) -> Result<Vec<String>, String> {
+        const BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?><d:propfind xmlns:d="DAV:"><d:prop><d:resourcetype/></d:prop></d:propfind>"#;
+        let mut keys = Vec::new();
+        let mut dirs = vec![String::new()];
+        while let Some(dir) = dirs.pop() {
+            let builder = self
+                .request(b"PROPFIND", self.url(&dir)?)
+                .header("Depth", "1")
+                .header("Content-Type", "application/xml")
+                .body(BODY);
+            let (status, text) = self.send(builder).await?;
+            // A folder that isn't there yet is an empty one
+            if status == 404 {
+                continue;
+            }
+            if !(200..300).contains(&status) {
+                return Err(format!(
+                    "WebDAV server returned {} listing /{}",
+                    status, dir
+                ));
+            }
+            for key in propfind_keys(&text, self.base.path()) {
+                match key.strip_suffix('/') {
+                    // One level down is enough for collections/
+                    Some(_) if dir.is_empty() => dirs.push(key),
+                    Some(_) => {}
+                    None => keys.push(key),
+                }
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn get(&self, key: &str) -> Result<String, String> {
+        let (status, text) = self.send(self.request(b"GET", self.url(key)?)).await?;
+        if !(200..300).contains(&status) {
+            return Err(format!("WebDAV server returned {} for {}", status, key));
+        }
+        Ok(text)
+    }
+
+    async fn put(&self, key: &str, content: &str) -> Result<(), String> {
+        let put = || -> Result<reqwest::RequestBuilder, String> {
+            Ok(self
+                .request(b"PUT", self.url(key)?)
+                .body(content.to_string()))
+        };
+        let (mut status, _) = self.send(put()?).await?;
+        // The folder isn't there yet: make it, then try again
+        if (status == 404 || status == 409)
+            && let Some((dir, _)) = key.rsplit_once('/')
+        {
+            self.send(self.request(b"MKCOL", self.url(&format!("{}/", dir))?))
+                .await?;
+            status = self.send(put()?).await?.0;
+        }
+        if !(200..300).contains(&status) {
+            return Err(format!("WebDAV server returned {} storing {}", status, key));
+        }
+        Ok(())
+    }
+}
+
+/// Keys from a PROPFIND multistatus reply, relative to `base_path` (the folder's URL
+/// path); folders end in `/`, the folder itself is left out
+pub fn propfind_keys(xml: &str, base_path: &str) -> Vec<String> {
+    let href =
+        regex::Regex::new(r"<(?:[A-Za-z0-9]+:)?href>\s*([^<]*?)\s*</(?:[A-Za-z0-9]+:)?href>")
+            .expect("valid regex");
+    let base_path = percent_decode(base_path);
+    href.captures_iter(xml)
+        .filter_map(|c| {
+            let href = xml_unescape(&c[1]);
+            // Either a path or a full URL
+            let path = match reqwest::Url::parse(&href) {
+                Ok(url) => url.path().to_string(),
+                Err(_) => href,
+            };
+            percent_decode(&path)
+                .strip_prefix(&base_path)
+                .filter(|key| !key.is_empty())
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = s.get(i + 1..i + 3)
+            && let Ok(b) = u8::from_str_radix(hex, 16)
+        {
+            out.push(b);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Credentials and scope for Signature Version 4
+pub struct SigningKey {
+    pub access_key: String,
+    pub secret_key: String,
+    pub region: String,
+}
+
+pub struct S3 {
+    scheme: String,
+    /// `host[:port]`, the bucket in front when virtual-hosted
+    host: String,
+    bucket: String,
+    prefix: String,
+    virtual_hosted: bool,
+    key: SigningKey,
+    client: reqwest::Client,
+}
+
+impl S3 {
+    fn new(settings: &StorageSettings) -> Result<S3, String> {
+        let url = reqwest::Url::parse(settings.url.trim())
+            .map_err(|e| format!("Bad S3 endpoint: {}", e))?;
+        if settings.bucket.is_empty() {
+            return Err("Set storage.bucket in config.json".to_string());
+        }
+        let (Some(access_key), Some(secret_key)) = (
+            StorageSettings::env(&settings.access_key_env),
+            StorageSettings::env(&settings.secret_key_env),
+        ) else {
+            return Err(format!(
+                "Set {} and {} to use S3 storage",
+                settings.access_key_env, settings.secret_key_env
+            ));
+        };
+        let mut host = url.host_str().unwrap_or_default().to_string();
+        if settings.virtual_hosted {
+            host = format!("{}.{}", settings.bucket, host);
+        }
+        if let Some(port) = url.port() {
+            host = format!("{}:{}", host, port);
+        }
+        Ok(S3 {
+            scheme: url.scheme().to_string(),
+            host,
+            bucket: settings.bucket.clone(),
+            prefix: settings.prefix.clone(),
+            virtual_hosted: settings.virtual_hosted,
+            key: SigningKey {
+                access_key,
+                secret_key,
+                region: settings.region.clone(),
+            },
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Encoded request path for an object, or the bucket when `key` is empty
+    fn path(&self, key: &str) -> String {
+        let object = if key.is_empty() {
+            String::new()
+        } else {
+            aws_encode(&format!("{}{}", self.prefix, key), true)
+        };
+        if self.virtual_hosted {
+            format!("/{}", object)
+        } else if object.is_empty() {
+            format!("/{}", aws_encode(&self.bucket, false))
+        } else {
+            format!("/{}/{}", aws_encode(&self.bucket, false), object)
+        }
+    }
+
+    async fn send(
+        &self,
+        method: &str,
+        key: &str,
+        query: &[(&str, &str)],
+        body: Option<&str>,
+    ) -> Result<String, String> {
+        let path = self.path(key);
+        let query = canonical_query(query);
+        let payload_hash = match body {
+            Some(body) => crate::features::gist_sync::hash(body),
+            None => EMPTY_HASH.to_string(),
+        };
+        let amz_date = amz_date(crate::features::history::now());
+        let authorization = authorization(
+            &self.key,
+            method,
+            &self.host,
+            &path,
+            &query,
+            &payload_hash,
+            &amz_date,
+        )?;
+        let mut url = format!("{}://{}{}", self.scheme, self.host, path);
+        if !query.is_empty() {
+            url = format!("{}?{}", url, query);
+        }
+        let method = reqwest::Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+        let mut builder = self
+            .client
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("Authorization", authorization);
+        if let Some(body) = body {
+            builder = builder.body(body.to_string());
+        }
+        let resp = builder.send().await.map_err(|e| e.to_string())?;
+        let status = resp.status();
+        let text = resp.text().await.map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            let message = xml_values(&text, "Message")
+                .into_iter()
+                .next()
+                .unwrap_or(text);
+            return Err(format!("S3 returned {}: {}", status.as_u16(), message));
+        }
+        Ok(text)
+    }
+
+    async fn list(&self) -> Result<Vec<String>, String> {
+        let mut keys = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", self.prefix.as_str())];
+            if let Some(token) = &token {
+                query.push(("continuation-token", token.as_str()));
+            }
+            let xml = self.send("GET", "", &query, None).await?;
+            keys.extend(
+                xml_values(&xml, "Key")
+                    .into_iter()
+                    .filter_map(|k| k.strip_prefix(&self.prefix).map(str::to_string)),
+            );
+            token = match xml_values(&xml, "IsTruncated").first().map(String::as_str) {
+                Some("true") => xml_values(&xml, "NextContinuationToken").into_iter().next(),
+                _ => None,
+            };
+            if token.is_none() {
+                break;
+            }
+        }
+        Ok(keys)
+    }
+
+    async fn get(&self, key: &str) -> Result<String, String> {
+        self.send("GET", key, &[], None).await
+    }
+
+    async fn put(&self, key: &str, content: &str) -> Result<(), String> {
+        self.send("PUT", key, &[], Some(content)).await.map(|_| ())
+    }
+}
+
+/// Text of every `<tag>` in an S3 reply
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let re =
+        regex::Regex::new(&format!("<{0}>([^<]*)</{0}>", regex::escape(tag))).expect("valid regex");
+    re.captures_iter(xml).map(|c| xml_unescape(&c[1])).collect()
+}
+
+/// URI-encodes everything but the unreserved characters, as SigV4 wants
+pub fn aws_encode(s: &str, keep_slash: bool) -> String {
+    let mut out = String::new();
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            b'/' if keep_slash => out.push('/'),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// Sorted and encoded; used both for signing and as the URL's query
+pub fn canonical_query(pairs: &[(&str, &str)]) -> String {
+    let mut pairs: Vec<(String, String)> = pairs
+        .iter()
+        .map(|(k, v)| (aws_encode(k, false), aws_encode(v, false)))
+        .collect();
+    pairs.sort();
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// `20130524T000000Z`
+pub fn amz_date(timestamp: u64) -> String {
+    let (y, m, d) = crate::features::history::civil_from_days((timestamp / 86_400) as i64);
+    let secs = timestamp % 86_400;
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        y,
+        m,
+        d,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// HMAC-SHA256 of `data`
+pub fn hmac(key: &[u8], data: &str) -> Result<Vec<u8>, String> {
+    let pkey = openssl::pkey::PKey::hmac(key).map_err(|e| e.to_string())?;
+    let mut signer = openssl::sign::Signer::new(openssl::hash::MessageDigest::sha256(), &pkey)
+        .map_err(|e| e.to_string())?;
+    signer.update(data.as_bytes()).map_err(|e| e.to_string())?;
+    signer.sign_to_vec().map_err(|e| e.to_string())
+}
+
+/// The `Authorization` header for a request signing `host`, `x-amz-content-sha256` and
+/// `x-amz-date`. `path` and `query` are already encoded.
+pub fn authorization(
+    key: &SigningKey,
+    method: &str,
+    host: &str,
+    path: &str,
+    query: &str,
+    payload_hash: &str,
+    amz_date: &str,
+) -> Result<String, String> {
+    const SIGNED: &str = "host;x-amz-content-sha256;x-amz-date";
+    let date = &amz_date[..8.min(amz_date.len())];
+    let scope = format!("{}/{}/s3/aws4_request", date, key.region);
+    let canonical = format!(
+        "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method, path, query, host, payload_hash, amz_date, SIGNED, payload_hash
+    );
+    let to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        crate::features::gist_sync::hash(&canonical)
+    );
+    let mut signing = hmac(format!("AWS4{}", key.secret_key).as_bytes(), date)?;
+    for part in [key.region.as_str(), "s3", "aws4_request"] {
+        signing = hmac(&signing, part)?;
+    }
+    let signature: String = hmac(&signing, &to_sign)?
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Ok(format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        key.access_key, scope, SIGNED, signature
+    ))
+}
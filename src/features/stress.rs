@@ -1,16 +1,411 @@
+use crate::domain::collection::Collection;
+use crate::features::template;
+use crate::net::pool::HostOverrides;
+use crate::net::rate_limit::{self, RateLimitSettings};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
-#[derive(Clone, Debug)]
-pub struct StressConfig {
+/// One request of the workload, environment variables already substituted. Template
+/// functions ({{$uuid}} etc.) are left in place and expanded on every send.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StressRequest {
+    pub name: String,
     pub url: String,
     pub method: String,
     pub headers: HashMap<String, String>,
     pub body: Option<String>,
-    pub concurrency: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct StressConfig {
+    /// Each VU sends these in turn, starting at a different one so they spread out
+    pub requests: Vec<StressRequest>,
+    /// Load profile; the test runs for their total duration
+    pub stages: Vec<Stage>,
+    /// Checked against the final stats, see `StressStats::thresholds`
+    pub thresholds: Vec<Threshold>,
+    /// Host limits hold the VUs back too; the run pace doesn't apply here
+    pub rate_limit: RateLimitSettings,
+    /// The environment's pinned hosts
+    pub resolve: HostOverrides,
+}
+
+/// Every request of `collection`, sorted by name as the runner does, with relative
+/// URLs put after `base_url` and `substitute` applied to URLs, headers and bodies.
+/// Scripts are not run.
+pub fn collection_workload(
+    collection: &Collection,
+    base_url: Option<&str>,
+    substitute: &dyn Fn(&str) -> String,
+) -> Result<Vec<StressRequest>, String> {
+    let mut names: Vec<&String> = collection.requests.keys().collect();
+    names.sort();
+    if names.is_empty() {
+        return Err(format!("Collection '{}' has no requests", collection.name));
+    }
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let config = &collection.requests[name];
+            let mut headers: HashMap<String, String> = config
+                .headers
+                .iter()
+                .flatten()
+                .map(|(k, v)| (k.clone(), substitute(v)))
+                .collect();
+            if config.body_type.as_deref() == Some("UrlEncoded")
+                && !headers
+                    .keys()
+                    .any(|k| k.eq_ignore_ascii_case("content-type"))
+            {
+                headers.insert(
+                    "Content-Type".to_string(),
+                    "application/x-www-form-urlencoded".to_string(),
+                );
+            }
+            StressRequest {
+                name: name.clone(),
+                url: substitute(&crate::domain::environment::resolve_url(
+                    &crate::domain::path_params::fill(&config.url, |name| {
+                        config.path_params.get(name).map(String::as_str)
+                    }),
+                    base_url,
+                )),
+                method: config.method.clone(),
+                headers,
+                body: config.body.as_deref().map(substitute),
+            }
+        })
+        .collect())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metric {
+    Avg,
+    Min,
+    Max,
+    P50,
+    P90,
+    P95,
+    P99,
+    /// Percentage of requests that failed (network errors and 4xx/5xx)
+    ErrorRate,
+    Rps,
+}
+
+impl Metric {
+    fn parse(name: &str) -> Option<Metric> {
+        Some(match name.trim().to_lowercase().as_str() {
+            "avg" => Metric::Avg,
+            "min" => Metric::Min,
+            "max" => Metric::Max,
+            "p50" | "med" => Metric::P50,
+            "p90" => Metric::P90,
+            "p95" => Metric::P95,
+            "p99" => Metric::P99,
+            "error_rate" | "errors" | "failed" => Metric::ErrorRate,
+            "rps" => Metric::Rps,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Metric::Avg => "avg",
+            Metric::Min => "min",
+            Metric::Max => "max",
+            Metric::P50 => "p50",
+            Metric::P90 => "p90",
+            Metric::P95 => "p95",
+            Metric::P99 => "p99",
+            Metric::ErrorRate => "error_rate",
+            Metric::Rps => "rps",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            Metric::ErrorRate => "%",
+            Metric::Rps => "/s",
+            _ => "ms",
+        }
+    }
+
+    pub fn value(&self, stats: &StressStats) -> f64 {
+        match self {
+            Metric::Avg => stats.avg_latency_ms,
+            Metric::Min => stats.min_latency_ms as f64,
+            Metric::Max => stats.max_latency_ms as f64,
+            Metric::P50 => stats.p50_latency_ms as f64,
+            Metric::P90 => stats.p90_latency_ms as f64,
+            Metric::P95 => stats.p95_latency_ms as f64,
+            Metric::P99 => stats.p99_latency_ms as f64,
+            Metric::ErrorRate => stats.error_rate(),
+            Metric::Rps => stats.rps,
+        }
+    }
+}
+
+/// A pass/fail condition on the final stats, e.g. `p95 < 300`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Threshold {
+    pub metric: Metric,
+    /// The metric has to stay below the limit (above it when false)
+    pub below: bool,
+    /// Inclusive bound (`<=`, `>=`, or the `p95=300` short form)
+    pub inclusive: bool,
+    pub limit: f64,
+}
+
+impl Threshold {
+    pub fn passes(&self, value: f64) -> bool {
+        match (self.below, self.inclusive) {
+            (true, true) => value <= self.limit,
+            (true, false) => value < self.limit,
+            (false, true) => value >= self.limit,
+            (false, false) => value > self.limit,
+        }
+    }
+}
+
+impl std::fmt::Display for Threshold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match (self.below, self.inclusive) {
+            (true, true) => "<=",
+            (true, false) => "<",
+            (false, true) => ">=",
+            (false, false) => ">",
+        };
+        write!(
+            f,
+            "{} {} {}{}",
+            self.metric.name(),
+            op,
+            self.limit,
+            self.metric.unit()
+        )
+    }
+}
+
+/// `p95<300, error_rate<1%, rps>=100`. `metric=value` is the CI short form for "no
+/// worse than": at most for latencies and errors, at least for rps. Latencies are in
+/// ms, error rates in percent (the `%` is optional).
+pub fn parse_thresholds(spec: &str) -> Result<Vec<Threshold>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(parse_threshold)
+        .collect()
+}
+
+pub fn parse_threshold(text: &str) -> Result<Threshold, String> {
+    let bad = || format!("Bad threshold '{}' (e.g. p95<300, error_rate<1%)", text);
+    let at = text.find(['<', '>', '=']).ok_or_else(bad)?;
+    let (name, rest) = text.split_at(at);
+    let metric = Metric::parse(name).ok_or_else(|| {
+        format!(
+            "Unknown metric '{}' (avg, min, max, p50, p90, p95, p99, error_rate, rps)",
+            name.trim()
+        )
+    })?;
+    let (below, inclusive, value) = if let Some(v) = rest.strip_prefix("<=") {
+        (true, true, v)
+    } else if let Some(v) = rest.strip_prefix(">=") {
+        (false, true, v)
+    } else if let Some(v) = rest.strip_prefix('<') {
+        (true, false, v)
+    } else if let Some(v) = rest.strip_prefix('>') {
+        (false, false, v)
+    } else {
+        (metric != Metric::Rps, true, &rest[1..])
+    };
+    let value = value.trim();
+    let value = value
+        .strip_suffix('%')
+        .or_else(|| value.strip_suffix("ms"))
+        .unwrap_or(value);
+    let limit = value.trim().parse().map_err(|_| bad())?;
+    Ok(Threshold {
+        metric,
+        below,
+        inclusive,
+        limit,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThresholdResult {
+    pub threshold: Threshold,
+    pub actual: f64,
+    pub passed: bool,
+}
+
+pub fn check_thresholds(stats: &StressStats, thresholds: &[Threshold]) -> Vec<ThresholdResult> {
+    thresholds
+        .iter()
+        .map(|threshold| {
+            let actual = threshold.metric.value(stats);
+            ThresholdResult {
+                threshold: *threshold,
+                actual,
+                passed: threshold.passes(actual),
+            }
+        })
+        .collect()
+}
+
+/// Move from the previous stage's VU count to `target` over `duration_secs` (k6-style).
+/// A zero-length stage jumps straight to `target`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stage {
     pub duration_secs: u64,
+    pub target: u32,
+}
+
+/// The stages field of the stress modal, also `stress_stages` on a saved request:
+/// empty or `constant` for `vus` users throughout, `ramp` (up over the first 20%,
+/// steady, down over the last 20%), `step` (four equal steps up to `vus`), or a list
+/// like `30s:20, 1m:20, 10s:0`
+pub fn parse_stages(spec: &str, vus: u32, duration_secs: u64) -> Result<Vec<Stage>, String> {
+    let stage = |duration_secs, target| Stage {
+        duration_secs,
+        target,
+    };
+    let stages = match spec.trim().to_lowercase().as_str() {
+        "" | "constant" => vec![stage(0, vus), stage(duration_secs, vus)],
+        "ramp" => {
+            let edge = duration_secs / 5;
+            vec![
+                stage(edge, vus),
+                stage(duration_secs - 2 * edge, vus),
+                stage(edge, 0),
+            ]
+        }
+        "step" => {
+            let step = duration_secs / 4;
+            (1..=4u32)
+                .flat_map(|k| {
+                    let length = if k == 4 {
+                        duration_secs - 3 * step
+                    } else {
+                        step
+                    };
+                    [stage(0, vus * k / 4), stage(length, vus * k / 4)]
+                })
+                .collect()
+        }
+        list => list
+            .split(',')
+            .map(|part| {
+                let (duration, target) = part
+                    .split_once(':')
+                    .ok_or_else(|| format!("Stage '{}' should look like 30s:20", part.trim()))?;
+                let target = target
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Bad VU count in stage '{}'", part.trim()))?;
+                Ok(stage(parse_duration(duration)?, target))
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+    };
+    if total_secs(&stages) == 0 {
+        return Err("Stages add up to no time at all".to_string());
+    }
+    if max_vus(&stages) == 0 {
+        return Err("No stage has any virtual users".to_string());
+    }
+    Ok(stages)
+}
+
+/// `90`, `90s`, `2m`, `1h` or combinations like `1m30s`
+pub fn parse_duration(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let bad = || format!("Bad duration '{}' (use 30s, 2m, 1m30s)", text);
+    if text.is_empty() {
+        return Err(bad());
+    }
+    if let Ok(secs) = text.parse() {
+        return Ok(secs);
+    }
+    let (mut total, mut number) = (0u64, String::new());
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            _ => return Err(bad()),
+        };
+        total += number.parse::<u64>().map_err(|_| bad())? * unit;
+        number.clear();
+    }
+    if !number.is_empty() {
+        return Err(bad());
+    }
+    Ok(total)
+}
+
+pub fn total_secs(stages: &[Stage]) -> u64 {
+    stages.iter().map(|s| s.duration_secs).sum()
+}
+
+pub fn max_vus(stages: &[Stage]) -> u32 {
+    stages.iter().map(|s| s.target).max().unwrap_or(0)
+}
+
+/// VUs that should be running `elapsed` seconds in, interpolated within the stage
+pub fn vus_at(stages: &[Stage], elapsed: f64) -> u32 {
+    let (mut from, mut start) = (0u32, 0.0);
+    for stage in stages {
+        let end = start + stage.duration_secs as f64;
+        if elapsed < end {
+            let progress = (elapsed - start) / stage.duration_secs as f64;
+            let delta = stage.target as f64 - from as f64;
+            return (from as f64 + delta * progress).round() as u32;
+        }
+        from = stage.target;
+        start = end;
+    }
+    from
+}
+
+/// Index of the stage running `elapsed` seconds in (the last one once it is over)
+pub fn stage_at(stages: &[Stage], elapsed: f64) -> usize {
+    let mut start = 0.0;
+    for (i, stage) in stages.iter().enumerate() {
+        start += stage.duration_secs as f64;
+        if elapsed < start {
+            return i;
+        }
+    }
+    stages.len().saturating_sub(1)
+}
+
+/// One line per stage for the modal preview, e.g. `0 → 20 VUs over 30s`
+pub fn describe(stages: &[Stage]) -> Vec<String> {
+    let mut from = 0;
+    stages
+        .iter()
+        .filter_map(|stage| {
+            let line = if stage.duration_secs == 0 {
+                None
+            } else if stage.target == from {
+                Some(format!("{} VUs for {}s", from, stage.duration_secs))
+            } else {
+                Some(format!(
+                    "{} → {} VUs over {}s",
+                    from, stage.target, stage.duration_secs
+                ))
+            };
+            from = stage.target;
+            line
+        })
+        .collect()
 }
 
 #[derive(Clone, Debug, Default)]
@@ -24,43 +419,170 @@ pub struct StressStats {
     pub max_latency_ms: u64,
     pub p50_latency_ms: u64,
     pub p90_latency_ms: u64,
+    pub p95_latency_ms: u64,
     pub p99_latency_ms: u64,
     pub rps: f64,
     pub status_dist: HashMap<u16, u64>,
+    /// How each of the config's thresholds came out
+    pub thresholds: Vec<ThresholdResult>,
+    /// Broken down by request, in workload order. Only filled for a workload of
+    /// more than one request.
+    pub per_request: Vec<RequestStats>,
+}
+
+impl StressStats {
+    /// Network errors and 4xx/5xx responses, as a percentage of all requests
+    pub fn error_rate(&self) -> f64 {
+        if self.total_requests == 0 {
+            return 0.0;
+        }
+        let bad_status: u64 = self
+            .status_dist
+            .iter()
+            .filter(|(code, _)| **code >= 400)
+            .map(|(_, n)| n)
+            .sum();
+        (self.errors_count + bad_status) as f64 * 100.0 / self.total_requests as f64
+    }
+
+    pub fn thresholds_passed(&self) -> bool {
+        self.thresholds.iter().all(|t| t.passed)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RequestStats {
+    pub name: String,
+    pub method: String,
+    pub total_requests: u64,
+    pub errors_count: u64,
+    pub avg_latency_ms: f64,
+    pub p50_latency_ms: u64,
+    pub p90_latency_ms: u64,
+    pub p99_latency_ms: u64,
+    pub max_latency_ms: u64,
+    pub status_dist: HashMap<u16, u64>,
+}
+
+/// `q` (0..1) of latencies sorted ascending
+pub fn percentile(sorted: &[u64], q: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    sorted[((sorted.len() as f64 * q) as usize).min(sorted.len() - 1)]
+}
+
+/// Per-request stats from `(request index, latency, status)` results
+pub fn per_request_stats(
+    requests: &[StressRequest],
+    results: &[(usize, u64, Option<u16>)],
+) -> Vec<RequestStats> {
+    requests
+        .iter()
+        .enumerate()
+        .map(|(i, request)| {
+            let mut latencies = Vec::new();
+            let mut stats = RequestStats {
+                name: request.name.clone(),
+                method: request.method.clone(),
+                ..Default::default()
+            };
+            for (_, latency, status) in results.iter().filter(|(index, ..)| *index == i) {
+                latencies.push(*latency);
+                match status {
+                    Some(code) => *stats.status_dist.entry(*code).or_insert(0) += 1,
+                    None => stats.errors_count += 1,
+                }
+            }
+            latencies.sort_unstable();
+            stats.total_requests = latencies.len() as u64;
+            if !latencies.is_empty() {
+                stats.avg_latency_ms =
+                    latencies.iter().sum::<u64>() as f64 / latencies.len() as f64;
+            }
+            stats.p50_latency_ms = percentile(&latencies, 0.5);
+            stats.p90_latency_ms = percentile(&latencies, 0.9);
+            stats.p99_latency_ms = percentile(&latencies, 0.99);
+            stats.max_latency_ms = latencies.last().copied().unwrap_or(0);
+            stats
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StressProgress {
+    pub requests_done: u64,
+    pub elapsed_secs: u64,
+    pub total_secs: u64,
+    pub active_vus: u32,
+    /// Position among the timed stages, 1-based, out of `stages`
+    pub stage: usize,
+    pub stages: usize,
+    /// Requests so far that had to wait for a host rate limit
+    pub throttled: u64,
 }
 
 #[derive(Debug)]
 pub enum StressEvent {
-    Progress {
-        requests_done: u64,
-        elapsed_secs: u64,
-    },
+    Progress(StressProgress),
     Finished(StressStats),
     Error(String),
 }
 
 pub async fn run_stress_test(config: StressConfig, tx: mpsc::Sender<StressEvent>) {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .pool_max_idle_per_host(config.concurrency as usize)
-        .build()
-        .unwrap_or_else(|_| reqwest::Client::new());
+    let client = crate::net::pool::apply_overrides(
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .pool_max_idle_per_host(max_vus(&config.stages) as usize),
+        &config.resolve,
+    )
+    .build()
+    .unwrap_or_else(|_| reqwest::Client::new());
 
     let start_time = Instant::now();
-    let duration = Duration::from_secs(config.duration_secs);
+    let duration = Duration::from_secs(total_secs(&config.stages));
     let (res_tx, mut res_rx) = mpsc::channel(1000);
 
     let config = Arc::new(config);
-    // Spawn workers
-    for _ in 0..config.concurrency {
+    // One worker per VU at the peak; worker `vu` only sends while the profile
+    // asks for more than `vu` users
+    for vu in 0..max_vus(&config.stages) {
         let client = client.clone();
         let config = config.clone();
         let res_tx = res_tx.clone();
 
         tokio::spawn(async move {
+            let mut next = vu as usize % config.requests.len().max(1);
             while start_time.elapsed() < duration {
+                if vu >= vus_at(&config.stages, start_time.elapsed().as_secs_f64()) {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    continue;
+                }
+                let Some(request) = config.requests.get(next) else {
+                    break;
+                };
+                let index = next;
+                next = (next + 1) % config.requests.len();
+
+                let url = template::expand(&request.url);
+                let remaining = duration.saturating_sub(start_time.elapsed());
+                if remaining.is_zero() {
+                    break;
+                }
+                let throttled = rate_limit::reserve(&config.rate_limit, None, &url);
+                if let Some(throttle) = &throttled {
+                    // A slot past the end of the test is never used: hand it back
+                    // rather than leave the host booked after the test is over
+                    if throttle.wait >= remaining {
+                        rate_limit::release(&config.rate_limit, None, &url);
+                        tokio::time::sleep(remaining).await;
+                        break;
+                    }
+                    tokio::time::sleep(throttle.wait).await;
+                }
+
                 let req_start = Instant::now();
-                let method = match config.method.as_str() {
+                let method = match request.method.as_str() {
                     "POST" => reqwest::Method::POST,
                     "PUT" => reqwest::Method::PUT,
                     "DELETE" => reqwest::Method::DELETE,
@@ -68,14 +590,14 @@ pub async fn run_stress_test(config: StressConfig, tx: mpsc::Sender<StressEvent>
                     _ => reqwest::Method::GET,
                 };
 
-                let mut req_builder = client.request(method, &config.url);
+                let mut req_builder = client.request(method, &url);
 
-                for (k, v) in &config.headers {
-                    req_builder = req_builder.header(k, v);
+                for (k, v) in &request.headers {
+                    req_builder = req_builder.header(k, template::expand(v));
                 }
 
-                if let Some(body) = &config.body {
-                    req_builder = req_builder.body(body.clone());
+                if let Some(body) = &request.body {
+                    req_builder = req_builder.body(template::expand(body));
                 }
 
                 let result = req_builder.send().await;
@@ -86,7 +608,11 @@ pub async fn run_stress_test(config: StressConfig, tx: mpsc::Sender<StressEvent>
                     Err(e) => Err(e.to_string()),
                 };
 
-                if res_tx.send((latency, status)).await.is_err() {
+                if res_tx
+                    .send((index, latency, status, throttled.is_some()))
+                    .await
+                    .is_err()
+                {
                     break;
                 }
             }
@@ -99,25 +625,41 @@ pub async fn run_stress_test(config: StressConfig, tx: mpsc::Sender<StressEvent>
     let mut latencies = Vec::new();
     let mut status_dist = HashMap::new();
     let mut errors_count = 0;
+    let mut results = Vec::new();
+    let mut throttled_count = 0;
     let mut last_tick = Instant::now();
+    let timed: Vec<usize> = (0..config.stages.len())
+        .filter(|&i| config.stages[i].duration_secs > 0)
+        .collect();
 
-    while let Some((latency, status)) = res_rx.recv().await {
+    while let Some((index, latency, status, throttled)) = res_rx.recv().await {
         latencies.push(latency);
-        match status {
+        throttled_count += throttled as u64;
+        let code = match status {
             Ok(code) => {
                 *status_dist.entry(code).or_insert(0) += 1;
+                Some(code)
             }
             Err(_) => {
                 errors_count += 1;
+                None
             }
-        }
+        };
+        results.push((index, latency, code));
 
         if last_tick.elapsed() >= Duration::from_millis(500) {
+            let elapsed = start_time.elapsed().as_secs_f64();
+            let current = stage_at(&config.stages, elapsed);
             let _ = tx
-                .send(StressEvent::Progress {
+                .send(StressEvent::Progress(StressProgress {
                     requests_done: latencies.len() as u64,
-                    elapsed_secs: start_time.elapsed().as_secs(),
-                })
+                    elapsed_secs: elapsed as u64,
+                    total_secs: duration.as_secs(),
+                    active_vus: vus_at(&config.stages, elapsed),
+                    stage: timed.iter().take_while(|&&i| i < current).count() + 1,
+                    stages: timed.len(),
+                    throttled: throttled_count,
+                }))
                 .await;
             last_tick = Instant::now();
         }
@@ -131,14 +673,15 @@ pub async fn run_stress_test(config: StressConfig, tx: mpsc::Sender<StressEvent>
         let avg = sum as f64 / total as f64;
         let min = *latencies.first().unwrap();
         let max = *latencies.last().unwrap();
-        let p50 = latencies[(total as f64 * 0.5) as usize];
-        let p90 = latencies[(total as f64 * 0.9) as usize];
-        let p99 = latencies[(total as f64 * 0.99) as usize];
+        let p50 = percentile(&latencies, 0.5);
+        let p90 = percentile(&latencies, 0.9);
+        let p95 = percentile(&latencies, 0.95);
+        let p99 = percentile(&latencies, 0.99);
         let duration_actual = start_time.elapsed().as_secs_f64();
         let rps = total as f64 / duration_actual;
         let success = total - errors_count;
 
-        let stats = StressStats {
+        let mut stats = StressStats {
             total_requests: total,
             successful_requests: success,
             failed_requests: total - success, // Count non-200s? No, failed means network error here.
@@ -148,11 +691,19 @@ pub async fn run_stress_test(config: StressConfig, tx: mpsc::Sender<StressEvent>
             max_latency_ms: max,
             p50_latency_ms: p50,
             p90_latency_ms: p90,
+            p95_latency_ms: p95,
             p99_latency_ms: p99,
             rps,
             status_dist,
+            thresholds: Vec::new(),
+            per_request: if config.requests.len() > 1 {
+                per_request_stats(&config.requests, &results)
+            } else {
+                Vec::new()
+            },
         };
 
+        stats.thresholds = check_thresholds(&stats, &config.thresholds);
         let _ = tx.send(StressEvent::Finished(stats)).await;
     } else {
         let _ = tx
@@ -0,0 +1,160 @@
+// Dynamic template functions like {{$uuid}} or {{$randomInt 1 100}}, expanded at send time
+use rand::Rng;
+use rand::seq::IndexedRandom;
+
+const LOREM_WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+    "enim",
+    "ad",
+    "minim",
+    "veniam",
+    "quis",
+    "nostrud",
+    "exercitation",
+    "ullamco",
+    "laboris",
+    "nisi",
+    "aliquip",
+];
+
+const FIRST_NAMES: &[&str] = &[
+    "alice", "bob", "carol", "dave", "erin", "frank", "grace", "heidi", "ivan", "judy",
+];
+
+const DOMAINS: &[&str] = &["example.com", "example.org", "test.dev", "mail.test"];
+
+/// Expands every `{{$function args}}` placeholder in `text`.
+/// Unknown functions are left untouched so they stay visible in the request.
+pub fn expand(text: &str) -> String {
+    if !text.contains("{{$") {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{$") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 3..];
+
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+
+        let expr = &after[..end];
+        match evaluate(expr) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..start + 3 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Evaluates a single template expression (without the surrounding `{{$` and `}}`)
+fn evaluate(expr: &str) -> Option<String> {
+    let mut parts = expr.split_whitespace();
+    let name = parts.next()?;
+    let args: Vec<&str> = parts.collect();
+    let mut rng = rand::rng();
+
+    let value = match name {
+        "uuid" | "guid" => uuid_v4(),
+        "timestamp" => unix_time().as_secs().to_string(),
+        "timestampMs" => unix_time().as_millis().to_string(),
+        "randomInt" => {
+            let min = args
+                .first()
+                .and_then(|a| a.parse::<i64>().ok())
+                .unwrap_or(0);
+            let max = args
+                .get(1)
+                .and_then(|a| a.parse::<i64>().ok())
+                .unwrap_or(1000);
+            if min >= max {
+                min.to_string()
+            } else {
+                rng.random_range(min..=max).to_string()
+            }
+        }
+        "randomBool" => rng.random_bool(0.5).to_string(),
+        "randomName" => {
+            let name = FIRST_NAMES.choose(&mut rng).copied().unwrap_or("user");
+            let mut chars = name.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        }
+        "randomEmail" => {
+            let name = FIRST_NAMES.choose(&mut rng).copied().unwrap_or("user");
+            let domain = DOMAINS.choose(&mut rng).copied().unwrap_or("example.com");
+            format!("{}{}@{}", name, rng.random_range(1..10000), domain)
+        }
+        "lorem" => {
+            let count = args
+                .first()
+                .and_then(|a| a.parse::<usize>().ok())
+                .unwrap_or(10);
+            (0..count)
+                .map(|_| *LOREM_WORDS.choose(&mut rng).unwrap_or(&"lorem"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+fn unix_time() -> std::time::Duration {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Random (version 4) UUID
+pub fn uuid_v4() -> String {
+    let mut rng = rand::rng();
+    let bytes: [u8; 16] = rng.random();
+    format!(
+        "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+        bytes[0],
+        bytes[1],
+        bytes[2],
+        bytes[3],
+        bytes[4],
+        bytes[5],
+        (bytes[6] & 0x0f) | 0x40,
+        bytes[7],
+        (bytes[8] & 0x3f) | 0x80,
+        bytes[9],
+        bytes[10],
+        bytes[11],
+        bytes[12],
+        bytes[13],
+        bytes[14],
+        bytes[15]
+    )
+}
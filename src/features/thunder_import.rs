@@ -0,0 +1,380 @@
+// Thunder Client (VS Code) exports: a collection file (`thunder-collection_*.json`)
+// with its folders as `Folder/Request` names, or an environment file
+// (`thunder-environment_*.json`).
+use crate::domain::collection::{Collection, RequestConfig};
+use crate::domain::environment::Environment;
+use crate::features::import::{Imported, basic_auth};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Export {
+    collection_name: Option<String>,
+    folders: Vec<Folder>,
+    requests: Vec<Request>,
+    settings: Settings,
+    environment_name: Option<String>,
+    data: Vec<Variable>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Folder {
+    #[serde(rename = "_id")]
+    id: String,
+    name: String,
+    /// The parent folder, empty at the top
+    container_id: String,
+    sort_num: f64,
+    settings: Settings,
+}
+
+/// Collection and folder settings their requests inherit
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Settings {
+    headers: Vec<Pair>,
+    auth: Option<Auth>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Request {
+    name: String,
+    url: String,
+    method: String,
+    container_id: String,
+    sort_num: f64,
+    headers: Vec<Pair>,
+    params: Vec<Param>,
+    body: Option<Body>,
+    auth: Option<Auth>,
+    tests: Vec<Test>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Pair {
+    name: String,
+    value: String,
+    is_disabled: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Param {
+    name: String,
+    value: String,
+    is_path: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Body {
+    #[serde(rename = "type")]
+    kind: String,
+    raw: Option<String>,
+    form: Vec<Pair>,
+    files: Vec<Pair>,
+    graphql: Option<GraphQl>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GraphQl {
+    query: String,
+    variables: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Auth {
+    #[serde(rename = "type")]
+    kind: String,
+    bearer: Option<String>,
+    bearer_prefix: Option<String>,
+    basic: Option<Basic>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+struct Basic {
+    username: String,
+    password: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Test {
+    #[serde(rename = "type")]
+    kind: String,
+    action: String,
+    value: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Variable {
+    name: String,
+    value: serde_json::Value,
+}
+
+/// Thunder's `{{#guid}}`-style system variables PostDad has a dynamic value for
+const SYSTEM_VARIABLES: &[(&str, &str)] = &[
+    ("{{#guid}}", "{{$uuid}}"),
+    ("{{#timestamp}}", "{{$timestamp}}"),
+    ("{{#email}}", "{{$randomEmail}}"),
+    ("{{#name}}", "{{$randomName}}"),
+    ("{{#bool}}", "{{$randomBool}}"),
+];
+
+fn convert(text: &str) -> String {
+    SYSTEM_VARIABLES
+        .iter()
+        .fold(text.to_string(), |text, (from, to)| text.replace(from, to))
+}
+
+/// Inherited headers and auth, nearest folder last
+#[derive(Clone, Default)]
+struct Inherited {
+    headers: Vec<Pair>,
+    auth: Option<Auth>,
+}
+
+impl Inherited {
+    fn with(&self, settings: &Settings) -> Inherited {
+        let mut next = self.clone();
+        next.headers.extend(settings.headers.iter().cloned());
+        if let Some(auth) = settings.auth.as_ref().filter(|a| a.kind != "inherit") {
+            next.auth = Some(auth.clone());
+        }
+        next
+    }
+}
+
+fn request(
+    name: &str,
+    req: &Request,
+    inherited: &Inherited,
+    warnings: &mut Vec<String>,
+) -> RequestConfig {
+    let mut headers = HashMap::new();
+    let mut disabled_headers = HashMap::new();
+    for header in inherited.headers.iter().chain(&req.headers) {
+        if header.name.is_empty() {
+            continue;
+        }
+        let target = if header.is_disabled {
+            &mut disabled_headers
+        } else {
+            &mut headers
+        };
+        target.insert(header.name.clone(), convert(&header.value));
+    }
+
+    let auth = match &req.auth {
+        Some(auth) if auth.kind != "inherit" => Some(auth),
+        _ => inherited.auth.as_ref(),
+    };
+    match auth.map(|a| (a.kind.as_str(), a)) {
+        Some(("bearer", auth)) => {
+            let prefix = auth.bearer_prefix.as_deref().unwrap_or("Bearer");
+            headers.insert(
+                "Authorization".to_string(),
+                format!(
+                    "{} {}",
+                    prefix,
+                    convert(auth.bearer.as_deref().unwrap_or_default())
+                ),
+            );
+        }
+        Some(("basic", auth)) => {
+            let basic = auth.basic.clone().unwrap_or_default();
+            match basic_auth(&basic.username, &basic.password) {
+                Some(value) => {
+                    headers.insert("Authorization".to_string(), value);
+                }
+                None => warnings.push(format!(
+                    "{}: basic auth uses variables, set it in the Auth tab",
+                    name
+                )),
+            }
+        }
+        Some(("none" | "", _)) | None => {}
+        Some((other, _)) => warnings.push(format!(
+            "{}: {} auth isn't imported, set it in the Auth tab",
+            name, other
+        )),
+    }
+
+    let mut config = RequestConfig {
+        url: convert(&req.url),
+        method: if req.method.is_empty() {
+            "GET".to_string()
+        } else {
+            req.method.to_uppercase()
+        },
+        headers: (!headers.is_empty()).then_some(headers),
+        disabled_headers,
+        path_params: req
+            .params
+            .iter()
+            .filter(|p| p.is_path && !p.name.is_empty())
+            .map(|p| (p.name.clone(), convert(&p.value)))
+            .collect(),
+        ..Default::default()
+    };
+
+    if let Some(body) = &req.body {
+        let form = || body.form.iter().filter(|p| !p.is_disabled);
+        match body.kind.as_str() {
+            "formencoded" => {
+                let encoded = form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(form().map(|p| (p.name.clone(), convert(&p.value))))
+                    .finish();
+                config.body = Some(encoded).filter(|b| !b.is_empty());
+                config.body_type = Some("UrlEncoded".to_string());
+            }
+            "formdata" => {
+                let mut fields: Vec<(String, String, bool)> = form()
+                    .map(|p| (p.name.clone(), convert(&p.value), false))
+                    .collect();
+                fields.extend(
+                    body.files
+                        .iter()
+                        .filter(|p| !p.is_disabled)
+                        .map(|p| (p.name.clone(), p.value.clone(), true)),
+                );
+                config.form_data = Some(fields);
+                config.body_type = Some("FormData".to_string());
+            }
+            "graphql" => {
+                let graphql = body.graphql.as_ref();
+                config.graphql_query = graphql.map(|g| g.query.clone());
+                config.graphql_variables = graphql
+                    .and_then(|g| g.variables.clone())
+                    .filter(|v| !v.trim().is_empty());
+                config.body_type = Some("GraphQL".to_string());
+            }
+            "binary" => warnings.push(format!("{}: binary bodies aren't imported", name)),
+            _ => config.body = body.raw.as_deref().filter(|r| !r.is_empty()).map(convert),
+        }
+    }
+
+    let mut unconverted = 0;
+    for test in &req.tests {
+        match (test.kind.as_str(), test.action.as_str()) {
+            ("res-code", "equal") if config.expected_status.is_none() => {
+                match test.value.trim().parse() {
+                    Ok(status) => config.expected_status = Some(status),
+                    Err(_) => unconverted += 1,
+                }
+            }
+            _ => unconverted += 1,
+        }
+    }
+    if unconverted > 0 {
+        warnings.push(format!(
+            "{}: {} test(s) aren't imported, only a status code check is",
+            name, unconverted
+        ));
+    }
+    config
+}
+
+fn walk(
+    export: &Export,
+    container: &str,
+    prefix: &str,
+    inherited: &Inherited,
+    requests: &mut HashMap<String, RequestConfig>,
+    warnings: &mut Vec<String>,
+) {
+    let join = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        }
+    };
+    let mut folders: Vec<&Folder> = export
+        .folders
+        .iter()
+        .filter(|f| f.container_id == container)
+        .collect();
+    folders.sort_by(|a, b| a.sort_num.total_cmp(&b.sort_num));
+    for folder in folders {
+        let inherited = inherited.with(&folder.settings);
+        walk(
+            export,
+            &folder.id,
+            &join(&folder.name),
+            &inherited,
+            requests,
+            warnings,
+        );
+    }
+    let mut items: Vec<&Request> = export
+        .requests
+        .iter()
+        .filter(|r| r.container_id == container)
+        .collect();
+    items.sort_by(|a, b| a.sort_num.total_cmp(&b.sort_num));
+    for item in items {
+        let name = join(&item.name);
+        let config = request(&name, item, inherited, warnings);
+        requests.insert(name, config);
+    }
+}
+
+pub fn parse(content: &str) -> Result<Imported, String> {
+    let export: Export = serde_json::from_str(content)
+        .map_err(|e| format!("Invalid Thunder Client export: {}", e))?;
+    let mut imported = Imported::default();
+
+    if let Some(name) = &export.environment_name {
+        imported.environments.push(Environment {
+            name: name.clone(),
+            variables: export
+                .data
+                .iter()
+                .filter(|v| !v.name.is_empty())
+                .map(|v| {
+                    let value = match &v.value {
+                        serde_json::Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (v.name.clone(), value)
+                })
+                .collect(),
+            ..Default::default()
+        });
+    }
+
+    if let Some(name) = &export.collection_name {
+        let mut requests = HashMap::new();
+        let inherited = Inherited::default().with(&export.settings);
+        walk(
+            &export,
+            "",
+            "",
+            &inherited,
+            &mut requests,
+            &mut imported.warnings,
+        );
+        imported.collections.push(Collection {
+            name: name.clone(),
+            requests,
+            ws_scenarios: HashMap::new(),
+            snapshots: Default::default(),
+            description: None,
+            variables: HashMap::new(),
+        });
+    }
+
+    if imported.collections.is_empty() && imported.environments.is_empty() {
+        return Err("Not a Thunder Client collection or environment export".to_string());
+    }
+    Ok(imported)
+}
@@ -0,0 +1,142 @@
+use base64::Engine;
+
+/// How often the main loop looks at token expirations
+pub const CHECK_INTERVAL_SECS: u64 = 15;
+/// Refresh (or warn) this long before a token actually expires
+pub const REFRESH_BEFORE_SECS: u64 = 120;
+
+/// What we need to refresh an OAuth2 access token without the browser dance
+#[derive(Clone, Debug, PartialEq)]
+pub struct OAuthGrant {
+    pub token_url: String,
+    pub client_id: String,
+    pub refresh_token: Option<String>,
+    /// Unix seconds, from the token endpoint's `expires_in`
+    pub expires_at: Option<u64>,
+}
+
+/// The interesting bits of a token endpoint response
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_in: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenAction {
+    Refresh,
+    Warn,
+    Expired,
+}
+
+pub fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Reads the `exp` claim from a JWT without verifying it
+pub fn jwt_expiry(token: &str) -> Option<u64> {
+    let token = token.trim().trim_start_matches("Bearer ").trim();
+    let mut parts = token.split('.');
+    let (_header, payload, _sig) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    let exp = claims.get("exp")?;
+    exp.as_u64().or_else(|| exp.as_f64().map(|f| f as u64))
+}
+
+/// Expiry from the OAuth grant if we have one, otherwise from the JWT itself
+pub fn token_expiry(token: &str, grant: Option<&OAuthGrant>) -> Option<u64> {
+    grant
+        .and_then(|g| g.expires_at)
+        .or_else(|| jwt_expiry(token))
+}
+
+/// Decides what to do about a token expiring at `expires_at`
+pub fn action_for(expires_at: u64, now: u64, can_refresh: bool) -> Option<TokenAction> {
+    if now + REFRESH_BEFORE_SECS < expires_at {
+        None
+    } else if can_refresh {
+        Some(TokenAction::Refresh)
+    } else if now >= expires_at {
+        Some(TokenAction::Expired)
+    } else {
+        Some(TokenAction::Warn)
+    }
+}
+
+/// Parses a token endpoint response, either JSON or `application/x-www-form-urlencoded`
+/// (GitHub answers with the latter unless asked nicely)
+pub fn parse_token_response(text: &str) -> Result<TokenResponse, String> {
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(text) {
+        let access_token = json
+            .get("access_token")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("No access_token in response: {}", text))?;
+        return Ok(TokenResponse {
+            access_token: access_token.to_string(),
+            refresh_token: json
+                .get("refresh_token")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            expires_in: json.get("expires_in").and_then(|v| {
+                v.as_u64()
+                    .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+            }),
+        });
+    }
+
+    let pairs = crate::app::parse_urlencoded(text);
+    let get = |key: &str| pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+    match get("access_token") {
+        Some(access_token) => Ok(TokenResponse {
+            access_token,
+            refresh_token: get("refresh_token"),
+            expires_in: get("expires_in").and_then(|s| s.parse().ok()),
+        }),
+        None => Err(format!("OAuth exchange failed: {}", text)),
+    }
+}
+
+/// Runs the `refresh_token` grant against the token endpoint
+pub async fn refresh(grant: &OAuthGrant) -> Result<TokenResponse, String> {
+    let refresh_token = grant
+        .refresh_token
+        .as_ref()
+        .ok_or("No refresh token available")?;
+
+    let body = crate::app::encode_urlencoded(&[
+        ("grant_type".to_string(), "refresh_token".to_string()),
+        ("refresh_token".to_string(), refresh_token.clone()),
+        ("client_id".to_string(), grant.client_id.clone()),
+    ]);
+
+    let resp = reqwest::Client::new()
+        .post(&grant.token_url)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .header("Accept", "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = resp.status();
+    let text = resp.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!(
+            "Token refresh returned {}: {}",
+            status.as_u16(),
+            text
+        ));
+    }
+    parse_token_response(&text)
+}
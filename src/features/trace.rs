@@ -0,0 +1,111 @@
+// W3C trace context on outgoing requests, so a request sent from here can be found
+// in Jaeger, Tempo or whatever the services report their spans to.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Stored under `trace` in `config.json`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraceSettings {
+    /// Add a `traceparent` header to every request
+    pub enabled: bool,
+    /// Zipkin's `X-B3-*` headers as well, for services that only read those
+    pub b3: bool,
+    /// Where a trace can be looked at, `{trace_id}` and `{span_id}` filled in,
+    /// e.g. `http://localhost:16686/trace/{trace_id}`
+    pub link: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceContext {
+    /// 32 lowercase hex digits
+    pub trace_id: String,
+    /// 16 lowercase hex digits, the request's span
+    pub span_id: String,
+}
+
+impl TraceContext {
+    pub fn generate() -> Self {
+        // All-zero ids are invalid
+        TraceContext {
+            trace_id: format!("{:032x}", rand::random::<u128>().max(1)),
+            span_id: format!("{:016x}", rand::random::<u64>().max(1)),
+        }
+    }
+
+    /// `00-<trace id>-<span id>-01`, sampled
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-01", self.trace_id, self.span_id)
+    }
+
+    /// The ids of a `traceparent` header, None unless it's well formed
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let parts: Vec<&str> = traceparent.trim().split('-').collect();
+        let hex = |s: &str, len: usize| {
+            s.len() == len
+                && s.chars().all(|c| c.is_ascii_hexdigit())
+                && s.chars().any(|c| c != '0')
+        };
+        match parts.as_slice() {
+            [version, trace_id, span_id, flags, ..]
+                if version.len() == 2
+                    && hex(trace_id, 32)
+                    && hex(span_id, 16)
+                    && flags.len() == 2 =>
+            {
+                Some(TraceContext {
+                    trace_id: trace_id.to_lowercase(),
+                    span_id: span_id.to_lowercase(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The settings' link template with the ids in, None without a template
+    pub fn link(&self, template: &str) -> Option<String> {
+        if template.trim().is_empty() {
+            return None;
+        }
+        Some(
+            template
+                .trim()
+                .replace("{trace_id}", &self.trace_id)
+                .replace("{span_id}", &self.span_id),
+        )
+    }
+}
+
+fn header<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a String> {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v)
+}
+
+/// Adds the trace headers to a request about to go out and returns its context.
+/// A `traceparent` the request already has is kept and its ids are the ones
+/// returned, same for B3 headers. None when tracing is off.
+pub fn inject(
+    settings: &TraceSettings,
+    headers: &mut HashMap<String, String>,
+) -> Option<TraceContext> {
+    if !settings.enabled {
+        return None;
+    }
+    let context = match header(headers, "traceparent").and_then(|v| TraceContext::parse(v)) {
+        Some(context) => context,
+        None => {
+            let context = TraceContext::generate();
+            headers.retain(|k, _| !k.eq_ignore_ascii_case("traceparent"));
+            headers.insert("traceparent".to_string(), context.traceparent());
+            context
+        }
+    };
+    if settings.b3 && header(headers, "X-B3-TraceId").is_none() {
+        headers.insert("X-B3-TraceId".to_string(), context.trace_id.clone());
+        headers.insert("X-B3-SpanId".to_string(), context.span_id.clone());
+        headers.insert("X-B3-Sampled".to_string(), "1".to_string());
+    }
+    Some(context)
+}
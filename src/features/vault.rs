@@ -0,0 +1,307 @@
+// At-rest encryption for history, cookies and environment secrets: AES-256-GCM with a
+// key derived from a passphrase (PBKDF2-SHA256) or a random key kept in the OS keyring.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as B64;
+use openssl::symm::{Cipher, decrypt_aead, encrypt_aead};
+use serde::{Deserialize, Serialize};
+
+/// First line of an encrypted history.json / cookies.json
+const FILE_MAGIC: &str = "postdad-encrypted-v1:";
+/// Environment values written as `token = "enc:v1:..."` are decrypted on load
+pub const SECRET_PREFIX: &str = "enc:v1:";
+/// Unlocks without the prompt, for scripts and CI
+pub const PASSPHRASE_ENV: &str = "POSTDAD_PASSPHRASE";
+
+pub const DEFAULT_ITERATIONS: u32 = 600_000;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+/// Encrypted with the key so a wrong passphrase is caught before anything is read
+const VERIFIER_TEXT: &str = "postdad";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    #[default]
+    Passphrase,
+    Keyring,
+}
+
+/// `encryption` in config.json
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EncryptionSettings {
+    pub enabled: bool,
+    pub key_source: KeySource,
+    /// Base64 PBKDF2 salt, passphrase keys only
+    pub salt: String,
+    pub iterations: u32,
+    pub verifier: String,
+}
+
+/// The unlocked key. Never written anywhere except the keyring.
+#[derive(Clone)]
+pub struct Vault {
+    key: [u8; 32],
+}
+
+impl std::fmt::Debug for Vault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Vault(..)")
+    }
+}
+
+impl Vault {
+    pub fn from_passphrase(
+        passphrase: &str,
+        salt: &[u8],
+        iterations: u32,
+    ) -> Result<Vault, String> {
+        let mut key = [0u8; 32];
+        openssl::pkcs5::pbkdf2_hmac(
+            passphrase.as_bytes(),
+            salt,
+            iterations as usize,
+            openssl::hash::MessageDigest::sha256(),
+            &mut key,
+        )
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
+        Ok(Vault { key })
+    }
+
+    pub fn generate() -> Result<Vault, String> {
+        let mut key = [0u8; 32];
+        openssl::rand::rand_bytes(&mut key).map_err(|e| e.to_string())?;
+        Ok(Vault { key })
+    }
+
+    fn from_encoded_key(encoded: &str) -> Result<Vault, String> {
+        let bytes = B64
+            .decode(encoded.trim())
+            .map_err(|_| "Keyring entry is not a PostDad key".to_string())?;
+        let key = bytes
+            .try_into()
+            .map_err(|_| "Keyring entry is not a PostDad key".to_string())?;
+        Ok(Vault { key })
+    }
+
+    fn encoded_key(&self) -> String {
+        B64.encode(self.key)
+    }
+
+    fn seal(&self, plaintext: &str, aad: &[u8]) -> Result<String, String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        openssl::rand::rand_bytes(&mut nonce).map_err(|e| e.to_string())?;
+        let mut tag = [0u8; TAG_LEN];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(&nonce),
+            aad,
+            plaintext.as_bytes(),
+            &mut tag,
+        )
+        .map_err(|e| format!("Encryption failed: {}", e))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        sealed.extend(tag);
+        Ok(B64.encode(sealed))
+    }
+
+    fn open(&self, encoded: &str, aad: &[u8]) -> Result<String, String> {
+        let sealed = B64
+            .decode(encoded.trim())
+            .map_err(|_| "Encrypted data is damaged".to_string())?;
+        if sealed.len() < NONCE_LEN + TAG_LEN {
+            return Err("Encrypted data is damaged".to_string());
+        }
+        let (nonce, rest) = sealed.split_at(NONCE_LEN);
+        let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+        let plaintext = decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(nonce),
+            aad,
+            ciphertext,
+            tag,
+        )
+        .map_err(|_| "Wrong key, or the data was changed".to_string())?;
+        String::from_utf8(plaintext).map_err(|_| "Decrypted data is not text".to_string())
+    }
+
+    /// Whole-file form, see `is_encrypted`
+    pub fn encrypt_file(&self, plaintext: &str) -> Result<String, String> {
+        Ok(format!(
+            "{}{}\n",
+            FILE_MAGIC,
+            self.seal(plaintext, FILE_MAGIC.as_bytes())?
+        ))
+    }
+
+    /// Plaintext files pass through unchanged, so turning encryption on needs no migration
+    pub fn decrypt_file(&self, content: &str) -> Result<String, String> {
+        match content.trim_start().strip_prefix(FILE_MAGIC) {
+            Some(encoded) => self.open(encoded, FILE_MAGIC.as_bytes()),
+            None => Ok(content.to_string()),
+        }
+    }
+
+    /// `enc:v1:...`, to paste into environments.hcl
+    pub fn encrypt_secret(&self, value: &str) -> Result<String, String> {
+        Ok(format!(
+            "{}{}",
+            SECRET_PREFIX,
+            self.seal(value, SECRET_PREFIX.as_bytes())?
+        ))
+    }
+
+    /// Values without the prefix are returned as they are
+    pub fn decrypt_secret(&self, value: &str) -> Result<String, String> {
+        match value.strip_prefix(SECRET_PREFIX) {
+            Some(encoded) => self.open(encoded, SECRET_PREFIX.as_bytes()),
+            None => Ok(value.to_string()),
+        }
+    }
+}
+
+pub fn is_encrypted(content: &str) -> bool {
+    content.trim_start().starts_with(FILE_MAGIC)
+}
+
+/// A new passphrase key and the settings that unlock it again
+pub fn setup_passphrase(
+    passphrase: &str,
+    iterations: u32,
+) -> Result<(EncryptionSettings, Vault), String> {
+    if passphrase.chars().count() < 8 {
+        return Err("Use a passphrase of at least 8 characters".to_string());
+    }
+    let mut salt = [0u8; 16];
+    openssl::rand::rand_bytes(&mut salt).map_err(|e| e.to_string())?;
+    let vault = Vault::from_passphrase(passphrase, &salt, iterations)?;
+    let settings = EncryptionSettings {
+        enabled: true,
+        key_source: KeySource::Passphrase,
+        salt: B64.encode(salt),
+        iterations,
+        verifier: vault.seal(VERIFIER_TEXT, b"verifier")?,
+    };
+    Ok((settings, vault))
+}
+
+/// A new random key, stored in the keyring under `account`
+pub fn setup_keyring(account: &str) -> Result<(EncryptionSettings, Vault), String> {
+    let vault = Vault::generate()?;
+    keyring::set(account, &vault.encoded_key())?;
+    let settings = EncryptionSettings {
+        enabled: true,
+        key_source: KeySource::Keyring,
+        verifier: vault.seal(VERIFIER_TEXT, b"verifier")?,
+        ..Default::default()
+    };
+    Ok((settings, vault))
+}
+
+/// The key for `settings`, checked against the verifier
+pub fn unlock_with_passphrase(
+    settings: &EncryptionSettings,
+    passphrase: &str,
+) -> Result<Vault, String> {
+    let salt = B64
+        .decode(&settings.salt)
+        .map_err(|_| "Encryption salt in config.json is damaged".to_string())?;
+    let vault = Vault::from_passphrase(passphrase, &salt, settings.iterations)?;
+    verify(settings, vault)
+}
+
+pub fn unlock_with_keyring(settings: &EncryptionSettings, account: &str) -> Result<Vault, String> {
+    let vault = Vault::from_encoded_key(&keyring::get(account)?)?;
+    verify(settings, vault)
+}
+
+fn verify(settings: &EncryptionSettings, vault: Vault) -> Result<Vault, String> {
+    match vault.open(&settings.verifier, b"verifier") {
+        Ok(text) if text == VERIFIER_TEXT => Ok(vault),
+        _ => Err("Wrong passphrase".to_string()),
+    }
+}
+
+/// The OS keyring through its command line tool: `secret-tool` (libsecret) on Linux,
+/// `security` on macOS
+pub mod keyring {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    const SERVICE: &str = "postdad";
+
+    pub fn get(account: &str) -> Result<String, String> {
+        let output = if cfg!(target_os = "macos") {
+            Command::new("security")
+                .args(["find-generic-password", "-s", SERVICE, "-a", account, "-w"])
+                .output()
+        } else {
+            Command::new("secret-tool")
+                .args(["lookup", "service", SERVICE, "account", account])
+                .output()
+        }
+        .map_err(not_available)?;
+        let secret = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || secret.is_empty() {
+            return Err("No PostDad key in the keyring for this workspace".to_string());
+        }
+        Ok(secret)
+    }
+
+    /// The secret goes in on stdin, never on the command line where `ps` shows it
+    pub fn set(account: &str, secret: &str) -> Result<(), String> {
+        let (mut command, input) = if cfg!(target_os = "macos") {
+            let mut command = Command::new("security");
+            // `-w` last and without a value asks for the password, then again to
+            // confirm it
+            command.args([
+                "add-generic-password",
+                "-U",
+                "-s",
+                SERVICE,
+                "-a",
+                account,
+                "-w",
+            ]);
+            (command, format!("{0}\n{0}\n", secret))
+        } else {
+            let mut command = Command::new("secret-tool");
+            command.args([
+                "store",
+                "--label=PostDad",
+                "service",
+                SERVICE,
+                "account",
+                account,
+            ]);
+            (command, secret.to_string())
+        };
+        let status = command
+            .stdin(Stdio::piped())
+            .spawn()
+            .and_then(|mut child| {
+                if let Some(stdin) = child.stdin.as_mut() {
+                    stdin.write_all(input.as_bytes())?;
+                }
+                child.wait()
+            })
+            .map_err(not_available)?;
+        if !status.success() {
+            return Err("The keyring refused to store the key".to_string());
+        }
+        Ok(())
+    }
+
+    fn not_available(e: std::io::Error) -> String {
+        let tool = if cfg!(target_os = "macos") {
+            "security"
+        } else {
+            "secret-tool"
+        };
+        format!("No keyring ({} not usable: {}), use a passphrase", tool, e)
+    }
+}
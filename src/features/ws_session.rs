@@ -0,0 +1,81 @@
+// WebSocket session logs: the messages of a session, both ways, with when they
+// happened. `:wsexport` writes one, `:wsreplay` sends its sent messages again with
+// the same gaps between them, over a new connection.
+use crate::net::websocket::WsMessage;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LoggedMessage {
+    pub direction: Direction,
+    /// Milliseconds after the session's first message; what replay goes by
+    pub offset_ms: u64,
+    /// Milliseconds since the epoch
+    #[serde(default)]
+    pub timestamp_ms: u64,
+    pub content: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionLog {
+    pub url: String,
+    pub messages: Vec<LoggedMessage>,
+}
+
+impl SessionLog {
+    pub fn from_messages(url: &str, messages: &[WsMessage]) -> SessionLog {
+        let start = messages.iter().map(|m| m.unix_ms).min().unwrap_or_default();
+        SessionLog {
+            url: url.to_string(),
+            messages: messages
+                .iter()
+                .map(|m| LoggedMessage {
+                    direction: if m.is_sent {
+                        Direction::Sent
+                    } else {
+                        Direction::Received
+                    },
+                    offset_ms: m.unix_ms.saturating_sub(start),
+                    timestamp_ms: m.unix_ms,
+                    content: m.content.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<SessionLog, String> {
+        serde_json::from_str(content).map_err(|e| format!("not a session log: {}", e))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// The sent messages, as offsets from the first of them: replay sends that one
+    /// as soon as it's connected, then keeps the original gaps
+    pub fn replay_schedule(&self) -> Vec<(u64, String)> {
+        let mut sent: Vec<(u64, String)> = self
+            .messages
+            .iter()
+            .filter(|m| m.direction == Direction::Sent)
+            .map(|m| (m.offset_ms, m.content.clone()))
+            .collect();
+        sent.sort_by_key(|(offset, _)| *offset);
+        let first = sent.first().map(|(offset, _)| *offset).unwrap_or_default();
+        for (offset, _) in &mut sent {
+            *offset -= first;
+        }
+        sent
+    }
+}
+
+/// `ws_session_<seconds>.json`, in the current directory
+pub fn default_file_name(now_secs: u64) -> String {
+    format!("ws_session_{}.json", now_secs)
+}
@@ -1,12 +1,140 @@
-use crate::app::{App, InputMode};
+use crate::app::{App, InputMode, MockEditorField};
+use crate::domain::line_edit::{self, Edit};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
+    let (tab_before, mode_before) = (app.active_tab, app.active_tab().input_mode);
+    dispatch_key(key_event, app);
+    // A newly focused input starts with its cursor at the end
+    if (app.active_tab, app.active_tab().input_mode) != (tab_before, mode_before) {
+        app.input_cursor = line_edit::CURSOR_END;
+    }
+}
+
+/// Applies keymap.toml to a key press before anything looks at it. None means the key
+/// was moved to another action and does nothing now. Text fields are never remapped.
+pub fn remap_key(key_event: KeyEvent, app: &mut App) -> Option<KeyEvent> {
+    if is_typing(app) {
+        Some(key_event)
+    } else {
+        app.keymap.translate(key_event)
+    }
+}
+
+fn is_typing(app: &mut App) -> bool {
+    with_input(app, |_, _| ()).is_some()
+        || app.mock_editor.is_some()
+        || (app.show_help && app.help_search_active)
+        || app.guard_prompt.is_some()
+        || app.send_warnings.is_some()
+        || app.sync_prompt.is_some()
+        || app.bundle_prompt.is_some()
+        || app.passphrase_prompt.is_some()
+        || app.recovery_prompt.is_some()
+        || app.hex_view.as_ref().is_some_and(|v| v.searching)
+        || app.proto_view.as_ref().is_some_and(|v| v.adding.is_some())
+}
+
+fn dispatch_key(key_event: KeyEvent, app: &mut App) {
+    // Asked for at startup, over the splash: every key is part of the passphrase
+    if let Some(prompt) = app.passphrase_prompt.as_mut() {
+        app.show_splash = false;
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        match key_event.code {
+            KeyCode::Enter => app.submit_passphrase(),
+            KeyCode::Esc => app.cancel_passphrase(),
+            KeyCode::Backspace => {
+                prompt.input.pop();
+            }
+            KeyCode::Char('u') if ctrl => prompt.input.clear(),
+            KeyCode::Char(c) if !ctrl => prompt.input.push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    // Also over the splash, right at startup
+    if app.recovery_prompt.is_some() {
+        app.show_splash = false;
+        match key_event.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => app.resolve_recovery(true),
+            KeyCode::Char('n') | KeyCode::Char('N') => app.resolve_recovery(false),
+            _ => {}
+        }
+        return;
+    }
+
     if app.show_splash {
         app.show_splash = false;
         return;
     }
 
+    if app.guard_prompt.is_some() {
+        match key_event.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => app.resolve_guard_prompt(true),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                app.resolve_guard_prompt(false)
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if app.send_warnings.is_some() {
+        match key_event.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => app.resolve_send_warnings(true),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                app.resolve_send_warnings(false)
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if let Some(prompt) = app.sync_prompt.as_mut() {
+        match key_event.code {
+            KeyCode::Char('l') | KeyCode::Char('L') => app.resolve_sync_conflict(true),
+            KeyCode::Char('r') | KeyCode::Char('R') => app.resolve_sync_conflict(false),
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                prompt.show_diff = !prompt.show_diff;
+                prompt.scroll = 0;
+            }
+            KeyCode::Char('j') | KeyCode::Down => prompt.scroll = prompt.scroll.saturating_add(1),
+            KeyCode::Char('k') | KeyCode::Up => prompt.scroll = prompt.scroll.saturating_sub(1),
+            KeyCode::Esc => app.cancel_sync_prompt(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.bundle_prompt.is_some() {
+        use crate::features::bundle::Resolution;
+        match key_event.code {
+            KeyCode::Char(c @ ('o' | 'O')) => {
+                app.resolve_bundle_collision(Resolution::Overwrite, c == 'O')
+            }
+            KeyCode::Char(c @ ('s' | 'S')) => {
+                app.resolve_bundle_collision(Resolution::Skip, c == 'S')
+            }
+            KeyCode::Char(c @ ('b' | 'B')) => {
+                app.resolve_bundle_collision(Resolution::KeepBoth, c == 'B')
+            }
+            KeyCode::Esc => app.cancel_bundle_import(),
+            _ => {}
+        }
+        return;
+    }
+
+    // Ctrl+V pastes from the system clipboard, for terminals without bracketed paste
+    if key_event.code == KeyCode::Char('v') && key_event.modifiers.contains(KeyModifiers::CONTROL) {
+        if let Some(text) = app.clipboard_text()
+            && !handle_paste(&text, app)
+        {
+            app.show_notification("Start editing a field to paste into it".to_string());
+        }
+        return;
+    }
+
     if app.active_tab().show_schema_modal {
         if key_event.code == KeyCode::Esc {
             app.close_schema_modal();
@@ -72,6 +200,17 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 app.active_tab_mut().show_grpc_description_modal = false;
                 app.active_tab_mut().show_grpc_services_modal = true;
             }
+            KeyCode::Char('j') | KeyCode::Down => {
+                let tab = app.active_tab_mut();
+                if tab.grpc_method_selected + 1 < tab.grpc_methods.len() {
+                    tab.grpc_method_selected += 1;
+                }
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                let tab = app.active_tab_mut();
+                tab.grpc_method_selected = tab.grpc_method_selected.saturating_sub(1);
+            }
+            KeyCode::Enter => app.use_grpc_method(),
             _ => {}
         }
         return;
@@ -82,11 +221,14 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
             KeyCode::Esc | KeyCode::Char('q') => {
                 app.close_diff();
             }
-            KeyCode::Char('j') | KeyCode::Down => {
+            KeyCode::Char('j') => {
+                app.toggle_diff_json_mode();
+            }
+            KeyCode::Down => {
                 let i = app.diff_list_state.selected().unwrap_or(0);
                 app.diff_list_state.select(Some(i + 1));
             }
-            KeyCode::Char('k') | KeyCode::Up => {
+            KeyCode::Up => {
                 let i = app.diff_list_state.selected().unwrap_or(0);
                 if i > 0 {
                     app.diff_list_state.select(Some(i - 1));
@@ -97,11 +239,384 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
         return;
     }
 
+    if app.response_compare.is_some() {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                app.response_compare = None;
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                let last = app
+                    .response_compare
+                    .as_ref()
+                    .map(|c| c.body.len())
+                    .unwrap_or(0)
+                    .saturating_sub(1);
+                app.response_compare_scroll = (app.response_compare_scroll + 1).min(last);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                app.response_compare_scroll = app.response_compare_scroll.saturating_sub(1);
+            }
+            KeyCode::Char('r') => {
+                // Compare the latest response with yet another re-send
+                app.response_compare = None;
+                app.resend_and_compare();
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_notification_history {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('N') => {
+                app.show_notification_history = false;
+                app.notification_history_scroll = 0;
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                let last = app.notifications.history.len().saturating_sub(1);
+                app.notification_history_scroll = (app.notification_history_scroll + 1).min(last);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                app.notification_history_scroll = app.notification_history_scroll.saturating_sub(1);
+            }
+            KeyCode::Char('x') => {
+                app.notifications.history.clear();
+                app.notification_history_scroll = 0;
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if app.theme_picker.is_some() {
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => app.move_theme_picker(true),
+            KeyCode::Char('k') | KeyCode::Up => app.move_theme_picker(false),
+            KeyCode::Enter => app.close_theme_picker(true),
+            KeyCode::Esc | KeyCode::Char('q') => app.close_theme_picker(false),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_keymap {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.show_keymap = false,
+            KeyCode::Char('j') | KeyCode::Down => {
+                app.keymap_scroll = app.keymap_scroll.saturating_add(1)
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                app.keymap_scroll = app.keymap_scroll.saturating_sub(1)
+            }
+            KeyCode::Char('r') => app.reload_keymap(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.doctor_report.is_some() {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => app.doctor_report = None,
+            KeyCode::Char('j') | KeyCode::Down => {
+                app.doctor_scroll = app.doctor_scroll.saturating_add(1)
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                app.doctor_scroll = app.doctor_scroll.saturating_sub(1)
+            }
+            KeyCode::Char('r') => app.run_doctor(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.collection_diff.is_some() {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => app.collection_diff = None,
+            KeyCode::Char('j') | KeyCode::Down => {
+                app.collection_diff_scroll = app.collection_diff_scroll.saturating_add(1)
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                app.collection_diff_scroll = app.collection_diff_scroll.saturating_sub(1)
+            }
+            KeyCode::PageDown => {
+                app.collection_diff_scroll = app.collection_diff_scroll.saturating_add(10)
+            }
+            KeyCode::PageUp => {
+                app.collection_diff_scroll = app.collection_diff_scroll.saturating_sub(10)
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if let Some(view) = app.dedupe.as_mut() {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.dedupe = None,
+            KeyCode::Char('j') | KeyCode::Down => view.scroll = view.scroll.saturating_add(1),
+            KeyCode::Char('k') | KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+            KeyCode::Char('n') | KeyCode::Right => view.step_group(true),
+            KeyCode::Char('N') | KeyCode::Left => view.step_group(false),
+            KeyCode::Tab => view.step_other(),
+            KeyCode::Char('s') => view.swap(),
+            KeyCode::Char('m') => app.dedupe_merge(),
+            KeyCode::Char('d') => app.dedupe_delete(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.env_usage.is_some() {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.env_usage = None,
+            KeyCode::Enter => app.open_env_usage_selection(),
+            KeyCode::Char('j') | KeyCode::Down => app.move_env_usage(1),
+            KeyCode::Char('k') | KeyCode::Up => app.move_env_usage(-1),
+            KeyCode::PageDown => app.move_env_usage(10),
+            KeyCode::PageUp => app.move_env_usage(-10),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.variables_inspector.is_some() {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => app.variables_inspector = None,
+            KeyCode::Char('j') | KeyCode::Down => {
+                app.variables_inspector_scroll = app.variables_inspector_scroll.saturating_add(1)
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                app.variables_inspector_scroll = app.variables_inspector_scroll.saturating_sub(1)
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if let Some(view) = app.log_view.as_mut() {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.log_view = None,
+            KeyCode::Char('k') | KeyCode::Up => view.scroll = view.scroll.saturating_add(1),
+            KeyCode::Char('j') | KeyCode::Down => view.scroll = view.scroll.saturating_sub(1),
+            KeyCode::PageUp => view.scroll = view.scroll.saturating_add(20),
+            KeyCode::PageDown => view.scroll = view.scroll.saturating_sub(20),
+            KeyCode::Char('g') | KeyCode::Home => view.scroll = usize::MAX,
+            KeyCode::Char('G') | KeyCode::End => view.scroll = 0,
+            KeyCode::Char('e') => {
+                view.problems_only = !view.problems_only;
+                view.scroll = 0;
+            }
+            KeyCode::Char('r') => app.toggle_log_request(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.latency_chart.is_some() {
+        if matches!(
+            key_event.code,
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter
+        ) {
+            app.latency_chart = None;
+        }
+        return;
+    }
+
+    if app.audit_report.is_some() {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => app.audit_report = None,
+            KeyCode::Char('j') | KeyCode::Down => {
+                app.audit_scroll = app.audit_scroll.saturating_add(1)
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                app.audit_scroll = app.audit_scroll.saturating_sub(1)
+            }
+            KeyCode::Char('r') => app.run_audit(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.hex_view.is_some() {
+        handle_hex_view(key_event, app);
+        return;
+    }
+
+    if app.proto_view.is_some() {
+        handle_proto_view(key_event, app);
+        return;
+    }
+
+    if let Some(dashboard) = app.dashboard.as_mut() {
+        let last = dashboard.tiles.len().saturating_sub(1);
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.close_dashboard(),
+            KeyCode::Char('j') | KeyCode::Char('l') | KeyCode::Down | KeyCode::Right => {
+                dashboard.selected = (dashboard.selected + 1).min(last)
+            }
+            KeyCode::Char('k') | KeyCode::Char('h') | KeyCode::Up | KeyCode::Left => {
+                dashboard.selected = dashboard.selected.saturating_sub(1)
+            }
+            KeyCode::Enter => app.open_dashboard_tile(),
+            KeyCode::Char('s') => app.toggle_dashboard(),
+            // Restarting runs a round right away
+            KeyCode::Char('r') => {
+                app.stop_dashboard();
+                app.should_start_dashboard = true;
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if let Some(view) = app.wire_view.as_mut() {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('W') => app.wire_view = None,
+            KeyCode::Char('j') | KeyCode::Down => view.scroll = view.scroll.saturating_add(1),
+            KeyCode::Char('k') | KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+            KeyCode::PageDown => view.scroll = view.scroll.saturating_add(10),
+            KeyCode::PageUp => view.scroll = view.scroll.saturating_sub(10),
+            KeyCode::Tab => app.toggle_wire_view(),
+            KeyCode::Char('c') => {
+                let text = view.text.clone();
+                app.copy_to_clipboard(text);
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_batch {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.show_batch = false,
+            KeyCode::Char('j') | KeyCode::Down => {
+                let i = app.batch_state.selected().unwrap_or(0);
+                app.batch_state
+                    .select(Some((i + 1).min(app.batch_rows.len().saturating_sub(1))));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                let i = app.batch_state.selected().unwrap_or(0);
+                app.batch_state.select(Some(i.saturating_sub(1)));
+            }
+            KeyCode::Enter => app.open_batch_tab(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_ndjson {
+        let count = app.ndjson_rows().len();
+        if app.ndjson_filtering {
+            match key_event.code {
+                KeyCode::Enter | KeyCode::Esc => app.ndjson_filtering = false,
+                KeyCode::Backspace => {
+                    app.ndjson_filter.pop();
+                    app.ndjson_state.select(Some(0));
+                }
+                KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app.ndjson_filter.push(c);
+                    app.ndjson_state.select(Some(0));
+                }
+                _ => {}
+            }
+            return;
+        }
+        match key_event.code {
+            KeyCode::Esc if !app.ndjson_filter.is_empty() => {
+                app.ndjson_filter.clear();
+                app.ndjson_state.select(Some(0));
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('L') => app.show_ndjson = false,
+            KeyCode::Char('/') => app.ndjson_filtering = true,
+            KeyCode::Char('j') | KeyCode::Down => {
+                let i = app.ndjson_state.selected().unwrap_or(0);
+                app.ndjson_state
+                    .select(Some((i + 1).min(count.saturating_sub(1))));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                let i = app.ndjson_state.selected().unwrap_or(0);
+                app.ndjson_state.select(Some(i.saturating_sub(1)));
+            }
+            KeyCode::PageDown => {
+                let i = app.ndjson_state.selected().unwrap_or(0);
+                app.ndjson_state
+                    .select(Some((i + 10).min(count.saturating_sub(1))));
+            }
+            KeyCode::PageUp => {
+                let i = app.ndjson_state.selected().unwrap_or(0);
+                app.ndjson_state.select(Some(i.saturating_sub(10)));
+            }
+            KeyCode::Enter => app.open_ndjson_record(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_fuzz_report {
+        let count = app.fuzz_report.as_ref().map_or(0, |r| r.groups.len());
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.show_fuzz_report = false,
+            KeyCode::Char('j') | KeyCode::Down => {
+                let i = app.fuzz_state.selected().unwrap_or(0);
+                app.fuzz_state
+                    .select(Some((i + 1).min(count.saturating_sub(1))));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                let i = app.fuzz_state.selected().unwrap_or(0);
+                app.fuzz_state.select(Some(i.saturating_sub(1)));
+            }
+            KeyCode::Enter => app.open_fuzz_case(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_timeline {
+        let count = app.active_tab().timeline.len();
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('R') => app.show_timeline = false,
+            KeyCode::Char('j') | KeyCode::Down => {
+                let i = app.timeline_state.selected().unwrap_or(0);
+                app.timeline_state
+                    .select(Some((i + 1).min(count.saturating_sub(1))));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                let i = app.timeline_state.selected().unwrap_or(0);
+                app.timeline_state.select(Some(i.saturating_sub(1)));
+            }
+            KeyCode::Enter => app.restore_timeline_entry(),
+            _ => {}
+        }
+        return;
+    }
+
+    // Typing a cheat sheet search
+    if app.show_help && app.help_search_active {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Enter => app.help_search_active = false,
+            KeyCode::Backspace => {
+                app.help_query.pop();
+                app.help_scroll = 0;
+            }
+            KeyCode::Char(c) => {
+                app.help_query.push(c);
+                app.help_scroll = 0;
+            }
+            _ => {}
+        }
+        return;
+    }
+
     // Handle help menu scrolling
     if app.show_help {
         match key_event.code {
-            KeyCode::Char('?') | KeyCode::Esc => {
-                app.show_help = false;
+            KeyCode::Esc if !app.help_query.is_empty() => {
+                app.help_query.clear();
+                app.help_scroll = 0;
+            }
+            KeyCode::Char('?') | KeyCode::Esc => app.close_help(),
+            KeyCode::Char('/') => app.help_search_active = true,
+            KeyCode::Char('a') => {
+                app.help_show_all = !app.help_show_all;
                 app.help_scroll = 0;
             }
             KeyCode::Char('j') | KeyCode::Down => {
@@ -118,6 +633,7 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
             }
             _ => {}
         }
+        return;
     }
 
     // Cookie Manager Modal
@@ -166,37 +682,37 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
             KeyCode::Enter => {
+                let valid = app
+                    .stress_stages()
+                    .and(app.stress_thresholds())
+                    .and(app.stress_requests());
+                if let Err(e) = valid {
+                    app.show_error(e);
+                    return;
+                }
                 app.show_stress_modal = false;
                 app.active_tab_mut().input_mode = InputMode::Normal;
-                app.should_run_stress_test = true;
+                app.should_run_stress_test = app.check_guard(crate::app::GuardedAction::StressTest);
             }
 
             KeyCode::Tab => {
-                if app.active_tab().input_mode == InputMode::EditingStressVUs {
-                    app.active_tab_mut().input_mode = InputMode::EditingStressDuration;
-                } else {
-                    app.active_tab_mut().input_mode = InputMode::EditingStressVUs;
-                }
+                let next = match app.active_tab().input_mode {
+                    InputMode::EditingStressVUs => InputMode::EditingStressDuration,
+                    InputMode::EditingStressDuration => InputMode::EditingStressStages,
+                    InputMode::EditingStressStages => InputMode::EditingStressThresholds,
+                    InputMode::EditingStressThresholds => InputMode::EditingStressTarget,
+                    _ => InputMode::EditingStressVUs,
+                };
+                app.active_tab_mut().input_mode = next;
             }
-            KeyCode::Char(c) => {
-                if app.active_tab().input_mode == InputMode::EditingStressVUs {
-                    if c.is_ascii_digit() {
-                        app.stress_vus_input.push(c);
-                    }
-                } else if app.active_tab().input_mode == InputMode::EditingStressDuration
-                    && c.is_ascii_digit()
-                {
-                    app.stress_duration_input.push(c);
-                }
+            KeyCode::Left | KeyCode::Right
+                if app.active_tab().input_mode == InputMode::EditingStressTarget =>
+            {
+                app.cycle_stress_target(key_event.code == KeyCode::Right);
             }
-            KeyCode::Backspace => {
-                if app.active_tab().input_mode == InputMode::EditingStressVUs {
-                    app.stress_vus_input.pop();
-                } else if app.active_tab().input_mode == InputMode::EditingStressDuration {
-                    app.stress_duration_input.pop();
-                }
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         }
         return;
     }
@@ -216,15 +732,9 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 KeyCode::Esc | KeyCode::Enter => {
                     app.active_tab_mut().input_mode = InputMode::Normal;
                 }
-                KeyCode::Char(c) => {
-                    if c.is_ascii_digit() {
-                        app.sentinel_interval_input.push(c);
-                    }
-                }
-                KeyCode::Backspace => {
-                    app.sentinel_interval_input.pop();
+                _ => {
+                    edit_input(&key_event, app);
                 }
-                _ => {}
             }
             return;
         }
@@ -251,8 +761,8 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
             KeyCode::Char('L') | KeyCode::Char('l') => {
                 if let Some(state) = &app.sentinel_state {
                     match state.save_history() {
-                        Ok(fname) => app.show_notification(format!("History saved to {}", fname)),
-                        Err(e) => app.show_notification(format!("Failed to save: {}", e)),
+                        Ok(fname) => app.show_success(format!("History saved to {}", fname)),
+                        Err(e) => app.show_error(format!("Failed to save: {}", e)),
                     }
                 }
             }
@@ -265,7 +775,9 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
         return;
     }
 
-    if key_event.modifiers.contains(KeyModifiers::CONTROL) {
+    // Inside an input Ctrl+A/E/W/U/K edit the text, the shortcuts below wait for Normal mode
+    let editing_key = Edit::from_key(&key_event).is_some() && with_input(app, |_, _| ()).is_some();
+    if key_event.modifiers.contains(KeyModifiers::CONTROL) && !editing_key {
         match key_event.code {
             KeyCode::Char('h') => {
                 app.active_sidebar = !app.active_sidebar;
@@ -297,42 +809,75 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                         app.collection_state.select(Some(0));
                     }
                 }
-                return;
+                return;
+            }
+            KeyCode::Char('t') => {
+                app.next_theme();
+                app.show_notification(format!("Theme: {}", app.theme.name));
+                return;
+            }
+            KeyCode::Char('k') => {
+                app.mock_mode = !app.mock_mode;
+                return;
+            }
+            _ => {}
+        }
+    }
+
+    // Mock route editor modal
+    if app.mock_mode
+        && let Some(editor) = app.mock_editor.as_mut()
+    {
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        match key_event.code {
+            KeyCode::Esc => app.mock_editor = None,
+            KeyCode::Char('s') if ctrl => app.save_mock_editor(),
+            KeyCode::Tab => editor.field = editor.field.next(),
+            KeyCode::BackTab => editor.field = editor.field.prev(),
+            KeyCode::Left | KeyCode::Right if editor.field == MockEditorField::Method => {
+                editor.cycle_method(key_event.code == KeyCode::Right);
+            }
+            KeyCode::Char(' ') if editor.field == MockEditorField::Method => {
+                editor.cycle_method(true);
+            }
+            KeyCode::Enter if editor.field.is_multiline() => {
+                if let Some(text) = editor.field_mut() {
+                    text.push('\n');
+                }
             }
-            KeyCode::Char('t') => {
-                app.next_theme();
-                app.show_notification(format!("Theme: {}", app.theme.name));
-                return;
+            KeyCode::Enter => editor.field = editor.field.next(),
+            KeyCode::Backspace => {
+                if let Some(text) = editor.field_mut() {
+                    text.pop();
+                }
             }
-            KeyCode::Char('k') => {
-                app.mock_mode = !app.mock_mode;
-                return;
+            KeyCode::Char(c) if !ctrl => {
+                if let Some(text) = editor.field_mut() {
+                    text.push(c);
+                }
             }
             _ => {}
         }
+        return;
     }
 
     // Handle Mock Mode
     if app.mock_mode {
         match key_event.code {
             KeyCode::Esc => app.mock_mode = false,
+            KeyCode::Char('?') => app.show_help = true,
             KeyCode::Char('s') => app.toggle_mock_server(),
-            KeyCode::Char('a') => {
-                // Add new mock route
-                app.mock_routes.push(crate::net::mock_server::MockRoute {
-                    path: "/api/new".to_string(),
-                    method: "GET".to_string(),
-                    status: 200,
-                    body: "{\"message\": \"Hello Mock!\"}".to_string(),
-                    headers: std::collections::HashMap::new(),
-                });
+            KeyCode::Char('c') => app.toggle_mock_cors(),
+            KeyCode::Char('t') => app.toggle_mock_tls(),
+            KeyCode::Char('a') => app.open_mock_editor(None),
+            KeyCode::Char('e') | KeyCode::Enter => {
+                if let Some(selected) = app.mock_list_state.selected() {
+                    app.open_mock_editor(Some(selected));
+                }
             }
             KeyCode::Char('d') => {
-                if let Some(selected) = app.mock_list_state.selected()
-                    && selected < app.mock_routes.len()
-                {
-                    app.mock_routes.remove(selected);
-                    app.restart_mock_server_if_running();
+                if let Some(selected) = app.mock_list_state.selected() {
+                    app.delete_mock_route(selected);
                 }
             }
             KeyCode::Char('j') | KeyCode::Down => {
@@ -413,9 +958,27 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 app.runner_result = None;
                 app.runner_scroll = 0;
             }
+            KeyCode::Char('e') => app.export_run(""),
+            KeyCode::Char('o') => app.export_run_responses(""),
+            KeyCode::Char('E') => {
+                if let Some(result) = &app.runner_result {
+                    let file = format!("{}-results.json", result.collection_name);
+                    app.export_run(&file);
+                }
+            }
+            KeyCode::Char('%') => {
+                let selected = app
+                    .collection_state
+                    .selected()
+                    .and_then(|i| app.collections.get(i))
+                    .map(|c| c.name.clone());
+                if let Some(name) = selected {
+                    app.open_stress_modal(&name);
+                }
+            }
             KeyCode::Esc => {
                 app.runner_mode = false;
-                app.popup_message = None;
+                app.notifications.dismiss_all();
             }
             KeyCode::Char('?') => {
                 app.show_help = !app.show_help;
@@ -433,49 +996,33 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 KeyCode::Enter | KeyCode::Esc => {
                     app.active_tab_mut().input_mode = InputMode::Normal;
                 }
-                KeyCode::Char(c) => {
-                    app.active_tab_mut().ws_url.push(c);
-                }
-                KeyCode::Backspace => {
-                    app.active_tab_mut().ws_url.pop();
+                _ => {
+                    edit_input(&key_event, app);
                 }
-                _ => {}
             },
             InputMode::EditingWsMessage => match key_event.code {
                 KeyCode::Esc => {
                     app.active_tab_mut().input_mode = InputMode::Normal;
                 }
-                KeyCode::Char(c) => {
-                    app.active_tab_mut().ws_message_input.push(c);
-                }
-                KeyCode::Backspace => {
-                    app.active_tab_mut().ws_message_input.pop();
+                _ => {
+                    edit_input(&key_event, app);
                 }
-                _ => {}
             },
             InputMode::EditingGrpcService => match key_event.code {
                 KeyCode::Enter | KeyCode::Esc => {
                     app.active_tab_mut().input_mode = InputMode::Normal;
                 }
-                KeyCode::Char(c) => {
-                    app.active_tab_mut().grpc_service.push(c);
+                _ => {
+                    edit_input(&key_event, app);
                 }
-                KeyCode::Backspace => {
-                    app.active_tab_mut().grpc_service.pop();
-                }
-                _ => {}
             },
             InputMode::EditingGrpcProto => match key_event.code {
                 KeyCode::Enter | KeyCode::Esc => {
                     app.active_tab_mut().input_mode = InputMode::Normal;
                 }
-                KeyCode::Char(c) => {
-                    app.active_tab_mut().grpc_proto_path.push(c);
-                }
-                KeyCode::Backspace => {
-                    app.active_tab_mut().grpc_proto_path.pop();
+                _ => {
+                    edit_input(&key_event, app);
                 }
-                _ => {}
             },
             InputMode::Normal => match key_event.code {
                 KeyCode::Char('e') => {
@@ -502,11 +1049,19 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                     tab.ws_messages.clear();
                     tab.ws_scroll = 0;
                 }
+                KeyCode::Char('s') => app.export_ws_session(""),
+                KeyCode::Char('r') => {
+                    if app.active_tab().ws_replay.is_some() {
+                        app.stop_ws_replay();
+                    } else {
+                        app.ws_replay_command("");
+                    }
+                }
                 KeyCode::Char('?') => {
                     app.show_help = !app.show_help;
                 }
                 KeyCode::Esc => {
-                    app.popup_message = None;
+                    app.notifications.dismiss();
                 }
                 _ => {}
             },
@@ -527,6 +1082,11 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                     app.toggle_diff_selection(hist_idx);
                 }
             }
+            KeyCode::Char('s') => {
+                if let Some(hist_idx) = app.get_selected_history_index() {
+                    app.open_save_history(hist_idx);
+                }
+            }
             KeyCode::Esc => app.active_sidebar = false,
             _ => {}
         }
@@ -534,36 +1094,39 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
     }
 
     match app.active_tab().input_mode {
-        InputMode::EditingStressVUs | InputMode::EditingStressDuration => {
+        InputMode::EditingStressVUs
+        | InputMode::EditingStressDuration
+        | InputMode::EditingStressStages
+        | InputMode::EditingStressThresholds
+        | InputMode::EditingStressTarget => {
             if key_event.code == KeyCode::Esc {
                 app.active_tab_mut().input_mode = InputMode::Normal;
                 app.show_stress_modal = false;
             }
         }
         InputMode::EditingSentinelInterval => {}
+        InputMode::EditingTimeout => match key_event.code {
+            KeyCode::Enter => app.commit_timeout_input(),
+            KeyCode::Esc => app.active_tab_mut().input_mode = InputMode::Normal,
+            _ => {
+                edit_input(&key_event, app);
+            }
+        },
         InputMode::EditingGrpcService => match key_event.code {
             KeyCode::Enter | KeyCode::Esc => {
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
-            KeyCode::Char(c) => {
-                app.active_tab_mut().grpc_service.push(c);
-            }
-            KeyCode::Backspace => {
-                app.active_tab_mut().grpc_service.pop();
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::EditingGrpcProto => match key_event.code {
             KeyCode::Enter | KeyCode::Esc => {
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
-            KeyCode::Char(c) => {
-                app.active_tab_mut().grpc_proto_path.push(c);
-            }
-            KeyCode::Backspace => {
-                app.active_tab_mut().grpc_proto_path.pop();
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
 
         InputMode::CommandPalette => match key_event.code {
@@ -619,6 +1182,16 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                         "Duplicate Tab" => {
                             app.duplicate_tab();
                         }
+                        "Rename Tab" => {
+                            app.command_query.clear();
+                            app.start_rename_tab();
+                            return;
+                        }
+                        "Browse History" => {
+                            app.command_query.clear();
+                            app.open_history_view("");
+                            return;
+                        }
                         "Clear History" => {
                             app.clear_history();
                         }
@@ -646,6 +1219,11 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                         "Switch Theme" => {
                             app.next_theme();
                         }
+                        "Choose Theme" => {
+                            app.open_theme_picker();
+                        }
+                        "Save Response Snapshot" => app.snapshot_command("save"),
+                        "Treat Response As" => app.cycle_treat_as(),
                         "Filter Collections" => {
                             app.show_sidebar_filter = true;
                             app.active_tab_mut().input_mode = InputMode::FilteringSidebar;
@@ -664,61 +1242,226 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                             app.show_help = !app.show_help;
                         }
                         "Quit" => {
-                            std::process::exit(0);
-                        }
-                        "Export HTML Docs" => {
-                            if let Err(e) =
-                                crate::features::doc_gen::save_html_docs(&app.collections)
-                            {
-                                app.active_tab_mut().response =
-                                    Some(format!("Error saving docs: {}", e));
-                            } else {
-                                app.popup_message =
-                                    Some("Documentation saved to API_DOCS.html".to_string());
-                            }
+                            app.should_quit = true;
+                        }
+                        "Save Tabs as Collection" => {
+                            let timestamp = std::time::SystemTime::now()
+                                .duration_since(std::time::UNIX_EPOCH)
+                                .unwrap()
+                                .as_secs();
+                            app.save_tabs_as_collection(&format!("tabs_{}", timestamp));
+                        }
+                        "Re-send and Compare" => {
+                            app.resend_and_compare();
+                        }
+                        "Switch Workspace" => app.next_workspace(),
+                        "Encrypt History & Cookies" => {
+                            let args = if app.encryption.enabled { "" } else { "on" };
+                            app.encrypt_command(args);
+                        }
+                        "Sync with Gist" => app.sync_command(""),
+                        "Sync with Storage" => app.storage_command(""),
+                        "Export Workspace Bundle" => {
+                            app.bundle_command("export");
+                        }
+                        "Switch Profile" => {
+                            app.next_profile();
                         }
+                        "Generate Code" => {
+                            app.show_command_palette = false;
+                            app.command_query.clear();
+                            app.open_codegen_picker();
+                            return;
+                        }
+                        "Fetch All Pages" => app.paginate_command(""),
+                        "Insert Snippet" => {
+                            app.show_command_palette = false;
+                            app.command_query.clear();
+                            app.open_snippet_picker(crate::features::snippets::ScriptKind::Pre);
+                            return;
+                        }
+                        "Flush Connections / DNS" => {
+                            app.flush_connections();
+                        }
+                        "Copy Response as CSV" => {
+                            app.export_table(',', "");
+                        }
+                        "Notification History" => {
+                            app.show_notification_history = true;
+                            app.notification_history_scroll = 0;
+                        }
+                        "Request Timeline" => app.open_timeline(),
+                        "Preview Request" => app.preview_request(),
+                        "Fuzz Request" => app.fuzz_command(""),
+                        "Send All Tabs" => app.send_tabs_command("all"),
+                        "Security Audit" => app.run_audit(),
+                        "Find Anything" => {
+                            app.command_query.clear();
+                            app.open_finder();
+                            return;
+                        }
+                        "Doctor" => app.run_doctor(),
+                        "Show Keymap" => {
+                            app.show_keymap = true;
+                            app.keymap_scroll = 0;
+                        }
+                        "Export HTML Docs" => app.generate_docs(),
+                        "Proto Registry" => app.open_proto_view(),
+                        "Health Dashboard" => app.dashboard_command(""),
+                        "Latency Trend" => app.open_latency_chart(""),
+                        "Environment Variable Usage" => app.inspect_environment_usage(),
+                        "Find Duplicate Requests" => app.dedupe_command(""),
+                        "Clear Tag Filter" => app.set_tag_filter(Vec::new()),
+                        "Export History" => app.export_history(""),
+                        "Export Run Responses" => app.export_run_responses(""),
+                        "Copy Trace Link" => app.copy_trace_link(),
+                        "Logs" => app.open_logs(""),
+                        "Open Trace" => app.open_trace_link(),
                         _ => {}
                     }
                 }
                 app.show_command_palette = false;
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
-            KeyCode::Char(c) => {
-                app.command_query.push(c);
-                app.command_index = 0;
+            _ => {
+                edit_input(&key_event, app);
             }
-            KeyCode::Backspace => {
-                app.command_query.pop();
-                app.command_index = 0;
+        },
+
+        InputMode::SnippetPicker => match key_event.code {
+            KeyCode::Esc => {
+                app.close_snippet_picker();
+            }
+            KeyCode::Down => {
+                let count = app.filtered_snippets().len();
+                if app.snippet_index + 1 < count {
+                    app.snippet_index += 1;
+                }
+            }
+            KeyCode::Up => {
+                app.snippet_index = app.snippet_index.saturating_sub(1);
+            }
+            KeyCode::Tab => {
+                use crate::features::snippets::ScriptKind;
+                app.snippet_target = match app.snippet_target {
+                    ScriptKind::Pre => ScriptKind::Post,
+                    ScriptKind::Post => ScriptKind::Pre,
+                };
+            }
+            KeyCode::Enter => {
+                app.confirm_snippet_picker();
+            }
+            _ => {
+                edit_input(&key_event, app);
+            }
+        },
+
+        InputMode::CodegenPicker => match key_event.code {
+            KeyCode::Esc => {
+                app.close_codegen_picker();
+            }
+            KeyCode::Down => {
+                let count = app.filtered_codegen_targets().len();
+                if app.codegen_index + 1 < count {
+                    app.codegen_index += 1;
+                }
+            }
+            KeyCode::Up => {
+                app.codegen_index = app.codegen_index.saturating_sub(1);
+            }
+            KeyCode::Enter => {
+                app.confirm_codegen_picker();
+            }
+            _ => {
+                edit_input(&key_event, app);
+            }
+        },
+
+        InputMode::Finder => match key_event.code {
+            KeyCode::Esc => {
+                app.close_finder();
+            }
+            KeyCode::Down => {
+                let count = app.finder_matches().len();
+                if app.finder_index + 1 < count {
+                    app.finder_index += 1;
+                }
+            }
+            KeyCode::Up => {
+                app.finder_index = app.finder_index.saturating_sub(1);
+            }
+            KeyCode::Enter => {
+                app.confirm_finder();
+            }
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
 
+        InputMode::HistoryView => {
+            let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+            match key_event.code {
+                KeyCode::Esc => app.close_history_view(),
+                KeyCode::Down => app.move_history_view(true),
+                KeyCode::Up => app.move_history_view(false),
+                KeyCode::Enter => app.confirm_history_view(),
+                KeyCode::Tab => app.toggle_history_mark(),
+                KeyCode::BackTab => app.toggle_history_mark_all(),
+                KeyCode::Char('p') if ctrl => app.toggle_history_pin(),
+                KeyCode::Char('x') if ctrl => app.delete_history_view_entries(),
+                KeyCode::Char('s') if ctrl => {
+                    if let Some(i) = app.selected_history_view_entry() {
+                        app.close_history_view();
+                        app.open_save_history(i);
+                    }
+                }
+                _ => {
+                    edit_input(&key_event, app);
+                }
+            }
+        }
+
         InputMode::Command => match key_event.code {
             KeyCode::Enter => {
                 let cmd = app.command_input.trim().to_string();
+                app.command_completions.clear();
                 if !cmd.is_empty() {
+                    app.record_command(&cmd);
                     let parts: Vec<&str> = cmd.split_whitespace().collect();
                     match parts[0] {
-                        "q" | "quit" => std::process::exit(0),
+                        "q" | "quit" => app.should_quit = true,
                         "w" | "save" => app
                             .show_notification("Save not implemented via command yet.".to_string()),
+                        "send" if parts.len() > 1 => {
+                            let spec = cmd[parts[0].len()..].trim().to_string();
+                            app.command_input.clear();
+                            app.send_tabs_command(&spec);
+                            return;
+                        }
+                        "send" => app.active_tab_mut().resend_requested = true,
+                        "method" => {
+                            if parts.len() > 1 {
+                                app.set_method(parts[1]);
+                            } else {
+                                app.show_notification("Usage: method <GET|POST|...>".to_string());
+                            }
+                        }
+                        "header" | "headers" => app.header_command(cmd[parts[0].len()..].trim()),
+                        "env" => app.env_command(cmd[parts[0].len()..].trim()),
+                        "open" => app.open_request(cmd[parts[0].len()..].trim()),
+                        "run" => app.run_collection_by_name(cmd[parts[0].len()..].trim()),
+                        "stress" => {
+                            app.open_stress_modal(cmd[parts[0].len()..].trim());
+                            if app.show_stress_modal {
+                                app.command_input.clear();
+                                return;
+                            }
+                        }
                         "theme" => {
                             if parts.len() > 1 {
-                                if parts[1] == "matrix" {
-                                    app.theme = crate::app::Theme::matrix();
-                                    app.theme_index = 1;
-                                } else if parts[1] == "cyberpunk" {
-                                    app.theme = crate::app::Theme::cyberpunk();
-                                    app.theme_index = 2;
-                                } else if parts[1] == "default" {
-                                    app.theme = crate::app::Theme::default_theme();
-                                    app.theme_index = 0;
-                                } else {
-                                    app.show_notification("Unknown theme".to_string());
-                                }
+                                app.set_theme(parts[1]);
                             } else {
-                                app.next_theme();
+                                app.open_theme_picker();
                             }
                         }
                         "new" => {
@@ -736,7 +1479,178 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                             }
                         }
                         "zen" => app.zen_mode = !app.zen_mode,
-                        _ => app.show_notification(format!("Unknown command: {}", parts[0])),
+                        "range" => {
+                            let spec = cmd[parts[0].len()..].trim();
+                            if spec.is_empty() {
+                                app.show_notification(
+                                    "Usage: range <0-1023 | 500- | -500 | first 64k | off>"
+                                        .to_string(),
+                                );
+                            } else {
+                                app.set_range(spec);
+                            }
+                        }
+                        "resume" => {
+                            if parts.len() > 1 {
+                                app.resume_download(parts[1]);
+                            } else {
+                                app.show_notification("Usage: resume <file>".to_string());
+                            }
+                        }
+                        "compare" | "resend" => app.resend_and_compare(),
+                        "csv" => app.export_table(',', cmd[parts[0].len()..].trim()),
+                        "tsv" => app.export_table('\t', cmd[parts[0].len()..].trim()),
+                        "profile" => app.switch_profile(cmd[parts[0].len()..].trim()),
+                        "workspace" | "ws" => app.workspace_command(cmd[parts[0].len()..].trim()),
+                        "encrypt" => app.encrypt_command(cmd[parts[0].len()..].trim()),
+                        "sync" => app.sync_command(cmd[parts[0].len()..].trim()),
+                        "storage" => app.storage_command(cmd[parts[0].len()..].trim()),
+                        "bundle" => {
+                            app.bundle_command(cmd[parts[0].len()..].trim());
+                            if app.passphrase_prompt.is_some() {
+                                app.command_input.clear();
+                                return;
+                            }
+                        }
+                        "unlock" => app.unlock_command(),
+                        "secret" => app.secret_command(cmd[parts[0].len()..].trim()),
+                        "history" | "hist" => {
+                            app.history_command(cmd[parts[0].len()..].trim());
+                            if app.show_history_view {
+                                app.command_input.clear();
+                                return;
+                            }
+                        }
+                        "coldiff" => app.collection_diff_command(cmd[parts[0].len()..].trim()),
+                        "vars" => app.vars_command(cmd[parts[0].len()..].trim()),
+                        "tag" | "tags" => app.tag_command(cmd[parts[0].len()..].trim()),
+                        "dedupe" | "dupes" => app.dedupe_command(cmd[parts[0].len()..].trim()),
+                        "protos" => app.open_proto_view(),
+                        "trend" => app.open_latency_chart(cmd[parts[0].len()..].trim()),
+                        "export" => app.export_command(cmd[parts[0].len()..].trim()),
+                        "trace" => app.trace_command(cmd[parts[0].len()..].trim()),
+                        "logs" | "log" => {
+                            app.open_logs(cmd[parts[0].len()..].trim());
+                            app.command_input.clear();
+                            app.active_tab_mut().input_mode = InputMode::Normal;
+                            return;
+                        }
+                        "dashboard" => app.dashboard_command(cmd[parts[0].len()..].trim()),
+                        "docs" => app.docs_command(cmd[parts[0].len()..].trim()),
+                        "rename" => app.rename_tab(cmd[parts[0].len()..].trim()),
+                        "saveas" => app.save_response_as(cmd[parts[0].len()..].trim()),
+                        "describe" => app.describe_command(cmd[parts[0].len()..].trim()),
+                        "snapshot" => app.snapshot_command(cmd[parts[0].len()..].trim()),
+                        "pool" => app.pool_command(cmd[parts[0].len()..].trim()),
+                        "ratelimit" => app.rate_limit_command(cmd[parts[0].len()..].trim()),
+                        "curl" => app.copy_curl_command(cmd[parts[0].len()..].trim()),
+                        "import-curl" => app.import_curl_file(cmd[parts[0].len()..].trim()),
+                        "paginate" | "pages" => app.paginate_command(&cmd[parts[0].len()..]),
+                        "timeout" => app.timeout_command(&cmd[parts[0].len()..]),
+                        "snippet" | "snip" => {
+                            let args = cmd[parts[0].len()..].trim().to_string();
+                            app.command_input.clear();
+                            app.snippet_command(&args);
+                            if app.show_snippet_picker {
+                                return;
+                            }
+                        }
+                        "codegen" | "gen" => {
+                            let target = cmd[parts[0].len()..].trim();
+                            if target.is_empty() {
+                                app.command_input.clear();
+                                app.open_codegen_picker();
+                                return;
+                            }
+                            app.copy_generated_code(target);
+                        }
+                        "flush" => app.flush_connections(),
+                        "flat" | "flatten" => app.toggle_json_flat(),
+                        "jsonpath" | "jp" => {
+                            let query = cmd[parts[0].len()..].trim();
+                            if query.is_empty() {
+                                app.clear_jsonpath_query();
+                            } else {
+                                app.run_jsonpath_query(query);
+                            }
+                        }
+                        "chain" => {
+                            if parts.len() > 1 {
+                                app.add_jsonpath_to_chain(parts[1]);
+                            } else {
+                                app.show_notification("Usage: chain <variable>".to_string());
+                            }
+                        }
+                        "fuzz" => {
+                            let args = cmd[parts[0].len()..].trim().to_string();
+                            app.command_input.clear();
+                            app.fuzz_command(&args);
+                            return;
+                        }
+                        "timeline" => {
+                            app.command_input.clear();
+                            app.open_timeline();
+                            return;
+                        }
+                        "wire" => {
+                            app.command_input.clear();
+                            if parts.get(1) == Some(&"sent") {
+                                app.show_sent_request();
+                            } else {
+                                app.preview_request();
+                            }
+                            return;
+                        }
+                        "ndjson" | "lines" => {
+                            app.command_input.clear();
+                            app.open_ndjson();
+                            return;
+                        }
+                        "treat" => app.treat_command(cmd[parts[0].len()..].trim()),
+                        "wsexport" => app.export_ws_session(cmd[parts[0].len()..].trim()),
+                        "wsreplay" => app.ws_replay_command(cmd[parts[0].len()..].trim()),
+                        "hex" => {
+                            app.command_input.clear();
+                            app.open_hex_view(parts.get(1) == Some(&"request"));
+                            return;
+                        }
+                        "keymap" => {
+                            app.command_input.clear();
+                            if cmd[parts[0].len()..].trim() == "reload" {
+                                app.reload_keymap();
+                            }
+                            app.show_keymap = true;
+                            app.keymap_scroll = 0;
+                            return;
+                        }
+                        "doctor" => {
+                            app.command_input.clear();
+                            app.run_doctor();
+                            return;
+                        }
+                        "audit" => {
+                            app.command_input.clear();
+                            app.run_audit();
+                            return;
+                        }
+                        "find" => {
+                            app.command_input.clear();
+                            app.open_finder();
+                            app.finder_query = cmd[parts[0].len()..].trim().to_string();
+                            return;
+                        }
+                        "notifications" | "messages" => {
+                            app.show_notification_history = true;
+                            app.notification_history_scroll = 0;
+                        }
+                        "savetabs" => {
+                            if parts.len() > 1 {
+                                app.save_tabs_as_collection(&parts[1..].join(" "));
+                            } else {
+                                app.show_notification("Usage: savetabs <name>".to_string());
+                            }
+                        }
+                        _ => app.show_error(format!("Unknown command: {}", parts[0])),
                     }
                 }
                 app.active_tab_mut().input_mode = InputMode::Normal;
@@ -745,14 +1659,17 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
             KeyCode::Esc => {
                 app.active_tab_mut().input_mode = InputMode::Normal;
                 app.command_input.clear();
+                app.command_completions.clear();
+                app.command_history_index = None;
             }
-            KeyCode::Char(c) => {
-                app.command_input.push(c);
-            }
-            KeyCode::Backspace => {
-                app.command_input.pop();
+            KeyCode::Tab => app.complete_command(),
+            KeyCode::Up => app.browse_command_history(true),
+            KeyCode::Down => app.browse_command_history(false),
+            _ => {
+                if edit_input(&key_event, app) {
+                    app.command_completions.clear();
+                }
             }
-            _ => {}
         },
         InputMode::FilteringSidebar => match key_event.code {
             KeyCode::Enter => {
@@ -765,13 +1682,9 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 app.show_sidebar_filter = false;
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
-            KeyCode::Char(c) => {
-                app.sidebar_filter.push(c);
-            }
-            KeyCode::Backspace => {
-                app.sidebar_filter.pop();
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::Normal => match key_event.code {
             KeyCode::Char(':') => {
@@ -781,6 +1694,10 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
             KeyCode::Char('e') => {
                 let mut handled = false;
                 match app.active_tab().selected_tab {
+                    0 if app.active_tab().path_params_list_state.selected().is_some() => {
+                        app.active_tab_mut().input_mode = InputMode::EditingPathParam;
+                        handled = true;
+                    }
                     0 => {
                         if !app.active_tab().params.is_empty()
                             && app.active_tab().params_list_state.selected().is_some()
@@ -789,6 +1706,10 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                             handled = true;
                         }
                     }
+                    1 if app.active_tab().headers_list_state.selected().is_some() => {
+                        app.edit_header(false);
+                        handled = true;
+                    }
                     2 => {
                         if app.active_tab().body_type == crate::app::BodyType::FormData
                             && !app.active_tab().form_data.is_empty()
@@ -796,6 +1717,12 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                         {
                             app.active_tab_mut().input_mode = InputMode::EditingFormKey;
                             handled = true;
+                        } else if app.active_tab().body_type == crate::app::BodyType::UrlEncoded
+                            && !app.active_tab().urlencoded.is_empty()
+                            && app.active_tab().urlencoded_list_state.selected().is_some()
+                        {
+                            app.active_tab_mut().input_mode = InputMode::EditingUrlEncodedKey;
+                            handled = true;
                         }
                     }
                     3 => {
@@ -812,6 +1739,10 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                             handled = true;
                         }
                     }
+                    5 => {
+                        app.edit_timeout();
+                        handled = true;
+                    }
                     _ => {}
                 }
 
@@ -821,10 +1752,7 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                     app.active_tab_mut().input_mode = InputMode::Editing;
                 }
             }
-            KeyCode::Char('%') => {
-                app.show_stress_modal = true;
-                app.active_tab_mut().input_mode = InputMode::EditingStressVUs;
-            }
+            KeyCode::Char('%') => app.open_stress_modal(""),
             KeyCode::Char('q') => {}
             KeyCode::Char('u') => {
                 // Trigger editing gRPC Service if in correct tab/mode
@@ -860,11 +1788,13 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 let tab = app.active_tab();
                 if tab.selected_tab == 3 && tab.auth_type == crate::app::AuthType::OAuth2 {
                     app.active_tab_mut().trigger_oauth_flow = true;
+                } else if tab.selected_tab == 5 {
+                    app.edit_timeout();
                 }
             }
             KeyCode::Tab => {
                 let current = app.active_tab().selected_tab;
-                app.active_tab_mut().selected_tab = (current + 1) % 5;
+                app.active_tab_mut().selected_tab = (current + 1) % 6;
             }
             KeyCode::Char('m') => {
                 app.cycle_method();
@@ -883,9 +1813,9 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 let selected_tab = tab.selected_tab;
 
                 if selected_tab == 0 {
-                    let len = tab.params.len();
+                    let len = tab.path_params.len() + tab.params.len();
                     if len > 0 {
-                        let current = tab.params_list_state.selected();
+                        let current = app.params_row();
                         let next = match current {
                             Some(i) => {
                                 if i >= len - 1 {
@@ -896,7 +1826,16 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                             }
                             None => 0,
                         };
-                        app.active_tab_mut().params_list_state.select(Some(next));
+                        app.select_params_row(next);
+                    }
+                } else if selected_tab == 1 {
+                    let len = app.header_row_count();
+                    if len > 0 {
+                        let next = match app.active_tab().headers_list_state.selected() {
+                            Some(i) if i + 1 < len => i + 1,
+                            _ => 0,
+                        };
+                        app.active_tab_mut().headers_list_state.select(Some(next));
                     }
                 } else if selected_tab == 2 && tab.body_type == crate::app::BodyType::FormData {
                     let len = tab.form_data.len();
@@ -914,6 +1853,24 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                         };
                         app.active_tab_mut().form_list_state.select(Some(next));
                     }
+                } else if selected_tab == 2 && tab.body_type == crate::app::BodyType::UrlEncoded {
+                    let len = tab.urlencoded.len();
+                    if len > 0 {
+                        let current = tab.urlencoded_list_state.selected();
+                        let next = match current {
+                            Some(i) => {
+                                if i >= len - 1 {
+                                    0
+                                } else {
+                                    i + 1
+                                }
+                            }
+                            None => 0,
+                        };
+                        app.active_tab_mut()
+                            .urlencoded_list_state
+                            .select(Some(next));
+                    }
                 } else if selected_tab == 4 {
                     let len = tab.extract_rules.len();
                     if len > 0 {
@@ -930,6 +1887,10 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                         };
                         app.active_tab_mut().extract_list_state.select(Some(next));
                     }
+                } else if selected_tab == 5 {
+                    let current = tab.settings_list_state.selected().unwrap_or(0);
+                    let next = (current + 1) % crate::net::timeouts::Phase::ALL.len();
+                    app.active_tab_mut().settings_list_state.select(Some(next));
                 } else {
                     app.next_item();
                 }
@@ -939,9 +1900,9 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 let selected_tab = tab.selected_tab;
 
                 if selected_tab == 0 {
-                    let len = tab.params.len();
+                    let len = tab.path_params.len() + tab.params.len();
                     if len > 0 {
-                        let current = tab.params_list_state.selected();
+                        let current = app.params_row();
                         let prev = match current {
                             Some(i) => {
                                 if i == 0 {
@@ -952,7 +1913,16 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                             }
                             None => 0,
                         };
-                        app.active_tab_mut().params_list_state.select(Some(prev));
+                        app.select_params_row(prev);
+                    }
+                } else if selected_tab == 1 {
+                    let len = app.header_row_count();
+                    if len > 0 {
+                        let prev = match app.active_tab().headers_list_state.selected() {
+                            Some(i) if i > 0 && i < len => i - 1,
+                            _ => len - 1,
+                        };
+                        app.active_tab_mut().headers_list_state.select(Some(prev));
                     }
                 } else if selected_tab == 2 && tab.body_type == crate::app::BodyType::FormData {
                     let len = tab.form_data.len();
@@ -970,6 +1940,24 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                         };
                         app.active_tab_mut().form_list_state.select(Some(prev));
                     }
+                } else if selected_tab == 2 && tab.body_type == crate::app::BodyType::UrlEncoded {
+                    let len = tab.urlencoded.len();
+                    if len > 0 {
+                        let current = tab.urlencoded_list_state.selected();
+                        let prev = match current {
+                            Some(i) => {
+                                if i == 0 {
+                                    len - 1
+                                } else {
+                                    i - 1
+                                }
+                            }
+                            None => 0,
+                        };
+                        app.active_tab_mut()
+                            .urlencoded_list_state
+                            .select(Some(prev));
+                    }
                 } else if selected_tab == 4 {
                     let len = tab.extract_rules.len();
                     if len > 0 {
@@ -986,6 +1974,12 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                         };
                         app.active_tab_mut().extract_list_state.select(Some(prev));
                     }
+                } else if selected_tab == 5 {
+                    let count = crate::net::timeouts::Phase::ALL.len();
+                    let current = tab.settings_list_state.selected().unwrap_or(0);
+                    app.active_tab_mut()
+                        .settings_list_state
+                        .select(Some((current + count - 1) % count));
                 } else {
                     app.previous_item();
                 }
@@ -1007,9 +2001,11 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                         .params
                         .push(("new_key".to_string(), "value".to_string()));
                     let len = app.active_tab().params.len();
-                    app.active_tab_mut().params_list_state.select(Some(len - 1));
+                    app.select_params_row(app.active_tab().path_params.len() + len - 1);
                     app.sync_params_to_url();
                     app.active_tab_mut().input_mode = InputMode::EditingParamKey;
+                } else if selected_tab == 1 {
+                    app.edit_header(true);
                 } else if selected_tab == 2 && body_type == crate::app::BodyType::FormData {
                     app.active_tab_mut().form_data.push((
                         "key".to_string(),
@@ -1019,6 +2015,15 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                     let len = app.active_tab().form_data.len();
                     app.active_tab_mut().form_list_state.select(Some(len - 1));
                     app.active_tab_mut().input_mode = InputMode::EditingFormKey;
+                } else if selected_tab == 2 && body_type == crate::app::BodyType::UrlEncoded {
+                    app.active_tab_mut()
+                        .urlencoded
+                        .push(("key".to_string(), "val".to_string()));
+                    let len = app.active_tab().urlencoded.len();
+                    app.active_tab_mut()
+                        .urlencoded_list_state
+                        .select(Some(len - 1));
+                    app.active_tab_mut().input_mode = InputMode::EditingUrlEncodedKey;
                 } else if selected_tab == 4 {
                     app.active_tab_mut()
                         .extract_rules
@@ -1039,7 +2044,12 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                     (tab.selected_tab, tab.body_type)
                 };
 
-                if selected_tab == 0 {
+                if selected_tab == 0 && app.active_tab().path_params_list_state.selected().is_some()
+                {
+                    app.show_notification(
+                        "Path params come from the URL, edit the path to remove one".to_string(),
+                    );
+                } else if selected_tab == 0 {
                     let i = app.active_tab().params_list_state.selected();
                     let len = app.active_tab().params.len();
                     if let Some(i) = i
@@ -1054,25 +2064,45 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                             app.active_tab_mut().params_list_state.select(None);
                         } else if i >= new_len {
                             app.active_tab_mut()
-                                .params_list_state
+                                .params_list_state
+                                .select(Some(new_len - 1));
+                        }
+                    }
+                } else if selected_tab == 1 {
+                    app.delete_selected_header();
+                } else if selected_tab == 2 && body_type == crate::app::BodyType::FormData {
+                    let i = app.active_tab().form_list_state.selected();
+                    let len = app.active_tab().form_data.len();
+                    if let Some(i) = i
+                        && len > 0
+                        && i < len
+                    {
+                        app.active_tab_mut().form_data.remove(i);
+
+                        let new_len = app.active_tab().form_data.len();
+                        if new_len == 0 {
+                            app.active_tab_mut().form_list_state.select(None);
+                        } else if i >= new_len {
+                            app.active_tab_mut()
+                                .form_list_state
                                 .select(Some(new_len - 1));
                         }
                     }
-                } else if selected_tab == 2 && body_type == crate::app::BodyType::FormData {
-                    let i = app.active_tab().form_list_state.selected();
-                    let len = app.active_tab().form_data.len();
+                } else if selected_tab == 2 && body_type == crate::app::BodyType::UrlEncoded {
+                    let i = app.active_tab().urlencoded_list_state.selected();
+                    let len = app.active_tab().urlencoded.len();
                     if let Some(i) = i
                         && len > 0
                         && i < len
                     {
-                        app.active_tab_mut().form_data.remove(i);
+                        app.active_tab_mut().urlencoded.remove(i);
 
-                        let new_len = app.active_tab().form_data.len();
+                        let new_len = app.active_tab().urlencoded.len();
                         if new_len == 0 {
-                            app.active_tab_mut().form_list_state.select(None);
+                            app.active_tab_mut().urlencoded_list_state.select(None);
                         } else if i >= new_len {
                             app.active_tab_mut()
-                                .form_list_state
+                                .urlencoded_list_state
                                 .select(Some(new_len - 1));
                         }
                     }
@@ -1110,7 +2140,9 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                     )
                 };
 
-                if selected_tab == 2 && body_type == crate::app::BodyType::FormData {
+                if selected_tab == 1 {
+                    app.toggle_selected_header();
+                } else if selected_tab == 2 && body_type == crate::app::BodyType::FormData {
                     if let Some(i) = i_form
                         && let Some(row) = app.active_tab_mut().form_data.get_mut(i)
                     {
@@ -1135,6 +2167,12 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
             KeyCode::Char('z') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
                 app.zen_mode = !app.zen_mode;
             }
+            KeyCode::Char('o') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.open_finder();
+            }
+            KeyCode::Char('g') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.open_history_view("");
+            }
             KeyCode::Char('n') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
                 app.add_tab();
             }
@@ -1174,7 +2212,8 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                     // Cycle Body Type
                     let new_type = match tab.body_type {
                         crate::app::BodyType::Raw => crate::app::BodyType::FormData,
-                        crate::app::BodyType::FormData => crate::app::BodyType::GraphQL,
+                        crate::app::BodyType::FormData => crate::app::BodyType::UrlEncoded,
+                        crate::app::BodyType::UrlEncoded => crate::app::BodyType::GraphQL,
                         crate::app::BodyType::GraphQL => crate::app::BodyType::Grpc,
                         crate::app::BodyType::Grpc => crate::app::BodyType::Raw,
                     };
@@ -1198,58 +2237,46 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 let cmd = app.generate_curl_command();
                 app.copy_to_clipboard(cmd);
             }
+            KeyCode::Char('E') => app.start_rename_tab(),
             KeyCode::Char('I') => {
                 // Import from cURL command
                 app.curl_import_input.clear();
                 app.active_tab_mut().input_mode = InputMode::ImportCurl;
             }
             KeyCode::Char('G') => {
-                let code = app.generate_python_code();
-                app.copy_to_clipboard(code);
-                app.show_notification("Copied Python Code".to_string());
-            }
-            KeyCode::Char('J') => {
-                let code = app.generate_javascript_code();
-                app.copy_to_clipboard(code);
-                app.show_notification("Copied JS Code".to_string());
+                app.open_codegen_picker();
             }
             KeyCode::Char('C') => {
                 // Copy response output to clipboard
                 app.copy_response();
             }
-            KeyCode::Char('O') => {
-                let code = app.generate_go_code();
-                app.copy_to_clipboard(code);
-                app.show_notification("Copied Go Code".to_string());
-            }
-            KeyCode::Char('R') => {
-                let code = app.generate_rust_code();
-                app.copy_to_clipboard(code);
-                app.show_notification("Copied Rust Code".to_string());
-            }
-
-            KeyCode::Char('B') => {
-                let code = app.generate_ruby_code();
-                app.copy_to_clipboard(code);
-                app.show_notification("Copied Ruby Code".to_string());
+            KeyCode::Char('M') => {
+                app.generate_docs();
             }
-            KeyCode::Char('E') => {
-                let code = app.generate_php_code();
-                app.copy_to_clipboard(code);
-                app.show_notification("Copied PHP Code".to_string());
+            KeyCode::Char('n') if !app.active_tab().find_query.is_empty() => {
+                app.active_tab_mut().jump_to_match(true);
             }
-            KeyCode::Char('S') => {
-                let code = app.generate_csharp_code();
-                app.copy_to_clipboard(code);
-                app.show_notification("Copied C# Code".to_string());
+            KeyCode::Char('N') if !app.active_tab().find_query.is_empty() => {
+                app.active_tab_mut().jump_to_match(false);
             }
-            KeyCode::Char('M') => {
-                app.generate_docs();
+            KeyCode::Char('N') => {
+                app.show_notification_history = true;
+                app.notification_history_scroll = 0;
             }
+            KeyCode::Char('R') => app.open_timeline(),
+            KeyCode::Char('B') => app.open_hex_view(false),
+            KeyCode::Char('W') => app.preview_request(),
+            KeyCode::Char('U') => app.toggle_undecoded(),
+            KeyCode::Char('A') => app.run_audit(),
+            KeyCode::Char('=') => app.format_body(false),
+            KeyCode::Char('-') => app.format_body(true),
+            KeyCode::Char('J') => app.toggle_response_raw(),
             KeyCode::Char('L') => {
-                // Only if in gRPC mode - list services via reflection
+                // In gRPC mode it lists services via reflection
                 if app.active_tab().body_type == crate::app::BodyType::Grpc {
                     app.active_tab_mut().should_list_grpc_services = true;
+                } else {
+                    app.open_ndjson();
                 }
             }
             KeyCode::Char('i') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -1265,12 +2292,23 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 }
             }
             KeyCode::Esc => {
-                app.popup_message = None;
+                if !app.active_tab().find_query.is_empty() {
+                    app.active_tab_mut().find_query.clear();
+                } else {
+                    app.notifications.dismiss();
+                }
             }
             KeyCode::Char('/') => {
                 app.active_tab_mut().input_mode = InputMode::Search;
                 app.active_tab_mut().search_query.clear();
             }
+            KeyCode::Char('O') => app.toggle_follow_stream(),
+            KeyCode::Char('F') => {
+                let tab = app.active_tab_mut();
+                tab.input_mode = InputMode::FindInResponse;
+                tab.find_query.clear();
+                tab.find_index = 0;
+            }
             KeyCode::Char('f') => {
                 if key_event.modifiers.contains(KeyModifiers::CONTROL) {
                     app.show_sidebar_filter = true;
@@ -1281,15 +2319,25 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 }
             }
             KeyCode::Char('y') => {
-                let tab = app.active_tab();
-                if let Some(selected_idx) = tab.json_list_state.selected()
-                    && let Some(entries) = &tab.response_json
-                {
-                    let filter = &tab.search_query;
-                    let path = crate::ui::get_json_path(entries, selected_idx, filter);
+                if let Some(path) = app.selected_json_path() {
                     app.copy_to_clipboard(path);
                 }
             }
+            KeyCode::Char('Z') if app.active_tab().response_json.is_some() => {
+                app.toggle_json_flat();
+            }
+            KeyCode::Char('o') => app.sort_csv_column(),
+            KeyCode::Char('z') => app.cycle_image_zoom(),
+            KeyCode::Char('K') => app.toggle_docs(),
+            KeyCode::Char('v') => {
+                app.copy_json_value();
+            }
+            KeyCode::Char('Y') => {
+                app.copy_json_subtree();
+            }
+            KeyCode::Char('X') => {
+                app.export_json_subtree();
+            }
             KeyCode::Char('Q') => {
                 if app.active_tab().selected_tab == 2
                     && app.active_tab().body_type == crate::app::BodyType::GraphQL
@@ -1298,10 +2346,13 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 }
             }
             KeyCode::Char('V') => {
+                // On a GraphQL body it edits the variables
                 if app.active_tab().selected_tab == 2
                     && app.active_tab().body_type == crate::app::BodyType::GraphQL
                 {
                     app.editor_mode = crate::app::EditorMode::GraphQLVariables;
+                } else {
+                    app.cycle_treat_as();
                 }
             }
 
@@ -1336,73 +2387,43 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
             KeyCode::Tab => {
                 app.cycle_method();
             }
-            KeyCode::Left => {
-                let current = app.active_tab().url_cursor_index;
-                if current > 0 {
-                    app.active_tab_mut().url_cursor_index = current - 1;
-                }
-            }
-            KeyCode::Right => {
-                let current = app.active_tab().url_cursor_index;
-                let len = app.active_tab().url.len();
-                if current < len {
-                    app.active_tab_mut().url_cursor_index = current + 1;
-                }
-            }
-            KeyCode::Home => {
-                app.active_tab_mut().url_cursor_index = 0;
-            }
-            KeyCode::End => {
-                let len = app.active_tab().url.len();
-                app.active_tab_mut().url_cursor_index = len;
-            }
-            KeyCode::Char(c) => {
-                let idx = app.active_tab().url_cursor_index;
-                app.active_tab_mut().url.insert(idx, c);
-                app.active_tab_mut().url_cursor_index += 1;
-            }
-            KeyCode::Backspace => {
-                let idx = app.active_tab().url_cursor_index;
-                if idx > 0 {
-                    app.active_tab_mut().url.remove(idx - 1);
-                    app.active_tab_mut().url_cursor_index -= 1;
-                }
-            }
-            KeyCode::Delete => {
-                let idx = app.active_tab().url_cursor_index;
-                let len = app.active_tab().url.len();
-                if idx < len {
-                    app.active_tab_mut().url.remove(idx);
-                }
-            }
             KeyCode::Esc => {
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
-            _ => {}
+            _ => {
+                edit_input(&key_event, app);
+            }
         },
         InputMode::EditingAuth => match key_event.code {
             KeyCode::Enter | KeyCode::Esc => {
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
-            KeyCode::Char(c) => {
-                app.active_tab_mut().auth_token.push(c);
+            _ => {
+                edit_input(&key_event, app);
             }
-            KeyCode::Backspace => {
-                app.active_tab_mut().auth_token.pop();
+        },
+        InputMode::FindInResponse => match key_event.code {
+            KeyCode::Enter => {
+                let tab = app.active_tab_mut();
+                tab.input_mode = InputMode::Normal;
+                tab.jump_to_first_match();
+            }
+            KeyCode::Esc => {
+                let tab = app.active_tab_mut();
+                tab.input_mode = InputMode::Normal;
+                tab.find_query.clear();
+            }
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::Search => match key_event.code {
             KeyCode::Enter | KeyCode::Esc => {
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
-            KeyCode::Char(c) => {
-                app.active_tab_mut().search_query.push(c);
-            }
-            KeyCode::Backspace => {
-                app.active_tab_mut().search_query.pop();
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::EditingParamKey => match key_event.code {
             KeyCode::Enter | KeyCode::Tab => {
@@ -1411,25 +2432,9 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
             KeyCode::Esc => {
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
-            KeyCode::Char(c) => {
-                let i = app.active_tab().params_list_state.selected();
-                if let Some(i) = i {
-                    if let Some((k, _)) = app.active_tab_mut().params.get_mut(i) {
-                        k.push(c);
-                    }
-                    app.sync_params_to_url();
-                }
-            }
-            KeyCode::Backspace => {
-                let i = app.active_tab().params_list_state.selected();
-                if let Some(i) = i {
-                    if let Some((k, _)) = app.active_tab_mut().params.get_mut(i) {
-                        k.pop();
-                    }
-                    app.sync_params_to_url();
-                }
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::EditingParamValue => match key_event.code {
             KeyCode::Enter | KeyCode::Tab => {
@@ -1438,25 +2443,36 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
             KeyCode::Esc => {
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
-            KeyCode::Char(c) => {
-                let i = app.active_tab().params_list_state.selected();
-                if let Some(i) = i {
-                    if let Some((_, v)) = app.active_tab_mut().params.get_mut(i) {
-                        v.push(c);
-                    }
-                    app.sync_params_to_url();
-                }
+            _ => {
+                edit_input(&key_event, app);
             }
-            KeyCode::Backspace => {
-                let i = app.active_tab().params_list_state.selected();
-                if let Some(i) = i {
-                    if let Some((_, v)) = app.active_tab_mut().params.get_mut(i) {
-                        v.pop();
-                    }
-                    app.sync_params_to_url();
-                }
+        },
+        InputMode::EditingPathParam => match key_event.code {
+            KeyCode::Enter | KeyCode::Tab | KeyCode::Esc => {
+                app.active_tab_mut().input_mode = InputMode::Normal;
+            }
+            _ => {
+                edit_input(&key_event, app);
+            }
+        },
+        InputMode::EditingHeaderKey => match key_event.code {
+            KeyCode::Enter | KeyCode::Tab => {
+                app.active_tab_mut().input_mode = InputMode::EditingHeaderValue;
+            }
+            KeyCode::Esc => app.cancel_header_draft(),
+            _ => {
+                edit_input(&key_event, app);
+            }
+        },
+        InputMode::EditingHeaderValue => match key_event.code {
+            KeyCode::Enter => app.commit_header_draft(),
+            KeyCode::Tab => {
+                app.active_tab_mut().input_mode = InputMode::EditingHeaderKey;
+            }
+            KeyCode::Esc => app.cancel_header_draft(),
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::EditingBasicAuthUser => match key_event.code {
             KeyCode::Enter | KeyCode::Esc => {
@@ -1465,13 +2481,9 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
             KeyCode::Tab => {
                 app.active_tab_mut().input_mode = InputMode::EditingBasicAuthPass;
             }
-            KeyCode::Char(c) => {
-                app.active_tab_mut().basic_auth_user.push(c);
-            }
-            KeyCode::Backspace => {
-                app.active_tab_mut().basic_auth_user.pop();
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::EditingBasicAuthPass => match key_event.code {
             KeyCode::Enter | KeyCode::Esc => {
@@ -1480,138 +2492,97 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
             KeyCode::Tab => {
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
-            KeyCode::Char(c) => {
-                app.active_tab_mut().basic_auth_pass.push(c);
-            }
-            KeyCode::Backspace => {
-                app.active_tab_mut().basic_auth_pass.pop();
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
 
         InputMode::EditingOAuthUrl => match key_event.code {
             KeyCode::Enter | KeyCode::Esc => app.active_tab_mut().input_mode = InputMode::Normal,
-            KeyCode::Char(c) => app.active_tab_mut().oauth_auth_url.push(c),
-            KeyCode::Backspace => {
-                app.active_tab_mut().oauth_auth_url.pop();
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::EditingOAuthTokenUrl => match key_event.code {
             KeyCode::Enter | KeyCode::Esc => app.active_tab_mut().input_mode = InputMode::Normal,
-            KeyCode::Char(c) => app.active_tab_mut().oauth_token_url.push(c),
-            KeyCode::Backspace => {
-                app.active_tab_mut().oauth_token_url.pop();
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::EditingOAuthClientId => match key_event.code {
             KeyCode::Enter | KeyCode::Esc => app.active_tab_mut().input_mode = InputMode::Normal,
-            KeyCode::Char(c) => app.active_tab_mut().oauth_client_id.push(c),
-            KeyCode::Backspace => {
-                app.active_tab_mut().oauth_client_id.pop();
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::EditingChainKey => match key_event.code {
             KeyCode::Enter | KeyCode::Tab => {
                 app.active_tab_mut().input_mode = InputMode::EditingChainPath
             }
             KeyCode::Esc => app.active_tab_mut().input_mode = InputMode::Normal,
-            KeyCode::Char(c) => {
-                let i = app.active_tab().extract_list_state.selected();
-                if let Some(i) = i
-                    && let Some(rule) = app.active_tab_mut().extract_rules.get_mut(i)
-                {
-                    rule.0.push(c);
-                }
-            }
-            KeyCode::Backspace => {
-                let i = app.active_tab().extract_list_state.selected();
-                if let Some(i) = i
-                    && let Some(rule) = app.active_tab_mut().extract_rules.get_mut(i)
-                {
-                    rule.0.pop();
-                }
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::EditingChainPath => match key_event.code {
             KeyCode::Enter | KeyCode::Tab => app.active_tab_mut().input_mode = InputMode::Normal,
             KeyCode::Esc => app.active_tab_mut().input_mode = InputMode::Normal,
-            KeyCode::Char(c) => {
-                let i = app.active_tab().extract_list_state.selected();
-                if let Some(i) = i
-                    && let Some(rule) = app.active_tab_mut().extract_rules.get_mut(i)
-                {
-                    rule.1.push(c);
-                }
-            }
-            KeyCode::Backspace => {
-                let i = app.active_tab().extract_list_state.selected();
-                if let Some(i) = i
-                    && let Some(rule) = app.active_tab_mut().extract_rules.get_mut(i)
-                {
-                    rule.1.pop();
-                }
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::EditingFormKey => match key_event.code {
             KeyCode::Enter | KeyCode::Tab => {
                 app.active_tab_mut().input_mode = InputMode::EditingFormValue
             }
             KeyCode::Esc => app.active_tab_mut().input_mode = InputMode::Normal,
-            KeyCode::Char(c) => {
-                let i = app.active_tab().form_list_state.selected();
-                if let Some(i) = i
-                    && let Some(row) = app.active_tab_mut().form_data.get_mut(i)
-                {
-                    row.0.push(c);
-                }
-            }
-            KeyCode::Backspace => {
-                let i = app.active_tab().form_list_state.selected();
-                if let Some(i) = i
-                    && let Some(row) = app.active_tab_mut().form_data.get_mut(i)
-                {
-                    row.0.pop();
-                }
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         InputMode::EditingFormValue => match key_event.code {
             KeyCode::Enter | KeyCode::Tab => app.active_tab_mut().input_mode = InputMode::Normal,
             KeyCode::Esc => app.active_tab_mut().input_mode = InputMode::Normal,
-            KeyCode::Char(c) => {
-                let i = app.active_tab().form_list_state.selected();
-                if let Some(i) = i
-                    && let Some(row) = app.active_tab_mut().form_data.get_mut(i)
-                {
-                    row.1.push(c);
-                }
+            _ => {
+                edit_input(&key_event, app);
             }
-            KeyCode::Backspace => {
-                let i = app.active_tab().form_list_state.selected();
-                if let Some(i) = i
-                    && let Some(row) = app.active_tab_mut().form_data.get_mut(i)
-                {
-                    row.1.pop();
-                }
+        },
+        InputMode::EditingUrlEncodedKey => match key_event.code {
+            KeyCode::Enter | KeyCode::Tab => {
+                app.active_tab_mut().input_mode = InputMode::EditingUrlEncodedValue
+            }
+            KeyCode::Esc => app.active_tab_mut().input_mode = InputMode::Normal,
+            _ => {
+                edit_input(&key_event, app);
+            }
+        },
+        InputMode::EditingUrlEncodedValue => match key_event.code {
+            KeyCode::Enter | KeyCode::Tab => app.active_tab_mut().input_mode = InputMode::Normal,
+            KeyCode::Esc => app.active_tab_mut().input_mode = InputMode::Normal,
+            _ => {
+                edit_input(&key_event, app);
             }
-            _ => {}
         },
         // WebSocket input modes are handled earlier in this function
         InputMode::EditingWsUrl | InputMode::EditingWsMessage => {}
+        InputMode::SaveHistory => match key_event.code {
+            KeyCode::Enter => app.confirm_save_history(),
+            KeyCode::Esc => app.close_save_history(),
+            KeyCode::Tab => app.complete_save_history_collection(),
+            _ => {
+                edit_input(&key_event, app);
+            }
+        },
+
         InputMode::ImportCurl => match key_event.code {
             KeyCode::Enter => {
                 let curl_cmd = app.curl_import_input.clone();
                 match app.import_from_curl(&curl_cmd) {
                     Ok(()) => {
-                        app.show_notification("cURL command imported successfully!".to_string());
+                        app.show_success("cURL command imported successfully!".to_string());
                     }
                     Err(e) => {
-                        app.popup_message = Some(format!("Import error: {}", e));
+                        app.show_error(format!("Import error: {}", e));
                     }
                 }
                 app.curl_import_input.clear();
@@ -1621,33 +2592,336 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) {
                 app.curl_import_input.clear();
                 app.active_tab_mut().input_mode = InputMode::Normal;
             }
-            KeyCode::Char(c) => {
-                app.curl_import_input.push(c);
+            _ => {
+                edit_input(&key_event, app);
+            }
+        },
+    }
+}
+
+/// Runs `edit` on the text the current input mode edits and its cursor.
+/// None when the mode has no text input (Normal, or a list row that's gone).
+fn with_input<T>(app: &mut App, edit: impl FnOnce(&mut String, &mut usize) -> T) -> Option<T> {
+    let mode = app.active_tab().input_mode;
+    let mut cursor = app.input_cursor;
+    let tab = &mut app.tabs[app.active_tab];
+    let text = match mode {
+        InputMode::Normal | InputMode::EditingStressTarget => return None,
+        InputMode::Editing => {
+            cursor = tab.url_cursor_index;
+            &mut tab.url
+        }
+        InputMode::EditingAuth => &mut tab.auth_token,
+        InputMode::EditingBasicAuthUser => &mut tab.basic_auth_user,
+        InputMode::EditingBasicAuthPass => &mut tab.basic_auth_pass,
+        InputMode::EditingOAuthUrl => &mut tab.oauth_auth_url,
+        InputMode::EditingOAuthTokenUrl => &mut tab.oauth_token_url,
+        InputMode::EditingOAuthClientId => &mut tab.oauth_client_id,
+        InputMode::EditingParamKey => &mut tab.params.get_mut(tab.params_list_state.selected()?)?.0,
+        InputMode::EditingParamValue => {
+            &mut tab.params.get_mut(tab.params_list_state.selected()?)?.1
+        }
+        InputMode::EditingPathParam => {
+            &mut tab
+                .path_params
+                .get_mut(tab.path_params_list_state.selected()?)?
+                .1
+        }
+        InputMode::EditingHeaderKey => &mut tab.header_draft.as_mut()?.name,
+        InputMode::EditingHeaderValue => &mut tab.header_draft.as_mut()?.value,
+        InputMode::EditingChainKey => {
+            &mut tab
+                .extract_rules
+                .get_mut(tab.extract_list_state.selected()?)?
+                .0
+        }
+        InputMode::EditingChainPath => {
+            &mut tab
+                .extract_rules
+                .get_mut(tab.extract_list_state.selected()?)?
+                .1
+        }
+        InputMode::EditingFormKey => &mut tab.form_data.get_mut(tab.form_list_state.selected()?)?.0,
+        InputMode::EditingFormValue => {
+            &mut tab.form_data.get_mut(tab.form_list_state.selected()?)?.1
+        }
+        InputMode::EditingUrlEncodedKey => {
+            &mut tab
+                .urlencoded
+                .get_mut(tab.urlencoded_list_state.selected()?)?
+                .0
+        }
+        InputMode::EditingUrlEncodedValue => {
+            &mut tab
+                .urlencoded
+                .get_mut(tab.urlencoded_list_state.selected()?)?
+                .1
+        }
+        InputMode::Search => &mut tab.search_query,
+        InputMode::FindInResponse => &mut tab.find_query,
+        InputMode::EditingWsUrl => &mut tab.ws_url,
+        InputMode::EditingWsMessage => &mut tab.ws_message_input,
+        InputMode::EditingGrpcService => &mut tab.grpc_service,
+        InputMode::EditingGrpcProto => &mut tab.grpc_proto_path,
+        InputMode::FilteringSidebar => &mut app.sidebar_filter,
+        InputMode::CommandPalette => &mut app.command_query,
+        InputMode::CodegenPicker => &mut app.codegen_query,
+        InputMode::SnippetPicker => &mut app.snippet_query,
+        InputMode::Finder => &mut app.finder_query,
+        InputMode::HistoryView => &mut app.history_query,
+        InputMode::Command => &mut app.command_input,
+        InputMode::EditingStressVUs => &mut app.stress_vus_input,
+        InputMode::EditingStressDuration => &mut app.stress_duration_input,
+        InputMode::EditingStressStages => &mut tab.stress_stages,
+        InputMode::EditingStressThresholds => &mut app.stress_thresholds_input,
+        InputMode::EditingSentinelInterval => &mut app.sentinel_interval_input,
+        InputMode::EditingTimeout => &mut app.timeout_input,
+        InputMode::ImportCurl => &mut app.curl_import_input,
+        InputMode::SaveHistory => &mut app.save_history_input,
+    };
+    let result = edit(text, &mut cursor);
+    if mode == InputMode::Editing {
+        app.active_tab_mut().url_cursor_index = cursor;
+    } else {
+        app.input_cursor = cursor;
+    }
+    Some(result)
+}
+
+/// Keeps what depends on an input in step after its text changed
+fn input_changed(app: &mut App) {
+    match app.active_tab().input_mode {
+        InputMode::EditingParamKey | InputMode::EditingParamValue => app.sync_params_to_url(),
+        InputMode::FindInResponse => app.active_tab_mut().jump_to_first_match(),
+        InputMode::CommandPalette => app.command_index = 0,
+        InputMode::CodegenPicker => app.codegen_index = 0,
+        InputMode::SnippetPicker => app.snippet_index = 0,
+        InputMode::Finder => app.finder_index = 0,
+        InputMode::HistoryView => {
+            app.history_index = 0;
+            app.history_marked.clear();
+        }
+        _ => {}
+    }
+}
+
+fn is_numeric_input(mode: InputMode) -> bool {
+    matches!(
+        mode,
+        InputMode::EditingStressVUs
+            | InputMode::EditingStressDuration
+            | InputMode::EditingSentinelInterval
+    )
+}
+
+/// Readline-style editing of the focused input: typing, Backspace/Delete, arrows,
+/// Home/End, Ctrl+A/E, word moves and deletes, Ctrl+U/K. Returns false for keys that
+/// aren't editing keys (or outside inputs) so the caller can handle them.
+fn edit_input(key_event: &KeyEvent, app: &mut App) -> bool {
+    let Some(edit) = Edit::from_key(key_event) else {
+        return false;
+    };
+    if let Edit::Insert(c) = edit
+        && is_numeric_input(app.active_tab().input_mode)
+        && !c.is_ascii_digit()
+    {
+        return true;
+    }
+    match with_input(app, |text, cursor| edit.apply(text, cursor)) {
+        Some(changed) => {
+            if changed {
+                input_changed(app);
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// Collapses pasted text for a one-line field: a trailing newline is dropped and lines
+/// are joined, so a token or URL that got wrapped when copied comes back in one piece
+fn single_line(text: &str) -> String {
+    text.trim_end_matches(['\r', '\n'])
+        .replace(['\r', '\n'], "")
+}
+
+/// Inserts pasted text into the field being edited, the way typing it would.
+/// Terminals with bracketed paste deliver the whole string in one event; Ctrl+V reads
+/// the system clipboard and lands here too. Returns false if nothing takes text input.
+/// Keys while the hex pane is open; `/` starts a search that takes every key until
+/// Enter or Esc
+fn handle_hex_view(key_event: KeyEvent, app: &mut App) {
+    use crate::features::hex;
+
+    let rows = app.hex_bytes().map_or(0, |b| hex::rows(b.len()));
+    let Some(view) = app.hex_view.as_mut() else {
+        return;
+    };
+    if view.searching {
+        match key_event.code {
+            KeyCode::Enter => {
+                view.searching = false;
+                app.hex_search();
             }
+            KeyCode::Esc => view.searching = false,
             KeyCode::Backspace => {
-                app.curl_import_input.pop();
+                view.query.pop();
             }
+            KeyCode::Char(c) => view.query.push(c),
             _ => {}
-        },
+        }
+        return;
+    }
+    let last = rows.saturating_sub(1);
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('B') => app.hex_view = None,
+        KeyCode::Char('j') | KeyCode::Down => view.scroll = (view.scroll + 1).min(last),
+        KeyCode::Char('k') | KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+        KeyCode::PageDown | KeyCode::Char('d') => view.scroll = (view.scroll + 16).min(last),
+        KeyCode::PageUp | KeyCode::Char('u') => view.scroll = view.scroll.saturating_sub(16),
+        KeyCode::Char('g') | KeyCode::Home => view.scroll = 0,
+        KeyCode::Char('G') | KeyCode::End => view.scroll = last,
+        KeyCode::Char('/') => {
+            view.query.clear();
+            view.matches.clear();
+            view.searching = true;
+        }
+        KeyCode::Char('n') => view.step(true),
+        KeyCode::Char('N') => view.step(false),
+        KeyCode::Tab | KeyCode::Char('r') => app.toggle_hex_source(),
+        KeyCode::Char('U') => app.toggle_undecoded(),
+        KeyCode::Char('c') => {
+            let dump = hex::dump(app.hex_bytes().unwrap_or_default());
+            app.copy_to_clipboard(dump);
+        }
+        _ => {}
+    }
+}
+
+fn handle_proto_view(key_event: KeyEvent, app: &mut App) {
+    use crate::features::protos::Section;
+
+    let Some(view) = app.proto_view.as_mut() else {
+        return;
+    };
+    if let Some(input) = view.adding.as_mut() {
+        match key_event.code {
+            KeyCode::Enter => app.add_proto_dir(),
+            KeyCode::Esc => view.adding = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+        return;
+    }
+    let len = app.proto_registry.list(view.section).len();
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.proto_view = None,
+        KeyCode::Tab => {
+            view.section = match view.section {
+                Section::Roots => Section::ImportPaths,
+                Section::ImportPaths => Section::Roots,
+            };
+            view.selected = 0;
+        }
+        KeyCode::Char('j') | KeyCode::Down if view.selected + 1 < len => view.selected += 1,
+        KeyCode::Char('k') | KeyCode::Up => view.selected = view.selected.saturating_sub(1),
+        KeyCode::Char('a') => view.adding = Some(String::new()),
+        KeyCode::Char('d') | KeyCode::Delete => app.remove_proto_dir(),
+        KeyCode::Char('r') => app.open_proto_view(),
+        _ => {}
+    }
+}
+
+pub fn handle_paste(text: &str, app: &mut App) -> bool {
+    if app.guard_prompt.is_some()
+        || app.send_warnings.is_some()
+        || app.sync_prompt.is_some()
+        || app.bundle_prompt.is_some()
+    {
+        return false;
+    }
+    if let Some(prompt) = app.passphrase_prompt.as_mut() {
+        prompt.input.push_str(&single_line(text));
+        return true;
+    }
+    if let Some(view) = app.hex_view.as_mut() {
+        if view.searching {
+            view.query.push_str(&single_line(text));
+        }
+        return view.searching;
+    }
+    if let Some(input) = app.proto_view.as_mut().and_then(|v| v.adding.as_mut()) {
+        input.push_str(&single_line(text));
+        return true;
+    }
+    if app.help_search_active {
+        app.help_query.push_str(&single_line(text));
+        app.help_scroll = 0;
+        return true;
+    }
+    if app.mock_mode
+        && let Some(editor) = app.mock_editor.as_mut()
+    {
+        let multiline = editor.field.is_multiline();
+        return match editor.field_mut() {
+            Some(field) if multiline => {
+                field.push_str(&text.replace("\r\n", "\n"));
+                true
+            }
+            Some(field) => {
+                field.push_str(&single_line(text));
+                true
+            }
+            None => false,
+        };
+    }
+
+    let mode = app.active_tab().input_mode;
+    let pasted = if mode == InputMode::ImportCurl {
+        // Multi-line curl commands with `\` continuations are what this box is for
+        text.replace("\r\n", "\n")
+    } else if is_numeric_input(mode) {
+        text.chars().filter(|c| c.is_ascii_digit()).collect()
+    } else {
+        single_line(text)
+    };
+    match with_input(app, |field, cursor| {
+        line_edit::insert_str(field, cursor, &pasted)
+    }) {
+        Some(()) => {
+            input_changed(app);
+            true
+        }
+        // A list row being edited that's gone still swallows the paste
+        None => mode != InputMode::Normal,
     }
 }
 
+/// Lines the response moves per wheel notch; lists move one row
+const WHEEL_LINES: isize = 3;
+
+/// Clicks land on whatever the last frame drew there (`App::screen_areas`): a pane
+/// takes focus, a list row is selected (opened or expanded on a second click), a
+/// tab title switches to it. The wheel scrolls what's under the pointer, and the
+/// sidebar's edge can be dragged to resize it.
 pub fn handle_mouse_event(
     mouse_event: ratatui::crossterm::event::MouseEvent,
     app: &mut crate::app::App,
 ) {
-    use ratatui::crossterm::event::MouseEventKind;
-
-    // Basic mouse support: Panel Focus
-    // We roughly know the layout regions:
-    // Sidebar: x < 20%
-    // Main: x > 20%
+    use crate::ui::layout;
+    use ratatui::crossterm::event::{MouseButton, MouseEventKind};
+    use ratatui::layout::Position;
 
-    // Since we don't have access to the exact layout rects here easily (they are computed in ui.rs),
-    // we can use approximate percentages based on typical terminal width.
-    // For a robust implementation, the UI would need to store the last computed layout Rects in the App state.
+    let at = Position::new(mouse_event.column, mouse_event.row);
+    let wheel = match mouse_event.kind {
+        MouseEventKind::ScrollDown => 1,
+        MouseEventKind::ScrollUp => -1,
+        _ => 0,
+    };
 
-    // For now, let's implement scrolling for the help menu which we know is a modal
     if app.show_diff_view {
         match mouse_event.kind {
             MouseEventKind::ScrollDown => {
@@ -1678,59 +2952,134 @@ pub fn handle_mouse_event(
         return;
     }
 
-    // Scroll JSON response
-    match mouse_event.kind {
-        MouseEventKind::ScrollDown => {
-            // Scroll JSON list if present
-            // Note: This matches search result items count if flattened, not just response_json len.
-            // But getting exact item count here is hard without re-flattening.
-            // We can just increment selection safely.
-
-            let current = app.active_tab().json_list_state.selected().unwrap_or(0);
-            app.active_tab_mut()
-                .json_list_state
-                .select(Some(current + 1));
-        }
-        MouseEventKind::ScrollUp => {
-            let current = app.active_tab().json_list_state.selected().unwrap_or(0);
-            if current > 0 {
-                app.active_tab_mut()
-                    .json_list_state
-                    .select(Some(current - 1));
-            }
-        }
-        MouseEventKind::Down(ratatui::crossterm::event::MouseButton::Left) => {
-            let x = mouse_event.column;
-            let y = mouse_event.row;
-
-            // Rough hit testing
-            // Sidebar is approx 20% width
-            let term_width = ratatui::crossterm::terminal::size().unwrap_or((80, 24)).0;
-            let sidebar_width = if app.active_sidebar {
-                (term_width as f32 * 0.2) as u16
-            } else {
-                0
-            };
+    if app.show_history_view {
+        if wheel != 0 {
+            app.move_history_view(wheel > 0);
+        }
+        return;
+    }
 
-            if x < sidebar_width {
-                // Clicked sidebar
-                // app.active_sidebar = true; // It's already active
-                // Select item at row? Hard without knowing list offset
-            } else {
-                // Clicked main area
-                // Check tabs area (top ~3 rows)
-                if y < 3 {
-                    // Clicked tabs
-                    // Calculate tab width
-                    let tab_width = (term_width - sidebar_width) / 5; // 5 tabs
-                    let relative_x = x - sidebar_width;
-                    let tab_idx = (relative_x / tab_width) as usize;
-                    if tab_idx < 5 {
-                        app.active_tab_mut().selected_tab = tab_idx;
-                    }
+    if app.theme_picker.is_some() {
+        if wheel != 0 {
+            app.move_theme_picker(wheel > 0);
+        }
+        return;
+    }
+
+    if app.show_splash {
+        if matches!(mouse_event.kind, MouseEventKind::Down(_)) && app.recovery_prompt.is_none() {
+            app.show_splash = false;
+        }
+        return;
+    }
+
+    if app.overlay_open() {
+        return;
+    }
+
+    let areas = app.screen_areas.clone();
+
+    if let Some(ws) = areas.ws {
+        if ws.messages.contains(at) && wheel != 0 {
+            app.scroll_ws_messages(wheel);
+        }
+        if mouse_event.kind != MouseEventKind::Down(MouseButton::Left) || is_typing(app) {
+            return;
+        }
+        if ws.url.contains(at) {
+            app.active_tab_mut().input_mode = InputMode::EditingWsUrl;
+            app.input_cursor = line_edit::CURSOR_END;
+        } else if ws.input.contains(at) {
+            app.active_tab_mut().input_mode = InputMode::EditingWsMessage;
+            app.input_cursor = line_edit::CURSOR_END;
+        } else if let Some(row) = layout::list_row(ws.messages, at) {
+            let tab = app.active_tab_mut();
+            let height = ws.messages.height.saturating_sub(2) as usize;
+            let count = tab.ws_messages.len();
+            let offset = crate::ui::json_rows::window_offset(0, Some(tab.ws_scroll), height, count);
+            if offset + row < count {
+                tab.ws_scroll = offset + row;
+            }
+        }
+        return;
+    }
+
+    let in_sidebar = areas.sidebar_list.is_some_and(|a| a.contains(at));
+    let in_config = areas
+        .main
+        .and_then(|m| m.config)
+        .is_some_and(|a| a.contains(at));
+    let in_response = areas.response.is_some_and(|a| a.contains(at));
+
+    match mouse_event.kind {
+        MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+            if in_sidebar {
+                app.scroll_sidebar(wheel);
+            } else if in_config {
+                app.scroll_config(wheel);
+            } else if in_response {
+                app.scroll_response(wheel * WHEEL_LINES);
+            }
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let (Some(edge), Some(main)) = (areas.sidebar_edge(), areas.main)
+                && (edge..=edge + 1).contains(&at.x)
+                && main
+                    .sidebar
+                    .is_some_and(|s| (s.y..s.bottom()).contains(&at.y))
+            {
+                app.dragging_sidebar = true;
+                return;
+            }
+            // Typing goes on until Enter or Esc, a stray click doesn't end it
+            if is_typing(app) {
+                return;
+            }
+            if let Some(i) = layout::hit(&areas.open_tabs, at) {
+                if i < app.tabs.len() {
+                    app.active_tab = i;
+                }
+            } else if let Some(i) = layout::hit(&areas.config_tabs, at) {
+                app.active_sidebar = false;
+                app.active_tab_mut().selected_tab = i;
+            } else if let Some(main) = areas.main
+                && main.url.contains(at)
+            {
+                let start = areas.url_text.unwrap_or(main.url.x + 1);
+                app.click_url(at.x.saturating_sub(start));
+            } else if let Some(sidebar) = areas.sidebar_list
+                && sidebar.contains(at)
+            {
+                app.active_sidebar = true;
+                if let Some(row) = layout::list_row(sidebar, at) {
+                    app.click_sidebar_row(row);
+                }
+            } else if let Some(config) = areas.main.and_then(|m| m.config)
+                && config.contains(at)
+            {
+                app.active_sidebar = false;
+                if let Some(row) = layout::list_row(config, at) {
+                    app.click_config_row(row);
+                }
+            } else if let Some(response) = areas.response
+                && response.contains(at)
+            {
+                app.active_sidebar = false;
+                if let Some(row) = layout::list_row(response, at) {
+                    app.click_response_row(row);
                 }
             }
         }
+        MouseEventKind::Drag(MouseButton::Left) if app.dragging_sidebar => {
+            // The status bar spans the screen
+            if let Some(main) = areas.main {
+                app.resize_sidebar(layout::sidebar_percent_at(main.status, at.x));
+            }
+        }
+        MouseEventKind::Up(MouseButton::Left) if app.dragging_sidebar => {
+            app.dragging_sidebar = false;
+            app.save_config();
+        }
         _ => {}
     }
 }
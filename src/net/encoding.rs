@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+/// What requests advertise unless they set Accept-Encoding themselves: only what
+/// `decode` can undo
+pub const ACCEPT: &str = "gzip, deflate, br";
+
+/// A response body that came with a Content-Encoding, as it arrived
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentEncoding {
+    /// The header value, e.g. `gzip` or `deflate, gzip`
+    pub encoding: String,
+    /// The bytes before decoding
+    pub raw: Vec<u8>,
+    /// Set when the body couldn't be decoded and is shown as it came
+    pub error: Option<String>,
+}
+
+/// Undoes a Content-Encoding. Codings are listed in the order they were applied,
+/// so they come off last first.
+pub fn decode(encoding: &str, raw: &[u8]) -> Result<Vec<u8>, String> {
+    let mut body = raw.to_vec();
+    for coding in encoding.split(',').map(str::trim).rev() {
+        body = match coding.to_ascii_lowercase().as_str() {
+            "" | "identity" => body,
+            "gzip" | "x-gzip" => read_all(flate2::read::MultiGzDecoder::new(body.as_slice()))?,
+            // Meant to be zlib-wrapped, but plenty of servers send raw deflate
+            "deflate" => read_all(flate2::read::ZlibDecoder::new(body.as_slice()))
+                .or_else(|_| read_all(flate2::read::DeflateDecoder::new(body.as_slice())))?,
+            "br" => read_all(brotli::Decompressor::new(body.as_slice(), 4096))?,
+            other => return Err(format!("{} isn't supported", other)),
+        };
+    }
+    Ok(body)
+}
+
+fn read_all(mut reader: impl Read) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    reader
+        .read_to_end(&mut out)
+        .map_err(|e| format!("corrupt body: {}", e))?;
+    Ok(out)
+}
+
+/// The body to show, and how it came when the response had a Content-Encoding.
+/// A body that doesn't decode is passed on untouched, with the reason.
+pub fn decode_body(
+    headers: &HashMap<String, String>,
+    raw: Vec<u8>,
+) -> (Vec<u8>, Option<ContentEncoding>) {
+    let Some(encoding) = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-encoding"))
+        .map(|(_, v)| v.trim().to_string())
+        .filter(|v| !v.is_empty() && !v.eq_ignore_ascii_case("identity"))
+        .filter(|_| !raw.is_empty())
+    else {
+        return (raw, None);
+    };
+    match decode(&encoding, &raw) {
+        Ok(body) => (
+            body,
+            Some(ContentEncoding {
+                encoding,
+                raw,
+                error: None,
+            }),
+        ),
+        Err(e) => (
+            raw.clone(),
+            Some(ContentEncoding {
+                encoding,
+                raw,
+                error: Some(e),
+            }),
+        ),
+    }
+}
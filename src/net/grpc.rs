@@ -8,6 +8,161 @@ pub struct GrpcResponse {
     pub body: String,
     pub error: Option<String>,
     pub latency_ms: u128,
+    pub metadata: GrpcMetadata,
+}
+
+/// Status code names, indexed by code
+const CODES: [&str; 17] = [
+    "OK",
+    "CANCELLED",
+    "UNKNOWN",
+    "INVALID_ARGUMENT",
+    "DEADLINE_EXCEEDED",
+    "NOT_FOUND",
+    "ALREADY_EXISTS",
+    "PERMISSION_DENIED",
+    "RESOURCE_EXHAUSTED",
+    "FAILED_PRECONDITION",
+    "ABORTED",
+    "OUT_OF_RANGE",
+    "UNIMPLEMENTED",
+    "INTERNAL",
+    "UNAVAILABLE",
+    "DATA_LOSS",
+    "UNAUTHENTICATED",
+];
+
+/// The status a call ended with, as `google.rpc.Status`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrpcStatus {
+    pub code: u16,
+    pub message: String,
+    /// The error details, each a JSON object with its `@type`
+    /// (`type.googleapis.com/google.rpc.BadRequest`, ...)
+    pub details: Vec<serde_json::Value>,
+}
+
+impl GrpcStatus {
+    pub fn ok() -> Self {
+        GrpcStatus {
+            code: 0,
+            message: String::new(),
+            details: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        CODES.get(self.code as usize).copied().unwrap_or("UNKNOWN")
+    }
+}
+
+/// Header or trailer pairs, in the order the server sent them
+pub type Pairs = Vec<(String, String)>;
+
+/// What came back besides the messages
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GrpcMetadata {
+    /// None when the call never got a status (grpcurl missing, connection refused)
+    pub status: Option<GrpcStatus>,
+    pub headers: Pairs,
+    pub trailers: Pairs,
+}
+
+/// Splits `grpcurl -v` output into the response messages, the response headers
+/// and the trailers. Output without the verbose sections is all body.
+pub fn parse_verbose(stdout: &str) -> (String, Pairs, Pairs) {
+    enum Section {
+        Skip,
+        Headers,
+        Body,
+        Trailers,
+    }
+    let mut section = None;
+    let mut messages: Vec<String> = Vec::new();
+    let mut headers = Vec::new();
+    let mut trailers = Vec::new();
+    for line in stdout.lines() {
+        let next = match line.trim_end() {
+            "Resolved method descriptor:" | "Request metadata to send:" => Some(Section::Skip),
+            "Response headers received:" => Some(Section::Headers),
+            "Response contents:" => Some(Section::Body),
+            "Response trailers received:" => Some(Section::Trailers),
+            l if l.starts_with("Sent ") && l.contains(" request") => Some(Section::Skip),
+            _ => None,
+        };
+        if let Some(next) = next {
+            if matches!(next, Section::Body) {
+                messages.push(String::new());
+            }
+            section = Some(next);
+            continue;
+        }
+        let metadata = match section {
+            Some(Section::Headers) => &mut headers,
+            Some(Section::Trailers) => &mut trailers,
+            Some(Section::Body) => {
+                if !line.starts_with("Estimated response size:")
+                    && let Some(message) = messages.last_mut()
+                {
+                    message.push_str(line);
+                    message.push('\n');
+                }
+                continue;
+            }
+            Some(Section::Skip) => continue,
+            None if line.trim().is_empty() => continue,
+            None => return (stdout.to_string(), Vec::new(), Vec::new()),
+        };
+        if let Some((key, value)) = line.split_once(": ") {
+            metadata.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    let body = messages
+        .iter()
+        .map(|m| m.trim())
+        .collect::<Vec<_>>()
+        .join("\n");
+    (body, headers, trailers)
+}
+
+/// The status grpcurl printed to stderr, as JSON with `-format-error` or as its
+/// `Code:`/`Message:` text. `exit_code` (64 + the status code) fills in when
+/// neither is there.
+pub fn parse_status(stderr: &str, exit_code: Option<i32>) -> Option<GrpcStatus> {
+    if let Some(start) = stderr.find('{')
+        && let Ok(value) = serde_json::from_str::<serde_json::Value>(&stderr[start..])
+        && value.get("code").is_some()
+    {
+        return Some(GrpcStatus {
+            code: value["code"].as_u64().unwrap_or(2) as u16,
+            message: value["message"].as_str().unwrap_or_default().to_string(),
+            details: value["details"].as_array().cloned().unwrap_or_default(),
+        });
+    }
+
+    let field = |name: &str| {
+        stderr
+            .lines()
+            .find_map(|l| l.trim().strip_prefix(name))
+            .map(|v| v.trim().to_string())
+    };
+    let from_exit = exit_code
+        .filter(|c| (64..64 + CODES.len() as i32).contains(c))
+        .map(|c| (c - 64) as u16);
+    // "NotFound" in the text, NOT_FOUND in CODES
+    let from_name = field("Code:").and_then(|name| {
+        let name = name.replace('_', "").to_lowercase();
+        CODES
+            .iter()
+            .position(|c| c.replace('_', "").to_lowercase() == name)
+            .map(|c| c as u16)
+    });
+    let code = from_name.or(from_exit)?;
+    Some(GrpcStatus {
+        code,
+        message: field("Message:").unwrap_or_else(|| stderr.trim().to_string()),
+        details: Vec::new(),
+    })
 }
 
 /// Execute a gRPC request using grpcurl
@@ -15,14 +170,14 @@ pub struct GrpcResponse {
 /// # Arguments
 /// * `url` - The gRPC server address (e.g., "localhost:50051")
 /// * `service_method` - Full service/method path (e.g., "grpc.health.v1.Health/Check")
-/// * `proto_path` - Optional path to .proto file (if not using reflection)
+/// * `protos` - grpcurl `-import-path`/`-proto` arguments, empty to use reflection
 /// * `payload` - JSON payload for the request
 /// * `headers` - Additional headers/metadata
 /// * `use_plaintext` - Whether to use plaintext (no TLS)
 pub fn execute_grpc_request(
     url: &str,
     service_method: &str,
-    proto_path: Option<&str>,
+    protos: &[String],
     payload: &str,
     headers: &HashMap<String, String>,
     use_plaintext: bool,
@@ -41,26 +196,21 @@ pub fn execute_grpc_request(
                     .to_string(),
             ),
             latency_ms: start.elapsed().as_millis(),
+            metadata: GrpcMetadata::default(),
         };
     }
 
     let mut cmd = Command::new("grpcurl");
+    // Headers, trailers and the status with its details, not just the messages
+    cmd.arg("-v").arg("-format-error");
 
     // Add plaintext flag if needed (for non-TLS connections)
     if use_plaintext || !url.starts_with("https") {
         cmd.arg("-plaintext");
     }
 
-    // Add proto file if specified
-    if let Some(proto) = proto_path
-        && !proto.is_empty()
-    {
-        // Extract import path (directory containing the proto file)
-        if let Some(parent) = std::path::Path::new(proto).parent() {
-            cmd.arg("-import-path").arg(parent);
-        }
-        cmd.arg("-proto").arg(proto);
-    }
+    // Add proto files if registered
+    cmd.args(protos);
 
     // Add headers as metadata
     for (key, value) in headers {
@@ -82,27 +232,39 @@ pub fn execute_grpc_request(
             let latency = start.elapsed().as_millis();
             let stdout = String::from_utf8_lossy(&output.stdout).to_string();
             let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            let (body, headers, trailers) = parse_verbose(&stdout);
 
             if output.status.success() {
                 GrpcResponse {
                     success: true,
-                    body: stdout,
+                    body,
                     error: None,
                     latency_ms: latency,
+                    metadata: GrpcMetadata {
+                        status: Some(GrpcStatus::ok()),
+                        headers,
+                        trailers,
+                    },
                 }
             } else {
+                let status = parse_status(&stderr, output.status.code());
                 // grpcurl outputs errors to stderr
-                let error_msg = if stderr.is_empty() {
-                    stdout.clone()
-                } else {
-                    stderr
+                let error_msg = match &status {
+                    Some(status) => format!("{}: {}", status.name(), status.message),
+                    None if stderr.is_empty() => stdout.clone(),
+                    None => stderr,
                 };
 
                 GrpcResponse {
                     success: false,
-                    body: stdout,
+                    body,
                     error: Some(error_msg),
                     latency_ms: latency,
+                    metadata: GrpcMetadata {
+                        status,
+                        headers,
+                        trailers,
+                    },
                 }
             }
         }
@@ -111,19 +273,29 @@ pub fn execute_grpc_request(
             body: String::new(),
             error: Some(format!("Failed to execute grpcurl: {}", e)),
             latency_ms: start.elapsed().as_millis(),
+            metadata: GrpcMetadata::default(),
         },
     }
 }
 
-/// List available services using server reflection
-pub fn list_services(url: &str, use_plaintext: bool) -> Result<Vec<String>, String> {
+/// List available services using server reflection, or the ones in `protos`
+pub fn list_services(
+    url: &str,
+    protos: &[String],
+    use_plaintext: bool,
+) -> Result<Vec<String>, String> {
     let mut cmd = Command::new("grpcurl");
 
     if use_plaintext {
         cmd.arg("-plaintext");
     }
 
-    cmd.arg(url);
+    // With .proto files there's no server to ask
+    if protos.is_empty() {
+        cmd.arg(url);
+    } else {
+        cmd.args(protos);
+    }
     cmd.arg("list");
 
     match cmd.output() {
@@ -145,15 +317,24 @@ pub fn list_services(url: &str, use_plaintext: bool) -> Result<Vec<String>, Stri
     }
 }
 
-/// Describe a service or method using server reflection
-pub fn describe_service(url: &str, service: &str, use_plaintext: bool) -> Result<String, String> {
+/// Describe a service or method using server reflection, or `protos`
+pub fn describe_service(
+    url: &str,
+    service: &str,
+    protos: &[String],
+    use_plaintext: bool,
+) -> Result<String, String> {
     let mut cmd = Command::new("grpcurl");
 
     if use_plaintext {
         cmd.arg("-plaintext");
     }
 
-    cmd.arg(url);
+    if protos.is_empty() {
+        cmd.arg(url);
+    } else {
+        cmd.args(protos);
+    }
     cmd.arg("describe");
     cmd.arg(service);
 
@@ -170,6 +351,176 @@ pub fn describe_service(url: &str, service: &str, use_plaintext: bool) -> Result
     }
 }
 
+/// Describe a message type with grpcurl's JSON template for it appended
+pub fn describe_message(
+    url: &str,
+    message: &str,
+    protos: &[String],
+    use_plaintext: bool,
+) -> Result<String, String> {
+    let mut cmd = Command::new("grpcurl");
+    if use_plaintext {
+        cmd.arg("-plaintext");
+    }
+    cmd.arg("-msg-template");
+    // With .proto files there's no server to ask
+    if protos.is_empty() {
+        cmd.arg(url);
+    } else {
+        cmd.args(protos);
+    }
+    cmd.arg("describe");
+    cmd.arg(message.trim_start_matches('.'));
+
+    match cmd.output() {
+        Ok(output) if output.status.success() => {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        }
+        Ok(output) => Err(format!(
+            "Failed to describe {}: {}",
+            message,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Err(e) => Err(format!("Failed to execute grpcurl: {}", e)),
+    }
+}
+
+/// One `rpc` of a service description
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcMethod {
+    pub name: String,
+    /// Fully qualified, e.g. `.helloworld.HelloRequest`
+    pub input: String,
+    pub output: String,
+    pub client_streaming: bool,
+    pub server_streaming: bool,
+}
+
+/// The methods in `grpcurl describe <service>` output, e.g.
+/// `rpc SayHello ( .helloworld.HelloRequest ) returns ( stream .helloworld.HelloReply );`
+pub fn parse_methods(description: &str) -> Vec<RpcMethod> {
+    fn message(part: &str) -> Option<(String, bool)> {
+        let inner = part.split_once('(')?.1.split_once(')')?.0.trim();
+        match inner.strip_prefix("stream ") {
+            Some(rest) => Some((rest.trim().to_string(), true)),
+            None => Some((inner.to_string(), false)),
+        }
+    }
+    description
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("rpc ")?;
+            let (name, rest) = rest.split_once('(')?;
+            let (request, response) = format!("({}", rest)
+                .split_once("returns")
+                .map(|(a, b)| (a.to_string(), b.to_string()))?;
+            let (input, client_streaming) = message(&request)?;
+            let (output, server_streaming) = message(&response)?;
+            Some(RpcMethod {
+                name: name.trim().to_string(),
+                input,
+                output,
+                client_streaming,
+                server_streaming,
+            })
+        })
+        .collect()
+}
+
+/// A placeholder for a field of this proto type, per the proto3 JSON mapping
+fn placeholder(kind: &str, enums: &HashMap<String, String>) -> serde_json::Value {
+    use serde_json::{Value, json};
+    let short = kind.rsplit('.').next().unwrap_or(kind);
+    match kind.trim_start_matches('.') {
+        "string" | "bytes" => json!(""),
+        "bool" => json!(false),
+        "int32" | "uint32" | "sint32" | "fixed32" | "sfixed32" => json!(0),
+        // 64-bit integers are strings in JSON
+        "int64" | "uint64" | "sint64" | "fixed64" | "sfixed64" => json!("0"),
+        "float" | "double" => json!(0.0),
+        "google.protobuf.Timestamp" => json!("1970-01-01T00:00:00Z"),
+        "google.protobuf.Duration" => json!("0s"),
+        "google.protobuf.Value" | "google.protobuf.NullValue" => Value::Null,
+        "google.protobuf.StringValue" | "google.protobuf.BytesValue" => json!(""),
+        "google.protobuf.BoolValue" => json!(false),
+        "google.protobuf.Int32Value"
+        | "google.protobuf.UInt32Value"
+        | "google.protobuf.FloatValue"
+        | "google.protobuf.DoubleValue" => json!(0),
+        "google.protobuf.Int64Value" | "google.protobuf.UInt64Value" => json!("0"),
+        "google.protobuf.ListValue" => json!([]),
+        _ => match enums.get(short) {
+            Some(first) => json!(first),
+            // Another message: its fields aren't in this description
+            None => json!({}),
+        },
+    }
+}
+
+/// A JSON payload to start from for the message in a `grpcurl describe` output: the
+/// template grpcurl printed (`-msg-template`), or else one built from the fields
+/// in the description with a typed placeholder each
+pub fn skeleton(description: &str) -> Option<String> {
+    use serde_json::{Map, Value};
+    if let Some((_, template)) = description.split_once("Message template:")
+        && let Ok(value) = serde_json::from_str::<Value>(template.trim())
+    {
+        return serde_json::to_string_pretty(&value).ok();
+    }
+
+    let body = description.split_once("message ")?.1.split_once('{')?.1;
+    // Enums declared in the message, with their first value. They may come after
+    // the fields using them.
+    let mut enums = HashMap::new();
+    let mut lines = body.lines().map(str::trim).peekable();
+    while let Some(line) = lines.next() {
+        if let Some(rest) = line.strip_prefix("enum ")
+            && let Some(name) = rest.split(|c: char| c == '{' || c.is_whitespace()).next()
+            && let Some((first, _)) = lines.peek().and_then(|l| l.split_once('='))
+        {
+            enums.insert(name.to_string(), first.trim().to_string());
+        }
+    }
+    // Blocks we're inside: nested messages and enums are skipped, a oneof gives
+    // its first field
+    let mut blocks: Vec<(&str, bool)> = Vec::new();
+    let mut fields = Map::new();
+    for line in body.lines().map(str::trim) {
+        if line.ends_with('{') {
+            let kind = line.split_whitespace().next().unwrap_or_default();
+            blocks.push((kind, false));
+            continue;
+        }
+        if line.starts_with('}') {
+            if blocks.pop().is_none() {
+                break;
+            }
+            continue;
+        }
+        if !line.ends_with(';') || !line.contains('=') {
+            continue;
+        }
+        match blocks.last_mut() {
+            None => {}
+            Some(("oneof", taken)) if !*taken => *taken = true,
+            Some(_) => continue,
+        }
+        let declaration = line.split_once('=').map(|(d, _)| d.trim()).unwrap_or(line);
+        let words: Vec<&str> = declaration.split_whitespace().collect();
+        let Some((name, rest)) = words.split_last() else {
+            continue;
+        };
+        let value = match rest {
+            _ if declaration.starts_with("map<") => Value::Object(Map::new()),
+            ["repeated", kind] => Value::Array(vec![placeholder(kind, &enums)]),
+            ["optional", kind] | [kind] => placeholder(kind, &enums),
+            _ => continue,
+        };
+        fields.insert(name.to_string(), value);
+    }
+    serde_json::to_string_pretty(&Value::Object(fields)).ok()
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -10,6 +10,9 @@ pub enum AuthPayload {
     Basic(String, String),
 }
 
+/// Work for the network task and what it reports back. Replies to a send carry its
+/// request id (first, or as `request_id`) so they reach the tab that sent it, even
+/// once another tab is in front or has a send of its own in flight.
 pub enum NetworkEvent {
     RunRequest {
         url: String,
@@ -19,6 +22,8 @@ pub enum NetworkEvent {
         form_data: Option<Vec<(String, String, bool)>>,
         auth: Option<AuthPayload>,
         timeout_ms: Option<u64>,
+        /// Connect and read timeouts, on the client
+        phase_timeouts: crate::net::timeouts::PhaseTimeouts,
         // SSL Configuration
         ssl_verify: bool,
         ssl_ca_cert: Option<Vec<u8>>, // CA cert bytes (pre-loaded)
@@ -30,18 +35,47 @@ pub enum NetworkEvent {
         proxy_url: Option<String>,
         proxy_auth: Option<(String, String)>, // (user, pass)
         no_proxy: Option<String>,
+        pool: crate::net::pool::PoolSettings,
+        /// Hosts pinned by the environment's `resolve` table
+        resolve: crate::net::pool::HostOverrides,
+        /// Ties the request's log records together
+        request_id: String,
     },
+    /// Drops the shared client: pooled connections and cached DNS answers go with it
+    FlushConnections,
+    /// The request as written to the connection, just before it goes out
+    Sent(String, Vec<u8>),
+    /// Status and headers of a response whose body comes in pieces: `Chunk`s
+    /// follow as they arrive and `GotResponse` still ends it
+    Streaming(String, u16, HashMap<String, String>),
+    Chunk(String, Vec<u8>),
     GotResponse(
+        String,
         Vec<u8>,
         u16,
         u128,
         Vec<String>,
         String,
         HashMap<String, String>,
+        /// How the body came before it was decoded, when it had a Content-Encoding
+        Option<crate::net::encoding::ContentEncoding>,
     ),
+    /// A send that got no response
+    Failed {
+        request_id: String,
+        error: String,
+    },
     Error(String),
     OAuthCode(String),
-    OAuthToken(String),
+    OAuthToken(crate::features::token_refresh::TokenResponse),
+    TokenRefreshed {
+        old_token: String,
+        response: crate::features::token_refresh::TokenResponse,
+    },
+    TokenRefreshFailed {
+        old_token: String,
+        error: String,
+    },
     IntrospectSchema {
         url: String,
         headers: HashMap<String, String>,
@@ -50,36 +84,249 @@ pub enum NetworkEvent {
     RunGrpc {
         url: String,
         service_method: String,
-        proto_path: Option<String>,
+        /// grpcurl `-import-path`/`-proto` arguments, empty for reflection
+        protos: Vec<String>,
         payload: String,
         headers: HashMap<String, String>,
         use_plaintext: bool,
+        request_id: String,
     },
     GotGrpcResponse {
+        request_id: String,
         success: bool,
         body: String,
         error: Option<String>,
         latency_ms: u128,
+        metadata: crate::net::grpc::GrpcMetadata,
     },
     ListGrpcServices {
         url: String,
+        protos: Vec<String>,
         use_plaintext: bool,
     },
     GotGrpcServices(Vec<String>),
     DescribeGrpcService {
         url: String,
         service: String,
+        protos: Vec<String>,
         use_plaintext: bool,
     },
     GotGrpcServiceDescription(String),
+    /// Describe a method's input message to scaffold its payload
+    DescribeGrpcMessage {
+        url: String,
+        message: String,
+        protos: Vec<String>,
+        use_plaintext: bool,
+    },
+    GotGrpcMessageDescription {
+        message: String,
+        description: Result<String, String>,
+    },
+}
+
+impl NetworkEvent {
+    /// The send this is a reply to
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            NetworkEvent::Sent(id, _)
+            | NetworkEvent::Streaming(id, ..)
+            | NetworkEvent::Chunk(id, _)
+            | NetworkEvent::GotResponse(id, ..)
+            | NetworkEvent::Failed { request_id: id, .. }
+            | NetworkEvent::GotGrpcResponse { request_id: id, .. } => Some(id),
+            _ => None,
+        }
+    }
+}
+
+/// Everything a shared HTTP client is built from. Requests with the same config
+/// reuse the same client (and its connection pool), see `pool::ClientCache`.
+/// Batches and pagination build theirs from it too, so they go the way a send does.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClientConfig {
+    pub ssl_verify: bool,
+    pub ssl_ca_cert: Option<Vec<u8>>,
+    pub proxy_url: Option<String>,
+    pub proxy_auth: Option<(String, String)>,
+    pub pool: crate::net::pool::PoolSettings,
+    pub resolve: crate::net::pool::HostOverrides,
+    pub phase_timeouts: crate::net::timeouts::PhaseTimeouts,
+}
+
+impl ClientConfig {
+    /// A client with the TLS, proxy, pool, timeout and pinned host settings. Bodies
+    /// come back as they went over the wire, for `encoding::decode`.
+    pub fn build(&self) -> Client {
+        let mut client_builder = crate::net::pool::apply_overrides(
+            self.phase_timeouts.apply(
+                self.pool.apply(
+                    Client::builder()
+                        .user_agent("PostDad/1.0")
+                        .no_gzip()
+                        .danger_accept_invalid_certs(!self.ssl_verify),
+                ),
+            ),
+            &self.resolve,
+        );
+
+        // Add custom CA certificate if provided
+        if let Some(ca_bytes) = &self.ssl_ca_cert
+            && let Ok(cert) = reqwest::Certificate::from_pem(ca_bytes)
+        {
+            client_builder = client_builder.add_root_certificate(cert);
+        }
+
+        // Configure proxy if provided
+        if let Some(proxy_str) = &self.proxy_url
+            && let Ok(mut proxy) = reqwest::Proxy::all(proxy_str)
+        {
+            // Add proxy authentication if provided
+            if let Some((user, pass)) = &self.proxy_auth {
+                proxy = proxy.basic_auth(user, pass);
+            }
+            client_builder = client_builder.proxy(proxy);
+        }
+
+        client_builder.build().unwrap_or_else(|_| Client::new())
+    }
+}
+
+/// The client's request for `url` with the headers and auth on it, asking for the
+/// encodings `encoding::decode` handles. The body, the timeout and the build are up
+/// to the caller.
+pub fn request_builder(
+    client: &Client,
+    method: &str,
+    url: &str,
+    headers: &HashMap<String, String>,
+    auth: Option<&AuthPayload>,
+) -> reqwest::RequestBuilder {
+    let req_method = Method::from_str(method).unwrap_or(Method::GET);
+    let mut req_builder = client.request(req_method, url);
+
+    for (k, v) in headers {
+        req_builder = req_builder.header(k, v);
+    }
+    // Ranges of a compressed body can't be decoded on their own
+    if !headers
+        .keys()
+        .any(|k| k.eq_ignore_ascii_case("accept-encoding") || k.eq_ignore_ascii_case("range"))
+    {
+        req_builder = req_builder.header(
+            reqwest::header::ACCEPT_ENCODING,
+            crate::net::encoding::ACCEPT,
+        );
+    }
+
+    match auth {
+        Some(AuthPayload::Bearer(token)) => req_builder.bearer_auth(token),
+        Some(AuthPayload::Basic(u, p)) => req_builder.basic_auth(u, Some(p)),
+        None => req_builder,
+    }
+}
+
+/// The multipart body for the form fields; `files` has what the file fields point
+/// at, by path. A file that couldn't be read is left out.
+pub fn multipart_form(
+    fields: &[(String, String, bool)],
+    files: &HashMap<String, Vec<u8>>,
+) -> reqwest::multipart::Form {
+    let mut form = reqwest::multipart::Form::new();
+    for (k, v, is_file) in fields {
+        if !is_file {
+            form = form.text(k.clone(), v.clone());
+        } else if let Some(bytes) = files.get(v) {
+            let filename = std::path::Path::new(v)
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("file")
+                .to_string();
+            let part = reqwest::multipart::Part::bytes(bytes.clone()).file_name(filename);
+            form = form.part(k.clone(), part);
+        }
+    }
+    form
+}
+
+/// The request as HTTP/1.1 puts it on the wire: request line, headers and body.
+/// Host, User-Agent and Accept are filled in the way the client adds them when the
+/// request doesn't set its own. A multipart body is streamed
+/// from the form, so it only gets a note.
+pub fn wire_bytes(request: &reqwest::Request) -> Vec<u8> {
+    let url = request.url();
+    let mut target = url.path().to_string();
+    if let Some(query) = url.query() {
+        target.push('?');
+        target.push_str(query);
+    }
+    let mut out = format!("{} {} HTTP/1.1\r\n", request.method(), target).into_bytes();
+    let headers = request.headers();
+    fn line(out: &mut Vec<u8>, name: &str, value: &[u8]) {
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(b": ");
+        out.extend_from_slice(value);
+        out.extend_from_slice(b"\r\n");
+    }
+
+    if !headers.contains_key(reqwest::header::HOST) {
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            _ => String::new(),
+        };
+        line(&mut out, "host", host.as_bytes());
+    }
+    for (name, value) in headers {
+        line(&mut out, name.as_str(), value.as_bytes());
+    }
+    let defaults = [
+        (reqwest::header::USER_AGENT, "PostDad/1.0"),
+        (reqwest::header::ACCEPT, "*/*"),
+    ];
+    for (name, value) in defaults {
+        if !headers.contains_key(&name) {
+            line(&mut out, name.as_str(), value.as_bytes());
+        }
+    }
+
+    match request.body().map(|b| b.as_bytes()) {
+        Some(Some(body)) => {
+            if !headers.contains_key(reqwest::header::CONTENT_LENGTH) {
+                line(
+                    &mut out,
+                    "content-length",
+                    body.len().to_string().as_bytes(),
+                );
+            }
+            out.extend_from_slice(b"\r\n");
+            out.extend_from_slice(body);
+        }
+        Some(None) => {
+            if !headers.contains_key(reqwest::header::CONTENT_LENGTH) {
+                line(&mut out, "transfer-encoding", b"chunked");
+            }
+            out.extend_from_slice(b"\r\n");
+            out.extend_from_slice(b"<multipart body, streamed from the form>");
+        }
+        None => out.extend_from_slice(b"\r\n"),
+    }
+    out
 }
 
 pub async fn handle_network(
     mut receiver: mpsc::Receiver<NetworkEvent>,
     sender: mpsc::Sender<NetworkEvent>,
 ) {
+    let mut clients = crate::net::pool::ClientCache::new(
+        crate::net::pool::ClientCache::<ClientConfig, Client>::CAPACITY,
+    );
+
     while let Some(event) = receiver.recv().await {
         match event {
+            NetworkEvent::FlushConnections => {
+                clients.clear();
+            }
             NetworkEvent::RunRequest {
                 url,
                 method,
@@ -88,6 +335,7 @@ pub async fn handle_network(
                 form_data,
                 auth,
                 timeout_ms,
+                phase_timeouts,
                 ssl_verify,
                 ssl_ca_cert,
                 ssl_client_cert: _,
@@ -95,25 +343,26 @@ pub async fn handle_network(
                 proxy_url,
                 proxy_auth,
                 no_proxy,
+                pool,
+                resolve,
+                request_id,
             } => {
                 let start = std::time::Instant::now();
+                tracing::debug!(request_id, method, url, "sending");
 
-                // Build client with SSL configuration
                 let timeout = timeout_ms
                     .map(Duration::from_millis)
                     .unwrap_or(Duration::from_secs(30));
 
-                let mut client_builder = Client::builder()
-                    .timeout(timeout)
-                    .user_agent("PostDad/1.0")
-                    .danger_accept_invalid_certs(!ssl_verify);
-
-                // Add custom CA certificate if provided
-                if let Some(ca_bytes) = ssl_ca_cert
-                    && let Ok(cert) = reqwest::Certificate::from_pem(&ca_bytes)
-                {
-                    client_builder = client_builder.add_root_certificate(cert);
-                }
+                let config = ClientConfig {
+                    ssl_verify,
+                    ssl_ca_cert,
+                    proxy_url,
+                    proxy_auth,
+                    pool,
+                    resolve,
+                    phase_timeouts,
+                };
 
                 // Add client certificate for mTLS if both cert and key provided
                 // Note: native-tls does not support Identity::from_pem.
@@ -132,108 +381,143 @@ pub async fn handle_network(
                 }
                 */
 
-                // Configure proxy if provided
-                if let Some(proxy_str) = proxy_url
-                    && let Ok(mut proxy) = reqwest::Proxy::all(&proxy_str)
-                {
-                    // Add proxy authentication if provided
-                    if let Some((user, pass)) = proxy_auth {
-                        proxy = proxy.basic_auth(&user, &pass);
-                    }
-                    client_builder = client_builder.proxy(proxy);
-                }
-
                 // Note: no_proxy is passed but reqwest automatically respects
                 // the NO_PROXY environment variable, so we don't need to handle it explicitly.
                 // It's included in the event for potential future use or logging.
                 let _ = no_proxy; // Acknowledge the field is intentionally unused here
 
-                let client = client_builder.build().unwrap_or_else(|_| Client::new());
+                let (client, reused) = if config.pool.fresh_client {
+                    (config.build(), false)
+                } else {
+                    clients.get_or_build(config.clone(), || config.build())
+                };
+                tracing::debug!(request_id, reused, "client");
 
-                let req_method = Method::from_str(&method).unwrap_or(Method::GET);
-                let mut req_builder = client.request(req_method, &url);
+                // Each send runs on its own, so a slow one doesn't hold up another
+                // tab's
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    let mut req_builder =
+                        request_builder(&client, &method, &url, &headers, auth.as_ref())
+                            .timeout(timeout);
 
-                for (k, v) in headers {
-                    req_builder = req_builder.header(k, v);
-                }
-
-                if let Some(a) = auth {
-                    match a {
-                        AuthPayload::Bearer(token) => {
-                            req_builder = req_builder.bearer_auth(token);
-                        }
-                        AuthPayload::Basic(u, p) => {
-                            req_builder = req_builder.basic_auth(u, Some(p));
-                        }
-                    }
-                }
-
-                if let Some(fd) = form_data {
-                    let mut form = reqwest::multipart::Form::new();
-                    for (k, v, is_file) in fd {
-                        if is_file {
-                            if let Ok(bytes) = tokio::fs::read(&v).await {
-                                let filename = std::path::Path::new(&v)
-                                    .file_name()
-                                    .and_then(|s| s.to_str())
-                                    .unwrap_or("file")
-                                    .to_string();
-
-                                let part =
-                                    reqwest::multipart::Part::bytes(bytes).file_name(filename);
-                                form = form.part(k, part);
+                    if let Some(fd) = form_data {
+                        let mut files = HashMap::new();
+                        for (_, path, _) in fd.iter().filter(|(_, _, is_file)| *is_file) {
+                            if let Ok(bytes) = tokio::fs::read(path).await {
+                                files.insert(path.clone(), bytes);
                             }
-                        } else {
-                            form = form.text(k, v);
                         }
+                        req_builder = req_builder.multipart(multipart_form(&fd, &files));
+                    } else if let Some(b) = body {
+                        req_builder = req_builder.body(b);
                     }
-                    req_builder = req_builder.multipart(form);
-                } else if let Some(b) = body {
-                    req_builder = req_builder.body(b);
-                }
 
-                let res = req_builder.send().await;
-                let duration = start.elapsed().as_millis();
-
-                match res {
-                    Ok(resp) => {
-                        let status = resp.status().as_u16();
-                        let mut resp_headers = HashMap::new();
-                        for (k, v) in resp.headers() {
-                            resp_headers.insert(
-                                k.as_str().to_string(),
-                                v.to_str().unwrap_or("").to_string(),
-                            );
+                    let request = match req_builder.build() {
+                        Ok(request) => request,
+                        Err(e) => {
+                            tracing::error!(request_id, error = %e, "request not built");
+                            let _ = sender
+                                .send(NetworkEvent::Failed {
+                                    request_id,
+                                    error: e.to_string(),
+                                })
+                                .await;
+                            return;
                         }
+                    };
+                    let _ = sender
+                        .send(NetworkEvent::Sent(request_id.clone(), wire_bytes(&request)))
+                        .await;
+                    let res = client.execute(request).await;
+                    let duration = start.elapsed().as_millis();
+
+                    match res {
+                        Ok(mut resp) => {
+                            let status = resp.status().as_u16();
+                            let mut resp_headers = HashMap::new();
+                            for (k, v) in resp.headers() {
+                                resp_headers.insert(
+                                    k.as_str().to_string(),
+                                    v.to_str().unwrap_or("").to_string(),
+                                );
+                            }
 
-                        let cookies: Vec<String> = resp
-                            .headers()
-                            .get_all("set-cookie")
-                            .iter()
-                            .filter_map(|h| h.to_str().ok().map(|s| s.to_string()))
-                            .collect();
-
-                        let bytes = resp
-                            .bytes()
-                            .await
-                            .map(|b| b.to_vec())
-                            .unwrap_or_else(|_| Vec::new());
-
-                        let _ = sender
-                            .send(NetworkEvent::GotResponse(
-                                bytes,
+                            let cookies: Vec<String> = resp
+                                .headers()
+                                .get_all("set-cookie")
+                                .iter()
+                                .filter_map(|h| h.to_str().ok().map(|s| s.to_string()))
+                                .collect();
+
+                            let raw = if crate::net::streaming::is_streaming(&resp_headers) {
+                                let _ = sender
+                                    .send(NetworkEvent::Streaming(
+                                        request_id.clone(),
+                                        status,
+                                        resp_headers.clone(),
+                                    ))
+                                    .await;
+                                // Ends with the body, or at the timeout with what came so far
+                                let mut raw = Vec::new();
+                                while let Ok(Some(chunk)) = resp.chunk().await {
+                                    raw.extend_from_slice(&chunk);
+                                    let _ = sender
+                                        .send(NetworkEvent::Chunk(
+                                            request_id.clone(),
+                                            chunk.to_vec(),
+                                        ))
+                                        .await;
+                                }
+                                raw
+                            } else {
+                                resp.bytes()
+                                    .await
+                                    .map(|b| b.to_vec())
+                                    .unwrap_or_else(|_| Vec::new())
+                            };
+                            let (bytes, encoding) =
+                                crate::net::encoding::decode_body(&resp_headers, raw);
+                            tracing::info!(
+                                request_id,
                                 status,
-                                duration,
-                                cookies,
-                                url.clone(),
-                                resp_headers,
-                            ))
-                            .await;
-                    }
-                    Err(e) => {
-                        let _ = sender.send(NetworkEvent::Error(e.to_string())).await;
+                                latency_ms = duration as u64,
+                                bytes = bytes.len(),
+                                "response"
+                            );
+                            if let Some(error) = encoding.as_ref().and_then(|e| e.error.as_ref()) {
+                                tracing::warn!(request_id, error, "body not decoded");
+                            }
+
+                            let _ = sender
+                                .send(NetworkEvent::GotResponse(
+                                    request_id,
+                                    bytes,
+                                    status,
+                                    duration,
+                                    cookies,
+                                    url.clone(),
+                                    resp_headers,
+                                    encoding,
+                                ))
+                                .await;
+                        }
+                        Err(e) => {
+                            tracing::error!(
+                                request_id,
+                                latency_ms = duration as u64,
+                                error = ?e,
+                                "request failed"
+                            );
+                            let _ = sender
+                                .send(NetworkEvent::Failed {
+                                    request_id,
+                                    error: e.to_string(),
+                                })
+                                .await;
+                        }
                     }
-                }
+                });
             }
             NetworkEvent::IntrospectSchema { url, headers } => {
                 let client = Client::builder()
@@ -261,47 +545,70 @@ pub async fn handle_network(
             NetworkEvent::RunGrpc {
                 url,
                 service_method,
-                proto_path,
+                protos,
                 payload,
                 headers,
                 use_plaintext,
+                request_id,
             } => {
-                // Execute gRPC request using grpcurl
-                let result = crate::net::grpc::execute_grpc_request(
-                    &url,
-                    &service_method,
-                    proto_path.as_deref(),
-                    &payload,
-                    &headers,
-                    use_plaintext,
-                );
+                tracing::debug!(request_id, url, service_method, "sending gRPC");
+                // grpcurl blocks until it's done, so it runs off the loop like an HTTP send
+                let sender = sender.clone();
+                tokio::task::spawn_blocking(move || {
+                    let result = crate::net::grpc::execute_grpc_request(
+                        &url,
+                        &service_method,
+                        &protos,
+                        &payload,
+                        &headers,
+                        use_plaintext,
+                    );
+                    match (&result.error, &result.metadata.status) {
+                        (Some(error), status) => tracing::error!(
+                            request_id,
+                            code = status.as_ref().map(|s| s.code),
+                            latency_ms = result.latency_ms as u64,
+                            error,
+                            "gRPC call failed"
+                        ),
+                        (None, _) => tracing::info!(
+                            request_id,
+                            latency_ms = result.latency_ms as u64,
+                            bytes = result.body.len(),
+                            "gRPC response"
+                        ),
+                    }
 
-                let _ = sender
-                    .send(NetworkEvent::GotGrpcResponse {
+                    let _ = sender.blocking_send(NetworkEvent::GotGrpcResponse {
+                        request_id,
                         success: result.success,
                         body: result.body,
                         error: result.error,
                         latency_ms: result.latency_ms,
-                    })
-                    .await;
+                        metadata: result.metadata,
+                    });
+                });
             }
-            NetworkEvent::ListGrpcServices { url, use_plaintext } => {
-                match crate::net::grpc::list_services(&url, use_plaintext) {
-                    Ok(services) => {
-                        let _ = sender.send(NetworkEvent::GotGrpcServices(services)).await;
-                    }
-                    Err(e) => {
-                        let _ = sender
-                            .send(NetworkEvent::GotGrpcServices(vec![format!("Error: {}", e)]))
-                            .await;
-                    }
+            NetworkEvent::ListGrpcServices {
+                url,
+                protos,
+                use_plaintext,
+            } => match crate::net::grpc::list_services(&url, &protos, use_plaintext) {
+                Ok(services) => {
+                    let _ = sender.send(NetworkEvent::GotGrpcServices(services)).await;
                 }
-            }
+                Err(e) => {
+                    let _ = sender
+                        .send(NetworkEvent::GotGrpcServices(vec![format!("Error: {}", e)]))
+                        .await;
+                }
+            },
             NetworkEvent::DescribeGrpcService {
                 url,
                 service,
+                protos,
                 use_plaintext,
-            } => match crate::net::grpc::describe_service(&url, &service, use_plaintext) {
+            } => match crate::net::grpc::describe_service(&url, &service, &protos, use_plaintext) {
                 Ok(desc) => {
                     let _ = sender
                         .send(NetworkEvent::GotGrpcServiceDescription(desc))
@@ -316,6 +623,23 @@ pub async fn handle_network(
                         .await;
                 }
             },
+            NetworkEvent::DescribeGrpcMessage {
+                url,
+                message,
+                protos,
+                use_plaintext,
+            } => {
+                // grpcurl again, off the loop so a slow server doesn't hold up sends
+                let sender = sender.clone();
+                tokio::task::spawn_blocking(move || {
+                    let description =
+                        crate::net::grpc::describe_message(&url, &message, &protos, use_plaintext);
+                    let _ = sender.blocking_send(NetworkEvent::GotGrpcMessageDescription {
+                        message,
+                        description,
+                    });
+                });
+            }
             _ => {}
         }
     }
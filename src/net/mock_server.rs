@@ -1,60 +1,747 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::{Arc, Mutex};
-use warp::Filter;
+use tokio::sync::{mpsc, watch};
+use warp::{Filter, Reply};
 
-#[derive(Clone, Debug)]
+/// Where mock routes are kept between sessions
+pub const MOCKS_FILE: &str = "mocks.hcl";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct MockRoute {
+    /// Literal segments plus `:name` params, e.g. `/users/:id`
     pub path: String,
     pub method: String,
     pub status: u16,
+    /// Template filled in per request, see `render_template`
+    #[serde(default)]
     pub body: String,
+    #[serde(default)]
     pub headers: HashMap<String, String>,
+    /// Artificial latency before answering
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+    /// With `delay_ms`, the delay is picked randomly between the two
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_max_ms: Option<u64>,
+    /// Percentage of requests answered with a 500 instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_rate: Option<f64>,
+    /// Percentage of requests whose connection is cut before the body arrives
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drop_rate: Option<f64>,
+}
+
+/// `cors { ... }` in mocks.hcl: answers browser preflights and puts the
+/// `Access-Control-*` headers on every response, so a web app on another origin can
+/// call the mock directly
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CorsConfig {
+    /// A `cors {}` block turns it on, `enabled = false` keeps the settings but off
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    /// `["*"]`, or exact origins like `http://localhost:5173`
+    #[serde(default = "any")]
+    pub allow_origins: Vec<String>,
+    #[serde(default = "default_methods")]
+    pub allow_methods: Vec<String>,
+    /// `["*"]` allows whatever headers a preflight asks for
+    #[serde(default = "any")]
+    pub allow_headers: Vec<String>,
+    /// Response headers the page's script may read
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expose_headers: Vec<String>,
+    /// Lets the page send cookies; the request's origin is echoed instead of `*`
+    #[serde(default)]
+    pub allow_credentials: bool,
+    /// How long a browser may cache a preflight, in seconds
+    #[serde(default = "default_max_age", skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u64>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn any() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+fn default_methods() -> Vec<String> {
+    ["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"]
+        .iter()
+        .map(|m| m.to_string())
+        .collect()
+}
+
+fn default_max_age() -> Option<u64> {
+    Some(600)
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            enabled: true,
+            allow_origins: any(),
+            allow_methods: default_methods(),
+            allow_headers: any(),
+            expose_headers: Vec::new(),
+            allow_credentials: false,
+            max_age: default_max_age(),
+        }
+    }
+}
+
+impl CorsConfig {
+    /// The `Access-Control-Allow-Origin` value for a request from `origin`, None
+    /// when that origin isn't allowed
+    pub fn allowed_origin(&self, origin: Option<&str>) -> Option<String> {
+        let wildcard = self.allow_origins.iter().any(|o| o == "*");
+        match origin {
+            Some(origin)
+                if wildcard
+                    || self
+                        .allow_origins
+                        .iter()
+                        .any(|o| o.eq_ignore_ascii_case(origin)) =>
+            {
+                // `*` can't go with credentials, browsers want the origin itself
+                if wildcard && !self.allow_credentials {
+                    Some("*".to_string())
+                } else {
+                    Some(origin.to_string())
+                }
+            }
+            None if wildcard => Some("*".to_string()),
+            _ => None,
+        }
+    }
+
+    /// The headers every response to `origin` gets
+    pub fn response_headers(&self, origin: Option<&str>) -> Vec<(String, String)> {
+        let Some(allowed) = self.allowed_origin(origin) else {
+            return Vec::new();
+        };
+        let mut headers = Vec::new();
+        if allowed != "*" {
+            headers.push(("Vary".to_string(), "Origin".to_string()));
+        }
+        headers.push(("Access-Control-Allow-Origin".to_string(), allowed));
+        if self.allow_credentials {
+            headers.push((
+                "Access-Control-Allow-Credentials".to_string(),
+                "true".to_string(),
+            ));
+        }
+        if !self.expose_headers.is_empty() {
+            headers.push((
+                "Access-Control-Expose-Headers".to_string(),
+                self.expose_headers.join(", "),
+            ));
+        }
+        headers
+    }
+
+    /// The answer to a preflight from `origin` asking for `requested_headers`
+    pub fn preflight_headers(
+        &self,
+        origin: Option<&str>,
+        requested_headers: Option<&str>,
+    ) -> Vec<(String, String)> {
+        let mut headers = self.response_headers(origin);
+        if headers.is_empty() {
+            return headers;
+        }
+        headers.push((
+            "Access-Control-Allow-Methods".to_string(),
+            self.allow_methods.join(", "),
+        ));
+        let allow_headers = if self.allow_headers.iter().any(|h| h == "*") {
+            requested_headers.unwrap_or("*").to_string()
+        } else {
+            self.allow_headers.join(", ")
+        };
+        if !allow_headers.is_empty() {
+            headers.push(("Access-Control-Allow-Headers".to_string(), allow_headers));
+        }
+        if let Some(secs) = self.max_age {
+            headers.push(("Access-Control-Max-Age".to_string(), secs.to_string()));
+        }
+        headers
+    }
+}
+
+/// `tls { ... }` in mocks.hcl: serve HTTPS, with the given certificate or a
+/// self-signed one for localhost (see `mock_tls`)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    /// PEM files, relative to mocks.hcl. Without them a self-signed pair is made
+    /// once and kept next to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cert: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        TlsConfig {
+            enabled: true,
+            cert: None,
+            key: None,
+        }
+    }
+}
+
+/// Server-wide settings kept in mocks.hcl next to the routes
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MockSettings {
+    pub cors: Option<CorsConfig>,
+    pub tls: Option<TlsConfig>,
+    /// Where to listen, 127.0.0.1 unless `postdad mock --host` says otherwise.
+    /// Not saved.
+    pub host: Option<IpAddr>,
+}
+
+impl MockSettings {
+    pub fn cors_on(&self) -> bool {
+        self.cors.as_ref().is_some_and(|c| c.enabled)
+    }
+
+    pub fn tls_on(&self) -> bool {
+        self.tls.as_ref().is_some_and(|t| t.enabled)
+    }
+
+    pub fn host(&self) -> IpAddr {
+        self.host.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    }
+
+    /// `http://127.0.0.1:3000`
+    pub fn base_url(&self, port: u16) -> String {
+        let scheme = if self.tls_on() { "https" } else { "http" };
+        format!("{}://{}", scheme, SocketAddr::new(self.host(), port))
+    }
+}
+
+/// What a single mock request gets answered with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fault {
+    None,
+    Error,
+    Drop,
+}
+
+impl MockRoute {
+    /// Picks the delay for one request
+    pub fn delay(&self) -> Option<std::time::Duration> {
+        let min = self.delay_ms?;
+        let ms = match self.delay_max_ms {
+            Some(max) if max > min => rand::rng().random_range(min..=max),
+            _ => min,
+        };
+        Some(std::time::Duration::from_millis(ms))
+    }
+
+    /// Decides the fault for a roll in `0.0..100.0`
+    pub fn fault_for(&self, roll: f64) -> Fault {
+        let drop = self.drop_rate.unwrap_or(0.0);
+        let error = self.error_rate.unwrap_or(0.0);
+        if roll < drop {
+            Fault::Drop
+        } else if roll < drop + error {
+            Fault::Error
+        } else {
+            Fault::None
+        }
+    }
+}
+
+/// The parts of an incoming request that response templates can echo back
+#[derive(Clone, Debug, Default)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    pub params: Vec<(String, String)>,
+    pub query: Vec<(String, String)>,
+    /// Names lowercased
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// One request the mock server answered, for the `postdad mock` log
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MockHit {
+    /// Unix milliseconds
+    pub at_ms: u64,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub latency_ms: u64,
 }
 
+impl MockHit {
+    /// `12:04:31.250 GET /users/7 -> 200 (3ms)`, UTC
+    pub fn line(&self) -> String {
+        let ms = self.at_ms % 86_400_000;
+        format!(
+            "{:02}:{:02}:{:02}.{:03} {} {} -> {} ({}ms)",
+            ms / 3_600_000,
+            ms / 60_000 % 60,
+            ms / 1000 % 60,
+            ms % 1000,
+            self.method,
+            self.path,
+            self.status,
+            self.latency_ms
+        )
+    }
+}
+
+type HitLog = Arc<Mutex<Option<mpsc::UnboundedSender<MockHit>>>>;
+
 pub struct MockServerHandle {
-    pub handle: tokio::task::JoinHandle<()>,
+    /// Ends with an error when the port can't be bound
+    pub handle: tokio::task::JoinHandle<Result<(), String>>,
+    pub routes: Arc<Mutex<Vec<MockRoute>>>,
+    pub cors: Arc<Mutex<Option<CorsConfig>>>,
+    log: HitLog,
+    stop: watch::Sender<bool>,
 }
 
+impl MockServerHandle {
+    /// Swaps the routes of the running server, no restart needed
+    pub fn update_routes(&self, routes: Vec<MockRoute>) {
+        if let Ok(mut current) = self.routes.lock() {
+            *current = routes;
+        }
+    }
+
+    /// Swaps the CORS settings of the running server, None turns CORS off
+    pub fn update_cors(&self, cors: Option<CorsConfig>) {
+        if let Ok(mut current) = self.cors.lock() {
+            *current = cors.filter(|c| c.enabled);
+        }
+    }
+
+    /// Every request answered from now on
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<MockHit> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        if let Ok(mut log) = self.log.lock() {
+            *log = Some(tx);
+        }
+        rx
+    }
+
+    /// Stops taking connections and waits for the requests in flight
+    pub async fn shutdown(self) -> Result<(), String> {
+        let _ = self.stop.send(true);
+        self.handle.await.map_err(|e| e.to_string())?
+    }
+}
+
+/// Matches a request path against a route pattern, returning the `:param` values
+pub fn match_path(pattern: &str, path: &str) -> Option<Vec<(String, String)>> {
+    let pattern_parts: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let path_parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+    if pattern_parts.len() != path_parts.len() {
+        return None;
+    }
+
+    let mut params = Vec::new();
+    for (expected, actual) in pattern_parts.iter().zip(&path_parts) {
+        if let Some(name) = expected.strip_prefix(':') {
+            if actual.is_empty() {
+                return None;
+            }
+            params.push((name.to_string(), actual.to_string()));
+        } else if expected != actual {
+            return None;
+        }
+    }
+    Some(params)
+}
+
+/// First route matching the request. Routes without params win over parameterised ones,
+/// so `/users/me` can sit next to `/users/:id`.
+pub fn find_route<'a>(
+    routes: &'a [MockRoute],
+    method: &str,
+    path: &str,
+) -> Option<(&'a MockRoute, Vec<(String, String)>)> {
+    let candidates = routes
+        .iter()
+        .filter(|r| r.method.eq_ignore_ascii_case(method));
+    let mut fallback = None;
+    for route in candidates {
+        if let Some(params) = match_path(&route.path, path) {
+            if params.is_empty() {
+                return Some((route, params));
+            }
+            if fallback.is_none() {
+                fallback = Some((route, params));
+            }
+        }
+    }
+    fallback
+}
+
+fn lookup(pairs: &[(String, String)], key: &str) -> Option<String> {
+    pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone())
+}
+
+/// Value for one placeholder, `None` leaves it in the output untouched
+fn resolve(expr: &str, request: &MockRequest) -> Option<String> {
+    match expr {
+        "method" => return Some(request.method.clone()),
+        "path" => return Some(request.path.clone()),
+        "body" => return Some(request.body.clone()),
+        _ => {}
+    }
+    let (scope, key) = expr.split_once('.')?;
+    match scope {
+        "params" => lookup(&request.params, key),
+        "query" => lookup(&request.query, key),
+        "headers" => lookup(&request.headers, &key.to_lowercase()),
+        "body" => {
+            let json: serde_json::Value = serde_json::from_str(&request.body).ok()?;
+            let mut selector = jsonpath_lib::selector(&json);
+            let found = selector(&format!("$.{}", key)).ok()?;
+            let value = found.first()?;
+            Some(
+                value
+                    .as_str()
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| value.to_string()),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Response body template: `{{params.id}}`, `{{query.page}}`, `{{headers.x-request-id}}`,
+/// `{{body}}` / `{{body.user.name}}`, `{{method}}`, `{{path}}` echo the request,
+/// `{{$uuid}}`, `{{$randomInt 1 10}}`, ... produce random data.
+pub fn render_template(template: &str, request: &MockRequest) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let expr = after[..end].trim();
+        match resolve(expr, request).filter(|_| !expr.starts_with('$')) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+
+    crate::features::template::expand(&out)
+}
+
+pub fn load_routes(path: &str) -> Result<Vec<MockRoute>, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let body: hcl::Body =
+        hcl::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path, e))?;
+
+    body.blocks()
+        .filter(|block| block.identifier() == "route")
+        .map(|block| {
+            hcl::from_body(block.body().clone())
+                .map_err(|e| format!("Invalid route in {}: {}", path, e))
+        })
+        .collect()
+}
+
+/// The `cors` and `tls` blocks of a mocks file, defaults when it has none
+pub fn load_settings(path: &str) -> Result<MockSettings, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(MockSettings::default()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let body: hcl::Body =
+        hcl::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path, e))?;
+    let block = |name: &str| body.blocks().find(|b| b.identifier() == name).cloned();
+    Ok(MockSettings {
+        cors: block("cors")
+            .map(|b| hcl::from_body(b.body().clone()))
+            .transpose()
+            .map_err(|e| format!("Invalid cors block in {}: {}", path, e))?,
+        tls: block("tls")
+            .map(|b| hcl::from_body(b.body().clone()))
+            .transpose()
+            .map_err(|e| format!("Invalid tls block in {}: {}", path, e))?,
+        host: None,
+    })
+}
+
+fn settings_hcl(settings: &MockSettings) -> Result<String, String> {
+    let mut content = String::new();
+    if let Some(cors) = &settings.cors {
+        let body = hcl::to_string(cors).map_err(|e| e.to_string())?;
+        content.push_str(&format!("cors {{\n{}}}\n\n", body));
+    }
+    if let Some(tls) = &settings.tls {
+        let body = hcl::to_string(tls).map_err(|e| e.to_string())?;
+        content.push_str(&format!("tls {{\n{}}}\n\n", body));
+    }
+    Ok(content)
+}
+
+/// Writes the server settings, keeping the routes already in the file
+pub fn save_settings(path: &str, settings: &MockSettings) -> Result<(), String> {
+    let routes = load_routes(path)?;
+    write_mocks(path, &routes, settings)
+}
+
+/// Writes the routes, keeping the server settings already in the file
+pub fn save_routes(path: &str, routes: &[MockRoute]) -> Result<(), String> {
+    let settings = load_settings(path)?;
+    write_mocks(path, routes, &settings)
+}
+
+fn write_mocks(path: &str, routes: &[MockRoute], settings: &MockSettings) -> Result<(), String> {
+    let mut content = settings_hcl(settings)?;
+    for route in routes {
+        let body_hcl = hcl::to_string(route).map_err(|e| e.to_string())?;
+        content.push_str(&format!("route {{\n{}}}\n\n", body_hcl));
+    }
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// Answers one request from the routes; CORS is added by the caller
+async fn respond(
+    routes: &Mutex<Vec<MockRoute>>,
+    method: &str,
+    path: &str,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: String,
+) -> warp::reply::Response {
+    let found = {
+        let routes = routes.lock().unwrap();
+        find_route(&routes, method, path).map(|(route, params)| (route.clone(), params))
+    };
+    let Some((route, params)) = found else {
+        return warp::http::Response::builder()
+            .status(404)
+            .body(format!("Mock Not Found: {} {}", method, path))
+            .unwrap()
+            .into_response();
+    };
+
+    if let Some(delay) = route.delay() {
+        tokio::time::sleep(delay).await;
+    }
+
+    match route.fault_for(rand::rng().random_range(0.0..100.0)) {
+        Fault::Error => {
+            return warp::http::Response::builder()
+                .status(500)
+                .header("Content-Type", "application/json")
+                .body(r#"{"error": "Injected fault"}"#.to_string())
+                .unwrap()
+                .into_response();
+        }
+        Fault::Drop => {
+            // A body stream that fails right away makes hyper abort the
+            // connection after the headers, like a crashed upstream
+            let failing = futures_util::stream::once(async {
+                Err::<warp::sse::Event, _>(std::io::Error::other("Injected drop"))
+            });
+            let mut resp = warp::sse::reply(failing).into_response();
+            *resp.status_mut() = warp::http::StatusCode::from_u16(route.status)
+                .unwrap_or(warp::http::StatusCode::OK);
+            return resp;
+        }
+        Fault::None => {}
+    }
+
+    let request = MockRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        params,
+        query,
+        headers,
+        body,
+    };
+
+    let mut resp = warp::http::Response::builder().status(route.status);
+    for (k, v) in &route.headers {
+        resp = resp.header(k, v);
+    }
+    resp.body(render_template(&route.body, &request))
+        .unwrap_or_else(|_| warp::http::Response::new("Internal Server Error".to_string()))
+        .into_response()
+}
+
+/// A plain HTTP mock server on `port`, what tests start
+#[cfg(test)]
 pub fn start_mock_server(port: u16, routes: Vec<MockRoute>) -> MockServerHandle {
+    start_mock_server_with(port, routes, &MockSettings::default(), None)
+}
+
+/// The mock server with CORS, and HTTPS when `tls` is given (see
+/// `mock_tls::acceptor`)
+pub fn start_mock_server_with(
+    port: u16,
+    routes: Vec<MockRoute>,
+    settings: &MockSettings,
+    tls: Option<tokio_native_tls::TlsAcceptor>,
+) -> MockServerHandle {
     let routes_state = Arc::new(Mutex::new(routes));
-    let state_filter = warp::any().map(move || routes_state.clone());
+    let cors_state = Arc::new(Mutex::new(settings.cors.clone().filter(|c| c.enabled)));
+    let shared_routes = routes_state.clone();
+    let shared_cors = cors_state.clone();
+    let log_state: HitLog = Arc::new(Mutex::new(None));
+    let shared_log = log_state.clone();
+    let routes_filter = warp::any().map(move || routes_state.clone());
+    let cors_filter = warp::any().map(move || cors_state.clone());
+    let log_filter = warp::any().map(move || log_state.clone());
 
     let handler = warp::any()
         .and(warp::path::full())
         .and(warp::method())
-        .and(state_filter)
-        .map(
+        .and(warp::query::<Vec<(String, String)>>())
+        .and(warp::header::headers_cloned())
+        .and(warp::body::bytes())
+        .and(routes_filter)
+        .and(cors_filter)
+        .and(log_filter)
+        .then(
             |path: warp::path::FullPath,
              method: warp::http::Method,
-             state: Arc<Mutex<Vec<MockRoute>>>| {
-                let path_str = path.as_str();
-                let method_str = method.as_str();
-
-                let routes = state.lock().unwrap();
-
-                if let Some(route) = routes
+             query: Vec<(String, String)>,
+             headers: warp::http::HeaderMap,
+             body: warp::hyper::body::Bytes,
+             routes: Arc<Mutex<Vec<MockRoute>>>,
+             cors: Arc<Mutex<Option<CorsConfig>>>,
+             log: HitLog| async move {
+                let started = std::time::Instant::now();
+                let headers: Vec<(String, String)> = headers
                     .iter()
-                    .find(|r| r.path == path_str && r.method == method_str)
-                {
-                    let mut resp = warp::http::Response::builder().status(route.status);
+                    .map(|(k, v)| {
+                        (
+                            k.as_str().to_lowercase(),
+                            v.to_str().unwrap_or("").to_string(),
+                        )
+                    })
+                    .collect();
+                let cors = cors.lock().unwrap().clone();
+                let origin = lookup(&headers, "origin");
 
-                    for (k, v) in &route.headers {
-                        resp = resp.header(k, v);
+                // A preflight nobody wrote an OPTIONS route for
+                let preflight = method == warp::http::Method::OPTIONS
+                    && lookup(&headers, "access-control-request-method").is_some()
+                    && find_route(&routes.lock().unwrap(), "OPTIONS", path.as_str()).is_none();
+                let (mut resp, cors_headers) = match &cors {
+                    Some(cors) if preflight => {
+                        let requested = lookup(&headers, "access-control-request-headers");
+                        let resp = warp::http::Response::builder()
+                            .status(204)
+                            .body(String::new())
+                            .unwrap()
+                            .into_response();
+                        (
+                            resp,
+                            cors.preflight_headers(origin.as_deref(), requested.as_deref()),
+                        )
                     }
-
-                    resp.body(route.body.clone()).unwrap_or_else(|_| {
-                        warp::http::Response::new("Internal Server Error".to_string())
-                    })
-                } else {
-                    warp::http::Response::builder()
-                        .status(404)
-                        .body(format!("Mock Not Found: {} {}", method_str, path_str))
-                        .unwrap()
+                    _ => {
+                        let resp = respond(
+                            &routes,
+                            method.as_str(),
+                            path.as_str(),
+                            query,
+                            headers,
+                            String::from_utf8_lossy(&body).to_string(),
+                        )
+                        .await;
+                        let cors_headers = cors
+                            .map(|c| c.response_headers(origin.as_deref()))
+                            .unwrap_or_default();
+                        (resp, cors_headers)
+                    }
+                };
+                for (name, value) in cors_headers {
+                    if let (Ok(name), Ok(value)) = (
+                        warp::http::HeaderName::from_bytes(name.as_bytes()),
+                        warp::http::HeaderValue::from_str(&value),
+                    ) {
+                        resp.headers_mut().insert(name, value);
+                    }
+                }
+                if let Some(log) = log.lock().unwrap().as_ref() {
+                    let _ = log.send(MockHit {
+                        at_ms: std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .map(|d| d.as_millis() as u64)
+                            .unwrap_or(0),
+                        method: method.to_string(),
+                        path: path.as_str().to_string(),
+                        status: resp.status().as_u16(),
+                        latency_ms: started.elapsed().as_millis() as u64,
+                    });
                 }
+                resp
             },
         );
 
-    let handle = tokio::spawn(warp::serve(handler).run(([127, 0, 0, 1], port)));
+    let (stop, stopped) = watch::channel(false);
+    let graceful = |mut stopped: watch::Receiver<bool>| async move {
+        let _ = stopped.wait_for(|stop| *stop).await;
+    };
+    let addr = SocketAddr::new(settings.host(), port);
+    let handle = tokio::spawn(async move {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| format!("Could not listen on {}: {}", addr, e))?;
+        match tls {
+            None => {
+                warp::serve(handler)
+                    .incoming(listener)
+                    .graceful(graceful(stopped))
+                    .run()
+                    .await
+            }
+            Some(acceptor) => {
+                // TLS is done in front of a plain server on a loopback port of its own
+                let inner = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let inner_addr = inner.local_addr().map_err(|e| e.to_string())?;
+                tokio::join!(
+                    warp::serve(handler)
+                        .incoming(inner)
+                        .graceful(graceful(stopped.clone()))
+                        .run(),
+                    crate::net::mock_tls::serve(listener, inner_addr, acceptor, stopped),
+                );
+            }
+        }
+        Ok(())
+    });
 
-    MockServerHandle { handle }
+    MockServerHandle {
+        handle,
+        routes: shared_routes,
+        cors: shared_cors,
+        log: shared_log,
+        stop,
+    }
 }
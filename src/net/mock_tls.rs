@@ -0,0 +1,146 @@
+// HTTPS for the mock server: a self-signed certificate for localhost (or the user's
+// own pair), and a TLS listener that hands each decrypted connection to the plain
+// mock server behind it.
+use openssl::asn1::Asn1Time;
+use openssl::bn::{BigNum, MsbOption};
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::rsa::Rsa;
+use openssl::x509::extension::{ExtendedKeyUsage, SubjectAlternativeName};
+use openssl::x509::{X509, X509NameBuilder};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use super::mock_server::TlsConfig;
+
+/// Where the generated pair is kept, next to mocks.hcl
+pub const CERT_FILE: &str = "mock-cert.pem";
+pub const KEY_FILE: &str = "mock-key.pem";
+
+/// A certificate for `localhost`, `127.0.0.1` and `::1`, valid for a year:
+/// (certificate PEM, PKCS#8 key PEM)
+pub fn self_signed() -> Result<(Vec<u8>, Vec<u8>), String> {
+    let build = || -> Result<(Vec<u8>, Vec<u8>), openssl::error::ErrorStack> {
+        let key = PKey::from_rsa(Rsa::generate(2048)?)?;
+        let mut name = X509NameBuilder::new()?;
+        name.append_entry_by_text("CN", "PostDad mock server")?;
+        let name = name.build();
+
+        let mut serial = BigNum::new()?;
+        serial.rand(128, MsbOption::MAYBE_ZERO, false)?;
+        let mut cert = X509::builder()?;
+        cert.set_version(2)?;
+        let serial = serial.to_asn1_integer()?;
+        cert.set_serial_number(&serial)?;
+        cert.set_subject_name(&name)?;
+        cert.set_issuer_name(&name)?;
+        cert.set_pubkey(&key)?;
+        let not_before = Asn1Time::days_from_now(0)?;
+        let not_after = Asn1Time::days_from_now(365)?;
+        cert.set_not_before(&not_before)?;
+        cert.set_not_after(&not_after)?;
+        let san = SubjectAlternativeName::new()
+            .dns("localhost")
+            .ip("127.0.0.1")
+            .ip("::1")
+            .build(&cert.x509v3_context(None, None))?;
+        cert.append_extension(san)?;
+        cert.append_extension(ExtendedKeyUsage::new().server_auth().build()?)?;
+        cert.sign(&key, MessageDigest::sha256())?;
+        Ok((cert.build().to_pem()?, key.private_key_to_pem_pkcs8()?))
+    };
+    build().map_err(|e| format!("Could not make a certificate: {}", e))
+}
+
+/// PEM certificate and PKCS#8 key to serve with
+pub struct Identity {
+    pub cert: Vec<u8>,
+    pub key: Vec<u8>,
+    /// Set when a self-signed pair was just made and saved here
+    pub created: Option<PathBuf>,
+}
+
+/// The configured files, or the generated pair in `dir` (made on first use)
+pub fn identity(dir: &Path, config: &TlsConfig) -> Result<Identity, String> {
+    let read = |file: &str| {
+        let path = dir.join(file);
+        std::fs::read(&path).map_err(|e| format!("Could not read {}: {}", path.display(), e))
+    };
+    match (&config.cert, &config.key) {
+        (Some(cert), Some(key)) => Ok(Identity {
+            cert: read(cert)?,
+            key: read(key)?,
+            created: None,
+        }),
+        (None, None) => {
+            if dir.join(CERT_FILE).exists() && dir.join(KEY_FILE).exists() {
+                return Ok(Identity {
+                    cert: read(CERT_FILE)?,
+                    key: read(KEY_FILE)?,
+                    created: None,
+                });
+            }
+            let (cert, key) = self_signed()?;
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            let cert_path = dir.join(CERT_FILE);
+            std::fs::write(&cert_path, &cert).map_err(|e| e.to_string())?;
+            write_private(&dir.join(KEY_FILE), &key).map_err(|e| e.to_string())?;
+            Ok(Identity {
+                cert,
+                key,
+                created: Some(cert_path),
+            })
+        }
+        _ => Err("tls needs both cert and key, or neither for a self-signed pair".to_string()),
+    }
+}
+
+/// Writes the key readable by its owner only (0600 on unix)
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents)
+}
+
+pub fn acceptor(cert: &[u8], key: &[u8]) -> Result<tokio_native_tls::TlsAcceptor, String> {
+    let identity = tokio_native_tls::native_tls::Identity::from_pkcs8(cert, key)
+        .map_err(|e| format!("Bad certificate or key: {}", e))?;
+    tokio_native_tls::native_tls::TlsAcceptor::new(identity)
+        .map(tokio_native_tls::TlsAcceptor::from)
+        .map_err(|e| format!("TLS setup failed: {}", e))
+}
+
+/// Accepts TLS on `listener` and pipes each connection to the plain server at
+/// `inner`, until `stop` turns true
+pub async fn serve(
+    listener: tokio::net::TcpListener,
+    inner: SocketAddr,
+    acceptor: tokio_native_tls::TlsAcceptor,
+    mut stop: tokio::sync::watch::Receiver<bool>,
+) {
+    loop {
+        let tcp = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((tcp, _)) => tcp,
+                Err(_) => continue,
+            },
+            _ = stop.wait_for(|stop| *stop) => return,
+        };
+        let acceptor = acceptor.clone();
+        tokio::spawn(async move {
+            let mut tls = match acceptor.accept(tcp).await {
+                Ok(tls) => tls,
+                Err(e) => {
+                    tracing::debug!(error = %e, "mock TLS handshake failed");
+                    return;
+                }
+            };
+            if let Ok(mut plain) = tokio::net::TcpStream::connect(inner).await {
+                let _ = tokio::io::copy_bidirectional(&mut tls, &mut plain).await;
+            }
+        });
+    }
+}
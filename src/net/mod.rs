@@ -1,4 +1,10 @@
+pub mod encoding;
 pub mod grpc;
 pub mod http;
 pub mod mock_server;
+pub mod mock_tls;
+pub mod pool;
+pub mod rate_limit;
+pub mod streaming;
+pub mod timeouts;
 pub mod websocket;
@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Connection reuse and DNS caching for the HTTP client. Stored under `connection`
+/// in `config.json`, changed at runtime with `:pool`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolSettings {
+    /// Every request opens a fresh connection
+    pub disable_pooling: bool,
+    pub max_idle_per_host: Option<usize>,
+    /// How long an idle connection is kept around (reqwest default: 90s)
+    pub idle_timeout_secs: Option<u64>,
+    /// Cache resolved addresses this long, whatever the record's own TTL says.
+    /// Unset means no caching, every new connection does a lookup.
+    pub dns_ttl_secs: Option<u64>,
+    /// A brand new client for every request, none kept: cold DNS, TCP and TLS each
+    /// time, for timing a first request the way a new visitor would see it
+    pub fresh_client: bool,
+}
+
+impl PoolSettings {
+    pub fn apply(&self, mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        if self.disable_pooling {
+            builder = builder.pool_max_idle_per_host(0);
+        } else if let Some(max) = self.max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(secs) = self.idle_timeout_secs {
+            builder = builder.pool_idle_timeout(Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.dns_ttl_secs {
+            builder = builder.dns_resolver(DnsCache::new(Duration::from_secs(secs)));
+        }
+        builder
+    }
+
+    pub fn describe(&self) -> String {
+        let pool = if self.disable_pooling {
+            "pooling off".to_string()
+        } else {
+            format!(
+                "pooling on, idle/host {}, idle timeout {}",
+                self.max_idle_per_host
+                    .map(|n| n.to_string())
+                    .unwrap_or("∞".to_string()),
+                self.idle_timeout_secs
+                    .map(|s| format!("{}s", s))
+                    .unwrap_or("90s".to_string())
+            )
+        };
+        let dns = self
+            .dns_ttl_secs
+            .map(|s| format!("DNS cache {}s", s))
+            .unwrap_or("DNS cache off".to_string());
+        let fresh = if self.fresh_client {
+            ", new client per request"
+        } else {
+            ""
+        };
+        format!("{}, {}{}", pool, dns, fresh)
+    }
+}
+
+/// `:pool on|off`, `:pool idle <n|default>`, `:pool timeout <secs|default>`,
+/// `:pool dns <secs|off>`, `:pool fresh on|off`. Returns the new settings summary.
+pub fn apply_pool_command(settings: &mut PoolSettings, args: &str) -> Result<String, String> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let unset = |v: &str| matches!(v, "off" | "default" | "none");
+    match parts.as_slice() {
+        [] => {}
+        ["on"] => settings.disable_pooling = false,
+        ["off"] => settings.disable_pooling = true,
+        ["idle", v] if unset(v) => settings.max_idle_per_host = None,
+        ["idle", v] => {
+            settings.max_idle_per_host =
+                Some(v.parse().map_err(|_| format!("Not a number: {}", v))?)
+        }
+        ["timeout", v] if unset(v) => settings.idle_timeout_secs = None,
+        ["timeout", v] => {
+            settings.idle_timeout_secs =
+                Some(v.parse().map_err(|_| format!("Not a number: {}", v))?)
+        }
+        ["dns", v] if unset(v) => settings.dns_ttl_secs = None,
+        ["dns", v] => {
+            settings.dns_ttl_secs = Some(v.parse().map_err(|_| format!("Not a number: {}", v))?)
+        }
+        ["fresh", "on"] => settings.fresh_client = true,
+        ["fresh", "off"] => settings.fresh_client = false,
+        _ => {
+            return Err(
+                "Usage: :pool [on|off|idle <n>|timeout <secs>|dns <secs|off>|fresh on|off]"
+                    .to_string(),
+            );
+        }
+    }
+    Ok(settings.describe())
+}
+
+/// Clients kept to be reused, one per set of settings (TLS, proxy, pool, pinned
+/// hosts) they were built with. Going back and forth between environments with
+/// different proxies keeps each one's warm connections instead of rebuilding.
+pub struct ClientCache<K, C> {
+    capacity: usize,
+    /// Least recently used first
+    entries: Vec<(K, C)>,
+}
+
+impl<K: PartialEq, C: Clone> ClientCache<K, C> {
+    /// Clients kept at most, the least recently used goes first
+    pub const CAPACITY: usize = 4;
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Vec::new(),
+        }
+    }
+
+    /// The client built for `key`, built with `build` and kept if there isn't one.
+    /// The flag says whether it was reused.
+    pub fn get_or_build(&mut self, key: K, build: impl FnOnce() -> C) -> (C, bool) {
+        if let Some(i) = self.entries.iter().position(|(k, _)| *k == key) {
+            let entry = self.entries.remove(i);
+            let client = entry.1.clone();
+            self.entries.push(entry);
+            return (client, true);
+        }
+        let client = build();
+        if self.entries.len() >= self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push((key, client.clone()));
+        (client, false)
+    }
+
+    /// Drops every client and the connections they hold
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Host -> (resolved at, addresses)
+type DnsEntries = HashMap<String, (Instant, Vec<SocketAddr>)>;
+
+/// Resolver that remembers answers for a fixed TTL, so a test can pin a host to the
+/// address it had at first lookup (or flush and see the new one).
+#[derive(Clone)]
+pub struct DnsCache {
+    ttl: Duration,
+    entries: Arc<Mutex<DnsEntries>>,
+}
+
+impl DnsCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn lookup_at(&self, host: &str, now: Instant) -> Option<Vec<SocketAddr>> {
+        let entries = self.entries.lock().ok()?;
+        let (stored, addrs) = entries.get(host)?;
+        (now.duration_since(*stored) < self.ttl).then(|| addrs.clone())
+    }
+
+    pub fn store_at(&self, host: &str, addrs: Vec<SocketAddr>, now: Instant) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(host.to_string(), (now, addrs));
+        }
+    }
+}
+
+impl reqwest::dns::Resolve for DnsCache {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let cache = self.clone();
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = match cache.lookup_at(&host, Instant::now()) {
+                Some(addrs) => addrs,
+                None => {
+                    let addrs: Vec<SocketAddr> =
+                        tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+                    cache.store_at(&host, addrs.clone(), Instant::now());
+                    addrs
+                }
+            };
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Host (lowercase) -> addresses to connect to instead of asking DNS
+pub type HostOverrides = Vec<(String, Vec<SocketAddr>)>;
+
+/// An environment's `resolve` table, checked. Several addresses are comma-separated.
+/// A port on either side is dropped: the URL's port is used, as with curl's `--resolve`.
+pub fn parse_overrides(
+    resolve: &std::collections::BTreeMap<String, String>,
+) -> Result<HostOverrides, String> {
+    resolve
+        .iter()
+        .map(|(host, addrs)| {
+            let addrs = addrs
+                .split(',')
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .map(|a| {
+                    a.parse::<SocketAddr>()
+                        .map(|s| s.ip())
+                        .or_else(|_| a.trim_matches(['[', ']']).parse())
+                        .map(|ip| SocketAddr::new(ip, 0))
+                        .map_err(|_| format!("resolve: '{}' for {} is not an IP address", a, host))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            if addrs.is_empty() {
+                return Err(format!("resolve: no address for {}", host));
+            }
+            Ok((override_host(host), addrs))
+        })
+        .collect()
+}
+
+/// Points every pinned host of `resolve` at its addresses. Every client that sends
+/// user requests goes through this, so the pins hold outside the main send too.
+pub fn apply_overrides(
+    mut builder: reqwest::ClientBuilder,
+    resolve: &HostOverrides,
+) -> reqwest::ClientBuilder {
+    for (host, addrs) in resolve {
+        builder = builder.resolve_to_addrs(host, addrs);
+    }
+    builder
+}
+
+/// `API.example.com:443` -> `api.example.com`
+fn override_host(host: &str) -> String {
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if port.parse::<u16>().is_ok() && !name.contains(':') => name,
+        _ => host,
+    };
+    host.to_lowercase()
+}
+
+/// The `resolve` entry for `host`, if it's pinned
+pub fn override_for<'a>(
+    resolve: &'a std::collections::BTreeMap<String, String>,
+    host: &str,
+) -> Option<&'a String> {
+    let host = host.to_lowercase();
+    resolve
+        .iter()
+        .find(|(pinned, _)| override_host(pinned) == host)
+        .map(|(_, addrs)| addrs)
+}
@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Request pacing for the background senders: collection runs, stress tests and
+/// sentinel. Stored under `rate_limit` in `config.json`, changed with `:ratelimit`.
+/// Requests sent by hand are never held back.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitSettings {
+    /// A collection run sends at most this many requests a second
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runner_rps: Option<f64>,
+    /// No host gets more than this many requests a second from all of them together
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_host_rps: Option<f64>,
+    /// Hosts with a limit of their own, over `per_host_rps`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub hosts: BTreeMap<String, f64>,
+}
+
+/// The slowest rate a limit can be set to, one request every 1000 seconds. Slower
+/// ones would space requests further apart than a `Duration` can hold.
+pub const MIN_RPS: f64 = 0.001;
+
+/// A rate a limiter can space requests by: at least `MIN_RPS` and finite
+pub fn is_rate(rps: f64) -> bool {
+    rps >= MIN_RPS && rps.is_finite()
+}
+
+impl RateLimitSettings {
+    /// Without the rates `is_rate` turns down, which a hand-edited config.json can
+    /// have; those limits are off
+    pub fn sanitized(mut self) -> Self {
+        self.runner_rps = self.runner_rps.filter(|rps| is_rate(*rps));
+        self.per_host_rps = self.per_host_rps.filter(|rps| is_rate(*rps));
+        self.hosts.retain(|_, rps| is_rate(*rps));
+        self
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.runner_rps.is_some() || self.per_host_rps.is_some() || !self.hosts.is_empty()
+    }
+
+    /// The limit for `host`, its own or the global one
+    pub fn host_rps(&self, host: &str) -> Option<f64> {
+        self.hosts
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(host))
+            .map(|(_, rps)| *rps)
+            .or(self.per_host_rps)
+    }
+
+    pub fn describe(&self) -> String {
+        let rate = |rps: Option<f64>| rps.map(|r| format!("{}/s", r)).unwrap_or("off".into());
+        let mut summary = format!(
+            "runs {}, per host {}",
+            rate(self.runner_rps),
+            rate(self.per_host_rps)
+        );
+        for (host, rps) in &self.hosts {
+            summary.push_str(&format!(", {} {}/s", host, rps));
+        }
+        summary
+    }
+}
+
+/// `:ratelimit run <rps|off>`, `:ratelimit host <rps|off>`,
+/// `:ratelimit host <name> <rps|off>`, `:ratelimit off`. Returns the new summary.
+pub fn apply_rate_limit_command(
+    settings: &mut RateLimitSettings,
+    args: &str,
+) -> Result<String, String> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let rate = |v: &str| -> Result<Option<f64>, String> {
+        if matches!(v, "off" | "none") {
+            return Ok(None);
+        }
+        match v.trim_end_matches("/s").parse::<f64>() {
+            Ok(rps) if is_rate(rps) => Ok(Some(rps)),
+            _ => Err(format!("Not a rate: {} (requests a second, or off)", v)),
+        }
+    };
+    match parts.as_slice() {
+        [] => {}
+        ["off"] => *settings = RateLimitSettings::default(),
+        ["run", v] => settings.runner_rps = rate(v)?,
+        ["host", v] => settings.per_host_rps = rate(v)?,
+        ["host", host, v] => match rate(v)? {
+            Some(rps) => {
+                settings.hosts.insert(host.to_lowercase(), rps);
+            }
+            None => {
+                settings.hosts.remove(&host.to_lowercase());
+            }
+        },
+        _ => {
+            return Err("Usage: :ratelimit [run <rps|off>|host [name] <rps|off>|off]".to_string());
+        }
+    }
+    Ok(settings.describe())
+}
+
+/// Hands out send times spaced `1/rps` apart, per key. A request that can go now
+/// takes the next slot; one that comes too soon is given a later one and waits.
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    next: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes the next slot for `key` at `rps` and says how long after `now` it is.
+    /// A rate that isn't one holds nothing back.
+    pub fn reserve_at(&self, key: &str, rps: f64, now: Instant) -> Duration {
+        if !is_rate(rps) {
+            return Duration::ZERO;
+        }
+        let Ok(mut next) = self.next.lock() else {
+            return Duration::ZERO;
+        };
+        let slot = next.get(key).copied().unwrap_or(now).max(now);
+        next.insert(key.to_string(), slot + Duration::from_secs_f64(1.0 / rps));
+        slot - now
+    }
+
+    /// Gives back a slot taken with `reserve_at` that won't be used, so the next
+    /// request can have it instead of queueing behind it
+    pub fn release_at(&self, key: &str, rps: f64, now: Instant) {
+        if !is_rate(rps) {
+            return;
+        }
+        let Ok(mut next) = self.next.lock() else {
+            return;
+        };
+        if let Some(slot) = next.get_mut(key) {
+            *slot = slot
+                .checked_sub(Duration::from_secs_f64(1.0 / rps))
+                .unwrap_or(now)
+                .max(now);
+        }
+    }
+}
+
+/// The limiter every background sender shares, so a run and a stress test against
+/// the same host count together
+pub fn shared() -> &'static RateLimiter {
+    static SHARED: OnceLock<RateLimiter> = OnceLock::new();
+    SHARED.get_or_init(RateLimiter::new)
+}
+
+/// Why a request is being held back, and for how long
+#[derive(Clone, Debug, PartialEq)]
+pub struct Throttle {
+    pub wait: Duration,
+    pub reason: String,
+}
+
+/// Reserves `url`'s host a slot on the shared limiter, and the run a slot on `run`
+/// when it's paced. The caller sleeps for `wait`; `None` means it can go right away.
+pub fn reserve(
+    settings: &RateLimitSettings,
+    run: Option<&RateLimiter>,
+    url: &str,
+) -> Option<Throttle> {
+    let now = Instant::now();
+    let mut throttle: Option<Throttle> = None;
+    let mut hold = |wait: Duration, reason: String| {
+        if !wait.is_zero() && throttle.as_ref().is_none_or(|t| wait > t.wait) {
+            throttle = Some(Throttle { wait, reason });
+        }
+    };
+    if let (Some(limiter), Some(rps)) = (run, settings.runner_rps) {
+        hold(
+            limiter.reserve_at("", rps, now),
+            format!("run at {}/s", rps),
+        );
+    }
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_lowercase));
+    if let Some(host) = host
+        && let Some(rps) = settings.host_rps(&host)
+    {
+        let wait = shared().reserve_at(&host, rps, now);
+        hold(wait, format!("{} at {}/s", host, rps));
+    }
+    throttle
+}
+
+/// Undoes a `reserve` whose request is not going to be sent after all
+pub fn release(settings: &RateLimitSettings, run: Option<&RateLimiter>, url: &str) {
+    let now = Instant::now();
+    if let (Some(limiter), Some(rps)) = (run, settings.runner_rps) {
+        limiter.release_at("", rps, now);
+    }
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_lowercase));
+    if let Some(host) = host
+        && let Some(rps) = settings.host_rps(&host)
+    {
+        shared().release_at(&host, rps, now);
+    }
+}
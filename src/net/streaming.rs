@@ -0,0 +1,53 @@
+// Responses shown while they're still coming in: NDJSON feeds, logs, event
+// streams. The network task sends the body in pieces, the response pane appends
+// them and keeps the end in view while following.
+use std::collections::HashMap;
+
+/// Whether a response's body is worth showing as it arrives: no length up front
+/// (chunked, or read until close) and not compressed, so each piece reads as is
+pub fn is_streaming(headers: &HashMap<String, String>) -> bool {
+    let header = |name: &str| headers.get(name).map(|v| v.to_ascii_lowercase());
+    let open_ended = header("transfer-encoding").is_some_and(|v| v.contains("chunked"))
+        || !headers.contains_key("content-length");
+    let compressed = header("content-encoding").is_some_and(|v| v.trim() != "identity");
+    open_ended && !compressed
+}
+
+/// Turns chunks into text, holding back a character split between two chunks
+#[derive(Clone, Debug, Default)]
+pub struct StreamText {
+    pending: Vec<u8>,
+    /// Bytes received so far
+    pub received: usize,
+}
+
+impl StreamText {
+    pub fn push(&mut self, bytes: &[u8], text: &mut String) {
+        self.received += bytes.len();
+        self.pending.extend_from_slice(bytes);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            // Cut short at the end: wait for the rest of the character
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => {
+                text.push_str(&String::from_utf8_lossy(&self.pending));
+                self.pending.clear();
+                return;
+            }
+        };
+        let rest = self.pending.split_off(valid);
+        text.push_str(&String::from_utf8_lossy(&self.pending));
+        self.pending = rest;
+    }
+}
+
+/// The scroll offset that puts the last line of `text` at the bottom of a pane
+/// `width` columns wide and `height` rows high, counting wrapped lines
+pub fn tail_scroll(text: &str, width: u16, height: u16) -> u16 {
+    let width = width.max(1) as usize;
+    let rows: usize = text
+        .lines()
+        .map(|line| line.chars().count().div_ceil(width).max(1))
+        .sum();
+    rows.saturating_sub(height as usize).min(u16::MAX as usize) as u16
+}
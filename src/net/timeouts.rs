@@ -0,0 +1,98 @@
+// A request's timeouts: the whole exchange, opening the connection, and the wait for
+// each read of the response. Set in the Settings tab or with `:timeout`, saved with
+// the request as `timeout_ms`, `connect_timeout_ms` and `read_timeout_ms`.
+use std::time::Duration;
+
+/// The whole request when nothing else is set
+pub const DEFAULT_TOTAL_MS: u64 = 30_000;
+
+/// One of the timeouts, as the Settings tab lists them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Total,
+    Connect,
+    Read,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 3] = [Phase::Total, Phase::Connect, Phase::Read];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Phase::Total => "Total",
+            Phase::Connect => "Connect",
+            Phase::Read => "Read",
+        }
+    }
+
+    /// What an unset value means
+    pub fn unset(self) -> &'static str {
+        match self {
+            Phase::Total => "30s (default)",
+            Phase::Connect => "within total",
+            Phase::Read => "within total",
+        }
+    }
+
+    pub fn parse(word: &str) -> Option<Phase> {
+        match word.to_lowercase().as_str() {
+            "total" | "request" => Some(Phase::Total),
+            "connect" => Some(Phase::Connect),
+            "read" => Some(Phase::Read),
+            _ => None,
+        }
+    }
+}
+
+/// The phase timeouts the HTTP client is built with. The total one goes on each
+/// request instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PhaseTimeouts {
+    pub connect_ms: Option<u64>,
+    pub read_ms: Option<u64>,
+}
+
+impl PhaseTimeouts {
+    pub fn apply(&self, mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        if let Some(ms) = self.connect_ms {
+            builder = builder.connect_timeout(Duration::from_millis(ms));
+        }
+        if let Some(ms) = self.read_ms {
+            builder = builder.read_timeout(Duration::from_millis(ms));
+        }
+        builder
+    }
+}
+
+/// `1500`, `1500ms`, `5s` or `2m` in milliseconds; `off`, `default` or nothing
+/// unsets it
+pub fn parse_ms(text: &str) -> Result<Option<u64>, String> {
+    let text = text.trim().to_lowercase();
+    if matches!(text.as_str(), "" | "off" | "none" | "default") {
+        return Ok(None);
+    }
+    let bad = || format!("Bad timeout '{}' (use 1500, 1500ms, 5s or 2m)", text);
+    let (number, unit) = if let Some(n) = text.strip_suffix("ms") {
+        (n, 1)
+    } else if let Some(n) = text.strip_suffix('s') {
+        (n, 1000)
+    } else if let Some(n) = text.strip_suffix('m') {
+        (n, 60_000)
+    } else {
+        (text.as_str(), 1)
+    };
+    match number.trim().parse::<u64>() {
+        Ok(0) => Err("A timeout of 0 would fail every request".to_string()),
+        Ok(n) => Ok(Some(n * unit)),
+        Err(_) => Err(bad()),
+    }
+}
+
+/// `30s`, `1500ms`
+pub fn format_ms(ms: u64) -> String {
+    if ms >= 1000 && ms.is_multiple_of(1000) {
+        format!("{}s", ms / 1000)
+    } else {
+        format!("{}ms", ms)
+    }
+}
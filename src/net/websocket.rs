@@ -9,12 +9,32 @@ pub struct WsMessage {
     pub content: String,
     pub is_sent: bool,
     pub timestamp: std::time::Instant,
+    /// Wall-clock time in milliseconds since the epoch, for session logs
+    pub unix_ms: u64,
+}
+
+impl WsMessage {
+    pub fn new(content: String, is_sent: bool) -> Self {
+        WsMessage {
+            content,
+            is_sent,
+            timestamp: std::time::Instant::now(),
+            unix_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+        }
+    }
 }
 
 /// Commands that can be sent to the WebSocket task
 pub enum WsCommand {
     Connect(String),
     Send(String),
+    /// Sends each message once its offset (ms from now) comes up, reporting each
+    /// as `Replayed`
+    Replay(Vec<(u64, String)>),
+    StopReplay,
     Disconnect,
 }
 
@@ -24,6 +44,12 @@ pub enum WsEvent {
     Connected,
     Disconnected,
     Message(String),
+    /// A replayed message went out
+    Replayed(String),
+    /// The replay sent its last message; how many it sent
+    ReplayFinished(usize),
+    /// The replay gave up, and why
+    ReplayStopped(String),
     Error(String),
 }
 
@@ -49,8 +75,16 @@ pub fn spawn_ws_handler(event_tx: mpsc::Sender<WsEvent>) -> WsHandle {
                 >,
             >,
         > = Arc::new(Mutex::new(None));
+        let mut replay: Option<tokio::task::JoinHandle<()>> = None;
 
         while let Some(cmd) = command_rx.recv().await {
+            if matches!(
+                cmd,
+                WsCommand::Replay(_) | WsCommand::StopReplay | WsCommand::Disconnect
+            ) && let Some(task) = replay.take()
+            {
+                task.abort();
+            }
             match cmd {
                 WsCommand::Connect(url) => {
                     let event_tx_clone = event_tx.clone();
@@ -128,6 +162,36 @@ pub fn spawn_ws_handler(event_tx: mpsc::Sender<WsEvent>) -> WsHandle {
                             .await;
                     }
                 }
+                WsCommand::Replay(schedule) => {
+                    let ws_stream_clone = ws_stream.clone();
+                    let event_tx_clone = event_tx.clone();
+                    replay = Some(tokio::spawn(async move {
+                        let start = tokio::time::Instant::now();
+                        let total = schedule.len();
+                        for (offset, msg) in schedule {
+                            tokio::time::sleep_until(
+                                start + std::time::Duration::from_millis(offset),
+                            )
+                            .await;
+                            let mut ws = ws_stream_clone.lock().await;
+                            let Some(ref mut writer) = *ws else {
+                                let _ = event_tx_clone
+                                    .send(WsEvent::ReplayStopped("not connected".to_string()))
+                                    .await;
+                                return;
+                            };
+                            if let Err(e) = writer.send(Message::Text(msg.clone().into())).await {
+                                let _ = event_tx_clone
+                                    .send(WsEvent::ReplayStopped(e.to_string()))
+                                    .await;
+                                return;
+                            }
+                            let _ = event_tx_clone.send(WsEvent::Replayed(msg)).await;
+                        }
+                        let _ = event_tx_clone.send(WsEvent::ReplayFinished(total)).await;
+                    }));
+                }
+                WsCommand::StopReplay => {}
                 WsCommand::Disconnect => {
                     let ws_stream_clone = ws_stream.clone();
                     let event_tx_clone = event_tx.clone();
@@ -0,0 +1,241 @@
+use super::common::temp_dir;
+use crate::domain::environment::Environment;
+use crate::domain::workspace::Workspace;
+use crate::features::import::{self, Format};
+use crate::features::{insomnia_import, thunder_import};
+use std::collections::HashMap;
+
+const INSOMNIA: &str = r#"{
+  "_type": "export",
+  "__export_format": 4,
+  "resources": [
+    {"_id": "wrk_1", "_type": "workspace", "name": "Shop API", "description": "The shop"},
+    {"_id": "env_base", "_type": "environment", "parentId": "wrk_1", "name": "Base Environment",
+     "data": {"base_url": "https://shop.test", "api": {"version": 2}}},
+    {"_id": "env_prod", "_type": "environment", "parentId": "env_base", "name": "Production",
+     "data": {"base_url": "https://shop.example.com", "token": "prod"}},
+    {"_id": "fld_1", "_type": "request_group", "parentId": "wrk_1", "name": "Users",
+     "authentication": {"type": "bearer", "token": "{{ _.token }}"},
+     "headers": [{"name": "X-Team", "value": "core"}]},
+    {"_id": "req_1", "_type": "request", "parentId": "fld_1", "name": "List",
+     "method": "GET", "url": "{{ _.base_url }}/users",
+     "parameters": [{"name": "page", "value": "1"}, {"name": "off", "value": "x", "disabled": true}],
+     "headers": [{"name": "Accept", "value": "application/json"}, {"name": "X-Debug", "value": "1", "disabled": true}],
+     "authentication": {}, "body": {}},
+    {"_id": "req_2", "_type": "request", "parentId": "wrk_1", "name": "Login",
+     "method": "post", "url": "{{base_url}}/login",
+     "authentication": {"type": "basic", "username": "ada", "password": "secret"},
+     "body": {"mimeType": "application/x-www-form-urlencoded",
+              "params": [{"name": "user", "value": "ada"}, {"name": "id", "value": "{% uuid 'v4' %}"}]}},
+    {"_id": "req_3", "_type": "request", "parentId": "wrk_1", "name": "Search",
+     "method": "POST", "url": "{{ _.base_url }}/graphql",
+     "authentication": {"type": "oauth2"},
+     "body": {"mimeType": "application/graphql", "text": "{\"query\":\"{ users { id } }\",\"variables\":{\"n\":1}}"}},
+    {"_id": "req_4", "_type": "request", "parentId": "wrk_1", "name": "Orders",
+     "method": "GET", "url": "{% response 'body', 'req_2', '$.url' %}/orders",
+     "authentication": {"type": "apikey", "key": "api_key", "value": "k", "addTo": "queryParams"},
+     "body": {}}
+  ]
+}"#;
+
+const THUNDER_COLLECTION: &str = r#"{
+  "client": "Thunder Client",
+  "collectionName": "Billing",
+  "version": "1.1",
+  "folders": [
+    {"_id": "f1", "name": "Invoices", "containerId": "", "sortNum": 10000,
+     "settings": {"auth": {"type": "basic", "basic": {"username": "{{user}}", "password": "{{pass}}"}}}}
+  ],
+  "requests": [
+    {"_id": "r1", "colId": "c1", "containerId": "f1", "name": "Get invoice", "url": "{{base}}/invoices/{id}",
+     "method": "GET", "sortNum": 10000,
+     "headers": [{"name": "Accept", "value": "*/*"}, {"name": "X-Old", "value": "1", "isDisabled": true}],
+     "params": [{"name": "id", "value": "42", "isPath": true}],
+     "auth": {"type": "inherit"},
+     "tests": [{"type": "res-code", "custom": "", "action": "equal", "value": "200"},
+               {"type": "json-query", "custom": "json.id", "action": "equal", "value": "42"}]},
+    {"_id": "r2", "colId": "c1", "containerId": "", "name": "Create invoice", "url": "{{base}}/invoices",
+     "method": "POST", "sortNum": 20000,
+     "body": {"type": "json", "raw": "{\"ref\": \"{{#guid}}\"}", "form": []},
+     "auth": {"type": "bearer", "bearer": "{{token}}"}},
+    {"_id": "r3", "colId": "c1", "containerId": "", "name": "Upload", "url": "{{base}}/files",
+     "method": "POST", "sortNum": 30000,
+     "body": {"type": "formdata", "form": [{"name": "note", "value": "hi"}],
+              "files": [{"name": "file", "value": "/tmp/a.pdf"}]}}
+  ],
+  "settings": {"headers": [{"name": "X-Client", "value": "thunder"}]}
+}"#;
+
+const THUNDER_ENV: &str = r#"{
+  "client": "Thunder Client",
+  "environmentName": "dev",
+  "data": [{"name": "base", "value": "http://localhost:8080"}, {"name": "retries", "value": 3}]
+}"#;
+
+fn header<'a>(config: &'a crate::domain::collection::RequestConfig, name: &str) -> Option<&'a str> {
+    config
+        .headers
+        .as_ref()
+        .and_then(|h| h.get(name))
+        .map(String::as_str)
+}
+
+#[test]
+fn test_detect_format() {
+    assert_eq!(Format::detect(INSOMNIA), Some(Format::Insomnia));
+    assert_eq!(Format::detect(THUNDER_COLLECTION), Some(Format::Thunder));
+    assert_eq!(Format::detect(THUNDER_ENV), Some(Format::Thunder));
+    assert_eq!(
+        Format::detect(r#"{"info": {"name": "x"}, "item": []}"#),
+        Some(Format::Postman)
+    );
+    assert_eq!(Format::detect("curl https://a.test"), Some(Format::Curl));
+    assert_eq!(Format::detect("{}"), None);
+    assert_eq!(Format::parse("Thunder-Client"), Some(Format::Thunder));
+    assert_eq!(Format::parse("har"), None);
+}
+
+#[test]
+fn test_insomnia_export() {
+    let imported = insomnia_import::parse(INSOMNIA).unwrap();
+    let shop = &imported.collections[0];
+    assert_eq!(shop.name, "Shop API");
+    assert_eq!(shop.description.as_deref(), Some("The shop"));
+
+    let list = &shop.requests["Users/List"];
+    assert_eq!(list.url, "{{base_url}}/users?page=1");
+    assert_eq!(header(list, "Authorization"), Some("Bearer {{token}}"));
+    assert_eq!(header(list, "X-Team"), Some("core"));
+    assert_eq!(header(list, "Accept"), Some("application/json"));
+    assert_eq!(
+        list.disabled_headers.get("X-Debug").map(String::as_str),
+        Some("1")
+    );
+
+    let login = &shop.requests["Login"];
+    assert_eq!(login.method, "POST");
+    assert_eq!(
+        header(login, "Authorization"),
+        Some("Basic YWRhOnNlY3JldA==")
+    );
+    assert_eq!(login.body_type.as_deref(), Some("UrlEncoded"));
+    assert_eq!(
+        login.body.as_deref(),
+        Some("user=ada&id=%7B%7B%24uuid%7D%7D")
+    );
+
+    let search = &shop.requests["Search"];
+    assert_eq!(search.graphql_query.as_deref(), Some("{ users { id } }"));
+    assert_eq!(
+        search.graphql_variables.as_deref(),
+        Some("{\n  \"n\": 1\n}")
+    );
+
+    let orders = &shop.requests["Orders"];
+    assert!(orders.url.ends_with("/orders?api_key=k"), "{}", orders.url);
+
+    assert!(
+        imported
+            .warnings
+            .iter()
+            .any(|w| w.contains("Search: oauth2 auth"))
+    );
+    assert!(
+        imported
+            .warnings
+            .iter()
+            .any(|w| w.contains("Orders: Insomnia's {% response %}"))
+    );
+
+    let envs: HashMap<&str, &Environment> = imported
+        .environments
+        .iter()
+        .map(|e| (e.name.as_str(), e))
+        .collect();
+    let prod = envs["Production"];
+    assert_eq!(prod.variables["base_url"], "https://shop.example.com");
+    assert_eq!(prod.variables["api.version"], "2");
+    assert_eq!(prod.variables["token"], "prod");
+    assert_eq!(envs.len(), 1);
+}
+
+#[test]
+fn test_thunder_exports() {
+    let imported = thunder_import::parse(THUNDER_COLLECTION).unwrap();
+    let billing = &imported.collections[0];
+    assert_eq!(billing.name, "Billing");
+
+    let get = &billing.requests["Invoices/Get invoice"];
+    assert_eq!(get.url, "{{base}}/invoices/{id}");
+    assert_eq!(get.path_params["id"], "42");
+    assert_eq!(get.expected_status, Some(200));
+    assert_eq!(header(get, "X-Client"), Some("thunder"));
+    assert_eq!(header(get, "Authorization"), None);
+    assert!(get.disabled_headers.contains_key("X-Old"));
+    assert!(
+        imported
+            .warnings
+            .iter()
+            .any(|w| w.contains("basic auth uses variables"))
+    );
+    assert!(imported.warnings.iter().any(|w| w.contains("1 test(s)")));
+
+    let create = &billing.requests["Create invoice"];
+    assert_eq!(create.body.as_deref(), Some("{\"ref\": \"{{$uuid}}\"}"));
+    assert_eq!(header(create, "Authorization"), Some("Bearer {{token}}"));
+
+    let upload = &billing.requests["Upload"];
+    assert_eq!(
+        upload.form_data.as_deref(),
+        Some(
+            &[
+                ("note".to_string(), "hi".to_string(), false),
+                ("file".to_string(), "/tmp/a.pdf".to_string(), true),
+            ][..]
+        )
+    );
+
+    let env = thunder_import::parse(THUNDER_ENV).unwrap();
+    assert!(env.collections.is_empty());
+    assert_eq!(env.environments[0].name, "dev");
+    assert_eq!(env.environments[0].variables["retries"], "3");
+
+    assert!(thunder_import::parse("{}").is_err());
+}
+
+#[test]
+fn test_import_into_workspace() {
+    let dir = temp_dir("app_import");
+    std::fs::write(
+        dir.join("environments.hcl"),
+        "env \"dev\" {\n  base = \"http://keep.me\"\n}\n",
+    )
+    .unwrap();
+    let collection = dir.join("thunder-collection_billing.json");
+    let environment = dir.join("thunder-environment_dev.json");
+    std::fs::write(&collection, THUNDER_COLLECTION).unwrap();
+    std::fs::write(&environment, THUNDER_ENV).unwrap();
+
+    let workspace = Workspace::at(&dir);
+    import::import_file(collection.to_str().unwrap(), None, &workspace).unwrap();
+    import::import_file(
+        environment.to_str().unwrap(),
+        Some(Format::Thunder),
+        &workspace,
+    )
+    .unwrap();
+
+    let collections =
+        crate::domain::collection::Collection::load_from_dir(&workspace.path("collections"))
+            .unwrap();
+    let billing = collections.iter().find(|c| c.name == "billing").unwrap();
+    assert_eq!(billing.requests.len(), 3);
+
+    // Existing values win, new variables are added
+    let envs = Environment::load_from_file(&workspace.path("environments.hcl")).unwrap();
+    assert_eq!(envs.len(), 2);
+    assert_eq!(envs[1].variables["base"], "http://keep.me");
+    assert_eq!(envs[1].variables["retries"], "3");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
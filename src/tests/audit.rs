@@ -0,0 +1,155 @@
+use crate::features::audit;
+use crate::features::doctor::{Check, Level};
+use std::collections::HashMap;
+
+fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn level(checks: &[Check], name: &str) -> Level {
+    checks
+        .iter()
+        .find(|c| c.name == name)
+        .unwrap_or_else(|| panic!("no {} check", name))
+        .level
+}
+
+#[test]
+fn test_headers_cookies_and_cors() {
+    let strict = headers(&[
+        (
+            "Strict-Transport-Security",
+            "max-age=31536000; includeSubDomains",
+        ),
+        ("Content-Security-Policy", "default-src 'none'"),
+        ("X-Content-Type-Options", "nosniff"),
+    ]);
+    let checks = audit::header_checks(&strict, true);
+    assert!(checks.iter().all(|c| c.level == Level::Ok));
+
+    let loose = headers(&[
+        ("content-type", "text/html"),
+        ("strict-transport-security", "max-age=300"),
+        (
+            "content-security-policy",
+            "script-src 'self' 'unsafe-inline'",
+        ),
+        ("server", "nginx/1.18.0"),
+    ]);
+    let checks = audit::header_checks(&loose, true);
+    assert_eq!(level(&checks, "Strict-Transport-Security"), Level::Warn);
+    assert_eq!(level(&checks, "Content-Security-Policy"), Level::Warn);
+    assert_eq!(level(&checks, "X-Content-Type-Options"), Level::Warn);
+    assert_eq!(level(&checks, "Clickjacking"), Level::Warn);
+    assert_eq!(level(&checks, "Server"), Level::Warn);
+    // HSTS means nothing over plain HTTP
+    assert!(
+        !audit::header_checks(&loose, false)
+            .iter()
+            .any(|c| c.name == "Strict-Transport-Security")
+    );
+
+    let cookies = vec![
+        "session=abc; Path=/; Secure; HttpOnly; SameSite=Lax".to_string(),
+        "tracking=1; SameSite=None".to_string(),
+        "theme=dark; Secure".to_string(),
+    ];
+    let checks = audit::cookie_checks(&cookies, true);
+    assert_eq!(level(&checks, "session"), Level::Ok);
+    assert_eq!(level(&checks, "tracking"), Level::Fail);
+    assert!(checks[1].detail.contains("SameSite=None without Secure"));
+    assert_eq!(level(&checks, "theme"), Level::Warn);
+
+    let cors =
+        |pairs: &[(&str, &str)], origin| audit::cors_checks(&headers(pairs), origin)[0].level;
+    assert_eq!(cors(&[], None), Level::Ok);
+    assert_eq!(
+        cors(&[("Access-Control-Allow-Origin", "*")], None),
+        Level::Warn
+    );
+    assert_eq!(
+        cors(
+            &[
+                ("Access-Control-Allow-Origin", "*"),
+                ("Access-Control-Allow-Credentials", "true")
+            ],
+            None
+        ),
+        Level::Fail
+    );
+    assert_eq!(
+        cors(&[("Access-Control-Allow-Origin", "null")], None),
+        Level::Fail
+    );
+    let echoed = [
+        ("Access-Control-Allow-Origin", "https://evil.test"),
+        ("Access-Control-Allow-Credentials", "true"),
+    ];
+    assert_eq!(cors(&echoed, Some("https://evil.test")), Level::Warn);
+    assert_eq!(cors(&echoed, Some("https://app.test")), Level::Ok);
+
+    // Plain HTTP fails the transport check, which caps the grade
+    let report = audit::audit("http://api.test", &strict, &[], None, None);
+    assert_eq!(report.checks[0].level, Level::Fail);
+    assert_eq!(report.grade, 'C');
+    let ok = Check::ok("tls", "Version", "TLSv1.3");
+    assert_eq!(audit::grade(&[ok.clone(), ok]), 'A');
+}
+
+#[test]
+fn test_tls_probe_reports_the_handshake() {
+    use openssl::asn1::Asn1Time;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::hash::MessageDigest;
+    use openssl::nid::Nid;
+    use openssl::pkey::PKey;
+    use openssl::ssl::{SslAcceptor, SslMethod};
+    use openssl::x509::{X509, X509NameBuilder};
+
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, "localhost")
+        .unwrap();
+    let name = name.build();
+    let mut cert = X509::builder().unwrap();
+    cert.set_version(2).unwrap();
+    cert.set_subject_name(&name).unwrap();
+    cert.set_issuer_name(&name).unwrap();
+    cert.set_pubkey(&key).unwrap();
+    cert.set_not_before(&Asn1Time::days_from_now(0).unwrap())
+        .unwrap();
+    // Soon to expire, to be flagged
+    cert.set_not_after(&Asn1Time::days_from_now(10).unwrap())
+        .unwrap();
+    cert.sign(&key, MessageDigest::sha256()).unwrap();
+    let cert = cert.build();
+
+    let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+    acceptor.set_private_key(&key).unwrap();
+    acceptor.set_certificate(&cert).unwrap();
+    let acceptor = acceptor.build();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let _ = acceptor.accept(stream);
+    });
+
+    let tls = audit::probe_tls("localhost", port, audit::TLS_TIMEOUT).unwrap();
+    server.join().unwrap();
+    assert_eq!(tls.version, "TLSv1.3");
+    assert!(tls.verify_error.is_some());
+    assert_eq!(tls.chain.len(), 1);
+    assert_eq!(tls.chain[0].subject, "localhost");
+    assert_eq!(tls.chain[0].key, "EC 256");
+    assert!((9..=10).contains(&tls.chain[0].days_left));
+
+    let checks = audit::tls_checks(&Ok(tls));
+    assert_eq!(level(&checks, "Version"), Level::Ok);
+    assert_eq!(level(&checks, "Chain"), Level::Fail);
+    assert_eq!(level(&checks, "#0 localhost"), Level::Warn);
+}
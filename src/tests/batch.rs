@@ -0,0 +1,191 @@
+use crate::app::{App, AppMode, BodyType, RequestTab};
+use crate::features::batch::{self, BatchEvent, BatchResult, BatchRow};
+use crate::net::mock_server::MockRoute;
+
+fn row(name: &str, result: Option<BatchResult>) -> BatchRow {
+    BatchRow {
+        tab: 0,
+        name: name.to_string(),
+        method: "GET".to_string(),
+        url: "http://api.test/".to_string(),
+        result,
+    }
+}
+
+fn answered(status: u16, latency_ms: u64) -> Option<BatchResult> {
+    Some(BatchResult {
+        status: Some(status),
+        latency_ms,
+        size: 2,
+        error: None,
+    })
+}
+
+#[test]
+fn test_parse_tabs() {
+    assert_eq!(batch::parse_tabs("all", 3), Ok(vec![0, 1, 2]));
+    assert_eq!(batch::parse_tabs("", 2), Ok(vec![0, 1]));
+    assert_eq!(batch::parse_tabs("3, 1-2, 2", 4), Ok(vec![2, 0, 1]));
+    assert_eq!(batch::parse_tabs("2-4", 5), Ok(vec![1, 2, 3]));
+    assert!(batch::parse_tabs("5", 4).unwrap_err().contains("no tab 5"));
+    assert!(batch::parse_tabs("0", 4).is_err());
+    assert!(batch::parse_tabs("x", 4).is_err());
+    assert!(
+        batch::parse_tabs("3-1", 4)
+            .unwrap_err()
+            .contains("backwards")
+    );
+}
+
+#[test]
+fn test_summary_counts_and_slowest() {
+    let rows = vec![
+        row("Users", answered(200, 120)),
+        row("Orders", answered(500, 430)),
+        row(
+            "Socket",
+            Some(BatchResult::failed("WebSocket tabs aren't sent")),
+        ),
+        row("Pending", None),
+    ];
+    assert_eq!(
+        batch::summary(&rows),
+        "3 sent: 1 ok, 2 failed, slowest 430ms (Orders)"
+    );
+}
+
+#[test]
+fn test_batch_requests_builds_each_tab() {
+    let mut app = App::new();
+    app.active_tab_mut().url = "http://api.test/users".to_string();
+    let mut orders = RequestTab::new();
+    orders.name = "Orders".to_string();
+    orders.method = "POST".to_string();
+    orders.url = "http://api.test/orders".to_string();
+    orders.request_body = r#"{"qty": 1}"#.to_string();
+    app.tabs.push(orders);
+    let mut socket = RequestTab::new();
+    socket.app_mode = AppMode::WebSocket;
+    app.tabs.push(socket);
+    let mut grpc = RequestTab::new();
+    grpc.body_type = BodyType::Grpc;
+    app.tabs.push(grpc);
+    app.active_tab = 0;
+
+    app.send_tabs_command("2-4, 1");
+    assert!(app.should_run_batch);
+    let requests = app.batch_requests();
+
+    assert_eq!(app.active_tab, 0);
+    assert!(app.show_batch);
+    let tabs: Vec<usize> = app.batch_rows.iter().map(|r| r.tab).collect();
+    assert_eq!(tabs, vec![1, 2, 3, 0]);
+    assert!(app.batch_rows[1].result.is_some());
+    assert!(app.batch_rows[2].result.is_some());
+
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].row, 0);
+    assert_eq!(requests[0].request.method, "POST");
+    assert_eq!(requests[0].request.url, "http://api.test/orders");
+    assert_eq!(requests[0].request.body.as_deref(), Some(r#"{"qty": 1}"#));
+    assert_eq!(requests[1].row, 3);
+    assert_eq!(requests[1].request.url, "http://api.test/users");
+
+    // Enter on a row goes to its tab
+    app.batch_state.select(Some(0));
+    app.open_batch_tab();
+    assert_eq!(app.active_tab, 1);
+    assert!(!app.show_batch);
+}
+
+#[tokio::test]
+async fn test_run_batch_reports_every_tab() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let routes = vec![
+        MockRoute {
+            path: "/ok".to_string(),
+            method: "GET".to_string(),
+            status: 200,
+            body: r#"{"ok": true}"#.to_string(),
+            ..Default::default()
+        },
+        MockRoute {
+            path: "/broken".to_string(),
+            method: "GET".to_string(),
+            status: 503,
+            body: "down".to_string(),
+            ..Default::default()
+        },
+    ];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let mut app = App::new();
+    app.active_tab_mut().url = format!("http://127.0.0.1:{}/ok", port);
+    let mut broken = RequestTab::new();
+    broken.url = format!("http://127.0.0.1:{}/broken", port);
+    app.tabs.push(broken);
+    let mut unreachable = RequestTab::new();
+    unreachable.url = "http://127.0.0.1:1/".to_string();
+    app.tabs.push(unreachable);
+
+    app.send_tabs_command("all");
+    let requests = app.batch_requests();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    batch::run_batch(requests, tx).await;
+    while let Ok(BatchEvent::Done(row, result)) = rx.try_recv() {
+        app.batch_done(row, result);
+    }
+    server.handle.abort();
+
+    let results: Vec<&BatchResult> = app
+        .batch_rows
+        .iter()
+        .map(|r| r.result.as_ref().unwrap())
+        .collect();
+    assert_eq!(results[0].status, Some(200));
+    assert_eq!(results[0].size, r#"{"ok": true}"#.len());
+    assert_eq!(results[1].status, Some(503));
+    assert_eq!(results[2].status, None);
+    assert!(results[2].error.is_some());
+    assert!(batch::summary(&app.batch_rows).starts_with("3 sent: 1 ok, 2 failed"));
+}
+
+#[tokio::test]
+async fn test_run_batch_goes_through_the_proxy() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    // The mock server stands in for the proxy: a host that doesn't resolve only
+    // answers when the request is sent to it
+    let routes = vec![MockRoute {
+        path: "/ok".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: "proxied".to_string(),
+        ..Default::default()
+    }];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let mut app = App::new();
+    app.proxy_url = Some(format!("http://127.0.0.1:{}", port));
+    app.active_tab_mut().url = "http://batch-proxy.invalid/ok".to_string();
+
+    app.send_tabs_command("all");
+    let requests = app.batch_requests();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    batch::run_batch(requests, tx).await;
+    server.handle.abort();
+
+    let Ok(BatchEvent::Done(_, result)) = rx.try_recv() else {
+        panic!("no result");
+    };
+    assert_eq!(result.status, Some(200));
+}
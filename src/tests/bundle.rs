@@ -0,0 +1,247 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::environment::Environment;
+use crate::domain::workspace::Workspace;
+use crate::features::bundle::{self, Existing, Kind, Resolution};
+use crate::features::vault;
+use crate::net::mock_server::MockRoute;
+use std::collections::{BTreeMap, HashMap};
+
+// Far below DEFAULT_ITERATIONS, to keep the tests quick
+const ITERATIONS: u32 = 1_000;
+
+fn env(name: &str, vars: &[(&str, &str)]) -> Environment {
+    Environment {
+        name: name.to_string(),
+        variables: vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn route(path: &str, body: &str) -> MockRoute {
+    MockRoute {
+        path: path.to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: body.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_secrets_left_out_or_sealed() {
+    let envs = vec![
+        env("None", &[]),
+        env(
+            "prod",
+            &[
+                ("base_url", "https://api"),
+                ("api_token", "t0ken"),
+                ("db_password", "hunter2"),
+                ("old", "enc:v1:AAAA"),
+            ],
+        ),
+    ];
+
+    let plain = bundle::build(BTreeMap::new(), &envs, &[], None).unwrap();
+    assert_eq!(plain.environments.len(), 1);
+    let prod = &plain.environments[0];
+    assert_eq!(prod.variables.len(), 1);
+    assert_eq!(prod.variables["base_url"], "https://api");
+    assert_eq!(prod.excluded, vec!["api_token", "db_password", "old"]);
+    assert!(plain.encryption.is_none());
+
+    let seal = vault::setup_passphrase("correct horse", ITERATIONS).unwrap();
+    let sealed = bundle::build(BTreeMap::new(), &envs, &[], Some(seal)).unwrap();
+    let json = serde_json::to_string(&sealed).unwrap();
+    assert!(!json.contains("t0ken") && !json.contains("hunter2"));
+    // Still sealed with a workspace key nobody else has
+    assert_eq!(sealed.environments[0].excluded, vec!["old"]);
+
+    let mut opened = bundle::parse(&json).unwrap();
+    assert!(bundle::unseal(&mut opened, "wrong horse").is_err());
+    bundle::unseal(&mut opened, "correct horse").unwrap();
+    assert!(opened.encryption.is_none());
+    assert_eq!(opened.environments[0].variables["api_token"], "t0ken");
+    assert_eq!(opened.environments[0].variables["db_password"], "hunter2");
+
+    assert!(bundle::parse("{\"postdad_bundle\": 9}").is_err());
+    assert!(bundle::parse("[]").is_err());
+}
+
+#[test]
+fn test_secret_default_headers_left_out_or_sealed() {
+    let mut prod = env("prod", &[("base_url", "https://api")]);
+    prod.headers = BTreeMap::from([
+        ("Accept".to_string(), "application/json".to_string()),
+        ("Authorization".to_string(), "Bearer t0ken".to_string()),
+    ]);
+    let envs = vec![prod.clone()];
+
+    let plain = bundle::build(BTreeMap::new(), &envs, &[], None).unwrap();
+    let json = serde_json::to_string(&plain).unwrap();
+    assert!(!json.contains("t0ken"));
+    let packed = &plain.environments[0];
+    assert_eq!(packed.headers.len(), 1);
+    assert_eq!(packed.headers["Accept"], "application/json");
+    assert_eq!(packed.excluded_headers, vec!["Authorization"]);
+
+    // Left out: the workspace's own value stays, a new environment is told to fill it in
+    let mut existing = Existing {
+        environments: vec![prod.clone()],
+        ..Default::default()
+    };
+    assert!(bundle::collisions(&plain, &existing).is_empty());
+    let mut renamed = plain.clone();
+    renamed.environments[0].name = "staging".to_string();
+    let summary = bundle::merge(&renamed, &[], &mut existing, None).unwrap();
+    assert_eq!(
+        summary.missing_secrets,
+        vec!["staging.headers.Authorization"]
+    );
+    assert!(
+        !existing.environments[1]
+            .headers
+            .contains_key("Authorization")
+    );
+
+    let seal = vault::setup_passphrase("correct horse", ITERATIONS).unwrap();
+    let sealed = bundle::build(BTreeMap::new(), &envs, &[], Some(seal)).unwrap();
+    let json = serde_json::to_string(&sealed).unwrap();
+    assert!(!json.contains("t0ken"));
+    assert!(sealed.environments[0].excluded_headers.is_empty());
+
+    let mut opened = bundle::parse(&json).unwrap();
+    bundle::unseal(&mut opened, "correct horse").unwrap();
+    assert_eq!(opened.environments[0].headers, prod.headers);
+}
+
+#[test]
+fn test_merge_follows_resolutions() {
+    let theirs = bundle::Bundle {
+        postdad_bundle: bundle::FORMAT_VERSION,
+        collections: [
+            ("shop.hcl".to_string(), "theirs".to_string()),
+            ("blog.hcl".to_string(), "same".to_string()),
+            ("new.hcl".to_string(), "new".to_string()),
+        ]
+        .into(),
+        environments: vec![bundle::BundleEnv {
+            name: "dev".to_string(),
+            variables: [("base".to_string(), "http://theirs".to_string())].into(),
+            excluded: vec!["token".to_string()],
+            ..Default::default()
+        }],
+        mocks: vec![route("/a", "theirs"), route("/b", "new")],
+        ..Default::default()
+    };
+    let mut mine = Existing {
+        collections: [
+            ("shop.hcl".to_string(), "mine".to_string()),
+            ("blog.hcl".to_string(), "same".to_string()),
+        ]
+        .into(),
+        environments: vec![env("dev", &[("base", "http://mine"), ("token", "abc")])],
+        mocks: vec![route("/a", "mine")],
+    };
+
+    let mut collisions = bundle::collisions(&theirs, &mine);
+    let found: Vec<(Kind, &str)> = collisions
+        .iter()
+        .map(|c| (c.kind, c.name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (Kind::Collection, "shop.hcl"),
+            (Kind::Environment, "dev"),
+            (Kind::Mock, "GET /a"),
+        ]
+    );
+    collisions[0].resolution = Some(Resolution::KeepBoth);
+    collisions[1].resolution = Some(Resolution::Overwrite);
+    collisions[2].resolution = Some(Resolution::Skip);
+
+    let summary = bundle::merge(&theirs, &collisions, &mut mine, None).unwrap();
+    assert_eq!(
+        (summary.added, summary.replaced, summary.skipped),
+        (3, 1, 1)
+    );
+    assert_eq!(mine.collections["shop.hcl"], "mine");
+    assert_eq!(mine.collections["shop_2.hcl"], "theirs");
+    assert_eq!(mine.collections["new.hcl"], "new");
+    // The left-out secret keeps the value that was there
+    assert_eq!(mine.environments.len(), 1);
+    assert_eq!(mine.environments[0].variables["base"], "http://theirs");
+    assert_eq!(mine.environments[0].variables["token"], "abc");
+    assert!(summary.missing_secrets.is_empty());
+    assert_eq!(mine.mocks, vec![route("/a", "mine"), route("/b", "new")]);
+}
+
+#[test]
+fn test_export_then_import_into_another_workspace() {
+    let base = temp_dir("bundle");
+    let (from, to) = (base.join("from"), base.join("to"));
+    for dir in [&from, &to] {
+        std::fs::create_dir_all(dir.join("collections")).unwrap();
+    }
+    std::fs::write(
+        from.join("collections/orders.hcl"),
+        "request \"List\" {\n  method = \"GET\"\n  url = \"{{base}}/orders\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        from.join("environments.hcl"),
+        "env \"dev\" {\n  base = \"http://localhost:8080\"\n  token = \"s3cret\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        to.join("environments.hcl"),
+        "env \"dev\" {\n  base = \"http://mine\"\n}\n",
+    )
+    .unwrap();
+    let file = base.join("team.json").to_string_lossy().into_owned();
+
+    let mut app = App::with_workspace(Workspace::at(&from));
+    app.show_splash = false;
+    app.mock_routes = vec![route("/health", "ok")];
+    app.bundle_command(&format!("export {}", file));
+    let content = std::fs::read_to_string(&file).unwrap();
+    assert!(content.contains("orders.hcl"));
+    assert!(!content.contains("s3cret"));
+
+    let mut app = App::with_workspace(Workspace::at(&to));
+    app.show_splash = false;
+    app.bundle_command(&format!("import {}", file));
+    let prompt = app.bundle_prompt.as_ref().unwrap();
+    assert_eq!(prompt.collisions[prompt.current().unwrap()].name, "dev");
+
+    // Keep both: the imported one comes in under a new name
+    app.resolve_bundle_collision(Resolution::KeepBoth, true);
+    assert!(app.bundle_prompt.is_none());
+    assert!(app.collections.iter().any(|c| c.name == "orders"));
+    assert_eq!(app.mock_routes, vec![route("/health", "ok")]);
+    assert!(to.join("mocks.hcl").exists());
+    let envs: HashMap<String, Environment> =
+        Environment::load_from_file(&to.join("environments.hcl").to_string_lossy())
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.name.clone(), e))
+            .collect();
+    assert_eq!(envs["dev"].variables["base"], "http://mine");
+    assert_eq!(envs["dev_2"].variables["base"], "http://localhost:8080");
+    // Left out of the bundle, to be filled in
+    assert_eq!(envs["dev_2"].variables["token"], "");
+
+    // Again: dev still differs, and Esc leaves everything alone
+    app.bundle_command(&format!("import {}", file));
+    assert!(app.bundle_prompt.is_some());
+    app.cancel_bundle_import();
+    assert!(app.bundle_prompt.is_none());
+    assert!(!to.join("collections/orders_2.hcl").exists());
+
+    let _ = std::fs::remove_dir_all(&base);
+}
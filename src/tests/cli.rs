@@ -0,0 +1,239 @@
+use super::common::temp_dir;
+use crate::app::{AuthType, BodyType};
+use crate::domain::workspace::Workspace;
+use crate::features::cli::{self, EnvArgs, ReqArgs};
+
+fn req(target: &str) -> ReqArgs {
+    ReqArgs {
+        method: None,
+        target: target.to_string(),
+        headers: Vec::new(),
+        data: None,
+        env: None,
+        user: None,
+        bearer: None,
+        include: false,
+        json_output: false,
+        fail: false,
+        yes: false,
+    }
+}
+
+#[test]
+fn test_req_fills_the_tab_from_arguments() {
+    let dir = temp_dir("cli_req");
+    std::fs::create_dir_all(dir.join("collections")).unwrap();
+    std::fs::write(
+        dir.join("collections/shop.hcl"),
+        "request \"List orders\" {\n  method = \"GET\"\n  url = \"{{base}}/orders?page=2\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("environments.hcl"),
+        "env \"local\" {\n  base = \"http://localhost:9\"\n}\nenv \"staging\" {\n  base = \"https://staging\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("body.json"), "{\"name\": \"dad\"}").unwrap();
+    let workspace = Workspace::at(&dir);
+
+    let mut args = req("{{base}}/users");
+    args.env = Some("STAGING".to_string());
+    args.headers = vec![("X-Trace".to_string(), "1".to_string())];
+    args.data = Some(format!("@{}", dir.join("body.json").display()));
+    args.user = Some("me:secret".to_string());
+    let app = cli::request_app(&args, workspace.clone()).unwrap();
+    let tab = app.active_tab();
+    assert_eq!(app.process_url(), "https://staging/users");
+    assert_eq!(tab.method, "POST");
+    assert_eq!(tab.body_type, BodyType::Raw);
+    assert_eq!(tab.request_body, "{\"name\": \"dad\"}");
+    assert_eq!(
+        tab.request_headers.get("X-Trace").map(String::as_str),
+        Some("1")
+    );
+    assert_eq!(tab.auth_type, AuthType::Basic);
+    assert_eq!(tab.basic_auth_pass, "secret");
+
+    // A saved request keeps its own method unless one is given
+    let mut args = req("shop/list orders");
+    args.data = Some("{}".to_string());
+    args.env = Some("local".to_string());
+    let app = cli::request_app(&args, workspace.clone()).unwrap();
+    assert_eq!(app.active_tab().method, "GET");
+    assert_eq!(app.process_url(), "http://localhost:9/orders?page=2");
+
+    let mut args = req("http://localhost/");
+    args.env = Some("prod".to_string());
+    assert!(cli::request_app(&args, workspace.clone()).is_err());
+    args.env = None;
+    args.data = Some("@/no/such/file".to_string());
+    assert!(cli::request_app(&args, workspace).is_err());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_req_stops_on_send_warnings() {
+    let dir = temp_dir("cli_warn");
+    let workspace = Workspace::at(&dir);
+
+    // A body without a Content-Type
+    let mut args = req("http://127.0.0.1:9/users/9");
+    args.data = Some("{\"a\":1}".to_string());
+    let mut app = cli::request_app(&args, workspace.clone()).unwrap();
+    assert!(!app.check_send_warnings());
+    assert_eq!(cli::finish_request_cli(&mut app, None, &args), 1);
+    assert_eq!(app.send_warnings.as_ref().map(Vec::len), Some(1));
+
+    // A variable nothing defines
+    let args = req("http://127.0.0.1:9/{{missing}}");
+    let mut app = cli::request_app(&args, workspace).unwrap();
+    assert!(!app.check_send_warnings());
+    assert_eq!(cli::finish_request_cli(&mut app, None, &args), 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_req_yes_sends_past_warnings() {
+    let dir = temp_dir("cli_yes");
+    let workspace = Workspace::at(&dir);
+
+    let mut args = req("http://127.0.0.1:9/{{missing}}");
+    args.data = Some("{\"a\":1}".to_string());
+    args.yes = true;
+    let mut app = cli::request_app(&args, workspace).unwrap();
+    assert!(app.check_send_warnings());
+    assert!(app.send_warnings.is_none());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_run_env_by_name_file_and_overrides() {
+    let dir = temp_dir("cli_env");
+    std::fs::write(
+        dir.join("environments.hcl"),
+        "env \"dev\" {\n  base = \"http://dev\"\n  token = \"a\"\n}\nenv \"staging\" {\n  base = \"https://staging\"\n  token = \"b\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("ci.hcl"),
+        "env \"ci\" {\n  base = \"http://ci\"\n}\nenv \"nightly\" {\n  base = \"http://nightly\"\n}\n",
+    )
+    .unwrap();
+    let workspace = Workspace::at(&dir);
+    let file = dir.join("ci.hcl").to_string_lossy().into_owned();
+
+    assert!(EnvArgs::default().resolve(&workspace).unwrap().is_empty());
+
+    let args = EnvArgs {
+        env: Some("Staging".to_string()),
+        vars: vec![("token".to_string(), "from-ci".to_string())],
+        ..Default::default()
+    };
+    let vars = args.resolve(&workspace).unwrap();
+    assert_eq!(vars["base"], "https://staging");
+    assert_eq!(vars["token"], "from-ci");
+
+    // A path still works the old way: the file's first environment
+    let args = EnvArgs {
+        env: Some(file.clone()),
+        ..Default::default()
+    };
+    assert_eq!(args.resolve(&workspace).unwrap()["base"], "http://ci");
+
+    let args = EnvArgs {
+        env: Some("nightly".to_string()),
+        env_file: Some(file.clone()),
+        vars: vec![("extra".to_string(), "1=2".to_string())],
+    };
+    let vars = args.resolve(&workspace).unwrap();
+    assert_eq!(vars["base"], "http://nightly");
+    assert_eq!(vars["extra"], "1=2");
+
+    let args = EnvArgs {
+        env: Some("prod".to_string()),
+        ..Default::default()
+    };
+    let err = args.resolve(&workspace).unwrap_err();
+    assert!(err.contains("dev, staging"), "{}", err);
+    let args = EnvArgs {
+        env_file: Some(dir.join("missing.hcl").to_string_lossy().into_owned()),
+        ..Default::default()
+    };
+    assert!(args.resolve(&workspace).is_err());
+    assert!(!dir.join("missing.hcl").exists());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_runs_send_default_and_environment_headers() {
+    use crate::features::runner::{self, RunnerEvent};
+    use crate::net::mock_server::MockRoute;
+
+    let dir = temp_dir("cli_defaults");
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{"default_headers": {"X-Trace": "{{token}}", "Accept": "*/*", "X-Off": "1"}}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("environments.hcl"),
+        "env \"staging\" {\n  token = \"abc\"\n  headers = {\n    \"X-Env\" = \"staging\"\n  }\n}\n",
+    )
+    .unwrap();
+    let workspace = Workspace::at(&dir);
+    let env = EnvArgs {
+        env: Some("staging".to_string()),
+        ..Default::default()
+    };
+    let defaults = env.default_headers(&workspace).unwrap();
+    assert_eq!(
+        defaults.keys().collect::<Vec<_>>(),
+        vec!["Accept", "X-Env", "X-Off", "X-Trace"]
+    );
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let echo = MockRoute {
+        path: "/echo".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: "{{headers.x-trace}} {{headers.x-env}} {{headers.accept}} {{headers.x-off}}"
+            .to_string(),
+        ..Default::default()
+    };
+    let server = crate::net::mock_server::start_mock_server(port, vec![echo]);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let collection = crate::domain::collection::Collection::from_hcl(
+        "defaults",
+        &format!(
+            "request \"Echo\" {{\n  method = \"GET\"\n  url = \"http://127.0.0.1:{}/echo\"\n  headers = {{\n    accept = \"text/plain\"\n  }}\n  disabled_default_headers = [\"x-off\"]\n}}\n",
+            port
+        ),
+    )
+    .unwrap();
+    let collection = collection.with_default_headers(&defaults);
+    let vars = env.resolve(&workspace).unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+    runner::run_collection(&collection, &vars, &Default::default(), &Vec::new(), tx).await;
+    server.handle.abort();
+    let mut body = None;
+    while let Ok(event) = rx.try_recv() {
+        if let RunnerEvent::RequestCompleted(result) = event {
+            body = result.response_body;
+        }
+    }
+    // The request's own Accept wins, X-Off is turned off for it, {{token}} is filled
+    assert_eq!(
+        body.as_deref(),
+        Some("abc staging text/plain {{headers.x-off}}")
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -1,3 +1,4 @@
+use super::common::temp_dir;
 use crate::app::{App, AuthType, BodyType};
 
 /// Helper to create a fully populated App with a specific active tab configuration
@@ -40,7 +41,7 @@ fn test_generate_curl() {
 #[test]
 fn test_generate_python() {
     let app = create_test_app();
-    let code = app.generate_python_code();
+    let code = app.generate_code("python").unwrap();
 
     assert!(code.contains("import requests"));
     assert!(code.contains("url = \"https://api.example.com/v1/resource\""));
@@ -52,7 +53,7 @@ fn test_generate_python() {
 #[test]
 fn test_generate_javascript() {
     let app = create_test_app();
-    let code = app.generate_javascript_code();
+    let code = app.generate_code("javascript").unwrap();
 
     assert!(code.contains("const url = \"https://api.example.com/v1/resource\";"));
     assert!(code.contains("method: 'POST'"));
@@ -63,7 +64,7 @@ fn test_generate_javascript() {
 #[test]
 fn test_generate_go() {
     let app = create_test_app();
-    let code = app.generate_go_code();
+    let code = app.generate_code("go").unwrap();
 
     assert!(code.contains("package main"));
     assert!(code.contains("net/http"));
@@ -75,7 +76,7 @@ fn test_generate_go() {
 #[test]
 fn test_generate_rust() {
     let app = create_test_app();
-    let code = app.generate_rust_code();
+    let code = app.generate_code("rust").unwrap();
 
     assert!(code.contains("reqwest::Client::new()"));
     assert!(code.contains("reqwest::Method::POST"));
@@ -86,7 +87,7 @@ fn test_generate_rust() {
 #[test]
 fn test_generate_ruby() {
     let app = create_test_app();
-    let code = app.generate_ruby_code();
+    let code = app.generate_code("ruby").unwrap();
 
     assert!(code.contains("require 'net/http'"));
     assert!(code.contains("Net::HTTP::Post.new(url)"));
@@ -97,7 +98,7 @@ fn test_generate_ruby() {
 #[test]
 fn test_generate_php() {
     let app = create_test_app();
-    let code = app.generate_php_code();
+    let code = app.generate_code("php").unwrap();
 
     assert!(code.contains("curl_init()"));
     assert!(code.contains("CURLOPT_CUSTOMREQUEST => 'POST'"));
@@ -108,10 +109,214 @@ fn test_generate_php() {
 #[test]
 fn test_generate_csharp() {
     let app = create_test_app();
-    let code = app.generate_csharp_code();
+    let code = app.generate_code("csharp").unwrap();
 
     assert!(code.contains("new HttpClient()"));
     assert!(code.contains("HttpMethod.Post"));
     assert!(code.contains("Headers.Add(\"Authorization\", \"Bearer test_token_123\")"));
     assert!(code.contains("new StringContent(\"{\\\"key\\\": \\\"value\\\"}\""));
 }
+
+#[test]
+fn test_generate_urlencoded() {
+    let mut app = create_test_app();
+    {
+        let tab = app.active_tab_mut();
+        tab.body_type = BodyType::UrlEncoded;
+        tab.urlencoded = vec![("name".to_string(), "Alice".to_string())];
+    }
+
+    assert!(
+        app.generate_curl_command()
+            .contains("--data-urlencode 'name=Alice'")
+    );
+    assert!(
+        app.generate_code("python")
+            .unwrap()
+            .contains("\"name\": \"Alice\"")
+    );
+    assert!(
+        app.generate_code("javascript")
+            .unwrap()
+            .contains("new URLSearchParams")
+    );
+    assert!(
+        app.generate_code("go")
+            .unwrap()
+            .contains("data.Set(\"name\", \"Alice\")")
+    );
+    assert!(app.generate_code("rust").unwrap().contains(".form(&["));
+    assert!(
+        app.generate_code("csharp")
+            .unwrap()
+            .contains("FormUrlEncodedContent")
+    );
+}
+
+#[test]
+fn test_curl_multiline_with_cookies_and_proxy() {
+    use crate::features::curl_export::Shell;
+
+    let mut app = create_test_app();
+    app.cookie_jar.insert(
+        "api.example.com".to_string(),
+        vec!["session=abc".to_string()],
+    );
+    app.proxy_url = Some("http://proxy:8080".to_string());
+    app.ssl_verify = false;
+
+    let code = app.curl_command(Shell::Posix, true);
+    let lines: Vec<&str> = code.lines().collect();
+    assert_eq!(
+        lines[0],
+        "curl -X POST \"https://api.example.com/v1/resource\" \\"
+    );
+    assert!(lines[1..lines.len() - 1].iter().all(|l| l.ends_with(" \\")));
+    assert!(!lines.last().unwrap().ends_with('\\'));
+    assert!(code.contains("-b \"session=abc\""));
+    assert!(code.contains("--proxy \"http://proxy:8080\""));
+    assert!(code.contains("--insecure"));
+
+    let oneline = app.curl_command(Shell::Posix, false);
+    assert_eq!(oneline.lines().count(), 1);
+}
+
+#[test]
+fn test_curl_quoting_per_shell() {
+    use crate::features::curl_export::Shell;
+
+    let mut app = create_test_app();
+    {
+        let tab = app.active_tab_mut();
+        tab.request_body = r#"{"price": "$5", "note": "it's"}"#.to_string();
+        tab.request_headers
+            .insert("X-Cost".to_string(), "$HOME".to_string());
+    }
+
+    let posix = app.curl_command(Shell::Posix, true);
+    assert!(posix.contains(r#"-d '{"price": "$5", "note": "it'\''s"}'"#));
+    // $ would expand inside double quotes
+    assert!(posix.contains("-H 'X-Cost: $HOME'"));
+
+    let cmd = app.curl_command(Shell::Cmd, true);
+    assert!(cmd.contains(" ^\n  "));
+    assert!(cmd.contains(r#"-d "{""price"": ""$5"", ""note"": ""it's""}""#));
+    // cmd.exe has no single quotes
+    assert!(!cmd.contains(" '"));
+
+    // Doubled quotes keep cmd inside them, so & and | stay literal; % can't be
+    // quoted and goes between quotes, escaped
+    assert_eq!(Shell::Cmd.quote("a&b|c"), r#""a&b|c""#);
+    assert_eq!(Shell::Cmd.quote(r#"say "hi" & go"#), r#""say ""hi"" & go""#);
+    assert_eq!(Shell::Cmd.quote("100%PATH%"), r#""100"^%"PATH"^%"""#);
+    assert_eq!(Shell::Cmd.quote(r"C:\dir\"), r#""C:\dir\\""#);
+    // A line break is a caret and an empty line, outside the quotes
+    assert_eq!(Shell::Cmd.quote("a\r\nb"), "\"a\"^\n\n\"b\"");
+
+    {
+        let tab = app.active_tab_mut();
+        tab.request_body = "{\n\"q\": \"a&b\"\n}".to_string();
+        tab.request_headers.clear();
+    }
+    let cmd = app.curl_command(Shell::Cmd, true);
+    assert!(cmd.contains("-d \"{\"^\n\n\"\"\"q\"\": \"\"a&b\"\"\"^\n\n\"}\""));
+}
+
+#[test]
+fn test_curl_graphql_body_is_valid_json() {
+    let mut app = create_test_app();
+    {
+        let tab = app.active_tab_mut();
+        tab.request_headers.clear();
+        tab.body_type = BodyType::GraphQL;
+        tab.graphql_query = "query {\n  user(id: \"1\") { name }\n}".to_string();
+        tab.graphql_variables = r#"{"id": 1}"#.to_string();
+    }
+    let code = app.generate_curl_command();
+    assert!(code.contains("-H \"Content-Type: application/json\""));
+
+    let body_line = code
+        .lines()
+        .find(|l| l.trim_start().starts_with("-d "))
+        .unwrap();
+    let json = body_line
+        .trim_start()
+        .trim_start_matches("-d '")
+        .trim_end_matches(" \\")
+        .trim_end_matches('\'');
+    let parsed: serde_json::Value = serde_json::from_str(json).unwrap();
+    assert_eq!(parsed["variables"]["id"], 1);
+    assert!(
+        parsed["query"]
+            .as_str()
+            .unwrap()
+            .contains("user(id: \"1\")")
+    );
+}
+
+#[test]
+fn test_template_engine() {
+    use crate::features::codegen::engine::render;
+
+    let context = serde_json::json!({
+        "method": "post",
+        "items": [{ "k": "a" }, { "k": "b" }],
+        "kind": "form",
+    });
+    let template = "\
+{# comment line disappears #}
+{{ method | upper }} {{ method | capitalize }}
+  {% for i in items %}
+{{ i.k }}{% if not loop.last %},{% endif %}
+  {% endfor %}
+{% if kind == \"raw\" %}
+raw
+{% elif kind != \"form\" %}
+other
+{% else %}
+form
+{% endif %}
+{{ missing }}done";
+    assert_eq!(
+        render(template, &context).unwrap(),
+        "POST Post\na,\nb\nform\ndone"
+    );
+
+    assert!(render("{{ x | nope }}", &context).is_err());
+    assert!(render("{% for x in items %}", &context).is_err());
+    assert!(render("{% endif %}", &context).is_err());
+}
+
+#[test]
+fn test_custom_templates_dir() {
+    use crate::features::codegen::{find_target, load_targets};
+
+    let dir = temp_dir("codegen");
+    std::fs::write(
+        dir.join("kotlin.tmpl"),
+        "{# name: Kotlin (OkHttp) #}\nRequest.Builder().url(\"{{ url }}\")\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("python.tmpl"), "print('{{ method }}')\n").unwrap();
+    std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+    let targets = load_targets(dir.to_str().unwrap());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(targets[0].id, "curl");
+    let kotlin = find_target(&targets, "kotlin (okhttp)").unwrap();
+    assert_eq!(kotlin.id, "kotlin");
+    assert!(!kotlin.builtin);
+    // Same id as a built-in replaces it in place
+    let python = find_target(&targets, "python").unwrap();
+    assert!(!python.builtin);
+    assert_eq!(targets.iter().filter(|t| t.id == "python").count(), 1);
+    assert!(find_target(&targets, "notes").is_none());
+
+    let app = create_test_app();
+    let code = crate::features::codegen::render_target(kotlin, &app.codegen_context()).unwrap();
+    assert_eq!(
+        code,
+        "Request.Builder().url(\"https://api.example.com/v1/resource\")\n"
+    );
+}
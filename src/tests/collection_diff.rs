@@ -0,0 +1,192 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::collection::Collection;
+use crate::features::collection_diff::{self, Source, Tone};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const OLD: &str = r#"
+request "List users" {
+  method = "GET"
+  url = "{{base}}/users"
+  headers = {
+    Accept = "application/json"
+    X-Old = "1"
+  }
+}
+
+request "Delete user" {
+  method = "DELETE"
+  url = "{{base}}/users/:id"
+}
+
+request "Create user" {
+  method = "POST"
+  url = "{{base}}/users"
+  body = "{\n  \"name\": \"Ada\"\n}"
+}
+"#;
+
+const NEW: &str = r#"
+request "List users" {
+  method = "GET"
+  url = "{{base}}/v2/users"
+  headers = {
+    Accept = "application/json"
+    Authorization = "Bearer {{token}}"
+  }
+}
+
+request "Create user" {
+  method = "POST"
+  url = "{{base}}/users"
+  body = "{\n  \"name\": \"Grace\"\n}"
+}
+
+request "Get user" {
+  method = "GET"
+  url = "{{base}}/users/:id"
+}
+"#;
+
+fn git(dir: &Path, args: &[&str]) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args([
+            "-c",
+            "user.name=t",
+            "-c",
+            "user.email=t@t",
+            "-c",
+            "commit.gpgsign=false",
+        ])
+        .args(args)
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+#[test]
+fn test_diff_requests() {
+    let old = Collection::from_hcl("users", OLD).unwrap();
+    let new = Collection::from_hcl("users", NEW).unwrap();
+    let diffs = collection_diff::diff(&old, &new);
+    let summary: Vec<(&str, Tone)> = diffs.iter().map(|d| (d.name.as_str(), d.tone)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Create user", Tone::Changed),
+            ("Delete user", Tone::Removed),
+            ("Get user", Tone::Added),
+            ("List users", Tone::Changed),
+        ]
+    );
+
+    let list = &diffs[3];
+    let fields: Vec<(&str, Option<&str>, Option<&str>)> = list
+        .changes
+        .iter()
+        .map(|c| (c.field.as_str(), c.before.as_deref(), c.after.as_deref()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("url", Some("{{base}}/users"), Some("{{base}}/v2/users")),
+            ("header X-Old", Some("1"), None),
+            ("header Authorization", None, Some("Bearer {{token}}")),
+        ]
+    );
+
+    let report = collection_diff::Report {
+        old: "a".to_string(),
+        new: "b".to_string(),
+        collection: Vec::new(),
+        requests: diffs,
+    };
+    assert_eq!(report.summary(), "1 added, 1 removed, 2 changed");
+    let lines: Vec<String> = report.lines().into_iter().map(|l| l.text).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "~ Create user",
+            "    body:",
+            "        {",
+            "      -   \"name\": \"Ada\"",
+            "      +   \"name\": \"Grace\"",
+            "        }",
+            "",
+            "- Delete user  DELETE {{base}}/users/:id",
+            "",
+            "+ Get user  GET {{base}}/users/:id",
+            "",
+            "~ List users",
+            "    ~ url: {{base}}/users → {{base}}/v2/users",
+            "    - header X-Old: 1",
+            "    + header Authorization: Bearer {{token}}",
+        ]
+    );
+
+    assert!(collection_diff::diff(&old, &old).is_empty());
+}
+
+#[test]
+fn test_sources() {
+    let dir = Path::new("collections");
+    assert_eq!(
+        Source::parse("users", dir),
+        Source {
+            rev: None,
+            path: dir.join("users.hcl")
+        }
+    );
+    assert_eq!(
+        Source::parse("HEAD~1:users", dir),
+        Source {
+            rev: Some("HEAD~1".to_string()),
+            path: dir.join("users.hcl")
+        }
+    );
+    assert_eq!(
+        Source::parse("main:old/shop.hcl", dir).path,
+        PathBuf::from("old/shop.hcl")
+    );
+}
+
+#[test]
+fn test_diff_against_git_revision() {
+    let dir = temp_dir("coldiff");
+    let collections = dir.join("collections");
+    std::fs::create_dir_all(&collections).unwrap();
+    std::fs::write(collections.join("users.hcl"), OLD).unwrap();
+    if !git(&dir, &["init", "-q"])
+        || !git(&dir, &["add", "."])
+        || !git(&dir, &["commit", "-qm", "users"])
+    {
+        // No git here, nothing to compare against
+        std::fs::remove_dir_all(&dir).unwrap();
+        return;
+    }
+    std::fs::write(collections.join("users.hcl"), NEW).unwrap();
+    std::fs::write(collections.join("users.md"), "Everything about users").unwrap();
+
+    let report = collection_diff::compare_sources("HEAD:users", None, &collections).unwrap();
+    assert_eq!(report.summary(), "1 added, 1 removed, 2 changed");
+    assert_eq!(
+        report.collection[0].after.as_deref(),
+        Some("Everything about users")
+    );
+    assert!(report.new.ends_with("users.hcl") && report.old.starts_with("HEAD:"));
+
+    let err = collection_diff::compare_sources("users", None, &collections).unwrap_err();
+    assert!(err.contains("git revision"), "{}", err);
+    assert!(collection_diff::compare_sources("nope:users", None, &collections).is_err());
+
+    let mut app = App::with_workspace(crate::domain::workspace::Workspace::at(&dir));
+    app.collection_diff_command("HEAD:users users");
+    assert_eq!(
+        app.collection_diff.as_ref().map(|r| r.requests.len()),
+        Some(4)
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
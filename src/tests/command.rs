@@ -0,0 +1,187 @@
+use crate::app::{App, InputMode};
+use crate::domain::collection::{Collection, RequestConfig};
+use crate::domain::environment::Environment;
+use crate::features::command::{self, Completions};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+
+fn type_command(app: &mut App, text: &str) {
+    let press = |app: &mut App, code| {
+        crate::handler::handle_key_events(KeyEvent::new(code, KeyModifiers::NONE), app)
+    };
+    press(app, KeyCode::Char(':'));
+    for c in text.chars() {
+        press(app, KeyCode::Char(c));
+    }
+    press(app, KeyCode::Enter);
+}
+
+fn test_app() -> App {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.command_history.clear();
+    app.environments = vec![
+        Environment {
+            name: "None".to_string(),
+            variables: HashMap::new(),
+            ..Default::default()
+        },
+        Environment {
+            name: "staging".to_string(),
+            variables: HashMap::new(),
+            ..Default::default()
+        },
+    ];
+    let request = RequestConfig {
+        url: "https://api.example.com/users".to_string(),
+        method: "POST".to_string(),
+        body: Some("{}".to_string()),
+        headers: None,
+        extract: None,
+        body_type: None,
+        form_data: None,
+        graphql_query: None,
+        graphql_variables: None,
+        expected_status: None,
+        timeout_ms: None,
+        connect_timeout_ms: None,
+        read_timeout_ms: None,
+        pre_request_script: None,
+        post_request_script: None,
+        stress_stages: None,
+        disabled_default_headers: Vec::new(),
+        disabled_headers: HashMap::new(),
+        path_params: HashMap::new(),
+        description: None,
+        variables: HashMap::new(),
+        grpc_service: None,
+        grpc_proto_path: None,
+        tags: Vec::new(),
+        treat_as: None,
+        paginate: None,
+    };
+    app.collections = vec![Collection {
+        name: "users".to_string(),
+        requests: HashMap::from([("Create user".to_string(), request)]),
+        ws_scenarios: HashMap::new(),
+        description: None,
+        variables: HashMap::new(),
+        snapshots: Default::default(),
+    }];
+    app
+}
+
+#[test]
+fn test_complete() {
+    let words = Completions {
+        environments: vec!["staging".to_string(), "prod".to_string()],
+        requests: vec!["users/Create user".to_string(), "users/List".to_string()],
+        headers: vec!["X-Trace".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(command::complete("me", &words), vec!["method"]);
+    assert_eq!(
+        command::complete("method p", &words),
+        vec!["method POST", "method PUT", "method PATCH"]
+    );
+    assert_eq!(
+        command::complete("env use s", &words),
+        vec!["env use staging"]
+    );
+    assert_eq!(
+        command::complete("header rm x", &words),
+        vec!["header rm X-Trace"]
+    );
+    assert_eq!(
+        command::complete("open users/c", &words),
+        vec!["open users/Create user"]
+    );
+    assert!(command::complete("zen x", &words).is_empty());
+
+    let candidates = command::complete("method p", &words);
+    assert_eq!(command::common_prefix(&candidates), "method P");
+}
+
+#[test]
+fn test_tokenize_and_header() {
+    assert_eq!(
+        command::tokenize(r#"use "eu west" 'a b'  c"#),
+        vec!["use", "eu west", "a b", "c"]
+    );
+    assert_eq!(
+        command::parse_header("X-Foo: bar: baz"),
+        Some(("X-Foo".to_string(), "bar: baz".to_string()))
+    );
+    assert_eq!(
+        command::parse_header("X-Foo bar"),
+        Some(("X-Foo".to_string(), "bar".to_string()))
+    );
+    assert_eq!(command::parse_header("no"), None);
+}
+
+#[test]
+fn test_commands_edit_the_tab() {
+    let mut app = test_app();
+
+    type_command(&mut app, "method patch");
+    assert_eq!(app.active_tab().method, "PATCH");
+
+    type_command(&mut app, "header add X-Foo: bar");
+    type_command(&mut app, "header set x-foo: baz");
+    assert_eq!(
+        app.active_tab().request_headers,
+        HashMap::from([("x-foo".to_string(), "baz".to_string())])
+    );
+    type_command(&mut app, "header rm X-FOO");
+    assert!(app.active_tab().request_headers.is_empty());
+
+    type_command(&mut app, "env use staging");
+    assert_eq!(app.selected_env_index, 1);
+
+    type_command(&mut app, "open users/create user");
+    assert_eq!(app.active_tab().url, "https://api.example.com/users");
+    assert_eq!(app.active_tab().method, "POST");
+
+    type_command(&mut app, "send");
+    assert!(app.active_tab().resend_requested);
+    assert_eq!(app.active_tab().input_mode, InputMode::Normal);
+
+    type_command(&mut app, "run users");
+    assert!(app.runner_mode && app.should_run_collection);
+}
+
+#[test]
+fn test_tab_completion_and_history() {
+    let mut app = test_app();
+    type_command(&mut app, "method put");
+    type_command(&mut app, "zen");
+
+    let press = |app: &mut App, code| {
+        crate::handler::handle_key_events(KeyEvent::new(code, KeyModifiers::NONE), app)
+    };
+    press(&mut app, KeyCode::Char(':'));
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.command_input, "zen");
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.command_input, "method put");
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    assert_eq!(app.command_input, "");
+
+    for c in "op".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.command_input, "open ");
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.command_input, "open users/Create user ");
+
+    // Several matches: the shared part first, then cycling
+    app.command_input = "env ".to_string();
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.command_input, "env use");
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.command_input, "env list");
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.command_input, "env staging");
+}
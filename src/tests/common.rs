@@ -0,0 +1,44 @@
+// Helpers shared by the tests
+use crate::app::App;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A fresh empty directory under the system temp dir, a new one every call, so
+/// tests running at once never share files and nothing lands in the checkout
+pub fn temp_dir(name: &str) -> std::path::PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "postdad_{}_{}_{}",
+        name,
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// The newest notification's text, or nothing
+pub fn last_message(app: &App) -> String {
+    app.notifications
+        .history
+        .front()
+        .map(|n| n.message.clone())
+        .unwrap_or_default()
+}
+
+/// A key press without modifiers, through the key handler
+pub fn press(app: &mut App, code: KeyCode) {
+    press_with(app, code, KeyModifiers::NONE);
+}
+
+pub fn press_with(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    crate::handler::handle_key_events(KeyEvent::new(code, modifiers), app);
+}
+
+/// Types `text` a key at a time
+pub fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        press(app, KeyCode::Char(c));
+    }
+}
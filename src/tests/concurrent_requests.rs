@@ -0,0 +1,137 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::workspace::Workspace;
+use crate::net::http::NetworkEvent;
+use crate::net::mock_server::MockRoute;
+use std::time::Duration;
+
+fn get(url: String, request_id: &str) -> NetworkEvent {
+    NetworkEvent::RunRequest {
+        url,
+        method: "GET".to_string(),
+        headers: Default::default(),
+        body: None,
+        form_data: None,
+        auth: None,
+        timeout_ms: Some(5000),
+        phase_timeouts: Default::default(),
+        ssl_verify: true,
+        ssl_ca_cert: None,
+        ssl_client_cert: None,
+        ssl_client_key: None,
+        proxy_url: None,
+        proxy_auth: None,
+        no_proxy: None,
+        pool: Default::default(),
+        resolve: Vec::new(),
+        request_id: request_id.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_slow_send_does_not_hold_up_the_next() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let routes = vec![
+        MockRoute {
+            path: "/slow".to_string(),
+            method: "GET".to_string(),
+            status: 200,
+            body: "slow".to_string(),
+            delay_ms: Some(800),
+            ..Default::default()
+        },
+        MockRoute {
+            path: "/fast".to_string(),
+            method: "GET".to_string(),
+            status: 200,
+            body: "fast".to_string(),
+            ..Default::default()
+        },
+    ];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let (tx, rx) = tokio::sync::mpsc::channel(8);
+    let (reply_tx, mut reply_rx) = tokio::sync::mpsc::channel(8);
+    tokio::spawn(crate::net::http::handle_network(rx, reply_tx));
+    let base = format!("http://127.0.0.1:{}", port);
+    tx.send(get(format!("{}/slow", base), "tab-1"))
+        .await
+        .unwrap();
+    tx.send(get(format!("{}/fast", base), "tab-2"))
+        .await
+        .unwrap();
+
+    let mut answered = Vec::new();
+    while answered.len() < 2 {
+        match reply_rx.recv().await {
+            Some(NetworkEvent::GotResponse(request_id, body, ..)) => {
+                answered.push((request_id, String::from_utf8(body).unwrap()))
+            }
+            Some(NetworkEvent::Failed { error, .. }) => panic!("request failed: {}", error),
+            Some(_) => {}
+            None => panic!("network task stopped"),
+        }
+    }
+    assert_eq!(
+        answered,
+        vec![
+            ("tab-2".to_string(), "fast".to_string()),
+            ("tab-1".to_string(), "slow".to_string()),
+        ]
+    );
+    server.handle.abort();
+}
+
+#[test]
+fn test_reply_goes_to_the_tab_that_sent() {
+    let dir = temp_dir("concurrent_route");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.add_tab();
+    app.tabs[0].request_id = Some("aaaa0001".to_string());
+    app.tabs[1].request_id = Some("bbbb0002".to_string());
+    assert_eq!(app.active_tab, 1);
+
+    assert_eq!(app.tab_for_request("aaaa0001"), Some(0));
+    let previous = app.enter_request_tab("aaaa0001").unwrap();
+    assert_eq!((previous, app.active_tab), (1, 0));
+    app.leave_request_tab(previous);
+    assert_eq!(app.active_tab, 1);
+
+    // The first tab sent again: the old send's reply has nowhere to go
+    app.tabs[0].request_id = Some("cccc0003".to_string());
+    assert_eq!(app.enter_request_tab("aaaa0001"), None);
+    assert_eq!(app.active_tab, 1);
+
+    // Restoring history over a send in flight: its reply is dropped, so the tab
+    // stops waiting for it
+    app.tabs[1].is_loading = true;
+    app.request_history.push(crate::app::RequestLog {
+        method: "GET".to_string(),
+        url: "http://localhost/old".to_string(),
+        status: 200,
+        latency: 3,
+        body: Some("old".to_string()),
+        headers: Default::default(),
+        response_bytes: None,
+        is_binary: false,
+        timestamp: 0,
+        pinned: false,
+        request: None,
+        size: 3,
+        tests: Vec::new(),
+    });
+    app.restore_history_entry(0);
+    assert_eq!(app.tab_for_request("bbbb0002"), None);
+    assert!(!app.active_tab().is_loading);
+    app.active_tab_mut().is_loading = true;
+    app.active_tab_mut().request_id = Some("dddd0004".to_string());
+    app.active_tab_mut().clear_response();
+    assert!(!app.active_tab().is_loading);
+    let _ = std::fs::remove_dir_all(&dir);
+}
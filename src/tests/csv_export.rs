@@ -0,0 +1,62 @@
+use crate::features::csv_export::{Table, parse_export_args, to_table};
+use serde_json::json;
+
+#[test]
+fn test_columns_are_union_of_keys() {
+    let value = json!([
+        {"id": 1, "name": "Ada"},
+        {"id": 2, "email": "bob@example.com", "tags": ["a", "b"]},
+        {"id": 3, "name": null}
+    ]);
+    let table = to_table(&value).unwrap();
+    assert_eq!(table.columns, vec!["id", "name", "email", "tags"]);
+    assert_eq!(
+        table.rows[1],
+        vec!["2", "", "bob@example.com", r#"["a","b"]"#]
+    );
+    assert_eq!(table.rows[2], vec!["3", "", "", ""]);
+}
+
+#[test]
+fn test_finds_wrapped_array() {
+    let value = json!({"page": 1, "data": [{"id": 1}, {"id": 2}]});
+    assert_eq!(to_table(&value).unwrap().rows.len(), 2);
+    assert!(to_table(&json!({"id": 1})).is_err());
+    assert!(to_table(&json!([1, 2, 3])).is_err());
+}
+
+#[test]
+fn test_csv_quoting_and_tsv() {
+    let table = Table {
+        columns: vec!["name".to_string(), "note".to_string()],
+        rows: vec![vec![
+            "Smith, J".to_string(),
+            "said \"hi\"\tthen left".to_string(),
+        ]],
+    };
+    assert_eq!(
+        table.to_delimited(','),
+        "name,note\n\"Smith, J\",\"said \"\"hi\"\"\tthen left\"\n"
+    );
+    assert_eq!(
+        table.to_delimited('\t'),
+        "name\tnote\nSmith, J\tsaid \"hi\" then left\n"
+    );
+}
+
+#[test]
+fn test_column_selection() {
+    let table = to_table(&json!([{"a": 1, "b": 2, "c": 3}])).unwrap();
+    let picked = table.select(&["c".to_string(), "a".to_string()]).unwrap();
+    assert_eq!(picked.to_delimited(','), "c,a\n3,1\n");
+    assert!(table.select(&["zzz".to_string()]).is_err());
+
+    assert_eq!(
+        parse_export_args(" id, name > out.csv"),
+        (
+            vec!["id".to_string(), "name".to_string()],
+            Some("out.csv".to_string())
+        )
+    );
+    assert_eq!(parse_export_args(""), (vec![], None));
+}
@@ -0,0 +1,132 @@
+use super::common::press;
+use crate::app::App;
+use crate::features::csv_view::{self, CsvView};
+use crossterm::event::KeyCode;
+
+const BODY: &str =
+    "name,age,city\r\nada,36,London\r\n\"Hopper, Grace\",85,\"New \"\"York\"\"\"\r\nlinus,9,\r\n";
+
+#[test]
+fn test_delimiter_from_content_type() {
+    assert_eq!(csv_view::delimiter("text/csv; charset=utf-8"), Some(','));
+    assert_eq!(csv_view::delimiter("Text/CSV"), Some(','));
+    assert_eq!(csv_view::delimiter("text/tab-separated-values"), Some('\t'));
+    assert_eq!(csv_view::delimiter("text/plain"), None);
+    assert_eq!(csv_view::delimiter("application/json"), None);
+}
+
+#[test]
+fn test_parse_quoted_fields() {
+    let table = csv_view::parse(BODY, ',');
+    assert_eq!(table.columns, vec!["name", "age", "city"]);
+    assert_eq!(table.rows.len(), 3);
+    assert_eq!(table.rows[1], vec!["Hopper, Grace", "85", "New \"York\""]);
+    assert_eq!(table.rows[2], vec!["linus", "9", ""]);
+
+    // Line breaks inside quotes stay in the cell, ragged rows are evened out
+    let table = csv_view::parse("a\tb\n\"x\ny\"\n1\t2\t3\n", '\t');
+    assert_eq!(table.columns, vec!["a", "b", "column 3"]);
+    assert_eq!(table.rows[0], vec!["x\ny", "", ""]);
+    assert_eq!(table.rows[1], vec!["1", "2", "3"]);
+}
+
+#[test]
+fn test_sort_cycles_and_keeps_the_cursor_on_its_record() {
+    let mut view = CsvView::new(csv_view::parse(BODY, ','));
+    view.column = 1;
+    view.row = 2;
+    assert_eq!(view.cell(), Some("9"));
+
+    view.cycle_sort();
+    assert_eq!(view.sort, Some((1, false)));
+    // Numbers compare as numbers
+    let ages: Vec<&str> = view.rows().map(|r| r[1].as_str()).collect();
+    assert_eq!(ages, vec!["9", "36", "85"]);
+    assert_eq!(view.cell(), Some("9"));
+
+    view.cycle_sort();
+    let ages: Vec<&str> = view.rows().map(|r| r[1].as_str()).collect();
+    assert_eq!(ages, vec!["85", "36", "9"]);
+
+    view.cycle_sort();
+    assert_eq!(view.sort, None);
+    assert_eq!(view.order, vec![0, 1, 2]);
+
+    // Empty cells sort last
+    view.column = 2;
+    view.cycle_sort();
+    let cities: Vec<&str> = view.rows().map(|r| r[2].as_str()).collect();
+    assert_eq!(cities, vec!["London", "New \"York\"", ""]);
+}
+
+#[test]
+fn test_sort_without_columns_or_with_short_rows() {
+    let mut view = CsvView::new(csv_view::parse("", ','));
+    assert!(view.table.columns.is_empty());
+    view.cycle_sort();
+    assert_eq!(view.sort, None);
+
+    // Rows built by hand can be shorter than the header
+    let mut view = CsvView::new(crate::features::csv_export::Table {
+        columns: vec!["a".to_string(), "b".to_string()],
+        rows: vec![
+            vec!["1".to_string(), "y".to_string()],
+            vec!["2".to_string()],
+            vec!["3".to_string(), "x".to_string()],
+        ],
+    });
+    view.column = 1;
+    view.cycle_sort();
+    assert_eq!(view.order, vec![2, 0, 1]);
+    assert_eq!(view.widths(), vec![3, 3]);
+}
+
+#[test]
+fn test_scroll_into_view_follows_the_cursor() {
+    let mut view = CsvView::new(csv_view::parse(BODY, ','));
+    // name is as wide as "Hopper, Grace", age as its header plus a sort arrow
+    assert_eq!(view.widths(), vec![13, 5, 10]);
+    assert_eq!(view.scroll_into_view(2, 20), 0..2);
+
+    view.move_column(5);
+    view.move_row(5);
+    assert_eq!((view.row, view.column), (2, 2));
+    assert_eq!(view.scroll_into_view(2, 20), 1..3);
+    assert_eq!((view.top, view.left), (1, 1));
+
+    view.move_column(-9);
+    assert_eq!(view.scroll_into_view(2, 20), 0..2);
+}
+
+#[test]
+fn test_csv_response_keys() {
+    let mut app = App::new();
+    app.show_splash = false;
+    {
+        let tab = app.active_tab_mut();
+        tab.selected_tab = 2;
+        tab.response = Some(BODY.to_string());
+        tab.response_headers
+            .insert("content-type".to_string(), "text/csv".to_string());
+        tab.detect_csv();
+        assert!(tab.response_csv.is_some());
+    }
+
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('l'));
+    press(&mut app, KeyCode::Char('o'));
+    let csv = app.active_tab().response_csv.as_ref().unwrap();
+    assert_eq!(csv.sort, Some((1, false)));
+    assert_eq!(csv.cell(), Some("85"));
+
+    // Raw shows the text, so the keys go back to scrolling it
+    press(&mut app, KeyCode::Char('J'));
+    assert!(app.active_tab_mut().shown_csv().is_none());
+
+    // Other content types aren't tables
+    let tab = app.active_tab_mut();
+    tab.response_headers
+        .insert("content-type".to_string(), "text/plain".to_string());
+    tab.detect_csv();
+    assert!(tab.response_csv.is_none());
+}
@@ -0,0 +1,79 @@
+use crate::features::curl_import::{collection_name_for, extract_commands, parse_batch};
+
+#[test]
+fn test_extract_commands_from_shell_history() {
+    let history = r#": 1700000000:0;curl https://api.example.com/users
+#1700000001
+ls -la
+cd api && curl -s https://api.example.com/health | jq .
+- cmd: curl -X DELETE https://api.example.com/users/1
+  when: 1700000002
+curl -X POST https://api.example.com/users \
+  -H "Content-Type: application/json" \
+  -d '{"name":
+  "Alice"}'
+"#;
+    let commands = extract_commands(history);
+    assert_eq!(commands.len(), 4);
+    assert_eq!(commands[0], "curl https://api.example.com/users");
+    assert_eq!(commands[1], "curl -s https://api.example.com/health");
+    assert_eq!(
+        commands[2],
+        "curl -X DELETE https://api.example.com/users/1"
+    );
+    assert!(commands[3].starts_with("curl -X POST"));
+    assert!(commands[3].contains("Alice"));
+}
+
+#[test]
+fn test_quoted_pipes_stay_in_the_command() {
+    let commands = extract_commands(r#"curl -d 'a|b;c' "https://x.io/?a=1&b=2" > out.json"#);
+    assert_eq!(commands, vec![r#"curl -d 'a|b;c' "https://x.io/?a=1&b=2""#]);
+}
+
+#[test]
+fn test_parse_batch_dedupes_and_names() {
+    let text = r#"curl https://api.example.com/users
+curl https://api.example.com/users
+curl -H "Accept: text/plain" https://api.example.com/users
+curl -X POST -d '{"a":1}' https://api.example.com/users/
+curl -u admin:secret https://api.example.com/admin
+curl -X
+"#;
+    let batch = parse_batch(text);
+    assert_eq!(batch.duplicates, 1);
+    assert_eq!(batch.failed.len(), 1);
+
+    let names: Vec<&str> = batch.requests.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "GET api.example.com/users",
+            "GET api.example.com/users (2)",
+            "POST api.example.com/users",
+            "GET api.example.com/admin",
+        ]
+    );
+
+    let post = &batch.requests[2].1;
+    assert_eq!(post.body.as_deref(), Some(r#"{"a":1}"#));
+
+    let admin = &batch.requests[3].1;
+    assert_eq!(
+        admin
+            .headers
+            .as_ref()
+            .unwrap()
+            .get("Authorization")
+            .unwrap(),
+        "Basic YWRtaW46c2VjcmV0"
+    );
+    assert!(batch.summary().contains("1 duplicates skipped"));
+}
+
+#[test]
+fn test_collection_name_for_source() {
+    assert_eq!(collection_name_for("/home/me/.zsh_history"), "zsh_history");
+    assert_eq!(collection_name_for("requests.sh"), "requests");
+    assert_eq!(collection_name_for("-"), "curl_import");
+}
@@ -0,0 +1,205 @@
+use crate::app::App;
+use crate::domain::collection::Collection;
+use crate::features::dashboard::{self, Dashboard, DashboardEvent, RECENT};
+use crate::features::runner::RunResult;
+use crate::net::mock_server::MockRoute;
+
+fn collection(name: &str, base: &str) -> Collection {
+    Collection::from_hcl(
+        name,
+        &format!(
+            r#"
+request "Ping" {{
+  method = "GET"
+  url = "{base}/ok"
+  tags = ["Health", "smoke"]
+}}
+
+request "Status" {{
+  method = "GET"
+  url = "{base}/broken"
+  tags = ["health"]
+}}
+
+request "Create" {{
+  method = "POST"
+  url = "{base}/items"
+  tags = ["smoke"]
+}}
+"#
+        ),
+    )
+    .unwrap()
+}
+
+fn result(name: &str, passed: bool) -> RunResult {
+    RunResult {
+        name: name.to_string(),
+        method: "GET".to_string(),
+        url: "http://api.test/".to_string(),
+        status: Some(if passed { 200 } else { 503 }),
+        latency_ms: Some(12),
+        size: Some(2),
+        expected_status: Some(200),
+        passed,
+        error: None,
+        tests: Vec::new(),
+        response_body: None,
+        response_headers: Default::default(),
+    }
+}
+
+#[test]
+fn test_health_checks_across_collections() {
+    let collections = vec![
+        collection("users", "http://users.test"),
+        Collection::from_hcl(
+            "billing",
+            "request \"Pay\" {\n  method = \"POST\"\n  url = \"/pay\"\n}\n",
+        )
+        .unwrap(),
+        collection("orders", "http://orders.test"),
+    ];
+    let checks = dashboard::health_checks(&collections, "health");
+    let names: Vec<&str> = checks.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["users", "orders"]);
+    assert_eq!(checks[0].requests.len(), 2);
+    assert!(!checks[0].requests.contains_key("Create"));
+
+    let board = Dashboard::new(&checks);
+    let tiles: Vec<(&str, &str)> = board
+        .tiles
+        .iter()
+        .map(|t| (t.collection.as_str(), t.name.as_str()))
+        .collect();
+    assert_eq!(
+        tiles,
+        vec![
+            ("orders", "Ping"),
+            ("orders", "Status"),
+            ("users", "Ping"),
+            ("users", "Status"),
+        ]
+    );
+    assert_eq!(board.summary(), (0, 0, 4));
+}
+
+#[test]
+fn test_record_results() {
+    let checks = dashboard::health_checks(&[collection("users", "http://users.test")], "health");
+    let mut board = Dashboard::new(&checks);
+    for i in 0..RECENT + 5 {
+        board.record(DashboardEvent {
+            collection: "users".to_string(),
+            result: result("Ping", i % 5 != 0),
+        });
+    }
+    board.record(DashboardEvent {
+        collection: "users".to_string(),
+        result: result("Status", false),
+    });
+    // Not on the board
+    board.record(DashboardEvent {
+        collection: "other".to_string(),
+        result: result("Ping", false),
+    });
+
+    let ping = &board.tiles[0];
+    assert_eq!(ping.checks, (RECENT + 5) as u64);
+    assert_eq!(ping.failures, 5);
+    assert_eq!(ping.recent.len(), RECENT);
+    assert_eq!(ping.healthy(), Some(true));
+    assert!(ping.checked_at.is_some());
+    assert_eq!(board.tiles[1].healthy(), Some(false));
+    assert_eq!(board.summary(), (1, 1, 0));
+}
+
+#[test]
+fn test_dashboard_command() {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.collections = vec![Collection::from_hcl("empty", "").unwrap()];
+    app.dashboard_command("");
+    assert!(app.dashboard.is_none());
+
+    app.collections = vec![collection("users", "http://users.test")];
+    app.dashboard_command("nope");
+    assert!(app.dashboard.is_none());
+    let interval = app.dashboard_settings.interval_secs;
+    app.dashboard_command("");
+    assert!(app.should_start_dashboard);
+    assert_eq!(app.dashboard.as_ref().unwrap().tiles.len(), 2);
+    assert_eq!(app.dashboard_settings.interval_secs, interval);
+
+    // Enter on a tile opens its request
+    app.dashboard.as_mut().unwrap().selected = 1;
+    app.open_dashboard_tile();
+    assert!(app.dashboard.is_none());
+    assert_eq!(app.active_tab().url, "http://users.test/broken");
+    assert_eq!(app.active_tab().tags, vec!["health".to_string()]);
+}
+
+#[test]
+fn test_checks_send_default_headers() {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.collections = vec![collection("users", "http://users.test")];
+    app.default_headers
+        .insert("Authorization".to_string(), "Bearer team".to_string());
+
+    let checks = app.dashboard_checks();
+    let headers = checks[0].requests["Ping"].headers.clone().unwrap();
+    assert_eq!(headers["Authorization"], "Bearer team");
+}
+
+#[tokio::test]
+async fn test_round_runs_every_check() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let routes = vec![
+        MockRoute {
+            path: "/ok".to_string(),
+            method: "GET".to_string(),
+            status: 200,
+            body: "ok".to_string(),
+            ..Default::default()
+        },
+        MockRoute {
+            path: "/broken".to_string(),
+            method: "GET".to_string(),
+            status: 503,
+            body: "down".to_string(),
+            ..Default::default()
+        },
+    ];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let base = format!("http://127.0.0.1:{}", port);
+    let checks = dashboard::health_checks(&[collection("users", &base)], "health");
+    let mut board = Dashboard::new(&checks);
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    let (stop_tx, stop_rx) = tokio::sync::mpsc::channel(1);
+    let task = tokio::spawn(dashboard::run_dashboard(
+        checks,
+        Default::default(),
+        60,
+        Default::default(),
+        Vec::new(),
+        tx,
+        stop_rx,
+    ));
+    for _ in 0..2 {
+        board.record(rx.recv().await.unwrap());
+    }
+    stop_tx.send(()).await.unwrap();
+    task.await.unwrap();
+    server.handle.abort();
+
+    assert_eq!(board.summary(), (1, 1, 0));
+    let status = board.tiles.iter().find(|t| t.name == "Status").unwrap();
+    assert_eq!(status.last.as_ref().unwrap().status, Some(503));
+}
@@ -0,0 +1,189 @@
+use super::common::{last_message, press, temp_dir};
+use crate::app::App;
+use crate::domain::collection::{Collection, RequestConfig};
+use crate::domain::workspace::Workspace;
+use crate::features::dedupe::{find_duplicates, merge, normalize_url, request_key};
+use crossterm::event::KeyCode;
+use std::collections::HashMap;
+
+const SHOP: &str = r#"# Shop API
+variables = {
+  version = "v2"
+}
+
+request "List users" {
+  method = "GET"
+  url = "{{base_url}}/users?page=1"
+  headers = {
+    Accept = "application/json"
+  }
+}
+
+# Imported twice
+request "Users" {
+  method = "get"
+  url = "{{host}}/Users/?page={{page}}"
+  headers = {
+    Authorization = "Bearer {{token}}"
+  }
+  post_request_script = "test(\"ok\", status_code() == 200);"
+}
+
+request "User" {
+  method = "GET"
+  url = "{{base_url}}/users/:id"
+}
+
+websocket "Feed" {
+  url = "ws://localhost/feed"
+}
+"#;
+
+const LEGACY: &str = r#"
+request "Get user" {
+  method = "GET"
+  url = "{{api}}/users/{{user_id}}"
+}
+"#;
+
+#[test]
+fn test_urls_compared_without_variables() {
+    assert_eq!(
+        normalize_url("{{base_url}}/Users/:id/?page=2&sort=name&page=3"),
+        "{{}}/users/{{}}?page&sort"
+    );
+    assert_eq!(normalize_url("{{a}}/users/{{b}}"), "{{}}/users/{{}}");
+    assert_eq!(
+        normalize_url("https://x.test/{{open"),
+        "https://x.test/{{open"
+    );
+    let config = |method: &str, url: &str| RequestConfig {
+        method: method.to_string(),
+        url: url.to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        request_key(&config("post", "{{h}}/a")),
+        request_key(&config("POST", "{{other}}/a/"))
+    );
+    assert_ne!(
+        request_key(&config("GET", "{{h}}/a")),
+        request_key(&config("DELETE", "{{h}}/a"))
+    );
+}
+
+#[test]
+fn test_groups_across_collections() {
+    let collections = vec![
+        Collection::from_hcl("legacy", LEGACY).unwrap(),
+        Collection::from_hcl("shop", SHOP).unwrap(),
+    ];
+    let groups = find_duplicates(&collections);
+    let paths: Vec<Vec<String>> = groups
+        .iter()
+        .map(|g| g.members.iter().map(|m| m.path()).collect())
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            vec!["legacy/Get user", "shop/User"],
+            vec!["shop/List users", "shop/Users"],
+        ]
+    );
+    assert_eq!(groups[1].key, "GET {{}}/users?page");
+}
+
+#[test]
+fn test_merge_keeps_values_and_adds_the_rest() {
+    let shop = Collection::from_hcl("shop", SHOP).unwrap();
+    let merged = merge(&shop.requests["List users"], &shop.requests["Users"]);
+    assert_eq!(merged.url, "{{base_url}}/users?page=1");
+    assert_eq!(merged.method, "GET");
+    assert_eq!(
+        merged.headers.unwrap(),
+        HashMap::from([
+            ("Accept".to_string(), "application/json".to_string()),
+            ("Authorization".to_string(), "Bearer {{token}}".to_string()),
+        ])
+    );
+    assert_eq!(
+        merged.post_request_script.as_deref(),
+        Some("test(\"ok\", status_code() == 200);")
+    );
+}
+
+#[test]
+fn test_write_back_leaves_the_rest_of_the_file() {
+    let dir = temp_dir("dedupe_file");
+    std::fs::create_dir_all(dir.join("collections")).unwrap();
+    let collections = dir.join("collections");
+    std::fs::write(collections.join("shop.hcl"), SHOP).unwrap();
+    let dir_str = collections.to_str().unwrap();
+
+    let mut config = Collection::from_hcl("shop", SHOP).unwrap().requests["User"].clone();
+    config.url = "{{base_url}}/users/{{id}}".to_string();
+    Collection::replace_request(dir_str, "shop", "User", &config).unwrap();
+    Collection::remove_request(dir_str, "shop", "List users").unwrap();
+    assert!(Collection::remove_request(dir_str, "shop", "Nope").is_err());
+
+    let text = std::fs::read_to_string(collections.join("shop.hcl")).unwrap();
+    assert!(text.starts_with("# Shop API\n"));
+    assert!(text.contains("# Imported twice"));
+    let shop = Collection::from_hcl("shop", &text).unwrap();
+    let mut names: Vec<&String> = shop.requests.keys().collect();
+    names.sort();
+    assert_eq!(names, vec!["User", "Users"]);
+    assert_eq!(shop.requests["User"].url, "{{base_url}}/users/{{id}}");
+    assert!(shop.ws_scenarios.contains_key("Feed"));
+    assert_eq!(shop.variables["version"], "v2");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_merge_and_delete_from_the_view() {
+    let dir = temp_dir("dedupe_app");
+    std::fs::create_dir_all(dir.join("collections")).unwrap();
+    std::fs::write(dir.join("collections/shop.hcl"), SHOP).unwrap();
+    std::fs::write(dir.join("collections/legacy.hcl"), LEGACY).unwrap();
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.collections = Collection::load_from_dir(dir.join("collections").to_str().unwrap()).unwrap();
+
+    app.dedupe_command("nope");
+    assert_eq!(last_message(&app), "No collection named 'nope'");
+    app.dedupe_command("shop");
+    assert_eq!(app.dedupe.as_ref().unwrap().groups.len(), 1);
+    let (left, right) = app.dedupe_pair().unwrap();
+    assert_eq!(left.url, "{{base_url}}/users?page=1");
+    assert_eq!(right.url, "{{host}}/Users/?page={{page}}");
+
+    press(&mut app, KeyCode::Char('m'));
+    assert_eq!(last_message(&app), "No duplicates left");
+    assert!(app.dedupe.is_none());
+    let shop = app.collections.iter().find(|c| c.name == "shop").unwrap();
+    assert!(!shop.requests.contains_key("Users"));
+    let kept = &shop.requests["List users"];
+    assert_eq!(kept.headers.as_ref().unwrap().len(), 2);
+    assert!(kept.post_request_script.is_some());
+
+    // Across collections; swapping keeps the other one
+    app.dedupe_command("");
+    press(&mut app, KeyCode::Char('s'));
+    assert_eq!(
+        app.dedupe.as_ref().unwrap().pair().unwrap().1.path(),
+        "legacy/Get user"
+    );
+    press(&mut app, KeyCode::Char('d'));
+    assert!(app.dedupe.is_none());
+    let legacy = std::fs::read_to_string(dir.join("collections/legacy.hcl")).unwrap();
+    assert!(!legacy.contains("Get user"));
+    assert!(
+        app.collections
+            .iter()
+            .any(|c| c.requests.contains_key("User"))
+    );
+
+    app.dedupe_command("");
+    assert_eq!(last_message(&app), "No duplicate requests");
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,170 @@
+use super::common::temp_dir;
+use crate::app::{App, RequestLog};
+use crate::domain::collection::Collection;
+use crate::features::doc_gen::{self, DocsSettings};
+use crate::features::doc_template;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn collections(dir: &std::path::Path) -> Vec<Collection> {
+    std::fs::write(
+        dir.join("users.hcl"),
+        "request \"List\" {\n  method = \"GET\"\n  url = \"{{base}}/users\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("orders.hcl"),
+        "request \"Create\" {\n  method = \"POST\"\n  url = \"{{base}}/orders\"\n}\n",
+    )
+    .unwrap();
+    let mut collections = Collection::load_from_dir(dir.to_str().unwrap()).unwrap();
+    collections.retain(|c| c.name != "default");
+    collections.sort_by(|a, b| a.name.cmp(&b.name));
+    collections
+}
+
+fn log(method: &str, url: &str, status: u16, body: &str) -> RequestLog {
+    RequestLog {
+        method: method.to_string(),
+        url: url.replace("{{base}}", "https://api.test"),
+        status,
+        latency: 10,
+        body: Some(body.to_string()),
+        headers: HashMap::from([("content-type".to_string(), "application/json".to_string())]),
+        response_bytes: None,
+        is_binary: false,
+        timestamp: 0,
+        pinned: false,
+        request: None,
+        size: 0,
+        tests: Vec::new(),
+    }
+}
+
+#[test]
+fn test_template_rendering() {
+    let context = json!({
+        "title": "Shop <API>",
+        "collections": [
+            {"name": "users", "requests": [{"name": "List", "example": null}, {"name": "Get", "example": {"status": 200}}]},
+            {"name": "empty", "requests": []}
+        ]
+    });
+    let template = "<h1>{{title}}</h1>{{! not shown }}{{#each collections}}[{{@index}}:{{name}}{{#each requests}} {{../name}}/{{this.name}}{{#if example}}={{example.status}}{{else}}-{{/if}}{{else}} none{{/each}}]{{/each}}{{{title}}}";
+    assert_eq!(
+        doc_template::render(template, &context).unwrap(),
+        "<h1>Shop &lt;API&gt;</h1>[0:users users/List- users/Get=200][1:empty none]Shop <API>"
+    );
+    assert_eq!(
+        doc_template::render("{{#unless missing}}yes{{/unless}}", &context).unwrap(),
+        "yes"
+    );
+
+    assert!(doc_template::render("{{#each collections}}", &context).is_err());
+    assert!(doc_template::render("{{#if a}}{{/each}}", &context).is_err());
+    assert!(doc_template::render("{{#with a}}{{/with}}", &context).is_err());
+}
+
+#[test]
+fn test_latest_response_as_example() {
+    let dir = temp_dir("doc_examples");
+    let collections = collections(&dir);
+    let users = &collections
+        .iter()
+        .find(|c| c.name == "users")
+        .unwrap()
+        .requests["List"];
+
+    // Newest first; matched on the URL as written when the entry kept it
+    let mut newest = log("GET", "{{base}}/users", 200, r#"[{"id":1}]"#);
+    newest.request = Some(users.clone());
+    let history = vec![
+        log("POST", "{{base}}/users", 201, "{}"),
+        newest,
+        log("GET", "{{base}}/users", 500, "old"),
+    ];
+    let example = doc_gen::latest_example(&history, users).unwrap();
+    assert_eq!(example.status, 200);
+    assert_eq!(example.body, "[\n  {\n    \"id\": 1\n  }\n]");
+    assert_eq!(example.content_type, "application/json");
+
+    let md = doc_gen::generate_markdown(&collections, &history);
+    assert!(md.contains("#### Example Response (200)\n```json\n[\n  {"));
+    assert!(!doc_gen::generate_markdown(&collections, &[]).contains("Example Response"));
+    let html = doc_gen::generate_html(&collections, &history);
+    assert!(html.contains("Example Response · 200"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_generate_per_collection_with_template() {
+    let dir = temp_dir("doc_split");
+    let collections = collections(&dir);
+    let template = dir.join("docs.hbs");
+    std::fs::write(
+        &template,
+        "{{#each collections}}<h1>{{name}}</h1>{{#each requests}}<p>{{method}} {{url}}{{#if example}} {{example.status}}{{/if}}</p>{{/each}}{{/each}}",
+    )
+    .unwrap();
+
+    let mut sent = log("POST", "{{base}}/orders", 201, "{}");
+    sent.request = Some(collections[0].requests["Create"].clone());
+    let history = vec![sent];
+    let out = dir.join("out");
+    let (settings, only) = doc_gen::parse_args(
+        &format!(
+            "split examples out {} template {}",
+            out.display(),
+            template.display()
+        ),
+        &DocsSettings::default(),
+    )
+    .unwrap();
+    assert_eq!(only, None);
+    let written = doc_gen::generate(&collections, &history, &settings).unwrap();
+    assert_eq!(
+        written,
+        vec![
+            out.join("orders.md"),
+            out.join("orders.html"),
+            out.join("users.md"),
+            out.join("users.html"),
+        ]
+    );
+    assert_eq!(
+        std::fs::read_to_string(out.join("orders.html")).unwrap(),
+        "<h1>orders</h1><p>POST {{base}}/orders 201</p>"
+    );
+    assert_eq!(
+        std::fs::read_to_string(out.join("users.html")).unwrap(),
+        "<h1>users</h1><p>GET {{base}}/users</p>"
+    );
+
+    // One pair by default
+    let single = DocsSettings {
+        out_dir: out.join("all").display().to_string(),
+        ..DocsSettings::default()
+    };
+    let written = doc_gen::generate(&collections, &history, &single).unwrap();
+    assert_eq!(
+        written,
+        vec![out.join("all/API_DOCS.md"), out.join("all/API_DOCS.html")]
+    );
+
+    assert!(doc_gen::parse_args("users orders", &single).is_err());
+    assert!(doc_gen::parse_args("out", &single).is_err());
+    let (settings, only) = doc_gen::parse_args("users no-examples", &settings).unwrap();
+    assert_eq!(only.as_deref(), Some("users"));
+    assert!(!settings.examples && settings.per_collection);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_docs_command_unknown_collection() {
+    let mut app = App::new();
+    app.docs_command("nope");
+    let last = app.notifications.history.front().map(|n| n.message.clone());
+    assert_eq!(last.as_deref(), Some("No collection named 'nope'"));
+}
@@ -0,0 +1,136 @@
+use super::common::temp_dir;
+use crate::app::{App, EditorMode, Theme};
+use crate::domain::collection::Collection;
+use crate::features::{doc_gen, import};
+use ratatui::style::Modifier;
+
+fn text(line: &ratatui::text::Line) -> String {
+    line.spans.iter().map(|s| s.content.as_ref()).collect()
+}
+
+#[test]
+fn test_markdown_lines() {
+    let theme = Theme::default_theme();
+    let md = "# Users\nReturns **active** users, see [docs](https://api.test/docs).\n\n- pass `user_id`\n> deprecated_soon\n```\nGET /users\n```";
+    let lines = crate::ui::markdown::render(md, &theme);
+    let texts: Vec<String> = lines.iter().map(text).collect();
+    assert_eq!(
+        texts,
+        vec![
+            "Users",
+            "Returns active users, see docs (https://api.test/docs).",
+            "",
+            "• pass user_id",
+            "│ deprecated_soon",
+            "  GET /users",
+        ]
+    );
+    assert!(
+        lines[0].spans[0]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD)
+    );
+    let bold = lines[1]
+        .spans
+        .iter()
+        .find(|s| s.content == "active")
+        .unwrap();
+    assert!(bold.style.add_modifier.contains(Modifier::BOLD));
+    let link = lines[1].spans.iter().find(|s| s.content == "docs").unwrap();
+    assert!(link.style.add_modifier.contains(Modifier::UNDERLINED));
+
+    // Underscores inside words stay as they are
+    let lines = crate::ui::markdown::render("the user_id_field and _this_", &theme);
+    assert_eq!(text(&lines[0]), "the user_id_field and this");
+}
+
+#[test]
+fn test_description_is_saved_with_the_request() {
+    let mut app = App::new();
+    app.active_tab_mut().description = "Lists users.".to_string();
+    let config = app.active_tab().to_request_config();
+    assert_eq!(config.description.as_deref(), Some("Lists users."));
+
+    app.active_tab_mut().description.clear();
+    assert_eq!(app.active_tab().to_request_config().description, None);
+
+    app.apply_request_config(config);
+    assert_eq!(app.active_tab().description, "Lists users.");
+
+    app.toggle_docs();
+    assert!(app.active_tab().show_docs);
+    app.describe_command("");
+    assert_eq!(app.editor_mode, EditorMode::Description);
+}
+
+#[test]
+fn test_collection_descriptions() {
+    let dir = temp_dir("collection_docs");
+    std::fs::write(
+        dir.join("users.hcl"),
+        "description = \"Everything about users\"\nrequest \"List\" {\n  method = \"GET\"\n  url = \"https://api.test/users\"\n  description = \"All of them\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("orders.hcl"),
+        "request \"List\" {\n  method = \"GET\"\n  url = \"https://api.test/orders\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("orders.md"), "# Orders\nPaid ones only.").unwrap();
+
+    let dir_str = dir.to_str().unwrap();
+    let collections = Collection::load_from_dir(dir_str).unwrap();
+    let find = |cols: &[Collection], name: &str| {
+        cols.iter()
+            .find(|c| c.name == name)
+            .and_then(|c| c.description.clone())
+    };
+    assert_eq!(
+        find(&collections, "users").as_deref(),
+        Some("Everything about users")
+    );
+    assert_eq!(
+        find(&collections, "orders").as_deref(),
+        Some("# Orders\nPaid ones only.")
+    );
+    assert_eq!(find(&collections, "default"), None);
+
+    let md = doc_gen::generate_markdown(&collections, &[]);
+    assert!(md.contains("## Collection: orders\n\n# Orders\nPaid ones only.\n\n"));
+    assert!(md.contains("`https://api.test/users`\n\nAll of them\n\n"));
+    let html = doc_gen::generate_html(&collections, &[]);
+    assert!(html.contains("<p>Everything about users</p>"));
+
+    // Blank removes the file; the file wins over the attribute
+    Collection::save_description(dir_str, "orders", "  ").unwrap();
+    assert!(!dir.join("orders.md").exists());
+    Collection::save_description(dir_str, "users", "From the file").unwrap();
+    let collections = Collection::load_from_dir(dir_str).unwrap();
+    assert_eq!(
+        find(&collections, "users").as_deref(),
+        Some("From the file")
+    );
+    assert_eq!(find(&collections, "orders"), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_postman_descriptions_are_imported() {
+    let json = r#"{
+        "info": {"name": "Shop", "description": "The shop API"},
+        "item": [
+            {"name": "Plain", "request": {"method": "GET", "url": "https://shop.test/a", "description": "Text form"}},
+            {"name": "Object", "request": {"method": "GET", "url": "https://shop.test/b",
+                "description": {"content": "**Markdown** form", "type": "text/markdown"}}},
+            {"name": "None", "request": {"method": "GET", "url": "https://shop.test/c"}}
+        ]
+    }"#;
+    let (collection, _) = import::parse_postman_collection(json).unwrap();
+    assert_eq!(collection.description.as_deref(), Some("The shop API"));
+    let description = |name: &str| collection.requests[name].description.clone();
+    assert_eq!(description("Plain").as_deref(), Some("Text form"));
+    assert_eq!(description("Object").as_deref(), Some("**Markdown** form"));
+    assert_eq!(description("None"), None);
+}
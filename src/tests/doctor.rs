@@ -0,0 +1,92 @@
+use super::common::temp_dir;
+use crate::domain::workspace::Workspace;
+use crate::features::doctor::{self, Level, Probe};
+
+#[test]
+fn test_workspace_checks_report_lines() {
+    let dir = temp_dir("doctor");
+    std::fs::create_dir_all(dir.join("collections")).unwrap();
+    std::fs::write(
+        dir.join("collections/ok.hcl"),
+        "request \"a\" {\n  method = \"GET\"\n  url = \"http://x\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("collections/broken.hcl"),
+        "request \"a\" {\n  method = \"GET\"\n  url = \n}\n",
+    )
+    .unwrap();
+    // Parses, but the second request has no url
+    std::fs::write(
+        dir.join("collections/incomplete.hcl"),
+        "request \"a\" {\n  method = \"GET\"\n  url = \"http://x\"\n}\n\nrequest \"b\" {\n  method = \"GET\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("config.json"), "{\n  \"theme_index\": ,\n}").unwrap();
+
+    let checks = doctor::workspace_checks(&Workspace::at(&dir));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let find = |name: &str| checks.iter().find(|c| c.name == name).unwrap();
+    assert_eq!(find("collections/ok.hcl").level, Level::Ok);
+
+    let broken = find("collections/broken.hcl");
+    assert_eq!(broken.level, Level::Fail);
+    assert!(
+        broken.detail.starts_with("line 3, column 9"),
+        "{}",
+        broken.detail
+    );
+    assert!(broken.fix.is_some());
+
+    let incomplete = find("collections/incomplete.hcl");
+    assert_eq!(incomplete.level, Level::Fail);
+    assert!(
+        incomplete.detail.starts_with("line 6, request 'b'"),
+        "{}",
+        incomplete.detail
+    );
+
+    let config = find("config.json");
+    assert_eq!(config.level, Level::Fail);
+    assert!(config.detail.contains("line 2"), "{}", config.detail);
+}
+
+#[test]
+fn test_terminal_checks() {
+    let probe = Probe {
+        colorterm: Some("truecolor".to_string()),
+        term: Some("dumb".to_string()),
+        image_protocol: Some("Halfblocks".to_string()),
+        ..Default::default()
+    };
+    let checks = doctor::terminal_checks(&probe);
+    let levels: Vec<(&str, Level)> = checks.iter().map(|c| (c.name.as_str(), c.level)).collect();
+    assert_eq!(
+        levels,
+        vec![
+            ("Truecolor", Level::Ok),
+            ("TERM", Level::Fail),
+            ("Image protocol", Level::Warn)
+        ]
+    );
+    assert_eq!(doctor::counts(&checks), (1, 1));
+}
+
+#[test]
+fn test_proxy_address() {
+    assert_eq!(
+        doctor::proxy_address("http://user:pw@proxy.local:3128/"),
+        Some("proxy.local:3128".to_string())
+    );
+    assert_eq!(
+        doctor::proxy_address("https://proxy.local"),
+        Some("proxy.local:443".to_string())
+    );
+    assert_eq!(
+        doctor::proxy_address("proxy.local"),
+        Some("proxy.local:80".to_string())
+    );
+    assert_eq!(doctor::proxy_address("http://"), None);
+    assert!(doctor::proxy_check(None).is_none());
+}
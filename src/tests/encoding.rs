@@ -0,0 +1,156 @@
+use crate::net::encoding::{self, ContentEncoding};
+use crate::net::http::NetworkEvent;
+use std::io::Write;
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn br(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    brotli::CompressorWriter::new(&mut out, 4096, 5, 22)
+        .write_all(data)
+        .unwrap();
+    out
+}
+
+#[test]
+fn test_decode_gzip_deflate_and_stacked() {
+    let body = b"{\"items\": [1, 2, 3]}".repeat(20);
+    assert_eq!(encoding::decode("gzip", &gzip(&body)).unwrap(), body);
+    assert_eq!(encoding::decode("X-GZIP", &gzip(&body)).unwrap(), body);
+
+    let mut zlib = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::fast());
+    zlib.write_all(&body).unwrap();
+    assert_eq!(
+        encoding::decode("deflate", &zlib.finish().unwrap()).unwrap(),
+        body
+    );
+    let mut raw = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::fast());
+    raw.write_all(&body).unwrap();
+    assert_eq!(
+        encoding::decode("deflate", &raw.finish().unwrap()).unwrap(),
+        body
+    );
+
+    // Applied gzip then deflate: deflate comes off first
+    let mut twice = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::fast());
+    twice.write_all(&gzip(&body)).unwrap();
+    let twice = twice.finish().unwrap();
+    assert_eq!(encoding::decode("gzip, deflate", &twice).unwrap(), body);
+
+    assert!(
+        encoding::decode("zstd", b"xx")
+            .unwrap_err()
+            .contains("zstd")
+    );
+    assert!(encoding::decode("gzip", b"not gzip").is_err());
+}
+
+#[test]
+fn test_decode_brotli() {
+    let body = b"{\"items\": [1, 2, 3]}".repeat(20);
+    assert_eq!(encoding::decode("br", &br(&body)).unwrap(), body);
+    assert_eq!(encoding::decode("BR", &br(&body)).unwrap(), body);
+    // Applied gzip then brotli
+    assert_eq!(
+        encoding::decode("gzip, br", &br(&gzip(&body))).unwrap(),
+        body
+    );
+    assert!(encoding::decode("br", b"not brotli at all").is_err());
+    assert!(encoding::ACCEPT.contains("br"));
+}
+
+#[test]
+fn test_decode_body_keeps_what_came() {
+    let headers = [("Content-Encoding".to_string(), "gzip".to_string())].into();
+    let (body, found) = encoding::decode_body(&headers, gzip(b"hello"));
+    assert_eq!(body, b"hello");
+    let found = found.unwrap();
+    assert_eq!(found.raw, gzip(b"hello"));
+    assert!(found.error.is_none());
+
+    let headers = [("content-encoding".to_string(), "zstd".to_string())].into();
+    let (body, found) = encoding::decode_body(&headers, b"\x0b\x02".to_vec());
+    assert_eq!(body, b"\x0b\x02");
+    assert!(found.unwrap().error.is_some());
+
+    let (body, found) = encoding::decode_body(&headers, Vec::new());
+    assert!(body.is_empty() && found.is_none());
+    let (_, found) = encoding::decode_body(&Default::default(), b"plain".to_vec());
+    assert!(found.is_none());
+}
+
+#[tokio::test]
+async fn test_network_decodes_and_keeps_the_wire_bytes() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let compressed = gzip(b"{\"ok\":true}");
+    let reply = compressed.clone();
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = vec![0; 4096];
+        let n = socket.read(&mut request).await.unwrap();
+        let request = String::from_utf8_lossy(&request[..n]).to_lowercase();
+        let mut head = format!(
+            "HTTP/1.1 200 OK\r\ncontent-encoding: gzip\r\ncontent-length: {}\r\n\r\n",
+            reply.len()
+        )
+        .into_bytes();
+        head.extend_from_slice(&reply);
+        socket.write_all(&head).await.unwrap();
+        request
+    });
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let (reply_tx, mut reply_rx) = tokio::sync::mpsc::channel(4);
+    tokio::spawn(crate::net::http::handle_network(rx, reply_tx));
+    tx.send(NetworkEvent::RunRequest {
+        url: format!("http://127.0.0.1:{}/", port),
+        method: "GET".to_string(),
+        headers: Default::default(),
+        body: None,
+        form_data: None,
+        auth: None,
+        timeout_ms: Some(5000),
+        phase_timeouts: Default::default(),
+        ssl_verify: true,
+        ssl_ca_cert: None,
+        ssl_client_cert: None,
+        ssl_client_key: None,
+        proxy_url: None,
+        proxy_auth: None,
+        no_proxy: None,
+        pool: Default::default(),
+        resolve: Vec::new(),
+        request_id: String::new(),
+    })
+    .await
+    .unwrap();
+
+    let mut event = reply_rx.recv().await;
+    while let Some(NetworkEvent::Sent(..)) = event {
+        event = reply_rx.recv().await;
+    }
+    match event {
+        Some(NetworkEvent::GotResponse(_, body, .., found)) => {
+            assert_eq!(body, b"{\"ok\":true}");
+            assert_eq!(
+                found,
+                Some(ContentEncoding {
+                    encoding: "gzip".to_string(),
+                    raw: compressed,
+                    error: None,
+                })
+            );
+        }
+        Some(NetworkEvent::Failed { error, .. }) => panic!("request failed: {}", error),
+        _ => panic!("no response"),
+    }
+    let request = server.await.unwrap();
+    assert!(request.contains("accept-encoding: gzip, deflate, br\r\n"));
+}
@@ -0,0 +1,152 @@
+use super::common::temp_dir;
+use crate::app::{App, PassphrasePurpose};
+use crate::domain::workspace::Workspace;
+use crate::features::vault::{self, Vault};
+use std::collections::HashMap;
+
+// Far below DEFAULT_ITERATIONS, to keep the tests quick
+const ITERATIONS: u32 = 1_000;
+
+#[test]
+fn test_vault_round_trips() {
+    let (settings, vault) = vault::setup_passphrase("correct horse", ITERATIONS).unwrap();
+    assert!(settings.enabled);
+
+    let sealed = vault.encrypt_file("[{\"url\":\"http://x\"}]").unwrap();
+    assert!(vault::is_encrypted(&sealed));
+    assert!(!sealed.contains("http://x"));
+    assert_eq!(
+        vault.decrypt_file(&sealed).unwrap(),
+        "[{\"url\":\"http://x\"}]"
+    );
+    // Files written before encryption was turned on still load
+    assert_eq!(vault.decrypt_file("[]").unwrap(), "[]");
+
+    let secret = vault.encrypt_secret("s3cr3t").unwrap();
+    assert!(secret.starts_with(vault::SECRET_PREFIX));
+    assert_eq!(vault.decrypt_secret(&secret).unwrap(), "s3cr3t");
+    assert_eq!(vault.decrypt_secret("plain").unwrap(), "plain");
+
+    let again = vault::unlock_with_passphrase(&settings, "correct horse").unwrap();
+    assert_eq!(
+        again.decrypt_file(&sealed).unwrap(),
+        "[{\"url\":\"http://x\"}]"
+    );
+    assert!(vault::unlock_with_passphrase(&settings, "wrong horse").is_err());
+
+    // Another key can't read it, and tampering is caught
+    let other = Vault::from_passphrase("correct horse", b"other salt", ITERATIONS).unwrap();
+    assert!(other.decrypt_file(&sealed).is_err());
+    let mut tampered = sealed.trim_end().to_string();
+    tampered.pop();
+    tampered.push(if sealed.trim_end().ends_with('A') {
+        'B'
+    } else {
+        'A'
+    });
+    assert!(vault.decrypt_file(&tampered).is_err());
+
+    assert!(vault::setup_passphrase("short", ITERATIONS).is_err());
+}
+
+#[test]
+fn test_encrypted_workspace_locks_until_unlocked() {
+    let dir = temp_dir("encryption");
+
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    let (settings, key) = vault::setup_passphrase("correct horse", ITERATIONS).unwrap();
+    let token = key.encrypt_secret("s3cr3t").unwrap();
+    app.enable_encryption(settings, key);
+    app.add_history(
+        "GET".to_string(),
+        "http://private/orders".to_string(),
+        12,
+        200,
+        None,
+        HashMap::new(),
+        None,
+        false,
+    );
+    app.add_cookies("http://private/login", vec!["sid=1; Path=/".to_string()]);
+    app.record_command("secret s3cr3t");
+    app.record_command("send");
+
+    let history = std::fs::read_to_string(dir.join("history.json")).unwrap();
+    assert!(vault::is_encrypted(&history));
+    assert!(!history.contains("private"));
+    assert_eq!(app.command_history, vec!["send"]);
+    std::fs::write(
+        dir.join("environments.hcl"),
+        format!("env \"prod\" {{\n  token = \"{}\"\n}}\n", token),
+    )
+    .unwrap();
+
+    // Next start: asked for the passphrase, nothing loaded until then
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    assert!(app.passphrase_prompt.as_ref().map(|p| &p.purpose) == Some(&PassphrasePurpose::Unlock));
+    assert!(app.request_history.is_empty());
+    assert!(app.cookie_jar.is_empty());
+
+    assert!(!app.unlock("wrong horse"));
+    assert!(app.unlock("correct horse"));
+    assert_eq!(app.request_history[0].url, "http://private/orders");
+    assert_eq!(app.cookie_jar["private"], vec!["sid=1"]);
+    let prod = app.environments.iter().find(|e| e.name == "prod").unwrap();
+    assert_eq!(prod.variables["token"], "s3cr3t");
+
+    app.encrypt_command("off");
+    let history = std::fs::read_to_string(dir.join("history.json")).unwrap();
+    assert!(!vault::is_encrypted(&history));
+    assert!(history.contains("http://private/orders"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_cli_decrypts_environment_secrets() {
+    use crate::features::cli::{self, EnvArgs};
+
+    let (settings, vault) = vault::setup_passphrase("correct horse", ITERATIONS).unwrap();
+    let secret = vault.encrypt_secret("s3cr3t").unwrap();
+    let mut vars = HashMap::from([
+        ("token".to_string(), secret.clone()),
+        ("base".to_string(), "http://api".to_string()),
+    ]);
+    let missing = cli::decrypt_secrets(&mut vars.clone(), &settings, "acct", None).unwrap_err();
+    assert!(missing.contains(vault::PASSPHRASE_ENV), "{}", missing);
+    assert!(
+        cli::decrypt_secrets(
+            &mut vars.clone(),
+            &settings,
+            "acct",
+            Some("wrong horse".to_string())
+        )
+        .is_err()
+    );
+    cli::decrypt_secrets(
+        &mut vars,
+        &settings,
+        "acct",
+        Some("correct horse".to_string()),
+    )
+    .unwrap();
+    assert_eq!(vars["token"], "s3cr3t");
+    assert_eq!(vars["base"], "http://api");
+
+    // Encryption off in the workspace: refused rather than sent as ciphertext
+    let dir = temp_dir("cli_secrets");
+    std::fs::write(
+        dir.join("environments.hcl"),
+        format!("env \"prod\" {{\n  token = \"{}\"\n}}\n", secret),
+    )
+    .unwrap();
+    let args = EnvArgs {
+        env: Some("prod".to_string()),
+        ..Default::default()
+    };
+    let err = args.resolve(&Workspace::at(&dir)).unwrap_err();
+    assert!(err.contains("encrypted"), "{}", err);
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -1,3 +1,4 @@
+use super::common::temp_dir;
 use crate::app::App;
 
 fn create_app_with_env(vars: Vec<(&str, &str)>) -> App {
@@ -8,7 +9,7 @@ fn create_app_with_env(vars: Vec<(&str, &str)>) -> App {
         app.environments
             .push(crate::domain::environment::Environment {
                 name: "Test".to_string(),
-                variables: std::collections::HashMap::new(),
+                ..Default::default()
             });
     }
 
@@ -77,3 +78,209 @@ fn test_partial_match_should_not_substitute() {
     // "{{base}}" matches exactly "{{base}}".
     assert_eq!(processed, "BASIC vs FULL");
 }
+
+#[test]
+fn test_resolve_table_pins_hosts() {
+    use crate::domain::environment::Environment;
+
+    let dir = temp_dir("resolve");
+    let path = dir.join("environments.hcl");
+    std::fs::write(
+        &path,
+        r#"env "staging" {
+  base_url = "https://api.example.com"
+  resolve = {
+    "api.example.com" = "10.0.0.5"
+  }
+}
+"#,
+    )
+    .unwrap();
+    let envs = Environment::load_from_file(&path.to_string_lossy()).unwrap();
+    assert_eq!(envs[1].variables.len(), 1);
+    assert_eq!(envs[1].resolve["api.example.com"], "10.0.0.5");
+
+    // Written back (e.g. by a bundle import) with the table intact
+    let content = crate::features::bundle::environments_hcl(&envs[1..]).unwrap();
+    std::fs::write(&path, content).unwrap();
+    let again = Environment::load_from_file(&path.to_string_lossy()).unwrap();
+    assert_eq!(again[1].resolve, envs[1].resolve);
+
+    let mut app = App::new();
+    app.environments = again;
+    app.selected_env_index = 1;
+    app.active_tab_mut().url = "{{base_url}}/users".to_string();
+    assert_eq!(
+        app.host_override(),
+        Some(("api.example.com".to_string(), 443, "10.0.0.5".to_string()))
+    );
+    let curl = app.generate_curl_command();
+    assert!(curl.contains("--resolve") && curl.contains("api.example.com:443:10.0.0.5"));
+    app.active_tab_mut().url = "https://other.example.com".to_string();
+    assert_eq!(app.host_override(), None);
+
+    std::fs::write(&path, "env \"x\" {\n  resolve = \"10.0.0.5\"\n}\n").unwrap();
+    assert!(Environment::load_from_file(&path.to_string_lossy()).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_relative_path_follows_base_url() {
+    let mut app = create_app_with_env(vec![("base_url", "https://dev.api.test/v1/"), ("id", "7")]);
+    app.environments
+        .push(crate::domain::environment::Environment {
+            name: "prod".to_string(),
+            variables: [("base_url".to_string(), "https://api.test".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        });
+    app.active_tab_mut().url = "/users/{{id}}".to_string();
+
+    assert_eq!(app.process_url(), "https://dev.api.test/v1/users/7");
+    assert_eq!(
+        app.relative_url_preview(),
+        Some(Ok("https://dev.api.test/v1/users/7".to_string()))
+    );
+
+    app.selected_env_index = app.environments.len() - 1;
+    assert_eq!(app.process_url(), "https://api.test/users/{{id}}");
+    // Absolute URLs don't get a preview
+    app.active_tab_mut().url = "https://other.test/users".to_string();
+    assert_eq!(app.relative_url_preview(), None);
+    assert_eq!(app.process_url(), "https://other.test/users");
+}
+
+#[test]
+fn test_relative_path_without_base_url() {
+    let mut app = create_app_with_env(vec![]);
+    app.environments[app.selected_env_index]
+        .variables
+        .remove("base_url");
+    app.active_tab_mut().url = "/health".to_string();
+    assert_eq!(app.process_url(), "/health");
+    assert!(
+        app.relative_url_preview()
+            .unwrap()
+            .unwrap_err()
+            .contains("no base_url")
+    );
+}
+
+#[test]
+fn test_relative_path_params_keep_placeholders() {
+    let mut app = create_app_with_env(vec![]);
+    app.active_tab_mut().url = "/users/{{id}}?page=2&q=a b".to_string();
+    app.sync_url_to_params();
+    assert_eq!(
+        app.active_tab().params,
+        vec![
+            ("page".to_string(), "2".to_string()),
+            ("q".to_string(), "a b".to_string())
+        ]
+    );
+
+    app.active_tab_mut()
+        .params
+        .push(("sort".to_string(), "name".to_string()));
+    app.sync_params_to_url();
+    assert_eq!(app.active_tab().url, "/users/{{id}}?page=2&q=a+b&sort=name");
+
+    app.active_tab_mut().params.clear();
+    app.sync_params_to_url();
+    assert_eq!(app.active_tab().url, "/users/{{id}}");
+}
+
+#[test]
+fn test_default_headers_from_workspace_and_environment() {
+    use crate::app::DefaultHeaderState;
+    use crate::domain::environment::Environment;
+
+    let dir = temp_dir("default_headers");
+    let path = dir.join("environments.hcl");
+    std::fs::write(
+        &path,
+        r#"env "staging" {
+  token = "abc"
+  headers = {
+    accept = "application/vnd.api+json"
+    "X-Env" = "staging"
+  }
+}
+"#,
+    )
+    .unwrap();
+    let envs = Environment::load_from_file(&path.to_string_lossy()).unwrap();
+    assert_eq!(envs[1].variables.len(), 1);
+    assert_eq!(envs[1].headers["X-Env"], "staging");
+    let content = crate::features::bundle::environments_hcl(&envs[1..]).unwrap();
+    std::fs::write(&path, content).unwrap();
+    let again = Environment::load_from_file(&path.to_string_lossy()).unwrap();
+    assert_eq!(again[1].headers, envs[1].headers);
+
+    let mut app = App::new();
+    app.environments = again;
+    app.selected_env_index = 1;
+    app.default_headers = [
+        ("User-Agent", "PostDad"),
+        ("Accept", "*/*"),
+        ("X-Trace", "{{token}}"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    app.active_tab_mut().url = "https://api.test/".to_string();
+    app.active_tab_mut()
+        .request_headers
+        .insert("user-agent".to_string(), "mine".to_string());
+    app.header_command("off x-trace");
+
+    let defaults: Vec<(String, String, DefaultHeaderState)> = app
+        .default_headers()
+        .into_iter()
+        .map(|d| (d.name, d.source, d.state))
+        .collect();
+    assert_eq!(
+        defaults,
+        vec![
+            (
+                "accept".to_string(),
+                "staging".to_string(),
+                DefaultHeaderState::Sent
+            ),
+            (
+                "User-Agent".to_string(),
+                "workspace".to_string(),
+                DefaultHeaderState::Overridden
+            ),
+            (
+                "X-Env".to_string(),
+                "staging".to_string(),
+                DefaultHeaderState::Sent
+            ),
+            (
+                "X-Trace".to_string(),
+                "workspace".to_string(),
+                DefaultHeaderState::Disabled
+            ),
+        ]
+    );
+
+    let (request, _) = app.outgoing_request();
+    assert_eq!(request.headers["accept"], "application/vnd.api+json");
+    assert_eq!(request.headers["user-agent"], "mine");
+    assert!(!request.headers.contains_key("User-Agent"));
+    assert!(!request.headers.contains_key("X-Trace"));
+    // Kept with the request when it's saved
+    assert_eq!(
+        app.active_tab()
+            .to_request_config()
+            .disabled_default_headers,
+        vec!["X-Trace".to_string()]
+    );
+
+    app.header_command("on X-Trace");
+    let (request, _) = app.outgoing_request();
+    assert_eq!(request.headers["X-Trace"], "abc");
+    let _ = std::fs::remove_dir_all(&dir);
+}
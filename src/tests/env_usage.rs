@@ -0,0 +1,132 @@
+use super::common::press;
+use crate::app::App;
+use crate::domain::collection::Collection;
+use crate::domain::environment::Environment;
+use crate::features::env_usage::{Row, report, request_references};
+use crossterm::event::KeyCode;
+use std::collections::{BTreeMap, HashMap};
+
+const SHOP: &str = r#"
+variables = {
+  shop_id = "42"
+}
+
+request "Login" {
+  method = "POST"
+  url    = "{{base_url}}/login"
+  body   = "{\"user\": \"{{user}}\", \"password\": \"{{password}}\"}"
+  post_request_script = "set_var(\"session\", json_path(\"$.session\"));"
+}
+
+request "Orders" {
+  method = "GET"
+  url    = "{{base_url}}/shops/{{shop_id}}/orders"
+  headers = {
+    Authorization = "Bearer {{token}}"
+    Cookie        = "session={{session}}"
+  }
+  pre_request_script = "set_header(\"X-Trace\", get_var('trace_id'));"
+}
+"#;
+
+const ADMIN: &str = r#"
+request "Stats" {
+  method = "GET"
+  url    = "{{admin_url}}/stats?key={{api_key}}"
+}
+"#;
+
+fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn collections() -> Vec<Collection> {
+    vec![
+        Collection::from_hcl("shop", SHOP).unwrap(),
+        Collection::from_hcl("admin", ADMIN).unwrap(),
+    ]
+}
+
+#[test]
+fn test_places_a_request_uses_variables() {
+    let shop = Collection::from_hcl("shop", SHOP).unwrap();
+    let found = request_references(&shop.requests["Orders"]);
+    assert_eq!(found["base_url"], vec!["url"]);
+    assert_eq!(found["token"], vec!["headers"]);
+    assert_eq!(found["trace_id"], vec!["pre-request script"]);
+    assert!(!found.contains_key("X-Trace"));
+}
+
+#[test]
+fn test_report() {
+    let env = vars(&[
+        ("base_url", "https://shop.test"),
+        ("user", "ann"),
+        ("unused", "x"),
+    ]);
+    let globals = BTreeMap::from([("password".to_string(), "pw".to_string())]);
+    let report = report("staging", &env, &globals, &collections());
+
+    let names: Vec<&str> = report.variables.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(names, vec!["base_url", "unused", "user"]);
+    let base = &report.variables[0];
+    assert_eq!(base.value.as_deref(), Some("https://shop.test"));
+    let paths: Vec<String> = base.references.iter().map(|r| r.path()).collect();
+    assert_eq!(paths, vec!["shop/Login", "shop/Orders"]);
+    assert!(report.variables[1].references.is_empty());
+
+    // Globals, collection variables and what scripts set aren't undefined
+    let undefined: Vec<&str> = report.undefined.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(undefined, vec!["admin_url", "api_key", "token", "trace_id"]);
+    assert_eq!(report.undefined[0].references[0].path(), "admin/Stats");
+
+    let rows = report.rows();
+    assert_eq!(rows[0], Row::Undefined(0));
+    assert_eq!(rows[1], Row::UndefinedReference(0, 0));
+    assert_eq!(
+        report.target(Row::Variable(0)).map(|r| r.request.as_str()),
+        Some("Login")
+    );
+    assert_eq!(report.target(Row::Variable(1)), None);
+}
+
+#[test]
+fn test_jump_to_a_referencing_request() {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.environments = vec![Environment {
+        name: "dev".to_string(),
+        variables: vars(&[("base_url", "https://shop.test"), ("user", "ann")]),
+        ..Default::default()
+    }];
+    app.selected_env_index = 0;
+    app.collections = collections();
+
+    app.vars_command("env");
+    let view = app.env_usage.as_ref().unwrap();
+    assert_eq!(view.report.environment, "dev");
+    // admin_url, api_key, password, token and trace_id are undefined, one request each
+    let first_variable = view
+        .report
+        .rows()
+        .iter()
+        .position(|r| *r == Row::Variable(0))
+        .unwrap();
+    assert_eq!(first_variable, 10);
+
+    for _ in 0..first_variable + 2 {
+        press(&mut app, KeyCode::Char('j'));
+    }
+    press(&mut app, KeyCode::Enter);
+    assert!(app.env_usage.is_none());
+    assert_eq!(app.active_tab().collection.as_deref(), Some("shop"));
+    assert_eq!(app.active_tab().request_name.as_deref(), Some("Orders"));
+
+    app.vars_command("oops");
+    assert!(app.env_usage.is_none());
+    app.vars_command("");
+    assert!(app.variables_inspector.is_some());
+}
@@ -0,0 +1,95 @@
+use crate::events::{AppEvent, BATCH, EventSources};
+use crate::net::http::NetworkEvent;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use tokio::sync::mpsc;
+
+/// Sources whose input and network ends the test holds, the rest already closed
+fn sources() -> (
+    EventSources,
+    mpsc::Sender<Event>,
+    mpsc::Sender<NetworkEvent>,
+) {
+    let (input_tx, input) = mpsc::channel(8);
+    let (network_tx, network) = mpsc::channel(256);
+    let sources = EventSources {
+        input,
+        tick: EventSources::tick(),
+        network,
+        ws: mpsc::channel(1).1,
+        runner: mpsc::channel(1).1,
+        stress: mpsc::channel(1).1,
+        fuzz: mpsc::channel(1).1,
+        batch: mpsc::channel(1).1,
+        paginate: mpsc::channel(1).1,
+        sync: mpsc::channel(1).1,
+        sentinel: mpsc::channel(1).1,
+        dashboard: mpsc::channel(1).1,
+    };
+    (sources, input_tx, network_tx)
+}
+
+fn key(c: char) -> Event {
+    Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+}
+
+#[tokio::test]
+async fn test_input_goes_first_and_bursts_are_batched() {
+    let (mut sources, input_tx, network_tx) = sources();
+    network_tx
+        .send(NetworkEvent::Sent(String::new(), vec![1]))
+        .await
+        .unwrap();
+    network_tx
+        .send(NetworkEvent::Sent(String::new(), vec![2]))
+        .await
+        .unwrap();
+    input_tx.send(key('j')).await.unwrap();
+
+    assert!(matches!(
+        sources.next().await,
+        Some(AppEvent::Input(Event::Key(_)))
+    ));
+    let ready = sources.ready();
+    assert_eq!(ready.len(), 2);
+    assert!(
+        ready
+            .iter()
+            .all(|e| matches!(e, AppEvent::Network(n) if matches!(**n, NetworkEvent::Sent(..))))
+    );
+    // The interval's first tick is due right away, it's left for `next`
+    assert!(matches!(sources.next().await, Some(AppEvent::Tick)));
+    assert!(sources.ready().is_empty());
+}
+
+#[tokio::test]
+async fn test_ready_stops_at_batch() {
+    let (mut sources, _input_tx, network_tx) = sources();
+    for i in 0..BATCH + 10 {
+        network_tx
+            .send(NetworkEvent::Sent(String::new(), vec![i as u8]))
+            .await
+            .unwrap();
+    }
+    let mut seen = Vec::new();
+    while seen.len() < BATCH + 10 {
+        let ready = sources.ready();
+        assert!(!ready.is_empty() && ready.len() <= BATCH);
+        for event in ready {
+            if let AppEvent::Network(event) = event
+                && let NetworkEvent::Sent(_, bytes) = *event
+            {
+                seen.push(bytes[0] as usize);
+            }
+        }
+        // What the loop's redraw does: back to the runtime between batches
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(seen, (0..BATCH + 10).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_closed_input_ends_the_loop() {
+    let (mut sources, input_tx, _network_tx) = sources();
+    drop(input_tx);
+    assert!(sources.next().await.is_none());
+}
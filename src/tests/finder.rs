@@ -0,0 +1,171 @@
+use super::common::press_with;
+use crate::app::{App, InputMode, RequestLog, RequestTab};
+use crate::domain::collection::{Collection, RequestConfig};
+use crate::domain::environment::Environment;
+use crate::features::finder::{self, ItemKind};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+
+fn request(method: &str, url: &str) -> RequestConfig {
+    RequestConfig {
+        url: url.to_string(),
+        method: method.to_string(),
+        body: None,
+        headers: None,
+        extract: None,
+        body_type: None,
+        form_data: None,
+        graphql_query: None,
+        graphql_variables: None,
+        expected_status: None,
+        timeout_ms: None,
+        connect_timeout_ms: None,
+        read_timeout_ms: None,
+        pre_request_script: None,
+        post_request_script: None,
+        stress_stages: None,
+        disabled_default_headers: Vec::new(),
+        disabled_headers: HashMap::new(),
+        path_params: HashMap::new(),
+        description: None,
+        variables: HashMap::new(),
+        grpc_service: None,
+        grpc_proto_path: None,
+        tags: Vec::new(),
+        treat_as: None,
+        paginate: None,
+    }
+}
+
+fn test_app() -> App {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.collections = vec![Collection {
+        name: "users".to_string(),
+        requests: HashMap::from([
+            (
+                "Create user".to_string(),
+                request("POST", "https://api.example.com/users"),
+            ),
+            (
+                "Delete user".to_string(),
+                request("DELETE", "https://api.example.com/users/1"),
+            ),
+        ]),
+        ws_scenarios: HashMap::new(),
+        description: None,
+        variables: HashMap::new(),
+        snapshots: Default::default(),
+    }];
+    app.request_history = vec![RequestLog {
+        method: "GET".to_string(),
+        url: "https://api.example.com/orders".to_string(),
+        status: 200,
+        latency: 42,
+        body: Some("{\"id\":7}".to_string()),
+        headers: HashMap::new(),
+        response_bytes: None,
+        is_binary: false,
+        timestamp: 0,
+        pinned: false,
+        request: None,
+        size: 0,
+        tests: Vec::new(),
+    }];
+    app.environments = vec![
+        Environment {
+            name: "None".to_string(),
+            variables: HashMap::new(),
+            ..Default::default()
+        },
+        Environment {
+            name: "staging".to_string(),
+            variables: HashMap::new(),
+            ..Default::default()
+        },
+    ];
+    let mut second = RequestTab::new();
+    second.name = "Billing".to_string();
+    app.tabs.push(second);
+    app
+}
+
+#[test]
+fn test_fuzzy_match_prefers_word_starts_and_runs() {
+    let (_, positions) = finder::fuzzy_match("cu", "users/Create user").unwrap();
+    assert_eq!(positions, vec![6, 13]);
+    assert!(finder::fuzzy_match("xyz", "users/Create user").is_none());
+    assert_eq!(finder::fuzzy_match("", "anything"), Some((0, Vec::new())));
+
+    let score = |q, t| finder::fuzzy_match(q, t).unwrap().0;
+    assert!(score("user", "users/List") > score("user", "billing/get-a-useful-report"));
+    assert!(score("gu", "get-user") > score("gu", "signup"));
+}
+
+#[test]
+fn test_search_ranks_and_falls_back_to_detail() {
+    let app = test_app();
+    let items = app.finder_items();
+    let kinds: Vec<ItemKind> = items.iter().map(|i| i.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ItemKind::Tab,
+            ItemKind::Tab,
+            ItemKind::Request,
+            ItemKind::Request,
+            ItemKind::History,
+            ItemKind::Environment,
+            ItemKind::Environment,
+        ]
+    );
+
+    let found = finder::search(&items, "deluser");
+    assert_eq!(found[0].item.label, "users/Delete user");
+
+    let found = finder::search(&items, "orders");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].item.kind, ItemKind::History);
+
+    // "POST" is only in the request's detail: still found, nothing highlighted
+    let found = finder::search(&items, "post");
+    assert_eq!(found[0].item.label, "users/Create user");
+    assert!(found[0].positions.is_empty());
+}
+
+#[test]
+fn test_finder_opens_the_pick() {
+    let mut app = test_app();
+
+    press_with(&mut app, KeyCode::Char('o'), KeyModifiers::CONTROL);
+    assert!(app.show_finder);
+    assert_eq!(app.active_tab().input_mode, InputMode::Finder);
+    for c in "create".chars() {
+        press_with(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    press_with(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert!(!app.show_finder);
+    assert_eq!(app.active_tab().url, "https://api.example.com/users");
+    assert_eq!(app.active_tab().method, "POST");
+
+    app.open_finder();
+    app.finder_query = "billing".to_string();
+    app.confirm_finder();
+    assert_eq!(app.active_tab, 1);
+
+    app.open_finder();
+    app.finder_query = "stag".to_string();
+    app.confirm_finder();
+    assert_eq!(app.selected_env_index, 1);
+
+    app.open_finder();
+    app.finder_query = "orders".to_string();
+    app.confirm_finder();
+    assert_eq!(app.active_tab().status_code, Some(200));
+    assert!(app.active_tab().response_json.is_some());
+
+    press_with(&mut app, KeyCode::Char('o'), KeyModifiers::CONTROL);
+    press_with(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+    assert!(!app.show_finder);
+    assert_eq!(app.active_tab().input_mode, InputMode::Normal);
+}
@@ -0,0 +1,88 @@
+use crate::app::{App, BodyType};
+use crate::features::format::{self, Kind};
+
+#[test]
+fn test_json_keeps_order_numbers_and_placeholders() {
+    let body = r#"{"z": 1.50, "id": {{user_id}}, "tags": [], "nested": {"a": "x, y: {z}", "b": [1,2]}, "e": {}}"#;
+    let pretty = format::pretty_json(body).unwrap();
+    assert_eq!(
+        pretty,
+        r#"{
+  "z": 1.50,
+  "id": {{user_id}},
+  "tags": [],
+  "nested": {
+    "a": "x, y: {z}",
+    "b": [
+      1,
+      2
+    ]
+  },
+  "e": {}
+}"#
+    );
+    assert_eq!(
+        format::minify_json(&pretty).unwrap(),
+        r#"{"z":1.50,"id":{{user_id}},"tags":[],"nested":{"a":"x, y: {z}","b":[1,2]},"e":{}}"#
+    );
+    // The same layout serde_json gives valid JSON
+    let valid = r#"{"a":[1,{"b":"q\"uote"}],"c":null}"#;
+    let value: serde_json::Value = serde_json::from_str(valid).unwrap();
+    assert_eq!(
+        format::pretty_json(valid).unwrap(),
+        serde_json::to_string_pretty(&value).unwrap()
+    );
+
+    assert!(format::pretty_json(r#"{"a": [1}"#).is_err());
+    assert!(format::pretty_json(r#"{"a": "open"#).is_err());
+    assert!(format::pretty("plain text").is_err());
+}
+
+#[test]
+fn test_xml_indents_and_minifies() {
+    let xml = "<?xml version=\"1.0\"?><order id=\"7\"><!-- note --><item>Tea</item><empty></empty><br/>\n  <qty>2</qty></order>";
+    let (kind, pretty) = format::pretty(xml).unwrap();
+    assert_eq!(kind, Kind::Xml);
+    assert_eq!(
+        pretty,
+        "<?xml version=\"1.0\"?>\n<order id=\"7\">\n  <!-- note -->\n  <item>Tea</item>\n  <empty></empty>\n  <br/>\n  <qty>2</qty>\n</order>"
+    );
+    assert_eq!(
+        format::minify_xml(&pretty).unwrap(),
+        "<?xml version=\"1.0\"?><order id=\"7\"><!-- note --><item>Tea</item><empty></empty><br/><qty>2</qty></order>"
+    );
+    assert!(format::pretty_xml("<a><b").is_err());
+}
+
+#[test]
+fn test_format_body_in_place_and_raw_response() {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.active_tab_mut().body_type = BodyType::Raw;
+    app.active_tab_mut().request_body = "{\"a\":[1,2]}".to_string();
+    app.format_body(false);
+    assert_eq!(
+        app.active_tab().request_body,
+        "{\n  \"a\": [\n    1,\n    2\n  ]\n}"
+    );
+    assert_eq!(app.active_tab().selected_tab, 2);
+    app.format_body(true);
+    assert_eq!(app.active_tab().request_body, "{\"a\":[1,2]}");
+
+    // Not JSON: left alone
+    app.active_tab_mut().request_body = "name=ann".to_string();
+    app.format_body(false);
+    assert_eq!(app.active_tab().request_body, "name=ann");
+
+    let tab = app.active_tab_mut();
+    tab.response = Some("<a><b>1</b></a>".to_string());
+    tab.response_headers
+        .insert("content-type".to_string(), "application/xml".to_string());
+    assert_eq!(tab.display_text().unwrap(), "<a>\n  <b>1</b>\n</a>");
+    app.toggle_response_raw();
+    assert_eq!(app.active_tab().display_text().unwrap(), "<a><b>1</b></a>");
+    assert_eq!(
+        app.active_tab().searchable_text().unwrap(),
+        "<a><b>1</b></a>"
+    );
+}
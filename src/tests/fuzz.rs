@@ -0,0 +1,195 @@
+use crate::app::OutgoingRequest;
+use crate::features::fuzz::{self, Category, Flag, Outcome, Target};
+use crate::net::mock_server::MockRoute;
+use serde_json::Value;
+
+fn request(url: &str, headers: &[(&str, &str)], body: Option<&str>) -> OutgoingRequest {
+    OutgoingRequest {
+        method: "POST".to_string(),
+        url: url.to_string(),
+        headers: headers
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        body: body.map(str::to_string),
+        form_data: None,
+        auth: None,
+    }
+}
+
+fn outcome(status: Option<u16>, latency_ms: u64, shape: &str) -> Outcome {
+    Outcome {
+        status,
+        latency_ms,
+        shape: shape.to_string(),
+        flags: Vec::new(),
+    }
+}
+
+#[test]
+fn test_cases_cover_params_headers_and_body_fields() {
+    let base = request(
+        "http://api.test/users?page=1&sort=name",
+        &[("X-Api-Key", "k"), ("Cookie", "session=1")],
+        Some(r#"{"user": {"age": 30, "tags": ["a"]}, "active": true}"#),
+    );
+    let cases = fuzz::cases(&base);
+
+    let targets: Vec<String> = cases.iter().map(|c| c.target.label()).collect();
+    for expected in [
+        "?page",
+        "?sort",
+        "X-Api-Key:",
+        "body.user.age",
+        "body.user.tags.0",
+        "body.active",
+    ] {
+        assert!(targets.iter().any(|t| t == expected), "{}", expected);
+    }
+    assert!(
+        !cases
+            .iter()
+            .any(|c| c.target == Target::Header("Cookie".to_string()))
+    );
+
+    // Only the one field changes, the rest of the request stays
+    let age_null = cases
+        .iter()
+        .find(|c| c.target == Target::Body("/user/age".to_string()) && c.payload == "null")
+        .unwrap();
+    assert_eq!(age_null.category, Category::TypeConfusion);
+    let body: Value = serde_json::from_str(age_null.request.body.as_deref().unwrap()).unwrap();
+    assert_eq!(body["user"]["age"], Value::Null);
+    assert_eq!(body["user"]["tags"][0], "a");
+    assert_eq!(age_null.request.url, base.url);
+    assert!(
+        cases
+            .iter()
+            .any(|c| c.target == Target::Body("/user/age".to_string()) && c.payload == "\"30\"")
+    );
+
+    let page = cases
+        .iter()
+        .find(|c| c.target == Target::Param("page".to_string()) && c.payload == "-1")
+        .unwrap();
+    assert_eq!(page.request.url, "http://api.test/users?page=-1&sort=name");
+    // Header values that can't go on the wire aren't tried
+    assert!(
+        cases
+            .iter()
+            .filter(|c| matches!(c.target, Target::Header(_)))
+            .all(|c| !c.payload.contains('\0'))
+    );
+    assert!(fuzz::cases(&request("http://api.test/", &[], Some("plain"))).is_empty());
+}
+
+#[test]
+fn test_shapes_flags_and_groups() {
+    assert_eq!(
+        fuzz::shape(r#"{"error": "bad", "code": 400}"#),
+        "{code:number,error:string}"
+    );
+    assert_eq!(fuzz::shape(r#"[{"id": 1}]"#), "[object]");
+    assert_eq!(fuzz::shape("  "), "empty");
+    assert_eq!(fuzz::shape("<html>oops</html>"), "markup");
+    assert_eq!(fuzz::shape("Internal error"), "text");
+
+    let base = request("http://api.test/?q=hi", &[], None);
+    let cases = fuzz::cases(&base);
+    let injection = cases
+        .iter()
+        .find(|c| c.category == Category::Injection && c.payload.starts_with("<script>"))
+        .unwrap();
+    let baseline = outcome(Some(200), 20, "{q:string}");
+
+    let reflected = outcome(Some(200), 30, "text");
+    assert_eq!(
+        fuzz::flags(
+            injection,
+            &reflected,
+            "<script>alert(1)</script>",
+            &baseline
+        ),
+        vec![Flag::NewShape, Flag::Reflected]
+    );
+    let crashed = outcome(Some(500), 2000, "text");
+    assert_eq!(
+        fuzz::flags(injection, &crashed, "boom", &baseline),
+        vec![Flag::ServerError, Flag::Slow]
+    );
+    let rejected = outcome(Some(400), 25, "{error:string}");
+    assert!(fuzz::flags(injection, &rejected, "{}", &baseline).is_empty());
+
+    let mut outcomes = vec![rejected.clone(), rejected.clone(), rejected];
+    let mut crashed = crashed;
+    crashed.flags = vec![Flag::ServerError];
+    outcomes.insert(1, crashed);
+    let groups = fuzz::group(&outcomes);
+    assert_eq!(groups.len(), 2);
+    // Flagged first, however few
+    assert_eq!(groups[0].status, Some(500));
+    assert_eq!(groups[0].cases, vec![1]);
+    assert_eq!(groups[0].flags, vec![Flag::ServerError]);
+    assert_eq!(groups[1].cases, vec![0, 2, 3]);
+}
+
+#[tokio::test]
+async fn test_run_reports_reflected_payloads() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let echo = MockRoute {
+        path: "/search".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: r#"{"q": "{{query.q}}"}"#.to_string(),
+        ..Default::default()
+    };
+    let server = crate::net::mock_server::start_mock_server(port, vec![echo]);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let mut base = request(&format!("http://127.0.0.1:{}/search?q=hi", port), &[], None);
+    base.method = "GET".to_string();
+    let cases = fuzz::cases(&base);
+    let total = cases.len();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(total + 1);
+    let (_stop_tx, stop_rx) = tokio::sync::mpsc::channel(1);
+    fuzz::run_fuzz(
+        fuzz::FuzzConfig {
+            base,
+            cases,
+            rate: 1000,
+            ssl_verify: true,
+            resolve: Vec::new(),
+        },
+        tx,
+        stop_rx,
+    )
+    .await;
+
+    let mut report = None;
+    while let Ok(event) = rx.try_recv() {
+        if let fuzz::FuzzEvent::Finished(finished) = event {
+            report = Some(finished);
+        }
+    }
+    let report = report.unwrap();
+    assert!(!report.stopped);
+    assert_eq!(report.outcomes.len(), total);
+    assert_eq!(report.baseline.status, Some(200));
+    assert_eq!(report.baseline.shape, "{q:string}");
+    assert!(
+        report
+            .groups
+            .iter()
+            .any(|g| g.flags.contains(&Flag::Reflected))
+    );
+    assert_eq!(
+        report.groups.iter().map(|g| g.cases.len()).sum::<usize>(),
+        total
+    );
+
+    server.handle.abort();
+}
@@ -0,0 +1,291 @@
+use super::common::temp_dir;
+use crate::app::{App, BodyType};
+use crate::domain::workspace::Workspace;
+use crate::net::grpc::{self, RpcMethod};
+use crate::net::http::NetworkEvent;
+
+const SERVICE: &str = r#"helloworld.Greeter is a service:
+service Greeter {
+  rpc SayHello ( .helloworld.HelloRequest ) returns ( .helloworld.HelloReply );
+  rpc Chat ( stream .helloworld.HelloRequest ) returns ( stream .helloworld.HelloReply );
+}
+"#;
+
+const MESSAGE: &str = r#"helloworld.HelloRequest is a message:
+message HelloRequest {
+  string name = 1;
+  int64 id = 2;
+  repeated string tags = 3;
+  map<string, int32> scores = 4;
+  .helloworld.HelloRequest.Mood mood = 5;
+  .google.protobuf.Timestamp at = 6;
+  .helloworld.Address address = 7;
+  oneof contact {
+    string email = 8;
+    string phone = 9;
+  }
+  enum Mood {
+    HAPPY = 0;
+    SAD = 1;
+  }
+  message Inner {
+    bool skipped = 1;
+  }
+}
+"#;
+
+#[test]
+fn test_parse_methods() {
+    let methods = grpc::parse_methods(SERVICE);
+    assert_eq!(
+        methods,
+        vec![
+            RpcMethod {
+                name: "SayHello".to_string(),
+                input: ".helloworld.HelloRequest".to_string(),
+                output: ".helloworld.HelloReply".to_string(),
+                client_streaming: false,
+                server_streaming: false,
+            },
+            RpcMethod {
+                name: "Chat".to_string(),
+                input: ".helloworld.HelloRequest".to_string(),
+                output: ".helloworld.HelloReply".to_string(),
+                client_streaming: true,
+                server_streaming: true,
+            },
+        ]
+    );
+    assert!(grpc::parse_methods("message Foo {\n}\n").is_empty());
+}
+
+#[test]
+fn test_skeleton_from_descriptor() {
+    let payload = grpc::skeleton(MESSAGE).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "name": "",
+            "id": "0",
+            "tags": [""],
+            "scores": {},
+            "mood": "HAPPY",
+            "at": "1970-01-01T00:00:00Z",
+            "address": {},
+            "email": ""
+        })
+    );
+}
+
+#[test]
+fn test_skeleton_prefers_template() {
+    let description = format!(
+        "{}\nMessage template:\n{{\n  \"name\": \"\",\n  \"address\": {{\"city\": \"\"}}\n}}\n",
+        MESSAGE
+    );
+    let value: serde_json::Value =
+        serde_json::from_str(&grpc::skeleton(&description).unwrap()).unwrap();
+    assert_eq!(value["address"]["city"], "");
+    assert!(value.get("tags").is_none());
+    assert_eq!(grpc::skeleton("nothing here"), None);
+}
+
+#[test]
+fn test_use_method_and_scaffold() {
+    let mut app = App::new();
+    app.show_splash = false;
+    let tab = app.active_tab_mut();
+    tab.grpc_service_to_describe = "helloworld.Greeter".to_string();
+    tab.grpc_methods = grpc::parse_methods(SERVICE);
+    tab.grpc_method_selected = 1;
+    tab.show_grpc_description_modal = true;
+
+    app.use_grpc_method();
+    let tab = app.active_tab();
+    assert_eq!(tab.grpc_service, "helloworld.Greeter/Chat");
+    assert_eq!(tab.grpc_target(), "helloworld.Greeter/Chat");
+    assert_eq!(tab.body_type, BodyType::Grpc);
+    assert!(!tab.show_grpc_description_modal);
+    assert_eq!(
+        tab.scaffold_grpc_message.as_deref(),
+        Some(".helloworld.HelloRequest")
+    );
+
+    app.apply_grpc_skeleton(".helloworld.HelloRequest", Ok(MESSAGE.to_string()));
+    assert!(
+        app.active_tab()
+            .request_body
+            .contains("\"mood\": \"HAPPY\"")
+    );
+
+    app.active_tab_mut().request_body = "{\"keep\": 1}".to_string();
+    app.apply_grpc_skeleton(".x.Y", Err("no grpcurl".to_string()));
+    assert_eq!(app.active_tab().request_body, "{\"keep\": 1}");
+}
+
+#[test]
+fn test_grpc_history_reloads_call() {
+    let dir = temp_dir("grpc_history");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    let tab = app.active_tab_mut();
+    tab.body_type = BodyType::Grpc;
+    tab.url = "localhost:50051".to_string();
+    tab.grpc_service = "helloworld.Greeter".to_string();
+    tab.grpc_method = "SayHello".to_string();
+    tab.grpc_proto_path = "protos/hello.proto".to_string();
+    tab.request_body = "{\"name\": \"ada\"}".to_string();
+
+    let config = app.active_tab().to_request_config();
+    assert_eq!(
+        config.grpc_service.as_deref(),
+        Some("helloworld.Greeter/SayHello")
+    );
+    assert_eq!(
+        config.grpc_proto_path.as_deref(),
+        Some("protos/hello.proto")
+    );
+    assert_eq!(config.body_type.as_deref(), Some("Grpc"));
+
+    app.add_history(
+        "GRPC".to_string(),
+        "localhost:50051 helloworld.Greeter/SayHello".to_string(),
+        12,
+        0,
+        Some("{\"message\": \"hi\"}".to_string()),
+        std::collections::HashMap::new(),
+        None,
+        false,
+    );
+
+    app.add_tab();
+    app.restore_history_entry(0);
+    let tab = app.active_tab();
+    assert_eq!(tab.body_type, BodyType::Grpc);
+    assert_eq!(tab.url, "localhost:50051");
+    assert_eq!(tab.grpc_target(), "helloworld.Greeter/SayHello");
+    assert_eq!(tab.grpc_proto_path, "protos/hello.proto");
+    assert_eq!(tab.request_body, "{\"name\": \"ada\"}");
+    assert_eq!(tab.response.as_deref(), Some("{\"message\": \"hi\"}"));
+
+    // HTTP requests don't carry the gRPC fields
+    app.active_tab_mut().body_type = BodyType::Raw;
+    assert_eq!(app.active_tab().to_request_config().grpc_service, None);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+const VERBOSE: &str = r#"
+Resolved method descriptor:
+rpc GetUser ( .users.GetUserRequest ) returns ( .users.User );
+
+Request metadata to send:
+authorization: Bearer abc
+
+Response headers received:
+content-type: application/grpc
+x-request-id: 42
+
+Response contents:
+{
+  "id": "7"
+}
+
+Response trailers received:
+x-served-by: users-1
+Sent 1 request and received 1 response
+"#;
+
+#[test]
+fn test_parse_verbose_output() {
+    let (body, headers, trailers) = grpc::parse_verbose(VERBOSE);
+    assert_eq!(body, "{\n  \"id\": \"7\"\n}");
+    assert_eq!(
+        headers,
+        vec![
+            ("content-type".to_string(), "application/grpc".to_string()),
+            ("x-request-id".to_string(), "42".to_string()),
+        ]
+    );
+    assert_eq!(
+        trailers,
+        vec![("x-served-by".to_string(), "users-1".to_string())]
+    );
+
+    // Server streaming: one message after another
+    let stream = "Response contents:\n{\"n\": 1}\n\nResponse contents:\n{\"n\": 2}\n";
+    assert_eq!(grpc::parse_verbose(stream).0, "{\"n\": 1}\n{\"n\": 2}");
+    // Not verbose: everything is the body
+    assert_eq!(grpc::parse_verbose("{\"a\": 1}\n").0, "{\"a\": 1}\n");
+}
+
+#[test]
+fn test_parse_status() {
+    let json = r#"{
+  "code": 3,
+  "message": "name is required",
+  "details": [
+    {
+      "@type": "type.googleapis.com/google.rpc.BadRequest",
+      "fieldViolations": [{"field": "name", "description": "must not be empty"}]
+    }
+  ]
+}"#;
+    let status = grpc::parse_status(json, Some(67)).unwrap();
+    assert_eq!(status.code, 3);
+    assert_eq!(status.name(), "INVALID_ARGUMENT");
+    assert_eq!(status.message, "name is required");
+    assert_eq!(
+        status.details[0]["fieldViolations"][0]["field"],
+        serde_json::json!("name")
+    );
+
+    let text = "ERROR:\n  Code: NotFound\n  Message: user 7 not found\n";
+    let status = grpc::parse_status(text, None).unwrap();
+    assert_eq!((status.code, status.name()), (5, "NOT_FOUND"));
+    assert_eq!(status.message, "user 7 not found");
+
+    // Only the exit code, 64 + status
+    let status = grpc::parse_status("deadline hit", Some(68)).unwrap();
+    assert_eq!(status.name(), "DEADLINE_EXCEEDED");
+    assert_eq!(status.message, "deadline hit");
+
+    // A failure before any status (connection refused)
+    assert_eq!(
+        grpc::parse_status("Failed to dial target host", Some(1)),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_grpc_sends_default_headers_not_disabled_ones() {
+    let dir = temp_dir("grpc_headers");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.default_headers
+        .insert("Authorization".to_string(), "Bearer team".to_string());
+    app.default_headers
+        .insert("X-Client".to_string(), "postdad".to_string());
+    {
+        let tab = app.active_tab_mut();
+        tab.body_type = BodyType::Grpc;
+        tab.url = "grpc://localhost:1".to_string();
+        tab.grpc_service = "helloworld.Greeter/SayHello".to_string();
+        tab.request_headers
+            .insert("X-Tenant".to_string(), "acme".to_string());
+        tab.disabled_headers
+            .insert("X-Debug".to_string(), "1".to_string());
+        tab.disabled_defaults.push("X-Client".to_string());
+    }
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+    crate::send_active_request(&mut app, &tx).await;
+    let Some(NetworkEvent::RunGrpc { headers, .. }) = rx.recv().await else {
+        panic!("nothing sent");
+    };
+    assert_eq!(headers["Authorization"], "Bearer team");
+    assert_eq!(headers["X-Tenant"], "acme");
+    assert!(!headers.contains_key("X-Debug"));
+    assert!(!headers.contains_key("X-Client"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
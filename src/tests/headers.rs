@@ -0,0 +1,117 @@
+use super::common::{press, type_text};
+use crate::app::{App, InputMode};
+use crossterm::event::KeyCode;
+
+fn headers_tab() -> App {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.default_headers.clear();
+    app.active_tab_mut().url = "https://api.test/".to_string();
+    app.active_tab_mut().selected_tab = 1;
+    app
+}
+
+#[test]
+fn test_add_edit_and_delete_headers_inline() {
+    let mut app = headers_tab();
+
+    press(&mut app, KeyCode::Char('a'));
+    assert_eq!(app.active_tab().input_mode, InputMode::EditingHeaderKey);
+    type_text(&mut app, "X-Api-Key");
+    press(&mut app, KeyCode::Tab);
+    type_text(&mut app, "secret");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.active_tab().input_mode, InputMode::Normal);
+    assert_eq!(app.active_tab().request_headers["X-Api-Key"], "secret");
+
+    press(&mut app, KeyCode::Char('a'));
+    type_text(&mut app, "Accept");
+    press(&mut app, KeyCode::Enter);
+    type_text(&mut app, "text/plain");
+    press(&mut app, KeyCode::Enter);
+    // Listed by name, the new row selected
+    let names: Vec<String> = app
+        .active_tab()
+        .header_rows()
+        .into_iter()
+        .map(|r| r.0)
+        .collect();
+    assert_eq!(names, vec!["Accept", "X-Api-Key"]);
+    assert_eq!(app.active_tab().headers_list_state.selected(), Some(0));
+
+    // Renaming replaces the row rather than adding one
+    press(&mut app, KeyCode::Char('e'));
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Backspace);
+    type_text(&mut app, "Content-Type");
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Enter);
+    let tab = app.active_tab();
+    assert_eq!(tab.request_headers.len(), 2);
+    assert_eq!(tab.request_headers["Content-Type"], "text/plain");
+
+    // Esc leaves things as they were
+    press(&mut app, KeyCode::Char('e'));
+    type_text(&mut app, "-Nope");
+    press(&mut app, KeyCode::Esc);
+    assert!(
+        app.active_tab()
+            .request_headers
+            .contains_key("Content-Type")
+    );
+    assert!(app.active_tab().header_draft.is_none());
+
+    press(&mut app, KeyCode::Char('d'));
+    let tab = app.active_tab();
+    assert_eq!(tab.request_headers.len(), 1);
+    assert!(tab.request_headers.contains_key("X-Api-Key"));
+    assert_eq!(tab.headers_list_state.selected(), Some(0));
+}
+
+#[test]
+fn test_disabled_headers_are_kept_but_not_sent() {
+    let mut app = headers_tab();
+    app.default_headers
+        .insert("User-Agent".to_string(), "PostDad".to_string());
+    app.header_command("add X-Debug: 1");
+    app.header_command("add Accept: application/json");
+
+    // Rows: Accept, X-Debug, then the User-Agent default
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.active_tab().headers_list_state.selected(), Some(1));
+    press(&mut app, KeyCode::Char(' '));
+    assert_eq!(app.active_tab().disabled_headers["X-Debug"], "1");
+    let (request, _) = app.outgoing_request();
+    assert!(!request.headers.contains_key("X-Debug"));
+    assert_eq!(request.headers["Accept"], "application/json");
+
+    // Defaults turn off the same way
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char(' '));
+    assert_eq!(app.active_tab().disabled_defaults, vec!["User-Agent"]);
+    let (request, _) = app.outgoing_request();
+    assert!(!request.headers.contains_key("User-Agent"));
+
+    // Saved with the request and back on load
+    let config = app.active_tab().to_request_config();
+    assert_eq!(config.disabled_headers["X-Debug"], "1");
+    assert!(!config.headers.as_ref().unwrap().contains_key("X-Debug"));
+    let json = serde_json::to_string(&config).unwrap();
+    let config: crate::domain::collection::RequestConfig = serde_json::from_str(&json).unwrap();
+    let mut other = headers_tab();
+    other.apply_request_config(config);
+    assert_eq!(other.active_tab().disabled_headers["X-Debug"], "1");
+    assert_eq!(other.active_tab().header_rows().len(), 2);
+
+    // And back on
+    press(&mut app, KeyCode::Char('k'));
+    press(&mut app, KeyCode::Char(' '));
+    let (request, _) = app.outgoing_request();
+    assert_eq!(request.headers["X-Debug"], "1");
+    assert!(app.active_tab().disabled_headers.is_empty());
+}
@@ -0,0 +1,87 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::workspace::Workspace;
+use crate::features::hex::{self, HexView, Source};
+
+#[test]
+fn test_rows_offset_hex_and_ascii() {
+    let bytes = b"HTTP/1.1 200 OK\r\n\x00\xff";
+    assert_eq!(hex::rows(bytes.len()), 2);
+    assert_eq!(
+        hex::format_row(bytes, 0),
+        "00000000  48 54 54 50 2f 31 2e 31  20 32 30 30 20 4f 4b 0d  |HTTP/1.1 200 OK.|"
+    );
+    assert_eq!(
+        hex::format_row(bytes, 1),
+        format!("00000010  0a 00 ff {}|...|", " ".repeat(3 * 13 + 2))
+    );
+    assert_eq!(hex::dump(bytes).lines().count(), 2);
+    assert_eq!(hex::dump(b""), "");
+}
+
+#[test]
+fn test_query_as_bytes_or_text() {
+    assert_eq!(
+        hex::parse_query("de ad be ef"),
+        vec![0xde, 0xad, 0xbe, 0xef]
+    );
+    assert_eq!(hex::parse_query("0xCAFE"), vec![0xca, 0xfe]);
+    assert_eq!(hex::parse_query("cafe"), b"cafe".to_vec());
+    assert_eq!(hex::parse_query("add bee"), b"add bee".to_vec());
+    assert_eq!(hex::parse_query("\"de ad\""), b"de ad".to_vec());
+
+    let bytes = b"aaa\x89PNG aa";
+    assert_eq!(hex::find(bytes, b"aa"), vec![0, 1, 8]);
+    assert_eq!(hex::find(bytes, &hex::parse_query("0x89504e47")), vec![3]);
+    assert!(hex::find(bytes, b"").is_empty());
+
+    let mut view = HexView::new(Source::Response);
+    view.query = "aa".to_string();
+    let long: Vec<u8> = [vec![0; 40], b"aa".to_vec()].concat();
+    view.search(&long);
+    assert_eq!((view.matches.clone(), view.scroll), (vec![40], 2));
+    view.step(false);
+    assert_eq!(view.current(), Some(40));
+}
+
+#[test]
+fn test_wire_bytes_of_a_request() {
+    let request = reqwest::Client::new()
+        .post("http://api.example.com:8080/orders?page=2")
+        .header("x-trace", "abc")
+        .body("{\"id\":1}")
+        .build()
+        .unwrap();
+    let wire = String::from_utf8(crate::net::http::wire_bytes(&request)).unwrap();
+    assert!(wire.starts_with("POST /orders?page=2 HTTP/1.1\r\nhost: api.example.com:8080\r\n"));
+    assert!(wire.contains("x-trace: abc\r\n"));
+    assert!(wire.contains("user-agent: PostDad/1.0\r\n"));
+    assert!(wire.ends_with("content-length: 8\r\n\r\n{\"id\":1}"));
+}
+
+#[test]
+fn test_hex_pane_on_response_and_sent_request() {
+    let dir = temp_dir("hex");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.open_hex_view(false);
+    assert!(app.hex_view.is_none());
+
+    app.active_tab_mut().request_bytes = Some(b"GET / HTTP/1.1\r\n\r\n".to_vec());
+    app.open_hex_view(false);
+    assert_eq!(app.hex_view.as_ref().unwrap().source, Source::Request);
+
+    app.active_tab_mut().response_bytes = Some(vec![0x89, b'P', b'N', b'G']);
+    app.open_hex_view(false);
+    app.hex_view.as_mut().unwrap().query = "PNG".to_string();
+    app.hex_search();
+    assert_eq!(app.hex_view.as_ref().unwrap().matches, vec![1]);
+
+    // The query carries over to the other side
+    app.toggle_hex_source();
+    let view = app.hex_view.as_ref().unwrap();
+    assert_eq!(view.source, Source::Request);
+    assert!(view.matches.is_empty());
+    assert_eq!(app.hex_bytes().unwrap().len(), 18);
+    let _ = std::fs::remove_dir_all(&dir);
+}
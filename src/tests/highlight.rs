@@ -0,0 +1,143 @@
+use crate::ui::highlight::{BACKGROUND_BYTES, HighlightCache, LineIndex, fingerprint};
+use ratatui::text::Line;
+use std::sync::atomic::AtomicBool;
+
+fn is_highlighted(line: &Line) -> bool {
+    line.spans.iter().any(|span| span.style.fg.is_some())
+}
+
+/// A JSON array of one small object per line, at least `bytes` long
+fn big_json(bytes: usize) -> String {
+    let mut text = String::from("[\n");
+    let mut i = 0;
+    while text.len() < bytes {
+        text.push_str(&format!("  {{\"id\": {}, \"name\": \"item {}\"}},\n", i, i));
+        i += 1;
+    }
+    text.push_str("  {}\n]");
+    text
+}
+
+#[test]
+fn test_small_body_is_highlighted_once_up_front() {
+    let text = "{\n  \"ok\": true\n}";
+    let mut cache = HighlightCache::default();
+    let (lines, skip) = cache.lines(text, "json", &Default::default(), 0, 10, 80);
+    assert!(!cache.is_pending());
+    assert_eq!(lines.len(), 3);
+    assert_eq!(skip, 0);
+    assert!(lines.iter().all(is_highlighted));
+}
+
+#[test]
+fn test_big_body_highlights_the_viewport_then_everything() {
+    let text = big_json(BACKGROUND_BYTES + 1);
+    let total = text.lines().count();
+    let first = total / 2;
+    let mut cache = HighlightCache::default();
+
+    // Every line is one row at 80 columns: row `first` is line `first`
+    let (lines, skip) = cache.lines(&text, "json", &Default::default(), first, 20, 80);
+    assert!(cache.is_pending());
+    assert_eq!(lines.len(), 20);
+    assert_eq!(skip, 0);
+    assert_eq!(lines[0].to_string(), text.lines().nth(first).unwrap());
+    assert!(lines.iter().all(is_highlighted));
+
+    let started = std::time::Instant::now();
+    while cache.is_pending() {
+        assert!(
+            started.elapsed().as_secs() < 120,
+            "background job never finished"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let _ = cache.lines(&text, "json", &Default::default(), first, 20, 80);
+    }
+    // Done: the top and the bottom come out highlighted, only what's on screen
+    let (lines, _) = cache.lines(&text, "json", &Default::default(), 0, 20, 80);
+    assert_eq!(lines.len(), 20);
+    assert!(is_highlighted(&lines[0]));
+    let (lines, _) = cache.lines(&text, "json", &Default::default(), total - 20, 20, 80);
+    assert_eq!(lines.len(), 20);
+    assert!(is_highlighted(&lines[19]));
+    assert_eq!(lines[19].to_string(), "]");
+}
+
+#[test]
+fn test_new_body_starts_over() {
+    let mut cache = HighlightCache::default();
+    let (first, _) = cache.lines("{\"a\": 1}", "json", &Default::default(), 0, 10, 80);
+    assert_eq!(first.len(), 1);
+    let (second, _) = cache.lines(
+        "{\"a\": 1}\n{\"b\": 2}",
+        "json",
+        &Default::default(),
+        0,
+        10,
+        80,
+    );
+    assert_eq!(second.len(), 2);
+    assert_ne!(fingerprint("{}", "json"), fingerprint("{}", "txt"));
+    assert_ne!(
+        fingerprint("{\"a\": 1}", "json"),
+        fingerprint("{\"a\": 2}", "json")
+    );
+}
+
+#[test]
+fn test_replaced_body_stops_its_job() {
+    let stop = AtomicBool::new(true);
+    assert!(crate::ui::syntax::highlight_until("{}", "json", &Default::default(), &stop).is_none());
+
+    // The first body's job is dropped mid-way, the second one still finishes
+    let mut cache = HighlightCache::default();
+    let old = big_json(BACKGROUND_BYTES + 1);
+    let _ = cache.lines(&old, "json", &Default::default(), 0, 20, 80);
+    assert!(cache.is_pending());
+    let new = big_json(BACKGROUND_BYTES + 100);
+    let _ = cache.lines(&new, "json", &Default::default(), 0, 20, 80);
+    let started = std::time::Instant::now();
+    while cache.is_pending() {
+        assert!(started.elapsed().as_secs() < 120, "job never finished");
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let _ = cache.lines(&new, "json", &Default::default(), 0, 20, 80);
+    }
+}
+
+#[test]
+fn test_wrapped_rows_map_to_source_lines() {
+    let text = format!("{}\nshort\n{}\nend", "x".repeat(250), "y".repeat(80));
+    let mut index = LineIndex::new(&text);
+    assert_eq!(index.len(), 4);
+    assert_eq!(index.line(&text, 1), "short");
+    // 250 chars at 100 columns take rows 0-2, "short" row 3, the 80 chars row 4
+    assert_eq!(index.line_at_row(&text, 0, 100), 0);
+    assert_eq!(index.line_at_row(&text, 2, 100), 0);
+    assert_eq!(index.line_at_row(&text, 3, 100), 1);
+    assert_eq!(index.line_at_row(&text, 4, 100), 2);
+    assert_eq!(index.line_at_row(&text, 5, 100), 3);
+    assert_eq!(index.line_at_row(&text, 99, 100), 4);
+    assert_eq!(index.rows_before(2), 4);
+    // Counted again for another width
+    assert_eq!(index.line_at_row(&text, 4, 50), 0);
+    assert_eq!(index.line_at_row(&text, 5, 50), 1);
+    assert_eq!(LineIndex::new("a\r\nb\n").len(), 2);
+    assert_eq!(LineIndex::new("a\r\nb\n").line("a\r\nb\n", 0), "a");
+    assert!(LineIndex::new("").is_empty());
+
+    // Long wrapped lines: the viewport scrolled by rows still lands highlighted,
+    // with the rows of the first line above the viewport left to scroll past
+    let long = format!("  {{\"pad\": \"{}\"}},\n", "z".repeat(300));
+    let text = format!(
+        "[\n{}  {{}}\n]",
+        long.repeat(BACKGROUND_BYTES / long.len() + 1)
+    );
+    let total = text.lines().count();
+    let line = total / 2;
+    let row = 1 + (line - 1) * 4;
+    let mut cache = HighlightCache::default();
+    let (lines, skip) = cache.lines(&text, "json", &Default::default(), row + 2, 20, 80);
+    assert_eq!(skip, 2);
+    assert!(lines[0].to_string().contains("pad"));
+    assert!(is_highlighted(&lines[0]));
+}
@@ -0,0 +1,299 @@
+use super::common::{press_with, temp_dir};
+use crate::app::{App, InputMode, RequestLog};
+use crate::domain::workspace::Workspace;
+use crate::features::history::{self, Row, Term};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+
+const DAY: u64 = 86_400;
+// 2024-03-10 12:00 UTC
+const NOW: u64 = 1_710_072_000;
+
+fn log(method: &str, url: &str, status: u16, timestamp: u64) -> RequestLog {
+    RequestLog {
+        method: method.to_string(),
+        url: url.to_string(),
+        status,
+        latency: 10,
+        body: None,
+        headers: HashMap::new(),
+        response_bytes: None,
+        is_binary: false,
+        timestamp,
+        pinned: false,
+        request: None,
+        size: 0,
+        tests: Vec::new(),
+    }
+}
+
+fn sample() -> Vec<RequestLog> {
+    vec![
+        log("GET", "https://api.example.com/users", 200, NOW - 60),
+        log("POST", "https://api.example.com/users", 422, NOW - 120),
+        log("GET", "https://api.example.com/orders", 404, NOW - DAY),
+        log(
+            "DELETE",
+            "https://api.example.com/users/1",
+            204,
+            NOW - 3 * DAY,
+        ),
+        log("GET", "https://api.example.com/health", 200, 0),
+    ]
+}
+
+#[test]
+fn test_query_filters_by_url_method_and_status() {
+    assert_eq!(
+        history::parse_query("post 4xx Users status:422 method:get"),
+        vec![
+            Term::Method("POST".to_string()),
+            Term::StatusClass(4),
+            Term::Text("users".to_string()),
+            Term::Status(422),
+            Term::Method("GET".to_string()),
+        ]
+    );
+
+    let logs = sample();
+    let shown = |query: &str| -> Vec<usize> {
+        let terms = history::parse_query(query);
+        (0..logs.len())
+            .filter(|&i| history::matches(&logs[i], &terms))
+            .collect()
+    };
+    assert_eq!(shown("users"), vec![0, 1, 3]);
+    assert_eq!(shown("GET users"), vec![0]);
+    assert_eq!(shown("4xx"), vec![1, 2]);
+    assert_eq!(shown("404"), vec![2]);
+    assert_eq!(shown("2xx users"), vec![0, 3]);
+    assert_eq!(shown(""), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_rows_group_by_day_with_pinned_first() {
+    let mut logs = sample();
+    logs[3].pinned = true;
+    assert_eq!(
+        history::rows(&logs, "", NOW),
+        vec![
+            Row::Group("Pinned".to_string()),
+            Row::Entry(3),
+            Row::Group("Today".to_string()),
+            Row::Entry(0),
+            Row::Entry(1),
+            Row::Group("Yesterday".to_string()),
+            Row::Entry(2),
+            Row::Group("Earlier".to_string()),
+            Row::Entry(4),
+        ]
+    );
+    assert_eq!(history::day_label(NOW - 3 * DAY, NOW), "2024-03-07");
+    assert_eq!(history::day_label(951_782_400, NOW), "2000-02-29");
+}
+
+#[test]
+fn test_trim_keeps_pinned_entries() {
+    let mut logs = sample();
+    logs[4].pinned = true;
+    history::trim(&mut logs, 2);
+    let urls: Vec<&str> = logs.iter().map(|l| l.url.as_str()).collect();
+    assert_eq!(
+        urls,
+        vec![
+            "https://api.example.com/users",
+            "https://api.example.com/users",
+            "https://api.example.com/health",
+        ]
+    );
+}
+
+#[test]
+fn test_history_view_pins_marks_and_deletes() {
+    let dir = temp_dir("history");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.request_history = sample();
+
+    press_with(&mut app, KeyCode::Char('g'), KeyModifiers::CONTROL);
+    assert!(app.show_history_view);
+    assert_eq!(app.active_tab().input_mode, InputMode::HistoryView);
+    for c in "users".chars() {
+        press_with(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    assert_eq!(app.history_view_entries(), vec![0, 1, 3]);
+
+    // Pin the POST: it moves to the top and stays highlighted
+    press_with(&mut app, KeyCode::Down, KeyModifiers::NONE);
+    press_with(&mut app, KeyCode::Char('p'), KeyModifiers::CONTROL);
+    assert!(app.request_history[1].pinned);
+    assert_eq!(app.history_view_entries(), vec![1, 0, 3]);
+    assert_eq!(app.selected_history_view_entry(), Some(1));
+
+    // Mark all leaves the pinned one alone
+    press_with(&mut app, KeyCode::BackTab, KeyModifiers::SHIFT);
+    assert_eq!(
+        app.history_marked.iter().copied().collect::<Vec<_>>(),
+        vec![0, 3]
+    );
+    press_with(&mut app, KeyCode::Char('x'), KeyModifiers::CONTROL);
+    assert_eq!(app.request_history.len(), 3);
+    assert_eq!(app.history_view_entries(), vec![0]);
+    assert_eq!(app.request_history[0].method, "POST");
+
+    press_with(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert!(!app.show_history_view);
+    assert_eq!(app.active_tab().method, "POST");
+
+    app.clear_history();
+    assert_eq!(app.request_history.len(), 1);
+    assert!(app.request_history[0].pinned);
+
+    app.history_command("limit 0");
+    assert_eq!(app.history_settings.limit, 50);
+    app.history_command("4xx");
+    assert!(app.show_history_view);
+    assert_eq!(app.history_query, "4xx");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_save_history_entry_to_collection() {
+    let dir = temp_dir("save_history");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+
+    let tab = app.active_tab_mut();
+    tab.method = "POST".to_string();
+    tab.url = "{{base_url}}/users".to_string();
+    tab.request_body = "{\"name\":\"Ada\"}".to_string();
+    tab.request_headers
+        .insert("X-Trace".to_string(), "on".to_string());
+    app.add_history(
+        "POST".to_string(),
+        "https://api.example.com/users".to_string(),
+        12,
+        201,
+        None,
+        HashMap::new(),
+        None,
+        false,
+    );
+    // An entry from before requests were kept only has its method and URL
+    app.request_history
+        .push(log("GET", "https://api.example.com/users?page=2", 200, 0));
+
+    app.active_sidebar = true;
+    app.open_save_history(0);
+    assert_eq!(app.active_tab().input_mode, InputMode::SaveHistory);
+    assert!(!app.active_sidebar);
+    assert_eq!(app.save_history_input, "saved/POST /users");
+
+    // Tab completes the collection part
+    app.save_history_input = "def".to_string();
+    press_with(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+    assert_eq!(app.save_history_input, "default/");
+
+    app.save_history_input = "users/Create user".to_string();
+    press_with(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert_eq!(app.active_tab().input_mode, InputMode::Normal);
+    let users = app.collections.iter().find(|c| c.name == "users").unwrap();
+    let saved = &users.requests["Create user"];
+    assert_eq!(saved.method, "POST");
+    assert_eq!(saved.url, "{{base_url}}/users");
+    assert_eq!(saved.body.as_deref(), Some("{\"name\":\"Ada\"}"));
+    assert_eq!(saved.headers.as_ref().unwrap()["X-Trace"], "on");
+
+    // Same name again: refused, the prompt stays
+    app.open_save_history(0);
+    app.save_history_input = "users/Create user".to_string();
+    app.confirm_save_history();
+    assert_eq!(app.active_tab().input_mode, InputMode::SaveHistory);
+    app.save_history_input = "no slash".to_string();
+    app.confirm_save_history();
+    assert_eq!(app.active_tab().input_mode, InputMode::SaveHistory);
+    press_with(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+
+    app.open_save_history(1);
+    app.save_history_input = "users/Page two".to_string();
+    app.confirm_save_history();
+    let users = app.collections.iter().find(|c| c.name == "users").unwrap();
+    assert_eq!(users.requests.len(), 2);
+    assert_eq!(
+        users.requests["Page two"].url,
+        "https://api.example.com/users?page=2"
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_marks_follow_entries_when_a_response_arrives() {
+    let dir = temp_dir("history_marks");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.request_history = sample();
+    app.history_settings.limit = 5;
+
+    app.open_history_view("");
+    press_with(&mut app, KeyCode::Down, KeyModifiers::NONE);
+    press_with(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+    assert_eq!(
+        app.history_marked.iter().copied().collect::<Vec<_>>(),
+        vec![1]
+    );
+    assert_eq!(app.selected_history_view_entry(), Some(2));
+
+    // A response lands with the view open; the oldest entry falls off the end
+    app.add_history(
+        "PUT".to_string(),
+        "https://api.example.com/users/2".to_string(),
+        5,
+        200,
+        None,
+        HashMap::new(),
+        None,
+        false,
+    );
+    assert_eq!(app.request_history.len(), 5);
+    assert_eq!(
+        app.history_marked.iter().copied().collect::<Vec<_>>(),
+        vec![2]
+    );
+    assert_eq!(app.selected_history_view_entry(), Some(3));
+
+    press_with(&mut app, KeyCode::Char('x'), KeyModifiers::CONTROL);
+    let methods: Vec<&str> = app
+        .request_history
+        .iter()
+        .map(|l| l.method.as_str())
+        .collect();
+    assert!(!methods.contains(&"POST"));
+    assert_eq!(methods[0], "PUT");
+
+    // The finder keeps its highlight on the same entry
+    app.open_finder();
+    app.finder_query = "orders".to_string();
+    let picked = app.finder_matches()[app.finder_index].item.target.clone();
+    app.add_history(
+        "GET".to_string(),
+        "https://api.example.com/orders/9".to_string(),
+        5,
+        200,
+        None,
+        HashMap::new(),
+        None,
+        false,
+    );
+    let now = app.finder_matches()[app.finder_index].item.target.clone();
+    match (picked, now) {
+        (
+            crate::features::finder::Target::History(before),
+            crate::features::finder::Target::History(after),
+        ) => assert_eq!(after, before + 1),
+        other => panic!("unexpected targets {:?}", other),
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
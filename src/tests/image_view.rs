@@ -0,0 +1,81 @@
+use super::common::temp_dir;
+use crate::app::{App, InputMode};
+use crate::features::image_view::{self, ImageZoom};
+use image::{DynamicImage, ImageFormat, RgbImage};
+
+fn png(width: u32, height: u32) -> (Vec<u8>, DynamicImage) {
+    let image = DynamicImage::ImageRgb8(RgbImage::new(width, height));
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut bytes, ImageFormat::Png).unwrap();
+    (bytes.into_inner(), image)
+}
+
+#[test]
+fn test_describe_image() {
+    let (bytes, image) = png(64, 32);
+    assert_eq!(image_view::describe(&bytes, &image), "PNG 64×32");
+    assert_eq!(image_view::extension(&bytes), Some("png"));
+    assert_eq!(image_view::describe(b"not an image", &image), "64×32");
+    assert_eq!(image_view::extension(b"not an image"), None);
+}
+
+#[test]
+fn test_zoom_cycles() {
+    let mut app = App::new();
+    app.cycle_image_zoom();
+    assert_eq!(app.image_zoom, ImageZoom::Fit);
+
+    let (bytes, image) = png(4, 4);
+    app.active_tab_mut().response_bytes = Some(bytes);
+    app.active_tab_mut().response_image = Some(image);
+    app.cycle_image_zoom();
+    assert_eq!(app.image_zoom, ImageZoom::Fill);
+    app.cycle_image_zoom();
+    assert_eq!(app.image_zoom, ImageZoom::Actual);
+    app.cycle_image_zoom();
+    assert_eq!(app.image_zoom, ImageZoom::Fit);
+}
+
+#[test]
+fn test_suggested_filename() {
+    let mut app = App::new();
+    let (bytes, _) = png(4, 4);
+    app.active_tab_mut().response_bytes = Some(bytes);
+
+    app.active_tab_mut().url = "https://img.test/avatars/ada.png?size=64".to_string();
+    assert_eq!(app.suggested_filename(), "ada.png");
+
+    // No extension in the URL: the format goes by the bytes
+    app.active_tab_mut().url = "https://img.test/avatars/42".to_string();
+    let name = app.suggested_filename();
+    assert!(
+        name.starts_with("response_") && name.ends_with(".png"),
+        "{}",
+        name
+    );
+
+    app.active_tab_mut().response_headers.insert(
+        "content-disposition".to_string(),
+        "attachment; filename=\"photo.png\"; size=10".to_string(),
+    );
+    assert_eq!(app.suggested_filename(), "photo.png");
+}
+
+#[test]
+fn test_save_as_prompts_then_writes() {
+    let mut app = App::new();
+    app.download_response();
+    assert_eq!(app.active_tab().input_mode, InputMode::Normal);
+
+    let (bytes, _) = png(4, 4);
+    app.active_tab_mut().response_bytes = Some(bytes.clone());
+    app.active_tab_mut().url = "https://img.test/ada.png".to_string();
+    app.download_response();
+    assert_eq!(app.active_tab().input_mode, InputMode::Command);
+    assert_eq!(app.command_input, "saveas ada.png");
+
+    let path = temp_dir("saveas").join("saveas.png");
+    app.save_response_as(path.to_str().unwrap());
+    assert_eq!(std::fs::read(&path).unwrap(), bytes);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,135 @@
+use crate::app::App;
+use crate::features::json_diff::{BodyDiff, ChangeKind, diff_bodies, diff_json};
+use serde_json::json;
+
+#[test]
+fn test_key_order_is_not_a_change() {
+    let old = json!({"a": 1, "b": {"c": true, "d": [1, 2]}});
+    let new: serde_json::Value =
+        serde_json::from_str(r#"{"b": {"d": [1, 2], "c": true}, "a": 1}"#).unwrap();
+    assert!(diff_json(&old, &new).is_empty());
+}
+
+#[test]
+fn test_added_removed_changed() {
+    let old = json!({"id": 1, "user": {"plan": "free", "legacy": true}, "tags": ["a"]});
+    let new = json!({"id": 1, "user": {"plan": "pro", "email": "x@y.z"}, "tags": ["a", "b"]});
+    let changes = diff_json(&old, &new);
+
+    let summary: Vec<(String, ChangeKind)> =
+        changes.iter().map(|c| (c.path.clone(), c.kind)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("$.tags[1]".to_string(), ChangeKind::Added),
+            ("$.user.email".to_string(), ChangeKind::Added),
+            ("$.user.legacy".to_string(), ChangeKind::Removed),
+            ("$.user.plan".to_string(), ChangeKind::Changed),
+        ]
+    );
+    assert_eq!(changes[3].describe(), r#"~ $.user.plan: "free" → "pro""#);
+}
+
+#[test]
+fn test_odd_keys_use_bracket_paths() {
+    let changes = diff_json(&json!({"a b": 1}), &json!({"a b": 2}));
+    assert_eq!(changes[0].path, "$['a b']");
+}
+
+#[test]
+fn test_text_fallback() {
+    let diff = diff_bodies("one\ntwo\n", "one\nthree\n");
+    assert_eq!(
+        diff,
+        BodyDiff::Text(vec![
+            (ChangeKind::Removed, "two".to_string()),
+            (ChangeKind::Added, "three".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_resend_and_compare() {
+    let mut app = App::new();
+    app.resend_and_compare();
+    assert!(!app.active_tab().resend_requested);
+
+    let tab = app.active_tab_mut();
+    tab.response = Some(r#"{"count": 1, "ok": true}"#.to_string());
+    tab.status_code = Some(200);
+    app.resend_and_compare();
+    assert!(app.active_tab().resend_requested);
+
+    // The re-sent response comes in
+    let tab = app.active_tab_mut();
+    tab.resend_requested = false;
+    tab.response = Some(r#"{"ok": true, "count": 2}"#.to_string());
+    tab.status_code = Some(201);
+    app.finish_compare();
+
+    let cmp = app.response_compare.as_ref().unwrap();
+    assert_eq!(cmp.old_status, Some(200));
+    assert_eq!(cmp.new_status, Some(201));
+    assert_eq!(cmp.body.len(), 1);
+    assert!(app.active_tab().compare_baseline.is_none());
+}
+
+#[test]
+fn test_header_diff_ignores_case() {
+    use std::collections::HashMap;
+    let old: HashMap<String, String> = [
+        ("Content-Type".to_string(), "application/json".to_string()),
+        ("X-Cache".to_string(), "MISS".to_string()),
+    ]
+    .into();
+    let new: HashMap<String, String> = [
+        ("content-type".to_string(), "application/json".to_string()),
+        ("x-cache".to_string(), "HIT".to_string()),
+        ("ETag".to_string(), "\"abc\"".to_string()),
+    ]
+    .into();
+
+    let changes = crate::features::json_diff::diff_headers(&old, &new);
+    let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+    assert_eq!(paths, vec!["headers.etag", "headers.x-cache"]);
+    assert_eq!(changes[1].kind, ChangeKind::Changed);
+}
+
+#[test]
+fn test_structural_history_diff() {
+    use crate::app::RequestLog;
+    let log = |body: &str| RequestLog {
+        method: "GET".to_string(),
+        url: "http://localhost/users".to_string(),
+        status: 200,
+        latency: 10,
+        body: Some(body.to_string()),
+        headers: Default::default(),
+        response_bytes: None,
+        is_binary: false,
+        timestamp: 0,
+        pinned: false,
+        request: None,
+        size: 0,
+        tests: Vec::new(),
+    };
+
+    let mut app = App::new();
+    app.request_history = vec![
+        log(r#"{"b": [1, 2], "a": "x"}"#),
+        log(r#"{"a": "x", "b": [1, 3]}"#),
+    ];
+    assert!(app.structural_history_diff().is_none());
+
+    app.toggle_diff_selection(0);
+    app.toggle_diff_selection(1);
+    let (headers, body) = app.structural_history_diff().unwrap();
+    assert!(headers.is_empty());
+    match body {
+        BodyDiff::Json(changes) => {
+            assert_eq!(changes.len(), 1);
+            assert_eq!(changes[0].path, "$.b[1]");
+        }
+        other => panic!("expected a JSON diff, got {:?}", other),
+    }
+}
@@ -0,0 +1,192 @@
+use crate::app::JsonEntry;
+use crate::ui::json_rows::{self, JsonRows};
+use serde_json::json;
+
+/// The entry at a row of the tree view
+fn find_visible<'a>(entries: &'a [JsonEntry], row: usize, filter: &str) -> Option<&'a JsonEntry> {
+    let path = JsonRows::default().path(entries, filter, row)?;
+    json_rows::entry(entries, &path)
+}
+
+fn tree() -> Vec<JsonEntry> {
+    vec![
+        JsonEntry::from_value(
+            "user".to_string(),
+            &json!({ "name": "Alice", "tags": ["a", "b"] }),
+            0,
+        ),
+        JsonEntry::from_value("count".to_string(), &json!(2), 0),
+    ]
+}
+
+#[test]
+fn test_find_visible_walks_expanded_tree() {
+    let entries = tree();
+    // user, name, tags, [0], [1], count
+    assert_eq!(find_visible(&entries, 0, "").unwrap().key, "user");
+    assert_eq!(find_visible(&entries, 1, "").unwrap().key, "name");
+    assert_eq!(find_visible(&entries, 4, "").unwrap().key, "[1]");
+    assert_eq!(find_visible(&entries, 5, "").unwrap().key, "count");
+    assert!(find_visible(&entries, 6, "").is_none());
+}
+
+#[test]
+fn test_find_visible_respects_filter_and_collapsed_nodes() {
+    let mut entries = tree();
+    assert_eq!(find_visible(&entries, 0, "tag").unwrap().key, "tags");
+
+    entries[0].is_expanded = false;
+    assert_eq!(find_visible(&entries, 1, "").unwrap().key, "count");
+    assert!(find_visible(&entries, 2, "").is_none());
+}
+
+#[test]
+fn test_value_and_subtree_text() {
+    let entries = tree();
+    let name = find_visible(&entries, 1, "").unwrap();
+    assert_eq!(name.value_text(), "Alice");
+
+    let tags = find_visible(&entries, 2, "").unwrap();
+    assert_eq!(tags.value_text(), r#"["a","b"]"#);
+
+    let user = find_visible(&entries, 0, "").unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&user.subtree_json()).unwrap();
+    assert_eq!(parsed, json!({ "name": "Alice", "tags": ["a", "b"] }));
+}
+
+#[test]
+fn test_flatten_lists_leaves_with_full_paths() {
+    let mut entries = vec![JsonEntry::from_value(
+        "root".to_string(),
+        &json!({
+            "data": { "items": [{ "price": 3 }, { "price": 5 }], "tags": [] },
+            "odd key": "x",
+        }),
+        0,
+    )];
+    // Collapsed nodes don't hide anything in the flat view
+    entries[0].is_expanded = false;
+
+    let rows = JsonEntry::flatten(&entries, "");
+    let paths: Vec<&str> = rows.iter().map(|(p, _)| p.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "data.items[0].price",
+            "data.items[1].price",
+            "data.tags",
+            r#"["odd key"]"#,
+        ]
+    );
+    assert_eq!(JsonEntry::flat_json_path(paths[1]), "$.data.items[1].price");
+    assert_eq!(JsonEntry::flat_json_path(paths[3]), r#"$["odd key"]"#);
+
+    // Search covers paths and values
+    let rows = JsonEntry::flatten(&entries, "PRICE");
+    assert_eq!(rows.len(), 2);
+    let rows = JsonEntry::flatten(&entries, "5");
+    assert_eq!(rows[0].0, "data.items[1].price");
+    assert_eq!(rows[0].1.value_text(), "5");
+}
+
+#[test]
+fn test_flat_mode_selection() {
+    let mut app = crate::app::App::new();
+    {
+        let tab = app.active_tab_mut();
+        tab.response_json = Some(tree());
+        tab.search_query = "tags".to_string();
+    }
+    app.toggle_json_flat();
+    app.active_tab_mut().json_list_state.select(Some(1));
+
+    assert_eq!(app.calculate_visible_item_count(), 2);
+    assert_eq!(app.selected_json_entry().unwrap().value_text(), "b");
+    assert_eq!(app.selected_json_path().unwrap(), "$.user.tags[1]");
+}
+
+#[test]
+fn test_rows_follow_expand_collapse_and_filter() {
+    let mut app = crate::app::App::new();
+    app.active_tab_mut().set_response_json(Some(tree()));
+    assert_eq!(app.calculate_visible_item_count(), 6);
+
+    // Collapsing `user` hides its four rows
+    app.active_tab_mut().json_list_state.select(Some(0));
+    app.toggle_current_selection();
+    assert_eq!(app.calculate_visible_item_count(), 2);
+    app.active_tab_mut().json_list_state.select(Some(1));
+    assert_eq!(app.selected_json_entry().unwrap().key, "count");
+    app.active_tab_mut().json_list_state.select(Some(0));
+    app.set_expanded_current_selection(true);
+    assert_eq!(app.calculate_visible_item_count(), 6);
+
+    // With a filter the selected row is the filtered one, `tags` here
+    app.active_tab_mut().search_query = "tags".to_string();
+    assert_eq!(app.calculate_visible_item_count(), 1);
+    assert_eq!(app.selected_json_path().unwrap(), "$.user.tags");
+    app.toggle_current_selection();
+    app.active_tab_mut().search_query.clear();
+    assert_eq!(app.calculate_visible_item_count(), 4);
+
+    // A new response starts from its own rows
+    app.active_tab_mut()
+        .set_response_json(Some(vec![JsonEntry::from_value(
+            "root".to_string(),
+            &json!([1, 2, 3]),
+            0,
+        )]));
+    assert_eq!(app.calculate_visible_item_count(), 4);
+    app.active_tab_mut().json_list_state.select(Some(3));
+    assert_eq!(app.selected_json_path().unwrap(), "$.root[2]");
+}
+
+#[test]
+fn test_rows_of_a_huge_tree() {
+    let items: Vec<serde_json::Value> = (0..50_000).map(|i| json!({ "id": i })).collect();
+    let mut tree = vec![JsonEntry::from_parsed("root".to_string(), json!(items), 0)];
+    // Too many to open them all: the items are there, collapsed, their ids not built
+    let last = tree[0].children.last().unwrap();
+    assert!(last.has_children() && !last.is_expanded && last.children.is_empty());
+    let rows = JsonRows::default();
+    assert_eq!(rows.len(&tree, ""), 50_001);
+
+    tree[0].children[49_999].set_expanded(true);
+    let rows = JsonRows::default();
+    assert_eq!(rows.len(&tree, ""), 50_002);
+    let window = rows.paths(&tree, "", 49_990..50_010);
+    assert_eq!(window.len(), 12);
+    assert_eq!(json_rows::json_path(&tree, &window[11]), "$.root[49999].id");
+    assert_eq!(rows.len(&tree, "[4999"), 11);
+}
+
+#[test]
+fn test_nodes_share_the_parsed_value() {
+    let value = json!({ "a/b": { "c~d": [1, { "e": null }] }, "list": [] });
+    let tree = JsonEntry::from_parsed("root".to_string(), value, 0);
+    // Small enough to open fully, keys with `/` and `~` found by pointer
+    let nested = &tree.children[0].children[0];
+    assert_eq!(nested.key, "c~d");
+    assert_eq!(nested.value(), &json!([1, { "e": null }]));
+    assert_eq!(nested.children[1].children[0].value(), &json!(null));
+    assert_eq!(nested.children[1].level, 3);
+    // An empty array is a leaf
+    assert!(!tree.children[1].has_children());
+
+    let mut collapsed = tree.children[0].clone();
+    collapsed.set_expanded(false);
+    assert!(!collapsed.is_expanded);
+    assert_eq!(collapsed.children.len(), 1);
+}
+
+#[test]
+fn test_window_offset_keeps_selection_in_view() {
+    // Moves only when the selection leaves the window
+    assert_eq!(json_rows::window_offset(0, Some(5), 10, 100), 0);
+    assert_eq!(json_rows::window_offset(0, Some(10), 10, 100), 1);
+    assert_eq!(json_rows::window_offset(40, Some(45), 10, 100), 40);
+    assert_eq!(json_rows::window_offset(40, Some(3), 10, 100), 3);
+    // Past the end after a collapse: back to the last full page
+    assert_eq!(json_rows::window_offset(90, Some(4), 10, 12), 2);
+    assert_eq!(json_rows::window_offset(7, None, 10, 5), 0);
+}
@@ -0,0 +1,61 @@
+use crate::app::App;
+
+fn app_with_response() -> App {
+    let mut app = App::new();
+    app.active_tab_mut().response = Some(
+        r#"{"items": [{"name": "pen", "price": 2}, {"name": "lamp", "price": 25}, {"name": "desk", "price": 120}]}"#
+            .to_string(),
+    );
+    app
+}
+
+#[test]
+fn test_jsonpath_query_shows_only_matches() {
+    let mut app = app_with_response();
+    app.run_jsonpath_query("$.items[?(@.price>10)].name");
+
+    let tab = app.active_tab();
+    assert_eq!(
+        tab.jsonpath_query.as_deref(),
+        Some("$.items[?(@.price>10)].name")
+    );
+    let tree = tab.response_json.as_ref().unwrap();
+    assert_eq!(tree.len(), 1);
+    assert_eq!(*tree[0].value(), serde_json::json!(["lamp", "desk"]));
+}
+
+#[test]
+fn test_clear_jsonpath_query_restores_full_tree() {
+    let mut app = app_with_response();
+    app.run_jsonpath_query("$.items[0]");
+    app.clear_jsonpath_query();
+
+    let tab = app.active_tab();
+    assert!(tab.jsonpath_query.is_none());
+    let tree = tab.response_json.as_ref().unwrap();
+    assert_eq!(tree[0].key, "root");
+    assert!(tree[0].value().get("items").is_some());
+}
+
+#[test]
+fn test_invalid_jsonpath_keeps_tree() {
+    let mut app = app_with_response();
+    app.run_jsonpath_query("$.items[");
+    assert!(app.active_tab().jsonpath_query.is_none());
+}
+
+#[test]
+fn test_add_jsonpath_to_chain() {
+    let mut app = app_with_response();
+    // Nothing to add without an active query
+    app.add_jsonpath_to_chain("first_item");
+    assert!(app.active_tab().extract_rules.is_empty());
+
+    app.run_jsonpath_query("$.items[0].name");
+    app.add_jsonpath_to_chain("first_item");
+    app.add_jsonpath_to_chain("first_item");
+    assert_eq!(
+        app.active_tab().extract_rules,
+        vec![("first_item".to_string(), "$.items[0].name".to_string())]
+    );
+}
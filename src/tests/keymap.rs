@@ -0,0 +1,176 @@
+use crate::app::{App, AppMode, BodyType};
+use crate::domain::keymap::{
+    ACTIONS, BINDINGS, Chord, KeyContext, Keymap, PROFILES, all_contexts, cheat_sheet,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+
+#[test]
+fn test_contexts_follow_the_screen() {
+    let mut app = App::new();
+    app.active_tab_mut().selected_tab = 3;
+    let contexts = app.key_contexts();
+    assert!(contexts.contains(&KeyContext::Auth));
+    assert!(contexts.contains(&KeyContext::Response));
+    assert!(!contexts.contains(&KeyContext::Runner));
+    assert!(!contexts.contains(&KeyContext::Grpc));
+
+    let tab = app.active_tab_mut();
+    tab.selected_tab = 2;
+    tab.body_type = BodyType::Grpc;
+    assert!(app.key_contexts().contains(&KeyContext::Grpc));
+
+    app.active_tab_mut().app_mode = AppMode::WebSocket;
+    let contexts = app.key_contexts();
+    assert!(contexts.contains(&KeyContext::WebSocket));
+    assert!(!contexts.contains(&KeyContext::Request));
+
+    app.runner_mode = true;
+    assert_eq!(
+        app.key_contexts(),
+        vec![KeyContext::Global, KeyContext::Runner]
+    );
+
+    app.help_show_all = true;
+    assert_eq!(app.key_contexts(), all_contexts());
+}
+
+#[test]
+fn test_cheat_sheet_search() {
+    let keymap = Keymap::default();
+    let sections = cheat_sheet(
+        &[KeyContext::Global, KeyContext::Codegen],
+        "python",
+        &keymap,
+    );
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].0, KeyContext::Codegen);
+    assert_eq!(sections[0].1[0].keys, "G");
+
+    // Matching a section title keeps the whole section
+    let sections = cheat_sheet(&[KeyContext::Runner], "RUNNER", &keymap);
+    assert_eq!(
+        sections[0].1.len(),
+        BINDINGS
+            .iter()
+            .filter(|b| b.context == KeyContext::Runner)
+            .count()
+    );
+
+    assert!(cheat_sheet(&[KeyContext::Global], "no such key", &keymap).is_empty());
+}
+
+#[test]
+fn test_every_context_has_bindings() {
+    let contexts = all_contexts();
+    assert_eq!(contexts.first(), Some(&KeyContext::Global));
+    for context in contexts {
+        assert!(
+            !cheat_sheet(&[context], "", &Keymap::default()).is_empty(),
+            "{:?}",
+            context
+        );
+    }
+}
+
+#[test]
+fn test_chord_parse() {
+    let chord = |code, modifiers| Chord { code, modifiers };
+    assert_eq!(
+        Chord::parse("Ctrl+N").unwrap(),
+        chord(KeyCode::Char('n'), KeyModifiers::CONTROL)
+    );
+    assert_eq!(
+        Chord::parse("shift+g").unwrap(),
+        chord(KeyCode::Char('G'), KeyModifiers::NONE)
+    );
+    assert_eq!(
+        Chord::parse("Ctrl++").unwrap(),
+        chord(KeyCode::Char('+'), KeyModifiers::CONTROL)
+    );
+    assert_eq!(
+        Chord::parse("pagedown").unwrap(),
+        chord(KeyCode::PageDown, KeyModifiers::NONE)
+    );
+    assert_eq!(Chord::parse("F5").unwrap().to_string(), "F5");
+    assert_eq!(Chord::parse("Alt+Space").unwrap().to_string(), "Alt+Space");
+    assert!(Chord::parse("Hyper+x").is_err());
+    assert!(Chord::parse("Banana").is_err());
+
+    // Terminals send uppercase letters with Shift
+    let event = KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT);
+    assert_eq!(Chord::from_event(&event), Chord::parse("R").unwrap());
+}
+
+#[test]
+fn test_profiles_are_valid() {
+    for (name, _) in PROFILES {
+        let keymap = Keymap::build(name, &HashMap::new()).unwrap();
+        assert_eq!(keymap.keys.len(), ACTIONS.len());
+    }
+    assert!(Keymap::build("nano", &HashMap::new()).is_err());
+}
+
+#[test]
+fn test_translate_remapped_keys() {
+    let key = |code, modifiers| KeyEvent::new(code, modifiers);
+    let keymap = Keymap::parse(
+        r#"
+profile = "emacs"
+
+[keys]
+send = "Ctrl+s"
+quit = ["Ctrl+q", "q"]
+"#,
+    )
+    .unwrap();
+
+    // Ctrl+n moves down, the old Ctrl+n (new tab) moved to Alt+n
+    assert_eq!(
+        keymap.translate(key(KeyCode::Char('n'), KeyModifiers::CONTROL)),
+        Some(key(KeyCode::Char('j'), KeyModifiers::NONE))
+    );
+    assert_eq!(
+        keymap.translate(key(KeyCode::Char('n'), KeyModifiers::ALT)),
+        Some(key(KeyCode::Char('n'), KeyModifiers::CONTROL))
+    );
+    // Enter was moved to Ctrl+s, so it does nothing now
+    assert_eq!(
+        keymap.translate(key(KeyCode::Char('s'), KeyModifiers::CONTROL)),
+        Some(key(KeyCode::Enter, KeyModifiers::NONE))
+    );
+    assert_eq!(
+        keymap.translate(key(KeyCode::Enter, KeyModifiers::NONE)),
+        None
+    );
+    // Kept and unmapped keys pass through untouched
+    let q = key(KeyCode::Char('q'), KeyModifiers::NONE);
+    assert_eq!(keymap.translate(q), Some(q));
+    let down = key(KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(keymap.translate(down), Some(down));
+
+    assert_eq!(keymap.describe("j / k"), "Ctrl+n, j / Ctrl+p, k");
+    assert_eq!(keymap.describe(":jp <path>"), ":jp <path>");
+
+    assert!(Keymap::parse("[keys]\nfly = \"x\"").is_err());
+    let err = Keymap::parse("[keys]\nsend = \"j\"").unwrap_err();
+    assert!(err.contains("j is bound to both"), "{}", err);
+}
+
+#[test]
+fn test_remapped_key_drives_the_handler() {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.keymap = Keymap::parse("[keys]\ntimeline = \"Ctrl+y\"").unwrap();
+    app.active_tab_mut().timeline.clear();
+
+    let key = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::CONTROL);
+    let key = crate::handler::remap_key(key, &mut app).unwrap();
+    crate::handler::handle_key_events(key, &mut app);
+    let last = app.notifications.history.front().map(|n| n.message.clone());
+    assert_eq!(last.as_deref(), Some("Nothing sent from this tab yet"));
+
+    // The cheat sheet shows the new key
+    let sections = cheat_sheet(&[KeyContext::Request], "timeline", &app.keymap);
+    assert_eq!(sections[0].1[0].keys, "Ctrl+y");
+}
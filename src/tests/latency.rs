@@ -0,0 +1,99 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::workspace::Workspace;
+use crate::features::latency::{self, LatencyStore, Sample, Stats};
+
+fn sample(at: u64, ms: u64) -> Sample {
+    Sample {
+        at,
+        ms,
+        status: 200,
+    }
+}
+
+#[test]
+fn test_percentiles() {
+    let samples: Vec<Sample> = (1..=100).rev().map(|ms| sample(0, ms)).collect();
+    let stats = Stats::of(&samples).unwrap();
+    assert_eq!(
+        stats,
+        Stats {
+            count: 100,
+            min: 1,
+            p50: 50,
+            p90: 90,
+            p95: 95,
+            p99: 99,
+            max: 100,
+        }
+    );
+    let one = Stats::of(&[sample(0, 7)]).unwrap();
+    assert_eq!((one.p50, one.p99), (7, 7));
+    assert!(Stats::of(&[]).is_none());
+}
+
+#[test]
+fn test_sessions_and_change() {
+    let gap = latency::SESSION_GAP + 1;
+    let samples = vec![
+        sample(100, 100),
+        sample(110, 120),
+        sample(120, 100),
+        sample(120 + gap, 150),
+        sample(130 + gap, 160),
+    ];
+    let sessions = latency::sessions(&samples);
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0].len(), 3);
+    assert_eq!(latency::session_change(&samples), Some(50.0));
+    assert_eq!(latency::session_change(&samples[..3]), None);
+    assert!(latency::sessions(&[]).is_empty());
+}
+
+#[test]
+fn test_store_keeps_the_newest() {
+    let mut store = LatencyStore::default();
+    for i in 0..latency::LIMIT as u64 + 5 {
+        store.record(latency::key("users", "List"), sample(i, i));
+    }
+    let samples = store.samples("users/List");
+    assert_eq!(samples.len(), latency::LIMIT);
+    assert_eq!(samples[0].ms, 5);
+    assert!(store.samples("users/Other").is_empty());
+}
+
+#[test]
+fn test_recorded_for_saved_requests_across_sessions() {
+    let dir = temp_dir("latency");
+    std::fs::create_dir_all(dir.join("collections")).unwrap();
+    std::fs::write(
+        dir.join("collections/users.hcl"),
+        "request \"List\" {\n  method = \"GET\"\n  url = \"https://users.test\"\n}\n",
+    )
+    .unwrap();
+
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    // An unsaved tab has nothing to chart
+    app.record_latency(10, 200);
+    assert!(app.latency_trends.0.is_empty());
+    app.open_latency_chart("");
+    assert!(app.latency_chart.is_none());
+
+    app.open_request("users/List");
+    app.record_latency(40, 200);
+    app.record_latency(60, 500);
+
+    let mut again = App::with_workspace(Workspace::at(&dir));
+    again.show_splash = false;
+    let samples = again.latency_trends.samples("users/List");
+    assert_eq!(samples.len(), 2);
+    assert_eq!((samples[1].ms, samples[1].status), (60, 500));
+
+    again.open_latency_chart("users/List");
+    assert_eq!(again.latency_chart.as_deref(), Some("users/List"));
+    again.latency_chart = None;
+    again.open_latency_chart("users/Missing");
+    assert!(again.latency_chart.is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,215 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::workspace::Workspace;
+use crate::ui::layout::{
+    self, COMPACT_HEIGHT, MIN_HEIGHT, MIN_WIDTH, SIDEBAR_PERCENT, SIDEBAR_WIDTH, TABS_HEIGHT,
+    main_layout,
+};
+use ratatui::layout::Rect;
+
+fn panes(l: &layout::MainLayout) -> Vec<Rect> {
+    [
+        l.sidebar,
+        l.tabs,
+        Some(l.url),
+        l.config_tabs,
+        l.config,
+        Some(l.response),
+        Some(l.status),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|r| !r.is_empty())
+    .collect()
+}
+
+#[test]
+fn test_panes_never_overlap_or_spill() {
+    // Every threshold from both sides, and a spread in between
+    let widths: Vec<u16> = (MIN_WIDTH..=220)
+        .step_by(13)
+        .chain([
+            MIN_WIDTH + 1,
+            SIDEBAR_WIDTH - 1,
+            SIDEBAR_WIDTH,
+            SIDEBAR_WIDTH + 1,
+        ])
+        .collect();
+    let heights: Vec<u16> = (MIN_HEIGHT..=70)
+        .step_by(7)
+        .chain([
+            16,
+            17,
+            TABS_HEIGHT - 1,
+            TABS_HEIGHT,
+            COMPACT_HEIGHT - 1,
+            COMPACT_HEIGHT,
+        ])
+        .collect();
+    for &width in &widths {
+        for &height in &heights {
+            let area = Rect::new(0, 0, width, height);
+            for (zen, focused) in [(false, false), (false, true), (true, false)] {
+                let l = main_layout(area, zen, focused, SIDEBAR_PERCENT);
+                let panes = panes(&l);
+                for (i, a) in panes.iter().enumerate() {
+                    assert_eq!(
+                        area.intersection(*a),
+                        *a,
+                        "{:?} outside {}x{}",
+                        a,
+                        width,
+                        height
+                    );
+                    for b in &panes[i + 1..] {
+                        assert!(
+                            !a.intersects(*b),
+                            "{:?} overlaps {:?} at {}x{}",
+                            a,
+                            b,
+                            width,
+                            height
+                        );
+                    }
+                }
+                assert!(
+                    l.response.height >= 3,
+                    "no room for the response at {}x{}: {:?}",
+                    width,
+                    height,
+                    l
+                );
+                assert_eq!(l.url.height, 3);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sidebar_folds_away_on_narrow_terminals() {
+    let wide = main_layout(
+        Rect::new(0, 0, SIDEBAR_WIDTH, 40),
+        false,
+        false,
+        SIDEBAR_PERCENT,
+    );
+    let sidebar = wide.sidebar.unwrap();
+    assert_eq!((sidebar.height, sidebar.y), (39, 0));
+    assert!(wide.url.x > sidebar.x);
+
+    let narrow = main_layout(
+        Rect::new(0, 0, SIDEBAR_WIDTH - 1, 40),
+        false,
+        false,
+        SIDEBAR_PERCENT,
+    );
+    assert_eq!(narrow.sidebar, None);
+    assert_eq!(narrow.url.width, SIDEBAR_WIDTH - 1);
+
+    // Focused: stacked above the request, full width
+    let stacked = main_layout(
+        Rect::new(0, 0, SIDEBAR_WIDTH - 1, 40),
+        false,
+        true,
+        SIDEBAR_PERCENT,
+    );
+    let sidebar = stacked.sidebar.unwrap();
+    assert_eq!(sidebar.width, SIDEBAR_WIDTH - 1);
+    assert!(stacked.url.y >= sidebar.bottom());
+    assert_eq!(stacked.config, None);
+
+    assert_eq!(
+        main_layout(Rect::new(0, 0, 200, 40), true, true, SIDEBAR_PERCENT).sidebar,
+        None
+    );
+}
+
+#[test]
+fn test_short_terminals_shrink_the_config_pane_then_drop_the_tabs_bar() {
+    let tall = main_layout(
+        Rect::new(0, 0, 120, COMPACT_HEIGHT + 1),
+        false,
+        false,
+        SIDEBAR_PERCENT,
+    );
+    assert_eq!(tall.config.unwrap().height, 8);
+    assert!(tall.tabs.is_some());
+
+    let compact = main_layout(
+        Rect::new(0, 0, 120, COMPACT_HEIGHT),
+        false,
+        false,
+        SIDEBAR_PERCENT,
+    );
+    assert_eq!(compact.config.unwrap().height, 5);
+    assert!(compact.tabs.is_some());
+
+    let short = main_layout(
+        Rect::new(0, 0, 120, TABS_HEIGHT),
+        false,
+        false,
+        SIDEBAR_PERCENT,
+    );
+    assert_eq!(short.tabs, None);
+    assert_eq!(short.url.y, 0);
+
+    let zen = main_layout(Rect::new(0, 0, 120, 40), true, false, SIDEBAR_PERCENT);
+    assert_eq!((zen.config_tabs, zen.config), (None, None));
+}
+
+#[test]
+fn test_cursor_counts_display_columns_and_stays_inside() {
+    assert_eq!(layout::width("abc"), 3);
+    assert_eq!(layout::width("📜"), 2);
+    assert_eq!(layout::width("名前"), 4);
+
+    let area = Rect::new(10, 5, 20, 3);
+    assert_eq!(layout::cursor_at(area, 0, 0), (11, 6));
+    assert_eq!(layout::cursor_at(area, 4, 0), (15, 6));
+    // Past the right border it's held at the last column inside
+    assert_eq!(layout::cursor_at(area, 100, 0), (28, 6));
+
+    // 18 columns fit between the borders
+    assert_eq!(layout::scroll_for(17, 18), 0);
+    assert_eq!(layout::scroll_for(18, 18), 1);
+    assert_eq!(
+        layout::cursor_at(area, 18, layout::scroll_for(18, 18)),
+        (28, 6)
+    );
+}
+
+#[test]
+fn test_draws_at_any_size_without_panicking() {
+    let dir = temp_dir("layout_draw");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.active_tab_mut().url = "https://例え.jp/検索?q=📜".to_string();
+    app.active_tab_mut().input_mode = crate::app::InputMode::Editing;
+
+    for (width, height) in [
+        (1, 1),
+        (20, 8),
+        (49, 30),
+        (50, 15),
+        (80, 24),
+        (99, 30),
+        (160, 50),
+    ] {
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| crate::ui::render(f, &mut app)).unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        // 1x1 has no room to say so
+        if width >= 20 {
+            let small = width < MIN_WIDTH || height < MIN_HEIGHT;
+            assert_eq!(text.contains("too small"), small, "{}x{}", width, height);
+        }
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,121 @@
+use crate::app::{App, AppMode, InputMode};
+use crate::domain::line_edit::{CURSOR_END, Edit, with_cursor};
+use crate::handler::handle_key_events;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn ctrl(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+}
+
+fn alt(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::ALT)
+}
+
+fn run(text: &str, cursor: usize, keys: &[KeyEvent]) -> (String, usize) {
+    let mut text = text.to_string();
+    let mut cursor = cursor;
+    for k in keys {
+        Edit::from_key(k).unwrap().apply(&mut text, &mut cursor);
+    }
+    (text, cursor)
+}
+
+#[test]
+fn test_word_motion_and_deletes() {
+    let url = "https://api.test/v1/users?page=2";
+    // Ctrl+W from the end takes the last word, leaving the separator
+    assert_eq!(
+        run(url, CURSOR_END, &[ctrl('w')]).0,
+        "https://api.test/v1/users?page="
+    );
+    assert_eq!(
+        run(url, CURSOR_END, &[ctrl('w'), ctrl('w')]).0,
+        "https://api.test/v1/users?"
+    );
+
+    let (_, cursor) = run(url, CURSOR_END, &[alt('b'), alt('b')]);
+    assert_eq!(&url[cursor..], "page=2");
+    let (_, cursor) = run(url, 0, &[alt('f'), alt('f')]);
+    assert_eq!(&url[..cursor], "https://api");
+
+    // Alt+D deletes the word after the cursor, Ctrl+K/U kill to either end
+    let at = "https://".len();
+    assert_eq!(run(url, at, &[alt('d')]).0, "https://.test/v1/users?page=2");
+    assert_eq!(run(url, at, &[ctrl('k')]), ("https://".to_string(), at));
+    assert_eq!(
+        run(url, at, &[ctrl('u')]),
+        ("api.test/v1/users?page=2".to_string(), 0)
+    );
+}
+
+#[test]
+fn test_insert_and_delete_at_cursor() {
+    let (text, cursor) = run(
+        "héllo",
+        CURSOR_END,
+        &[
+            ctrl('a'),
+            key(KeyCode::Right),
+            key(KeyCode::Right),
+            key(KeyCode::Backspace),
+            key(KeyCode::Char('e')),
+            key(KeyCode::Delete),
+            key(KeyCode::End),
+            key(KeyCode::Char('!')),
+        ],
+    );
+    assert_eq!(text, "helo!");
+    assert_eq!(cursor, text.len());
+    assert_eq!(with_cursor("abc", 1, '│'), "a│bc");
+
+    // AltGr comes in as Ctrl+Alt and still types
+    let altgr = KeyEvent::new(
+        KeyCode::Char('@'),
+        KeyModifiers::CONTROL | KeyModifiers::ALT,
+    );
+    assert_eq!(Edit::from_key(&altgr), Some(Edit::Insert('@')));
+    assert_eq!(Edit::from_key(&key(KeyCode::Enter)), None);
+}
+
+#[test]
+fn test_inputs_share_the_editor() {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.active_tab_mut().url = "https://api.test/items?id=1".to_string();
+    app.active_tab_mut().params = vec![("id".to_string(), "1".to_string())];
+    app.active_tab_mut().params_list_state.select(Some(0));
+
+    // Editing a param value in the middle keeps the URL in sync
+    app.active_tab_mut().input_mode = InputMode::EditingParamValue;
+    for k in [ctrl('a'), key(KeyCode::Char('4')), key(KeyCode::Char('2'))] {
+        handle_key_events(k, &mut app);
+    }
+    assert_eq!(app.active_tab().params[0].1, "421");
+    assert_eq!(app.active_tab().url, "https://api.test/items?id=421");
+
+    // Leaving the field puts the cursor back at the end for the next one
+    handle_key_events(key(KeyCode::Esc), &mut app);
+    assert_eq!(app.input_cursor, CURSOR_END);
+
+    // Ctrl+W deletes a word rather than switching to WebSocket mode
+    handle_key_events(key(KeyCode::Char(':')), &mut app);
+    for c in "theme dark".chars() {
+        handle_key_events(key(KeyCode::Char(c)), &mut app);
+    }
+    handle_key_events(ctrl('w'), &mut app);
+    assert_eq!(app.command_input, "theme ");
+    assert_eq!(app.active_tab().app_mode, AppMode::Http);
+
+    // Numeric inputs only take digits
+    app.active_tab_mut().input_mode = InputMode::EditingSentinelInterval;
+    app.sentinel_mode = true;
+    app.sentinel_interval_input = "5".to_string();
+    for c in ['x', '0'] {
+        handle_key_events(key(KeyCode::Char(c)), &mut app);
+    }
+    assert_eq!(app.sentinel_interval_input, "50");
+}
@@ -0,0 +1,133 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::workspace::Workspace;
+use crate::features::logging::{LogBuffer, LogLayer, LogView};
+use tracing::Level;
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Runs `f` with only the log layer listening, returns what it recorded
+fn capture(level: Level, f: impl FnOnce()) -> LogBuffer {
+    let buffer = LogBuffer::default();
+    let layer = LogLayer::new(buffer.clone(), None, level, u64::MAX);
+    tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), f);
+    buffer
+}
+
+#[test]
+fn test_request_id_from_field_and_span() {
+    let buffer = capture(Level::DEBUG, || {
+        tracing::info!(request_id = "aaaa0001", status = 200, "response");
+        let span = tracing::info_span!("request", request_id = "bbbb0002");
+        span.in_scope(|| tracing::warn!(error = "boom", "script failed"));
+        tracing::info!("nothing to do with a send");
+    });
+    let records = buffer.lock().unwrap();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].request_id.as_deref(), Some("aaaa0001"));
+    assert_eq!(records[0].message, "response");
+    assert_eq!(records[0].fields["status"], "200");
+    assert!(!records[0].fields.contains_key("request_id"));
+    assert_eq!(records[1].request_id.as_deref(), Some("bbbb0002"));
+    assert_eq!(records[1].fields["error"], "boom");
+    assert!(records[1].is_problem());
+    assert_eq!(records[2].request_id, None);
+}
+
+#[test]
+fn test_level_filter() {
+    let buffer = capture(Level::WARN, || {
+        tracing::debug!("sending");
+        tracing::info!("response");
+        tracing::warn!("body not decoded");
+        tracing::error!("request failed");
+    });
+    let levels: Vec<String> = buffer
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|r| r.level.clone())
+        .collect();
+    assert_eq!(levels, ["WARN", "ERROR"]);
+}
+
+#[test]
+fn test_file_rotates() {
+    let dir = temp_dir("logging_rotate");
+    let path = dir.join("logs").join("postdad.log");
+    let layer = LogLayer::new(LogBuffer::default(), Some(&path), Level::INFO, 300);
+    tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+        for i in 0..20 {
+            tracing::info!(request_id = "cccc0003", i, "response");
+        }
+    });
+    let current = std::fs::read_to_string(&path).unwrap();
+    let rotated = std::fs::read_to_string(dir.join("logs").join("postdad.log.1")).unwrap();
+    assert!(current.len() <= 300);
+    assert!(!rotated.is_empty());
+    assert!(!dir.join("logs").join("postdad.log.4").exists());
+    // JSON lines, one record each
+    let record: serde_json::Value = serde_json::from_str(current.lines().last().unwrap()).unwrap();
+    assert_eq!(record["request_id"], "cccc0003");
+    assert_eq!(record["fields"]["i"], "19");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_view_filters() {
+    let buffer = capture(Level::DEBUG, || {
+        tracing::info!(
+            request_id = "dddd0004",
+            url = "https://api.test/users",
+            "send"
+        );
+        tracing::error!(request_id = "dddd0004", "request failed");
+        tracing::info!(
+            request_id = "eeee0005",
+            url = "https://api.test/orders",
+            "send"
+        );
+    });
+    let records = buffer.lock().unwrap();
+    let messages = |view: &LogView| -> Vec<String> {
+        view.visible(&records)
+            .iter()
+            .map(|r| format!("{} {}", r.request_id.as_deref().unwrap_or(""), r.message))
+            .collect()
+    };
+    assert_eq!(messages(&LogView::default()).len(), 3);
+    let problems = LogView {
+        problems_only: true,
+        ..Default::default()
+    };
+    assert_eq!(messages(&problems), ["dddd0004 request failed"]);
+    let one = LogView {
+        request_id: Some("eeee0005".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(messages(&one), ["eeee0005 send"]);
+    let text = LogView {
+        filter: "USERS".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(messages(&text), ["dddd0004 send"]);
+}
+
+#[test]
+fn test_view_follows_the_tabs_request() {
+    let dir = temp_dir("logging_view");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.open_logs("");
+    app.toggle_log_request();
+    assert_eq!(app.log_view.as_ref().unwrap().request_id, None);
+
+    app.active_tab_mut().request_id = Some("ffff0006".to_string());
+    app.toggle_log_request();
+    assert_eq!(
+        app.log_view.as_ref().unwrap().request_id.as_deref(),
+        Some("ffff0006")
+    );
+    app.toggle_log_request();
+    assert_eq!(app.log_view.as_ref().unwrap().request_id, None);
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,133 @@
+use super::common::temp_dir;
+use crate::domain::workspace::Workspace;
+use crate::features::cli::{self, MockArgs};
+use crate::net::mock_server::{MockHit, MockRoute, MockSettings, start_mock_server_with};
+
+fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+fn slow_route() -> MockRoute {
+    MockRoute {
+        path: "/slow".to_string(),
+        method: "GET".to_string(),
+        status: 201,
+        body: "done".to_string(),
+        delay_ms: Some(300),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_hit_line() {
+    let hit = MockHit {
+        at_ms: 1_700_000_000_250,
+        method: "GET".to_string(),
+        path: "/users/7".to_string(),
+        status: 404,
+        latency_ms: 3,
+    };
+    assert_eq!(hit.line(), "22:13:20.250 GET /users/7 -> 404 (3ms)");
+    assert_eq!(
+        serde_json::to_value(&hit).unwrap()["status"],
+        serde_json::json!(404)
+    );
+}
+
+#[tokio::test]
+async fn test_requests_are_logged_and_finish_on_shutdown() {
+    let port = free_port();
+    let server = start_mock_server_with(port, vec![slow_route()], &MockSettings::default(), None);
+    let mut hits = server.subscribe();
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    // Built beforehand: making a client can take longer than the wait below, and a
+    // request that isn't in yet when the server stops is turned away
+    let client = reqwest::Client::new();
+    let request = tokio::spawn(async move {
+        client
+            .get(format!("http://127.0.0.1:{}/slow", port))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap()
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    // The request in flight still gets its answer
+    server.shutdown().await.unwrap();
+    assert_eq!(request.await.unwrap(), "done");
+
+    let hit = hits.recv().await.unwrap();
+    assert_eq!((hit.method.as_str(), hit.path.as_str()), ("GET", "/slow"));
+    assert_eq!(hit.status, 201);
+    assert!(hit.latency_ms >= 300);
+    // No longer listening
+    assert!(
+        reqwest::get(format!("http://127.0.0.1:{}/slow", port))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_headless_exit_codes() {
+    let dir = temp_dir("mock_cli");
+    let routes = dir.join("routes.hcl");
+    std::fs::write(
+        &routes,
+        "route {\n  path = \"/up\"\n  method = \"GET\"\n  status = 200\n  body = \"ok\"\n}\n",
+    )
+    .unwrap();
+    let workspace = Workspace::at(&dir);
+    let args = |routes: &std::path::Path, port: u16| MockArgs {
+        port,
+        routes: Some(routes.to_string_lossy().into_owned()),
+        host: None,
+        json_output: true,
+    };
+
+    // Serves until told to stop, then exits cleanly
+    let port = free_port();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let run = {
+        let args = args(&routes, port);
+        let workspace = workspace.clone();
+        tokio::spawn(async move {
+            cli::serve_mock_cli(args, &workspace, async {
+                let _ = stopped.await;
+            })
+            .await
+        })
+    };
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    let body = reqwest::get(format!("http://127.0.0.1:{}/up", port))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(body, "ok");
+    stop.send(()).unwrap();
+    assert_eq!(run.await.unwrap(), 0);
+
+    // A missing routes file, or a port that's taken
+    let never = std::future::pending::<()>();
+    assert_eq!(
+        cli::serve_mock_cli(args(&dir.join("nope.hcl"), port), &workspace, never).await,
+        1
+    );
+    let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = taken.local_addr().unwrap().port();
+    let never = std::future::pending::<()>();
+    assert_eq!(
+        cli::serve_mock_cli(args(&routes, port), &workspace, never).await,
+        1
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,233 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::workspace::Workspace;
+use crate::net::mock_server::{
+    CorsConfig, MockRoute, MockSettings, TlsConfig, load_routes, load_settings, save_routes,
+    save_settings, start_mock_server_with,
+};
+use crate::net::mock_tls;
+
+fn route() -> MockRoute {
+    MockRoute {
+        path: "/users/:id".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: "{\"id\": \"{{params.id}}\"}".to_string(),
+        ..Default::default()
+    }
+}
+
+fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.as_str())
+}
+
+#[test]
+fn test_cors_headers() {
+    let cors = CorsConfig::default();
+    let headers = cors.response_headers(Some("http://localhost:5173"));
+    assert_eq!(header(&headers, "Access-Control-Allow-Origin"), Some("*"));
+    assert_eq!(header(&headers, "Vary"), None);
+
+    let preflight =
+        cors.preflight_headers(Some("http://localhost:5173"), Some("content-type, x-trace"));
+    assert_eq!(
+        header(&preflight, "Access-Control-Allow-Headers"),
+        Some("content-type, x-trace")
+    );
+    assert_eq!(
+        header(&preflight, "Access-Control-Allow-Methods"),
+        Some("GET, POST, PUT, PATCH, DELETE, OPTIONS")
+    );
+    assert_eq!(header(&preflight, "Access-Control-Max-Age"), Some("600"));
+
+    // Listed origins only, echoed back with credentials
+    let strict = CorsConfig {
+        allow_origins: vec!["http://localhost:5173".to_string()],
+        allow_credentials: true,
+        expose_headers: vec!["X-Total-Count".to_string()],
+        ..Default::default()
+    };
+    let headers = strict.response_headers(Some("http://localhost:5173"));
+    assert_eq!(
+        header(&headers, "Access-Control-Allow-Origin"),
+        Some("http://localhost:5173")
+    );
+    assert_eq!(header(&headers, "Vary"), Some("Origin"));
+    assert_eq!(
+        header(&headers, "Access-Control-Allow-Credentials"),
+        Some("true")
+    );
+    assert_eq!(
+        header(&headers, "Access-Control-Expose-Headers"),
+        Some("X-Total-Count")
+    );
+    assert!(strict.response_headers(Some("http://evil.test")).is_empty());
+    assert!(
+        strict
+            .preflight_headers(Some("http://evil.test"), None)
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_settings_live_next_to_routes() {
+    let dir = temp_dir("mock_settings");
+    let path = dir.join("mocks.hcl").to_string_lossy().into_owned();
+    assert_eq!(load_settings(&path).unwrap(), MockSettings::default());
+
+    save_routes(&path, &[route()]).unwrap();
+    let settings = MockSettings {
+        cors: Some(CorsConfig {
+            allow_origins: vec!["http://localhost:5173".to_string()],
+            ..Default::default()
+        }),
+        tls: Some(TlsConfig::default()),
+        host: None,
+    };
+    save_settings(&path, &settings).unwrap();
+    assert_eq!(load_routes(&path).unwrap(), vec![route()]);
+
+    // Saving routes keeps the settings
+    save_routes(&path, &[]).unwrap();
+    assert_eq!(load_settings(&path).unwrap(), settings);
+    assert!(load_settings(&path).unwrap().tls_on());
+
+    // A bare block means on, with the defaults
+    std::fs::write(&path, "cors {}\n").unwrap();
+    let loaded = load_settings(&path).unwrap();
+    assert_eq!(loaded.cors, Some(CorsConfig::default()));
+    assert_eq!(loaded.base_url(3000), "http://127.0.0.1:3000");
+
+    // A block that doesn't parse stops the save instead of being dropped
+    std::fs::write(&path, "cors {\n  max_age = \"soon\"\n}\n").unwrap();
+    assert!(save_routes(&path, &[route()]).is_err());
+    assert!(std::fs::read_to_string(&path).unwrap().contains("soon"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_preflight_and_cors_headers() {
+    let port = free_port();
+    let settings = MockSettings {
+        cors: Some(CorsConfig::default()),
+        tls: None,
+        host: None,
+    };
+    let server = start_mock_server_with(port, vec![route()], &settings, None);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    let client = reqwest::Client::new();
+    let url = format!("http://127.0.0.1:{}/users/7", port);
+
+    let preflight = client
+        .request(reqwest::Method::OPTIONS, &url)
+        .header("Origin", "http://localhost:5173")
+        .header("Access-Control-Request-Method", "GET")
+        .header("Access-Control-Request-Headers", "authorization")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(preflight.status(), 204);
+    assert_eq!(
+        preflight.headers()["access-control-allow-headers"],
+        "authorization"
+    );
+
+    let resp = client
+        .get(&url)
+        .header("Origin", "http://localhost:5173")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.headers()["access-control-allow-origin"], "*");
+    assert_eq!(resp.text().await.unwrap(), "{\"id\": \"7\"}");
+
+    // Off while running: no headers, and preflights hit the routes (404 here)
+    server.update_cors(None);
+    let resp = client
+        .request(reqwest::Method::OPTIONS, &url)
+        .header("Origin", "http://localhost:5173")
+        .header("Access-Control-Request-Method", "GET")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+    assert!(resp.headers().get("access-control-allow-origin").is_none());
+    server.handle.abort();
+}
+
+#[tokio::test]
+async fn test_https_with_a_self_signed_certificate() {
+    let dir = temp_dir("mock_tls");
+    let identity = mock_tls::identity(&dir, &TlsConfig::default()).unwrap();
+    assert_eq!(identity.created, Some(dir.join(mock_tls::CERT_FILE)));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let key = std::fs::metadata(dir.join(mock_tls::KEY_FILE)).unwrap();
+        assert_eq!(key.permissions().mode() & 0o777, 0o600);
+    }
+    // Made once, then reused
+    let again = mock_tls::identity(&dir, &TlsConfig::default()).unwrap();
+    assert_eq!(again.cert, identity.cert);
+    assert_eq!(again.created, None);
+    let partial = TlsConfig {
+        cert: Some("cert.pem".to_string()),
+        ..Default::default()
+    };
+    assert!(mock_tls::identity(&dir, &partial).is_err());
+
+    let port = free_port();
+    let settings = MockSettings {
+        cors: None,
+        tls: Some(TlsConfig::default()),
+        host: None,
+    };
+    let acceptor = mock_tls::acceptor(&identity.cert, &identity.key).unwrap();
+    let server = start_mock_server_with(port, vec![route()], &settings, Some(acceptor));
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::builder()
+        .add_root_certificate(reqwest::Certificate::from_pem(&identity.cert).unwrap())
+        .build()
+        .unwrap();
+    let url = format!("{}/users/9", settings.base_url(port));
+    assert!(url.starts_with("https://"));
+    let resp = client.get(&url).send().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.text().await.unwrap(), "{\"id\": \"9\"}");
+    server.handle.abort();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_toggle_from_the_manager() {
+    let dir = temp_dir("mock_toggle");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.toggle_mock_cors();
+    assert!(app.mock_settings.cors_on());
+    app.toggle_mock_tls();
+    assert_eq!(
+        app.notifications.history.front().unwrap().message,
+        format!(
+            "Mock server will serve https://127.0.0.1:{}",
+            app.mock_server_port
+        )
+    );
+
+    let reloaded = App::with_workspace(Workspace::at(&dir));
+    assert_eq!(reloaded.mock_settings, app.mock_settings);
+    app.toggle_mock_cors();
+    assert!(!app.mock_settings.cors_on());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,200 @@
+use super::common::temp_dir;
+use crate::app::{MockEditorField, MockRouteEditor};
+use crate::net::mock_server::{
+    Fault, MockRequest, MockRoute, find_route, load_routes, match_path, render_template,
+    save_routes,
+};
+use std::collections::HashMap;
+
+fn route(method: &str, path: &str, body: &str) -> MockRoute {
+    MockRoute {
+        path: path.to_string(),
+        method: method.to_string(),
+        status: 200,
+        body: body.to_string(),
+        headers: HashMap::new(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_match_path_params() {
+    assert_eq!(
+        match_path("/users/:id/posts/:post", "/users/42/posts/7"),
+        Some(vec![
+            ("id".to_string(), "42".to_string()),
+            ("post".to_string(), "7".to_string())
+        ])
+    );
+    assert_eq!(match_path("/users", "/users/"), Some(vec![]));
+    assert_eq!(match_path("/users/:id", "/users"), None);
+    assert_eq!(match_path("/users/:id", "/orders/1"), None);
+}
+
+#[test]
+fn test_literal_routes_win() {
+    let routes = vec![
+        route("GET", "/users/:id", "user {{params.id}}"),
+        route("GET", "/users/me", "me"),
+        route("POST", "/users/:id", "post"),
+    ];
+
+    let (found, _) = find_route(&routes, "GET", "/users/me").unwrap();
+    assert_eq!(found.body, "me");
+
+    let (found, params) = find_route(&routes, "GET", "/users/42").unwrap();
+    let request = MockRequest {
+        params,
+        ..Default::default()
+    };
+    assert_eq!(render_template(&found.body, &request), "user 42");
+
+    assert!(find_route(&routes, "DELETE", "/users/42").is_none());
+}
+
+#[test]
+fn test_routes_roundtrip_hcl() {
+    let path = temp_dir("mocks").join("mocks.hcl");
+    let path = path.to_str().unwrap();
+
+    let mut created = route("POST", "/orders/:id", "{\"id\": \"{{params.id}}\"}");
+    created.status = 201;
+    created
+        .headers
+        .insert("Content-Type".to_string(), "application/json".to_string());
+    let routes = vec![route("GET", "/health", "ok"), created];
+
+    save_routes(path, &routes).unwrap();
+    assert_eq!(load_routes(path).unwrap(), routes);
+    std::fs::remove_file(path).ok();
+
+    assert!(load_routes(path).unwrap().is_empty());
+}
+
+#[test]
+fn test_editor_builds_route() {
+    let mut editor = MockRouteEditor::new_route();
+    editor.path = "/items/:id".to_string();
+    editor.status = "404".to_string();
+    editor.headers = "X-Mock: yes\n\nContent-Type: text/plain".to_string();
+    editor.cycle_method(true);
+    assert_eq!(editor.method, "POST");
+    editor.cycle_method(false);
+    editor.cycle_method(false);
+    assert_eq!(editor.method, "OPTIONS");
+
+    let built = editor.to_route().unwrap();
+    assert_eq!(built.status, 404);
+    assert_eq!(built.headers.get("X-Mock").map(String::as_str), Some("yes"));
+    assert_eq!(built.headers.len(), 2);
+
+    let reopened = MockRouteEditor::from_route(0, &built);
+    assert_eq!(reopened.to_route().unwrap(), built);
+    assert_eq!(reopened.field.next(), MockEditorField::Status);
+
+    editor.status = "999".to_string();
+    assert!(editor.to_route().is_err());
+    editor.status = "200".to_string();
+    editor.path = "items".to_string();
+    assert!(editor.to_route().is_err());
+}
+
+#[test]
+fn test_template_echoes_request() {
+    let request = MockRequest {
+        method: "POST".to_string(),
+        path: "/orders".to_string(),
+        params: vec![],
+        query: vec![("page".to_string(), "3".to_string())],
+        headers: vec![("x-request-id".to_string(), "abc".to_string())],
+        body: r#"{"user": {"name": "Ada"}, "items": [{"sku": "X1"}]}"#.to_string(),
+    };
+    let out = render_template(
+        "{{method}} {{path}} p={{query.page}} id={{headers.X-Request-Id}} \
+         name={{body.user.name}} sku={{body.items[0].sku}} {{unknown.thing}} n={{$randomInt 5 5}}",
+        &request,
+    );
+    assert_eq!(
+        out,
+        "POST /orders p=3 id=abc name=Ada sku=X1 {{unknown.thing}} n=5"
+    );
+}
+
+#[test]
+fn test_faults_and_delay() {
+    let mut chaos = route("GET", "/flaky", "ok");
+    assert_eq!(chaos.fault_for(0.0), Fault::None);
+    assert!(chaos.delay().is_none());
+
+    chaos.drop_rate = Some(5.0);
+    chaos.error_rate = Some(10.0);
+    assert_eq!(chaos.fault_for(4.9), Fault::Drop);
+    assert_eq!(chaos.fault_for(5.0), Fault::Error);
+    assert_eq!(chaos.fault_for(14.9), Fault::Error);
+    assert_eq!(chaos.fault_for(15.0), Fault::None);
+
+    chaos.delay_ms = Some(100);
+    chaos.delay_max_ms = Some(200);
+    let delay = chaos.delay().unwrap().as_millis();
+    assert!((100..=200).contains(&delay));
+}
+
+#[test]
+fn test_editor_chaos_fields() {
+    let mut editor = MockRouteEditor::new_route();
+    editor.delay = "100-400".to_string();
+    editor.error_rate = "10%".to_string();
+    let built = editor.to_route().unwrap();
+    assert_eq!((built.delay_ms, built.delay_max_ms), (Some(100), Some(400)));
+    assert_eq!(built.error_rate, Some(10.0));
+    assert_eq!(built.drop_rate, None);
+    assert_eq!(MockRouteEditor::from_route(0, &built).delay, "100-400");
+
+    editor.drop_rate = "150".to_string();
+    assert!(editor.to_route().is_err());
+}
+
+#[tokio::test]
+async fn test_live_server_echo_and_drop() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let echo = route(
+        "POST",
+        "/echo/:id",
+        "{{params.id}}:{{query.q}}:{{body.name}}",
+    );
+    let mut broken = route("GET", "/broken", "never");
+    broken.drop_rate = Some(100.0);
+    let server = crate::net::mock_server::start_mock_server(port, vec![echo]);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let text = client
+        .post(format!("http://127.0.0.1:{}/echo/7?q=hi", port))
+        .body(r#"{"name": "Ada"}"#)
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(text, "7:hi:Ada");
+
+    // Hot reload: the new route is served without a restart
+    server.update_routes(vec![broken]);
+    let resp = client
+        .get(format!("http://127.0.0.1:{}/broken", port))
+        .send()
+        .await;
+    let body = match resp {
+        Ok(resp) => resp.text().await,
+        Err(e) => Err(e),
+    };
+    assert!(body.is_err());
+
+    server.handle.abort();
+}
@@ -1,6 +1,166 @@
 #[cfg(test)]
+pub mod app_import;
+#[cfg(test)]
+pub mod audit;
+#[cfg(test)]
+pub mod batch;
+#[cfg(test)]
+pub mod bundle;
+#[cfg(test)]
+pub mod cli;
+#[cfg(test)]
 pub mod codegen;
 #[cfg(test)]
+pub mod collection_diff;
+#[cfg(test)]
+pub mod command;
+#[cfg(test)]
+pub mod common;
+#[cfg(test)]
+pub mod concurrent_requests;
+#[cfg(test)]
+pub mod csv_export;
+#[cfg(test)]
+pub mod csv_view;
+#[cfg(test)]
+pub mod curl_import;
+#[cfg(test)]
+pub mod dashboard;
+#[cfg(test)]
+pub mod dedupe;
+#[cfg(test)]
+pub mod doc_gen;
+#[cfg(test)]
+pub mod docs;
+#[cfg(test)]
+pub mod doctor;
+#[cfg(test)]
+pub mod encoding;
+#[cfg(test)]
+pub mod encryption;
+#[cfg(test)]
 pub mod env;
 #[cfg(test)]
+pub mod env_usage;
+#[cfg(test)]
+pub mod events;
+#[cfg(test)]
+pub mod finder;
+#[cfg(test)]
+pub mod format;
+#[cfg(test)]
+pub mod fuzz;
+#[cfg(test)]
+pub mod grpc;
+#[cfg(test)]
+pub mod headers;
+#[cfg(test)]
+pub mod hex;
+#[cfg(test)]
+pub mod highlight;
+#[cfg(test)]
+pub mod history;
+#[cfg(test)]
+pub mod image_view;
+#[cfg(test)]
+pub mod json_diff;
+#[cfg(test)]
+pub mod json_explorer;
+#[cfg(test)]
+pub mod jsonpath_console;
+#[cfg(test)]
+pub mod keymap;
+#[cfg(test)]
+pub mod latency;
+#[cfg(test)]
+pub mod layout;
+#[cfg(test)]
+pub mod line_edit;
+#[cfg(test)]
+pub mod logging;
+#[cfg(test)]
+pub mod mock_cli;
+#[cfg(test)]
+pub mod mock_cors;
+#[cfg(test)]
+pub mod mock_server;
+#[cfg(test)]
+pub mod monitor;
+#[cfg(test)]
+pub mod mouse;
+#[cfg(test)]
+pub mod ndjson;
+#[cfg(test)]
+pub mod notification;
+#[cfg(test)]
+pub mod paginate;
+#[cfg(test)]
+pub mod paste;
+#[cfg(test)]
+pub mod path_params;
+#[cfg(test)]
+pub mod pool;
+#[cfg(test)]
+pub mod postman_script;
+#[cfg(test)]
+pub mod presend;
+#[cfg(test)]
+pub mod profile;
+#[cfg(test)]
+pub mod protos;
+#[cfg(test)]
+pub mod range;
+#[cfg(test)]
+pub mod rate_limit;
+#[cfg(test)]
+pub mod recovery;
+#[cfg(test)]
 pub mod request_building;
+#[cfg(test)]
+pub mod response_dump;
+#[cfg(test)]
+pub mod response_find;
+#[cfg(test)]
+pub mod results_export;
+#[cfg(test)]
+pub mod run_hooks;
+#[cfg(test)]
+pub mod save_tabs;
+#[cfg(test)]
+pub mod script_history;
+#[cfg(test)]
+pub mod snapshot;
+#[cfg(test)]
+pub mod snippets;
+#[cfg(test)]
+pub mod storage;
+#[cfg(test)]
+pub mod streaming;
+#[cfg(test)]
+pub mod stress;
+#[cfg(test)]
+pub mod sync;
+#[cfg(test)]
+pub mod tags;
+#[cfg(test)]
+pub mod template;
+#[cfg(test)]
+pub mod theme;
+#[cfg(test)]
+pub mod timeline;
+#[cfg(test)]
+pub mod timeouts;
+#[cfg(test)]
+pub mod token_refresh;
+#[cfg(test)]
+pub mod trace;
+#[cfg(test)]
+pub mod treat_as;
+#[cfg(test)]
+pub mod variables;
+#[cfg(test)]
+pub mod workspace;
+#[cfg(test)]
+pub mod ws_scenario;
+#[cfg(test)]
+pub mod ws_session;
@@ -0,0 +1,162 @@
+use super::common::temp_dir;
+use crate::domain::workspace::Workspace;
+use crate::features::cli::{self, EnvArgs, MonitorArgs};
+use crate::features::monitor::{Monitor, MonitorSettings};
+use crate::features::runner::RunResult;
+use crate::net::mock_server::{MockRoute, MockSettings, start_mock_server_with};
+
+fn result(name: &str, status: u16, passed: bool) -> RunResult {
+    RunResult {
+        name: name.to_string(),
+        method: "GET".to_string(),
+        url: format!("http://api/{}", name),
+        status: Some(status),
+        latency_ms: Some(100),
+        size: None,
+        expected_status: None,
+        passed,
+        error: None,
+        tests: Vec::new(),
+        response_body: None,
+        response_headers: Default::default(),
+    }
+}
+
+fn route(path: &str, method: &str, status: u16) -> MockRoute {
+    MockRoute {
+        path: path.to_string(),
+        method: method.to_string(),
+        status,
+        body: "ok".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_streaks_and_transitions() {
+    let mut monitor = Monitor::new(MonitorSettings {
+        max_failures: 2,
+        ..Default::default()
+    });
+    let down = monitor.record_round(&[result("Login", 500, false), result("Users", 200, true)]);
+    assert_eq!(down.len(), 1);
+    assert_eq!((down[0].event, down[0].status), ("down", Some(500)));
+    assert_eq!(monitor.breach(), None);
+    assert_eq!(
+        monitor.status_line(&[result("Login", 500, false), result("Users", 200, true)]),
+        "round 1 · 1/2 up · avg 100ms · error rate 50.0% · down: Login (500, 1×)"
+    );
+
+    // Still down: no new transition, but the streak reaches the limit
+    assert!(
+        monitor
+            .record_round(&[result("Login", 500, false)])
+            .is_empty()
+    );
+    assert_eq!(
+        monitor.breach().as_deref(),
+        Some("Login failed 2 rounds in a row")
+    );
+
+    let up = monitor.record_round(&[result("Login", 200, true)]);
+    assert_eq!((up[0].event, up[0].streak), ("up", 2));
+    assert_eq!(monitor.breach(), None);
+    assert_eq!((monitor.total_checks, monitor.total_failed), (4, 2));
+}
+
+#[test]
+fn test_error_rate_window_and_rounds() {
+    let mut monitor = Monitor::new(MonitorSettings {
+        max_failures: 100,
+        max_error_rate: Some(20.0),
+        rounds: Some(12),
+        ..Default::default()
+    });
+    // One request flapping: never two failures in a row, but half the checks fail
+    for i in 0..4 {
+        monitor.record_round(&[result("Flaky", 500, i % 2 == 0)]);
+    }
+    assert_eq!(monitor.error_rate(), 50.0);
+    assert_eq!(
+        monitor.breach().as_deref(),
+        Some("Error rate 50.0% over the last 4 rounds is above 20%")
+    );
+
+    // Only the last 10 rounds count
+    for _ in 0..10 {
+        monitor.record_round(&[result("Flaky", 200, true)]);
+    }
+    assert_eq!(monitor.window.len(), 10);
+    assert_eq!(monitor.breach(), None);
+    assert!(monitor.is_done());
+}
+
+#[tokio::test]
+async fn test_headless_monitor_exit_codes_and_webhook() {
+    let dir = temp_dir("monitor");
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let routes = vec![
+        route("/up", "GET", 200),
+        route("/down", "GET", 503),
+        route("/hook", "POST", 200),
+    ];
+    let server = start_mock_server_with(port, routes, &MockSettings::default(), None);
+    let mut hits = server.subscribe();
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let collection = dir.join("health.hcl");
+    std::fs::write(
+        &collection,
+        format!(
+            "request \"Up\" {{\n  method = \"GET\"\n  url = \"http://127.0.0.1:{0}/up\"\n  tags = [\"ok\"]\n}}\n\n\
+             request \"Down\" {{\n  method = \"GET\"\n  url = \"http://127.0.0.1:{0}/down\"\n}}\n",
+            port
+        ),
+    )
+    .unwrap();
+    let args = |tags: &str, max_failures: u32| MonitorArgs {
+        collection_path: collection.to_string_lossy().into_owned(),
+        env: EnvArgs::default(),
+        settings: MonitorSettings {
+            interval_secs: 1,
+            max_failures,
+            max_error_rate: None,
+            rounds: Some(1),
+        },
+        webhook: Some(format!("http://127.0.0.1:{}/hook", port)),
+        tags: crate::domain::collection::parse_tags(tags),
+        json_output: true,
+        rate_limit: Default::default(),
+    };
+    let workspace = Workspace::at(&dir);
+    let never = || std::future::pending::<()>();
+
+    // The healthy request alone: one round, nothing crossed
+    assert_eq!(
+        cli::monitor_cli(args("ok", 1), &workspace, never()).await,
+        0
+    );
+    // Everything: /down fails its first round and that's the limit
+    assert_eq!(cli::monitor_cli(args("", 1), &workspace, never()).await, 1);
+    // Stopped before it got to cross anything
+    assert_eq!(cli::monitor_cli(args("", 3), &workspace, async {}).await, 0);
+    assert_eq!(
+        cli::monitor_cli(args("nightly", 1), &workspace, never()).await,
+        1
+    );
+    server.handle.abort();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    // The down transition and the breach were both posted
+    let mut posted = 0;
+    while let Ok(hit) = hits.try_recv() {
+        if hit.path == "/hook" {
+            posted += 1;
+        }
+    }
+    assert_eq!(posted, 2);
+}
@@ -0,0 +1,332 @@
+use super::common::temp_dir;
+use crate::app::{App, InputMode, JsonEntry, RequestLog};
+use crate::domain::collection::{Collection, RequestConfig};
+use crate::domain::workspace::Workspace;
+use crate::handler::handle_mouse_event;
+use crate::ui::layout;
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn test_app(dir: &std::path::Path) -> App {
+    let mut app = App::with_workspace(Workspace::at(dir));
+    app.show_splash = false;
+    app
+}
+
+/// Draws a frame so the app knows where things are
+fn draw(app: &mut App, width: u16, height: u16) {
+    let mut terminal =
+        ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+    terminal.draw(|f| crate::ui::render(f, app)).unwrap();
+}
+
+fn mouse(app: &mut App, kind: MouseEventKind, column: u16, row: u16) {
+    handle_mouse_event(
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        },
+        app,
+    );
+}
+
+fn click(app: &mut App, column: u16, row: u16) {
+    mouse(app, MouseEventKind::Down(MouseButton::Left), column, row);
+    mouse(app, MouseEventKind::Up(MouseButton::Left), column, row);
+}
+
+fn click_in(app: &mut App, area: Rect) {
+    click(app, area.x + area.width / 2, area.y);
+}
+
+fn request(method: &str, url: &str) -> RequestConfig {
+    RequestConfig {
+        method: method.to_string(),
+        url: url.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_tab_titles_match_what_ratatui_draws() {
+    let titles = ["1 first", "2 名前", "3 third"];
+    let area = Rect::new(4, 2, 60, 3);
+    let mut buffer = ratatui::buffer::Buffer::empty(Rect::new(0, 0, 80, 6));
+    ratatui::widgets::Widget::render(
+        ratatui::widgets::Tabs::new(titles.to_vec()).block(ratatui::widgets::Block::bordered()),
+        area,
+        &mut buffer,
+    );
+    let rects = layout::tab_titles(area, titles.iter().map(|t| layout::width(t)));
+    for (title, rect) in titles.iter().zip(&rects) {
+        let drawn: String = (rect.x..rect.right())
+            .map(|x| buffer[(x, rect.y)].symbol())
+            .collect();
+        // The cell after a wide character holds a blank
+        assert_eq!(drawn.replace(' ', ""), title.replace(' ', ""));
+        assert_eq!(rect.width, layout::width(title) + 2);
+    }
+    // Past the right border they're cut off, then empty
+    let cramped = layout::tab_titles(Rect::new(0, 0, 12, 3), [6, 6, 6]);
+    assert_eq!(cramped[0], Rect::new(1, 1, 8, 1));
+    assert_eq!(cramped[1].width, 1);
+    assert_eq!(cramped[2].width, 0);
+}
+
+#[test]
+fn test_click_switches_tabs() {
+    let dir = temp_dir("mouse_tabs");
+    let mut app = test_app(&dir);
+    app.add_tab();
+    app.active_tab = 0;
+    draw(&mut app, 120, 40);
+
+    let second = app.screen_areas.open_tabs[1];
+    click_in(&mut app, second);
+    assert_eq!(app.active_tab, 1);
+
+    let headers = app.screen_areas.config_tabs[1];
+    click_in(&mut app, headers);
+    assert_eq!(app.active_tab().selected_tab, 1);
+
+    // Something open over the screen keeps clicks from reaching through
+    app.show_command_palette = true;
+    let first = app.screen_areas.open_tabs[0];
+    click_in(&mut app, first);
+    assert_eq!(app.active_tab, 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_sidebar_click_selects_then_opens() {
+    let dir = temp_dir("mouse_sidebar");
+    let mut app = test_app(&dir);
+    app.collections = vec![Collection {
+        name: "users".to_string(),
+        requests: HashMap::from([
+            (
+                "Create user".to_string(),
+                request("POST", "https://api.example.com/users"),
+            ),
+            (
+                "Delete user".to_string(),
+                request("DELETE", "https://api.example.com/users/1"),
+            ),
+        ]),
+        ws_scenarios: HashMap::new(),
+        description: None,
+        variables: HashMap::new(),
+        snapshots: Default::default(),
+    }];
+    draw(&mut app, 120, 40);
+    let list = app.screen_areas.sidebar_list.unwrap();
+
+    // Row 0 is the "Collections" heading, 1 and 2 the requests in name order
+    click(&mut app, list.x + 3, list.y + 3);
+    assert!(app.active_sidebar);
+    assert_eq!(app.collection_state.selected(), Some(2));
+    click(&mut app, list.x + 3, list.y + 3);
+    assert_eq!(app.active_tab().url, "https://api.example.com/users/1");
+
+    mouse(&mut app, MouseEventKind::ScrollUp, list.x + 3, list.y + 3);
+    assert_eq!(app.collection_state.selected(), Some(1));
+    // Stops at the ends instead of wrapping
+    for _ in 0..5 {
+        mouse(&mut app, MouseEventKind::ScrollDown, list.x + 3, list.y + 3);
+    }
+    assert_eq!(app.collection_state.selected(), Some(2));
+
+    // A click below the last row selects nothing
+    click(&mut app, list.x + 3, list.bottom() - 2);
+    assert_eq!(app.collection_state.selected(), Some(2));
+
+    app.request_history = vec![RequestLog {
+        method: "GET".to_string(),
+        url: "https://api.example.com/orders".to_string(),
+        status: 200,
+        latency: 42,
+        body: None,
+        headers: HashMap::new(),
+        response_bytes: None,
+        is_binary: false,
+        timestamp: 0,
+        pinned: false,
+        request: None,
+        size: 0,
+        tests: Vec::new(),
+    }];
+    draw(&mut app, 120, 40);
+    // Blank line and "History" heading after the collection, then the entry
+    click(&mut app, list.x + 3, list.y + 6);
+    click(&mut app, list.x + 3, list.y + 6);
+    assert_eq!(app.active_tab().url, "https://api.example.com/orders");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_response_click_and_wheel() {
+    let dir = temp_dir("mouse_response");
+    let mut app = test_app(&dir);
+    let items: Vec<_> = (0..30).map(|i| json!({ "id": i })).collect();
+    app.active_tab_mut()
+        .set_response_json(Some(vec![JsonEntry::from_value(
+            "root".to_string(),
+            &json!({ "items": items }),
+            0,
+        )]));
+    draw(&mut app, 120, 40);
+    let response = app.screen_areas.response.unwrap();
+
+    mouse(
+        &mut app,
+        MouseEventKind::ScrollDown,
+        response.x + 5,
+        response.y + 5,
+    );
+    assert_eq!(app.active_tab().json_list_state.selected(), Some(3));
+
+    // Rows count from the list's first line on screen
+    click(&mut app, response.x + 5, response.y + 2);
+    assert_eq!(app.active_tab().json_list_state.selected(), Some(1));
+    let rows = app.calculate_visible_item_count();
+    // A second click on the selected row collapses it, and expands it again
+    click(&mut app, response.x + 5, response.y + 2);
+    assert!(app.calculate_visible_item_count() < rows);
+    click(&mut app, response.x + 5, response.y + 2);
+    assert_eq!(app.calculate_visible_item_count(), rows);
+
+    // Text responses scroll by lines
+    app.active_tab_mut().set_response_json(None);
+    app.active_tab_mut().response = Some("line\n".repeat(100));
+    draw(&mut app, 120, 40);
+    mouse(
+        &mut app,
+        MouseEventKind::ScrollDown,
+        response.x + 5,
+        response.y + 5,
+    );
+    mouse(
+        &mut app,
+        MouseEventKind::ScrollDown,
+        response.x + 5,
+        response.y + 5,
+    );
+    assert_eq!(app.active_tab().response_scroll.0, 6);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_config_rows_and_url_click() {
+    let dir = temp_dir("mouse_config");
+    let mut app = test_app(&dir);
+    app.active_tab_mut().url = "https://名前.example.com/a".to_string();
+    app.active_tab_mut().params = vec![
+        ("a".to_string(), "1".to_string()),
+        ("b".to_string(), "2".to_string()),
+    ];
+    draw(&mut app, 120, 40);
+    let config = app.screen_areas.main.unwrap().config.unwrap();
+    app.active_sidebar = true;
+    click(&mut app, config.x + 4, config.y + 2);
+    assert!(!app.active_sidebar);
+    assert_eq!(app.active_tab().params_list_state.selected(), Some(1));
+    mouse(
+        &mut app,
+        MouseEventKind::ScrollUp,
+        config.x + 4,
+        config.y + 2,
+    );
+    assert_eq!(app.active_tab().params_list_state.selected(), Some(0));
+
+    // "https://" is 8 columns, "名" 2 more: the click lands on "前"
+    let start = app.screen_areas.url_text.unwrap();
+    let url = app.screen_areas.main.unwrap().url;
+    click(&mut app, start + 10, url.y + 1);
+    assert_eq!(app.active_tab().input_mode, InputMode::Editing);
+    assert_eq!(app.active_tab().url_cursor_index, "https://名".len());
+    assert_eq!(layout::byte_at("名前", 1), 0);
+    assert_eq!(layout::byte_at("名前", 99), "名前".len());
+
+    // While typing, clicks elsewhere don't pull focus away
+    click(&mut app, config.x + 4, config.y + 1);
+    assert_eq!(app.active_tab().input_mode, InputMode::Editing);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_drag_resizes_sidebar_and_is_kept() {
+    let dir = temp_dir("mouse_drag");
+    let mut app = test_app(&dir);
+    draw(&mut app, 100, 30);
+    let edge = app.screen_areas.sidebar_edge().unwrap();
+    assert_eq!(edge, 19);
+
+    mouse(&mut app, MouseEventKind::Down(MouseButton::Left), edge, 10);
+    assert!(app.dragging_sidebar);
+    mouse(&mut app, MouseEventKind::Drag(MouseButton::Left), 34, 10);
+    assert_eq!(app.sidebar_width, 35);
+    mouse(&mut app, MouseEventKind::Drag(MouseButton::Left), 90, 10);
+    assert_eq!(app.sidebar_width, layout::MAX_SIDEBAR_PERCENT);
+    mouse(&mut app, MouseEventKind::Drag(MouseButton::Left), 2, 10);
+    assert_eq!(app.sidebar_width, layout::MIN_SIDEBAR_PERCENT);
+    mouse(&mut app, MouseEventKind::Drag(MouseButton::Left), 29, 10);
+    mouse(&mut app, MouseEventKind::Up(MouseButton::Left), 29, 10);
+    assert!(!app.dragging_sidebar);
+
+    draw(&mut app, 100, 30);
+    assert_eq!(app.screen_areas.main.unwrap().sidebar.unwrap().width, 30);
+
+    // Saved with the rest of the settings
+    let reopened = test_app(&dir);
+    assert_eq!(reopened.sidebar_width, 30);
+
+    // A sidebar stacked over the request has no edge to drag
+    draw(&mut app, 80, 30);
+    assert_eq!(app.screen_areas.sidebar_edge(), None);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_websocket_clicks_and_wheel() {
+    let dir = temp_dir("mouse_ws");
+    let mut app = test_app(&dir);
+    app.active_tab_mut().app_mode = crate::app::AppMode::WebSocket;
+    for i in 0..5 {
+        app.active_tab_mut()
+            .ws_messages
+            .push(crate::net::websocket::WsMessage::new(
+                format!("message {}", i),
+                false,
+            ));
+    }
+    draw(&mut app, 100, 30);
+    let ws = app.screen_areas.ws.unwrap();
+    assert!(app.screen_areas.main.is_none());
+
+    mouse(
+        &mut app,
+        MouseEventKind::ScrollDown,
+        ws.messages.x + 5,
+        ws.messages.y + 3,
+    );
+    assert_eq!(app.active_tab().ws_scroll, 1);
+    click(&mut app, ws.messages.x + 5, ws.messages.y + 4);
+    assert_eq!(app.active_tab().ws_scroll, 3);
+    for _ in 0..10 {
+        mouse(
+            &mut app,
+            MouseEventKind::ScrollDown,
+            ws.messages.x + 5,
+            ws.messages.y + 3,
+        );
+    }
+    assert_eq!(app.active_tab().ws_scroll, 4);
+
+    click(&mut app, ws.input.x + 5, ws.input.y + 1);
+    assert_eq!(app.active_tab().input_mode, InputMode::EditingWsMessage);
+    let _ = std::fs::remove_dir_all(&dir);
+}
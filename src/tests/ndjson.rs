@@ -0,0 +1,82 @@
+use super::common::press;
+use crate::app::App;
+use crate::features::ndjson;
+use crossterm::event::KeyCode;
+
+const BODY: &str = "{\"id\":1,\"level\":\"info\"}\n\n{\"id\":2,\"level\":\"error\",\"msg\":\"disk full\"}\n{\"id\":3,\"level\":\"info\"}\n";
+
+#[test]
+fn test_detects_ndjson() {
+    assert!(ndjson::is_ndjson("application/x-ndjson", "{}"));
+    assert!(ndjson::is_ndjson("application/jsonl; charset=utf-8", ""));
+    // Sniffed when the content type doesn't say
+    assert!(ndjson::is_ndjson("application/json", BODY));
+    assert!(!ndjson::is_ndjson("application/json", "{\"id\":1}\n"));
+    assert!(!ndjson::is_ndjson("application/json", "[1,\n2]"));
+    assert!(!ndjson::is_ndjson("text/plain", "1\n2\n"));
+    assert!(!ndjson::is_ndjson("text/plain", "{\"a\":1}\nnot json\n"));
+}
+
+#[test]
+fn test_parse_and_filter_lines() {
+    let lines = ndjson::parse(&format!("{}\u{1e}{{\"id\":4}}\n{{broken\n", BODY));
+    let numbers: Vec<usize> = lines.iter().map(|l| l.number).collect();
+    assert_eq!(numbers, vec![1, 3, 4, 5, 6]);
+    assert_eq!(lines[3].value, Ok(serde_json::json!({"id": 4})));
+    assert!(lines[4].value.is_err());
+
+    assert_eq!(ndjson::filter(&lines, "ERROR"), vec![1]);
+    assert_eq!(ndjson::filter(&lines, "").len(), 5);
+    assert!(ndjson::filter(&lines, "warn").is_empty());
+}
+
+#[test]
+fn test_line_list_opens_records_in_the_tree() {
+    let mut app = App::new();
+    app.show_splash = false;
+    {
+        let tab = app.active_tab_mut();
+        tab.response = Some(BODY.to_string());
+        tab.response_headers.insert(
+            "content-type".to_string(),
+            "application/x-ndjson".to_string(),
+        );
+        tab.detect_ndjson();
+        assert_eq!(tab.response_ndjson.as_ref().unwrap().len(), 3);
+    }
+
+    press(&mut app, KeyCode::Char('L'));
+    assert!(app.show_ndjson);
+    press(&mut app, KeyCode::Char('/'));
+    for c in "info".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.ndjson_rows(), vec![0, 2]);
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Enter);
+
+    assert!(!app.show_ndjson);
+    let tab = app.active_tab();
+    assert_eq!(tab.ndjson_record, Some(4));
+    let tree = tab.response_json.as_ref().unwrap();
+    assert_eq!(tree[0].key, "line 4");
+    assert_eq!(tree[0].children.len(), 2);
+
+    // Back to the list, on the same record; Esc clears the filter, then closes
+    press(&mut app, KeyCode::Char('L'));
+    assert_eq!(app.ndjson_state.selected(), Some(1));
+    press(&mut app, KeyCode::Esc);
+    assert!(app.ndjson_filter.is_empty());
+    press(&mut app, KeyCode::Esc);
+    assert!(!app.show_ndjson);
+
+    // A plain JSON response has no line list
+    let tab = app.active_tab_mut();
+    tab.response = Some("{\"id\":1}".to_string());
+    tab.response_headers
+        .insert("content-type".to_string(), "application/json".to_string());
+    tab.detect_ndjson();
+    assert!(tab.response_ndjson.is_none());
+    assert!(tab.ndjson_record.is_none());
+}
@@ -0,0 +1,55 @@
+use crate::domain::notification::{NotificationCenter, NotificationLevel, NotificationTimeouts};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_notifications_stack_instead_of_overwriting() {
+    let mut center = NotificationCenter::default();
+    center.push("Request failed".to_string(), NotificationLevel::Error);
+    center.push("Copied!".to_string(), NotificationLevel::Success);
+
+    let visible: Vec<&str> = center.visible().map(|n| n.message.as_str()).collect();
+    assert_eq!(visible, vec!["Copied!", "Request failed"]);
+    assert_eq!(center.history.len(), 2);
+}
+
+#[test]
+fn test_notifications_expire_per_level() {
+    let mut center = NotificationCenter::default();
+    center.push("info".to_string(), NotificationLevel::Info);
+    center.push("boom".to_string(), NotificationLevel::Error);
+
+    // Info is gone after its 3s, the error sticks around for 8s
+    center.expire(Instant::now() + Duration::from_secs(5));
+    let visible: Vec<&str> = center.visible().map(|n| n.message.as_str()).collect();
+    assert_eq!(visible, vec!["boom"]);
+
+    center.expire(Instant::now() + Duration::from_secs(9));
+    assert_eq!(center.visible().count(), 0);
+
+    // History keeps everything
+    assert_eq!(center.history.len(), 2);
+}
+
+#[test]
+fn test_zero_timeout_keeps_notification_until_dismissed() {
+    let mut center = NotificationCenter {
+        timeouts: NotificationTimeouts {
+            error_secs: 0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    center.push("sticky".to_string(), NotificationLevel::Error);
+    center.expire(Instant::now() + Duration::from_secs(3600));
+    assert_eq!(center.visible().count(), 1);
+
+    center.dismiss();
+    assert_eq!(center.visible().count(), 0);
+}
+
+#[test]
+fn test_timeouts_default_when_missing_from_config() {
+    let timeouts: NotificationTimeouts = serde_json::from_str(r#"{"error_secs": 20}"#).unwrap();
+    assert_eq!(timeouts.error_secs, 20);
+    assert_eq!(timeouts.info_secs, 3);
+}
@@ -0,0 +1,406 @@
+use super::common::last_message;
+use crate::app::App;
+use crate::domain::collection::Collection;
+use crate::features::paginate::{
+    Next, PaginateConfig, PaginateEvent, PaginationResult, apply_command, first_url, next_url,
+    page_items, page_request, run_pagination,
+};
+use crate::net::mock_server::MockRoute;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn config(args: &[&str]) -> PaginateConfig {
+    let mut config = PaginateConfig::default();
+    for a in args {
+        apply_command(&mut config, a).unwrap();
+    }
+    config
+}
+
+#[test]
+fn test_commands_set_one_way_to_the_next_page() {
+    let mut config = PaginateConfig::default();
+    assert_eq!(
+        apply_command(&mut config, "next $.links.next").unwrap(),
+        "next $.links.next, max 50 pages"
+    );
+    apply_command(&mut config, "items $.data").unwrap();
+    let summary = apply_command(&mut config, "page p 0 10").unwrap();
+    assert_eq!(summary, "page p from 0 by 10, items $.data, max 50 pages");
+    assert_eq!(config.next, None);
+    apply_command(&mut config, "cursor X-Next-Cursor cursor").unwrap();
+    assert_eq!(config.page_param, None);
+    apply_command(&mut config, "more $.has_more").unwrap();
+    apply_command(&mut config, "max 5").unwrap();
+    assert_eq!(
+        config.describe(),
+        "cursor X-Next-Cursor -> cursor, items $.data, while $.has_more, max 5 pages"
+    );
+
+    for bad in [
+        "cursor X-Next",
+        "page p one",
+        "max 0",
+        "next $[",
+        "sideways",
+    ] {
+        assert!(apply_command(&mut config, bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn test_items() {
+    let by_path = config(&["items $.data"]);
+    assert_eq!(
+        page_items(&by_path, r#"{"data": [1, 2], "meta": [9]}"#).unwrap(),
+        vec![json!(1), json!(2)]
+    );
+    assert_eq!(
+        page_items(&by_path, r#"{"data": []}"#).unwrap(),
+        Vec::<serde_json::Value>::new()
+    );
+    let guess = PaginateConfig::default();
+    assert_eq!(page_items(&guess, "[3]").unwrap(), vec![json!(3)]);
+    assert_eq!(
+        page_items(&guess, r#"{"users": [4], "total": 1}"#).unwrap(),
+        vec![json!(4)]
+    );
+    assert!(page_items(&guess, r#"{"a": [], "b": []}"#).is_err());
+    assert!(page_items(&guess, "<html>").is_err());
+}
+
+#[test]
+fn test_next_page() {
+    let headers = HashMap::new();
+    let next = config(&["next $.next"]);
+    assert_eq!(
+        next_url(
+            &next,
+            1,
+            "https://api.test/v1/items?x=1",
+            r#"{"next": "/v1/items?after=9"}"#,
+            &headers
+        ),
+        Next::Page("https://api.test/v1/items?after=9".to_string())
+    );
+    assert_eq!(
+        next_url(&next, 1, "https://api.test/", r#"{"next": null}"#, &headers),
+        Next::Last("no next page at $.next".to_string())
+    );
+
+    let pages = config(&["page page", "more $.has_more"]);
+    assert_eq!(
+        first_url(&pages, "https://api.test/items?page=7&q=a").unwrap(),
+        "https://api.test/items?q=a&page=1"
+    );
+    assert_eq!(
+        next_url(
+            &pages,
+            1,
+            "https://api.test/items?q=a&page=1",
+            r#"{"has_more": true}"#,
+            &headers
+        ),
+        Next::Page("https://api.test/items?q=a&page=2".to_string())
+    );
+    assert_eq!(
+        next_url(
+            &pages,
+            2,
+            "https://api.test/items?page=2",
+            r#"{"has_more": false}"#,
+            &headers
+        ),
+        Next::Last("$.has_more says it's the last page".to_string())
+    );
+
+    let cursor = config(&["cursor x-next-cursor after"]);
+    let with_cursor = HashMap::from([("X-Next-Cursor".to_string(), "abc".to_string())]);
+    assert_eq!(
+        next_url(&cursor, 1, "https://api.test/items", "", &with_cursor),
+        Next::Page("https://api.test/items?after=abc".to_string())
+    );
+    assert_eq!(
+        next_url(&cursor, 1, "https://api.test/items", "", &headers),
+        Next::Last("no x-next-cursor header".to_string())
+    );
+
+    let link = config(&["cursor Link"]);
+    let with_link = HashMap::from([(
+        "link".to_string(),
+        r#"<https://api.test/items?page=1>; rel="prev", <https://api.test/items?page=3>; rel="next""#
+            .to_string(),
+    )]);
+    assert_eq!(
+        next_url(&link, 2, "https://api.test/items?page=2", "", &with_link),
+        Next::Page("https://api.test/items?page=3".to_string())
+    );
+
+    // A step that would take the page number past i64 stops instead of overflowing
+    let huge = config(&["page p 1 9223372036854775807"]);
+    assert_eq!(
+        next_url(&huge, 1, "https://api.test/items?p=1", "", &headers),
+        Next::Failed("page 2 is past the largest page number".to_string())
+    );
+}
+
+#[test]
+fn test_saved_with_the_request() {
+    let collection = Collection::from_hcl(
+        "paged",
+        "request \"List\" {\n  method = \"GET\"\n  url = \"https://api.test/items\"\n  \
+         paginate = {\n    next = \"$.links.next\"\n    items = \"$.data\"\n    max_pages = 3\n  }\n}\n",
+    )
+    .unwrap();
+    let paginate = collection.requests["List"].paginate.clone().unwrap();
+    assert_eq!(paginate.next.as_deref(), Some("$.links.next"));
+    assert_eq!(paginate.max_pages(), 3);
+
+    let mut app = App::new();
+    app.show_splash = false;
+    app.apply_request_config(collection.requests["List"].clone());
+    assert_eq!(app.active_tab().paginate, Some(paginate.clone()));
+    let hcl = hcl::to_string(&app.active_tab().to_request_config()).unwrap();
+    let back =
+        Collection::from_hcl("again", &format!("request \"List\" {{\n{}\n}}\n", hcl)).unwrap();
+    assert_eq!(back.requests["List"].paginate, Some(paginate));
+
+    app.paginate_command("off");
+    assert_eq!(app.active_tab().paginate, None);
+    app.paginate_command("");
+    assert!(last_message(&app).starts_with("Set how to find the next page first"));
+    assert!(!app.should_paginate);
+    app.paginate_command("page p");
+    assert_eq!(
+        last_message(&app),
+        "Pages: page p from 1 by 1, max 50 pages"
+    );
+    app.paginate_command("");
+    assert!(app.should_paginate);
+}
+
+#[tokio::test]
+async fn test_walks_pages_into_one_response() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let route = |path: &str, body: &str| MockRoute {
+        path: path.to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: body.to_string(),
+        ..Default::default()
+    };
+    let routes = vec![
+        route("/p1", r#"{"data": [{"id": 1}, {"id": 2}], "next": "/p2"}"#),
+        route("/p2", r#"{"data": [{"id": 3}], "next": "/p3"}"#),
+        route("/p3", r#"{"data": [], "next": "/p4"}"#),
+    ];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let mut app = App::new();
+    app.show_splash = false;
+    app.active_tab_mut().url = format!("http://127.0.0.1:{}/p1", port);
+    app.paginate_command("next $.next");
+    app.paginate_command("items $.data");
+    app.paginate_command("");
+    assert!(app.should_paginate);
+    let job = app.pagination_request().unwrap();
+    assert!(app.active_tab().is_loading);
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    run_pagination(job, tx).await;
+    server.handle.abort();
+    let Some(PaginateEvent::Done(result)) = rx.recv().await else {
+        panic!("no result");
+    };
+    assert_eq!(result.pages, 3);
+    assert_eq!(result.stopped, "page 3 was empty");
+    assert_eq!(result.error, None);
+
+    app.pagination_done(result);
+    let tab = app.active_tab();
+    assert!(!tab.is_loading);
+    assert_eq!(tab.status_code, Some(200));
+    let body: serde_json::Value = serde_json::from_str(tab.response.as_deref().unwrap()).unwrap();
+    assert_eq!(body, json!([{"id": 1}, {"id": 2}, {"id": 3}]));
+    assert_eq!(tab.response_headers["x-postdad-items"], "3");
+    assert_eq!(
+        last_message(&app),
+        "Fetched 3 pages, 3 items (page 3 was empty)"
+    );
+}
+
+#[tokio::test]
+async fn test_stops_on_repeats_limits_and_errors() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let routes = vec![
+        MockRoute {
+            path: "/a".to_string(),
+            method: "GET".to_string(),
+            status: 200,
+            body: r#"[1, 2]"#.to_string(),
+            headers: HashMap::from([("Link".to_string(), "</b>; rel=\"next\"".to_string())]),
+            ..Default::default()
+        },
+        MockRoute {
+            path: "/b".to_string(),
+            method: "GET".to_string(),
+            status: 200,
+            body: r#"[3]"#.to_string(),
+            headers: HashMap::from([("Link".to_string(), "</a>; rel=\"next\"".to_string())]),
+            ..Default::default()
+        },
+    ];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let mut app = App::new();
+    app.show_splash = false;
+    app.active_tab_mut().url = format!("http://127.0.0.1:{}/a", port);
+    app.paginate_command("cursor Link");
+    let job = app.pagination_request().unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    run_pagination(job, tx).await;
+    let Some(PaginateEvent::Done(result)) = rx.recv().await else {
+        panic!("no result");
+    };
+    // /b links back to /a: that's not fetched again
+    assert_eq!(result.pages, 2);
+    assert_eq!(result.items, vec![json!(1), json!(2), json!(3)]);
+    assert_eq!(result.stopped, "the next page is one already fetched");
+
+    // Page numbers go on until max_pages
+    app.active_tab_mut().url = format!("http://127.0.0.1:{}/a", port);
+    app.paginate_command("page n");
+    app.paginate_command("max 3");
+    let job = app.pagination_request().unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    run_pagination(job, tx).await;
+    let Some(PaginateEvent::Done(result)) = rx.recv().await else {
+        panic!("no result");
+    };
+    assert_eq!(result.pages, 3);
+    assert_eq!(result.stopped, "stopped at 3 pages");
+    app.pagination_done(result);
+    assert_eq!(
+        last_message(&app),
+        "Fetched 3 pages, 6 items (stopped at 3 pages)"
+    );
+
+    app.active_tab_mut().url = format!("http://127.0.0.1:{}/missing", port);
+    let job = app.pagination_request().unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    run_pagination(job, tx).await;
+    server.handle.abort();
+    let Some(PaginateEvent::Done(result)) = rx.recv().await else {
+        panic!("no result");
+    };
+    app.pagination_done(result);
+    assert_eq!(last_message(&app), "Paging failed: page 1: HTTP 404");
+}
+
+#[test]
+fn test_result_reaches_its_tab_after_another_closes() {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.add_tab();
+    app.active_tab_mut().url = "http://127.0.0.1:9/items".to_string();
+    app.paginate_command("page n");
+    let request_id = app.pagination_request().unwrap().request_id;
+    let paging = app.active_tab().name.clone();
+
+    app.active_tab = 0;
+    app.close_tab();
+    app.pagination_done(PaginationResult {
+        request_id,
+        pages: 1,
+        items: vec![json!(1)],
+        status: Some(200),
+        headers: HashMap::new(),
+        latency_ms: 5,
+        stopped: "page 2 was empty".to_string(),
+        error: None,
+    });
+    let tab = &app.tabs[0];
+    assert_eq!(tab.name, paging);
+    assert!(!tab.is_loading);
+    assert_eq!(tab.response.as_deref(), Some("[\n  1\n]"));
+}
+
+#[test]
+fn test_credentials_stay_on_the_origin() {
+    let request = crate::app::OutgoingRequest {
+        method: "GET".to_string(),
+        url: "https://api.example.com/items".to_string(),
+        headers: HashMap::from([
+            ("Authorization".to_string(), "Bearer t".to_string()),
+            ("Cookie".to_string(), "sid=1".to_string()),
+            ("Accept".to_string(), "application/json".to_string()),
+        ]),
+        body: None,
+        form_data: None,
+        auth: Some(crate::net::http::AuthPayload::Bearer("t".to_string())),
+    };
+    let same = page_request(&request, "https://api.example.com/items?page=2");
+    assert_eq!(same.headers.len(), 3);
+    assert!(same.auth.is_some());
+
+    for url in [
+        "https://cdn.example.net/items?page=2",
+        "http://api.example.com/items?page=2",
+        "https://api.example.com:8443/items?page=2",
+    ] {
+        let other = page_request(&request, url);
+        assert_eq!(other.url, url);
+        assert!(other.auth.is_none());
+        assert_eq!(other.headers.keys().collect::<Vec<_>>(), vec!["Accept"]);
+    }
+}
+
+#[tokio::test]
+async fn test_pages_go_through_the_proxy() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let route = |path: &str, body: &str| MockRoute {
+        path: path.to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: body.to_string(),
+        ..Default::default()
+    };
+    // The mock server is the proxy; the host itself doesn't resolve
+    let routes = vec![
+        route("/p1", r#"{"data": [1], "next": "/p2"}"#),
+        route("/p2", r#"{"data": [2]}"#),
+    ];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let mut app = App::new();
+    app.proxy_url = Some(format!("http://127.0.0.1:{}", port));
+    app.active_tab_mut().url = "http://paged-proxy.invalid/p1".to_string();
+    app.paginate_command("next $.next");
+    app.paginate_command("items $.data");
+    let job = app.pagination_request().unwrap();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    run_pagination(job, tx).await;
+    server.handle.abort();
+    let Some(PaginateEvent::Done(result)) = rx.recv().await else {
+        panic!("no result");
+    };
+    assert_eq!(result.error, None);
+    assert_eq!(result.pages, 2);
+    assert_eq!(result.items, vec![json!(1), json!(2)]);
+}
@@ -0,0 +1,64 @@
+use crate::app::{App, InputMode};
+use crate::handler::handle_paste;
+
+#[test]
+fn test_paste_into_url_at_cursor() {
+    let mut app = App::new();
+    {
+        let tab = app.active_tab_mut();
+        tab.url = "https://api.example.com/users".to_string();
+        tab.url_cursor_index = "https://".len();
+        tab.input_mode = InputMode::Editing;
+    }
+    assert!(handle_paste("staging.\n", &mut app));
+
+    let tab = app.active_tab();
+    assert_eq!(tab.url, "https://staging.api.example.com/users");
+    assert_eq!(tab.url_cursor_index, "https://staging.".len());
+}
+
+#[test]
+fn test_paste_joins_wrapped_token() {
+    let mut app = App::new();
+    app.active_tab_mut().input_mode = InputMode::EditingAuth;
+    handle_paste("eyJhbGciOi\r\nJIUzI1NiJ9\n", &mut app);
+    assert_eq!(app.active_tab().auth_token, "eyJhbGciOiJIUzI1NiJ9");
+}
+
+#[test]
+fn test_paste_into_param_value_updates_url() {
+    let mut app = App::new();
+    {
+        let tab = app.active_tab_mut();
+        tab.url = "https://api.example.com/search".to_string();
+        tab.params = vec![("q".to_string(), String::new())];
+        tab.params_list_state.select(Some(0));
+        tab.input_mode = InputMode::EditingParamValue;
+    }
+    handle_paste("rust", &mut app);
+    assert_eq!(
+        app.active_tab().url,
+        "https://api.example.com/search?q=rust"
+    );
+}
+
+#[test]
+fn test_paste_respects_field_kind() {
+    let mut app = App::new();
+
+    app.active_tab_mut().input_mode = InputMode::EditingStressVUs;
+    app.stress_vus_input.clear();
+    handle_paste(" 1,000\n", &mut app);
+    assert_eq!(app.stress_vus_input, "1000");
+
+    // The curl box keeps its line continuations
+    app.active_tab_mut().input_mode = InputMode::ImportCurl;
+    handle_paste("curl https://example.com \\\r\n  -H 'A: b'", &mut app);
+    assert_eq!(
+        app.curl_import_input,
+        "curl https://example.com \\\n  -H 'A: b'"
+    );
+
+    app.active_tab_mut().input_mode = InputMode::Normal;
+    assert!(!handle_paste("ignored", &mut app));
+}
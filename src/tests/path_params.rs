@@ -0,0 +1,98 @@
+use super::common::press;
+use crate::app::{App, InputMode};
+use crate::domain::path_params;
+use crossterm::event::KeyCode;
+
+#[test]
+fn test_names_in_path_only() {
+    assert_eq!(
+        path_params::names("https://api.test:8443/users/:id/posts/{post_id}?sort=:asc"),
+        vec!["id", "post_id"]
+    );
+    // Environment variables and repeats aren't path params
+    assert_eq!(
+        path_params::names("{{base}}/orgs/{org}/{{team}}/:org/files/{id}.json"),
+        vec!["org", "id"]
+    );
+    assert!(path_params::names("/users/:/{}/{1x}").is_empty());
+    assert!(path_params::names("https://api.test").is_empty());
+}
+
+#[test]
+fn test_fill_leaves_unset_params() {
+    let values = |name: &str| match name {
+        "id" => Some("42"),
+        "org" => Some(""),
+        _ => None,
+    };
+    assert_eq!(
+        path_params::fill("/orgs/{org}/users/:id/{id}.json?id=:id", values),
+        "/orgs/{org}/users/42/42.json?id=:id"
+    );
+}
+
+#[test]
+fn test_path_params_in_params_tab() {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.active_tab_mut().url = "https://api.test/users/{id}/posts/:post?page=2".to_string();
+    app.sync_url_to_params();
+    assert_eq!(
+        app.active_tab().path_params,
+        vec![
+            ("id".to_string(), String::new()),
+            ("post".to_string(), String::new())
+        ]
+    );
+
+    // Path rows come before the query's, and only their values edit
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('e'));
+    assert_eq!(app.active_tab().input_mode, InputMode::EditingPathParam);
+    for c in "{{user}}".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('e'));
+    press(&mut app, KeyCode::Char('7'));
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.params_row(), Some(2));
+    assert_eq!(app.active_tab().params_list_state.selected(), Some(0));
+
+    // Editing the query keeps the template as written
+    app.active_tab_mut().params[0].1 = "3".to_string();
+    app.sync_params_to_url();
+    assert_eq!(
+        app.active_tab().url,
+        "https://api.test/users/{id}/posts/:post?page=3"
+    );
+
+    app.environments[app.selected_env_index]
+        .variables
+        .insert("user".to_string(), "ada".to_string());
+    let (request, _) = app.outgoing_request();
+    assert_eq!(request.url, "https://api.test/users/ada/posts/7?page=3");
+    assert!(
+        app.codegen_context()["url"]
+            .as_str()
+            .unwrap()
+            .contains("/users/ada/posts/7")
+    );
+
+    // Values are saved, and survive the template changing around them
+    let config = app.active_tab().to_request_config();
+    assert_eq!(config.path_params["post"], "7");
+    let mut other = App::new();
+    let mut config = config;
+    config.url = "https://api.test/posts/:post/comments/:comment".to_string();
+    other.apply_request_config(config);
+    assert_eq!(
+        other.active_tab().path_params,
+        vec![
+            ("post".to_string(), "7".to_string()),
+            ("comment".to_string(), String::new())
+        ]
+    );
+}
@@ -0,0 +1,275 @@
+use crate::net::pool::{DnsCache, PoolSettings, apply_pool_command, parse_overrides};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_pool_command_updates_settings() {
+    let mut settings = PoolSettings::default();
+
+    apply_pool_command(&mut settings, "off").unwrap();
+    assert!(settings.disable_pooling);
+    apply_pool_command(&mut settings, "on").unwrap();
+    assert!(!settings.disable_pooling);
+
+    apply_pool_command(&mut settings, "idle 2").unwrap();
+    apply_pool_command(&mut settings, "timeout 15").unwrap();
+    let summary = apply_pool_command(&mut settings, "dns 60").unwrap();
+    assert_eq!(settings.max_idle_per_host, Some(2));
+    assert_eq!(settings.idle_timeout_secs, Some(15));
+    assert_eq!(settings.dns_ttl_secs, Some(60));
+    assert!(summary.contains("DNS cache 60s"));
+
+    apply_pool_command(&mut settings, "dns off").unwrap();
+    apply_pool_command(&mut settings, "idle default").unwrap();
+    assert_eq!(settings.dns_ttl_secs, None);
+    assert_eq!(settings.max_idle_per_host, None);
+}
+
+#[test]
+fn test_pool_command_rejects_garbage() {
+    let mut settings = PoolSettings::default();
+    assert!(apply_pool_command(&mut settings, "idle lots").is_err());
+    assert!(apply_pool_command(&mut settings, "sideways").is_err());
+    assert_eq!(settings, PoolSettings::default());
+    // No args just reports
+    assert!(apply_pool_command(&mut settings, "").is_ok());
+}
+
+#[test]
+fn test_settings_missing_from_config_use_defaults() {
+    let settings: PoolSettings = serde_json::from_str(r#"{"dns_ttl_secs": 30}"#).unwrap();
+    assert_eq!(settings.dns_ttl_secs, Some(30));
+    assert!(!settings.disable_pooling);
+    assert_eq!(settings.max_idle_per_host, None);
+}
+
+#[test]
+fn test_dns_cache_expires_after_ttl() {
+    let cache = DnsCache::new(Duration::from_secs(10));
+    let addr: SocketAddr = "10.0.0.1:0".parse().unwrap();
+    let now = Instant::now();
+
+    assert_eq!(cache.lookup_at("api.example.com", now), None);
+    cache.store_at("api.example.com", vec![addr], now);
+    assert_eq!(
+        cache.lookup_at("api.example.com", now + Duration::from_secs(9)),
+        Some(vec![addr])
+    );
+    assert_eq!(
+        cache.lookup_at("api.example.com", now + Duration::from_secs(10)),
+        None
+    );
+}
+
+#[test]
+fn test_resolve_table_parsed_like_curl() {
+    let resolve = [
+        ("API.example.com:443", "10.0.0.5, 10.0.0.6:8443"),
+        ("v6.example.com", "[::1]"),
+    ]
+    .iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    let overrides = parse_overrides(&resolve).unwrap();
+    let addr = |s: &str| s.parse::<SocketAddr>().unwrap();
+    assert_eq!(
+        overrides,
+        vec![
+            (
+                "api.example.com".to_string(),
+                vec![addr("10.0.0.5:0"), addr("10.0.0.6:0")]
+            ),
+            ("v6.example.com".to_string(), vec![addr("[::1]:0")]),
+        ]
+    );
+
+    let bad = [("api.example.com".to_string(), "staging".to_string())].into();
+    assert!(parse_overrides(&bad).unwrap_err().contains("'staging'"));
+}
+
+#[tokio::test]
+async fn test_pinned_host_reaches_the_pinned_address() {
+    use crate::net::http::NetworkEvent;
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let route = crate::net::mock_server::MockRoute {
+        path: "/ping".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: "pong".to_string(),
+        ..Default::default()
+    };
+    let server = crate::net::mock_server::start_mock_server(port, vec![route]);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let (reply_tx, mut reply_rx) = tokio::sync::mpsc::channel(4);
+    tokio::spawn(crate::net::http::handle_network(rx, reply_tx));
+    // .invalid never resolves, so only the override can get it there
+    tx.send(NetworkEvent::RunRequest {
+        url: format!("http://staging.postdad.invalid:{}/ping", port),
+        method: "GET".to_string(),
+        headers: Default::default(),
+        body: None,
+        form_data: None,
+        auth: None,
+        timeout_ms: Some(5000),
+        phase_timeouts: Default::default(),
+        ssl_verify: true,
+        ssl_ca_cert: None,
+        ssl_client_cert: None,
+        ssl_client_key: None,
+        proxy_url: None,
+        proxy_auth: None,
+        no_proxy: None,
+        pool: PoolSettings::default(),
+        resolve: vec![(
+            "staging.postdad.invalid".to_string(),
+            vec!["127.0.0.1:0".parse().unwrap()],
+        )],
+        request_id: String::new(),
+    })
+    .await
+    .unwrap();
+    let mut event = reply_rx.recv().await;
+    while let Some(NetworkEvent::Sent(..)) = event {
+        event = reply_rx.recv().await;
+    }
+    match event {
+        Some(NetworkEvent::GotResponse(_, body, status, ..)) => {
+            assert_eq!((status, body.as_slice()), (200, b"pong".as_slice()));
+        }
+        Some(NetworkEvent::Failed { error, .. }) => panic!("request failed: {}", error),
+        _ => panic!("no response"),
+    }
+    server.handle.abort();
+}
+
+#[tokio::test]
+async fn test_runner_and_batch_honour_pins() {
+    use crate::features::runner::{self, RunnerEvent};
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let route = crate::net::mock_server::MockRoute {
+        path: "/ping".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: "pong".to_string(),
+        ..Default::default()
+    };
+    let server = crate::net::mock_server::start_mock_server(port, vec![route]);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let url = format!("http://staging.postdad.invalid:{}/ping", port);
+    let resolve = parse_overrides(
+        &[(
+            "staging.postdad.invalid".to_string(),
+            "127.0.0.1".to_string(),
+        )]
+        .into(),
+    )
+    .unwrap();
+
+    let collection = crate::domain::collection::Collection::from_hcl(
+        "pinned",
+        &format!(
+            "request \"Ping\" {{\n  method = \"GET\"\n  url = \"{}\"\n}}\n",
+            url
+        ),
+    )
+    .unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+    runner::run_collection(
+        &collection,
+        &Default::default(),
+        &Default::default(),
+        &resolve,
+        tx,
+    )
+    .await;
+    let mut status = None;
+    while let Ok(event) = rx.try_recv() {
+        if let RunnerEvent::RequestCompleted(result) = event {
+            status = result.status;
+        }
+    }
+    assert_eq!(status, Some(200));
+
+    let request = crate::app::OutgoingRequest {
+        method: "GET".to_string(),
+        url,
+        headers: Default::default(),
+        body: None,
+        form_data: None,
+        auth: None,
+    };
+    let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+    crate::features::batch::run_batch(
+        vec![crate::features::batch::BatchRequest {
+            row: 0,
+            request,
+            timeout_ms: 5000,
+            client: crate::net::http::ClientConfig {
+                ssl_verify: true,
+                ssl_ca_cert: None,
+                proxy_url: None,
+                proxy_auth: None,
+                pool: Default::default(),
+                resolve,
+                phase_timeouts: Default::default(),
+            },
+        }],
+        tx,
+    )
+    .await;
+    match rx.recv().await {
+        Some(crate::features::batch::BatchEvent::Done(0, result)) => {
+            assert_eq!(result.status, Some(200))
+        }
+        _ => panic!("no batch result"),
+    }
+    server.handle.abort();
+}
+
+#[test]
+fn test_client_cache_reuses_per_settings() {
+    use crate::net::pool::ClientCache;
+    let mut cache = ClientCache::new(2);
+    let mut built = 0;
+    let mut client = |key: &'static str, cache: &mut ClientCache<&str, usize>| {
+        cache.get_or_build(key, || {
+            built += 1;
+            built
+        })
+    };
+
+    assert_eq!(client("direct", &mut cache), (1, false));
+    assert_eq!(client("proxy", &mut cache), (2, false));
+    // Switching back keeps the first one's client
+    assert_eq!(client("direct", &mut cache), (1, true));
+    // Full: the least recently used ("proxy") makes room
+    assert_eq!(client("insecure", &mut cache), (3, false));
+    assert_eq!(client("direct", &mut cache), (1, true));
+    assert_eq!(client("proxy", &mut cache), (4, false));
+
+    cache.clear();
+    assert_eq!(client("direct", &mut cache), (5, false));
+}
+
+#[test]
+fn test_pool_command_fresh_client() {
+    let mut settings = PoolSettings::default();
+    let summary = apply_pool_command(&mut settings, "fresh on").unwrap();
+    assert!(settings.fresh_client);
+    assert!(summary.contains("new client per request"));
+    apply_pool_command(&mut settings, "fresh off").unwrap();
+    assert!(!settings.fresh_client);
+    assert!(apply_pool_command(&mut settings, "fresh sometimes").is_err());
+}
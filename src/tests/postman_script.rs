@@ -0,0 +1,121 @@
+use crate::features::import::parse_postman_collection;
+use crate::features::postman_script::{ScriptKind, UNCONVERTED, transpile};
+use crate::features::scripting::{run_post_script, run_script};
+use std::collections::HashMap;
+
+#[test]
+fn test_transpile_pre_request() {
+    let source = r#"// auth
+pm.environment.set("ts", Date.now());
+pm.request.headers.add({ key: 'X-Api-Key', value: pm.environment.get("api_key") });
+const sig = CryptoJS.HmacSHA256("x", "y");"#;
+    let result = transpile(source, ScriptKind::PreRequest);
+    assert_eq!(result.converted, 2);
+    assert_eq!(result.skipped, 1);
+    assert!(
+        result
+            .script
+            .contains("set_var(\"ts\", (timestamp_ms()).to_string());")
+    );
+    assert!(
+        result
+            .script
+            .contains("set_header(\"X-Api-Key\", (get_var(\"api_key\")).to_string());")
+    );
+    assert!(
+        result
+            .script
+            .contains(&format!("{}const sig = CryptoJS", UNCONVERTED))
+    );
+
+    let mut env = HashMap::new();
+    env.insert("api_key".to_string(), "secret".to_string());
+    let run = run_script(
+        &result.script,
+        "GET",
+        "http://x",
+        &HashMap::new(),
+        "",
+        &env,
+        &[],
+    );
+    assert!(run.errors.is_empty(), "{:?}", run.errors);
+    assert_eq!(run.headers.get("X-Api-Key"), Some(&"secret".to_string()));
+    assert!(run.variables.contains_key("ts"));
+}
+
+#[test]
+fn test_transpile_tests_and_run() {
+    let source = r#"var jsonData = pm.response.json();
+pm.test("Status code is 200", function () {
+    pm.response.to.have.status(200);
+});
+pm.test("Body matches", function () {
+    pm.expect(jsonData.user.name).to.eql("Ada");
+    pm.expect(jsonData.items[1].price).to.be.below(10);
+    pm.expect(pm.response.text()).to.include("Ada");
+});
+pm.test("Has token", () => {
+    pm.expect(jsonData).to.have.property('token');
+});
+tests["Fast"] = responseTime < 500;
+pm.environment.set("token", jsonData.token);
+pm.test("Schema", function () {
+    pm.response.to.have.jsonSchema(schema);
+});"#;
+    let result = transpile(source, ScriptKind::Test);
+    assert_eq!(result.converted, 6);
+    assert_eq!(result.skipped, 1);
+    assert!(result.script.starts_with("// Converted from Postman"));
+    assert!(
+        result
+            .script
+            .contains("parse_float(json_path(\"$.items[1].price\")) < 10.0")
+    );
+
+    let body =
+        r#"{"user": {"name": "Ada"}, "items": [{"price": 50}, {"price": 4.5}], "token": "t0k"}"#;
+    let run = run_post_script(&result.script, 200, body, &HashMap::new(), 120, &[]);
+    assert!(run.errors.is_empty(), "{:?}", run.errors);
+    assert_eq!(
+        run.tests,
+        vec![
+            ("Status code is 200".to_string(), true),
+            ("Body matches".to_string(), true),
+            ("Has token".to_string(), true),
+            ("Fast".to_string(), true),
+        ]
+    );
+    assert_eq!(run.variables.get("token"), Some(&"t0k".to_string()));
+
+    let failing = run_post_script(&result.script, 404, "{}", &HashMap::new(), 900, &[]);
+    assert_eq!(failing.tests[0], ("Status code is 200".to_string(), false));
+}
+
+#[test]
+fn test_import_inherits_folder_scripts() {
+    let collection = r#"{
+        "info": {"name": "Shop"},
+        "event": [{"listen": "test", "script": {"exec": ["pm.response.to.have.status(200);"]}}],
+        "item": [{
+            "name": "Orders",
+            "event": [{"listen": "prerequest", "script": {"exec": "pm.variables.set('scope', 'orders');"}}],
+            "item": [{
+                "name": "List",
+                "request": {"method": "GET", "url": "https://shop.test/orders"},
+                "event": [{"listen": "test", "script": {"exec": ["postman.setNextRequest('Next');"]}}]
+            }]
+        }]
+    }"#;
+    let (collection, stats) = parse_postman_collection(collection).unwrap();
+    let request = &collection.requests["Orders/List"];
+    assert_eq!(
+        request.pre_request_script.as_deref(),
+        Some("set_var(\"scope\", \"orders\");")
+    );
+    let post = request.post_request_script.as_deref().unwrap();
+    assert!(post.contains("// [postman] pm.response.to.have.status(200);"));
+    assert!(post.contains("// [postman] postman.setNextRequest('Next');"));
+    assert_eq!(stats.converted, 1);
+    assert_eq!(stats.skipped, 2);
+}
@@ -0,0 +1,124 @@
+use crate::app::{App, OutgoingRequest};
+use crate::features::presend::{self, Check, SendChecks};
+use std::collections::HashMap;
+
+fn request(method: &str, headers: &[(&str, &str)], body: Option<&str>) -> OutgoingRequest {
+    OutgoingRequest {
+        method: method.to_string(),
+        url: "https://api.test/users".to_string(),
+        headers: headers
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>(),
+        body: body.map(str::to_string),
+        form_data: None,
+        auth: None,
+    }
+}
+
+fn checks(request: &OutgoingRequest) -> Vec<Check> {
+    presend::check(request, &SendChecks::default())
+        .into_iter()
+        .map(|w| w.check)
+        .collect()
+}
+
+#[test]
+fn test_clean_request() {
+    assert!(checks(&request("GET", &[("Accept", "application/json")], None)).is_empty());
+    assert!(
+        checks(&request(
+            "POST",
+            &[("Content-Type", "application/json")],
+            Some("{\"a\": 1}")
+        ))
+        .is_empty()
+    );
+}
+
+#[test]
+fn test_common_mistakes() {
+    let mut req = request("POST", &[], Some("{\"id\": \"{{user_id}}\"}"));
+    req.url = "{{base}}/users/{{$uuid}}".to_string();
+    let warnings = presend::check(&req, &SendChecks::default());
+    assert_eq!(
+        warnings.iter().map(|w| w.check).collect::<Vec<_>>(),
+        vec![Check::Variables, Check::MissingContentType]
+    );
+    assert_eq!(
+        warnings[0].message,
+        "{{base}}, {{user_id}} not defined in any scope, sent as is"
+    );
+
+    let req = request(
+        "POST",
+        &[("content-type", "application/vnd.api+json")],
+        Some("{\"a\": 1,}"),
+    );
+    let warnings = presend::check(&req, &SendChecks::default());
+    assert_eq!(warnings[0].check, Check::InvalidJson);
+    assert!(
+        warnings[0]
+            .message
+            .ends_with("but Content-Type is application/vnd.api+json")
+    );
+
+    assert_eq!(
+        checks(&request(
+            "get",
+            &[("Content-Type", "text/plain")],
+            Some("hello")
+        )),
+        vec![Check::BodyOnGet]
+    );
+    assert_eq!(
+        checks(&request(
+            "GET",
+            &[("Accept", "a"), ("ACCEPT", "b"), ("X-Id", "1")],
+            None
+        )),
+        vec![Check::DuplicateHeaders]
+    );
+}
+
+#[test]
+fn test_settings() {
+    let req = request("GET", &[], Some("hello"));
+    let skip = SendChecks {
+        skip: vec!["Content-Type".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(
+        presend::check(&req, &skip)
+            .into_iter()
+            .map(|w| w.check)
+            .collect::<Vec<_>>(),
+        vec![Check::BodyOnGet]
+    );
+    let off = SendChecks {
+        enabled: false,
+        ..Default::default()
+    };
+    assert!(presend::check(&req, &off).is_empty());
+}
+
+#[test]
+fn test_send_anyway_dismisses() {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.active_tab_mut().url = "https://api.test/{{nope}}".to_string();
+    assert!(!app.check_send_warnings());
+    assert_eq!(app.send_warnings.as_ref().map(Vec::len), Some(1));
+
+    app.resolve_send_warnings(false);
+    assert!(app.send_warnings.is_none());
+    assert!(!app.active_tab().resend_requested);
+
+    assert!(!app.check_send_warnings());
+    app.resolve_send_warnings(true);
+    assert!(app.active_tab().resend_requested);
+    // The same warning doesn't stop the resend, a new one does
+    assert!(app.check_send_warnings());
+    app.active_tab_mut().url = "https://api.test/{{other}}".to_string();
+    assert!(!app.check_send_warnings());
+}
@@ -0,0 +1,143 @@
+use super::common::temp_dir;
+use crate::app::{App, GuardedAction};
+use crate::domain::profile::{Guard, Profile, host_matches, load_profiles};
+
+fn intern() -> Profile {
+    Profile {
+        name: "intern".to_string(),
+        production_hosts: vec![
+            "api.example.com".to_string(),
+            "*.prod.example.com".to_string(),
+        ],
+        destructive: Guard::Block,
+        safe: Guard::Allow,
+    }
+}
+
+#[test]
+fn test_host_patterns() {
+    assert!(host_matches("api.example.com", "API.example.com"));
+    assert!(host_matches("*.prod.example.com", "eu.prod.example.com"));
+    assert!(!host_matches("*.prod.example.com", "staging.example.com"));
+    assert!(host_matches("*prod*", "db-prod-1.internal"));
+    assert!(!host_matches("api.example.com", "api.example.com.evil.io"));
+}
+
+#[test]
+fn test_guard_by_method_and_host() {
+    let profile = intern();
+    assert_eq!(
+        profile.guard_for("DELETE", "https://api.example.com/users/1"),
+        Guard::Block
+    );
+    assert_eq!(
+        profile.guard_for("get", "https://api.example.com/users"),
+        Guard::Allow
+    );
+    assert_eq!(
+        profile.guard_for("POST", "https://eu.prod.example.com:8443/x"),
+        Guard::Block
+    );
+    assert_eq!(
+        profile.guard_for("POST", "https://staging.example.com/x"),
+        Guard::Allow
+    );
+    assert_eq!(profile.guard_for("POST", "not a url"), Guard::Allow);
+}
+
+#[test]
+fn test_load_profiles() {
+    let path = temp_dir("profiles").join("profiles.hcl");
+    std::fs::write(
+        &path,
+        r#"
+profile "read-only intern" {
+  production_hosts = ["api.example.com"]
+  destructive = "block"
+}
+
+profile "full access" {
+  production_hosts = ["api.example.com"]
+  destructive = "confirm"
+}
+"#,
+    )
+    .unwrap();
+    let profiles = load_profiles(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(profiles.len(), 2);
+    assert_eq!(profiles[0].name, "read-only intern");
+    assert_eq!(profiles[0].destructive, Guard::Block);
+    assert_eq!(profiles[0].safe, Guard::Allow);
+    assert_eq!(profiles[1].destructive, Guard::Confirm);
+
+    assert!(
+        load_profiles("/nonexistent/profiles.hcl")
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_confirm_lets_one_send_through() {
+    let mut app = App::new();
+    let mut profile = intern();
+    profile.destructive = Guard::Confirm;
+    app.profiles = vec![profile];
+    app.active_profile = Some(0);
+    {
+        let tab = app.active_tab_mut();
+        tab.url = "https://api.example.com/users".to_string();
+        tab.method = "POST".to_string();
+    }
+
+    assert!(!app.check_guard(GuardedAction::Send));
+    assert!(app.guard_prompt.is_some());
+
+    app.resolve_guard_prompt(true);
+    assert!(app.guard_prompt.is_none());
+    assert!(app.active_tab().resend_requested);
+    // The re-send goes through once, the next one asks again
+    assert!(app.check_guard(GuardedAction::Send));
+    assert!(!app.check_guard(GuardedAction::Send));
+
+    app.resolve_guard_prompt(false);
+    assert!(!app.guard_confirmed);
+}
+
+#[test]
+fn test_blocked_and_no_profile() {
+    let mut app = App::new();
+    app.profiles = vec![intern()];
+    app.active_tab_mut().url = "https://api.example.com/users".to_string();
+    app.active_tab_mut().method = "PUT".to_string();
+
+    app.active_profile = None;
+    assert!(app.check_guard(GuardedAction::Send));
+
+    app.active_profile = Some(0);
+    assert!(!app.check_guard(GuardedAction::StressTest));
+    assert!(app.guard_prompt.is_none());
+}
+
+#[test]
+fn test_send_tabs_guards_every_picked_tab() {
+    let mut app = App::new();
+    app.profiles = vec![intern()];
+    app.active_profile = Some(0);
+    app.active_tab_mut().url = "https://staging.example.com/users".to_string();
+    let mut delete = crate::app::RequestTab::new();
+    delete.method = "DELETE".to_string();
+    delete.url = "https://api.example.com/users/1".to_string();
+    app.tabs.push(delete);
+    app.active_tab = 0;
+
+    // The active tab is harmless, the other one hits production
+    app.send_tabs_command("all");
+    assert!(!app.should_run_batch);
+    assert_eq!(app.active_tab, 0);
+
+    app.send_tabs_command("1");
+    assert!(app.should_run_batch);
+}
@@ -0,0 +1,126 @@
+use super::common::{press, temp_dir, type_text};
+use crate::app::App;
+use crate::domain::workspace::Workspace;
+use crate::features::protos::{self, ProtoRegistry, Section};
+use crossterm::event::KeyCode;
+use std::path::Path;
+
+fn workspace(name: &str) -> std::path::PathBuf {
+    let dir = temp_dir(&format!("protos_{}", name));
+    for sub in ["protos/users/v1", "protos/.git", "third_party/google/api"] {
+        std::fs::create_dir_all(dir.join(sub)).unwrap();
+    }
+    for file in [
+        "protos/users/v1/users.proto",
+        "protos/common.proto",
+        "protos/.git/stale.proto",
+        "protos/README.md",
+        "third_party/google/api/annotations.proto",
+    ] {
+        std::fs::write(dir.join(file), "syntax = \"proto3\";\n").unwrap();
+    }
+    dir
+}
+
+#[test]
+fn test_find_protos() {
+    let dir = workspace("find");
+    assert_eq!(
+        protos::find_protos(&dir.join("protos")),
+        vec!["common.proto", "users/v1/users.proto"]
+    );
+    assert!(protos::find_protos(&dir.join("nope")).is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_grpcurl_args() {
+    let dir = workspace("args");
+    let registry = ProtoRegistry {
+        roots: vec!["protos".to_string()],
+        import_paths: vec!["third_party".to_string()],
+    };
+    let path = |p: &str| dir.join(p).to_string_lossy().into_owned();
+    assert_eq!(
+        registry.args(&dir, None),
+        vec![
+            "-import-path".to_string(),
+            path("protos"),
+            "-import-path".to_string(),
+            path("third_party"),
+            "-proto".to_string(),
+            "common.proto".to_string(),
+            "-proto".to_string(),
+            "users/v1/users.proto".to_string(),
+        ]
+    );
+
+    // A request's own file wins over the roots
+    assert_eq!(
+        registry.args(&dir, Some("other/hello.proto")),
+        vec![
+            "-import-path",
+            "other",
+            "-import-path",
+            &path("third_party"),
+            "-proto",
+            "hello.proto"
+        ]
+    );
+
+    // Nothing registered: reflection
+    assert!(ProtoRegistry::default().args(&dir, Some("")).is_empty());
+    assert!(
+        ProtoRegistry::default()
+            .args(Path::new("."), None)
+            .is_empty()
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_registry_view_persists() {
+    let dir = workspace("view");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+
+    app.open_proto_view();
+    press(&mut app, KeyCode::Char('a'));
+    type_text(&mut app, "protos/");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.proto_registry.roots, vec!["protos".to_string()]);
+    assert_eq!(
+        app.proto_view.as_ref().unwrap().files,
+        vec!["protos: common.proto", "protos: users/v1/users.proto"]
+    );
+
+    // Directories that don't exist aren't added
+    press(&mut app, KeyCode::Char('a'));
+    type_text(&mut app, "missing");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.proto_registry.roots.len(), 1);
+
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(
+        app.proto_view.as_ref().unwrap().section,
+        Section::ImportPaths
+    );
+    press(&mut app, KeyCode::Char('a'));
+    type_text(&mut app, "third_party");
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Esc);
+    assert!(app.proto_view.is_none());
+
+    // Saved in the workspace's config.json
+    let mut again = App::with_workspace(Workspace::at(&dir));
+    again.show_splash = false;
+    assert_eq!(again.proto_registry, app.proto_registry);
+    let args = again.grpc_proto_args();
+    assert!(args.contains(&"users/v1/users.proto".to_string()));
+
+    again.open_proto_view();
+    press(&mut again, KeyCode::Char('d'));
+    assert!(again.proto_registry.roots.is_empty());
+    assert!(again.grpc_proto_args().is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
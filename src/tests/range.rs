@@ -0,0 +1,62 @@
+use super::common::temp_dir;
+use crate::features::range::{
+    ContentRange, parse_content_range, parse_range_spec, resume_range_header, write_resumed,
+};
+
+#[test]
+fn test_parse_range_spec() {
+    assert_eq!(parse_range_spec("0-1023").unwrap(), "bytes=0-1023");
+    assert_eq!(parse_range_spec("500-").unwrap(), "bytes=500-");
+    assert_eq!(parse_range_spec("-500").unwrap(), "bytes=-500");
+    assert_eq!(parse_range_spec("first 64k").unwrap(), "bytes=0-65535");
+    assert_eq!(parse_range_spec("64KB").unwrap(), "bytes=0-65535");
+    assert_eq!(parse_range_spec("last 1m").unwrap(), "bytes=-1048576");
+    assert_eq!(parse_range_spec("bytes=10-20").unwrap(), "bytes=10-20");
+    assert!(parse_range_spec("20-10").is_err());
+    assert!(parse_range_spec("lots").is_err());
+}
+
+#[test]
+fn test_parse_content_range() {
+    let range = parse_content_range("bytes 0-1023/4096").unwrap();
+    assert_eq!(
+        range,
+        ContentRange {
+            start: 0,
+            end: 1023,
+            total: Some(4096)
+        }
+    );
+    assert_eq!(range.byte_count(), 1024);
+    assert_eq!(range.describe(), "bytes 0-1023 of 4096 (25.0%)");
+    assert_eq!(range.bar(4), "[█░░░]");
+
+    assert_eq!(parse_content_range("bytes 10-19/*").unwrap().total, None);
+    assert!(parse_content_range("items 0-1/2").is_none());
+}
+
+#[test]
+fn test_resume_appends_partial_content() {
+    let path = temp_dir("resume").join("resume.bin");
+    let path = path.to_string_lossy().to_string();
+    std::fs::write(&path, b"hello ").unwrap();
+
+    assert_eq!(resume_range_header(&path), "bytes=6-");
+
+    let range = ContentRange {
+        start: 6,
+        end: 10,
+        total: Some(11),
+    };
+    assert_eq!(write_resumed(&path, Some(&range), b"world").unwrap(), 11);
+    assert_eq!(std::fs::read(&path).unwrap(), b"hello world");
+
+    // A range that doesn't line up with the file is refused
+    assert!(write_resumed(&path, Some(&range), b"world").is_err());
+
+    // A full response replaces the file
+    assert_eq!(write_resumed(&path, None, b"fresh").unwrap(), 5);
+    assert_eq!(std::fs::read(&path).unwrap(), b"fresh");
+
+    let _ = std::fs::remove_file(&path);
+}
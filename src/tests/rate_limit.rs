@@ -0,0 +1,244 @@
+use super::common::{last_message, temp_dir};
+use crate::app::App;
+use crate::domain::collection::Collection;
+use crate::domain::workspace::Workspace;
+use crate::features::cli::WorkspaceConfig;
+use crate::features::runner::{self, RunnerEvent};
+use crate::net::mock_server::MockRoute;
+use crate::net::rate_limit::{
+    self, MIN_RPS, RateLimitSettings, RateLimiter, apply_rate_limit_command,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_command_sets_and_clears_limits() {
+    let mut settings = RateLimitSettings::default();
+    assert!(!settings.is_active());
+    assert_eq!(
+        apply_rate_limit_command(&mut settings, "run 5").unwrap(),
+        "runs 5/s, per host off"
+    );
+    apply_rate_limit_command(&mut settings, "host 2/s").unwrap();
+    let summary = apply_rate_limit_command(&mut settings, "host Staging.Example.com 0.5").unwrap();
+    assert_eq!(summary, "runs 5/s, per host 2/s, staging.example.com 0.5/s");
+    assert_eq!(settings.host_rps("STAGING.example.com"), Some(0.5));
+    assert_eq!(settings.host_rps("api.example.com"), Some(2.0));
+
+    apply_rate_limit_command(&mut settings, "host staging.example.com off").unwrap();
+    assert_eq!(settings.host_rps("staging.example.com"), Some(2.0));
+    apply_rate_limit_command(&mut settings, "run off").unwrap();
+    assert_eq!(settings.runner_rps, None);
+
+    for bad in ["run 0", "run -1", "host fast", "burst 3"] {
+        assert!(
+            apply_rate_limit_command(&mut settings, bad).is_err(),
+            "{}",
+            bad
+        );
+    }
+    apply_rate_limit_command(&mut settings, "off").unwrap();
+    assert_eq!(settings, RateLimitSettings::default());
+}
+
+#[test]
+fn test_slots_are_spaced_per_key() {
+    let limiter = RateLimiter::new();
+    let now = Instant::now();
+    let waits: Vec<u128> = (0..3)
+        .map(|_| limiter.reserve_at("a", 4.0, now).as_millis())
+        .collect();
+    assert_eq!(waits, vec![0, 250, 500]);
+    // Another key has its own slots
+    assert_eq!(limiter.reserve_at("b", 4.0, now), Duration::ZERO);
+    // Once the booked slots have gone by, nothing waits
+    let later = now + Duration::from_secs(2);
+    assert_eq!(limiter.reserve_at("a", 4.0, later), Duration::ZERO);
+
+    // A slot given back is the next one handed out
+    assert_eq!(limiter.reserve_at("c", 4.0, now), Duration::ZERO);
+    assert_eq!(limiter.reserve_at("c", 4.0, now).as_millis(), 250);
+    limiter.release_at("c", 4.0, now);
+    assert_eq!(limiter.reserve_at("c", 4.0, now).as_millis(), 250);
+    limiter.release_at("c", 4.0, now);
+    limiter.release_at("c", 4.0, now);
+    limiter.release_at("c", 4.0, now);
+    assert_eq!(limiter.reserve_at("c", 4.0, now), Duration::ZERO);
+}
+
+#[test]
+fn test_reserve_says_which_limit_holds_it_back() {
+    let settings = RateLimitSettings {
+        per_host_rps: Some(1.0),
+        hosts: [("slow.reserve.test".to_string(), 0.5)].into(),
+        ..Default::default()
+    };
+    assert!(rate_limit::reserve(&settings, None, "http://fast.reserve.test/a").is_none());
+    let throttle = rate_limit::reserve(&settings, None, "http://FAST.reserve.test/b").unwrap();
+    assert_eq!(throttle.reason, "fast.reserve.test at 1/s");
+    assert!(throttle.wait > Duration::from_millis(900));
+
+    // A host's own limit goes over the global one
+    assert!(rate_limit::reserve(&settings, None, "http://slow.reserve.test/").is_none());
+    let throttle = rate_limit::reserve(&settings, None, "http://slow.reserve.test/").unwrap();
+    assert_eq!(throttle.reason, "slow.reserve.test at 0.5/s");
+    assert!(throttle.wait > Duration::from_millis(1900));
+}
+
+#[test]
+fn test_run_pace_only_with_a_run() {
+    let settings = RateLimitSettings {
+        runner_rps: Some(2.0),
+        ..Default::default()
+    };
+    assert!(rate_limit::reserve(&settings, None, "http://pace.test/").is_none());
+    assert!(rate_limit::reserve(&settings, None, "http://pace.test/").is_none());
+
+    let run = RateLimiter::new();
+    assert!(rate_limit::reserve(&settings, Some(&run), "http://pace.test/").is_none());
+    let throttle = rate_limit::reserve(&settings, Some(&run), "not a url").unwrap();
+    assert_eq!(throttle.reason, "run at 2/s");
+}
+
+#[test]
+fn test_ratelimit_command_is_saved() {
+    let mut app = App::new();
+    app.show_splash = false;
+    crate::handler::handle_key_events(
+        KeyEvent::new(KeyCode::Char(':'), KeyModifiers::NONE),
+        &mut app,
+    );
+    for c in "ratelimit host 3".chars() {
+        crate::handler::handle_key_events(
+            KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE),
+            &mut app,
+        );
+    }
+    crate::handler::handle_key_events(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &mut app);
+    assert_eq!(app.rate_limit.per_host_rps, Some(3.0));
+    assert_eq!(last_message(&app), "Rate limits: runs off, per host 3/s");
+
+    app.rate_limit_command("run nope");
+    assert!(last_message(&app).starts_with("Not a rate: nope"));
+    assert_eq!(app.rate_limit.runner_rps, None);
+}
+
+#[test]
+fn test_command_line_limits_take_the_workspace_hosts() {
+    let dir = temp_dir("cli_limits");
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{"rate_limit": {"runner_rps": 5.0, "per_host_rps": 2.0, "hosts": {"staging.test": 0.5}}}"#,
+    )
+    .unwrap();
+    let config = WorkspaceConfig::load(&Workspace::at(&dir));
+
+    let limits = config.limits(&RateLimitSettings::default());
+    assert_eq!(limits.runner_rps, None);
+    assert_eq!(limits.per_host_rps, Some(2.0));
+    assert_eq!(limits.hosts.get("staging.test"), Some(&0.5));
+
+    let flags = RateLimitSettings {
+        runner_rps: Some(1.0),
+        per_host_rps: Some(4.0),
+        ..Default::default()
+    };
+    let limits = config.limits(&flags);
+    assert_eq!(limits.runner_rps, Some(1.0));
+    assert_eq!(limits.per_host_rps, Some(4.0));
+    assert_eq!(limits.hosts.len(), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_runner_keeps_its_pace() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let routes = vec![MockRoute {
+        path: "/ok".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: "ok".to_string(),
+        ..Default::default()
+    }];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let requests: String = ["a", "b", "c"]
+        .iter()
+        .map(|name| {
+            format!(
+                "request \"{}\" {{\n  method = \"GET\"\n  url = \"http://127.0.0.1:{}/ok\"\n}}\n",
+                name, port
+            )
+        })
+        .collect();
+    let collection = Collection::from_hcl("paced", &requests).unwrap();
+    let limits = RateLimitSettings {
+        runner_rps: Some(10.0),
+        ..Default::default()
+    };
+    let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+    let started = Instant::now();
+    runner::run_collection(&collection, &Default::default(), &limits, &Vec::new(), tx).await;
+    let elapsed = started.elapsed();
+    server.handle.abort();
+
+    let mut throttled = Vec::new();
+    let mut finished = None;
+    while let Ok(event) = rx.try_recv() {
+        match event {
+            RunnerEvent::Throttled { reason, .. } => throttled.push(reason),
+            RunnerEvent::Finished(result) => finished = Some(result),
+            _ => {}
+        }
+    }
+    let result = finished.unwrap();
+    assert_eq!(result.passed, 3);
+    // The first goes right away, the other two wait their 100ms
+    assert!(elapsed >= Duration::from_millis(180), "{:?}", elapsed);
+    assert!(throttled.len() <= 2);
+    assert!(throttled.iter().all(|r| r == "run at 10/s"));
+    assert!(result.throttled.is_none());
+}
+
+#[test]
+fn test_rates_that_are_not_rates_hold_nothing_back() {
+    let limiter = RateLimiter::new();
+    let now = Instant::now();
+    for rps in [0.0, -1.0, 1e-20, f64::NAN, f64::INFINITY] {
+        assert_eq!(limiter.reserve_at("zero.test", rps, now), Duration::ZERO);
+        limiter.release_at("zero.test", rps, now);
+    }
+
+    let settings: RateLimitSettings = serde_json::from_str(
+        r#"{"runner_rps": 0, "per_host_rps": -2, "hosts": {"ok.test": 1.5, "bad.test": 0, "slow.test": 1e-20}}"#,
+    )
+    .unwrap();
+    let settings = settings.sanitized();
+    assert_eq!(settings.runner_rps, None);
+    assert_eq!(settings.per_host_rps, None);
+    assert_eq!(settings.hosts.len(), 1);
+    assert_eq!(settings.host_rps("ok.test"), Some(1.5));
+}
+
+#[test]
+fn test_tiny_rates_are_turned_down() {
+    let mut settings = RateLimitSettings::default();
+    assert!(apply_rate_limit_command(&mut settings, "host 1e-20").is_err());
+    assert!(apply_rate_limit_command(&mut settings, "host slow.test 0.0001").is_err());
+    assert!(apply_rate_limit_command(&mut settings, "run 0.001").is_ok());
+    assert_eq!(settings.runner_rps, Some(0.001));
+
+    // The slowest rate still spaces slots without overflowing
+    let limiter = RateLimiter::new();
+    let now = Instant::now();
+    limiter.reserve_at("slow.test", MIN_RPS, now);
+    assert_eq!(
+        limiter.reserve_at("slow.test", MIN_RPS, now),
+        Duration::from_secs(1000)
+    );
+}
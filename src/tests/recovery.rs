@@ -0,0 +1,141 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::workspace::Workspace;
+use crate::features::recovery::{self, Snapshot};
+use std::time::{Duration, Instant};
+
+fn app_in(dir: &std::path::Path) -> App {
+    let mut app = App::with_workspace(Workspace::at(dir));
+    app.show_splash = false;
+    app
+}
+
+fn saved(dir: &std::path::Path) -> Option<Snapshot> {
+    let content = std::fs::read_to_string(dir.join(recovery::FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[test]
+fn test_autosave_writes_edits_and_forgets_reverted_ones() {
+    let dir = temp_dir("recovery_autosave");
+    let mut app = app_in(&dir);
+    let start = Instant::now();
+
+    // Off until started
+    app.active_tab_mut().url = "https://api.example.com/orders".to_string();
+    app.autosave_drafts(start);
+    assert!(saved(&dir).is_none());
+
+    app.active_tab_mut().url = String::new();
+    app.start_recovery();
+    assert!(app.recovery_prompt.is_none());
+    app.autosave_drafts(start);
+    assert!(saved(&dir).is_none(), "nothing changed yet");
+
+    app.active_tab_mut().url = "https://api.example.com/orders".to_string();
+    app.active_tab_mut().method = "POST".to_string();
+    app.autosave_drafts(start + recovery::CHECK);
+    let snapshot = saved(&dir).unwrap();
+    assert_eq!(
+        snapshot.tabs[0].request.url,
+        "https://api.example.com/orders"
+    );
+    assert_eq!(snapshot.tabs[0].request.method, "POST");
+
+    // Back the way it started: nothing to recover
+    app.active_tab_mut().url.clear();
+    app.active_tab_mut().method = "GET".to_string();
+    app.autosave_drafts(start + recovery::CHECK * 2 + recovery::SAVE);
+    assert!(saved(&dir).is_none());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_latest_edits_wait_for_the_save_but_not_for_a_panic() {
+    let dir = temp_dir("recovery_pending");
+    let mut app = app_in(&dir);
+    app.start_recovery();
+    let start = Instant::now();
+
+    app.active_tab_mut().url = "https://first.example.com".to_string();
+    app.autosave_drafts(start);
+    app.active_tab_mut().url = "https://second.example.com".to_string();
+    app.autosave_drafts(start + Duration::from_secs(1));
+    assert_eq!(
+        saved(&dir).unwrap().tabs[0].request.url,
+        "https://first.example.com"
+    );
+
+    // What the panic hook does
+    recovery::write_pending(&app.recovery.pending);
+    assert_eq!(
+        saved(&dir).unwrap().tabs[0].request.url,
+        "https://second.example.com"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_unclean_exit_offers_tabs_back() {
+    let dir = temp_dir("recovery_restore");
+    {
+        let mut app = app_in(&dir);
+        app.start_recovery();
+        app.active_tab_mut().url = "https://api.example.com/login".to_string();
+        app.active_tab_mut().collection = Some("auth".to_string());
+        app.active_tab_mut().request_name = Some("Login".to_string());
+        app.add_tab();
+        app.active_tab_mut().name = "Orders".to_string();
+        app.active_tab_mut().url = "https://api.example.com/orders".to_string();
+        app.active_tab_mut().request_body = "{\"id\": 1}".to_string();
+        app.autosave_drafts(Instant::now());
+        // Dropped without a clean exit
+    }
+
+    let mut app = app_in(&dir);
+    app.start_recovery();
+    let prompt = app.recovery_prompt.as_ref().unwrap();
+    assert_eq!(prompt.tabs.len(), 2);
+    assert_eq!(prompt.active_tab, 1);
+    // Nothing saved over it while it's asked about
+    app.autosave_drafts(Instant::now());
+    assert_eq!(saved(&dir).unwrap().tabs.len(), 2);
+
+    app.resolve_recovery(true);
+    assert!(app.recovery_prompt.is_none());
+    assert_eq!(app.tabs.len(), 2);
+    assert_eq!(app.active_tab, 1);
+    assert_eq!(app.tabs[0].url, "https://api.example.com/login");
+    assert_eq!(app.tabs[0].collection.as_deref(), Some("auth"));
+    assert_eq!(app.tabs[0].request_name.as_deref(), Some("Login"));
+    assert_eq!(app.tabs[1].name, "Orders");
+    assert_eq!(app.tabs[1].request_body, "{\"id\": 1}");
+
+    // A clean exit leaves nothing behind
+    app.discard_drafts();
+    assert!(saved(&dir).is_none());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_discarded_recovery_is_gone() {
+    let dir = temp_dir("recovery_discard");
+    {
+        let mut app = app_in(&dir);
+        app.start_recovery();
+        app.active_tab_mut().url = "https://api.example.com".to_string();
+        app.autosave_drafts(Instant::now());
+    }
+    let mut app = app_in(&dir);
+    app.start_recovery();
+    assert!(app.recovery_prompt.is_some());
+    app.resolve_recovery(false);
+    assert!(saved(&dir).is_none());
+    assert_eq!(app.tabs.len(), 1);
+    assert_ne!(app.active_tab().url, "https://api.example.com");
+
+    let mut app = app_in(&dir);
+    app.start_recovery();
+    assert!(app.recovery_prompt.is_none());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -102,3 +102,77 @@ fn test_curl_quotes_handling() {
     assert!(val.is_some());
     assert_eq!(val.unwrap(), "value with spaces");
 }
+
+#[test]
+fn test_curl_import_data_urlencode() {
+    let mut app = App::new();
+    let curl = r#"curl https://example.com/login --data-urlencode "user=alice" --data-urlencode "note=a b&c""#;
+
+    assert!(app.import_from_curl(curl).is_ok());
+    let tab = app.active_tab();
+
+    assert_eq!(tab.method, "POST");
+    assert_eq!(tab.body_type, BodyType::UrlEncoded);
+    assert_eq!(tab.urlencoded.len(), 2);
+    assert_eq!(tab.urlencoded[1], ("note".to_string(), "a b&c".to_string()));
+    assert_eq!(tab.urlencoded_body(), "user=alice&note=a+b%26c");
+}
+
+#[test]
+fn test_curl_import_urlencoded_content_type() {
+    let mut app = App::new();
+    let curl = r#"curl -X POST https://example.com/token -H "Content-Type: application/x-www-form-urlencoded" -d "grant_type=client_credentials&scope=read%20write""#;
+
+    assert!(app.import_from_curl(curl).is_ok());
+    let tab = app.active_tab();
+
+    assert_eq!(tab.body_type, BodyType::UrlEncoded);
+    assert_eq!(
+        tab.urlencoded,
+        vec![
+            ("grant_type".to_string(), "client_credentials".to_string()),
+            ("scope".to_string(), "read write".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_wire_preview_shows_computed_headers() {
+    let mut app = App::new();
+    app.show_splash = false;
+    let tab = app.active_tab_mut();
+    tab.url = "https://api.example.com/login?next=%2Fhome".to_string();
+    tab.method = "POST".to_string();
+    tab.body_type = BodyType::UrlEncoded;
+    tab.urlencoded = vec![("user".to_string(), "ann lee".to_string())];
+    tab.auth_type = AuthType::Basic;
+    tab.basic_auth_user = "ann".to_string();
+    tab.basic_auth_pass = "pw".to_string();
+
+    app.preview_request();
+    let view = app.wire_view.clone().unwrap();
+    assert!(!view.sent);
+    assert_eq!(view.url, "POST https://api.example.com/login?next=%2Fhome");
+    assert!(
+        view.text
+            .starts_with("POST /login?next=%2Fhome HTTP/1.1\r\nhost: api.example.com\r\n")
+    );
+    assert!(view.text.contains("authorization: Basic YW5uOnB3\r\n"));
+    assert!(
+        view.text
+            .contains("content-type: application/x-www-form-urlencoded\r\n")
+    );
+    assert!(
+        view.text
+            .ends_with("content-length: 12\r\n\r\nuser=ann+lee")
+    );
+
+    // Nothing sent yet: the view stays on the preview
+    app.toggle_wire_view();
+    assert!(!app.wire_view.as_ref().unwrap().sent);
+    app.active_tab_mut().request_bytes = Some(b"POST /login HTTP/1.1\r\n\r\n".to_vec());
+    app.toggle_wire_view();
+    let view = app.wire_view.as_ref().unwrap();
+    assert!(view.sent);
+    assert!(view.text.starts_with("POST /login"));
+}
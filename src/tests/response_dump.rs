@@ -0,0 +1,158 @@
+use super::common::{last_message, temp_dir};
+use crate::app::App;
+use crate::domain::workspace::Workspace;
+use crate::features::cli::{self, RunArgs};
+use crate::features::response_dump::{self, body_extension, request_dir};
+use crate::features::runner::{CollectionRunResult, RunResult};
+use crate::net::mock_server::MockRoute;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn result(name: &str, body: Option<&str>, content_type: &str) -> RunResult {
+    RunResult {
+        name: name.to_string(),
+        method: "GET".to_string(),
+        url: format!("http://api.test/{}", name),
+        status: body.map(|_| 200),
+        latency_ms: Some(5),
+        size: body.map(str::len),
+        expected_status: Some(200),
+        passed: body.is_some(),
+        error: body.is_none().then(|| "connection refused".to_string()),
+        tests: Vec::new(),
+        response_body: body.map(str::to_string),
+        response_headers: HashMap::from([("Content-Type".to_string(), content_type.to_string())]),
+    }
+}
+
+#[test]
+fn test_names_and_extensions() {
+    assert_eq!(
+        request_dir("Users/Get by id"),
+        PathBuf::from("Users/Get-by-id")
+    );
+    assert_eq!(
+        request_dir("../etc: passwd?"),
+        PathBuf::from("request/etc-passwd")
+    );
+    assert_eq!(request_dir("v1.2 health"), PathBuf::from("v1.2-health"));
+
+    let headers = |ct: &str| HashMap::from([("content-type".to_string(), ct.to_string())]);
+    assert_eq!(
+        body_extension(&headers("application/problem+json"), ""),
+        "json"
+    );
+    assert_eq!(
+        body_extension(&headers("text/xml; charset=utf-8"), ""),
+        "xml"
+    );
+    assert_eq!(body_extension(&headers("text/html"), "{}"), "html");
+    assert_eq!(body_extension(&HashMap::new(), "[1, 2]"), "json");
+    assert_eq!(body_extension(&HashMap::new(), "plain"), "txt");
+}
+
+#[test]
+fn test_writes_a_folder_per_request() {
+    let dir = temp_dir("dump_write");
+    let results = vec![
+        result("Users/List", Some(r#"{"users": []}"#), "application/json"),
+        result("Users/List", Some("<p>again</p>"), "text/html"),
+        result("Down", None, ""),
+    ];
+    assert_eq!(response_dump::write(&dir, &results), Ok(3));
+
+    assert_eq!(
+        std::fs::read_to_string(dir.join("Users/List/body.json")).unwrap(),
+        r#"{"users": []}"#
+    );
+    assert!(dir.join("Users/List-2/body.html").exists());
+    assert!(!dir.join("Down/body.txt").exists());
+    let meta: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("Down/response.json")).unwrap())
+            .unwrap();
+    assert_eq!(meta["error"], "connection refused");
+    assert_eq!(meta["status"], serde_json::Value::Null);
+    let meta: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(dir.join("Users/List/response.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(meta["url"], "http://api.test/Users/List");
+    assert_eq!(meta["headers"]["Content-Type"], "application/json");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_export_from_the_runner() {
+    let dir = temp_dir("dump_app");
+    let mut app = App::new();
+    app.show_splash = false;
+    app.export_command("responses");
+    assert_eq!(last_message(&app), "No finished collection run to export");
+
+    app.runner_result = Some(CollectionRunResult {
+        collection_name: "shop".to_string(),
+        results: vec![result("Orders", Some("[]"), "application/json")],
+        ..Default::default()
+    });
+    let out = dir.join("out");
+    app.export_command(&format!("responses {}", out.display()));
+    assert_eq!(
+        last_message(&app),
+        format!("Saved 1 responses to {}/", out.display())
+    );
+    assert_eq!(
+        std::fs::read_to_string(out.join("Orders/body.json")).unwrap(),
+        "[]"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_cli_run_output_dir() {
+    let dir = temp_dir("dump_cli");
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let routes = vec![MockRoute {
+        path: "/users".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: r#"[{"id": 1}]"#.to_string(),
+        ..Default::default()
+    }];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let collection = dir.join("api.hcl");
+    std::fs::write(
+        &collection,
+        format!(
+            "request \"List users\" {{\n  method = \"GET\"\n  url = \"http://127.0.0.1:{}/users\"\n}}\n",
+            port
+        ),
+    )
+    .unwrap();
+    let out = dir.join("responses");
+    let args = RunArgs {
+        collection_path: collection.to_string_lossy().into_owned(),
+        env: Default::default(),
+        verbose: false,
+        json_output: true,
+        hooks: Default::default(),
+        export: None,
+        output_dir: Some(out.to_string_lossy().into_owned()),
+        tags: Vec::new(),
+        rate_limit: Default::default(),
+    };
+    let code = cli::run_collection_cli(args, &Workspace::at(&dir)).await;
+    server.handle.abort();
+    assert_eq!(code, 0);
+    assert_eq!(
+        std::fs::read_to_string(out.join("List-users/body.json")).unwrap(),
+        r#"[{"id": 1}]"#
+    );
+    assert!(out.join("List-users/response.json").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,42 @@
+use crate::app::{RequestTab, find_text_matches};
+
+#[test]
+fn test_find_text_matches_case_insensitive() {
+    let text = "Error: first\nok\nanother error, and ERROR again";
+    assert_eq!(
+        find_text_matches(text, "error"),
+        vec![(0, 0), (2, 8), (2, 19)]
+    );
+    assert!(find_text_matches(text, "").is_empty());
+    assert!(find_text_matches(text, "missing").is_empty());
+}
+
+#[test]
+fn test_json_response_is_searched_pretty_printed() {
+    let mut tab = RequestTab::new();
+    tab.response = Some(r#"{"a":{"name":"x"},"b":{"name":"y"}}"#.to_string());
+    tab.find_query = "name".to_string();
+
+    let matches = tab.find_matches();
+    assert_eq!(matches.len(), 2);
+    // Each match sits on its own line once pretty-printed
+    assert_ne!(matches[0].0, matches[1].0);
+}
+
+#[test]
+fn test_jump_to_match_wraps_around() {
+    let mut tab = RequestTab::new();
+    tab.response = Some((0..20).map(|i| format!("line {}\n", i)).collect());
+    tab.find_query = "line 1".to_string(); // line 1, 10..19
+
+    tab.jump_to_first_match();
+    assert_eq!(tab.find_index, 0);
+
+    tab.jump_to_match(false);
+    assert_eq!(tab.find_index, 10);
+    assert_eq!(tab.response_scroll.0, 16);
+
+    tab.jump_to_match(true);
+    assert_eq!(tab.find_index, 0);
+    assert_eq!(tab.response_scroll.0, 0);
+}
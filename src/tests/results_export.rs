@@ -0,0 +1,130 @@
+use super::common::temp_dir;
+use crate::app::{App, RequestLog};
+use crate::features::results_export::{self, Format};
+use crate::features::runner::{CollectionRunResult, RunResult};
+use std::collections::HashMap;
+
+fn log(method: &str, status: u16, tests: &[(&str, bool)]) -> RequestLog {
+    RequestLog {
+        method: method.to_string(),
+        url: "https://api.test/users?a=1,2".to_string(),
+        status,
+        latency: 42,
+        body: None,
+        headers: HashMap::new(),
+        response_bytes: None,
+        is_binary: false,
+        timestamp: 1_710_072_000,
+        pinned: false,
+        request: None,
+        size: 128,
+        tests: tests.iter().map(|(n, p)| (n.to_string(), *p)).collect(),
+    }
+}
+
+fn result(name: &str, status: Option<u16>, tests: &[(&str, bool)]) -> RunResult {
+    let tests: Vec<(String, bool)> = tests.iter().map(|(n, p)| (n.to_string(), *p)).collect();
+    RunResult {
+        name: name.to_string(),
+        method: "GET".to_string(),
+        url: format!("http://api.test/{}", name),
+        status,
+        latency_ms: Some(12),
+        size: status.map(|_| 64),
+        expected_status: Some(200),
+        passed: status == Some(200) && tests.iter().all(|(_, p)| *p),
+        error: status.is_none().then(|| "connection refused".to_string()),
+        tests,
+        response_body: None,
+        response_headers: Default::default(),
+    }
+}
+
+#[test]
+fn test_format_from_extension() {
+    assert_eq!(Format::for_path("out/run.JSON"), Format::Json);
+    assert_eq!(Format::for_path("run.csv"), Format::Csv);
+    assert_eq!(Format::for_path("run"), Format::Csv);
+}
+
+#[test]
+fn test_history_rows() {
+    let history = vec![
+        log("GET", 200, &[("status is 200", true), ("has id", false)]),
+        log("GRPC", 0, &[]),
+        log("POST", 500, &[]),
+    ];
+    let table = results_export::history_table(&history);
+    assert_eq!(
+        table.columns[..6],
+        [
+            "timestamp",
+            "method",
+            "url",
+            "status",
+            "latency_ms",
+            "size_bytes"
+        ]
+    );
+    assert_eq!(table.rows[0][6..], ["false", "1", "1", "has id"]);
+    // gRPC OK is status 0
+    assert_eq!(table.rows[1][6], "true");
+    assert_eq!(table.rows[2][6], "false");
+
+    let csv = results_export::history(&history, Format::Csv);
+    assert!(csv.contains("\"https://api.test/users?a=1,2\""), "{}", csv);
+    assert_eq!(csv.lines().count(), 4);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&results_export::history(&history, Format::Json)).unwrap();
+    assert_eq!(json[0]["size_bytes"], 128);
+    assert_eq!(json[0]["tests"][1]["passed"], false);
+}
+
+#[test]
+fn test_run_results() {
+    let results = vec![
+        result("login", Some(200), &[("token", true)]),
+        result("orders", None, &[]),
+    ];
+    let table = results_export::run_table("shop", &results);
+    let column = |name: &str| table.columns.iter().position(|c| c == name).unwrap();
+    assert_eq!(table.rows[0][column("size_bytes")], "64");
+    assert_eq!(table.rows[0][column("tests_passed")], "1");
+    assert_eq!(table.rows[1][column("status")], "");
+    assert_eq!(table.rows[1][column("error")], "connection refused");
+
+    let json = results_export::run_json("shop", &results);
+    assert_eq!(json["passed"], 1);
+    assert_eq!(json["failed"], 1);
+    assert_eq!(json["results"][0]["size_bytes"], 64);
+}
+
+#[test]
+fn test_export_from_the_tui() {
+    let dir = temp_dir("results_export");
+    let mut app = App::new();
+    app.show_splash = false;
+
+    let run_file = dir.join("run.csv");
+    app.export_command(&format!("run {}", run_file.display()));
+    assert!(!run_file.exists());
+
+    app.request_history = vec![log("GET", 200, &[])];
+    let mut run = CollectionRunResult::new("shop", 1);
+    run.add_result(result("login", Some(200), &[]));
+    run.finish();
+    app.runner_result = Some(run);
+
+    let history_file = dir.join("history.json");
+    app.export_command(&format!("history {}", history_file.display()));
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&history_file).unwrap()).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+
+    app.export_command(&format!("run {}", run_file.display()));
+    let csv = std::fs::read_to_string(&run_file).unwrap();
+    assert!(csv.starts_with("collection,name,method"), "{}", csv);
+    assert!(csv.contains("shop,login,GET"), "{}", csv);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,91 @@
+use crate::features::run_hooks::{self, RunHooks, hook_env, summary_json, summary_text};
+use crate::features::runner::{CollectionRunResult, RunResult};
+
+fn result(name: &str, passed: bool) -> RunResult {
+    RunResult {
+        name: name.to_string(),
+        method: "GET".to_string(),
+        url: format!("http://localhost/{}", name),
+        status: Some(if passed { 200 } else { 500 }),
+        latency_ms: Some(12),
+        size: Some(2),
+        expected_status: Some(200),
+        passed,
+        error: None,
+        tests: Vec::new(),
+        response_body: None,
+        response_headers: Default::default(),
+    }
+}
+
+fn finished_run() -> CollectionRunResult {
+    let mut run = CollectionRunResult::new("api_tests", 3);
+    run.add_result(result("Login", true));
+    run.add_result(result("Orders", false));
+    run.add_result(result("Profile", true));
+    run.finish();
+    run
+}
+
+#[test]
+fn test_should_fire_threshold() {
+    assert!(!RunHooks::default().should_fire(5));
+
+    let mut hooks = RunHooks {
+        webhook: Some("http://localhost/hook".to_string()),
+        ..Default::default()
+    };
+    assert!(hooks.should_fire(0));
+
+    hooks.failure_threshold = Some(2);
+    assert!(!hooks.should_fire(1));
+    assert!(hooks.should_fire(2));
+}
+
+#[test]
+fn test_summary_payload() {
+    let run = finished_run();
+    assert_eq!(
+        summary_text(&run),
+        "PostDad: api_tests ✗ 2/3 passed, 1 failed (Orders)"
+    );
+
+    let json = summary_json(&run);
+    assert_eq!(json["text"], summary_text(&run));
+    assert_eq!(json["failed"], 1);
+    assert_eq!(json["success"], false);
+    assert_eq!(json["results"].as_array().unwrap().len(), 3);
+    assert_eq!(json["results"][1]["status"], 500);
+}
+
+#[test]
+fn test_hook_env() {
+    let env = hook_env(&finished_run());
+    let get = |key: &str| env.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+    assert_eq!(get("POSTDAD_COLLECTION"), Some("api_tests"));
+    assert_eq!(get("POSTDAD_PASSED"), Some("2"));
+    assert_eq!(get("POSTDAD_FAILED"), Some("1"));
+    assert_eq!(get("POSTDAD_STATUS"), Some("failed"));
+    assert_eq!(get("POSTDAD_FAILED_REQUESTS"), Some("Orders"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_command_hook_sees_results() {
+    let hooks = RunHooks {
+        command: Some(r#"test "$POSTDAD_FAILED" = 1 && test "$POSTDAD_TOTAL" = 3"#.to_string()),
+        ..Default::default()
+    };
+    let outcomes = run_hooks::fire(&hooks, &finished_run()).await;
+    assert_eq!(outcomes, vec![Ok("Hook command finished".to_string())]);
+
+    let failing = RunHooks {
+        command: Some("exit 3".to_string()),
+        ..Default::default()
+    };
+    let outcomes = run_hooks::fire(&failing, &finished_run()).await;
+    assert_eq!(
+        outcomes,
+        vec![Err("Hook command exited with 3".to_string())]
+    );
+}
@@ -0,0 +1,101 @@
+use super::common::temp_dir;
+use crate::app::{App, BodyType, RequestTab};
+use crate::domain::collection::Collection;
+
+fn tab(name: &str, url: &str) -> RequestTab {
+    let mut tab = RequestTab::new();
+    tab.name = name.to_string();
+    tab.url = url.to_string();
+    tab
+}
+
+#[test]
+fn test_tabs_as_requests_keeps_order_and_names() {
+    let mut app = App::new();
+    app.tabs = vec![
+        tab("Zebra", "https://example.com/z"),
+        tab("Alpha", "https://example.com/a"),
+        tab("Alpha", "https://example.com/a2"),
+    ];
+    app.tabs[2].body_type = BodyType::UrlEncoded;
+    app.tabs[2].urlencoded = vec![("q".to_string(), "a b".to_string())];
+
+    let requests = app.tabs_as_requests();
+    let labels: Vec<&str> = requests.iter().map(|(l, _)| l.as_str()).collect();
+    assert_eq!(labels, vec!["01 Zebra", "02 Alpha", "03 Alpha"]);
+    assert_eq!(requests[0].1.url, "https://example.com/z");
+    assert_eq!(requests[0].1.body, None);
+    assert_eq!(requests[2].1.body_type.as_deref(), Some("UrlEncoded"));
+    assert_eq!(requests[2].1.body.as_deref(), Some("q=a+b"));
+}
+
+#[test]
+fn test_write_new_collection_round_trip() {
+    let dir = temp_dir("savetabs");
+    let dir_str = dir.to_string_lossy().to_string();
+
+    let requests = vec![
+        (
+            "01 Get user".to_string(),
+            tab("x", "https://example.com/u").to_request_config(),
+        ),
+        (
+            "02 List".to_string(),
+            tab("y", "https://example.com/l").to_request_config(),
+        ),
+    ];
+
+    let path = Collection::write_new(&dir_str, "Incident 42", &requests).unwrap();
+    assert!(path.ends_with("incident_42.hcl"));
+
+    // Never clobber an existing collection
+    assert!(Collection::write_new(&dir_str, "Incident 42", &requests).is_err());
+
+    let cols = Collection::load_from_dir(&dir_str).unwrap();
+    let col = cols.iter().find(|c| c.name == "incident_42").unwrap();
+    assert_eq!(col.requests.len(), 2);
+    assert_eq!(col.requests["01 Get user"].url, "https://example.com/u");
+    assert_eq!(col.requests["02 List"].method, "GET");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_rename_tab_names_the_saved_request() {
+    use crate::domain::workspace::Workspace;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let dir = temp_dir("rename_tab");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.add_tab();
+    assert!(app.active_tab().has_default_name());
+
+    let press = |app: &mut App, code: KeyCode, modifiers: KeyModifiers| {
+        crate::handler::handle_key_events(KeyEvent::new(code, modifiers), app);
+    };
+    press(&mut app, KeyCode::Char('E'), KeyModifiers::SHIFT);
+    assert_eq!(
+        app.command_input,
+        format!("rename {}", app.active_tab().name)
+    );
+    // Ctrl+w twice: "1", then "Req "
+    press(&mut app, KeyCode::Char('w'), KeyModifiers::CONTROL);
+    press(&mut app, KeyCode::Char('w'), KeyModifiers::CONTROL);
+    for c in "Login flow".chars() {
+        press(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert_eq!(app.active_tab().name, "Login flow");
+    assert!(!app.active_tab().has_default_name());
+
+    app.rename_tab("   ");
+    assert_eq!(app.active_tab().name, "Login flow");
+
+    app.save_current_request();
+    let cols = Collection::load_from_dir(&app.workspace.path("collections")).unwrap();
+    let saved = cols.iter().find(|c| c.name == "saved").unwrap();
+    assert!(saved.requests.contains_key("Login flow"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
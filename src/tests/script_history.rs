@@ -0,0 +1,199 @@
+use crate::app::{App, RequestLog};
+use crate::domain::collection::Collection;
+use crate::features::history;
+use crate::features::runner::{self, RunnerEvent};
+use crate::features::scripting::{
+    HISTORY_MAX_BODY, HISTORY_MAX_ENTRIES, HistoryEntry, guard_history, run_post_script, run_script,
+};
+use crate::net::mock_server::MockRoute;
+use std::collections::HashMap;
+
+fn entry(url: &str, status: u16, body: &str, timestamp: u64) -> HistoryEntry {
+    HistoryEntry {
+        method: "GET".to_string(),
+        url: url.to_string(),
+        status,
+        body: body.to_string(),
+        headers: HashMap::from([("ETag".to_string(), "\"v2\"".to_string())]),
+        timestamp,
+    }
+}
+
+fn log(url: &str, body: &str, timestamp: u64, is_binary: bool) -> RequestLog {
+    RequestLog {
+        method: "POST".to_string(),
+        url: url.to_string(),
+        status: 200,
+        latency: 10,
+        body: Some(body.to_string()),
+        headers: HashMap::new(),
+        response_bytes: None,
+        is_binary,
+        timestamp,
+        pinned: false,
+        request: None,
+        size: 0,
+        tests: Vec::new(),
+    }
+}
+
+#[test]
+fn test_guard_keeps_history_small() {
+    let now = 10_000;
+    let mut entries = vec![
+        entry(
+            "http://a.test/big",
+            200,
+            &"x".repeat(HISTORY_MAX_BODY + 1),
+            now,
+        ),
+        entry("http://a.test/undated", 200, "{}", 0),
+        entry("http://a.test/old", 200, "{}", now - 3601),
+    ];
+    entries.extend((0..60).map(|i| entry(&format!("http://a.test/{}", i), 200, "{}", now - 10)));
+
+    let guarded = guard_history(entries, now);
+    assert_eq!(guarded.len(), HISTORY_MAX_ENTRIES);
+    assert_eq!(guarded[0].url, "http://a.test/big");
+    assert!(guarded[0].body.is_empty());
+    assert!(
+        guarded
+            .iter()
+            .all(|e| !e.url.ends_with("/undated") && !e.url.ends_with("/old"))
+    );
+}
+
+#[test]
+fn test_pre_request_script_reads_history() {
+    let history = vec![
+        entry("http://api.test/login", 200, r#"{"token": "new"}"#, 2),
+        entry("http://api.test/orders", 404, "", 1),
+        entry("http://api.test/login", 200, r#"{"token": "old"}"#, 1),
+    ];
+    let script = r#"
+        set_header("Authorization", "Bearer " + last_json("/login", "$.token"));
+        set_var("orders", "" + last_status("/orders"));
+        set_var("etag", last_header("/orders", "etag"));
+        set_var("newest", last_response(""));
+        set_var("missing", "" + last_status("/nope") + last_response("/nope"));
+        let urls = "";
+        for e in history(2) { urls += e.method + " " + e.url + " " + e.status + ";"; }
+        set_var("urls", urls);
+        set_var("none", "" + history(-1).len());
+    "#;
+    let result = run_script(
+        script,
+        "GET",
+        "http://api.test/me",
+        &HashMap::new(),
+        "",
+        &HashMap::new(),
+        &history,
+    );
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.headers["Authorization"], "Bearer new");
+    assert_eq!(result.variables["orders"], "404");
+    assert_eq!(result.variables["etag"], "\"v2\"");
+    assert_eq!(result.variables["newest"], r#"{"token": "new"}"#);
+    assert_eq!(result.variables["missing"], "0");
+    assert_eq!(
+        result.variables["urls"],
+        "GET http://api.test/login 200;GET http://api.test/orders 404;"
+    );
+    assert_eq!(result.variables["none"], "0");
+
+    // Test scripts get the same functions
+    let result = run_post_script(
+        r#"test("same token", json_path("$.token") == last_json("login", "$.token"));"#,
+        200,
+        r#"{"token": "new"}"#,
+        &HashMap::new(),
+        5,
+        &history,
+    );
+    assert_eq!(result.tests, vec![("same token".to_string(), true)]);
+}
+
+#[test]
+fn test_app_hands_scripts_its_history() {
+    let mut app = App::new();
+    app.show_splash = false;
+    let now = history::now();
+    app.request_history = vec![
+        log("http://api.test/image", "", now, true),
+        log(
+            "http://api.test/login",
+            r#"{"token": "abc"}"#,
+            now - 5,
+            false,
+        ),
+        log(
+            "http://api.test/login",
+            r#"{"token": "stale"}"#,
+            now - 7200,
+            false,
+        ),
+    ];
+    let entries = app.script_history();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].url, "http://api.test/login");
+
+    app.active_tab_mut().url = "http://api.test/me".to_string();
+    app.active_tab_mut().pre_request_script =
+        r#"set_header("X-Token", last_json("/login", "$.token"));"#.to_string();
+    let (request, _) = app.outgoing_request();
+    assert_eq!(request.headers["X-Token"], "abc");
+}
+
+#[tokio::test]
+async fn test_runner_scripts_see_earlier_requests() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let routes = vec![MockRoute {
+        path: "/login".to_string(),
+        method: "POST".to_string(),
+        status: 200,
+        body: r#"{"token": "t-123"}"#.to_string(),
+        ..Default::default()
+    }];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    // Requests run by name: "a login" goes before "b check"
+    let hcl = format!(
+        "request \"a login\" {{\n  method = \"POST\"\n  url = \"http://127.0.0.1:{0}/login\"\n}}\n\
+         request \"b check\" {{\n  method = \"POST\"\n  url = \"http://127.0.0.1:{0}/login\"\n  \
+         post_request_script = \"test(\\\"earlier token\\\", last_json(\\\"/login\\\", \\\"$.token\\\") == \\\"t-123\\\"); test(\\\"one before\\\", history(5).len() == 1);\"\n}}\n",
+        port
+    );
+    let collection = Collection::from_hcl("chain", &hcl).unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+    runner::run_collection(
+        &collection,
+        &Default::default(),
+        &Default::default(),
+        &Vec::new(),
+        tx,
+    )
+    .await;
+    server.handle.abort();
+
+    let mut finished = None;
+    while let Ok(event) = rx.try_recv() {
+        if let RunnerEvent::Finished(result) = event {
+            finished = Some(result);
+        }
+    }
+    let result = finished.unwrap();
+    let check = result.results.iter().find(|r| r.name == "b check").unwrap();
+    assert_eq!(
+        check.tests,
+        vec![
+            ("earlier token".to_string(), true),
+            ("one before".to_string(), true)
+        ]
+    );
+}
@@ -0,0 +1,218 @@
+use super::common::{last_message, temp_dir};
+use crate::app::App;
+use crate::domain::collection::Collection;
+use crate::domain::snapshot;
+use crate::domain::workspace::Workspace;
+use crate::features::runner::{self, RunnerEvent};
+use crate::features::snapshot::{capture, drift, is_ignored, tests};
+use crate::net::mock_server::MockRoute;
+
+const USER: &str = r#"{"id": 7, "name": "Ada", "updated_at": "2026-10-17T10:00:00Z",
+    "items": [{"id": "a1", "sku": "X"}, {"id": "b2", "sku": "Y"}], "meta": {"trace": "abc"}}"#;
+
+fn respond(app: &mut App, status: u16, body: &str) {
+    let tab = app.active_tab_mut();
+    tab.status_code = Some(status);
+    tab.response = Some(body.to_string());
+    tab.response_bytes = Some(body.as_bytes().to_vec());
+    tab.test_results = vec![("status is 200".to_string(), status == 200)];
+    app.check_snapshot();
+}
+
+#[test]
+fn test_ignore_paths() {
+    let ignore = |patterns: &[&str], path: &str| {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        is_ignored(path, &patterns)
+    };
+    assert!(ignore(&["$.updated_at"], "$.updated_at"));
+    assert!(!ignore(&["$.updated_at"], "$.created_at"));
+    // Everything under an ignored path goes with it
+    assert!(ignore(&["$.meta"], "$.meta.trace"));
+    assert!(ignore(&["$.items[*].id"], "$.items[1].id"));
+    assert!(!ignore(&["$.items[*].id"], "$.items[1].sku"));
+    assert!(ignore(&["$.items[0]"], "$.items[0].sku"));
+    assert!(!ignore(&["$.items[0]"], "$.items[1].sku"));
+    assert!(ignore(&["$..timestamp"], "$.a.b[2].timestamp"));
+    assert!(ignore(&["$..timestamp"], "$.timestamp"));
+    assert!(ignore(&["$.*.trace"], "$.meta.trace"));
+    assert!(ignore(&["$['odd key'].x"], "$['odd key'].x"));
+    assert!(!ignore(&[], "$.id"));
+}
+
+#[test]
+fn test_drift_leaves_out_ignored_fields() {
+    let mut snap = capture(200, USER, vec!["$.updated_at".into(), "$.meta".into()], 1);
+    assert!(snap.json.is_some() && snap.text.is_none());
+
+    let later = USER.replace("10:00:00", "11:30:00").replace("abc", "def");
+    assert!(drift(&snap, 200, &later).is_empty());
+    assert_eq!(
+        tests(&snap, 200, &later),
+        vec![("snapshot matches".to_string(), true)]
+    );
+
+    let broken = later.replace("\"Ada\"", "\"Grace\"");
+    assert_eq!(
+        drift(&snap, 500, &broken),
+        vec!["status 200 → 500", "~ $.name: \"Ada\" → \"Grace\""]
+    );
+    assert_eq!(drift(&snap, 200, "<html>"), vec!["body is no longer JSON"]);
+
+    // Long drifts are cut short
+    snap.json = Some(serde_json::json!({}));
+    let many: String = format!(
+        "{{{}}}",
+        (0..15)
+            .map(|i| format!("\"k{:02}\": {}", i, i))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let results = tests(&snap, 200, &many);
+    assert_eq!(results.len(), 11);
+    assert!(results.iter().all(|(_, passed)| !passed));
+    assert_eq!(results[10].0, "snapshot: … and 5 more");
+}
+
+#[test]
+fn test_text_bodies_compare_whole() {
+    let snap = capture(200, "pong", Vec::new(), 1);
+    assert_eq!(snap.text.as_deref(), Some("pong"));
+    assert!(drift(&snap, 200, "pong").is_empty());
+    assert_eq!(
+        drift(&snap, 200, "pong!"),
+        vec!["body changed (4 → 5 bytes)"]
+    );
+}
+
+#[test]
+fn test_save_check_and_ignore_from_the_app() {
+    let dir = temp_dir("snapshot_app");
+    std::fs::create_dir_all(dir.join("collections")).unwrap();
+    std::fs::write(
+        dir.join("collections/users.hcl"),
+        "request \"Get user\" {\n  method = \"GET\"\n  url = \"http://users.test/7\"\n}\n",
+    )
+    .unwrap();
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+
+    app.snapshot_command("save");
+    assert!(last_message(&app).contains("open one from a collection first"));
+
+    app.open_request("users/Get user");
+    app.snapshot_command("");
+    assert_eq!(last_message(&app), "No text response to snapshot yet");
+
+    respond(&mut app, 200, USER);
+    app.snapshot_command("save");
+    assert!(last_message(&app).starts_with("Snapshot of 'Get user' saved"));
+    let file = dir.join("collections/users.snapshots.json");
+    let saved = snapshot::load(&dir.join("collections/users.hcl")).unwrap();
+    assert_eq!(saved["Get user"].status, 200);
+    assert!(file.exists());
+
+    // The next response has moved on: drift shows up with the other tests
+    let later = USER.replace("10:00:00", "11:30:00");
+    respond(&mut app, 200, &later);
+    assert_eq!(
+        app.active_tab().test_results,
+        vec![
+            ("status is 200".to_string(), true),
+            (
+                "snapshot: ~ $.updated_at: \"2026-10-17T10:00:00Z\" → \"2026-10-17T11:30:00Z\""
+                    .to_string(),
+                false
+            ),
+        ]
+    );
+    assert!(last_message(&app).starts_with("Response drifted from its snapshot (1 difference"));
+
+    app.snapshot_command("ignore $.updated_at");
+    assert!(last_message(&app).contains("ignores $.updated_at"));
+    assert_eq!(
+        app.active_tab().test_results[1],
+        ("snapshot matches".to_string(), true)
+    );
+    // Saving again keeps what's ignored, and it's all on disk for the next start
+    app.snapshot_command("save");
+    let app = App::with_workspace(Workspace::at(&dir));
+    let users = app.collections.iter().find(|c| c.name == "users").unwrap();
+    assert_eq!(
+        users.snapshots["Get user"].ignore,
+        vec!["$.updated_at".to_string()]
+    );
+
+    let mut app = app;
+    app.open_request("users/Get user");
+    app.snapshot_command("unignore $.nope");
+    assert_eq!(last_message(&app), "$.nope isn't ignored");
+    app.snapshot_command("delete");
+    assert!(!file.exists());
+    app.snapshot_command("check");
+    assert!(last_message(&app).starts_with("No snapshot of 'Get user' yet"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_runner_fails_on_drift() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let routes = vec![MockRoute {
+        path: "/user".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: USER.replace("Ada", "Grace"),
+        ..Default::default()
+    }];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let mut collection = Collection::from_hcl(
+        "users",
+        &format!(
+            "request \"Changed\" {{\n  method = \"GET\"\n  url = \"http://127.0.0.1:{0}/user\"\n}}\n\
+             request \"Unchecked\" {{\n  method = \"GET\"\n  url = \"http://127.0.0.1:{0}/user\"\n}}\n",
+            port
+        ),
+    )
+    .unwrap();
+    collection.snapshots.insert(
+        "Changed".to_string(),
+        capture(200, USER, vec!["$.updated_at".into()], 1),
+    );
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+    runner::run_collection(
+        &collection,
+        &Default::default(),
+        &Default::default(),
+        &Vec::new(),
+        tx,
+    )
+    .await;
+    server.handle.abort();
+
+    let mut finished = None;
+    while let Ok(event) = rx.try_recv() {
+        if let RunnerEvent::Finished(result) = event {
+            finished = Some(result);
+        }
+    }
+    let result = finished.unwrap();
+    let changed = result.results.iter().find(|r| r.name == "Changed").unwrap();
+    assert!(!changed.passed);
+    assert_eq!(
+        changed.tests,
+        vec![("snapshot: ~ $.name: \"Ada\" → \"Grace\"".to_string(), false)]
+    );
+    let unchecked = result
+        .results
+        .iter()
+        .find(|r| r.name == "Unchecked")
+        .unwrap();
+    assert!(unchecked.passed && unchecked.tests.is_empty());
+}
@@ -0,0 +1,202 @@
+use super::common::{last_message, press, temp_dir};
+use crate::app::{App, InputMode};
+use crate::domain::workspace::Workspace;
+use crate::features::scripting::{HistoryEntry, run_post_script, run_script};
+use crate::features::snippets::{ScriptKind, Snippet, find_snippet, insert, load_snippets, slug};
+use crossterm::event::KeyCode;
+use std::collections::HashMap;
+
+#[test]
+fn test_header_lines() {
+    let snippet = Snippet::parse(
+        "sign",
+        "// name: Sign it\n// script: post\n// What it does\nset_var(\"a\", \"b\");\n\n",
+        false,
+    );
+    assert_eq!(snippet.name, "Sign it");
+    assert_eq!(snippet.kind, Some(ScriptKind::Post));
+    assert_eq!(snippet.code, "// What it does\nset_var(\"a\", \"b\");");
+
+    let bare = Snippet::parse("bare", "print(\"hi\");", false);
+    assert_eq!((bare.name.as_str(), bare.kind), ("bare", None));
+
+    assert_eq!(insert("", &bare), "print(\"hi\");\n");
+    assert_eq!(
+        insert("let a = 1;\n\n", &bare),
+        "let a = 1;\n\nprint(\"hi\");\n"
+    );
+    assert_eq!(slug(" Refresh  Token (v2) "), "refresh-token-v2");
+}
+
+#[test]
+fn test_user_snippets_join_and_replace_builtins() {
+    let dir = temp_dir("snippets_load");
+    std::fs::create_dir_all(dir.join("collections")).unwrap();
+    let snippets_dir = dir.join("snippets");
+    std::fs::create_dir_all(&snippets_dir).unwrap();
+    std::fs::write(
+        snippets_dir.join("pagination.rhai"),
+        "// name: Our paging\nset_var(\"page\", \"2\");\n",
+    )
+    .unwrap();
+    std::fs::write(snippets_dir.join("Audit.rhai"), "print(URL);\n").unwrap();
+    std::fs::write(snippets_dir.join("notes.txt"), "not a snippet").unwrap();
+
+    let snippets = load_snippets(snippets_dir.to_str().unwrap());
+    let ids: Vec<&str> = snippets.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec!["bearer-refresh", "hmac-signing", "pagination", "audit"]
+    );
+    let paging = find_snippet(&snippets, "OUR PAGING").unwrap();
+    assert!(!paging.builtin);
+    assert_eq!(paging.code, "set_var(\"page\", \"2\");");
+    assert!(find_snippet(&snippets, "bearer-refresh").unwrap().builtin);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_builtins_run() {
+    let snippets = load_snippets("/nonexistent");
+    let code = |id: &str| find_snippet(&snippets, id).unwrap().code.clone();
+
+    let history = vec![HistoryEntry {
+        method: "POST".to_string(),
+        url: "https://auth.test/oauth/token".to_string(),
+        status: 200,
+        body: r#"{"access_token": "tok-1"}"#.to_string(),
+        timestamp: 1,
+        ..Default::default()
+    }];
+    let result = run_script(
+        &code("bearer-refresh"),
+        "GET",
+        "https://api.test/me",
+        &HashMap::new(),
+        "",
+        &HashMap::new(),
+        &history,
+    );
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.headers["Authorization"], "Bearer tok-1");
+    assert_eq!(result.variables["access_token"], "tok-1");
+
+    let vars = HashMap::from([("hmac_secret".to_string(), "key".to_string())]);
+    let result = run_script(
+        &code("hmac-signing"),
+        "POST",
+        "https://api.test/orders",
+        &HashMap::new(),
+        "",
+        &vars,
+        &[],
+    );
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.headers["X-Signature"].len(), 64);
+    assert!(result.headers["X-Timestamp"].parse::<u64>().is_ok());
+
+    let result = run_post_script(
+        &code("pagination"),
+        200,
+        r#"{"items": [1], "next_cursor": "c2"}"#,
+        &HashMap::new(),
+        10,
+        &[],
+    );
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.variables["next_cursor"], "c2");
+    assert_eq!(result.tests, vec![("page has items".to_string(), true)]);
+}
+
+#[test]
+fn test_signing_functions() {
+    let script = r#"
+        set_var("mac", hmac_sha256("key", "The quick brown fox jumps over the lazy dog"));
+        set_var("sum", sha256(""));
+    "#;
+    let result = run_script(
+        script,
+        "GET",
+        "http://x",
+        &HashMap::new(),
+        "",
+        &HashMap::new(),
+        &[],
+    );
+    assert_eq!(
+        result.variables["mac"],
+        "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+    );
+    assert_eq!(
+        result.variables["sum"],
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+}
+
+#[test]
+fn test_picker_save_and_remove() {
+    let dir = temp_dir("snippets_app");
+    std::fs::create_dir_all(dir.join("collections")).unwrap();
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.active_tab_mut().pre_request_script = "set_header(\"X-Base\", \"1\");".to_string();
+
+    app.snippet_command("");
+    assert!(app.show_snippet_picker);
+    assert_eq!(app.active_tab().input_mode, InputMode::SnippetPicker);
+    for c in "hmac".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    assert_eq!(app.filtered_snippets().len(), 1);
+    press(&mut app, KeyCode::Enter);
+    assert!(!app.show_snippet_picker);
+    let script = app.active_tab().pre_request_script.clone();
+    assert!(script.starts_with("set_header(\"X-Base\", \"1\");\n\n// Signs method"));
+    assert_eq!(
+        last_message(&app),
+        "Inserted 'HMAC request signing' into the pre-request script"
+    );
+
+    // Tab sends it to the test script instead
+    app.snippet_command("");
+    press(&mut app, KeyCode::Tab);
+    press(&mut app, KeyCode::Enter);
+    assert!(
+        app.active_tab()
+            .post_request_script
+            .contains("access_token")
+    );
+    assert!(last_message(&app).ends_with("(it's written for the pre-request script)"));
+
+    // By name it goes where it was written for
+    app.snippet_command("pagination");
+    assert!(app.active_tab().post_request_script.contains("next_cursor"));
+
+    app.snippet_command("save post Page check");
+    assert!(last_message(&app).starts_with("Snippet 'Page check' saved"));
+    let saved = std::fs::read_to_string(dir.join("snippets/page-check.rhai")).unwrap();
+    assert!(saved.starts_with("// name: Page check\n// script: post\n"));
+    app.open_snippet_picker(ScriptKind::Pre);
+    assert!(
+        app.snippets
+            .iter()
+            .any(|s| s.id == "page-check" && !s.builtin)
+    );
+    app.close_snippet_picker();
+
+    app.snippet_command("rm pagination");
+    assert_eq!(
+        last_message(&app),
+        "'Pagination loop' is built in and can't be removed"
+    );
+    app.snippet_command("rm page-check");
+    assert_eq!(last_message(&app), "Snippet 'Page check' removed");
+    assert!(!dir.join("snippets/page-check.rhai").exists());
+    app.snippet_command("nope");
+    assert_eq!(last_message(&app), "No snippet 'nope'");
+
+    app.active_tab_mut().post_request_script.clear();
+    app.snippet_command("save post Empty");
+    assert_eq!(last_message(&app), "The test script is empty");
+    let _ = std::fs::remove_dir_all(&dir);
+}
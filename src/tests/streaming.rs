@@ -0,0 +1,154 @@
+use crate::app::App;
+use crate::net::http::NetworkEvent;
+use crate::net::streaming::{self, StreamText};
+use std::collections::HashMap;
+
+fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_which_responses_stream() {
+    assert!(streaming::is_streaming(&headers(&[(
+        "transfer-encoding",
+        "chunked"
+    )])));
+    assert!(streaming::is_streaming(&headers(&[(
+        "content-type",
+        "application/x-ndjson"
+    )])));
+    assert!(!streaming::is_streaming(&headers(&[(
+        "content-length",
+        "12"
+    )])));
+    // Compressed pieces don't read on their own
+    assert!(!streaming::is_streaming(&headers(&[
+        ("transfer-encoding", "chunked"),
+        ("content-encoding", "gzip")
+    ])));
+    assert!(streaming::is_streaming(&headers(&[
+        ("transfer-encoding", "chunked"),
+        ("content-encoding", "identity")
+    ])));
+}
+
+#[test]
+fn test_stream_text_keeps_split_characters() {
+    let mut stream = StreamText::default();
+    let mut text = String::new();
+    let bytes = "{\"msg\":\"héllo\"}\n".as_bytes();
+    // The é is two bytes, cut between them
+    stream.push(&bytes[..10], &mut text);
+    assert_eq!(text, "{\"msg\":\"h");
+    stream.push(&bytes[10..], &mut text);
+    assert_eq!(text, "{\"msg\":\"héllo\"}\n");
+    assert_eq!(stream.received, bytes.len());
+
+    // Bytes that are just wrong show up replaced rather than holding things up
+    stream.push(&[0xff, b'x'], &mut text);
+    assert!(text.ends_with("\u{fffd}x"));
+}
+
+#[test]
+fn test_tail_scroll_counts_wrapped_lines() {
+    assert_eq!(streaming::tail_scroll("a\nb\nc", 10, 5), 0);
+    assert_eq!(streaming::tail_scroll("a\nb\nc\nd", 10, 2), 2);
+    // 25 characters take 3 rows at 10 wide
+    assert_eq!(streaming::tail_scroll(&"x".repeat(25), 10, 2), 1);
+}
+
+#[test]
+fn test_app_shows_chunks_as_they_arrive() {
+    let mut app = App::new();
+    app.active_tab_mut().is_loading = true;
+    app.stream_started(200, headers(&[("transfer-encoding", "chunked")]));
+    app.stream_chunk(b"{\"n\":1}\n");
+    app.stream_chunk(b"{\"n\":2}\n");
+    let tab = app.active_tab();
+    assert_eq!(tab.status_code, Some(200));
+    assert_eq!(tab.response.as_deref(), Some("{\"n\":1}\n{\"n\":2}\n"));
+    assert!(app.follow_stream);
+
+    // Scrolling back up to read stops following
+    app.scroll_up();
+    assert!(!app.follow_stream);
+    app.toggle_follow_stream();
+    assert!(app.follow_stream);
+}
+
+#[tokio::test]
+async fn test_network_sends_chunks_before_the_response() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (go_tx, go_rx) = tokio::sync::oneshot::channel::<()>();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = vec![0; 4096];
+        let _ = socket.read(&mut request).await.unwrap();
+        socket
+            .write_all(b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n8\r\n{\"n\":1}\n\r\n")
+            .await
+            .unwrap();
+        // The second line only goes out once the first one was seen
+        let _ = go_rx.await;
+        socket
+            .write_all(b"8\r\n{\"n\":2}\n\r\n0\r\n\r\n")
+            .await
+            .unwrap();
+    });
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let (reply_tx, mut reply_rx) = tokio::sync::mpsc::channel(4);
+    tokio::spawn(crate::net::http::handle_network(rx, reply_tx));
+    tx.send(NetworkEvent::RunRequest {
+        url: format!("http://127.0.0.1:{}/logs", port),
+        method: "GET".to_string(),
+        headers: Default::default(),
+        body: None,
+        form_data: None,
+        auth: None,
+        timeout_ms: Some(5000),
+        phase_timeouts: Default::default(),
+        ssl_verify: true,
+        ssl_ca_cert: None,
+        ssl_client_cert: None,
+        ssl_client_key: None,
+        proxy_url: None,
+        proxy_auth: None,
+        no_proxy: None,
+        pool: Default::default(),
+        resolve: Vec::new(),
+        request_id: String::new(),
+    })
+    .await
+    .unwrap();
+
+    let mut go = Some(go_tx);
+    let mut chunks = Vec::new();
+    loop {
+        match reply_rx.recv().await {
+            Some(NetworkEvent::Sent(..)) => {}
+            Some(NetworkEvent::Streaming(_, status, _)) => assert_eq!(status, 200),
+            Some(NetworkEvent::Chunk(_, bytes)) => {
+                chunks.push(bytes);
+                if let Some(go) = go.take() {
+                    let _ = go.send(());
+                }
+            }
+            Some(NetworkEvent::GotResponse(_, body, status, ..)) => {
+                assert_eq!(status, 200);
+                assert_eq!(body, b"{\"n\":1}\n{\"n\":2}\n");
+                break;
+            }
+            Some(NetworkEvent::Failed { error, .. }) => panic!("request failed: {}", error),
+            _ => panic!("no response"),
+        }
+    }
+    assert_eq!(chunks.concat(), b"{\"n\":1}\n{\"n\":2}\n");
+    assert_eq!(chunks[0], b"{\"n\":1}\n");
+}
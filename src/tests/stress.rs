@@ -0,0 +1,265 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::collection::RequestConfig;
+use crate::domain::workspace::Workspace;
+use crate::features::stress::{self, Metric, Stage, StressRequest, StressStats};
+
+fn stage(duration_secs: u64, target: u32) -> Stage {
+    Stage {
+        duration_secs,
+        target,
+    }
+}
+
+#[test]
+fn test_parse_stage_lists_and_presets() {
+    assert_eq!(
+        stress::parse_stages("30s:20, 1m:20, 10s:0", 5, 5).unwrap(),
+        vec![stage(30, 20), stage(60, 20), stage(10, 0)]
+    );
+    assert_eq!(
+        stress::parse_stages("", 10, 60).unwrap(),
+        vec![stage(0, 10), stage(60, 10)]
+    );
+    assert_eq!(
+        stress::parse_stages("ramp", 10, 100).unwrap(),
+        vec![stage(20, 10), stage(60, 10), stage(20, 0)]
+    );
+    let step = stress::parse_stages("Step", 8, 42).unwrap();
+    assert_eq!(stress::total_secs(&step), 42);
+    assert_eq!(stress::max_vus(&step), 8);
+    assert_eq!(stress::describe(&step)[0], "2 VUs for 10s");
+
+    assert_eq!(stress::parse_duration("1m30s"), Ok(90));
+    assert_eq!(stress::parse_duration("45"), Ok(45));
+    assert!(stress::parse_duration("5x").is_err());
+    assert!(stress::parse_stages("30s", 5, 5).is_err());
+    assert!(stress::parse_stages("10s:0", 5, 5).is_err());
+    assert!(stress::parse_stages("0s:10", 5, 5).is_err());
+}
+
+#[test]
+fn test_vus_follow_the_stages() {
+    let stages = vec![stage(10, 20), stage(20, 20), stage(0, 40), stage(10, 0)];
+    assert_eq!(stress::vus_at(&stages, 0.0), 0);
+    assert_eq!(stress::vus_at(&stages, 5.0), 10);
+    assert_eq!(stress::vus_at(&stages, 15.0), 20);
+    // The zero-length stage jumps to 40, then the last one ramps down from there
+    assert_eq!(stress::vus_at(&stages, 30.0), 40);
+    assert_eq!(stress::vus_at(&stages, 35.0), 20);
+    assert_eq!(stress::vus_at(&stages, 99.0), 0);
+
+    assert_eq!(stress::stage_at(&stages, 5.0), 0);
+    assert_eq!(stress::stage_at(&stages, 30.0), 3);
+    assert_eq!(stress::stage_at(&stages, 99.0), 3);
+    assert_eq!(
+        stress::describe(&stages),
+        vec![
+            "0 → 20 VUs over 10s",
+            "20 VUs for 20s",
+            "40 → 0 VUs over 10s"
+        ]
+    );
+}
+
+#[test]
+fn test_saved_request_brings_its_stages() {
+    let mut app = App::new();
+    app.show_splash = false;
+    let config: RequestConfig = hcl::from_str(
+        "url = \"http://localhost/checkout\"\nmethod = \"POST\"\nstress_stages = \"ramp\"\n",
+    )
+    .unwrap();
+    app.apply_request_config(config);
+    assert_eq!(app.active_tab().stress_stages, "ramp");
+    app.stress_vus_input = "10".to_string();
+    app.stress_duration_input = "50".to_string();
+    assert_eq!(
+        app.stress_stages().unwrap(),
+        vec![stage(10, 10), stage(30, 10), stage(10, 0)]
+    );
+
+    let saved = hcl::to_string(&app.active_tab().to_request_config()).unwrap();
+    assert!(saved.contains("stress_stages = \"ramp\""));
+    app.active_tab_mut().stress_stages.clear();
+    let saved = hcl::to_string(&app.active_tab().to_request_config()).unwrap();
+    assert!(!saved.contains("stress_stages"));
+
+    app.stress_vus_input = "lots".to_string();
+    assert!(app.stress_stages().is_err());
+}
+
+#[test]
+fn test_collection_workload_and_per_request_stats() {
+    let dir = temp_dir("stress");
+    std::fs::create_dir_all(dir.join("collections")).unwrap();
+    std::fs::write(
+        dir.join("collections/shop.hcl"),
+        "request \"List\" {\n  method = \"GET\"\n  url = \"{{base}}/orders\"\n}\n\
+         request \"Create\" {\n  method = \"POST\"\n  url = \"{{base}}/orders\"\n  body = \"{}\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("environments.hcl"),
+        "env \"local\" {\n  base = \"http://localhost:9\"\n}\n",
+    )
+    .unwrap();
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.active_tab_mut().url = "http://localhost/one".to_string();
+    app.selected_env_index = app
+        .environments
+        .iter()
+        .position(|e| e.name == "local")
+        .unwrap();
+
+    app.open_stress_modal("SHOP");
+    assert!(app.show_stress_modal);
+    assert_eq!(app.stress_target, "shop");
+    let requests = app.stress_requests().unwrap();
+    let names: Vec<&str> = requests.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["Create", "List"]);
+    assert_eq!(requests[1].url, "http://localhost:9/orders");
+    assert_eq!(requests[0].body.as_deref(), Some("{}"));
+
+    app.cycle_stress_target(true);
+    assert_eq!(app.stress_target, "");
+    assert_eq!(
+        app.stress_requests().unwrap()[0].url,
+        "http://localhost/one"
+    );
+    app.cycle_stress_target(false);
+    assert_eq!(app.stress_target, "shop");
+
+    app.show_stress_modal = false;
+    app.open_stress_modal("nope");
+    assert!(!app.show_stress_modal);
+
+    let results = vec![
+        (0, 10, Some(201)),
+        (1, 30, Some(200)),
+        (0, 20, None),
+        (1, 50, Some(500)),
+    ];
+    let stats = stress::per_request_stats(&requests, &results);
+    assert_eq!(stats[0].name, "Create");
+    assert_eq!(stats[0].total_requests, 2);
+    assert_eq!(stats[0].errors_count, 1);
+    assert_eq!(stats[0].avg_latency_ms, 15.0);
+    assert_eq!(stats[1].status_dist.get(&500), Some(&1));
+    assert_eq!(stats[1].max_latency_ms, 50);
+    assert_eq!(stress::percentile(&[], 0.9), 0);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_single_request_workload_keeps_its_name() {
+    let request = StressRequest {
+        name: "Req 1".to_string(),
+        url: "http://localhost".to_string(),
+        method: "GET".to_string(),
+        headers: Default::default(),
+        body: None,
+    };
+    let stats = stress::per_request_stats(std::slice::from_ref(&request), &[(0, 5, Some(200))]);
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].p99_latency_ms, 5);
+}
+
+#[test]
+fn test_thresholds_parse_and_check() {
+    let thresholds = stress::parse_thresholds("p95<300, error_rate<1%, rps>=50").unwrap();
+    assert_eq!(thresholds.len(), 3);
+    assert_eq!(thresholds[0].metric, Metric::P95);
+    assert!(thresholds[0].below && !thresholds[0].inclusive);
+    assert_eq!(thresholds[1].to_string(), "error_rate < 1%");
+    assert_eq!(thresholds[2].to_string(), "rps >= 50/s");
+
+    // The CI short form means "no worse than"
+    let p95 = stress::parse_threshold("p95=300ms").unwrap();
+    assert_eq!(p95.to_string(), "p95 <= 300ms");
+    assert!(p95.passes(300.0) && !p95.passes(301.0));
+    let rps = stress::parse_threshold("rps=100").unwrap();
+    assert!(rps.passes(120.0) && !rps.passes(99.0));
+
+    assert!(stress::parse_thresholds("").unwrap().is_empty());
+    assert!(stress::parse_threshold("p97<3").is_err());
+    assert!(stress::parse_threshold("p95").is_err());
+    assert!(stress::parse_threshold("p95<fast").is_err());
+
+    let stats = StressStats {
+        total_requests: 200,
+        errors_count: 1,
+        status_dist: [(200, 196), (503, 3)].into_iter().collect(),
+        p95_latency_ms: 280,
+        rps: 40.0,
+        ..Default::default()
+    };
+    assert_eq!(stats.error_rate(), 2.0);
+    let results = stress::check_thresholds(&stats, &thresholds);
+    let passed: Vec<bool> = results.iter().map(|r| r.passed).collect();
+    assert_eq!(passed, vec![true, false, false]);
+    assert_eq!(results[1].actual, 2.0);
+}
+
+#[tokio::test]
+async fn test_host_limit_does_not_outlast_the_test() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let route = crate::net::mock_server::MockRoute {
+        path: "/".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: "ok".to_string(),
+        ..Default::default()
+    };
+    let server = crate::net::mock_server::start_mock_server(port, vec![route]);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    // One slot a second for five VUs over one second: one request goes, the four
+    // slots booked past the end are handed back
+    let url = format!("http://localhost:{}/", port);
+    let limits = crate::net::rate_limit::RateLimitSettings {
+        per_host_rps: Some(1.0),
+        ..Default::default()
+    };
+    let started = std::time::Instant::now();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+    stress::run_stress_test(
+        stress::StressConfig {
+            requests: vec![StressRequest {
+                name: "root".to_string(),
+                url: url.clone(),
+                method: "GET".to_string(),
+                headers: Default::default(),
+                body: None,
+            }],
+            stages: vec![stage(1, 5)],
+            thresholds: Vec::new(),
+            rate_limit: limits.clone(),
+            resolve: Vec::new(),
+        },
+        tx,
+    )
+    .await;
+    server.handle.abort();
+    assert!(started.elapsed() < std::time::Duration::from_millis(1800));
+    let mut total = None;
+    while let Ok(event) = rx.try_recv() {
+        if let stress::StressEvent::Finished(stats) = event {
+            total = Some(stats.total_requests);
+        }
+    }
+    assert_eq!(total, Some(1));
+    // The host isn't left booked seconds ahead
+    let wait = crate::net::rate_limit::reserve(&limits, None, &url).map(|t| t.wait);
+    assert!(
+        wait.unwrap_or_default() < std::time::Duration::from_millis(500),
+        "{:?}",
+        wait
+    );
+}
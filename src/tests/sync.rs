@@ -0,0 +1,219 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::workspace::Workspace;
+use crate::features::gist_sync::{
+    self, Action, Direction, RemoteFiles, SyncRequest, SyncState, SyncStatus, Target,
+};
+use std::collections::BTreeMap;
+
+fn files(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(name, content)| (name.to_string(), content.to_string()))
+        .collect()
+}
+
+fn hashes(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(name, content)| (name.to_string(), gist_sync::hash(content)))
+        .collect()
+}
+
+#[test]
+fn test_plan_compares_both_sides_with_last_sync() {
+    let base = hashes(&[
+        ("same.hcl", "a"),
+        ("ours.hcl", "a"),
+        ("theirs.hcl", "a"),
+        ("both.hcl", "a"),
+        ("gone_here.hcl", "a"),
+        ("gone_there.hcl", "a"),
+    ]);
+    let local = files(&[
+        ("same.hcl", "a"),
+        ("ours.hcl", "b"),
+        ("theirs.hcl", "a"),
+        ("both.hcl", "b"),
+        ("gone_there.hcl", "a"),
+        ("new_here.hcl", "n"),
+    ]);
+    let remote = files(&[
+        ("same.hcl", "a"),
+        ("ours.hcl", "a"),
+        ("theirs.hcl", "c"),
+        ("both.hcl", "c"),
+        ("gone_here.hcl", "a"),
+        ("new_there.hcl", "n"),
+    ]);
+    let plan = gist_sync::plan(&local, &remote, &base);
+    let action = |name: &str| plan.iter().find(|f| f.name == name).unwrap().action;
+    assert_eq!(action("same.hcl"), Action::Unchanged);
+    assert_eq!(action("ours.hcl"), Action::Push);
+    assert_eq!(action("theirs.hcl"), Action::Pull);
+    assert_eq!(action("both.hcl"), Action::Conflict);
+    // Deleted on one side: not brought back
+    assert_eq!(action("gone_here.hcl"), Action::Unchanged);
+    assert_eq!(action("gone_there.hcl"), Action::Unchanged);
+    assert_eq!(action("new_here.hcl"), Action::Push);
+    assert_eq!(action("new_there.hcl"), Action::Pull);
+    assert_eq!(gist_sync::conflicts(&plan), 1);
+
+    // Without a last sync, any difference is a conflict
+    let plan = gist_sync::plan(
+        &files(&[("x.hcl", "1")]),
+        &files(&[("x.hcl", "2")]),
+        &BTreeMap::new(),
+    );
+    assert_eq!(plan[0].action, Action::Conflict);
+}
+
+#[test]
+fn test_outcome_follows_direction_and_resolutions() {
+    let base = SyncState {
+        gist_id: "g1".to_string(),
+        synced_at: 1,
+        files: hashes(&[("ours.hcl", "a"), ("theirs.hcl", "a"), ("both.hcl", "a")]),
+    };
+    let local = files(&[("ours.hcl", "b"), ("theirs.hcl", "a"), ("both.hcl", "b")]);
+    let remote = files(&[("ours.hcl", "a"), ("theirs.hcl", "c"), ("both.hcl", "c")]);
+    let mut plan = gist_sync::plan(&local, &remote, &base.files);
+
+    let pull = gist_sync::outcome(&plan, Direction::Pull, "g1", &base);
+    assert_eq!(
+        pull.pulls,
+        vec![("theirs.hcl".to_string(), "c".to_string())]
+    );
+    assert!(pull.pushes.is_empty());
+    // The local change and the open conflict
+    assert_eq!(pull.skipped, 2);
+    assert_eq!(pull.state.files["ours.hcl"], gist_sync::hash("a"));
+    assert_eq!(pull.state.files["theirs.hcl"], gist_sync::hash("c"));
+
+    assert!(gist_sync::resolve_all(&mut plan, "theirs").is_err());
+    gist_sync::resolve_all(&mut plan, "local").unwrap();
+    let push = gist_sync::outcome(&plan, Direction::Push, "g1", &base);
+    assert_eq!(
+        push.pushes,
+        vec![
+            ("both.hcl".to_string(), "b".to_string()),
+            ("ours.hcl".to_string(), "b".to_string()),
+        ]
+    );
+    assert_eq!(push.skipped, 1);
+    assert_eq!(push.state.files["both.hcl"], gist_sync::hash("b"));
+
+    // Hashes from another gist are not reused
+    let other = gist_sync::outcome(&[], Direction::Both, "g2", &base);
+    assert!(other.state.files.is_empty());
+    assert_eq!(other.state.gist_id, "g2");
+}
+
+#[test]
+fn test_conflict_prompt_keeps_chosen_side() {
+    let dir = temp_dir("sync");
+    std::fs::create_dir_all(dir.join("collections")).unwrap();
+    let request = |url: &str| {
+        format!(
+            "request \"List\" {{\n  method = \"GET\"\n  url = \"{}\"\n}}\n",
+            url
+        )
+    };
+    std::fs::write(dir.join("collections/shop.hcl"), request("http://local")).unwrap();
+    std::fs::write(dir.join("collections/blog.hcl"), request("http://blog")).unwrap();
+
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    assert_eq!(app.sync_status, SyncStatus::Idle);
+
+    // Pulling needs a gist
+    app.sync_command("pull");
+    assert!(app.sync_request.is_none());
+
+    app.sync_command("link abc123");
+    assert_eq!(app.gist.gist_id, "abc123");
+    app.sync_command("");
+    assert_eq!(
+        app.sync_request.take(),
+        Some(SyncRequest::Fetch(Target::Gist, Direction::Both))
+    );
+    assert_eq!(app.sync_status, SyncStatus::Busy);
+
+    let remote = RemoteFiles {
+        id: "abc123".to_string(),
+        updated_at: "2026-10-01T12:00:00Z".to_string(),
+        files: files(&[
+            ("shop.hcl", &request("http://remote")),
+            ("users.hcl", &request("http://users")),
+        ]),
+    };
+    app.sync_fetched(Target::Gist, Direction::Both, Ok(remote.clone()));
+    assert_eq!(app.sync_status, SyncStatus::Conflicts(1));
+    let prompt = app.sync_prompt.as_ref().unwrap();
+    assert_eq!(prompt.plan[prompt.current().unwrap()].name, "shop.hcl");
+    let diff = gist_sync::diff(
+        "shop.hcl",
+        &request("http://local"),
+        &request("http://remote"),
+    );
+    assert!(
+        diff.iter()
+            .any(|l| l.contains("-  url = \"http://remote\""))
+    );
+    assert!(diff.iter().any(|l| l.contains("+  url = \"http://local\"")));
+
+    // Esc leaves everything as it was
+    app.cancel_sync_prompt();
+    assert!(app.sync_prompt.is_none());
+    assert!(!dir.join("collections/users.hcl").exists());
+
+    app.sync_fetched(Target::Gist, Direction::Both, Ok(remote));
+    app.resolve_sync_conflict(false);
+    assert!(app.sync_prompt.is_none());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("collections/shop.hcl")).unwrap(),
+        request("http://remote")
+    );
+    assert!(dir.join("collections/users.hcl").exists());
+    assert!(app.collections.iter().any(|c| c.name == "users"));
+
+    // Files only here (blog.hcl and the sample default.hcl) go up; the hashes are
+    // recorded after the push
+    let Some(SyncRequest::Push { files, state, .. }) = app.sync_request.take() else {
+        panic!("expected a push");
+    };
+    let pushed: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(pushed, vec!["blog.hcl", "default.hcl"]);
+    app.sync_pushed(Target::Gist, Ok("abc123".to_string()), state, files.len());
+    assert!(matches!(app.sync_status, SyncStatus::Synced(_)));
+    let saved = gist_sync::load_state(&Workspace::at(&dir).path(gist_sync::STATE_FILE));
+    assert_eq!(saved.gist_id, "abc123");
+    assert_eq!(saved.files.len(), 4);
+    assert!(app.sync_summary(Target::Gist).contains("0 local change(s)"));
+
+    // A failed push keeps the old state
+    app.sync_pushed(
+        Target::Gist,
+        Err("GitHub returned 401".to_string()),
+        SyncState::default(),
+        1,
+    );
+    assert_eq!(app.sync_status, SyncStatus::Failed);
+    assert_eq!(
+        gist_sync::load_state(&Workspace::at(&dir).path(gist_sync::STATE_FILE))
+            .files
+            .len(),
+        4
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_pulled_names_stay_in_collections() {
+    let dir = temp_dir("sync_names");
+    let pulls = vec![("../config.json".to_string(), "{}".to_string())];
+    assert!(gist_sync::write_pulls(&dir.to_string_lossy(), &pulls).is_err());
+    assert!(!dir.join("../config.json").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,184 @@
+use super::common::{last_message, temp_dir};
+use crate::app::App;
+use crate::domain::collection::{Collection, parse_tags};
+use crate::domain::workspace::Workspace;
+use crate::features::cli::{self, RunArgs};
+use crate::net::mock_server::MockRoute;
+
+const API: &str = r#"
+request "Login" {
+  method = "POST"
+  url = "{{base_url}}/login"
+  tags = ["smoke", "auth"]
+}
+
+request "Orders" {
+  method = "GET"
+  url = "{{base_url}}/orders"
+  tags = ["Slow"]
+}
+
+request "Health" {
+  method = "GET"
+  url = "{{base_url}}/healthz"
+  tags = ["smoke"]
+}
+
+websocket "Feed" {
+  url = "ws://localhost/feed"
+}
+"#;
+
+fn app() -> App {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.collections = vec![
+        Collection::from_hcl("api", API).unwrap(),
+        Collection::from_hcl(
+            "admin",
+            "request \"Stats\" {\n  method = \"GET\"\n  url = \"/stats\"\n}\n",
+        )
+        .unwrap(),
+    ];
+    app
+}
+
+#[test]
+fn test_parse_and_match_tags() {
+    assert_eq!(
+        parse_tags("smoke, auth smoke  SMOKE"),
+        vec!["smoke", "auth"]
+    );
+    assert!(parse_tags(" , ").is_empty());
+
+    let api = Collection::from_hcl("api", API).unwrap();
+    assert!(api.requests["Orders"].has_any_tag(&["slow".to_string()]));
+    assert!(api.requests["Orders"].has_any_tag(&[]));
+    assert!(!api.requests["Orders"].has_any_tag(&["smoke".to_string()]));
+
+    let smoke = api.only_tagged(&["smoke".to_string()]);
+    let mut names: Vec<&String> = smoke.requests.keys().collect();
+    names.sort();
+    assert_eq!(names, vec!["Health", "Login"]);
+    assert!(smoke.ws_scenarios.is_empty());
+    assert_eq!(api.only_tagged(&[]).ws_scenarios.len(), 1);
+}
+
+#[test]
+fn test_sidebar_filter_by_tag() {
+    let mut app = app();
+    assert_eq!(app.sidebar_requests().len(), 4);
+
+    app.tag_command("filter smoke");
+    assert_eq!(last_message(&app), "Showing 2 requests tagged smoke");
+    let names: Vec<&str> = app
+        .sidebar_requests()
+        .iter()
+        .map(|(_, name, _)| name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Health", "Login"]);
+
+    // Sidebar rows line up with what's shown
+    app.collection_state.select(Some(2));
+    app.load_selected_request();
+    assert_eq!(app.active_tab().request_name.as_deref(), Some("Login"));
+
+    app.tag_command("");
+    assert_eq!(
+        last_message(&app),
+        "Tags: auth (1), slow (1), smoke (2); showing smoke"
+    );
+    app.tag_command("clear");
+    assert_eq!(last_message(&app), "Tag filter cleared");
+    assert_eq!(app.sidebar_requests().len(), 4);
+    app.tag_command("bogus");
+    assert_eq!(
+        last_message(&app),
+        "Usage: :tag [add|rm <tags> | filter [tags] | clear]"
+    );
+}
+
+#[test]
+fn test_tag_the_active_request() {
+    let mut app = app();
+    app.tag_command("add");
+    assert_eq!(last_message(&app), "Usage: :tag add <tag>[,tag]");
+    app.tag_command("add smoke, slow");
+    app.tag_command("add SMOKE");
+    assert_eq!(app.active_tab().tags, vec!["smoke", "slow"]);
+    assert_eq!(
+        app.active_tab().to_request_config().tags,
+        vec!["smoke", "slow"]
+    );
+    app.tag_command("rm smoke");
+    assert_eq!(last_message(&app), "Tags: slow");
+    app.tag_command("rm slow");
+    assert_eq!(last_message(&app), "No tags left on this request");
+}
+
+#[test]
+fn test_run_only_tagged_requests() {
+    let mut app = app();
+    app.run_collection_by_name("api --tags slow");
+    assert!(app.should_run_collection);
+    assert_eq!(app.tag_filter, vec!["slow"]);
+    let collection = app.collection_to_run(0).unwrap();
+    assert_eq!(collection.requests.len(), 1);
+    assert!(collection.requests.contains_key("Orders"));
+
+    app.tag_filter = vec!["nightly".to_string()];
+    assert_eq!(
+        app.collection_to_run(0).unwrap_err(),
+        "No requests in api tagged nightly"
+    );
+}
+
+#[tokio::test]
+async fn test_cli_run_with_tags() {
+    let dir = temp_dir("tags");
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let routes = vec![MockRoute {
+        path: "/up".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: "ok".to_string(),
+        ..Default::default()
+    }];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    // The untagged request would fail: nothing listens on port 9
+    let collection = dir.join("api.hcl");
+    std::fs::write(
+        &collection,
+        format!(
+            "request \"Up\" {{\n  method = \"GET\"\n  url = \"http://127.0.0.1:{}/up\"\n  tags = [\"smoke\"]\n}}\n\n\
+             request \"Down\" {{\n  method = \"GET\"\n  url = \"http://127.0.0.1:9/down\"\n}}\n",
+            port
+        ),
+    )
+    .unwrap();
+    let args = |tags: &str| RunArgs {
+        collection_path: collection.to_string_lossy().into_owned(),
+        env: Default::default(),
+        verbose: false,
+        json_output: true,
+        hooks: Default::default(),
+        export: None,
+        output_dir: None,
+        tags: parse_tags(tags),
+        rate_limit: Default::default(),
+    };
+    let workspace = Workspace::at(&dir);
+    assert_eq!(cli::run_collection_cli(args("smoke"), &workspace).await, 0);
+    assert_eq!(
+        cli::run_collection_cli(args("nightly"), &workspace).await,
+        1
+    );
+    server.handle.abort();
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,51 @@
+use crate::app::App;
+use crate::features::template::expand;
+
+#[test]
+fn test_uuid_shape() {
+    let out = expand("{{$uuid}}");
+    assert_eq!(out.len(), 36);
+    assert_eq!(out.chars().filter(|c| *c == '-').count(), 4);
+    assert_eq!(out.chars().nth(14), Some('4'));
+}
+
+#[test]
+fn test_random_int_in_range() {
+    for _ in 0..50 {
+        let n: i64 = expand("{{$randomInt 5 7}}").parse().unwrap();
+        assert!((5..=7).contains(&n));
+    }
+}
+
+#[test]
+fn test_lorem_word_count() {
+    let out = expand("{{$lorem 12}}");
+    assert_eq!(out.split_whitespace().count(), 12);
+}
+
+#[test]
+fn test_random_email() {
+    let out = expand("{{$randomEmail}}");
+    assert!(out.contains('@'));
+}
+
+#[test]
+fn test_unknown_and_unterminated_left_alone() {
+    assert_eq!(expand("id={{$nope 1}}"), "id={{$nope 1}}");
+    assert_eq!(expand("open {{$uuid"), "open {{$uuid");
+    assert_eq!(expand("{{plain}}"), "{{plain}}");
+}
+
+#[test]
+fn test_mixed_with_text() {
+    let out = expand(r#"{"ts": {{$timestamp}}, "n": {{$randomInt 1 1}}}"#);
+    assert!(out.starts_with(r#"{"ts": "#));
+    assert!(out.ends_with(r#", "n": 1}"#));
+}
+
+#[test]
+fn test_process_url_expands_templates() {
+    let mut app = App::new();
+    app.active_tab_mut().url = "https://api.example.com/items/{{$randomInt 3 3}}".to_string();
+    assert_eq!(app.process_url(), "https://api.example.com/items/3");
+}
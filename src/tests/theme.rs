@@ -0,0 +1,210 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::theme::{self, BUILTIN, SyntaxColors, Theme};
+use crate::domain::workspace::Workspace;
+use ratatui::style::Color;
+
+fn write_theme(dir: &std::path::Path, id: &str, content: &str) {
+    std::fs::write(
+        dir.join(theme::THEMES_DIR).join(format!("{}.toml", id)),
+        content,
+    )
+    .unwrap();
+}
+
+const NORD: &str = r##"
+name = "Nord"
+extends = "dracula"
+
+[colors]
+background = "#2e3440"
+border_focus = "#8cf"
+error = "light-red"
+accent = "75"
+
+[syntax]
+base = "base16-ocean.dark"
+string = "#a3be8c"
+key = "green"
+"##;
+
+/// The color of the first span whose text contains `needle`
+fn color_of(lines: &[ratatui::text::Line], needle: &str) -> Option<Color> {
+    lines
+        .iter()
+        .flat_map(|l| &l.spans)
+        .find(|s| s.content.contains(needle))
+        .and_then(|s| s.style.fg)
+}
+
+#[test]
+fn test_theme_file_sets_what_it_names() {
+    let nord = theme::parse("nord", NORD).unwrap();
+    assert_eq!(nord.id, "nord");
+    assert_eq!(nord.name, "Nord");
+    assert_eq!(nord.file.as_deref(), Some("themes/nord.toml"));
+    assert_eq!(nord.background, Color::Rgb(0x2e, 0x34, 0x40));
+    assert_eq!(nord.border_focus, Color::Rgb(0x88, 0xcc, 0xff));
+    assert_eq!(nord.error, Color::LightRed);
+    assert_eq!(nord.accent, Color::Indexed(75));
+    // The rest is Dracula's
+    assert_eq!(nord.success, Theme::dracula().success);
+    assert_eq!(nord.syntax.comment, Theme::dracula().syntax.comment);
+    assert_eq!(nord.syntax.string, Some(Color::Rgb(0xa3, 0xbe, 0x8c)));
+    assert_eq!(nord.syntax.key, Some(Color::Green));
+
+    // No name, no extends: named after the file, on top of the default theme
+    let plain = theme::parse("plain", "[colors]\nborder = \"blue\"").unwrap();
+    assert_eq!(plain.name, "plain");
+    assert_eq!(plain.border, Color::Blue);
+    assert_eq!(plain.accent, Theme::default_theme().accent);
+}
+
+#[test]
+fn test_theme_file_mistakes_are_explained() {
+    let err = theme::parse("x", "[colors]\nboder = \"red\"").unwrap_err();
+    assert!(err.contains("unknown colour 'boder'"), "{}", err);
+    assert!(err.contains("border_focus"), "{}", err);
+
+    let err = theme::parse("x", "[colors]\nborder = \"#12\"").unwrap_err();
+    assert!(err.contains("colors.border"), "{}", err);
+    assert!(err.contains("isn't a colour"), "{}", err);
+
+    let err = theme::parse("x", "[syntax]\nbase = \"nope\"").unwrap_err();
+    assert!(err.contains("InspiredGitHub"), "{}", err);
+
+    let err = theme::parse("x", "[syntax]\nstrings = \"red\"").unwrap_err();
+    assert!(err.contains("unknown syntax colour 'strings'"), "{}", err);
+
+    let err = theme::parse("x", "extends = \"nord\"").unwrap_err();
+    assert!(err.contains("not a built-in"), "{}", err);
+
+    assert!(theme::parse("x", "colour = 1").is_err());
+    assert!(theme::parse("x", "[colors\n").is_err());
+}
+
+#[test]
+fn test_load_all_adds_files_after_the_builtins() {
+    let dir = temp_dir("theme_load");
+    std::fs::create_dir_all(dir.join(theme::THEMES_DIR)).unwrap();
+    write_theme(&dir, "nord", NORD);
+    write_theme(&dir, "broken", "[colors]\nborder = 7up");
+    write_theme(&dir, "light", "[colors]\naccent = \"magenta\"");
+    std::fs::write(dir.join(theme::THEMES_DIR).join("notes.txt"), "not a theme").unwrap();
+
+    let (themes, errors) = theme::load_all(&dir.join(theme::THEMES_DIR).to_string_lossy());
+    let ids: Vec<&str> = themes.iter().map(|t| t.id.as_str()).collect();
+    let mut expected = BUILTIN.to_vec();
+    expected.push("nord");
+    assert_eq!(ids, expected);
+    // A file named after a built-in replaces it in place
+    let light = themes.iter().find(|t| t.id == "light").unwrap();
+    assert_eq!(light.accent, Color::Magenta);
+    assert!(light.file.is_some());
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("broken.toml: "), "{}", errors[0]);
+
+    // No themes directory: just the built-ins
+    let (themes, errors) = theme::load_all("/nonexistent/postdad/themes");
+    assert_eq!(themes.len(), BUILTIN.len());
+    assert!(errors.is_empty());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_theme_is_kept_by_name() {
+    let dir = temp_dir("theme_persist");
+    std::fs::create_dir_all(dir.join(theme::THEMES_DIR)).unwrap();
+    write_theme(&dir, "nord", NORD);
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    assert_eq!(app.themes.len(), BUILTIN.len() + 1);
+    app.set_theme("Nord");
+    assert_eq!(app.theme.id, "nord");
+
+    let reopened = App::with_workspace(Workspace::at(&dir));
+    assert_eq!(reopened.theme.id, "nord");
+    assert_eq!(reopened.theme.background, Color::Rgb(0x2e, 0x34, 0x40));
+
+    // Configs from before keep the built-in they had by index
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{"theme_index": 3, "selected_env_index": 0, "zen_mode": false}"#,
+    )
+    .unwrap();
+    let old = App::with_workspace(Workspace::at(&dir));
+    assert_eq!(old.theme.id, "dracula");
+
+    let mut app = old;
+    app.set_theme("neon");
+    assert_eq!(app.theme.id, "dracula");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_picker_previews_and_reverts() {
+    let dir = temp_dir("theme_picker");
+    std::fs::create_dir_all(dir.join(theme::THEMES_DIR)).unwrap();
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.open_theme_picker();
+    assert!(app.overlay_open());
+
+    app.move_theme_picker(true);
+    assert_eq!(app.theme.id, "matrix");
+    app.move_theme_picker(false);
+    app.move_theme_picker(false);
+    // Wraps to the last one
+    assert_eq!(app.theme.id, "solarized-light");
+
+    app.close_theme_picker(false);
+    assert_eq!(app.theme.id, "default");
+    assert!(app.theme_picker.is_none());
+
+    // A theme file added while PostDad runs shows up the next time it opens
+    write_theme(&dir, "nord", NORD);
+    app.open_theme_picker();
+    while app.theme.id != "nord" {
+        app.move_theme_picker(true);
+    }
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|f| crate::ui::render(f, &mut app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let text: String = buffer.content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("Nord (file)"));
+    // The whole screen takes the theme's background
+    assert_eq!(buffer[(0, 0)].bg, Color::Rgb(0x2e, 0x34, 0x40));
+
+    app.close_theme_picker(true);
+    assert_eq!(App::with_workspace(Workspace::at(&dir)).theme.id, "nord");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_syntax_colors_recolor_tokens() {
+    let json = "{\"name\": \"PostDad\", \"n\": 42, \"ok\": true}";
+    let colors = SyntaxColors {
+        string: Some(Color::Rgb(1, 2, 3)),
+        key: Some(Color::Green),
+        number: Some(Color::Indexed(200)),
+        keyword: Some(Color::Rgb(9, 9, 9)),
+        ..Default::default()
+    };
+    let lines = crate::ui::syntax::highlight(json, "json", &colors);
+    assert_eq!(color_of(&lines, "PostDad"), Some(Color::Rgb(1, 2, 3)));
+    // Named colours stay named, so they follow the terminal's palette
+    assert_eq!(color_of(&lines, "name"), Some(Color::Green));
+    assert_eq!(color_of(&lines, "42"), Some(Color::Indexed(200)));
+    assert_eq!(color_of(&lines, "true"), Some(Color::Rgb(9, 9, 9)));
+
+    // Untouched, it's the bundled theme as before
+    let plain = crate::ui::syntax::highlight(json, "json", &SyntaxColors::default());
+    assert_ne!(color_of(&plain, "PostDad"), Some(Color::Rgb(1, 2, 3)));
+
+    // The response cache notices a theme change
+    let mut cache = crate::ui::highlight::HighlightCache::default();
+    let (before, _) = cache.lines(json, "json", &SyntaxColors::default(), 0, 10, 80);
+    let before = color_of(&before, "PostDad");
+    let (after, _) = cache.lines(json, "json", &colors, 0, 10, 80);
+    assert_ne!(before, color_of(&after, "PostDad"));
+}
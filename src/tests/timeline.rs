@@ -0,0 +1,76 @@
+use crate::app::{App, AuthType, BodyType, TIMELINE_LIMIT};
+
+#[test]
+fn test_restore_tab_to_earlier_send() {
+    let mut app = App::new();
+    {
+        let tab = app.active_tab_mut();
+        tab.method = "POST".to_string();
+        tab.url = "https://api.test/orders?dry=1".to_string();
+        tab.params = vec![("dry".to_string(), "1".to_string())];
+        tab.params_list_state.select(Some(0));
+        tab.request_body = r#"{"qty": 1}"#.to_string();
+        tab.auth_type = AuthType::Bearer;
+        tab.auth_token = "old-token".to_string();
+    }
+    app.record_send();
+    app.active_tab_mut().finish_timeline_entry(Ok((201, 87)));
+
+    {
+        let tab = app.active_tab_mut();
+        tab.url = "https://api.test/orders".to_string();
+        tab.params.clear();
+        tab.body_type = BodyType::GraphQL;
+        tab.auth_token = "new-token".to_string();
+    }
+    app.record_send();
+    app.active_tab_mut()
+        .finish_timeline_entry(Err("timed out".to_string()));
+    // A late answer doesn't overwrite an outcome
+    app.active_tab_mut().finish_timeline_entry(Ok((200, 5)));
+
+    let timeline = &app.active_tab().timeline;
+    assert_eq!(timeline.len(), 2);
+    assert_eq!(timeline[0].status, Some(201));
+    assert_eq!(timeline[1].error.as_deref(), Some("timed out"));
+    assert_eq!(timeline[1].status, None);
+    assert_eq!(
+        timeline[1].request.changes_from(&timeline[0].request),
+        vec!["URL", "body", "auth"]
+    );
+
+    // The browser lists newest first, so the first send is index 1
+    app.open_timeline();
+    assert!(app.show_timeline);
+    app.timeline_state.select(Some(1));
+    app.restore_timeline_entry();
+    assert!(!app.show_timeline);
+
+    let tab = app.active_tab();
+    assert_eq!(tab.method, "POST");
+    assert_eq!(tab.url, "https://api.test/orders?dry=1");
+    assert_eq!(tab.params, vec![("dry".to_string(), "1".to_string())]);
+    assert_eq!(tab.body_type, BodyType::Raw);
+    assert_eq!(tab.auth_token, "old-token");
+    // Restoring doesn't count as a send
+    assert_eq!(tab.timeline.len(), 2);
+}
+
+#[test]
+fn test_timeline_is_capped() {
+    let mut app = App::new();
+    app.open_timeline();
+    assert!(!app.show_timeline);
+
+    for i in 0..TIMELINE_LIMIT + 5 {
+        app.active_tab_mut().url = format!("https://api.test/{}", i);
+        app.record_send();
+    }
+    let timeline = &app.active_tab().timeline;
+    assert_eq!(timeline.len(), TIMELINE_LIMIT);
+    assert_eq!(timeline[0].request.url, "https://api.test/5");
+    assert_eq!(
+        app.timeline_entry(0).unwrap().request.url,
+        format!("https://api.test/{}", TIMELINE_LIMIT + 4)
+    );
+}
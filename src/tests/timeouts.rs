@@ -0,0 +1,145 @@
+use super::common::{last_message, press, temp_dir};
+use crate::app::App;
+use crate::domain::collection::Collection;
+use crate::features::runner::{self, RunnerEvent};
+use crate::net::mock_server::MockRoute;
+use crate::net::timeouts::{format_ms, parse_ms};
+use crossterm::event::KeyCode;
+
+#[test]
+fn test_parse_and_format() {
+    assert_eq!(parse_ms("1500"), Ok(Some(1500)));
+    assert_eq!(parse_ms("250ms"), Ok(Some(250)));
+    assert_eq!(parse_ms(" 5s "), Ok(Some(5000)));
+    assert_eq!(parse_ms("2m"), Ok(Some(120_000)));
+    assert_eq!(parse_ms("off"), Ok(None));
+    assert_eq!(parse_ms(""), Ok(None));
+    assert!(parse_ms("0").is_err());
+    assert!(parse_ms("soon").is_err());
+    assert_eq!(format_ms(30_000), "30s");
+    assert_eq!(format_ms(1500), "1500ms");
+}
+
+#[test]
+fn test_edit_in_the_settings_tab() {
+    let mut app = App::new();
+    app.show_splash = false;
+    for _ in 0..5 {
+        press(&mut app, KeyCode::Tab);
+    }
+    assert_eq!(app.active_tab().selected_tab, 5);
+
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('e'));
+    for c in "2s".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.active_tab().connect_timeout_ms, Some(2000));
+    assert_eq!(
+        last_message(&app),
+        "Timeouts: total 30s (default), connect 2s, read within total"
+    );
+
+    // A bad value keeps the field open
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Char('x'));
+    press(&mut app, KeyCode::Enter);
+    assert!(last_message(&app).starts_with("Bad timeout"));
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.active_tab().read_timeout_ms, None);
+
+    // Emptying the total puts the default back
+    app.timeout_command("10s");
+    assert_eq!(app.active_tab().timeout_ms, 10_000);
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('e'));
+    assert_eq!(app.timeout_input, "10000");
+    app.timeout_input.clear();
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.active_tab().timeout_ms, 30_000);
+}
+
+#[test]
+fn test_saved_with_the_request() {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.timeout_command("total 2m");
+    app.timeout_command("read 500ms");
+    app.timeout_command("write 1s");
+    assert_eq!(
+        last_message(&app),
+        "Unknown timeout 'write' (total, connect, read)"
+    );
+
+    let config = app.active_tab().to_request_config();
+    assert_eq!(config.timeout_ms, Some(120_000));
+    assert_eq!(config.connect_timeout_ms, None);
+    let dir = temp_dir("timeouts");
+    let path = Collection::append_request(dir.to_str().unwrap(), "api", "Slow", &config).unwrap();
+    let saved = Collection::from_hcl("api", &std::fs::read_to_string(path).unwrap()).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(saved.requests["Slow"].read_timeout_ms, Some(500));
+
+    let mut other = App::new();
+    other.apply_request_config(saved.requests["Slow"].clone());
+    assert_eq!(other.active_tab().timeout_ms, 120_000);
+    assert_eq!(other.active_tab().read_timeout_ms, Some(500));
+    assert_eq!(other.active_tab().connect_timeout_ms, None);
+
+    // Nothing set, nothing written
+    let config = App::new().active_tab().to_request_config();
+    assert_eq!(config.timeout_ms, None);
+    assert!(!hcl::to_string(&config).unwrap().contains("read_timeout_ms"));
+}
+
+#[tokio::test]
+async fn test_runner_read_timeout() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let routes = vec![MockRoute {
+        path: "/slow".to_string(),
+        method: "GET".to_string(),
+        status: 200,
+        body: "late".to_string(),
+        delay_ms: Some(1000),
+        ..Default::default()
+    }];
+    let server = crate::net::mock_server::start_mock_server(port, routes);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let hcl = format!(
+        "request \"Slow\" {{\n  method = \"GET\"\n  url = \"http://127.0.0.1:{}/slow\"\n  read_timeout_ms = 100\n}}\n",
+        port
+    );
+    let collection = Collection::from_hcl("api", &hcl).unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+    let start = std::time::Instant::now();
+    runner::run_collection(
+        &collection,
+        &Default::default(),
+        &Default::default(),
+        &Vec::new(),
+        tx,
+    )
+    .await;
+    server.handle.abort();
+    assert!(start.elapsed() < std::time::Duration::from_millis(900));
+
+    let mut finished = None;
+    while let Ok(event) = rx.try_recv() {
+        if let RunnerEvent::Finished(result) = event {
+            finished = Some(result);
+        }
+    }
+    let result = &finished.unwrap().results[0];
+    assert!(!result.passed);
+    assert_eq!(
+        result.error.as_deref(),
+        Some("Request timed out after 30000ms (read 100ms)")
+    );
+}
@@ -0,0 +1,107 @@
+use crate::app::{App, AuthType};
+use crate::domain::environment::Environment;
+use crate::features::token_refresh::{
+    OAuthGrant, TokenAction, TokenResponse, action_for, jwt_expiry, parse_token_response,
+};
+use base64::Engine;
+
+fn jwt(claims: &str) -> String {
+    let enc = |s: &str| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(s);
+    format!("{}.{}.sig", enc(r#"{"alg":"none"}"#), enc(claims))
+}
+
+#[test]
+fn test_jwt_expiry() {
+    assert_eq!(
+        jwt_expiry(&jwt(r#"{"sub":"1","exp":1700000000}"#)),
+        Some(1700000000)
+    );
+    assert_eq!(jwt_expiry(&jwt(r#"{"sub":"1"}"#)), None);
+    assert_eq!(jwt_expiry("not-a-jwt"), None);
+}
+
+#[test]
+fn test_action_for_expiry_window() {
+    assert_eq!(action_for(10_000, 1_000, true), None);
+    assert_eq!(action_for(1_060, 1_000, true), Some(TokenAction::Refresh));
+    assert_eq!(action_for(1_060, 1_000, false), Some(TokenAction::Warn));
+    assert_eq!(action_for(900, 1_000, false), Some(TokenAction::Expired));
+}
+
+#[test]
+fn test_parse_token_response_json_and_form() {
+    let json =
+        parse_token_response(r#"{"access_token":"abc","refresh_token":"r1","expires_in":3600}"#)
+            .unwrap();
+    assert_eq!(json.access_token, "abc");
+    assert_eq!(json.refresh_token.as_deref(), Some("r1"));
+    assert_eq!(json.expires_in, Some(3600));
+
+    let form = parse_token_response("access_token=xyz&token_type=bearer&expires_in=60").unwrap();
+    assert_eq!(form.access_token, "xyz");
+    assert_eq!(form.refresh_token, None);
+    assert_eq!(form.expires_in, Some(60));
+
+    assert!(parse_token_response(r#"{"error":"bad_verification_code"}"#).is_err());
+}
+
+#[test]
+fn test_expiring_jwt_in_environment_alerts_once() {
+    let mut app = App::new();
+    let token = jwt(r#"{"exp":1000}"#);
+    app.environments = vec![Environment {
+        name: "prod".to_string(),
+        variables: [("token".to_string(), token)].into_iter().collect(),
+        ..Default::default()
+    }];
+
+    let before = app.notifications.history.len();
+    assert!(app.due_token_refreshes(2000).is_empty());
+    assert!(app.due_token_refreshes(2000).is_empty());
+    assert_eq!(app.notifications.history.len(), before + 1);
+    assert!(app.notifications.history[0].message.contains("prod.token"));
+}
+
+#[test]
+fn test_refresh_replaces_token_everywhere() {
+    let mut app = App::new();
+    app.active_tab_mut().auth_type = AuthType::Bearer;
+    app.active_tab_mut().auth_token = "old".to_string();
+    app.environments = vec![Environment {
+        name: "dev".to_string(),
+        variables: [("token".to_string(), "old".to_string())]
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    }];
+    app.oauth_grants.insert(
+        "old".to_string(),
+        OAuthGrant {
+            token_url: "http://localhost/token".to_string(),
+            client_id: "cli".to_string(),
+            refresh_token: Some("r1".to_string()),
+            expires_at: Some(100),
+        },
+    );
+
+    let jobs = app.due_token_refreshes(90);
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].0, "old");
+    // Already in flight, don't start another one
+    assert!(app.due_token_refreshes(95).is_empty());
+
+    app.apply_refreshed_token(
+        "old",
+        TokenResponse {
+            access_token: "new".to_string(),
+            refresh_token: None,
+            expires_in: Some(3600),
+        },
+    );
+
+    assert_eq!(app.active_tab().auth_token, "new");
+    assert_eq!(app.environments[0].variables["token"], "new");
+    let grant = &app.oauth_grants["new"];
+    assert_eq!(grant.refresh_token.as_deref(), Some("r1"));
+    assert!(!app.oauth_grants.contains_key("old"));
+}
@@ -0,0 +1,95 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::workspace::Workspace;
+use crate::features::trace::{self, TraceContext, TraceSettings};
+use crate::net::http::NetworkEvent;
+use std::collections::HashMap;
+
+fn settings(b3: bool) -> TraceSettings {
+    TraceSettings {
+        enabled: true,
+        b3,
+        link: "http://jaeger.test/trace/{trace_id}?span={span_id}".to_string(),
+    }
+}
+
+#[test]
+fn test_traceparent_round_trip() {
+    let context = TraceContext::generate();
+    assert_eq!(context.trace_id.len(), 32);
+    assert_eq!(context.span_id.len(), 16);
+    assert_eq!(TraceContext::parse(&context.traceparent()), Some(context));
+
+    let parsed =
+        TraceContext::parse("00-4BF92F3577B34DA6A3CE929D0E0E4736-00F067AA0BA902B7-01").unwrap();
+    assert_eq!(parsed.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert_eq!(
+        parsed.link(&settings(false).link).as_deref(),
+        Some("http://jaeger.test/trace/4bf92f3577b34da6a3ce929d0e0e4736?span=00f067aa0ba902b7")
+    );
+    assert!(parsed.link("  ").is_none());
+
+    for bad in [
+        "",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-01",
+        "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e47zz-00f067aa0ba902b7-01",
+    ] {
+        assert!(TraceContext::parse(bad).is_none(), "{}", bad);
+    }
+}
+
+#[test]
+fn test_inject() {
+    let mut headers = HashMap::new();
+    assert!(trace::inject(&TraceSettings::default(), &mut headers).is_none());
+    assert!(headers.is_empty());
+
+    let context = trace::inject(&settings(true), &mut headers).unwrap();
+    assert_eq!(headers["traceparent"], context.traceparent());
+    assert_eq!(headers["X-B3-TraceId"], context.trace_id);
+    assert_eq!(headers["X-B3-Sampled"], "1");
+
+    // A traceparent set on the request wins
+    let mut headers = HashMap::from([(
+        "TraceParent".to_string(),
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+    )]);
+    let context = trace::inject(&settings(false), &mut headers).unwrap();
+    assert_eq!(context.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert_eq!(headers.len(), 1);
+}
+
+#[tokio::test]
+async fn test_sent_with_the_request() {
+    let dir = temp_dir("trace");
+    let mut app = App::with_workspace(Workspace::at(&dir));
+    app.show_splash = false;
+    app.active_tab_mut().url = "http://localhost:1/users".to_string();
+
+    app.trace_command("b3 on");
+    app.trace_command("link http://tempo.test/{trace_id}");
+    assert!(app.trace_settings.enabled);
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+    crate::send_active_request(&mut app, &tx).await;
+    let Some(NetworkEvent::RunRequest { headers, .. }) = rx.recv().await else {
+        panic!("nothing sent");
+    };
+    let trace = app.active_tab().trace.clone().unwrap();
+    assert_eq!(headers["traceparent"], trace.traceparent());
+    assert_eq!(headers["X-B3-SpanId"], trace.span_id);
+
+    // Saved with the workspace
+    let again = App::with_workspace(Workspace::at(&dir));
+    assert_eq!(again.trace_settings, app.trace_settings);
+
+    app.trace_command("off");
+    crate::send_active_request(&mut app, &tx).await;
+    let Some(NetworkEvent::RunRequest { headers, .. }) = rx.recv().await else {
+        panic!("nothing sent");
+    };
+    assert!(!headers.contains_key("traceparent"));
+    assert!(app.active_tab().trace.is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,187 @@
+use super::common::{last_message, press};
+use crate::app::{App, BodyType, EditorMode, ResponseType};
+use crate::domain::collection::{Collection, RequestConfig};
+use crossterm::event::KeyCode;
+
+const BODY: &str = "{\"id\":1,\"tags\":[\"a\",\"b\"]}";
+
+fn type_command(app: &mut App, text: &str) {
+    press(app, KeyCode::Char(':'));
+    for c in text.chars() {
+        press(app, KeyCode::Char(c));
+    }
+    press(app, KeyCode::Enter);
+}
+
+/// An app whose active tab holds `body` served as `content_type`
+fn with_response(body: &[u8], content_type: &str) -> App {
+    let mut app = App::new();
+    app.show_splash = false;
+    let tab = app.active_tab_mut();
+    let text = String::from_utf8(body.to_vec()).ok();
+    tab.response_is_binary = text.is_none();
+    tab.response = Some(text.unwrap_or_else(|| "[binary]".to_string()));
+    tab.response_bytes = Some(body.to_vec());
+    tab.status_code = Some(200);
+    tab.latency = Some(12);
+    tab.response_headers
+        .insert("content-type".to_string(), content_type.to_string());
+    tab.reinterpret_response();
+    app
+}
+
+fn screen(app: &mut App) -> String {
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 40)).unwrap();
+    terminal.draw(|f| crate::ui::render(f, app)).unwrap();
+    terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|c| c.symbol())
+        .collect()
+}
+
+#[test]
+fn test_plain_text_json_read_as_json() {
+    let mut app = with_response(BODY.as_bytes(), "text/plain");
+    assert_eq!(app.response_extension(), "txt");
+
+    press(&mut app, KeyCode::Char('V'));
+    let tab = app.active_tab();
+    assert_eq!(tab.treat_as, Some(ResponseType::Json));
+    assert!(tab.response_json.is_some());
+    assert_eq!(app.response_extension(), "json");
+    assert_eq!(last_message(&app), "Response treated as json");
+    assert!(screen(&mut app).contains("as json (V)"));
+}
+
+#[test]
+fn test_other_types_turn_the_explorer_off() {
+    let mut app = with_response(BODY.as_bytes(), "application/json");
+    assert!(app.active_tab().response_json.is_some());
+
+    for (name, ext) in [("xml", "xml"), ("html", "html"), ("text", "txt")] {
+        type_command(&mut app, &format!("treat {}", name));
+        assert_eq!(app.active_tab().treat_as, ResponseType::parse(name));
+        assert!(app.active_tab().response_json.is_none(), "{}", name);
+        assert_eq!(app.response_extension(), ext);
+    }
+
+    type_command(&mut app, "treat auto");
+    assert_eq!(app.active_tab().treat_as, None);
+    assert!(app.active_tab().response_json.is_some());
+    assert_eq!(last_message(&app), "Response type: from Content-Type");
+
+    type_command(&mut app, "treat yaml");
+    assert!(last_message(&app).contains("Unknown type 'yaml'"));
+    assert_eq!(app.active_tab().treat_as, None);
+}
+
+#[test]
+fn test_cycle_goes_through_every_type_then_back_to_auto() {
+    let mut app = with_response(b"<a><b>1</b></a>", "text/plain");
+    let mut seen = Vec::new();
+    for _ in 0..ResponseType::ALL.len() + 1 {
+        app.cycle_treat_as();
+        seen.push(app.active_tab().treat_as);
+    }
+    let mut expected: Vec<_> = ResponseType::ALL.into_iter().map(Some).collect();
+    expected.push(None);
+    assert_eq!(seen, expected);
+
+    // Read as XML it's indented like an XML response
+    app.set_treat_as(Some(ResponseType::Xml));
+    let text = app.active_tab().display_text().unwrap();
+    assert!(text.contains("\n  <b>1</b>"), "{}", text);
+}
+
+#[test]
+fn test_json_that_isnt_says_so() {
+    let mut app = with_response(b"not json {", "text/plain");
+    app.set_treat_as(Some(ResponseType::Json));
+    assert!(app.active_tab().response_json.is_none());
+    assert!(last_message(&app).contains("isn't valid JSON"));
+    assert_eq!(app.response_extension(), "json");
+}
+
+#[test]
+fn test_binary_both_ways() {
+    let mut app = with_response(b"caf\xe9 \xff ok", "application/octet-stream");
+    assert!(app.active_tab().shown_binary());
+    assert!(screen(&mut app).contains("Binary Content Detected"));
+
+    app.set_treat_as(Some(ResponseType::Text));
+    let tab = app.active_tab();
+    assert!(!tab.shown_binary());
+    assert_eq!(tab.display_text().unwrap(), "caf\u{fffd} \u{fffd} ok");
+    assert!(screen(&mut app).contains("ok"));
+
+    // And text shown as binary, for the hex view
+    let mut app = with_response(BODY.as_bytes(), "application/json");
+    app.set_treat_as(Some(ResponseType::Binary));
+    assert!(app.active_tab().shown_binary());
+    assert!(app.active_tab().response_json.is_none());
+    assert!(app.active_tab().searchable_text().is_none());
+}
+
+#[test]
+fn test_csv_table_only_by_content_type() {
+    let mut app = with_response(b"a,b\n1,2\n", "text/csv");
+    assert!(app.active_tab().response_csv.is_some());
+    app.set_treat_as(Some(ResponseType::Text));
+    assert!(app.active_tab().response_csv.is_none());
+    app.set_treat_as(None);
+    assert!(app.active_tab().response_csv.is_some());
+}
+
+#[test]
+fn test_kept_with_the_request() {
+    let mut app = with_response(BODY.as_bytes(), "text/plain");
+    app.set_treat_as(Some(ResponseType::Html));
+    let config = app.active_tab().to_request_config();
+    assert_eq!(config.treat_as.as_deref(), Some("html"));
+
+    // Written and read back from a collection file
+    let hcl = format!(
+        "request \"page\" {{\n{}\n}}\n",
+        hcl::to_string(&config).unwrap()
+    );
+    let collection = Collection::from_hcl("site", &hcl).unwrap();
+    assert_eq!(
+        collection.requests["page"].treat_as.as_deref(),
+        Some("html")
+    );
+    // Not written when there's none
+    assert!(
+        !hcl::to_string(&RequestConfig::default())
+            .unwrap()
+            .contains("treat_as")
+    );
+
+    // A new response on the tab is read the same way
+    let tab = app.active_tab_mut();
+    tab.response = Some(BODY.to_string());
+    tab.reinterpret_response();
+    assert!(tab.response_json.is_none());
+
+    // Changed on an opened request, it comes back when that's opened again
+    app.collections = vec![collection];
+    app.open_request("site/page");
+    assert_eq!(app.active_tab().treat_as, Some(ResponseType::Html));
+    app.set_treat_as(Some(ResponseType::Text));
+    app.add_tab();
+    app.open_request("site/page");
+    assert_eq!(app.active_tab().treat_as, Some(ResponseType::Text));
+}
+
+#[test]
+fn test_graphql_body_keeps_v_for_variables() {
+    let mut app = with_response(BODY.as_bytes(), "text/plain");
+    let tab = app.active_tab_mut();
+    tab.selected_tab = 2;
+    tab.body_type = BodyType::GraphQL;
+    press(&mut app, KeyCode::Char('V'));
+    assert_eq!(app.editor_mode, EditorMode::GraphQLVariables);
+    assert_eq!(app.active_tab().treat_as, None);
+}
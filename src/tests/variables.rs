@@ -0,0 +1,215 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::collection::{Collection, RequestConfig};
+use crate::domain::environment::Environment;
+use crate::domain::variables::{self, Layer, Scope};
+use std::collections::HashMap;
+
+const USERS: &str = r#"
+variables = {
+  base_url = "https://users.test"
+  page     = 2
+  debug    = true
+}
+
+request "List users" {
+  method = "GET"
+  url = "/users?page={{page}}&size={{size}}"
+  variables = {
+    page = "7"
+  }
+}
+"#;
+
+fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn test_app() -> App {
+    let mut app = App::new();
+    app.show_splash = false;
+    app.global_variables = [("size", "10"), ("token", "global")]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    app.environments = vec![
+        Environment {
+            name: "None".to_string(),
+            ..Default::default()
+        },
+        Environment {
+            name: "dev".to_string(),
+            variables: vars(&[("base_url", "https://dev.test"), ("token", "dev")]),
+            ..Default::default()
+        },
+    ];
+    app.selected_env_index = 1;
+    app.collections = vec![Collection::from_hcl("users", USERS).unwrap()];
+    app
+}
+
+#[test]
+fn test_resolve_order() {
+    let layers = vec![
+        Layer {
+            scope: Scope::Global,
+            source: "config.json".to_string(),
+            variables: vars(&[("a", "global"), ("b", "global")]),
+        },
+        Layer {
+            scope: Scope::Environment,
+            source: "dev".to_string(),
+            variables: vars(&[("a", "env")]),
+        },
+        Layer {
+            scope: Scope::Request,
+            source: "r".to_string(),
+            variables: vars(&[("a", "request"), ("c", "request")]),
+        },
+    ];
+    let resolved = variables::resolve(&layers);
+    assert_eq!(resolved.len(), 3);
+    assert_eq!(resolved[0].name, "a");
+    assert_eq!(resolved[0].value, "request");
+    assert_eq!(resolved[0].scope, Scope::Request);
+    assert_eq!(
+        resolved[0].shadowed,
+        vec![
+            (Scope::Environment, "env".to_string()),
+            (Scope::Global, "global".to_string())
+        ]
+    );
+    assert_eq!(resolved[1].scope, Scope::Global);
+    assert_eq!(variables::merge(&layers)["a"], "request");
+
+    assert_eq!(
+        variables::references("{{a}}/{{$uuid}}/{{ b }}/{{a}}/{{c")
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![" b ", "a"]
+    );
+}
+
+#[test]
+fn test_collection_variables_from_hcl() {
+    let users = Collection::from_hcl("users", USERS).unwrap();
+    assert_eq!(users.variables["page"], "2");
+    assert_eq!(users.variables["debug"], "true");
+    assert_eq!(users.requests["List users"].variables["page"], "7");
+
+    let err = Collection::from_hcl("bad", "variables = \"nope\"\n").unwrap_err();
+    assert!(err.contains("table"), "{}", err);
+
+    let hcl = crate::domain::collection::variables_hcl(&users.variables).unwrap();
+    let again = Collection::from_hcl("users", &hcl).unwrap();
+    assert_eq!(again.variables, users.variables);
+    assert!(
+        crate::domain::collection::variables_hcl(&HashMap::new())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_request_sees_every_scope() {
+    let mut app = test_app();
+    // Outside a collection only the environment and global values apply
+    app.active_tab_mut().url = "{{base_url}}/{{token}}/{{size}}".to_string();
+    assert_eq!(app.process_url(), "https://dev.test/dev/10");
+
+    app.open_request("users/List users");
+    assert_eq!(app.active_tab().collection.as_deref(), Some("users"));
+    assert_eq!(app.process_url(), "https://users.test/users?page=7&size=10");
+
+    app.inspect_variables();
+    let inspection = app.variables_inspector.clone().unwrap();
+    let page = inspection
+        .variables
+        .iter()
+        .find(|v| v.name == "page")
+        .unwrap();
+    assert_eq!(page.scope, Scope::Request);
+    assert_eq!(page.shadowed, vec![(Scope::Collection, "2".to_string())]);
+    let base = inspection
+        .variables
+        .iter()
+        .find(|v| v.name == "base_url")
+        .unwrap();
+    assert_eq!(
+        (base.scope, base.source.as_str()),
+        (Scope::Collection, "users")
+    );
+    assert!(inspection.used.contains("size"));
+    assert!(inspection.missing.is_empty());
+    assert_eq!(
+        inspection.layers[1],
+        (Scope::Environment, "dev".to_string())
+    );
+
+    app.active_tab_mut().url = "{{nope}}".to_string();
+    app.inspect_variables();
+    assert_eq!(
+        app.variables_inspector.as_ref().unwrap().missing,
+        vec!["nope".to_string()]
+    );
+
+    // Saved back with the request
+    assert_eq!(app.active_tab().to_request_config().variables["page"], "7");
+}
+
+#[test]
+fn test_set_variable_where_defined() {
+    let mut app = test_app();
+    app.open_request("users/List users");
+    app.set_variable("page".to_string(), "8".to_string());
+    app.set_variable("base_url".to_string(), "https://other.test".to_string());
+    app.set_variable("session".to_string(), "abc".to_string());
+
+    assert_eq!(app.active_tab().variables["page"], "8");
+    assert_eq!(
+        app.collections[0].variables["base_url"],
+        "https://other.test"
+    );
+    assert_eq!(app.environments[1].variables["session"], "abc");
+    assert_eq!(
+        app.environments[1].variables["base_url"],
+        "https://dev.test"
+    );
+}
+
+#[test]
+fn test_postman_collection_variables() {
+    let content = r#"{
+      "info": {"name": "Shop"},
+      "item": [],
+      "variable": [
+        {"key": "base_url", "value": "https://shop.test"},
+        {"key": "retries", "value": 3},
+        {"key": "old", "value": "x", "disabled": true}
+      ]
+    }"#;
+    let (collection, _) = crate::features::import::parse_postman_collection(content).unwrap();
+    assert_eq!(collection.variables.len(), 2);
+    assert_eq!(collection.variables["retries"], "3");
+
+    let dir = temp_dir("variables");
+    let mut collection = collection;
+    collection.requests.insert(
+        "Ping".to_string(),
+        RequestConfig {
+            url: "{{base_url}}/ping".to_string(),
+            method: "GET".to_string(),
+            ..Default::default()
+        },
+    );
+    let path =
+        crate::features::import::write_collection(&collection, dir.to_str().unwrap()).unwrap();
+    let content = std::fs::read_to_string(path).unwrap();
+    let loaded = Collection::from_hcl("shop", &content).unwrap();
+    assert_eq!(loaded.variables["base_url"], "https://shop.test");
+    assert_eq!(loaded.requests.len(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,153 @@
+use super::common::temp_dir;
+use crate::app::App;
+use crate::domain::workspace::{self, Workspace};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[test]
+fn test_resolve_names_and_paths() {
+    let data = Some(PathBuf::from("/data/postdad/workspaces"));
+    assert_eq!(
+        workspace::resolve_in("shop", data.clone()).unwrap(),
+        Workspace::at("/data/postdad/workspaces/shop")
+    );
+    assert_eq!(
+        workspace::resolve_in("./api", data.clone()).unwrap(),
+        Workspace::at("./api")
+    );
+    assert_eq!(
+        workspace::resolve_in(".", data.clone()).unwrap(),
+        Workspace::default()
+    );
+    assert!(workspace::resolve_in("  ", data).is_err());
+    assert!(workspace::resolve_in("shop", None).is_err());
+
+    let ws = Workspace::at("/data/postdad/workspaces/shop");
+    assert_eq!(
+        ws.path("history.json"),
+        "/data/postdad/workspaces/shop/history.json"
+    );
+    assert_eq!(Workspace::default().path("history.json"), "history.json");
+}
+
+#[test]
+fn test_data_dir_follows_xdg() {
+    let home = Some(PathBuf::from("/home/me"));
+    assert_eq!(
+        workspace::data_dir_from(Some(PathBuf::from("/xdg")), home.clone(), None),
+        Some(PathBuf::from("/xdg/postdad"))
+    );
+    // A relative XDG_DATA_HOME is ignored
+    assert_eq!(
+        workspace::data_dir_from(Some(PathBuf::from("rel")), home, None),
+        Some(PathBuf::from("/home/me/.local/share/postdad"))
+    );
+    assert_eq!(
+        workspace::data_dir_from(None, None, Some(PathBuf::from("C:/AppData"))),
+        Some(PathBuf::from("C:/AppData/postdad"))
+    );
+    assert_eq!(workspace::data_dir_from(None, None, None), None);
+}
+
+#[test]
+fn test_workspaces_keep_their_own_files() {
+    let base = temp_dir("workspace");
+    let (shop, blog) = (base.join("shop"), base.join("blog"));
+    std::fs::create_dir_all(shop.join("collections")).unwrap();
+    std::fs::write(
+        shop.join("collections/orders.hcl"),
+        "request \"List\" {\n  method = \"GET\"\n  url = \"http://shop/orders\"\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        shop.join("environments.hcl"),
+        "env \"shop-dev\" {\n  a = \"1\"\n}\n",
+    )
+    .unwrap();
+    assert_eq!(workspace::list_in(&base), vec!["shop"]);
+
+    let mut app = App::with_workspace(Workspace::at(&shop));
+    app.show_splash = false;
+    assert!(app.collections.iter().any(|c| c.name == "orders"));
+    assert!(app.environments.iter().any(|e| e.name == "shop-dev"));
+
+    app.add_history(
+        "GET".to_string(),
+        "http://shop/orders".to_string(),
+        12,
+        200,
+        None,
+        HashMap::new(),
+        None,
+        false,
+    );
+    assert!(shop.join("history.json").exists());
+
+    app.switch_workspace(&blog.to_string_lossy());
+    assert_eq!(app.workspace, Workspace::at(&blog));
+    assert!(app.request_history.is_empty());
+    assert!(!app.collections.iter().any(|c| c.name == "orders"));
+    assert!(!blog.join("history.json").exists());
+    assert_eq!(workspace::list_in(&base), vec!["blog", "shop"]);
+
+    app.switch_workspace(&shop.to_string_lossy());
+    assert_eq!(app.request_history.len(), 1);
+    assert_eq!(app.request_history[0].url, "http://shop/orders");
+
+    let _ = std::fs::remove_dir_all(&base);
+}
+
+#[test]
+fn test_default_workspace_splits_config_from_data() {
+    assert_eq!(
+        workspace::config_dir_from(None, Some(PathBuf::from("/home/me")), None),
+        Some(PathBuf::from("/home/me/.config/postdad"))
+    );
+    let ws = Workspace {
+        root: PathBuf::from("/data/postdad"),
+        config_root: Some(PathBuf::from("/config/postdad")),
+    };
+    assert_eq!(ws.path("config.json"), "/config/postdad/config.json");
+    assert_eq!(ws.path("keymap.toml"), "/config/postdad/keymap.toml");
+    assert_eq!(ws.path("history.json"), "/data/postdad/history.json");
+    assert_eq!(ws.path("collections"), "/data/postdad/collections");
+    assert_eq!(ws.label(), "default");
+    assert!(ws.is_default());
+    assert!(!Workspace::at("/data/postdad/workspaces/shop").is_default());
+}
+
+#[test]
+fn test_migrate_copies_without_overwriting() {
+    let base = temp_dir("migrate");
+    let old = base.join("project");
+    std::fs::create_dir_all(old.join("collections")).unwrap();
+    std::fs::write(old.join("collections/api.hcl"), "# api").unwrap();
+    std::fs::write(old.join("history.json"), "[]").unwrap();
+    std::fs::write(old.join("config.json"), "{}").unwrap();
+    std::fs::write(old.join("README.md"), "not ours").unwrap();
+    assert!(workspace::has_legacy_files(&old));
+    assert!(!workspace::has_legacy_files(&base));
+
+    let to = Workspace {
+        root: base.join("data"),
+        config_root: Some(base.join("config")),
+    };
+    std::fs::create_dir_all(base.join("data")).unwrap();
+    std::fs::write(base.join("data/history.json"), "[\"newer\"]").unwrap();
+
+    let copied = workspace::migrate(&old, &to).unwrap();
+    assert_eq!(copied, vec!["config.json", "collections/api.hcl"]);
+    assert!(base.join("config/config.json").exists());
+    assert!(base.join("data/collections/api.hcl").exists());
+    assert!(!base.join("data/README.md").exists());
+    // Already there: left alone
+    assert_eq!(
+        std::fs::read_to_string(base.join("data/history.json")).unwrap(),
+        "[\"newer\"]"
+    );
+    // The originals stay
+    assert!(old.join("history.json").exists());
+    assert!(workspace::migrate(&old, &to).unwrap().is_empty());
+
+    let _ = std::fs::remove_dir_all(&base);
+}
@@ -0,0 +1,88 @@
+use crate::domain::collection::{WsExpectation, WsScenarioConfig};
+use crate::features::runner::{message_matches, run_ws_scenario};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+
+fn expect(path: Option<&str>, equals: Option<&str>, regex: Option<&str>) -> WsExpectation {
+    WsExpectation {
+        path: path.map(|s| s.to_string()),
+        equals: equals.map(|s| s.to_string()),
+        regex: regex.map(|s| s.to_string()),
+    }
+}
+
+#[test]
+fn test_json_path_equals() {
+    let e = expect(Some("$.type"), Some("pong"), None);
+    assert!(message_matches(&e, r#"{"type": "pong"}"#));
+    assert!(!message_matches(&e, r#"{"type": "ping"}"#));
+    assert!(!message_matches(&e, "not json"));
+}
+
+#[test]
+fn test_regex_on_raw_message() {
+    let e = expect(None, None, Some(r"^hello \d+$"));
+    assert!(message_matches(&e, "hello 42"));
+    assert!(!message_matches(&e, "hello world"));
+}
+
+#[test]
+fn test_json_path_exists() {
+    let e = expect(Some("$.data.id"), None, None);
+    assert!(message_matches(&e, r#"{"data": {"id": 7}}"#));
+    assert!(!message_matches(&e, r#"{"data": {}}"#));
+}
+
+#[test]
+fn test_parse_websocket_block() {
+    let hcl_src = r#"
+websocket "Echo" {
+  url = "ws://localhost:9000"
+  send = ["{\"type\": \"ping\"}"]
+  expect = [{ path = "$.type", equals = "pong" }, { regex = "bye" }]
+  timeout_ms = 2000
+}
+"#;
+    let body: hcl::Body = hcl::from_str(hcl_src).unwrap();
+    let block = body.blocks().next().unwrap();
+    assert_eq!(block.identifier(), "websocket");
+
+    let config: WsScenarioConfig = hcl::from_body(block.body().clone()).unwrap();
+    assert_eq!(config.url, "ws://localhost:9000");
+    assert_eq!(config.send.len(), 1);
+    assert_eq!(config.expect.len(), 2);
+    assert_eq!(config.expect[1].regex.as_deref(), Some("bye"));
+    assert_eq!(config.timeout_ms, Some(2000));
+}
+
+#[tokio::test]
+async fn test_run_scenario_against_echo_server() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        if let Ok((stream, _)) = listener.accept().await
+            && let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await
+        {
+            while let Some(Ok(msg)) = ws.next().await {
+                if msg.is_text() && ws.send(msg).await.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    let config = WsScenarioConfig {
+        url: "ws://127.0.0.1:{{port}}".to_string(),
+        send: vec![r#"{"type": "pong"}"#.to_string()],
+        expect: vec![expect(Some("$.type"), Some("pong"), None)],
+        timeout_ms: Some(2000),
+    };
+    let mut vars = HashMap::new();
+    vars.insert("port".to_string(), port.to_string());
+
+    let result = run_ws_scenario("Echo", &config, &vars).await;
+    assert!(result.passed, "{:?}", result);
+    assert_eq!(result.tests.len(), 1);
+    assert_eq!(result.status, Some(101));
+}
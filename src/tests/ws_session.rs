@@ -0,0 +1,201 @@
+use super::common::{last_message, temp_dir};
+use crate::app::{App, AppMode};
+use crate::features::ws_session::{Direction, SessionLog};
+use crate::net::websocket::{WsCommand, WsEvent, WsMessage, spawn_ws_handler};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use futures_util::StreamExt;
+use std::time::{Duration, Instant};
+
+fn message(content: &str, is_sent: bool, unix_ms: u64) -> WsMessage {
+    WsMessage {
+        unix_ms,
+        ..WsMessage::new(content.to_string(), is_sent)
+    }
+}
+
+fn temp_file(name: &str) -> String {
+    temp_dir("ws_session")
+        .join(format!("{}.json", name))
+        .to_string_lossy()
+        .to_string()
+}
+
+fn ws_app() -> App {
+    let mut app = App::new();
+    app.show_splash = false;
+    let tab = app.active_tab_mut();
+    tab.app_mode = AppMode::WebSocket;
+    tab.ws_url = "ws://localhost:9000/feed".to_string();
+    tab.ws_messages = vec![
+        message("hello", false, 1_000_000),
+        message("{\"subscribe\":\"prices\"}", true, 1_000_500),
+        message("{\"price\":1}", false, 1_000_600),
+        message("{\"unsubscribe\":\"prices\"}", true, 1_002_000),
+    ];
+    app
+}
+
+#[test]
+fn test_log_keeps_both_directions_with_offsets() {
+    let app = ws_app();
+    let tab = app.active_tab();
+    let log = SessionLog::from_messages(&tab.ws_url, &tab.ws_messages);
+    assert_eq!(log.url, "ws://localhost:9000/feed");
+    let offsets: Vec<u64> = log.messages.iter().map(|m| m.offset_ms).collect();
+    assert_eq!(offsets, vec![0, 500, 600, 2000]);
+    assert_eq!(log.messages[0].direction, Direction::Received);
+    assert_eq!(log.messages[1].direction, Direction::Sent);
+    assert_eq!(log.messages[3].timestamp_ms, 1_002_000);
+
+    let json = log.to_json();
+    assert!(json.contains("\"direction\": \"sent\""));
+    assert_eq!(SessionLog::parse(&json).unwrap(), log);
+    assert!(SessionLog::parse("[1, 2]").is_err());
+
+    // Only what was sent, timed from the first of it
+    assert_eq!(
+        log.replay_schedule(),
+        vec![
+            (0, "{\"subscribe\":\"prices\"}".to_string()),
+            (1500, "{\"unsubscribe\":\"prices\"}".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_hand_written_log_replays_in_time_order() {
+    let log = SessionLog::parse(
+        r#"{"url": "ws://x", "messages": [
+            {"direction": "sent", "offset_ms": 900, "content": "b"},
+            {"direction": "sent", "offset_ms": 400, "content": "a"},
+            {"direction": "received", "offset_ms": 0, "content": "hi"}
+        ]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        log.replay_schedule(),
+        vec![(0, "a".to_string()), (500, "b".to_string())]
+    );
+}
+
+#[test]
+fn test_export_and_replay_from_the_app() {
+    let mut app = ws_app();
+    let path = temp_file("export");
+    app.export_ws_session(&path);
+    assert!(last_message(&app).contains("Saved 4 WebSocket messages"));
+    let saved = SessionLog::parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved.messages.len(), 4);
+
+    // Replayed from the HTTP screen, against whatever URL the bar has now
+    let tab = app.active_tab_mut();
+    tab.app_mode = AppMode::Http;
+    tab.ws_url = "ws://localhost:9001/feed".to_string();
+    app.ws_replay_command(&path);
+    let tab = app.active_tab();
+    assert_eq!(tab.app_mode, AppMode::WebSocket);
+    assert!(tab.ws_messages.is_empty());
+    assert_eq!(tab.ws_replay, Some((0, 2)));
+    assert_eq!(app.ws_replay_request.as_ref().unwrap().len(), 2);
+    assert!(last_message(&app).contains("ws://localhost:9001/feed"));
+
+    app.ws_replayed("{\"subscribe\":\"prices\"}".to_string());
+    assert_eq!(app.active_tab().ws_replay, Some((1, 2)));
+    assert!(app.active_tab().ws_messages[0].is_sent);
+    app.ws_replay_finished(2);
+    assert_eq!(app.active_tab().ws_replay, None);
+    assert_eq!(last_message(&app), "Replay done: 2 messages sent");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_replay_mistakes() {
+    let mut app = ws_app();
+    app.ws_replay_command("/nonexistent/session.json");
+    assert!(last_message(&app).starts_with("Couldn't replay /nonexistent/session.json"));
+    assert!(app.ws_replay_request.is_none());
+
+    app.active_tab_mut().ws_messages.retain(|m| !m.is_sent);
+    app.ws_replay_command("");
+    assert_eq!(last_message(&app), "No sent messages to replay");
+
+    app.active_tab_mut().ws_messages.clear();
+    app.export_ws_session("");
+    assert_eq!(last_message(&app), "No WebSocket messages to export");
+}
+
+#[test]
+fn test_r_replays_the_screen_and_stops() {
+    let mut app = ws_app();
+    let press = |app: &mut App, c| {
+        crate::handler::handle_key_events(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE), app)
+    };
+    press(&mut app, 'r');
+    assert_eq!(app.active_tab().ws_replay, Some((0, 2)));
+    assert!(app.ws_replay_request.take().is_some());
+
+    press(&mut app, 'r');
+    assert_eq!(app.active_tab().ws_replay, None);
+    assert!(app.should_stop_ws_replay);
+    assert_eq!(last_message(&app), "Replay stopped");
+}
+
+#[tokio::test]
+async fn test_replay_keeps_the_gaps() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (seen_tx, mut seen_rx) = tokio::sync::mpsc::channel::<(String, Instant)>(8);
+    tokio::spawn(async move {
+        if let Ok((stream, _)) = listener.accept().await
+            && let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await
+        {
+            while let Some(Ok(msg)) = ws.next().await {
+                if msg.is_text() {
+                    let _ = seen_tx
+                        .send((msg.to_text().unwrap().to_string(), Instant::now()))
+                        .await;
+                }
+            }
+        }
+    });
+
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(32);
+    let handle = spawn_ws_handler(event_tx);
+    let commands = [
+        WsCommand::Connect(format!("ws://127.0.0.1:{}", port)),
+        WsCommand::Replay(vec![(0, "first".to_string()), (300, "second".to_string())]),
+    ];
+    for command in commands {
+        handle.command_tx.send(command).await.unwrap();
+    }
+
+    let timeout = Duration::from_secs(5);
+    let (first, at_first) = tokio::time::timeout(timeout, seen_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    let (second, at_second) = tokio::time::timeout(timeout, seen_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((first.as_str(), second.as_str()), ("first", "second"));
+    assert!(at_second - at_first >= Duration::from_millis(250));
+
+    let mut events = Vec::new();
+    while let Ok(Some(event)) = tokio::time::timeout(timeout, event_rx.recv()).await {
+        let done = matches!(event, WsEvent::ReplayFinished(_));
+        events.push(format!("{:?}", event));
+        if done {
+            break;
+        }
+    }
+    assert_eq!(
+        events,
+        vec![
+            "Connected",
+            "Replayed(\"first\")",
+            "Replayed(\"second\")",
+            "ReplayFinished(2)"
+        ]
+    );
+}
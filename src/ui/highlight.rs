@@ -0,0 +1,254 @@
+// Response highlighting that doesn't redo syntect's work every frame. Small bodies
+// are highlighted once and kept; big ones get only the lines around the viewport
+// highlighted right away while a background thread does the whole body.
+use crate::domain::theme::SyntaxColors;
+use ratatui::text::Line;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// Bodies at least this big are highlighted in the background
+pub const BACKGROUND_BYTES: usize = 256 * 1024;
+/// Lines highlighted above and below the viewport while the background job runs
+pub const MARGIN: usize = 200;
+/// Bytes from each end of the body that go into its fingerprint
+const FINGERPRINT_BYTES: usize = 4096;
+
+/// The response's highlighted lines, kept on the tab between frames
+#[derive(Clone, Debug, Default)]
+pub struct HighlightCache {
+    /// What the lines were highlighted from, see `fingerprint`, and in which colours
+    key: Option<u64>,
+    /// Where the body's lines are, built with the key
+    index: LineIndex,
+    /// Every line, once it's done
+    full: Option<Arc<Vec<Line<'static>>>>,
+    /// Filled in by the background job
+    job: Option<Arc<Job>>,
+    /// `count` lines from `start` on (fewer at the end of the body), highlighted
+    /// as if the body started there
+    window: Option<Window>,
+}
+
+#[derive(Clone, Debug)]
+struct Window {
+    start: usize,
+    count: usize,
+    lines: Vec<Line<'static>>,
+}
+
+/// A background highlight of a whole body. It stops once the last cache holding
+/// it lets go, so a response replaced mid-way doesn't keep a thread busy.
+#[derive(Debug)]
+struct Job {
+    done: Arc<OnceLock<Arc<Vec<Line<'static>>>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Where each line of a body starts, and the wrapped rows before each one at the
+/// last width asked for, so scrolling doesn't walk the whole body every frame
+#[derive(Clone, Debug, Default)]
+pub struct LineIndex {
+    /// Byte offset of every line's start, then the end of the body
+    starts: Vec<usize>,
+    /// The width `rows` was counted at
+    width: usize,
+    /// Wrapped rows before each line, then the total
+    rows: Vec<usize>,
+}
+
+impl LineIndex {
+    /// Lines as `str::lines` splits them
+    pub fn new(text: &str) -> LineIndex {
+        let mut starts: Vec<usize> = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        // A trailing newline doesn't start another line
+        if starts.last() != Some(&text.len()) {
+            starts.push(text.len());
+        }
+        LineIndex {
+            starts,
+            ..Default::default()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.starts.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Line `i` of `text`, without its line ending
+    pub fn line<'a>(&self, text: &'a str, i: usize) -> &'a str {
+        let line = &text[self.starts[i]..self.starts[i + 1]];
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        line.strip_suffix('\r').unwrap_or(line)
+    }
+
+    /// Lines `first..end` of `text` as one slice
+    fn range<'a>(&self, text: &'a str, first: usize, end: usize) -> &'a str {
+        &text[self.starts[first]..self.starts[end]]
+    }
+
+    /// Counts rows the way `streaming::tail_scroll` does, once per width
+    fn count_rows(&mut self, text: &str, width: usize) {
+        let width = width.max(1);
+        if self.width == width && self.rows.len() == self.starts.len() {
+            return;
+        }
+        self.width = width;
+        self.rows = Vec::with_capacity(self.starts.len());
+        let mut rows = 0;
+        self.rows.push(rows);
+        for i in 0..self.len() {
+            rows += self.line(text, i).chars().count().div_ceil(width).max(1);
+            self.rows.push(rows);
+        }
+    }
+
+    /// The line that wrapped row `row` falls in, in a pane `width` columns wide;
+    /// `len()` past the end
+    pub fn line_at_row(&mut self, text: &str, row: usize, width: usize) -> usize {
+        self.count_rows(text, width);
+        let line = self.rows.partition_point(|&r| r <= row).saturating_sub(1);
+        line.min(self.len())
+    }
+
+    /// Wrapped rows before line `i`, at the width of the last `line_at_row`
+    pub fn rows_before(&self, i: usize) -> usize {
+        self.rows.get(i).copied().unwrap_or_default()
+    }
+}
+
+/// Tells bodies apart without hashing megabytes every frame: the length, the
+/// extension and both ends
+pub fn fingerprint(text: &str, extension: &str) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    let bytes = text.as_bytes();
+    let head = bytes.len().min(FINGERPRINT_BYTES);
+    let tail = bytes.len().saturating_sub(FINGERPRINT_BYTES).max(head);
+    (bytes.len(), extension, &bytes[..head], &bytes[tail..]).hash(&mut hasher);
+    hasher.finish()
+}
+
+impl HighlightCache {
+    /// Still highlighting in the background
+    pub fn is_pending(&self) -> bool {
+        self.full.is_none() && self.job.is_some()
+    }
+
+    /// Starts over for `text` unless it's what the cache already holds. The old
+    /// job, if any, is dropped with the rest and stops.
+    fn prepare(&mut self, text: &str, extension: &str, colors: &SyntaxColors) {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (fingerprint(text, extension), colors).hash(&mut hasher);
+        let key = hasher.finish();
+        if self.key == Some(key) {
+            return;
+        }
+        *self = HighlightCache {
+            key: Some(key),
+            index: LineIndex::new(text),
+            ..Default::default()
+        };
+        if text.len() < BACKGROUND_BYTES {
+            self.full = Some(Arc::new(crate::ui::syntax::highlight(
+                text, extension, colors,
+            )));
+            return;
+        }
+        let job = Job {
+            done: Arc::new(OnceLock::new()),
+            stop: Arc::new(AtomicBool::new(false)),
+        };
+        let (done, stop) = (job.done.clone(), job.stop.clone());
+        self.job = Some(Arc::new(job));
+        let (text, extension, colors) = (text.to_string(), extension.to_string(), colors.clone());
+        std::thread::spawn(move || {
+            if let Some(lines) =
+                crate::ui::syntax::highlight_until(&text, &extension, &colors, &stop)
+            {
+                let _ = done.set(Arc::new(lines));
+            }
+        });
+    }
+
+    /// The lines of `text` that can be on screen, highlighted: from the one wrapped
+    /// row `scroll` falls in, `height` of them, in a pane `width` columns wide. Big
+    /// bodies get only `MARGIN` lines around the viewport highlighted until the
+    /// background job is done. Also returns the rows of the first line above the
+    /// viewport, for the paragraph to scroll past.
+    pub fn lines<'a>(
+        &mut self,
+        text: &'a str,
+        extension: &str,
+        colors: &SyntaxColors,
+        scroll: usize,
+        height: usize,
+        width: usize,
+    ) -> (Vec<Line<'a>>, usize) {
+        self.prepare(text, extension, colors);
+        if self.index.is_empty() {
+            return (Vec::new(), 0);
+        }
+        let first = self.index.line_at_row(text, scroll, width);
+        let skip = scroll.saturating_sub(self.index.rows_before(first));
+        let end = (first + height).min(self.index.len());
+        if self.full.is_none()
+            && let Some(done) = self.job.as_ref().and_then(|job| job.done.get())
+        {
+            self.full = Some(done.clone());
+            self.job = None;
+            self.window = None;
+        }
+        if let Some(full) = &self.full {
+            let lines = (first..end)
+                .map(|i| match full.get(i) {
+                    Some(highlighted) => highlighted.clone(),
+                    None => Line::raw(self.index.line(text, i)),
+                })
+                .collect();
+            return (lines, skip);
+        }
+
+        let covered = self.window.as_ref().is_some_and(|w| {
+            w.start <= first.saturating_sub(MARGIN / 2)
+                && first + height + MARGIN / 2 <= w.start + w.count
+        });
+        if !covered {
+            let start = first.saturating_sub(MARGIN);
+            let count = height + 2 * MARGIN;
+            let source = self
+                .index
+                .range(text, start, (start + count).min(self.index.len()));
+            let lines = crate::ui::syntax::highlight(source, extension, colors);
+            self.window = Some(Window {
+                start,
+                count,
+                lines,
+            });
+        }
+        let window = self.window.as_ref().expect("window was just filled");
+        let lines = (first..end)
+            .map(|i| {
+                match i
+                    .checked_sub(window.start)
+                    .and_then(|j| window.lines.get(j))
+                {
+                    Some(highlighted) => highlighted.clone(),
+                    None => Line::raw(self.index.line(text, i)),
+                }
+            })
+            .collect();
+        (lines, skip)
+    }
+}
@@ -0,0 +1,148 @@
+// The JSON tree's visible rows, worked out once and kept until the tree, its
+// expanded nodes or the filter change. The explorer only turns the rows that fit on
+// screen into list items, so a 100k-node response scrolls as fast as a small one.
+use crate::app::JsonEntry;
+use std::cell::RefCell;
+
+/// Where a row's entry is: its index among the roots, then among each level's children
+pub type RowPath = Box<[u32]>;
+
+#[derive(Clone, Debug, Default)]
+struct Built {
+    version: u64,
+    /// The tree it was built from, to notice one swapped in without `invalidate`
+    tree: (usize, usize),
+    filter: String,
+    rows: Vec<RowPath>,
+}
+
+/// Kept on the tab next to `response_json`. Rebuilt on demand, so it's behind a
+/// RefCell for the `&self` lookups (row count, the selected node) to use too.
+#[derive(Clone, Debug, Default)]
+pub struct JsonRows {
+    version: u64,
+    built: RefCell<Option<Built>>,
+}
+
+fn tree_id(tree: &[JsonEntry]) -> (usize, usize) {
+    (tree.as_ptr() as usize, tree.len())
+}
+
+/// Shown rows the way the tree view always listed them: depth first through
+/// expanded nodes, rows whose key doesn't contain `filter` left out (their
+/// children still show)
+fn build(tree: &[JsonEntry], filter: &str) -> Vec<RowPath> {
+    fn walk(entries: &[JsonEntry], filter: &str, path: &mut Vec<u32>, rows: &mut Vec<RowPath>) {
+        for (i, entry) in entries.iter().enumerate() {
+            path.push(i as u32);
+            if filter.is_empty() || entry.key.to_lowercase().contains(filter) {
+                rows.push(path.clone().into_boxed_slice());
+            }
+            if entry.is_expanded {
+                walk(&entry.children, filter, path, rows);
+            }
+            path.pop();
+        }
+    }
+    let mut rows = Vec::new();
+    walk(tree, &filter.to_lowercase(), &mut Vec::new(), &mut rows);
+    rows
+}
+
+impl JsonRows {
+    /// The tree was replaced or a node expanded or collapsed
+    pub fn invalidate(&mut self) {
+        self.version += 1;
+    }
+
+    fn with_rows<T>(&self, tree: &[JsonEntry], filter: &str, f: impl FnOnce(&[RowPath]) -> T) -> T {
+        let mut built = self.built.borrow_mut();
+        let stale = built.as_ref().is_none_or(|b| {
+            b.version != self.version || b.tree != tree_id(tree) || b.filter != filter
+        });
+        if stale {
+            *built = Some(Built {
+                version: self.version,
+                tree: tree_id(tree),
+                filter: filter.to_string(),
+                rows: build(tree, filter),
+            });
+        }
+        f(&built.as_ref().expect("rows were just built").rows)
+    }
+
+    pub fn len(&self, tree: &[JsonEntry], filter: &str) -> usize {
+        self.with_rows(tree, filter, |rows| rows.len())
+    }
+
+    /// The path of row `index`
+    pub fn path(&self, tree: &[JsonEntry], filter: &str, index: usize) -> Option<RowPath> {
+        self.with_rows(tree, filter, |rows| rows.get(index).cloned())
+    }
+
+    /// The paths of the rows in `range`, fewer past the end
+    pub fn paths(
+        &self,
+        tree: &[JsonEntry],
+        filter: &str,
+        range: std::ops::Range<usize>,
+    ) -> Vec<RowPath> {
+        self.with_rows(tree, filter, |rows| {
+            rows[range.start.min(rows.len())..range.end.min(rows.len())].to_vec()
+        })
+    }
+}
+
+/// The first row to show so `selected` is on screen, moving as little as possible
+/// from where the list was (`previous`), like ratatui's own List does
+pub fn window_offset(
+    previous: usize,
+    selected: Option<usize>,
+    height: usize,
+    count: usize,
+) -> usize {
+    let mut offset = previous.min(count.saturating_sub(height.max(1)));
+    if let Some(selected) = selected.map(|s| s.min(count.saturating_sub(1))) {
+        if selected < offset {
+            offset = selected;
+        } else if height > 0 && selected >= offset + height {
+            offset = selected + 1 - height;
+        }
+    }
+    offset
+}
+
+pub fn entry<'a>(tree: &'a [JsonEntry], path: &[u32]) -> Option<&'a JsonEntry> {
+    let (first, rest) = path.split_first()?;
+    let mut entry = tree.get(*first as usize)?;
+    for &i in rest {
+        entry = entry.children.get(i as usize)?;
+    }
+    Some(entry)
+}
+
+pub fn entry_mut<'a>(tree: &'a mut [JsonEntry], path: &[u32]) -> Option<&'a mut JsonEntry> {
+    let (first, rest) = path.split_first()?;
+    let mut entry = tree.get_mut(*first as usize)?;
+    for &i in rest {
+        entry = entry.children.get_mut(i as usize)?;
+    }
+    Some(entry)
+}
+
+/// The row's JSONPath the way the explorer title shows it, `$.root.items[3].price`
+pub fn json_path(tree: &[JsonEntry], path: &[u32]) -> String {
+    let mut text = String::from("$.");
+    let mut entries = tree;
+    for (depth, &i) in path.iter().enumerate() {
+        let Some(entry) = entries.get(i as usize) else {
+            break;
+        };
+        if depth > 0 && !entry.key.starts_with('[') {
+            text.push('.');
+        }
+        text.push_str(&entry.key);
+        entries = &entry.children;
+    }
+    text
+}
@@ -0,0 +1,218 @@
+// Where the main screen's panes go for the terminal's size. Narrow terminals fold
+// the sidebar away (it comes back stacked above the request while it has focus),
+// short ones get a smaller config pane and then lose the open tabs bar, and below
+// the minimum there's a "too small" screen instead of panes drawn over each other.
+// Each frame also leaves behind where it put things, for mouse clicks to land on.
+use ratatui::layout::{Constraint, Direction, Layout, Position, Rect};
+use unicode_width::UnicodeWidthStr;
+
+/// Smallest terminal the main screen is drawn on
+pub const MIN_WIDTH: u16 = 50;
+pub const MIN_HEIGHT: u16 = 15;
+/// Narrower than this and the sidebar only shows while it has focus
+pub const SIDEBAR_WIDTH: u16 = 100;
+/// Shorter than this and the config pane shrinks
+pub const COMPACT_HEIGHT: u16 = 28;
+/// Shorter than this and the open tabs bar goes too (`[` / `]` still switch)
+pub const TABS_HEIGHT: u16 = 20;
+/// The sidebar's share of the width in percent, and how far dragging its edge goes
+pub const SIDEBAR_PERCENT: u16 = 20;
+pub const MIN_SIDEBAR_PERCENT: u16 = 10;
+pub const MAX_SIDEBAR_PERCENT: u16 = 50;
+
+pub fn too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MainLayout {
+    pub sidebar: Option<Rect>,
+    /// The open tabs bar, None when there's no room for it
+    pub tabs: Option<Rect>,
+    pub url: Rect,
+    /// The Params / Headers / ... row and the pane under it, None in zen mode and
+    /// under a stacked sidebar
+    pub config_tabs: Option<Rect>,
+    pub config: Option<Rect>,
+    pub response: Rect,
+    pub status: Rect,
+}
+
+/// `sidebar_percent` is how much of the width a side-by-side sidebar takes
+pub fn main_layout(
+    area: Rect,
+    zen: bool,
+    sidebar_focused: bool,
+    sidebar_percent: u16,
+) -> MainLayout {
+    let [main, status] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area);
+
+    // Stacked, the sidebar leaves room for the URL and the response only
+    let mut url_and_response = zen;
+    let (sidebar, right) = if zen {
+        (None, main)
+    } else if main.width >= SIDEBAR_WIDTH {
+        let percent = sidebar_percent.clamp(MIN_SIDEBAR_PERCENT, MAX_SIDEBAR_PERCENT);
+        let [sidebar, right] = Layout::horizontal([
+            Constraint::Percentage(percent),
+            Constraint::Percentage(100 - percent),
+        ])
+        .areas(main);
+        (Some(sidebar), right)
+    } else if sidebar_focused {
+        url_and_response = true;
+        let [sidebar, right] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(main);
+        (Some(sidebar), right)
+    } else {
+        (None, main)
+    };
+
+    let show_tabs = right.height >= TABS_HEIGHT;
+    let config_height = if right.height >= COMPACT_HEIGHT { 8 } else { 5 };
+    let mut constraints = Vec::new();
+    if show_tabs {
+        constraints.push(Constraint::Length(3));
+    }
+    constraints.push(Constraint::Length(3));
+    if !url_and_response {
+        constraints.push(Constraint::Length(3));
+        constraints.push(Constraint::Length(config_height));
+    }
+    constraints.push(Constraint::Min(0));
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(right);
+
+    let mut rows = rows.iter().copied();
+    let tabs = if show_tabs { rows.next() } else { None };
+    let url = rows.next().unwrap_or_default();
+    let (config_tabs, config) = if url_and_response {
+        (None, None)
+    } else {
+        (rows.next(), rows.next())
+    };
+    MainLayout {
+        sidebar,
+        tabs,
+        url,
+        config_tabs,
+        config,
+        response: rows.next().unwrap_or_default(),
+        status,
+    }
+}
+
+/// Columns `text` takes on screen: wide characters (CJK, most emoji) count twice
+pub fn width(text: &str) -> u16 {
+    text.width().min(u16::MAX as usize) as u16
+}
+
+/// How far to scroll a one-line input so a cursor `column` columns in stays
+/// within `visible` columns
+pub fn scroll_for(column: u16, visible: u16) -> u16 {
+    (column + 1).saturating_sub(visible.max(1))
+}
+
+/// Where the terminal cursor goes for an input drawn in `area` (borders included),
+/// `column` columns into its text, the text scrolled by `scroll`. Kept inside the
+/// borders whatever the numbers.
+pub fn cursor_at(area: Rect, column: u16, scroll: u16) -> (u16, u16) {
+    let inner_right = area.right().saturating_sub(2).max(area.x);
+    let x = (area.x + 1)
+        .saturating_add(column.saturating_sub(scroll))
+        .min(inner_right);
+    (x, area.y + 1.min(area.height.saturating_sub(1)))
+}
+
+/// The sidebar width in percent of `area` that puts its edge at `column`
+pub fn sidebar_percent_at(area: Rect, column: u16) -> u16 {
+    let width = (column + 1).saturating_sub(area.x) as u32;
+    (width * 100 / area.width.max(1) as u32).min(100) as u16
+}
+
+/// Byte index in `text` of the character `columns` display columns in, the end when
+/// that's past it
+pub fn byte_at(text: &str, columns: u16) -> usize {
+    let mut used = 0;
+    for (i, c) in text.char_indices() {
+        let w = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0) as u16;
+        if used + w > columns {
+            return i;
+        }
+        used += w;
+    }
+    text.len()
+}
+
+/// Where each title of a bordered `Tabs` bar in `area` is drawn, padding included,
+/// the way ratatui lays them out: ` title ` then a one column divider. Titles that
+/// don't fit get an empty rect.
+pub fn tab_titles(area: Rect, widths: impl IntoIterator<Item = u16>) -> Vec<Rect> {
+    let (right, y) = (area.right().saturating_sub(1), area.y + 1);
+    let mut x = area.x + 1;
+    widths
+        .into_iter()
+        .map(|w| {
+            let start = x.min(right);
+            let end = (x + w + 2).min(right);
+            x += w + 3;
+            Rect::new(start, y, end - start, 1)
+        })
+        .collect()
+}
+
+/// The line of a bordered list in `area` that `position` is on, None on the borders or
+/// outside
+pub fn list_row(area: Rect, position: Position) -> Option<usize> {
+    let inner = Rect::new(
+        area.x + 1,
+        area.y + 1,
+        area.width.saturating_sub(2),
+        area.height.saturating_sub(2),
+    );
+    inner
+        .contains(position)
+        .then(|| (position.y - inner.y) as usize)
+}
+
+/// The WebSocket screen's parts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WsLayout {
+    pub url: Rect,
+    pub messages: Rect,
+    pub input: Rect,
+}
+
+/// Where the last frame drew what the mouse can point at. Empty while a screen
+/// without mouse support (runner, mock server, ...) is up.
+#[derive(Clone, Debug, Default)]
+pub struct ScreenAreas {
+    /// The HTTP screen's panes
+    pub main: Option<MainLayout>,
+    /// The collections and history list, inside the sidebar
+    pub sidebar_list: Option<Rect>,
+    /// Each open tab's title in the tabs bar
+    pub open_tabs: Vec<Rect>,
+    /// Params, Headers, Body, Auth, Chain
+    pub config_tabs: Vec<Rect>,
+    /// The screen column the URL's first character is drawn at
+    pub url_text: Option<u16>,
+    /// The response body, below test results and console output
+    pub response: Option<Rect>,
+    pub ws: Option<WsLayout>,
+}
+
+impl ScreenAreas {
+    /// The sidebar's right edge, when it's beside the request and can be dragged
+    pub fn sidebar_edge(&self) -> Option<u16> {
+        let main = self.main?;
+        let sidebar = main.sidebar?;
+        (sidebar.right() <= main.url.x).then(|| sidebar.right().saturating_sub(1))
+    }
+}
+
+pub fn hit(areas: &[Rect], position: Position) -> Option<usize> {
+    areas.iter().position(|area| area.contains(position))
+}
@@ -0,0 +1,142 @@
+// Just enough markdown for request and collection descriptions: headings, lists,
+// quotes, fenced code, rules, and inline code, bold, italics and links.
+use crate::app::Theme;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Inline markup within one line: `code`, **bold**, *italic* / _italic_ and
+/// [text](url), each on top of `base`
+fn inline(text: &str, base: Style, theme: &Theme) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    // `snake_case_names` aren't emphasis
+    let mut in_word = false;
+    let flush = |plain: &mut String, spans: &mut Vec<Span<'static>>| {
+        if !plain.is_empty() {
+            spans.push(Span::styled(std::mem::take(plain), base));
+        }
+    };
+
+    while let Some(c) = rest.chars().next() {
+        let styled = match c {
+            '`' => rest[1..]
+                .find('`')
+                .map(|end| (end + 2, rest[1..end + 1].to_string(), base.fg(theme.accent))),
+            '*' | '_' if in_word => None,
+            '*' | '_' if rest[1..].starts_with(c) => {
+                let marker = &rest[..2];
+                rest[2..].find(marker).filter(|end| *end > 0).map(|end| {
+                    (
+                        end + 4,
+                        rest[2..end + 2].to_string(),
+                        base.add_modifier(Modifier::BOLD),
+                    )
+                })
+            }
+            '*' | '_' => rest[1..]
+                .find(c)
+                .filter(|end| *end > 0 && !rest[1..].starts_with(' '))
+                .map(|end| {
+                    (
+                        end + 2,
+                        rest[1..end + 1].to_string(),
+                        base.add_modifier(Modifier::ITALIC),
+                    )
+                }),
+            '[' => rest.find("](").and_then(|mid| {
+                let close = rest[mid + 2..].find(')')? + mid + 2;
+                flush(&mut plain, &mut spans);
+                spans.push(Span::styled(
+                    rest[1..mid].to_string(),
+                    base.fg(theme.highlight).add_modifier(Modifier::UNDERLINED),
+                ));
+                Some((
+                    close + 1,
+                    format!(" ({})", &rest[mid + 2..close]),
+                    base.fg(Color::DarkGray),
+                ))
+            }),
+            _ => None,
+        };
+        match styled {
+            Some((len, content, style)) => {
+                flush(&mut plain, &mut spans);
+                spans.push(Span::styled(content, style));
+                rest = &rest[len..];
+                in_word = false;
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+                in_word = c.is_alphanumeric();
+            }
+        }
+    }
+    flush(&mut plain, &mut spans);
+    spans
+}
+
+/// Styled lines for `text`. Paragraph text is left to the Paragraph's wrapping.
+pub fn render(text: &str, theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    let code_style = Style::default().fg(theme.accent);
+
+    for raw in text.lines() {
+        let line = raw.trim_end();
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(Line::from(Span::styled(format!("  {}", line), code_style)));
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        let indent = " ".repeat(line.len() - trimmed.len());
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            let style = Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD);
+            let style = if level == 1 {
+                style.add_modifier(Modifier::UNDERLINED)
+            } else {
+                style
+            };
+            lines.push(Line::from(inline(trimmed[level..].trim(), style, theme)));
+        } else if ["---", "***", "___"].contains(&trimmed) {
+            lines.push(Line::from(Span::styled(
+                "─".repeat(40),
+                Style::default().fg(Color::DarkGray),
+            )));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            let style = Style::default()
+                .fg(Color::Gray)
+                .add_modifier(Modifier::ITALIC);
+            let mut spans = vec![Span::styled("│ ", Style::default().fg(Color::DarkGray))];
+            spans.extend(inline(quote.trim_start(), style, theme));
+            lines.push(Line::from(spans));
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+            .or_else(|| trimmed.strip_prefix("+ "))
+        {
+            let mut spans = vec![Span::styled(
+                format!("{}• ", indent),
+                Style::default().fg(theme.accent),
+            )];
+            spans.extend(inline(item, Style::default(), theme));
+            lines.push(Line::from(spans));
+        } else {
+            let mut spans = vec![Span::raw(indent)];
+            spans.extend(inline(trimmed, Style::default(), theme));
+            lines.push(Line::from(spans));
+        }
+    }
+    lines
+}
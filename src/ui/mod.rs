@@ -1,5 +1,10 @@
 use crate::app::{App, InputMode, JsonEntry};
+use crate::domain::line_edit::{self, with_cursor};
 use crate::ui::sentinel::render_sentinel_mode;
+pub mod highlight;
+pub mod json_rows;
+pub mod layout;
+pub mod markdown;
 pub mod sentinel;
 pub mod syntax;
 
@@ -9,13 +14,17 @@ use ratatui::{
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
-        Block, BorderType, Borders, List, ListItem, ListState, Paragraph, Sparkline, Tabs, Wrap,
+        Axis, Block, BorderType, Borders, Chart, Dataset, GraphType, List, ListItem, ListState,
+        Paragraph, Sparkline, Tabs, Wrap,
     },
 };
 use ratatui_image::StatefulImage;
 use similar::{ChangeTag, TextDiff};
 
 /// ASCII art logo for PostDad
+/// Frames of the loading spinner, one per tick
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
 pub const LOGO: &str = r#"
 ██████╗  ██████╗ ███████╗████████╗██████╗  █████╗ ██████╗ 
 ██╔══██╗██╔═══██╗██╔════╝╚══██╔══╝██╔══██╗██╔══██╗██╔══██╗
@@ -93,103 +102,487 @@ fn get_style_for_value(v: &serde_json::Value) -> Style {
     }
 }
 
-fn flatten_tree(
-    entries: &[JsonEntry],
-    list_items: &mut Vec<ListItem<'static>>,
-    filter: &str,
-    line_counter: &mut usize,
-) {
-    for entry in entries {
-        let matches = if filter.is_empty() {
-            true
-        } else {
-            entry.key.to_lowercase().contains(&filter.to_lowercase())
-        };
+/// A row of the tree view, `row` counting from 0
+fn tree_item(entry: &JsonEntry, row: usize) -> ListItem<'static> {
+    let indent = "  ".repeat(entry.level);
+    let icon = if !entry.has_children() {
+        " "
+    } else if entry.is_expanded {
+        "▼"
+    } else {
+        "▶"
+    };
 
-        if matches {
-            *line_counter += 1;
-            let indent = "  ".repeat(entry.level);
-            let icon = if entry.children.is_empty() {
-                " "
-            } else if entry.is_expanded {
-                "▼"
-            } else {
-                "▶"
-            };
+    let val_str = match entry.value() {
+        serde_json::Value::String(s) => format!("\"{}\"", s),
+        v => format!("{}", v),
+    };
+
+    let display_text = format!(
+        "{:>4} {}{} {}: {}",
+        row + 1,
+        indent,
+        icon,
+        entry.key,
+        val_str
+    );
+    let style = get_style_for_value(entry.value());
+    ListItem::new(display_text).style(style)
+}
 
-            let val_str = match &entry.value {
+/// The flat view: one `path = value` row per leaf, paths padded into a column
+fn flat_rows(entries: &[JsonEntry], filter: &str) -> Vec<ListItem<'static>> {
+    let rows = JsonEntry::flatten(entries, filter);
+    let width = rows
+        .iter()
+        .map(|(path, _)| path.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(60);
+    rows.iter()
+        .enumerate()
+        .map(|(i, (path, entry))| {
+            let path = if path.is_empty() { "$" } else { path.as_str() };
+            let value = match entry.value() {
                 serde_json::Value::String(s) => format!("\"{}\"", s),
-                v => format!("{}", v),
+                v => v.to_string(),
             };
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:>4} {:<width$} ", i + 1, path, width = width)),
+                Span::styled(format!("= {}", value), get_style_for_value(entry.value())),
+            ]))
+        })
+        .collect()
+}
 
-            let display_text = format!(
-                "{:>4} {}{} {}: {}",
-                *line_counter, indent, icon, entry.key, val_str
-            );
-            let style = get_style_for_value(&entry.value);
-            list_items.push(ListItem::new(display_text).style(style));
+/// Plain response text with every find match highlighted, the current one stronger
+/// A tag's chip color, the same for the tag everywhere
+fn tag_color(tag: &str) -> Color {
+    const COLORS: [Color; 6] = [
+        Color::LightBlue,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightMagenta,
+        Color::LightCyan,
+        Color::LightRed,
+    ];
+    let hash = tag
+        .to_lowercase()
+        .bytes()
+        .fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+    COLORS[hash % COLORS.len()]
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1_000_000 {
+        format!("{:.1} MB", bytes as f64 / 1_000_000.0)
+    } else if bytes >= 1_000 {
+        format!("{:.1} KB", bytes as f64 / 1_000.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Offset, hex bytes and ASCII, with search matches highlighted; replaces the
+/// response body while open
+fn render_hex_view(f: &mut Frame, app: &App, area: ratatui::layout::Rect, border_style: Style) {
+    use crate::features::hex::{self, BYTES_PER_ROW};
+
+    let Some(view) = app.hex_view.as_ref() else {
+        return;
+    };
+    let bytes = app.hex_bytes().unwrap_or_default();
+    let needle = hex::parse_query(&view.query).len();
+    let current = view.current();
+    let match_style = Style::default().bg(Color::Yellow).fg(Color::Black);
+    let current_style = Style::default()
+        .bg(Color::LightRed)
+        .fg(Color::Black)
+        .add_modifier(Modifier::BOLD);
+    // Matches are sorted and all the same length, so the nearest start before a byte
+    // tells whether it's inside one
+    let style_of = |offset: usize| {
+        let i = view.matches.partition_point(|&m| m <= offset);
+        match i.checked_sub(1).map(|i| view.matches[i]) {
+            Some(m) if offset < m + needle && Some(m) == current => current_style,
+            Some(m) if offset < m + needle => match_style,
+            _ => Style::default(),
         }
+    };
+
+    let height = area.height.saturating_sub(2) as usize;
+    let lines: Vec<Line> = (view.scroll..hex::rows(bytes.len()).min(view.scroll + height))
+        .map(|row| {
+            let start = row * BYTES_PER_ROW;
+            let chunk = &bytes[start..(start + BYTES_PER_ROW).min(bytes.len())];
+            let mut spans = vec![Span::styled(
+                format!("{:08x}  ", start),
+                Style::default().fg(Color::DarkGray),
+            )];
+            for i in 0..BYTES_PER_ROW {
+                if i == BYTES_PER_ROW / 2 {
+                    spans.push(Span::raw(" "));
+                }
+                match chunk.get(i) {
+                    Some(b) => {
+                        spans.push(Span::styled(format!("{:02x}", b), style_of(start + i)));
+                        spans.push(Span::raw(" "));
+                    }
+                    None => spans.push(Span::raw("   ")),
+                }
+            }
+            spans.push(Span::raw(" |"));
+            for (i, &b) in chunk.iter().enumerate() {
+                spans.push(Span::styled(
+                    hex::printable(b).to_string(),
+                    style_of(start + i),
+                ));
+            }
+            spans.push(Span::raw("|"));
+            Line::from(spans)
+        })
+        .collect();
 
-        if entry.is_expanded {
-            flatten_tree(&entry.children, list_items, filter, line_counter);
+    let search = if view.searching {
+        format!(" [Search: {}_] ", view.query)
+    } else if view.query.is_empty() {
+        String::new()
+    } else if view.matches.is_empty() {
+        format!(" [Search: {} 0/0] ", view.query)
+    } else {
+        format!(
+            " [Search: {} {}/{}] ",
+            view.query,
+            view.index + 1,
+            view.matches.len()
+        )
+    };
+    let title = format!(
+        " Hex: {} ({} bytes){} · Tab: {} · Esc to close ",
+        view.source.label(),
+        bytes.len(),
+        search,
+        match view.source {
+            hex::Source::Response | hex::Source::Encoded => "sent request",
+            hex::Source::Request => "response",
         }
-    }
+    );
+    let para = Paragraph::new(lines).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(border_style),
+    );
+    f.render_widget(para, area);
 }
 
-pub fn get_json_path(entries: &[JsonEntry], target_idx: usize, filter: &str) -> String {
-    let mut current_idx = 0;
-    find_path_by_index(entries, target_idx, &mut current_idx, filter, String::new())
-        .map(|path| format!("$.{}", path))
-        .unwrap_or_default()
+/// The Docs sub-tab: the request's name, method and URL over its description
+fn render_docs(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let tab = app.active_tab();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tab.name.clone(),
+            Style::default()
+                .fg(app.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(vec![
+            Span::styled(
+                format!("{} ", tab.method),
+                Style::default()
+                    .fg(app.theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(tab.url.clone()),
+        ]),
+        Line::from(""),
+    ];
+    if tab.description.trim().is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No description yet. :describe opens it in your editor (markdown).",
+            Style::default().fg(Color::DarkGray),
+        )));
+    } else {
+        lines.extend(crate::ui::markdown::render(&tab.description, &app.theme));
+    }
+
+    let para = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" Response │ Docs ")
+                .title_bottom(" K: back to the response · :describe: edit ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border_focus)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((tab.response_scroll.0, 0));
+    f.render_widget(para, area);
 }
 
-fn find_path_by_index(
-    entries: &[JsonEntry],
-    target_idx: usize,
-    current_idx: &mut usize,
-    filter: &str,
-    parent_path: String,
-) -> Option<String> {
-    for entry in entries {
-        let matches = if filter.is_empty() {
-            true
+/// A CSV response as a table: the header stays put, rows scroll under it and
+/// columns scroll sideways to keep the selected cell in view
+fn render_csv_table(
+    f: &mut Frame,
+    app: &mut App,
+    area: ratatui::layout::Rect,
+    title: String,
+    border_style: Style,
+) {
+    let header_style = Style::default()
+        .fg(app.theme.accent)
+        .add_modifier(Modifier::BOLD);
+    let row_style = Style::default().add_modifier(Modifier::REVERSED);
+    let cell_style = Style::default()
+        .bg(app.theme.highlight)
+        .fg(Color::Black)
+        .add_modifier(Modifier::BOLD);
+    let Some(csv) = app.active_tab_mut().response_csv.as_mut() else {
+        return;
+    };
+
+    // Borders, then the header row
+    let height = area.height.saturating_sub(3) as usize;
+    let columns = csv.scroll_into_view(height, area.width.saturating_sub(2) as usize);
+    let widths = csv.widths();
+    let fit = |text: &str, width: usize| {
+        if text.chars().count() > width {
+            let cut: String = text.chars().take(width.saturating_sub(1)).collect();
+            format!("{:<width$}", format!("{}…", cut))
         } else {
-            entry.key.to_lowercase().contains(&filter.to_lowercase())
-        };
+            format!("{:<width$}", text)
+        }
+    };
 
-        if matches {
-            if *current_idx == target_idx {
-                if parent_path.is_empty() {
-                    return Some(entry.key.clone());
-                } else if entry.key.starts_with('[') {
-                    return Some(format!("{}{}", parent_path, entry.key));
+    let mut header = Vec::new();
+    for i in columns.clone() {
+        let arrow = match csv.sort {
+            Some((column, false)) if column == i => " ▲",
+            Some((column, true)) if column == i => " ▼",
+            _ => "",
+        };
+        let name = format!("{}{}", csv.table.columns[i], arrow);
+        header.push(Span::styled(fit(&name, widths[i]), header_style));
+        header.push(Span::raw(" "));
+    }
+    let mut lines = vec![Line::from(header)];
+    for (n, row) in csv.rows().enumerate().skip(csv.top).take(height) {
+        let mut spans = Vec::new();
+        for i in columns.clone() {
+            let style = match (n == csv.row, i == csv.column) {
+                (true, true) => cell_style,
+                (true, false) => row_style,
+                _ => Style::default(),
+            };
+            let cell = row.get(i).map(String::as_str).unwrap_or("");
+            spans.push(Span::styled(fit(cell, widths[i]), style));
+            spans.push(Span::styled(
+                " ",
+                if n == csv.row {
+                    row_style
                 } else {
-                    return Some(format!("{}.{}", parent_path, entry.key));
-                }
-            }
-            *current_idx += 1;
+                    Style::default()
+                },
+            ));
         }
+        lines.push(Line::from(spans));
+    }
 
-        if entry.is_expanded {
-            let my_path = if parent_path.is_empty() {
-                entry.key.clone()
-            } else if entry.key.starts_with('[') {
-                format!("{}{}", parent_path, entry.key)
-            } else {
-                format!("{}.{}", parent_path, entry.key)
-            };
+    let position = format!(
+        " row {}/{} · column {}/{} ",
+        (csv.row + 1).min(csv.order.len()),
+        csv.order.len(),
+        csv.column + 1,
+        csv.table.columns.len()
+    );
+    let para = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!("{}│ CSV ", title))
+            .title_bottom(position)
+            .borders(Borders::ALL)
+            .border_style(border_style),
+    );
+    f.render_widget(para, area);
+}
 
-            if let Some(p) =
-                find_path_by_index(&entry.children, target_idx, current_idx, filter, my_path)
-            {
-                return Some(p);
+fn highlight_find_matches(
+    text: &str,
+    query_len: usize,
+    matches: &[(usize, usize)],
+    current: Option<(usize, usize)>,
+) -> Vec<Line<'static>> {
+    let match_style = Style::default().bg(Color::Yellow).fg(Color::Black);
+    let current_style = Style::default()
+        .bg(Color::LightRed)
+        .fg(Color::Black)
+        .add_modifier(Modifier::BOLD);
+
+    let mut pending = matches.iter().peekable();
+    text.lines()
+        .enumerate()
+        .map(|(line_no, line)| {
+            let mut spans = Vec::new();
+            let mut pos = 0;
+            while let Some(&&(m_line, start)) = pending.peek() {
+                if m_line != line_no {
+                    break;
+                }
+                pending.next();
+                let end = (start + query_len).min(line.len());
+                if start < pos || !line.is_char_boundary(start) || !line.is_char_boundary(end) {
+                    continue;
+                }
+                spans.push(Span::raw(line[pos..start].to_string()));
+                let style = if current == Some((m_line, start)) {
+                    current_style
+                } else {
+                    match_style
+                };
+                spans.push(Span::styled(line[start..end].to_string(), style));
+                pos = end;
             }
+            spans.push(Span::raw(line[pos..].to_string()));
+            Line::from(spans)
+        })
+        .collect()
+}
+
+pub fn render(f: &mut Frame, app: &mut App) {
+    app.screen_areas = Default::default();
+    // A theme with a background of its own paints it everywhere, not just modals
+    if app.theme.background != Color::Reset {
+        f.render_widget(
+            Block::default().style(
+                Style::default()
+                    .bg(app.theme.background)
+                    .fg(app.theme.text_primary),
+            ),
+            f.area(),
+        );
+    }
+    if layout::too_small(f.area()) {
+        render_too_small(f, app);
+        return;
+    }
+    render_screen(f, app);
+
+    if app.response_compare.is_some() {
+        render_response_compare(f, app);
+    }
+
+    if app.show_help && !app.show_splash {
+        render_cheat_sheet(f, app);
+    }
+
+    if app.guard_prompt.is_some() {
+        render_guard_prompt(f, app);
+    }
+    if app.send_warnings.is_some() {
+        render_send_warnings(f, app);
+    }
+
+    if app.sync_prompt.is_some() {
+        render_sync_prompt(f, app);
+    }
+
+    if app.bundle_prompt.is_some() {
+        render_bundle_prompt(f, app);
+    }
+
+    if app.passphrase_prompt.is_some() {
+        render_passphrase_prompt(f, app);
+    }
+
+    if app.recovery_prompt.is_some() {
+        render_recovery_prompt(f, app);
+    }
+
+    if !app.show_splash {
+        render_notifications(f, app);
+        if app.show_notification_history {
+            render_notification_history(f, app);
+        }
+        if app.show_timeline {
+            render_timeline(f, app);
+        }
+        if app.wire_view.is_some() {
+            render_wire_view(f, app);
+        }
+        if app.fuzz_progress.is_some() {
+            render_fuzz_progress(f, app);
+        }
+        if app.show_fuzz_report {
+            render_fuzz_report(f, app);
+        }
+        if app.show_batch {
+            render_batch(f, app);
+        }
+        if app.show_ndjson {
+            render_ndjson(f, app);
+        }
+        if app.doctor_report.is_some() {
+            render_doctor(f, app);
+        }
+        if app.audit_report.is_some() {
+            render_audit(f, app);
+        }
+        if app.collection_diff.is_some() {
+            render_collection_diff(f, app);
+        }
+        if app.variables_inspector.is_some() {
+            render_variables_inspector(f, app);
+        }
+        if app.env_usage.is_some() {
+            render_env_usage(f, app);
+        }
+        if app.dedupe.is_some() {
+            render_dedupe(f, app);
+        }
+        if app.latency_chart.is_some() {
+            render_latency_chart(f, app);
+        }
+        if app.log_view.is_some() {
+            render_log_view(f, app);
+        }
+        if app.proto_view.is_some() {
+            render_proto_view(f, app);
+        }
+        if app.dashboard.is_some() {
+            render_dashboard(f, app);
+        }
+        if app.show_keymap {
+            render_keymap(f, app);
+        }
+        if app.theme_picker.is_some() {
+            render_theme_picker(f, app);
         }
     }
-    None
 }
 
-pub fn render(f: &mut Frame, app: &mut App) {
+/// Instead of panes squeezed on top of each other
+fn render_too_small(f: &mut Frame, app: &App) {
+    let area = f.area();
+    let lines = vec![
+        Line::from(Span::styled(
+            "Terminal too small",
+            Style::default()
+                .fg(app.theme.error)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(format!("{}×{}", area.width, area.height)),
+        Line::from(Span::styled(
+            format!("need {}×{}", layout::MIN_WIDTH, layout::MIN_HEIGHT),
+            Style::default().fg(app.theme.text_secondary),
+        )),
+    ];
+    let top = area.height.saturating_sub(lines.len() as u16) / 2;
+    let [_, middle] = Layout::vertical([Constraint::Length(top), Constraint::Min(0)]).areas(area);
+    f.render_widget(
+        Paragraph::new(lines).alignment(ratatui::layout::Alignment::Center),
+        middle,
+    );
+}
+
+fn render_screen(f: &mut Frame, app: &mut App) {
     if app.show_diff_view {
         render_diff_view(f, app);
         return;
@@ -224,31 +617,19 @@ pub fn render(f: &mut Frame, app: &mut App) {
     if app.active_tab().fullscreen_response {
         render_response_area(f, app, f.area());
     } else {
-        // Main layout with status bar at bottom
-        let main_with_status = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Min(10), Constraint::Length(1)])
-            .split(f.area());
-
-        let main_area = main_with_status[0];
-        let status_bar_area = main_with_status[1];
+        // Sized to the terminal, see `layout::main_layout`
+        let layout = layout::main_layout(
+            f.area(),
+            app.zen_mode,
+            app.active_sidebar,
+            app.sidebar_width,
+        );
+        app.screen_areas.main = Some(layout);
 
         // Render status bar
-        render_status_bar(f, app, status_bar_area);
-
-        let chunks = if app.zen_mode {
-            Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(0), Constraint::Percentage(100)])
-                .split(main_area)
-        } else {
-            Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(20), Constraint::Percentage(80)])
-                .split(main_area)
-        };
+        render_status_bar(f, app, layout.status);
 
-        if !app.zen_mode {
+        if let Some(sidebar_area) = layout.sidebar {
             let sidebar_constraints = if app.show_sidebar_filter {
                 vec![
                     Constraint::Length(3),
@@ -262,14 +643,21 @@ pub fn render(f: &mut Frame, app: &mut App) {
             let sidebar_chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints(sidebar_constraints)
-                .split(chunks[0]);
+                .split(sidebar_area);
 
             let mut main_sidebar_area = sidebar_chunks[0];
 
             // Render Search Bar if active
             if app.show_sidebar_filter {
                 main_sidebar_area = sidebar_chunks[1];
-                let search_text = format!(" 🔍 {}_", app.sidebar_filter);
+                let search_text = if app.active_tab().input_mode == InputMode::FilteringSidebar {
+                    format!(
+                        " 🔍 {}",
+                        with_cursor(&app.sidebar_filter, app.input_cursor, '│')
+                    )
+                } else {
+                    format!(" 🔍 {}", app.sidebar_filter)
+                };
                 let search_bar = Paragraph::new(search_text).block(
                     Block::default()
                         .borders(Borders::ALL)
@@ -279,7 +667,10 @@ pub fn render(f: &mut Frame, app: &mut App) {
                 f.render_widget(search_bar, sidebar_chunks[0]);
             }
 
-            let sidebar_title = format!(" Postdad (Env: {}) ", app.get_active_env().name);
+            let mut sidebar_title = format!(" Postdad (Env: {}) ", app.get_active_env().name);
+            if !app.tag_filter.is_empty() {
+                sidebar_title.push_str(&format!("[#{}] ", app.tag_filter.join(" #")));
+            }
             let sidebar_block = Block::default()
                 .title(sidebar_title)
                 .borders(Borders::ALL)
@@ -290,54 +681,43 @@ pub fn render(f: &mut Frame, app: &mut App) {
                 });
 
             let mut collection_items = Vec::new();
-            let filter_text = app.sidebar_filter.to_lowercase();
 
             collection_items.push(ListItem::new(Span::styled(
                 "--- Collections ---",
                 Style::default().add_modifier(Modifier::BOLD),
             )));
 
-            for col in &app.collections {
-                let mut keys: Vec<&String> = col.requests.keys().collect();
-                keys.sort();
-
-                // Check visibility based on filter
-                let matches_collection = col.name.to_lowercase().contains(&filter_text);
-                let matching_requests: Vec<&&String> = keys
-                    .iter()
-                    .filter(|k| filter_text.is_empty() || k.to_lowercase().contains(&filter_text))
-                    .collect();
-
-                if !filter_text.is_empty() && !matches_collection && matching_requests.is_empty() {
-                    continue;
-                }
-
-                for key in keys {
-                    let req = &col.requests[key];
-                    let badge_color = match req.method.as_str() {
-                        "GET" => app.theme.success,
-                        "POST" => app.theme.highlight,
-                        "PUT" => app.theme.accent,
-                        "DELETE" => app.theme.error,
-                        _ => app.theme.text_secondary,
-                    };
+            for (col, key, req) in app.sidebar_requests() {
+                let badge_color = match req.method.as_str() {
+                    "GET" => app.theme.success,
+                    "POST" => app.theme.highlight,
+                    "PUT" => app.theme.accent,
+                    "DELETE" => app.theme.error,
+                    _ => app.theme.text_secondary,
+                };
 
-                    let content = Line::from(vec![
-                        Span::styled(
-                            format!("{} ", col.name),
-                            Style::default().fg(app.theme.text_secondary),
-                        ),
-                        Span::styled(
-                            format!(" {} ", req.method),
-                            Style::default()
-                                .bg(badge_color)
-                                .fg(Color::Black)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                        Span::raw(format!(" {}", key)),
-                    ]);
-                    collection_items.push(ListItem::new(content));
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{} ", col.name),
+                        Style::default().fg(app.theme.text_secondary),
+                    ),
+                    Span::styled(
+                        format!(" {} ", req.method),
+                        Style::default()
+                            .bg(badge_color)
+                            .fg(Color::Black)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(format!(" {}", key)),
+                ];
+                for tag in &req.tags {
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(
+                        format!(" {} ", tag),
+                        Style::default().bg(tag_color(tag)).fg(Color::Black),
+                    ));
                 }
+                collection_items.push(ListItem::new(Line::from(spans)));
             }
             if !app.request_history.is_empty() {
                 collection_items.push(ListItem::new(Span::raw(" ")));
@@ -346,13 +726,15 @@ pub fn render(f: &mut Frame, app: &mut App) {
                     Style::default().add_modifier(Modifier::BOLD),
                 )));
                 for (i, log) in app.request_history.iter().enumerate() {
-                    let status_style = if log.status >= 200 && log.status < 300 {
-                        Style::default().fg(app.theme.success)
-                    } else if log.status >= 400 {
-                        Style::default().fg(app.theme.error)
-                    } else {
-                        Style::default().fg(app.theme.highlight)
-                    };
+                    let grpc = log.method == "GRPC";
+                    let status_style =
+                        if (grpc && log.status == 0) || (log.status >= 200 && log.status < 300) {
+                            Style::default().fg(app.theme.success)
+                        } else if grpc || log.status >= 400 {
+                            Style::default().fg(app.theme.error)
+                        } else {
+                            Style::default().fg(app.theme.highlight)
+                        };
 
                     let lat_style = if log.latency < 200 {
                         Style::default().fg(app.theme.success)
@@ -383,6 +765,9 @@ pub fn render(f: &mut Frame, app: &mut App) {
                         Span::styled(format!("({}ms) ", log.latency), lat_style),
                         Span::raw(&log.url),
                     ];
+                    if log.pinned {
+                        spans.insert(0, Span::raw("📌"));
+                    }
 
                     if let Some(base_idx) = app.diff_base_index
                         && base_idx == i
@@ -413,23 +798,25 @@ pub fn render(f: &mut Frame, app: &mut App) {
                 main_sidebar_area,
                 &mut app.collection_state,
             );
+            app.screen_areas.sidebar_list = Some(main_sidebar_area);
 
             // Calculate response size for display
-            let response_size = app
+            let mut response_size = app
                 .active_tab()
                 .response_bytes
                 .as_ref()
-                .map(|b| {
-                    let bytes = b.len();
-                    if bytes >= 1_000_000 {
-                        format!("{:.1} MB", bytes as f64 / 1_000_000.0)
-                    } else if bytes >= 1_000 {
-                        format!("{:.1} KB", bytes as f64 / 1_000.0)
-                    } else {
-                        format!("{} B", bytes)
-                    }
-                })
+                .map(|b| format_size(b.len()))
                 .unwrap_or_else(|| "—".to_string());
+            // Decoded size first, then what actually came over the wire
+            if let Some(encoding) = &app.active_tab().response_encoding
+                && encoding.error.is_none()
+            {
+                response_size.push_str(&format!(
+                    " ← {} {}",
+                    encoding.encoding,
+                    format_size(encoding.raw.len())
+                ));
+            }
 
             let latency_display = app
                 .active_tab()
@@ -453,7 +840,7 @@ pub fn render(f: &mut Frame, app: &mut App) {
 
         let url_border_color = match app.active_tab().input_mode {
             InputMode::Editing => app.theme.border_focus,
-            InputMode::Search => app.theme.accent,
+            InputMode::Search | InputMode::FindInResponse => app.theme.accent,
             _ => app.theme.border,
         };
 
@@ -491,10 +878,33 @@ pub fn render(f: &mut Frame, app: &mut App) {
             " URL (Press 'e' to edit, 'm' to cycle method, 'P' for script, 'Enter' to fetch) "
         };
 
+        // A relative path goes wherever the environment's base_url points
+        let base_indicator = match app.relative_url_preview() {
+            Some(Ok(resolved)) => Span::styled(
+                format!(" → {}", resolved),
+                Style::default().fg(app.theme.text_secondary),
+            ),
+            Some(Err(e)) => Span::styled(format!(" ⚠ {}", e), Style::default().fg(app.theme.error)),
+            None => Span::raw(""),
+        };
+
+        // Pinned by the environment's resolve table, like curl --resolve
+        let resolve_indicator = match app.host_override() {
+            Some((_, _, addrs)) => Span::styled(
+                format!("  📌 {}", addrs),
+                Style::default().fg(app.theme.text_secondary),
+            ),
+            None => Span::raw(""),
+        };
+
+        let url_bar_prefix =
+            ratatui::text::Line::from(vec![method_text.clone(), script_indicator.clone()]);
         let url_bar = Paragraph::new(ratatui::text::Line::from(vec![
             method_text,
             script_indicator,
             url_text,
+            base_indicator,
+            resolve_indicator,
         ]))
         .block(
             Block::default()
@@ -503,18 +913,19 @@ pub fn render(f: &mut Frame, app: &mut App) {
                 .border_style(Style::default().fg(url_border_color)),
         );
 
-        let titles = ["Params", "Headers", "Body", "Auth", "Chain"]
+        let titles = ["Params", "Headers", "Body", "Auth", "Chain", "Settings"]
             .iter()
             .cloned()
             .map(ratatui::text::Line::from)
             .collect::<Vec<_>>();
 
         // Build breadcrumb trail
-        let tab_names = ["Params", "Headers", "Body", "Auth", "Chain"];
+        let tab_names = ["Params", "Headers", "Body", "Auth", "Chain", "Settings"];
         let current_tab = tab_names.get(app.active_tab().selected_tab).unwrap_or(&"");
         let body_type_str = match app.active_tab().body_type {
             crate::app::BodyType::Raw => "Raw",
             crate::app::BodyType::FormData => "Form",
+            crate::app::BodyType::UrlEncoded => "Urlencoded",
             crate::app::BodyType::GraphQL => "GraphQL",
             crate::app::BodyType::Grpc => "gRPC",
         };
@@ -539,8 +950,18 @@ pub fn render(f: &mut Frame, app: &mut App) {
         let req_titles = app
             .tabs
             .iter()
-            .map(|t| Line::from(t.name.clone()))
+            .enumerate()
+            .map(|(i, t)| {
+                // Tabs waiting on a send of their own
+                let loading = if t.is_loading {
+                    format!(" {}", SPINNER[app.spinner_state % SPINNER.len()])
+                } else {
+                    String::new()
+                };
+                Line::from(format!("{} {}{}", i + 1, t.name, loading))
+            })
             .collect::<Vec<_>>();
+        let req_title_widths: Vec<u16> = req_titles.iter().map(|t| t.width() as u16).collect();
         let req_tabs_widget = Tabs::new(req_titles)
             .block(Block::default().borders(Borders::ALL).title(" Open Tabs "))
             .select(app.active_tab)
@@ -551,50 +972,30 @@ pub fn render(f: &mut Frame, app: &mut App) {
                     .add_modifier(Modifier::BOLD),
             );
 
-        let main_constraints = if app.zen_mode {
-            vec![
-                Constraint::Length(3), // Tabs
-                Constraint::Length(3), // URL
-                Constraint::Min(10),   // Response
-            ]
-        } else {
-            vec![
-                Constraint::Length(3), // Tabs
-                Constraint::Length(3), // URL
-                Constraint::Length(3), // Config Tabs
-                Constraint::Length(8), // Config Area
-                Constraint::Min(10),   // Response
-            ]
-        };
-
-        let right_col = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(main_constraints)
-            .split(chunks[1]);
-
-        f.render_widget(req_tabs_widget, right_col[0]);
-        f.render_widget(url_bar, right_col[1]);
+        if let Some(tabs_area) = layout.tabs {
+            app.screen_areas.open_tabs = layout::tab_titles(tabs_area, req_title_widths);
+            f.render_widget(req_tabs_widget, tabs_area);
+        }
+        app.screen_areas.url_text = Some(layout.url.x + 1 + url_bar_prefix.width() as u16 + 1);
 
         if app.active_tab().input_mode == InputMode::Editing {
-            let script_offset = if !app.active_tab().pre_request_script.trim().is_empty() {
-                3
-            } else {
-                0
-            };
-            let x = right_col[1].x
+            // Display columns, not bytes or chars: the 📜 and wide characters in
+            // the URL take two. A URL longer than the bar scrolls with the cursor.
+            let url = &app.active_tab().url;
+            let column = url_bar_prefix.width() as u16
                 + 1
-                + (app.active_tab().method.len() as u16 + 2)
-                + script_offset
-                + 1
-                + app.active_tab().url_cursor_index as u16;
-            let y = right_col[1].y + 1;
-            f.set_cursor_position((x, y));
+                + layout::width(&url[..line_edit::clamp(url, app.active_tab().url_cursor_index)]);
+            let scroll = layout::scroll_for(column, layout.url.width.saturating_sub(2));
+            f.render_widget(url_bar.scroll((0, scroll)), layout.url);
+            f.set_cursor_position(layout::cursor_at(layout.url, column, scroll));
+        } else {
+            f.render_widget(url_bar, layout.url);
         }
 
-        if app.zen_mode {
-            render_response_area(f, app, right_col[2]);
-        } else {
-            f.render_widget(tabs, right_col[2]);
+        if let (Some(config_tabs), Some(config_area)) = (layout.config_tabs, layout.config) {
+            f.render_widget(tabs, config_tabs);
+            app.screen_areas.config_tabs =
+                layout::tab_titles(config_tabs, tab_names.iter().map(|t| layout::width(t)));
 
             let config_block = Block::default()
                 .borders(Borders::ALL)
@@ -606,14 +1007,45 @@ pub fn render(f: &mut Frame, app: &mut App) {
                     {
                         let tab = app.active_tab();
                         input_mode = tab.input_mode;
-                        if tab.params.is_empty() {
+                        // Path params first, named by the URL, only their values edit
+                        for (i, (k, v)) in tab.path_params.iter().enumerate() {
+                            let value = if Some(i) == tab.path_params_list_state.selected()
+                                && tab.input_mode == InputMode::EditingPathParam
+                            {
+                                with_cursor(v, app.input_cursor, '│')
+                            } else if v.is_empty() {
+                                "(not set)".to_string()
+                            } else {
+                                v.clone()
+                            };
+                            param_items.push(ListItem::new(Line::from(vec![
+                                Span::styled(
+                                    format!(":{}", k),
+                                    Style::default().fg(app.theme.accent),
+                                ),
+                                Span::raw(format!(" = {}", value)),
+                            ])));
+                        }
+                        if tab.params.is_empty() && tab.path_params.is_empty() {
                             param_items.push(ListItem::new("No params. Press 'a' to add."));
                         } else {
                             for (i, (k, v)) in tab.params.iter().enumerate() {
                                 let content = if Some(i) == tab.params_list_state.selected() {
                                     match tab.input_mode {
-                                        InputMode::EditingParamKey => format!("> {}_ = {}", k, v),
-                                        InputMode::EditingParamValue => format!("> {} = {}_", k, v),
+                                        InputMode::EditingParamKey => {
+                                            format!(
+                                                "> {} = {}",
+                                                with_cursor(k, app.input_cursor, '│'),
+                                                v
+                                            )
+                                        }
+                                        InputMode::EditingParamValue => {
+                                            format!(
+                                                "> {} = {}",
+                                                k,
+                                                with_cursor(v, app.input_cursor, '│')
+                                            )
+                                        }
                                         _ => format!("{} = {}", k, v),
                                     }
                                 } else {
@@ -625,14 +1057,16 @@ pub fn render(f: &mut Frame, app: &mut App) {
                     }
 
                     let title = match input_mode {
-                        InputMode::EditingParamKey | InputMode::EditingParamValue => {
-                            " Params (Editing...) "
-                        }
+                        InputMode::EditingParamKey
+                        | InputMode::EditingParamValue
+                        | InputMode::EditingPathParam => " Params (Editing...) ",
                         _ => " Params (Press 'e' to Edit, 'a' to Add, 'd' to Delete) ",
                     };
 
                     let style = match input_mode {
-                        InputMode::EditingParamKey | InputMode::EditingParamValue => {
+                        InputMode::EditingParamKey
+                        | InputMode::EditingParamValue
+                        | InputMode::EditingPathParam => {
                             Style::default().fg(app.theme.border_focus)
                         }
                         _ => Style::default().fg(app.theme.border),
@@ -648,41 +1082,137 @@ pub fn render(f: &mut Frame, app: &mut App) {
                         .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
                         .highlight_symbol("> ");
 
-                    f.render_stateful_widget(
-                        list,
-                        right_col[2],
-                        &mut app.active_tab_mut().params_list_state,
-                    );
+                    let offset = app.active_tab().params_list_state.offset();
+                    let mut state = ListState::default()
+                        .with_selected(app.params_row())
+                        .with_offset(offset);
+                    f.render_stateful_widget(list, config_area, &mut state);
+                    *app.active_tab_mut().params_list_state.offset_mut() = state.offset();
                 }
                 1 => {
-                    let headers: Vec<ListItem> = app
-                        .active_tab()
-                        .request_headers
-                        .iter()
-                        .map(|(k, v)| ListItem::new(format!("{}: {}", k, v)))
-                        .collect();
-                    f.render_widget(
-                        List::new(headers).block(config_block.title(" Headers ")),
-                        right_col[2],
+                    use crate::app::DefaultHeaderState;
+                    let tab = app.active_tab();
+                    let editing = matches!(
+                        tab.input_mode,
+                        InputMode::EditingHeaderKey | InputMode::EditingHeaderValue
                     );
-                }
-                2 => {
-                    let body_type = app.active_tab().body_type;
-
-                    let type_str = match body_type {
-                        crate::app::BodyType::Raw => "Raw (Text/JSON)",
-                        crate::app::BodyType::FormData => "Multipart Form",
-                        crate::app::BodyType::GraphQL => "GraphQL",
-                        crate::app::BodyType::Grpc => "gRPC (Proto)",
+                    let draft_line = |d: &crate::app::HeaderDraft| match tab.input_mode {
+                        InputMode::EditingHeaderKey => format!(
+                            "{}: {}",
+                            with_cursor(&d.name, app.input_cursor, '│'),
+                            d.value
+                        ),
+                        _ => format!(
+                            "{}: {}",
+                            d.name,
+                            with_cursor(&d.value, app.input_cursor, '│')
+                        ),
                     };
-                    let main_title = format!(" Body: {} (Press 't' to switch) ", type_str);
-
-                    match body_type {
-                        crate::app::BodyType::Raw => {
-                            let request_body = app.active_tab().request_body.clone();
-                            let body_txt = if request_body.is_empty() {
-                                "No Body. Press 'b' to open editor.".to_string()
-                            } else {
+                    let mut headers: Vec<ListItem> = Vec::new();
+                    for (name, value, enabled) in tab.header_rows() {
+                        match &tab.header_draft {
+                            Some(d) if editing && d.original.as_deref() == Some(&name) => {
+                                headers.push(ListItem::new(draft_line(d)));
+                            }
+                            _ if enabled => {
+                                headers.push(ListItem::new(format!("{}: {}", name, value)))
+                            }
+                            _ => headers.push(ListItem::new(Line::from(vec![
+                                Span::styled(
+                                    format!("{}: {}", name, value),
+                                    Style::default()
+                                        .fg(app.theme.text_secondary)
+                                        .add_modifier(Modifier::CROSSED_OUT),
+                                ),
+                                Span::styled(
+                                    "  (off)",
+                                    Style::default().fg(app.theme.text_secondary),
+                                ),
+                            ]))),
+                        }
+                    }
+                    if let Some(d) = &tab.header_draft
+                        && editing
+                        && d.original.is_none()
+                    {
+                        headers.push(ListItem::new(draft_line(d)));
+                    }
+                    // Defaults greyed out under the tab's own, crossed out when not sent
+                    for default in app.default_headers() {
+                        let (note, style) = match default.state {
+                            DefaultHeaderState::Sent => (
+                                default.source.clone(),
+                                Style::default().fg(app.theme.text_secondary),
+                            ),
+                            DefaultHeaderState::Overridden => (
+                                format!("{}, overridden", default.source),
+                                Style::default()
+                                    .fg(app.theme.text_secondary)
+                                    .add_modifier(Modifier::CROSSED_OUT),
+                            ),
+                            DefaultHeaderState::Disabled => (
+                                format!("{}, off", default.source),
+                                Style::default()
+                                    .fg(app.theme.text_secondary)
+                                    .add_modifier(Modifier::CROSSED_OUT),
+                            ),
+                        };
+                        headers.push(ListItem::new(Line::from(vec![
+                            Span::styled(format!("{}: {}", default.name, default.value), style),
+                            Span::styled(
+                                format!("  ({})", note),
+                                Style::default().fg(app.theme.text_secondary),
+                            ),
+                        ])));
+                    }
+                    if headers.is_empty() {
+                        headers.push(ListItem::new("No headers. Press 'a' to add."));
+                    }
+
+                    let (title, style) = if editing {
+                        (
+                            " Headers (Editing...) ",
+                            Style::default().fg(app.theme.border_focus),
+                        )
+                    } else {
+                        (
+                            " Headers ('e' Edit, 'a' Add, 'd' Delete, Space On/Off) ",
+                            Style::default().fg(app.theme.border),
+                        )
+                    };
+                    let list = List::new(headers)
+                        .block(
+                            Block::default()
+                                .title(title)
+                                .borders(Borders::ALL)
+                                .border_style(style),
+                        )
+                        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+                        .highlight_symbol("> ");
+                    f.render_stateful_widget(
+                        list,
+                        config_area,
+                        &mut app.active_tab_mut().headers_list_state,
+                    );
+                }
+                2 => {
+                    let body_type = app.active_tab().body_type;
+
+                    let type_str = match body_type {
+                        crate::app::BodyType::Raw => "Raw (Text/JSON)",
+                        crate::app::BodyType::FormData => "Multipart Form",
+                        crate::app::BodyType::UrlEncoded => "x-www-form-urlencoded",
+                        crate::app::BodyType::GraphQL => "GraphQL",
+                        crate::app::BodyType::Grpc => "gRPC (Proto)",
+                    };
+                    let main_title = format!(" Body: {} (Press 't' to switch) ", type_str);
+
+                    match body_type {
+                        crate::app::BodyType::Raw => {
+                            let request_body = app.active_tab().request_body.clone();
+                            let body_txt = if request_body.is_empty() {
+                                "No Body. Press 'b' to open editor.".to_string()
+                            } else {
                                 request_body
                             };
 
@@ -698,13 +1228,14 @@ pub fn render(f: &mut Frame, app: &mut App) {
                                 "txt"
                             };
 
-                            let highlighted = crate::ui::syntax::highlight(&body_txt, ext);
+                            let highlighted =
+                                crate::ui::syntax::highlight(&body_txt, ext, &app.theme.syntax);
 
                             f.render_widget(
                                 Paragraph::new(highlighted)
                                     .block(config_block.title(main_title))
                                     .wrap(Wrap { trim: true }),
-                                right_col[2],
+                                config_area,
                             );
                         }
                         crate::app::BodyType::FormData => {
@@ -721,15 +1252,15 @@ pub fn render(f: &mut Frame, app: &mut App) {
                                         let content = if Some(i) == tab.form_list_state.selected() {
                                             match tab.input_mode {
                                                 InputMode::EditingFormKey => format!(
-                                                    "{} _ = {} {}",
-                                                    k,
+                                                    "{} = {} {}",
+                                                    with_cursor(k, app.input_cursor, '│'),
                                                     v,
                                                     if *is_file { "[FILE]" } else { "" }
                                                 ),
                                                 InputMode::EditingFormValue => format!(
-                                                    "{} = {} _ {}",
+                                                    "{} = {} {}",
                                                     k,
-                                                    v,
+                                                    with_cursor(v, app.input_cursor, '│'),
                                                     if *is_file { "[FILE]" } else { "" }
                                                 ),
                                                 _ => format!(
@@ -780,13 +1311,80 @@ pub fn render(f: &mut Frame, app: &mut App) {
                                 .highlight_symbol("> ");
                             f.render_stateful_widget(
                                 list,
-                                right_col[3],
+                                config_area,
                                 &mut app.active_tab_mut().form_list_state,
                             );
                         }
+                        crate::app::BodyType::UrlEncoded => {
+                            let mut items = Vec::new();
+                            let input_mode;
+                            {
+                                let tab = app.active_tab();
+                                input_mode = tab.input_mode;
+                                if tab.urlencoded.is_empty() {
+                                    items.push(ListItem::new("No fields. Press 'a' to add."));
+                                } else {
+                                    for (i, (k, v)) in tab.urlencoded.iter().enumerate() {
+                                        let content =
+                                            if Some(i) == tab.urlencoded_list_state.selected() {
+                                                match tab.input_mode {
+                                                    InputMode::EditingUrlEncodedKey => {
+                                                        format!(
+                                                            "{} = {}",
+                                                            with_cursor(k, app.input_cursor, '│'),
+                                                            v
+                                                        )
+                                                    }
+                                                    InputMode::EditingUrlEncodedValue => {
+                                                        format!(
+                                                            "{} = {}",
+                                                            k,
+                                                            with_cursor(v, app.input_cursor, '│')
+                                                        )
+                                                    }
+                                                    _ => format!("{} = {}", k, v),
+                                                }
+                                            } else {
+                                                format!("{} = {}", k, v)
+                                            };
+                                        items.push(ListItem::new(content));
+                                    }
+                                }
+                            }
+
+                            let title = match input_mode {
+                                InputMode::EditingUrlEncodedKey
+                                | InputMode::EditingUrlEncodedValue => " Fields (Editing...) ",
+                                _ => " Fields ('e': Edit, 'a': Add, 'd': Del) ",
+                            };
+
+                            let style = match input_mode {
+                                InputMode::EditingUrlEncodedKey
+                                | InputMode::EditingUrlEncodedValue => {
+                                    Style::default().fg(Color::Yellow)
+                                }
+                                _ => Style::default().fg(Color::Blue),
+                            };
+
+                            let list = List::new(items)
+                                .block(
+                                    Block::default()
+                                        .title(main_title)
+                                        .borders(Borders::ALL)
+                                        .border_style(style)
+                                        .title_bottom(title),
+                                )
+                                .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+                                .highlight_symbol("> ");
+                            f.render_stateful_widget(
+                                list,
+                                config_area,
+                                &mut app.active_tab_mut().urlencoded_list_state,
+                            );
+                        }
                         crate::app::BodyType::GraphQL => {
-                            f.render_widget(config_block.clone().title(main_title), right_col[3]);
-                            let inner = config_block.inner(right_col[3]);
+                            f.render_widget(config_block.clone().title(main_title), config_area);
+                            let inner = config_block.inner(config_area);
                             let chunks = Layout::default()
                                 .direction(Direction::Vertical)
                                 .constraints([
@@ -821,7 +1419,8 @@ pub fn render(f: &mut Frame, app: &mut App) {
                             } else {
                                 graphql_variables
                             };
-                            let highlighted_vars = crate::ui::syntax::highlight(&vars_txt, "json");
+                            let highlighted_vars =
+                                crate::ui::syntax::highlight(&vars_txt, "json", &app.theme.syntax);
                             f.render_widget(
                                 Paragraph::new(highlighted_vars)
                                     .block(
@@ -834,8 +1433,8 @@ pub fn render(f: &mut Frame, app: &mut App) {
                             );
                         }
                         crate::app::BodyType::Grpc => {
-                            f.render_widget(config_block.clone().title(main_title), right_col[2]);
-                            let inner = config_block.inner(right_col[2]);
+                            f.render_widget(config_block.clone().title(main_title), config_area);
+                            let inner = config_block.inner(config_area);
                             let chunks = Layout::default()
                                 .direction(Direction::Vertical)
                                 .constraints([
@@ -895,7 +1494,7 @@ pub fn render(f: &mut Frame, app: &mut App) {
                             f.render_widget(
                                 Paragraph::new("No Authentication selected.")
                                     .block(config_block.title(main_title)),
-                                right_col[3],
+                                config_area,
                             );
                         }
                         crate::app::AuthType::Bearer => {
@@ -913,7 +1512,9 @@ pub fn render(f: &mut Frame, app: &mut App) {
                             } else {
                                 Style::default()
                             };
-                            let auth_txt = if auth_token.is_empty() {
+                            let auth_txt = if input_mode == InputMode::EditingAuth {
+                                with_cursor(&auth_token, app.input_cursor, '│')
+                            } else if auth_token.is_empty() {
                                 "No token set".to_string()
                             } else {
                                 auth_token
@@ -928,7 +1529,7 @@ pub fn render(f: &mut Frame, app: &mut App) {
                                             .title_bottom(title),
                                     )
                                     .wrap(Wrap { trim: true }),
-                                right_col[3],
+                                config_area,
                             );
                         }
                         crate::app::AuthType::Basic => {
@@ -952,9 +1553,18 @@ pub fn render(f: &mut Frame, app: &mut App) {
                                 Style::default()
                             };
 
-                            let user_txt = format!("Username: {}", basic_auth_user);
-                            let pass_txt =
-                                format!("Password: {}", "*".repeat(basic_auth_pass.len()));
+                            let mut user = basic_auth_user;
+                            if input_mode == InputMode::EditingBasicAuthUser {
+                                user = with_cursor(&user, app.input_cursor, '│');
+                            }
+                            let mut masked = "*".repeat(basic_auth_pass.chars().count());
+                            if input_mode == InputMode::EditingBasicAuthPass {
+                                let cursor = line_edit::clamp(&basic_auth_pass, app.input_cursor);
+                                let at = basic_auth_pass[..cursor].chars().count();
+                                masked = with_cursor(&masked, at, '│');
+                            }
+                            let user_txt = format!("Username: {}", user);
+                            let pass_txt = format!("Password: {}", masked);
 
                             let content = vec![
                                 ListItem::new(user_txt).style(user_style),
@@ -967,7 +1577,7 @@ pub fn render(f: &mut Frame, app: &mut App) {
                                         .title(main_title)
                                         .title_bottom(" Press 'u' for User, 'p' for Pass "),
                                 ),
-                                right_col[3],
+                                config_area,
                             );
                         }
                         crate::app::AuthType::OAuth2 => {
@@ -998,6 +1608,17 @@ pub fn render(f: &mut Frame, app: &mut App) {
                                 Style::default()
                             };
 
+                            let shown = |mode: InputMode, text: String| {
+                                if input_mode == mode {
+                                    with_cursor(&text, app.input_cursor, '│')
+                                } else {
+                                    text
+                                }
+                            };
+                            let client_id = shown(InputMode::EditingOAuthClientId, client_id);
+                            let auth_url = shown(InputMode::EditingOAuthUrl, auth_url);
+                            let token_url = shown(InputMode::EditingOAuthTokenUrl, token_url);
+
                             let content = vec![
                                 ListItem::new(format!("Client ID: {}", client_id)).style(id_style),
                                 ListItem::new(format!("Auth URL: {}", auth_url)).style(url1_style),
@@ -1024,7 +1645,7 @@ pub fn render(f: &mut Frame, app: &mut App) {
                                         " 'i': ID, '1': AuthURL, '2': TokenURL, 'Enter': Connect ",
                                     ),
                                 ),
-                                right_col[3],
+                                config_area,
                             );
                         }
                     }
@@ -1043,10 +1664,18 @@ pub fn render(f: &mut Frame, app: &mut App) {
                                 let content = if Some(i) == tab.extract_list_state.selected() {
                                     match tab.input_mode {
                                         InputMode::EditingChainKey => {
-                                            format!("{} _ <- {}", key, path)
+                                            format!(
+                                                "{} <- {}",
+                                                with_cursor(key, app.input_cursor, '│'),
+                                                path
+                                            )
                                         }
                                         InputMode::EditingChainPath => {
-                                            format!("{} <- {} _", key, path)
+                                            format!(
+                                                "{} <- {}",
+                                                key,
+                                                with_cursor(path, app.input_cursor, '│')
+                                            )
                                         }
                                         _ => format!("{} <- {}", key, path),
                                     }
@@ -1086,15 +1715,80 @@ pub fn render(f: &mut Frame, app: &mut App) {
 
                     f.render_stateful_widget(
                         list,
-                        right_col[3],
+                        config_area,
                         &mut app.active_tab_mut().extract_list_state,
                     );
                 }
+                5 => {
+                    use crate::net::timeouts::{Phase, format_ms};
+                    let tab = app.active_tab();
+                    let editing = tab.input_mode == InputMode::EditingTimeout;
+                    let selected = tab.settings_list_state.selected();
+                    let items: Vec<ListItem> = Phase::ALL
+                        .iter()
+                        .enumerate()
+                        .map(|(i, phase)| {
+                            let value = if editing && selected == Some(i) {
+                                Span::raw(format!(
+                                    "{} ms",
+                                    with_cursor(&app.timeout_input, app.input_cursor, '│')
+                                ))
+                            } else {
+                                match tab.timeout(*phase) {
+                                    Some(ms) => Span::raw(format_ms(ms)),
+                                    None => Span::styled(
+                                        phase.unset(),
+                                        Style::default().fg(app.theme.text_secondary),
+                                    ),
+                                }
+                            };
+                            ListItem::new(Line::from(vec![
+                                Span::raw(format!("{:<8} timeout  ", phase.label())),
+                                value,
+                            ]))
+                        })
+                        .collect();
+
+                    let (title, style) = if editing {
+                        (
+                            " Timeouts (Enter to set, empty for the default, Esc to cancel) ",
+                            Style::default().fg(app.theme.border_focus),
+                        )
+                    } else {
+                        (
+                            " Timeouts (Press 'e' to Edit; saved with the request) ",
+                            Style::default().fg(app.theme.border),
+                        )
+                    };
+                    let list = List::new(items)
+                        .block(
+                            Block::default()
+                                .title(title)
+                                .borders(Borders::ALL)
+                                .border_style(style),
+                        )
+                        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+                        .highlight_symbol("> ");
+
+                    let [timeouts_area, pins_area] = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Length(Phase::ALL.len() as u16 + 2),
+                            Constraint::Min(0),
+                        ])
+                        .areas(config_area);
+                    f.render_stateful_widget(
+                        list,
+                        timeouts_area,
+                        &mut app.active_tab_mut().settings_list_state,
+                    );
+                    render_dns_pins(f, app, pins_area);
+                }
                 _ => {}
             };
-
-            render_response_area(f, app, right_col[4]);
         }
+
+        render_response_area(f, app, layout.response);
     }
 
     if app.active_tab().show_schema_modal {
@@ -1109,34 +1803,33 @@ pub fn render(f: &mut Frame, app: &mut App) {
         render_grpc_description_modal(f, app);
     }
 
-    if let Some(msg) = &app.popup_message {
-        let area = centered_rect(60, 20, f.area());
-
-        // Clear and Render Popup
-        f.render_widget(ratatui::widgets::Clear, area);
-        let block = Block::default()
-            .title(Span::styled(
-                " 🔔 Notification ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ))
-            .title_bottom(Span::styled(
-                " Press Esc to close ",
-                Style::default().fg(app.theme.text_secondary),
-            ))
-            .borders(Borders::ALL)
-            .border_type(BorderType::Double)
-            .border_style(Style::default().fg(app.theme.highlight))
-            .style(
-                Style::default()
-                    .bg(app.theme.background)
-                    .fg(app.theme.text_primary),
-            );
-
-        let para = Paragraph::new(msg.as_str())
-            .block(block)
-            .wrap(Wrap { trim: true })
-            .alignment(ratatui::layout::Alignment::Center);
-
+    /// The active environment's `resolve` table, which every send, run and test uses
+    fn render_dns_pins(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+        let env = app.get_active_env();
+        let dim = Style::default().fg(app.theme.text_secondary);
+        let lines: Vec<Line> = if env.resolve.is_empty() {
+            vec![Line::from(Span::styled(
+                "No hosts pinned (add a resolve table to the environment)",
+                dim,
+            ))]
+        } else {
+            env.resolve
+                .iter()
+                .map(|(host, addrs)| {
+                    Line::from(vec![
+                        Span::raw(format!("📌 {} ", host)),
+                        Span::styled("→ ", dim),
+                        Span::raw(addrs.clone()),
+                    ])
+                })
+                .collect()
+        };
+        let para = Paragraph::new(lines).block(
+            Block::default()
+                .title(format!(" DNS Pins ({}) ", env.name))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border)),
+        );
         f.render_widget(para, area);
     }
 
@@ -1231,7 +1924,24 @@ pub fn render(f: &mut Frame, app: &mut App) {
             f.render_widget(para, test_area);
         }
 
-        let (is_loading, status_code, latency, search_query, input_mode) = {
+        if let Some(metadata) = app.active_tab().grpc_metadata.as_ref() {
+            let lines = grpc_metadata_lines(app, metadata);
+            let height = (lines.len() as u16 + 2).min(main_area.height / 2);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(height), Constraint::Min(0)])
+                .split(main_area);
+            main_area = chunks[1];
+            let para = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .title(" gRPC Status & Metadata ")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(app.theme.accent)),
+            );
+            f.render_widget(para, chunks[0]);
+        }
+
+        let (is_loading, status_code, latency, search_query, input_mode, find_query) = {
             let tab = app.active_tab();
             (
                 tab.is_loading,
@@ -1239,12 +1949,27 @@ pub fn render(f: &mut Frame, app: &mut App) {
                 tab.latency,
                 tab.search_query.clone(),
                 tab.input_mode,
+                tab.find_query.clone(),
             )
         };
+        let finding = input_mode == InputMode::FindInResponse || !find_query.is_empty();
 
         let status_bar_text = if is_loading {
-            let spinner_frames = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
-            format!(" {} Fetching... ", spinner_frames[app.spinner_state % 10])
+            let spinner = SPINNER[app.spinner_state % SPINNER.len()];
+            match (&app.active_tab().stream, status_code) {
+                (Some(stream), Some(code)) => format!(
+                    " {} {} | Streaming {} | {} (O) ",
+                    spinner,
+                    code,
+                    format_size(stream.received),
+                    if app.follow_stream {
+                        "Following"
+                    } else {
+                        "Not following"
+                    }
+                ),
+                _ => format!(" {} Fetching... ", spinner),
+            }
         } else {
             match (status_code, latency) {
                 (Some(code), Some(ms)) => {
@@ -1266,6 +1991,19 @@ pub fn render(f: &mut Frame, app: &mut App) {
                     if !tab.script_output.is_empty() {
                         s.push_str("| Console: Yes ");
                     }
+                    if let Some(trace) = &tab.trace {
+                        s.push_str(&format!("| Trace: {} ", trace.trace_id));
+                    }
+                    match &tab.response_encoding {
+                        Some(e) if e.error.is_some() => {
+                            s.push_str(&format!("| {}: not decoded (U: raw) ", e.encoding))
+                        }
+                        Some(e) => s.push_str(&format!("| {} (U: raw) ", e.encoding)),
+                        None => {}
+                    }
+                    if let Some(t) = tab.treat_as {
+                        s.push_str(&format!("| as {} (V) ", t.name()));
+                    }
                     s
                 }
                 (Some(code), None) => {
@@ -1294,7 +2032,54 @@ pub fn render(f: &mut Frame, app: &mut App) {
             Style::default().fg(app.theme.border)
         };
 
-        let block_title = if input_mode == InputMode::Search {
+        let status_bar_text = match app
+            .active_tab()
+            .response_headers
+            .get("content-range")
+            .and_then(|v| crate::features::range::parse_content_range(v))
+        {
+            Some(range) => format!(
+                "{}| {} {} ",
+                status_bar_text,
+                range.describe(),
+                range.bar(10)
+            ),
+            None => status_bar_text,
+        };
+
+        let status_bar_text = match &app.active_tab().jsonpath_query {
+            Some(query) => format!("{}[JSONPath: {}] ", status_bar_text, query),
+            None => status_bar_text,
+        };
+
+        let status_bar_text = match (
+            &app.active_tab().response_ndjson,
+            app.active_tab().ndjson_record,
+        ) {
+            (Some(lines), Some(number)) => format!(
+                "{}| NDJSON line {} of {} (L: lines) ",
+                status_bar_text,
+                number,
+                lines.len()
+            ),
+            (Some(lines), None) => {
+                format!("{}| NDJSON {} records (L) ", status_bar_text, lines.len())
+            }
+            _ => status_bar_text,
+        };
+
+        let block_title = if finding {
+            let matches = app.active_tab().find_matches().len();
+            let current = if matches == 0 {
+                0
+            } else {
+                app.active_tab().find_index.min(matches - 1) + 1
+            };
+            format!(
+                "{} [Find: {} {}/{}] ",
+                status_bar_text, find_query, current, matches
+            )
+        } else if input_mode == InputMode::Search {
             format!("{} [Search: {}] ", status_bar_text, search_query)
         } else if !search_query.is_empty() {
             format!("{} [Filter: {}] ", status_bar_text, search_query)
@@ -1303,21 +2088,54 @@ pub fn render(f: &mut Frame, app: &mut App) {
         };
 
         // Determine if we have JSON response
-        let has_json = app.active_tab().response_json.is_some();
-
-        if has_json {
+        let has_json =
+            app.active_tab().response_json.is_some() && !finding && !app.active_tab().response_raw;
+
+        let has_csv =
+            app.active_tab().response_csv.is_some() && !finding && !app.active_tab().response_raw;
+
+        app.screen_areas.response = Some(main_area);
+        if app.active_tab().show_docs {
+            render_docs(f, app, main_area);
+        } else if app.hex_view.is_some() {
+            render_hex_view(f, app, main_area, status_style);
+        } else if has_csv {
+            render_csv_table(f, app, main_area, block_title, status_style);
+        } else if has_json {
             let mut items = Vec::new();
             let mut json_path = String::new();
+            // The tree view only turns the rows on screen into items: `window` is
+            // where they start and which of them is selected
+            let mut window = None;
             {
                 let tab = app.active_tab();
-                if let Some(tree) = &tab.response_json {
-                    let mut counter = 0;
-                    flatten_tree(tree, &mut items, &tab.search_query, &mut counter);
-
-                    // Get JSON path for selected item
-                    if let Some(selected_idx) = tab.json_list_state.selected() {
-                        json_path = get_json_path(tree, selected_idx, &tab.search_query);
-                    }
+                if let Some(tree) = &tab.response_json
+                    && tab.json_flat
+                {
+                    items = flat_rows(tree, &tab.search_query);
+                    json_path = app.selected_json_path().unwrap_or_default();
+                } else if let Some(tree) = &tab.response_json {
+                    let filter = &tab.search_query;
+                    let height = main_area.height.saturating_sub(2) as usize;
+                    let selected = tab.json_list_state.selected();
+                    let offset = crate::ui::json_rows::window_offset(
+                        tab.json_list_state.offset(),
+                        selected,
+                        height,
+                        tab.json_rows.len(tree, filter),
+                    );
+                    items = tab
+                        .json_rows
+                        .paths(tree, filter, offset..offset + height)
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, path)| {
+                            crate::ui::json_rows::entry(tree, path)
+                                .map(|entry| tree_item(entry, offset + i))
+                        })
+                        .collect();
+                    json_path = app.selected_json_path().unwrap_or_default();
+                    window = Some((offset, selected.map(|s| s.saturating_sub(offset))));
                 }
             }
 
@@ -1337,27 +2155,69 @@ pub fn render(f: &mut Frame, app: &mut App) {
                 )
                 .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
                 .highlight_symbol(">> ");
-            f.render_stateful_widget(list, main_area, &mut app.active_tab_mut().json_list_state);
-        } else if app.active_tab().response_is_binary {
+            match window {
+                Some((offset, selected)) => {
+                    *app.active_tab_mut().json_list_state.offset_mut() = offset;
+                    let mut state = ListState::default().with_selected(selected);
+                    f.render_stateful_widget(list, main_area, &mut state);
+                }
+                None => f.render_stateful_widget(
+                    list,
+                    main_area,
+                    &mut app.active_tab_mut().json_list_state,
+                ),
+            }
+        } else if app.active_tab().shown_binary() {
             let img_opt = app.active_tab().response_image.clone();
+            let size = app
+                .active_tab()
+                .response_bytes
+                .as_ref()
+                .map(|b| b.len())
+                .unwrap_or(0);
+            let image_info = img_opt.as_ref().map(|img| {
+                let bytes = app
+                    .active_tab()
+                    .response_bytes
+                    .as_deref()
+                    .unwrap_or_default();
+                format!(
+                    "{}, {}",
+                    crate::features::image_view::describe(bytes, img),
+                    format_size(size)
+                )
+            });
 
-            if let Some(img) = img_opt
+            if let (Some(img), Some(info)) = (img_opt, &image_info)
                 && let Some(picker) = &mut app.image_picker
             {
+                let block = Block::default()
+                    .title(format!("{}| {} ", block_title, info))
+                    .title_bottom(format!(
+                        " {} (z) · D: save as · P: open in viewer ",
+                        app.image_zoom.label()
+                    ))
+                    .borders(Borders::ALL)
+                    .border_style(status_style);
+                let inner = block.inner(main_area);
+                f.render_widget(block, main_area);
                 let mut protocol = picker.new_resize_protocol(img);
-                let widget = StatefulImage::new();
-                f.render_stateful_widget(widget, main_area, &mut protocol);
+                let widget = StatefulImage::new().resize(app.image_zoom.resize());
+                f.render_stateful_widget(widget, inner, &mut protocol);
                 return;
             }
 
-            let size = app
-                .active_tab()
-                .response_bytes
-                .as_ref()
-                .map(|b| b.len())
-                .unwrap_or(0);
-            let content = vec![
-                Line::from(vec![
+            let heading = match &image_info {
+                Some(info) => vec![
+                    Span::styled(
+                        "🖼 Image ",
+                        Style::default()
+                            .add_modifier(Modifier::BOLD)
+                            .fg(Color::Yellow),
+                    ),
+                    Span::raw(format!("({})", info)),
+                ],
+                None => vec![
                     Span::styled(
                         "📦 Binary Content Detected ",
                         Style::default()
@@ -1365,7 +2225,10 @@ pub fn render(f: &mut Frame, app: &mut App) {
                             .fg(Color::Yellow),
                     ),
                     Span::raw(format!("({} bytes)", size)),
-                ]),
+                ],
+            };
+            let content = vec![
+                Line::from(heading),
                 Line::from(""),
                 Line::from(vec![
                     Span::raw("Press "),
@@ -1375,7 +2238,7 @@ pub fn render(f: &mut Frame, app: &mut App) {
                             .add_modifier(Modifier::BOLD)
                             .fg(Color::Cyan),
                     ),
-                    Span::raw(" to Download"),
+                    Span::raw(" to Save As"),
                 ]),
                 Line::from(vec![
                     Span::raw("Press "),
@@ -1387,6 +2250,16 @@ pub fn render(f: &mut Frame, app: &mut App) {
                     ),
                     Span::raw(" to Preview (Open in Default Viewer)"),
                 ]),
+                Line::from(vec![
+                    Span::raw("Press "),
+                    Span::styled(
+                        "B",
+                        Style::default()
+                            .add_modifier(Modifier::BOLD)
+                            .fg(Color::Cyan),
+                    ),
+                    Span::raw(" to View as Hex"),
+                ]),
             ];
 
             let para = Paragraph::new(content)
@@ -1399,19 +2272,58 @@ pub fn render(f: &mut Frame, app: &mut App) {
                 .alignment(ratatui::layout::Alignment::Center)
                 .wrap(Wrap { trim: false });
             f.render_widget(para, main_area);
+        } else if finding && let Some(content) = app.active_tab().searchable_text() {
+            let matches = app.active_tab().find_matches();
+            let current = matches.get(app.active_tab().find_index).copied();
+            let lines = highlight_find_matches(&content, find_query.len(), &matches, current);
+
+            let para = Paragraph::new(lines)
+                .block(
+                    Block::default()
+                        .title(block_title)
+                        .borders(Borders::ALL)
+                        .border_style(status_style),
+                )
+                .wrap(Wrap { trim: false })
+                .scroll((app.active_tab().response_scroll.0, 0));
+            f.render_widget(para, main_area);
         } else {
             let content = app
                 .active_tab()
-                .response
-                .as_deref()
-                .unwrap_or("No data yet. Press Enter to send request.")
-                .to_string(); // clone string to simplify lifetime
-
-            // Highlight response
-            let ext = app.guess_extension().unwrap_or("txt".to_string());
-            let highlighted = crate::ui::syntax::highlight(&content, &ext);
-
+                .display_text()
+                .unwrap_or_else(|| "No data yet. Press Enter to send request.".to_string());
+
+            // Still coming in: plain text, the end kept in view while following
+            let streaming = app.active_tab().stream.is_some();
+            if streaming && app.follow_stream {
+                let inner = main_area.inner(ratatui::layout::Margin::new(1, 1));
+                app.active_tab_mut().response_scroll.0 =
+                    crate::net::streaming::tail_scroll(&content, inner.width, inner.height);
+            }
             let scroll = app.active_tab().response_scroll;
+            // Only the lines that can be on screen, and the rows of the first one
+            // above the viewport
+            let (highlighted, skip) = if streaming {
+                (content.lines().map(Line::raw).collect(), scroll.0 as usize)
+            } else {
+                let ext = app.response_extension();
+                let height = main_area.height.saturating_sub(2) as usize;
+                let width = main_area.width.saturating_sub(2) as usize;
+                let colors = app.theme.syntax.clone();
+                app.active_tab_mut().highlight.lines(
+                    &content,
+                    &ext,
+                    &colors,
+                    scroll.0 as usize,
+                    height,
+                    width,
+                )
+            };
+            let block_title = if app.active_tab().highlight.is_pending() && !streaming {
+                format!("{}| highlighting… ", block_title)
+            } else {
+                block_title
+            };
 
             let para = Paragraph::new(highlighted)
                 .block(
@@ -1421,114 +2333,11 @@ pub fn render(f: &mut Frame, app: &mut App) {
                         .border_style(status_style),
                 )
                 .wrap(Wrap { trim: false })
-                .scroll((scroll.0, 0));
+                .scroll((skip as u16, 0));
             f.render_widget(para, main_area);
         }
     }
 
-    if app.show_help {
-        let area = centered_rect(65, 70, f.area());
-        f.render_widget(ratatui::widgets::Clear, area);
-        let block = Block::default()
-            .title(" Help (j/k: Scroll, ?: Close) ")
-            .borders(Borders::ALL)
-            .style(
-                Style::default()
-                    .bg(app.theme.background)
-                    .fg(app.theme.text_primary),
-            );
-
-        let help_text = vec![
-            "General:",
-            "  q          Quit",
-            "  ?          Toggle Help",
-            "  Ctrl+h     Focus Sidebar / Main",
-            "  Ctrl+e     Switch Environment",
-            "  Ctrl+t     Cycle Themes",
-            "  Ctrl+z     Toggle Zen Mode",
-            "  Ctrl+p     Command Palette",
-            ":          Command Mode",
-            "",
-            "Request Tabs:",
-            "  Ctrl+n     New Tab",
-            "  Ctrl+x     Close Tab",
-            "  [ / ]      Cycle Open Tabs",
-            "  I          Import cURL Command",
-            "",
-            "Sidebar / History (Focus with Ctrl+h):",
-            "  Enter      Load Request",
-            "  D          Diff: Select Base (1st) then Target (2nd)",
-            "",
-            "Navigation:",
-            "  j / k      Move Up / Down",
-            "  h / l      Collapse / Expand JSON",
-            "  Space      Toggle JSON / Form File",
-            "  Tab        Cycle Tabs (Params, Headers, ...)",
-            "",
-            "Request:",
-            "  e          Edit URL (Tab to Cycle Method)",
-            "  m          Cycle Method (GET, POST, ...)",
-            "  t          Switch Body Type (in Body Tab)",
-            "  b          Edit Body (Ext. Editor)",
-            "  Q / V      Edit GraphQL Query / Vars",
-            "  H          Edit Headers (Ext. Editor)",
-            "  f          Toggle Fullscreen/Sidebar Filter",
-            "  s          Save Request",
-            "  Enter      Send Request",
-            "",
-            "Params / Chain Tabs:",
-            "  a          Add Item",
-            "  d          Delete Item",
-            "  e          Edit Item",
-            "",
-            "Auth Tab:",
-            "  t          Switch Auth Type",
-            "  u / p      Edit User / Pass (Basic)",
-            "  i / 1 / 2  Edit OAuth ID / URLs",
-            "",
-            "Code Generators (copy to clipboard):",
-            "  c          cURL command",
-            "  G / J      Python / JavaScript",
-            "  O / R      Go / Rust",
-            "  B / E      Ruby / PHP",
-            "  S          C#",
-            "",
-            "Response:",
-            "  C          Copy Response Output",
-            "  D          Download Response (Binary)",
-            "  P          Preview Response (External)",
-            "  y          Copy JSON Path",
-            "  /          Search / Filter JSON",
-            "  (Images render automatically in supported terminals)",
-            "",
-            "Scripts & Testing:",
-            "  P          Edit Pre-Request Script (If not binary)",
-            "  T          Edit Post/Test Script",
-            "  %          Stress Test (Shift+5)",
-            "  S          Sentinel Mode (Live Monitor)",
-            "  M          Generate API Docs (MD + HTML)",
-            "",
-            "Modes:",
-            "  Ctrl+w     Toggle WebSocket Mode",
-            "  Ctrl+r     Toggle Collection Runner",
-            "  Ctrl+k     Mock Server Manager",
-            "  Ctrl+j     Cookie Manager",
-            "",
-            "gRPC (Body Tab -> 't' to gRPC mode):",
-            "  u          Edit Service/Method",
-            "  p          Edit Proto file path",
-            "  L          List services (reflection)",
-            "  D          Describe service (in modal)",
-            "  Enter      Send gRPC request",
-        ]
-        .join("\n");
-
-        let para = Paragraph::new(help_text)
-            .block(block)
-            .style(Style::default().fg(Color::White))
-            .scroll((app.help_scroll, 0));
-        f.render_widget(para, area);
-    }
     if app.stress_running {
         render_stress_running_overlay(f, app);
     }
@@ -1541,10 +2350,25 @@ pub fn render(f: &mut Frame, app: &mut App) {
     if app.show_command_palette {
         render_command_palette(f, app);
     }
+    if app.show_codegen_picker {
+        render_codegen_picker(f, app);
+    }
+    if app.show_snippet_picker {
+        render_snippet_picker(f, app);
+    }
+    if app.show_finder {
+        render_finder(f, app);
+    }
+    if app.show_history_view {
+        render_history_view(f, app);
+    }
     // Render cURL import modal
     if app.active_tab().input_mode == crate::app::InputMode::ImportCurl {
         render_curl_import_modal(f, app);
     }
+    if app.active_tab().input_mode == crate::app::InputMode::SaveHistory {
+        render_save_history_modal(f, app);
+    }
     if app.show_cookie_modal {
         render_cookie_modal(f, app);
     }
@@ -1571,7 +2395,12 @@ fn render_runner_mode(f: &mut Frame, app: &mut App) {
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(app.theme.accent))
-                .title(" Ctrl+R to exit | Enter to run | ?: Help "),
+                .title(" Ctrl+R to exit | Enter to run | ?: Help ")
+                .title_bottom(if app.rate_limit.is_active() {
+                    format!(" ⏱ Rate limits: {} ", app.rate_limit.describe())
+                } else {
+                    String::new()
+                }),
         );
     f.render_widget(title, chunks[0]);
 
@@ -1593,14 +2422,29 @@ fn render_runner_mode(f: &mut Frame, app: &mut App) {
                 result.passed, result.failed, result.total
             )
         };
-        result_items.push(ListItem::new(Line::from(vec![Span::styled(
+        let mut status_line = vec![Span::styled(
             status_text,
             Style::default()
                 .fg(app.theme.text_primary)
                 .add_modifier(Modifier::BOLD),
-        )])));
-        result_items.push(ListItem::new("─".repeat(50)));
-
+        )];
+        match (&result.throttled, result.running) {
+            (Some(reason), true) => status_line.push(Span::styled(
+                format!(" ⏳ Throttled: {}", reason),
+                Style::default().fg(app.theme.highlight),
+            )),
+            _ if result.throttled_ms > 0 => status_line.push(Span::styled(
+                format!(
+                    " ⏳ {:.1}s waiting for rate limits",
+                    result.throttled_ms as f64 / 1000.0
+                ),
+                Style::default().fg(app.theme.text_secondary),
+            )),
+            _ => {}
+        }
+        result_items.push(ListItem::new(Line::from(status_line)));
+        result_items.push(ListItem::new("─".repeat(50)));
+
         // Individual results
         for run in result.results.iter() {
             let status_icon = if run.passed {
@@ -1710,7 +2554,7 @@ fn render_runner_mode(f: &mut Frame, app: &mut App) {
                     .title(format!(" Results: {} ", result.collection_name))
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(Color::Blue))
-                    .title_bottom(" j/k: Scroll | x: Clear | Esc: Exit "),
+                    .title_bottom(" j/k: Scroll | e/E: Export CSV/JSON | o: Save responses | x: Clear | Esc: Exit "),
             )
             .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
 
@@ -1779,62 +2623,6 @@ fn render_runner_mode(f: &mut Frame, app: &mut App) {
                 .border_style(Style::default().fg(Color::DarkGray)),
         );
     f.render_widget(status, chunks[2]);
-
-    // Notification popup
-    if let Some(msg) = &app.popup_message {
-        let area = centered_rect(60, 20, f.area());
-        f.render_widget(ratatui::widgets::Clear, area);
-        let block = Block::default()
-            .title(" Notification (Esc to close) ")
-            .borders(Borders::ALL)
-            .style(Style::default().bg(Color::Blue).fg(Color::White));
-        let para = Paragraph::new(msg.as_str())
-            .block(block)
-            .wrap(Wrap { trim: true })
-            .alignment(ratatui::layout::Alignment::Center);
-        f.render_widget(para, area);
-    }
-
-    // Help screen for Runner mode
-    if app.show_help {
-        let area = centered_rect(60, 50, f.area());
-        f.render_widget(ratatui::widgets::Clear, area);
-        let block = Block::default()
-            .title(" Collection Runner Help (Press '?' to close) ")
-            .borders(Borders::ALL)
-            .style(
-                Style::default()
-                    .bg(app.theme.background)
-                    .fg(app.theme.text_primary),
-            );
-
-        let help_text = vec![
-            "Collection Runner Mode:",
-            "  Ctrl+r     Exit Runner Mode",
-            "  Esc        Exit Runner Mode",
-            "  q          Quit Application",
-            "  ?          Toggle Help",
-            "",
-            "Before Run:",
-            "  j / k      Navigate collections",
-            "  Enter      Run selected collection",
-            "",
-            "After Run:",
-            "  j / k      Scroll through results",
-            "  x          Clear results",
-            "",
-            "Status Code Assertions:",
-            "  By default, expects HTTP 200",
-            "  Add 'expected_status = XXX' in .hcl",
-            "  to specify expected status code",
-        ]
-        .join("\n");
-
-        let para = Paragraph::new(help_text)
-            .block(block)
-            .style(Style::default().fg(app.theme.text_primary));
-        f.render_widget(para, area);
-    }
 }
 
 fn render_websocket_mode(f: &mut Frame, app: &mut App) {
@@ -1846,6 +2634,11 @@ fn render_websocket_mode(f: &mut Frame, app: &mut App) {
             Constraint::Length(3), // Input field
         ])
         .split(f.area());
+    app.screen_areas.ws = Some(layout::WsLayout {
+        url: chunks[0],
+        messages: chunks[1],
+        input: chunks[2],
+    });
 
     // Extract basic state
     let (input_mode, ws_connected, ws_url) = {
@@ -1883,6 +2676,7 @@ fn render_websocket_mode(f: &mut Frame, app: &mut App) {
         Style::default().fg(app.theme.text_primary),
     );
 
+    let url_prefix = Line::from(vec![ws_label.clone(), status_indicator.clone()]).width() as u16;
     let url_bar = Paragraph::new(Line::from(vec![ws_label, status_indicator, url_text])).block(
         Block::default()
             .title(if ws_connected {
@@ -1893,12 +2687,14 @@ fn render_websocket_mode(f: &mut Frame, app: &mut App) {
             .borders(Borders::ALL)
             .border_style(Style::default().fg(url_border_color)),
     );
-    f.render_widget(url_bar, chunks[0]);
-
     if input_mode == InputMode::EditingWsUrl {
-        let x = chunks[0].x + 1 + 4 + 3 + 1 + ws_url.len() as u16;
-        let y = chunks[0].y + 1;
-        f.set_cursor_position((x, y));
+        let cursor = line_edit::clamp(&ws_url, app.input_cursor);
+        let column = url_prefix + 1 + layout::width(&ws_url[..cursor]);
+        let scroll = layout::scroll_for(column, chunks[0].width.saturating_sub(2));
+        f.render_widget(url_bar.scroll((0, scroll)), chunks[0]);
+        f.set_cursor_position(layout::cursor_at(chunks[0], column, scroll));
+    } else {
+        f.render_widget(url_bar, chunks[0]);
     }
 
     // Messages area
@@ -1931,10 +2727,16 @@ fn render_websocket_mode(f: &mut Frame, app: &mut App) {
         .collect();
 
     let msg_count = app.active_tab().ws_messages.len();
-    let msg_title = format!(" Messages ({}) ", msg_count);
+    let msg_title = match app.active_tab().ws_replay {
+        Some((sent, total)) => format!(
+            " Messages ({}) | Replaying {}/{} (r: Stop) ",
+            msg_count, sent, total
+        ),
+        None => format!(" Messages ({}) ", msg_count),
+    };
     let msg_block = Block::default()
         .title(msg_title)
-        .title_bottom(" j/k: Scroll | x: Clear | ?: Help ")
+        .title_bottom(" j/k: Scroll | x: Clear | s: Save Log | r: Replay | ?: Help ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(app.theme.border));
 
@@ -1981,210 +2783,2410 @@ fn render_websocket_mode(f: &mut Frame, app: &mut App) {
             .borders(Borders::ALL)
             .border_style(Style::default().fg(input_border_color)),
     );
-    f.render_widget(input_bar, chunks[2]);
-
     if input_mode == InputMode::EditingWsMessage {
-        let x = chunks[2].x + 1 + ws_message_input.len() as u16;
-        let y = chunks[2].y + 1;
-        f.set_cursor_position((x, y));
+        let cursor = line_edit::clamp(&ws_message_input, app.input_cursor);
+        let column = layout::width(&ws_message_input[..cursor]);
+        let scroll = layout::scroll_for(column, chunks[2].width.saturating_sub(2));
+        f.render_widget(input_bar.scroll((0, scroll)), chunks[2]);
+        f.set_cursor_position(layout::cursor_at(chunks[2], column, scroll));
+    } else {
+        f.render_widget(input_bar, chunks[2]);
     }
+}
 
-    // Notification popup (Global)
-    if let Some(msg) = &app.popup_message {
-        let area = centered_rect(60, 20, f.area());
-        f.render_widget(ratatui::widgets::Clear, area);
-        let block = Block::default()
-            .title(" Notification (Esc to close) ")
-            .borders(Borders::ALL)
-            .style(Style::default().bg(Color::Blue).fg(Color::White));
-        let para = Paragraph::new(msg.as_str())
-            .block(block)
-            .wrap(Wrap { trim: true })
-            .alignment(ratatui::layout::Alignment::Center);
-        f.render_widget(para, area);
-    }
+/// Help overlay built from the binding table, limited to the current context
+fn render_cheat_sheet(f: &mut Frame, app: &App) {
+    let area = centered_rect(65, 70, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
 
-    // Help screen for WebSocket mode (Global)
-    if app.show_help {
-        let area = centered_rect(60, 50, f.area());
-        f.render_widget(ratatui::widgets::Clear, area);
-        let block = Block::default()
-            .title(" WebSocket Help (Press '?' to close) ")
-            .borders(Borders::ALL)
-            .style(Style::default().bg(Color::DarkGray).fg(Color::White));
-
-        let help_text = vec![
-            "WebSocket Mode:",
-            "  Ctrl+w     Switch to HTTP mode",
-            "  q          Quit",
-            "  ?          Toggle Help",
-            "",
-            "Connection:",
-            "  e          Edit WebSocket URL",
-            "  Enter      Connect / Disconnect",
-            "",
-            "Messaging:",
-            "  i          Start typing message",
-            "  Enter      Send message (while typing)",
-            "  Esc        Cancel typing",
-            "",
-            "Navigation:",
-            "  j / k      Scroll messages Up / Down",
-            "  x          Clear message history",
-        ]
-        .join("\n");
-
-        let para = Paragraph::new(help_text)
-            .block(block)
-            .style(Style::default().fg(Color::White));
-        f.render_widget(para, area);
+    let scope = if app.help_show_all {
+        "All Keys"
+    } else {
+        "Keys Here"
+    };
+    let block = Block::default()
+        .title(format!(
+            " Help: {} (/: Search, a: Show All, j/k: Scroll, ?: Close) ",
+            scope
+        ))
+        .borders(Borders::ALL)
+        .style(
+            Style::default()
+                .bg(app.theme.background)
+                .fg(app.theme.text_primary),
+        );
+
+    let mut lines = Vec::new();
+    if app.help_search_active || !app.help_query.is_empty() {
+        let cursor = if app.help_search_active { "█" } else { "" };
+        lines.push(Line::from(vec![
+            Span::styled(" Search: ", Style::default().fg(app.theme.highlight)),
+            Span::raw(format!("{}{}", app.help_query, cursor)),
+        ]));
+        lines.push(Line::from(""));
     }
-}
 
-fn centered_rect(
-    percent_x: u16,
-    percent_y: u16,
-    r: ratatui::layout::Rect,
-) -> ratatui::layout::Rect {
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(r);
+    let sections =
+        crate::domain::keymap::cheat_sheet(&app.key_contexts(), &app.help_query, &app.keymap);
+    if sections.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No matching keys (a: search all modes)",
+            Style::default().fg(app.theme.text_secondary),
+        )));
+    }
+    for (context, bindings) in sections {
+        lines.push(Line::from(Span::styled(
+            format!("{}:", context.title()),
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for binding in bindings {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<11}", binding.keys),
+                    Style::default().fg(app.theme.highlight),
+                ),
+                Span::raw(binding.action),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
 
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(popup_layout[1])[1]
+    let para = Paragraph::new(lines)
+        .block(block)
+        .scroll((app.help_scroll, 0));
+    f.render_widget(para, area);
 }
 
-pub fn render_diff_view(f: &mut Frame, app: &mut App) {
-    if let (Some(base_idx), Some(target_idx)) = (app.diff_base_index, app.diff_target_index)
-        && let (Some(base), Some(target)) = (
-            app.request_history.get(base_idx),
-            app.request_history.get(target_idx),
-        )
-    {
-        let area = f.area();
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Length(3), Constraint::Min(0)])
-            .split(area);
+/// "Re-send and compare" results: status/latency, then only what changed in the body
+fn render_guard_prompt(f: &mut Frame, app: &App) {
+    let Some(prompt) = &app.guard_prompt else {
+        return;
+    };
+    let profile = app
+        .current_profile()
+        .map(|p| p.name.as_str())
+        .unwrap_or_default();
+    let area = centered_rect(50, 20, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
 
-        // Title
-        let title = format!(
-            " Diff: Base ({}) vs Target ({}) - Press 'Esc' to close ",
-            base.url, target.url
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {}", prompt.message),
+            Style::default()
+                .fg(app.theme.text_primary)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            " y: Send   n / Esc: Cancel",
+            Style::default().fg(app.theme.text_secondary),
+        )),
+    ];
+    let para = Paragraph::new(lines)
+        .wrap(ratatui::widgets::Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" ⚠ Profile: {} ", profile))
+                .border_style(Style::default().fg(Color::Yellow)),
         );
-        let block = Block::default().borders(Borders::ALL).title(title);
-        f.render_widget(block, area);
-
-        // Inner chunks for diff content
-        let content_area = chunks[1];
-        let diff_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(content_area);
+    f.render_widget(para, area);
+}
 
-        let old_text = base.body.as_deref().unwrap_or("");
-        let new_text = target.body.as_deref().unwrap_or("");
+/// What looks wrong with the request about to go out, before it does
+fn render_send_warnings(f: &mut Frame, app: &App) {
+    let Some(warnings) = &app.send_warnings else {
+        return;
+    };
+    let area = centered_rect(60, 35, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
 
-        let diff = TextDiff::from_lines(old_text, new_text);
+    let hint = Style::default().fg(app.theme.text_secondary);
+    let mut lines = vec![Line::from("")];
+    for warning in warnings {
+        lines.push(Line::from(vec![
+            Span::styled(" ⚠ ", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                warning.message.clone(),
+                Style::default().fg(app.theme.text_primary),
+            ),
+            Span::styled(format!("  [{}]", warning.check.id()), hint),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " y: Send anyway   n / Esc: Cancel",
+        hint,
+    )));
+    let para = Paragraph::new(lines)
+        .wrap(ratatui::widgets::Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" Before sending: {} warning(s) ", warnings.len()))
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+    f.render_widget(para, area);
+}
 
-        let mut left_lines = Vec::new();
-        let mut right_lines = Vec::new();
+/// A file changed both here and in the gist (or storage): keep one side, or look at the
+/// diff first
+fn render_sync_prompt(f: &mut Frame, app: &App) {
+    let Some(prompt) = &app.sync_prompt else {
+        return;
+    };
+    let Some(file) = prompt.current().map(|i| &prompt.plan[i]) else {
+        return;
+    };
+    let open = crate::features::gist_sync::conflicts(&prompt.plan);
+    let area = if prompt.show_diff {
+        centered_rect(80, 80, f.area())
+    } else {
+        centered_rect(55, 30, f.area())
+    };
+    f.render_widget(ratatui::widgets::Clear, area);
 
-        for change in diff.iter_all_changes() {
-            match change.tag() {
-                ChangeTag::Delete => {
-                    left_lines.push(ListItem::new(Line::from(Span::styled(
-                        format!("- {}", change),
-                        Style::default().bg(Color::Red).fg(Color::Black),
-                    ))));
-                    right_lines.push(ListItem::new(Line::from("")));
-                }
-                ChangeTag::Insert => {
-                    left_lines.push(ListItem::new(Line::from("")));
-                    right_lines.push(ListItem::new(Line::from(Span::styled(
-                        format!("+ {}", change),
-                        Style::default().bg(Color::Green).fg(Color::Black),
-                    ))));
-                }
-                ChangeTag::Equal => {
-                    left_lines.push(ListItem::new(Line::from(format!("  {}", change))));
-                    right_lines.push(ListItem::new(Line::from(format!("  {}", change))));
-                }
-            }
+    let hint = Style::default().fg(app.theme.text_secondary);
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                " {} changed here and in the {} since the last sync",
+                file.name,
+                prompt.target.label()
+            ),
+            Style::default()
+                .fg(app.theme.text_primary)
+                .add_modifier(Modifier::BOLD),
+        )),
+    ];
+    let path = match prompt.target {
+        crate::features::gist_sync::Target::Gist => {
+            std::path::Path::new(&app.workspace.path("collections")).join(&file.name)
         }
-
-        // Render left
-        // Render left
-        f.render_stateful_widget(
-            List::new(left_lines)
-                .block(Block::default().borders(Borders::RIGHT).title(" Base "))
-                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
-            diff_chunks[0],
-            &mut app.diff_list_state,
-        );
-        // Render right
-        f.render_stateful_widget(
-            List::new(right_lines)
-                .block(Block::default().title(" Target "))
-                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
-            diff_chunks[1],
-            &mut app.diff_list_state,
+        crate::features::gist_sync::Target::Storage => app.workspace.path(&file.name).into(),
+    };
+    let modified = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| {
+            crate::features::gist_sync::ago(
+                crate::features::history::now().saturating_sub(d.as_secs()),
+            )
+        })
+        .unwrap_or_else(|| "-".to_string());
+    let remote = match prompt.remote_updated_at.as_str() {
+        "" => String::new(),
+        at => format!(
+            "   Gist updated {}",
+            at.replace('T', " ").trim_end_matches('Z')
+        ),
+    };
+    lines.push(Line::from(Span::styled(
+        format!(" Local saved {}{}", modified, remote),
+        hint,
+    )));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!(
+            " l: Keep local   r: Keep {}   d: Diff   Esc: Cancel sync",
+            prompt.target.label()
+        ),
+        hint,
+    )));
+
+    if prompt.show_diff {
+        lines.push(Line::from(""));
+        let diff = crate::features::gist_sync::diff(
+            &file.name,
+            file.local.as_deref().unwrap_or_default(),
+            file.remote.as_deref().unwrap_or_default(),
         );
+        for line in diff {
+            let style = if line.starts_with("+++") || line.starts_with("---") {
+                hint
+            } else if line.starts_with('+') {
+                Style::default().fg(app.theme.success)
+            } else if line.starts_with('-') {
+                Style::default().fg(app.theme.error)
+            } else if line.starts_with("@@") {
+                Style::default().fg(app.theme.accent)
+            } else {
+                Style::default().fg(app.theme.text_primary)
+            };
+            lines.push(Line::from(Span::styled(format!(" {}", line), style)));
+        }
     }
+
+    let para = Paragraph::new(lines)
+        .scroll((if prompt.show_diff { prompt.scroll } else { 0 }, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" ⇅ Sync conflict (1 of {}) ", open))
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+    f.render_widget(para, area);
 }
 
-pub fn render_mock_mode(f: &mut Frame, app: &mut App) {
-    let area = f.area();
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Title
-            Constraint::Min(0),    // Main Content
-            Constraint::Length(3), // Help
-        ])
-        .split(area);
+/// Something in an imported bundle is already in the workspace, with other content
+fn render_bundle_prompt(f: &mut Frame, app: &App) {
+    let Some(prompt) = &app.bundle_prompt else {
+        return;
+    };
+    let Some(i) = prompt.current() else {
+        return;
+    };
+    let collision = &prompt.collisions[i];
+    let area = centered_rect(55, 25, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
 
-    // Title
-    let status_text = if app.mock_server_running {
-        Span::styled(
-            " RUNNING ",
-            Style::default().bg(Color::Green).fg(Color::Black),
-        )
+    let hint = Style::default().fg(app.theme.text_secondary);
+    let keys = if collision.kind == crate::features::bundle::Kind::Mock {
+        " o: Overwrite   s: Skip   Esc: Cancel import"
     } else {
-        Span::styled(
-            " STOPPED ",
-            Style::default().bg(Color::Red).fg(Color::Black),
-        )
+        " o: Overwrite   s: Skip   b: Keep both   Esc: Cancel import"
     };
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                " {} '{}' is already here, and differs",
+                collision.kind.label(),
+                collision.name
+            ),
+            Style::default()
+                .fg(app.theme.text_primary)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(keys, hint)),
+        Line::from(Span::styled(
+            " O / S / B: the same for every one left",
+            hint,
+        )),
+    ];
+    let para = Paragraph::new(lines)
+        .wrap(ratatui::widgets::Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    " Import {} ({} of {}) ",
+                    prompt.path,
+                    i + 1,
+                    prompt.collisions.len()
+                ))
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+    f.render_widget(para, area);
+}
 
-    let port_text = Span::raw(format!(" on port {} ", app.mock_server_port));
-
-    let title = Line::from(vec![
-        Span::styled(
-            " Mock Server Manager ",
-            Style::default().add_modifier(Modifier::BOLD),
-        ),
-        status_text,
-        port_text,
-    ]);
+fn render_passphrase_prompt(f: &mut Frame, app: &App) {
+    use crate::app::PassphrasePurpose;
 
-    let block = Block::default().borders(Borders::ALL).title(title);
-    f.render_widget(block, chunks[0]);
+    let Some(prompt) = &app.passphrase_prompt else {
+        return;
+    };
+    let (title, question) = match prompt.purpose {
+        PassphrasePurpose::Unlock => (" 🔒 Locked ", "Passphrase for history and cookies:"),
+        PassphrasePurpose::Enable => (" 🔒 Encrypt ", "New passphrase (8+ characters):"),
+        PassphrasePurpose::Confirm(_) => (" 🔒 Encrypt ", "Same passphrase again:"),
+        PassphrasePurpose::ExportBundle(_) => {
+            (" 🔒 Bundle ", "Passphrase to seal secrets (8+ characters):")
+        }
+        PassphrasePurpose::ConfirmExport(..) => (" 🔒 Bundle ", "Same passphrase again:"),
+        PassphrasePurpose::ImportBundle(_) => {
+            (" 🔒 Bundle ", "Passphrase for the bundle's secrets:")
+        }
+    };
+    let area = centered_rect(50, 20, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
 
-    // Content
-    let content_chunks = Layout::default()
-        .direction(Direction::Horizontal)
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {}", question),
+            Style::default()
+                .fg(app.theme.text_primary)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!(" {}█", "•".repeat(prompt.input.chars().count())),
+            Style::default().fg(app.theme.accent),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            " Enter: OK   Esc: Skip   Ctrl+u: Clear",
+            Style::default().fg(app.theme.text_secondary),
+        )),
+    ];
+    let para = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(app.theme.accent)),
+    );
+    f.render_widget(para, area);
+}
+
+fn render_recovery_prompt(f: &mut Frame, app: &App) {
+    let Some(snapshot) = &app.recovery_prompt else {
+        return;
+    };
+    let area = centered_rect(60, 40, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let dim = Style::default().fg(app.theme.text_secondary);
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                " PostDad didn't close properly last time. Restore {} saved {}?",
+                crate::features::recovery::describe(snapshot),
+                crate::features::gist_sync::ago(
+                    crate::features::history::now().saturating_sub(snapshot.saved_at)
+                )
+            ),
+            Style::default()
+                .fg(app.theme.text_primary)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    let shown = (area.height as usize).saturating_sub(7);
+    for draft in snapshot.tabs.iter().take(shown) {
+        let request = if draft.request.url.is_empty() {
+            "(no URL)".to_string()
+        } else {
+            format!("{} {}", draft.request.method, draft.request.url)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(" {:<16} ", draft.name.chars().take(16).collect::<String>()),
+                Style::default().fg(app.theme.accent),
+            ),
+            Span::styled(request, dim),
+        ]));
+    }
+    if snapshot.tabs.len() > shown {
+        lines.push(Line::from(Span::styled(
+            format!(" … {} more", snapshot.tabs.len() - shown),
+            dim,
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(" y: Restore   n: Discard", dim)));
+    let para = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" ♻ Recover Tabs ")
+            .border_style(Style::default().fg(app.theme.accent)),
+    );
+    f.render_widget(para, area);
+}
+
+fn render_response_compare(f: &mut Frame, app: &App) {
+    use crate::features::json_diff::{BodyDiff, ChangeKind};
+
+    let Some(cmp) = &app.response_compare else {
+        return;
+    };
+    let area = centered_rect(80, 80, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let fmt_status = |s: Option<u16>| s.map(|c| c.to_string()).unwrap_or("-".to_string());
+    let fmt_latency = |l: Option<u128>| l.map(|ms| format!("{}ms", ms)).unwrap_or("-".to_string());
+    let status_style = if cmp.old_status == cmp.new_status {
+        Style::default().fg(app.theme.text_secondary)
+    } else {
+        Style::default()
+            .fg(app.theme.error)
+            .add_modifier(Modifier::BOLD)
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                format!(
+                    " Status {} → {} ",
+                    fmt_status(cmp.old_status),
+                    fmt_status(cmp.new_status)
+                ),
+                status_style,
+            ),
+            Span::styled(
+                format!(
+                    "│ Latency {} → {} ",
+                    fmt_latency(cmp.old_latency),
+                    fmt_latency(cmp.new_latency)
+                ),
+                Style::default().fg(app.theme.text_secondary),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    let kind_style = |kind: ChangeKind| match kind {
+        ChangeKind::Added => Style::default().fg(app.theme.success),
+        ChangeKind::Removed => Style::default().fg(app.theme.error),
+        ChangeKind::Changed => Style::default().fg(Color::Yellow),
+    };
+
+    if cmp.body.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " Bodies are identical",
+            Style::default().fg(app.theme.success),
+        )));
+    } else {
+        match &cmp.body {
+            BodyDiff::Json(changes) => {
+                for change in changes.iter().skip(app.response_compare_scroll) {
+                    lines.push(Line::from(Span::styled(
+                        format!(" {}", change.describe()),
+                        kind_style(change.kind),
+                    )));
+                }
+            }
+            BodyDiff::Text(changed) => {
+                for (kind, text) in changed.iter().skip(app.response_compare_scroll) {
+                    let sign = if *kind == ChangeKind::Added { "+" } else { "-" };
+                    lines.push(Line::from(Span::styled(
+                        format!(" {} {}", sign, text),
+                        kind_style(*kind),
+                    )));
+                }
+            }
+        }
+    }
+
+    let kind = match cmp.body {
+        crate::features::json_diff::BodyDiff::Json(_) => "structural",
+        crate::features::json_diff::BodyDiff::Text(_) => "line",
+    };
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Compare with latest ({} changes, {} diff) ",
+                cmp.body.len(),
+                kind
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " j/k: Scroll | r: Re-send again | Esc: Close ",
+            Style::default().fg(app.theme.text_secondary),
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(app.theme.highlight))
+        .style(
+            Style::default()
+                .bg(app.theme.background)
+                .fg(app.theme.text_primary),
+        );
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn notification_style(
+    app: &App,
+    level: crate::domain::notification::NotificationLevel,
+) -> (Color, &'static str) {
+    use crate::domain::notification::NotificationLevel;
+    match level {
+        NotificationLevel::Info => (app.theme.highlight, "🔔"),
+        NotificationLevel::Success => (app.theme.success, "✔"),
+        NotificationLevel::Error => (app.theme.error, "✖"),
+    }
+}
+
+/// Stacked toasts in the top-right corner, newest on top. They don't take focus.
+fn render_notifications(f: &mut Frame, app: &App) {
+    let screen = f.area();
+    let width = screen.width.min(50);
+    if width < 10 {
+        return;
+    }
+    let x = screen.x + screen.width - width;
+    let mut y = screen.y + 1;
+
+    for notification in app.notifications.visible() {
+        let inner_width = (width - 2) as usize;
+        let lines = notification
+            .message
+            .chars()
+            .count()
+            .div_ceil(inner_width)
+            .clamp(1, 4);
+        let height = lines as u16 + 2;
+        if y + height > screen.y + screen.height {
+            break;
+        }
+
+        let area = ratatui::layout::Rect::new(x, y, width, height);
+        let (color, icon) = notification_style(app, notification.level);
+        f.render_widget(ratatui::widgets::Clear, area);
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" {} {} ", icon, notification.level.label()),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(color))
+            .style(
+                Style::default()
+                    .bg(app.theme.background)
+                    .fg(app.theme.text_primary),
+            );
+        let para = Paragraph::new(notification.message.as_str())
+            .block(block)
+            .wrap(Wrap { trim: true });
+        f.render_widget(para, area);
+
+        y += height;
+    }
+}
+
+fn render_notification_history(f: &mut Frame, app: &App) {
+    let area = centered_rect(70, 60, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let items: Vec<ListItem> = app
+        .notifications
+        .history
+        .iter()
+        .skip(app.notification_history_scroll)
+        .map(|n| {
+            let (color, icon) = notification_style(app, n.level);
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:>8} ", n.age()),
+                    Style::default().fg(app.theme.text_secondary),
+                ),
+                Span::styled(format!("{} ", icon), Style::default().fg(color)),
+                Span::raw(n.message.clone()),
+            ]))
+        })
+        .collect();
+
+    let title = format!(
+        " Notification History ({}) ",
+        app.notifications.history.len()
+    );
+    let list = List::new(items).block(
+        Block::default()
+            .title(Span::styled(
+                title,
+                Style::default().add_modifier(Modifier::BOLD),
+            ))
+            .title_bottom(Span::styled(
+                " j/k: Scroll | x: Clear | Esc: Close ",
+                Style::default().fg(app.theme.text_secondary),
+            ))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(app.theme.highlight))
+            .style(
+                Style::default()
+                    .bg(app.theme.background)
+                    .fg(app.theme.text_primary),
+            ),
+    );
+    f.render_widget(list, area);
+}
+
+/// Every remappable action with the keys it's on now, marking the ones keymap.toml moved
+fn render_keymap(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 70, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let lines: Vec<Line> = app
+        .keymap
+        .keys
+        .iter()
+        .map(|(def, _)| {
+            let keys = app.keymap.keys_for(def.id);
+            let changed = keys != def.default;
+            Line::from(vec![
+                Span::styled(
+                    format!("  {:<16}", keys),
+                    Style::default().fg(if changed {
+                        app.theme.accent
+                    } else {
+                        app.theme.highlight
+                    }),
+                ),
+                Span::raw(format!("{:<30}", def.desc)),
+                Span::styled(
+                    if changed {
+                        format!("{} (was {})", def.id, def.default)
+                    } else {
+                        def.id.to_string()
+                    },
+                    Style::default().fg(app.theme.text_secondary),
+                ),
+            ])
+        })
+        .collect();
+
+    let title = format!(
+        " Keymap: {} profile ({}) ",
+        app.keymap.profile,
+        crate::domain::keymap::KEYMAP_FILE
+    );
+    let para = Paragraph::new(lines).scroll((app.keymap_scroll, 0)).block(
+        Block::default()
+            .title(Span::styled(
+                title,
+                Style::default().add_modifier(Modifier::BOLD),
+            ))
+            .title_bottom(Span::styled(
+                " j/k: Scroll | r: Reload keymap.toml | Esc: Close ",
+                Style::default().fg(app.theme.text_secondary),
+            ))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(app.theme.highlight))
+            .style(
+                Style::default()
+                    .bg(app.theme.background)
+                    .fg(app.theme.text_primary),
+            ),
+    );
+    f.render_widget(para, area);
+}
+
+/// Every theme on the left, the selected one's colours and highlighting on the right.
+/// The screen behind already shows it: moving the selection applies it.
+fn render_theme_picker(f: &mut Frame, app: &App) {
+    let Some(picker) = &app.theme_picker else {
+        return;
+    };
+    let area = centered_rect(70, 60, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+    let theme = &app.theme;
+    let block = Block::default()
+        .title(Span::styled(
+            " Themes ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            format!(
+                " j/k: Preview | Enter: Use | Esc: Back to the old one | {}/*.toml ",
+                crate::domain::theme::THEMES_DIR
+            ),
+            Style::default().fg(theme.text_secondary),
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border_focus))
+        .style(Style::default().bg(theme.background).fg(theme.text_primary));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [list_area, preview_area] =
+        Layout::horizontal([Constraint::Length(26), Constraint::Min(0)]).areas(inner);
+
+    let items: Vec<ListItem> = app
+        .themes
+        .iter()
+        .map(|t| match &t.file {
+            Some(_) => ListItem::new(format!("{} (file)", t.name)),
+            None => ListItem::new(t.name.clone()),
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(picker.selected));
+    f.render_stateful_widget(
+        List::new(items)
+            .highlight_style(
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::REVERSED),
+            )
+            .highlight_symbol("> "),
+        list_area,
+        &mut state,
+    );
+
+    let swatch = |label: &str, color: Color| {
+        Line::from(vec![
+            Span::styled("    ", Style::default().bg(color)),
+            Span::raw(format!(" {}", label)),
+        ])
+    };
+    let mut lines = vec![
+        swatch("background", theme.background),
+        swatch("border", theme.border),
+        swatch("border_focus", theme.border_focus),
+        swatch("text_primary", theme.text_primary),
+        swatch("text_secondary", theme.text_secondary),
+        swatch("highlight", theme.highlight),
+        swatch("success", theme.success),
+        swatch("error", theme.error),
+        swatch("accent", theme.accent),
+        Line::raw(""),
+    ];
+    let sample = "{\n  \"id\": 42,\n  \"name\": \"PostDad\",\n  \"active\": true,\n  \"tags\": [\"api\", null]\n}";
+    lines.extend(crate::ui::syntax::highlight(sample, "json", &theme.syntax));
+    f.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::LEFT)
+                .border_style(Style::default().fg(theme.border)),
+        ),
+        preview_area,
+    );
+}
+
+/// Doctor results grouped by area, each problem followed by its fix
+fn render_doctor(f: &mut Frame, app: &App) {
+    let Some(checks) = &app.doctor_report else {
+        return;
+    };
+    let (warnings, failures) = crate::features::doctor::counts(checks);
+    let title = format!(" Doctor: {} failed, {} warnings ", failures, warnings);
+    render_checklist(f, app, checks, title, app.doctor_scroll);
+}
+
+fn render_audit(f: &mut Frame, app: &App) {
+    let Some(report) = &app.audit_report else {
+        return;
+    };
+    let (warnings, failures) = crate::features::doctor::counts(&report.checks);
+    let title = format!(
+        " Security Audit: {} | grade {} | {} failed, {} warnings ",
+        report.url, report.grade, failures, warnings
+    );
+    render_checklist(f, app, &report.checks, title, app.audit_scroll);
+}
+
+/// Requests added, removed and changed between two versions of a collection
+fn render_collection_diff(f: &mut Frame, app: &App) {
+    use crate::features::collection_diff::Tone;
+    let Some(report) = &app.collection_diff else {
+        return;
+    };
+    let area = centered_rect(80, 75, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let mut lines: Vec<Line> = report
+        .lines()
+        .into_iter()
+        .map(|line| {
+            let style = match line.tone {
+                Tone::Added => Style::default().fg(app.theme.success),
+                Tone::Removed => Style::default().fg(app.theme.error),
+                Tone::Changed => Style::default().fg(app.theme.accent),
+                Tone::Plain => Style::default().fg(app.theme.text_secondary),
+            };
+            Line::from(Span::styled(line.text, style))
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No differences",
+            Style::default().fg(app.theme.text_secondary),
+        )));
+    }
+
+    let title = format!(" {} → {}: {} ", report.old, report.new, report.summary());
+    let diff = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((app.collection_diff_scroll, 0))
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    title,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+                .title_bottom(Span::styled(
+                    " j/k: Scroll | Esc: Close ",
+                    Style::default().fg(app.theme.text_secondary),
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(app.theme.highlight))
+                .style(
+                    Style::default()
+                        .bg(app.theme.background)
+                        .fg(app.theme.text_primary),
+                ),
+        );
+    f.render_widget(diff, area);
+}
+
+/// Every variable the active request sees, the scope its value comes from and
+/// what it hides, with the ones the request uses marked and undefined ones listed
+fn render_variables_inspector(f: &mut Frame, app: &App) {
+    let Some(inspection) = &app.variables_inspector else {
+        return;
+    };
+    let area = centered_rect(75, 70, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let dim = Style::default().fg(app.theme.text_secondary);
+    let order: Vec<String> = inspection
+        .layers
+        .iter()
+        .rev()
+        .map(|(scope, source)| format!("{} ({})", scope.label(), source))
+        .collect();
+    let mut lines = vec![
+        Line::from(Span::styled(format!("Lookup: {}", order.join(" > ")), dim)),
+        Line::from(""),
+    ];
+    for name in &inspection.missing {
+        lines.push(Line::from(vec![
+            Span::styled("• ", Style::default().fg(app.theme.error)),
+            Span::styled(
+                format!("{{{{{}}}}}", name),
+                Style::default()
+                    .fg(app.theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  undefined in every scope", dim),
+        ]));
+    }
+    for var in &inspection.variables {
+        let used = inspection.used.contains(&var.name);
+        lines.push(Line::from(vec![
+            Span::styled(
+                if used { "• " } else { "  " },
+                Style::default().fg(app.theme.accent),
+            ),
+            Span::styled(
+                var.name.clone(),
+                Style::default()
+                    .fg(app.theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" = "),
+            Span::styled(var.value.clone(), Style::default().fg(app.theme.success)),
+            Span::styled(format!("  [{}]", var.scope.label()), dim),
+        ]));
+        for (scope, value) in &var.shadowed {
+            lines.push(Line::from(Span::styled(
+                format!("      hides {}: {}", scope.label(), value),
+                dim,
+            )));
+        }
+    }
+    if inspection.variables.is_empty() && inspection.missing.is_empty() {
+        lines.push(Line::from(Span::styled("No variables in any scope", dim)));
+    }
+
+    let title = format!(
+        " Variables: {} defined, {} used, {} undefined ",
+        inspection.variables.len(),
+        inspection.used.len(),
+        inspection.missing.len()
+    );
+    let view = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((app.variables_inspector_scroll, 0))
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    title,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+                .title_bottom(Span::styled(
+                    " • used by the request | j/k: Scroll | Esc: Close ",
+                    dim,
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(app.theme.highlight))
+                .style(
+                    Style::default()
+                        .bg(app.theme.background)
+                        .fg(app.theme.text_primary),
+                ),
+        );
+    f.render_widget(view, area);
+}
+
+/// The active environment's variables, each with the saved requests that use it,
+/// and the references no scope defines on top in the error color
+fn render_env_usage(f: &mut Frame, app: &App) {
+    use crate::features::env_usage::{Reference, Row};
+    let Some(view) = &app.env_usage else {
+        return;
+    };
+    let report = &view.report;
+    let area = centered_rect(75, 70, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let dim = Style::default().fg(app.theme.text_secondary);
+    let reference_item = |r: &Reference| {
+        ListItem::new(Line::from(vec![
+            Span::raw("    "),
+            Span::styled(r.path(), Style::default().fg(app.theme.text_primary)),
+            Span::styled(format!("  {}", r.places.join(", ")), dim),
+        ]))
+    };
+    let rows = report.rows();
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| match *row {
+            Row::Undefined(i) => {
+                let usage = &report.undefined[i];
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{{{{{}}}}}", usage.name),
+                        Style::default()
+                            .fg(app.theme.error)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled("  undefined", Style::default().fg(app.theme.error)),
+                ]))
+            }
+            Row::UndefinedReference(i, r) => reference_item(&report.undefined[i].references[r]),
+            Row::Variable(i) => {
+                let usage = &report.variables[i];
+                let uses = match usage.references.len() {
+                    0 => "  unused".to_string(),
+                    1 => "  1 request".to_string(),
+                    n => format!("  {} requests", n),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        usage.name.clone(),
+                        Style::default()
+                            .fg(app.theme.accent)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(" = "),
+                    Span::styled(
+                        usage.value.clone().unwrap_or_default(),
+                        Style::default().fg(app.theme.success),
+                    ),
+                    Span::styled(uses, dim),
+                ]))
+            }
+            Row::Reference(i, r) => reference_item(&report.variables[i].references[r]),
+        })
+        .collect();
+
+    let title = format!(
+        " Environment '{}': {} variables, {} unused, {} undefined ",
+        report.environment,
+        report.variables.len(),
+        report
+            .variables
+            .iter()
+            .filter(|u| u.references.is_empty())
+            .count(),
+        report.undefined.len()
+    );
+    let list = if items.is_empty() {
+        List::new(vec![ListItem::new(Span::styled(
+            "No variables in the environment, and no references",
+            dim,
+        ))])
+    } else {
+        List::new(items)
+    };
+    let list = list
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    title,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+                .title_bottom(Span::styled(
+                    " j/k: Move | Enter: Open request | Esc: Close ",
+                    dim,
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(app.theme.highlight))
+                .style(
+                    Style::default()
+                        .bg(app.theme.background)
+                        .fg(app.theme.text_primary),
+                ),
+        )
+        .highlight_style(Style::default().bg(app.theme.highlight).fg(Color::Black));
+    let mut state = ListState::default();
+    if !rows.is_empty() {
+        state.select(Some(view.selected.min(rows.len() - 1)));
+    }
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// A group of duplicate requests: the one kept on the left, a duplicate on the
+/// right, lines only one side has highlighted
+fn render_dedupe(f: &mut Frame, app: &App) {
+    let Some(view) = &app.dedupe else {
+        return;
+    };
+    let (Some(group), Some((keep, other)), Some((left, right))) =
+        (view.current(), view.pair(), app.dedupe_pair())
+    else {
+        return;
+    };
+    let area = centered_rect(90, 80, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+    let dim = Style::default().fg(app.theme.text_secondary);
+
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " Duplicates {}/{}: {} ",
+                view.group + 1,
+                view.groups.len(),
+                group.key
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " m: Merge into kept | d: Delete duplicate | s: Swap | Tab: Next duplicate | n/N: Group | j/k: Scroll | Esc ",
+            dim,
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(app.theme.highlight))
+        .style(
+            Style::default()
+                .bg(app.theme.background)
+                .fg(app.theme.text_primary),
+        );
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+
+    let (left_lines, right_lines) = crate::features::dedupe::side_by_side(&left, &right);
+    let side = |lines: Vec<(String, bool)>, title: String| {
+        let lines: Vec<Line> = lines
+            .into_iter()
+            .map(|(text, differs)| {
+                if differs {
+                    Line::from(Span::styled(text, Style::default().fg(app.theme.accent)))
+                } else {
+                    Line::from(text)
+                }
+            })
+            .collect();
+        Paragraph::new(lines)
+            .scroll((view.scroll, 0))
+            .block(Block::default().borders(Borders::ALL).title(title))
+    };
+    f.render_widget(
+        side(left_lines, format!(" Keep: {} ", keep.path())),
+        columns[0],
+    );
+    f.render_widget(
+        side(
+            right_lines,
+            format!(
+                " Duplicate {}/{}: {} ",
+                view.other,
+                group.members.len() - 1,
+                other.path()
+            ),
+        ),
+        columns[1],
+    );
+}
+
+fn render_log_view(f: &mut Frame, app: &App) {
+    let Some(view) = &app.log_view else {
+        return;
+    };
+    let area = centered_rect(90, 80, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+    let mut scope = Vec::new();
+    if let Some(id) = &view.request_id {
+        scope.push(format!("request {}", id));
+    }
+    if view.problems_only {
+        scope.push("warnings and errors".to_string());
+    }
+    if !view.filter.is_empty() {
+        scope.push(format!("\"{}\"", view.filter));
+    }
+    let title = if scope.is_empty() {
+        " Logs ".to_string()
+    } else {
+        format!(" Logs: {} ", scope.join(", "))
+    };
+    let block = Block::default()
+        .title(Span::styled(
+            title,
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .title(
+            Line::from(Span::styled(
+                format!(" {} ", app.workspace.path(crate::features::logging::FILE)),
+                Style::default().fg(app.theme.text_secondary),
+            ))
+            .right_aligned(),
+        )
+        .title_bottom(Span::styled(
+            " j/k: Scroll | g/G: Oldest/Newest | e: Problems only | r: This tab's request | Esc: Close ",
+            Style::default().fg(app.theme.text_secondary),
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(app.theme.highlight))
+        .style(
+            Style::default()
+                .bg(app.theme.background)
+                .fg(app.theme.text_primary),
+        );
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let dim = Style::default().fg(app.theme.text_secondary);
+    let lines: Vec<Line> = match app.log_buffer.lock() {
+        Ok(records) => {
+            let visible = view.visible(&records);
+            let height = inner.height as usize;
+            // Scrolled up from the newest, never past the oldest page
+            let end = visible
+                .len()
+                .saturating_sub(view.scroll.min(visible.len().saturating_sub(height)));
+            let start = end.saturating_sub(height);
+            visible[start..end]
+                .iter()
+                .map(|record| {
+                    let level = match record.level.as_str() {
+                        "ERROR" => Style::default().fg(app.theme.error),
+                        "WARN" => Style::default().fg(Color::Yellow),
+                        "INFO" => Style::default().fg(app.theme.success),
+                        _ => dim,
+                    };
+                    let mut spans = vec![
+                        Span::styled(format!("{} ", record.clock()), dim),
+                        Span::styled(format!("{:<5} ", record.level), level),
+                        Span::styled(
+                            format!("[{}] ", record.request_id.as_deref().unwrap_or("--------")),
+                            Style::default().fg(app.theme.accent),
+                        ),
+                        Span::styled(
+                            format!(
+                                "{} ",
+                                record.target.rsplit("::").next().unwrap_or(&record.target)
+                            ),
+                            dim,
+                        ),
+                        Span::raw(record.message.clone()),
+                    ];
+                    for (key, value) in &record.fields {
+                        spans.push(Span::styled(format!("  {}=", key), dim));
+                        spans.push(Span::raw(value.clone()));
+                    }
+                    Line::from(spans)
+                })
+                .collect()
+        }
+        Err(_) => Vec::new(),
+    };
+    if lines.is_empty() {
+        f.render_widget(
+            Paragraph::new(Span::styled("Nothing logged yet", dim)),
+            inner,
+        );
+    } else {
+        f.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+fn render_latency_chart(f: &mut Frame, app: &App) {
+    use crate::features::latency::{self, Stats};
+    let Some(key) = &app.latency_chart else {
+        return;
+    };
+    let samples = app.latency_trends.samples(key);
+    let Some(all) = Stats::of(samples) else {
+        return;
+    };
+    let area = centered_rect(85, 75, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" Latency: {} ", key),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " Esc: Close ",
+            Style::default().fg(app.theme.text_secondary),
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(app.theme.highlight))
+        .style(
+            Style::default()
+                .bg(app.theme.background)
+                .fg(app.theme.text_primary),
+        );
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(30), Constraint::Length(30)])
+        .split(inner);
+
+    // One point per send, oldest on the left, so long pauses don't squash the sessions
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (i as f64, s.ms as f64))
+        .collect();
+    let last_x = (samples.len() - 1).max(1) as f64;
+    let p50 = [(0.0, all.p50 as f64), (last_x, all.p50 as f64)];
+    let p95 = [(0.0, all.p95 as f64), (last_x, all.p95 as f64)];
+    let top = (all.max as f64 * 1.1).max(1.0);
+    let dim = Style::default().fg(app.theme.text_secondary);
+    let now = crate::features::history::now();
+    let when = |at: u64| crate::features::gist_sync::ago(now.saturating_sub(at));
+    let chart = Chart::new(vec![
+        Dataset::default()
+            .name("ms")
+            .marker(ratatui::symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(app.theme.accent))
+            .data(&points),
+        Dataset::default()
+            .name(format!("p50 {}ms", all.p50))
+            .marker(ratatui::symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(app.theme.success))
+            .data(&p50),
+        Dataset::default()
+            .name(format!("p95 {}ms", all.p95))
+            .marker(ratatui::symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(app.theme.error))
+            .data(&p95),
+    ])
+    .x_axis(
+        Axis::default()
+            .style(dim)
+            .bounds([0.0, last_x])
+            .labels([when(samples[0].at), when(samples[samples.len() - 1].at)]),
+    )
+    .y_axis(
+        Axis::default()
+            .style(dim)
+            .bounds([0.0, top])
+            .labels(["0".to_string(), format!("{}ms", top as u64)]),
+    );
+    f.render_widget(chart, chunks[0]);
+
+    let row = |label: &str, stats: &Stats| {
+        Line::from(vec![
+            Span::styled(format!("{:<8}", label), dim),
+            Span::raw(format!(
+                "{:>3} sends  p50 {}ms  p95 {}ms",
+                stats.count, stats.p50, stats.p95
+            )),
+        ])
+    };
+    let mut lines = vec![Line::from(Span::styled(
+        "All sends",
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    for (label, ms) in [
+        ("min", all.min),
+        ("p50", all.p50),
+        ("p90", all.p90),
+        ("p95", all.p95),
+        ("p99", all.p99),
+        ("max", all.max),
+    ] {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<8}", label), dim),
+            Span::raw(format!("{}ms", ms)),
+        ]));
+    }
+    lines.push(Line::from(""));
+    let sessions = latency::sessions(samples);
+    lines.push(Line::from(Span::styled(
+        format!("Sessions: {}", sessions.len()),
+        Style::default().add_modifier(Modifier::BOLD),
+    )));
+    let mut recent = sessions.iter().rev();
+    if let Some(last) = recent.next().and_then(|s| Stats::of(s)) {
+        lines.push(row("last", &last));
+    }
+    if let Some(before) = recent.next().and_then(|s| Stats::of(s)) {
+        lines.push(row("previous", &before));
+    }
+    if let Some(change) = latency::session_change(samples) {
+        let color = if change > 10.0 {
+            app.theme.error
+        } else if change < -10.0 {
+            app.theme.success
+        } else {
+            app.theme.text_primary
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<8}", "median"), dim),
+            Span::styled(
+                format!("{:+.1}% vs previous", change),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    f.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::LEFT).border_style(dim)),
+        chunks[1],
+    );
+}
+
+fn render_dashboard(f: &mut Frame, app: &App) {
+    const TILE_WIDTH: u16 = 30;
+    const TILE_HEIGHT: u16 = 6;
+    let Some(dashboard) = &app.dashboard else {
+        return;
+    };
+    let area = centered_rect(95, 90, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let dim = Style::default().fg(app.theme.text_secondary);
+    let (up, down, pending) = dashboard.summary();
+    let state = if dashboard.is_running() {
+        format!("every {}s", app.dashboard_settings.interval_secs)
+    } else {
+        "paused".to_string()
+    };
+    let title = format!(
+        " Health: {} up, {} down, {} pending ({}) ",
+        up, down, pending, state
+    );
+    let block = Block::default()
+        .title(Span::styled(
+            title,
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " h/j/k/l: Move | Enter: Open request | r: Check now | s: Pause/resume | Esc: Close ",
+            dim,
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(if down > 0 {
+            app.theme.error
+        } else {
+            app.theme.highlight
+        }))
+        .style(Style::default().bg(app.theme.background));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let columns = (inner.width / TILE_WIDTH).max(1) as usize;
+    let visible_rows = (inner.height / TILE_HEIGHT).max(1) as usize;
+    // Scroll by whole rows to keep the selected tile on screen
+    let first_row = (dashboard.selected / columns).saturating_sub(visible_rows - 1);
+    let now = crate::features::history::now();
+    for (i, tile) in dashboard.tiles.iter().enumerate().skip(first_row * columns) {
+        let (row, col) = (i / columns - first_row, i % columns);
+        if row >= visible_rows {
+            break;
+        }
+        let rect = ratatui::layout::Rect {
+            x: inner.x + col as u16 * TILE_WIDTH,
+            y: inner.y + row as u16 * TILE_HEIGHT,
+            width: TILE_WIDTH.min(inner.width),
+            height: TILE_HEIGHT.min(inner.height),
+        };
+        let color = match tile.healthy() {
+            Some(true) => app.theme.success,
+            Some(false) => app.theme.error,
+            None => app.theme.text_secondary,
+        };
+        let selected = i == dashboard.selected;
+        let status = match &tile.last {
+            Some(result) => match (result.status, &result.error) {
+                (Some(code), _) => format!("{} {}", if result.passed { "●" } else { "✗" }, code),
+                (None, Some(e)) => format!("✗ {}", e),
+                (None, None) => "✗".to_string(),
+            },
+            None => "… checking".to_string(),
+        };
+        let latency = tile
+            .last
+            .as_ref()
+            .and_then(|r| r.latency_ms)
+            .map_or_else(String::new, |ms| format!("  {}ms", ms));
+        let ago = tile
+            .checked_at
+            .map_or_else(String::new, |t| format!("{}s ago", now.saturating_sub(t)));
+        let strip: String = tile
+            .recent
+            .iter()
+            .map(|ok| if *ok { '▮' } else { '▯' })
+            .collect();
+        let lines = vec![
+            Line::from(vec![
+                Span::styled(
+                    status,
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(latency),
+            ]),
+            Line::from(Span::styled(format!("{} {}", ago, tile.collection), dim)),
+            Line::from(vec![
+                Span::styled(strip, Style::default().fg(color)),
+                Span::styled(
+                    format!(" {}/{}", tile.checks - tile.failures, tile.checks),
+                    dim,
+                ),
+            ]),
+        ];
+        let border = if selected {
+            Style::default()
+                .fg(app.theme.highlight)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(color)
+        };
+        let widget = Paragraph::new(lines).block(
+            Block::default()
+                .title(format!(" {} {} ", tile.method, tile.name))
+                .borders(Borders::ALL)
+                .border_type(if selected {
+                    BorderType::Thick
+                } else {
+                    BorderType::Rounded
+                })
+                .border_style(border),
+        );
+        f.render_widget(widget, rect);
+    }
+}
+
+fn render_proto_view(f: &mut Frame, app: &App) {
+    use crate::features::protos::Section;
+    let Some(view) = &app.proto_view else {
+        return;
+    };
+    let area = centered_rect(70, 70, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let dim = Style::default().fg(app.theme.text_secondary);
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = Vec::new();
+    for (section, title, empty) in [
+        (
+            Section::Roots,
+            "Proto roots",
+            "none, gRPC requests use server reflection",
+        ),
+        (Section::ImportPaths, "Import paths", "none"),
+    ] {
+        let current = section == view.section;
+        lines.push(Line::from(Span::styled(
+            title,
+            if current {
+                bold.fg(app.theme.highlight)
+            } else {
+                bold
+            },
+        )));
+        let dirs = app.proto_registry.list(section);
+        for (i, dir) in dirs.iter().enumerate() {
+            let selected = current && i == view.selected && view.adding.is_none();
+            let exists = app.workspace.dir().join(dir).is_dir();
+            lines.push(Line::from(vec![
+                Span::raw(if selected { "> " } else { "  " }),
+                Span::styled(
+                    dir.clone(),
+                    if selected {
+                        Style::default().fg(app.theme.highlight)
+                    } else {
+                        Style::default().fg(app.theme.accent)
+                    },
+                ),
+                Span::styled(
+                    if exists { "" } else { "  (missing)" },
+                    Style::default().fg(app.theme.error),
+                ),
+            ]));
+        }
+        if current && let Some(input) = &view.adding {
+            lines.push(Line::from(vec![
+                Span::raw("+ "),
+                Span::styled(
+                    format!("{}█", input),
+                    Style::default().fg(app.theme.success),
+                ),
+            ]));
+        } else if dirs.is_empty() {
+            lines.push(Line::from(Span::styled(format!("  {}", empty), dim)));
+        }
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        format!("Loaded files ({})", view.files.len()),
+        bold,
+    )));
+    for file in &view.files {
+        lines.push(Line::from(Span::styled(format!("  {}", file), dim)));
+    }
+
+    let hint = if view.adding.is_some() {
+        " Directory, relative to the workspace | Enter: Add | Esc: Cancel "
+    } else {
+        " Tab: Section | a: Add | d: Remove | r: Rescan | Esc: Close "
+    };
+    let widget = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(Span::styled(" Protos ", bold))
+            .title_bottom(Span::styled(hint, dim))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(app.theme.highlight))
+            .style(
+                Style::default()
+                    .bg(app.theme.background)
+                    .fg(app.theme.text_primary),
+            ),
+    );
+    f.render_widget(widget, area);
+}
+
+/// Checks grouped by area, each with its fix underneath, for the doctor and the audit
+fn render_checklist(
+    f: &mut Frame,
+    app: &App,
+    checks: &[crate::features::doctor::Check],
+    title: String,
+    scroll: u16,
+) {
+    use crate::features::doctor::Level;
+    let area = centered_rect(75, 70, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let mut lines = Vec::new();
+    let mut current_area = "";
+    for check in checks {
+        if check.area != current_area {
+            current_area = check.area;
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled(
+                current_area,
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+        }
+        let (icon, color) = match check.level {
+            Level::Ok => ("✓", app.theme.success),
+            Level::Warn => ("!", app.theme.accent),
+            Level::Fail => ("✗", app.theme.error),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} ", icon), Style::default().fg(color)),
+            Span::raw(format!("{} ", check.name)),
+            Span::styled(
+                check.detail.clone(),
+                Style::default().fg(app.theme.text_secondary),
+            ),
+        ]));
+        if let Some(fix) = &check.fix {
+            lines.push(Line::from(Span::styled(
+                format!("      → {}", fix),
+                Style::default().fg(app.theme.highlight),
+            )));
+        }
+    }
+
+    let report = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0))
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    title,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+                .title_bottom(Span::styled(
+                    " j/k: Scroll | r: Re-run | Esc: Close ",
+                    Style::default().fg(app.theme.text_secondary),
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(app.theme.highlight))
+                .style(
+                    Style::default()
+                        .bg(app.theme.background)
+                        .fg(app.theme.text_primary),
+                ),
+        );
+    f.render_widget(report, area);
+}
+
+/// The active tab's sends, newest first, with what changed since the one before
+fn render_timeline(f: &mut Frame, app: &mut App) {
+    let area = centered_rect(75, 60, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let timeline = &app.active_tab().timeline;
+    let items: Vec<ListItem> = timeline
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, entry)| {
+            let outcome = match (&entry.error, entry.status) {
+                (Some(_), _) => Span::styled("ERR", Style::default().fg(app.theme.error)),
+                (None, Some(status)) => Span::styled(
+                    format!("{}", status),
+                    Style::default().fg(if status < 400 {
+                        app.theme.success
+                    } else {
+                        app.theme.error
+                    }),
+                ),
+                (None, None) => Span::styled("...", Style::default().fg(app.theme.text_secondary)),
+            };
+            let latency = entry
+                .latency
+                .map(|ms| format!(" {:>5}ms", ms))
+                .unwrap_or_else(|| "        ".to_string());
+            let changes = match i.checked_sub(1).map(|prev| &timeline[prev]) {
+                Some(prev) => match entry.request.changes_from(&prev.request) {
+                    c if c.is_empty() => "unchanged".to_string(),
+                    c => format!("changed {}", c.join(", ")),
+                },
+                None => "first send".to_string(),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:>8} ", entry.age()),
+                    Style::default().fg(app.theme.text_secondary),
+                ),
+                outcome,
+                Span::styled(latency, Style::default().fg(app.theme.text_secondary)),
+                Span::styled(
+                    format!(" {:<7}", entry.request.method),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(entry.request.url.clone()),
+                Span::styled(
+                    format!("  ({})", changes),
+                    Style::default().fg(app.theme.text_secondary),
+                ),
+            ]))
+        })
+        .collect();
+
+    let title = format!(
+        " Request Timeline: {} ({}) ",
+        app.active_tab().name,
+        items.len()
+    );
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    title,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+                .title_bottom(Span::styled(
+                    " j/k: Move | Enter: Restore Tab | Esc: Close ",
+                    Style::default().fg(app.theme.text_secondary),
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(app.theme.highlight))
+                .style(
+                    Style::default()
+                        .bg(app.theme.background)
+                        .fg(app.theme.text_primary),
+                ),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.highlight)
+                .fg(app.theme.background),
+        );
+    f.render_stateful_widget(list, area, &mut app.timeline_state);
+}
+
+/// The raw request: request line in bold, header names highlighted, then the body
+fn render_wire_view(f: &mut Frame, app: &App) {
+    let Some(view) = app.wire_view.as_ref() else {
+        return;
+    };
+    let area = centered_rect(80, 75, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let (head, body) = view
+        .text
+        .split_once("\r\n\r\n")
+        .unwrap_or((view.text.as_str(), ""));
+    let mut lines = Vec::new();
+    if let Some(note) = &view.note {
+        lines.push(Line::from(Span::styled(
+            format!("ⓘ {}", note),
+            Style::default().fg(app.theme.accent),
+        )));
+        lines.push(Line::from(""));
+    }
+    for (i, line) in head.split("\r\n").enumerate() {
+        if i == 0 {
+            lines.push(Line::from(Span::styled(
+                line.to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+        } else if let Some((name, value)) = line.split_once(':') {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{}:", name),
+                    Style::default().fg(app.theme.highlight),
+                ),
+                Span::raw(value.to_string()),
+            ]));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.extend(body.lines().map(|line| Line::from(line.to_string())));
+
+    let title = if view.sent {
+        " Last Sent Request ".to_string()
+    } else {
+        format!(" Next Send: {} ", view.url)
+    };
+    let para = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    title,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+                .title_bottom(Span::styled(
+                    if view.sent {
+                        " j/k: Scroll | Tab: Preview Next Send | c: Copy | Esc: Close "
+                    } else {
+                        " j/k: Scroll | Tab: Last Sent | c: Copy | Esc: Close "
+                    },
+                    Style::default().fg(app.theme.text_secondary),
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(app.theme.highlight))
+                .style(
+                    Style::default()
+                        .bg(app.theme.background)
+                        .fg(app.theme.text_primary),
+                ),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((view.scroll, 0));
+    f.render_widget(para, area);
+}
+
+fn render_fuzz_progress(f: &mut Frame, app: &App) {
+    let Some((done, total, flagged)) = app.fuzz_progress else {
+        return;
+    };
+    let area = f.area();
+    let width = 44;
+    let rect = ratatui::layout::Rect {
+        x: area.width.saturating_sub(width + 2),
+        y: area.height.saturating_sub(6),
+        width: width.min(area.width),
+        height: 4.min(area.height),
+    };
+    f.render_widget(ratatui::widgets::Clear, rect);
+    let text = vec![
+        Line::from(format!("{}/{} sent at {}/s", done, total, app.fuzz_rate)),
+        Line::from(Span::styled(
+            format!("{} flagged | :fuzz stop", flagged),
+            Style::default().fg(if flagged > 0 {
+                app.theme.error
+            } else {
+                app.theme.text_secondary
+            }),
+        )),
+    ];
+    let p = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Fuzzing ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.accent)),
+        )
+        .alignment(Alignment::Center);
+    f.render_widget(p, rect);
+}
+
+fn render_fuzz_report(f: &mut Frame, app: &mut App) {
+    let Some(report) = app.fuzz_report.as_ref() else {
+        return;
+    };
+    let area = centered_rect(85, 75, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" Fuzz Report: {} {} ", report.method, report.url),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " j/k: Move | Enter: Open Variant in New Tab | Esc: Close ",
+            Style::default().fg(app.theme.text_secondary),
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(app.theme.highlight))
+        .style(
+            Style::default()
+                .bg(app.theme.background)
+                .fg(app.theme.text_primary),
+        );
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(3),
+            Constraint::Length(9),
+        ])
+        .split(inner);
+
+    let status_text = |status: Option<u16>| status.map_or("ERR".to_string(), |s| s.to_string());
+    let status_color = |status: Option<u16>| match status {
+        Some(s) if s < 400 => app.theme.success,
+        Some(s) if s < 500 => app.theme.accent,
+        _ => app.theme.error,
+    };
+
+    let summary = vec![
+        Line::from(vec![
+            Span::styled("Original: ", Style::default().fg(app.theme.text_secondary)),
+            Span::styled(
+                status_text(report.baseline.status),
+                Style::default().fg(status_color(report.baseline.status)),
+            ),
+            Span::raw(format!(
+                " {}ms {}",
+                report.baseline.latency_ms, report.baseline.shape
+            )),
+        ]),
+        Line::from(Span::styled(
+            format!(
+                "{} variant(s) at {}/s, {} flagged{}",
+                report.outcomes.len(),
+                report.rate,
+                report.flagged(),
+                if report.stopped {
+                    " (stopped early)"
+                } else {
+                    ""
+                }
+            ),
+            Style::default().fg(app.theme.text_secondary),
+        )),
+    ];
+    f.render_widget(Paragraph::new(summary), chunks[0]);
+
+    let items: Vec<ListItem> = report
+        .groups
+        .iter()
+        .map(|group| {
+            let flags: Vec<&str> = group.flags.iter().map(|flag| flag.label()).collect();
+            let mut spans = vec![
+                Span::styled(
+                    if flags.is_empty() { "  " } else { "⚠ " },
+                    Style::default().fg(app.theme.error),
+                ),
+                Span::styled(
+                    format!("{:<4}", status_text(group.status)),
+                    Style::default().fg(status_color(group.status)),
+                ),
+                Span::styled(
+                    format!(" {:>4}× ", group.cases.len()),
+                    Style::default().fg(app.theme.text_secondary),
+                ),
+                Span::raw(group.shape.clone()),
+            ];
+            if !flags.is_empty() {
+                spans.push(Span::styled(
+                    format!("  [{}]", flags.join(", ")),
+                    Style::default().fg(app.theme.error),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .title(" Responses by status and shape "),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.highlight)
+                .fg(app.theme.background)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let details: Vec<Line> = app
+        .fuzz_state
+        .selected()
+        .and_then(|i| report.groups.get(i))
+        .map(|group| {
+            group
+                .cases
+                .iter()
+                .map(|&i| {
+                    let (case, outcome) = (&report.cases[i], &report.outcomes[i]);
+                    let flags: Vec<&str> = outcome.flags.iter().map(|flag| flag.label()).collect();
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{:<10}", case.category.label()),
+                            Style::default().fg(app.theme.text_secondary),
+                        ),
+                        Span::raw(case.label()),
+                        Span::styled(
+                            format!("  {}ms", outcome.latency_ms),
+                            Style::default().fg(app.theme.text_secondary),
+                        ),
+                        Span::styled(
+                            if flags.is_empty() {
+                                String::new()
+                            } else {
+                                format!("  [{}]", flags.join(", "))
+                            },
+                            Style::default().fg(app.theme.error),
+                        ),
+                    ])
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let details =
+        Paragraph::new(details).block(Block::default().borders(Borders::TOP).title(" Variants "));
+
+    f.render_stateful_widget(list, chunks[1], &mut app.fuzz_state);
+    f.render_widget(details, chunks[2]);
+}
+
+/// The NDJSON response one record per line, a broken line in red
+fn render_ndjson(f: &mut Frame, app: &mut App) {
+    let area = centered_rect(80, 70, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+    let rows = app.ndjson_rows();
+    let open = app.active_tab().ndjson_record;
+    let width = area.width.saturating_sub(12) as usize;
+    let lines = app
+        .active_tab()
+        .response_ndjson
+        .as_deref()
+        .unwrap_or_default();
+
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|&i| {
+            let line = &lines[i];
+            let text: String = line.text.chars().take(width).collect();
+            let style = if line.value.is_err() {
+                Style::default().fg(app.theme.error)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(
+                        "{}{:>5} ",
+                        if Some(line.number) == open {
+                            "●"
+                        } else {
+                            " "
+                        },
+                        line.number
+                    ),
+                    Style::default().fg(app.theme.text_secondary),
+                ),
+                Span::styled(text, style),
+            ]))
+        })
+        .collect();
+    let total = lines.len();
+
+    let title = if app.ndjson_filter.is_empty() && !app.ndjson_filtering {
+        format!(" NDJSON: {} records ", total)
+    } else {
+        format!(
+            " NDJSON: {} of {} match /{}{} ",
+            rows.len(),
+            total,
+            app.ndjson_filter,
+            if app.ndjson_filtering { "│" } else { "" }
+        )
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    title,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+                .title_bottom(Span::styled(
+                    " j/k: Move | Enter: Open in Tree | /: Filter | Esc: Close ",
+                    Style::default().fg(app.theme.text_secondary),
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(app.theme.highlight))
+                .style(
+                    Style::default()
+                        .bg(app.theme.background)
+                        .fg(app.theme.text_primary),
+                ),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.highlight)
+                .fg(app.theme.background)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_stateful_widget(list, area, &mut app.ndjson_state);
+}
+
+/// Status, latency and size per tab of the last `:send all`, filled in as they arrive
+fn render_batch(f: &mut Frame, app: &mut App) {
+    let area = centered_rect(80, 60, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+    let pending = app
+        .batch_rows
+        .iter()
+        .filter(|row| row.result.is_none())
+        .count();
+    let title = if pending > 0 {
+        format!(" Send Tabs: {} waiting ", pending)
+    } else {
+        format!(
+            " Send Tabs: {} ",
+            crate::features::batch::summary(&app.batch_rows)
+        )
+    };
+    let name_width = app
+        .batch_rows
+        .iter()
+        .map(|row| row.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(24);
+
+    let items: Vec<ListItem> = app
+        .batch_rows
+        .iter()
+        .map(|row| {
+            let (status, color, figures) = match &row.result {
+                None => ("…".to_string(), app.theme.text_secondary, String::new()),
+                Some(result) => match result.status {
+                    Some(status) => (
+                        status.to_string(),
+                        if status < 400 {
+                            app.theme.success
+                        } else if status < 500 {
+                            app.theme.accent
+                        } else {
+                            app.theme.error
+                        },
+                        format!("{:>6}ms {:>9}", result.latency_ms, format_size(result.size)),
+                    ),
+                    None => (
+                        "ERR".to_string(),
+                        app.theme.error,
+                        result.error.clone().unwrap_or_default(),
+                    ),
+                },
+            };
+            let name: String = row.name.chars().take(name_width).collect();
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:>2} ", row.tab + 1),
+                    Style::default().fg(app.theme.text_secondary),
+                ),
+                Span::raw(format!("{:<width$} ", name, width = name_width)),
+                Span::styled(format!("{:<4}", status), Style::default().fg(color)),
+                Span::raw(format!(" {}  ", figures)),
+                Span::styled(
+                    format!("{} {}", row.method, row.url),
+                    Style::default().fg(app.theme.text_secondary),
+                ),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    title,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+                .title_bottom(Span::styled(
+                    " j/k: Move | Enter: Go to Tab | Esc: Close ",
+                    Style::default().fg(app.theme.text_secondary),
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(app.theme.highlight))
+                .style(
+                    Style::default()
+                        .bg(app.theme.background)
+                        .fg(app.theme.text_primary),
+                ),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.highlight)
+                .fg(app.theme.background)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_stateful_widget(list, area, &mut app.batch_state);
+}
+
+fn centered_rect(
+    percent_x: u16,
+    percent_y: u16,
+    r: ratatui::layout::Rect,
+) -> ratatui::layout::Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
+
+pub fn render_diff_view(f: &mut Frame, app: &mut App) {
+    if let (Some(base_idx), Some(target_idx)) = (app.diff_base_index, app.diff_target_index)
+        && let (Some(base), Some(target)) = (
+            app.request_history.get(base_idx),
+            app.request_history.get(target_idx),
+        )
+    {
+        let area = f.area();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+
+        // Title
+        let mode = if app.diff_json_mode {
+            "structural"
+        } else {
+            "lines"
+        };
+        let title = format!(
+            " Diff: Base ({}) vs Target ({}) [{}] - 'j' toggle JSON diff, 'Esc' to close ",
+            base.url, target.url, mode
+        );
+        let block = Block::default().borders(Borders::ALL).title(title);
+        f.render_widget(block, area);
+
+        // Inner chunks for diff content
+        let content_area = chunks[1];
+
+        if app.diff_json_mode {
+            render_structural_diff(f, app, content_area);
+            return;
+        }
+        let diff_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(content_area);
+
+        let old_text = base.body.as_deref().unwrap_or("");
+        let new_text = target.body.as_deref().unwrap_or("");
+
+        let diff = TextDiff::from_lines(old_text, new_text);
+
+        let mut left_lines = Vec::new();
+        let mut right_lines = Vec::new();
+
+        for change in diff.iter_all_changes() {
+            match change.tag() {
+                ChangeTag::Delete => {
+                    left_lines.push(ListItem::new(Line::from(Span::styled(
+                        format!("- {}", change),
+                        Style::default().bg(Color::Red).fg(Color::Black),
+                    ))));
+                    right_lines.push(ListItem::new(Line::from("")));
+                }
+                ChangeTag::Insert => {
+                    left_lines.push(ListItem::new(Line::from("")));
+                    right_lines.push(ListItem::new(Line::from(Span::styled(
+                        format!("+ {}", change),
+                        Style::default().bg(Color::Green).fg(Color::Black),
+                    ))));
+                }
+                ChangeTag::Equal => {
+                    left_lines.push(ListItem::new(Line::from(format!("  {}", change))));
+                    right_lines.push(ListItem::new(Line::from(format!("  {}", change))));
+                }
+            }
+        }
+
+        // Render left
+        // Render left
+        f.render_stateful_widget(
+            List::new(left_lines)
+                .block(Block::default().borders(Borders::RIGHT).title(" Base "))
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            diff_chunks[0],
+            &mut app.diff_list_state,
+        );
+        // Render right
+        f.render_stateful_widget(
+            List::new(right_lines)
+                .block(Block::default().title(" Target "))
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            diff_chunks[1],
+            &mut app.diff_list_state,
+        );
+    }
+}
+
+/// Only real changes: headers first, then the body (parsed JSON, or changed lines as a fallback)
+fn render_structural_diff(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    use crate::features::json_diff::{BodyDiff, ChangeKind, JsonChange};
+
+    let Some((header_changes, body_diff)) = app.structural_history_diff() else {
+        return;
+    };
+
+    let kind_style = |kind: ChangeKind| match kind {
+        ChangeKind::Added => Style::default().fg(Color::Green),
+        ChangeKind::Removed => Style::default().fg(Color::Red),
+        ChangeKind::Changed => Style::default().fg(Color::Yellow),
+    };
+    let section = |title: String| {
+        ListItem::new(Line::from(Span::styled(
+            title,
+            Style::default()
+                .fg(app.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )))
+    };
+    let change_item = |c: &JsonChange| {
+        ListItem::new(Line::from(Span::styled(
+            format!("  {}", c.describe()),
+            kind_style(c.kind),
+        )))
+    };
+    let unchanged = || {
+        ListItem::new(Line::from(Span::styled(
+            "  (no changes)",
+            Style::default().fg(app.theme.text_secondary),
+        )))
+    };
+
+    let mut items = vec![section(format!(" Headers ({})", header_changes.len()))];
+    if header_changes.is_empty() {
+        items.push(unchanged());
+    }
+    items.extend(header_changes.iter().map(change_item));
+
+    items.push(ListItem::new(Line::from("")));
+    match &body_diff {
+        BodyDiff::Json(changes) => {
+            items.push(section(format!(" Body ({})", changes.len())));
+            items.extend(changes.iter().map(change_item));
+        }
+        BodyDiff::Text(lines) => {
+            items.push(section(format!(
+                " Body ({}, not JSON on both sides - changed lines)",
+                lines.len()
+            )));
+            items.extend(lines.iter().map(|(kind, text)| {
+                let sign = if *kind == ChangeKind::Added { "+" } else { "-" };
+                ListItem::new(Line::from(Span::styled(
+                    format!("  {} {}", sign, text),
+                    kind_style(*kind),
+                )))
+            }));
+        }
+    }
+    if body_diff.is_empty() {
+        items.push(unchanged());
+    }
+
+    f.render_stateful_widget(
+        List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        area,
+        &mut app.diff_list_state,
+    );
+}
+
+pub fn render_mock_mode(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Main Content
+            Constraint::Length(3), // Help
+        ])
+        .split(area);
+
+    // Title
+    let status_text = if app.mock_server_running {
+        Span::styled(
+            " RUNNING ",
+            Style::default().bg(Color::Green).fg(Color::Black),
+        )
+    } else {
+        Span::styled(
+            " STOPPED ",
+            Style::default().bg(Color::Red).fg(Color::Black),
+        )
+    };
+
+    let port_text = Span::raw(format!(
+        " {} ",
+        app.mock_settings.base_url(app.mock_server_port)
+    ));
+    let cors_text = if app.mock_settings.cors_on() {
+        Span::styled(" CORS ", Style::default().fg(Color::Green))
+    } else {
+        Span::styled(" no CORS ", Style::default().fg(Color::DarkGray))
+    };
+
+    let title = Line::from(vec![
+        Span::styled(
+            " Mock Server Manager ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        status_text,
+        port_text,
+        cors_text,
+    ]);
+
+    let block = Block::default().borders(Borders::ALL).title(title);
+    f.render_widget(block, chunks[0]);
+
+    // Content
+    let content_chunks = Layout::default()
+        .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
         .split(chunks[1]);
 
@@ -2254,17 +5256,150 @@ pub fn render_mock_mode(f: &mut Frame, app: &mut App) {
                 .wrap(Wrap { trim: false });
             f.render_widget(p, content_chunks[1]);
         }
-    } else {
+    } else {
+        f.render_widget(
+            Paragraph::new("Select a route to view details").alignment(Alignment::Center),
+            content_chunks[1],
+        );
+    }
+
+    // Help
+    let help = Paragraph::new(
+        " 'a': Add | 'e'/Enter: Edit | 'd': Delete | 's': Toggle Server | 'c': CORS | 't': HTTPS | 'Esc': Exit (saved to mocks.hcl) ",
+    )
+    .block(Block::default().borders(Borders::TOP));
+    f.render_widget(help, chunks[2]);
+
+    if app.mock_editor.is_some() {
+        render_mock_editor(f, app);
+    }
+}
+
+fn render_mock_editor(f: &mut Frame, app: &App) {
+    use crate::app::MockEditorField;
+
+    let Some(editor) = &app.mock_editor else {
+        return;
+    };
+    let area = centered_rect(70, 80, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let title = if editor.index.is_some() {
+        " Edit Mock Route "
+    } else {
+        " New Mock Route "
+    };
+    let block = Block::default()
+        .title(title)
+        .title_bottom(" Tab: Next Field | ←/→: Method | Ctrl+S: Save | Esc: Cancel ")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(app.theme.accent))
+        .style(Style::default().bg(app.theme.background));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(6),
+            Constraint::Min(3),
+        ])
+        .split(inner);
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(14),
+            Constraint::Min(20),
+            Constraint::Length(10),
+        ])
+        .split(rows[0]);
+    let chaos = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(40),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+        ])
+        .split(rows[1]);
+
+    let fields = [
+        (
+            top[0],
+            MockEditorField::Method,
+            " Method ",
+            format!("◀ {} ▶", editor.method),
+        ),
+        (
+            top[1],
+            MockEditorField::Path,
+            " Path (/users/:id) ",
+            editor.path.clone(),
+        ),
+        (
+            top[2],
+            MockEditorField::Status,
+            " Status ",
+            editor.status.clone(),
+        ),
+        (
+            chaos[0],
+            MockEditorField::Delay,
+            " Delay ms (150 or 100-400) ",
+            editor.delay.clone(),
+        ),
+        (
+            chaos[1],
+            MockEditorField::ErrorRate,
+            " 500 Error % ",
+            editor.error_rate.clone(),
+        ),
+        (
+            chaos[2],
+            MockEditorField::DropRate,
+            " Drop Conn. % ",
+            editor.drop_rate.clone(),
+        ),
+        (
+            rows[2],
+            MockEditorField::Headers,
+            " Headers (Key: Value per line) ",
+            editor.headers.clone(),
+        ),
+        (
+            rows[3],
+            MockEditorField::Body,
+            " Body ({{params.id}}, {{query.page}}, {{body.name}}, {{$uuid}}, ...) ",
+            editor.body.clone(),
+        ),
+    ];
+
+    for (chunk, field, label, value) in fields {
+        let focused = editor.field == field;
+        let border = if focused {
+            Style::default().fg(app.theme.highlight)
+        } else {
+            Style::default().fg(app.theme.border)
+        };
+        let mut text = value;
+        if focused && field != MockEditorField::Method {
+            text.push('█');
+        }
         f.render_widget(
-            Paragraph::new("Select a route to view details").alignment(Alignment::Center),
-            content_chunks[1],
+            Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .style(Style::default().fg(app.theme.text_primary))
+                .block(
+                    Block::default()
+                        .title(label)
+                        .borders(Borders::ALL)
+                        .border_style(border),
+                ),
+            chunk,
         );
     }
-
-    // Help
-    let help = Paragraph::new(" 'a': Add | 'd': Delete | 's': Toggle Server | 'Esc': Exit ")
-        .block(Block::default().borders(Borders::TOP));
-    f.render_widget(help, chunks[2]);
 }
 
 fn render_schema_modal(f: &mut Frame, app: &mut App) {
@@ -2337,6 +5472,79 @@ fn render_grpc_services_modal(f: &mut Frame, app: &mut App) {
     f.render_stateful_widget(list, inner_area, &mut app.active_tab_mut().form_list_state);
 }
 
+/// The gRPC section over the response: status, its error details, then the
+/// headers and trailers the server sent
+fn grpc_metadata_lines<'a>(
+    app: &App,
+    metadata: &'a crate::net::grpc::GrpcMetadata,
+) -> Vec<Line<'a>> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(app.theme.text_secondary);
+    let mut lines = Vec::new();
+    match &metadata.status {
+        Some(status) => {
+            let color = if status.code == 0 {
+                app.theme.success
+            } else {
+                app.theme.error
+            };
+            let mut spans = vec![
+                Span::styled("Status: ", bold),
+                Span::styled(
+                    format!("{} ({})", status.name(), status.code),
+                    Style::default().fg(color),
+                ),
+            ];
+            if !status.message.is_empty() {
+                spans.push(Span::raw(format!(" {}", status.message)));
+            }
+            lines.push(Line::from(spans));
+            for detail in &status.details {
+                let kind = detail["@type"].as_str().unwrap_or("detail");
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", kind.rsplit('/').next().unwrap_or(kind)),
+                    Style::default().fg(app.theme.highlight),
+                )));
+                let mut fields = detail.clone();
+                if let Some(object) = fields.as_object_mut() {
+                    object.remove("@type");
+                }
+                let pretty = serde_json::to_string_pretty(&fields).unwrap_or_default();
+                for line in pretty.lines() {
+                    lines.push(Line::from(Span::raw(format!("    {}", line))));
+                }
+            }
+        }
+        None => lines.push(Line::from(vec![
+            Span::styled("Status: ", bold),
+            Span::styled("none, the call didn't complete", dim),
+        ])),
+    }
+    for (title, metadata) in [
+        ("Headers", &metadata.headers),
+        ("Trailers", &metadata.trailers),
+    ] {
+        if metadata.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{}: ", title), bold),
+                Span::styled("(empty)", dim),
+            ]));
+            continue;
+        }
+        lines.push(Line::from(Span::styled(format!("{}:", title), bold)));
+        for (key, value) in metadata {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}: ", key),
+                    Style::default().fg(app.theme.accent),
+                ),
+                Span::raw(value.as_str()),
+            ]));
+        }
+    }
+    lines
+}
+
 fn render_grpc_description_modal(f: &mut Frame, app: &mut App) {
     let area = centered_rect(70, 80, f.area());
     f.render_widget(ratatui::widgets::Clear, area);
@@ -2346,19 +5554,61 @@ fn render_grpc_description_modal(f: &mut Frame, app: &mut App) {
 
     let block = Block::default()
         .title(title)
-        .title_bottom(" Esc: Close | b: Back to Services ")
+        .title_bottom(" j/k: Method | Enter: Use + scaffold payload | b: Back | Esc: Close ")
         .borders(Borders::ALL)
         .border_type(BorderType::Double)
         .style(Style::default().fg(app.theme.accent));
 
     f.render_widget(block.clone(), area);
 
-    let inner_area = block.inner(area);
+    let mut inner_area = block.inner(area);
+
+    let tab = app.active_tab();
+    if !tab.grpc_methods.is_empty() {
+        let rows = (tab.grpc_methods.len() as u16 + 1).min(inner_area.height / 2);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(rows), Constraint::Min(0)])
+            .split(inner_area);
+        let lines: Vec<Line> = tab
+            .grpc_methods
+            .iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let stream = |on: bool| if on { "stream " } else { "" };
+                let text = format!(
+                    "{} {}({}{}) -> {}{}",
+                    if i == tab.grpc_method_selected {
+                        ">"
+                    } else {
+                        " "
+                    },
+                    m.name,
+                    stream(m.client_streaming),
+                    m.input.trim_start_matches('.'),
+                    stream(m.server_streaming),
+                    m.output.trim_start_matches('.'),
+                );
+                let style = if i == tab.grpc_method_selected {
+                    Style::default()
+                        .fg(app.theme.highlight)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(app.theme.text_primary)
+                };
+                Line::from(Span::styled(text, style))
+            })
+            .collect();
+        // Keep the selection on screen in long services
+        let offset = (tab.grpc_method_selected as u16 + 1).saturating_sub(rows);
+        f.render_widget(Paragraph::new(lines).scroll((offset, 0)), chunks[0]);
+        inner_area = chunks[1];
+    }
 
     let desc = app.active_tab().grpc_service_description.clone();
 
     // Syntax highlight the proto description
-    let highlighted = crate::ui::syntax::highlight(&desc, "protobuf");
+    let highlighted = crate::ui::syntax::highlight(&desc, "protobuf", &app.theme.syntax);
 
     let paragraph = Paragraph::new(highlighted)
         .wrap(Wrap { trim: false })
@@ -2371,8 +5621,43 @@ fn render_status_bar(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let tab = app.active_tab();
 
     if tab.input_mode == InputMode::Command {
-        let text = format!(":{}█", app.command_input); // Add cursor block
-        let p = Paragraph::new(text).style(
+        let mut spans = vec![Span::raw(format!(
+            ":{}",
+            with_cursor(&app.command_input, app.input_cursor, '█')
+        ))];
+        let hint = Style::default().fg(app.theme.text_secondary);
+        if app.command_completions.len() > 1 {
+            // The word each candidate would put in, the current one highlighted
+            for (i, line) in app.command_completions.iter().enumerate() {
+                let word = line.rsplit(' ').next().unwrap_or(line);
+                let word = if line.starts_with("open ") || line.starts_with("run ") {
+                    line.split_once(' ').map_or(line.as_str(), |(_, rest)| rest)
+                } else {
+                    word
+                };
+                let style = if i == app.command_completion_index && *line == app.command_input {
+                    Style::default().fg(app.theme.highlight)
+                } else {
+                    hint
+                };
+                spans.push(Span::styled(format!("  {}", word), style));
+            }
+        } else if let Some(spec) = app
+            .command_input
+            .split_whitespace()
+            .next()
+            .and_then(|name| {
+                crate::features::command::COMMANDS
+                    .iter()
+                    .find(|c| c.name == name)
+            })
+        {
+            spans.push(Span::styled(
+                format!("   {} - {}", spec.usage, spec.desc),
+                hint,
+            ));
+        }
+        let p = Paragraph::new(Line::from(spans)).style(
             Style::default()
                 .bg(app.theme.background)
                 .fg(app.theme.text_primary),
@@ -2386,6 +5671,7 @@ fn render_status_bar(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         InputMode::Normal => "NORMAL",
         InputMode::Editing => "EDIT:URL",
         InputMode::Search => "SEARCH",
+        InputMode::FindInResponse => "FIND",
         InputMode::EditingAuth
         | InputMode::EditingBasicAuthUser
         | InputMode::EditingBasicAuthPass => "EDIT:AUTH",
@@ -2395,76 +5681,376 @@ fn render_status_bar(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         _ => "EDIT",
     };
 
-    let mode_style = match tab.input_mode {
-        InputMode::Normal => Style::default()
-            .bg(Color::Blue)
-            .fg(Color::White)
-            .add_modifier(Modifier::BOLD),
-        _ => Style::default()
-            .bg(Color::Yellow)
-            .fg(Color::Black)
-            .add_modifier(Modifier::BOLD),
-    };
+    let mode_style = match tab.input_mode {
+        InputMode::Normal => Style::default()
+            .bg(Color::Blue)
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+        _ => Style::default()
+            .bg(Color::Yellow)
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD),
+    };
+
+    // Body type indicator
+    let body_type = match tab.body_type {
+        crate::app::BodyType::Raw => "RAW",
+        crate::app::BodyType::FormData => "FORM",
+        crate::app::BodyType::UrlEncoded => "URLENC",
+        crate::app::BodyType::GraphQL => "GQL",
+        crate::app::BodyType::Grpc => "gRPC",
+    };
+
+    // Connection status for WebSocket
+    let ws_status = if tab.app_mode == crate::app::AppMode::WebSocket {
+        if tab.ws_connected {
+            " 🟢 WS "
+        } else {
+            " 🔴 WS "
+        }
+    } else {
+        ""
+    };
+
+    // Build status line
+    let mut left_side = vec![
+        Span::styled(format!(" {} ", mode), mode_style),
+        Span::raw(" "),
+        Span::styled(
+            format!(" {} ", tab.method),
+            Style::default().bg(Color::DarkGray).fg(Color::White),
+        ),
+        Span::raw(" "),
+        Span::styled(
+            format!(" {} ", body_type),
+            Style::default().fg(app.theme.accent),
+        ),
+        Span::raw(ws_status),
+    ];
+
+    if !app.workspace.is_default() {
+        left_side.push(Span::raw(" "));
+        left_side.push(Span::styled(
+            format!(" ▣ {} ", app.workspace.label()),
+            Style::default().fg(app.theme.accent),
+        ));
+    }
+
+    if let Some(label) = app.sync_status.label(crate::features::history::now()) {
+        let style = match app.sync_status {
+            crate::features::gist_sync::SyncStatus::Conflicts(_) => {
+                Style::default().fg(Color::Yellow)
+            }
+            crate::features::gist_sync::SyncStatus::Failed => Style::default().fg(app.theme.error),
+            _ => Style::default().fg(app.theme.text_secondary),
+        };
+        left_side.push(Span::raw(" "));
+        left_side.push(Span::styled(format!(" {} ", label), style));
+    }
+
+    // Active profile, coloured by what it would do with the request in this tab
+    if let Some(profile) = app.current_profile() {
+        let style = match app.current_guard() {
+            crate::domain::profile::Guard::Allow => Style::default().fg(app.theme.success),
+            crate::domain::profile::Guard::Confirm => Style::default().fg(Color::Yellow),
+            crate::domain::profile::Guard::Block => Style::default()
+                .fg(app.theme.error)
+                .add_modifier(Modifier::BOLD),
+        };
+        left_side.push(Span::raw(" "));
+        left_side.push(Span::styled(format!(" ⛨ {} ", profile.name), style));
+    }
+
+    // Keybind hints on right side
+    let hints = " ?:Help │ e:URL │ Tab:Sections │ Enter:Send │ q:Quit ";
+
+    let right_side = Span::styled(hints, Style::default().fg(app.theme.text_secondary));
+
+    // Calculate padding
+    let left_len: usize = left_side.iter().map(|s| s.content.len()).sum();
+    let right_len = hints.len();
+    let padding = area.width.saturating_sub((left_len + right_len) as u16);
+
+    let mut spans = left_side;
+    spans.push(Span::raw(" ".repeat(padding as usize)));
+    spans.push(right_side);
+
+    let status_line =
+        Paragraph::new(Line::from(spans)).style(Style::default().bg(app.theme.background));
+
+    f.render_widget(status_line, area);
+}
+
+fn render_command_palette(f: &mut Frame, app: &mut App) {
+    use crate::app::get_available_commands;
+
+    let area = centered_rect(60, 50, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let search_bar = Paragraph::new(with_cursor(&app.command_query, app.input_cursor, '│')).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Command Palette ")
+            .border_style(Style::default().fg(app.theme.highlight)),
+    );
+    f.render_widget(search_bar, chunks[0]);
+
+    // Filter commands
+    let commands = get_available_commands();
+    let filter = app.command_query.to_lowercase();
+    let filtered: Vec<&crate::app::CommandAction> = commands
+        .iter()
+        .filter(|c| {
+            c.name.to_lowercase().contains(&filter) || c.desc.to_lowercase().contains(&filter)
+        })
+        .collect();
+
+    let items: Vec<ListItem> = filtered
+        .iter()
+        .map(|c| {
+            let content = Line::from(vec![
+                Span::styled(
+                    format!("{:<20}", c.name),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(c.desc),
+            ]);
+            ListItem::new(content)
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL))
+        .highlight_style(Style::default().bg(app.theme.highlight).fg(Color::Black))
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    if app.command_index >= filtered.len() && !filtered.is_empty() {
+        app.command_index = filtered.len() - 1;
+    }
+    state.select(Some(app.command_index));
+
+    f.render_stateful_widget(list, chunks[1], &mut state);
+}
+
+fn render_codegen_picker(f: &mut Frame, app: &mut App) {
+    let area = centered_rect(50, 50, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let search_bar = Paragraph::new(with_cursor(&app.codegen_query, app.input_cursor, '│')).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Generate Code ")
+            .border_style(Style::default().fg(app.theme.highlight)),
+    );
+    f.render_widget(search_bar, chunks[0]);
+
+    let filtered = app.filtered_codegen_targets();
+    let count = filtered.len();
+    let items: Vec<ListItem> = filtered
+        .iter()
+        .map(|t| {
+            let source = if t.builtin {
+                String::new()
+            } else {
+                format!("{}/{}.tmpl", crate::features::codegen::CODEGEN_DIR, t.id)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<24}", t.name),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(source, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title_bottom(" Enter: copy | Esc: close "),
+        )
+        .highlight_style(Style::default().bg(app.theme.highlight).fg(Color::Black))
+        .highlight_symbol("> ");
+
+    if app.codegen_index >= count && count > 0 {
+        app.codegen_index = count - 1;
+    }
+    let mut state = ListState::default();
+    state.select(Some(app.codegen_index));
+
+    f.render_stateful_widget(list, chunks[1], &mut state);
+}
+
+fn render_snippet_picker(f: &mut Frame, app: &mut App) {
+    let area = centered_rect(70, 70, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Percentage(40),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    let title = format!(" Insert Snippet into the {} ", app.snippet_target.title());
+    let search_bar = Paragraph::new(with_cursor(&app.snippet_query, app.input_cursor, '│')).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(app.theme.highlight)),
+    );
+    f.render_widget(search_bar, chunks[0]);
+
+    let filtered = app.filtered_snippets();
+    let count = filtered.len();
+    let items: Vec<ListItem> = filtered
+        .iter()
+        .map(|s| {
+            let kind = s.kind.map(|k| k.name()).unwrap_or("");
+            let source = if s.builtin {
+                "built-in".to_string()
+            } else {
+                format!("{}/{}.rhai", crate::features::snippets::SNIPPETS_DIR, s.id)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<28}", s.name),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{:<6}", kind), Style::default().fg(Color::Cyan)),
+                Span::styled(source, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+    let code = filtered
+        .get(app.snippet_index.min(count.saturating_sub(1)))
+        .map(|s| s.code.clone())
+        .unwrap_or_default();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title_bottom(" Enter: insert | Tab: pre/test script | Esc: close "),
+        )
+        .highlight_style(Style::default().bg(app.theme.highlight).fg(Color::Black))
+        .highlight_symbol("> ");
 
-    // Body type indicator
-    let body_type = match tab.body_type {
-        crate::app::BodyType::Raw => "RAW",
-        crate::app::BodyType::FormData => "FORM",
-        crate::app::BodyType::GraphQL => "GQL",
-        crate::app::BodyType::Grpc => "gRPC",
-    };
+    if app.snippet_index >= count && count > 0 {
+        app.snippet_index = count - 1;
+    }
+    let mut state = ListState::default();
+    state.select(Some(app.snippet_index));
 
-    // Connection status for WebSocket
-    let ws_status = if tab.app_mode == crate::app::AppMode::WebSocket {
-        if tab.ws_connected {
-            " 🟢 WS "
-        } else {
-            " 🔴 WS "
-        }
-    } else {
-        ""
-    };
+    f.render_stateful_widget(list, chunks[1], &mut state);
 
-    // Build status line
-    let left_side = vec![
-        Span::styled(format!(" {} ", mode), mode_style),
-        Span::raw(" "),
-        Span::styled(
-            format!(" {} ", tab.method),
-            Style::default().bg(Color::DarkGray).fg(Color::White),
-        ),
-        Span::raw(" "),
-        Span::styled(
-            format!(" {} ", body_type),
-            Style::default().fg(app.theme.accent),
-        ),
-        Span::raw(ws_status),
-    ];
+    let preview = Paragraph::new(code)
+        .style(Style::default().fg(Color::Gray))
+        .block(Block::default().borders(Borders::ALL).title(" Preview "));
+    f.render_widget(preview, chunks[2]);
+}
 
-    // Keybind hints on right side
-    let hints = " ?:Help │ e:URL │ Tab:Sections │ Enter:Send │ q:Quit ";
+fn render_finder(f: &mut Frame, app: &mut App) {
+    let area = centered_rect(70, 60, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
 
-    let right_side = Span::styled(hints, Style::default().fg(app.theme.text_secondary));
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
 
-    // Calculate padding
-    let left_len: usize = left_side.iter().map(|s| s.content.len()).sum();
-    let right_len = hints.len();
-    let padding = area.width.saturating_sub((left_len + right_len) as u16);
+    let search_bar = Paragraph::new(with_cursor(&app.finder_query, app.input_cursor, '│')).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Find ")
+            .border_style(Style::default().fg(app.theme.highlight)),
+    );
+    f.render_widget(search_bar, chunks[0]);
 
-    let mut spans = left_side;
-    spans.push(Span::raw(" ".repeat(padding as usize)));
-    spans.push(right_side);
+    let matches = app.finder_matches();
+    let count = matches.len();
+    let label_width = (chunks[1].width as usize / 2).max(20);
+    let items: Vec<ListItem> = matches
+        .iter()
+        .map(|m| {
+            let mut spans = vec![Span::styled(
+                format!("{:<5}", m.item.kind.tag()),
+                Style::default().fg(Color::DarkGray),
+            )];
+            // Matched characters stand out in the label
+            let mut label = String::new();
+            for (i, c) in m.item.label.chars().enumerate() {
+                if i >= label_width {
+                    break;
+                }
+                if m.positions.contains(&i) {
+                    if !label.is_empty() {
+                        spans.push(Span::styled(
+                            std::mem::take(&mut label),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ));
+                    }
+                    spans.push(Span::styled(
+                        c.to_string(),
+                        Style::default()
+                            .fg(app.theme.highlight)
+                            .add_modifier(Modifier::BOLD),
+                    ));
+                } else {
+                    label.push(c);
+                }
+            }
+            let shown = m.item.label.chars().count().min(label_width);
+            label.push_str(&" ".repeat(label_width - shown + 1));
+            spans.push(Span::styled(
+                label,
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::styled(
+                m.item.detail.clone(),
+                Style::default().fg(Color::DarkGray),
+            ));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
 
-    let status_line =
-        Paragraph::new(Line::from(spans)).style(Style::default().bg(app.theme.background));
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {} matches ", count))
+                .title_bottom(" Enter: open | ↑↓: move | Esc: close "),
+        )
+        .highlight_style(Style::default().bg(app.theme.highlight).fg(Color::Black))
+        .highlight_symbol("> ");
 
-    f.render_widget(status_line, area);
+    if app.finder_index >= count && count > 0 {
+        app.finder_index = count - 1;
+    }
+    let mut state = ListState::default();
+    state.select(Some(app.finder_index));
+
+    f.render_stateful_widget(list, chunks[1], &mut state);
 }
 
-fn render_command_palette(f: &mut Frame, app: &mut App) {
-    use crate::app::get_available_commands;
+fn render_history_view(f: &mut Frame, app: &mut App) {
+    use crate::features::history::Row;
 
-    let area = centered_rect(60, 50, f.area());
+    let area = centered_rect(80, 70, f.area());
     f.render_widget(ratatui::widgets::Clear, area);
 
     let chunks = Layout::default()
@@ -2472,54 +6058,103 @@ fn render_command_palette(f: &mut Frame, app: &mut App) {
         .constraints([Constraint::Length(3), Constraint::Min(0)])
         .split(area);
 
-    let search_bar = Paragraph::new(app.command_query.clone()).block(
+    let search_bar = Paragraph::new(with_cursor(&app.history_query, app.input_cursor, '│')).block(
         Block::default()
             .borders(Borders::ALL)
-            .title(" Command Palette ")
+            .title(" History: URL, method (POST), status (404, 4xx) ")
             .border_style(Style::default().fg(app.theme.highlight)),
     );
     f.render_widget(search_bar, chunks[0]);
 
-    // Filter commands
-    let commands = get_available_commands();
-    let filter = app.command_query.to_lowercase();
-    let filtered: Vec<&crate::app::CommandAction> = commands
-        .iter()
-        .filter(|c| {
-            c.name.to_lowercase().contains(&filter) || c.desc.to_lowercase().contains(&filter)
-        })
-        .collect();
+    let rows = app.history_rows();
+    let entries = rows.iter().filter(|r| matches!(r, Row::Entry(_))).count();
+    if app.history_index >= entries && entries > 0 {
+        app.history_index = entries - 1;
+    }
 
-    let items: Vec<ListItem> = filtered
+    // Headings aren't selectable: map the entry position to its row
+    let mut selected_row = None;
+    let mut entry_pos = 0;
+    let items: Vec<ListItem> = rows
         .iter()
-        .map(|c| {
-            let content = Line::from(vec![
-                Span::styled(
-                    format!("{:<20}", c.name),
-                    Style::default().add_modifier(Modifier::BOLD),
-                ),
-                Span::raw(c.desc),
-            ]);
-            ListItem::new(content)
+        .enumerate()
+        .map(|(row, r)| match r {
+            Row::Group(label) => ListItem::new(Line::from(Span::styled(
+                format!("── {} ", label),
+                Style::default()
+                    .fg(app.theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ))),
+            Row::Entry(i) => {
+                if entry_pos == app.history_index {
+                    selected_row = Some(row);
+                }
+                entry_pos += 1;
+                let log = &app.request_history[*i];
+                let grpc = log.method == "GRPC";
+                let status_color = if log.status >= 400 || (grpc && log.status != 0) {
+                    app.theme.error
+                } else if grpc || (200..300).contains(&log.status) {
+                    app.theme.success
+                } else {
+                    app.theme.highlight
+                };
+                let mark = if app.history_marked.contains(i) {
+                    "✓ "
+                } else {
+                    "  "
+                };
+                let pin = if log.pinned { "📌" } else { "  " };
+                ListItem::new(Line::from(vec![
+                    Span::styled(mark, Style::default().fg(app.theme.error)),
+                    Span::raw(pin),
+                    Span::styled(
+                        format!(" {:<7}", log.method),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!("{} ", log.status),
+                        Style::default().fg(status_color),
+                    ),
+                    Span::styled(
+                        format!("{:>6}ms ", log.latency),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::raw(log.url.clone()),
+                ]))
+            }
         })
         .collect();
 
+    let title = if app.history_marked.is_empty() {
+        format!(" {} of {} ", entries, app.request_history.len())
+    } else {
+        format!(
+            " {} of {}, {} marked ",
+            entries,
+            app.request_history.len(),
+            app.history_marked.len()
+        )
+    };
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .title_bottom(
+                    " Enter: open | Ctrl+s: save | Ctrl+p: pin | Tab/Shift+Tab: mark (all) | Ctrl+x: delete | Esc ",
+                ),
+        )
         .highlight_style(Style::default().bg(app.theme.highlight).fg(Color::Black))
         .highlight_symbol("> ");
 
     let mut state = ListState::default();
-    if app.command_index >= filtered.len() && !filtered.is_empty() {
-        app.command_index = filtered.len() - 1;
-    }
-    state.select(Some(app.command_index));
-
+    state.select(selected_row);
     f.render_stateful_widget(list, chunks[1], &mut state);
 }
 
 fn render_stress_modal(f: &mut Frame, app: &mut App) {
-    let area = centered_rect(50, 40, f.area());
+    let area = centered_rect(70, 80, f.area());
     f.render_widget(ratatui::widgets::Clear, area);
 
     let block = Block::default()
@@ -2533,8 +6168,11 @@ fn render_stress_modal(f: &mut Frame, app: &mut App) {
         .constraints([
             Constraint::Length(3), // VUs
             Constraint::Length(3), // Duration
-            Constraint::Length(1), // Spacer
-            Constraint::Min(0),    // Help/Info
+            Constraint::Length(3), // Stages
+            Constraint::Length(3), // Thresholds
+            Constraint::Length(3), // Target
+            Constraint::Min(0),    // Stage preview
+            Constraint::Length(3), // Help
         ])
         .split(area);
 
@@ -2568,13 +6206,100 @@ fn render_stress_modal(f: &mut Frame, app: &mut App) {
     );
     f.render_widget(dur_input, chunks[1]);
 
+    let editing_stages = app.active_tab().input_mode == InputMode::EditingStressStages;
+    let stages_style = if editing_stages {
+        Style::default().fg(app.theme.border_focus)
+    } else {
+        Style::default().fg(app.theme.border)
+    };
+    let stages_text = if editing_stages {
+        with_cursor(&app.active_tab().stress_stages, app.input_cursor, '│')
+    } else {
+        app.active_tab().stress_stages.clone()
+    };
+    let stages_input = Paragraph::new(stages_text).block(
+        Block::default()
+            .title(" Stages (constant, ramp, step or 30s:20, 1m:20, 10s:0) ")
+            .borders(Borders::ALL)
+            .border_style(stages_style),
+    );
+    f.render_widget(stages_input, chunks[2]);
+
+    let editing_thresholds = app.active_tab().input_mode == InputMode::EditingStressThresholds;
+    let thresholds_style = if editing_thresholds {
+        Style::default().fg(app.theme.border_focus)
+    } else {
+        Style::default().fg(app.theme.border)
+    };
+    let thresholds_text = if editing_thresholds {
+        with_cursor(&app.stress_thresholds_input, app.input_cursor, '│')
+    } else {
+        app.stress_thresholds_input.clone()
+    };
+    let thresholds_input = Paragraph::new(thresholds_text).block(
+        Block::default()
+            .title(" Thresholds (e.g. p95<300, error_rate<1%) ")
+            .borders(Borders::ALL)
+            .border_style(thresholds_style),
+    );
+    f.render_widget(thresholds_input, chunks[3]);
+
+    let target_style = if app.active_tab().input_mode == InputMode::EditingStressTarget {
+        Style::default().fg(app.theme.border_focus)
+    } else {
+        Style::default().fg(app.theme.border)
+    };
+    let target_text = match app.stress_requests() {
+        Ok(requests) if app.stress_target.is_empty() => {
+            format!(
+                "Current request: {} {}",
+                requests[0].method, requests[0].url
+            )
+        }
+        Ok(requests) => format!(
+            "Collection '{}' ({} requests)",
+            app.stress_target,
+            requests.len()
+        ),
+        Err(e) => e,
+    };
+    let target_input = Paragraph::new(format!("◀ {} ▶", target_text)).block(
+        Block::default()
+            .title(" Target (←/→) ")
+            .borders(Borders::ALL)
+            .border_style(target_style),
+    );
+    f.render_widget(target_input, chunks[4]);
+
+    let mut preview: Vec<Line> = match app.stress_stages() {
+        Ok(stages) => crate::features::stress::describe(&stages)
+            .into_iter()
+            .enumerate()
+            .map(|(i, stage)| Line::from(format!("{}. {}", i + 1, stage)))
+            .collect(),
+        Err(e) => vec![Line::styled(e, Style::default().fg(app.theme.error))],
+    };
+    match app.stress_thresholds() {
+        Ok(thresholds) if !thresholds.is_empty() => preview.push(Line::from(format!(
+            "Pass if {}",
+            thresholds
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+        Ok(_) => {}
+        Err(e) => preview.push(Line::styled(e, Style::default().fg(app.theme.error))),
+    }
+    f.render_widget(Paragraph::new(preview), chunks[5]);
+
     let help_text = vec![
         Line::from("Press Enter to Start"),
         Line::from("Press Tab to Switch Field"),
         Line::from("Press Esc to Cancel"),
     ];
     let help = Paragraph::new(help_text).alignment(Alignment::Center);
-    f.render_widget(help, chunks[3]);
+    f.render_widget(help, chunks[6]);
 }
 
 fn render_curl_import_modal(f: &mut Frame, app: &mut App) {
@@ -2598,7 +6323,7 @@ fn render_curl_import_modal(f: &mut Frame, app: &mut App) {
 
     f.render_widget(block, area);
 
-    let input = Paragraph::new(app.curl_import_input.clone()).block(
+    let input = Paragraph::new(with_cursor(&app.curl_import_input, app.input_cursor, '│')).block(
         Block::default()
             .title(" Paste cURL Command ")
             .borders(Borders::ALL)
@@ -2614,6 +6339,55 @@ fn render_curl_import_modal(f: &mut Frame, app: &mut App) {
     f.render_widget(help, chunks[2]);
 }
 
+fn render_save_history_modal(f: &mut Frame, app: &mut App) {
+    let area = centered_rect(60, 30, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let source = app
+        .save_history_entry
+        .and_then(|i| app.request_history.get(i))
+        .map(|log| format!("{} {}", log.method, log.url))
+        .unwrap_or_default();
+    let block = Block::default()
+        .title(" Save History Entry to Collection ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.highlight));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(1), // Source
+            Constraint::Length(3), // Input
+            Constraint::Min(0),    // Help
+        ])
+        .split(area);
+
+    f.render_widget(block, area);
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            source,
+            Style::default().fg(app.theme.text_secondary),
+        )),
+        chunks[0],
+    );
+
+    let input = Paragraph::new(with_cursor(&app.save_history_input, app.input_cursor, '│')).block(
+        Block::default()
+            .title(" collection/name ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(app.theme.border_focus)),
+    );
+    f.render_widget(input, chunks[1]);
+
+    let help_text = vec![
+        Line::from("Enter: Save   Tab: Complete Collection   Esc: Cancel"),
+        Line::from("A new collection name creates collections/<name>.hcl"),
+    ];
+    let help = Paragraph::new(help_text).alignment(Alignment::Center);
+    f.render_widget(help, chunks[2]);
+}
+
 fn render_cookie_modal(f: &mut Frame, app: &mut App) {
     let area = centered_rect(70, 70, f.area());
     f.render_widget(ratatui::widgets::Clear, area);
@@ -2667,8 +6441,9 @@ fn render_cookie_modal(f: &mut Frame, app: &mut App) {
 fn render_stress_running_overlay(f: &mut Frame, app: &mut App) {
     let area = f.area();
     // Bottom right corner
-    let width = 40;
-    let height = 3;
+    let progress = app.stress_progress.unwrap_or_default();
+    let width = 44;
+    let height = if progress.throttled > 0 { 5 } else { 4 };
     let x = area.width.saturating_sub(width + 2);
     let y = area.height.saturating_sub(height + 2); // Above status bar
     let rect = ratatui::layout::Rect {
@@ -2680,12 +6455,26 @@ fn render_stress_running_overlay(f: &mut Frame, app: &mut App) {
 
     f.render_widget(ratatui::widgets::Clear, rect);
 
-    let (reqs, sex) = app.stress_progress.unwrap_or((0, 0));
-
-    let text = format!(" Stress Test Running: {} reqs | {}s ", reqs, sex);
+    let mut text = vec![
+        Line::from(format!(
+            "{} reqs | {}s / {}s",
+            progress.requests_done, progress.elapsed_secs, progress.total_secs
+        )),
+        Line::from(format!(
+            "{} VUs | stage {}/{}",
+            progress.active_vus, progress.stage, progress.stages
+        )),
+    ];
+    if progress.throttled > 0 {
+        text.push(Line::from(Span::styled(
+            format!("⏳ {} throttled by host limit", progress.throttled),
+            Style::default().fg(app.theme.highlight),
+        )));
+    }
     let p = Paragraph::new(text)
         .block(
             Block::default()
+                .title(" Stress Test Running ")
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Red)),
         )
@@ -2696,13 +6485,21 @@ fn render_stress_running_overlay(f: &mut Frame, app: &mut App) {
 
 fn render_stress_results(f: &mut Frame, app: &mut App) {
     if let Some(stats) = &app.stress_stats {
-        let area = centered_rect(60, 60, f.area());
+        let area = if stats.per_request.is_empty() {
+            centered_rect(60, 60, f.area())
+        } else {
+            centered_rect(80, 90, f.area())
+        };
         f.render_widget(ratatui::widgets::Clear, area);
 
         let block = Block::default()
             .title(" Stress Test Results ")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(app.theme.success));
+            .border_style(Style::default().fg(if stats.thresholds_passed() {
+                app.theme.success
+            } else {
+                app.theme.error
+            }));
 
         let inner = block.inner(area);
         f.render_widget(block, area);
@@ -2768,6 +6565,10 @@ fn render_stress_results(f: &mut Frame, app: &mut App) {
                 Span::raw("  P90: "),
                 Span::raw(format!("{}", stats.p90_latency_ms)),
             ]),
+            Line::from(vec![
+                Span::raw("  P95: "),
+                Span::raw(format!("{}", stats.p95_latency_ms)),
+            ]),
             Line::from(vec![
                 Span::raw("  P99: "),
                 Span::raw(format!("{}", stats.p99_latency_ms)),
@@ -2802,6 +6603,66 @@ fn render_stress_results(f: &mut Frame, app: &mut App) {
 
         let mut all_lines = lines;
         all_lines.append(&mut status_lines);
+        if !stats.thresholds.is_empty() {
+            all_lines.push(Line::from(""));
+            all_lines.push(Line::from(Span::styled(
+                "Thresholds:",
+                Style::default().add_modifier(Modifier::UNDERLINED),
+            )));
+            for result in &stats.thresholds {
+                let (icon, color) = if result.passed {
+                    ("✓", Color::Green)
+                } else {
+                    ("✗", Color::Red)
+                };
+                all_lines.push(Line::from(vec![
+                    Span::styled(format!("  {} ", icon), Style::default().fg(color)),
+                    Span::raw(format!("{} (got {:.2})", result.threshold, result.actual)),
+                ]));
+            }
+        }
+        if !stats.per_request.is_empty() {
+            all_lines.push(Line::from(""));
+            all_lines.push(Line::from(Span::styled(
+                "Per Request (count, failed, avg / p90 / p99 ms):",
+                Style::default().add_modifier(Modifier::UNDERLINED),
+            )));
+            let width = stats
+                .per_request
+                .iter()
+                .map(|r| r.name.chars().count())
+                .max()
+                .unwrap_or(0);
+            for request in &stats.per_request {
+                let errors = request.errors_count
+                    + request
+                        .status_dist
+                        .iter()
+                        .filter(|(code, _)| **code >= 400)
+                        .map(|(_, n)| n)
+                        .sum::<u64>();
+                all_lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {:<7}", request.method),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(format!(
+                        "{:<width$}  {:>6}  ",
+                        request.name,
+                        request.total_requests,
+                        width = width
+                    )),
+                    Span::styled(
+                        format!("{:>5}", errors),
+                        Style::default().fg(if errors > 0 { Color::Red } else { Color::Green }),
+                    ),
+                    Span::raw(format!(
+                        "  {:.0} / {} / {}",
+                        request.avg_latency_ms, request.p90_latency_ms, request.p99_latency_ms
+                    )),
+                ]));
+            }
+        }
         all_lines.push(Line::from(""));
         all_lines.push(Line::from("Press Esc to Close"));
 
@@ -35,7 +35,14 @@ pub fn render_sentinel_mode(f: &mut Frame, app: &mut App) {
         Color::Red
     };
 
-    let title = Paragraph::new(format!(" SENTINEL MODE - {} ", running_status))
+    let throttled = app
+        .sentinel_state
+        .as_ref()
+        .filter(|state| state.is_running)
+        .and_then(|state| state.throttled.as_ref())
+        .map(|reason| format!("(⏳ throttled: {}) ", reason))
+        .unwrap_or_default();
+    let title = Paragraph::new(format!(" SENTINEL MODE - {} {}", running_status, throttled))
         .style(Style::default().fg(color).add_modifier(Modifier::BOLD))
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(title, chunks[0]);
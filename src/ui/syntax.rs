@@ -1,23 +1,42 @@
+use crate::domain::theme::SyntaxColors;
 use ratatui::style::Color;
 use ratatui::text::{Line, Span};
-use std::sync::OnceLock;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use syntect::easy::HighlightLines;
-use syntect::highlighting::{Color as SyntectColor, ThemeSet};
+use syntect::highlighting::{
+    Color as SyntectColor, ScopeSelectors, StyleModifier, Theme, ThemeItem, ThemeSet,
+};
 use syntect::parsing::SyntaxSet;
 use syntect::util::LinesWithEndings;
 
 // Global singleton for syntax set and theme set to avoid loading on every render
 static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
 static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+// Syntect themes built from a PostDad theme's colours, kept once built
+static CUSTOM_THEMES: OnceLock<Mutex<HashMap<SyntaxColors, Arc<Theme>>>> = OnceLock::new();
+
+const DEFAULT_BASE: &str = "base16-ocean.dark";
 
 pub fn init() {
     SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines);
     THEME_SET.get_or_init(ThemeSet::load_defaults);
 }
 
-pub fn highlight<'a>(text: &'a str, extension: &str) -> Vec<Line<'a>> {
+pub fn highlight(text: &str, extension: &str, colors: &SyntaxColors) -> Vec<Line<'static>> {
+    highlight_until(text, extension, colors, &AtomicBool::new(false)).unwrap_or_default()
+}
+
+/// `highlight`, giving up with None once `stop` is set, checked every line
+pub fn highlight_until(
+    text: &str,
+    extension: &str,
+    colors: &SyntaxColors,
+    stop: &AtomicBool,
+) -> Option<Vec<Line<'static>>> {
     let ps = SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines);
-    let ts = THEME_SET.get_or_init(ThemeSet::load_defaults);
 
     // Find syntax
     let syntax = ps
@@ -25,18 +44,14 @@ pub fn highlight<'a>(text: &'a str, extension: &str) -> Vec<Line<'a>> {
         .or_else(|| ps.find_syntax_by_extension("txt"))
         .unwrap_or_else(|| ps.find_syntax_plain_text());
 
-    // Use a theme - "base16-ocean.dark" is usually good for TUI
-    // Available defaults: base16-ocean.dark, base16-eighties.dark, base16-mocha.dark, base16-ocean.light
-    // Use a theme - try "base16-ocean.dark", fallback to first available
-    let theme_name = "base16-ocean.dark";
-    let theme = ts.themes.get(theme_name)
-        .or_else(|| ts.themes.values().next())
-        .unwrap_or_else(|| panic!("No themes available in syntect"));
-
-    let mut h = HighlightLines::new(syntax, theme);
+    let theme = syntect_theme(colors);
+    let mut h = HighlightLines::new(syntax, &theme);
     let mut lines = Vec::new();
 
     for line in LinesWithEndings::from(text) {
+        if stop.load(Ordering::Relaxed) {
+            return None;
+        }
         let ranges: Vec<(syntect::highlighting::Style, &str)> =
             h.highlight_line(line, ps).unwrap_or_default();
         let spans: Vec<Span> = ranges
@@ -53,9 +68,125 @@ pub fn highlight<'a>(text: &'a str, extension: &str) -> Vec<Line<'a>> {
         lines.push(Line::from(spans));
     }
 
-    lines
+    Some(lines)
 }
 
+// The 16 terminal colours by ANSI index
+const ANSI: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
 fn to_ratatui_color(c: SyntectColor) -> Color {
-    Color::Rgb(c.r, c.g, c.b)
+    match c.a {
+        // See `to_syntect_color`
+        0 => ANSI
+            .get(c.r as usize)
+            .copied()
+            .unwrap_or(Color::Indexed(c.r)),
+        _ => Color::Rgb(c.r, c.g, c.b),
+    }
+}
+
+/// Syntect's bundled themes: base16-ocean.dark, base16-eighties.dark, base16-mocha.dark,
+/// base16-ocean.light, InspiredGitHub, Solarized (dark), Solarized (light)
+pub fn base_themes() -> Vec<String> {
+    let ts = THEME_SET.get_or_init(ThemeSet::load_defaults);
+    ts.themes.keys().cloned().collect()
+}
+
+fn to_syntect_color(c: Color) -> Option<SyntectColor> {
+    match c {
+        Color::Rgb(r, g, b) => Some(SyntectColor { r, g, b, a: 0xFF }),
+        // A named or palette colour: syntect's alpha 0 marks the red as an ANSI index
+        Color::Indexed(i) => Some(SyntectColor {
+            r: i,
+            g: 0,
+            b: 0,
+            a: 0,
+        }),
+        other => {
+            let index = ANSI.iter().position(|c| *c == other)?;
+            Some(SyntectColor {
+                r: index as u8,
+                g: 0,
+                b: 0,
+                a: 0,
+            })
+        }
+    }
+}
+
+/// The base theme with the token kinds `colors` sets painted over it
+fn syntect_theme(colors: &SyntaxColors) -> Arc<Theme> {
+    let custom = CUSTOM_THEMES.get_or_init(Default::default);
+    if let Some(theme) = custom.lock().ok().and_then(|c| c.get(colors).cloned()) {
+        return theme;
+    }
+
+    let ts = THEME_SET.get_or_init(ThemeSet::load_defaults);
+    let base = if colors.base.is_empty() {
+        DEFAULT_BASE
+    } else {
+        colors.base.as_str()
+    };
+    let mut theme = ts
+        .themes
+        .get(base)
+        .or_else(|| ts.themes.get(DEFAULT_BASE))
+        .or_else(|| ts.themes.values().next())
+        .cloned()
+        .unwrap_or_default();
+    if let Some(text) = colors.text.and_then(to_syntect_color) {
+        theme.settings.foreground = Some(text);
+    }
+    // Put first: on an equally specific match syntect keeps the earlier rule
+    let kinds = [
+        (colors.string, "string"),
+        (colors.number, "constant.numeric"),
+        (colors.keyword, "keyword, storage, constant.language"),
+        (colors.comment, "comment"),
+        (
+            colors.key,
+            "meta.structure.dictionary.key string, meta.mapping.key string, support.type.property-name, entity.name.tag, entity.other.attribute-name",
+        ),
+        (colors.punctuation, "punctuation"),
+    ];
+    let mut overrides = Vec::new();
+    for (color, scopes) in kinds {
+        if let (Some(color), Ok(scope)) = (
+            color.and_then(to_syntect_color),
+            ScopeSelectors::from_str(scopes),
+        ) {
+            overrides.push(ThemeItem {
+                scope,
+                style: StyleModifier {
+                    foreground: Some(color),
+                    background: None,
+                    font_style: None,
+                },
+            });
+        }
+    }
+    theme.scopes.splice(0..0, overrides);
+
+    let theme = Arc::new(theme);
+    if let Ok(mut custom) = custom.lock() {
+        custom.insert(colors.clone(), theme.clone());
+    }
+    theme
 }